@@ -5,10 +5,12 @@ use crate::render::WgpuContext;
 use graph_craft::wasm_application_io::WasmApplicationIo;
 use graphite_editor::application::Editor;
 use graphite_editor::messages::prelude::*;
+use graphite_editor::node_graph_executor::{NodeGraphExecutionError, application_io_swap_pending};
 use std::sync::Arc;
 use std::sync::mpsc::Sender;
 use std::time::Duration;
 use std::time::Instant;
+use std::time::{SystemTime, UNIX_EPOCH};
 use winit::application::ApplicationHandler;
 use winit::dpi::PhysicalSize;
 use winit::event::StartCause;
@@ -24,6 +26,10 @@ pub(crate) struct WinitApp {
 	pub(crate) cef_context: cef::Context<cef::Initialized>,
 	pub(crate) window: Option<Arc<Window>>,
 	cef_schedule: Option<Instant>,
+	/// When the animation playing in the active document needs its next frame.
+	playback_schedule: Option<Instant>,
+	/// Documents to open once the graph's application IO has been replaced, so they aren't evaluated with the one on its way out.
+	deferred_document_opens: Vec<Message>,
 	window_size_sender: Sender<WindowSize>,
 	graphics_state: Option<GraphicsState>,
 	wgpu_context: WgpuContext,
@@ -36,6 +42,8 @@ impl WinitApp {
 			cef_context,
 			window: None,
 			cef_schedule: Some(Instant::now()),
+			playback_schedule: None,
+			deferred_document_opens: Vec::new(),
 			graphics_state: None,
 			window_size_sender,
 			wgpu_context,
@@ -46,6 +54,18 @@ impl WinitApp {
 	fn dispatch_message(&mut self, message: Message) {
 		let responses = self.editor.handle_message(message);
 		self.send_messages_to_editor(responses);
+		self.render_overlays();
+	}
+
+	/// Renders the overlays drawn while the editor handled the last message, which are shown in the viewport on the next redraw.
+	fn render_overlays(&mut self) {
+		let Some(frame) = self.editor.take_overlay_frame() else { return };
+		let Some(graphics_state) = &mut self.graphics_state else { return };
+
+		graphics_state.render_overlays(frame);
+		if let Some(window) = &self.window {
+			window.request_redraw();
+		}
 	}
 
 	fn send_messages_to_editor(&mut self, responses: Vec<FrontendMessage>) {
@@ -58,15 +78,27 @@ impl WinitApp {
 		};
 		self.cef_context.send_web_message(message.as_bytes());
 	}
+
+	/// Sends the current time to the editor, which advances the animation playing in the active document if its next frame is due.
+	fn dispatch_current_time(&mut self) {
+		let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64;
+		self.dispatch_message(InputPreprocessorMessage::CurrentTime { timestamp }.into());
+	}
 }
 
 impl ApplicationHandler<CustomEvent> for WinitApp {
 	fn about_to_wait(&mut self, event_loop: &ActiveEventLoop) {
 		// Set a timeout in case we miss any cef schedule requests
 		let timeout = Instant::now() + Duration::from_millis(10);
-		let wait_until = timeout.min(self.cef_schedule.unwrap_or(timeout));
+		let mut wait_until = timeout.min(self.cef_schedule.unwrap_or(timeout));
 		self.cef_context.work();
 
+		// While an animation plays, wake up in time for each of its frames instead of polling for them
+		match self.editor.playback_frame_duration() {
+			Some(frame_duration) => wait_until = wait_until.min(*self.playback_schedule.get_or_insert_with(|| Instant::now() + frame_duration)),
+			None => self.playback_schedule = None,
+		}
+
 		event_loop.set_control_flow(ControlFlow::WaitUntil(wait_until));
 	}
 
@@ -77,6 +109,12 @@ impl ApplicationHandler<CustomEvent> for WinitApp {
 			self.cef_schedule = None;
 			self.cef_context.work();
 		}
+		if let Some(schedule) = self.playback_schedule
+			&& schedule <= Instant::now()
+		{
+			self.playback_schedule = None;
+			self.dispatch_current_time();
+		}
 		if let StartCause::ResumeTimeReached { .. } = cause {
 			if let Some(window) = &self.window {
 				window.request_redraw();
@@ -103,7 +141,9 @@ impl ApplicationHandler<CustomEvent> for WinitApp {
 
 		let application_io = WasmApplicationIo::new_with_context(self.wgpu_context.clone());
 
-		futures::executor::block_on(graphite_editor::node_graph_executor::replace_application_io(application_io));
+		if let Err(e) = futures::executor::block_on(graphite_editor::node_graph_executor::replace_application_io(application_io)) {
+			tracing::info!("{e}");
+		}
 	}
 
 	fn user_event(&mut self, _: &ActiveEventLoop, event: CustomEvent) {
@@ -144,25 +184,36 @@ impl ApplicationHandler<CustomEvent> for WinitApp {
 						panic!("graphics state not intialized, viewport offset might be lost");
 					}
 				}
+				let opens_document = matches!(message, Message::Portfolio(PortfolioMessage::OpenDocumentFile { .. } | PortfolioMessage::OpenDocumentFileWithId { .. }));
+				if opens_document && application_io_swap_pending() {
+					self.deferred_document_opens.push(message);
+					return;
+				}
 				self.dispatch_message(message);
 			}
 			CustomEvent::NodeGraphRan { texture } => {
+				// A render cancelled in favor of a newer one has no texture, so the viewport keeps showing the previous one until the newer render finishes
 				if let Some(texture) = texture
 					&& let Some(graphics_state) = &mut self.graphics_state
 				{
 					graphics_state.bind_viewport_texture(&texture);
 				}
 				let mut responses = VecDeque::new();
-				let err = self.editor.poll_node_graph_evaluation(&mut responses);
-				if let Err(e) = err {
-					if e != "No active document" {
-						tracing::error!("Error poling node graph: {}", e);
-					}
+				match self.editor.poll_node_graph_evaluation(&mut responses) {
+					Ok(()) | Err(NodeGraphExecutionError::NoActiveDocument) => {}
+					Err(e) => tracing::error!("Error poling node graph: {}", e),
 				}
 
 				for message in responses {
 					self.dispatch_message(message);
 				}
+
+				// The runtime swaps in a pending application IO before it evaluates the graph
+				if !application_io_swap_pending() {
+					for message in std::mem::take(&mut self.deferred_document_opens) {
+						self.dispatch_message(message);
+					}
+				}
 			}
 		}
 	}
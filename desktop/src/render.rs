@@ -1,7 +1,9 @@
 use std::sync::Arc;
 
 use bytemuck::{Pod, Zeroable};
+use graphite_editor::messages::portfolio::document::overlays::utility_types::{OverlayFrame, OverlayTextureFilter};
 use thiserror::Error;
+use wgpu_executor::WgpuExecutor;
 use winit::window::Window;
 
 pub(crate) struct FrameBufferRef<'a> {
@@ -69,6 +71,15 @@ pub(crate) struct GraphicsState {
 	viewport_offset: [f32; 2],
 	viewport_texture: Option<wgpu::Texture>,
 	ui_texture: Option<wgpu::Texture>,
+	overlays_background_texture: Option<wgpu::Texture>,
+	overlays_foreground_texture: Option<wgpu::Texture>,
+	// Bound in place of the overlay textures until the editor provides them
+	transparent_texture: wgpu::Texture,
+	// Samples the overlay textures with the filter requested by the most recent frame of overlays
+	overlays_sampler: wgpu::Sampler,
+	overlays_filter: wgpu::FilterMode,
+	// Renders the overlays with its own Vello renderer, created once the editor draws its first frame of them
+	overlays_executor: Option<WgpuExecutor>,
 	bind_group: Option<wgpu::BindGroup>,
 }
 
@@ -136,6 +147,32 @@ impl GraphicsState {
 					ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
 					count: None,
 				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 3,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Texture {
+						multisampled: false,
+						view_dimension: wgpu::TextureViewDimension::D2,
+						sample_type: wgpu::TextureSampleType::Float { filterable: true },
+					},
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 4,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Texture {
+						multisampled: false,
+						view_dimension: wgpu::TextureViewDimension::D2,
+						sample_type: wgpu::TextureSampleType::Float { filterable: true },
+					},
+					count: None,
+				},
+				wgpu::BindGroupLayoutEntry {
+					binding: 5,
+					visibility: wgpu::ShaderStages::FRAGMENT,
+					ty: wgpu::BindingType::Sampler(wgpu::SamplerBindingType::Filtering),
+					count: None,
+				},
 			],
 			label: Some("texture_bind_group_layout"),
 		});
@@ -187,6 +224,25 @@ impl GraphicsState {
 			cache: None,
 		});
 
+		// Textures are zero-initialized, so this is fully transparent
+		let transparent_texture = context.device.create_texture(&wgpu::TextureDescriptor {
+			label: Some("transparent_texture"),
+			size: wgpu::Extent3d {
+				width: 1,
+				height: 1,
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			format: wgpu::TextureFormat::Rgba8Unorm,
+			usage: wgpu::TextureUsages::TEXTURE_BINDING,
+			view_formats: &[],
+		});
+
+		let overlays_filter = wgpu::FilterMode::Linear;
+		let overlays_sampler = create_overlays_sampler(&context.device, overlays_filter);
+
 		Self {
 			surface,
 			context,
@@ -197,6 +253,12 @@ impl GraphicsState {
 			viewport_offset: [0.0, 0.0],
 			viewport_texture: None,
 			ui_texture: None,
+			overlays_background_texture: None,
+			overlays_foreground_texture: None,
+			transparent_texture,
+			overlays_sampler,
+			overlays_filter,
+			overlays_executor: None,
 			bind_group: None,
 		}
 	}
@@ -225,6 +287,63 @@ impl GraphicsState {
 		self.bind_group = Some(bind_group);
 	}
 
+	/// Binds the overlays drawn beneath and above the artwork, which are composited in the viewport on either side of it.
+	pub(crate) fn bind_overlay_textures(&mut self, background: &wgpu::Texture, foreground: &wgpu::Texture) {
+		self.overlays_background_texture = Some(background.clone());
+		self.overlays_foreground_texture = Some(foreground.clone());
+
+		if let Some(ui_texture) = self.ui_texture.clone() {
+			let viewport_texture = self.viewport_texture.clone().unwrap_or(ui_texture.clone());
+			self.bind_group = Some(self.create_bindgroup(&ui_texture, &viewport_texture));
+		}
+	}
+
+	/// Renders each layer of the editor's overlays into a texture of the frame's size, which is then bound to be composited with the frame's filter.
+	pub(crate) fn render_overlays(&mut self, frame: OverlayFrame) {
+		let settings = frame.settings;
+		if settings.texture_size.x == 0 || settings.texture_size.y == 0 {
+			return;
+		}
+
+		if self.overlays_executor.is_none() {
+			self.overlays_executor = WgpuExecutor::with_context(self.context.clone());
+		}
+		let Some(executor) = &self.overlays_executor else {
+			tracing::error!("Failed to create the overlays renderer");
+			return;
+		};
+
+		let antialiasing_method = settings.antialiasing_method();
+		let render_layer = |layer_scene: &vello::Scene, previous: &Option<wgpu::Texture>| {
+			// The previous frame's texture is reused while the viewport keeps its size
+			let texture = match previous {
+				Some(texture) if texture.width() == settings.texture_size.x && texture.height() == settings.texture_size.y => texture.clone(),
+				_ => executor.create_target_texture(settings.texture_size),
+			};
+			futures::executor::block_on(executor.render_transparent_vello_scene(layer_scene, &texture, settings.render_scale, antialiasing_method)).map(|_| texture)
+		};
+		let background = render_layer(&frame.background, &self.overlays_background_texture);
+		let foreground = render_layer(&frame.foreground, &self.overlays_foreground_texture);
+		let (background, foreground) = match (background, foreground) {
+			(Ok(background), Ok(foreground)) => (background, foreground),
+			(Err(e), _) | (_, Err(e)) => {
+				tracing::error!("Failed to render the overlays: {e}");
+				return;
+			}
+		};
+
+		let filter = match settings.texture_filter {
+			OverlayTextureFilter::Nearest => wgpu::FilterMode::Nearest,
+			OverlayTextureFilter::Linear => wgpu::FilterMode::Linear,
+		};
+		if filter != self.overlays_filter {
+			self.overlays_sampler = create_overlays_sampler(&self.context.device, filter);
+			self.overlays_filter = filter;
+		}
+
+		self.bind_overlay_textures(&background, &foreground);
+	}
+
 	pub(crate) fn set_viewport_scale(&mut self, scale: [f32; 2]) {
 		self.viewport_scale = scale;
 	}
@@ -236,6 +355,16 @@ impl GraphicsState {
 	fn create_bindgroup(&self, ui_texture: &wgpu::Texture, viewport_texture: &wgpu::Texture) -> wgpu::BindGroup {
 		let ui_texture_view = ui_texture.create_view(&wgpu::TextureViewDescriptor::default());
 		let viewport_texture_view = viewport_texture.create_view(&wgpu::TextureViewDescriptor::default());
+		let overlays_background_texture_view = self
+			.overlays_background_texture
+			.as_ref()
+			.unwrap_or(&self.transparent_texture)
+			.create_view(&wgpu::TextureViewDescriptor::default());
+		let overlays_foreground_texture_view = self
+			.overlays_foreground_texture
+			.as_ref()
+			.unwrap_or(&self.transparent_texture)
+			.create_view(&wgpu::TextureViewDescriptor::default());
 
 		self.context.device.create_bind_group(&wgpu::BindGroupDescriptor {
 			layout: &self.render_pipeline.get_bind_group_layout(0),
@@ -252,6 +381,18 @@ impl GraphicsState {
 					binding: 2,
 					resource: wgpu::BindingResource::Sampler(&self.sampler),
 				},
+				wgpu::BindGroupEntry {
+					binding: 3,
+					resource: wgpu::BindingResource::TextureView(&overlays_background_texture_view),
+				},
+				wgpu::BindGroupEntry {
+					binding: 4,
+					resource: wgpu::BindingResource::TextureView(&overlays_foreground_texture_view),
+				},
+				wgpu::BindGroupEntry {
+					binding: 5,
+					resource: wgpu::BindingResource::Sampler(&self.overlays_sampler),
+				},
 			],
 			label: Some("texture_bind_group"),
 		})
@@ -302,6 +443,20 @@ impl GraphicsState {
 	}
 }
 
+/// Samples the overlay textures, which are minified as well as magnified when they're supersampled.
+fn create_overlays_sampler(device: &wgpu::Device, filter: wgpu::FilterMode) -> wgpu::Sampler {
+	device.create_sampler(&wgpu::SamplerDescriptor {
+		label: Some("overlays_sampler"),
+		address_mode_u: wgpu::AddressMode::ClampToEdge,
+		address_mode_v: wgpu::AddressMode::ClampToEdge,
+		address_mode_w: wgpu::AddressMode::ClampToEdge,
+		mag_filter: filter,
+		min_filter: filter,
+		mipmap_filter: wgpu::FilterMode::Nearest,
+		..Default::default()
+	})
+}
+
 #[repr(C)]
 #[derive(Copy, Clone, Pod, Zeroable)]
 struct Constants {
@@ -38,6 +38,16 @@ var t_ui: texture_2d<f32>;
 var t_viewport: texture_2d<f32>;
 @group(0) @binding(2)
 var s_diffuse: sampler;
+@group(0) @binding(3)
+var t_overlays_background: texture_2d<f32>;
+@group(0) @binding(4)
+var t_overlays_foreground: texture_2d<f32>;
+@group(0) @binding(5)
+var s_overlays: sampler;
+
+fn blend_over(top: vec4<f32>, bottom: vec4<f32>) -> vec4<f32> {
+	return top * top.a + bottom * (1.0 - top.a);
+}
 
 @fragment
 fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
@@ -47,5 +57,10 @@ fn fs_main(in: VertexOutput) -> @location(0) vec4<f32> {
 	}
 	let viewport_tex_coords = (in.tex_coords - constants.viewport_offset) * constants.viewport_scale;
 	let viewport_color: vec4<f32> = textureSample(t_viewport, s_diffuse, viewport_tex_coords);
-	return ui_color * ui_color.a + viewport_color * (1.0 - ui_color.a);
+	let overlays_background_color: vec4<f32> = textureSample(t_overlays_background, s_overlays, viewport_tex_coords);
+	let overlays_foreground_color: vec4<f32> = textureSample(t_overlays_foreground, s_overlays, viewport_tex_coords);
+
+	// Within the viewport, the artwork is sandwiched between the background and foreground overlays
+	let document_color = blend_over(overlays_foreground_color, blend_over(viewport_color, overlays_background_color));
+	return blend_over(ui_color, document_color);
 }
@@ -0,0 +1,141 @@
+use criterion::{BenchmarkId, Criterion, criterion_group, criterion_main};
+use glam::DVec2;
+use graphite_editor::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE, MANIPULATOR_GROUP_MARKER_SIZE};
+use graphite_editor::messages::portfolio::document::overlays::overlay_command_buffer::{DrawBatch, DrawCommand, DrawStyle, OverlayCommandBuffer, PathCommand, RecordedPath};
+use std::f64::consts::TAU;
+use vello::Scene;
+use vello::kurbo::{self, Affine, BezPath};
+use vello::peniko;
+
+/// The size of a 4K viewport, across which the anchors are spread.
+const VIEWPORT_SIZE: DVec2 = DVec2::new(3840., 2160.);
+
+/// Records the commands the overlay context issues while editing a dense path, where each visible anchor shows a handle.
+/// These are the same paths and styles as `line`, `manipulator_handle`, and `manipulator_anchor` record in the browser.
+fn handle_heavy_frame(anchors: usize) -> OverlayCommandBuffer {
+	let mut buffer = OverlayCommandBuffer::default();
+
+	let columns = (anchors as f64 * VIEWPORT_SIZE.x / VIEWPORT_SIZE.y).sqrt().ceil() as usize;
+	let rows = anchors.div_ceil(columns);
+	let spacing = VIEWPORT_SIZE / DVec2::new(columns as f64, rows as f64);
+	for index in 0..anchors {
+		let anchor = ((DVec2::new((index % columns) as f64, (index / columns) as f64) + 0.5) * spacing).round() - DVec2::splat(0.5);
+		let handle = anchor + DVec2::new(12., -8.);
+
+		let mut line = RecordedPath::new();
+		line.move_to(anchor);
+		line.line_to(handle);
+		buffer.push_path(line, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
+
+		let mut handle_circle = RecordedPath::new();
+		handle_circle.arc(handle, MANIPULATOR_GROUP_MARKER_SIZE / 2., 0., TAU);
+		buffer.push_path(handle_circle.clone(), DrawStyle::fill(COLOR_OVERLAY_WHITE));
+		buffer.push_path(handle_circle, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
+
+		let mut anchor_square = RecordedPath::new();
+		anchor_square.rect(anchor - DVec2::splat(MANIPULATOR_GROUP_MARKER_SIZE) / 2., DVec2::splat(MANIPULATOR_GROUP_MARKER_SIZE));
+		let anchor_fill = if index % 10 == 0 { COLOR_OVERLAY_BLUE } else { COLOR_OVERLAY_WHITE };
+		buffer.push_path(anchor_square.clone(), DrawStyle::fill(anchor_fill));
+		buffer.push_path(anchor_square, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
+	}
+
+	buffer
+}
+
+fn parse_color(color: &str) -> peniko::Color {
+	let hex = color.trim_start_matches('#');
+	let channel = |index: usize| hex.get(index..index + 2).and_then(|channel| u8::from_str_radix(channel, 16).ok());
+	peniko::Color::from_rgba8(channel(0).unwrap_or(0), channel(2).unwrap_or(0), channel(4).unwrap_or(0), channel(6).unwrap_or(255))
+}
+
+fn append_command(path: &mut BezPath, command: PathCommand) {
+	let point = |point: DVec2| kurbo::Point::new(point.x, point.y);
+	match command {
+		PathCommand::MoveTo(to) => path.move_to(point(to)),
+		PathCommand::LineTo(to) => path.line_to(point(to)),
+		PathCommand::QuadraticCurveTo { handle, end } => path.quad_to(point(handle), point(end)),
+		PathCommand::BezierCurveTo { handle_start, handle_end, end } => path.curve_to(point(handle_start), point(handle_end), point(end)),
+		PathCommand::Arc {
+			center,
+			radius,
+			start_angle,
+			end_angle,
+		} => {
+			// Like on the canvas, the arc is joined to the end of the path, or begins a new one if it's empty
+			let start = point(center + DVec2::from_angle(start_angle) * radius);
+			if path.elements().is_empty() {
+				path.move_to(start);
+			} else {
+				path.line_to(start);
+			}
+			path.extend(kurbo::Arc::new(point(center), (radius, radius), start_angle, end_angle - start_angle, 0.).append_iter(0.1));
+		}
+		PathCommand::Rect { corner, size } => {
+			path.move_to(point(corner));
+			path.line_to(point(corner + DVec2::new(size.x, 0.)));
+			path.line_to(point(corner + size));
+			path.line_to(point(corner + DVec2::new(0., size.y)));
+			path.close_path();
+		}
+		PathCommand::ClosePath => path.close_path(),
+	}
+}
+
+/// Builds the path of each draw call and fills or strokes it into a vector scene.
+/// The browser's canvas can't be driven outside of a browser, so this stands in for the work every draw call costs when the frame is flushed.
+fn flush<'a>(scene: &mut Scene, draw_calls: impl IntoIterator<Item = (&'a DrawStyle, Vec<&'a RecordedPath>)>) {
+	for (style, paths) in draw_calls {
+		let mut path = BezPath::new();
+		for &command in paths.iter().flat_map(|path| &path.commands) {
+			append_command(&mut path, command);
+		}
+
+		match style {
+			DrawStyle::Fill { color } => scene.fill(peniko::Fill::NonZero, Affine::IDENTITY, parse_color(color), None, &path),
+			DrawStyle::FillPattern { color: [r, g, b, a] } => scene.fill(peniko::Fill::NonZero, Affine::IDENTITY, peniko::Color::from_rgba8(*r, *g, *b, *a), None, &path),
+			DrawStyle::Stroke { color, width, .. } => scene.stroke(&kurbo::Stroke::new(*width), Affine::IDENTITY, parse_color(color), None, &path),
+		}
+	}
+}
+
+/// Every recorded path drawn with its own call, as the overlays were flushed before batching.
+fn unbatched_draw_calls(buffer: &OverlayCommandBuffer) -> impl Iterator<Item = (&DrawStyle, Vec<&RecordedPath>)> {
+	buffer.commands().iter().filter_map(|command| match command {
+		DrawCommand::Path { path, style } => Some((style, vec![path])),
+		DrawCommand::Text(_) => None,
+	})
+}
+
+fn batched_draw_calls(buffer: &OverlayCommandBuffer) -> impl Iterator<Item = (&DrawStyle, Vec<&RecordedPath>)> {
+	buffer.batches().into_iter().filter_map(|batch| match batch {
+		DrawBatch::Paths { style, paths } => Some((style, paths)),
+		DrawBatch::Text(_) => None,
+	})
+}
+
+/// Times whole frames, from recording the commands to flushing them, with and without batching.
+fn overlay_batching(c: &mut Criterion) {
+	let mut group = c.benchmark_group("Overlay Frame");
+	for anchors in [500, 5_000] {
+		group.bench_with_input(BenchmarkId::new("Unbatched", anchors), &anchors, |b, &anchors| {
+			let mut scene = Scene::new();
+			b.iter(|| {
+				scene.reset();
+				let buffer = handle_heavy_frame(anchors);
+				flush(&mut scene, unbatched_draw_calls(&buffer));
+			})
+		});
+		group.bench_with_input(BenchmarkId::new("Batched", anchors), &anchors, |b, &anchors| {
+			let mut scene = Scene::new();
+			b.iter(|| {
+				scene.reset();
+				let buffer = handle_heavy_frame(anchors);
+				flush(&mut scene, batched_draw_calls(&buffer));
+			})
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, overlay_batching);
+criterion_main!(benches);
@@ -0,0 +1,57 @@
+use criterion::{Criterion, criterion_group, criterion_main};
+use glam::DVec2;
+use graphite_editor::messages::portfolio::document::overlays::utility_types::{OverlayContext, OverlayRenderSettings};
+use graphite_editor::messages::preferences::{OverlayQuality, OverlaySupersampling};
+use wgpu_executor::WgpuExecutor;
+
+/// The size of a 4K viewport, where rendering the overlays is the most expensive.
+const VIEWPORT_SIZE: DVec2 = DVec2::new(3840., 2160.);
+
+/// The overlays drawn while editing a dense path, with 5,000 visible anchors which each show a handle.
+fn handle_heavy_scene() -> vello::Scene {
+	let mut overlay_context = OverlayContext {
+		size: VIEWPORT_SIZE,
+		..Default::default()
+	};
+
+	let (columns, rows) = (100, 50);
+	let spacing = VIEWPORT_SIZE / DVec2::new(columns as f64, rows as f64);
+	for index in 0..columns * rows {
+		let anchor = (DVec2::new((index % columns) as f64, (index / columns) as f64) + 0.5) * spacing;
+		let handle = anchor + DVec2::new(12., -8.);
+		overlay_context.line(anchor, handle, None, None);
+		overlay_context.manipulator_handle(handle, false, None);
+		overlay_context.manipulator_anchor(anchor, index % 10 == 0, None);
+	}
+
+	overlay_context.scene.borrow().clone()
+}
+
+fn overlay_frame_time(c: &mut Criterion) {
+	let Some(executor) = futures::executor::block_on(WgpuExecutor::new()) else {
+		eprintln!("Skipping the overlay frame time benchmark, since no GPU adapter is available");
+		return;
+	};
+	let scene = handle_heavy_scene();
+
+	let mut group = c.benchmark_group("Overlay Frame Time");
+	for supersampling in [OverlaySupersampling::X1, OverlaySupersampling::X2] {
+		for anti_aliasing in [false, true] {
+			let settings = OverlayRenderSettings::new(VIEWPORT_SIZE, 1., OverlayQuality { anti_aliasing, supersampling });
+			let texture = executor.create_target_texture(settings.texture_size);
+
+			let name = format!("{supersampling}, {}", if anti_aliasing { "anti-aliased" } else { "aliased" });
+			group.bench_function(name, |b| {
+				b.iter(|| {
+					futures::executor::block_on(executor.render_transparent_vello_scene(&scene, &texture, settings.render_scale, settings.antialiasing_method())).unwrap();
+					// Wait for the GPU, so the rendering is timed rather than only its submission
+					executor.context.device.poll(wgpu::PollType::Wait).unwrap();
+				})
+			});
+		}
+	}
+	group.finish();
+}
+
+criterion_group!(benches, overlay_frame_time);
+criterion_main!(benches);
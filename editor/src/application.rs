@@ -1,5 +1,6 @@
 use crate::dispatcher::Dispatcher;
 use crate::messages::prelude::*;
+use crate::node_graph_executor::NodeGraphExecutionError;
 pub use graphene_std::uuid::*;
 
 // TODO: serialize with serde to save the current editor state
@@ -27,9 +28,22 @@ impl Editor {
 		std::mem::take(&mut self.dispatcher.responses)
 	}
 
-	pub fn poll_node_graph_evaluation(&mut self, responses: &mut VecDeque<Message>) -> Result<(), String> {
+	pub fn poll_node_graph_evaluation(&mut self, responses: &mut VecDeque<Message>) -> Result<(), NodeGraphExecutionError> {
 		self.dispatcher.poll_node_graph_evaluation(responses)
 	}
+
+	/// The overlays of the active document drawn since this was last called, for the native app to render over its viewport.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn take_overlay_frame(&mut self) -> Option<crate::messages::portfolio::document::overlays::utility_types::OverlayFrame> {
+		let document = self.dispatcher.message_handlers.portfolio_message_handler.active_document_mut()?;
+		document.overlays_message_handler.take_finished_frame()
+	}
+
+	/// How often the active document's animation needs a new frame while it's playing, for scheduling when to next send the current time.
+	pub fn playback_frame_duration(&self) -> Option<std::time::Duration> {
+		let document = self.dispatcher.message_handlers.portfolio_message_handler.active_document()?;
+		document.playback.is_playing().then(|| document.playback.frame_duration())
+	}
 }
 
 impl Default for Editor {
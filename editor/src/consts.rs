@@ -4,6 +4,10 @@ pub const EXPORTS_TO_TOP_EDGE_PIXEL_GAP: u32 = 72;
 pub const EXPORTS_TO_RIGHT_EDGE_PIXEL_GAP: u32 = 120;
 pub const IMPORTS_TO_TOP_EDGE_PIXEL_GAP: u32 = 72;
 pub const IMPORTS_TO_LEFT_EDGE_PIXEL_GAP: u32 = 120;
+/// The largest output of an inspected node, in bytes, which is kept after each evaluation
+pub const INSPECTED_VALUE_SIZE_LIMIT: u64 = 16 * 1024 * 1024;
+/// The largest output of an inspected node, in bytes, which is sent to the frontend in full when requested instead of only its preview
+pub const INSPECTED_FULL_VALUE_SIZE_LIMIT: u64 = 64 * 1024;
 
 // VIEWPORT
 pub const VIEWPORT_ZOOM_WHEEL_RATE: f64 = (1. / 600.) * 3.;
@@ -31,6 +35,9 @@ pub const VIEWPORT_ZOOM_TO_FIT_PADDING_SCALE_FACTOR: f64 = 0.95;
 pub const DRAG_BEYOND_VIEWPORT_MAX_OVEREXTENSION_PIXELS: f64 = 50.;
 pub const DRAG_BEYOND_VIEWPORT_SPEED_FACTOR: f64 = 20.;
 
+/// The time in milliseconds which the automatic interactive render quality tries to keep each render within while dragging, about 30 frames per second.
+pub const INTERACTIVE_RENDER_TIME_BUDGET_MILLISECONDS: f64 = 33.;
+
 // SNAPPING POINT
 pub const SNAP_POINT_TOLERANCE: f64 = 5.;
 /// These are layers whose bounding boxes are used for alignment.
@@ -39,6 +46,10 @@ pub const MAX_ALIGNMENT_CANDIDATES: usize = 100;
 pub const MAX_SNAP_CANDIDATES: usize = 10;
 /// These are points (anchors and bounding box corners etc.) in the layer snapper.
 pub const MAX_LAYER_SNAP_POINTS: usize = 100;
+/// The width and height of the glyph indicating the type of the active snap.
+pub const SNAP_INDICATOR_GLYPH_SIZE: f64 = 8.;
+/// How long the snap indicator glyph takes to fade out once the snap deactivates.
+pub const SNAP_INDICATOR_FADE_MILLISECONDS: u64 = 250;
 
 pub const DRAG_THRESHOLD: f64 = 1.;
 
@@ -120,6 +131,8 @@ pub const LINE_ROTATE_SNAP_ANGLE: f64 = 15.;
 // BRUSH TOOL
 pub const BRUSH_SIZE_CHANGE_KEYBOARD: f64 = 5.;
 pub const DEFAULT_BRUSH_SIZE: f64 = 20.;
+/// The length of the stabilizer's leash, in viewport pixels, at full stabilization strength.
+pub const BRUSH_STABILIZATION_MAX_LEASH: f64 = 100.;
 
 // GIZMOS
 pub const POINT_RADIUS_HANDLE_SNAP_THRESHOLD: f64 = 8.;
@@ -153,6 +166,13 @@ pub const DEFAULT_DOCUMENT_NAME: &str = "Untitled Document";
 pub const FILE_SAVE_SUFFIX: &str = ".graphite";
 pub const MAX_UNDO_HISTORY_LEN: usize = 100; // TODO: Add this to user preferences
 pub const AUTO_SAVE_TIMEOUT_SECONDS: u64 = 15;
+/// Pixels per SVG unit for the parts of an imported SVG which are rasterized, so they stay sharp when zoomed in a bit
+pub const SVG_IMPORT_RASTERIZATION_SCALE: f64 = 2.;
+/// How many documents the recent documents list remembers, not counting the pinned ones which are never dropped
+pub const MAX_RECENT_DOCUMENTS: usize = 20;
+
+// ANIMATION
+pub const DEFAULT_PLAYBACK_FPS: f64 = 30.;
 
 // INPUT
 pub const DOUBLE_CLICK_MILLISECONDS: u64 = 500;
@@ -2,6 +2,7 @@ use crate::messages::debug::utility_types::MessageLoggingVerbosity;
 use crate::messages::dialog::DialogMessageContext;
 use crate::messages::layout::layout_message_handler::LayoutMessageContext;
 use crate::messages::prelude::*;
+use crate::node_graph_executor::NodeGraphExecutionError;
 
 #[derive(Debug, Default)]
 pub struct Dispatcher {
@@ -54,6 +55,9 @@ const SIDE_EFFECT_FREE_MESSAGES: &[MessageDiscriminant] = &[
 const DEBUG_MESSAGE_BLOCK_LIST: &[MessageDiscriminant] = &[
 	MessageDiscriminant::Broadcast(BroadcastMessageDiscriminant::TriggerEvent(BroadcastEventDiscriminant::AnimationFrame)),
 	MessageDiscriminant::Animation(AnimationMessageDiscriminant::IncrementFrameCounter),
+	MessageDiscriminant::Portfolio(PortfolioMessageDiscriminant::Document(DocumentMessageDiscriminant::Overlays(
+		OverlaysMessageDiscriminant::AnimationFrame,
+	))),
 ];
 // TODO: Find a way to combine these with the list above. We use strings for now since these are the standard variant names used by multiple messages. But having these also type-checked would be best.
 const DEBUG_MESSAGE_ENDING_BLOCK_LIST: &[&str] = &["PointerMove", "PointerOutsideViewport", "Overlays", "Draw", "CurrentTime", "Time"];
@@ -123,7 +127,10 @@ impl Dispatcher {
 			// Process the action by forwarding it to the relevant message handler, or saving the FrontendMessage to be sent to the frontend
 			match message {
 				Message::Animation(message) => {
-					self.message_handlers.animation_message_handler.process_message(message, &mut queue, ());
+					let playback = self.message_handlers.portfolio_message_handler.active_document_mut().map(|document| &mut document.playback);
+					let context = AnimationMessageContext { playback };
+
+					self.message_handlers.animation_message_handler.process_message(message, &mut queue, context);
 				}
 				Message::AppWindow(message) => {
 					self.message_handlers.app_window_message_handler.process_message(message, &mut queue, ());
@@ -186,7 +193,6 @@ impl Dispatcher {
 					let message_logging_verbosity = self.message_handlers.debug_message_handler.message_logging_verbosity;
 					let reset_node_definitions_on_open = self.message_handlers.portfolio_message_handler.reset_node_definitions_on_open;
 					let timing_information = self.message_handlers.animation_message_handler.timing_information();
-					let animation = &self.message_handlers.animation_message_handler;
 
 					self.message_handlers.portfolio_message_handler.process_message(
 						message,
@@ -198,7 +204,6 @@ impl Dispatcher {
 							message_logging_verbosity,
 							reset_node_definitions_on_open,
 							timing_information,
-							animation,
 						},
 					);
 				}
@@ -261,8 +266,9 @@ impl Dispatcher {
 		list
 	}
 
-	pub fn poll_node_graph_evaluation(&mut self, responses: &mut VecDeque<Message>) -> Result<(), String> {
-		self.message_handlers.portfolio_message_handler.poll_node_graph_evaluation(responses)
+	pub fn poll_node_graph_evaluation(&mut self, responses: &mut VecDeque<Message>) -> Result<(), NodeGraphExecutionError> {
+		let time = self.message_handlers.input_preprocessor_message_handler.time as f64;
+		self.message_handlers.portfolio_message_handler.poll_node_graph_evaluation(time, responses)
 	}
 
 	/// Create the tree structure for logging the messages as a tree
@@ -6,10 +6,13 @@ use super::animation_message_handler::AnimationTimeMode;
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
 pub enum AnimationMessage {
 	ToggleLivePreview,
-	EnableLivePreview,
-	DisableLivePreview,
+	Play,
+	Pause,
 	RestartAnimation,
+	Seek { time: f64 },
 	SetFrameIndex { frame: f64 },
+	SetFps { fps: f64 },
+	SetLoopRange { range: Option<(f64, f64)> },
 	SetTime { time: f64 },
 	UpdateTime,
 	IncrementFrameCounter,
@@ -3,132 +3,172 @@ use std::time::Duration;
 use crate::messages::prelude::*;
 
 use super::TimingInformation;
+use super::utility_types::PlaybackState;
 
 #[derive(PartialEq, Clone, Default, Debug, serde::Serialize, serde::Deserialize)]
 pub enum AnimationTimeMode {
+	/// Playback advances with the clock, evaluating the graph at the playback's frame rate.
 	#[default]
 	TimeBased,
+	/// Playback advances by one frame for each frame drawn by the editor, however long it took.
 	FrameBased,
 }
 
-#[derive(Default, Debug, Clone, PartialEq)]
-enum AnimationState {
-	#[default]
-	Stopped,
-	Playing {
-		start: f64,
-	},
-	Paused {
-		start: f64,
-		pause_time: f64,
-	},
+#[derive(ExtractField)]
+pub struct AnimationMessageContext<'a> {
+	/// The playback of the active document, if there is one.
+	pub playback: Option<&'a mut PlaybackState>,
 }
 
+/// Plays back the animation of the active document, advancing its time with the clock and rendering each of its frames.
 #[derive(Default, Debug, Clone, PartialEq, ExtractField)]
 pub struct AnimationMessageHandler {
-	/// Used to re-send the UI on the next frame after playback starts
-	live_preview_recently_zero: bool,
+	/// The current time of the clock, in milliseconds.
 	timestamp: f64,
-	frame_index: f64,
-	animation_state: AnimationState,
-	fps: f64,
 	animation_time_mode: AnimationTimeMode,
 }
 impl AnimationMessageHandler {
+	/// The current time of the clock, along with the animation time of a document which isn't animated.
+	/// Each document's graph is evaluated at the time of its own [`PlaybackState`].
 	pub(crate) fn timing_information(&self) -> TimingInformation {
-		let animation_time = self.timestamp - self.animation_start();
-		let animation_time = match self.animation_time_mode {
-			AnimationTimeMode::TimeBased => Duration::from_millis(animation_time as u64),
-			AnimationTimeMode::FrameBased => Duration::from_secs((self.frame_index / self.fps) as u64),
-		};
-		TimingInformation { time: self.timestamp, animation_time }
+		TimingInformation {
+			time: self.timestamp,
+			animation_time: Duration::ZERO,
+		}
 	}
+}
+
+#[message_handler_data]
+impl MessageHandler<AnimationMessage, AnimationMessageContext<'_>> for AnimationMessageHandler {
+	fn process_message(&mut self, message: AnimationMessage, responses: &mut VecDeque<Message>, context: AnimationMessageContext) {
+		let AnimationMessageContext { playback } = context;
 
-	pub(crate) fn animation_start(&self) -> f64 {
-		match self.animation_state {
-			AnimationState::Stopped => self.timestamp,
-			AnimationState::Playing { start } => start,
-			AnimationState::Paused { start, pause_time } => start + self.timestamp - pause_time,
+		match message {
+			AnimationMessage::SetTime { time } => {
+				self.timestamp = time;
+				responses.add(AnimationMessage::UpdateTime);
+			}
+			AnimationMessage::SetAnimationTimeMode { animation_time_mode } => {
+				self.animation_time_mode = animation_time_mode;
+			}
+			_ => {
+				let Some(playback) = playback else { return };
+				self.process_playback_message(message, playback, responses);
+			}
 		}
 	}
 
-	pub fn is_playing(&self) -> bool {
-		matches!(self.animation_state, AnimationState::Playing { .. })
-	}
+	advertise_actions!(AnimationMessageDiscriminant;
+		ToggleLivePreview,
+		SetFrameIndex,
+		RestartAnimation,
+	);
 }
 
-#[message_handler_data]
-impl MessageHandler<AnimationMessage, ()> for AnimationMessageHandler {
-	fn process_message(&mut self, message: AnimationMessage, responses: &mut VecDeque<Message>, _: ()) {
+impl AnimationMessageHandler {
+	fn process_playback_message(&mut self, message: AnimationMessage, playback: &mut PlaybackState, responses: &mut VecDeque<Message>) {
+		let was_at_start = playback.time() == 0.;
+		let was_playing = playback.is_playing();
+
 		match message {
-			AnimationMessage::ToggleLivePreview => match self.animation_state {
-				AnimationState::Stopped => responses.add(AnimationMessage::EnableLivePreview),
-				AnimationState::Playing { .. } => responses.add(AnimationMessage::DisableLivePreview),
-				AnimationState::Paused { .. } => responses.add(AnimationMessage::EnableLivePreview),
-			},
-			AnimationMessage::EnableLivePreview => {
-				self.animation_state = AnimationState::Playing { start: self.animation_start() };
-
-				// Update the restart and pause/play buttons
-				responses.add(PortfolioMessage::UpdateDocumentWidgets);
+			AnimationMessage::ToggleLivePreview => {
+				responses.add(if playback.is_playing() { AnimationMessage::Pause } else { AnimationMessage::Play });
+			}
+			AnimationMessage::Play => {
+				playback.play(self.timestamp);
+				responses.add(PortfolioMessage::SubmitActiveGraphRender);
 			}
-			AnimationMessage::DisableLivePreview => {
-				match self.animation_state {
-					AnimationState::Stopped => (),
-					AnimationState::Playing { start } => self.animation_state = AnimationState::Paused { start, pause_time: self.timestamp },
-					AnimationState::Paused { .. } => (),
+			AnimationMessage::Pause => playback.pause(),
+			AnimationMessage::RestartAnimation => {
+				playback.seek(playback.loop_range().map_or(0., |(start, _)| start));
+				if playback.is_playing() {
+					playback.play(self.timestamp);
 				}
-
-				// Update the restart and pause/play buttons
-				responses.add(PortfolioMessage::UpdateDocumentWidgets);
+				responses.add(PortfolioMessage::SubmitActiveGraphRender);
 			}
-			AnimationMessage::SetFrameIndex { frame } => {
-				self.frame_index = frame;
+			AnimationMessage::Seek { time } => {
+				playback.seek(time);
 				responses.add(PortfolioMessage::SubmitActiveGraphRender);
-				// Update the restart and pause/play buttons
-				responses.add(PortfolioMessage::UpdateDocumentWidgets);
 			}
-			AnimationMessage::SetTime { time } => {
-				self.timestamp = time;
-				responses.add(AnimationMessage::UpdateTime);
+			AnimationMessage::SetFrameIndex { frame } => {
+				playback.seek(frame / playback.fps());
+				responses.add(PortfolioMessage::SubmitActiveGraphRender);
 			}
-			AnimationMessage::IncrementFrameCounter => {
-				if self.is_playing() {
-					self.frame_index += 1.;
-					responses.add(AnimationMessage::UpdateTime);
-				}
+			AnimationMessage::SetFps { fps } => playback.set_fps(fps),
+			AnimationMessage::SetLoopRange { range } => {
+				playback.set_loop_range(range);
+				responses.add(PortfolioMessage::SubmitActiveGraphRender);
 			}
 			AnimationMessage::UpdateTime => {
-				if self.is_playing() {
+				if self.animation_time_mode == AnimationTimeMode::TimeBased && playback.advance(self.timestamp) {
 					responses.add(PortfolioMessage::SubmitActiveGraphRender);
-
-					if self.live_preview_recently_zero {
-						// Update the restart and pause/play buttons
-						responses.add(PortfolioMessage::UpdateDocumentWidgets);
-						self.live_preview_recently_zero = false;
-					}
 				}
 			}
-			AnimationMessage::RestartAnimation => {
-				self.frame_index = 0.;
-				self.animation_state = match self.animation_state {
-					AnimationState::Playing { .. } => AnimationState::Playing { start: self.timestamp },
-					_ => AnimationState::Stopped,
-				};
-				self.live_preview_recently_zero = true;
-				responses.add(PortfolioMessage::SubmitActiveGraphRender);
-				// Update the restart and pause/play buttons
-				responses.add(PortfolioMessage::UpdateDocumentWidgets);
-			}
-			AnimationMessage::SetAnimationTimeMode { animation_time_mode } => {
-				self.animation_time_mode = animation_time_mode;
+			AnimationMessage::IncrementFrameCounter => {
+				if self.animation_time_mode == AnimationTimeMode::FrameBased && playback.step() {
+					responses.add(PortfolioMessage::SubmitActiveGraphRender);
+				}
 			}
+			AnimationMessage::SetTime { .. } | AnimationMessage::SetAnimationTimeMode { .. } => {}
+		}
+
+		// Update the restart and pause/play buttons
+		if was_playing != playback.is_playing() || was_at_start != (playback.time() == 0.) {
+			responses.add(PortfolioMessage::UpdateDocumentWidgets);
 		}
 	}
+}
 
-	advertise_actions!(AnimationMessageDiscriminant;
-		ToggleLivePreview,
-		SetFrameIndex,
-		RestartAnimation,
-	);
+#[cfg(test)]
+mod test {
+	use crate::test_utils::test_prelude::*;
+
+	fn submitted_renders(editor: &EditorTestUtils) -> u64 {
+		editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.submitted_evaluation_count()
+	}
+
+	fn playback_time(editor: &EditorTestUtils) -> f64 {
+		editor.active_document().playback.time()
+	}
+
+	#[tokio::test]
+	async fn playing_renders_each_frame_at_the_frame_rate() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.editor.handle_message(AnimationMessage::SetFps { fps: 10. });
+		editor.editor.handle_message(AnimationMessage::SetTime { time: 1000. });
+		editor.editor.handle_message(AnimationMessage::Play);
+		let renders = submitted_renders(&editor);
+
+		// The clock ticking before the next frame is due doesn't render the document again
+		editor.editor.handle_message(AnimationMessage::SetTime { time: 1050. });
+		assert_eq!(submitted_renders(&editor), renders);
+		assert_eq!(playback_time(&editor), 0.);
+
+		editor.editor.handle_message(AnimationMessage::SetTime { time: 1100. });
+		assert_eq!(submitted_renders(&editor), renders + 1);
+		assert_eq!(playback_time(&editor), 0.1);
+
+		editor.editor.handle_message(AnimationMessage::Pause);
+		editor.editor.handle_message(AnimationMessage::SetTime { time: 2000. });
+		assert_eq!(submitted_renders(&editor), renders + 1);
+		assert_eq!(playback_time(&editor), 0.1);
+
+		// Seeking renders the frame at the new time
+		editor.editor.handle_message(AnimationMessage::Seek { time: 5. });
+		assert_eq!(submitted_renders(&editor), renders + 2);
+		assert_eq!(playback_time(&editor), 5.);
+	}
+
+	#[tokio::test]
+	async fn each_document_has_its_own_playback() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.editor.handle_message(AnimationMessage::Seek { time: 2. });
+		editor.editor.handle_message(AnimationMessage::Play);
+
+		editor.new_document().await;
+		assert_eq!(playback_time(&editor), 0.);
+		assert!(!editor.active_document().playback.is_playing());
+	}
 }
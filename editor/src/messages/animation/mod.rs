@@ -1,9 +1,11 @@
 mod animation_message;
 mod animation_message_handler;
 
+pub mod utility_types;
+
 #[doc(inline)]
 pub use animation_message::{AnimationMessage, AnimationMessageDiscriminant};
 #[doc(inline)]
-pub use animation_message_handler::AnimationMessageHandler;
+pub use animation_message_handler::{AnimationMessageContext, AnimationMessageHandler};
 
 pub use graphene_std::application_io::TimingInformation;
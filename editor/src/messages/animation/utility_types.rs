@@ -0,0 +1,187 @@
+use crate::consts::DEFAULT_PLAYBACK_FPS;
+use std::time::Duration;
+
+/// The playback of a document's animation, which sets the time that its graph is evaluated at.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+#[serde(default)]
+pub struct PlaybackState {
+	/// The current time of the animation in seconds.
+	time: f64,
+	/// How many frames are evaluated each second while playing.
+	fps: f64,
+	/// The start and end times, in seconds, which playback loops between.
+	loop_range: Option<(f64, f64)>,
+	#[serde(skip)]
+	playing: bool,
+	/// The timestamp, in milliseconds, at which the current frame started to be shown while playing.
+	#[serde(skip)]
+	frame_timestamp: Option<f64>,
+}
+
+impl Default for PlaybackState {
+	fn default() -> Self {
+		Self {
+			time: 0.,
+			fps: DEFAULT_PLAYBACK_FPS,
+			loop_range: None,
+			playing: false,
+			frame_timestamp: None,
+		}
+	}
+}
+
+impl PlaybackState {
+	pub fn time(&self) -> f64 {
+		self.time
+	}
+
+	/// The current time of the animation, as given to the graph when evaluating it.
+	pub fn animation_time(&self) -> Duration {
+		Duration::from_secs_f64(self.time)
+	}
+
+	pub fn fps(&self) -> f64 {
+		self.fps
+	}
+
+	pub fn loop_range(&self) -> Option<(f64, f64)> {
+		self.loop_range
+	}
+
+	pub fn is_playing(&self) -> bool {
+		self.playing
+	}
+
+	/// How long each frame is shown for while playing, which is how often the graph is evaluated.
+	pub fn frame_duration(&self) -> Duration {
+		Duration::from_secs_f64(1. / self.fps)
+	}
+
+	/// Starts playing from the current time, with the first frame being shown at the given timestamp in milliseconds.
+	pub fn play(&mut self, timestamp: f64) {
+		self.playing = true;
+		self.frame_timestamp = Some(timestamp);
+		self.time = self.wrapped(self.time);
+	}
+
+	pub fn pause(&mut self) {
+		self.playing = false;
+		self.frame_timestamp = None;
+	}
+
+	/// Jumps to the given time in seconds, from where playback continues.
+	pub fn seek(&mut self, time: f64) {
+		if time.is_finite() {
+			self.time = time.max(0.);
+		}
+	}
+
+	/// Sets how many frames are evaluated each second while playing, ignoring rates which aren't positive.
+	pub fn set_fps(&mut self, fps: f64) {
+		if fps.is_finite() && fps > 0. {
+			self.fps = fps;
+		}
+	}
+
+	/// Sets the times which playback loops between, or stops looping with `None`. Ranges which end before they start are ignored.
+	pub fn set_loop_range(&mut self, range: Option<(f64, f64)>) {
+		if let Some((start, end)) = range
+			&& !(start.is_finite() && end.is_finite() && 0. <= start && start < end)
+		{
+			return;
+		}
+
+		self.loop_range = range;
+		if self.playing {
+			self.time = self.wrapped(self.time);
+		}
+	}
+
+	/// Advances the time by the frames which have passed by the given timestamp in milliseconds while playing.
+	/// Returns whether a new frame is due, which needs the graph to be evaluated at the new time.
+	pub fn advance(&mut self, timestamp: f64) -> bool {
+		let Some(frame_timestamp) = self.frame_timestamp.filter(|_| self.playing) else { return false };
+		let frame_milliseconds = 1000. / self.fps;
+
+		let frames = ((timestamp - frame_timestamp) / frame_milliseconds).floor();
+		if frames < 1. {
+			// The clock may also have gone back, in which case the next frame is timed from now
+			if timestamp < frame_timestamp {
+				self.frame_timestamp = Some(timestamp);
+			}
+			return false;
+		}
+
+		self.frame_timestamp = Some(frame_timestamp + frames * frame_milliseconds);
+		self.time = self.wrapped(self.time + frames / self.fps);
+		true
+	}
+
+	/// Advances the time by exactly one frame while playing, regardless of how much time has passed. Returns whether the time changed.
+	pub fn step(&mut self) -> bool {
+		if !self.playing {
+			return false;
+		}
+		self.time = self.wrapped(self.time + 1. / self.fps);
+		true
+	}
+
+	/// Brings a time which is outside of the loop range back into it.
+	fn wrapped(&self, time: f64) -> f64 {
+		let Some((start, end)) = self.loop_range else { return time };
+		if time < start {
+			start
+		} else if time >= end {
+			start + (time - start) % (end - start)
+		} else {
+			time
+		}
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn playback_advances_by_whole_frames() {
+		let mut playback = PlaybackState::default();
+		playback.set_fps(10.);
+		assert!(!playback.advance(1000.));
+
+		playback.play(0.);
+		// Less than a frame has passed
+		assert!(!playback.advance(50.));
+		assert_eq!(playback.time(), 0.);
+
+		assert!(playback.advance(100.));
+		assert_eq!(playback.time(), 0.1);
+		// Frames which were missed are skipped over, keeping the playback in time with the clock
+		assert!(playback.advance(350.));
+		assert!((playback.time() - 0.3).abs() < 1e-9);
+
+		playback.pause();
+		assert!(!playback.advance(1000.));
+		assert!((playback.time() - 0.3).abs() < 1e-9);
+	}
+
+	#[test]
+	fn playback_loops_within_its_range() {
+		let mut playback = PlaybackState::default();
+		playback.set_fps(10.);
+		playback.set_loop_range(Some((1., 2.)));
+		// Ranges which end before they start are ignored
+		playback.set_loop_range(Some((2., 1.)));
+		assert_eq!(playback.loop_range(), Some((1., 2.)));
+
+		// Playing from before the range starts at its start
+		playback.play(0.);
+		assert_eq!(playback.time(), 1.);
+
+		assert!(playback.advance(1500.));
+		assert!((playback.time() - 1.5).abs() < 1e-9);
+		playback.seek(1.95);
+		assert!(playback.advance(1600.));
+		assert!((playback.time() - 1.05).abs() < 1e-9);
+	}
+}
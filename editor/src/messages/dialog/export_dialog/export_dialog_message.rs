@@ -1,4 +1,5 @@
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, RasterColorSpace};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
 
 #[impl_message(Message, DialogMessage, ExportDialog)]
@@ -8,6 +9,11 @@ pub enum ExportDialogMessage {
 	ScaleFactor(f64),
 	TransparentBackground(bool),
 	ExportBounds(ExportBounds),
+	ColorSpace(RasterColorSpace),
+	EachArtboard(bool),
+	IncludeArtboard { artboard: LayerNodeIdentifier, include: bool },
+	ScaleFactors(String),
+	FileNameTemplate(String),
 
 	Submit,
 }
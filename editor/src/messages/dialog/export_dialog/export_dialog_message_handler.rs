@@ -1,4 +1,5 @@
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::consts::FILE_SAVE_SUFFIX;
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, RasterColorSpace};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
@@ -15,8 +16,16 @@ pub struct ExportDialogMessageHandler {
 	pub scale_factor: f64,
 	pub bounds: ExportBounds,
 	pub transparent_background: bool,
-	pub artboards: HashMap<LayerNodeIdentifier, String>,
+	pub color_space: RasterColorSpace,
+	pub artboards: Vec<(LayerNodeIdentifier, String)>,
 	pub has_selection: bool,
+	/// Whether each artboard is exported as a separate file, at each of the scale factors, instead of exporting the bounds as one file.
+	pub each_artboard: bool,
+	/// The artboards left out when exporting each artboard.
+	pub excluded_artboards: HashSet<LayerNodeIdentifier>,
+	pub scale_factors: Vec<f64>,
+	/// The name given to the file of each artboard and scale factor, filled in by [`artboard_file_name`].
+	pub file_name_template: String,
 }
 
 impl Default for ExportDialogMessageHandler {
@@ -26,12 +35,54 @@ impl Default for ExportDialogMessageHandler {
 			scale_factor: 1.,
 			bounds: Default::default(),
 			transparent_background: false,
+			color_space: Default::default(),
 			artboards: Default::default(),
 			has_selection: false,
+			each_artboard: false,
+			excluded_artboards: Default::default(),
+			scale_factors: vec![1.],
+			file_name_template: DEFAULT_FILE_NAME_TEMPLATE.to_string(),
 		}
 	}
 }
 
+const DEFAULT_FILE_NAME_TEMPLATE: &str = "{artboard}_{scale}x";
+
+/// Fills in the `{artboard}`, `{scale}`, and `{document}` placeholders of a file name template for the export of an artboard at a scale factor.
+pub fn artboard_file_name(template: &str, document_name: &str, artboard_name: &str, scale_factor: f64) -> String {
+	let document_name = document_name.strip_suffix(FILE_SAVE_SUFFIX).unwrap_or(document_name);
+	let name = template
+		.replace("{artboard}", artboard_name)
+		.replace("{scale}", &scale_factor.to_string())
+		.replace("{document}", document_name);
+
+	// Names can't place the file into other folders
+	name.replace(['/', '\\'], "_")
+}
+
+/// Reads a list of scale factors like `1x, 2x, 3x`, ignoring those which aren't positive numbers.
+fn parse_scale_factors(text: &str) -> Vec<f64> {
+	let mut scale_factors = Vec::new();
+	for scale_factor in text.split([',', ' ']).map(|scale_factor| scale_factor.trim().trim_end_matches(['x', 'X'])) {
+		let Ok(scale_factor) = scale_factor.parse::<f64>() else { continue };
+		if scale_factor.is_finite() && scale_factor > 0. && !scale_factors.contains(&scale_factor) {
+			scale_factors.push(scale_factor);
+		}
+	}
+	scale_factors
+}
+
+impl ExportDialogMessageHandler {
+	/// Whether each artboard is exported, which requires the document to have artboards.
+	fn exports_each_artboard(&self) -> bool {
+		self.each_artboard && !self.artboards.is_empty()
+	}
+
+	fn included_artboards(&self) -> impl Iterator<Item = &(LayerNodeIdentifier, String)> {
+		self.artboards.iter().filter(|(artboard, _)| !self.excluded_artboards.contains(artboard))
+	}
+}
+
 #[message_handler_data]
 impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for ExportDialogMessageHandler {
 	fn process_message(&mut self, message: ExportDialogMessage, responses: &mut VecDeque<Message>, context: ExportDialogMessageContext) {
@@ -42,13 +93,40 @@ impl MessageHandler<ExportDialogMessage, ExportDialogMessageContext<'_>> for Exp
 			ExportDialogMessage::ScaleFactor(factor) => self.scale_factor = factor,
 			ExportDialogMessage::TransparentBackground(transparent_background) => self.transparent_background = transparent_background,
 			ExportDialogMessage::ExportBounds(export_area) => self.bounds = export_area,
+			ExportDialogMessage::ColorSpace(color_space) => self.color_space = color_space,
+			ExportDialogMessage::EachArtboard(each_artboard) => self.each_artboard = each_artboard,
+			ExportDialogMessage::IncludeArtboard { artboard, include } => {
+				if include {
+					self.excluded_artboards.remove(&artboard);
+				} else {
+					self.excluded_artboards.insert(artboard);
+				}
+			}
+			ExportDialogMessage::ScaleFactors(text) => {
+				let scale_factors = parse_scale_factors(&text);
+				// Keep the previous scale factors if none could be read, so the list is never left empty
+				if !scale_factors.is_empty() {
+					self.scale_factors = scale_factors;
+				}
+			}
+			ExportDialogMessage::FileNameTemplate(file_name_template) => self.file_name_template = file_name_template,
 
+			ExportDialogMessage::Submit if self.exports_each_artboard() => responses.add_front(PortfolioMessage::SubmitArtboardsExport {
+				artboards: self.included_artboards().cloned().collect(),
+				// SVG files are vector graphics, so they're only exported once at their original size
+				scale_factors: if self.file_type == FileType::Svg { vec![1.] } else { self.scale_factors.clone() },
+				file_name_template: self.file_name_template.clone(),
+				file_type: self.file_type,
+				transparent_background: self.file_type != FileType::Jpg && self.transparent_background,
+				color_space: self.color_space,
+			}),
 			ExportDialogMessage::Submit => responses.add_front(PortfolioMessage::SubmitDocumentExport {
 				file_name: portfolio.active_document().map(|document| document.name.clone()).unwrap_or_default(),
 				file_type: self.file_type,
 				scale_factor: self.scale_factor,
 				bounds: self.bounds,
 				transparent_background: self.file_type != FileType::Jpg && self.transparent_background,
+				color_space: self.color_space,
 			}),
 		}
 
@@ -66,6 +144,7 @@ impl DialogLayoutHolder for ExportDialogMessageHandler {
 		let widgets = vec![
 			TextButton::new("Export")
 				.emphasized(true)
+				.disabled(self.exports_each_artboard() && self.included_artboards().next().is_none())
 				.on_update(|_| {
 					DialogMessage::CloseDialogAndThen {
 						followups: vec![ExportDialogMessage::Submit.into()],
@@ -93,6 +172,10 @@ impl LayoutHolder for ExportDialogMessageHandler {
 			RadioInput::new(entries).selected_index(Some(self.file_type as u32)).widget_holder(),
 		];
 
+		if self.exports_each_artboard() {
+			return self.each_artboard_layout(export_type);
+		}
+
 		let resolution = vec![
 			TextLabel::new("Scale Factor").table_align(true).min_width(100).widget_holder(),
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
@@ -110,7 +193,7 @@ impl LayoutHolder for ExportDialogMessageHandler {
 			(ExportBounds::AllArtwork, "All Artwork".to_string(), false),
 			(ExportBounds::Selection, "Selection".to_string(), !self.has_selection),
 		];
-		let artboards = self.artboards.iter().map(|(&layer, name)| (ExportBounds::Artboard(layer), name.to_string(), false)).collect();
+		let artboards = self.artboards.iter().map(|(layer, name)| (ExportBounds::Artboard(*layer), name.to_string(), false)).collect();
 		let groups = [standard_bounds, artboards];
 
 		let current_bounds = if !self.has_selection && self.bounds == ExportBounds::Selection {
@@ -145,8 +228,95 @@ impl LayoutHolder for ExportDialogMessageHandler {
 			DropdownInput::new(entries).selected_index(Some(index as u32)).widget_holder(),
 		];
 
+		Layout::WidgetLayout(WidgetLayout::new(vec![
+			LayoutGroup::Row { widgets: export_type },
+			LayoutGroup::Row { widgets: self.each_artboard_row() },
+			LayoutGroup::Row { widgets: resolution },
+			LayoutGroup::Row { widgets: export_area },
+			LayoutGroup::Row {
+				widgets: self.transparent_background_row(),
+			},
+			LayoutGroup::Row { widgets: self.color_space_row() },
+		]))
+	}
+}
+
+impl ExportDialogMessageHandler {
+	fn each_artboard_layout(&self, export_type: Vec<WidgetHolder>) -> Layout {
+		let scale_factors = self.scale_factors.iter().map(|scale_factor| format!("{scale_factor}x")).collect::<Vec<_>>().join(", ");
+		let resolutions = vec![
+			TextLabel::new("Scale Factors").table_align(true).min_width(100).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextInput::new(scale_factors)
+				.tooltip("The scale factors at which each artboard is exported, separated by commas")
+				.disabled(self.file_type == FileType::Svg)
+				.on_update(|text_input: &TextInput| ExportDialogMessage::ScaleFactors(text_input.value.clone()).into())
+				.min_width(200)
+				.widget_holder(),
+		];
+
+		let file_name_template = vec![
+			TextLabel::new("File Names").table_align(true).min_width(100).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextInput::new(&self.file_name_template)
+				.tooltip("The name of each exported file, where {artboard} is replaced by the artboard's name, {scale} by the scale factor, and {document} by the document's name")
+				.on_update(|text_input: &TextInput| ExportDialogMessage::FileNameTemplate(text_input.value.clone()).into())
+				.min_width(200)
+				.widget_holder(),
+		];
+
+		let mut rows = vec![
+			LayoutGroup::Row { widgets: export_type },
+			LayoutGroup::Row { widgets: self.each_artboard_row() },
+			LayoutGroup::Row { widgets: resolutions },
+			LayoutGroup::Row { widgets: file_name_template },
+		];
+
+		for (index, (artboard, name)) in self.artboards.iter().enumerate() {
+			let artboard = *artboard;
+			let mut checkbox_id = CheckboxId::default();
+			let name = TextLabel::new(name).for_checkbox(&mut checkbox_id).widget_holder();
+			rows.push(LayoutGroup::Row {
+				widgets: vec![
+					TextLabel::new(if index == 0 { "Artboards" } else { "" }).table_align(true).min_width(100).widget_holder(),
+					Separator::new(SeparatorType::Unrelated).widget_holder(),
+					CheckboxInput::new(!self.excluded_artboards.contains(&artboard))
+						.on_update(move |value: &CheckboxInput| ExportDialogMessage::IncludeArtboard { artboard, include: value.checked }.into())
+						.for_label(checkbox_id.clone())
+						.widget_holder(),
+					Separator::new(SeparatorType::Related).widget_holder(),
+					name,
+				],
+			});
+		}
+
+		rows.push(LayoutGroup::Row {
+			widgets: self.transparent_background_row(),
+		});
+		rows.push(LayoutGroup::Row { widgets: self.color_space_row() });
+
+		Layout::WidgetLayout(WidgetLayout::new(rows))
+	}
+
+	fn each_artboard_row(&self) -> Vec<WidgetHolder> {
+		let entries = [(false, "Single File"), (true, "Each Artboard")]
+			.into_iter()
+			.map(|(each_artboard, name)| RadioEntryData::new(name).label(name).on_update(move |_| ExportDialogMessage::EachArtboard(each_artboard).into()))
+			.collect();
+
+		vec![
+			TextLabel::new("Export").table_align(true).min_width(100).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			RadioInput::new(entries)
+				.selected_index(Some(self.exports_each_artboard() as u32))
+				.disabled(self.artboards.is_empty())
+				.widget_holder(),
+		]
+	}
+
+	fn transparent_background_row(&self) -> Vec<WidgetHolder> {
 		let mut checkbox_id = CheckboxId::default();
-		let transparent_background = vec![
+		vec![
 			TextLabel::new("Transparency").table_align(true).min_width(100).for_checkbox(&mut checkbox_id).widget_holder(),
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
 			CheckboxInput::new(self.transparent_background)
@@ -154,13 +324,60 @@ impl LayoutHolder for ExportDialogMessageHandler {
 				.on_update(move |value: &CheckboxInput| ExportDialogMessage::TransparentBackground(value.checked).into())
 				.for_label(checkbox_id.clone())
 				.widget_holder(),
-		];
+		]
+	}
 
-		Layout::WidgetLayout(WidgetLayout::new(vec![
-			LayoutGroup::Row { widgets: export_type },
-			LayoutGroup::Row { widgets: resolution },
-			LayoutGroup::Row { widgets: export_area },
-			LayoutGroup::Row { widgets: transparent_background },
-		]))
+	fn color_space_row(&self) -> Vec<WidgetHolder> {
+		// SVG files don't need this since colors outside of sRGB are written in the Display P3 space
+		let entries = [
+			(
+				RasterColorSpace::Srgb,
+				"sRGB",
+				"Convert colors to sRGB, clipping those outside of it, so the image looks the same in every viewer",
+			),
+			(
+				RasterColorSpace::DisplayP3,
+				"Display P3",
+				"Tag the image as Display P3, keeping wide-gamut colors for viewers and displays which support it",
+			),
+		]
+		.into_iter()
+		.map(|(val, name, tooltip)| {
+			RadioEntryData::new(format!("{val:?}"))
+				.label(name)
+				.tooltip(tooltip)
+				.on_update(move |_| ExportDialogMessage::ColorSpace(val).into())
+		})
+		.collect();
+
+		vec![
+			TextLabel::new("Color Space").table_align(true).min_width(100).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			RadioInput::new(entries)
+				.selected_index(Some(self.color_space as u32))
+				.disabled(self.file_type == FileType::Svg)
+				.widget_holder(),
+		]
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn file_names_fill_in_the_template() {
+		assert_eq!(artboard_file_name(DEFAULT_FILE_NAME_TEMPLATE, "Untitled Document.graphite", "Icon", 2.), "Icon_2x");
+		assert_eq!(artboard_file_name("{document} - {artboard}@{scale}", "Poster.graphite", "Front", 0.5), "Poster - Front@0.5");
+
+		// Artboard names can't place the files into other folders
+		assert_eq!(artboard_file_name("{artboard}", "Document", "Left/Right", 1.), "Left_Right");
+	}
+
+	#[test]
+	fn scale_factors_skip_invalid_and_repeated_values() {
+		assert_eq!(parse_scale_factors("1x, 2x,3X 0.5"), vec![1., 2., 3., 0.5]);
+		assert_eq!(parse_scale_factors("2, 2x, -1, 0, abc, inf"), vec![2.]);
+		assert!(parse_scale_factors("").is_empty());
 	}
 }
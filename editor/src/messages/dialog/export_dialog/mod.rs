@@ -4,4 +4,4 @@ mod export_dialog_message_handler;
 #[doc(inline)]
 pub use export_dialog_message::{ExportDialogMessage, ExportDialogMessageDiscriminant};
 #[doc(inline)]
-pub use export_dialog_message_handler::{ExportDialogMessageContext, ExportDialogMessageHandler};
+pub use export_dialog_message_handler::{ExportDialogMessageContext, ExportDialogMessageHandler, artboard_file_name};
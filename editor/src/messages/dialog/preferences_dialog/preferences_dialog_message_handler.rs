@@ -1,7 +1,7 @@
 use crate::consts::{VIEWPORT_ZOOM_WHEEL_RATE, VIEWPORT_ZOOM_WHEEL_RATE_CHANGE};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
-use crate::messages::preferences::SelectionMode;
+use crate::messages::preferences::{InteractiveRenderQuality, OverlayQuality, OverlaySupersampling, SelectionMode};
 use crate::messages::prelude::*;
 
 #[derive(ExtractField)]
@@ -206,6 +206,130 @@ impl PreferencesDialogMessageHandler {
 				.widget_holder(),
 		];
 
+		let overlay_quality = preferences.overlay_quality;
+		let overlay_quality_tooltip = "Rendering quality of the viewport overlays when they are drawn by the native renderer of the desktop app";
+		let overlay_quality_label = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new("Overlay Quality").tooltip(overlay_quality_tooltip).widget_holder(),
+		];
+		let supersampling_entry = |supersampling: OverlaySupersampling| {
+			RadioEntryData::new(supersampling.to_string())
+				.label(supersampling.to_string())
+				.tooltip(supersampling.tooltip_description())
+				.on_update(move |_| {
+					PreferencesMessage::OverlayQuality {
+						quality: OverlayQuality { supersampling, ..overlay_quality },
+					}
+					.into()
+				})
+		};
+		let overlay_supersampling = RadioInput::new(vec![supersampling_entry(OverlaySupersampling::X1), supersampling_entry(OverlaySupersampling::X2)])
+			.selected_index(Some(overlay_quality.supersampling as u32 - 1))
+			.widget_holder();
+		let overlay_supersampling = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			overlay_supersampling,
+		];
+
+		let mut checkbox_id = CheckboxId::default();
+		let overlay_anti_aliasing_tooltip = "Smooth the edges of overlay strokes and shapes.\n\nTurning this off makes overlays cheaper to draw, but their diagonal and curved edges appear jagged.";
+		let overlay_anti_aliasing = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			CheckboxInput::new(overlay_quality.anti_aliasing)
+				.tooltip(overlay_anti_aliasing_tooltip)
+				.on_update(move |checkbox_input: &CheckboxInput| {
+					PreferencesMessage::OverlayQuality {
+						quality: OverlayQuality {
+							anti_aliasing: checkbox_input.checked,
+							..overlay_quality
+						},
+					}
+					.into()
+				})
+				.for_label(checkbox_id.clone())
+				.widget_holder(),
+			TextLabel::new("Overlay Anti-Aliasing")
+				.table_align(true)
+				.tooltip(overlay_anti_aliasing_tooltip)
+				.for_checkbox(&mut checkbox_id)
+				.widget_holder(),
+		];
+
+		let interactive_render_quality_tooltip = "Resolution of raster content while dragging sliders, gizmos, and tools, which is restored to full quality upon release";
+		let interactive_render_quality_label = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new("Interactive Render Quality").tooltip(interactive_render_quality_tooltip).widget_holder(),
+		];
+		let interactive_render_quality_entry = |quality: InteractiveRenderQuality| {
+			RadioEntryData::new(quality.to_string())
+				.label(quality.to_string())
+				.tooltip(quality.tooltip_description())
+				.on_update(move |_| PreferencesMessage::InteractiveRenderQuality { quality }.into())
+		};
+		let interactive_render_quality = RadioInput::new(vec![
+			interactive_render_quality_entry(InteractiveRenderQuality::Off),
+			interactive_render_quality_entry(InteractiveRenderQuality::Half),
+			interactive_render_quality_entry(InteractiveRenderQuality::Quarter),
+			interactive_render_quality_entry(InteractiveRenderQuality::Auto),
+		])
+		.selected_index(Some(preferences.interactive_render_quality as u32))
+		.widget_holder();
+		let interactive_render_quality = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			interactive_render_quality,
+		];
+
+		let brush_cache_budget_tooltip = "The memory which may be used to speed up painting with the Brush tool, after which the least recently used brush textures are discarded.\n\nLeave empty to use a quarter of the available memory.";
+		let brush_cache_budget_label = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new("Brush Cache Budget").tooltip(brush_cache_budget_tooltip).widget_holder(),
+		];
+		let brush_cache_budget = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			NumberInput::new(preferences.brush_cache_budget.map(|megabytes| megabytes as f64))
+				.tooltip(brush_cache_budget_tooltip)
+				.int()
+				.min(16.)
+				.unit(" MB")
+				.on_update(|number_input: &NumberInput| {
+					PreferencesMessage::BrushCacheBudget {
+						megabytes: number_input.value.map(|megabytes| megabytes as u32),
+					}
+					.into()
+				})
+				.widget_holder(),
+		];
+
+		let node_cache_budget_tooltip = "The memory which may be used to keep the outputs of nodes from earlier graph evaluations for reuse, after which the least recently used outputs are discarded.\n\nLeave empty to use 512 MB.";
+		let node_cache_budget_label = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new("Node Cache Budget").tooltip(node_cache_budget_tooltip).widget_holder(),
+		];
+		let node_cache_budget = vec![
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			NumberInput::new(preferences.node_cache_budget.map(|megabytes| megabytes as f64))
+				.tooltip(node_cache_budget_tooltip)
+				.int()
+				.min(16.)
+				.unit(" MB")
+				.on_update(|number_input: &NumberInput| {
+					PreferencesMessage::NodeCacheBudget {
+						megabytes: number_input.value.map(|megabytes| megabytes as u32),
+					}
+					.into()
+				})
+				.widget_holder(),
+		];
+
 		Layout::WidgetLayout(WidgetLayout::new(vec![
 			LayoutGroup::Row { widgets: navigation_header },
 			LayoutGroup::Row { widgets: zoom_rate_label },
@@ -219,6 +343,17 @@ impl PreferencesDialogMessageHandler {
 			LayoutGroup::Row { widgets: graph_wire_style },
 			LayoutGroup::Row { widgets: use_vello },
 			LayoutGroup::Row { widgets: vector_meshes },
+			LayoutGroup::Row { widgets: overlay_quality_label },
+			LayoutGroup::Row { widgets: overlay_supersampling },
+			LayoutGroup::Row { widgets: overlay_anti_aliasing },
+			LayoutGroup::Row {
+				widgets: interactive_render_quality_label,
+			},
+			LayoutGroup::Row { widgets: interactive_render_quality },
+			LayoutGroup::Row { widgets: brush_cache_budget_label },
+			LayoutGroup::Row { widgets: brush_cache_budget },
+			LayoutGroup::Row { widgets: node_cache_budget_label },
+			LayoutGroup::Row { widgets: node_cache_budget },
 		]))
 	}
 
@@ -0,0 +1,44 @@
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::prelude::*;
+
+/// A dialog to show the progress of exporting each artboard, which lets users cancel the exports that remain.
+pub struct ExportProgressDialog {
+	/// How many of the files have been exported so far.
+	pub exported: usize,
+	pub total: usize,
+	/// The name of the file being exported.
+	pub file_name: String,
+}
+
+impl DialogLayoutHolder for ExportProgressDialog {
+	const ICON: &'static str = "File";
+	const TITLE: &'static str = "Exporting";
+
+	fn layout_buttons(&self) -> Layout {
+		let widgets = vec![
+			TextButton::new("Cancel")
+				.on_update(|_| {
+					DialogMessage::CloseDialogAndThen {
+						followups: vec![PortfolioMessage::CancelArtboardsExport.into()],
+					}
+					.into()
+				})
+				.widget_holder(),
+		];
+
+		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets }]))
+	}
+}
+
+impl LayoutHolder for ExportProgressDialog {
+	fn layout(&self) -> Layout {
+		Layout::WidgetLayout(WidgetLayout::new(vec![
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new(format!("Exporting \"{}\"", self.file_name)).bold(true).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new(format!("{} of {} files exported", self.exported, self.total)).widget_holder()],
+			},
+		]))
+	}
+}
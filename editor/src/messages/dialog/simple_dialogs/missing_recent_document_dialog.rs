@@ -0,0 +1,48 @@
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::prelude::*;
+
+/// A dialog offering to remove a recent document from the list when its file can no longer be opened.
+pub struct MissingRecentDocumentDialog {
+	pub path: String,
+	pub name: String,
+}
+
+impl DialogLayoutHolder for MissingRecentDocumentDialog {
+	const ICON: &'static str = "Warning";
+	const TITLE: &'static str = "Missing Document";
+
+	fn layout_buttons(&self) -> Layout {
+		let path = self.path.clone();
+		let widgets = vec![
+			TextButton::new("Remove from List")
+				.emphasized(true)
+				.on_update(move |_| {
+					DialogMessage::CloseDialogAndThen {
+						followups: vec![RecentDocumentsMessage::Remove { path: path.clone() }.into()],
+					}
+					.into()
+				})
+				.widget_holder(),
+			TextButton::new("Keep").on_update(|_| FrontendMessage::DisplayDialogDismiss.into()).widget_holder(),
+		];
+
+		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets }]))
+	}
+}
+
+impl LayoutHolder for MissingRecentDocumentDialog {
+	fn layout(&self) -> Layout {
+		Layout::WidgetLayout(WidgetLayout::new(vec![
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new(format!("\"{}\" could not be opened", self.name)).bold(true).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					TextLabel::new("The file may have been moved, renamed, or deleted, or the browser may not allow reopening it. Remove it from the recent documents?")
+						.multiline(true)
+						.widget_holder(),
+				],
+			},
+		]))
+	}
+}
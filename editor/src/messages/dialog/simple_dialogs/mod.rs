@@ -4,7 +4,10 @@ mod close_document_dialog;
 mod coming_soon_dialog;
 mod demo_artwork_dialog;
 mod error_dialog;
+mod export_progress_dialog;
 mod licenses_dialog;
+mod missing_recent_document_dialog;
+mod svg_import_report_dialog;
 
 pub use about_graphite_dialog::AboutGraphiteDialog;
 pub use close_all_documents_dialog::CloseAllDocumentsDialog;
@@ -13,4 +16,7 @@ pub use coming_soon_dialog::ComingSoonDialog;
 pub use demo_artwork_dialog::ARTWORK;
 pub use demo_artwork_dialog::DemoArtworkDialog;
 pub use error_dialog::ErrorDialog;
+pub use export_progress_dialog::ExportProgressDialog;
 pub use licenses_dialog::LicensesDialog;
+pub use missing_recent_document_dialog::MissingRecentDocumentDialog;
+pub use svg_import_report_dialog::SvgImportReportDialog;
@@ -0,0 +1,32 @@
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::portfolio::document::graph_operation::utility_types::SvgImportReport;
+use crate::messages::prelude::*;
+
+/// A dialog to tell users which parts of an imported SVG couldn't become editable layers, and what was done with them instead.
+pub struct SvgImportReportDialog {
+	pub report: SvgImportReport,
+}
+
+impl DialogLayoutHolder for SvgImportReportDialog {
+	const ICON: &'static str = "Warning";
+	const TITLE: &'static str = "SVG Import";
+
+	fn layout_buttons(&self) -> Layout {
+		let widgets = vec![TextButton::new("OK").emphasized(true).on_update(|_| FrontendMessage::DisplayDialogDismiss.into()).widget_holder()];
+
+		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets }]))
+	}
+}
+
+impl LayoutHolder for SvgImportReportDialog {
+	fn layout(&self) -> Layout {
+		let title = LayoutGroup::Row {
+			widgets: vec![TextLabel::new("Some parts of the SVG have no editable equivalent").bold(true).widget_holder()],
+		};
+		let descriptions = self.report.descriptions().into_iter().map(|description| LayoutGroup::Row {
+			widgets: vec![TextLabel::new(description).widget_holder()],
+		});
+
+		Layout::WidgetLayout(WidgetLayout::new(std::iter::once(title).chain(descriptions).collect()))
+	}
+}
@@ -1,16 +1,20 @@
-use super::utility_types::{FrontendDocumentDetails, MouseCursorIcon};
+use super::utility_types::{FrontendDocumentDetails, FrontendInspectedValue, FrontendNodeCacheStats, MouseCursorIcon};
 use crate::messages::app_window::app_window_message_handler::AppWindowPlatform;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::node_graph::utility_types::{
-	BoxSelection, ContextMenuInformation, FrontendClickTargets, FrontendGraphInput, FrontendGraphOutput, FrontendNode, FrontendNodeType, Transform,
+	BoxSelection, ContextMenuInformation, FrontendClickTargets, FrontendGraphInput, FrontendGraphOutput, FrontendNode, FrontendNodeError, FrontendNodeType, Transform,
 };
+use crate::messages::portfolio::document::utility_types::history::HistoryEntry;
 use crate::messages::portfolio::document::utility_types::nodes::{JsRawBuffer, LayerPanelEntry, RawBuffer};
 use crate::messages::portfolio::document::utility_types::wires::{WirePath, WirePathUpdate};
+use crate::messages::portfolio::recent_documents::RecentDocument;
+use crate::messages::preferences::palettes::Palette;
 use crate::messages::prelude::*;
 use crate::messages::tool::utility_types::HintData;
 use graph_craft::document::NodeId;
 use graphene_std::raster::Image;
 use graphene_std::raster::color::Color;
+use graphene_std::raster::histogram::Histogram;
 use graphene_std::text::{Font, TextAlign};
 
 #[impl_message(Message, Frontend)]
@@ -28,8 +32,12 @@ pub enum FrontendMessage {
 	},
 	DisplayEditableTextbox {
 		text: String,
-		#[serde(rename = "lineHeightRatio")]
-		line_height_ratio: f64,
+		#[serde(rename = "lineHeight")]
+		line_height: f64,
+		#[serde(rename = "characterSpacing")]
+		character_spacing: f64,
+		#[serde(rename = "wordSpacing")]
+		word_spacing: f64,
 		#[serde(rename = "fontSize")]
 		font_size: f64,
 		color: Color,
@@ -59,16 +67,25 @@ pub enum FrontendMessage {
 		#[serde(rename = "commitDate")]
 		commit_date: String,
 	},
+	TriggerCaptureRecentDocumentThumbnail {
+		path: String,
+	},
 	TriggerDownloadImage {
 		svg: String,
 		name: String,
 		mime: String,
 		size: (f64, f64),
+		#[serde(rename = "colorSpace")]
+		color_space: String,
 	},
 	TriggerDownloadTextFile {
 		document: String,
 		name: String,
 	},
+	TriggerDownloadBinaryFile {
+		data: Vec<u8>,
+		name: String,
+	},
 	TriggerFetchAndOpenDocument {
 		name: String,
 		filename: String,
@@ -77,6 +94,7 @@ pub enum FrontendMessage {
 		font: Font,
 	},
 	TriggerImport,
+	TriggerImportBrushTip,
 	TriggerIndexedDbRemoveDocument {
 		#[serde(rename = "documentId")]
 		document_id: DocumentId,
@@ -88,11 +106,18 @@ pub enum FrontendMessage {
 	TriggerLoadFirstAutoSaveDocument,
 	TriggerLoadRestAutoSaveDocuments,
 	TriggerLoadPreferences,
+	TriggerLoadRecentDocuments,
 	TriggerOpenDocument,
+	TriggerOpenRecentDocument {
+		path: String,
+	},
 	TriggerPaste,
 	TriggerSavePreferences {
 		preferences: PreferencesMessageHandler,
 	},
+	TriggerSaveRecentDocuments {
+		serialized: String,
+	},
 	TriggerSaveActiveDocument {
 		#[serde(rename = "documentId")]
 		document_id: DocumentId,
@@ -135,6 +160,10 @@ pub enum FrontendMessage {
 		#[serde(rename = "clickTargets")]
 		click_targets: Option<FrontendClickTargets>,
 	},
+	UpdateCustomNodeTypes {
+		#[serde(rename = "nodeTypes")]
+		node_types: Vec<FrontendNodeType>,
+	},
 	UpdateGraphViewOverlay {
 		open: bool,
 	},
@@ -147,6 +176,14 @@ pub enum FrontendMessage {
 		layout_target: LayoutTarget,
 		diff: Vec<WidgetDiff>,
 	},
+	UpdateSwatchesPanelState {
+		open: bool,
+	},
+	UpdateSwatchesPanelLayout {
+		#[serde(rename = "layoutTarget")]
+		layout_target: LayoutTarget,
+		diff: Vec<WidgetDiff>,
+	},
 	UpdateImportReorderIndex {
 		#[serde(rename = "importIndex")]
 		index: Option<usize>,
@@ -181,6 +218,9 @@ pub enum FrontendMessage {
 	UpdateDocumentArtwork {
 		svg: String,
 	},
+	UpdateDocumentError {
+		error: Option<String>,
+	},
 	UpdateImageData {
 		image_data: Vec<(u64, Image<Color>)>,
 	},
@@ -189,6 +229,11 @@ pub enum FrontendMessage {
 		layout_target: LayoutTarget,
 		diff: Vec<WidgetDiff>,
 	},
+	UpdateDocumentHistory {
+		entries: Vec<HistoryEntry>,
+		#[serde(rename = "currentIndex")]
+		current_index: usize,
+	},
 	UpdateDocumentLayerDetails {
 		data: LayerPanelEntry,
 	},
@@ -226,9 +271,21 @@ pub enum FrontendMessage {
 		#[serde(rename = "setColorChoice")]
 		set_color_choice: Option<String>,
 	},
+	UpdateHistogram {
+		layer: Option<NodeId>,
+		histogram: Option<Histogram>,
+	},
 	UpdateGraphFadeArtwork {
 		percentage: f64,
 	},
+	UpdateInspectedValue {
+		#[serde(rename = "nodePath")]
+		node_path: Vec<NodeId>,
+		value: Option<FrontendInspectedValue>,
+	},
+	UpdateNodeCacheStats {
+		stats: Vec<FrontendNodeCacheStats>,
+	},
 	UpdateInputHints {
 		#[serde(rename = "hintData")]
 		hint_data: HintData,
@@ -262,6 +319,9 @@ pub enum FrontendMessage {
 	UpdateVisibleNodes {
 		nodes: Vec<NodeId>,
 	},
+	UpdateNodeGraphErrors {
+		errors: Vec<FrontendNodeError>,
+	},
 	UpdateNodeGraphWires {
 		wires: Vec<WirePathUpdate>,
 	},
@@ -281,10 +341,21 @@ pub enum FrontendMessage {
 		id: NodeId,
 		value: String,
 	},
+	UpdateNodeThumbnailImage {
+		id: NodeId,
+		png: Vec<u8>,
+	},
 	UpdateOpenDocumentsList {
 		#[serde(rename = "openDocuments")]
 		open_documents: Vec<FrontendDocumentDetails>,
 	},
+	UpdatePalette {
+		palette: Palette,
+	},
+	UpdateRecentDocuments {
+		#[serde(rename = "recentDocuments")]
+		recent_documents: Vec<RecentDocument>,
+	},
 	UpdatePropertyPanelSectionsLayout {
 		#[serde(rename = "layoutTarget")]
 		layout_target: LayoutTarget,
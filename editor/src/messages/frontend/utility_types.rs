@@ -1,5 +1,6 @@
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::prelude::*;
+use graph_craft::document::NodeId;
 
 #[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct FrontendDocumentDetails {
@@ -11,6 +12,29 @@ pub struct FrontendDocumentDetails {
 	pub id: DocumentId,
 }
 
+#[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct FrontendNodeCacheStats {
+	#[serde(rename = "nodePath")]
+	pub node_path: Vec<NodeId>,
+	pub hits: u64,
+	pub misses: u64,
+	pub entries: usize,
+	pub bytes: u64,
+}
+
+/// The output of a node which is inspected in the data panel, from the most recent evaluation of the graph
+#[derive(PartialEq, Eq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct FrontendInspectedValue {
+	#[serde(rename = "typeName")]
+	pub type_name: String,
+	/// The estimated size of the output in bytes, if it's known
+	pub size: Option<u64>,
+	/// A short description of the output, or `None` if it's too large to be kept or can't be kept as a tagged value
+	pub preview: Option<String>,
+	/// The whole output serialized as JSON, if it was requested and is small enough
+	pub full: Option<String>,
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum MouseCursorIcon {
 	#[default]
@@ -47,6 +71,26 @@ impl FileType {
 	}
 }
 
+/// How colors outside of sRGB are handled when exporting a raster image.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum RasterColorSpace {
+	/// Colors are converted to sRGB, clipping those outside of it, so the image looks the same in every viewer.
+	#[default]
+	Srgb,
+	/// The image is tagged as Display P3, keeping wide-gamut colors for viewers and displays which support it.
+	DisplayP3,
+}
+
+impl RasterColorSpace {
+	/// The name of the color space used by the canvas which the image is rasterized with.
+	pub fn to_canvas_color_space(self) -> &'static str {
+		match self {
+			RasterColorSpace::Srgb => "srgb",
+			RasterColorSpace::DisplayP3 => "display-p3",
+		}
+	}
+}
+
 #[derive(Clone, Copy, Debug, Default, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum ExportBounds {
 	#[default]
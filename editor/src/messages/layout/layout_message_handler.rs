@@ -198,7 +198,16 @@ impl LayoutMessageHandler {
 									})
 									.collect::<Vec<_>>();
 
-								color_button.value = FillChoice::Gradient(GradientStops::new(gradient_stops));
+								let midpoints = update_value
+									.get("midpoints")
+									.and_then(|x| x.as_array())
+									.map(|midpoints| midpoints.iter().filter_map(|midpoint| midpoint.as_f64()).collect())
+									.unwrap_or_default();
+
+								color_button.value = FillChoice::Gradient(GradientStops {
+									midpoints,
+									..GradientStops::new(gradient_stops)
+								});
 								return (color_button.on_update.callback)(color_button);
 							}
 
@@ -493,6 +502,7 @@ impl LayoutMessageHandler {
 			LayoutTarget::NodeGraphControlBar => FrontendMessage::UpdateNodeGraphControlBarLayout { layout_target, diff },
 			LayoutTarget::PropertiesSections => FrontendMessage::UpdatePropertyPanelSectionsLayout { layout_target, diff },
 			LayoutTarget::Spreadsheet => FrontendMessage::UpdateSpreadsheetLayout { layout_target, diff },
+			LayoutTarget::SwatchesPanel => FrontendMessage::UpdateSwatchesPanelLayout { layout_target, diff },
 			LayoutTarget::ToolOptions => FrontendMessage::UpdateToolOptionsLayout { layout_target, diff },
 			LayoutTarget::ToolShelf => FrontendMessage::UpdateToolShelfLayout { layout_target, diff },
 			LayoutTarget::WorkingColors => FrontendMessage::UpdateWorkingColorsLayout { layout_target, diff },
@@ -45,6 +45,8 @@ pub enum LayoutTarget {
 	PropertiesSections,
 	/// The spredsheet panel allows for the visualisation of data in the graph.
 	Spreadsheet,
+	/// The list of the document's swatches in the Swatches panel.
+	SwatchesPanel,
 	/// The bar directly above the canvas, left-aligned and to the right of the document mode dropdown.
 	ToolOptions,
 	/// The vertical buttons for all of the tools on the left of the canvas.
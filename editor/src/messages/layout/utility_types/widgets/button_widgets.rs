@@ -179,6 +179,9 @@ pub struct ColorInput {
 	#[derivative(Default(value = "true"))]
 	pub allow_none: bool,
 
+	/// A short label shown in the corner of the swatch, such as the name of the document swatch which the color is linked to.
+	pub badge: Option<String>,
+
 	// TODO: Implement
 	// pub disabled: bool,
 	//
@@ -2,7 +2,7 @@ use crate::messages::input_mapper::utility_types::misc::ActionKeys;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use derivative::*;
 use graphene_std::Color;
-use graphene_std::raster::curve::Curve;
+use graphene_std::raster::curve::CurveSpline;
 use graphene_std::transform::ReferencePoint;
 use graphite_proc_macros::WidgetBuilder;
 use once_cell::sync::OnceCell;
@@ -452,7 +452,7 @@ pub struct TextInput {
 #[derivative(Debug, PartialEq, Default)]
 pub struct CurveInput {
 	#[widget_builder(constructor)]
-	pub value: Curve,
+	pub value: CurveSpline,
 
 	pub disabled: bool,
 
@@ -3,7 +3,9 @@ use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::overlays::utility_types::OverlaysType;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GridSnapping};
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, AlignTarget, DistributeMode, FlipAxis, GridSnapping};
+use crate::messages::portfolio::document::utility_types::similar_layers::SimilarityCriterion;
+use crate::messages::portfolio::document::utility_types::style_search::StyleValue;
 use crate::messages::portfolio::utility_types::PanelType;
 use crate::messages::prelude::*;
 use glam::DAffine2;
@@ -36,17 +38,27 @@ pub enum DocumentMessage {
 	AlignSelectedLayers {
 		axis: AlignAxis,
 		aggregate: AlignAggregate,
+		target: AlignTarget,
 	},
 	RemoveArtboards,
 	ClearLayersPanel,
+	ClearSoloLayer,
 	CreateEmptyFolder,
 	DeleteNode {
 		node_id: NodeId,
 	},
 	DeleteSelectedLayers,
 	DeselectAllLayers,
+	/// Moves the selected layers along the axis so they're spread evenly between the two outermost ones.
+	DistributeSelectedLayers {
+		axis: AlignAxis,
+		mode: DistributeMode,
+	},
 	DocumentHistoryBackward,
 	DocumentHistoryForward,
+	DocumentHistoryJump {
+		index: usize,
+	},
 	DocumentStructureChanged,
 	DrawArtboardOverlays(OverlayContext),
 	DuplicateSelectedLayers,
@@ -73,6 +85,10 @@ pub enum DocumentMessage {
 	GroupSelectedLayers {
 		group_folder_type: GroupFolderType,
 	},
+	/// Undoes or redoes to the state reached by the given number of steps from the oldest state in the history.
+	JumpToHistoryState {
+		index: usize,
+	},
 	MoveSelectedLayersTo {
 		parent: LayerNodeIdentifier,
 		insert_index: usize,
@@ -104,9 +120,26 @@ pub enum DocumentMessage {
 	},
 	RenderRulers,
 	RenderScrollbars,
+	ReplaceStyle {
+		target: StyleValue,
+		tolerance: f64,
+		replacement: StyleValue,
+	},
 	SaveDocument,
+	SendHistory,
 	SelectParentLayer,
 	SelectAllLayers,
+	SelectLayersWithStyle {
+		target: StyleValue,
+		tolerance: f64,
+	},
+	SelectSimilarLayers {
+		/// The layer whose property is matched, or `None` to match that of any selected layer.
+		layer: Option<NodeId>,
+		criterion: SimilarityCriterion,
+		/// Whether the similar layers are added to the selection instead of replacing it.
+		extend: bool,
+	},
 	SelectedLayersLower,
 	SelectedLayersLowerToBack,
 	SelectedLayersRaise,
@@ -164,6 +197,10 @@ pub enum DocumentMessage {
 	},
 	AddTransaction,
 	StartTransaction,
+	/// Labels the newest history step with the given name instead of describing it by the messages which make its changes.
+	NameTransaction {
+		name: String,
+	},
 	EndTransaction,
 	CommitTransaction,
 	AbortTransaction,
@@ -174,6 +211,9 @@ pub enum DocumentMessage {
 		id: NodeId,
 		recursive: bool,
 	},
+	ToggleLayerSolo {
+		id: NodeId,
+	},
 	ToggleSelectedVisibility,
 	ToggleSelectedLocked,
 	ToggleGridVisibility,
@@ -2,11 +2,14 @@ use super::node_graph::document_node_definitions;
 use super::node_graph::utility_types::Transform;
 use super::overlays::utility_types::Pivot;
 use super::utility_types::error::EditorError;
+use super::utility_types::history::{HistoryEntry, HistoryLabel, HistoryState};
 use super::utility_types::misc::{GroupFolderType, SNAP_FUNCTIONS_FOR_BOUNDING_BOXES, SNAP_FUNCTIONS_FOR_PATHS, SnappingOptions, SnappingState};
 use super::utility_types::network_interface::{self, NodeNetworkInterface, TransactionStatus};
 use super::utility_types::nodes::{CollapsedLayers, SelectedNodes};
+use super::utility_types::{alignment, similar_layers, style_search};
 use crate::application::{GRAPHITE_GIT_COMMIT_HASH, generate_uuid};
-use crate::consts::{ASYMPTOTIC_EFFECT, COLOR_OVERLAY_GRAY, DEFAULT_DOCUMENT_NAME, FILE_SAVE_SUFFIX, SCALE_EFFECT, SCROLLBAR_SPACING, VIEWPORT_ROTATE_SNAP_INTERVAL};
+use crate::consts::{ASYMPTOTIC_EFFECT, COLOR_OVERLAY_GRAY, DEFAULT_DOCUMENT_NAME, SCALE_EFFECT, SCROLLBAR_SPACING, VIEWPORT_ROTATE_SNAP_INTERVAL};
+use crate::messages::animation::utility_types::PlaybackState;
 use crate::messages::input_mapper::utility_types::macros::action_keys;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
@@ -15,9 +18,10 @@ use crate::messages::portfolio::document::overlays::grid_overlays::{grid_overlay
 use crate::messages::portfolio::document::overlays::utility_types::{OverlaysType, OverlaysVisibilitySettings};
 use crate::messages::portfolio::document::properties_panel::properties_panel_message_handler::PropertiesPanelMessageContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, DocumentMode, FlipAxis, PTZ};
+use crate::messages::portfolio::document::utility_types::misc::{AlignTarget, DocumentMode, FlipAxis, PTZ};
 use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, InputConnector, NodeTemplate};
 use crate::messages::portfolio::document::utility_types::nodes::RawBuffer;
+use crate::messages::portfolio::recent_documents::document_path;
 use crate::messages::portfolio::utility_types::PersistentData;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::graph_modification_utils::{self, get_blend_mode, get_fill, get_opacity};
@@ -36,7 +40,9 @@ use graphene_std::raster_types::{Raster, RasterDataTable};
 use graphene_std::vector::PointId;
 use graphene_std::vector::click_target::{ClickTarget, ClickTargetType};
 use graphene_std::vector::style::ViewMode;
-use std::time::Duration;
+
+/// Held while clicking or drawing a selection box to also hit locked layers, which are otherwise skipped.
+pub const CLICK_THROUGH_LOCKS_KEY: Key = Key::Alt;
 
 #[derive(ExtractField)]
 pub struct DocumentMessageContext<'a> {
@@ -61,7 +67,7 @@ pub struct DocumentMessageHandler {
 	#[serde(skip)]
 	pub node_graph_handler: NodeGraphMessageHandler,
 	#[serde(skip)]
-	overlays_message_handler: OverlaysMessageHandler,
+	pub overlays_message_handler: OverlaysMessageHandler,
 	#[serde(skip)]
 	properties_panel_message_handler: PropertiesPanelMessageHandler,
 
@@ -97,6 +103,8 @@ pub struct DocumentMessageHandler {
 	pub graph_view_overlay_open: bool,
 	/// The current opacity of the faded node graph background that covers up the artwork.
 	pub graph_fade_artwork_percentage: f64,
+	/// The playback of the document's animation, which sets the time that its graph is evaluated at.
+	pub playback: PlaybackState,
 
 	// =============================================
 	// Fields omitted from the saved document format
@@ -110,10 +118,13 @@ pub struct DocumentMessageHandler {
 	selection_network_path: Vec<NodeId>,
 	/// Stack of document network snapshots for previous history states.
 	#[serde(skip)]
-	document_undo_history: VecDeque<NodeNetworkInterface>,
+	document_undo_history: VecDeque<HistoryState>,
 	/// Stack of document network snapshots for future history states.
 	#[serde(skip)]
-	document_redo_history: VecDeque<NodeNetworkInterface>,
+	document_redo_history: VecDeque<HistoryState>,
+	/// Whether the newest step in the undo history is still described by the messages which make its changes, until the history is undone or redone.
+	#[serde(skip)]
+	history_step_open: bool,
 	/// Hash of the document snapshot that was most recently saved to disk by the user.
 	#[serde(skip)]
 	saved_hash: Option<u64>,
@@ -124,6 +135,10 @@ pub struct DocumentMessageHandler {
 	/// If the user clicks or Ctrl-clicks one layer, it becomes the start of the range selection and then Shift-clicking another layer selects all layers between the start and end.
 	#[serde(skip)]
 	layer_range_selection_reference: Option<LayerNodeIdentifier>,
+	/// The layer which is soloed, so only it and its subtree are rendered in the viewport until the solo is cleared.
+	/// This only affects the viewport, not exports, and isn't part of the document's history.
+	#[serde(skip)]
+	solo_layer: Option<LayerNodeIdentifier>,
 	/// Whether or not the editor has executed the network to render the document yet. If this is opened as an inactive tab, it won't be loaded initially because the active tab is prioritized.
 	#[serde(skip)]
 	pub is_loaded: bool,
@@ -154,6 +169,7 @@ impl Default for DocumentMessageHandler {
 			graph_view_overlay_open: false,
 			snapping_state: SnappingState::default(),
 			graph_fade_artwork_percentage: 80.,
+			playback: PlaybackState::default(),
 			// =============================================
 			// Fields omitted from the saved document format
 			// =============================================
@@ -161,9 +177,11 @@ impl Default for DocumentMessageHandler {
 			selection_network_path: Vec::new(),
 			document_undo_history: VecDeque::new(),
 			document_redo_history: VecDeque::new(),
+			history_step_open: false,
 			saved_hash: None,
 			auto_saved_hash: None,
 			layer_range_selection_reference: None,
+			solo_layer: None,
 			is_loaded: false,
 		}
 	}
@@ -182,6 +200,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			device_pixel_ratio,
 		} = context;
 
+		// The newest history step is labelled by the first message which makes a change in it, unless its transaction is named
+		if self.history_step_open
+			&& let Some(state) = self.document_undo_history.back_mut()
+			&& state.label.describe(&message, &self.network_interface, &self.selection_network_path)
+		{
+			responses.add(DocumentMessage::SendHistory);
+		}
+
 		match message {
 			// Sub-messages
 			DocumentMessage::Navigation(message) => {
@@ -207,6 +233,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 						visibility_settings,
 						ipp,
 						device_pixel_ratio,
+						quality: preferences.overlay_quality,
 					},
 				);
 			}
@@ -230,6 +257,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 						breadcrumb_network_path: &self.breadcrumb_network_path,
 						document_id,
 						collapsed: &mut self.collapsed,
+						solo_layer: self.solo_layer(),
 						ipp,
 						graph_view_overlay_open: self.graph_view_overlay_open,
 						graph_fade_artwork_percentage: self.graph_fade_artwork_percentage,
@@ -247,43 +275,21 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				let mut graph_operation_message_handler = GraphOperationMessageHandler {};
 				graph_operation_message_handler.process_message(message, responses, context);
 			}
-			DocumentMessage::AlignSelectedLayers { axis, aggregate } => {
-				let axis = match axis {
-					AlignAxis::X => DVec2::X,
-					AlignAxis::Y => DVec2::Y,
-				};
-				let Some(combined_box) = self.network_interface.selected_layers_artwork_bounding_box_viewport() else {
-					return;
-				};
+			DocumentMessage::AlignSelectedLayers { axis, aggregate, target } => {
+				let layers = self.movable_selected_layer_bounds();
+				let selection_bounds = layers.iter().map(|&(_, bounds)| bounds).reduce(Quad::combine_bounds);
 
-				let aggregated = match aggregate {
-					AlignAggregate::Min => combined_box[0],
-					AlignAggregate::Max => combined_box[1],
-					AlignAggregate::Center => (combined_box[0] + combined_box[1]) / 2.,
-				};
-
-				let mut added_transaction = false;
-				for layer in self.network_interface.selected_nodes().selected_unlocked_layers(&self.network_interface) {
-					let Some(bbox) = self.metadata().bounding_box_viewport(layer) else {
-						continue;
-					};
-					let center = match aggregate {
-						AlignAggregate::Min => bbox[0],
-						AlignAggregate::Max => bbox[1],
-						_ => (bbox[0] + bbox[1]) / 2.,
+				let offsets = layers.into_iter().filter_map(|(layer, bounds)| {
+					let target_bounds = match target {
+						AlignTarget::Selection => selection_bounds?,
+						AlignTarget::Artboard => {
+							let artboard = layer.ancestors(self.metadata()).skip(1).find(|ancestor| self.network_interface.is_artboard(&ancestor.to_node(), &[]))?;
+							self.metadata().bounding_box_viewport(artboard)?
+						}
 					};
-					let translation = (aggregated - center) * axis;
-					if !added_transaction {
-						responses.add(DocumentMessage::AddTransaction);
-						added_transaction = true;
-					}
-					responses.add(GraphOperationMessage::TransformChange {
-						layer,
-						transform: DAffine2::from_translation(translation),
-						transform_in: TransformIn::Viewport,
-						skip_rerender: false,
-					});
-				}
+					Some((layer, alignment::align_offset(bounds, target_bounds, axis, aggregate)))
+				});
+				self.move_layers_in_viewport(offsets.collect(), "Align Layers", responses);
 			}
 			DocumentMessage::RemoveArtboards => {
 				responses.add(GraphOperationMessage::RemoveArtboards);
@@ -309,6 +315,15 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					layout_target: LayoutTarget::LayersPanelBottomBar,
 				});
 			}
+			DocumentMessage::ClearSoloLayer => {
+				if self.solo_layer.take().is_none() {
+					return;
+				}
+
+				responses.add(NodeGraphMessage::UpdateLayerPanel);
+				responses.add(MenuBarMessage::SendLayout);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			DocumentMessage::CreateEmptyFolder => {
 				let selected_nodes = self.network_interface.selected_nodes();
 				let id = NodeId::new();
@@ -347,8 +362,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![] });
 				self.layer_range_selection_reference = None;
 			}
+			DocumentMessage::DistributeSelectedLayers { axis, mode } => {
+				let (layers, bounds): (Vec<_>, Vec<_>) = self.movable_selected_layer_bounds().into_iter().unzip();
+				let offsets = alignment::distribute_offsets(&bounds, axis, mode);
+				self.move_layers_in_viewport(layers.into_iter().zip(offsets).collect(), "Distribute Layers", responses);
+			}
 			DocumentMessage::DocumentHistoryBackward => self.undo_with_history(ipp, responses),
 			DocumentMessage::DocumentHistoryForward => self.redo_with_history(ipp, responses),
+			DocumentMessage::DocumentHistoryJump { index } => self.jump_to_history_state(index, ipp, responses),
 			DocumentMessage::DocumentStructureChanged => {
 				self.update_layers_panel_control_bar_widgets(responses);
 				self.update_layers_panel_bottom_bar_widgets(responses);
@@ -396,6 +417,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			}
 			DocumentMessage::DuplicateSelectedLayers => {
 				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::NameTransaction { name: "Duplicate Layers".to_string() });
 
 				let mut new_dragging = Vec::new();
 				let mut layers = self.network_interface.shallowest_unique_layers(&[]).collect::<Vec<_>>();
@@ -465,6 +487,8 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				}
 			}
 			DocumentMessage::ExitNestedNetwork { steps_back } => {
+				// Edits inside a custom node's network are given to its other instances before leaving it
+				self.network_interface.update_custom_node_definitions(&self.selection_network_path);
 				for _ in 0..steps_back {
 					self.breadcrumb_network_path.pop();
 					self.selection_network_path.clone_from(&self.breadcrumb_network_path);
@@ -486,6 +510,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					for layer in self.network_interface.selected_nodes().selected_unlocked_layers(&self.network_interface) {
 						if !added_transaction {
 							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::NameTransaction { name: "Flip Layers".to_string() });
 							added_transaction = true;
 						}
 						responses.add(GraphOperationMessage::TransformChange {
@@ -509,6 +534,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					for layer in self.network_interface.selected_nodes().selected_unlocked_layers(&self.network_interface) {
 						if !added_transaction {
 							responses.add(DocumentMessage::AddTransaction);
+							responses.add(DocumentMessage::NameTransaction { name: "Rotate Layers".to_string() });
 							added_transaction = true;
 						}
 
@@ -570,6 +596,11 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			}
 			DocumentMessage::GroupSelectedLayers { group_folder_type } => {
 				responses.add(DocumentMessage::AddTransaction);
+				let name = match group_folder_type {
+					GroupFolderType::Layer => "Group Layers",
+					GroupFolderType::BooleanOperation(_) => "Boolean Operation",
+				};
+				responses.add(DocumentMessage::NameTransaction { name: name.to_string() });
 
 				let mut parent_per_selected_nodes: HashMap<LayerNodeIdentifier, Vec<NodeId>> = HashMap::new();
 				let artboards = LayerNodeIdentifier::ROOT_PARENT
@@ -616,6 +647,15 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					responses.add(NodeGraphMessage::SelectedNodesSet { nodes: new_folders });
 				}
 			}
+			DocumentMessage::JumpToHistoryState { index } => {
+				if self.network_interface.transaction_status() != TransactionStatus::Finished {
+					return;
+				}
+				// Tools are aborted rather than stepped back like the Pen tool is by undo, since several steps may be crossed
+				responses.add(BroadcastEvent::ToolAbort);
+				responses.add(DocumentMessage::DocumentHistoryJump { index });
+				responses.add(OverlaysMessage::Draw);
+			}
 			DocumentMessage::MoveSelectedLayersTo { parent, insert_index } => {
 				if !self.selection_network_path.is_empty() {
 					log::error!("Moving selected layers is only supported for the Document Network");
@@ -682,6 +722,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					.collect::<Vec<_>>();
 
 				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::NameTransaction { name: "Move Layers".to_string() });
 
 				for (layer_index, (layer_to_move, insert_offset)) in layers_to_move_with_insert_offset.into_iter().enumerate() {
 					responses.add(NodeGraphMessage::MoveLayerToStack {
@@ -985,21 +1026,43 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					multiplier: scrollbar_multiplier.into(),
 				});
 			}
+			DocumentMessage::ReplaceStyle { target, tolerance, replacement } => {
+				let changes = style_search::style_replacement_changes(&self.network_interface, target, tolerance, replacement);
+
+				// Every replaced input is changed in the same history step, so a single undo restores all of them
+				let transaction_name = match replacement {
+					style_search::StyleValue::Color(_) => "Replace Color",
+					style_search::StyleValue::StrokeWeight(_) => "Replace Stroke Weight",
+				};
+				responses.add(NodeGraphMessage::SetInputs {
+					changes,
+					skip_adding_history_step: false,
+					transaction_name: Some(transaction_name.to_string()),
+				});
+				responses.add(PropertiesPanelMessage::Refresh);
+			}
 			DocumentMessage::SaveDocument => {
 				self.set_save_state(true);
 				responses.add(PortfolioMessage::AutoSaveActiveDocument);
 				// Update the save status of the just saved document
 				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
 
-				let name = match self.name.ends_with(FILE_SAVE_SUFFIX) {
-					true => self.name.clone(),
-					false => self.name.clone() + FILE_SAVE_SUFFIX,
-				};
+				let name = document_path(&self.name);
+				responses.add(RecentDocumentsMessage::Record {
+					path: name.clone(),
+					name: self.name.clone(),
+				});
 				responses.add(FrontendMessage::TriggerDownloadTextFile {
 					document: self.serialize_document(),
 					name,
 				})
 			}
+			DocumentMessage::SendHistory => {
+				responses.add(FrontendMessage::UpdateDocumentHistory {
+					entries: self.history_entries(),
+					current_index: self.history_index(),
+				});
+			}
 			DocumentMessage::SelectParentLayer => {
 				let selected_nodes = self.network_interface.selected_nodes();
 				let selected_layers = selected_nodes.selected_layers(self.metadata());
@@ -1037,6 +1100,32 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				let nodes = all_layers_except_artboards_invisible_and_locked.map(|layer| layer.to_node()).collect();
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
 			}
+			DocumentMessage::SelectLayersWithStyle { target, tolerance } => {
+				let layers = style_search::layers_with_style(&self.network_interface, target, tolerance);
+				let nodes = layers.into_iter().map(|layer| layer.to_node()).collect();
+				responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
+			}
+			DocumentMessage::SelectSimilarLayers { layer, criterion, extend } => {
+				let selected_nodes = self.network_interface.selected_nodes();
+				let references = match layer {
+					Some(layer) => vec![LayerNodeIdentifier::new(layer, &self.network_interface)],
+					None => selected_nodes.selected_layers(self.metadata()).collect(),
+				};
+
+				let similar = similar_layers::similar_layers(&self.network_interface, &references, criterion);
+				// Keep the selection rather than clearing it when the layers have nothing to match, such as a font for a shape
+				if similar.is_empty() {
+					return;
+				}
+
+				let mut nodes: Vec<NodeId> = if extend { selected_nodes.selected_nodes().copied().collect() } else { Vec::new() };
+				for layer in similar {
+					if !nodes.contains(&layer.to_node()) {
+						nodes.push(layer.to_node());
+					}
+				}
+				responses.add(NodeGraphMessage::SelectedNodesSet { nodes });
+			}
 			DocumentMessage::SelectedLayersLower => {
 				responses.add(DocumentMessage::SelectedLayersReorder { relative_index_offset: 1 });
 			}
@@ -1144,6 +1233,8 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			}
 			DocumentMessage::SetNodePinned { node_id, pinned } => {
 				responses.add(DocumentMessage::AddTransaction);
+				let name = if pinned { "Pin Node" } else { "Unpin Node" };
+				responses.add(DocumentMessage::NameTransaction { name: name.to_string() });
 				responses.add(NodeGraphMessage::SetPinned { node_id, pinned });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 				responses.add(NodeGraphMessage::SelectedNodesUpdated);
@@ -1218,13 +1309,26 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 			// Note: A transaction should never be started in a scope that mutates the network interface, since it will only be run after that scope ends.
 			DocumentMessage::StartTransaction => {
 				self.network_interface.start_transaction();
-				let network_interface_clone = self.network_interface.clone();
-				self.document_undo_history.push_back(network_interface_clone);
+				let network_interface = self.network_interface.clone();
+				self.document_undo_history.push_back(HistoryState {
+					network_interface,
+					label: HistoryLabel::Unnamed,
+				});
 				if self.document_undo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
 					self.document_undo_history.pop_front();
 				}
+				self.history_step_open = true;
 				// Push the UpdateOpenDocumentsList message to the bus in order to update the save status of the open documents
 				responses.add(PortfolioMessage::UpdateOpenDocumentsList);
+				responses.add(DocumentMessage::SendHistory);
+			}
+			DocumentMessage::NameTransaction { name } => {
+				if !self.history_step_open {
+					return;
+				}
+				let Some(state) = self.document_undo_history.back_mut() else { return };
+				state.label = HistoryLabel::Named(name);
+				responses.add(DocumentMessage::SendHistory);
 			}
 			// Commits the transaction if the network was mutated since the transaction started, otherwise it aborts the transaction
 			DocumentMessage::EndTransaction => match self.network_interface.transaction_status() {
@@ -1242,6 +1346,8 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 				}
 				self.network_interface.finish_transaction();
 				self.document_redo_history.clear();
+				responses.add(PortfolioMessage::EndInteractiveRender);
+				responses.add(DocumentMessage::SendHistory);
 			}
 			DocumentMessage::AbortTransaction => {
 				responses.add(DocumentMessage::RepeatedAbortTransaction { undo_count: 1 });
@@ -1257,6 +1363,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 
 				self.network_interface.finish_transaction();
 				responses.add(OverlaysMessage::Draw);
+				responses.add(PortfolioMessage::EndInteractiveRender);
 			}
 			DocumentMessage::ToggleLayerExpansion { id, recursive } => {
 				let layer = LayerNodeIdentifier::new(id, &self.network_interface);
@@ -1281,6 +1388,14 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 
 				responses.add(NodeGraphMessage::SendGraph);
 			}
+			DocumentMessage::ToggleLayerSolo { id } => {
+				let layer = LayerNodeIdentifier::new(id, &self.network_interface);
+				self.solo_layer = if self.solo_layer() == Some(layer) { None } else { Some(layer) };
+
+				responses.add(NodeGraphMessage::UpdateLayerPanel);
+				responses.add(MenuBarMessage::SendLayout);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			DocumentMessage::ToggleSelectedLocked => responses.add(NodeGraphMessage::ToggleSelectedLocked),
 			DocumentMessage::ToggleSelectedVisibility => {
 				responses.add(NodeGraphMessage::ToggleSelectedVisibility);
@@ -1341,6 +1456,7 @@ impl MessageHandler<DocumentMessage, DocumentMessageContext<'_>> for DocumentMes
 					return;
 				}
 				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::NameTransaction { name: "Ungroup Layers".to_string() });
 
 				let folder_paths = self.network_interface.folders_sorted_by_most_nested(&self.selection_network_path);
 				for folder in folder_paths {
@@ -1586,7 +1702,7 @@ impl DocumentMessageHandler {
 		let document_to_viewport = self.navigation_handler.calculate_offset_transform(ipp.viewport_bounds.center(), &self.document_ptz);
 		let document_quad = document_to_viewport.inverse() * viewport_quad;
 
-		ClickXRayIter::new(&self.network_interface, XRayTarget::Quad(document_quad))
+		ClickXRayIter::new(&self.network_interface, XRayTarget::Quad(document_quad), Self::clicks_through_locks(ipp))
 	}
 
 	/// Runs an intersection test with all layers and a viewport space quad; ignoring artboards
@@ -1599,7 +1715,7 @@ impl DocumentMessageHandler {
 		let document_to_viewport = self.navigation_handler.calculate_offset_transform(ipp.viewport_bounds.center(), &self.document_ptz);
 		viewport_polygon.apply_transform(document_to_viewport.inverse());
 
-		ClickXRayIter::new(&self.network_interface, XRayTarget::Polygon(viewport_polygon))
+		ClickXRayIter::new(&self.network_interface, XRayTarget::Polygon(viewport_polygon), Self::clicks_through_locks(ipp))
 	}
 
 	/// Runs an intersection test with all layers and a viewport space subpath; ignoring artboards
@@ -1657,7 +1773,12 @@ impl DocumentMessageHandler {
 	pub fn click_xray(&self, ipp: &InputPreprocessorMessageHandler) -> impl Iterator<Item = LayerNodeIdentifier> + use<'_> {
 		let document_to_viewport = self.navigation_handler.calculate_offset_transform(ipp.viewport_bounds.center(), &self.document_ptz);
 		let point = document_to_viewport.inverse().transform_point2(ipp.mouse.position);
-		ClickXRayIter::new(&self.network_interface, XRayTarget::Point(point))
+		ClickXRayIter::new(&self.network_interface, XRayTarget::Point(point), Self::clicks_through_locks(ipp))
+	}
+
+	/// Whether the modifier for also hitting locked layers is held.
+	fn clicks_through_locks(ipp: &InputPreprocessorMessageHandler) -> bool {
+		ipp.keyboard.get(CLICK_THROUGH_LOCKS_KEY as usize)
 	}
 
 	/// Find the deepest layer given in the sorted array (by returning the one which is not a folder from the list of layers under the click location).
@@ -1701,7 +1822,7 @@ impl DocumentMessageHandler {
 	}
 
 	pub fn click_based_on_position(&self, mouse_snapped_positon: DVec2) -> Option<LayerNodeIdentifier> {
-		ClickXRayIter::new(&self.network_interface, XRayTarget::Point(mouse_snapped_positon))
+		ClickXRayIter::new(&self.network_interface, XRayTarget::Point(mouse_snapped_positon), false)
 			.filter(move |&layer| !self.network_interface.is_artboard(&layer.to_node(), &[]))
 			.skip_while(|&layer| layer == LayerNodeIdentifier::ROOT_PARENT)
 			.scan(true, |last_had_children, layer| {
@@ -1723,6 +1844,15 @@ impl DocumentMessageHandler {
 		self.network_interface.document_metadata()
 	}
 
+	/// The layer which is soloed, unless it has since been deleted.
+	pub fn solo_layer(&self) -> Option<LayerNodeIdentifier> {
+		self.solo_layer.filter(|&layer| self.metadata().layer_exists(layer))
+	}
+
+	pub fn selection_network_path(&self) -> &[NodeId] {
+		&self.selection_network_path
+	}
+
 	pub fn serialize_document(&self) -> String {
 		let val = serde_json::to_string(self);
 		// We fully expect the serialization to succeed
@@ -1855,17 +1985,19 @@ impl DocumentMessageHandler {
 	}
 
 	pub fn undo_with_history(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) {
-		let Some(previous_network) = self.undo(ipp, responses) else { return };
+		let Some(previous_state) = self.undo(ipp, responses) else { return };
 
-		self.document_redo_history.push_back(previous_network);
+		self.document_redo_history.push_back(previous_state);
 		if self.document_redo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
 			self.document_redo_history.pop_front();
 		}
 	}
 
-	pub fn undo(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) -> Option<NodeNetworkInterface> {
+	/// Restores the previous history state, giving the replaced state labelled with the step which was undone.
+	pub fn undo(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) -> Option<HistoryState> {
 		// If there is no history return and don't broadcast SelectionChanged
-		let mut network_interface = self.document_undo_history.pop_back()?;
+		let HistoryState { mut network_interface, label } = self.document_undo_history.pop_back()?;
+		self.history_step_open = false;
 
 		// Set the previous network navigation metadata to the current navigation metadata
 		network_interface.copy_all_navigation_metadata(&self.network_interface);
@@ -1884,26 +2016,34 @@ impl DocumentMessageHandler {
 		responses.add(PortfolioMessage::UpdateOpenDocumentsList);
 		responses.add(NodeGraphMessage::SelectedNodesUpdated);
 		responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+		responses.add(SwatchesMessage::SendLayout);
+		responses.add(NodeGraphMessage::SendCustomNodeTypes);
 
 		// TODO: Remove once the footprint is used to load the imports/export distances from the edge
 		responses.add(NodeGraphMessage::UnloadWires);
 		responses.add(NodeGraphMessage::SetGridAlignedEdges);
+		responses.add(DocumentMessage::SendHistory);
 
-		Some(previous_network)
+		Some(HistoryState {
+			network_interface: previous_network,
+			label,
+		})
 	}
 	pub fn redo_with_history(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) {
 		// Push the UpdateOpenDocumentsList message to the queue in order to update the save status of the open documents
-		let Some(previous_network) = self.redo(ipp, responses) else { return };
+		let Some(previous_state) = self.redo(ipp, responses) else { return };
 
-		self.document_undo_history.push_back(previous_network);
+		self.document_undo_history.push_back(previous_state);
 		if self.document_undo_history.len() > crate::consts::MAX_UNDO_HISTORY_LEN {
 			self.document_undo_history.pop_front();
 		}
 	}
 
-	pub fn redo(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) -> Option<NodeNetworkInterface> {
+	/// Restores the next history state, giving the replaced state labelled with the step which was redone.
+	pub fn redo(&mut self, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) -> Option<HistoryState> {
 		// If there is no history return and don't broadcast SelectionChanged
-		let mut network_interface = self.document_redo_history.pop_back()?;
+		let HistoryState { mut network_interface, label } = self.document_redo_history.pop_back()?;
+		self.history_step_open = false;
 
 		// Set the previous network navigation metadata to the current navigation metadata
 		network_interface.copy_all_navigation_metadata(&self.network_interface);
@@ -1918,13 +2058,59 @@ impl DocumentMessageHandler {
 		responses.add(PortfolioMessage::UpdateOpenDocumentsList);
 		responses.add(NodeGraphMessage::SelectedNodesUpdated);
 		responses.add(NodeGraphMessage::ForceRunDocumentGraph);
+		responses.add(SwatchesMessage::SendLayout);
+		responses.add(NodeGraphMessage::SendCustomNodeTypes);
 		responses.add(NodeGraphMessage::UnloadWires);
 		responses.add(NodeGraphMessage::SendWires);
-		Some(previous_network)
+		responses.add(DocumentMessage::SendHistory);
+		Some(HistoryState {
+			network_interface: previous_network,
+			label,
+		})
+	}
+
+	/// Undoes or redoes steps until the given number of steps from the oldest kept state are applied, or as many as can be.
+	/// Only the messages from the last step are sent, since each step's messages bring the rest of the editor up to date with the whole document.
+	pub fn jump_to_history_state(&mut self, index: usize, ipp: &InputPreprocessorMessageHandler, responses: &mut VecDeque<Message>) {
+		let mut step_responses = VecDeque::new();
+		while self.document_undo_history.len() > index {
+			step_responses.clear();
+			self.undo_with_history(ipp, &mut step_responses);
+		}
+		while self.document_undo_history.len() < index && !self.document_redo_history.is_empty() {
+			step_responses.clear();
+			self.redo_with_history(ipp, &mut step_responses);
+		}
+		responses.extend(step_responses);
+	}
+
+	/// The steps which can be undone, from the oldest, followed by the steps which can be redone.
+	pub fn history_entries(&self) -> Vec<HistoryEntry> {
+		let undo_steps = self.document_undo_history.iter();
+		let redo_steps = self.document_redo_history.iter().rev();
+		undo_steps
+			.chain(redo_steps)
+			.enumerate()
+			.map(|(index, state)| HistoryEntry {
+				index: index + 1,
+				label: state.label.text(),
+			})
+			.collect()
+	}
+
+	/// The current position in the history, which is the number of steps which can be undone.
+	pub fn history_index(&self) -> usize {
+		self.document_undo_history.len()
+	}
+
+	/// The number of steps which can be undone.
+	#[cfg(test)]
+	pub(crate) fn undo_history_len(&self) -> usize {
+		self.document_undo_history.len()
 	}
 
 	pub fn current_hash(&self) -> Option<u64> {
-		self.document_undo_history.iter().last().map(|network| network.document_network().current_hash())
+		self.document_undo_history.iter().last().map(|state| state.network_interface.document_hash())
 	}
 
 	pub fn is_auto_saved(&self) -> bool {
@@ -2079,7 +2265,7 @@ impl DocumentMessageHandler {
 		for (_node_id, node, _) in self.document_network().recursive_nodes() {
 			for input in &node.inputs {
 				if let Some(TaggedValue::Font(font)) = input.as_value() {
-					fonts.insert(font.clone());
+					fonts.insert(font.without_variations());
 				}
 			}
 		}
@@ -2088,7 +2274,7 @@ impl DocumentMessageHandler {
 		}
 	}
 
-	pub fn update_document_widgets(&self, responses: &mut VecDeque<Message>, animation_is_playing: bool, time: Duration) {
+	pub fn update_document_widgets(&self, responses: &mut VecDeque<Message>) {
 		// Document mode (dropdown menu at the left of the bar above the viewport, before the tool options)
 
 		let document_mode_layout = WidgetLayout::new(vec![LayoutGroup::Row {
@@ -2130,10 +2316,10 @@ impl DocumentMessageHandler {
 				.tooltip("Restart Animation")
 				.tooltip_shortcut(action_keys!(AnimationMessageDiscriminant::RestartAnimation))
 				.on_update(|_| AnimationMessage::RestartAnimation.into())
-				.disabled(time == Duration::ZERO)
+				.disabled(self.playback.time() == 0.)
 				.widget_holder(),
-			IconButton::new(if animation_is_playing { "PlaybackPause" } else { "PlaybackPlay" }, 24)
-				.tooltip(if animation_is_playing { "Pause Animation" } else { "Play Animation" })
+			IconButton::new(if self.playback.is_playing() { "PlaybackPause" } else { "PlaybackPlay" }, 24)
+				.tooltip(if self.playback.is_playing() { "Pause Animation" } else { "Play Animation" })
 				.tooltip_shortcut(action_keys!(AnimationMessageDiscriminant::ToggleLivePreview))
 				.on_update(|_| AnimationMessage::ToggleLivePreview.into())
 				.widget_holder(),
@@ -2785,6 +2971,38 @@ impl DocumentMessageHandler {
 		});
 	}
 
+	/// The viewport bounds of the selected layers which can be moved, excluding locked layers and those inside other selected layers, which move along with them.
+	fn movable_selected_layer_bounds(&self) -> Vec<(LayerNodeIdentifier, [DVec2; 2])> {
+		let selected_nodes = self.network_interface.selected_nodes();
+		self.network_interface
+			.shallowest_unique_layers(&[])
+			.filter(|&layer| !selected_nodes.layer_locked(layer, &self.network_interface))
+			.filter_map(|layer| Some((layer, self.metadata().bounding_box_viewport(layer)?)))
+			.collect()
+	}
+
+	/// Translates each layer by its viewport offset as a single history step, by setting the transform in the layer's own parent space so nested and transformed layers move by the same visual distance.
+	fn move_layers_in_viewport(&self, offsets: Vec<(LayerNodeIdentifier, DVec2)>, transaction_name: &str, responses: &mut VecDeque<Message>) {
+		let offsets: Vec<_> = offsets.into_iter().filter(|(_, offset)| offset.length_squared() > f64::EPSILON).collect();
+		if offsets.is_empty() {
+			return;
+		}
+
+		responses.add(DocumentMessage::AddTransaction);
+		responses.add(DocumentMessage::NameTransaction { name: transaction_name.to_string() });
+		for (layer, offset) in offsets {
+			let to = self.metadata().downstream_transform_to_viewport(layer);
+			let original_transform = self.metadata().upstream_transform(layer.to_node());
+			let transform = to.inverse() * DAffine2::from_translation(offset) * to * original_transform;
+			responses.add(GraphOperationMessage::TransformSet {
+				layer,
+				transform,
+				transform_in: TransformIn::Local,
+				skip_rerender: false,
+			});
+		}
+	}
+
 	pub fn selected_layers_reverse(&mut self, responses: &mut VecDeque<Message>) {
 		let selected_layers = self.network_interface.selected_nodes();
 		let metadata = self.metadata();
@@ -2945,6 +3163,8 @@ pub struct ClickXRayIter<'a> {
 	next_layer: Option<LayerNodeIdentifier>,
 	network_interface: &'a NodeNetworkInterface,
 	parent_targets: Vec<(LayerNodeIdentifier, XRayTarget)>,
+	/// Whether locked layers, including those in locked groups, are hit like the rest rather than skipped.
+	include_locked: bool,
 }
 
 fn quad_to_path_lib_segments(quad: Quad) -> Vec<path_bool_lib::PathSegment> {
@@ -2971,18 +3191,20 @@ fn click_targets_to_path_lib_segments<'a>(click_targets: impl Iterator<Item = &'
 }
 
 impl<'a> ClickXRayIter<'a> {
-	fn new(network_interface: &'a NodeNetworkInterface, target: XRayTarget) -> Self {
+	fn new(network_interface: &'a NodeNetworkInterface, target: XRayTarget, include_locked: bool) -> Self {
 		if let Some(first_layer) = LayerNodeIdentifier::ROOT_PARENT.first_child(network_interface.document_metadata()) {
 			Self {
 				network_interface,
 				next_layer: Some(first_layer),
 				parent_targets: vec![(LayerNodeIdentifier::ROOT_PARENT, target)],
+				include_locked,
 			}
 		} else {
 			Self {
 				network_interface,
 				next_layer: Default::default(),
 				parent_targets: Default::default(),
+				include_locked,
 			}
 		}
 	}
@@ -3020,8 +3242,8 @@ impl<'a> ClickXRayIter<'a> {
 	/// Handles the checking of the layer to find if it has been clicked
 	fn check_layer(&mut self, layer: LayerNodeIdentifier) -> XRayResult {
 		let selected_layers = self.network_interface.selected_nodes();
-		// Discard invisible and locked layers
-		if !selected_layers.layer_visible(layer, self.network_interface) || selected_layers.layer_locked(layer, self.network_interface) {
+		// Discard invisible layers, and locked layers unless they are clicked through
+		if !selected_layers.layer_visible(layer, self.network_interface) || (!self.include_locked && selected_layers.layer_locked(layer, self.network_interface)) {
 			return XRayResult { clicked: false, use_children: false };
 		}
 
@@ -3208,6 +3430,416 @@ mod document_message_handler_tests {
 		assert!(selected_nodes.selected_layers_contains(layers[2], document.metadata()));
 	}
 
+	#[tokio::test]
+	async fn set_inputs_adds_one_history_step_and_evaluation() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+		let history_len = document.undo_history_len();
+		let evaluation_count = editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.submitted_evaluation_count();
+
+		let radius = |radius: f64| NodeInput::value(TaggedValue::F64(radius), false);
+		editor.editor.handle_message(NodeGraphMessage::SetInputs {
+			changes: vec![(InputConnector::node(ellipse_node, 1), radius(20.)), (InputConnector::node(ellipse_node, 2), radius(30.))],
+			skip_adding_history_step: false,
+			transaction_name: None,
+		});
+
+		let document = editor.active_document();
+		assert_eq!(document.undo_history_len(), history_len + 1);
+		assert_eq!(
+			editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.submitted_evaluation_count(),
+			evaluation_count + 1
+		);
+		let inputs = &document.network_interface.document_network().nodes[&ellipse_node].inputs;
+		assert_eq!(inputs[1].as_value(), Some(&TaggedValue::F64(20.)));
+		assert_eq!(inputs[2].as_value(), Some(&TaggedValue::F64(30.)));
+
+		// Undoing the step reverts both inputs together
+		editor.handle_message(DocumentMessage::Undo).await;
+		let inputs = &editor.active_document().network_interface.document_network().nodes[&ellipse_node].inputs;
+		assert_eq!(inputs[1].as_value(), Some(&TaggedValue::F64(50.)));
+		assert_eq!(inputs[2].as_value(), Some(&TaggedValue::F64(25.)));
+	}
+
+	#[tokio::test]
+	async fn single_set_input_leaves_the_evaluation_to_its_caller() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+		let evaluation_count = |editor: &EditorTestUtils| editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.submitted_evaluation_count();
+		let before = evaluation_count(&editor);
+
+		editor.editor.handle_message(NodeGraphMessage::SetInput {
+			input_connector: InputConnector::node(ellipse_node, 1),
+			input: NodeInput::value(TaggedValue::F64(20.), false),
+		});
+		let inputs = &editor.active_document().network_interface.document_network().nodes[&ellipse_node].inputs;
+		assert_eq!(inputs[1].as_value(), Some(&TaggedValue::F64(20.)));
+		assert_eq!(evaluation_count(&editor), before);
+
+		// Setting a value from the Properties panel evaluates the graph once
+		editor.editor.handle_message(NodeGraphMessage::SetInputValue {
+			node_id: ellipse_node,
+			input_index: 1,
+			value: TaggedValue::F64(30.),
+		});
+		assert_eq!(evaluation_count(&editor), before + 1);
+	}
+
+	#[tokio::test]
+	async fn set_inputs_applies_nothing_if_any_change_is_invalid() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+		let history_len = document.undo_history_len();
+
+		editor
+			.handle_message(NodeGraphMessage::SetInputs {
+				changes: vec![
+					(InputConnector::node(ellipse_node, 1), NodeInput::value(TaggedValue::F64(20.), false)),
+					(InputConnector::node(ellipse_node, 100), NodeInput::value(TaggedValue::F64(30.), false)),
+				],
+				skip_adding_history_step: false,
+				transaction_name: None,
+			})
+			.await;
+
+		let document = editor.active_document();
+		assert_eq!(document.undo_history_len(), history_len);
+		let inputs = &document.network_interface.document_network().nodes[&ellipse_node].inputs;
+		assert_eq!(inputs[1].as_value(), Some(&TaggedValue::F64(50.)));
+	}
+
+	fn selected_layers(editor: &EditorTestUtils) -> Vec<LayerNodeIdentifier> {
+		let document = editor.active_document();
+		document.network_interface.selected_nodes().selected_layers(document.metadata()).collect()
+	}
+
+	async fn set_locked(editor: &mut EditorTestUtils, layer: LayerNodeIdentifier, locked: bool) {
+		editor.handle_message(NodeGraphMessage::SetLocked { node_id: layer.to_node(), locked }).await;
+	}
+
+	/// Puts the layer in a new group, which is returned.
+	async fn group_layer(editor: &mut EditorTestUtils, layer: LayerNodeIdentifier) -> LayerNodeIdentifier {
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![layer.to_node()] }).await;
+		editor
+			.handle_message(DocumentMessage::GroupSelectedLayers {
+				group_folder_type: GroupFolderType::Layer,
+			})
+			.await;
+		layer.parent(editor.active_document().metadata()).unwrap()
+	}
+
+	#[tokio::test]
+	async fn clicking_skips_locked_layers_unless_clicking_through_locks() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(50., 50., 150., 150.).await;
+		let [top, bottom] = editor.active_document().metadata().all_layers().collect::<Vec<_>>()[..] else {
+			panic!("There should be two layers");
+		};
+
+		set_locked(&mut editor, top, true).await;
+
+		// The click where the layers overlap goes through the locked one on top
+		editor.click_tool(ToolType::Select, MouseKeys::LEFT, DVec2::new(75., 75.), ModifierKeys::empty()).await;
+		assert_eq!(selected_layers(&editor), vec![bottom]);
+
+		editor.click_tool(ToolType::Select, MouseKeys::LEFT, DVec2::new(75., 75.), ModifierKeys::ALT).await;
+		assert_eq!(selected_layers(&editor), vec![top]);
+
+		// Locking a group locks the layers in it too
+		set_locked(&mut editor, top, false).await;
+		let group = group_layer(&mut editor, bottom).await;
+		set_locked(&mut editor, group, true).await;
+
+		editor.click_tool(ToolType::Select, MouseKeys::LEFT, DVec2::new(25., 25.), ModifierKeys::empty()).await;
+		assert_eq!(selected_layers(&editor), Vec::new());
+		editor.click_tool(ToolType::Select, MouseKeys::LEFT, DVec2::new(75., 75.), ModifierKeys::empty()).await;
+		assert_eq!(selected_layers(&editor), vec![top]);
+	}
+
+	#[tokio::test]
+	async fn operations_on_locked_layers_are_rejected() {
+		use graphene_std::vector::style::Fill;
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		let layer = editor.active_document().metadata().all_layers().next().unwrap();
+		let move_right = GraphOperationMessage::TransformChange {
+			layer,
+			transform: DAffine2::from_translation(DVec2::new(10., 0.)),
+			transform_in: TransformIn::Local,
+			skip_rerender: false,
+		};
+		let fill_red = GraphOperationMessage::FillSet { layer, fill: Fill::Solid(Color::RED) };
+
+		set_locked(&mut editor, layer, true).await;
+		let locked_hash = editor.active_document().network_interface.document_hash();
+		editor.handle_message(move_right.clone()).await;
+		editor.handle_message(fill_red.clone()).await;
+		assert_eq!(editor.active_document().network_interface.document_hash(), locked_hash);
+
+		// The layer is still locked through its group once its own lock is removed
+		set_locked(&mut editor, layer, false).await;
+		let group = group_layer(&mut editor, layer).await;
+		set_locked(&mut editor, group, true).await;
+		let group_locked_hash = editor.active_document().network_interface.document_hash();
+		editor.handle_message(move_right.clone()).await;
+		editor.handle_message(fill_red.clone()).await;
+		assert_eq!(editor.active_document().network_interface.document_hash(), group_locked_hash);
+
+		set_locked(&mut editor, group, false).await;
+		let bounds_before = editor.active_document().metadata().bounding_box_document(layer).unwrap();
+		editor.handle_message(move_right).await;
+		let bounds_after = editor.active_document().metadata().bounding_box_document(layer).unwrap();
+		assert!((bounds_after[0] - bounds_before[0]).abs_diff_eq(DVec2::new(10., 0.), 1e-6));
+	}
+
+	fn history_labels(editor: &EditorTestUtils) -> Vec<String> {
+		editor.active_document().history_entries().into_iter().map(|entry| entry.label).collect()
+	}
+
+	fn document_hash(editor: &EditorTestUtils) -> u64 {
+		editor.active_document().network_interface.document_hash()
+	}
+
+	#[tokio::test]
+	async fn history_steps_are_labelled_by_their_changes() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		let start = editor.active_document().history_index();
+
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_ellipse(200., 0., 300., 100.).await;
+
+		editor.handle_message(DocumentMessage::SelectAllLayers).await;
+		editor.handle_message(TransformLayerMessage::BeginGrab).await;
+		editor.move_mouse(50., 25., ModifierKeys::empty(), MouseKeys::NONE).await;
+		editor
+			.handle_message(TransformLayerMessage::PointerMove {
+				slow_key: Key::Shift,
+				increments_key: Key::Control,
+			})
+			.await;
+		editor.handle_message(TransformLayerMessage::ApplyTransformOperation { final_transform: true }).await;
+
+		// A batch of changes is labelled by its transaction name
+		let document = editor.active_document();
+		let ellipse = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(ellipse, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+		let radius = |radius: f64| NodeInput::value(TaggedValue::F64(radius), false);
+		editor
+			.handle_message(NodeGraphMessage::SetInputs {
+				changes: vec![(InputConnector::node(ellipse_node, 1), radius(20.)), (InputConnector::node(ellipse_node, 2), radius(30.))],
+				skip_adding_history_step: false,
+				transaction_name: Some("Resize Ellipse".to_string()),
+			})
+			.await;
+
+		editor.handle_message(DocumentMessage::DuplicateSelectedLayers).await;
+
+		let labels = history_labels(&editor);
+		assert_eq!(labels[start..], ["Draw Rectangle", "Draw Ellipse", "Transform 2 layers", "Resize Ellipse", "Duplicate Layers"]);
+		assert_eq!(editor.active_document().history_index(), start + 5);
+
+		// Undoing keeps the labels of the steps which can be redone
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(history_labels(&editor), labels);
+		assert_eq!(editor.active_document().history_index(), start + 4);
+	}
+
+	#[tokio::test]
+	async fn renaming_a_layer_is_an_undoable_step_labelled_by_the_names() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		let start = editor.active_document().history_index();
+
+		let layer = editor.active_document().metadata().all_layers().next().unwrap();
+		let old_name = editor.active_document().network_interface.display_name(&layer.to_node(), &[]);
+		let rename = |new_name: &str| NodeGraphMessage::RenameLayer {
+			layer,
+			new_name: new_name.to_string(),
+		};
+		editor.handle_message(rename("Sky Background")).await;
+		assert_eq!(editor.active_document().network_interface.display_name(&layer.to_node(), &[]), "Sky Background");
+		assert_eq!(history_labels(&editor)[start..], [format!("Rename \"{old_name}\" to \"Sky Background\"")]);
+
+		// Giving the layer the name it already has isn't a step
+		editor.handle_message(rename("Sky Background")).await;
+		assert_eq!(editor.active_document().history_index(), start + 1);
+
+		// The layer is named by later steps which change only it
+		editor.handle_message(TransformLayerMessage::BeginGrab).await;
+		editor.move_mouse(50., 25., ModifierKeys::empty(), MouseKeys::NONE).await;
+		editor
+			.handle_message(TransformLayerMessage::PointerMove {
+				slow_key: Key::Shift,
+				increments_key: Key::Control,
+			})
+			.await;
+		editor.handle_message(TransformLayerMessage::ApplyTransformOperation { final_transform: true }).await;
+		assert_eq!(history_labels(&editor)[start + 1..], ["Transform \"Sky Background\""]);
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.display_name(&layer.to_node(), &[]), old_name);
+	}
+
+	#[tokio::test]
+	async fn jumping_through_history_restores_each_state() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		let start = editor.active_document().history_index();
+
+		let mut hashes = vec![document_hash(&editor)];
+		for x in [0., 200., 400.] {
+			editor.draw_rect(x, 0., x + 100., 100.).await;
+			hashes.push(document_hash(&editor));
+		}
+
+		for steps in [1, 3, 0, 2] {
+			editor.handle_message(DocumentMessage::JumpToHistoryState { index: start + steps }).await;
+			assert_eq!(editor.active_document().history_index(), start + steps);
+			assert_eq!(document_hash(&editor), hashes[steps]);
+			assert_eq!(history_labels(&editor).len(), start + 3);
+		}
+
+		// Jumping past the newest state stops there
+		editor.handle_message(DocumentMessage::JumpToHistoryState { index: start + 10 }).await;
+		assert_eq!(editor.active_document().history_index(), start + 3);
+		assert_eq!(document_hash(&editor), hashes[3]);
+
+		// Making a change after jumping back drops the steps which could have been redone
+		editor.handle_message(DocumentMessage::JumpToHistoryState { index: start + 1 }).await;
+		editor.draw_ellipse(0., 200., 100., 300.).await;
+		assert_eq!(history_labels(&editor)[start..], ["Draw Rectangle", "Draw Ellipse"]);
+		assert_eq!(editor.active_document().history_index(), start + 2);
+	}
+
+	fn newest_layer(editor: &EditorTestUtils) -> LayerNodeIdentifier {
+		editor.active_document().metadata().all_layers().next().unwrap()
+	}
+
+	fn viewport_bounds(editor: &EditorTestUtils, layer: LayerNodeIdentifier) -> [DVec2; 2] {
+		editor.active_document().metadata().bounding_box_viewport(layer).unwrap()
+	}
+
+	#[tokio::test]
+	async fn distributing_moves_nested_layers_by_their_viewport_offset_in_one_step() {
+		use crate::messages::portfolio::document::utility_types::misc::{AlignAxis, DistributeMode};
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		let left = newest_layer(&editor);
+		editor.draw_rect(75., 0., 100., 25.).await;
+		let middle = newest_layer(&editor);
+		editor.draw_rect(450., 0., 500., 100.).await;
+		let right = newest_layer(&editor);
+
+		// Doubling the size of the middle layer's group puts the layer between 150 and 200
+		let group = group_layer(&mut editor, middle).await;
+		editor
+			.handle_message(GraphOperationMessage::TransformSet {
+				layer: group,
+				transform: DAffine2::from_scale(DVec2::splat(2.)),
+				transform_in: TransformIn::Local,
+				skip_rerender: false,
+			})
+			.await;
+		assert!((viewport_bounds(&editor, middle)[0].x - 150.).abs() < 1e-6);
+
+		let nodes = vec![left.to_node(), middle.to_node(), right.to_node()];
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes }).await;
+		let start = editor.active_document().history_index();
+
+		// The 500 px span holds 200 px of layers, which leaves two gaps of 150 px
+		editor
+			.handle_message(DocumentMessage::DistributeSelectedLayers {
+				axis: AlignAxis::X,
+				mode: DistributeMode::Spacing,
+			})
+			.await;
+		let [min, max] = viewport_bounds(&editor, middle);
+		assert!(min.abs_diff_eq(DVec2::new(250., 0.), 1e-6) && max.abs_diff_eq(DVec2::new(300., 50.), 1e-6));
+		assert!(viewport_bounds(&editor, left)[0].x.abs() < 1e-6);
+		assert!((viewport_bounds(&editor, right)[0].x - 450.).abs() < 1e-6);
+		assert_eq!(editor.active_document().history_index(), start + 1);
+		assert_eq!(history_labels(&editor)[start], "Distribute Layers");
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert!((viewport_bounds(&editor, middle)[0].x - 150.).abs() < 1e-6);
+
+		// Halfway between the centers at 50 and 475, the middle layer's center is at 262.5
+		editor
+			.handle_message(DocumentMessage::DistributeSelectedLayers {
+				axis: AlignAxis::X,
+				mode: DistributeMode::Centers,
+			})
+			.await;
+		assert!((viewport_bounds(&editor, middle)[0].x - 237.5).abs() < 1e-6);
+	}
+
+	#[tokio::test]
+	async fn aligning_to_the_artboard_skips_layers_outside_of_one() {
+		use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis};
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Artboard, 0., 0., 300., 300., ModifierKeys::empty()).await;
+		editor.draw_rect(50., 50., 100., 100.).await;
+		let inside = newest_layer(&editor);
+		editor.draw_rect(400., 50., 450., 100.).await;
+		let outside = newest_layer(&editor);
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![outside.to_node()] }).await;
+		editor
+			.handle_message(DocumentMessage::MoveSelectedLayersTo {
+				parent: LayerNodeIdentifier::ROOT_PARENT,
+				insert_index: 0,
+			})
+			.await;
+
+		let nodes = vec![inside.to_node(), outside.to_node()];
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes }).await;
+		editor
+			.handle_message(DocumentMessage::AlignSelectedLayers {
+				axis: AlignAxis::X,
+				aggregate: AlignAggregate::Max,
+				target: AlignTarget::Artboard,
+			})
+			.await;
+		assert!((viewport_bounds(&editor, inside)[1].x - 300.).abs() < 1e-6);
+		assert!((viewport_bounds(&editor, outside)[1].x - 450.).abs() < 1e-6);
+
+		// Aligned to the selection instead, both layers line up with the right edge of the outside one
+		editor
+			.handle_message(DocumentMessage::AlignSelectedLayers {
+				axis: AlignAxis::X,
+				aggregate: AlignAggregate::Max,
+				target: AlignTarget::Selection,
+			})
+			.await;
+		assert!((viewport_bounds(&editor, inside)[1].x - 450.).abs() < 1e-6);
+		assert!((viewport_bounds(&editor, outside)[1].x - 450.).abs() < 1e-6);
+	}
+
 	#[tokio::test]
 	async fn test_layer_rearrangement() {
 		let mut editor = EditorTestUtils::create();
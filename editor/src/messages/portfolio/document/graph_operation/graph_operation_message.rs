@@ -21,6 +21,14 @@ pub enum GraphOperationMessage {
 		layer: LayerNodeIdentifier,
 		fill: Fill,
 	},
+	FillPatternSet {
+		layer: LayerNodeIdentifier,
+		tile_layer: LayerNodeIdentifier,
+	},
+	RoundCornersSet {
+		layer: LayerNodeIdentifier,
+		anchors: Vec<PointId>,
+	},
 	BlendingFillSet {
 		layer: LayerNodeIdentifier,
 		fill: f64,
@@ -60,6 +68,10 @@ pub enum GraphOperationMessage {
 		layer: LayerNodeIdentifier,
 		strokes: Vec<BrushStroke>,
 	},
+	BrushCloneSource {
+		layer: LayerNodeIdentifier,
+		sample_merged: bool,
+	},
 	SetUpstreamToChain {
 		layer: LayerNodeIdentifier,
 	},
@@ -96,6 +108,8 @@ pub enum GraphOperationMessage {
 		text: String,
 		font: Font,
 		typesetting: TypesettingConfig,
+		/// The layer whose path the new text flows along, if any.
+		on_path: Option<LayerNodeIdentifier>,
 		parent: LayerNodeIdentifier,
 		insert_index: usize,
 	},
@@ -1,18 +1,25 @@
 use super::transform_utils;
-use super::utility_types::ModifyInputsContext;
+use super::utility_types::{ModifyInputsContext, SvgImportReport, UnsupportedSvgFeature};
+use crate::consts::SVG_IMPORT_RASTERIZATION_SCALE;
+use crate::messages::dialog::simple_dialogs;
+use crate::messages::layout::utility_types::layout_widget::DialogLayoutHolder;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, NodeNetworkInterface, OutputConnector};
 use crate::messages::portfolio::document::utility_types::nodes::CollapsedLayers;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::graph_modification_utils::get_clip_mode;
+use crate::messages::tool::common_functionality::utility_functions::send_locked_layers_hint;
 use glam::{DAffine2, DVec2, IVec2};
 use graph_craft::document::{NodeId, NodeInput};
 use graphene_std::Color;
+use graphene_std::raster::BlendMode;
+use graphene_std::raster_types::{Raster, RasterDataTable};
 use graphene_std::renderer::Quad;
 use graphene_std::renderer::convert_usvg_path::convert_usvg_path;
+use graphene_std::renderer::rasterize::rasterize_usvg_node;
 use graphene_std::text::{Font, TypesettingConfig};
-use graphene_std::vector::style::{Fill, Gradient, GradientStops, GradientType, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::style::{Fill, FillRule, Gradient, GradientStops, GradientType, PaintOrder, Stroke, StrokeAlign, StrokeCap, StrokeJoin, StrokeMarker};
 
 #[derive(ExtractField)]
 pub struct GraphOperationMessageContext<'a> {
@@ -31,12 +38,29 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 	fn process_message(&mut self, message: GraphOperationMessage, responses: &mut VecDeque<Message>, context: GraphOperationMessageContext) {
 		let network_interface = context.network_interface;
 
+		if let Some(layer) = edited_layer(&message)
+			&& network_interface.selected_nodes().layer_locked(layer, network_interface)
+		{
+			send_locked_layers_hint(responses);
+			return;
+		}
+
 		match message {
 			GraphOperationMessage::FillSet { layer, fill } => {
 				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
 					modify_inputs.fill_set(fill);
 				}
 			}
+			GraphOperationMessage::FillPatternSet { layer, tile_layer } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.fill_pattern_set(tile_layer);
+				}
+			}
+			GraphOperationMessage::RoundCornersSet { layer, anchors } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.round_corners_set(anchors);
+				}
+			}
 			GraphOperationMessage::BlendingFillSet { layer, fill } => {
 				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
 					modify_inputs.blending_fill_set(fill);
@@ -98,6 +122,11 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 					modify_inputs.brush_modify(strokes);
 				}
 			}
+			GraphOperationMessage::BrushCloneSource { layer, sample_merged } => {
+				if let Some(mut modify_inputs) = ModifyInputsContext::new_with_layer(layer, network_interface, responses) {
+					modify_inputs.brush_clone_source(sample_merged);
+				}
+			}
 			GraphOperationMessage::SetUpstreamToChain { layer } => {
 				let Some(OutputConnector::Node { node_id: first_chain_node, .. }) = network_interface.upstream_output_connector(&InputConnector::node(layer.to_node(), 1), &[]) else {
 					return;
@@ -183,12 +212,18 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 				text,
 				font,
 				typesetting,
+				on_path,
 				parent,
 				insert_index,
 			} => {
+				// Feed the text the same vector data that the path layer receives, so editing the path re-flows the text
+				let path = on_path
+					.and_then(|path_layer| network_interface.upstream_output_connector(&InputConnector::node(path_layer.to_node(), 1), &[]))
+					.and_then(|output| Some(NodeInput::node(output.node_id()?, output.index())));
+
 				let mut modify_inputs = ModifyInputsContext::new(network_interface, responses);
 				let layer = modify_inputs.create_layer(id);
-				modify_inputs.insert_text(text, font, typesetting, layer);
+				modify_inputs.insert_text(text, font, typesetting, path, layer);
 				network_interface.move_layer_to_stack(layer, parent, insert_index, &[]);
 				responses.add(GraphOperationMessage::StrokeSet { layer, stroke: Stroke::default() });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
@@ -306,7 +341,12 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 				let offset_to_center = DVec2::new(size.width() as f64, size.height() as f64) / -2.;
 				let transform = transform * DAffine2::from_translation(offset_to_center);
 
-				import_usvg_node(&mut modify_inputs, &usvg::Node::Group(Box::new(tree.root().clone())), transform, id, parent, insert_index);
+				let mut report = SvgImportReport::default();
+				import_usvg_node(&mut modify_inputs, &usvg::Node::Group(Box::new(tree.root().clone())), transform, id, parent, insert_index, &mut report);
+
+				if !report.is_empty() {
+					simple_dialogs::SvgImportReportDialog { report }.send_dialog_to_frontend(responses);
+				}
 			}
 		}
 	}
@@ -316,6 +356,27 @@ impl MessageHandler<GraphOperationMessage, GraphOperationMessageContext<'_>> for
 	}
 }
 
+/// The existing layer whose content or transform the message edits, which is rejected if the layer or one of its groups is locked.
+/// Setting a transform isn't included since it also keeps layers in place when they are moved to another group, which locked layers may be.
+fn edited_layer(message: &GraphOperationMessage) -> Option<LayerNodeIdentifier> {
+	match message {
+		GraphOperationMessage::FillSet { layer, .. }
+		| GraphOperationMessage::FillPatternSet { layer, .. }
+		| GraphOperationMessage::RoundCornersSet { layer, .. }
+		| GraphOperationMessage::BlendingFillSet { layer, .. }
+		| GraphOperationMessage::OpacitySet { layer, .. }
+		| GraphOperationMessage::BlendModeSet { layer, .. }
+		| GraphOperationMessage::ClipModeToggle { layer }
+		| GraphOperationMessage::StrokeSet { layer, .. }
+		| GraphOperationMessage::TransformChange { layer, .. }
+		| GraphOperationMessage::Vector { layer, .. }
+		| GraphOperationMessage::Brush { layer, .. }
+		| GraphOperationMessage::BrushCloneSource { layer, .. }
+		| GraphOperationMessage::ResizeArtboard { layer, .. } => Some(*layer),
+		_ => None,
+	}
+}
+
 #[derive(Debug, Clone)]
 struct ArtboardInfo {
 	input_node: NodeInput,
@@ -331,19 +392,85 @@ fn usvg_transform(c: usvg::Transform) -> DAffine2 {
 	DAffine2::from_cols_array(&[c.sx as f64, c.ky as f64, c.kx as f64, c.sy as f64, c.tx as f64, c.ty as f64])
 }
 
-fn import_usvg_node(modify_inputs: &mut ModifyInputsContext, node: &usvg::Node, transform: DAffine2, id: NodeId, parent: LayerNodeIdentifier, insert_index: usize) {
+fn usvg_blend_mode(blend_mode: usvg::BlendMode) -> BlendMode {
+	match blend_mode {
+		usvg::BlendMode::Normal => BlendMode::Normal,
+		usvg::BlendMode::Multiply => BlendMode::Multiply,
+		usvg::BlendMode::Screen => BlendMode::Screen,
+		usvg::BlendMode::Overlay => BlendMode::Overlay,
+		usvg::BlendMode::Darken => BlendMode::Darken,
+		usvg::BlendMode::Lighten => BlendMode::Lighten,
+		usvg::BlendMode::ColorDodge => BlendMode::ColorDodge,
+		usvg::BlendMode::ColorBurn => BlendMode::ColorBurn,
+		usvg::BlendMode::HardLight => BlendMode::HardLight,
+		usvg::BlendMode::SoftLight => BlendMode::SoftLight,
+		usvg::BlendMode::Difference => BlendMode::Difference,
+		usvg::BlendMode::Exclusion => BlendMode::Exclusion,
+		usvg::BlendMode::Hue => BlendMode::Hue,
+		usvg::BlendMode::Saturation => BlendMode::Saturation,
+		usvg::BlendMode::Color => BlendMode::Color,
+		usvg::BlendMode::Luminosity => BlendMode::Luminosity,
+	}
+}
+
+/// The feature used by the SVG node which keeps it from being imported as editable layers, if any.
+fn unsupported_feature(node: &usvg::Node) -> Option<UnsupportedSvgFeature> {
+	match node {
+		usvg::Node::Group(group) if !group.filters().is_empty() => Some(UnsupportedSvgFeature::Filter),
+		usvg::Node::Group(group) if group.mask().is_some() => Some(UnsupportedSvgFeature::Mask),
+		usvg::Node::Group(group) if group.clip_path().is_some() => Some(UnsupportedSvgFeature::ClipPath),
+		usvg::Node::Group(_) | usvg::Node::Text(_) => None,
+		usvg::Node::Path(path) => {
+			let fill = path.fill().map(|fill| fill.paint());
+			let stroke = path.stroke().map(|stroke| stroke.paint());
+			if [fill, stroke].into_iter().flatten().any(|paint| matches!(paint, usvg::Paint::Pattern(_))) {
+				Some(UnsupportedSvgFeature::Pattern)
+			} else if stroke.is_some_and(|paint| !matches!(paint, usvg::Paint::Color(_))) {
+				Some(UnsupportedSvgFeature::GradientStroke)
+			} else {
+				None
+			}
+		}
+		usvg::Node::Image(_) => Some(UnsupportedSvgFeature::Image),
+	}
+}
+
+fn import_usvg_node(modify_inputs: &mut ModifyInputsContext, node: &usvg::Node, transform: DAffine2, id: NodeId, parent: LayerNodeIdentifier, insert_index: usize, report: &mut SvgImportReport) {
 	let layer = modify_inputs.create_layer(id);
 	modify_inputs.network_interface.move_layer_to_stack(layer, parent, insert_index, &[]);
 	modify_inputs.layer_node = Some(layer);
 	if let Some(upstream_layer) = layer.next_sibling(modify_inputs.network_interface.document_metadata()) {
 		modify_inputs.network_interface.shift_node(&upstream_layer.to_node(), IVec2::new(0, 3), &[]);
 	}
+
+	// Keep the look of elements which can't be edited by rasterizing them, rather than importing them without the features they depend on
+	if let Some(feature) = unsupported_feature(node) {
+		match rasterize_usvg_node(node, SVG_IMPORT_RASTERIZATION_SCALE) {
+			Some((image, placement)) => {
+				modify_inputs.insert_image_data(RasterDataTable::new(Raster::new_cpu(image)), layer);
+				if let Some(transform_node_id) = modify_inputs.existing_node_id("Transform", true) {
+					transform_utils::update_transform(modify_inputs.network_interface, &transform_node_id, transform * placement);
+				}
+				*report.rasterized.entry(feature).or_default() += 1;
+			}
+			None => *report.skipped.entry(feature).or_default() += 1,
+		}
+		return;
+	}
+
 	match node {
 		usvg::Node::Group(group) => {
 			for child in group.children() {
-				import_usvg_node(modify_inputs, child, transform, NodeId::new(), layer, 0);
+				import_usvg_node(modify_inputs, child, transform, NodeId::new(), layer, 0, report);
 			}
 			modify_inputs.layer_node = Some(layer);
+
+			if group.opacity().get() < 1. {
+				modify_inputs.opacity_set(group.opacity().get() as f64);
+			}
+			if group.blend_mode() != usvg::BlendMode::Normal {
+				modify_inputs.blend_mode_set(usvg_blend_mode(group.blend_mode()));
+			}
 		}
 		usvg::Node::Path(path) => {
 			let subpaths = convert_usvg_path(path);
@@ -363,13 +490,13 @@ fn import_usvg_node(modify_inputs: &mut ModifyInputsContext, node: &usvg::Node,
 				apply_usvg_stroke(stroke, modify_inputs, transform * usvg_transform(node.abs_transform()));
 			}
 		}
-		usvg::Node::Image(_image) => {
-			warn!("Skip image")
-		}
+		// Images are always rasterized above
+		usvg::Node::Image(_) => {}
 		usvg::Node::Text(text) => {
 			let font = Font::new(graphene_std::consts::DEFAULT_FONT_FAMILY.to_string(), graphene_std::consts::DEFAULT_FONT_STYLE.to_string());
-			modify_inputs.insert_text(text.chunks().iter().map(|chunk| chunk.text()).collect(), font, TypesettingConfig::default(), layer);
+			modify_inputs.insert_text(text.chunks().iter().map(|chunk| chunk.text()).collect(), font, TypesettingConfig::default(), None, layer);
 			modify_inputs.fill_set(Fill::Solid(Color::BLACK));
+			report.default_font_text += 1;
 		}
 	}
 }
@@ -397,11 +524,20 @@ fn apply_usvg_stroke(stroke: &usvg::Stroke, modify_inputs: &mut ModifyInputsCont
 			paint_order: PaintOrder::StrokeAbove,
 			transform,
 			non_scaling: false,
+			// Imported SVG markers are already converted by usvg into separate paths
+			start_marker: StrokeMarker::None,
+			end_marker: StrokeMarker::None,
+			marker_size: 1.,
 		})
 	}
 }
 
 fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, transform: DAffine2, bounds_transform: DAffine2) {
+	modify_inputs.fill_rule_set(match fill.rule() {
+		usvg::FillRule::NonZero => FillRule::NonZero,
+		usvg::FillRule::EvenOdd => FillRule::EvenOdd,
+	});
+
 	modify_inputs.fill_set(match &fill.paint() {
 		usvg::Paint::Color(color) => Fill::solid(usvg_color(*color, fill.opacity().get())),
 		usvg::Paint::LinearGradient(linear) => {
@@ -429,10 +565,12 @@ fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, t
 				transform: DAffine2::IDENTITY,
 				gradient_type: GradientType::Linear,
 				stops,
+				..Default::default()
 			})
 		}
 		usvg::Paint::RadialGradient(radial) => {
-			let local = [DVec2::new(radial.cx() as f64, radial.cy() as f64), DVec2::new(radial.fx() as f64, radial.fy() as f64)];
+			let center = DVec2::new(radial.cx() as f64, radial.cy() as f64);
+			let local = [center, center + DVec2::X * radial.r().get() as f64, DVec2::new(radial.fx() as f64, radial.fy() as f64)];
 
 			// TODO: fix this
 			// let to_doc_transform = if radial.base.units == usvg::Units::UserSpaceOnUse {
@@ -443,10 +581,7 @@ fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, t
 			let to_doc_transform = transform;
 			let to_doc = to_doc_transform * usvg_transform(radial.transform());
 
-			let document = [to_doc.transform_point2(local[0]), to_doc.transform_point2(local[1])];
-			let layer = [transform.inverse().transform_point2(document[0]), transform.inverse().transform_point2(document[1])];
-
-			let [start, end] = [bounds_transform.inverse().transform_point2(layer[0]), bounds_transform.inverse().transform_point2(layer[1])];
+			let [start, end, focal_point] = local.map(|point| bounds_transform.inverse().transform_point2(transform.inverse().transform_point2(to_doc.transform_point2(point))));
 			let stops = radial.stops().iter().map(|stop| (stop.offset().get() as f64, usvg_color(stop.color(), stop.opacity().get()))).collect();
 			let stops = GradientStops::new(stops);
 
@@ -456,6 +591,8 @@ fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, t
 				transform: DAffine2::IDENTITY,
 				gradient_type: GradientType::Radial,
 				stops,
+				focal_offset: focal_point - start,
+				..Default::default()
 			})
 		}
 		usvg::Paint::Pattern(_) => {
@@ -464,3 +601,214 @@ fn apply_usvg_fill(fill: &usvg::Fill, modify_inputs: &mut ModifyInputsContext, t
 		}
 	});
 }
+
+#[cfg(test)]
+mod test {
+	use super::utility_types::{ModifyInputsContext, TransformIn};
+	use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
+	use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, OutputConnector};
+	use crate::messages::tool::common_functionality::graph_modification_utils::{get_gradient, get_opacity};
+	use crate::test_utils::test_prelude::*;
+	use glam::DAffine2;
+	use graph_craft::document::NodeId;
+	use graph_craft::document::value::TaggedValue;
+	use graphene_std::NodeInputDecleration;
+	use graphene_std::vector::style::{FillRule, GradientType};
+
+	/// Imports the SVG into a new document, returning the layer of its root along with the messages sent to the frontend while importing it.
+	async fn import_svg(editor: &mut EditorTestUtils, svg: &str) -> (LayerNodeIdentifier, Vec<FrontendMessage>) {
+		editor.new_document().await;
+		let frontend_messages = editor.editor.handle_message(PortfolioMessage::PasteSvg {
+			name: None,
+			svg: svg.to_string(),
+			mouse: None,
+			parent_and_insert_index: None,
+		});
+		editor.eval_graph().await.unwrap();
+
+		(editor.get_selected_layer().await.expect("The imported SVG should be selected"), frontend_messages)
+	}
+
+	fn children(editor: &EditorTestUtils, layer: LayerNodeIdentifier) -> Vec<LayerNodeIdentifier> {
+		layer.children(editor.active_document().metadata()).collect()
+	}
+
+	fn size(editor: &EditorTestUtils, layer: LayerNodeIdentifier) -> DVec2 {
+		let [min, max] = editor.active_document().metadata().bounding_box_document(layer).unwrap();
+		max - min
+	}
+
+	fn shows_import_report(frontend_messages: &[FrontendMessage]) -> bool {
+		frontend_messages
+			.iter()
+			.any(|message| matches!(message, FrontendMessage::DisplayDialog { title, .. } if title == "SVG Import"))
+	}
+
+	#[tokio::test]
+	async fn nested_groups_become_nested_layers_with_their_transforms() {
+		let mut editor = EditorTestUtils::create();
+		let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+			<g id="outer" transform="translate(10 20)">
+				<g id="inner" transform="scale(2)">
+					<rect width="10" height="5" />
+				</g>
+				<circle cx="50" cy="50" r="5" />
+			</g>
+		</svg>"#;
+		let (svg_layer, frontend_messages) = import_svg(&mut editor, svg).await;
+		assert!(!shows_import_report(&frontend_messages));
+
+		let [outer] = children(&editor, svg_layer)[..] else { panic!("The SVG should contain one group") };
+		// Later elements are drawn on top, so they come first in the layer stack
+		let [circle, inner] = children(&editor, outer)[..] else {
+			panic!("The outer group should contain the inner group and the circle")
+		};
+		let [rect] = children(&editor, inner)[..] else {
+			panic!("The inner group should contain the rectangle")
+		};
+
+		assert!((size(&editor, rect) - DVec2::new(20., 10.)).length() < 1e-6);
+		assert!((size(&editor, circle) - DVec2::new(10., 10.)).length() < 1e-6);
+
+		let metadata = editor.active_document().metadata();
+		let offset = metadata.bounding_box_document(circle).unwrap()[0] - metadata.bounding_box_document(rect).unwrap()[0];
+		assert!((offset - DVec2::new(45., 45.)).length() < 1e-6);
+	}
+
+	#[tokio::test]
+	async fn gradient_fills_are_imported_relative_to_the_bounds() {
+		let mut editor = EditorTestUtils::create();
+		let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+			<defs>
+				<linearGradient id="fade">
+					<stop offset="0" stop-color="red" />
+					<stop offset="1" stop-color="blue" />
+				</linearGradient>
+			</defs>
+			<rect x="10" y="10" width="50" height="20" fill="url(#fade)" />
+		</svg>"#;
+		let (svg_layer, _) = import_svg(&mut editor, svg).await;
+
+		let [rect] = children(&editor, svg_layer)[..] else {
+			panic!("The SVG should contain one rectangle")
+		};
+		let gradient = get_gradient(rect, &editor.active_document().network_interface).expect("The rectangle should have a gradient fill");
+		assert_eq!(gradient.gradient_type, GradientType::Linear);
+		assert_eq!(gradient.stops.len(), 2);
+		assert!((gradient.start - DVec2::ZERO).length() < 1e-6);
+		assert!((gradient.end - DVec2::X).length() < 1e-6);
+		assert!((size(&editor, rect) - DVec2::new(50., 20.)).length() < 1e-6);
+	}
+
+	#[tokio::test]
+	async fn fill_rules_and_group_opacity_are_kept() {
+		let mut editor = EditorTestUtils::create();
+		let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+			<g id="faded" opacity="0.5">
+				<path fill-rule="evenodd" d="M 0 0 H 40 V 40 H 0 Z M 10 10 H 30 V 30 H 10 Z" />
+			</g>
+		</svg>"#;
+		let (svg_layer, _) = import_svg(&mut editor, svg).await;
+
+		let [group] = children(&editor, svg_layer)[..] else { panic!("The SVG should contain one group") };
+		let [path] = children(&editor, group)[..] else { panic!("The group should contain one path") };
+		let network_interface = &editor.active_document().network_interface;
+
+		assert_eq!(get_opacity(group, network_interface), Some(50.));
+		let fill_rule = NodeGraphLayer::new(path, network_interface).find_input("Fill", graphene_std::vector::fill::FillRuleInput::INDEX);
+		assert_eq!(fill_rule, Some(&TaggedValue::FillRule(FillRule::EvenOdd)));
+		assert!((size(&editor, path) - DVec2::new(40., 40.)).length() < 1e-6);
+	}
+
+	#[tokio::test]
+	async fn filtered_groups_are_rasterized_and_reported() {
+		let mut editor = EditorTestUtils::create();
+		let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="100" height="100">
+			<defs>
+				<filter id="blur"><feGaussianBlur stdDeviation="2" /></filter>
+			</defs>
+			<g id="blurred" filter="url(#blur)">
+				<rect width="50" height="50" />
+			</g>
+			<rect id="sharp" x="60" width="10" height="10" />
+		</svg>"#;
+		let (svg_layer, frontend_messages) = import_svg(&mut editor, svg).await;
+		assert!(shows_import_report(&frontend_messages));
+
+		let [sharp, blurred] = children(&editor, svg_layer)[..] else {
+			panic!("The SVG should contain the blurred group and the sharp rectangle")
+		};
+		let network_interface = &editor.active_document().network_interface;
+		assert!(is_layer_fed_by_node_of_name(blurred, network_interface, "Image Value"));
+		assert!(children(&editor, blurred).is_empty());
+		assert!(is_layer_fed_by_node_of_name(sharp, network_interface, "Path"));
+
+		// The image covers the filter region, which extends 10% beyond the group's bounds on each side
+		assert!((size(&editor, blurred) - DVec2::new(60., 60.)).length() < 1.);
+	}
+
+	#[tokio::test]
+	async fn brush_clone_source_samples_the_layers_below() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		let rectangle = editor.get_selected_layer().await.unwrap();
+
+		// A brush layer above the rectangle
+		let brush_layer = LayerNodeIdentifier::new_unchecked(NodeId::new());
+		editor
+			.handle_message(GraphOperationMessage::NewCustomLayer {
+				id: brush_layer.to_node(),
+				nodes: vec![(NodeId(0), resolve_document_node_type("Brush").unwrap().default_node_template())],
+				parent: rectangle.parent(editor.active_document().metadata()).unwrap(),
+				insert_index: 0,
+			})
+			.await;
+
+		let clone_source = |editor: &EditorTestUtils| {
+			let network_interface = &editor.active_document().network_interface;
+			let brush_node = ModifyInputsContext::locate_node_in_layer_chain("Brush", brush_layer, network_interface).unwrap();
+			network_interface.upstream_output_connector(&InputConnector::node(brush_node, graphene_std::brush::brush::brush::CloneSourceInput::INDEX), &[])
+		};
+		assert_eq!(clone_source(&editor), None);
+
+		editor
+			.handle_message(GraphOperationMessage::BrushCloneSource {
+				layer: brush_layer,
+				sample_merged: true,
+			})
+			.await;
+		assert_eq!(clone_source(&editor), Some(OutputConnector::node(rectangle.to_node(), 0)));
+
+		editor
+			.handle_message(GraphOperationMessage::BrushCloneSource {
+				layer: brush_layer,
+				sample_merged: false,
+			})
+			.await;
+		assert_eq!(clone_source(&editor), None);
+
+		// Moving the brush layer with a Transform node places its strokes differently from the layers below, so it no longer samples them
+		editor
+			.handle_message(GraphOperationMessage::BrushCloneSource {
+				layer: brush_layer,
+				sample_merged: true,
+			})
+			.await;
+		editor
+			.handle_message(GraphOperationMessage::TransformSet {
+				layer: brush_layer,
+				transform: DAffine2::from_translation(DVec2::new(20., 0.)),
+				transform_in: TransformIn::Local,
+				skip_rerender: false,
+			})
+			.await;
+		editor
+			.handle_message(GraphOperationMessage::BrushCloneSource {
+				layer: brush_layer,
+				sample_merged: true,
+			})
+			.await;
+		assert_eq!(clone_source(&editor), None);
+	}
+}
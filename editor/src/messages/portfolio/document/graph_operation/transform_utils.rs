@@ -30,14 +30,21 @@ pub fn compute_scale_angle_translation_shear(transform: DAffine2) -> (DVec2, f64
 	(scale, angle, translation, shear)
 }
 
-/// Update the inputs of the transform node to match a new transform
-pub fn update_transform(network_interface: &mut NodeNetworkInterface, node_id: &NodeId, transform: DAffine2) {
+/// The input changes which set the transform node to a new transform, to be applied together as one batch
+pub fn transform_input_changes(node_id: NodeId, transform: DAffine2) -> Vec<(InputConnector, NodeInput)> {
 	let (scale, angle, translation, shear) = compute_scale_angle_translation_shear(transform);
 
-	network_interface.set_input(&InputConnector::node(*node_id, 1), NodeInput::value(TaggedValue::DVec2(translation), false), &[]);
-	network_interface.set_input(&InputConnector::node(*node_id, 2), NodeInput::value(TaggedValue::F64(angle), false), &[]);
-	network_interface.set_input(&InputConnector::node(*node_id, 3), NodeInput::value(TaggedValue::DVec2(scale), false), &[]);
-	network_interface.set_input(&InputConnector::node(*node_id, 4), NodeInput::value(TaggedValue::DVec2(shear), false), &[]);
+	vec![
+		(InputConnector::node(node_id, 1), NodeInput::value(TaggedValue::DVec2(translation), false)),
+		(InputConnector::node(node_id, 2), NodeInput::value(TaggedValue::F64(angle), false)),
+		(InputConnector::node(node_id, 3), NodeInput::value(TaggedValue::DVec2(scale), false)),
+		(InputConnector::node(node_id, 4), NodeInput::value(TaggedValue::DVec2(shear), false)),
+	]
+}
+
+/// Update the inputs of the transform node to match a new transform
+pub fn update_transform(network_interface: &mut NodeNetworkInterface, node_id: &NodeId, transform: DAffine2) {
+	network_interface.set_inputs(transform_input_changes(*node_id, transform), &[]);
 }
 
 // TODO: This should be extracted from the graph at the location of the transform node.
@@ -13,10 +13,11 @@ use graphene_std::brush::brush_stroke::BrushStroke;
 use graphene_std::raster::BlendMode;
 use graphene_std::raster_types::{CPU, RasterDataTable};
 use graphene_std::text::{Font, TypesettingConfig};
-use graphene_std::vector::style::{Fill, Stroke};
+use graphene_std::vector::style::{Fill, FillRule, Pattern, Stroke};
 use graphene_std::vector::{PointId, VectorModificationType};
 use graphene_std::vector::{VectorData, VectorDataTable};
 use graphene_std::{GraphicGroupTable, NodeInputDecleration};
+use std::collections::BTreeMap;
 
 #[derive(PartialEq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum TransformIn {
@@ -25,6 +26,60 @@ pub enum TransformIn {
 	Viewport,
 }
 
+/// A feature of SVG files without an editable equivalent in the document, so elements which use it can't be imported as editable layers.
+#[derive(PartialEq, Eq, PartialOrd, Ord, Clone, Copy, Debug)]
+pub enum UnsupportedSvgFeature {
+	Filter,
+	Mask,
+	ClipPath,
+	Image,
+	Pattern,
+	GradientStroke,
+}
+
+impl UnsupportedSvgFeature {
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::Filter => "Filters",
+			Self::Mask => "Masks",
+			Self::ClipPath => "Clipping paths",
+			Self::Image => "Embedded images",
+			Self::Pattern => "Pattern paints",
+			Self::GradientStroke => "Gradient strokes",
+		}
+	}
+}
+
+/// What happened to the parts of an imported SVG which couldn't become editable layers, which is reported to the user once the import finishes.
+#[derive(PartialEq, Clone, Debug, Default)]
+pub struct SvgImportReport {
+	/// How many elements were rasterized into image layers because of each feature, which keeps their look but not their editability.
+	pub rasterized: BTreeMap<UnsupportedSvgFeature, usize>,
+	/// How many elements were left out because of each feature, since they couldn't be rasterized either.
+	pub skipped: BTreeMap<UnsupportedSvgFeature, usize>,
+	/// How many text elements became text layers in the default font, which may look different from the font and layout they had in the SVG.
+	pub default_font_text: usize,
+}
+
+impl SvgImportReport {
+	pub fn is_empty(&self) -> bool {
+		self.rasterized.is_empty() && self.skipped.is_empty() && self.default_font_text == 0
+	}
+
+	/// One line for each way the import differs from the SVG, to be listed for the user.
+	pub fn descriptions(&self) -> Vec<String> {
+		let elements = |count: usize| if count == 1 { "1 element".to_string() } else { format!("{count} elements") };
+
+		let rasterized = self
+			.rasterized
+			.iter()
+			.map(|(feature, &count)| format!("{}: {} rasterized into images", feature.label(), elements(count)));
+		let skipped = self.skipped.iter().map(|(feature, &count)| format!("{}: {} left out", feature.label(), elements(count)));
+		let text = (self.default_font_text > 0).then(|| format!("Text: {} imported in the default font", elements(self.default_font_text)));
+		rasterized.chain(skipped).chain(text).collect()
+	}
+}
+
 // This struct is helpful to prevent passing the same arguments to multiple functions
 // Should only be used by GraphOperationMessage, since it only affects the document network.
 pub struct ModifyInputsContext<'a> {
@@ -184,7 +239,7 @@ impl<'a> ModifyInputsContext<'a> {
 		}
 	}
 
-	pub fn insert_text(&mut self, text: String, font: Font, typesetting: TypesettingConfig, layer: LayerNodeIdentifier) {
+	pub fn insert_text(&mut self, text: String, font: Font, typesetting: TypesettingConfig, path: Option<NodeInput>, layer: LayerNodeIdentifier) {
 		let stroke = resolve_document_node_type("Stroke").expect("Stroke node does not exist").default_node_template();
 		let fill = resolve_document_node_type("Fill").expect("Fill node does not exist").default_node_template();
 		let transform = resolve_document_node_type("Transform").expect("Transform node does not exist").default_node_template();
@@ -199,11 +254,21 @@ impl<'a> ModifyInputsContext<'a> {
 			Some(NodeInput::value(TaggedValue::OptionalF64(typesetting.max_height), false)),
 			Some(NodeInput::value(TaggedValue::F64(typesetting.tilt), false)),
 			Some(NodeInput::value(TaggedValue::TextAlign(typesetting.align), false)),
+			None,
+			None,
+			None,
+			None,
+			None,
+			Some(NodeInput::value(TaggedValue::F64(typesetting.word_spacing), false)),
+			Some(NodeInput::value(TaggedValue::OptionalF64(typesetting.absolute_line_height), false)),
 		]);
 
 		let text_id = NodeId::new();
 		self.network_interface.insert_node(text_id, text, &[]);
 		self.network_interface.move_node_to_chain_start(&text_id, layer, &[]);
+		if let Some(path) = path {
+			self.network_interface.set_input(&InputConnector::node(text_id, graphene_std::text::text::PathInput::INDEX), path, &[]);
+		}
 
 		let transform_id = NodeId::new();
 		self.network_interface.insert_node(transform_id, transform, &[]);
@@ -337,11 +402,93 @@ impl<'a> ModifyInputsContext<'a> {
 				let input_connector = InputConnector::node(fill_node_id, backup_gradient_index);
 				self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Gradient(gradient.clone()), false), true);
 			}
+			Fill::Pattern(pattern) => {
+				let input_connector = InputConnector::node(fill_node_id, graphene_std::vector::fill::BackupPatternInput::INDEX);
+				self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Pattern(pattern.clone()), false), true);
+			}
 		}
 		let input_connector = InputConnector::node(fill_node_id, fill_index);
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Fill(fill), false), false);
 	}
 
+	/// Fills the layer with a pattern repeating the content of another layer.
+	/// The content is wired into the Fill node's pattern tile, so editing the tile layer updates the pattern.
+	pub fn fill_pattern_set(&mut self, tile_layer: LayerNodeIdentifier) {
+		use graphene_std::vector::fill::{BackupPatternInput, FillInput, PatternTileInput};
+
+		let Some(fill_node_id) = self.existing_node_id("Fill", true) else { return };
+
+		// The tile layer's own output also includes the layers stacked below it, so the wire is taken from the content feeding into the layer instead
+		let Some(content) = self.network_interface.upstream_output_connector(&InputConnector::node(tile_layer.to_node(), 1), &[]) else {
+			log::error!("The tile layer has no content to repeat in ModifyInputsContext::fill_pattern_set");
+			return;
+		};
+		let Some(content_node_id) = content.node_id() else { return };
+
+		// A layer can't repeat itself or a group containing it, since its fill would then be upstream of its own tile
+		let mut tile_upstream = self
+			.network_interface
+			.upstream_flow_back_from_nodes(vec![content_node_id], &[], network_interface::FlowType::UpstreamFlow);
+		if tile_upstream.any(|node_id| node_id == fill_node_id) {
+			log::error!("A layer can't be filled with a pattern of itself in ModifyInputsContext::fill_pattern_set");
+			return;
+		}
+
+		// The content may be vector or raster data, so it's converted to the group that the Fill node expects
+		let to_group = resolve_document_node_type("To Group").expect("To Group node does not exist").default_node_template();
+		let to_group_id = NodeId::new();
+		self.network_interface.insert_node(to_group_id, to_group, &[]);
+		self.network_interface
+			.set_input(&InputConnector::node(to_group_id, 0), NodeInput::node(content_node_id, content.index()), &[]);
+		if let Some(fill_position) = self.network_interface.position(&fill_node_id, &[]) {
+			self.network_interface.shift_node(&to_group_id, fill_position + IVec2::new(-7, 3), &[]);
+		}
+		self.set_input_with_refresh(InputConnector::node(fill_node_id, PatternTileInput::INDEX), NodeInput::node(to_group_id, 0), true);
+
+		// Keep the tile placement from a pattern this layer previously had
+		let backup_pattern = self.network_interface.document_node(&fill_node_id, &[]).and_then(|node| node.inputs.get(BackupPatternInput::INDEX));
+		let pattern = match backup_pattern.and_then(NodeInput::as_value) {
+			Some(TaggedValue::Pattern(pattern)) => pattern.clone(),
+			_ => Pattern::default(),
+		};
+		let input_connector = InputConnector::node(fill_node_id, FillInput::<Fill>::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::Fill(Fill::Pattern(pattern)), false), false);
+	}
+
+	/// Rounds the given anchors, in addition to any chosen before, with the Round Corners node's selected radius.
+	/// If the layer has no Round Corners node yet, one is added which leaves every other anchor sharp.
+	pub fn round_corners_set(&mut self, anchors: Vec<PointId>) {
+		use graphene_std::vector::round_corners::{RadiusInput, SelectedPointsInput};
+
+		let round_corners_node_id = match self.existing_node_id("Round Corners", false) {
+			Some(node_id) => node_id,
+			None => {
+				let Some(node_id) = self.create_node("Round Corners") else { return };
+				let input_connector = InputConnector::node(node_id, RadiusInput::INDEX);
+				self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::F64(0.), false), true);
+				node_id
+			}
+		};
+
+		let input_connector = InputConnector::node(round_corners_node_id, SelectedPointsInput::INDEX);
+		let mut selected_points = match self.network_interface.input_from_connector(&input_connector, &[]).and_then(|input| input.as_non_exposed_value()) {
+			Some(TaggedValue::PointIds(point_ids)) => point_ids.clone(),
+			_ => Vec::new(),
+		};
+		for anchor in anchors {
+			if !selected_points.contains(&anchor) {
+				selected_points.push(anchor);
+			}
+		}
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::PointIds(selected_points), false), false);
+	}
+
+	pub fn fill_rule_set(&mut self, fill_rule: FillRule) {
+		let Some(fill_node_id) = self.existing_node_id("Fill", true) else { return };
+		let input_connector = InputConnector::node(fill_node_id, graphene_std::vector::fill::FillRuleInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::FillRule(fill_rule), false), false);
+	}
+
 	pub fn blend_mode_set(&mut self, blend_mode: BlendMode) {
 		let Some(blend_node_id) = self.existing_node_id("Blending", true) else { return };
 		let input_connector = InputConnector::node(blend_node_id, 1);
@@ -388,6 +535,12 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::VecF64(stroke.dash_lengths), false), true);
 		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::DashOffsetInput::INDEX);
 		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::F64(stroke.dash_offset), false), true);
+		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::StartMarkerInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::StrokeMarker(stroke.start_marker), false), false);
+		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::EndMarkerInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::StrokeMarker(stroke.end_marker), false), false);
+		let input_connector = InputConnector::node(stroke_node_id, graphene_std::vector::stroke::MarkerSizeInput::INDEX);
+		self.set_input_with_refresh(input_connector, NodeInput::value(TaggedValue::F64(stroke.marker_size), false), false);
 	}
 
 	/// Update the transform value of the upstream Transform node based a change to its existing value and the given parent transform.
@@ -471,6 +624,55 @@ impl<'a> ModifyInputsContext<'a> {
 		self.set_input_with_refresh(InputConnector::node(brush_node_id, 1), NodeInput::value(TaggedValue::BrushStrokes(strokes), false), false);
 	}
 
+	/// Connects the Clone Source input of the layer's Brush node to the merged layers below the layer, or disconnects it from them while leaving any other source in place.
+	///
+	/// Transform nodes between the Brush node and the layer place the strokes differently from the layers below, whose merged image isn't transformed to match.
+	/// So the Brush node of such a layer is disconnected from them instead, and its clone strokes copy from the image being painted.
+	pub fn brush_clone_source(&mut self, sample_merged: bool) {
+		let Some(layer) = self.layer_node else { return };
+		let Some(brush_node_id) = self.existing_node_id("Brush", false) else { return };
+		let sample_merged = sample_merged && self.transform_between(layer, brush_node_id) == DAffine2::IDENTITY;
+
+		// Artboards below a layer at the root of the document can't be sampled, since they aren't a graphic group
+		let layers_below = self
+			.network_interface
+			.upstream_output_connector(&InputConnector::node(layer.to_node(), 0), &[])
+			.filter(|output| output.node_id().is_some_and(|node_id| !self.network_interface.is_artboard(&node_id, &[])));
+		let clone_source = InputConnector::node(brush_node_id, graphene_std::brush::brush::brush::CloneSourceInput::INDEX);
+		let current_source = self.network_interface.upstream_output_connector(&clone_source, &[]);
+
+		if sample_merged {
+			let Some(OutputConnector::Node { node_id, output_index }) = layers_below else { return };
+			if current_source != layers_below {
+				self.set_input_with_refresh(clone_source, NodeInput::node(node_id, output_index), false);
+			}
+		} else if current_source.is_some() && current_source == layers_below {
+			self.set_input_with_refresh(clone_source, NodeInput::value(TaggedValue::GraphicGroup(GraphicGroupTable::default()), true), false);
+		}
+	}
+
+	/// The combined transform of the visible Transform nodes between the node and the layer, which places the node's output within the layer.
+	fn transform_between(&self, layer: LayerNodeIdentifier, node_id: NodeId) -> DAffine2 {
+		let upstream = self
+			.network_interface
+			.upstream_flow_back_from_nodes(vec![layer.to_node()], &[], network_interface::FlowType::HorizontalFlow);
+
+		let mut transform = DAffine2::IDENTITY;
+		for upstream_node in upstream.take_while(|&upstream_node| upstream_node != node_id) {
+			let is_transform = self
+				.network_interface
+				.reference(&upstream_node, &[])
+				.is_some_and(|reference| *reference == Some("Transform".to_string()));
+			if !is_transform || !self.network_interface.is_visible(&upstream_node, &[]) {
+				continue;
+			}
+			if let Some(node) = self.network_interface.document_network().nodes.get(&upstream_node) {
+				transform = transform * transform_utils::get_current_transform(&node.inputs);
+			}
+		}
+		transform
+	}
+
 	pub fn resize_artboard(&mut self, location: IVec2, dimensions: IVec2) {
 		let Some(artboard_node_id) = self.existing_node_id("Artboard", true) else {
 			return;
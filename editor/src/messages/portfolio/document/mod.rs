@@ -11,4 +11,4 @@ pub mod utility_types;
 #[doc(inline)]
 pub use document_message::{DocumentMessage, DocumentMessageDiscriminant};
 #[doc(inline)]
-pub use document_message_handler::{DocumentMessageContext, DocumentMessageHandler};
+pub use document_message_handler::{CLICK_THROUGH_LOCKS_KEY, DocumentMessageContext, DocumentMessageHandler};
@@ -5,8 +5,8 @@ use super::node_properties::{self, ParameterWidgetsInfo};
 use super::utility_types::FrontendNodeType;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::network_interface::{
-	DocumentNodeMetadata, DocumentNodePersistentMetadata, InputMetadata, NodeNetworkInterface, NodeNetworkMetadata, NodeNetworkPersistentMetadata, NodeTemplate, NodeTypePersistentMetadata,
-	NumberInputSettings, Vec2InputSettings, WidgetOverride,
+	DocumentNodeMetadata, DocumentNodePersistentMetadata, InputConnector, InputMetadata, NodeNetworkInterface, NodeNetworkMetadata, NodeNetworkPersistentMetadata, NodeTemplate,
+	NodeTypePersistentMetadata, NumberInputSettings, Vec2InputSettings, WidgetOverride,
 };
 use crate::messages::portfolio::utility_types::PersistentData;
 use crate::messages::prelude::Message;
@@ -18,9 +18,10 @@ use graph_craft::document::value::*;
 use graph_craft::document::*;
 use graphene_std::brush::brush_cache::BrushCache;
 use graphene_std::extract_xy::XY;
+use graphene_std::raster::curve::CurveChannel;
 use graphene_std::raster::{CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, NoiseType, RedGreenBlueAlpha};
 use graphene_std::raster_types::{CPU, RasterDataTable};
-use graphene_std::text::{Font, TypesettingConfig};
+use graphene_std::text::{Font, TextPathConfig, TypesettingConfig};
 #[allow(unused_imports)]
 use graphene_std::transform::Footprint;
 use graphene_std::vector::VectorDataTable;
@@ -34,6 +35,10 @@ pub struct NodePropertiesContext<'a> {
 	pub network_interface: &'a mut NodeNetworkInterface,
 	pub selection_network_path: &'a [NodeId],
 	pub document_name: &'a str,
+	/// The gradient stops selected in the stops list of each node input, keyed by the node and input index.
+	pub selected_gradient_stops: &'a HashMap<(NodeId, usize), Vec<usize>>,
+	/// The channel shown in the curve editor of each node input, keyed by the node and input index.
+	pub selected_curve_channels: &'a HashMap<(NodeId, usize), CurveChannel>,
 }
 
 impl NodePropertiesContext<'_> {
@@ -254,12 +259,19 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 								manual_composition: Some(generic!(T)),
 								inputs: vec![
 									NodeInput::node(NodeId(1), 0),
-									NodeInput::node(NodeId(2), 0),
+									NodeInput::node(NodeId(4), 0),
 									NodeInput::Reflection(graph_craft::document::DocumentNodeMetadata::DocumentNodePath),
 								],
 								implementation: DocumentNodeImplementation::ProtoNode(graphic_element::layer::IDENTIFIER),
 								..Default::default()
 							},
+							// The cull node skips evaluating the layer while it's outside of the viewport
+							DocumentNode {
+								inputs: vec![NodeInput::node(NodeId(2), 0)],
+								implementation: DocumentNodeImplementation::ProtoNode(culling::cull::IDENTIFIER),
+								manual_composition: Some(concrete!(Context)),
+								..Default::default()
+							},
 						]
 						.into_iter()
 						.enumerate()
@@ -312,6 +324,14 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 									},
 									..Default::default()
 								},
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Cull".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(-3, -1)),
+										..Default::default()
+									},
+									..Default::default()
+								},
 							]
 							.into_iter()
 							.enumerate()
@@ -708,6 +728,10 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 						NodeInput::value(TaggedValue::CellularDistanceFunction(CellularDistanceFunction::default()), false),
 						NodeInput::value(TaggedValue::CellularReturnType(CellularReturnType::default()), false),
 						NodeInput::value(TaggedValue::F64(1.), false),
+						NodeInput::value(TaggedValue::Bool(false), false),
+						NodeInput::value(TaggedValue::DVec2(DVec2::new(100., 100.)), false),
+						NodeInput::value(TaggedValue::Bool(false), false),
+						NodeInput::value(TaggedValue::F64(0.), false),
 					],
 					..Default::default()
 				},
@@ -729,6 +753,20 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 						InputMetadata::with_name_description_override("Cellular Distance Function", "TODO", WidgetOverride::Custom("noise_properties_cellular_distance_function".to_string())),
 						InputMetadata::with_name_description_override("Cellular Return Type", "TODO", WidgetOverride::Custom("noise_properties_cellular_return_type".to_string())),
 						InputMetadata::with_name_description_override("Cellular Jitter", "TODO", WidgetOverride::Custom("noise_properties_cellular_jitter".to_string())),
+						("Tileable", "Make the noise repeat seamlessly across tiles of the given size.").into(),
+						InputMetadata::with_name_description_override(
+							"Tile Size",
+							"The size of the tile that tileable noise repeats across.",
+							WidgetOverride::Vec2(Vec2InputSettings {
+								x: "W".to_string(),
+								y: "H".to_string(),
+								unit: " px".to_string(),
+								min: Some(1.),
+								..Default::default()
+							}),
+						),
+						("Sample 3D", "Sample a slice through 3D noise, so animating the Z Slice makes the noise evolve smoothly in place.").into(),
+						("Z Slice", "The depth of the slice through 3D noise, in the same units as the scale.").into(),
 					],
 					output_names: vec!["Image".to_string()],
 					..Default::default()
@@ -797,7 +835,7 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 					..Default::default()
 				},
 				persistent_node_metadata: DocumentNodePersistentMetadata {
-					input_metadata: vec![("Image", "TODO").into()],
+					input_metadata: vec![("Image", "The image whose channels are split apart.").into()],
 					output_names: vec!["Red".to_string(), "Green".to_string(), "Blue".to_string(), "Alpha".to_string()],
 					has_primary_output: false,
 					network_metadata: Some(NodeNetworkMetadata {
@@ -847,7 +885,7 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 					..Default::default()
 				},
 			},
-			description: Cow::Borrowed("TODO"),
+			description: Cow::Borrowed("Splits an image into grayscale images of its red, green, blue, and alpha channels, which the Combine Channels node can reassemble."),
 			properties: None,
 		},
 		DocumentNodeDefinition {
@@ -928,17 +966,32 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 			node_template: NodeTemplate {
 				document_node: DocumentNode {
 					implementation: DocumentNodeImplementation::Network(NodeNetwork {
-						exports: vec![NodeInput::node(NodeId(0), 0)],
-						nodes: vec![DocumentNode {
-							inputs: vec![
-								NodeInput::network(concrete!(RasterDataTable<CPU>), 0),
-								NodeInput::network(concrete!(Vec<brush::brush_stroke::BrushStroke>), 1),
-								NodeInput::network(concrete!(BrushCache), 2),
-							],
-							manual_composition: Some(concrete!(Context)),
-							implementation: DocumentNodeImplementation::ProtoNode(brush::brush::brush::IDENTIFIER),
-							..Default::default()
-						}]
+						exports: vec![NodeInput::node(NodeId(2), 0)],
+						nodes: [
+							DocumentNode {
+								inputs: vec![NodeInput::network(concrete!(GraphicGroupTable), 3)],
+								manual_composition: Some(concrete!(Context)),
+								implementation: DocumentNodeImplementation::ProtoNode(clone_source::rasterize_clone_source::IDENTIFIER),
+								..Default::default()
+							},
+							DocumentNode {
+								inputs: vec![NodeInput::node(NodeId(0), 0)],
+								manual_composition: Some(concrete!(Context)),
+								implementation: DocumentNodeImplementation::ProtoNode(memo::memo::IDENTIFIER),
+								..Default::default()
+							},
+							DocumentNode {
+								inputs: vec![
+									NodeInput::network(concrete!(RasterDataTable<CPU>), 0),
+									NodeInput::network(concrete!(Vec<brush::brush_stroke::BrushStroke>), 1),
+									NodeInput::network(concrete!(BrushCache), 2),
+									NodeInput::node(NodeId(1), 0),
+								],
+								manual_composition: Some(concrete!(Context)),
+								implementation: DocumentNodeImplementation::ProtoNode(brush::brush::brush::IDENTIFIER),
+								..Default::default()
+							},
+						]
 						.into_iter()
 						.enumerate()
 						.map(|(id, node)| (NodeId(id as u64), node))
@@ -949,22 +1002,50 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 						NodeInput::value(TaggedValue::RasterData(RasterDataTable::default()), true),
 						NodeInput::value(TaggedValue::BrushStrokes(Vec::new()), false),
 						NodeInput::value(TaggedValue::BrushCache(BrushCache::default()), false),
+						NodeInput::value(TaggedValue::GraphicGroup(GraphicGroupTable::default()), true),
 					],
 					..Default::default()
 				},
 				persistent_node_metadata: DocumentNodePersistentMetadata {
-					input_metadata: vec![("Background", "TODO").into(), ("Trace", "TODO").into(), ("Cache", "TODO").into()],
+					input_metadata: vec![
+						("Background", "TODO").into(),
+						("Trace", "TODO").into(),
+						("Cache", "TODO").into(),
+						(
+							"Clone Source",
+							"The graphic which clone strokes copy pixels from, such as the merged layers below. When left empty, they copy from the image being painted.",
+						)
+							.into(),
+					],
 					output_names: vec!["Image".to_string()],
 					network_metadata: Some(NodeNetworkMetadata {
 						persistent_metadata: NodeNetworkPersistentMetadata {
-							node_metadata: [DocumentNodeMetadata {
-								persistent_metadata: DocumentNodePersistentMetadata {
-									display_name: "Brush".to_string(),
-									node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(0, 0)),
+							node_metadata: [
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Rasterize Clone Source".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(0, 2)),
+										..Default::default()
+									},
 									..Default::default()
 								},
-								..Default::default()
-							}]
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Memoize".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(7, 2)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Brush".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(14, 0)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+							]
 							.into_iter()
 							.enumerate()
 							.map(|(id, node)| (NodeId(id as u64), node))
@@ -979,6 +1060,73 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 			description: Cow::Borrowed("TODO"),
 			properties: None,
 		},
+		DocumentNodeDefinition {
+			identifier: "Render Visible Tiles",
+			category: "Raster",
+			node_template: NodeTemplate {
+				document_node: DocumentNode {
+					implementation: DocumentNodeImplementation::Network(NodeNetwork {
+						exports: vec![NodeInput::node(NodeId(1), 0)],
+						nodes: [
+							DocumentNode {
+								inputs: vec![NodeInput::network(concrete!(RasterDataTable<CPU>), 0)],
+								implementation: DocumentNodeImplementation::ProtoNode(memo::memo::IDENTIFIER),
+								manual_composition: Some(concrete!(Context)),
+								..Default::default()
+							},
+							DocumentNode {
+								inputs: vec![NodeInput::node(NodeId(0), 0)],
+								implementation: DocumentNodeImplementation::ProtoNode(raster_nodes::tiles::render_visible_tiles::IDENTIFIER),
+								manual_composition: Some(concrete!(Context)),
+								..Default::default()
+							},
+						]
+						.into_iter()
+						.enumerate()
+						.map(|(id, node)| (NodeId(id as u64), node))
+						.collect(),
+						..Default::default()
+					}),
+					inputs: vec![NodeInput::value(TaggedValue::RasterData(RasterDataTable::default()), true)],
+					..Default::default()
+				},
+				persistent_node_metadata: DocumentNodePersistentMetadata {
+					input_metadata: vec![("Image", "The images to render, which are evaluated separately for each tile of the viewport.").into()],
+					output_names: vec!["Image".to_string()],
+					network_metadata: Some(NodeNetworkMetadata {
+						persistent_metadata: NodeNetworkPersistentMetadata {
+							node_metadata: [
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Memoize".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(0, 0)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+								DocumentNodeMetadata {
+									persistent_metadata: DocumentNodePersistentMetadata {
+										display_name: "Render Visible Tiles".to_string(),
+										node_type_metadata: NodeTypePersistentMetadata::node(IVec2::new(7, 0)),
+										..Default::default()
+									},
+									..Default::default()
+								},
+							]
+							.into_iter()
+							.enumerate()
+							.map(|(id, node)| (NodeId(id as u64), node))
+							.collect(),
+							..Default::default()
+						},
+						..Default::default()
+					}),
+					..Default::default()
+				},
+			},
+			description: Cow::Borrowed("Renders the images one tile of the viewport at a time and caches each tile, so panning only renders the newly visible tiles of expensive raster effects."),
+			properties: None,
+		},
 		DocumentNodeDefinition {
 			identifier: "Memoize",
 			category: "Debug",
@@ -1181,34 +1329,6 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 			description: Cow::Borrowed("TODO"),
 			properties: None,
 		},
-		// Aims for interoperable compatibility with:
-		// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=levl%27%20%3D%20Levels-,%27curv%27%20%3D%20Curves,-%27expA%27%20%3D%20Exposure
-		// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=Max%20input%20range-,Curves,-Curves%20settings%20files
-		//
-		// Some further analysis available at:
-		// https://geraldbakker.nl/psnumbers/curves.html
-		// TODO: Fix this, it's currently broken
-		// DocumentNodeDefinition {
-		// 	identifier: "Curves",
-		// 	category: "Raster: Adjustment",
-		// 	node_template: NodeTemplate {
-		// 		document_node: DocumentNode {
-		// 			implementation: DocumentNodeImplementation::proto("graphene_core::raster::CurvesNode"),
-		// 			inputs: vec![
-		// 				NodeInput::value(TaggedValue::RasterData(RasterDataTable::default()), true),
-		// 				NodeInput::value(TaggedValue::Curve(Default::default()), false),
-		// 			],
-		// 			..Default::default()
-		// 		},
-		// 		persistent_node_metadata: DocumentNodePersistentMetadata {
-		// 			input_properties: vec![("Image", "TODO").into(), ("Curve", "TODO").into()],
-		// 			output_names: vec!["Image".to_string()],
-		// 			..Default::default()
-		// 		},
-		// 	},
-		// 	description: Cow::Borrowed("TODO"),
-		// 	properties: None,
-		// },
 		DocumentNodeDefinition {
 			identifier: "Path",
 			category: "Vector",
@@ -1306,6 +1426,13 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 						NodeInput::value(TaggedValue::F64(TypesettingConfig::default().tilt), false),
 						NodeInput::value(TaggedValue::TextAlign(text::TextAlign::default()), false),
 						NodeInput::value(TaggedValue::Bool(false), false),
+						NodeInput::value(TaggedValue::VectorData(VectorDataTable::default()), true),
+						NodeInput::value(TaggedValue::F64(TextPathConfig::default().start_offset), false),
+						NodeInput::value(TaggedValue::TextPathSide(TextPathConfig::default().side), false),
+						NodeInput::value(TaggedValue::TextPathOverflow(TextPathConfig::default().overflow), false),
+						NodeInput::value(TaggedValue::F64(TypesettingConfig::default().word_spacing), false),
+						NodeInput::value(TaggedValue::OptionalF64(TypesettingConfig::default().absolute_line_height), false),
+						NodeInput::value(TaggedValue::Bool(TypesettingConfig::default().justify_letters), false),
 					],
 					..Default::default()
 				},
@@ -1374,6 +1501,42 @@ fn static_nodes() -> Vec<DocumentNodeDefinition> {
 						),
 						InputMetadata::with_name_description_override("Align", "TODO", WidgetOverride::Custom("text_align".to_string())),
 						("Per-Glyph Instances", "Splits each text glyph into its own instance, i.e. row in the table of vector data.").into(),
+						("Path", "A path for the text to flow along. When it's empty, the text is laid out on straight lines.").into(),
+						InputMetadata::with_name_description_override(
+							"Start Offset",
+							"The distance along the path where the text begins.",
+							WidgetOverride::Number(NumberInputSettings {
+								unit: Some(" px".to_string()),
+								..Default::default()
+							}),
+						),
+						("Side", "Which side of the path the text sits on, relative to the direction the path is drawn in.").into(),
+						("Overflow", "Whether text that runs past the ends of the path is hidden or continues in a straight line.").into(),
+						InputMetadata::with_name_description_override(
+							"Word Spacing",
+							"Extra space added after each space character, on top of the character spacing.",
+							WidgetOverride::Number(NumberInputSettings {
+								unit: Some(" px".to_string()),
+								step: Some(0.1),
+								..Default::default()
+							}),
+						),
+						InputMetadata::with_name_description_override(
+							"Absolute Line Height",
+							"A fixed distance between the baselines of consecutive lines, which replaces the line height ratio when set.",
+							WidgetOverride::Number(NumberInputSettings {
+								unit: Some(" px".to_string()),
+								min: Some(0.),
+								step: Some(0.1),
+								blank_assist: false,
+								..Default::default()
+							}),
+						),
+						(
+							"Justify Letters",
+							"Spreads the extra space of justified lines between every pair of glyphs, rather than only the gaps between words.",
+						)
+							.into(),
 					],
 					output_names: vec!["Vector".to_string()],
 					..Default::default()
@@ -1887,9 +2050,12 @@ pub static NODE_OVERRIDES: once_cell::sync::Lazy<NodeProperties> = once_cell::sy
 /// Defines the logic for inputs to display a custom properties panel widget.
 fn static_node_properties() -> NodeProperties {
 	let mut map: NodeProperties = HashMap::new();
+	map.insert("blending_properties".to_string(), Box::new(node_properties::blending_properties));
+	map.insert("blur_properties".to_string(), Box::new(node_properties::blur_properties));
 	map.insert("brightness_contrast_properties".to_string(), Box::new(node_properties::brightness_contrast_properties));
 	map.insert("channel_mixer_properties".to_string(), Box::new(node_properties::channel_mixer_properties));
 	map.insert("fill_properties".to_string(), Box::new(node_properties::fill_properties));
+	map.insert("levels_properties".to_string(), Box::new(node_properties::levels_properties));
 	map.insert("stroke_properties".to_string(), Box::new(node_properties::stroke_properties));
 	map.insert("offset_path_properties".to_string(), Box::new(node_properties::offset_path_properties));
 	map.insert("selective_color_properties".to_string(), Box::new(node_properties::selective_color_properties));
@@ -2388,11 +2554,21 @@ fn static_input_properties() -> InputProperties {
 	map.insert(
 		"text_font".to_string(),
 		Box::new(|node_id, index, context| {
-			let (font, style) = node_properties::font_inputs(ParameterWidgetsInfo::new(node_id, index, true, context));
-			let mut result = vec![LayoutGroup::Row { widgets: font }];
+			let input = context.network_interface.input_from_connector(&InputConnector::node(node_id, index), context.selection_network_path);
+			let font = match input.and_then(NodeInput::as_non_exposed_value) {
+				Some(TaggedValue::Font(font)) => Some(font.clone()),
+				_ => None,
+			};
+			let variation_info = font.as_ref().and_then(|font| context.persistent_data.font_cache.variation_info(font));
+
+			let (font_widgets, style) = node_properties::font_inputs(ParameterWidgetsInfo::new(node_id, index, true, context));
+			let mut result = vec![LayoutGroup::Row { widgets: font_widgets }];
 			if let Some(style) = style {
 				result.push(LayoutGroup::Row { widgets: style });
 			}
+			if let (Some(font), Some(variation_info)) = (font, variation_info) {
+				result.extend(node_properties::font_variation_widgets(&font, &variation_info, node_id, index));
+			}
 			Ok(result)
 		}),
 	);
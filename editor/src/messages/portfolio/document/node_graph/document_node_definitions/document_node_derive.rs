@@ -1,5 +1,5 @@
 use super::DocumentNodeDefinition;
-use crate::messages::portfolio::document::utility_types::network_interface::{DocumentNodePersistentMetadata, InputMetadata, NodeTemplate, WidgetOverride};
+use crate::messages::portfolio::document::utility_types::network_interface::{DocumentNodePersistentMetadata, InputHints, InputMetadata, InputUnit, NodeTemplate, WidgetHint, WidgetOverride};
 use graph_craft::ProtoNodeIdentifier;
 use graph_craft::document::*;
 use graphene_std::registry::*;
@@ -69,11 +69,17 @@ pub(super) fn post_process_nodes(mut custom: Vec<DocumentNodeDefinition>) -> Vec
 					// TODO: Store information for input overrides in the node macro
 					input_metadata: fields
 						.iter()
-						.map(|f| match f.widget_override {
-							RegistryWidgetOverride::None => (f.name, f.description).into(),
-							RegistryWidgetOverride::Hidden => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::Hidden),
-							RegistryWidgetOverride::String(str) => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::String(str.to_string())),
-							RegistryWidgetOverride::Custom(str) => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::Custom(str.to_string())),
+						.map(|f| {
+							let mut input_metadata: InputMetadata = match f.widget_override {
+								RegistryWidgetOverride::None => (f.name, f.description).into(),
+								RegistryWidgetOverride::Hidden => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::Hidden),
+								RegistryWidgetOverride::String(str) => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::String(str.to_string())),
+								RegistryWidgetOverride::Custom(str) => InputMetadata::with_name_description_override(f.name, f.description, WidgetOverride::Custom(str.to_string())),
+							};
+							if let Some(hints) = input_hints(display_name, f.name) {
+								input_metadata.persistent_metadata.hints = hints;
+							}
+							input_metadata
 						})
 						.collect(),
 					output_names: vec![output_type.to_string()],
@@ -92,3 +98,33 @@ pub(super) fn post_process_nodes(mut custom: Vec<DocumentNodeDefinition>) -> Vec
 
 	custom
 }
+
+/// Describes the values expected by the inputs of nodes defined with the node macro, keyed by the names of the node and input.
+// TODO: Move these into the node macro's attributes once it can describe widget hints
+fn input_hints(node_name: &str, input_name: &str) -> Option<InputHints> {
+	let hints = InputHints::default();
+	Some(match (node_name, input_name) {
+		("Arc", "Radius") => hints.min(0.).unit(InputUnit::Pixels).tooltip("The distance from the center of the arc to its curve."),
+		("Arc", "Start Angle") => hints.widget(WidgetHint::Angle).tooltip("The angle, clockwise from the right, at which the arc begins."),
+		("Arc", "Sweep Angle") => hints
+			.min(0.)
+			.max(360.)
+			.soft_range(0., 360.)
+			.widget(WidgetHint::Angle)
+			.tooltip("How far the arc turns from its start angle. A full turn draws a circle."),
+		("Hue/Saturation", "Hue Shift") => hints
+			.min(-180.)
+			.max(180.)
+			.widget(WidgetHint::Angle)
+			.tooltip("How far to rotate each color's hue around the color wheel."),
+		("Hue/Saturation", "Saturation Shift" | "Lightness Shift") | ("Vibrance", "Vibrance" | "Saturation") => hints.min(-100.).max(100.).soft_range(-100., 100.).widget(WidgetHint::Percent),
+		("Gamma Correction", "Gamma") => hints.min(0.0001).soft_range(0.01, 10.).widget(WidgetHint::Slider),
+		("Posterize", "Levels") => hints
+			.min(2.)
+			.max(255.)
+			.step(1.)
+			.widget(WidgetHint::Slider)
+			.tooltip("The number of brightness levels kept in each color channel."),
+		_ => return None,
+	})
+}
@@ -36,6 +36,7 @@ pub enum NodeGraphMessage {
 		xy: Option<(i32, i32)>,
 		add_transaction: bool,
 	},
+	CreateCustomNodeFromSelection,
 	CreateWire {
 		output_connector: OutputConnector,
 		input_connector: InputConnector,
@@ -55,6 +56,9 @@ pub enum NodeGraphMessage {
 	DisconnectInput {
 		input_connector: InputConnector,
 	},
+	DefineCustomNode {
+		node_id: NodeId,
+	},
 	DisconnectRootNode,
 	EnterNestedNetwork,
 	DuplicateSelectedNodes,
@@ -128,6 +132,7 @@ pub enum NodeGraphMessage {
 		nodes: Vec<NodeId>,
 	},
 	SendClickTargets,
+	SendCustomNodeTypes,
 	EndSendClickTargets,
 	UnloadWires,
 	SendWires,
@@ -143,6 +148,15 @@ pub enum NodeGraphMessage {
 		input_connector: InputConnector,
 		input: NodeInput,
 	},
+	SetInputs {
+		changes: Vec<(InputConnector, NodeInput)>,
+		skip_adding_history_step: bool,
+		/// The label of the history step which the batch adds, if it adds one, instead of one describing the first change.
+		transaction_name: Option<String>,
+	},
+	SetInputsImpl {
+		changes: Vec<(InputConnector, NodeInput)>,
+	},
 	SetDisplayName {
 		node_id: NodeId,
 		alias: String,
@@ -152,6 +166,14 @@ pub enum NodeGraphMessage {
 		node_id: NodeId,
 		alias: String,
 	},
+	RenameLayer {
+		layer: LayerNodeIdentifier,
+		new_name: String,
+	},
+	RenameLayerImpl {
+		layer: LayerNodeIdentifier,
+		new_name: String,
+	},
 	SetToNodeOrLayer {
 		node_id: NodeId,
 		is_layer: bool,
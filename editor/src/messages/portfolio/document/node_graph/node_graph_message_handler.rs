@@ -1,4 +1,4 @@
-use super::utility_types::{BoxSelection, ContextMenuInformation, DragStart, FrontendGraphInput, FrontendGraphOutput, FrontendNode};
+use super::utility_types::{BoxSelection, ContextMenuInformation, DragStart, FrontendGraphInput, FrontendGraphOutput, FrontendNode, FrontendNodeType};
 use super::{document_node_definitions, node_properties};
 use crate::consts::GRID_SIZE;
 use crate::messages::input_mapper::utility_types::macros::action_keys;
@@ -10,7 +10,7 @@ use crate::messages::portfolio::document::node_graph::utility_types::{ContextMen
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 use crate::messages::portfolio::document::utility_types::network_interface::{
-	self, FlowType, InputConnector, NodeNetworkInterface, NodeTemplate, NodeTypePersistentMetadata, OutputConnector, Previewing, TypeSource,
+	self, FlowType, ImportOrExport, InputConnector, NodeNetworkInterface, NodeTemplate, NodeTypePersistentMetadata, OutputConnector, Previewing, TransactionStatus, TypeSource,
 };
 use crate::messages::portfolio::document::utility_types::nodes::{CollapsedLayers, LayerPanelEntry};
 use crate::messages::portfolio::document::utility_types::wires::{GraphWireStyle, WirePath, WirePathUpdate, build_vector_wire};
@@ -38,6 +38,7 @@ pub struct NodeGraphMessageContext<'a> {
 	pub breadcrumb_network_path: &'a [NodeId],
 	pub document_id: DocumentId,
 	pub collapsed: &'a mut CollapsedLayers,
+	pub solo_layer: Option<LayerNodeIdentifier>,
 	pub ipp: &'a InputPreprocessorMessageHandler,
 	pub graph_view_overlay_open: bool,
 	pub graph_fade_artwork_percentage: f64,
@@ -107,6 +108,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			breadcrumb_network_path,
 			document_id,
 			collapsed,
+			solo_layer,
 			ipp,
 			graph_view_overlay_open,
 			graph_fade_artwork_percentage,
@@ -236,15 +238,30 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 
 				let node_id = node_id.unwrap_or_else(NodeId::new);
 
-				let Some(document_node_type) = document_node_definitions::resolve_document_node_type(&node_type) else {
+				let node_template = if let Some(document_node_type) = document_node_definitions::resolve_document_node_type(&node_type) {
+					document_node_type.default_node_template()
+				} else if let Some(custom_node) = network_interface.custom_nodes().get_by_name(&node_type) {
+					// A custom node can't be placed inside its own network, which would then contain itself endlessly
+					let mut contained_custom_nodes = network_interface.custom_nodes().dependencies(custom_node.id);
+					contained_custom_nodes.insert(custom_node.id);
+					let placed_inside_itself = (0..selection_network_path.len())
+						.filter_map(|depth| network_interface.custom_node_id(&selection_network_path[depth], &selection_network_path[..depth]))
+						.any(|id| contained_custom_nodes.contains(&id));
+					if placed_inside_itself {
+						responses.add(DialogMessage::DisplayDialogError {
+							title: "Cannot insert node".to_string(),
+							description: format!("The custom node '{node_type}' cannot be placed inside its own network"),
+						});
+						return;
+					}
+					custom_node.node_template.clone()
+				} else {
 					responses.add(DialogMessage::DisplayDialogError {
 						title: "Cannot insert node".to_string(),
 						description: format!("The document node '{node_type}' does not exist in the document node list"),
 					});
 					return;
 				};
-
-				let node_template = document_node_type.default_node_template();
 				self.context_menu = None;
 
 				if add_transaction {
@@ -317,6 +334,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					return;
 				};
 				responses.add(DocumentMessage::AddTransaction);
+				responses.add(DocumentMessage::NameTransaction { name: "Delete".to_string() });
 				responses.add(NodeGraphMessage::DeleteNodes {
 					node_ids: selected_nodes.selected_nodes().cloned().collect::<Vec<_>>(),
 					delete_children,
@@ -325,6 +343,12 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(NodeGraphMessage::SelectedNodesUpdated);
 				responses.add(NodeGraphMessage::SendGraph);
 			}
+			NodeGraphMessage::DefineCustomNode { node_id } => {
+				let name = network_interface.custom_nodes().unique_name("Custom Node");
+				network_interface.set_display_name(&node_id, name.clone(), selection_network_path);
+				network_interface.define_custom_node(&node_id, selection_network_path, &name);
+				responses.add(NodeGraphMessage::SendCustomNodeTypes);
+			}
 			NodeGraphMessage::DisconnectInput { input_connector } => {
 				network_interface.disconnect_input(&input_connector, selection_network_path);
 			}
@@ -434,7 +458,8 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			} => {
 				network_interface.insert_node_between(&node_id, &input_connector, insert_node_input_index, selection_network_path);
 			}
-			NodeGraphMessage::MergeSelectedNodes => {
+			merge @ (NodeGraphMessage::MergeSelectedNodes | NodeGraphMessage::CreateCustomNodeFromSelection) => {
+				let define_custom_node = matches!(merge, NodeGraphMessage::CreateCustomNodeFromSelection);
 				let new_ids = network_interface
 					.selected_nodes_in_nested_network(breadcrumb_network_path)
 					.unwrap()
@@ -549,12 +574,47 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					alias: "Untitled Node".to_string(),
 				});
 
+				// The imports and exports are named after the first input or output which each one is wired to
+				let import_names = (0..input_connections.len())
+					.map(|import_index| {
+						let (input_connector, _) = import_connections.iter().find(|(_, index)| *index == import_index)?;
+						let node_id = input_connector.node_id()?;
+						let input_index = input_connector.input_index();
+						network_interface
+							.persistent_input_metadata(&node_id, input_index, breadcrumb_network_path)
+							.map(|input_metadata| input_metadata.input_name.clone())
+					})
+					.collect::<Vec<_>>();
+				let export_names = export_connections
+					.iter()
+					.map(|output_connector| {
+						let node_id = output_connector.node_id()?;
+						let output_name = network_interface
+							.node_metadata(&node_id, breadcrumb_network_path)
+							.and_then(|node_metadata| node_metadata.persistent_metadata.output_names.get(output_connector.index()).cloned())
+							.filter(|output_name| !output_name.is_empty());
+						Some(output_name.unwrap_or_else(|| network_interface.display_name(&node_id, breadcrumb_network_path)))
+					})
+					.collect::<Vec<_>>();
+
 				responses.add(DocumentMessage::EnterNestedNetwork { node_id: encapsulating_node_id });
-				for _ in 0..input_connections.len() {
+				for (import_index, name) in import_names.into_iter().enumerate() {
 					responses.add(NodeGraphMessage::AddImport);
+					if let Some(name) = name {
+						responses.add(NodeGraphMessage::SetImportExportNameImpl {
+							name,
+							index: ImportOrExport::Import(import_index),
+						});
+					}
 				}
-				for _ in 0..output_connections.len() {
+				for (export_index, name) in export_names.into_iter().enumerate() {
 					responses.add(NodeGraphMessage::AddExport);
+					if let Some(name) = name {
+						responses.add(NodeGraphMessage::SetImportExportNameImpl {
+							name,
+							index: ImportOrExport::Export(export_index),
+						});
+					}
 				}
 				responses.add(NodeGraphMessage::AddNodes { nodes: copied_nodes, new_ids });
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: selected_node_ids_vec.clone() });
@@ -597,6 +657,9 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					delete_children: false,
 				});
 				responses.add(NodeGraphMessage::SelectedNodesSet { nodes: vec![encapsulating_node_id] });
+				if define_custom_node {
+					responses.add(NodeGraphMessage::DefineCustomNode { node_id: encapsulating_node_id });
+				}
 				responses.add(NodeGraphMessage::SendGraph);
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 			}
@@ -610,7 +673,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				network_interface.set_chain_position(&node_id, selection_network_path);
 			}
 			NodeGraphMessage::PasteNodes { serialized_nodes } => {
-				let data = match serde_json::from_str::<Vec<(NodeId, NodeTemplate)>>(&serialized_nodes) {
+				let mut data = match serde_json::from_str::<Vec<(NodeId, NodeTemplate)>>(&serialized_nodes) {
 					Ok(d) => d,
 					Err(e) => {
 						warn!("Invalid node data {e:?}");
@@ -621,6 +684,13 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 					return;
 				}
 
+				// Place the pasted nodes at the cursor when pasting into the graph view
+				if graph_view_overlay_open && let Some(network_metadata) = network_interface.network_metadata(selection_network_path) {
+					let node_graph_to_viewport = network_metadata.persistent_metadata.navigation_metadata.node_graph_to_viewport;
+					let cursor = node_graph_to_viewport.inverse().transform_point2(ipp.mouse.position) / GRID_SIZE as f64;
+					move_node_templates_to(&mut data, cursor.round().as_ivec2());
+				}
+
 				responses.add(DocumentMessage::AddTransaction);
 
 				let new_ids: HashMap<_, _> = data.iter().map(|(id, _)| (*id, NodeId::new())).collect();
@@ -1504,6 +1574,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(NodeGraphMessage::RunDocumentGraph);
 			}
 			NodeGraphMessage::RunDocumentGraph => {
+				network_interface.update_custom_node_definitions(selection_network_path);
 				responses.add(PortfolioMessage::SubmitGraphRender { document_id, ignore_hash: false });
 			}
 			NodeGraphMessage::ForceRunDocumentGraph => {
@@ -1538,6 +1609,14 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				click_targets: Some(network_interface.collect_frontend_click_targets(breadcrumb_network_path)),
 			}),
 			NodeGraphMessage::EndSendClickTargets => responses.add(FrontendMessage::UpdateClickTargets { click_targets: None }),
+			NodeGraphMessage::SendCustomNodeTypes => {
+				let node_types = network_interface
+					.custom_nodes()
+					.iter()
+					.map(|custom_node| FrontendNodeType::new(custom_node.name.clone(), "Custom"))
+					.collect();
+				responses.add(FrontendMessage::UpdateCustomNodeTypes { node_types });
+			}
 			NodeGraphMessage::UnloadWires => {
 				for input in network_interface.node_graph_input_connectors(breadcrumb_network_path) {
 					network_interface.unload_wire(&input, breadcrumb_network_path);
@@ -1577,6 +1656,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				responses.add(FrontendMessage::UpdateVisibleNodes { nodes });
 			}
 			NodeGraphMessage::SendGraph => {
+				network_interface.update_custom_node_definitions(selection_network_path);
 				responses.add(NodeGraphMessage::UpdateLayerPanel);
 				responses.add(DocumentMessage::DocumentStructureChanged);
 				responses.add(PropertiesPanelMessage::Refresh);
@@ -1617,7 +1697,47 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 				}
 			}
 			NodeGraphMessage::SetInput { input_connector, input } => {
-				network_interface.set_input(&input_connector, input, selection_network_path);
+				// A single input is applied as a batch of one, but its history step and evaluation are left to the caller, such as the widget which commits the value
+				network_interface.set_inputs(vec![(input_connector, input)], selection_network_path);
+			}
+			NodeGraphMessage::SetInputs {
+				changes,
+				skip_adding_history_step,
+				transaction_name,
+			} => {
+				// Nothing is changed if any of the changes are invalid, so the graph is never left partway through the batch
+				if changes.is_empty() || !network_interface.can_set_inputs(&changes, selection_network_path) {
+					return;
+				}
+
+				// Changes made during an ongoing transaction, such as a tool's drag, become part of its history step
+				let add_history_step = !skip_adding_history_step && network_interface.transaction_status() == TransactionStatus::Finished;
+				if add_history_step {
+					responses.add(DocumentMessage::StartTransaction);
+					if let Some(name) = transaction_name {
+						responses.add(DocumentMessage::NameTransaction { name });
+					}
+				}
+				responses.add(NodeGraphMessage::SetInputsImpl { changes });
+				if add_history_step {
+					// Does not add a history step if none of the inputs were changed
+					responses.add(DocumentMessage::EndTransaction);
+				}
+			}
+			NodeGraphMessage::SetInputsImpl { changes } => {
+				let input_connectors: Vec<_> = changes.iter().map(|(input_connector, _)| *input_connector).collect();
+				if !network_interface.set_inputs(changes, selection_network_path) {
+					return;
+				}
+
+				// Evaluate the graph once for the whole batch, and only if one of the changed inputs feeds into the output
+				let connected_to_output = input_connectors.iter().any(|input_connector| match input_connector {
+					InputConnector::Node { node_id, .. } => network_interface.connected_to_output(node_id, selection_network_path),
+					InputConnector::Export(_) => true,
+				});
+				if connected_to_output {
+					responses.add(NodeGraphMessage::RunDocumentGraph);
+				}
 			}
 			NodeGraphMessage::ShiftSelectedNodes { direction, rubber_band } => {
 				network_interface.shift_selected_nodes(direction, self.shift_without_push, selection_network_path);
@@ -1728,6 +1848,24 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			NodeGraphMessage::SetDisplayNameImpl { node_id, alias } => {
 				network_interface.set_display_name(&node_id, alias, selection_network_path);
 			}
+			NodeGraphMessage::RenameLayer { layer, new_name } => {
+				if layer == LayerNodeIdentifier::ROOT_PARENT {
+					log::error!("Cannot rename the root parent");
+					return;
+				}
+
+				responses.add(DocumentMessage::StartTransaction);
+				responses.add(NodeGraphMessage::RenameLayerImpl { layer, new_name });
+				// Does not add a history step if the name was not changed
+				responses.add(DocumentMessage::EndTransaction);
+				responses.add(DocumentMessage::RenderRulers);
+				responses.add(DocumentMessage::RenderScrollbars);
+				responses.add(NodeGraphMessage::SendGraph);
+			}
+			NodeGraphMessage::RenameLayerImpl { layer, new_name } => {
+				// Layers are always in the document network, regardless of which network the node graph is showing
+				network_interface.set_display_name(&layer.to_node(), new_name, &[]);
+			}
 			NodeGraphMessage::SetImportExportName { name, index } => {
 				responses.add(DocumentMessage::StartTransaction);
 				responses.add(NodeGraphMessage::SetImportExportNameImpl { name, index });
@@ -1920,7 +2058,7 @@ impl<'a> MessageHandler<NodeGraphMessage, NodeGraphMessageContext<'a>> for NodeG
 			}
 
 			NodeGraphMessage::UpdateLayerPanel => {
-				Self::update_layer_panel(network_interface, selection_network_path, collapsed, responses);
+				Self::update_layer_panel(network_interface, selection_network_path, collapsed, solo_layer, responses);
 			}
 			NodeGraphMessage::UpdateEdges => {
 				// Update the import/export UI edges whenever the PTZ changes or the bounding box of all nodes changes
@@ -1996,6 +2134,7 @@ impl NodeGraphMessageHandler {
 				DeleteSelectedNodes,
 				DuplicateSelectedNodes,
 				MergeSelectedNodes,
+				CreateCustomNodeFromSelection,
 				ToggleSelectedAsLayersOrNodes,
 				ToggleSelectedLocked,
 				ToggleSelectedVisibility,
@@ -2632,7 +2771,13 @@ impl NodeGraphMessageHandler {
 		Some(subgraph_names)
 	}
 
-	fn update_layer_panel(network_interface: &NodeNetworkInterface, selection_network_path: &[NodeId], collapsed: &CollapsedLayers, responses: &mut VecDeque<Message>) {
+	fn update_layer_panel(
+		network_interface: &NodeNetworkInterface,
+		selection_network_path: &[NodeId],
+		collapsed: &CollapsedLayers,
+		solo_layer: Option<LayerNodeIdentifier>,
+		responses: &mut VecDeque<Message>,
+	) {
 		let selected_layers = network_interface
 			.selected_nodes()
 			.selected_layers(network_interface.document_metadata())
@@ -2709,6 +2854,8 @@ impl NodeGraphMessageHandler {
 					descendant_of_selected: descendants_of_selected.contains(&node_id),
 					clipped: get_clip_mode(layer, network_interface).unwrap_or(false) && clippable,
 					clippable,
+					solo: solo_layer == Some(layer),
+					hidden_by_solo: solo_layer.is_some_and(|solo_layer| !layer.is_rendered_in_solo(network_interface.document_metadata(), &solo_layer)),
 				};
 				responses.add(FrontendMessage::UpdateDocumentLayerDetails { data });
 			}
@@ -2823,6 +2970,23 @@ fn frontend_inputs_lookup(breadcrumb_network_path: &[NodeId], network_interface:
 	frontend_inputs_lookup
 }
 
+/// Shifts the absolutely positioned nodes so the top left corner of their bounds is at the given grid position, keeping their arrangement.
+fn move_node_templates_to(node_templates: &mut [(NodeId, NodeTemplate)], position: IVec2) {
+	let Some(top_left) = node_templates
+		.iter_mut()
+		.filter_map(|(_, node_template)| node_template.persistent_node_metadata.absolute_position_mut().map(|position| *position))
+		.reduce(IVec2::min)
+	else {
+		return;
+	};
+
+	for (_, node_template) in node_templates {
+		if let Some(node_position) = node_template.persistent_node_metadata.absolute_position_mut() {
+			*node_position += position - top_left;
+		}
+	}
+}
+
 impl Default for NodeGraphMessageHandler {
 	fn default() -> Self {
 		Self {
@@ -2873,3 +3037,179 @@ impl PartialEq for NodeGraphMessageHandler {
 			&& self.context_menu == other.context_menu
 	}
 }
+
+#[cfg(test)]
+mod test {
+	use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, OutputConnector};
+	use crate::test_utils::test_prelude::*;
+	use graph_craft::document::value::TaggedValue;
+	use graph_craft::document::{NodeId, NodeInput};
+
+	async fn create_node(editor: &mut EditorTestUtils, node_id: NodeId, node_type: &str, x: i32) {
+		let message = NodeGraphMessage::CreateNodeFromContextMenu {
+			node_id: Some(node_id),
+			node_type: node_type.to_string(),
+			xy: Some((x, 0)),
+			add_transaction: true,
+		};
+		editor.handle_message(message).await;
+	}
+
+	/// - create a Number Value node feeding into a Multiply node feeding into an Add node
+	/// - copy the Multiply and Add nodes
+	/// - paste them into another document
+	/// - assert that the wire between them is kept and the wire from the Number Value node is replaced by its value
+	#[tokio::test]
+	async fn copy_paste_nodes_between_documents() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		let (number, multiply, add) = (NodeId(1), NodeId(2), NodeId(3));
+		create_node(&mut editor, number, "Number Value", 0).await;
+		create_node(&mut editor, multiply, "Multiply", 10).await;
+		create_node(&mut editor, add, "Add", 20).await;
+		editor
+			.handle_message(NodeGraphMessage::SetInputValue {
+				node_id: number,
+				input_index: 1,
+				value: TaggedValue::F64(42.),
+			})
+			.await;
+		for (upstream, downstream) in [(number, multiply), (multiply, add)] {
+			let message = NodeGraphMessage::CreateWire {
+				output_connector: OutputConnector::node(upstream, 0),
+				input_connector: InputConnector::node(downstream, 0),
+			};
+			editor.handle_message(message).await;
+		}
+
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![multiply, add] }).await;
+		let copy_text = editor
+			.editor
+			.handle_message(NodeGraphMessage::Copy)
+			.into_iter()
+			.find_map(|message| match message {
+				FrontendMessage::TriggerTextCopy { copy_text } => Some(copy_text),
+				_ => None,
+			})
+			.expect("Copying nodes should write them to the clipboard");
+		let serialized_nodes = copy_text.strip_prefix("graphite/nodes: ").expect("Copied nodes should be prefixed").to_string();
+
+		editor.new_document().await;
+		let node_count = editor.active_document().network_interface.document_network().nodes.len();
+		editor.handle_message(NodeGraphMessage::PasteNodes { serialized_nodes }).await;
+
+		let network = editor.active_document().network_interface.document_network();
+		let pasted = |name: &str| {
+			let network_interface = &editor.active_document().network_interface;
+			let (node_id, node) = network
+				.nodes
+				.iter()
+				.find(|(node_id, _)| network_interface.reference(node_id, &[]).is_some_and(|reference| reference.as_deref() == Some(name)))
+				.expect("The node should be pasted");
+			(*node_id, node.clone())
+		};
+		let (pasted_multiply_id, pasted_multiply) = pasted("Multiply");
+		let (pasted_add_id, pasted_add) = pasted("Add");
+
+		// The pasted nodes get fresh IDs, with the wire between them pointing to the pasted Multiply node
+		assert!(![multiply, add].contains(&pasted_multiply_id) && ![multiply, add].contains(&pasted_add_id));
+		assert_eq!(pasted_add.inputs[0].as_node(), Some(pasted_multiply_id));
+
+		// The wire from the Number Value node, which wasn't copied, is replaced by the value it provided
+		assert!(matches!(pasted_multiply.inputs[0], NodeInput::Value { ref tagged_value, .. } if **tagged_value == TaggedValue::F64(42.)));
+
+		// Pasting is undone in a single step
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.document_network().nodes.len(), node_count);
+	}
+
+	/// Creates a Number Value node feeding into a Multiply node feeding into an Add node which is exported, then creates a custom node from the Multiply and Add nodes.
+	/// Returns the instance which replaced them, along with the IDs of the Number Value node and of the Add node inside the instance.
+	async fn create_custom_node(editor: &mut EditorTestUtils) -> (NodeId, NodeId, NodeId) {
+		let (number, multiply, add) = (NodeId(1), NodeId(2), NodeId(3));
+		create_node(editor, number, "Number Value", 0).await;
+		create_node(editor, multiply, "Multiply", 10).await;
+		create_node(editor, add, "Add", 20).await;
+		let wires = [
+			(OutputConnector::node(number, 0), InputConnector::node(multiply, 0)),
+			(OutputConnector::node(multiply, 0), InputConnector::node(add, 0)),
+			(OutputConnector::node(add, 0), InputConnector::Export(0)),
+		];
+		for (output_connector, input_connector) in wires {
+			editor.handle_message(NodeGraphMessage::CreateWire { output_connector, input_connector }).await;
+		}
+
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![multiply, add] }).await;
+		editor.handle_message(NodeGraphMessage::CreateCustomNodeFromSelection).await;
+
+		let network_interface = &editor.active_document().network_interface;
+		let instance = network_interface
+			.document_network()
+			.nodes
+			.keys()
+			.copied()
+			.find(|node_id| network_interface.custom_node_id(node_id, &[]).is_some())
+			.expect("The selected nodes should be replaced by an instance of the custom node");
+		(instance, number, add)
+	}
+
+	#[tokio::test]
+	async fn create_custom_node_from_selection() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		let (instance, number, _) = create_custom_node(&mut editor).await;
+
+		let network_interface = &editor.active_document().network_interface;
+		let custom_nodes = network_interface.custom_nodes().iter().collect::<Vec<_>>();
+		assert_eq!(custom_nodes.len(), 1);
+		assert_eq!(custom_nodes[0].name, "Custom Node");
+		assert_eq!(network_interface.custom_node_id(&instance, &[]), Some(custom_nodes[0].id));
+		assert_eq!(network_interface.display_name(&instance, &[]), "Custom Node");
+
+		// The instance takes the place of the selected nodes, keeping the wires to the nodes around them
+		let network = network_interface.document_network();
+		assert_eq!(network.nodes[&instance].inputs[0].as_node(), Some(number));
+		assert_eq!(network.exports[0].as_node(), Some(instance));
+		assert_eq!(network_interface.nested_network(&[instance]).unwrap().nodes.len(), 2);
+
+		// The selected nodes aren't left behind, and the instance can be undone in a single step along with the custom node
+		assert!(network.nodes.contains_key(&number) && !network.nodes.contains_key(&NodeId(2)));
+		editor.handle_message(DocumentMessage::Undo).await;
+		let network_interface = &editor.active_document().network_interface;
+		assert!(network_interface.custom_nodes().iter().next().is_none());
+		assert!(network_interface.document_network().nodes.contains_key(&NodeId(2)));
+	}
+
+	#[tokio::test]
+	async fn editing_a_custom_node_updates_its_other_instances() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		let (instance, _, add) = create_custom_node(&mut editor).await;
+
+		let other_instance = NodeId(10);
+		create_node(&mut editor, other_instance, "Custom Node", 40).await;
+		let network_interface = &editor.active_document().network_interface;
+		assert_eq!(network_interface.custom_node_id(&other_instance, &[]), network_interface.custom_node_id(&instance, &[]));
+
+		editor.handle_message(DocumentMessage::EnterNestedNetwork { node_id: instance }).await;
+		editor
+			.handle_message(NodeGraphMessage::SetInputValue {
+				node_id: add,
+				input_index: 1,
+				value: TaggedValue::F64(5.),
+			})
+			.await;
+		editor.handle_message(DocumentMessage::ExitNestedNetwork { steps_back: 1 }).await;
+
+		let network_interface = &editor.active_document().network_interface;
+		let other_network = network_interface.nested_network(&[other_instance]).unwrap();
+		assert!(matches!(other_network.nodes[&add].inputs[1], NodeInput::Value { ref tagged_value, .. } if **tagged_value == TaggedValue::F64(5.)));
+
+		// Placing a custom node inside one of its own instances is refused
+		editor.handle_message(DocumentMessage::EnterNestedNetwork { node_id: other_instance }).await;
+		create_node(&mut editor, NodeId(11), "Custom Node", 0).await;
+		let network_interface = &editor.active_document().network_interface;
+		assert!(!network_interface.nested_network(&[other_instance]).unwrap().nodes.contains_key(&NodeId(11)));
+	}
+}
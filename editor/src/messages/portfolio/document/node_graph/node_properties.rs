@@ -2,32 +2,39 @@
 
 use super::document_node_definitions::{NODE_OVERRIDES, NodePropertiesContext};
 use super::utility_types::FrontendGraphDataType;
+use crate::application::generate_uuid;
 use crate::messages::layout::utility_types::widget_prelude::*;
-use crate::messages::portfolio::document::utility_types::network_interface::InputConnector;
+use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, InputHints, InputUnit, WidgetHint};
+use crate::messages::portfolio::document::utility_types::swatches::DocumentSwatches;
 use crate::messages::prelude::*;
-use choice::enum_choice;
+use choice::{WidgetFactory, enum_choice};
 use dyn_any::DynAny;
 use glam::{DAffine2, DVec2};
 use graph_craft::Type;
+use graph_craft::concrete;
 use graph_craft::document::value::TaggedValue;
 use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput};
 use graphene_std::animation::RealTimeMode;
+use graphene_std::effects::EffectType;
 use graphene_std::extract_xy::XY;
 use graphene_std::path_bool::BooleanOperation;
-use graphene_std::raster::curve::Curve;
+use graphene_std::raster::curve::{Curve, CurveChannel};
+use graphene_std::raster::filter::{BlurEdges, BlurMode};
+use graphene_std::raster::image_trace::TraceColorMode;
+use graphene_std::raster::threshold::DitheringMode;
 use graphene_std::raster::{
-	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
-	SelectiveColorChoice,
+	BlendMode, CellularDistanceFunction, CellularReturnType, Color, DomainWarpType, FractalType, LevelsChannel, LuminanceCalculation, NoiseType, RedGreenBlue, RedGreenBlueAlpha, RelativeAbsolute,
+	SelectiveColorChoice, WorkingSpace,
 };
 use graphene_std::raster_types::{CPU, GPU, RasterDataTable};
-use graphene_std::text::{Font, TextAlign};
+use graphene_std::text::{Font, FontVariationInfo, TextAlign, TextPathOverflow, TextPathSide};
 use graphene_std::transform::{Footprint, ReferencePoint, Transform};
-use graphene_std::vector::VectorDataTable;
 use graphene_std::vector::misc::GridType;
 use graphene_std::vector::misc::{ArcType, MergeByDistanceAlgorithm};
-use graphene_std::vector::misc::{CentroidType, PointSpacingType};
-use graphene_std::vector::style::{Fill, FillChoice, FillType, GradientStops};
-use graphene_std::vector::style::{GradientType, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use graphene_std::vector::misc::{CentroidType, OpenPathOffset, PointSpacingType, ScatterMode};
+use graphene_std::vector::style::{Fill, FillChoice, FillType, GradientStops, Pattern, SwatchColor, SwatchId};
+use graphene_std::vector::style::{FillRule, GradientInterpolation, GradientType, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, StrokeMarker};
+use graphene_std::vector::{PointId, VectorDataTable};
 use graphene_std::{GraphicGroupTable, NodeInputDecleration};
 
 pub(crate) fn string_properties(text: &str) -> Vec<LayoutGroup> {
@@ -90,6 +97,7 @@ pub fn start_widgets(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<Widget
 		input_type,
 		blank_assist,
 		exposeable,
+		..
 	} = parameter_widgets_info;
 
 	let Some(document_node) = document_node else {
@@ -182,6 +190,7 @@ pub(crate) fn property_from_type(
 						// ==========================
 						Some(x) if x == TypeId::of::<Vec<f64>>() => array_of_number_widget(default_info, TextInput::default()).into(),
 						Some(x) if x == TypeId::of::<Vec<DVec2>>() => array_of_vec2_widget(default_info, TextInput::default()).into(),
+						Some(x) if x == TypeId::of::<Vec<PointId>>() => point_ids_widget(default_info).into(),
 						// ====================
 						// GRAPHICAL DATA TYPES
 						// ====================
@@ -195,7 +204,7 @@ pub(crate) fn property_from_type(
 						Some(x) if x == TypeId::of::<Option<Color>>() => color_widget(default_info, ColorInput::default().allow_none(true)),
 						Some(x) if x == TypeId::of::<GradientStops>() => color_widget(default_info, ColorInput::default().allow_none(false)),
 						Some(x) if x == TypeId::of::<Font>() => font_widget(default_info),
-						Some(x) if x == TypeId::of::<Curve>() => curve_widget(default_info),
+						Some(x) if x == TypeId::of::<Curve>() => curve_widget(default_info, &mut extra_widgets),
 						Some(x) if x == TypeId::of::<Footprint>() => footprint_widget(default_info, &mut extra_widgets),
 						// ===============================
 						// MANUALLY IMPLEMENTED ENUM TYPES
@@ -207,9 +216,11 @@ pub(crate) fn property_from_type(
 						// =========================
 						Some(x) if x == TypeId::of::<FillType>() => enum_choice::<FillType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<GradientType>() => enum_choice::<GradientType>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<GradientInterpolation>() => enum_choice::<GradientInterpolation>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RealTimeMode>() => enum_choice::<RealTimeMode>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlue>() => enum_choice::<RedGreenBlue>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<RedGreenBlueAlpha>() => enum_choice::<RedGreenBlueAlpha>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<LevelsChannel>() => enum_choice::<LevelsChannel>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<XY>() => enum_choice::<XY>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<NoiseType>() => enum_choice::<NoiseType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<FractalType>() => enum_choice::<FractalType>().for_socket(default_info).disabled(false).property_row(),
@@ -222,13 +233,25 @@ pub(crate) fn property_from_type(
 						Some(x) if x == TypeId::of::<StrokeJoin>() => enum_choice::<StrokeJoin>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<StrokeAlign>() => enum_choice::<StrokeAlign>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<PaintOrder>() => enum_choice::<PaintOrder>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<StrokeMarker>() => enum_choice::<StrokeMarker>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<FillRule>() => enum_choice::<FillRule>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<OpenPathOffset>() => enum_choice::<OpenPathOffset>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<ArcType>() => enum_choice::<ArcType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<TextAlign>() => enum_choice::<TextAlign>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<TextPathSide>() => enum_choice::<TextPathSide>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<TextPathOverflow>() => enum_choice::<TextPathOverflow>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<MergeByDistanceAlgorithm>() => enum_choice::<MergeByDistanceAlgorithm>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<PointSpacingType>() => enum_choice::<PointSpacingType>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<ScatterMode>() => enum_choice::<ScatterMode>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<BooleanOperation>() => enum_choice::<BooleanOperation>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<CentroidType>() => enum_choice::<CentroidType>().for_socket(default_info).property_row(),
 						Some(x) if x == TypeId::of::<LuminanceCalculation>() => enum_choice::<LuminanceCalculation>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<BlurMode>() => enum_choice::<BlurMode>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<BlurEdges>() => enum_choice::<BlurEdges>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<EffectType>() => enum_choice::<EffectType>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<DitheringMode>() => enum_choice::<DitheringMode>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<TraceColorMode>() => enum_choice::<TraceColorMode>().for_socket(default_info).property_row(),
+						Some(x) if x == TypeId::of::<WorkingSpace>() => enum_choice::<WorkingSpace>().for_socket(default_info).property_row(),
 						// =====
 						// OTHER
 						// =====
@@ -260,6 +283,71 @@ pub(crate) fn property_from_type(
 	Ok(extra_widgets)
 }
 
+/// Generates the widget for an input from the hints describing the values it expects, or `None` if it has no hints so its widget is generated from its type.
+pub(crate) fn hinted_property(node_id: NodeId, index: usize, context: &mut NodePropertiesContext) -> Option<Vec<LayoutGroup>> {
+	let hints = context.network_interface.persistent_input_metadata(&node_id, index, context.selection_network_path)?.hints.clone();
+	if hints.is_empty() {
+		return None;
+	}
+
+	Some(hinted_widgets(ParameterWidgetsInfo::new(node_id, index, true, context), &hints))
+}
+
+fn hinted_widgets(parameter_widgets_info: ParameterWidgetsInfo, hints: &InputHints) -> Vec<LayoutGroup> {
+	let value = parameter_widgets_info
+		.document_node
+		.and_then(|document_node| document_node.inputs.get(parameter_widgets_info.index))
+		.and_then(|input| input.as_non_exposed_value());
+
+	if hints.widget == Some(WidgetHint::Color) {
+		let allow_none = matches!(value, Some(TaggedValue::OptionalColor(_)));
+		return vec![color_widget(parameter_widgets_info, ColorInput::default().allow_none(allow_none))];
+	}
+
+	let mut number_input = hinted_number_input(hints);
+	if matches!(value, Some(TaggedValue::U32(_) | TaggedValue::U64(_))) {
+		number_input = number_input.int();
+	}
+	vec![number_widget(parameter_widgets_info, number_input).into()]
+}
+
+/// The number input described by an input's hints.
+pub fn hinted_number_input(hints: &InputHints) -> NumberInput {
+	let mut number_input = NumberInput::default();
+
+	// The limits are set before the soft range, since setting them also sets the range a slider spans
+	if let Some(min) = hints.min {
+		number_input = number_input.min(min);
+	}
+	if let Some(max) = hints.max {
+		number_input = number_input.max(max);
+	}
+
+	let soft_range = match hints.widget {
+		Some(WidgetHint::Slider) => hints.soft_range.or(hints.min.zip(hints.max)),
+		Some(WidgetHint::Angle) => Some(hints.soft_range.unwrap_or((-180., 180.))),
+		Some(WidgetHint::Percent) => Some(hints.soft_range.unwrap_or((0., 100.))),
+		Some(WidgetHint::Color) | None => hints.soft_range,
+	};
+	if let Some((start, end)) = soft_range {
+		number_input = number_input.mode_range().range_min(Some(start)).range_max(Some(end));
+	}
+
+	let unit = hints.unit.or(match hints.widget {
+		Some(WidgetHint::Angle) => Some(InputUnit::Degrees),
+		Some(WidgetHint::Percent) => Some(InputUnit::Percent),
+		_ => None,
+	});
+	if let Some(unit) = unit {
+		number_input = number_input.unit(unit.suffix());
+	}
+	if let Some(step) = hints.step {
+		number_input = number_input.step(step);
+	}
+
+	number_input
+}
+
 pub fn text_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<WidgetHolder> {
 	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
 
@@ -759,6 +847,36 @@ pub fn array_of_vec2_widget(parameter_widgets_info: ParameterWidgetsInfo, text_p
 	widgets
 }
 
+pub fn point_ids_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<WidgetHolder> {
+	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+
+	let mut widgets = start_widgets(parameter_widgets_info);
+
+	let Some(document_node) = document_node else { return Vec::new() };
+	let Some(input) = document_node.inputs.get(index) else {
+		log::warn!("A widget failed to be built because its node's input index is invalid.");
+		return vec![];
+	};
+	if let Some(TaggedValue::PointIds(point_ids)) = &input.as_non_exposed_value() {
+		let count = match point_ids.len() {
+			1 => "1 anchor".to_string(),
+			count => format!("{count} anchors"),
+		};
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new(count).widget_holder(),
+			Separator::new(SeparatorType::Related).widget_holder(),
+			TextButton::new("Clear")
+				.tooltip("Forget the chosen anchors")
+				.disabled(point_ids.is_empty())
+				.on_update(update_value(|_| TaggedValue::PointIds(Vec::new()), node_id, index))
+				.on_commit(commit_value)
+				.widget_holder(),
+		])
+	}
+	widgets
+}
+
 pub fn font_inputs(parameter_widgets_info: ParameterWidgetsInfo) -> (Vec<WidgetHolder>, Option<Vec<WidgetHolder>>) {
 	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
 
@@ -796,6 +914,64 @@ pub fn font_inputs(parameter_widgets_info: ParameterWidgetsInfo) -> (Vec<WidgetH
 	(first_widgets, second_widgets)
 }
 
+/// Rows for choosing one of a variable font's named instances and adjusting each of its design axes, which are empty for fonts that aren't variable.
+pub fn font_variation_widgets(font: &Font, variation_info: &FontVariationInfo, node_id: NodeId, index: usize) -> Vec<LayoutGroup> {
+	let mut rows = Vec::new();
+	let axis_values = variation_info.axis_values(font);
+
+	if !variation_info.named_instances.is_empty() {
+		let entries = variation_info
+			.named_instances
+			.iter()
+			.map(|instance| {
+				let font = Font {
+					variations: instance.variations.clone(),
+					..font.clone()
+				};
+				MenuListEntry::new(instance.name.clone())
+					.label(instance.name.clone())
+					.on_update(update_value(move |_| TaggedValue::Font(font.clone()), node_id, index))
+					.on_commit(commit_value)
+			})
+			.collect();
+		let selected_index = variation_info.named_instances.iter().position(|instance| instance.variations == axis_values);
+
+		let mut widgets = vec![TextLabel::new("Instance").tooltip("Presets for the variation axes which are named by the font").widget_holder()];
+		add_blank_assist(&mut widgets);
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			DropdownInput::new(vec![entries]).selected_index(selected_index.map(|index| index as u32)).widget_holder(),
+		]);
+		rows.push(LayoutGroup::Row { widgets });
+	}
+
+	for axis in &variation_info.axes {
+		let name = if axis.name.is_empty() { axis.tag.clone() } else { axis.name.clone() };
+		let tag = axis.tag.clone();
+		let font = font.clone();
+
+		let mut widgets = vec![TextLabel::new(name).tooltip(format!("The \"{}\" variation axis of the font", axis.tag)).widget_holder()];
+		add_blank_assist(&mut widgets);
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			NumberInput::new(axis_values.get(&axis.tag).copied())
+				.mode_range()
+				.min(axis.min)
+				.max(axis.max)
+				.on_update(update_value(
+					move |number_input: &NumberInput| TaggedValue::Font(font.clone().with_variation(tag.clone(), number_input.value.unwrap_or_default())),
+					node_id,
+					index,
+				))
+				.on_commit(commit_value)
+				.widget_holder(),
+		]);
+		rows.push(LayoutGroup::Row { widgets });
+	}
+
+	rows
+}
+
 pub fn vector_data_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Vec<WidgetHolder> {
 	let mut widgets = start_widgets(parameter_widgets_info);
 
@@ -833,12 +1009,20 @@ pub fn number_widget(parameter_widgets_info: ParameterWidgetsInfo, number_props:
 		log::warn!("A widget failed to be built because its node's input index is invalid.");
 		return vec![];
 	};
+
+	// Values entered beyond the limits of the input are clamped to them
+	let (min, max) = (number_props.min, number_props.max);
+	let clamp = move |value: f64| {
+		let value = min.map_or(value, |min| value.max(min));
+		max.map_or(value, |max| value.min(max))
+	};
+
 	match input.as_non_exposed_value() {
 		Some(&TaggedValue::F64(x)) => widgets.extend_from_slice(&[
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
 			number_props
 				.value(Some(x))
-				.on_update(update_value(move |x: &NumberInput| TaggedValue::F64(x.value.unwrap()), node_id, index))
+				.on_update(update_value(move |x: &NumberInput| TaggedValue::F64(clamp(x.value.unwrap())), node_id, index))
 				.on_commit(commit_value)
 				.widget_holder(),
 		]),
@@ -846,7 +1030,7 @@ pub fn number_widget(parameter_widgets_info: ParameterWidgetsInfo, number_props:
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
 			number_props
 				.value(Some(x as f64))
-				.on_update(update_value(move |x: &NumberInput| TaggedValue::U32((x.value.unwrap()) as u32), node_id, index))
+				.on_update(update_value(move |x: &NumberInput| TaggedValue::U32(clamp(x.value.unwrap()) as u32), node_id, index))
 				.on_commit(commit_value)
 				.widget_holder(),
 		]),
@@ -854,7 +1038,7 @@ pub fn number_widget(parameter_widgets_info: ParameterWidgetsInfo, number_props:
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
 			number_props
 				.value(Some(x as f64))
-				.on_update(update_value(move |x: &NumberInput| TaggedValue::U64((x.value.unwrap()) as u64), node_id, index))
+				.on_update(update_value(move |x: &NumberInput| TaggedValue::U64(clamp(x.value.unwrap()) as u64), node_id, index))
 				.on_commit(commit_value)
 				.widget_holder(),
 		]),
@@ -873,7 +1057,7 @@ pub fn number_widget(parameter_widgets_info: ParameterWidgetsInfo, number_props:
 				Separator::new(SeparatorType::Unrelated).widget_holder(),
 				number_props
 					.value(x)
-					.on_update(update_value(move |x: &NumberInput| TaggedValue::OptionalF64(x.value), node_id, index))
+					.on_update(update_value(move |x: &NumberInput| TaggedValue::OptionalF64(x.value.map(clamp)), node_id, index))
 					.disabled(x.is_none())
 					.on_commit(commit_value)
 					.widget_holder(),
@@ -931,7 +1115,13 @@ pub fn blend_mode_widget(parameter_widgets_info: ParameterWidgetsInfo) -> Layout
 }
 
 pub fn color_widget(parameter_widgets_info: ParameterWidgetsInfo, color_button: ColorInput) -> LayoutGroup {
-	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+	let ParameterWidgetsInfo {
+		document_node,
+		node_id,
+		index,
+		swatches,
+		..
+	} = parameter_widgets_info;
 
 	let mut widgets = start_widgets(parameter_widgets_info);
 
@@ -974,37 +1164,175 @@ pub fn color_widget(parameter_widgets_info: ParameterWidgetsInfo, color_button:
 				.on_commit(commit_value)
 				.widget_holder(),
 		),
+		TaggedValue::SwatchColor(swatch_color) => {
+			let color = swatches.resolve(*swatch_color).color;
+			let detached = if color_button.allow_none {
+				TaggedValue::OptionalColor(Some(color))
+			} else {
+				TaggedValue::Color(color)
+			};
+			widgets.extend(linked_swatch_widgets(*swatch_color, swatches, color_button, node_id, index, detached));
+		}
 		_ => {}
 	}
 
 	LayoutGroup::Row { widgets }
 }
 
+/// The widgets for a color input which is linked to one of the document's swatches.
+/// Editing the color edits the swatch, which changes every color linked to it, and the button detaches the input back to the literal `detached` value.
+fn linked_swatch_widgets(swatch_color: SwatchColor, swatches: &DocumentSwatches, color_button: ColorInput, node_id: NodeId, index: usize, detached: TaggedValue) -> Vec<WidgetHolder> {
+	let id = swatch_color.swatch;
+	let color_button = color_button.value(FillChoice::Solid(swatches.resolve(swatch_color).color)).allow_none(false);
+	let color_button = match swatches.get(id) {
+		Some(swatch) => color_button
+			.badge(Some(swatch.name.clone()))
+			.tooltip(format!(
+				"Linked to the \"{}\" swatch, so changing this color changes the swatch and every color linked to it",
+				swatch.name
+			))
+			.on_update(move |x: &ColorInput| {
+				SwatchesMessage::SetColor {
+					id,
+					color: x.value.as_solid().unwrap_or_default(),
+				}
+				.into()
+			})
+			.on_commit(commit_value),
+		// The swatch has been deleted, so the color it last had is kept until the input is detached
+		None => color_button
+			.badge(Some("Missing Swatch".into()))
+			.disabled(true)
+			.tooltip("The swatch this color was linked to has been deleted. Detach it to edit the color again."),
+	};
+
+	vec![
+		color_button.widget_holder(),
+		Separator::new(SeparatorType::Related).widget_holder(),
+		IconButton::new("Link", 24)
+			.tooltip("Detach from the swatch, keeping its current color")
+			.on_update(update_value(move |_| detached.clone(), node_id, index))
+			.on_commit(commit_value)
+			.widget_holder(),
+	]
+}
+
+/// A dropdown which links a color input to one of the document's swatches, or to a new swatch made from the input's current color.
+fn swatch_link_dropdown(swatches: &DocumentSwatches, color: Color, node_id: NodeId, index: usize) -> WidgetHolder {
+	let mut entries = vec![vec![MenuListEntry::new("Unlinked").label("Unlinked")]];
+
+	let existing: Vec<_> = swatches
+		.iter()
+		.map(|swatch| {
+			let swatch_color = SwatchColor { swatch: swatch.id, color };
+			MenuListEntry::new(swatch.id.0.to_string())
+				.label(swatch.name.clone())
+				.on_update(update_value(move |_| TaggedValue::SwatchColor(swatch_color), node_id, index))
+				.on_commit(commit_value)
+		})
+		.collect();
+	if !existing.is_empty() {
+		entries.push(existing);
+	}
+
+	let name = format!("Swatch {}", swatches.iter().count() + 1);
+	entries.push(vec![
+		MenuListEntry::new("New Swatch")
+			.label("New Swatch from Color")
+			.on_update(move |_| {
+				let id = SwatchId(generate_uuid());
+				Message::Batched {
+					messages: Box::new([
+						SwatchesMessage::Create { id, name: name.clone(), color }.into(),
+						NodeGraphMessage::SetInputValue {
+							node_id,
+							input_index: index,
+							value: TaggedValue::SwatchColor(SwatchColor { swatch: id, color }),
+						}
+						.into(),
+					]),
+				}
+			})
+			.on_commit(commit_value),
+	]);
+
+	DropdownInput::new(entries)
+		.selected_index(Some(0))
+		.tooltip("Link this color to a document swatch, so it changes whenever the swatch is edited")
+		.widget_holder()
+}
+
 pub fn font_widget(parameter_widgets_info: ParameterWidgetsInfo) -> LayoutGroup {
 	let (font_widgets, style_widgets) = font_inputs(parameter_widgets_info);
 	font_widgets.into_iter().chain(style_widgets.unwrap_or_default()).collect::<Vec<_>>().into()
 }
 
-pub fn curve_widget(parameter_widgets_info: ParameterWidgetsInfo) -> LayoutGroup {
-	let ParameterWidgetsInfo { document_node, node_id, index, .. } = parameter_widgets_info;
+pub fn curve_widget(parameter_widgets_info: ParameterWidgetsInfo, extra_widgets: &mut Vec<LayoutGroup>) -> LayoutGroup {
+	let ParameterWidgetsInfo {
+		document_node,
+		node_id,
+		index,
+		curve_channel,
+		..
+	} = parameter_widgets_info;
 
-	let mut widgets = start_widgets(parameter_widgets_info);
+	let mut channel_widgets = start_widgets(parameter_widgets_info);
+
+	let mut curve_widgets = vec![TextLabel::new("").widget_holder()];
+	add_blank_assist(&mut curve_widgets);
 
 	let Some(document_node) = document_node else { return LayoutGroup::default() };
 	let Some(input) = document_node.inputs.get(index) else {
 		log::warn!("A widget failed to be built because its node's input index is invalid.");
 		return LayoutGroup::Row { widgets: vec![] };
 	};
-	if let Some(TaggedValue::Curve(curve)) = &input.as_non_exposed_value() {
-		widgets.extend_from_slice(&[
-			Separator::new(SeparatorType::Unrelated).widget_holder(),
-			CurveInput::new(curve.clone())
-				.on_update(update_value(|x: &CurveInput| TaggedValue::Curve(x.value.clone()), node_id, index))
-				.on_commit(commit_value)
-				.widget_holder(),
-		])
-	}
-	LayoutGroup::Row { widgets }
+	let Some(TaggedValue::Curve(curve)) = &input.as_non_exposed_value() else {
+		return LayoutGroup::Row { widgets: channel_widgets };
+	};
+
+	let entries = CurveChannel::ALL
+		.into_iter()
+		.map(|channel| {
+			let (label, tooltip) = match channel {
+				CurveChannel::Master => ("Master", "Edit the curve applied to the red, green, and blue channels after their own curves"),
+				CurveChannel::Red => ("R", "Edit the curve of the red channel"),
+				CurveChannel::Green => ("G", "Edit the curve of the green channel"),
+				CurveChannel::Blue => ("B", "Edit the curve of the blue channel"),
+				CurveChannel::Alpha => ("A", "Edit the curve of the alpha channel"),
+			};
+			RadioEntryData::new(format!("{channel:?}"))
+				.label(label)
+				.tooltip(tooltip)
+				.on_update(move |_| PropertiesPanelMessage::SelectCurveChannel { node_id, input_index: index, channel }.into())
+		})
+		.collect();
+	let selected_index = CurveChannel::ALL.iter().position(|&channel| channel == curve_channel).map(|index| index as u32);
+	channel_widgets.extend_from_slice(&[
+		Separator::new(SeparatorType::Unrelated).widget_holder(),
+		RadioInput::new(entries).selected_index(selected_index).widget_holder(),
+	]);
+
+	// A channel without its own curve is shown as the straight line it's equivalent to, until it's edited
+	let spline = curve.spline(curve_channel).cloned().unwrap_or_default();
+	let curve = curve.clone();
+	curve_widgets.extend_from_slice(&[
+		Separator::new(SeparatorType::Unrelated).widget_holder(),
+		CurveInput::new(spline)
+			.on_update(update_value(
+				move |x: &CurveInput| {
+					let mut curve = curve.clone();
+					curve.set_spline(curve_channel, x.value.clone());
+					TaggedValue::Curve(curve)
+				},
+				node_id,
+				index,
+			))
+			.on_commit(commit_value)
+			.widget_holder(),
+	]);
+
+	*extra_widgets = vec![LayoutGroup::Row { widgets: channel_widgets }];
+	LayoutGroup::Row { widgets: curve_widgets }
 }
 
 pub fn get_document_node<'a>(node_id: NodeId, context: &'a NodePropertiesContext<'a>) -> Result<&'a DocumentNode, String> {
@@ -1172,6 +1500,89 @@ pub(crate) fn channel_mixer_properties(node_id: NodeId, context: &mut NodeProper
 	layout
 }
 
+pub(crate) fn levels_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::raster::levels::*;
+
+	let mut parameter_info = ParameterWidgetsInfo::new(node_id, ChannelInput::INDEX, true, context);
+	parameter_info.exposeable = false;
+	let channel = enum_choice::<LevelsChannel>().for_socket(parameter_info).property_row();
+
+	let document_node = match get_document_node(node_id, context) {
+		Ok(document_node) => document_node,
+		Err(err) => {
+			log::error!("Could not get document node in levels_properties: {err}");
+			return Vec::new();
+		}
+	};
+	// Channel choice
+	let channel_value = match document_node.inputs.get(ChannelInput::INDEX).and_then(|input| input.as_value()) {
+		Some(&TaggedValue::LevelsChannel(channel)) => channel,
+		_ => {
+			warn!("Levels node properties panel could not be displayed.");
+			return vec![];
+		}
+	};
+	let image_is_connected = matches!(document_node.inputs.first(), Some(NodeInput::Node { .. }));
+
+	// Channel values
+	let [input_black_index, input_white_index, gamma_index, output_black_index, output_white_index] = match channel_value {
+		LevelsChannel::Master => [InputBlackInput::INDEX, InputWhiteInput::INDEX, GammaInput::INDEX, OutputBlackInput::INDEX, OutputWhiteInput::INDEX],
+		LevelsChannel::Red => [
+			RedInputBlackInput::INDEX,
+			RedInputWhiteInput::INDEX,
+			RedGammaInput::INDEX,
+			RedOutputBlackInput::INDEX,
+			RedOutputWhiteInput::INDEX,
+		],
+		LevelsChannel::Green => [
+			GreenInputBlackInput::INDEX,
+			GreenInputWhiteInput::INDEX,
+			GreenGammaInput::INDEX,
+			GreenOutputBlackInput::INDEX,
+			GreenOutputWhiteInput::INDEX,
+		],
+		LevelsChannel::Blue => [
+			BlueInputBlackInput::INDEX,
+			BlueInputWhiteInput::INDEX,
+			BlueGammaInput::INDEX,
+			BlueOutputBlackInput::INDEX,
+			BlueOutputWhiteInput::INDEX,
+		],
+	};
+	let percentage_input = NumberInput::default().mode_range().min(0.).max(100.).unit("%");
+	let input_black = number_widget(ParameterWidgetsInfo::new(node_id, input_black_index, true, context), percentage_input.clone());
+	let input_white = number_widget(ParameterWidgetsInfo::new(node_id, input_white_index, true, context), percentage_input.clone());
+	let gamma = number_widget(
+		ParameterWidgetsInfo::new(node_id, gamma_index, true, context),
+		NumberInput::default().min(0.01).max(9.99).increment_step(0.1),
+	);
+	let output_black = number_widget(ParameterWidgetsInfo::new(node_id, output_black_index, true, context), percentage_input.clone());
+	let output_white = number_widget(ParameterWidgetsInfo::new(node_id, output_white_index, true, context), percentage_input);
+
+	// Auto levels, which are computed from the image once the graph is next evaluated
+	let mut node_path = context.selection_network_path.to_vec();
+	node_path.push(node_id);
+	let mut auto_levels = vec![TextLabel::new("").widget_holder()];
+	add_blank_assist(&mut auto_levels);
+	auto_levels.push(
+		TextButton::new("Auto")
+			.tooltip("Set the input black and white points of each color channel to stretch its values to the full range, ignoring the darkest and lightest 0.1% of pixels")
+			.disabled(!image_is_connected)
+			.on_update(move |_: &TextButton| PortfolioMessage::AutoLevels { node_path: node_path.clone() }.into())
+			.widget_holder(),
+	);
+
+	vec![
+		channel,
+		LayoutGroup::Row { widgets: input_black },
+		LayoutGroup::Row { widgets: input_white },
+		LayoutGroup::Row { widgets: gamma },
+		LayoutGroup::Row { widgets: output_black },
+		LayoutGroup::Row { widgets: output_white },
+		LayoutGroup::Row { widgets: auto_levels },
+	]
+}
+
 pub(crate) fn selective_color_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::raster::selective_color::*;
 
@@ -1230,6 +1641,53 @@ pub(crate) fn selective_color_properties(node_id: NodeId, context: &mut NodeProp
 	]
 }
 
+pub(crate) fn blur_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::raster::filter::blur::*;
+
+	let document_node = match get_document_node(node_id, context) {
+		Ok(document_node) => document_node,
+		Err(err) => {
+			log::error!("Could not get document node in blur_properties: {err}");
+			return Vec::new();
+		}
+	};
+	let mode = match document_node.inputs.get(ModeInput::INDEX).and_then(|input| input.as_value()) {
+		Some(&TaggedValue::BlurMode(mode)) => Some(mode),
+		_ => None,
+	};
+
+	let mut widgets = vec![enum_choice::<BlurMode>().for_socket(ParameterWidgetsInfo::new(node_id, ModeInput::INDEX, true, context)).property_row()];
+
+	// Show the parameters of the chosen mode, or all of them when the mode is exposed to the graph
+	if mode != Some(BlurMode::Motion) {
+		let radius = number_widget(
+			ParameterWidgetsInfo::new(node_id, RadiusInput::INDEX, true, context),
+			NumberInput::default().mode_range().min(0.).max(100.).unit(" px"),
+		);
+		widgets.push(LayoutGroup::Row { widgets: radius });
+	}
+	if mode.is_none() || mode == Some(BlurMode::Motion) {
+		let angle = number_widget(
+			ParameterWidgetsInfo::new(node_id, AngleInput::INDEX, true, context),
+			NumberInput::default().mode_range().min(-180.).max(180.).unit("°"),
+		);
+		let distance = number_widget(
+			ParameterWidgetsInfo::new(node_id, DistanceInput::INDEX, true, context),
+			NumberInput::default().mode_range().min(0.).max(100.).unit(" px"),
+		);
+		widgets.extend([LayoutGroup::Row { widgets: angle }, LayoutGroup::Row { widgets: distance }]);
+	}
+
+	let edges = enum_choice::<BlurEdges>()
+		.for_socket(ParameterWidgetsInfo::new(node_id, EdgesInput::INDEX, true, context))
+		.property_row();
+	let high_quality = bool_widget(ParameterWidgetsInfo::new(node_id, HighQualityInput::INDEX, true, context), CheckboxInput::default());
+	let gamma = bool_widget(ParameterWidgetsInfo::new(node_id, GammaInput::INDEX, true, context), CheckboxInput::default());
+	widgets.extend([edges, LayoutGroup::Row { widgets: high_quality }, LayoutGroup::Row { widgets: gamma }]);
+
+	widgets
+}
+
 pub(crate) fn grid_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::generator_nodes::grid::*;
 
@@ -1323,6 +1781,37 @@ pub(crate) fn sample_polyline_properties(node_id: NodeId, context: &mut NodeProp
 	]
 }
 
+pub(crate) fn blending_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
+	use graphene_std::blending_nodes::blending::*;
+
+	let blend_mode = blend_mode_widget(ParameterWidgetsInfo::new(node_id, BlendModeInput::INDEX, true, context));
+	let opacity = number_widget(
+		ParameterWidgetsInfo::new(node_id, OpacityInput::INDEX, true, context),
+		NumberInput::default().percentage().min(0.).max(100.),
+	);
+	let fill = number_widget(
+		ParameterWidgetsInfo::new(node_id, FillInput::INDEX, true, context),
+		NumberInput::default().percentage().min(0.).max(100.),
+	);
+	let clip = bool_widget(ParameterWidgetsInfo::new(node_id, ClipInput::INDEX, true, context), CheckboxInput::default());
+
+	let mut layout = vec![
+		blend_mode,
+		LayoutGroup::Row { widgets: opacity },
+		LayoutGroup::Row { widgets: fill },
+		LayoutGroup::Row { widgets: clip },
+	];
+
+	// Only groups have contents which can pass through to blend against the backdrop below them
+	let input_type = context.network_interface.input_type(&InputConnector::node(node_id, 0), context.selection_network_path).0;
+	if input_type.nested_type() == &concrete!(GraphicGroupTable) {
+		let pass_through = bool_widget(ParameterWidgetsInfo::new(node_id, PassThroughInput::INDEX, true, context), CheckboxInput::default());
+		layout.push(LayoutGroup::Row { widgets: pass_through });
+	}
+
+	layout
+}
+
 pub(crate) fn exposure_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::raster::exposure::*;
 
@@ -1495,7 +1984,8 @@ pub(crate) fn generate_node_properties(node_id: NodeId, context: &mut NodeProper
 	} else {
 		let number_of_inputs = context.network_interface.number_of_inputs(&node_id, context.selection_network_path);
 		for input_index in 1..number_of_inputs {
-			let row = context.call_widget_override(&node_id, input_index).unwrap_or_else(|| {
+			let widget_override = context.call_widget_override(&node_id, input_index).or_else(|| hinted_property(node_id, input_index, context));
+			let row = widget_override.unwrap_or_else(|| {
 				let Some(implementation) = context.network_interface.implementation(&node_id, context.selection_network_path) else {
 					log::error!("Could not get implementation for node {node_id}");
 					return Vec::new();
@@ -1588,6 +2078,7 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	use graphene_std::vector::fill::*;
 
 	let mut widgets_first_row = start_widgets(ParameterWidgetsInfo::new(node_id, FillInput::<Color>::INDEX, true, context));
+	let pattern_tile_row = start_widgets(ParameterWidgetsInfo::new(node_id, PatternTileInput::INDEX, true, context));
 
 	let document_node = match get_document_node(node_id, context) {
 		Ok(document_node) => document_node,
@@ -1597,6 +2088,15 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 		}
 	};
 
+	// A fill linked to a swatch is always solid, so there is no fill type to choose until it's detached
+	if let Some(&TaggedValue::SwatchColor(swatch_color)) = document_node.inputs[FillInput::<Color>::INDEX].as_non_exposed_value() {
+		let swatches = context.network_interface.swatches();
+		let detached = TaggedValue::Fill(Fill::Solid(swatches.resolve(swatch_color).color));
+		widgets_first_row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+		widgets_first_row.extend(linked_swatch_widgets(swatch_color, swatches, ColorInput::default(), node_id, FillInput::<Color>::INDEX, detached));
+		return vec![LayoutGroup::Row { widgets: widgets_first_row }];
+	}
+
 	let (fill, backup_color, backup_gradient) = if let (Some(TaggedValue::Fill(fill)), &Some(&TaggedValue::OptionalColor(backup_color)), Some(TaggedValue::Gradient(backup_gradient))) = (
 		&document_node.inputs[FillInput::<Color>::INDEX].as_value(),
 		&document_node.inputs[BackupColorInput::INDEX].as_value(),
@@ -1609,50 +2109,68 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	let fill2 = fill.clone();
 	let backup_color_fill: Fill = backup_color.into();
 	let backup_gradient_fill: Fill = backup_gradient.clone().into();
+	// Fills from before patterns existed may lack this input, in which case the default pattern is used
+	let backup_pattern_fill = match document_node.inputs.get(BackupPatternInput::INDEX).and_then(|input| input.as_value()) {
+		Some(TaggedValue::Pattern(backup_pattern)) => Fill::Pattern(backup_pattern.clone()),
+		_ => Fill::Pattern(Pattern::default()),
+	};
 
-	widgets_first_row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
-	widgets_first_row.push(
-		ColorInput::default()
-			.value(fill.clone().into())
-			.on_update(move |x: &ColorInput| Message::Batched {
-				messages: Box::new([
-					match &fill2 {
-						Fill::None => NodeGraphMessage::SetInputValue {
-							node_id,
-							input_index: BackupColorInput::INDEX,
-							value: TaggedValue::OptionalColor(None),
-						}
-						.into(),
-						Fill::Solid(color) => NodeGraphMessage::SetInputValue {
-							node_id,
-							input_index: BackupColorInput::INDEX,
-							value: TaggedValue::OptionalColor(Some(*color)),
-						}
-						.into(),
-						Fill::Gradient(gradient) => NodeGraphMessage::SetInputValue {
+	// A pattern is made from its tile graphic rather than colors, so it has no color to pick
+	if fill.as_pattern().is_none() {
+		widgets_first_row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+		widgets_first_row.push(
+			ColorInput::default()
+				.value(fill.clone().into())
+				.on_update(move |x: &ColorInput| Message::Batched {
+					messages: Box::new([
+						match &fill2 {
+							Fill::None => NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: BackupColorInput::INDEX,
+								value: TaggedValue::OptionalColor(None),
+							}
+							.into(),
+							Fill::Solid(color) => NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: BackupColorInput::INDEX,
+								value: TaggedValue::OptionalColor(Some(*color)),
+							}
+							.into(),
+							Fill::Gradient(gradient) => NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: BackupGradientInput::INDEX,
+								value: TaggedValue::Gradient(gradient.clone()),
+							}
+							.into(),
+							Fill::Pattern(pattern) => NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: BackupPatternInput::INDEX,
+								value: TaggedValue::Pattern(pattern.clone()),
+							}
+							.into(),
+						},
+						NodeGraphMessage::SetInputValue {
 							node_id,
-							input_index: BackupGradientInput::INDEX,
-							value: TaggedValue::Gradient(gradient.clone()),
+							input_index: FillInput::<Color>::INDEX,
+							value: TaggedValue::Fill(x.value.to_fill(fill2.as_gradient())),
 						}
 						.into(),
-					},
-					NodeGraphMessage::SetInputValue {
-						node_id,
-						input_index: FillInput::<Color>::INDEX,
-						value: TaggedValue::Fill(x.value.to_fill(fill2.as_gradient())),
-					}
-					.into(),
-				]),
-			})
-			.on_commit(commit_value)
-			.widget_holder(),
-	);
+					]),
+				})
+				.on_commit(commit_value)
+				.widget_holder(),
+		);
+	}
+	if let Fill::Solid(color) = fill {
+		widgets_first_row.push(Separator::new(SeparatorType::Related).widget_holder());
+		widgets_first_row.push(swatch_link_dropdown(context.network_interface.swatches(), *color, node_id, FillInput::<Color>::INDEX));
+	}
 	let mut widgets = vec![LayoutGroup::Row { widgets: widgets_first_row }];
 
 	let fill_type_switch = {
 		let mut row = vec![TextLabel::new("").widget_holder()];
 		match fill {
-			Fill::Solid(_) | Fill::None => add_blank_assist(&mut row),
+			Fill::Solid(_) | Fill::None | Fill::Pattern(_) => add_blank_assist(&mut row),
 			Fill::Gradient(gradient) => {
 				let reverse_button = IconButton::new("Reverse", 24)
 					.tooltip("Reverse the gradient color stops")
@@ -1683,11 +2201,20 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 				.label("Gradient")
 				.on_update(update_value(move |_| TaggedValue::Fill(backup_gradient_fill.clone()), node_id, FillInput::<Color>::INDEX))
 				.on_commit(commit_value),
+			RadioEntryData::new("pattern")
+				.label("Pattern")
+				.on_update(update_value(move |_| TaggedValue::Fill(backup_pattern_fill.clone()), node_id, FillInput::<Color>::INDEX))
+				.on_commit(commit_value),
 		];
+		let selected_index = match fill {
+			Fill::None | Fill::Solid(_) => 0,
+			Fill::Gradient(_) => 1,
+			Fill::Pattern(_) => 2,
+		};
 
 		row.extend_from_slice(&[
 			Separator::new(SeparatorType::Unrelated).widget_holder(),
-			RadioInput::new(entries).selected_index(Some(if fill.as_gradient().is_some() { 1 } else { 0 })).widget_holder(),
+			RadioInput::new(entries).selected_index(Some(selected_index)).widget_holder(),
 		]);
 
 		LayoutGroup::Row { widgets: row }
@@ -1722,10 +2249,39 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 				row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
 				row.push(reverse_radial_gradient_button);
 			}
+			GradientType::Conic => {
+				// The start angle is the direction from the center (start) to the end point, so it's changed by rotating the end point around the center
+				let start_angle_input = NumberInput::new(Some(gradient.start_angle().to_degrees()))
+					.label("Start Angle")
+					.tooltip("The direction the conic gradient's first stop sweeps from, around its center")
+					.unit("°")
+					.mode(NumberInputMode::Range)
+					.range_min(Some(-180.))
+					.range_max(Some(180.))
+					.on_update(update_value(
+						{
+							let gradient = gradient.clone();
+							move |input: &NumberInput| {
+								let mut gradient = gradient.clone();
+								if let Some(angle) = input.value {
+									gradient.end = gradient.start + DVec2::from_angle(angle.to_radians()) * gradient.start.distance(gradient.end);
+								}
+								TaggedValue::Fill(Fill::Gradient(gradient))
+							}
+						},
+						node_id,
+						FillInput::<Color>::INDEX,
+					))
+					.on_commit(commit_value)
+					.widget_holder();
+				row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+				row.push(start_angle_input);
+			}
 		}
 
 		let new_gradient1 = gradient.clone();
 		let new_gradient2 = gradient.clone();
+		let new_gradient3 = gradient.clone();
 
 		let entries = vec![
 			RadioEntryData::new("Linear")
@@ -1752,6 +2308,18 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 					FillInput::<Color>::INDEX,
 				))
 				.on_commit(commit_value),
+			RadioEntryData::new("Conic")
+				.label("Conic")
+				.on_update(update_value(
+					move |_| {
+						let mut new_gradient = new_gradient3.clone();
+						new_gradient.gradient_type = GradientType::Conic;
+						TaggedValue::Fill(Fill::Gradient(new_gradient))
+					},
+					node_id,
+					FillInput::<Color>::INDEX,
+				))
+				.on_commit(commit_value),
 		];
 
 		row.extend_from_slice(&[
@@ -1760,11 +2328,363 @@ pub(crate) fn fill_properties(node_id: NodeId, context: &mut NodePropertiesConte
 		]);
 
 		widgets.push(LayoutGroup::Row { widgets: row });
+
+		let interpolation = enum_choice::<GradientInterpolation>().build(
+			gradient.interpolation,
+			|| {
+				let gradient = gradient.clone();
+				update_value(
+					move |interpolation: &GradientInterpolation| {
+						let mut gradient = gradient.clone();
+						gradient.interpolation = *interpolation;
+						TaggedValue::Fill(Fill::Gradient(gradient))
+					},
+					node_id,
+					FillInput::<Color>::INDEX,
+				)
+			},
+			|| commit_value,
+		);
+		let mut row = vec![TextLabel::new("").widget_holder()];
+		// SVG can only mix colors in sRGB, so other color spaces are approximated in exported SVG by dividing each segment between stops into steps
+		if gradient.interpolation == GradientInterpolation::Srgb {
+			add_blank_assist(&mut row);
+		} else {
+			let subdivisions_input = NumberInput::new(Some(gradient.interpolation_subdivisions as f64))
+				.label("SVG Steps")
+				.tooltip("The number of steps each segment between stops is divided into when exported as SVG, to approximate this color space")
+				.int()
+				.min(1.)
+				.max(64.)
+				.on_update(update_value(
+					{
+						let gradient = gradient.clone();
+						move |input: &NumberInput| {
+							let mut gradient = gradient.clone();
+							if let Some(subdivisions) = input.value {
+								gradient.interpolation_subdivisions = subdivisions as u32;
+							}
+							TaggedValue::Fill(Fill::Gradient(gradient))
+						}
+					},
+					node_id,
+					FillInput::<Color>::INDEX,
+				))
+				.on_commit(commit_value)
+				.widget_holder();
+			row.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+			row.push(subdivisions_input);
+		}
+		row.extend_from_slice(&[Separator::new(SeparatorType::Unrelated).widget_holder(), interpolation]);
+
+		widgets.push(LayoutGroup::Row { widgets: row }.with_tooltip("The color space the colors between stops are mixed in"));
+
+		let selected_stops = context.selected_gradient_stops.get(&(node_id, FillInput::<Color>::INDEX)).map_or(&[][..], Vec::as_slice);
+		widgets.extend(gradient_stops_widgets(&gradient.stops, gradient.interpolation, selected_stops, node_id, FillInput::<Color>::INDEX, {
+			let gradient = gradient.clone();
+			move |stops| {
+				let mut gradient = gradient.clone();
+				gradient.stops = stops;
+				TaggedValue::Fill(Fill::Gradient(gradient))
+			}
+		}));
+	}
+
+	if let Fill::Pattern(pattern) = fill.clone() {
+		widgets.push(LayoutGroup::Row { widgets: pattern_tile_row });
+
+		// Edits are also kept in the backup pattern so they survive switching to another fill type and back
+		let pattern_input = |value: f64, update: fn(&mut Pattern, f64)| {
+			let pattern = pattern.clone();
+			NumberInput::new(Some(value))
+				.on_update(move |input: &NumberInput| {
+					let mut pattern = pattern.clone();
+					if let Some(value) = input.value {
+						update(&mut pattern, value);
+					}
+					Message::Batched {
+						messages: Box::new([
+							NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: BackupPatternInput::INDEX,
+								value: TaggedValue::Pattern(pattern.clone()),
+							}
+							.into(),
+							NodeGraphMessage::SetInputValue {
+								node_id,
+								input_index: FillInput::<Color>::INDEX,
+								value: TaggedValue::Fill(Fill::Pattern(pattern)),
+							}
+							.into(),
+						]),
+					}
+				})
+				.on_commit(commit_value)
+		};
+
+		let scale = pattern_input(pattern.scale * 100., |pattern, value| pattern.scale = value / 100.)
+			.label("Scale")
+			.tooltip("The size of each tile relative to the tile graphic")
+			.unit("%")
+			.min(1.)
+			.widget_holder();
+		let rotation = pattern_input(pattern.rotation, |pattern, value| pattern.rotation = value)
+			.label("Rotation")
+			.tooltip("The angle the grid of tiles is turned by")
+			.unit("°")
+			.mode_range()
+			.min(-180.)
+			.max(180.)
+			.widget_holder();
+		widgets.push(LayoutGroup::Row {
+			widgets: vec![
+				TextLabel::new("Tile").widget_holder(),
+				Separator::new(SeparatorType::Unrelated).widget_holder(),
+				scale,
+				Separator::new(SeparatorType::Related).widget_holder(),
+				rotation,
+			],
+		});
+
+		let spacing_x = pattern_input(pattern.spacing.x, |pattern, value| pattern.spacing.x = value)
+			.label("X")
+			.tooltip("The gap left between neighboring tiles")
+			.unit(" px")
+			.min(0.)
+			.widget_holder();
+		let spacing_y = pattern_input(pattern.spacing.y, |pattern, value| pattern.spacing.y = value)
+			.label("Y")
+			.tooltip("The gap left between neighboring tiles")
+			.unit(" px")
+			.min(0.)
+			.widget_holder();
+		widgets.push(LayoutGroup::Row {
+			widgets: vec![
+				TextLabel::new("Spacing").widget_holder(),
+				Separator::new(SeparatorType::Unrelated).widget_holder(),
+				spacing_x,
+				Separator::new(SeparatorType::Related).widget_holder(),
+				spacing_y,
+			],
+		});
+
+		let offset_x = pattern_input(pattern.offset.x, |pattern, value| pattern.offset.x = value)
+			.label("X")
+			.tooltip("How far the grid of tiles is shifted")
+			.unit(" px")
+			.widget_holder();
+		let offset_y = pattern_input(pattern.offset.y, |pattern, value| pattern.offset.y = value)
+			.label("Y")
+			.tooltip("How far the grid of tiles is shifted")
+			.unit(" px")
+			.widget_holder();
+		widgets.push(LayoutGroup::Row {
+			widgets: vec![
+				TextLabel::new("Offset").widget_holder(),
+				Separator::new(SeparatorType::Unrelated).widget_holder(),
+				offset_x,
+				Separator::new(SeparatorType::Related).widget_holder(),
+				offset_y,
+			],
+		});
 	}
 
+	widgets.push(
+		enum_choice::<FillRule>()
+			.for_socket(ParameterWidgetsInfo::new(node_id, FillRuleInput::INDEX, true, context))
+			.property_row(),
+	);
+
 	widgets
 }
 
+/// The list of a gradient's stops, each with a checkbox selecting it, its color, and its position, followed by buttons acting on the selected stops.
+/// Edits set the input to the value `to_value` makes from the edited stops, and the selection follows the stops when they're reordered.
+pub(crate) fn gradient_stops_widgets(
+	stops: &GradientStops,
+	interpolation: GradientInterpolation,
+	selected: &[usize],
+	node_id: NodeId,
+	input_index: usize,
+	to_value: impl Fn(GradientStops) -> TaggedValue + Clone + Send + Sync + 'static,
+) -> Vec<LayoutGroup> {
+	let mut selected: Vec<usize> = selected.iter().copied().filter(|&index| index < stops.len()).collect();
+	selected.sort_unstable();
+	selected.dedup();
+
+	let select = move |selection: Vec<usize>| -> Message {
+		PropertiesPanelMessage::SelectGradientStops {
+			node_id,
+			input_index,
+			stops: selection,
+		}
+		.into()
+	};
+	let update = move |stops: GradientStops, selection: Vec<usize>| Message::Batched {
+		messages: Box::new([
+			NodeGraphMessage::SetInputValue {
+				node_id,
+				input_index,
+				value: to_value(stops),
+			}
+			.into(),
+			select(selection),
+		]),
+	};
+	// Buttons make their change in a single click, so the transaction is added before it rather than when a drag or edit is committed
+	let update_with_transaction = {
+		let update = update.clone();
+		move |stops: GradientStops, selection: Vec<usize>| Message::Batched {
+			messages: Box::new([DocumentMessage::AddTransaction.into(), update(stops, selection)]),
+		}
+	};
+
+	let mut rows = Vec::with_capacity(stops.len() + 1);
+	for (index, &(position, color)) in stops.iter().enumerate() {
+		let is_selected = selected.contains(&index);
+
+		let checkbox = CheckboxInput::new(is_selected)
+			.tooltip("Select this stop")
+			.on_update({
+				let selected = selected.clone();
+				move |_: &CheckboxInput| {
+					let mut selection = selected.clone();
+					if is_selected {
+						selection.retain(|&selected_index| selected_index != index);
+					} else {
+						selection.push(index);
+						selection.sort_unstable();
+					}
+					select(selection)
+				}
+			})
+			.widget_holder();
+
+		let color_input = ColorInput::default()
+			.value(FillChoice::Solid(color))
+			.allow_none(false)
+			.tooltip("The color of this stop")
+			.on_update({
+				let (stops, selected, update) = (stops.clone(), selected.clone(), update.clone());
+				move |input: &ColorInput| {
+					let mut stops = stops.clone();
+					if let Some(color) = input.value.as_solid() {
+						stops.color_stops[index].1 = color;
+					}
+					update(stops, selected.clone())
+				}
+			})
+			.on_commit(commit_value)
+			.widget_holder();
+
+		let position_input = NumberInput::new(Some(position * 100.))
+			.tooltip("The position of this stop along the gradient, which is reordered among the other stops if moved past its neighbors")
+			.unit("%")
+			.mode(NumberInputMode::Range)
+			.range_min(Some(0.))
+			.range_max(Some(100.))
+			.min(0.)
+			.max(100.)
+			.on_update({
+				let (stops, selected, update) = (stops.clone(), selected.clone(), update.clone());
+				move |input: &NumberInput| {
+					let Some(value) = input.value else { return Message::NoOp };
+					let mut stops = stops.clone();
+					let new_index = stops.set_position(index, value / 100.);
+					update(stops, selected.iter().map(|&selected_index| reordered_index(selected_index, index, new_index)).collect())
+				}
+			})
+			.on_commit(commit_value)
+			.widget_holder();
+
+		let mut row = vec![TextLabel::new("").widget_holder()];
+		add_blank_assist(&mut row);
+		row.extend([
+			checkbox,
+			Separator::new(SeparatorType::Related).widget_holder(),
+			color_input,
+			Separator::new(SeparatorType::Related).widget_holder(),
+			position_input,
+		]);
+		rows.push(LayoutGroup::Row { widgets: row });
+	}
+
+	let pair = match selected[..] {
+		[first, second] => Some((first, second)),
+		_ => None,
+	};
+	let add_button = TextButton::new("Add Stop")
+		.tooltip("Add a stop midway between the two selected stops, with the color the gradient already has there")
+		.disabled(pair.is_none())
+		.on_update({
+			let (stops, update_with_transaction) = (stops.clone(), update_with_transaction.clone());
+			move |_: &TextButton| {
+				let Some((first, second)) = pair else { return Message::NoOp };
+				let mut stops = stops.clone();
+				let position = (stops[first].0 + stops[second].0) / 2.;
+				let index = stops.insert_stop_at(position, interpolation);
+				update_with_transaction(stops, vec![index])
+			}
+		})
+		.widget_holder();
+
+	// A gradient needs at least two stops
+	let delete_button = TextButton::new("Delete")
+		.tooltip("Delete the selected stops")
+		.disabled(selected.is_empty() || stops.len() - selected.len() < 2)
+		.on_update({
+			let (stops, selected, update_with_transaction) = (stops.clone(), selected.clone(), update_with_transaction.clone());
+			move |_: &TextButton| {
+				let mut stops = stops.clone();
+				for &index in selected.iter().rev() {
+					stops.remove_stop(index);
+				}
+				update_with_transaction(stops, Vec::new())
+			}
+		})
+		.widget_holder();
+
+	let distribute_button = TextButton::new("Distribute")
+		.tooltip("Space the selected stops, along with any stops between them, evenly between the outermost selected stops")
+		.disabled(selected.len() < 3)
+		.on_update({
+			let (stops, selected) = (stops.clone(), selected.clone());
+			move |_: &TextButton| {
+				let (Some(&first), Some(&last)) = (selected.first(), selected.last()) else { return Message::NoOp };
+				let mut stops = stops.clone();
+				stops.distribute(first, last);
+				update_with_transaction(stops, selected.clone())
+			}
+		})
+		.widget_holder();
+
+	let mut row = vec![TextLabel::new("").widget_holder()];
+	add_blank_assist(&mut row);
+	row.extend([
+		add_button,
+		Separator::new(SeparatorType::Related).widget_holder(),
+		delete_button,
+		Separator::new(SeparatorType::Related).widget_holder(),
+		distribute_button,
+	]);
+	rows.push(LayoutGroup::Row { widgets: row });
+
+	rows
+}
+
+/// Where the stop at `index` ends up after the stop at `from` is moved to `to`.
+fn reordered_index(index: usize, from: usize, to: usize) -> usize {
+	if index == from {
+		to
+	} else if from < index && index <= to {
+		index - 1
+	} else if to <= index && index < from {
+		index + 1
+	} else {
+		index
+	}
+}
+
 pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
 	use graphene_std::vector::stroke::*;
 
@@ -1780,30 +2700,69 @@ pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -
 		_ => &StrokeJoin::Miter,
 	};
 
+	let align_value = match &document_node.inputs[AlignInput::INDEX].as_value() {
+		Some(TaggedValue::StrokeAlign(x)) => *x,
+		_ => StrokeAlign::Center,
+	};
+
 	let dash_lengths_val = match &document_node.inputs[DashLengthsInput::INDEX].as_value() {
 		Some(TaggedValue::VecF64(x)) => x,
 		_ => &vec![],
 	};
 	let has_dash_lengths = dash_lengths_val.is_empty();
-	let miter_limit_disabled = join_value != &StrokeJoin::Miter;
+	let markers_disabled = [StartMarkerInput::INDEX, EndMarkerInput::INDEX]
+		.iter()
+		.all(|&index| matches!(document_node.inputs[index].as_value(), Some(TaggedValue::StrokeMarker(StrokeMarker::None))));
+
+	let stroke_color = match document_node.inputs[ColorInput::<Option<Color>>::INDEX].as_non_exposed_value() {
+		Some(&TaggedValue::OptionalColor(Some(color))) => Some(color),
+		_ => None,
+	};
 
-	let color = color_widget(
+	let mut color = color_widget(
 		ParameterWidgetsInfo::new(node_id, ColorInput::<Option<Color>>::INDEX, true, context),
 		crate::messages::layout::utility_types::widgets::button_widgets::ColorInput::default(),
 	);
+	if let (LayoutGroup::Row { widgets }, Some(stroke_color)) = (&mut color, stroke_color) {
+		widgets.push(Separator::new(SeparatorType::Related).widget_holder());
+		widgets.push(swatch_link_dropdown(context.network_interface.swatches(), stroke_color, node_id, ColorInput::<Option<Color>>::INDEX));
+	}
 	let weight = number_widget(ParameterWidgetsInfo::new(node_id, WeightInput::INDEX, true, context), NumberInput::default().unit(" px").min(0.));
 	let align = enum_choice::<StrokeAlign>()
 		.for_socket(ParameterWidgetsInfo::new(node_id, AlignInput::INDEX, true, context))
 		.property_row();
+	let align_hint = align_value.is_not_centered().then(|| {
+		let mut widgets = vec![TextLabel::new("").widget_holder()];
+		add_blank_assist(&mut widgets);
+		widgets.extend_from_slice(&[
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			TextLabel::new("Open paths stay centered")
+				.italic(true)
+				.tooltip("Inside and outside alignment only apply to shapes made entirely of closed paths, so strokes on open paths are drawn centered")
+				.widget_holder(),
+		]);
+		LayoutGroup::Row { widgets }
+	});
 	let cap = enum_choice::<StrokeCap>().for_socket(ParameterWidgetsInfo::new(node_id, CapInput::INDEX, true, context)).property_row();
+	let start_marker = enum_choice::<StrokeMarker>()
+		.for_socket(ParameterWidgetsInfo::new(node_id, StartMarkerInput::INDEX, true, context))
+		.property_row();
+	let end_marker = enum_choice::<StrokeMarker>()
+		.for_socket(ParameterWidgetsInfo::new(node_id, EndMarkerInput::INDEX, true, context))
+		.property_row();
+	let marker_size = number_widget(
+		ParameterWidgetsInfo::new(node_id, MarkerSizeInput::INDEX, true, context),
+		NumberInput::default().unit("x").min(0.).disabled(markers_disabled),
+	);
 	let join = enum_choice::<StrokeJoin>()
 		.for_socket(ParameterWidgetsInfo::new(node_id, JoinInput::INDEX, true, context))
 		.property_row();
 
-	let miter_limit = number_widget(
-		ParameterWidgetsInfo::new(node_id, MiterLimitInput::INDEX, true, context),
-		NumberInput::default().min(0.).disabled(miter_limit_disabled),
-	);
+	// The miter limit only applies to miter joins, and SVG requires it to be at least 1
+	let miter_limit = (join_value == &StrokeJoin::Miter).then(|| {
+		let widgets = number_widget(ParameterWidgetsInfo::new(node_id, MiterLimitInput::INDEX, true, context), NumberInput::default().min(1.));
+		LayoutGroup::Row { widgets }
+	});
 	let paint_order = enum_choice::<PaintOrder>()
 		.for_socket(ParameterWidgetsInfo::new(node_id, PaintOrderInput::INDEX, true, context))
 		.property_row();
@@ -1812,21 +2771,16 @@ pub fn stroke_properties(node_id: NodeId, context: &mut NodePropertiesContext) -
 	let number_input = disabled_number_input;
 	let dash_offset = number_widget(ParameterWidgetsInfo::new(node_id, DashOffsetInput::INDEX, true, context), number_input);
 
-	vec![
-		color,
-		LayoutGroup::Row { widgets: weight },
-		align,
-		cap,
-		join,
-		LayoutGroup::Row { widgets: miter_limit },
-		paint_order,
-		LayoutGroup::Row { widgets: dash_lengths },
-		LayoutGroup::Row { widgets: dash_offset },
-	]
+	let mut layout = vec![color, LayoutGroup::Row { widgets: weight }, align];
+	layout.extend(align_hint);
+	layout.extend([cap, start_marker, end_marker, LayoutGroup::Row { widgets: marker_size }, join]);
+	layout.extend(miter_limit);
+	layout.extend([paint_order, LayoutGroup::Row { widgets: dash_lengths }, LayoutGroup::Row { widgets: dash_offset }]);
+	layout
 }
 
 pub fn offset_path_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
-	use graphene_std::vector::offset_path::*;
+	use graphene_std::path_bool::offset_path::*;
 
 	let number_input = NumberInput::default().unit(" px");
 	let distance = number_widget(ParameterWidgetsInfo::new(node_id, DistanceInput::INDEX, true, context), number_input);
@@ -1851,7 +2805,18 @@ pub fn offset_path_properties(node_id: NodeId, context: &mut NodePropertiesConte
 	});
 	let miter_limit = number_widget(ParameterWidgetsInfo::new(node_id, MiterLimitInput::INDEX, true, context), number_input);
 
-	vec![LayoutGroup::Row { widgets: distance }, join, LayoutGroup::Row { widgets: miter_limit }]
+	let open_paths = enum_choice::<OpenPathOffset>()
+		.for_socket(ParameterWidgetsInfo::new(node_id, OpenPathsInput::INDEX, true, context))
+		.property_row();
+	let clean_up_intersections = bool_widget(ParameterWidgetsInfo::new(node_id, CleanUpIntersectionsInput::INDEX, true, context), CheckboxInput::default());
+
+	vec![
+		LayoutGroup::Row { widgets: distance },
+		join,
+		LayoutGroup::Row { widgets: miter_limit },
+		open_paths,
+		LayoutGroup::Row { widgets: clean_up_intersections },
+	]
 }
 
 pub fn math_properties(node_id: NodeId, context: &mut NodePropertiesContext) -> Vec<LayoutGroup> {
@@ -1918,6 +2883,8 @@ pub struct ParameterWidgetsInfo<'a> {
 	input_type: FrontendGraphDataType,
 	blank_assist: bool,
 	exposeable: bool,
+	swatches: &'a DocumentSwatches,
+	curve_channel: CurveChannel,
 }
 
 impl<'a> ParameterWidgetsInfo<'a> {
@@ -1935,6 +2902,8 @@ impl<'a> ParameterWidgetsInfo<'a> {
 			input_type,
 			blank_assist,
 			exposeable: true,
+			swatches: context.network_interface.swatches(),
+			curve_channel: context.selected_curve_channels.get(&(node_id, index)).copied().unwrap_or_default(),
 		}
 	}
 }
@@ -2110,3 +3079,207 @@ pub mod choice {
 
 	pub struct ForValue<W>(PhantomData<W>);
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
+
+	const NODE_ID: NodeId = NodeId(1);
+
+	fn three_stops() -> GradientStops {
+		GradientStops::new(vec![(0., Color::BLACK), (0.5, Color::RED), (1., Color::WHITE)])
+	}
+
+	fn stops_widgets(stops: &GradientStops, selected: &[usize]) -> Vec<LayoutGroup> {
+		gradient_stops_widgets(stops, GradientInterpolation::Srgb, selected, NODE_ID, 0, TaggedValue::GradientStops)
+	}
+
+	fn row_widgets(group: &LayoutGroup) -> impl Iterator<Item = &Widget> {
+		let LayoutGroup::Row { widgets } = group else { panic!("Expected a row, found {group:?}") };
+		widgets.iter().map(|holder| &holder.widget)
+	}
+
+	fn buttons(group: &LayoutGroup) -> Vec<(&str, bool)> {
+		row_widgets(group)
+			.filter_map(|widget| match widget {
+				Widget::TextButton(button) => Some((button.label.as_str(), button.disabled)),
+				_ => None,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn gradient_stops_list_has_a_row_for_each_stop_followed_by_buttons() {
+		// Selected stops which no longer exist are ignored
+		let rows = stops_widgets(&three_stops(), &[0, 2, 5]);
+		assert_eq!(rows.len(), 4);
+
+		let checked: Vec<_> = rows[..3]
+			.iter()
+			.flat_map(|row| row_widgets(row).filter_map(|widget| if let Widget::CheckboxInput(checkbox) = widget { Some(checkbox.checked) } else { None }))
+			.collect();
+		assert_eq!(checked, [true, false, true]);
+		let positions: Vec<_> = rows[..3]
+			.iter()
+			.flat_map(|row| row_widgets(row).filter_map(|widget| if let Widget::NumberInput(input) = widget { input.value } else { None }))
+			.collect();
+		assert_eq!(positions, [0., 50., 100.]);
+
+		// Deleting two of the three stops would leave too few, and there's no stop between the selected pair to distribute
+		assert_eq!(buttons(&rows[3]), [("Add Stop", false), ("Delete", true), ("Distribute", true)]);
+
+		let rows = stops_widgets(&three_stops(), &[0, 1, 2]);
+		assert_eq!(buttons(&rows[3]), [("Add Stop", true), ("Delete", true), ("Distribute", false)]);
+	}
+
+	#[test]
+	fn editing_a_position_past_a_neighbor_reorders_the_stops_and_selection() {
+		let rows = stops_widgets(&three_stops(), &[0]);
+		let Some(Widget::NumberInput(position_input)) = row_widgets(&rows[0]).find(|widget| matches!(widget, Widget::NumberInput(_))) else {
+			panic!("Expected the first stop to have a position input");
+		};
+
+		let edited = NumberInput {
+			value: Some(75.),
+			..position_input.clone()
+		};
+		let Message::Batched { messages } = (position_input.on_update.callback)(&edited) else {
+			panic!("Expected the edit to update both the stops and the selection");
+		};
+
+		let reordered = GradientStops::new(vec![(0.5, Color::RED), (0.75, Color::BLACK), (1., Color::WHITE)]);
+		assert_eq!(
+			messages[0],
+			NodeGraphMessage::SetInputValue {
+				node_id: NODE_ID,
+				input_index: 0,
+				value: TaggedValue::GradientStops(reordered),
+			}
+			.into()
+		);
+		// The edited stop stays selected after moving past its neighbor
+		assert_eq!(
+			messages[1],
+			PropertiesPanelMessage::SelectGradientStops {
+				node_id: NODE_ID,
+				input_index: 0,
+				stops: vec![1],
+			}
+			.into()
+		);
+	}
+
+	/// The widgets generated for the hints of a node's input holding the value.
+	fn hinted_row(value: TaggedValue, hints: &InputHints) -> Vec<LayoutGroup> {
+		let document_node = DocumentNode {
+			inputs: vec![NodeInput::value(TaggedValue::None, true), NodeInput::value(value, false)],
+			..Default::default()
+		};
+		let swatches = DocumentSwatches::default();
+		let parameter_widgets_info = ParameterWidgetsInfo {
+			document_node: Some(&document_node),
+			node_id: NODE_ID,
+			index: 1,
+			name: "Input".to_string(),
+			description: String::new(),
+			input_type: FrontendGraphDataType::Number,
+			blank_assist: true,
+			exposeable: true,
+			swatches: &swatches,
+			curve_channel: CurveChannel::default(),
+		};
+		hinted_widgets(parameter_widgets_info, hints)
+	}
+
+	fn hinted_number_input_of(rows: &[LayoutGroup]) -> &NumberInput {
+		let Some(Widget::NumberInput(number_input)) = rows.iter().flat_map(row_widgets).find(|widget| matches!(widget, Widget::NumberInput(_))) else {
+			panic!("Expected the hints to generate a number input");
+		};
+		number_input
+	}
+
+	fn edit(number_input: &NumberInput, value: f64) -> Message {
+		let edited = NumberInput {
+			value: Some(value),
+			..number_input.clone()
+		};
+		(number_input.on_update.callback)(&edited)
+	}
+
+	#[test]
+	fn hints_generate_the_widget_of_an_input() {
+		let sweep_angle = InputHints::default().min(0.).max(360.).soft_range(0., 360.).widget(WidgetHint::Angle);
+		let rows = hinted_row(TaggedValue::F64(270.), &sweep_angle);
+		let number_input = hinted_number_input_of(&rows);
+		assert_eq!(number_input.mode, NumberInputMode::Range);
+		assert_eq!((number_input.min, number_input.max), (Some(0.), Some(360.)));
+		assert_eq!((number_input.range_min, number_input.range_max), (Some(0.), Some(360.)));
+		assert_eq!(number_input.unit, "°");
+
+		// Without a soft range, angles span a half turn in either direction and percentages span from none to all
+		let angle = hinted_number_input(&InputHints::default().widget(WidgetHint::Angle));
+		assert_eq!((angle.range_min, angle.range_max, angle.unit.as_str()), (Some(-180.), Some(180.), "°"));
+		let percent = hinted_number_input(&InputHints::default().widget(WidgetHint::Percent));
+		assert_eq!((percent.range_min, percent.range_max, percent.unit.as_str()), (Some(0.), Some(100.), "%"));
+
+		// A given unit replaces the widget's own, and inputs without a widget hint aren't sliders
+		let radius = hinted_number_input(&InputHints::default().min(0.).unit(InputUnit::Pixels));
+		assert_eq!((radius.mode, radius.min, radius.unit.as_str()), (NumberInputMode::Increment, Some(0.), " px"));
+
+		// Whole number values are edited as integers
+		let levels = InputHints::default().min(2.).max(255.).widget(WidgetHint::Slider);
+		let rows = hinted_row(TaggedValue::U32(4), &levels);
+		assert!(hinted_number_input_of(&rows).is_integer);
+
+		let tint = InputHints::default().widget(WidgetHint::Color);
+		let rows = hinted_row(TaggedValue::OptionalColor(Some(Color::BLACK)), &tint);
+		assert!(
+			rows.iter()
+				.flat_map(row_widgets)
+				.any(|widget| matches!(widget, Widget::ColorInput(color_input) if color_input.allow_none))
+		);
+	}
+
+	#[test]
+	fn values_beyond_the_hinted_limits_are_clamped() {
+		let sweep_angle = InputHints::default().min(0.).max(360.).widget(WidgetHint::Angle);
+		let rows = hinted_row(TaggedValue::F64(270.), &sweep_angle);
+		let number_input = hinted_number_input_of(&rows);
+		let set_value = |value| -> Message {
+			NodeGraphMessage::SetInputValue {
+				node_id: NODE_ID,
+				input_index: 1,
+				value,
+			}
+			.into()
+		};
+
+		assert_eq!(edit(number_input, 400.), set_value(TaggedValue::F64(360.)));
+		assert_eq!(edit(number_input, -10.), set_value(TaggedValue::F64(0.)));
+		assert_eq!(edit(number_input, 90.), set_value(TaggedValue::F64(90.)));
+
+		let levels = InputHints::default().min(2.).max(255.).widget(WidgetHint::Slider);
+		let rows = hinted_row(TaggedValue::U32(4), &levels);
+		assert_eq!(edit(hinted_number_input_of(&rows), 1000.), set_value(TaggedValue::U32(255)));
+	}
+
+	#[test]
+	fn node_definitions_provide_hints_for_their_inputs() {
+		let arc = resolve_document_node_type("Arc").expect("The Arc node should be defined");
+		let hints = |name: &str| {
+			let input_metadata = &arc.node_template.persistent_node_metadata.input_metadata;
+			let input = input_metadata
+				.iter()
+				.find(|input| input.persistent_metadata.input_name == name)
+				.expect("The Arc node should have the input");
+			input.persistent_metadata.hints.clone()
+		};
+
+		assert_eq!(hints("Radius").unit, Some(InputUnit::Pixels));
+		assert_eq!(hints("Start Angle").widget, Some(WidgetHint::Angle));
+		assert_eq!(hints("Sweep Angle").clamp(720.), 360.);
+		// Inputs without hints keep the widget generated from their type
+		assert!(hints("Arc Type").is_empty());
+	}
+}
@@ -96,6 +96,16 @@ pub struct FrontendNode {
 	pub ui_only: bool,
 }
 
+/// An error raised by a node during evaluation, which the node graph marks on the node at its path.
+#[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct FrontendNodeError {
+	#[serde(rename = "nodePath")]
+	pub node_path: Vec<graph_craft::document::NodeId>,
+	#[serde(rename = "inputIndex")]
+	pub input_index: Option<usize>,
+	pub message: String,
+}
+
 #[derive(Clone, Debug, Eq, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct FrontendNodeType {
 	pub name: Cow<'static, str>,
@@ -1,4 +1,6 @@
 pub mod grid_overlays;
+pub mod overlay_command_buffer;
+pub mod overlay_hit_test;
 mod overlays_message;
 mod overlays_message_handler;
 pub mod utility_functions;
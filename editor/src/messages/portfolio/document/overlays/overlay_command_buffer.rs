@@ -0,0 +1,482 @@
+use glam::{DAffine2, DVec2};
+use std::collections::HashMap;
+
+/// Side length, in viewport pixels, of the grid cells used to look up which recorded commands might overlap.
+const SPATIAL_CELL_SIZE: f64 = 64.;
+
+/// A single step of a path, mirroring the path-building calls of the canvas 2D API.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PathCommand {
+	MoveTo(DVec2),
+	LineTo(DVec2),
+	QuadraticCurveTo { handle: DVec2, end: DVec2 },
+	BezierCurveTo { handle_start: DVec2, handle_end: DVec2, end: DVec2 },
+	Arc { center: DVec2, radius: f64, start_angle: f64, end_angle: f64 },
+	Rect { corner: DVec2, size: DVec2 },
+	ClosePath,
+}
+
+/// A path recorded in viewport space along with its bounding box, used to decide if it can be drawn out of order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct RecordedPath {
+	pub commands: Vec<PathCommand>,
+	bounds: Option<[DVec2; 2]>,
+	current_point: Option<DVec2>,
+}
+
+impl RecordedPath {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	pub fn move_to(&mut self, point: DVec2) {
+		self.commands.push(PathCommand::MoveTo(point));
+		self.include(point);
+		self.current_point = Some(point);
+	}
+
+	pub fn line_to(&mut self, point: DVec2) {
+		self.begin_subpath_if_needed(point);
+		self.commands.push(PathCommand::LineTo(point));
+		self.include(point);
+		self.current_point = Some(point);
+	}
+
+	pub fn quadratic_curve_to(&mut self, handle: DVec2, end: DVec2) {
+		self.begin_subpath_if_needed(handle);
+		self.commands.push(PathCommand::QuadraticCurveTo { handle, end });
+		self.include(handle);
+		self.include(end);
+		self.current_point = Some(end);
+	}
+
+	pub fn bezier_curve_to(&mut self, handle_start: DVec2, handle_end: DVec2, end: DVec2) {
+		self.begin_subpath_if_needed(handle_start);
+		self.commands.push(PathCommand::BezierCurveTo { handle_start, handle_end, end });
+		self.include(handle_start);
+		self.include(handle_end);
+		self.include(end);
+		self.current_point = Some(end);
+	}
+
+	pub fn arc(&mut self, center: DVec2, radius: f64, start_angle: f64, end_angle: f64) {
+		// Unlike a lone canvas path, a recorded path may be merged after others, so it must never connect to whatever subpath came before it
+		self.begin_subpath_if_needed(center + radius * DVec2::from_angle(start_angle));
+		self.commands.push(PathCommand::Arc {
+			center,
+			radius,
+			start_angle,
+			end_angle,
+		});
+		self.include(center - DVec2::splat(radius));
+		self.include(center + DVec2::splat(radius));
+		self.current_point = Some(center + radius * DVec2::from_angle(end_angle));
+	}
+
+	pub fn rect(&mut self, corner: DVec2, size: DVec2) {
+		self.commands.push(PathCommand::Rect { corner, size });
+		self.include(corner);
+		self.include(corner + size);
+		self.current_point = Some(corner);
+	}
+
+	pub fn close_path(&mut self) {
+		self.commands.push(PathCommand::ClosePath);
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	/// The axis-aligned bounding box of every point in the path, including its control points.
+	pub fn bounds(&self) -> Option<[DVec2; 2]> {
+		self.bounds
+	}
+
+	fn begin_subpath_if_needed(&mut self, point: DVec2) {
+		if self.current_point.is_none() {
+			self.move_to(point);
+		}
+	}
+
+	fn include(&mut self, point: DVec2) {
+		self.bounds = Some(match self.bounds {
+			Some([min, max]) => [min.min(point), max.max(point)],
+			None => [point, point],
+		});
+	}
+}
+
+/// The canvas state that must be set before drawing a recorded path. Commands with equal styles can share a single draw call.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DrawStyle {
+	Fill {
+		color: String,
+	},
+	/// Fills with a repeating 4x4 pixel checkered pattern of the given sRGB color.
+	FillPattern {
+		color: [u8; 4],
+	},
+	Stroke {
+		color: String,
+		width: f64,
+		/// The dash width, gap width, and offset.
+		dash: Option<(f64, f64, f64)>,
+		round_cap: bool,
+	},
+}
+
+impl DrawStyle {
+	pub fn fill(color: &str) -> Self {
+		Self::Fill { color: color.to_string() }
+	}
+
+	pub fn stroke(color: &str, width: f64) -> Self {
+		Self::Stroke {
+			color: color.to_string(),
+			width,
+			dash: None,
+			round_cap: false,
+		}
+	}
+
+	/// Whether drawing two overlapping paths in this style as a single path looks the same as drawing them one after the other.
+	/// That's only the case for opaque strokes, since translucent colors compound where they overlap and the
+	/// nonzero winding rule can cut a hole where two fills with opposite windings overlap.
+	fn merges_overlaps(&self) -> bool {
+		match self {
+			Self::Stroke { color, .. } => {
+				let hex = color.trim_start_matches('#');
+				hex.len() != 8 || hex[6..8].eq_ignore_ascii_case("ff")
+			}
+			Self::Fill { .. } | Self::FillPattern { .. } => false,
+		}
+	}
+
+	/// Half the width of the stroke, by which the drawn area extends beyond the path's bounds.
+	fn outset(&self) -> f64 {
+		match self {
+			Self::Stroke { width, .. } => width / 2. + 1.,
+			_ => 1.,
+		}
+	}
+}
+
+/// Text which is drawn in place, without being merged with other commands.
+#[derive(Clone, PartialEq, Debug)]
+pub struct RecordedText {
+	pub text: String,
+	pub font_color: String,
+	/// The background color and the rectangle it fills, in the text's local space.
+	pub background: Option<(String, [f64; 4])>,
+	/// The full transform from the text's local space to canvas pixels, including the device pixel ratio.
+	pub transform: DAffine2,
+}
+
+#[derive(Clone, PartialEq, Debug)]
+pub enum DrawCommand {
+	Path { path: RecordedPath, style: DrawStyle },
+	Text(RecordedText),
+}
+
+/// A run of commands which are drawn together in a single call, at the position of the first of them.
+#[derive(Clone, PartialEq, Debug)]
+pub enum DrawBatch<'a> {
+	Paths { style: &'a DrawStyle, paths: Vec<&'a RecordedPath> },
+	Text(&'a RecordedText),
+}
+
+/// Records the overlay drawing commands issued by every overlay provider over the course of a frame so they can be flushed to the canvas at once.
+/// Crossing into JavaScript for every canvas call is expensive, so flushing groups commands of the same style into as few draw calls as possible.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct OverlayCommandBuffer {
+	commands: Vec<DrawCommand>,
+}
+
+impl OverlayCommandBuffer {
+	pub fn push_path(&mut self, path: RecordedPath, style: DrawStyle) {
+		if path.is_empty() {
+			return;
+		}
+		self.commands.push(DrawCommand::Path { path, style });
+	}
+
+	pub fn push_text(&mut self, text: RecordedText) {
+		self.commands.push(DrawCommand::Text(text));
+	}
+
+	pub fn len(&self) -> usize {
+		self.commands.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.commands.is_empty()
+	}
+
+	pub fn clear(&mut self) {
+		self.commands.clear();
+	}
+
+	/// The recorded commands in the order they were issued, as they'd be drawn one call each without batching.
+	pub fn commands(&self) -> &[DrawCommand] {
+		&self.commands
+	}
+
+	/// Groups the recorded commands into batches sharing a style, in the order they should be drawn.
+	///
+	/// A command is moved earlier into a batch with the same style only if that can't be told apart from drawing it in its recorded order.
+	/// That means it must not overlap anything drawn in between (which would otherwise end up above it), and unless the style
+	/// [merges overlaps](DrawStyle::merges_overlaps), it must not overlap the batch it joins either.
+	pub fn batches(&self) -> Vec<DrawBatch<'_>> {
+		struct PendingBatch<'a> {
+			style: Option<&'a DrawStyle>,
+			members: Vec<usize>,
+			occupancy: SpatialIndex,
+		}
+
+		let mut pending: Vec<PendingBatch> = Vec::new();
+
+		for (index, command) in self.commands.iter().enumerate() {
+			let (style, bounds) = match command {
+				DrawCommand::Path { path, style } => (Some(style), path.bounds().map(|[min, max]| [min - style.outset(), max + style.outset()])),
+				DrawCommand::Text(_) => (None, None),
+			};
+
+			// Look backwards for the earliest batch to join, stopping at the first one which this command would need to stay above.
+			// Joining the earliest one keeps the later batches free of this command, so the commands recorded after it can still move past them.
+			let mut target = None;
+			if let (Some(style), Some(bounds)) = (style, bounds) {
+				for (batch_index, batch) in pending.iter().enumerate().rev() {
+					let overlaps = batch.occupancy.overlaps(bounds);
+					let joinable = batch.style == Some(style) && (!overlaps || style.merges_overlaps());
+					if joinable {
+						target = Some(batch_index);
+					} else if overlaps {
+						break;
+					}
+				}
+			}
+
+			let batch_index = target.unwrap_or_else(|| {
+				pending.push(PendingBatch {
+					style,
+					members: Vec::new(),
+					occupancy: SpatialIndex::default(),
+				});
+				pending.len() - 1
+			});
+
+			let batch = &mut pending[batch_index];
+			batch.members.push(index);
+			match bounds {
+				Some(bounds) => batch.occupancy.insert(bounds),
+				// Commands without known bounds (like text) could cover anything, so nothing may be moved past them
+				None => batch.occupancy.cover_everything(),
+			}
+		}
+
+		pending
+			.into_iter()
+			.map(|batch| match (batch.style, &self.commands[batch.members[0]]) {
+				(Some(style), _) => DrawBatch::Paths {
+					style,
+					paths: batch
+						.members
+						.iter()
+						.filter_map(|&index| match &self.commands[index] {
+							DrawCommand::Path { path, .. } => Some(path),
+							DrawCommand::Text(_) => None,
+						})
+						.collect(),
+				},
+				(None, DrawCommand::Text(text)) => DrawBatch::Text(text),
+				(None, DrawCommand::Path { .. }) => unreachable!("Path commands always have a style"),
+			})
+			.collect()
+	}
+}
+
+/// A coarse grid of the areas already drawn by a batch, for quickly testing if a new command overlaps any of them.
+#[derive(Default)]
+struct SpatialIndex {
+	cells: HashMap<(i64, i64), Vec<[DVec2; 2]>>,
+	/// Areas spanning too many cells to be worth indexing (such as paths reaching far outside the viewport), which are checked one by one.
+	oversized: Vec<[DVec2; 2]>,
+	covers_everything: bool,
+}
+
+impl SpatialIndex {
+	/// The largest number of cells a single area is spread across before it's stored as oversized instead.
+	const MAX_CELLS_PER_AREA: i128 = 1024;
+
+	fn cell_range(bounds: [DVec2; 2]) -> Option<impl Iterator<Item = (i64, i64)>> {
+		let [min, max] = bounds.map(|point| (point / SPATIAL_CELL_SIZE).floor().as_i64vec2());
+		let cell_count = (max.x as i128 - min.x as i128 + 1) * (max.y as i128 - min.y as i128 + 1);
+		(cell_count <= Self::MAX_CELLS_PER_AREA).then(|| (min.x..=max.x).flat_map(move |x| (min.y..=max.y).map(move |y| (x, y))))
+	}
+
+	fn insert(&mut self, bounds: [DVec2; 2]) {
+		match Self::cell_range(bounds) {
+			Some(cells) => cells.for_each(|cell| self.cells.entry(cell).or_default().push(bounds)),
+			None => self.oversized.push(bounds),
+		}
+	}
+
+	fn cover_everything(&mut self) {
+		self.covers_everything = true;
+	}
+
+	fn overlaps(&self, bounds: [DVec2; 2]) -> bool {
+		if self.covers_everything {
+			return true;
+		}
+
+		let [min, max] = bounds;
+		let intersects = |[other_min, other_max]: &[DVec2; 2]| min.x <= other_max.x && max.x >= other_min.x && min.y <= other_max.y && max.y >= other_min.y;
+
+		if self.oversized.iter().any(intersects) {
+			return true;
+		}
+		match Self::cell_range(bounds) {
+			Some(mut cells) => cells.any(|cell| self.cells.get(&cell).is_some_and(|entries| entries.iter().any(intersects))),
+			None => self.cells.values().flatten().any(intersects),
+		}
+	}
+}
+
+#[cfg(test)]
+mod test_overlay_command_buffer {
+	use super::*;
+
+	fn square(center: DVec2) -> RecordedPath {
+		let mut path = RecordedPath::new();
+		path.rect(center - DVec2::splat(3.), DVec2::splat(6.));
+		path
+	}
+
+	fn batch_sizes(buffer: &OverlayCommandBuffer) -> Vec<usize> {
+		buffer
+			.batches()
+			.iter()
+			.map(|batch| match batch {
+				DrawBatch::Paths { paths, .. } => paths.len(),
+				DrawBatch::Text(_) => 1,
+			})
+			.collect()
+	}
+
+	#[test]
+	fn separate_anchors_are_merged_by_style() {
+		let mut buffer = OverlayCommandBuffer::default();
+		for i in 0..100 {
+			let center = DVec2::new(i as f64 * 20., 0.);
+			buffer.push_path(square(center), DrawStyle::fill("#ffffff"));
+			buffer.push_path(square(center), DrawStyle::stroke("#00a8ff", 1.));
+		}
+
+		let batches = buffer.batches();
+		assert_eq!(batch_sizes(&buffer), vec![100, 100]);
+		assert!(matches!(batches[0], DrawBatch::Paths { style: DrawStyle::Fill { .. }, .. }));
+		assert!(matches!(batches[1], DrawBatch::Paths { style: DrawStyle::Stroke { .. }, .. }));
+	}
+
+	#[test]
+	fn overlapping_commands_keep_their_order() {
+		let mut buffer = OverlayCommandBuffer::default();
+		// The second anchor's white fill must stay above the first anchor's stroke, so it can't join the first fill
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::fill("#ffffff"));
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::stroke("#00a8ff", 1.));
+		buffer.push_path(square(DVec2::new(4., 0.)), DrawStyle::fill("#ffffff"));
+		buffer.push_path(square(DVec2::new(4., 0.)), DrawStyle::stroke("#00a8ff", 1.));
+
+		assert_eq!(batch_sizes(&buffer), vec![1, 1, 1, 1]);
+	}
+
+	#[test]
+	fn consecutive_opaque_stroke_overlaps_are_merged() {
+		let mut buffer = OverlayCommandBuffer::default();
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::stroke("#00a8ff", 1.));
+		buffer.push_path(square(DVec2::new(2., 0.)), DrawStyle::stroke("#00a8ff", 1.));
+
+		assert_eq!(batch_sizes(&buffer), vec![2]);
+	}
+
+	#[test]
+	fn anchors_with_handles_are_merged_by_style() {
+		// Each anchor's handle line is overlapped by its handle and anchor, so it must stay below them while joining the earlier lines
+		let mut buffer = OverlayCommandBuffer::default();
+		for i in 0..100 {
+			let anchor = DVec2::new(i as f64 * 40., 0.);
+			let handle = anchor + DVec2::new(12., -8.);
+
+			let mut line = RecordedPath::new();
+			line.move_to(anchor);
+			line.line_to(handle);
+			buffer.push_path(line, DrawStyle::stroke("#00a8ff", 1.));
+			buffer.push_path(square(handle), DrawStyle::fill("#ffffff"));
+			buffer.push_path(square(handle), DrawStyle::stroke("#00a8ff", 1.));
+			buffer.push_path(square(anchor), DrawStyle::fill("#ffffff"));
+			buffer.push_path(square(anchor), DrawStyle::stroke("#00a8ff", 1.));
+		}
+
+		assert_eq!(batch_sizes(&buffer), vec![100, 200, 200]);
+	}
+
+	#[test]
+	fn translucent_overlaps_are_not_merged() {
+		let mut buffer = OverlayCommandBuffer::default();
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::stroke("#00a8ff80", 1.));
+		buffer.push_path(square(DVec2::new(2., 0.)), DrawStyle::stroke("#00a8ff80", 1.));
+		buffer.push_path(square(DVec2::new(100., 0.)), DrawStyle::stroke("#00a8ff80", 1.));
+
+		assert_eq!(batch_sizes(&buffer), vec![2, 1]);
+	}
+
+	#[test]
+	fn overlapping_fills_are_not_merged() {
+		// Combined into one path, fills with opposite windings would cancel out where they overlap
+		let mut buffer = OverlayCommandBuffer::default();
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::fill("#ffffff"));
+		buffer.push_path(square(DVec2::new(2., 0.)), DrawStyle::fill("#ffffff"));
+
+		assert_eq!(batch_sizes(&buffer), vec![1, 1]);
+	}
+
+	#[test]
+	fn far_off_screen_paths_are_still_ordered() {
+		let mut long_line = RecordedPath::new();
+		long_line.move_to(DVec2::new(-1e9, 0.));
+		long_line.line_to(DVec2::new(1e9, 0.));
+
+		let mut buffer = OverlayCommandBuffer::default();
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::fill("#ffffff"));
+		buffer.push_path(long_line, DrawStyle::stroke("#00a8ff", 1.));
+		buffer.push_path(square(DVec2::new(1000., 0.)), DrawStyle::fill("#ffffff"));
+
+		assert_eq!(batch_sizes(&buffer), vec![1, 1, 1]);
+	}
+
+	#[test]
+	fn text_is_a_barrier() {
+		let mut buffer = OverlayCommandBuffer::default();
+		buffer.push_path(square(DVec2::ZERO), DrawStyle::fill("#ffffff"));
+		buffer.push_text(RecordedText {
+			text: "100".to_string(),
+			font_color: "#00a8ff".to_string(),
+			background: None,
+			transform: DAffine2::IDENTITY,
+		});
+		buffer.push_path(square(DVec2::new(100., 0.)), DrawStyle::fill("#ffffff"));
+
+		assert_eq!(batch_sizes(&buffer), vec![1, 1, 1]);
+	}
+
+	#[test]
+	fn leading_arc_starts_its_own_subpath() {
+		let mut path = RecordedPath::new();
+		path.arc(DVec2::new(10., 0.), 5., 0., std::f64::consts::TAU);
+
+		assert_eq!(path.commands[0], PathCommand::MoveTo(DVec2::new(15., 0.)));
+		assert_eq!(path.bounds(), Some([DVec2::new(5., -5.), DVec2::new(15., 5.)]));
+	}
+}
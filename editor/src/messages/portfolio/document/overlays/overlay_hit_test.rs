@@ -0,0 +1,244 @@
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::tool::common_functionality::gizmos::shape_gizmos::sweep_angle_gizmo::EndpointType;
+use glam::DVec2;
+use graphene_std::vector::ManipulatorPointId;
+use std::collections::HashSet;
+
+/// Identifies an interactive element drawn in the overlays, which clicks can be resolved against using [`OverlayHitRegions::hit_test`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+pub enum OverlayHitId {
+	/// An anchor or handle of a selected layer's vector data.
+	ManipulatorPoint { layer: LayerNodeIdentifier, point: ManipulatorPointId },
+	/// One of the two endpoint handles of the sweep angle gizmo of an arc.
+	SweepAngleEndpoint { layer: LayerNodeIdentifier, endpoint: EndpointType },
+}
+
+/// The clickable area of an overlay element, in the same viewport space as the drawing functions of the overlay context.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum OverlayHitShape {
+	Circle {
+		center: DVec2,
+		radius: f64,
+	},
+	Rect {
+		min: DVec2,
+		max: DVec2,
+	},
+	/// A line segment, which is hit anywhere within `slop` of it.
+	Segment {
+		start: DVec2,
+		end: DVec2,
+		slop: f64,
+	},
+}
+
+impl OverlayHitShape {
+	/// How far the position lies outside of the shape, which is zero if it's inside.
+	fn distance_outside(&self, position: DVec2) -> f64 {
+		match *self {
+			Self::Circle { center, radius } => (position.distance(center) - radius).max(0.),
+			Self::Rect { min, max } => (position.clamp(min.min(max), min.max(max)) - position).length(),
+			Self::Segment { start, end, slop } => {
+				let direction = end - start;
+				let length_squared = direction.length_squared();
+				let t = if length_squared > 0. {
+					((position - start).dot(direction) / length_squared).clamp(0., 1.)
+				} else {
+					0.
+				};
+				(position.distance(start + t * direction) - slop).max(0.)
+			}
+		}
+	}
+
+	/// How far the position lies from the element the shape was registered for, such as the point at the center of a circle, which tells apart overlapping shapes.
+	fn distance_to_element(&self, position: DVec2) -> f64 {
+		match *self {
+			Self::Circle { center, .. } => position.distance(center),
+			Self::Rect { min, max } => position.distance(min.midpoint(max)),
+			Self::Segment { start, end, .. } => Self::Segment { start, end, slop: 0. }.distance_outside(position),
+		}
+	}
+}
+
+/// The hit regions registered while drawing the most recent overlays frame, in the order they were drawn.
+#[derive(Clone, PartialEq, Debug)]
+pub struct OverlayHitRegions {
+	regions: Vec<(OverlayHitId, OverlayHitShape)>,
+	device_pixel_ratio: f64,
+}
+
+impl Default for OverlayHitRegions {
+	fn default() -> Self {
+		Self {
+			regions: Vec::new(),
+			device_pixel_ratio: 1.,
+		}
+	}
+}
+
+impl OverlayHitRegions {
+	/// Discards the regions of the previous frame before the providers draw a new one.
+	pub fn begin_frame(&mut self, device_pixel_ratio: f64) {
+		self.regions.clear();
+		self.device_pixel_ratio = device_pixel_ratio;
+	}
+
+	pub fn register(&mut self, id: OverlayHitId, shape: OverlayHitShape) {
+		self.regions.push((id, shape));
+	}
+
+	pub fn len(&self) -> usize {
+		self.regions.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.regions.is_empty()
+	}
+
+	/// Whether any region was registered for an element matching the predicate, wherever it was drawn.
+	pub fn contains(&self, predicate: impl Fn(OverlayHitId) -> bool) -> bool {
+		self.regions.iter().any(|&(id, _)| predicate(id))
+	}
+
+	/// Returns the id of every region containing the position, ordered front-to-back (the most recently drawn first), each listed once.
+	pub fn hit_test(&self, position: DVec2) -> Vec<OverlayHitId> {
+		let mut seen = HashSet::new();
+		self.hits(position).filter_map(|&(id, _)| seen.insert(id).then_some(id)).collect()
+	}
+
+	/// Returns the id of every region containing the position like [`Self::hit_test`], but ordered by how near the position is to the element each region belongs to.
+	/// Elements at the same distance are ordered front-to-back.
+	///
+	/// This picks the intended element among overlapping regions, such as a handle inside the larger clickable area of the anchor drawn over it.
+	pub fn hit_test_nearest(&self, position: DVec2) -> Vec<OverlayHitId> {
+		let mut hits: Vec<(OverlayHitId, f64)> = Vec::new();
+		for &(id, shape) in self.hits(position) {
+			let distance = shape.distance_to_element(position);
+			match hits.iter_mut().find(|(hit_id, _)| *hit_id == id) {
+				Some((_, nearest)) => *nearest = nearest.min(distance),
+				None => hits.push((id, distance)),
+			}
+		}
+
+		// The sort is stable, so ties keep their front-to-back order
+		hits.sort_by(|(_, a), (_, b)| a.total_cmp(b));
+		hits.into_iter().map(|(id, _)| id).collect()
+	}
+
+	/// The regions containing the position, ordered front-to-back.
+	///
+	/// Since anti-aliasing bleeds the drawn edge of a shape up to half a physical pixel past its geometry, positions within that distance also count as a hit.
+	fn hits(&self, position: DVec2) -> impl Iterator<Item = &(OverlayHitId, OverlayHitShape)> {
+		let tolerance = 0.5 / self.device_pixel_ratio.max(f64::EPSILON);
+		self.regions.iter().rev().filter(move |(_, shape)| shape.distance_outside(position) <= tolerance)
+	}
+}
+
+#[cfg(test)]
+mod test_overlay_hit_test {
+	use super::*;
+	use graph_craft::document::NodeId;
+	use graphene_std::vector::PointId;
+
+	fn id(index: u64) -> OverlayHitId {
+		OverlayHitId::ManipulatorPoint {
+			layer: LayerNodeIdentifier::new_unchecked(NodeId(index + 1)),
+			point: ManipulatorPointId::Anchor(PointId::ZERO),
+		}
+	}
+
+	fn circle(x: f64) -> OverlayHitShape {
+		OverlayHitShape::Circle {
+			center: DVec2::new(x, 0.),
+			radius: 5.,
+		}
+	}
+
+	#[test]
+	fn hits_are_ordered_front_to_back() {
+		let mut regions = OverlayHitRegions::default();
+		regions.register(id(0), circle(0.));
+		regions.register(id(1), circle(2.));
+		regions.register(id(2), circle(100.));
+
+		assert_eq!(regions.hit_test(DVec2::new(1., 0.)), vec![id(1), id(0)]);
+		assert_eq!(regions.hit_test(DVec2::new(50., 0.)), vec![]);
+	}
+
+	#[test]
+	fn repeated_ids_are_listed_once_at_their_frontmost_position() {
+		let mut regions = OverlayHitRegions::default();
+		regions.register(id(0), circle(0.));
+		regions.register(id(1), circle(0.));
+		regions.register(
+			id(0),
+			OverlayHitShape::Rect {
+				min: DVec2::splat(-1.),
+				max: DVec2::splat(1.),
+			},
+		);
+
+		assert_eq!(regions.hit_test(DVec2::ZERO), vec![id(0), id(1)]);
+	}
+
+	#[test]
+	fn nearest_hits_come_first_regardless_of_drawing_order() {
+		let mut regions = OverlayHitRegions::default();
+		regions.register(id(0), circle(0.));
+		regions.register(id(1), circle(4.));
+		regions.register(id(2), circle(8.));
+
+		// The circle at 4 is drawn over the one at 0, but the position is nearest to the center of the one at 0
+		assert_eq!(regions.hit_test(DVec2::new(1., 0.)), vec![id(1), id(0)]);
+		assert_eq!(regions.hit_test_nearest(DVec2::new(1., 0.)), vec![id(0), id(1)]);
+		assert_eq!(regions.hit_test_nearest(DVec2::new(7., 0.)), vec![id(2), id(1)]);
+
+		// At equal distances, the frontmost comes first
+		assert_eq!(regions.hit_test_nearest(DVec2::new(2., 0.)), vec![id(1), id(0)]);
+	}
+
+	#[test]
+	fn segment_slop() {
+		let mut regions = OverlayHitRegions::default();
+		regions.register(
+			id(0),
+			OverlayHitShape::Segment {
+				start: DVec2::ZERO,
+				end: DVec2::new(100., 0.),
+				slop: 3.,
+			},
+		);
+
+		assert_eq!(regions.hit_test(DVec2::new(50., 3.)), vec![id(0)]);
+		assert_eq!(regions.hit_test(DVec2::new(50., -3.4)), vec![id(0)]);
+		assert_eq!(regions.hit_test(DVec2::new(50., 4.)), vec![]);
+		// The slop also extends past the ends of the segment
+		assert_eq!(regions.hit_test(DVec2::new(102., 0.)), vec![id(0)]);
+		assert_eq!(regions.hit_test(DVec2::new(104., 0.)), vec![]);
+	}
+
+	#[test]
+	fn edge_tolerance_is_half_a_physical_pixel() {
+		let mut regions = OverlayHitRegions::default();
+		let position = DVec2::new(5.4, 0.);
+
+		regions.begin_frame(1.);
+		regions.register(id(0), circle(0.));
+		assert_eq!(regions.hit_test(position), vec![id(0)]);
+
+		regions.begin_frame(2.);
+		regions.register(id(0), circle(0.));
+		assert_eq!(regions.hit_test(position), vec![]);
+	}
+
+	#[test]
+	fn regions_are_cleared_each_frame() {
+		let mut regions = OverlayHitRegions::default();
+		regions.register(id(0), circle(0.));
+		regions.begin_frame(1.);
+
+		assert!(regions.is_empty());
+		assert_eq!(regions.hit_test(DVec2::ZERO), vec![]);
+	}
+}
@@ -1,4 +1,4 @@
-use super::utility_types::{OverlayProvider, empty_provider};
+use super::utility_types::{OverlayProviderHandle, OverlayProviderOwner, empty_provider_handle};
 use crate::messages::prelude::*;
 
 #[impl_message(Message, DocumentMessage, Overlays)]
@@ -6,15 +6,23 @@ use crate::messages::prelude::*;
 #[derivative(Debug, PartialEq)]
 pub enum OverlaysMessage {
 	Draw,
+	/// Draws the commands recorded by every overlay provider during [`OverlaysMessage::Draw`] to the canvas at once.
+	/// In the native app, the frame's scenes are instead handed over to be rendered into textures.
+	Flush,
+	/// Draws the overlays again if a provider requested it for an animation during the previous frame.
+	AnimationFrame,
 	// Serde functionality isn't used but is required by the message system macros
 	AddProvider(
-		#[serde(skip, default = "empty_provider")]
+		#[serde(skip, default = "empty_provider_handle")]
 		#[derivative(Debug = "ignore", PartialEq = "ignore")]
-		OverlayProvider,
+		OverlayProviderHandle,
 	),
 	RemoveProvider(
-		#[serde(skip, default = "empty_provider")]
+		#[serde(skip, default = "empty_provider_handle")]
 		#[derivative(Debug = "ignore", PartialEq = "ignore")]
-		OverlayProvider,
+		OverlayProviderHandle,
 	),
+	RemoveOwnerProviders {
+		owner: OverlayProviderOwner,
+	},
 }
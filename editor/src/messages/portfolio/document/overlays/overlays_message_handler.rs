@@ -1,4 +1,6 @@
-use super::utility_types::{OverlayProvider, OverlaysVisibilitySettings};
+use super::overlay_hit_test::{OverlayHitId, OverlayHitRegions};
+use super::utility_types::{OverlayLayer, OverlayProviderHandle, OverlayProviderOwner, OverlaysVisibilitySettings};
+use crate::messages::preferences::OverlayQuality;
 use crate::messages::prelude::*;
 
 #[derive(ExtractField)]
@@ -6,21 +8,33 @@ pub struct OverlaysMessageContext<'a> {
 	pub visibility_settings: OverlaysVisibilitySettings,
 	pub ipp: &'a InputPreprocessorMessageHandler,
 	pub device_pixel_ratio: f64,
+	pub quality: OverlayQuality,
 }
 
 #[derive(Debug, Clone, Default, ExtractField)]
 pub struct OverlaysMessageHandler {
-	pub overlay_providers: HashSet<OverlayProvider>,
+	pub overlay_providers: HashSet<OverlayProviderHandle>,
+	hit_regions: std::rc::Rc<std::cell::RefCell<OverlayHitRegions>>,
+	animating: std::rc::Rc<std::cell::Cell<bool>>,
 	#[cfg(target_arch = "wasm32")]
-	canvas: Option<web_sys::HtmlCanvasElement>,
+	canvases: HashMap<OverlayLayer, (web_sys::HtmlCanvasElement, web_sys::CanvasRenderingContext2d)>,
 	#[cfg(target_arch = "wasm32")]
-	context: Option<web_sys::CanvasRenderingContext2d>,
+	commands: HashMap<OverlayLayer, std::rc::Rc<std::cell::RefCell<super::overlay_command_buffer::OverlayCommandBuffer>>>,
+	#[cfg(not(target_arch = "wasm32"))]
+	scenes: super::utility_types::OverlayScenes,
+	#[cfg(not(target_arch = "wasm32"))]
+	render_settings: super::utility_types::OverlayRenderSettings,
+	// The most recently finished frame, until the app takes it to be rendered
+	#[cfg(not(target_arch = "wasm32"))]
+	finished_frame: Option<super::utility_types::OverlayFrame>,
 }
 
 #[message_handler_data]
 impl MessageHandler<OverlaysMessage, OverlaysMessageContext<'_>> for OverlaysMessageHandler {
 	fn process_message(&mut self, message: OverlaysMessage, responses: &mut VecDeque<Message>, context: OverlaysMessageContext) {
-		let OverlaysMessageContext { visibility_settings, ipp, .. } = context;
+		let OverlaysMessageContext {
+			visibility_settings, ipp, quality, ..
+		} = context;
 		let device_pixel_ratio = context.device_pixel_ratio;
 
 		match message {
@@ -31,85 +45,368 @@ impl MessageHandler<OverlaysMessage, OverlaysMessageContext<'_>> for OverlaysMes
 				use glam::{DAffine2, DVec2};
 				use wasm_bindgen::JsCast;
 
-				let canvas = match &self.canvas {
-					Some(canvas) => canvas,
-					None => {
-						let Some(new_canvas) = overlay_canvas_element() else { return };
-						self.canvas.get_or_insert(new_canvas)
-					}
-				};
+				let size = ipp.viewport_bounds.size().as_uvec2();
 
-				let canvas_context = self.context.get_or_insert_with(|| {
-					let canvas_context = canvas.get_context("2d").ok().flatten().expect("Failed to get canvas context");
-					canvas_context.dyn_into().expect("Context should be a canvas 2d context")
-				});
+				self.hit_regions.borrow_mut().begin_frame(device_pixel_ratio);
+				self.animating.set(false);
 
-				let size = ipp.viewport_bounds.size().as_uvec2();
+				for layer in OverlayLayer::ALL {
+					let canvas_context = match self.canvases.get(&layer) {
+						Some((_, canvas_context)) => canvas_context.clone(),
+						None => {
+							let Some(canvas) = overlay_canvas_element(layer) else { continue };
+							let canvas_context = canvas.get_context("2d").ok().flatten().expect("Failed to get canvas context");
+							let canvas_context: web_sys::CanvasRenderingContext2d = canvas_context.dyn_into().expect("Context should be a canvas 2d context");
+							self.canvases.insert(layer, (canvas, canvas_context.clone()));
+							canvas_context
+						}
+					};
 
-				let [a, b, c, d, e, f] = DAffine2::from_scale(DVec2::splat(device_pixel_ratio)).to_cols_array();
-				let _ = canvas_context.set_transform(a, b, c, d, e, f);
-				canvas_context.clear_rect(0., 0., ipp.viewport_bounds.size().x, ipp.viewport_bounds.size().y);
-				let _ = canvas_context.reset_transform();
+					let [a, b, c, d, e, f] = DAffine2::from_scale(DVec2::splat(device_pixel_ratio)).to_cols_array();
+					let _ = canvas_context.set_transform(a, b, c, d, e, f);
+					canvas_context.clear_rect(0., 0., ipp.viewport_bounds.size().x, ipp.viewport_bounds.size().y);
+					let _ = canvas_context.reset_transform();
 
-				if visibility_settings.all() {
-					responses.add(DocumentMessage::GridOverlays(OverlayContext {
-						render_context: canvas_context.clone(),
+					let commands = self.commands.entry(layer).or_default().clone();
+					commands.borrow_mut().clear();
+
+					if !visibility_settings.all() {
+						continue;
+					}
+
+					let overlay_context = OverlayContext {
+						render_context: canvas_context,
+						commands,
+						hit_regions: self.hit_regions.clone(),
+						animating: self.animating.clone(),
 						size: size.as_dvec2(),
 						device_pixel_ratio,
 						visibility_settings: visibility_settings.clone(),
-					}));
-					for provider in &self.overlay_providers {
-						responses.add(provider(OverlayContext {
-							render_context: canvas_context.clone(),
-							size: size.as_dvec2(),
-							device_pixel_ratio,
-							visibility_settings: visibility_settings.clone(),
-						}));
+					};
+
+					// The grid is drawn beneath the artwork so it doesn't overprint it
+					if layer == OverlayLayer::Background {
+						responses.add(DocumentMessage::GridOverlays(overlay_context.clone()));
+					}
+					for handle in self.providers_in_layer(layer) {
+						responses.add((handle.provider)(overlay_context.clone()));
 					}
 				}
+
+				// The providers only record their drawing commands, so they're drawn once all of them have run
+				responses.add(OverlaysMessage::Flush);
+			}
+			#[cfg(target_arch = "wasm32")]
+			OverlaysMessage::Flush => {
+				use super::utility_types::flush_overlay_commands;
+
+				for (layer, (_, canvas_context)) in &self.canvases {
+					let Some(commands) = self.commands.get(layer) else { continue };
+					flush_overlay_commands(canvas_context, &mut commands.borrow_mut(), device_pixel_ratio);
+				}
 			}
-			#[cfg(test)]
-			OverlaysMessage::Draw => {}
-			#[cfg(all(not(target_arch = "wasm32"), not(test)))]
+			#[cfg(not(target_arch = "wasm32"))]
 			OverlaysMessage::Draw => {
-				use super::utility_types::OverlayContext;
-				use vello::Scene;
+				use super::utility_types::{OverlayContext, OverlayRenderSettings};
 
 				let size = ipp.viewport_bounds.size().as_uvec2();
 
-				let scene = Scene::new();
+				// Recomputed every frame so changes to the quality preference or viewport size apply to the next frame
+				self.render_settings = OverlayRenderSettings::new(ipp.viewport_bounds.size(), device_pixel_ratio, quality);
+				let render_scale = self.render_settings.render_scale;
 
-				if visibility_settings.all() {
-					let overlay_context = OverlayContext {
-						scene,
-						size: size.as_dvec2(),
-						device_pixel_ratio,
-						visibility_settings,
-					};
-
-					responses.add(DocumentMessage::GridOverlays(overlay_context.clone()));
+				self.scenes.begin_frame();
+				self.hit_regions.borrow_mut().begin_frame(device_pixel_ratio);
+				self.animating.set(false);
 
-					for provider in &self.overlay_providers {
+				if visibility_settings.all() {
+					for layer in OverlayLayer::ALL {
 						let overlay_context = OverlayContext {
-							scene: Scene::new(),
+							scene: self.scenes.layer(layer),
+							hit_regions: self.hit_regions.clone(),
+							animating: self.animating.clone(),
 							size: size.as_dvec2(),
 							device_pixel_ratio,
+							render_scale,
 							visibility_settings,
 						};
-						responses.add(provider(overlay_context));
+
+						// The grid is drawn beneath the artwork so it doesn't overprint it
+						if layer == OverlayLayer::Background {
+							responses.add(DocumentMessage::GridOverlays(overlay_context.clone()));
+						}
+						for handle in self.providers_in_layer(layer) {
+							responses.add((handle.provider)(overlay_context.clone()));
+						}
 					}
 				}
 
-				// TODO: Render the Vello scene to a texture and display it
+				// The providers draw into the scenes as their messages are handled, so the frame is finished once all of them have run
+				responses.add(OverlaysMessage::Flush);
+			}
+			#[cfg(not(target_arch = "wasm32"))]
+			OverlaysMessage::Flush => {
+				use super::utility_types::OverlayFrame;
+
+				// Replaces a frame the app hasn't taken yet, since only the latest one is shown
+				self.finished_frame = Some(OverlayFrame {
+					background: self.scenes.scene(OverlayLayer::Background),
+					foreground: self.scenes.scene(OverlayLayer::Foreground),
+					settings: self.render_settings,
+				});
+			}
+			OverlaysMessage::AnimationFrame => {
+				if self.animating.get() {
+					responses.add(OverlaysMessage::Draw);
+				}
 			}
-			OverlaysMessage::AddProvider(message) => {
-				self.overlay_providers.insert(message);
+			OverlaysMessage::AddProvider(handle) => {
+				self.overlay_providers.insert(handle);
 			}
-			OverlaysMessage::RemoveProvider(message) => {
-				self.overlay_providers.remove(&message);
+			OverlaysMessage::RemoveProvider(handle) => {
+				self.overlay_providers.remove(&handle);
+			}
+			OverlaysMessage::RemoveOwnerProviders { owner } => {
+				// Owners are expected to remove their own providers before being deactivated, so anything still registered at this point has leaked
+				let leaked = self.providers_owned_by(owner).collect::<Vec<_>>();
+				if !leaked.is_empty() {
+					log::warn!("{} overlay provider(s) outlived their owner {owner:?} and were removed: {leaked:?}", leaked.len());
+				}
+
+				self.overlay_providers.retain(|handle| handle.owner != owner);
 			}
 		}
 	}
 
 	advertise_actions!(OverlaysMessage;);
 }
+
+impl OverlaysMessageHandler {
+	/// The registered overlay providers which draw in the given layer.
+	pub fn providers_in_layer(&self, layer: OverlayLayer) -> impl Iterator<Item = &OverlayProviderHandle> {
+		self.overlay_providers.iter().filter(move |handle| handle.layer == layer)
+	}
+
+	/// The registered overlay providers which belong to the given owner.
+	pub fn providers_owned_by(&self, owner: OverlayProviderOwner) -> impl Iterator<Item = &OverlayProviderHandle> {
+		self.overlay_providers.iter().filter(move |handle| handle.owner == owner)
+	}
+
+	/// The interactive overlay elements under the position in the most recently drawn frame, ordered front-to-back.
+	pub fn hit_test(&self, position: glam::DVec2) -> Vec<OverlayHitId> {
+		self.hit_regions.borrow().hit_test(position)
+	}
+
+	/// The interactive overlay elements under the position in the most recently drawn frame, ordered from the nearest to the farthest.
+	pub fn hit_test_nearest(&self, position: glam::DVec2) -> Vec<OverlayHitId> {
+		self.hit_regions.borrow().hit_test_nearest(position)
+	}
+
+	/// Whether the most recently drawn frame registered any interactive overlay element matching the predicate.
+	pub fn has_hit_region(&self, predicate: impl Fn(OverlayHitId) -> bool) -> bool {
+		self.hit_regions.borrow().contains(predicate)
+	}
+
+	/// What the native overlays of the given layer drew in the most recently drawn frame.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn scene(&self, layer: OverlayLayer) -> vello::Scene {
+		self.scenes.scene(layer)
+	}
+
+	/// The resolution and rasterization the native overlays of the most recently drawn frame were rendered with.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn render_settings(&self) -> super::utility_types::OverlayRenderSettings {
+		self.render_settings
+	}
+
+	/// The native overlays finished since this was last called, which the app renders and composites around the artwork.
+	#[cfg(not(target_arch = "wasm32"))]
+	pub fn take_finished_frame(&mut self) -> Option<super::utility_types::OverlayFrame> {
+		self.finished_frame.take()
+	}
+}
+
+#[cfg(test)]
+mod test_overlays {
+	use super::super::overlay_hit_test::OverlayHitId;
+	use super::super::utility_types::{OverlayLayer, OverlayProviderHandle, OverlayProviderOwner};
+	pub use crate::test_utils::test_prelude::*;
+
+	fn providers_owned_by(editor: &EditorTestUtils, owner: OverlayProviderOwner) -> usize {
+		editor.active_document().overlays_message_handler.providers_owned_by(owner).count()
+	}
+
+	#[tokio::test]
+	async fn tool_switch_removes_old_tool_providers() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.select_tool(ToolType::Path).await;
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::Tool(ToolType::Path)), 1);
+
+		editor.select_tool(ToolType::Pen).await;
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::Tool(ToolType::Path)), 0);
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::Tool(ToolType::Pen)), 1);
+
+		editor.select_tool(ToolType::Select).await;
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::Tool(ToolType::Pen)), 0);
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::Tool(ToolType::Select)), 1);
+		assert_eq!(providers_owned_by(&editor, OverlayProviderOwner::ToolSystem), 1);
+	}
+
+	#[tokio::test]
+	async fn leaked_providers_removed_with_owner() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.select_tool(ToolType::Path).await;
+
+		// Simulate a tool which registered an extra provider and never removed it
+		let owner = OverlayProviderOwner::Tool(ToolType::Path);
+		let leaked = OverlayProviderHandle::new(owner, |_| Message::NoOp);
+		editor.handle_message(OverlaysMessage::AddProvider(leaked)).await;
+		assert_eq!(providers_owned_by(&editor, owner), 2);
+
+		editor.select_tool(ToolType::Select).await;
+		assert_eq!(providers_owned_by(&editor, owner), 0);
+	}
+
+	#[tokio::test]
+	async fn providers_draw_in_their_requested_layer() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.select_tool(ToolType::Path).await;
+
+		let owner = OverlayProviderOwner::Tool(ToolType::Path);
+		let background = OverlayProviderHandle::new(owner, |_| Message::NoOp).in_layer(OverlayLayer::Background);
+		editor.handle_message(OverlaysMessage::AddProvider(background)).await;
+
+		let overlays = &editor.active_document().overlays_message_handler;
+		assert_eq!(overlays.providers_in_layer(OverlayLayer::Background).collect::<Vec<_>>(), vec![&background]);
+		// Providers are registered in the foreground unless they request otherwise, like those of the Path tool
+		assert_eq!(overlays.providers_in_layer(OverlayLayer::Foreground).filter(|handle| handle.owner == owner).count(), 1);
+		assert_eq!(overlays.providers_in_layer(OverlayLayer::Foreground).count() + 1, overlays.overlay_providers.len());
+	}
+
+	#[tokio::test]
+	async fn clicking_a_handle_beside_its_anchor_selects_the_handle() {
+		use crate::consts::{HIDE_HANDLE_DISTANCE, SELECTION_THRESHOLD};
+		use bezier_rs::{Bezier, Subpath};
+		use graph_craft::document::NodeId;
+		use graphene_std::vector::ManipulatorPointId;
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		// A curve whose first handle is drawn inside the clickable area of its anchor, which is drawn over it
+		let bezier = Bezier::from_cubic_dvec2(DVec2::new(100., 100.), DVec2::new(106., 100.), DVec2::new(200., 50.), DVec2::new(200., 100.));
+		let layer = LayerNodeIdentifier::new_unchecked(NodeId::new());
+		editor
+			.handle_message(GraphOperationMessage::NewVectorLayer {
+				id: layer.to_node(),
+				subpaths: vec![Subpath::from_bezier(&bezier)],
+				parent: LayerNodeIdentifier::ROOT_PARENT,
+				insert_index: 0,
+			})
+			.await;
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![layer.to_node()] }).await;
+
+		let to_viewport = editor.active_document().metadata().transform_to_viewport(layer);
+		let anchor = to_viewport.transform_point2(bezier.start);
+		let handle = to_viewport.transform_point2(bezier.handle_start().unwrap());
+		assert!((HIDE_HANDLE_DISTANCE..SELECTION_THRESHOLD).contains(&anchor.distance(handle)));
+
+		// Selecting the anchor shows its handle
+		editor.click_tool(ToolType::Path, MouseKeys::LEFT, anchor, ModifierKeys::empty()).await;
+		editor.handle_message(OverlaysMessage::Draw).await;
+		let selected_points = |editor: &EditorTestUtils| {
+			let shape_editor = &editor.editor.dispatcher.message_handlers.tool_message_handler.shape_editor;
+			shape_editor.selected_points().copied().collect::<Vec<_>>()
+		};
+		assert!(matches!(selected_points(&editor)[..], [ManipulatorPointId::Anchor(_)]));
+		let frontmost = editor.active_document().overlays_message_handler.hit_test(handle)[0];
+		assert!(matches!(
+			frontmost,
+			OverlayHitId::ManipulatorPoint {
+				point: ManipulatorPointId::Anchor(_),
+				..
+			}
+		));
+
+		// The handle is nearer to the click than the anchor drawn over it
+		editor.click_tool(ToolType::Path, MouseKeys::LEFT, handle, ModifierKeys::empty()).await;
+		assert!(matches!(selected_points(&editor)[..], [ManipulatorPointId::PrimaryHandle(_)]));
+	}
+
+	#[tokio::test]
+	async fn clicking_an_anchor_with_its_overlays_hidden_selects_it() {
+		use super::super::utility_types::OverlaysType;
+		use bezier_rs::Subpath;
+		use graph_craft::document::NodeId;
+		use graphene_std::vector::ManipulatorPointId;
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		let layer = LayerNodeIdentifier::new_unchecked(NodeId::new());
+		editor
+			.handle_message(GraphOperationMessage::NewVectorLayer {
+				id: layer.to_node(),
+				subpaths: vec![Subpath::new_rect(DVec2::new(100., 100.), DVec2::new(200., 200.))],
+				parent: LayerNodeIdentifier::ROOT_PARENT,
+				insert_index: 0,
+			})
+			.await;
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes: vec![layer.to_node()] }).await;
+		let anchor = editor.active_document().metadata().transform_to_viewport(layer).transform_point2(DVec2::new(100., 100.));
+
+		editor.select_tool(ToolType::Path).await;
+		editor
+			.handle_message(DocumentMessage::SetOverlaysVisibility {
+				visible: false,
+				overlays_type: Some(OverlaysType::Anchors),
+			})
+			.await;
+		editor.handle_message(OverlaysMessage::Draw).await;
+
+		editor.click_tool(ToolType::Path, MouseKeys::LEFT, anchor, ModifierKeys::empty()).await;
+		let shape_editor = &editor.editor.dispatcher.message_handlers.tool_message_handler.shape_editor;
+		assert!(matches!(shape_editor.selected_points().copied().collect::<Vec<_>>()[..], [ManipulatorPointId::Anchor(_)]));
+	}
+
+	#[tokio::test]
+	async fn finished_frames_are_handed_over_to_be_rendered_once() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.select_tool(ToolType::Select).await;
+		editor.editor.take_overlay_frame();
+
+		editor.handle_message(OverlaysMessage::Draw).await;
+		let frame = editor.editor.take_overlay_frame().expect("The drawn frame should be waiting to be rendered");
+		assert_eq!(frame.settings, editor.active_document().overlays_message_handler.render_settings());
+		// The outline of the selected rectangle is drawn above the artwork
+		assert!(!frame.foreground.encoding().is_empty());
+
+		// Each frame is only rendered once
+		assert!(editor.editor.take_overlay_frame().is_none());
+	}
+
+	#[tokio::test]
+	async fn quality_preference_applies_to_next_frame() {
+		use crate::messages::preferences::{OverlayQuality, OverlaySupersampling};
+
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		let render_scale = |editor: &EditorTestUtils| editor.active_document().overlays_message_handler.render_settings().render_scale;
+		let initial_scale = render_scale(&editor);
+
+		let quality = OverlayQuality {
+			anti_aliasing: false,
+			supersampling: OverlaySupersampling::X2,
+		};
+		editor.handle_message(PreferencesMessage::OverlayQuality { quality }).await;
+
+		assert_eq!(render_scale(&editor), initial_scale * 2.);
+		assert!(!editor.active_document().overlays_message_handler.render_settings().anti_aliasing);
+	}
+}
@@ -1,5 +1,7 @@
-use super::utility_types::{DrawHandles, OverlayContext};
-use crate::consts::HIDE_HANDLE_DISTANCE;
+use super::overlay_hit_test::{OverlayHitId, OverlayHitShape};
+use super::utility_types::{DrawHandles, OverlayContext, OverlayLayer};
+use crate::consts::{HIDE_HANDLE_DISTANCE, SELECTION_THRESHOLD};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
 use crate::messages::tool::common_functionality::shape_editor::{SelectedLayerState, ShapeState};
 use crate::messages::tool::tool_messages::tool_prelude::{DocumentMessageHandler, PreferencesMessageHandler};
@@ -9,16 +11,21 @@ use graphene_std::vector::ManipulatorPointId;
 use graphene_std::vector::{PointId, SegmentId};
 use wasm_bindgen::JsCast;
 
-pub fn overlay_canvas_element() -> Option<web_sys::HtmlCanvasElement> {
+/// The canvas the overlays of the given layer are drawn to, which the frontend stacks below or above the artwork accordingly.
+pub fn overlay_canvas_element(layer: OverlayLayer) -> Option<web_sys::HtmlCanvasElement> {
 	let window = web_sys::window()?;
 	let document = window.document()?;
-	let canvas = document.query_selector("[data-overlays-canvas]").ok().flatten()?;
+	let name = match layer {
+		OverlayLayer::Background => "background",
+		OverlayLayer::Foreground => "foreground",
+	};
+	let canvas = document.query_selector(&format!("[data-overlays-canvas='{name}']")).ok().flatten()?;
 	canvas.dyn_into::<web_sys::HtmlCanvasElement>().ok()
 }
 
 pub fn overlay_canvas_context() -> web_sys::CanvasRenderingContext2d {
 	let create_context = || {
-		let context = overlay_canvas_element()?.get_context("2d").ok().flatten()?;
+		let context = overlay_canvas_element(OverlayLayer::Foreground)?.get_context("2d").ok().flatten()?;
 		context.dyn_into().ok()
 	};
 	create_context().expect("Failed to get canvas context")
@@ -54,36 +61,70 @@ pub fn selected_segments(network_interface: &NodeNetworkInterface, shape_editor:
 	selected_segments
 }
 
-fn overlay_bezier_handles(bezier: Bezier, segment_id: SegmentId, transform: DAffine2, is_selected: impl Fn(ManipulatorPointId) -> bool, overlay_context: &mut OverlayContext) {
+/// Draws a handle or anchor of a selected layer if its overlays are shown, and makes it clickable within the selection threshold either way.
+fn overlay_manipulator(layer: LayerNodeIdentifier, point: ManipulatorPointId, position: DVec2, display: bool, is_selected: &impl Fn(ManipulatorPointId) -> bool, overlay_context: &mut OverlayContext) {
+	if display {
+		let selected = is_selected(point);
+		match point {
+			ManipulatorPointId::Anchor(_) => overlay_context.manipulator_anchor(position, selected, None),
+			ManipulatorPointId::PrimaryHandle(_) | ManipulatorPointId::EndHandle(_) => overlay_context.manipulator_handle(position, selected, None),
+		}
+	}
+
+	let shape = OverlayHitShape::Circle {
+		center: position,
+		radius: SELECTION_THRESHOLD,
+	};
+	overlay_context.register_hit_region(OverlayHitId::ManipulatorPoint { layer, point }, shape);
+}
+
+fn overlay_bezier_handles(
+	layer: LayerNodeIdentifier,
+	bezier: Bezier,
+	segment_id: SegmentId,
+	transform: DAffine2,
+	display: bool,
+	is_selected: impl Fn(ManipulatorPointId) -> bool,
+	overlay_context: &mut OverlayContext,
+) {
 	let bezier = bezier.apply_transformation(|point| transform.transform_point2(point));
 	let not_under_anchor = |position: DVec2, anchor: DVec2| position.distance_squared(anchor) >= HIDE_HANDLE_DISTANCE * HIDE_HANDLE_DISTANCE;
 
 	match bezier.handles {
 		BezierHandles::Quadratic { handle } if not_under_anchor(handle, bezier.start) && not_under_anchor(handle, bezier.end) => {
-			overlay_context.line(handle, bezier.start, None, None);
-			overlay_context.line(handle, bezier.end, None, None);
-			overlay_context.manipulator_handle(handle, is_selected(ManipulatorPointId::PrimaryHandle(segment_id)), None);
+			if display {
+				overlay_context.line(handle, bezier.start, None, None);
+				overlay_context.line(handle, bezier.end, None, None);
+			}
+			overlay_manipulator(layer, ManipulatorPointId::PrimaryHandle(segment_id), handle, display, &is_selected, overlay_context);
 		}
 		BezierHandles::Cubic { handle_start, handle_end } => {
 			if not_under_anchor(handle_start, bezier.start) {
-				overlay_context.line(handle_start, bezier.start, None, None);
-				overlay_context.manipulator_handle(handle_start, is_selected(ManipulatorPointId::PrimaryHandle(segment_id)), None);
+				if display {
+					overlay_context.line(handle_start, bezier.start, None, None);
+				}
+				overlay_manipulator(layer, ManipulatorPointId::PrimaryHandle(segment_id), handle_start, display, &is_selected, overlay_context);
 			}
 			if not_under_anchor(handle_end, bezier.end) {
-				overlay_context.line(handle_end, bezier.end, None, None);
-				overlay_context.manipulator_handle(handle_end, is_selected(ManipulatorPointId::EndHandle(segment_id)), None);
+				if display {
+					overlay_context.line(handle_end, bezier.end, None, None);
+				}
+				overlay_manipulator(layer, ManipulatorPointId::EndHandle(segment_id), handle_end, display, &is_selected, overlay_context);
 			}
 		}
 		_ => {}
 	}
 }
 
+#[allow(clippy::too_many_arguments)]
 fn overlay_bezier_handle_specific_point(
+	layer: LayerNodeIdentifier,
 	bezier: Bezier,
 	segment_id: SegmentId,
 	(start, end): (PointId, PointId),
 	point_to_render: PointId,
 	transform: DAffine2,
+	display: bool,
 	is_selected: impl Fn(ManipulatorPointId) -> bool,
 	overlay_context: &mut OverlayContext,
 ) {
@@ -94,18 +135,24 @@ fn overlay_bezier_handle_specific_point(
 		BezierHandles::Quadratic { handle } => {
 			if not_under_anchor(handle, bezier.start) && not_under_anchor(handle, bezier.end) {
 				let end = if start == point_to_render { bezier.start } else { bezier.end };
-				overlay_context.line(handle, end, None, None);
-				overlay_context.manipulator_handle(handle, is_selected(ManipulatorPointId::PrimaryHandle(segment_id)), None);
+				if display {
+					overlay_context.line(handle, end, None, None);
+				}
+				overlay_manipulator(layer, ManipulatorPointId::PrimaryHandle(segment_id), handle, display, &is_selected, overlay_context);
 			}
 		}
 		BezierHandles::Cubic { handle_start, handle_end } => {
 			if not_under_anchor(handle_start, bezier.start) && (point_to_render == start) {
-				overlay_context.line(handle_start, bezier.start, None, None);
-				overlay_context.manipulator_handle(handle_start, is_selected(ManipulatorPointId::PrimaryHandle(segment_id)), None);
+				if display {
+					overlay_context.line(handle_start, bezier.start, None, None);
+				}
+				overlay_manipulator(layer, ManipulatorPointId::PrimaryHandle(segment_id), handle_start, display, &is_selected, overlay_context);
 			}
 			if not_under_anchor(handle_end, bezier.end) && (point_to_render == end) {
-				overlay_context.line(handle_end, bezier.end, None, None);
-				overlay_context.manipulator_handle(handle_end, is_selected(ManipulatorPointId::EndHandle(segment_id)), None);
+				if display {
+					overlay_context.line(handle_end, bezier.end, None, None);
+				}
+				overlay_manipulator(layer, ManipulatorPointId::EndHandle(segment_id), handle_end, display, &is_selected, overlay_context);
 			}
 		}
 		_ => {}
@@ -138,50 +185,48 @@ pub fn path_overlays(document: &DocumentMessageHandler, draw_handles: DrawHandle
 		let selected = shape_editor.selected_shape_state.get(&layer);
 		let is_selected = |point: ManipulatorPointId| selected.is_some_and(|selected| selected.is_point_selected(point));
 
-		if display_handles {
-			let opposite_handles_data: Vec<(PointId, SegmentId)> = shape_editor.selected_points().filter_map(|point_id| vector_data.adjacent_segment(point_id)).collect();
+		let opposite_handles_data: Vec<(PointId, SegmentId)> = shape_editor.selected_points().filter_map(|point_id| vector_data.adjacent_segment(point_id)).collect();
 
-			match draw_handles {
-				DrawHandles::All => {
-					vector_data.segment_bezier_iter().for_each(|(segment_id, bezier, _start, _end)| {
-						overlay_bezier_handles(bezier, segment_id, transform, is_selected, overlay_context);
+		// The points are made clickable even when their overlays are hidden, so only the drawing is skipped for them
+		match draw_handles {
+			DrawHandles::All => {
+				vector_data.segment_bezier_iter().for_each(|(segment_id, bezier, _start, _end)| {
+					overlay_bezier_handles(layer, bezier, segment_id, transform, display_handles, is_selected, overlay_context);
+				});
+			}
+			DrawHandles::SelectedAnchors(ref selected_segments) => {
+				vector_data
+					.segment_bezier_iter()
+					.filter(|(segment_id, ..)| selected_segments.contains(segment_id))
+					.for_each(|(segment_id, bezier, _start, _end)| {
+						overlay_bezier_handles(layer, bezier, segment_id, transform, display_handles, is_selected, overlay_context);
 					});
-				}
-				DrawHandles::SelectedAnchors(ref selected_segments) => {
-					vector_data
-						.segment_bezier_iter()
-						.filter(|(segment_id, ..)| selected_segments.contains(segment_id))
-						.for_each(|(segment_id, bezier, _start, _end)| {
-							overlay_bezier_handles(bezier, segment_id, transform, is_selected, overlay_context);
-						});
-
-					for (segment_id, bezier, start, end) in vector_data.segment_bezier_iter() {
-						if let Some((corresponding_anchor, _)) = opposite_handles_data.iter().find(|(_, adj_segment_id)| adj_segment_id == &segment_id) {
-							overlay_bezier_handle_specific_point(bezier, segment_id, (start, end), *corresponding_anchor, transform, is_selected, overlay_context);
-						}
+
+				for (segment_id, bezier, start, end) in vector_data.segment_bezier_iter() {
+					if let Some((corresponding_anchor, _)) = opposite_handles_data.iter().find(|(_, adj_segment_id)| adj_segment_id == &segment_id) {
+						overlay_bezier_handle_specific_point(layer, bezier, segment_id, (start, end), *corresponding_anchor, transform, display_handles, is_selected, overlay_context);
 					}
 				}
-				DrawHandles::FrontierHandles(ref segment_endpoints) => {
-					vector_data
-						.segment_bezier_iter()
-						.filter(|(segment_id, ..)| segment_endpoints.contains_key(segment_id))
-						.for_each(|(segment_id, bezier, start, end)| {
-							if segment_endpoints.get(&segment_id).unwrap().len() == 1 {
-								let point_to_render = segment_endpoints.get(&segment_id).unwrap()[0];
-								overlay_bezier_handle_specific_point(bezier, segment_id, (start, end), point_to_render, transform, is_selected, overlay_context);
-							} else {
-								overlay_bezier_handles(bezier, segment_id, transform, is_selected, overlay_context);
-							}
-						});
-				}
-				DrawHandles::None => {}
 			}
+			DrawHandles::FrontierHandles(ref segment_endpoints) => {
+				vector_data
+					.segment_bezier_iter()
+					.filter(|(segment_id, ..)| segment_endpoints.contains_key(segment_id))
+					.for_each(|(segment_id, bezier, start, end)| {
+						if segment_endpoints.get(&segment_id).unwrap().len() == 1 {
+							let point_to_render = segment_endpoints.get(&segment_id).unwrap()[0];
+							overlay_bezier_handle_specific_point(layer, bezier, segment_id, (start, end), point_to_render, transform, display_handles, is_selected, overlay_context);
+						} else {
+							overlay_bezier_handles(layer, bezier, segment_id, transform, display_handles, is_selected, overlay_context);
+						}
+					});
+			}
+			DrawHandles::None => {}
 		}
 
-		if display_anchors {
-			for (&id, &position) in vector_data.point_domain.ids().iter().zip(vector_data.point_domain.positions()) {
-				overlay_context.manipulator_anchor(transform.transform_point2(position), is_selected(ManipulatorPointId::Anchor(id)), None);
-			}
+		for (&id, &position) in vector_data.point_domain.ids().iter().zip(vector_data.point_domain.positions()) {
+			let position = transform.transform_point2(position);
+			overlay_manipulator(layer, ManipulatorPointId::Anchor(id), position, display_anchors, &is_selected, overlay_context);
 		}
 	}
 }
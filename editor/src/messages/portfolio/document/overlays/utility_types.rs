@@ -1,3 +1,5 @@
+use super::overlay_command_buffer::{DrawBatch, DrawStyle, OverlayCommandBuffer, PathCommand, RecordedPath, RecordedText};
+use super::overlay_hit_test::{OverlayHitId, OverlayHitRegions, OverlayHitShape};
 use super::utility_functions::overlay_canvas_context;
 use crate::consts::{
 	ARC_SWEEP_GIZMO_RADIUS, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_BLUE_50, COLOR_OVERLAY_GREEN, COLOR_OVERLAY_RED, COLOR_OVERLAY_WHITE, COLOR_OVERLAY_YELLOW, COLOR_OVERLAY_YELLOW_DULL,
@@ -5,6 +7,7 @@ use crate::consts::{
 	PIVOT_CROSSHAIR_LENGTH, PIVOT_CROSSHAIR_THICKNESS, PIVOT_DIAMETER,
 };
 use crate::messages::prelude::Message;
+use crate::messages::tool::utility_types::ToolType;
 use bezier_rs::{Bezier, Subpath};
 use core::borrow::Borrow;
 use core::f64::consts::{FRAC_PI_2, PI, TAU};
@@ -13,7 +16,9 @@ use graphene_std::Color;
 use graphene_std::math::quad::Quad;
 use graphene_std::vector::click_target::ClickTargetType;
 use graphene_std::vector::{PointId, SegmentId, VectorData};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::Rc;
 use wasm_bindgen::{JsCast, JsValue};
 use web_sys::{OffscreenCanvas, OffscreenCanvasRenderingContext2d};
 
@@ -23,6 +28,74 @@ pub fn empty_provider() -> OverlayProvider {
 	|_| Message::NoOp
 }
 
+pub fn empty_provider_handle() -> OverlayProviderHandle {
+	OverlayProviderHandle::new(OverlayProviderOwner::ToolSystem, empty_provider())
+}
+
+/// Identifies what registered an overlay provider, so every provider belonging to an owner can be removed together when it's deactivated.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum OverlayProviderOwner {
+	/// Providers kept alive by the tool system as a whole, regardless of which tool is active (such as the artboard overlays).
+	ToolSystem,
+	/// Providers registered by the GRS (grab, rotate, scale) transform operations while they're in progress.
+	TransformLayer,
+	/// Providers registered by a tool while it's the active tool.
+	Tool(ToolType),
+}
+
+/// The registration token for an overlay provider, which its owner stores and hands back to remove the provider.
+/// Since the provider is tied to its owner, any left behind are cleaned up (and reported) by [`super::OverlaysMessage::RemoveOwnerProviders`] once the owner is deactivated.
+#[derive(Eq, Clone, Copy, Debug)]
+pub struct OverlayProviderHandle {
+	pub owner: OverlayProviderOwner,
+	pub provider: OverlayProvider,
+	pub layer: OverlayLayer,
+}
+
+impl OverlayProviderHandle {
+	/// Registers a provider which draws in the [`OverlayLayer::Foreground`], above the artwork.
+	pub const fn new(owner: OverlayProviderOwner, provider: OverlayProvider) -> Self {
+		Self {
+			owner,
+			provider,
+			layer: OverlayLayer::Foreground,
+		}
+	}
+
+	pub const fn in_layer(self, layer: OverlayLayer) -> Self {
+		Self { layer, ..self }
+	}
+}
+
+impl PartialEq for OverlayProviderHandle {
+	fn eq(&self, other: &Self) -> bool {
+		self.owner == other.owner && std::ptr::fn_addr_eq(self.provider, other.provider) && self.layer == other.layer
+	}
+}
+
+impl core::hash::Hash for OverlayProviderHandle {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.owner.hash(state);
+		(self.provider as usize).hash(state);
+		self.layer.hash(state);
+	}
+}
+
+/// Which side of the rendered artwork an overlay is drawn on.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum OverlayLayer {
+	/// Drawn underneath the artwork, for guides like the grid which shouldn't overprint it.
+	Background,
+	/// Drawn above the artwork, for interactive overlays like outlines and handles.
+	#[default]
+	Foreground,
+}
+
+impl OverlayLayer {
+	/// Every layer, in the order they're stacked from bottom to top.
+	pub const ALL: [Self; 2] = [Self::Background, Self::Foreground];
+}
+
 // Types of overlays used by DocumentMessage to enable/disable select group of overlays in the frontend
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum OverlaysType {
@@ -138,6 +211,18 @@ pub struct OverlayContext {
 	#[serde(skip, default = "overlay_canvas_context")]
 	#[specta(skip)]
 	pub render_context: web_sys::CanvasRenderingContext2d,
+	/// Shared by every provider drawing in the current frame, and flushed to the canvas once they're all done by [`super::OverlaysMessage::Flush`].
+	#[serde(skip)]
+	#[specta(skip)]
+	pub commands: Rc<RefCell<OverlayCommandBuffer>>,
+	/// Shared by every provider drawing in the current frame, and kept until the next frame is drawn so clicks can be resolved against it.
+	#[serde(skip)]
+	#[specta(skip)]
+	pub hit_regions: Rc<RefCell<OverlayHitRegions>>,
+	/// Set by providers whose overlays animate over time, so the overlays are drawn again on the next animation frame.
+	#[serde(skip)]
+	#[specta(skip)]
+	pub animating: Rc<Cell<bool>>,
 	pub size: DVec2,
 	// The device pixel ratio is a property provided by the browser window and is the CSS pixel size divided by the physical monitor's pixel size.
 	// It allows better pixel density of visualizations on high-DPI displays where the OS display scaling is not 100%, or where the browser is zoomed.
@@ -150,6 +235,16 @@ impl core::hash::Hash for OverlayContext {
 }
 
 impl OverlayContext {
+	/// Makes the given area clickable as `id` until the next frame is drawn, in the same viewport space as the drawing functions.
+	pub fn register_hit_region(&self, id: OverlayHitId, shape: OverlayHitShape) {
+		self.hit_regions.borrow_mut().register(id, shape);
+	}
+
+	/// Draws the overlays again on the next animation frame, for overlays which change over time even without any input.
+	pub fn request_animation_frame(&self) {
+		self.animating.set(true);
+	}
+
 	pub fn quad(&mut self, quad: Quad, stroke_color: Option<&str>, color_fill: Option<&str>) {
 		self.dashed_polygon(&quad.0, stroke_color, color_fill, None, None, None);
 	}
@@ -162,20 +257,14 @@ impl OverlayContext {
 		let edge1 = base + normal * size / 2.;
 		let edge2 = base - normal * size / 2.;
 
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.render_context.move_to(top.x, top.y);
-		self.render_context.line_to(edge1.x, edge1.y);
-		self.render_context.line_to(edge2.x, edge2.y);
-		self.render_context.close_path();
+		let mut path = RecordedPath::new();
+		path.move_to(top);
+		path.line_to(edge1);
+		path.line_to(edge2);
+		path.close_path();
 
-		self.render_context.set_fill_style_str(color_fill);
-		self.render_context.set_stroke_style_str(color_stroke);
-		self.render_context.fill();
-		self.render_context.stroke();
-
-		self.end_dpi_aware_transform();
+		self.record(path.clone(), DrawStyle::fill(color_fill));
+		self.record(path, DrawStyle::stroke(color_stroke, 1.));
 	}
 
 	pub fn dashed_quad(&mut self, quad: Quad, stroke_color: Option<&str>, color_fill: Option<&str>, dash_width: Option<f64>, dash_gap_width: Option<f64>, dash_offset: Option<f64>) {
@@ -191,55 +280,18 @@ impl OverlayContext {
 			return;
 		}
 
-		self.start_dpi_aware_transform();
-
-		// Set the dash pattern
-		if let Some(dash_width) = dash_width {
-			let dash_gap_width = dash_gap_width.unwrap_or(1.);
-			let array = js_sys::Array::new();
-			array.push(&JsValue::from(dash_width));
-			array.push(&JsValue::from(dash_gap_width));
-
-			if let Some(dash_offset) = dash_offset {
-				if dash_offset != 0. {
-					self.render_context.set_line_dash_offset(dash_offset);
-				}
-			}
-
-			self.render_context
-				.set_line_dash(&JsValue::from(array))
-				.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
-				.ok();
-		}
-
-		self.render_context.begin_path();
-		self.render_context.move_to(polygon.last().unwrap().x.round() - 0.5, polygon.last().unwrap().y.round() - 0.5);
-
+		let mut path = RecordedPath::new();
+		path.move_to(polygon.last().unwrap().round() - DVec2::splat(0.5));
 		for point in polygon {
-			self.render_context.line_to(point.x.round() - 0.5, point.y.round() - 0.5);
+			path.line_to(point.round() - DVec2::splat(0.5));
 		}
 
 		if let Some(color_fill) = color_fill {
-			self.render_context.set_fill_style_str(color_fill);
-			self.render_context.fill();
+			self.record(path.clone(), DrawStyle::fill(color_fill));
 		}
 
 		let stroke_color = stroke_color.unwrap_or(COLOR_OVERLAY_BLUE);
-		self.render_context.set_stroke_style_str(stroke_color);
-		self.render_context.stroke();
-
-		// Reset the dash pattern back to solid
-		if dash_width.is_some() {
-			self.render_context
-				.set_line_dash(&JsValue::from(js_sys::Array::new()))
-				.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
-				.ok();
-		}
-		if dash_offset.is_some() && dash_offset != Some(0.) {
-			self.render_context.set_line_dash_offset(0.);
-		}
-
-		self.end_dpi_aware_transform();
+		self.record(path, Self::dashed_stroke(stroke_color, 1., dash_width, dash_gap_width, dash_offset));
 	}
 
 	pub fn line(&mut self, start: DVec2, end: DVec2, color: Option<&str>, thickness: Option<f64>) {
@@ -248,69 +300,23 @@ impl OverlayContext {
 
 	#[allow(clippy::too_many_arguments)]
 	pub fn dashed_line(&mut self, start: DVec2, end: DVec2, color: Option<&str>, thickness: Option<f64>, dash_width: Option<f64>, dash_gap_width: Option<f64>, dash_offset: Option<f64>) {
-		self.start_dpi_aware_transform();
-
-		// Set the dash pattern
-		if let Some(dash_width) = dash_width {
-			let dash_gap_width = dash_gap_width.unwrap_or(1.);
-			let array = js_sys::Array::new();
-			array.push(&JsValue::from(dash_width));
-			array.push(&JsValue::from(dash_gap_width));
-
-			if let Some(dash_offset) = dash_offset {
-				if dash_offset != 0. {
-					self.render_context.set_line_dash_offset(dash_offset);
-				}
-			}
-
-			self.render_context
-				.set_line_dash(&JsValue::from(array))
-				.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
-				.ok();
-		}
-
-		let start = start.round() - DVec2::splat(0.5);
-		let end = end.round() - DVec2::splat(0.5);
-
-		self.render_context.begin_path();
-		self.render_context.move_to(start.x, start.y);
-		self.render_context.line_to(end.x, end.y);
-		self.render_context.set_line_width(thickness.unwrap_or(1.));
-		self.render_context.set_stroke_style_str(color.unwrap_or(COLOR_OVERLAY_BLUE));
-		self.render_context.stroke();
-		self.render_context.set_line_width(1.);
-
-		// Reset the dash pattern back to solid
-		if dash_width.is_some() {
-			self.render_context
-				.set_line_dash(&JsValue::from(js_sys::Array::new()))
-				.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
-				.ok();
-		}
-		if dash_offset.is_some() && dash_offset != Some(0.) {
-			self.render_context.set_line_dash_offset(0.);
-		}
+		let mut path = RecordedPath::new();
+		path.move_to(start.round() - DVec2::splat(0.5));
+		path.line_to(end.round() - DVec2::splat(0.5));
 
-		self.end_dpi_aware_transform();
+		let color = color.unwrap_or(COLOR_OVERLAY_BLUE);
+		self.record(path, Self::dashed_stroke(color, thickness.unwrap_or(1.), dash_width, dash_gap_width, dash_offset));
 	}
 
 	pub fn manipulator_handle(&mut self, position: DVec2, selected: bool, color: Option<&str>) {
-		self.start_dpi_aware_transform();
-
 		let position = position.round() - DVec2::splat(0.5);
 
-		self.render_context.begin_path();
-		self.render_context
-			.arc(position.x, position.y, MANIPULATOR_GROUP_MARKER_SIZE / 2., 0., TAU)
-			.expect("Failed to draw the circle");
+		let mut path = RecordedPath::new();
+		path.arc(position, MANIPULATOR_GROUP_MARKER_SIZE / 2., 0., TAU);
 
 		let fill = if selected { COLOR_OVERLAY_BLUE } else { COLOR_OVERLAY_WHITE };
-		self.render_context.set_fill_style_str(fill);
-		self.render_context.set_stroke_style_str(color.unwrap_or(COLOR_OVERLAY_BLUE));
-		self.render_context.fill();
-		self.render_context.stroke();
-
-		self.end_dpi_aware_transform();
+		self.record(path.clone(), DrawStyle::fill(fill));
+		self.record(path, DrawStyle::stroke(color.unwrap_or(COLOR_OVERLAY_BLUE), 1.));
 	}
 
 	pub fn manipulator_anchor(&mut self, position: DVec2, selected: bool, color: Option<&str>) {
@@ -319,21 +325,18 @@ impl OverlayContext {
 		self.square(position, None, Some(color_fill), Some(color_stroke));
 	}
 
-	/// Transforms the canvas context to adjust for DPI scaling
-	///
-	/// Overwrites all existing tranforms. This operation can be reversed with [`Self::reset_transform`].
-	fn start_dpi_aware_transform(&self) {
-		let [a, b, c, d, e, f] = DAffine2::from_scale(DVec2::splat(self.device_pixel_ratio)).to_cols_array();
-		self.render_context
-			.set_transform(a, b, c, d, e, f)
-			.expect("transform should be able to be set to be able to account for DPI");
+	/// Queues a path to be drawn in the given style when the frame's commands are flushed.
+	fn record(&self, path: RecordedPath, style: DrawStyle) {
+		self.commands.borrow_mut().push_path(path, style);
 	}
 
-	/// Un-transforms the Canvas context to adjust for DPI scaling
-	///
-	/// Warning: this function doesn't only reset the DPI scaling adjustment, it resets the entire transform.
-	fn end_dpi_aware_transform(&self) {
-		self.render_context.reset_transform().expect("transform should be able to be reset to be able to account for DPI");
+	fn dashed_stroke(color: &str, width: f64, dash_width: Option<f64>, dash_gap_width: Option<f64>, dash_offset: Option<f64>) -> DrawStyle {
+		DrawStyle::Stroke {
+			color: color.to_string(),
+			width,
+			dash: dash_width.map(|dash_width| (dash_width, dash_gap_width.unwrap_or(1.), dash_offset.unwrap_or(0.))),
+			round_cap: false,
+		}
 	}
 
 	pub fn square(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>) {
@@ -344,17 +347,11 @@ impl OverlayContext {
 		let position = position.round() - DVec2::splat(0.5);
 		let corner = position - DVec2::splat(size) / 2.;
 
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.render_context.rect(corner.x, corner.y, size, size);
-		self.render_context.set_fill_style_str(color_fill);
-		self.render_context.set_stroke_style_str(color_stroke);
-		self.render_context.set_line_width(1.);
-		self.render_context.fill();
-		self.render_context.stroke();
+		let mut path = RecordedPath::new();
+		path.rect(corner, DVec2::splat(size));
 
-		self.end_dpi_aware_transform();
+		self.record(path.clone(), DrawStyle::fill(color_fill));
+		self.record(path, DrawStyle::stroke(color_stroke, 1.));
 	}
 
 	pub fn pixel(&mut self, position: DVec2, color: Option<&str>) {
@@ -364,14 +361,10 @@ impl OverlayContext {
 		let position = position.round() - DVec2::splat(0.5);
 		let corner = position - DVec2::splat(size) / 2.;
 
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.render_context.rect(corner.x, corner.y, size, size);
-		self.render_context.set_fill_style_str(color_fill);
-		self.render_context.fill();
+		let mut path = RecordedPath::new();
+		path.rect(corner, DVec2::splat(size));
 
-		self.end_dpi_aware_transform();
+		self.record(path, DrawStyle::fill(color_fill));
 	}
 
 	pub fn circle(&mut self, position: DVec2, radius: f64, color_fill: Option<&str>, color_stroke: Option<&str>) {
@@ -379,16 +372,11 @@ impl OverlayContext {
 		let color_stroke = color_stroke.unwrap_or(COLOR_OVERLAY_BLUE);
 		let position = position.round();
 
-		self.start_dpi_aware_transform();
+		let mut path = RecordedPath::new();
+		path.arc(position, radius, 0., TAU);
 
-		self.render_context.begin_path();
-		self.render_context.arc(position.x, position.y, radius, 0., TAU).expect("Failed to draw the circle");
-		self.render_context.set_fill_style_str(color_fill);
-		self.render_context.set_stroke_style_str(color_stroke);
-		self.render_context.fill();
-		self.render_context.stroke();
-
-		self.end_dpi_aware_transform();
+		self.record(path.clone(), DrawStyle::fill(color_fill));
+		self.record(path, DrawStyle::stroke(color_stroke, 1.));
 	}
 
 	pub fn draw_arc(&mut self, center: DVec2, radius: f64, start_from: f64, end_at: f64) {
@@ -397,7 +385,7 @@ impl OverlayContext {
 		let half_step = step / 2.;
 		let factor = 4. / 3. * half_step.sin() / (1. + half_step.cos());
 
-		self.render_context.begin_path();
+		let mut path = RecordedPath::new();
 
 		for i in 0..segments {
 			let start_angle = start_from + step * i as f64;
@@ -417,10 +405,10 @@ impl OverlayContext {
 				handles: bezier_rs::BezierHandles::Cubic { handle_start, handle_end },
 			};
 
-			self.bezier_command(bezier, DAffine2::IDENTITY, i == 0);
+			Self::bezier_command(&mut path, bezier, DAffine2::IDENTITY, i == 0);
 		}
 
-		self.render_context.stroke();
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
 	}
 
 	pub fn draw_arc_gizmo_angle(&mut self, pivot: DVec2, bold_radius: f64, arc_radius: f64, offset_angle: f64, angle: f64) {
@@ -467,27 +455,19 @@ impl OverlayContext {
 
 		let Some(show_hover_ring) = show_compass_with_hover_ring else { return };
 
-		self.start_dpi_aware_transform();
-
 		let center = compass_center.round() - DVec2::splat(0.5);
 
-		// Save the old line width to restore it later
-		let old_line_width = self.render_context.line_width();
-
 		// Hover ring
 		if show_hover_ring {
 			let mut fill_color = Color::from_rgb_str(COLOR_OVERLAY_BLUE.strip_prefix('#').unwrap()).unwrap().with_alpha(0.5).to_rgba_hex_srgb();
 			fill_color.insert(0, '#');
 
-			self.render_context.set_line_width(HOVER_RING_STROKE_WIDTH);
-			self.render_context.begin_path();
-			self.render_context.arc(center.x, center.y, HOVER_RING_CENTERLINE_RADIUS, 0., TAU).expect("Failed to draw hover ring");
-			self.render_context.set_stroke_style_str(&fill_color);
-			self.render_context.stroke();
+			let mut path = RecordedPath::new();
+			path.arc(center, HOVER_RING_CENTERLINE_RADIUS, 0., TAU);
+			self.record(path, DrawStyle::stroke(&fill_color, HOVER_RING_STROKE_WIDTH));
 		}
 
 		// Arrows
-		self.render_context.set_line_width(0.01);
 		for i in 0..4 {
 			let direction = DVec2::from_angle(i as f64 * FRAC_PI_2 + angle);
 			let color = if i % 2 == 0 { COLOR_OVERLAY_RED } else { COLOR_OVERLAY_GREEN };
@@ -500,94 +480,75 @@ impl OverlayContext {
 			let side1 = center + r * DVec2::new(cos * direction.x - sin * direction.y, sin * direction.x + direction.y * cos);
 			let side2 = center + r * DVec2::new(cos * direction.x + sin * direction.y, -sin * direction.x + direction.y * cos);
 
-			self.render_context.begin_path();
-			self.render_context.move_to(tip.x, tip.y);
-			self.render_context.line_to(side1.x, side1.y);
-			self.render_context.line_to(base.x, base.y);
-			self.render_context.line_to(side2.x, side2.y);
-			self.render_context.close_path();
-
-			self.render_context.set_fill_style_str(color);
-			self.render_context.fill();
-			self.render_context.set_stroke_style_str(color);
-			self.render_context.stroke();
+			let mut path = RecordedPath::new();
+			path.move_to(tip);
+			path.line_to(side1);
+			path.line_to(base);
+			path.line_to(side2);
+			path.close_path();
+
+			self.record(path.clone(), DrawStyle::fill(color));
+			self.record(path, DrawStyle::stroke(color, 0.01));
 		}
 
 		// Main ring
-		self.render_context.set_line_width(MAIN_RING_STROKE_WIDTH);
-		self.render_context.begin_path();
-		self.render_context.arc(center.x, center.y, MAIN_RING_CENTERLINE_RADIUS, 0., TAU).expect("Failed to draw main ring");
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE);
-		self.render_context.stroke();
-
-		// Restore the old line width
-		self.render_context.set_line_width(old_line_width);
+		let mut path = RecordedPath::new();
+		path.arc(center, MAIN_RING_CENTERLINE_RADIUS, 0., TAU);
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE, MAIN_RING_STROKE_WIDTH));
 	}
 
 	pub fn pivot(&mut self, position: DVec2, angle: f64) {
 		let uv = DVec2::from_angle(angle);
-		let (x, y) = (position.round() - DVec2::splat(0.5)).into();
-
-		self.start_dpi_aware_transform();
+		let position = position.round() - DVec2::splat(0.5);
 
 		// Circle
 
-		self.render_context.begin_path();
-		self.render_context.arc(x, y, PIVOT_DIAMETER / 2., 0., TAU).expect("Failed to draw the circle");
-		self.render_context.set_fill_style_str(COLOR_OVERLAY_YELLOW);
-		self.render_context.fill();
+		let mut path = RecordedPath::new();
+		path.arc(position, PIVOT_DIAMETER / 2., 0., TAU);
+		self.record(path, DrawStyle::fill(COLOR_OVERLAY_YELLOW));
 
 		// Crosshair
 
 		// Round line caps add half the stroke width to the length on each end, so we subtract that here before halving to get the radius
 		const CROSSHAIR_RADIUS: f64 = (PIVOT_CROSSHAIR_LENGTH - PIVOT_CROSSHAIR_THICKNESS) / 2.;
 
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_YELLOW);
-		self.render_context.set_line_cap("round");
-
-		self.render_context.begin_path();
-		self.render_context.move_to(x + CROSSHAIR_RADIUS * uv.x, y + CROSSHAIR_RADIUS * uv.y);
-		self.render_context.line_to(x - CROSSHAIR_RADIUS * uv.x, y - CROSSHAIR_RADIUS * uv.y);
-		self.render_context.stroke();
-
-		self.render_context.begin_path();
-		self.render_context.move_to(x - CROSSHAIR_RADIUS * uv.y, y + CROSSHAIR_RADIUS * uv.x);
-		self.render_context.line_to(x + CROSSHAIR_RADIUS * uv.y, y - CROSSHAIR_RADIUS * uv.x);
-		self.render_context.stroke();
-
-		self.render_context.set_line_cap("butt");
-
-		self.end_dpi_aware_transform();
+		let mut path = RecordedPath::new();
+		path.move_to(position + CROSSHAIR_RADIUS * uv);
+		path.line_to(position - CROSSHAIR_RADIUS * uv);
+		path.move_to(position + CROSSHAIR_RADIUS * DVec2::new(-uv.y, uv.x));
+		path.line_to(position + CROSSHAIR_RADIUS * DVec2::new(uv.y, -uv.x));
+		self.record(
+			path,
+			DrawStyle::Stroke {
+				color: COLOR_OVERLAY_YELLOW.to_string(),
+				width: 1.,
+				dash: None,
+				round_cap: true,
+			},
+		);
 	}
 
 	pub fn dowel_pin(&mut self, position: DVec2, angle: f64, color: Option<&str>) {
-		let (x, y) = (position.round() - DVec2::splat(0.5)).into();
+		let position = position.round() - DVec2::splat(0.5);
 		let color = color.unwrap_or(COLOR_OVERLAY_YELLOW_DULL);
 
-		self.start_dpi_aware_transform();
-
 		// Draw the background circle with a white fill and blue outline
-		self.render_context.begin_path();
-		self.render_context.arc(x, y, DOWEL_PIN_RADIUS, 0., TAU).expect("Failed to draw the circle");
-		self.render_context.set_fill_style_str(COLOR_OVERLAY_WHITE);
-		self.render_context.fill();
-		self.render_context.set_stroke_style_str(color);
-		self.render_context.stroke();
+		let mut path = RecordedPath::new();
+		path.arc(position, DOWEL_PIN_RADIUS, 0., TAU);
+		self.record(path.clone(), DrawStyle::fill(COLOR_OVERLAY_WHITE));
+		self.record(path, DrawStyle::stroke(color, 1.));
 
 		// Draw the two blue filled sectors
-		self.render_context.begin_path();
+		let mut path = RecordedPath::new();
 		// Top-left sector
-		self.render_context.move_to(x, y);
-		self.render_context.arc(x, y, DOWEL_PIN_RADIUS, FRAC_PI_2 + angle, PI + angle).expect("Failed to draw arc");
-		self.render_context.close_path();
+		path.move_to(position);
+		path.arc(position, DOWEL_PIN_RADIUS, FRAC_PI_2 + angle, PI + angle);
+		path.close_path();
 		// Bottom-right sector
-		self.render_context.move_to(x, y);
-		self.render_context.arc(x, y, DOWEL_PIN_RADIUS, PI + FRAC_PI_2 + angle, TAU + angle).expect("Failed to draw arc");
-		self.render_context.close_path();
-		self.render_context.set_fill_style_str(color);
-		self.render_context.fill();
-
-		self.end_dpi_aware_transform();
+		path.move_to(position);
+		path.arc(position, DOWEL_PIN_RADIUS, PI + FRAC_PI_2 + angle, TAU + angle);
+		path.close_path();
+		self.record(path, DrawStyle::fill(color));
 	}
 
 	pub fn arc_sweep_angle(&mut self, offset_angle: f64, angle: f64, end_point_position: DVec2, bold_radius: f64, pivot: DVec2, text: &str, transform: DAffine2) {
@@ -598,85 +559,53 @@ impl OverlayContext {
 
 	/// Used by the Pen and Path tools to outline the path of the shape.
 	pub fn outline_vector(&mut self, vector_data: &VectorData, transform: DAffine2) {
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
+		let mut path = RecordedPath::new();
 		let mut last_point = None;
 		for (_, bezier, start_id, end_id) in vector_data.segment_bezier_iter() {
 			let move_to = last_point != Some(start_id);
 			last_point = Some(end_id);
 
-			self.bezier_command(bezier, transform, move_to);
+			Self::bezier_command(&mut path, bezier, transform, move_to);
 		}
 
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE);
-		self.render_context.stroke();
-
-		self.end_dpi_aware_transform();
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
 	}
 
 	/// Used by the Pen tool in order to show how the bezier curve would look like.
 	pub fn outline_bezier(&mut self, bezier: Bezier, transform: DAffine2) {
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.bezier_command(bezier, transform, true);
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE);
-		self.render_context.stroke();
-
-		self.end_dpi_aware_transform();
+		let mut path = RecordedPath::new();
+		Self::bezier_command(&mut path, bezier, transform, true);
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 1.));
 	}
 
 	/// Used by the path tool segment mode in order to show the selected segments.
 	pub fn outline_select_bezier(&mut self, bezier: Bezier, transform: DAffine2) {
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.bezier_command(bezier, transform, true);
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE);
-		self.render_context.set_line_width(4.);
-		self.render_context.stroke();
-
-		self.render_context.set_line_width(1.);
-
-		self.end_dpi_aware_transform();
+		let mut path = RecordedPath::new();
+		Self::bezier_command(&mut path, bezier, transform, true);
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE, 4.));
 	}
 
 	pub fn outline_overlay_bezier(&mut self, bezier: Bezier, transform: DAffine2) {
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
-		self.bezier_command(bezier, transform, true);
-		self.render_context.set_stroke_style_str(COLOR_OVERLAY_BLUE_50);
-		self.render_context.set_line_width(4.);
-		self.render_context.stroke();
-
-		self.render_context.set_line_width(1.);
-
-		self.end_dpi_aware_transform();
+		let mut path = RecordedPath::new();
+		Self::bezier_command(&mut path, bezier, transform, true);
+		self.record(path, DrawStyle::stroke(COLOR_OVERLAY_BLUE_50, 4.));
 	}
 
-	fn bezier_command(&self, bezier: Bezier, transform: DAffine2, move_to: bool) {
-		self.start_dpi_aware_transform();
-
+	fn bezier_command(path: &mut RecordedPath, bezier: Bezier, transform: DAffine2, move_to: bool) {
 		let Bezier { start, end, handles } = bezier.apply_transformation(|point| transform.transform_point2(point));
 		if move_to {
-			self.render_context.move_to(start.x, start.y);
+			path.move_to(start);
 		}
 
 		match handles {
-			bezier_rs::BezierHandles::Linear => self.render_context.line_to(end.x, end.y),
-			bezier_rs::BezierHandles::Quadratic { handle } => self.render_context.quadratic_curve_to(handle.x, handle.y, end.x, end.y),
-			bezier_rs::BezierHandles::Cubic { handle_start, handle_end } => self.render_context.bezier_curve_to(handle_start.x, handle_start.y, handle_end.x, handle_end.y, end.x, end.y),
+			bezier_rs::BezierHandles::Linear => path.line_to(end),
+			bezier_rs::BezierHandles::Quadratic { handle } => path.quadratic_curve_to(handle, end),
+			bezier_rs::BezierHandles::Cubic { handle_start, handle_end } => path.bezier_curve_to(handle_start, handle_end, end),
 		}
-
-		self.end_dpi_aware_transform();
 	}
 
-	fn push_path(&mut self, subpaths: impl Iterator<Item = impl Borrow<Subpath<PointId>>>, transform: DAffine2) {
-		self.start_dpi_aware_transform();
-
-		self.render_context.begin_path();
+	fn push_path(subpaths: impl Iterator<Item = impl Borrow<Subpath<PointId>>>, transform: DAffine2) -> RecordedPath {
+		let mut path = RecordedPath::new();
 		for subpath in subpaths {
 			let subpath = subpath.borrow();
 			let mut curves = subpath.iter().peekable();
@@ -685,14 +614,14 @@ impl OverlayContext {
 				continue;
 			};
 
-			self.render_context.move_to(transform.transform_point2(first.start()).x, transform.transform_point2(first.start()).y);
+			path.move_to(transform.transform_point2(first.start()));
 			for curve in curves {
 				match curve.handles {
 					bezier_rs::BezierHandles::Linear => {
 						let a = transform.transform_point2(curve.end());
 						let a = a.round() - DVec2::splat(0.5);
 
-						self.render_context.line_to(a.x, a.y)
+						path.line_to(a)
 					}
 					bezier_rs::BezierHandles::Quadratic { handle } => {
 						let a = transform.transform_point2(handle);
@@ -700,7 +629,7 @@ impl OverlayContext {
 						let a = a.round() - DVec2::splat(0.5);
 						let b = b.round() - DVec2::splat(0.5);
 
-						self.render_context.quadratic_curve_to(a.x, a.y, b.x, b.y)
+						path.quadratic_curve_to(a, b)
 					}
 					bezier_rs::BezierHandles::Cubic { handle_start, handle_end } => {
 						let a = transform.transform_point2(handle_start);
@@ -710,17 +639,17 @@ impl OverlayContext {
 						let b = b.round() - DVec2::splat(0.5);
 						let c = c.round() - DVec2::splat(0.5);
 
-						self.render_context.bezier_curve_to(a.x, a.y, b.x, b.y, c.x, c.y)
+						path.bezier_curve_to(a, b, c)
 					}
 				}
 			}
 
 			if subpath.closed() {
-				self.render_context.close_path();
+				path.close_path();
 			}
 		}
 
-		self.end_dpi_aware_transform();
+		path
 	}
 
 	/// Used by the Select tool to outline a path or a free point when selected or hovered.
@@ -735,61 +664,27 @@ impl OverlayContext {
 		});
 
 		if !subpaths.is_empty() {
-			self.push_path(subpaths.iter(), transform);
+			let path = Self::push_path(subpaths.iter(), transform);
 
 			let color = color.unwrap_or(COLOR_OVERLAY_BLUE);
-			self.render_context.set_stroke_style_str(color);
-			self.render_context.set_line_width(1.);
-			self.render_context.stroke();
+			self.record(path, DrawStyle::stroke(color, 1.));
 		}
 	}
 
 	/// Fills the area inside the path. Assumes `color` is in gamma space.
 	/// Used by the Pen tool to show the path being closed.
 	pub fn fill_path(&mut self, subpaths: impl Iterator<Item = impl Borrow<Subpath<PointId>>>, transform: DAffine2, color: &str) {
-		self.push_path(subpaths, transform);
+		let path = Self::push_path(subpaths, transform);
 
-		self.render_context.set_fill_style_str(color);
-		self.render_context.fill();
+		self.record(path, DrawStyle::fill(color));
 	}
 
 	/// Fills the area inside the path with a pattern. Assumes `color` is in gamma space.
 	/// Used by the fill tool to show the area to be filled.
 	pub fn fill_path_pattern(&mut self, subpaths: impl Iterator<Item = impl Borrow<Subpath<PointId>>>, transform: DAffine2, color: &Color) {
-		const PATTERN_WIDTH: usize = 4;
-		const PATTERN_HEIGHT: usize = 4;
-
-		let pattern_canvas = OffscreenCanvas::new(PATTERN_WIDTH as u32, PATTERN_HEIGHT as u32).unwrap();
-		let pattern_context: OffscreenCanvasRenderingContext2d = pattern_canvas
-			.get_context("2d")
-			.ok()
-			.flatten()
-			.expect("Failed to get canvas context")
-			.dyn_into()
-			.expect("Context should be a canvas 2d context");
-
-		// 4x4 pixels, 4 components (RGBA) per pixel
-		let mut data = [0_u8; 4 * PATTERN_WIDTH * PATTERN_HEIGHT];
-
-		// ┌▄▄┬──┬──┬──┐
-		// ├▀▀┼──┼──┼──┤
-		// ├──┼──┼▄▄┼──┤
-		// ├──┼──┼▀▀┼──┤
-		// └──┴──┴──┴──┘
-		let pixels = [(0, 0), (2, 2)];
-		for &(x, y) in &pixels {
-			let index = (x + y * PATTERN_WIDTH) * 4;
-			data[index..index + 4].copy_from_slice(&color.to_rgba8_srgb());
-		}
+		let path = Self::push_path(subpaths, transform);
 
-		let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(&data), PATTERN_WIDTH as u32, PATTERN_HEIGHT as u32).unwrap();
-		pattern_context.put_image_data(&image_data, 0., 0.).unwrap();
-		let pattern = self.render_context.create_pattern_with_offscreen_canvas(&pattern_canvas, "repeat").unwrap().unwrap();
-
-		self.push_path(subpaths, transform);
-
-		self.render_context.set_fill_style_canvas_pattern(&pattern);
-		self.render_context.fill();
+		self.record(path, DrawStyle::FillPattern { color: color.to_rgba8_srgb() });
 	}
 
 	pub fn get_width(&self, text: &str) -> f64 {
@@ -809,23 +704,22 @@ impl OverlayContext {
 			Pivot::End => -padding,
 		};
 
-		let [a, b, c, d, e, f] = (DAffine2::from_scale(DVec2::splat(self.device_pixel_ratio)) * transform * DAffine2::from_translation(DVec2::new(x, y))).to_cols_array();
-		self.render_context.set_transform(a, b, c, d, e, f).expect("Failed to rotate the render context to the specified angle");
-
-		if let Some(background) = background_color {
-			self.render_context.set_fill_style_str(background);
-			self.render_context.fill_rect(
+		let background = background_color.map(|background| {
+			let rect = [
 				-padding,
 				padding,
 				metrics.actual_bounding_box_right() - metrics.actual_bounding_box_left() + padding * 2.,
 				metrics.font_bounding_box_descent() - metrics.font_bounding_box_ascent() - padding * 2.,
-			);
-		}
+			];
+			(background.to_string(), rect)
+		});
 
-		self.render_context.set_font(r#"12px "Source Sans Pro", Arial, sans-serif"#);
-		self.render_context.set_fill_style_str(font_color);
-		self.render_context.fill_text(text, 0., 0.).expect("Failed to draw the text at the calculated position");
-		self.render_context.reset_transform().expect("Failed to reset the render context transform");
+		self.commands.borrow_mut().push_text(RecordedText {
+			text: text.to_string(),
+			font_color: font_color.to_string(),
+			background,
+			transform: DAffine2::from_scale(DVec2::splat(self.device_pixel_ratio)) * transform * DAffine2::from_translation(DVec2::new(x, y)),
+		});
 	}
 
 	pub fn translation_box(&mut self, translation: DVec2, quad: Quad, typed_string: Option<String>) {
@@ -859,6 +753,137 @@ impl OverlayContext {
 	}
 }
 
+/// Draws every command recorded by the overlay providers this frame to the canvas, then empties the buffer for the next frame.
+/// Each batch of commands sharing a style is drawn with a single `Path2d`, so the number of calls across the JavaScript boundary grows with the number of batches instead of the number of primitives.
+pub fn flush_overlay_commands(render_context: &web_sys::CanvasRenderingContext2d, commands: &mut OverlayCommandBuffer, device_pixel_ratio: f64) {
+	let dpi_transform = DAffine2::from_scale(DVec2::splat(device_pixel_ratio));
+	let set_transform = |transform: DAffine2| {
+		let [a, b, c, d, e, f] = transform.to_cols_array();
+		render_context
+			.set_transform(a, b, c, d, e, f)
+			.expect("transform should be able to be set to be able to account for DPI");
+	};
+
+	set_transform(dpi_transform);
+	render_context.set_font(r#"12px "Source Sans Pro", Arial, sans-serif"#);
+
+	for batch in commands.batches() {
+		match batch {
+			DrawBatch::Paths { style, paths } => {
+				let path_2d = web_sys::Path2d::new().expect("Failed to create a Path2d");
+				for command in paths.iter().flat_map(|path| &path.commands) {
+					match *command {
+						PathCommand::MoveTo(point) => path_2d.move_to(point.x, point.y),
+						PathCommand::LineTo(point) => path_2d.line_to(point.x, point.y),
+						PathCommand::QuadraticCurveTo { handle, end } => path_2d.quadratic_curve_to(handle.x, handle.y, end.x, end.y),
+						PathCommand::BezierCurveTo { handle_start, handle_end, end } => path_2d.bezier_curve_to(handle_start.x, handle_start.y, handle_end.x, handle_end.y, end.x, end.y),
+						PathCommand::Arc {
+							center,
+							radius,
+							start_angle,
+							end_angle,
+						} => path_2d.arc(center.x, center.y, radius, start_angle, end_angle).expect("Failed to draw the arc"),
+						PathCommand::Rect { corner, size } => path_2d.rect(corner.x, corner.y, size.x, size.y),
+						PathCommand::ClosePath => path_2d.close_path(),
+					}
+				}
+
+				match style {
+					DrawStyle::Fill { color } => {
+						render_context.set_fill_style_str(color);
+						render_context.fill_with_path_2d(&path_2d);
+					}
+					DrawStyle::FillPattern { color } => {
+						render_context.set_fill_style_canvas_pattern(&fill_pattern(render_context, *color));
+						render_context.fill_with_path_2d(&path_2d);
+					}
+					DrawStyle::Stroke { color, width, dash, round_cap } => {
+						render_context.set_stroke_style_str(color);
+						render_context.set_line_width(*width);
+						if let Some((dash_width, dash_gap_width, dash_offset)) = *dash {
+							let array = js_sys::Array::new();
+							array.push(&JsValue::from(dash_width));
+							array.push(&JsValue::from(dash_gap_width));
+							render_context
+								.set_line_dash(&JsValue::from(array))
+								.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
+								.ok();
+							render_context.set_line_dash_offset(dash_offset);
+						}
+						if *round_cap {
+							render_context.set_line_cap("round");
+						}
+
+						render_context.stroke_with_path(&path_2d);
+
+						// Reset the dash pattern back to solid and the line cap back to the default
+						if dash.is_some() {
+							render_context
+								.set_line_dash(&JsValue::from(js_sys::Array::new()))
+								.map_err(|error| log::warn!("Error drawing dashed line: {:?}", error))
+								.ok();
+							render_context.set_line_dash_offset(0.);
+						}
+						if *round_cap {
+							render_context.set_line_cap("butt");
+						}
+					}
+				}
+			}
+			DrawBatch::Text(text) => {
+				set_transform(text.transform);
+
+				if let Some((background, [x, y, width, height])) = &text.background {
+					render_context.set_fill_style_str(background);
+					render_context.fill_rect(*x, *y, *width, *height);
+				}
+
+				render_context.set_fill_style_str(&text.font_color);
+				render_context.fill_text(&text.text, 0., 0.).expect("Failed to draw the text at the calculated position");
+
+				set_transform(dpi_transform);
+			}
+		}
+	}
+
+	render_context.set_line_width(1.);
+	render_context.reset_transform().expect("transform should be able to be reset to be able to account for DPI");
+	commands.clear();
+}
+
+/// Creates a repeating 4x4 pixel checkered pattern of the given sRGB color.
+fn fill_pattern(render_context: &web_sys::CanvasRenderingContext2d, color: [u8; 4]) -> web_sys::CanvasPattern {
+	const PATTERN_WIDTH: usize = 4;
+	const PATTERN_HEIGHT: usize = 4;
+
+	let pattern_canvas = OffscreenCanvas::new(PATTERN_WIDTH as u32, PATTERN_HEIGHT as u32).unwrap();
+	let pattern_context: OffscreenCanvasRenderingContext2d = pattern_canvas
+		.get_context("2d")
+		.ok()
+		.flatten()
+		.expect("Failed to get canvas context")
+		.dyn_into()
+		.expect("Context should be a canvas 2d context");
+
+	// 4x4 pixels, 4 components (RGBA) per pixel
+	let mut data = [0_u8; 4 * PATTERN_WIDTH * PATTERN_HEIGHT];
+
+	// ┌▄▄┬──┬──┬──┐
+	// ├▀▀┼──┼──┼──┤
+	// ├──┼──┼▄▄┼──┤
+	// ├──┼──┼▀▀┼──┤
+	// └──┴──┴──┴──┘
+	let pixels = [(0, 0), (2, 2)];
+	for &(x, y) in &pixels {
+		let index = (x + y * PATTERN_WIDTH) * 4;
+		data[index..index + 4].copy_from_slice(&color);
+	}
+
+	let image_data = web_sys::ImageData::new_with_u8_clamped_array_and_sh(wasm_bindgen::Clamped(&data), PATTERN_WIDTH as u32, PATTERN_HEIGHT as u32).unwrap();
+	pattern_context.put_image_data(&image_data, 0., 0.).unwrap();
+	render_context.create_pattern_with_offscreen_canvas(&pattern_canvas, "repeat").unwrap().unwrap()
+}
+
 pub enum Pivot {
 	Start,
 	Middle,
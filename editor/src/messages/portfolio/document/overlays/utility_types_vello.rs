@@ -1,9 +1,12 @@
+use super::overlay_hit_test::{OverlayHitId, OverlayHitRegions, OverlayHitShape};
 use crate::consts::{
 	ARC_SWEEP_GIZMO_RADIUS, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_BLUE_50, COLOR_OVERLAY_GREEN, COLOR_OVERLAY_RED, COLOR_OVERLAY_WHITE, COLOR_OVERLAY_YELLOW, COLOR_OVERLAY_YELLOW_DULL,
 	COMPASS_ROSE_ARROW_SIZE, COMPASS_ROSE_HOVER_RING_DIAMETER, COMPASS_ROSE_MAIN_RING_DIAMETER, COMPASS_ROSE_RING_INNER_DIAMETER, DOWEL_PIN_RADIUS, MANIPULATOR_GROUP_MARKER_SIZE,
 	PIVOT_CROSSHAIR_LENGTH, PIVOT_CROSSHAIR_THICKNESS, PIVOT_DIAMETER,
 };
+use crate::messages::preferences::{OverlayQuality, OverlaySupersampling};
 use crate::messages::prelude::Message;
+use crate::messages::tool::utility_types::ToolType;
 use bezier_rs::{Bezier, Subpath};
 use core::borrow::Borrow;
 use core::f64::consts::{FRAC_PI_2, PI, TAU};
@@ -12,7 +15,9 @@ use graphene_std::Color;
 use graphene_std::math::quad::Quad;
 use graphene_std::vector::click_target::ClickTargetType;
 use graphene_std::vector::{PointId, SegmentId, VectorData};
+use std::cell::{Cell, RefCell};
 use std::collections::HashMap;
+use std::rc::Rc;
 use vello::Scene;
 use vello::kurbo::{self, BezPath};
 use vello::peniko;
@@ -23,6 +28,74 @@ pub fn empty_provider() -> OverlayProvider {
 	|_| Message::NoOp
 }
 
+pub fn empty_provider_handle() -> OverlayProviderHandle {
+	OverlayProviderHandle::new(OverlayProviderOwner::ToolSystem, empty_provider())
+}
+
+/// Identifies what registered an overlay provider, so every provider belonging to an owner can be removed together when it's deactivated.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
+pub enum OverlayProviderOwner {
+	/// Providers kept alive by the tool system as a whole, regardless of which tool is active (such as the artboard overlays).
+	ToolSystem,
+	/// Providers registered by the GRS (grab, rotate, scale) transform operations while they're in progress.
+	TransformLayer,
+	/// Providers registered by a tool while it's the active tool.
+	Tool(ToolType),
+}
+
+/// The registration token for an overlay provider, which its owner stores and hands back to remove the provider.
+/// Since the provider is tied to its owner, any left behind are cleaned up (and reported) by [`super::OverlaysMessage::RemoveOwnerProviders`] once the owner is deactivated.
+#[derive(Eq, Clone, Copy, Debug)]
+pub struct OverlayProviderHandle {
+	pub owner: OverlayProviderOwner,
+	pub provider: OverlayProvider,
+	pub layer: OverlayLayer,
+}
+
+impl OverlayProviderHandle {
+	/// Registers a provider which draws in the [`OverlayLayer::Foreground`], above the artwork.
+	pub const fn new(owner: OverlayProviderOwner, provider: OverlayProvider) -> Self {
+		Self {
+			owner,
+			provider,
+			layer: OverlayLayer::Foreground,
+		}
+	}
+
+	pub const fn in_layer(self, layer: OverlayLayer) -> Self {
+		Self { layer, ..self }
+	}
+}
+
+impl PartialEq for OverlayProviderHandle {
+	fn eq(&self, other: &Self) -> bool {
+		self.owner == other.owner && std::ptr::fn_addr_eq(self.provider, other.provider) && self.layer == other.layer
+	}
+}
+
+impl core::hash::Hash for OverlayProviderHandle {
+	fn hash<H: core::hash::Hasher>(&self, state: &mut H) {
+		self.owner.hash(state);
+		(self.provider as usize).hash(state);
+		self.layer.hash(state);
+	}
+}
+
+/// Which side of the rendered artwork an overlay is drawn on.
+#[derive(PartialEq, Eq, Hash, Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum OverlayLayer {
+	/// Drawn underneath the artwork, for guides like the grid which shouldn't overprint it.
+	Background,
+	/// Drawn above the artwork, for interactive overlays like outlines and handles.
+	#[default]
+	Foreground,
+}
+
+impl OverlayLayer {
+	/// Every layer, in the order they're stacked from bottom to top.
+	pub const ALL: [Self; 2] = [Self::Background, Self::Foreground];
+}
+
 // Types of overlays used by DocumentMessage to enable/disable select group of overlays in the frontend
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum OverlaysType {
@@ -132,23 +205,129 @@ impl OverlaysVisibilitySettings {
 	}
 }
 
+/// The Vello scene of each [`OverlayLayer`], which the providers of a frame draw into through the shared scene of their [`OverlayContext`].
+#[derive(Clone, Default)]
+pub struct OverlayScenes(HashMap<OverlayLayer, Rc<RefCell<Scene>>>);
+
+// Manual implementation since Scene doesn't implement Debug
+impl std::fmt::Debug for OverlayScenes {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_set().entries(self.0.keys()).finish()
+	}
+}
+
+impl OverlayScenes {
+	/// Clears what every layer drew in the previous frame.
+	pub fn begin_frame(&mut self) {
+		self.0.values().for_each(|scene| scene.borrow_mut().reset());
+	}
+
+	/// The scene shared by everything drawn in the layer during the current frame.
+	pub fn layer(&mut self, layer: OverlayLayer) -> Rc<RefCell<Scene>> {
+		self.0.entry(layer).or_default().clone()
+	}
+
+	/// A copy of what was drawn in the layer during the most recent frame.
+	pub fn scene(&self, layer: OverlayLayer) -> Scene {
+		self.0.get(&layer).map(|scene| RefCell::borrow(scene).clone()).unwrap_or_default()
+	}
+}
+
+/// The native overlays of a finished frame, for the app to render into a texture for each layer and composite around the artwork.
+#[derive(Clone)]
+pub struct OverlayFrame {
+	/// Drawn in viewport coordinates, so they're scaled by [`OverlayRenderSettings::render_scale`] when rendered.
+	pub background: Scene,
+	pub foreground: Scene,
+	pub settings: OverlayRenderSettings,
+}
+
+// Manual implementation since Scene doesn't implement Debug
+impl std::fmt::Debug for OverlayFrame {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.debug_struct("OverlayFrame").field("settings", &self.settings).finish_non_exhaustive()
+	}
+}
+
+/// How the texture which the native overlays are rendered into is sampled when it's composited over the viewport.
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub enum OverlayTextureFilter {
+	/// Maps each texture pixel to exactly one viewport pixel, keeping aliased edges crisp.
+	Nearest,
+	/// Blends neighboring texture pixels, which is needed to downsample a supersampled texture.
+	#[default]
+	Linear,
+}
+
+/// The resolution and rasterization of the native overlays for a frame, derived from the viewport and the user's [`OverlayQuality`] preference.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct OverlayRenderSettings {
+	/// The size of the texture the overlays are rendered into, in physical pixels, which is a multiple of the viewport's size when supersampling.
+	pub texture_size: glam::UVec2,
+	/// The scale from viewport coordinates to texture pixels, which is the device pixel ratio multiplied by the supersampling factor.
+	pub render_scale: f64,
+	pub anti_aliasing: bool,
+	pub texture_filter: OverlayTextureFilter,
+}
+
+impl Default for OverlayRenderSettings {
+	fn default() -> Self {
+		Self::new(DVec2::ZERO, 1., OverlayQuality::default())
+	}
+}
+
+impl OverlayRenderSettings {
+	pub fn new(viewport_size: DVec2, device_pixel_ratio: f64, quality: OverlayQuality) -> Self {
+		let render_scale = device_pixel_ratio * quality.supersampling.factor() as f64;
+		// Without supersampling the texture matches the physical pixels of the viewport, so filtering would only blur the deliberately aliased edges
+		let texture_filter = if quality.anti_aliasing || quality.supersampling != OverlaySupersampling::X1 {
+			OverlayTextureFilter::Linear
+		} else {
+			OverlayTextureFilter::Nearest
+		};
+
+		Self {
+			texture_size: (viewport_size * render_scale).ceil().max(DVec2::ZERO).as_uvec2(),
+			render_scale,
+			anti_aliasing: quality.anti_aliasing,
+			texture_filter,
+		}
+	}
+
+	/// How Vello rasterizes the overlays. It always anti-aliases, so aliased overlays use its cheapest method and rely on the [`OverlayTextureFilter::Nearest`] filter for their crisp edges.
+	pub fn antialiasing_method(&self) -> vello::AaConfig {
+		if self.anti_aliasing { vello::AaConfig::Msaa16 } else { vello::AaConfig::Area }
+	}
+}
+
 #[derive(Clone, serde::Serialize, serde::Deserialize, specta::Type)]
 pub struct OverlayContext {
+	/// Shared by every provider drawing in the same [`OverlayLayer`] of the current frame, which renders it once they're all done.
 	// Serde functionality isn't used but is required by the message system macros
 	#[serde(skip)]
 	#[specta(skip)]
-	pub scene: Scene,
+	pub scene: Rc<RefCell<Scene>>,
+	/// Shared by every provider drawing in the current frame, and kept until the next frame is drawn so clicks can be resolved against it.
+	#[serde(skip)]
+	#[specta(skip)]
+	pub hit_regions: Rc<RefCell<OverlayHitRegions>>,
+	/// Set by providers whose overlays animate over time, so the overlays are drawn again on the next animation frame.
+	#[serde(skip)]
+	#[specta(skip)]
+	pub animating: Rc<Cell<bool>>,
 	pub size: DVec2,
 	// The device pixel ratio is a property provided by the browser window and is the CSS pixel size divided by the physical monitor's pixel size.
 	// It allows better pixel density of visualizations on high-DPI displays where the OS display scaling is not 100%, or where the browser is zoomed.
 	pub device_pixel_ratio: f64,
+	/// The scale from viewport coordinates to the pixels of the texture the scene is rendered into, see [`OverlayRenderSettings::render_scale`].
+	pub render_scale: f64,
 	pub visibility_settings: OverlaysVisibilitySettings,
 }
 
 // Manual implementations since Scene doesn't implement PartialEq or Debug
 impl PartialEq for OverlayContext {
 	fn eq(&self, other: &Self) -> bool {
-		self.size == other.size && self.device_pixel_ratio == other.device_pixel_ratio && self.visibility_settings == other.visibility_settings
+		self.size == other.size && self.device_pixel_ratio == other.device_pixel_ratio && self.render_scale == other.render_scale && self.visibility_settings == other.visibility_settings
 	}
 }
 
@@ -156,8 +335,11 @@ impl std::fmt::Debug for OverlayContext {
 	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
 		f.debug_struct("OverlayContext")
 			.field("scene", &"Scene { ... }")
+			.field("hit_regions", &self.hit_regions)
+			.field("animating", &self.animating)
 			.field("size", &self.size)
 			.field("device_pixel_ratio", &self.device_pixel_ratio)
+			.field("render_scale", &self.render_scale)
 			.field("visibility_settings", &self.visibility_settings)
 			.finish()
 	}
@@ -167,9 +349,12 @@ impl std::fmt::Debug for OverlayContext {
 impl Default for OverlayContext {
 	fn default() -> Self {
 		Self {
-			scene: Scene::new(),
+			scene: Default::default(),
+			hit_regions: Default::default(),
+			animating: Default::default(),
 			size: DVec2::ZERO,
 			device_pixel_ratio: 1.0,
+			render_scale: 1.0,
 			visibility_settings: OverlaysVisibilitySettings::default(),
 		}
 	}
@@ -190,6 +375,16 @@ impl OverlayContext {
 		peniko::Color::from_rgba8(r, g, b, a)
 	}
 
+	/// Makes the given area clickable as `id` until the next frame is drawn, in the same viewport space as the drawing functions.
+	pub fn register_hit_region(&self, id: OverlayHitId, shape: OverlayHitShape) {
+		self.hit_regions.borrow_mut().register(id, shape);
+	}
+
+	/// Draws the overlays again on the next animation frame, for overlays which change over time even without any input.
+	pub fn request_animation_frame(&self) {
+		self.animating.set(true);
+	}
+
 	pub fn quad(&mut self, quad: Quad, stroke_color: Option<&str>, color_fill: Option<&str>) {
 		self.dashed_polygon(&quad.0, stroke_color, color_fill, None, None, None);
 	}
@@ -210,9 +405,9 @@ impl OverlayContext {
 		path.line_to(kurbo::Point::new(edge2.x, edge2.y));
 		path.close_path();
 
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &path);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &path);
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &path);
+		self.scene.borrow_mut().stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &path);
 	}
 
 	pub fn dashed_quad(&mut self, quad: Quad, stroke_color: Option<&str>, color_fill: Option<&str>, dash_width: Option<f64>, dash_gap_width: Option<f64>, dash_offset: Option<f64>) {
@@ -241,7 +436,7 @@ impl OverlayContext {
 		path.close_path();
 
 		if let Some(color_fill) = color_fill {
-			self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &path);
+			self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &path);
 		}
 
 		let stroke_color = stroke_color.unwrap_or(COLOR_OVERLAY_BLUE);
@@ -252,7 +447,7 @@ impl OverlayContext {
 			stroke = stroke.with_dashes(dash_offset.unwrap_or(0.), [dash_width, dash_gap]);
 		}
 
-		self.scene.stroke(&stroke, transform, Self::parse_color(stroke_color), None, &path);
+		self.scene.borrow_mut().stroke(&stroke, transform, Self::parse_color(stroke_color), None, &path);
 	}
 
 	pub fn line(&mut self, start: DVec2, end: DVec2, color: Option<&str>, thickness: Option<f64>) {
@@ -277,7 +472,7 @@ impl OverlayContext {
 			stroke = stroke.with_dashes(dash_offset.unwrap_or(0.), [dash_width, dash_gap]);
 		}
 
-		self.scene.stroke(&stroke, transform, Self::parse_color(color.unwrap_or(COLOR_OVERLAY_BLUE)), None, &path);
+		self.scene.borrow_mut().stroke(&stroke, transform, Self::parse_color(color.unwrap_or(COLOR_OVERLAY_BLUE)), None, &path);
 	}
 
 	pub fn manipulator_handle(&mut self, position: DVec2, selected: bool, color: Option<&str>) {
@@ -287,9 +482,10 @@ impl OverlayContext {
 		let circle = kurbo::Circle::new((position.x, position.y), MANIPULATOR_GROUP_MARKER_SIZE / 2.);
 
 		let fill = if selected { COLOR_OVERLAY_BLUE } else { COLOR_OVERLAY_WHITE };
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(fill), None, &circle);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(fill), None, &circle);
 
 		self.scene
+			.borrow_mut()
 			.stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color.unwrap_or(COLOR_OVERLAY_BLUE)), None, &circle);
 	}
 
@@ -300,7 +496,7 @@ impl OverlayContext {
 	}
 
 	fn get_transform(&self) -> kurbo::Affine {
-		kurbo::Affine::scale(self.device_pixel_ratio)
+		kurbo::Affine::scale(self.render_scale)
 	}
 
 	pub fn square(&mut self, position: DVec2, size: Option<f64>, color_fill: Option<&str>, color_stroke: Option<&str>) {
@@ -314,9 +510,9 @@ impl OverlayContext {
 		let transform = self.get_transform();
 		let rect = kurbo::Rect::new(corner.x, corner.y, corner.x + size, corner.y + size);
 
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &rect);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &rect);
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &rect);
+		self.scene.borrow_mut().stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &rect);
 	}
 
 	pub fn pixel(&mut self, position: DVec2, color: Option<&str>) {
@@ -329,7 +525,7 @@ impl OverlayContext {
 		let transform = self.get_transform();
 		let rect = kurbo::Rect::new(corner.x, corner.y, corner.x + size, corner.y + size);
 
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &rect);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &rect);
 	}
 
 	pub fn circle(&mut self, position: DVec2, radius: f64, color_fill: Option<&str>, color_stroke: Option<&str>) {
@@ -340,9 +536,9 @@ impl OverlayContext {
 		let transform = self.get_transform();
 		let circle = kurbo::Circle::new((position.x, position.y), radius);
 
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &circle);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color_fill), None, &circle);
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &circle);
+		self.scene.borrow_mut().stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color_stroke), None, &circle);
 	}
 
 	pub fn draw_arc(&mut self, center: DVec2, radius: f64, start_from: f64, end_at: f64) {
@@ -376,7 +572,9 @@ impl OverlayContext {
 			);
 		}
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), self.get_transform(), Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
+		self.scene
+			.borrow_mut()
+			.stroke(&kurbo::Stroke::new(1.0), self.get_transform(), Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
 	}
 
 	pub fn draw_arc_gizmo_angle(&mut self, pivot: DVec2, bold_radius: f64, arc_radius: f64, offset_angle: f64, angle: f64) {
@@ -433,6 +631,7 @@ impl OverlayContext {
 
 			let circle = kurbo::Circle::new((center.x, center.y), HOVER_RING_CENTERLINE_RADIUS);
 			self.scene
+				.borrow_mut()
 				.stroke(&kurbo::Stroke::new(HOVER_RING_STROKE_WIDTH), transform, Self::parse_color(&fill_color), None, &circle);
 		}
 
@@ -457,13 +656,14 @@ impl OverlayContext {
 			path.close_path();
 
 			let color_parsed = Self::parse_color(color);
-			self.scene.fill(peniko::Fill::NonZero, transform, color_parsed, None, &path);
-			self.scene.stroke(&kurbo::Stroke::new(0.01), transform, color_parsed, None, &path);
+			self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, color_parsed, None, &path);
+			self.scene.borrow_mut().stroke(&kurbo::Stroke::new(0.01), transform, color_parsed, None, &path);
 		}
 
 		// Main ring
 		let circle = kurbo::Circle::new((center.x, center.y), MAIN_RING_CENTERLINE_RADIUS);
 		self.scene
+			.borrow_mut()
 			.stroke(&kurbo::Stroke::new(MAIN_RING_STROKE_WIDTH), transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &circle);
 	}
 
@@ -475,7 +675,7 @@ impl OverlayContext {
 
 		// Circle
 		let circle = kurbo::Circle::new((x, y), PIVOT_DIAMETER / 2.);
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &circle);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &circle);
 
 		// Crosshair
 		const CROSSHAIR_RADIUS: f64 = (PIVOT_CROSSHAIR_LENGTH - PIVOT_CROSSHAIR_THICKNESS) / 2.;
@@ -488,14 +688,14 @@ impl OverlayContext {
 		path.move_to(kurbo::Point::new(x + CROSSHAIR_RADIUS * uv.x, y + CROSSHAIR_RADIUS * uv.y));
 		path.line_to(kurbo::Point::new(x - CROSSHAIR_RADIUS * uv.x, y - CROSSHAIR_RADIUS * uv.y));
 
-		self.scene.stroke(&stroke, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &path);
+		self.scene.borrow_mut().stroke(&stroke, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &path);
 
 		// Vertical line
 		let mut path = BezPath::new();
 		path.move_to(kurbo::Point::new(x - CROSSHAIR_RADIUS * uv.y, y + CROSSHAIR_RADIUS * uv.x));
 		path.line_to(kurbo::Point::new(x + CROSSHAIR_RADIUS * uv.y, y - CROSSHAIR_RADIUS * uv.x));
 
-		self.scene.stroke(&stroke, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &path);
+		self.scene.borrow_mut().stroke(&stroke, transform, Self::parse_color(COLOR_OVERLAY_YELLOW), None, &path);
 	}
 
 	pub fn dowel_pin(&mut self, position: DVec2, angle: f64, color: Option<&str>) {
@@ -506,8 +706,8 @@ impl OverlayContext {
 
 		// Draw the background circle with a white fill and colored outline
 		let circle = kurbo::Circle::new((x, y), DOWEL_PIN_RADIUS);
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(COLOR_OVERLAY_WHITE), None, &circle);
-		self.scene.stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color), None, &circle);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(COLOR_OVERLAY_WHITE), None, &circle);
+		self.scene.borrow_mut().stroke(&kurbo::Stroke::new(1.0), transform, Self::parse_color(color), None, &circle);
 
 		// Draw the two filled sectors using paths
 		let mut path = BezPath::new();
@@ -536,7 +736,7 @@ impl OverlayContext {
 		});
 		path.close_path();
 
-		self.scene.fill(peniko::Fill::NonZero, transform, Self::parse_color(color), None, &path);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, transform, Self::parse_color(color), None, &path);
 	}
 
 	#[allow(clippy::too_many_arguments)]
@@ -559,7 +759,9 @@ impl OverlayContext {
 			self.bezier_to_path(bezier, transform, move_to, &mut path);
 		}
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
+		self.scene
+			.borrow_mut()
+			.stroke(&kurbo::Stroke::new(1.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
 	}
 
 	/// Used by the Pen tool in order to show how the bezier curve would look like.
@@ -568,7 +770,9 @@ impl OverlayContext {
 		let mut path = BezPath::new();
 		self.bezier_to_path(bezier, transform, true, &mut path);
 
-		self.scene.stroke(&kurbo::Stroke::new(1.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
+		self.scene
+			.borrow_mut()
+			.stroke(&kurbo::Stroke::new(1.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
 	}
 
 	/// Used by the path tool segment mode in order to show the selected segments.
@@ -577,7 +781,9 @@ impl OverlayContext {
 		let mut path = BezPath::new();
 		self.bezier_to_path(bezier, transform, true, &mut path);
 
-		self.scene.stroke(&kurbo::Stroke::new(4.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
+		self.scene
+			.borrow_mut()
+			.stroke(&kurbo::Stroke::new(4.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE), None, &path);
 	}
 
 	pub fn outline_overlay_bezier(&mut self, bezier: Bezier, transform: DAffine2) {
@@ -585,7 +791,9 @@ impl OverlayContext {
 		let mut path = BezPath::new();
 		self.bezier_to_path(bezier, transform, true, &mut path);
 
-		self.scene.stroke(&kurbo::Stroke::new(4.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE_50), None, &path);
+		self.scene
+			.borrow_mut()
+			.stroke(&kurbo::Stroke::new(4.0), vello_transform, Self::parse_color(COLOR_OVERLAY_BLUE_50), None, &path);
 	}
 
 	fn bezier_to_path(&self, bezier: Bezier, transform: DAffine2, move_to: bool, path: &mut BezPath) {
@@ -670,7 +878,7 @@ impl OverlayContext {
 			let path = self.push_path(subpaths.iter(), transform);
 			let color = color.unwrap_or(COLOR_OVERLAY_BLUE);
 
-			self.scene.stroke(&kurbo::Stroke::new(1.0), self.get_transform(), Self::parse_color(color), None, &path);
+			self.scene.borrow_mut().stroke(&kurbo::Stroke::new(1.0), self.get_transform(), Self::parse_color(color), None, &path);
 		}
 	}
 
@@ -679,7 +887,7 @@ impl OverlayContext {
 	pub fn fill_path(&mut self, subpaths: impl Iterator<Item = impl Borrow<Subpath<PointId>>>, transform: DAffine2, color: &str) {
 		let path = self.push_path(subpaths, transform);
 
-		self.scene.fill(peniko::Fill::NonZero, self.get_transform(), Self::parse_color(color), None, &path);
+		self.scene.borrow_mut().fill(peniko::Fill::NonZero, self.get_transform(), Self::parse_color(color), None, &path);
 	}
 
 	/// Fills the area inside the path with a pattern. Assumes `color` is in gamma space.
@@ -690,7 +898,7 @@ impl OverlayContext {
 		let path = self.push_path(subpaths, transform);
 		let semi_transparent_color = color.with_alpha(0.5);
 
-		self.scene.fill(
+		self.scene.borrow_mut().fill(
 			peniko::Fill::NonZero,
 			self.get_transform(),
 			peniko::Color::from_rgba8(
@@ -756,3 +964,35 @@ pub enum DrawHandles {
 	FrontierHandles(HashMap<SegmentId, Vec<PointId>>),
 	None,
 }
+
+#[cfg(test)]
+mod test_overlay_render_settings {
+	use super::*;
+
+	#[test]
+	fn supersampling_scales_the_texture() {
+		let quality = OverlayQuality {
+			anti_aliasing: true,
+			supersampling: OverlaySupersampling::X2,
+		};
+		let settings = OverlayRenderSettings::new(DVec2::new(800., 600.5), 1.5, quality);
+
+		assert_eq!(settings.render_scale, 3.);
+		assert_eq!(settings.texture_size, glam::UVec2::new(2400, 1802));
+		assert_eq!(settings.texture_filter, OverlayTextureFilter::Linear);
+	}
+
+	#[test]
+	fn aliased_overlays_are_sampled_without_filtering() {
+		let aliased = |supersampling| OverlayQuality { anti_aliasing: false, supersampling };
+
+		assert_eq!(
+			OverlayRenderSettings::new(DVec2::splat(100.), 1., aliased(OverlaySupersampling::X1)).texture_filter,
+			OverlayTextureFilter::Nearest
+		);
+		assert_eq!(
+			OverlayRenderSettings::new(DVec2::splat(100.), 1., aliased(OverlaySupersampling::X2)).texture_filter,
+			OverlayTextureFilter::Linear
+		);
+	}
+}
@@ -1,4 +1,6 @@
 use crate::messages::prelude::*;
+use graph_craft::document::NodeId;
+use graphene_std::raster::curve::CurveChannel;
 
 #[impl_message(Message, DocumentMessage, PropertiesPanel)]
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
@@ -6,4 +8,6 @@ pub enum PropertiesPanelMessage {
 	// Messages
 	Clear,
 	Refresh,
+	SelectGradientStops { node_id: NodeId, input_index: usize, stops: Vec<usize> },
+	SelectCurveChannel { node_id: NodeId, input_index: usize, channel: CurveChannel },
 }
@@ -1,3 +1,4 @@
+use graphene_std::raster::curve::CurveChannel;
 use graphene_std::uuid::NodeId;
 
 use crate::messages::layout::utility_types::widget_prelude::*;
@@ -17,7 +18,12 @@ pub struct PropertiesPanelMessageContext<'a> {
 }
 
 #[derive(Debug, Clone, Default, ExtractField)]
-pub struct PropertiesPanelMessageHandler {}
+pub struct PropertiesPanelMessageHandler {
+	/// The indices of the gradient stops selected in the stops list of each node input, keyed by the node and input index.
+	selected_gradient_stops: HashMap<(NodeId, usize), Vec<usize>>,
+	/// The channel shown in the curve editor of each node input, keyed by the node and input index.
+	selected_curve_channels: HashMap<(NodeId, usize), CurveChannel>,
+}
 
 #[message_handler_data]
 impl MessageHandler<PropertiesPanelMessage, PropertiesPanelMessageContext<'_>> for PropertiesPanelMessageHandler {
@@ -45,6 +51,8 @@ impl MessageHandler<PropertiesPanelMessage, PropertiesPanelMessageContext<'_>> f
 					selection_network_path,
 					document_name,
 					executor,
+					selected_gradient_stops: &self.selected_gradient_stops,
+					selected_curve_channels: &self.selected_curve_channels,
 				};
 				let properties_sections = NodeGraphMessageHandler::collate_properties(&mut node_properties_context);
 
@@ -53,6 +61,14 @@ impl MessageHandler<PropertiesPanelMessage, PropertiesPanelMessageContext<'_>> f
 					layout_target: LayoutTarget::PropertiesSections,
 				});
 			}
+			PropertiesPanelMessage::SelectGradientStops { node_id, input_index, stops } => {
+				self.selected_gradient_stops.insert((node_id, input_index), stops);
+				responses.add(PropertiesPanelMessage::Refresh);
+			}
+			PropertiesPanelMessage::SelectCurveChannel { node_id, input_index, channel } => {
+				self.selected_curve_channels.insert((node_id, input_index), channel);
+				responses.add(PropertiesPanelMessage::Refresh);
+			}
 		}
 	}
 
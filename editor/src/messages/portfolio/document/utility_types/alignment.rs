@@ -0,0 +1,112 @@
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, DistributeMode};
+use glam::DVec2;
+
+fn axis_vector(axis: AlignAxis) -> DVec2 {
+	match axis {
+		AlignAxis::X => DVec2::X,
+		AlignAxis::Y => DVec2::Y,
+	}
+}
+
+fn aggregate_point([min, max]: [DVec2; 2], aggregate: AlignAggregate) -> DVec2 {
+	match aggregate {
+		AlignAggregate::Min => min,
+		AlignAggregate::Max => max,
+		AlignAggregate::Center => (min + max) / 2.,
+	}
+}
+
+/// The offset which moves the bounds along the axis so their edge or center lines up with that of the target bounds.
+pub fn align_offset(bounds: [DVec2; 2], target: [DVec2; 2], axis: AlignAxis, aggregate: AlignAggregate) -> DVec2 {
+	(aggregate_point(target, aggregate) - aggregate_point(bounds, aggregate)) * axis_vector(axis)
+}
+
+/// The offsets, in the order of the given bounds, which spread them evenly along the axis between the two outermost ones, which stay in place.
+/// Bounds are ordered along the axis by their centers. Fewer than three bounds are left where they are.
+pub fn distribute_offsets(bounds: &[[DVec2; 2]], axis: AlignAxis, mode: DistributeMode) -> Vec<DVec2> {
+	let mut offsets = vec![DVec2::ZERO; bounds.len()];
+	if bounds.len() < 3 {
+		return offsets;
+	}
+
+	let axis_vector = axis_vector(axis);
+	let along = |point: DVec2| point.dot(axis_vector);
+	let center = |[min, max]: [DVec2; 2]| along((min + max) / 2.);
+	let size = |[min, max]: [DVec2; 2]| along(max - min);
+
+	let mut order: Vec<usize> = (0..bounds.len()).collect();
+	order.sort_by(|&a, &b| center(bounds[a]).total_cmp(&center(bounds[b])));
+	let (first, last) = (bounds[order[0]], bounds[order[order.len() - 1]]);
+	let intervals = (order.len() - 1) as f64;
+
+	match mode {
+		DistributeMode::Centers => {
+			let step = (center(last) - center(first)) / intervals;
+			for (position, &index) in order.iter().enumerate() {
+				let target = center(first) + step * position as f64;
+				offsets[index] = axis_vector * (target - center(bounds[index]));
+			}
+		}
+		DistributeMode::Spacing => {
+			let span = along(last[1]) - along(first[0]);
+			let total_size: f64 = order.iter().map(|&index| size(bounds[index])).sum();
+			let gap = (span - total_size) / intervals;
+
+			let mut start = along(first[0]);
+			for &index in &order {
+				offsets[index] = axis_vector * (start - along(bounds[index][0]));
+				start += size(bounds[index]) + gap;
+			}
+		}
+	}
+
+	offsets
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn bounds(min_x: f64, width: f64) -> [DVec2; 2] {
+		[DVec2::new(min_x, 0.), DVec2::new(min_x + width, 10.)]
+	}
+
+	#[test]
+	fn aligning_moves_only_along_the_axis() {
+		let layer = [DVec2::new(10., 20.), DVec2::new(30., 60.)];
+		let target = [DVec2::new(0., 0.), DVec2::new(100., 200.)];
+
+		assert_eq!(align_offset(layer, target, AlignAxis::X, AlignAggregate::Min), DVec2::new(-10., 0.));
+		assert_eq!(align_offset(layer, target, AlignAxis::X, AlignAggregate::Center), DVec2::new(30., 0.));
+		assert_eq!(align_offset(layer, target, AlignAxis::X, AlignAggregate::Max), DVec2::new(70., 0.));
+		assert_eq!(align_offset(layer, target, AlignAxis::Y, AlignAggregate::Min), DVec2::new(0., -20.));
+		assert_eq!(align_offset(layer, target, AlignAxis::Y, AlignAggregate::Center), DVec2::new(0., 60.));
+		assert_eq!(align_offset(layer, target, AlignAxis::Y, AlignAggregate::Max), DVec2::new(0., 140.));
+	}
+
+	#[test]
+	fn distributing_by_spacing_makes_the_gaps_equal() {
+		// Given out of order, with widths 10, 30, 20, and 10 between 0 and 130, which leaves gaps of 20
+		let layers = [bounds(120., 10.), bounds(5., 30.), bounds(0., 10.), bounds(90., 20.)];
+		let offsets = distribute_offsets(&layers, AlignAxis::X, DistributeMode::Spacing);
+
+		let moved: Vec<_> = layers.iter().zip(&offsets).map(|(bounds, offset)| bounds[0].x + offset.x).collect();
+		assert_eq!(moved, [120., 30., 0., 80.]);
+		assert!(offsets.iter().all(|offset| offset.y == 0.));
+	}
+
+	#[test]
+	fn distributing_by_centers_makes_the_steps_equal() {
+		// Centers at 5, 20, 30, and 95, which become 5, 35, 65, and 95
+		let layers = [bounds(0., 10.), bounds(10., 20.), bounds(25., 10.), bounds(90., 10.)];
+		let offsets = distribute_offsets(&layers, AlignAxis::X, DistributeMode::Centers);
+
+		assert_eq!(offsets, [DVec2::ZERO, DVec2::new(15., 0.), DVec2::new(35., 0.), DVec2::ZERO]);
+	}
+
+	#[test]
+	fn distributing_fewer_than_three_layers_does_nothing() {
+		let layers = [bounds(0., 10.), bounds(50., 10.)];
+		assert_eq!(distribute_offsets(&layers, AlignAxis::X, DistributeMode::Spacing), [DVec2::ZERO; 2]);
+	}
+}
@@ -0,0 +1,207 @@
+use super::network_interface::{DocumentNodePersistentMetadata, NodeNetworkMetadata, NodeTemplate};
+use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeNetwork};
+use std::collections::HashSet;
+use std::hash::{Hash, Hasher};
+
+/// Identifies one of the document's custom nodes, which each of its instances refers to.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct CustomNodeId(pub u64);
+
+/// A node which the user created from a selection of nodes, that can be placed from the node catalog like the built-in nodes.
+///
+/// Every instance shares the network of nodes inside, so editing the network of any instance edits the definition and all other instances.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomNodeDefinition {
+	pub id: CustomNodeId,
+	pub name: String,
+	/// The node which is inserted to place a new instance, whose implementation is the network of nodes inside.
+	pub node_template: NodeTemplate,
+}
+
+/// The document's library of custom nodes, in the order they were created.
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct CustomNodeLibrary(Vec<CustomNodeDefinition>);
+
+// Like the document network, only the parts of the definitions which affect the artwork are hashed and not their editor metadata
+impl Hash for CustomNodeLibrary {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		for definition in &self.0 {
+			definition.id.hash(state);
+			definition.name.hash(state);
+			definition.node_template.document_node.hash(state);
+		}
+	}
+}
+
+impl CustomNodeLibrary {
+	pub fn iter(&self) -> impl Iterator<Item = &CustomNodeDefinition> {
+		self.0.iter()
+	}
+
+	pub fn get(&self, id: CustomNodeId) -> Option<&CustomNodeDefinition> {
+		self.0.iter().find(|definition| definition.id == id)
+	}
+
+	pub fn get_by_name(&self, name: &str) -> Option<&CustomNodeDefinition> {
+		self.0.iter().find(|definition| definition.name == name)
+	}
+
+	pub fn add(&mut self, definition: CustomNodeDefinition) {
+		self.0.push(definition);
+	}
+
+	/// Replaces the network of the custom node, which is also given to its instances nested in the other custom nodes' networks.
+	pub fn update(&mut self, id: CustomNodeId, node_template: NodeTemplate) {
+		let Some(index) = self.0.iter().position(|definition| definition.id == id) else { return };
+		self.0[index].node_template = node_template;
+
+		let definition = self.0[index].clone();
+		for other in self.0.iter_mut().filter(|other| other.id != id) {
+			let node_template = &mut other.node_template;
+			if let (DocumentNodeImplementation::Network(network), Some(network_metadata)) =
+				(&mut node_template.document_node.implementation, &mut node_template.persistent_node_metadata.network_metadata)
+			{
+				apply_to_nested_instances(network, network_metadata, &definition);
+			}
+		}
+	}
+
+	/// A name for a new custom node which isn't used by any built-in or custom node, so the node catalog can place it by name.
+	pub fn unique_name(&self, name: &str) -> String {
+		let taken = |name: &str| resolve_document_node_type(name).is_some() || self.get_by_name(name).is_some();
+		if !taken(name) {
+			return name.to_string();
+		}
+		(2..).map(|number| format!("{name} {number}")).find(|name| !taken(name)).unwrap_or_default()
+	}
+
+	/// The custom nodes which have an instance inside the network of the custom node, including those nested further inside those instances.
+	pub fn dependencies(&self, id: CustomNodeId) -> HashSet<CustomNodeId> {
+		let mut dependencies = HashSet::new();
+		let mut stack = vec![id];
+		while let Some(id) = stack.pop() {
+			let Some(network_metadata) = self.get(id).and_then(|definition| definition.node_template.persistent_node_metadata.network_metadata.as_ref()) else {
+				continue;
+			};
+			for instance_of in instances_in(network_metadata) {
+				if dependencies.insert(instance_of) {
+					stack.push(instance_of);
+				}
+			}
+		}
+		dependencies
+	}
+}
+
+/// Gives an instance the network, imports, and exports of its custom node, while keeping the values and wires of the inputs it already had.
+pub fn apply_definition(node: &mut DocumentNode, metadata: &mut DocumentNodePersistentMetadata, definition: &NodeTemplate) {
+	node.implementation = definition.document_node.implementation.clone();
+	let definition_inputs = &definition.document_node.inputs;
+	node.inputs.truncate(definition_inputs.len());
+	node.inputs.extend(definition_inputs.iter().skip(node.inputs.len()).cloned());
+
+	let definition_metadata = &definition.persistent_node_metadata;
+	metadata.network_metadata = definition_metadata.network_metadata.clone();
+	metadata.input_metadata = definition_metadata.input_metadata.clone();
+	metadata.output_names = definition_metadata.output_names.clone();
+	metadata.has_primary_output = definition_metadata.has_primary_output;
+}
+
+/// Applies the definition to each of its instances in the network and its nested networks.
+fn apply_to_nested_instances(network: &mut NodeNetwork, network_metadata: &mut NodeNetworkMetadata, definition: &CustomNodeDefinition) {
+	for (node_id, node_metadata) in &mut network_metadata.persistent_metadata.node_metadata {
+		let Some(node) = network.nodes.get_mut(node_id) else { continue };
+		let metadata = &mut node_metadata.persistent_metadata;
+
+		if metadata.custom_node == Some(definition.id) {
+			apply_definition(node, metadata, &definition.node_template);
+		} else if let (DocumentNodeImplementation::Network(nested_network), Some(nested_metadata)) = (&mut node.implementation, &mut metadata.network_metadata) {
+			apply_to_nested_instances(nested_network, nested_metadata, definition);
+		}
+	}
+}
+
+/// The custom nodes which have an instance in the network or its nested networks, not counting those nested inside the instances themselves.
+fn instances_in(network_metadata: &NodeNetworkMetadata) -> Vec<CustomNodeId> {
+	let mut instances = Vec::new();
+	for node_metadata in network_metadata.persistent_metadata.node_metadata.values() {
+		let metadata = &node_metadata.persistent_metadata;
+		if let Some(id) = metadata.custom_node {
+			instances.push(id);
+		} else if let Some(nested_metadata) = &metadata.network_metadata {
+			instances.extend(instances_in(nested_metadata));
+		}
+	}
+	instances
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::messages::portfolio::document::utility_types::network_interface::DocumentNodeMetadata;
+	use graph_craft::document::value::TaggedValue;
+	use graph_craft::document::{NodeId, NodeInput};
+
+	/// A custom node whose network contains an instance of each of the given custom nodes.
+	fn definition(id: u64, name: &str, instances_of: &[u64]) -> CustomNodeDefinition {
+		let mut network = NodeNetwork::default();
+		let mut network_metadata = NodeNetworkMetadata::default();
+		for (index, &instance_of) in instances_of.iter().enumerate() {
+			network.nodes.insert(NodeId(index as u64), DocumentNode::default());
+			let mut node_metadata = DocumentNodeMetadata::default();
+			node_metadata.persistent_metadata.custom_node = Some(CustomNodeId(instance_of));
+			network_metadata.persistent_metadata.node_metadata.insert(NodeId(index as u64), node_metadata);
+		}
+
+		let mut node_template = NodeTemplate::default();
+		node_template.document_node.implementation = DocumentNodeImplementation::Network(network);
+		node_template.persistent_node_metadata.network_metadata = Some(network_metadata);
+		CustomNodeDefinition {
+			id: CustomNodeId(id),
+			name: name.to_string(),
+			node_template,
+		}
+	}
+
+	#[test]
+	fn names_are_unique_across_the_node_catalog() {
+		let mut library = CustomNodeLibrary::default();
+		assert_eq!(library.unique_name("Custom Node"), "Custom Node");
+
+		library.add(definition(1, "Custom Node", &[]));
+		assert_eq!(library.unique_name("Custom Node"), "Custom Node 2");
+
+		// Built-in nodes are placed by the same names, so theirs are taken too
+		assert_eq!(library.unique_name("Merge"), "Merge 2");
+	}
+
+	#[test]
+	fn dependencies_include_nested_custom_nodes() {
+		let mut library = CustomNodeLibrary::default();
+		library.add(definition(1, "Outer", &[2]));
+		library.add(definition(2, "Middle", &[3]));
+		library.add(definition(3, "Inner", &[]));
+
+		assert_eq!(library.dependencies(CustomNodeId(1)), HashSet::from([CustomNodeId(2), CustomNodeId(3)]));
+		assert!(library.dependencies(CustomNodeId(3)).is_empty());
+	}
+
+	#[test]
+	fn updates_reach_instances_nested_in_other_definitions() {
+		let mut library = CustomNodeLibrary::default();
+		library.add(definition(1, "Outer", &[2]));
+		library.add(definition(2, "Inner", &[]));
+
+		let mut node_template = NodeTemplate::default();
+		node_template.document_node.inputs = vec![NodeInput::value(TaggedValue::F64(1.), true)];
+		node_template.document_node.implementation = DocumentNodeImplementation::Network(NodeNetwork::default());
+		library.update(CustomNodeId(2), node_template.clone());
+
+		let outer = &library.get(CustomNodeId(1)).unwrap().node_template;
+		let DocumentNodeImplementation::Network(network) = &outer.document_node.implementation else {
+			panic!("Custom nodes are networks")
+		};
+		assert_eq!(network.nodes[&NodeId(0)].inputs, node_template.document_node.inputs);
+	}
+}
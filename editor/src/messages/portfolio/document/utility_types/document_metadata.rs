@@ -303,6 +303,11 @@ impl LayerNodeIdentifier {
 		child.ancestors(metadata).any(|ancestor| ancestor == self)
 	}
 
+	/// Is the layer rendered while the given layer is soloed? This holds for the soloed layer's subtree and for its ancestors, which carry their transforms down to it.
+	pub fn is_rendered_in_solo(self, metadata: &DocumentMetadata, solo_layer: &LayerNodeIdentifier) -> bool {
+		self.is_ancestor_of(metadata, solo_layer) || solo_layer.is_ancestor_of(metadata, &self)
+	}
+
 	/// Is the layer last child of parent group? Used for clipping
 	pub fn can_be_clipped(self, metadata: &DocumentMetadata) -> bool {
 		self.parent(metadata)
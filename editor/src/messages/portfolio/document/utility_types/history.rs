@@ -0,0 +1,130 @@
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
+use crate::messages::prelude::*;
+use graph_craft::document::NodeId;
+
+/// A snapshot of the document in its undo or redo history, with the label of the step between it and the next state.
+#[derive(Clone, Debug)]
+pub struct HistoryState {
+	pub network_interface: NodeNetworkInterface,
+	pub label: HistoryLabel,
+}
+
+/// A step in the document history list shown to the user.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct HistoryEntry {
+	/// The position in the history which this step reaches, counted in steps from the oldest state which is kept.
+	pub index: usize,
+	pub label: String,
+}
+
+/// Describes a history step by the name of its transaction or by the first message which made a change in it.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub enum HistoryLabel {
+	/// No message which describes the step has been handled yet.
+	#[default]
+	Unnamed,
+	Named(String),
+	/// A transform of these layers, which are counted as each one is transformed during the step.
+	/// The name of the first one is kept from when it was transformed, to describe a step which only transforms it.
+	Transform {
+		layers: Vec<LayerNodeIdentifier>,
+		first_layer_name: String,
+	},
+}
+
+impl HistoryLabel {
+	pub fn text(&self) -> String {
+		match self {
+			Self::Unnamed => "Edit".to_string(),
+			Self::Named(name) => name.clone(),
+			Self::Transform { layers, first_layer_name } if layers.len() == 1 => format!("Transform \"{first_layer_name}\""),
+			Self::Transform { layers, .. } => format!("Transform {} layers", layers.len()),
+		}
+	}
+
+	/// Describes the step by the message if it makes a change and the step isn't described yet, or counts the layer it transforms. Returns whether the label changed.
+	pub fn describe(&mut self, message: &DocumentMessage, network_interface: &NodeNetworkInterface, network_path: &[NodeId]) -> bool {
+		let transformed_layer = match message {
+			DocumentMessage::GraphOperation(GraphOperationMessage::TransformChange { layer, .. } | GraphOperationMessage::TransformSet { layer, .. }) => Some(*layer),
+			_ => None,
+		};
+
+		match self {
+			Self::Unnamed => {
+				if let Some(layer) = transformed_layer {
+					// The transformed layer may not exist, which the message is rejected for
+					if network_interface.document_node(&layer.to_node(), &[]).is_none() {
+						return false;
+					}
+					let first_layer_name = network_interface.display_name(&layer.to_node(), &[]);
+					*self = Self::Transform {
+						layers: vec![layer],
+						first_layer_name,
+					};
+					return true;
+				}
+				let Some(name) = message_name(message, network_interface, network_path) else { return false };
+				*self = Self::Named(name);
+				true
+			}
+			Self::Transform { layers, .. } => match transformed_layer {
+				Some(layer) if !layers.contains(&layer) => {
+					layers.push(layer);
+					true
+				}
+				_ => false,
+			},
+			Self::Named(_) => false,
+		}
+	}
+}
+
+/// The name of the change made by the message, or `None` if it doesn't describe a change by itself, like a transform or a change of selection.
+fn message_name(message: &DocumentMessage, network_interface: &NodeNetworkInterface, network_path: &[NodeId]) -> Option<String> {
+	let name = match message {
+		DocumentMessage::GraphOperation(message) => match message {
+			GraphOperationMessage::FillSet { .. } | GraphOperationMessage::FillPatternSet { .. } => "Set Fill",
+			GraphOperationMessage::RoundCornersSet { .. } => "Round Corners",
+			GraphOperationMessage::BlendingFillSet { .. } => "Set Blending Fill",
+			GraphOperationMessage::OpacitySet { .. } => "Set Opacity",
+			GraphOperationMessage::BlendModeSet { .. } => "Set Blend Mode",
+			GraphOperationMessage::ClipModeToggle { .. } => "Toggle Clipping",
+			GraphOperationMessage::StrokeSet { .. } => "Set Stroke",
+			GraphOperationMessage::Vector { .. } => "Edit Path",
+			GraphOperationMessage::Brush { .. } => "Brush Stroke",
+			GraphOperationMessage::NewArtboard { .. } => "Draw Artboard",
+			GraphOperationMessage::NewBitmapLayer { .. } => "Place Image",
+			GraphOperationMessage::NewBooleanOperationLayer { .. } => "Boolean Operation",
+			GraphOperationMessage::NewCustomLayer { nodes, .. } => {
+				// The node with the first ID is the one connected to the layer, such as the shape drawn by a tool
+				let reference = nodes.iter().find(|(id, _)| *id == NodeId(0)).and_then(|(_, node)| node.persistent_node_metadata.reference.as_ref());
+				return Some(reference.map_or_else(|| "New Layer".to_string(), |reference| format!("Draw {reference}")));
+			}
+			GraphOperationMessage::NewVectorLayer { .. } => "Draw Path",
+			GraphOperationMessage::NewTextLayer { .. } => "Add Text",
+			GraphOperationMessage::ResizeArtboard { .. } => "Resize Artboard",
+			GraphOperationMessage::RemoveArtboards => "Remove Artboards",
+			GraphOperationMessage::NewSvg { .. } => "Place SVG",
+			GraphOperationMessage::TransformChange { .. } | GraphOperationMessage::TransformSet { .. } | GraphOperationMessage::SetUpstreamToChain { .. } => return None,
+		},
+		// Layers are always in the document network, unlike the nodes whose inputs are edited in the network shown in the graph
+		DocumentMessage::NodeGraph(NodeGraphMessage::RenameLayerImpl { layer, new_name }) => {
+			network_interface.document_node(&layer.to_node(), &[])?;
+			return Some(format!("Rename \"{}\" to \"{new_name}\"", network_interface.display_name(&layer.to_node(), &[])));
+		}
+		DocumentMessage::NodeGraph(message) => {
+			let node_id = match message {
+				NodeGraphMessage::SetInputValue { node_id, .. } => *node_id,
+				NodeGraphMessage::SetInput { input_connector, .. } => input_connector.node_id()?,
+				NodeGraphMessage::SetInputs { changes, .. } => changes.first()?.0.node_id()?,
+				_ => return None,
+			};
+			// The input may be of a node which doesn't exist, which the message is rejected for
+			network_interface.document_node(&node_id, network_path)?;
+			return Some(format!("Edit {}", network_interface.display_name(&node_id, network_path)));
+		}
+		_ => return None,
+	};
+	Some(name.to_string())
+}
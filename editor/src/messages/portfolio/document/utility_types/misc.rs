@@ -26,6 +26,26 @@ pub enum AlignAggregate {
 	Center,
 }
 
+/// The bounds which the selected layers are aligned to.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize, Hash, specta::Type)]
+pub enum AlignTarget {
+	/// The combined bounds of the selected layers.
+	#[default]
+	Selection,
+	/// The bounds of the artboard which contains each layer. Layers outside of any artboard aren't moved.
+	Artboard,
+}
+
+/// What is made even between the selected layers when distributing them.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize, Hash, specta::Type)]
+pub enum DistributeMode {
+	/// The gaps between the edges of neighboring layers.
+	#[default]
+	Spacing,
+	/// The distances between the centers of neighboring layers.
+	Centers,
+}
+
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, serde::Serialize, serde::Deserialize)]
 pub enum DocumentMode {
 	#[default]
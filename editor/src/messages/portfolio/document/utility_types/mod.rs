@@ -1,8 +1,14 @@
+pub mod alignment;
 pub mod clipboards;
+pub mod custom_nodes;
 pub mod document_metadata;
 pub mod error;
+pub mod history;
 pub mod misc;
 pub mod network_interface;
 pub mod nodes;
+pub mod similar_layers;
+pub mod style_search;
+pub mod swatches;
 pub mod transformation;
 pub mod wires;
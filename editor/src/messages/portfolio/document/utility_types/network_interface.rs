@@ -1,6 +1,9 @@
+use super::custom_nodes::{CustomNodeDefinition, CustomNodeId, CustomNodeLibrary, apply_definition};
 use super::document_metadata::{DocumentMetadata, LayerNodeIdentifier, NodeRelations};
 use super::misc::PTZ;
 use super::nodes::SelectedNodes;
+use super::swatches::DocumentSwatches;
+use crate::application::generate_uuid;
 use crate::consts::{EXPORTS_TO_RIGHT_EDGE_PIXEL_GAP, EXPORTS_TO_TOP_EDGE_PIXEL_GAP, GRID_SIZE, IMPORTS_TO_LEFT_EDGE_PIXEL_GAP, IMPORTS_TO_TOP_EDGE_PIXEL_GAP};
 use crate::messages::portfolio::document::graph_operation::utility_types::ModifyInputsContext;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::{DocumentNodeDefinition, resolve_document_node_type};
@@ -31,6 +34,12 @@ pub struct NodeNetworkInterface {
 	network: NodeNetwork,
 	/// Stores all editor information for a NodeNetwork. Should automatically kept in sync by the setter methods when changes to the document network are made.
 	network_metadata: NodeNetworkMetadata,
+	/// The named colors which fills and strokes in the document can reference. They're stored alongside the network so the undo history covers them too.
+	#[serde(default)]
+	swatches: DocumentSwatches,
+	/// The nodes which the user created from selections of nodes, which can be placed again from the node catalog. They're also stored alongside the network so the undo history covers them.
+	#[serde(default)]
+	custom_nodes: CustomNodeLibrary,
 	// TODO: Wrap in TransientMetadata Option
 	/// Stores the document network's structural topology. Should automatically kept in sync by the setter methods when changes to the document network are made.
 	#[serde(skip)]
@@ -47,6 +56,8 @@ impl Clone for NodeNetworkInterface {
 		Self {
 			network: self.network.clone(),
 			network_metadata: self.network_metadata.clone(),
+			swatches: self.swatches.clone(),
+			custom_nodes: self.custom_nodes.clone(),
 			document_metadata: Default::default(),
 			resolved_types: Default::default(),
 			transaction_status: TransactionStatus::Finished,
@@ -56,7 +67,7 @@ impl Clone for NodeNetworkInterface {
 
 impl PartialEq for NodeNetworkInterface {
 	fn eq(&self, other: &Self) -> bool {
-		self.network == other.network && self.network_metadata == other.network_metadata
+		self.network == other.network && self.network_metadata == other.network_metadata && self.swatches == other.swatches && self.custom_nodes == other.custom_nodes
 	}
 }
 
@@ -91,6 +102,44 @@ impl NodeNetworkInterface {
 			.expect("Could not get root document network in NodeNetworkInterface::document_network()")
 	}
 
+	/// The document network with every swatch reference resolved to its swatch's current color, which is what gets evaluated.
+	pub fn resolved_document_network(&self) -> NodeNetwork {
+		let mut network = self.document_network().clone();
+		self.swatches.resolve_network(&mut network);
+		network
+	}
+
+	/// Identifies the state of the document content, covering the network, the swatches it references, and the custom nodes in the library.
+	pub fn document_hash(&self) -> u64 {
+		let mut hasher = DefaultHasher::new();
+		self.document_network().current_hash().hash(&mut hasher);
+		self.swatches.hash(&mut hasher);
+		self.custom_nodes.hash(&mut hasher);
+		hasher.finish()
+	}
+
+	pub fn swatches(&self) -> &DocumentSwatches {
+		&self.swatches
+	}
+
+	pub fn custom_nodes(&self) -> &CustomNodeLibrary {
+		&self.custom_nodes
+	}
+
+	/// The custom node which the node is an instance of, if any.
+	pub fn custom_node_id(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<CustomNodeId> {
+		self.node_metadata(node_id, network_path)?.persistent_metadata.custom_node
+	}
+
+	/// Every instance of the custom node in the document network and its nested networks, as the path of the network containing it and its node ID.
+	fn custom_node_instances(&self, id: CustomNodeId) -> Vec<(Vec<NodeId>, NodeId)> {
+		self.document_network()
+			.recursive_nodes()
+			.filter(|(node_id, _, network_path)| self.custom_node_id(node_id, network_path) == Some(id))
+			.map(|(node_id, _, network_path)| (network_path, *node_id))
+			.collect()
+	}
+
 	// TODO: Make private and use .field_name getter methods
 	/// Gets the nested network based on network_path
 	pub fn nested_network(&self, network_path: &[NodeId]) -> Option<&NodeNetwork> {
@@ -466,18 +515,74 @@ impl NodeNetworkInterface {
 					};
 				} else {
 					// Disconnect node input if it is not connected to another node in new_ids
-					let tagged_value = TaggedValue::from_type_or_none(&self.input_type(&InputConnector::node(*node_id, input_index), network_path).0);
+					let tagged_value = self.disconnected_input_value(input, &InputConnector::node(*node_id, input_index), network_path);
 					*input = NodeInput::value(tagged_value, true);
 				}
 			} else if let &mut NodeInput::Network { .. } = input {
 				// Always disconnect network node input
-				let tagged_value = TaggedValue::from_type_or_none(&self.input_type(&InputConnector::node(*node_id, input_index), network_path).0);
+				let tagged_value = self.disconnected_input_value(input, &InputConnector::node(*node_id, input_index), network_path);
 				*input = NodeInput::value(tagged_value, true);
 			}
 		}
 		node_template
 	}
 
+	/// The value which replaces a wire into the input when it's disconnected, such as when copying a node without its upstream nodes.
+	///
+	/// This is the value flowing into the input if it's a constant known without evaluating the graph, otherwise the default value of the input's type.
+	fn disconnected_input_value(&mut self, input: &NodeInput, input_connector: &InputConnector, network_path: &[NodeId]) -> TaggedValue {
+		let input_type = self.input_type(input_connector, network_path).0;
+		self.constant_input_value(input, network_path)
+			.filter(|value| value.ty().nested_type() == input_type.nested_type())
+			.unwrap_or_else(|| TaggedValue::from_type_or_none(&input_type))
+	}
+
+	/// The value flowing into an input, if it's a constant which is known without evaluating the graph.
+	///
+	/// Besides a value set on the input itself, this follows wires to Identity and value nodes such as Number, and imports to the corresponding input of the encapsulating node.
+	pub fn constant_input_value(&self, input: &NodeInput, network_path: &[NodeId]) -> Option<TaggedValue> {
+		match input {
+			NodeInput::Value { tagged_value, .. } => Some(TaggedValue::clone(tagged_value)),
+			NodeInput::Node { node_id, output_index: 0, .. } => self.constant_output_value(node_id, network_path),
+			NodeInput::Network { import_index, .. } => {
+				let (encapsulating_node, encapsulating_network_path) = network_path.split_last()?;
+				let input = self.document_node(encapsulating_node, encapsulating_network_path)?.inputs.get(*import_index)?;
+				self.constant_input_value(input, encapsulating_network_path)
+			}
+			_ => None,
+		}
+	}
+
+	/// The value from the primary output of a node which only passes through or creates a constant value.
+	fn constant_output_value(&self, node_id: &NodeId, network_path: &[NodeId]) -> Option<TaggedValue> {
+		use graphene_std::math_nodes::{bool_value, color_value, gradient_value, number_value, percentage_value, string_value, vec2_value};
+
+		let node = self.document_node(node_id, network_path)?;
+		let DocumentNodeImplementation::ProtoNode(identifier) = &node.implementation else { return None };
+		let input_value = |index: usize| self.constant_input_value(node.inputs.get(index)?, network_path);
+
+		if *identifier == graphene_std::ops::identity::IDENTIFIER {
+			return input_value(0);
+		}
+		if *identifier == vec2_value::IDENTIFIER {
+			let (TaggedValue::F64(x), TaggedValue::F64(y)) = (input_value(1)?, input_value(2)?) else {
+				return None;
+			};
+			return Some(TaggedValue::DVec2(DVec2::new(x, y)));
+		}
+
+		// The value nodes output the value of their only parameter, after their unused primary input
+		let value_nodes = [
+			bool_value::IDENTIFIER,
+			number_value::IDENTIFIER,
+			percentage_value::IDENTIFIER,
+			color_value::IDENTIFIER,
+			gradient_value::IDENTIFIER,
+			string_value::IDENTIFIER,
+		];
+		if value_nodes.contains(identifier) { input_value(1) } else { None }
+	}
+
 	/// Try and get the [`DocumentNodeDefinition`] for a node
 	pub fn get_node_definition(&self, network_path: &[NodeId], node_id: NodeId) -> Option<&DocumentNodeDefinition> {
 		let metadata = self.node_metadata(&node_id, network_path)?;
@@ -1106,7 +1211,7 @@ impl NodeNetworkInterface {
 			log::warn!("input metadata not found in displayed_input_name_and_description");
 			return (String::new(), String::new());
 		};
-		let description = input_metadata.input_description.to_string();
+		let description = input_metadata.hints.tooltip.clone().unwrap_or_else(|| input_metadata.input_description.to_string());
 		let name = if input_metadata.input_name.is_empty() {
 			self.input_type(&InputConnector::node(*node_id, input_index), network_path).0.nested_type().to_string()
 		} else {
@@ -1538,6 +1643,8 @@ impl NodeNetworkInterface {
 		Self {
 			network: node_network,
 			network_metadata,
+			swatches: DocumentSwatches::default(),
+			custom_nodes: CustomNodeLibrary::default(),
 			document_metadata: DocumentMetadata::default(),
 			resolved_types: ResolvedDocumentNodeTypes::default(),
 			transaction_status: TransactionStatus::Finished,
@@ -1650,6 +1757,112 @@ impl NodeNetworkInterface {
 		self.transaction_status = TransactionStatus::Finished;
 	}
 
+	/// Mutably get the document's swatches. References to them are resolved when the graph is next run.
+	pub fn swatches_mut(&mut self) -> &mut DocumentSwatches {
+		self.transaction_modified();
+		&mut self.swatches
+	}
+
+	/// Adds a custom node to the document's library, defined by the network of the given node which becomes its first instance.
+	pub fn define_custom_node(&mut self, node_id: &NodeId, network_path: &[NodeId], name: &str) -> Option<CustomNodeId> {
+		let id = CustomNodeId(generate_uuid());
+		self.node_metadata_mut(node_id, network_path)?.persistent_metadata.custom_node = Some(id);
+		let node_template = self.custom_node_template(node_id, network_path)?;
+
+		self.custom_nodes.add(CustomNodeDefinition {
+			id,
+			name: name.to_string(),
+			node_template,
+		});
+		self.transaction_modified();
+		Some(id)
+	}
+
+	/// Edits inside the network of a custom node instance are edits to its custom node, so this updates the definitions of the custom nodes whose instances contain the network.
+	/// The innermost instance is handled first, so the changes to it are included in the definitions of the custom nodes around it.
+	pub fn update_custom_node_definitions(&mut self, network_path: &[NodeId]) {
+		for depth in (0..network_path.len()).rev() {
+			self.update_custom_node_definition(&network_path[depth], &network_path[..depth]);
+		}
+	}
+
+	/// Makes the network of the instance the definition of its custom node, and gives the same network to all other instances.
+	fn update_custom_node_definition(&mut self, node_id: &NodeId, network_path: &[NodeId]) {
+		let Some(id) = self.custom_node_id(node_id, network_path) else { return };
+		let Some(mut node_template) = self.custom_node_template(node_id, network_path) else { return };
+		let Some(definition) = self.custom_nodes.get(id) else { return };
+
+		let definition_template = &definition.node_template;
+		if node_template.document_node.implementation == definition_template.document_node.implementation
+			&& node_template.persistent_node_metadata.input_metadata == definition_template.persistent_node_metadata.input_metadata
+			&& node_template.persistent_node_metadata.output_names == definition_template.persistent_node_metadata.output_names
+		{
+			return;
+		}
+		// Instances can be renamed individually, so the definition keeps its own display name
+		node_template
+			.persistent_node_metadata
+			.display_name
+			.clone_from(&definition_template.persistent_node_metadata.display_name);
+
+		let mut instance_path = network_path.to_vec();
+		instance_path.push(*node_id);
+		for (other_network_path, other_node_id) in self.custom_node_instances(id) {
+			// Instances nested inside this one would otherwise be given a copy of themselves each time it's edited
+			if other_network_path.starts_with(&instance_path) || (other_network_path == network_path && other_node_id == *node_id) {
+				continue;
+			}
+			self.apply_custom_node_definition(&other_node_id, &other_network_path, &node_template);
+		}
+
+		self.custom_nodes.update(id, node_template);
+		self.transaction_modified();
+	}
+
+	/// The template which places a new instance of the custom node that the given node is an instance of, with its current network.
+	fn custom_node_template(&mut self, node_id: &NodeId, network_path: &[NodeId]) -> Option<NodeTemplate> {
+		// Copying disconnects the inputs from the nodes around the instance, keeping the values which flowed into them
+		let new_ids = HashMap::from([(*node_id, *node_id)]);
+		let (_, mut node_template) = self.copy_nodes(&new_ids, network_path).next()?;
+
+		let metadata = &mut node_template.persistent_node_metadata;
+		metadata.node_type_metadata = if metadata.is_layer() {
+			NodeTypePersistentMetadata::layer(IVec2::ZERO)
+		} else {
+			NodeTypePersistentMetadata::node(IVec2::ZERO)
+		};
+		Some(node_template)
+	}
+
+	/// Gives the instance the network of its custom node, disconnecting the wires from any of its outputs which no longer exist.
+	fn apply_custom_node_definition(&mut self, node_id: &NodeId, network_path: &[NodeId], definition: &NodeTemplate) {
+		let Some(mut node) = self.document_node(node_id, network_path).cloned() else { return };
+		let Some(mut metadata) = self.node_metadata(node_id, network_path).map(|node_metadata| node_metadata.persistent_metadata.clone()) else {
+			return;
+		};
+
+		let number_of_outputs = definition.document_node.implementation.output_count();
+		let removed_outputs = (number_of_outputs..self.number_of_outputs(node_id, network_path)).map(|output_index| OutputConnector::node(*node_id, output_index));
+		let disconnected_inputs = removed_outputs
+			.filter_map(|output_connector| self.outward_wires(network_path).and_then(|outward_wires| outward_wires.get(&output_connector).cloned()))
+			.flatten()
+			.collect::<Vec<_>>();
+		for input_connector in disconnected_inputs {
+			self.disconnect_input(&input_connector, network_path);
+		}
+
+		apply_definition(&mut node, &mut metadata, definition);
+		let Some(network) = self.network_mut(network_path) else { return };
+		network.nodes.insert(*node_id, node);
+		let Some(node_metadata) = self.node_metadata_mut(node_id, network_path) else { return };
+		node_metadata.persistent_metadata = metadata;
+
+		self.transaction_modified();
+		self.unload_node_click_targets(node_id, network_path);
+		self.unload_all_nodes_bounding_box(network_path);
+		self.unload_outward_wires(network_path);
+	}
+
 	/// Mutably get the selected nodes for the network at the network_path. Every time they are mutated, the transient metadata for the top of the stack gets unloaded.
 	pub fn selected_nodes_mut(&mut self, network_path: &[NodeId]) -> Option<&mut SelectedNodes> {
 		let (last_selection_state, prev_state, is_selection_empty) = {
@@ -3372,11 +3585,10 @@ impl NodeNetworkInterface {
 	}
 
 	pub fn selected_unlocked_layers_bounding_box_viewport(&self) -> Option<[DVec2; 2]> {
-		self.selected_nodes()
-			.0
-			.iter()
-			.filter(|node| self.is_layer(&node, &[]) && !self.is_locked(&node, &[]))
-			.filter_map(|layer| self.document_metadata.bounding_box_viewport(LayerNodeIdentifier::new(*layer, self)))
+		let selected_nodes = self.selected_nodes();
+		selected_nodes
+			.selected_unlocked_layers(self)
+			.filter_map(|layer| self.document_metadata.bounding_box_viewport(layer))
 			.reduce(Quad::combine_bounds)
 	}
 
@@ -4171,6 +4383,33 @@ impl NodeNetworkInterface {
 		node.manual_composition = manual_composition;
 	}
 
+	/// Checks that every input in a batch of changes exists and can be given its new input, logging the first change that can't.
+	pub fn can_set_inputs(&self, changes: &[(InputConnector, NodeInput)], network_path: &[NodeId]) -> bool {
+		changes.iter().all(|(input_connector, new_input)| {
+			if matches!(input_connector, InputConnector::Export(_)) && matches!(new_input, NodeInput::Network { .. }) {
+				log::error!("Cannot connect a network to an export, see https://github.com/GraphiteEditor/Graphite/issues/1762");
+				return false;
+			}
+			if self.input_from_connector(input_connector, network_path).is_none() {
+				log::error!("Could not get input {input_connector:?} in can_set_inputs");
+				return false;
+			}
+			true
+		})
+	}
+
+	/// Sets every input in a batch of changes, or none of them if any change is invalid. Returns whether the changes were applied.
+	pub fn set_inputs(&mut self, changes: Vec<(InputConnector, NodeInput)>, network_path: &[NodeId]) -> bool {
+		if !self.can_set_inputs(&changes, network_path) {
+			return false;
+		}
+
+		for (input_connector, new_input) in changes {
+			self.set_input(&input_connector, new_input, network_path);
+		}
+		true
+	}
+
 	pub fn set_input(&mut self, input_connector: &InputConnector, new_input: NodeInput, network_path: &[NodeId]) {
 		if matches!(input_connector, InputConnector::Export(_)) && matches!(new_input, NodeInput::Network { .. }) {
 			// TODO: Add support for flattening NodeInput::Network exports in flatten_with_fns https://github.com/GraphiteEditor/Graphite/issues/1762
@@ -6512,6 +6751,98 @@ pub enum WidgetOverride {
 	Custom(String),
 }
 
+/// The unit of a number input, shown as a suffix of its value in the Properties panel.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum InputUnit {
+	Pixels,
+	Degrees,
+	Percent,
+	Multiplier,
+}
+
+impl InputUnit {
+	pub fn suffix(&self) -> &'static str {
+		match self {
+			InputUnit::Pixels => " px",
+			InputUnit::Degrees => "°",
+			InputUnit::Percent => "%",
+			InputUnit::Multiplier => "x",
+		}
+	}
+}
+
+/// The kind of widget which the Properties panel should show for an input, instead of the one it would choose for the input's type.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub enum WidgetHint {
+	/// A number input which is dragged across its soft range.
+	Slider,
+	/// A number of degrees, dragged across a half turn in either direction unless a soft range is given.
+	Angle,
+	/// A percentage, dragged from 0% to 100% unless a soft range is given.
+	Percent,
+	/// A color picker.
+	Color,
+}
+
+/// Describes the values an input expects, from which the Properties panel generates its widget.
+///
+/// Inputs without any hints get the widget generated from their type and the node macro's metadata instead.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
+pub struct InputHints {
+	/// The lowest value the input accepts, to which lower values are clamped.
+	pub min: Option<f64>,
+	/// The highest value the input accepts, to which higher values are clamped.
+	pub max: Option<f64>,
+	/// The range a slider spans, which values typed into it may go beyond within the `min` and `max`.
+	pub soft_range: Option<(f64, f64)>,
+	pub step: Option<f64>,
+	pub unit: Option<InputUnit>,
+	pub widget: Option<WidgetHint>,
+	/// Displayed as the tooltip, instead of the input's description.
+	pub tooltip: Option<String>,
+}
+
+impl InputHints {
+	pub fn is_empty(&self) -> bool {
+		self == &InputHints::default()
+	}
+
+	pub fn min(mut self, min: f64) -> Self {
+		self.min = Some(min);
+		self
+	}
+	pub fn max(mut self, max: f64) -> Self {
+		self.max = Some(max);
+		self
+	}
+	pub fn soft_range(mut self, start: f64, end: f64) -> Self {
+		self.soft_range = Some((start, end));
+		self
+	}
+	pub fn step(mut self, step: f64) -> Self {
+		self.step = Some(step);
+		self
+	}
+	pub fn unit(mut self, unit: InputUnit) -> Self {
+		self.unit = Some(unit);
+		self
+	}
+	pub fn widget(mut self, widget: WidgetHint) -> Self {
+		self.widget = Some(widget);
+		self
+	}
+	pub fn tooltip(mut self, tooltip: &str) -> Self {
+		self.tooltip = Some(tooltip.to_string());
+		self
+	}
+
+	/// Limits the value to the `min` and `max`, if there are any.
+	pub fn clamp(&self, value: f64) -> f64 {
+		let value = self.min.map_or(value, |min| value.max(min));
+		self.max.map_or(value, |max| value.min(max))
+	}
+}
+
 // TODO: Custom deserialization/serialization to ensure number of properties row matches number of node inputs
 #[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct InputPersistentMetadata {
@@ -6526,6 +6857,9 @@ pub struct InputPersistentMetadata {
 	pub input_name: String,
 	/// Displayed as the tooltip.
 	pub input_description: String,
+	/// Describes the values the input expects, from which its widget is generated when it doesn't have a widget override.
+	#[serde(default, skip_serializing_if = "InputHints::is_empty")]
+	pub hints: InputHints,
 }
 
 impl InputPersistentMetadata {
@@ -6591,6 +6925,11 @@ impl InputPersistentMetadata {
 		self.input_description = tooltip.to_string();
 		self
 	}
+
+	pub fn with_hints(mut self, hints: InputHints) -> Self {
+		self.hints = hints;
+		self
+	}
 }
 
 #[derive(Debug, Clone, Default)]
@@ -6655,6 +6994,9 @@ pub struct DocumentNodePersistentMetadata {
 	pub node_type_metadata: NodeTypePersistentMetadata,
 	/// This should always be Some for nodes with a [`DocumentNodeImplementation::Network`], and none for [`DocumentNodeImplementation::ProtoNode`]
 	pub network_metadata: Option<NodeNetworkMetadata>,
+	/// The custom node in the document's library which this node is an instance of, whose network it shares with the other instances.
+	#[serde(default)]
+	pub custom_node: Option<CustomNodeId>,
 }
 
 impl Default for DocumentNodePersistentMetadata {
@@ -6669,6 +7011,7 @@ impl Default for DocumentNodePersistentMetadata {
 			locked: false,
 			node_type_metadata: NodeTypePersistentMetadata::default(),
 			network_metadata: None,
+			custom_node: None,
 		}
 	}
 }
@@ -6677,6 +7020,20 @@ impl DocumentNodePersistentMetadata {
 	pub fn is_layer(&self) -> bool {
 		matches!(self.node_type_metadata, NodeTypePersistentMetadata::Layer(_))
 	}
+
+	/// The position in grid spaces of a node or layer which is placed absolutely, rather than in a chain or stack.
+	pub fn absolute_position_mut(&mut self) -> Option<&mut IVec2> {
+		match &mut self.node_type_metadata {
+			NodeTypePersistentMetadata::Layer(LayerPersistentMetadata {
+				position: LayerPosition::Absolute(position),
+				..
+			}) => Some(position),
+			NodeTypePersistentMetadata::Node(NodePersistentMetadata {
+				position: NodePosition::Absolute(position),
+			}) => Some(position),
+			_ => None,
+		}
+	}
 }
 
 #[derive(Debug, Default, serde::Serialize, serde::Deserialize)]
@@ -6786,6 +7143,7 @@ impl From<DocumentNodePersistentMetadataPropertiesRow> for DocumentNodePersisten
 					widget_override: properties_row.widget_override,
 					input_name: properties_row.input_name,
 					input_description: properties_row.input_description,
+					hints: InputHints::default(),
 				},
 				..Default::default()
 			})
@@ -6800,6 +7158,7 @@ impl From<DocumentNodePersistentMetadataPropertiesRow> for DocumentNodePersisten
 			pinned: old.pinned,
 			node_type_metadata: old.node_type_metadata,
 			network_metadata: old.network_metadata,
+			custom_node: None,
 		}
 	}
 }
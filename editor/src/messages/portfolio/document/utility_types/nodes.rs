@@ -59,6 +59,11 @@ pub struct LayerPanelEntry {
 	pub descendant_of_selected: bool,
 	pub clipped: bool,
 	pub clippable: bool,
+	/// Whether this layer is the one which is soloed, so only it and its subtree are rendered
+	pub solo: bool,
+	/// Whether another layer is soloed and this layer is outside of its subtree, so it isn't rendered
+	#[serde(rename = "hiddenBySolo")]
+	pub hidden_by_solo: bool,
 }
 
 /// IMPORTANT: the same node may appear multiple times.
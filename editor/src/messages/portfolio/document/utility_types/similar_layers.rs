@@ -0,0 +1,205 @@
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
+use crate::messages::tool::common_functionality::graph_modification_utils::{self, NodeGraphLayer};
+
+/// A property of the selected layers which "Select Similar" finds the other layers sharing.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum SimilarityCriterion {
+	/// The node at the start of the layer's chain which generates its content, such as a Rectangle, Text, or Path node.
+	ShapeType,
+	FillColor,
+	StrokeWeight,
+	Font,
+	BlendMode,
+}
+
+impl SimilarityCriterion {
+	pub const ALL: [Self; 5] = [Self::ShapeType, Self::FillColor, Self::StrokeWeight, Self::Font, Self::BlendMode];
+
+	pub fn label(self) -> &'static str {
+		match self {
+			Self::ShapeType => "Same Shape Type",
+			Self::FillColor => "Same Fill Color",
+			Self::StrokeWeight => "Same Stroke Weight",
+			Self::Font => "Same Font",
+			Self::BlendMode => "Same Blend Mode",
+		}
+	}
+
+	/// Whether the candidate layer shares this property with the reference layer. Layers without the property, like a rectangle when comparing fonts, match nothing.
+	pub fn matches(self, reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+		match self {
+			Self::ShapeType => same_shape_type(reference, candidate, network_interface),
+			Self::FillColor => same_fill_color(reference, candidate, network_interface),
+			Self::StrokeWeight => same_stroke_weight(reference, candidate, network_interface),
+			Self::Font => same_font(reference, candidate, network_interface),
+			Self::BlendMode => same_blend_mode(reference, candidate, network_interface),
+		}
+	}
+}
+
+/// The name of the node which generates the layer's content, at the start of its own chain of nodes, or `None` for a layer without a chain like a group.
+pub fn shape_type(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> Option<String> {
+	let generator = NodeGraphLayer::new(layer, network_interface)
+		.horizontal_layer_flow()
+		.skip(1)
+		.take_while(|node_id| !network_interface.is_layer(node_id, &[]))
+		.last()?;
+	network_interface.reference(&generator, &[]).cloned().flatten()
+}
+
+pub fn same_shape_type(reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let shape_type = shape_type(reference, network_interface);
+	shape_type.is_some() && shape_type == self::shape_type(candidate, network_interface)
+}
+
+pub fn same_fill_color(reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let fill_color = graph_modification_utils::get_fill_color(reference, network_interface);
+	fill_color.is_some() && fill_color == graph_modification_utils::get_fill_color(candidate, network_interface)
+}
+
+pub fn same_stroke_weight(reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let stroke_weight = graph_modification_utils::get_stroke_width(reference, network_interface);
+	stroke_weight.is_some() && stroke_weight == graph_modification_utils::get_stroke_width(candidate, network_interface)
+}
+
+/// Whether both layers are text set in the same font family and style, regardless of the values of its variation axes.
+pub fn same_font(reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let font = |layer| graph_modification_utils::get_text(layer, network_interface).map(|(_, font, _, _)| (font.font_family.clone(), font.font_style.clone()));
+	let reference_font = font(reference);
+	reference_font.is_some() && reference_font == font(candidate)
+}
+
+/// Whether both layers blend the same way, where a layer without a Blending node blends normally.
+pub fn same_blend_mode(reference: LayerNodeIdentifier, candidate: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let blend_mode = |layer| graph_modification_utils::get_blend_mode(layer, network_interface).unwrap_or_default();
+	blend_mode(reference) == blend_mode(candidate)
+}
+
+/// The visible and unlocked layers, other than artboards, which share the property with any of the reference layers.
+pub fn similar_layers(network_interface: &NodeNetworkInterface, references: &[LayerNodeIdentifier], criterion: SimilarityCriterion) -> Vec<LayerNodeIdentifier> {
+	let selected_nodes = network_interface.selected_nodes();
+	network_interface
+		.document_metadata()
+		.all_layers()
+		.filter(|&layer| !network_interface.is_artboard(&layer.to_node(), &[]))
+		.filter(|&layer| selected_nodes.layer_visible(layer, network_interface) && !selected_nodes.layer_locked(layer, network_interface))
+		.filter(|&layer| references.iter().any(|&reference| criterion.matches(reference, layer, network_interface)))
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::test_utils::test_prelude::*;
+	use graph_craft::document::NodeId;
+	use graphene_std::raster::BlendMode;
+	use graphene_std::text::Font;
+	use graphene_std::vector::style::{Fill, Stroke};
+
+	/// From the top of the Layers panel: two text layers in different fonts, an ellipse, and two rectangles.
+	/// The ellipse and the first rectangle share a red fill, a 4 px stroke, and the Multiply blend mode, while the other rectangle has a 1 px stroke.
+	async fn fixture() -> (EditorTestUtils, [LayerNodeIdentifier; 5]) {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(200., 0., 300., 100.).await;
+		editor.draw_ellipse(400., 0., 500., 100.).await;
+		for font_style in ["Regular (400)", "Bold (700)"] {
+			let font = Font::new(graphene_std::consts::DEFAULT_FONT_FAMILY.into(), font_style.into());
+			editor
+				.handle_message(GraphOperationMessage::NewTextLayer {
+					id: NodeId::new(),
+					text: "Text".into(),
+					font,
+					typesetting: Default::default(),
+					on_path: None,
+					parent: LayerNodeIdentifier::ROOT_PARENT,
+					insert_index: 0,
+				})
+				.await;
+		}
+
+		let [bold_text, normal_text, ellipse, red_rectangle, plain_rectangle] = editor.active_document().metadata().all_layers().collect::<Vec<_>>()[..] else {
+			panic!("There should be five layers");
+		};
+		let stroke = |weight| Stroke {
+			color: Some(Color::BLACK),
+			weight,
+			..Default::default()
+		};
+		for layer in [ellipse, red_rectangle] {
+			let blend_mode = BlendMode::Multiply;
+			editor.handle_message(GraphOperationMessage::FillSet { layer, fill: Fill::Solid(Color::RED) }).await;
+			editor.handle_message(GraphOperationMessage::StrokeSet { layer, stroke: stroke(4.) }).await;
+			editor.handle_message(GraphOperationMessage::BlendModeSet { layer, blend_mode }).await;
+		}
+		editor
+			.handle_message(GraphOperationMessage::StrokeSet {
+				layer: plain_rectangle,
+				stroke: stroke(1.),
+			})
+			.await;
+
+		(editor, [bold_text, normal_text, ellipse, red_rectangle, plain_rectangle])
+	}
+
+	fn similar(editor: &EditorTestUtils, reference: LayerNodeIdentifier, criterion: SimilarityCriterion) -> Vec<LayerNodeIdentifier> {
+		similar_layers(&editor.active_document().network_interface, &[reference], criterion)
+	}
+
+	#[tokio::test]
+	async fn each_criterion_finds_its_matches() {
+		let (editor, [bold_text, normal_text, ellipse, red_rectangle, plain_rectangle]) = fixture().await;
+
+		assert_eq!(similar(&editor, red_rectangle, SimilarityCriterion::ShapeType), vec![red_rectangle, plain_rectangle]);
+		assert_eq!(similar(&editor, bold_text, SimilarityCriterion::ShapeType), vec![bold_text, normal_text]);
+
+		assert_eq!(similar(&editor, ellipse, SimilarityCriterion::FillColor), vec![ellipse, red_rectangle]);
+		assert_eq!(similar(&editor, ellipse, SimilarityCriterion::StrokeWeight), vec![ellipse, red_rectangle]);
+		assert_eq!(similar(&editor, plain_rectangle, SimilarityCriterion::StrokeWeight), vec![plain_rectangle]);
+
+		assert_eq!(similar(&editor, bold_text, SimilarityCriterion::Font), vec![bold_text]);
+		// A layer which isn't text has no font to match
+		assert_eq!(similar(&editor, red_rectangle, SimilarityCriterion::Font), Vec::new());
+
+		assert_eq!(similar(&editor, red_rectangle, SimilarityCriterion::BlendMode), vec![ellipse, red_rectangle]);
+		assert_eq!(similar(&editor, plain_rectangle, SimilarityCriterion::BlendMode), vec![bold_text, normal_text, plain_rectangle]);
+	}
+
+	/// Sets the selection, runs the Select Similar command, and gives the selected layers.
+	async fn select_similar(
+		editor: &mut EditorTestUtils,
+		selection: &[LayerNodeIdentifier],
+		layer: Option<LayerNodeIdentifier>,
+		criterion: SimilarityCriterion,
+		extend: bool,
+	) -> Vec<LayerNodeIdentifier> {
+		let nodes = selection.iter().map(|layer| layer.to_node()).collect();
+		editor.handle_message(NodeGraphMessage::SelectedNodesSet { nodes }).await;
+		let layer = layer.map(|layer| layer.to_node());
+		editor.handle_message(DocumentMessage::SelectSimilarLayers { layer, criterion, extend }).await;
+
+		let document = editor.active_document();
+		document.network_interface.selected_nodes().selected_layers(document.metadata()).collect()
+	}
+
+	#[tokio::test]
+	async fn selecting_similar_replaces_or_extends_the_selection() {
+		let (mut editor, [bold_text, _, ellipse, red_rectangle, plain_rectangle]) = fixture().await;
+
+		let selected = select_similar(&mut editor, &[plain_rectangle], None, SimilarityCriterion::ShapeType, false).await;
+		assert_eq!(selected, vec![red_rectangle, plain_rectangle]);
+
+		let selected = select_similar(&mut editor, &[bold_text], None, SimilarityCriterion::Font, false).await;
+		assert_eq!(selected, vec![bold_text]);
+
+		// Layers similar to the one given, rather than to the selected ones, are added to the selection
+		let selected = select_similar(&mut editor, &[bold_text], Some(ellipse), SimilarityCriterion::FillColor, true).await;
+		assert_eq!(selected, vec![bold_text, ellipse, red_rectangle]);
+
+		// Nothing is similar to the rectangle's font, so the selection is kept
+		let selected = select_similar(&mut editor, &[plain_rectangle], None, SimilarityCriterion::Font, false).await;
+		assert_eq!(selected, vec![plain_rectangle]);
+	}
+}
@@ -0,0 +1,239 @@
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, NodeNetworkInterface};
+use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
+use graph_craft::document::NodeInput;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::Color;
+use graphene_std::vector::style::{Fill, GradientStops};
+
+/// A style value which the document can be searched for, to select the layers using it or to replace it throughout the document.
+#[derive(Clone, Copy, Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+pub enum StyleValue {
+	/// A color of a fill, a stroke, or a gradient stop.
+	/// WARNING: Color is gamma, not linear, like the colors stored in the inputs!
+	Color(Color),
+	/// The weight of a stroke.
+	StrokeWeight(f64),
+}
+
+/// A value input of a node in a layer's chain which may hold a style value.
+struct StyleInput<'a> {
+	layer: LayerNodeIdentifier,
+	input_connector: InputConnector,
+	value: &'a TaggedValue,
+	exposed: bool,
+	/// Whether this is the weight input of a Stroke node, which is the only number treated as a style value
+	stroke_weight: bool,
+	/// Whether this is one of the Fill node's backups, which only keep the fill of another kind for when the fill is switched to it
+	fill_backup: bool,
+}
+
+/// Finds the value inputs of the nodes in each layer's chain, up to the next layer.
+fn style_inputs(network_interface: &NodeNetworkInterface) -> Vec<StyleInput<'_>> {
+	use graphene_std::vector::fill::{BackupColorInput, BackupGradientInput};
+	use graphene_std::vector::stroke::WeightInput;
+
+	let mut style_inputs = Vec::new();
+	for layer in network_interface.document_metadata().all_layers() {
+		let chain = NodeGraphLayer::new(layer, network_interface)
+			.horizontal_layer_flow()
+			.skip(1)
+			.take_while(|node_id| !network_interface.is_layer(node_id, &[]));
+
+		for node_id in chain {
+			let Some(node) = network_interface.document_node(&node_id, &[]) else { continue };
+			let reference = network_interface.reference(&node_id, &[]).cloned().flatten();
+
+			for (input_index, input) in node.inputs.iter().enumerate() {
+				let NodeInput::Value { tagged_value, exposed } = input else { continue };
+				style_inputs.push(StyleInput {
+					layer,
+					input_connector: InputConnector::node(node_id, input_index),
+					value: tagged_value,
+					exposed: *exposed,
+					stroke_weight: reference.as_deref() == Some("Stroke") && input_index == WeightInput::INDEX,
+					fill_backup: reference.as_deref() == Some("Fill") && [BackupColorInput::INDEX, BackupGradientInput::INDEX].contains(&input_index),
+				});
+			}
+		}
+	}
+	style_inputs
+}
+
+fn colors_match(color: Color, target: Color, tolerance: f64) -> bool {
+	let (red, green, blue, alpha) = color.components();
+	let (target_red, target_green, target_blue, target_alpha) = target.components();
+	[red - target_red, green - target_green, blue - target_blue, alpha - target_alpha]
+		.into_iter()
+		.all(|difference| difference.abs() as f64 <= tolerance)
+}
+
+/// Replaces the colors of the gradient stops which match the target, returning whether any did.
+fn replace_stop_colors(stops: &mut GradientStops, target: Color, tolerance: f64, replacement: Color) -> bool {
+	let mut replaced = false;
+	for (_, color) in &mut stops.color_stops {
+		if colors_match(*color, target, tolerance) {
+			*color = replacement;
+			replaced = true;
+		}
+	}
+	replaced
+}
+
+/// Gives the value with each part that matches the target within the tolerance replaced, or `None` if no part of it matches.
+fn replace_in_value(style_input: &StyleInput, target: StyleValue, tolerance: f64, replacement: StyleValue) -> Option<TaggedValue> {
+	let value = style_input.value;
+	match (target, replacement) {
+		(StyleValue::StrokeWeight(target), StyleValue::StrokeWeight(replacement)) => match value {
+			TaggedValue::F64(weight) if style_input.stroke_weight && (weight - target).abs() <= tolerance => Some(TaggedValue::F64(replacement)),
+			_ => None,
+		},
+		(StyleValue::Color(target), StyleValue::Color(replacement)) => match value {
+			TaggedValue::Color(color) if colors_match(*color, target, tolerance) => Some(TaggedValue::Color(replacement)),
+			TaggedValue::OptionalColor(Some(color)) if colors_match(*color, target, tolerance) => Some(TaggedValue::OptionalColor(Some(replacement))),
+			TaggedValue::Fill(Fill::Solid(color)) if colors_match(*color, target, tolerance) => Some(TaggedValue::Fill(Fill::Solid(replacement))),
+			TaggedValue::Fill(Fill::Gradient(gradient)) => {
+				let mut gradient = gradient.clone();
+				replace_stop_colors(&mut gradient.stops, target, tolerance, replacement).then_some(TaggedValue::Fill(Fill::Gradient(gradient)))
+			}
+			TaggedValue::Gradient(gradient) => {
+				let mut gradient = gradient.clone();
+				replace_stop_colors(&mut gradient.stops, target, tolerance, replacement).then_some(TaggedValue::Gradient(gradient))
+			}
+			TaggedValue::GradientStops(stops) => {
+				let mut stops = stops.clone();
+				replace_stop_colors(&mut stops, target, tolerance, replacement).then_some(TaggedValue::GradientStops(stops))
+			}
+			_ => None,
+		},
+		_ => None,
+	}
+}
+
+/// The layers whose fills, strokes, or gradients use the target style value, within the tolerance, in their own chain of nodes.
+/// A tolerance of zero finds only the exact value. For colors, the tolerance applies to each channel, including alpha.
+pub fn layers_with_style(network_interface: &NodeNetworkInterface, target: StyleValue, tolerance: f64) -> Vec<LayerNodeIdentifier> {
+	let mut layers = Vec::new();
+	for style_input in style_inputs(network_interface) {
+		if !style_input.fill_backup && !layers.contains(&style_input.layer) && replace_in_value(&style_input, target, tolerance, target).is_some() {
+			layers.push(style_input.layer);
+		}
+	}
+	layers
+}
+
+/// The input changes which replace the target style value, within the tolerance, with another one of the same kind throughout the document, to be applied together as one batch.
+/// The Fill nodes' backups are replaced too, so switching a fill to another kind doesn't bring back the old value.
+pub fn style_replacement_changes(network_interface: &NodeNetworkInterface, target: StyleValue, tolerance: f64, replacement: StyleValue) -> Vec<(InputConnector, NodeInput)> {
+	style_inputs(network_interface)
+		.iter()
+		.filter_map(|style_input| {
+			let value = replace_in_value(style_input, target, tolerance, replacement)?;
+			Some((style_input.input_connector, NodeInput::value(value, style_input.exposed)))
+		})
+		.collect()
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::test_utils::test_prelude::*;
+	use graphene_std::vector::style::{Gradient, Stroke};
+
+	const NEAR_RED: Color = Color::from_rgbaf32_unchecked(0.98, 0., 0., 1.);
+
+	/// Three rectangles, from the top of the Layers panel: a gradient from red to green without a stroke color,
+	/// a nearly red fill with a thick black stroke, and a red fill with a blue stroke.
+	async fn fixture() -> (EditorTestUtils, [LayerNodeIdentifier; 3]) {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(200., 0., 300., 100.).await;
+		editor.draw_rect(400., 0., 500., 100.).await;
+
+		let [gradient, near_red, red] = editor.active_document().metadata().all_layers().collect::<Vec<_>>()[..] else {
+			panic!("There should be three layers");
+		};
+		let gradient_fill = Fill::Gradient(Gradient {
+			stops: GradientStops::new(vec![(0., Color::RED), (1., Color::GREEN)]),
+			..Default::default()
+		});
+		let stroke = |color, weight| Stroke { color, weight, ..Default::default() };
+		for (layer, fill, stroke) in [
+			(gradient, gradient_fill, stroke(None, 2.)),
+			(near_red, Fill::Solid(NEAR_RED), stroke(Some(Color::BLACK), 4.)),
+			(red, Fill::Solid(Color::RED), stroke(Some(Color::BLUE), 2.)),
+		] {
+			editor.handle_message(GraphOperationMessage::FillSet { layer, fill }).await;
+			editor.handle_message(GraphOperationMessage::StrokeSet { layer, stroke }).await;
+		}
+
+		(editor, [gradient, near_red, red])
+	}
+
+	fn find(editor: &EditorTestUtils, target: StyleValue, tolerance: f64) -> Vec<LayerNodeIdentifier> {
+		layers_with_style(&editor.active_document().network_interface, target, tolerance)
+	}
+
+	#[tokio::test]
+	async fn finds_the_layers_using_a_color_or_stroke_weight() {
+		let (mut editor, [gradient, near_red, red]) = fixture().await;
+
+		// The gradient's stop matches as well as the solid fill
+		assert_eq!(find(&editor, StyleValue::Color(Color::RED), 0.), vec![gradient, red]);
+		assert_eq!(find(&editor, StyleValue::Color(Color::RED), 0.05), vec![gradient, near_red, red]);
+		assert_eq!(find(&editor, StyleValue::Color(Color::BLUE), 0.), vec![red]);
+		assert_eq!(find(&editor, StyleValue::Color(Color::WHITE), 0.5), Vec::new());
+		assert_eq!(find(&editor, StyleValue::StrokeWeight(2.), 0.), vec![gradient, red]);
+		assert_eq!(find(&editor, StyleValue::StrokeWeight(3.), 1.), vec![gradient, near_red, red]);
+
+		editor
+			.handle_message(DocumentMessage::SelectLayersWithStyle {
+				target: StyleValue::Color(Color::RED),
+				tolerance: 0.,
+			})
+			.await;
+		let document = editor.active_document();
+		let selected_layers = document.network_interface.selected_nodes().selected_layers(document.metadata()).collect::<Vec<_>>();
+		assert_eq!(selected_layers.len(), 2);
+		assert!(selected_layers.contains(&gradient) && selected_layers.contains(&red));
+	}
+
+	#[tokio::test]
+	async fn replacing_is_one_step_which_undo_restores() {
+		let (mut editor, [gradient, near_red, red]) = fixture().await;
+		let original_hash = editor.active_document().network_interface.document_hash();
+		let history_len = editor.active_document().undo_history_len();
+
+		editor
+			.handle_message(DocumentMessage::ReplaceStyle {
+				target: StyleValue::Color(Color::RED),
+				tolerance: 0.05,
+				replacement: StyleValue::Color(Color::MAGENTA),
+			})
+			.await;
+		assert_eq!(editor.active_document().undo_history_len(), history_len + 1);
+		assert_eq!(find(&editor, StyleValue::Color(Color::RED), 0.05), Vec::new());
+		assert_eq!(find(&editor, StyleValue::Color(Color::MAGENTA), 0.), vec![gradient, near_red, red]);
+		// Only the matching stop of the gradient is replaced
+		assert_eq!(find(&editor, StyleValue::Color(Color::GREEN), 0.), vec![gradient]);
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.document_hash(), original_hash);
+		assert_eq!(find(&editor, StyleValue::Color(Color::RED), 0.05), vec![gradient, near_red, red]);
+		assert_eq!(find(&editor, StyleValue::Color(Color::MAGENTA), 0.), Vec::new());
+
+		editor
+			.handle_message(DocumentMessage::ReplaceStyle {
+				target: StyleValue::StrokeWeight(2.),
+				tolerance: 0.,
+				replacement: StyleValue::StrokeWeight(8.),
+			})
+			.await;
+		assert_eq!(find(&editor, StyleValue::StrokeWeight(8.), 0.), vec![gradient, red]);
+		assert_eq!(find(&editor, StyleValue::StrokeWeight(4.), 0.), vec![near_red]);
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.document_hash(), original_hash);
+	}
+}
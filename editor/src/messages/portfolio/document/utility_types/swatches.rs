@@ -0,0 +1,186 @@
+use graph_craft::document::value::TaggedValue;
+use graph_craft::document::{DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
+use graphene_std::raster::color::Color;
+use graphene_std::vector::style::{SwatchColor, SwatchId};
+
+/// A named color stored in the document, which fills and strokes can reference so they all change when it is edited.
+#[derive(Clone, Debug, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DocumentSwatch {
+	pub id: SwatchId,
+	pub name: String,
+	/// WARNING: Color is gamma, not linear!
+	pub color: Color,
+}
+
+/// An input, somewhere in the document network or its nested networks, whose value references a swatch.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SwatchReference {
+	pub network_path: Vec<NodeId>,
+	pub node_id: NodeId,
+	pub input_index: usize,
+	pub swatch_color: SwatchColor,
+}
+
+/// The document's swatches, in the order they are listed in the Swatches panel.
+#[derive(Clone, Debug, Default, PartialEq, Hash, serde::Serialize, serde::Deserialize)]
+pub struct DocumentSwatches(Vec<DocumentSwatch>);
+
+impl DocumentSwatches {
+	pub fn iter(&self) -> impl Iterator<Item = &DocumentSwatch> {
+		self.0.iter()
+	}
+
+	pub fn get(&self, id: SwatchId) -> Option<&DocumentSwatch> {
+		self.0.iter().find(|swatch| swatch.id == id)
+	}
+
+	pub fn get_mut(&mut self, id: SwatchId) -> Option<&mut DocumentSwatch> {
+		self.0.iter_mut().find(|swatch| swatch.id == id)
+	}
+
+	/// Adds a swatch to the end of the list.
+	pub fn add(&mut self, swatch: DocumentSwatch) {
+		self.0.push(swatch);
+	}
+
+	pub fn remove(&mut self, id: SwatchId) -> Option<DocumentSwatch> {
+		let index = self.0.iter().position(|swatch| swatch.id == id)?;
+		Some(self.0.remove(index))
+	}
+
+	/// Gives the reference the current color of its swatch. A dangling reference, whose swatch has been deleted, keeps the color it last resolved to.
+	pub fn resolve(&self, swatch_color: SwatchColor) -> SwatchColor {
+		let color = self.get(swatch_color.swatch).map_or(swatch_color.color, |swatch| swatch.color);
+		SwatchColor { color, ..swatch_color }
+	}
+
+	/// Resolves every swatch reference in the network and its nested networks, which is done to a copy of the document network before it is evaluated.
+	pub fn resolve_network(&self, network: &mut NodeNetwork) {
+		for node in network.nodes.values_mut() {
+			for input in &mut node.inputs {
+				if let NodeInput::Value { tagged_value, .. } = input
+					&& let TaggedValue::SwatchColor(swatch_color) = **tagged_value
+				{
+					let resolved = self.resolve(swatch_color);
+					if resolved != swatch_color {
+						*tagged_value.inner_mut() = TaggedValue::SwatchColor(resolved);
+					}
+				}
+			}
+
+			if let DocumentNodeImplementation::Network(nested_network) = &mut node.implementation {
+				self.resolve_network(nested_network);
+			}
+		}
+	}
+
+	/// Finds every input in the network and its nested networks which references the swatch.
+	pub fn references(network: &NodeNetwork, id: SwatchId) -> Vec<SwatchReference> {
+		fn collect(network: &NodeNetwork, network_path: &mut Vec<NodeId>, id: SwatchId, references: &mut Vec<SwatchReference>) {
+			for (&node_id, node) in &network.nodes {
+				for (input_index, input) in node.inputs.iter().enumerate() {
+					if let NodeInput::Value { tagged_value, .. } = input
+						&& let TaggedValue::SwatchColor(swatch_color) = **tagged_value
+						&& swatch_color.swatch == id
+					{
+						references.push(SwatchReference {
+							network_path: network_path.clone(),
+							node_id,
+							input_index,
+							swatch_color,
+						});
+					}
+				}
+
+				if let DocumentNodeImplementation::Network(nested_network) = &node.implementation {
+					network_path.push(node_id);
+					collect(nested_network, network_path, id, references);
+					network_path.pop();
+				}
+			}
+		}
+
+		let mut references = Vec::new();
+		collect(network, &mut Vec::new(), id, &mut references);
+		references
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graph_craft::document::DocumentNode;
+
+	fn network_with_inputs(inputs: Vec<NodeInput>) -> NodeNetwork {
+		let mut nested_network = NodeNetwork::default();
+		nested_network.nodes.insert(NodeId(1), DocumentNode { inputs, ..Default::default() });
+
+		let mut network = NodeNetwork::default();
+		let node = DocumentNode {
+			implementation: DocumentNodeImplementation::Network(nested_network),
+			..Default::default()
+		};
+		network.nodes.insert(NodeId(0), node);
+		network
+	}
+
+	fn add_swatch(swatches: &mut DocumentSwatches, id: u64, name: &str, color: Color) -> SwatchId {
+		let id = SwatchId(id);
+		swatches.add(DocumentSwatch { id, name: name.to_string(), color });
+		id
+	}
+
+	fn input_color(network: &NodeNetwork, input_index: usize) -> Option<Color> {
+		let DocumentNodeImplementation::Network(nested_network) = &network.nodes[&NodeId(0)].implementation else {
+			return None;
+		};
+		match nested_network.nodes[&NodeId(1)].inputs[input_index].as_value()? {
+			TaggedValue::SwatchColor(swatch_color) => Some(swatch_color.color),
+			TaggedValue::Color(color) => Some(*color),
+			_ => None,
+		}
+	}
+
+	#[test]
+	fn references_resolve_to_the_current_swatch_color() {
+		let mut swatches = DocumentSwatches::default();
+		let brand = add_swatch(&mut swatches, 1, "Brand", Color::RED);
+		let accent = add_swatch(&mut swatches, 2, "Accent", Color::BLUE);
+
+		let stale = |swatch| NodeInput::value(TaggedValue::SwatchColor(SwatchColor { swatch, color: Color::BLACK }), false);
+		let mut network = network_with_inputs(vec![stale(brand), NodeInput::value(TaggedValue::Color(Color::WHITE), false), stale(accent)]);
+
+		swatches.resolve_network(&mut network);
+		assert_eq!(input_color(&network, 0), Some(Color::RED));
+		assert_eq!(input_color(&network, 1), Some(Color::WHITE));
+		assert_eq!(input_color(&network, 2), Some(Color::BLUE));
+
+		// Resolving changes the hash of the network, so it's recompiled after a swatch is edited
+		let hash = network.current_hash();
+		swatches.get_mut(brand).unwrap().color = Color::GREEN;
+		swatches.resolve_network(&mut network);
+		assert_eq!(input_color(&network, 0), Some(Color::GREEN));
+		assert_ne!(network.current_hash(), hash);
+
+		let references = DocumentSwatches::references(&network, brand);
+		assert_eq!(references.len(), 1);
+		assert_eq!(
+			(references[0].network_path.as_slice(), references[0].node_id, references[0].input_index),
+			([NodeId(0)].as_slice(), NodeId(1), 0)
+		);
+	}
+
+	#[test]
+	fn dangling_references_keep_their_last_color() {
+		let mut swatches = DocumentSwatches::default();
+		let brand = add_swatch(&mut swatches, 1, "Brand", Color::RED);
+		let mut network = network_with_inputs(vec![NodeInput::value(TaggedValue::SwatchColor(SwatchColor { swatch: brand, color: Color::RED }), false)]);
+
+		assert_eq!(swatches.remove(brand).map(|swatch| swatch.name), Some("Brand".to_string()));
+		assert!(swatches.get(brand).is_none());
+
+		swatches.resolve_network(&mut network);
+		assert_eq!(input_color(&network, 0), Some(Color::RED));
+		assert_eq!(DocumentSwatches::references(&network, brand).len(), 1);
+	}
+}
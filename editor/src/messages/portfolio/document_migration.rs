@@ -10,6 +10,7 @@ use glam::IVec2;
 use graph_craft::document::DocumentNode;
 use graph_craft::document::{DocumentNodeImplementation, NodeInput, value::TaggedValue};
 use graphene_std::ProtoNodeIdentifier;
+use graphene_std::raster::filter::BlurMode;
 use graphene_std::text::{TextAlign, TypesettingConfig};
 use graphene_std::uuid::NodeId;
 use graphene_std::vector::style::{PaintOrder, StrokeAlign};
@@ -315,8 +316,12 @@ const NODE_REPLACEMENTS: &[NodeReplacement<'static>] = &[
 		],
 	},
 	NodeReplacement {
-		node: graphene_std::raster_nodes::adjustments::threshold::IDENTIFIER,
-		aliases: &["graphene_core::raster::adjustments::ThresholdNode", "graphene_core::raster::ThresholdNode"],
+		node: graphene_std::raster_nodes::threshold::threshold::IDENTIFIER,
+		aliases: &[
+			"graphene_raster_nodes::adjustments::ThresholdNode",
+			"graphene_core::raster::adjustments::ThresholdNode",
+			"graphene_core::raster::ThresholdNode",
+		],
 	},
 	NodeReplacement {
 		node: graphene_std::raster_nodes::adjustments::vibrance::IDENTIFIER,
@@ -455,6 +460,10 @@ const NODE_REPLACEMENTS: &[NodeReplacement<'static>] = &[
 		node: graphene_std::path_bool::boolean_operation::IDENTIFIER,
 		aliases: &["graphene_std::vector::BooleanOperationNode"],
 	},
+	NodeReplacement {
+		node: graphene_std::path_bool::offset_path::IDENTIFIER,
+		aliases: &["graphene_core::vector::OffsetPathNode"],
+	},
 	// brush
 	NodeReplacement {
 		node: graphene_std::brush::brush::brush_stamp_generator::IDENTIFIER,
@@ -574,6 +583,46 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		document.network_interface.set_input(&InputConnector::node(*node_id, 9), old_inputs[4].clone(), network_path);
 	}
 
+	// Upgrade Fill node to add the "Fill Rule" parameter, keeping the nonzero rule that was previously always used
+	if reference == "Fill" && inputs_count == 4 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Fill node to add the "Backup Pattern" and "Pattern Tile" parameters, which leave existing fills unchanged
+	if reference == "Fill" && inputs_count == 5 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Stroke node to add the "Start Marker", "End Marker", and "Marker Size" parameters, which default to no markers
+	if reference == "Stroke" && inputs_count == 10 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Blending node to add the "Pass Through" parameter, keeping groups blending against the backdrop below them as they always did
+	if reference == "Blending" && inputs_count == 5 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
 	// Rename the old "Splines from Points" node to "Spline" and upgrade it to the new "Spline" node
 	if reference == "Splines from Points" {
 		document.network_interface.set_reference(node_id, network_path, Some("Spline".to_string()));
@@ -651,8 +700,23 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		}
 	}
 
+	// Upgrade Merge node to include the Cull node, which skips evaluating layers outside of the viewport
+	if reference == "Merge" {
+		let culled = match document.network_interface.implementation(node_id, network_path) {
+			Some(DocumentNodeImplementation::Network(network)) => network
+				.nodes
+				.values()
+				.any(|node| node.implementation == DocumentNodeImplementation::ProtoNode(graphene_std::culling::cull::IDENTIFIER)),
+			_ => true,
+		};
+		if !culled {
+			let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+			document.network_interface.replace_implementation(node_id, network_path, &mut node_template);
+		}
+	}
+
 	// Upgrade Text node to include line height and character spacing, which were previously hardcoded to 1, from https://github.com/GraphiteEditor/Graphite/pull/2016
-	if reference == "Text" && inputs_count != 11 {
+	if reference == "Text" && inputs_count < 11 {
 		let mut template = resolve_document_node_type(reference)?.default_node_template();
 		document.network_interface.replace_implementation(node_id, network_path, &mut template);
 		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut template)?;
@@ -726,6 +790,36 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		);
 	}
 
+	// Upgrade Text node to add the "Path", "Start Offset", "Side", and "Overflow" parameters for flowing text along a path, which is empty by default
+	if reference == "Text" && inputs_count == 11 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Text node to add the "Word Spacing" and "Absolute Line Height" parameters, which default to no extra spacing and the line height ratio
+	if reference == "Text" && inputs_count == 15 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Text node to add the "Justify Letters" parameter, which is disabled by default so justification only stretches the gaps between words
+	if reference == "Text" && inputs_count == 17 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
 	// Upgrade Sine, Cosine, and Tangent nodes to include a boolean input for whether the output should be in radians, which was previously the only option but is now not the default
 	if (reference == "Sine" || reference == "Cosine" || reference == "Tangent") && inputs_count == 1 {
 		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
@@ -844,7 +938,8 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		// We have removed the last input, so we don't add index 3
 	}
 
-	if reference == "Brush" && inputs_count == 4 {
+	// The current Brush node also has four inputs, but its second input is the strokes rather than the removed bounds
+	if reference == "Brush" && inputs_count == 4 && !matches!(node.inputs[1].as_value(), Some(TaggedValue::BrushStrokes(_))) {
 		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
 		document.network_interface.replace_implementation(node_id, network_path, &mut node_template);
 
@@ -995,6 +1090,46 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		}
 	}
 
+	// Upgrade Round Corners node to add the "Selected Points" and "Selected Radius" parameters, which default to rounding no anchors differently
+	if reference == "Round Corners" && inputs_count == 5 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade Offset Path node to add the "Open Paths" and "Clean Up Intersections" parameters, keeping the old behavior of offsetting without cleaning up
+	if reference == "Offset Path" && inputs_count == 4 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+		document
+			.network_interface
+			.set_input(&InputConnector::node(*node_id, 5), NodeInput::value(TaggedValue::Bool(false), false), network_path);
+	}
+
+	// Upgrade the Blur node from its "Box Blur" checkbox to the "Mode" choice, adding the motion blur, edge, and quality parameters between them
+	if reference == "Blur" && inputs_count == 4 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		let mode = match old_inputs[2].as_value() {
+			Some(TaggedValue::Bool(true)) => BlurMode::Box,
+			_ => BlurMode::Gaussian,
+		};
+		document.network_interface.set_input(&InputConnector::node(*node_id, 0), old_inputs[0].clone(), network_path);
+		document
+			.network_interface
+			.set_input(&InputConnector::node(*node_id, 1), NodeInput::value(TaggedValue::BlurMode(mode), false), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 2), old_inputs[1].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 7), old_inputs[3].clone(), network_path);
+	}
+
 	// Add the "Depth" parameter to the "Instance Index" node
 	if reference == "Instance Index" && inputs_count == 0 {
 		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
@@ -1007,6 +1142,91 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 		document.network_interface.add_import(TaggedValue::U32(0), false, 1, "Loop Level", "TODO", &node_path);
 	}
 
+	// Upgrade the Levels node to per-channel levels, converting its "Shadows", "Midtones", and "Highlights" parameters into the master input range and gamma
+	if reference == "Levels" && inputs_count == 6 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		let old_value = |index: usize, default: f64| match old_inputs[index].as_value() {
+			Some(&TaggedValue::F64(value)) => value,
+			_ => default,
+		};
+		let (midtones, output_minimums, output_maximums) = (old_value(2, 50.), old_value(4, 0.), old_value(5, 100.));
+		document.network_interface.set_input(&InputConnector::node(*node_id, 0), old_inputs[0].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 1), old_inputs[1].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 2), old_inputs[3].clone(), network_path);
+		let gamma = TaggedValue::F64(levels_gamma_from_midtones(midtones, output_minimums, output_maximums));
+		document.network_interface.set_input(&InputConnector::node(*node_id, 3), NodeInput::value(gamma, false), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 4), old_inputs[4].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 5), old_inputs[5].clone(), network_path);
+	}
+
+	// Upgrade the Gradient Map node to add the "Luminance Calc", "Interpolation", "Opacity", and "Preserve Alpha" parameters, keeping the old behavior of taking on the gradient's transparency
+	if reference == "Gradient Map" && inputs_count == 3 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+		document
+			.network_interface
+			.set_input(&InputConnector::node(*node_id, 6), NodeInput::value(TaggedValue::Bool(false), false), network_path);
+	}
+
+	// Upgrade the Threshold node's luminance range to a single threshold with dithering and custom colors
+	if reference == "Threshold" && inputs_count == 4 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		// The old minimum luminance becomes the threshold, while the maximum luminance is dropped since a luminance band can't be dithered
+		document.network_interface.set_input(&InputConnector::node(*node_id, 0), old_inputs[0].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 1), old_inputs[1].clone(), network_path);
+		document.network_interface.set_input(&InputConnector::node(*node_id, 2), old_inputs[3].clone(), network_path);
+	}
+
+	// Upgrade the Noise Pattern node to add the "Tileable", "Tile Size", "Sample 3D", and "Z Slice" parameters, whose defaults keep the old output
+	if reference == "Noise Pattern" && inputs_count == 16 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade the Posterize node to add the "Working Space" and "Luminance Only" parameters
+	if reference == "Posterize" && inputs_count == 2 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Upgrade the Vibrance node to add the "Saturation" parameter
+	if reference == "Vibrance" && inputs_count == 2 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
+	// Add the "Clone Source" input to the Brush node
+	if reference == "Brush" && inputs_count == 3 {
+		let mut node_template = resolve_document_node_type(reference)?.default_node_template();
+		document.network_interface.replace_implementation(node_id, network_path, &mut node_template);
+
+		let old_inputs = document.network_interface.replace_inputs(node_id, network_path, &mut node_template)?;
+
+		for (index, old_input) in old_inputs.into_iter().enumerate() {
+			document.network_interface.set_input(&InputConnector::node(*node_id, index), old_input, network_path);
+		}
+	}
+
 	// ==================================
 	// PUT ALL MIGRATIONS ABOVE THIS LINE
 	// ==================================
@@ -1037,6 +1257,14 @@ fn migrate_node(node_id: &NodeId, node: &DocumentNode, network_path: &[NodeId],
 	Some(())
 }
 
+/// The gamma which the Levels node used to derive from its "Midtones" parameter, which was offset by its output range, with all parameters as percentages.
+fn levels_gamma_from_midtones(midtones: f64, output_minimums: f64, output_maximums: f64) -> f64 {
+	let (midtones, output_minimums, output_maximums) = (midtones / 100., output_minimums / 100., output_maximums / 100.);
+	let midtones = output_minimums + (output_maximums - output_minimums) * midtones;
+
+	if midtones < 0.5 { 1. + 9. * (1. - midtones * 2.) } else { ((1. - midtones) * 2.).max(0.01) }
+}
+
 #[cfg(test)]
 mod tests {
 	use super::*;
@@ -1052,4 +1280,40 @@ mod tests {
 			panic!("Duplicate entries in `NODE_REPLACEMENTS`: {:?}", duplicates);
 		}
 	}
+
+	#[test]
+	fn single_curves_load_as_the_master_curve() {
+		use graphene_std::raster::curve::{Curve, CurveManipulatorGroup, CurveSpline};
+
+		// A Curves input saved before per-channel curves were added
+		let saved = r#"{"Curve":{"manipulatorGroups":[{"anchor":[0.5,0.75],"handles":[[0.4,0.65],[0.6,0.85]]}],"firstHandle":[0.1,0.3],"lastHandle":[0.9,0.95]}}"#;
+		let master = CurveSpline {
+			manipulator_groups: vec![CurveManipulatorGroup {
+				anchor: [0.5, 0.75],
+				handles: [[0.4, 0.65], [0.6, 0.85]],
+			}],
+			first_handle: [0.1, 0.3],
+			last_handle: [0.9, 0.95],
+		};
+		let curve = Curve { master, ..Default::default() };
+		assert_eq!(serde_json::from_str::<TaggedValue>(saved).unwrap(), TaggedValue::Curve(curve.clone()));
+
+		// Curves with channels round trip
+		let curve = Curve {
+			green: Some(curve.master.clone()),
+			..curve
+		};
+		let saved = serde_json::to_string(&TaggedValue::Curve(curve.clone())).unwrap();
+		assert_eq!(serde_json::from_str::<TaggedValue>(&saved).unwrap(), TaggedValue::Curve(curve));
+	}
+
+	#[test]
+	fn levels_midtones_convert_to_gamma() {
+		// The default midtones of 50% left the gamma unchanged
+		assert_eq!(levels_gamma_from_midtones(50., 0., 100.), 1.);
+		assert_eq!(levels_gamma_from_midtones(25., 0., 100.), 5.5);
+		assert_eq!(levels_gamma_from_midtones(75., 0., 100.), 0.5);
+		// The midtones were offset by the output range
+		assert_eq!(levels_gamma_from_midtones(50., 50., 100.), 0.5);
+	}
 }
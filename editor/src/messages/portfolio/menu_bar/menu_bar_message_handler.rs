@@ -2,7 +2,8 @@ use crate::messages::debug::utility_types::MessageLoggingVerbosity;
 use crate::messages::input_mapper::utility_types::macros::action_keys;
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::utility_types::clipboards::Clipboard;
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GroupFolderType};
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, AlignTarget, DistributeMode, FlipAxis, GroupFolderType};
+use crate::messages::portfolio::document::utility_types::similar_layers::SimilarityCriterion;
 use crate::messages::prelude::*;
 use graphene_std::path_bool::BooleanOperation;
 
@@ -15,8 +16,12 @@ pub struct MenuBarMessageHandler {
 	pub node_graph_open: bool,
 	pub has_selected_nodes: bool,
 	pub has_selected_layers: bool,
+	pub has_solo_layer: bool,
 	pub has_selection_history: (bool, bool),
 	pub spreadsheet_view_open: bool,
+	pub swatches_panel_open: bool,
+	/// The path, name, and whether it's pinned of each recent document, in the order they're listed.
+	pub recent_documents: Vec<(String, String, bool)>,
 	pub message_logging_verbosity: MessageLoggingVerbosity,
 	pub reset_node_definitions_on_open: bool,
 	pub single_path_node_compatible_layer_selected: bool,
@@ -35,12 +40,40 @@ impl MessageHandler<MenuBarMessage, ()> for MenuBarMessageHandler {
 	}
 }
 
+impl MenuBarMessageHandler {
+	/// The entries of the Open Recent submenu, with the pinned documents grouped above the rest.
+	fn recent_documents_children(&self) -> MenuBarEntryChildren {
+		let entry = |(path, name, pinned): &(String, String, bool)| {
+			let path = path.clone();
+			MenuBarEntry {
+				label: name.clone(),
+				icon: pinned.then(|| "PinActive".into()),
+				action: MenuBarEntry::create_action(move |_| RecentDocumentsMessage::Open { path: path.clone() }.into()),
+				..MenuBarEntry::default()
+			}
+		};
+		let pinned = self.recent_documents.iter().filter(|(_, _, pinned)| *pinned).map(entry).collect::<Vec<_>>();
+		let unpinned = self.recent_documents.iter().filter(|(_, _, pinned)| !*pinned).map(entry).collect::<Vec<_>>();
+
+		let clear = vec![MenuBarEntry {
+			label: "Clear Unpinned".into(),
+			icon: Some("Trash".into()),
+			action: MenuBarEntry::create_action(|_| RecentDocumentsMessage::ClearUnpinned.into()),
+			disabled: unpinned.is_empty(),
+			..MenuBarEntry::default()
+		}];
+
+		MenuBarEntryChildren([pinned, unpinned, clear].into_iter().filter(|group| !group.is_empty()).collect())
+	}
+}
+
 impl LayoutHolder for MenuBarMessageHandler {
 	fn layout(&self) -> Layout {
 		let no_active_document = !self.has_active_document;
 		let node_graph_open = self.node_graph_open;
 		let has_selected_nodes = self.has_selected_nodes;
 		let has_selected_layers = self.has_selected_layers;
+		let has_solo_layer = self.has_solo_layer;
 		let has_selection_history = self.has_selection_history;
 		let message_logging_verbosity_off = self.message_logging_verbosity == MessageLoggingVerbosity::Off;
 		let message_logging_verbosity_names = self.message_logging_verbosity == MessageLoggingVerbosity::Names;
@@ -74,6 +107,14 @@ impl LayoutHolder for MenuBarMessageHandler {
 							action: MenuBarEntry::create_action(|_| PortfolioMessage::OpenDocument.into()),
 							..MenuBarEntry::default()
 						},
+						MenuBarEntry {
+							label: "Open Recent".into(),
+							icon: Some("HistoryUndo".into()),
+							action: MenuBarEntry::no_action(),
+							disabled: self.recent_documents.is_empty(),
+							children: self.recent_documents_children(),
+							..MenuBarEntry::default()
+						},
 						MenuBarEntry {
 							label: "Open Demo Artwork…".into(),
 							icon: Some("Image".into()),
@@ -260,6 +301,13 @@ impl LayoutHolder for MenuBarMessageHandler {
 							disabled: no_active_document || !has_selected_layers,
 							..MenuBarEntry::default()
 						},
+						MenuBarEntry {
+							label: "Clear Solo".into(),
+							icon: Some("EyeShow".into()),
+							action: MenuBarEntry::create_action(|_| DocumentMessage::ClearSoloLayer.into()),
+							disabled: no_active_document || !has_solo_layer,
+							..MenuBarEntry::default()
+						},
 					],
 					vec![
 						MenuBarEntry {
@@ -356,23 +404,59 @@ impl LayoutHolder for MenuBarMessageHandler {
 										(AlignAxis::Y, AlignAggregate::Max, "AlignBottom", "Align Bottom"),
 									],
 								];
+								let entries = |target| {
+									choices
+										.into_iter()
+										.map(|group| {
+											group
+												.into_iter()
+												.map(|(axis, aggregate, icon, name)| MenuBarEntry {
+													label: name.into(),
+													icon: Some(icon.into()),
+													action: MenuBarEntry::create_action(move |_| DocumentMessage::AlignSelectedLayers { axis, aggregate, target }.into()),
+													disabled: no_active_document || !has_selected_layers,
+													..MenuBarEntry::default()
+												})
+												.collect::<Vec<_>>()
+										})
+										.collect::<Vec<_>>()
+								};
 
-								choices
+								let mut groups = entries(AlignTarget::Selection);
+								groups.push(vec![MenuBarEntry {
+									label: "To Artboard".into(),
+									icon: Some("Artboard".into()),
+									action: MenuBarEntry::no_action(),
+									disabled: no_active_document || !has_selected_layers,
+									children: MenuBarEntryChildren(entries(AlignTarget::Artboard)),
+									..MenuBarEntry::default()
+								}]);
+								groups
+							}),
+							..MenuBarEntry::default()
+						},
+						MenuBarEntry {
+							label: "Distribute".into(),
+							icon: Some("DistributeHorizontal".into()),
+							action: MenuBarEntry::no_action(),
+							disabled: no_active_document || !has_selected_layers,
+							children: MenuBarEntryChildren(
+								[(AlignAxis::X, "DistributeHorizontal", "Horizontal"), (AlignAxis::Y, "DistributeVertical", "Vertical")]
 									.into_iter()
-									.map(|group| {
-										group
+									.map(|(axis, icon, direction)| {
+										[(DistributeMode::Spacing, "Spacing"), (DistributeMode::Centers, "Centers")]
 											.into_iter()
-											.map(|(axis, aggregate, icon, name)| MenuBarEntry {
-												label: name.into(),
+											.map(|(mode, by)| MenuBarEntry {
+												label: format!("{direction} {by}"),
 												icon: Some(icon.into()),
-												action: MenuBarEntry::create_action(move |_| DocumentMessage::AlignSelectedLayers { axis, aggregate }.into()),
+												action: MenuBarEntry::create_action(move |_| DocumentMessage::DistributeSelectedLayers { axis, mode }.into()),
 												disabled: no_active_document || !has_selected_layers,
 												..MenuBarEntry::default()
 											})
 											.collect()
 									})
-									.collect()
-							}),
+									.collect(),
+							),
 							..MenuBarEntry::default()
 						},
 						MenuBarEntry {
@@ -476,6 +560,29 @@ impl LayoutHolder for MenuBarMessageHandler {
 							disabled: no_active_document || !has_selected_nodes,
 							..MenuBarEntry::default()
 						},
+						MenuBarEntry {
+							label: "Select Similar".into(),
+							action: MenuBarEntry::no_action(),
+							disabled: no_active_document || !has_selected_layers,
+							children: MenuBarEntryChildren(vec![
+								SimilarityCriterion::ALL
+									.into_iter()
+									.map(|criterion| MenuBarEntry {
+										label: criterion.label().into(),
+										action: MenuBarEntry::create_action(move |_| {
+											DocumentMessage::SelectSimilarLayers {
+												layer: None,
+												criterion,
+												extend: false,
+											}
+											.into()
+										}),
+										..MenuBarEntry::default()
+									})
+									.collect(),
+							]),
+							..MenuBarEntry::default()
+						},
 					],
 					vec![
 						MenuBarEntry {
@@ -592,6 +699,13 @@ impl LayoutHolder for MenuBarMessageHandler {
 						disabled: no_active_document,
 						..MenuBarEntry::default()
 					}],
+					vec![MenuBarEntry {
+						label: "Window: Swatches".into(),
+						icon: Some(if self.swatches_panel_open { "CheckboxChecked" } else { "CheckboxUnchecked" }.into()),
+						action: MenuBarEntry::create_action(|_| SwatchesMessage::ToggleOpen.into()),
+						disabled: no_active_document,
+						..MenuBarEntry::default()
+					}],
 				]),
 			),
 			MenuBarEntry::new_root(
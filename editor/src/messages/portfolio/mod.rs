@@ -4,7 +4,9 @@ mod portfolio_message_handler;
 pub mod document;
 pub mod document_migration;
 pub mod menu_bar;
+pub mod recent_documents;
 pub mod spreadsheet;
+pub mod swatches;
 pub mod utility_types;
 
 #[doc(inline)]
@@ -1,8 +1,9 @@
 use super::document::utility_types::document_metadata::LayerNodeIdentifier;
 use super::utility_types::PanelType;
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, RasterColorSpace};
 use crate::messages::portfolio::document::utility_types::clipboards::Clipboard;
 use crate::messages::prelude::*;
+use graph_craft::document::NodeId;
 use graphene_std::Color;
 use graphene_std::raster::Image;
 use graphene_std::text::Font;
@@ -17,6 +18,10 @@ pub enum PortfolioMessage {
 	Document(DocumentMessage),
 	#[child]
 	Spreadsheet(SpreadsheetMessage),
+	#[child]
+	Swatches(SwatchesMessage),
+	#[child]
+	RecentDocuments(RecentDocumentsMessage),
 
 	// Messages
 	Init,
@@ -24,6 +29,9 @@ pub enum PortfolioMessage {
 		document_id: DocumentId,
 		message: DocumentMessage,
 	},
+	AutoLevels {
+		node_path: Vec<NodeId>,
+	},
 	AutoSaveActiveDocument,
 	AutoSaveAllDocuments,
 	AutoSaveDocument {
@@ -49,6 +57,7 @@ pub enum PortfolioMessage {
 	},
 	DestroyAllDocuments,
 	EditorPreferences,
+	EndInteractiveRender,
 	FontLoaded {
 		font_family: String,
 		font_style: String,
@@ -56,6 +65,9 @@ pub enum PortfolioMessage {
 		data: Vec<u8>,
 	},
 	Import,
+	InspectNodeOutput {
+		node_path: Vec<NodeId>,
+	},
 	LoadDocumentResources {
 		document_id: DocumentId,
 	},
@@ -104,22 +116,47 @@ pub enum PortfolioMessage {
 		parent_and_insert_index: Option<(LayerNodeIdentifier, usize)>,
 	},
 	PrevDocument,
+	RequestInspectedValue {
+		node_path: Vec<NodeId>,
+		full: bool,
+	},
+	RequestNodeCacheStats,
+	RequestSelectedLayerHistogram,
 	SetActivePanel {
 		panel: PanelType,
 	},
 	SetDevicePixelRatio {
 		ratio: f64,
 	},
+	SetNodeInspection {
+		enabled: bool,
+	},
+	SetWidgetDragInProgress {
+		in_progress: bool,
+	},
 	SelectDocument {
 		document_id: DocumentId,
 	},
+	StopInspectingNodeOutput {
+		node_path: Vec<NodeId>,
+	},
 	SubmitDocumentExport {
 		file_name: String,
 		file_type: FileType,
 		scale_factor: f64,
 		bounds: ExportBounds,
 		transparent_background: bool,
+		color_space: RasterColorSpace,
+	},
+	SubmitArtboardsExport {
+		artboards: Vec<(LayerNodeIdentifier, String)>,
+		scale_factors: Vec<f64>,
+		file_name_template: String,
+		file_type: FileType,
+		transparent_background: bool,
+		color_space: RasterColorSpace,
 	},
+	CancelArtboardsExport,
 	SubmitActiveGraphRender,
 	SubmitGraphRender {
 		document_id: DocumentId,
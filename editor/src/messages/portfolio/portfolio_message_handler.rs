@@ -1,13 +1,14 @@
 use super::document::utility_types::document_metadata::LayerNodeIdentifier;
-use super::document::utility_types::network_interface;
+use super::document::utility_types::network_interface::{self, TransactionStatus};
 use super::spreadsheet::SpreadsheetMessageHandler;
 use super::utility_types::{PanelType, PersistentData};
 use crate::application::generate_uuid;
 use crate::consts::DEFAULT_DOCUMENT_NAME;
 use crate::messages::animation::TimingInformation;
 use crate::messages::debug::utility_types::MessageLoggingVerbosity;
+use crate::messages::dialog::export_dialog::artboard_file_name;
 use crate::messages::dialog::simple_dialogs;
-use crate::messages::frontend::utility_types::FrontendDocumentDetails;
+use crate::messages::frontend::utility_types::{ExportBounds, FrontendDocumentDetails};
 use crate::messages::layout::utility_types::widget_prelude::*;
 use crate::messages::portfolio::document::DocumentMessageContext;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
@@ -16,11 +17,12 @@ use crate::messages::portfolio::document::utility_types::clipboards::{Clipboard,
 use crate::messages::portfolio::document::utility_types::network_interface::OutputConnector;
 use crate::messages::portfolio::document::utility_types::nodes::SelectedNodes;
 use crate::messages::portfolio::document_migration::*;
+use crate::messages::portfolio::recent_documents::document_path;
 use crate::messages::preferences::SelectionMode;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::graph_modification_utils;
 use crate::messages::tool::utility_types::{HintData, HintGroup, ToolType};
-use crate::node_graph_executor::{ExportConfig, NodeGraphExecutor};
+use crate::node_graph_executor::{ExportConfig, NodeGraphExecutionError, NodeGraphExecutor};
 use glam::{DAffine2, DVec2};
 use graph_craft::document::NodeId;
 use graph_craft::document::value::TaggedValue;
@@ -36,7 +38,6 @@ pub struct PortfolioMessageContext<'a> {
 	pub message_logging_verbosity: MessageLoggingVerbosity,
 	pub reset_node_definitions_on_open: bool,
 	pub timing_information: TimingInformation,
-	pub animation: &'a AnimationMessageHandler,
 }
 
 #[derive(Debug, Default, ExtractField)]
@@ -52,8 +53,14 @@ pub struct PortfolioMessageHandler {
 	pub selection_mode: SelectionMode,
 	/// The spreadsheet UI allows for instance data to be previewed.
 	pub spreadsheet: SpreadsheetMessageHandler,
+	/// The Swatches panel manages the named colors of the active document.
+	pub swatches: SwatchesMessageHandler,
+	/// The recently opened and saved documents, which can be reopened from the File menu and the start screen.
+	pub recent_documents: RecentDocumentsMessageHandler,
 	device_pixel_ratio: Option<f64>,
 	pub reset_node_definitions_on_open: bool,
+	/// Whether a number input widget is being dragged, during which the graph is rendered at the interactive render quality.
+	widget_drag_in_progress: bool,
 }
 
 #[message_handler_data]
@@ -66,7 +73,6 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 			message_logging_verbosity,
 			reset_node_definitions_on_open,
 			timing_information,
-			animation,
 		} = context;
 
 		match message {
@@ -79,9 +85,12 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				self.menu_bar_message_handler.node_graph_open = false;
 				self.menu_bar_message_handler.has_selected_nodes = false;
 				self.menu_bar_message_handler.has_selected_layers = false;
+				self.menu_bar_message_handler.has_solo_layer = false;
 				self.menu_bar_message_handler.has_selection_history = (false, false);
 				self.menu_bar_message_handler.single_path_node_compatible_layer_selected = false;
 				self.menu_bar_message_handler.spreadsheet_view_open = self.spreadsheet.spreadsheet_view_open;
+				self.menu_bar_message_handler.swatches_panel_open = self.swatches.swatches_panel_open;
+				self.menu_bar_message_handler.recent_documents = self.recent_documents.entries().iter().map(|entry| (entry.path.clone(), entry.name.clone(), entry.pinned)).collect();
 				self.menu_bar_message_handler.message_logging_verbosity = message_logging_verbosity;
 				self.menu_bar_message_handler.reset_node_definitions_on_open = reset_node_definitions_on_open;
 
@@ -94,6 +103,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					let selected_nodes = document.network_interface.selected_nodes();
 					self.menu_bar_message_handler.has_selected_nodes = selected_nodes.selected_nodes().next().is_some();
 					self.menu_bar_message_handler.has_selected_layers = selected_nodes.selected_visible_layers(&document.network_interface).next().is_some();
+					self.menu_bar_message_handler.has_solo_layer = document.solo_layer().is_some();
 					self.menu_bar_message_handler.has_selection_history = {
 						let metadata = &document.network_interface.document_network_metadata().persistent_metadata;
 						(!metadata.selection_undo_history.is_empty(), !metadata.selection_redo_history.is_empty())
@@ -129,6 +139,17 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 			PortfolioMessage::Spreadsheet(message) => {
 				self.spreadsheet.process_message(message, responses, ());
 			}
+			PortfolioMessage::Swatches(message) => {
+				let network_interface = self
+					.active_document_id
+					.and_then(|document_id| self.documents.get_mut(&document_id))
+					.map(|document| &mut document.network_interface);
+				self.swatches.process_message(message, responses, SwatchesMessageContext { network_interface });
+			}
+			PortfolioMessage::RecentDocuments(message) => {
+				let active_document_name = self.active_document_id.and_then(|document_id| self.documents.get(&document_id)).map(|document| document.name.as_str());
+				self.recent_documents.process_message(message, responses, RecentDocumentsMessageContext { active_document_name });
+			}
 			PortfolioMessage::Document(message) => {
 				if let Some(document_id) = self.active_document_id {
 					if let Some(document) = self.documents.get_mut(&document_id) {
@@ -151,6 +172,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				// Load persistent data from the browser database
 				responses.add(FrontendMessage::TriggerLoadFirstAutoSaveDocument);
 				responses.add(FrontendMessage::TriggerLoadPreferences);
+				responses.add(FrontendMessage::TriggerLoadRecentDocuments);
 
 				// Display the menu bar at the top of the window
 				responses.add(MenuBarMessage::SendLayout);
@@ -178,6 +200,11 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					document.process_message(message, responses, document_inputs)
 				}
 			}
+			PortfolioMessage::AutoLevels { node_path } => {
+				// The levels are computed from the node's input image during the next graph evaluation, then set on the node as an undoable change
+				self.executor.request_auto_levels(node_path);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			PortfolioMessage::AutoSaveActiveDocument => {
 				if let Some(document_id) = self.active_document_id {
 					if let Some(document) = self.active_document_mut() {
@@ -218,6 +245,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 
 					// Clear relevant UI layouts if there are no documents
 					responses.add(PropertiesPanelMessage::Clear);
+					responses.add(SwatchesMessage::SendLayout);
 					responses.add(DocumentMessage::ClearLayersPanel);
 					let hint_data = HintData(vec![HintGroup(vec![])]);
 					responses.add(FrontendMessage::UpdateInputHints { hint_data });
@@ -242,6 +270,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				if self.documents.len() == 1 && self.document_ids[0] == document_id {
 					// Clear UI layouts that assume the existence of a document
 					responses.add(PropertiesPanelMessage::Clear);
+					responses.add(SwatchesMessage::SendLayout);
 					responses.add(DocumentMessage::ClearLayersPanel);
 					let hint_data = HintData(vec![HintGroup(vec![])]);
 					responses.add(FrontendMessage::UpdateInputHints { hint_data });
@@ -369,6 +398,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 						timing_information,
 						inspect_node,
 						true,
+						1.,
 					) {
 						responses.add(message);
 					}
@@ -379,10 +409,25 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				}
 			}
 			PortfolioMessage::EditorPreferences => self.executor.update_editor_preferences(preferences.editor_preferences()),
+			PortfolioMessage::EndInteractiveRender => {
+				// Replace the reduced quality render from the end of the interaction with one at full quality
+				let interacting = self.widget_drag_in_progress
+					|| self
+						.active_document()
+						.is_some_and(|document| document.network_interface.transaction_status() != TransactionStatus::Finished);
+				if self.executor.reduced_quality_render() && !interacting {
+					responses.add(NodeGraphMessage::RunDocumentGraph);
+				}
+			}
 			PortfolioMessage::Import => {
 				// This portfolio message wraps the frontend message so it can be listed as an action, which isn't possible for frontend messages
 				responses.add(FrontendMessage::TriggerImport);
 			}
+			PortfolioMessage::InspectNodeOutput { node_path } => {
+				// The output is kept after each graph evaluation while node inspection is enabled
+				self.executor.inspect_node_output(node_path);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			PortfolioMessage::LoadDocumentResources { document_id } => {
 				if let Some(document) = self.document_mut(document_id) {
 					document.load_layer_resources(responses);
@@ -424,7 +469,31 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				document_name,
 				document_serialized_content,
 			} => {
+				// An SVG file is opened as a new document with the SVG imported into it as editable layers
+				if let Some(name) = document_name.strip_suffix(".svg").or_else(|| document_name.strip_suffix(".SVG")) {
+					responses.add(PortfolioMessage::NewDocumentWithName { name: name.to_string() });
+					responses.add(DocumentMessage::PasteSvg {
+						name: Some(document_name.clone()),
+						svg: document_serialized_content,
+						mouse: None,
+						parent_and_insert_index: None,
+					});
+
+					// Wait for the document to be rendered so the click targets can be calculated in order to determine the artboard size that will encompass the imported SVG
+					responses.add(DeferMessage::AfterGraphRun {
+						messages: vec![DocumentMessage::WrapContentInArtboard { place_artboard_at_origin: true }.into()],
+					});
+					responses.add(DeferMessage::AfterNavigationReady {
+						messages: vec![DocumentMessage::ZoomCanvasToFitAll.into()],
+					});
+					return;
+				}
+
 				let document_id = DocumentId(generate_uuid());
+				responses.add(RecentDocumentsMessage::Record {
+					path: document_path(&document_name),
+					name: document_name.clone(),
+				});
 				responses.add(PortfolioMessage::OpenDocumentFileWithId {
 					document_id,
 					document_name,
@@ -753,6 +822,30 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(PortfolioMessage::SelectDocument { document_id: prev_id });
 				}
 			}
+			PortfolioMessage::RequestInspectedValue { node_path, full } => {
+				let value = self.executor.frontend_inspected_value(&node_path, full);
+				responses.add(FrontendMessage::UpdateInspectedValue { node_path, value });
+			}
+			PortfolioMessage::RequestNodeCacheStats => {
+				// The statistics are collected after the next graph evaluation, then sent to the frontend
+				self.executor.request_node_cache_stats();
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
+			PortfolioMessage::RequestSelectedLayerHistogram => {
+				let Some(document) = self.active_document() else { return };
+
+				// The histogram is only computed when exactly one layer is selected
+				let selected_nodes = document.network_interface.selected_nodes();
+				let mut selected_layers = selected_nodes.selected_layers(document.metadata());
+				let (Some(layer), None) = (selected_layers.next(), selected_layers.next()) else {
+					responses.add(FrontendMessage::UpdateHistogram { layer: None, histogram: None });
+					return;
+				};
+
+				// The histogram is computed from the layer's content during the next graph evaluation, then sent to the frontend
+				self.executor.request_histogram(layer.to_node());
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			PortfolioMessage::SetActivePanel { panel } => {
 				self.active_panel = panel;
 				responses.add(DocumentMessage::SetActivePanel { active_panel: self.active_panel });
@@ -761,6 +854,16 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				self.device_pixel_ratio = Some(ratio);
 				responses.add(OverlaysMessage::Draw);
 			}
+			PortfolioMessage::SetNodeInspection { enabled } => {
+				self.executor.set_node_inspection(enabled);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
+			PortfolioMessage::SetWidgetDragInProgress { in_progress } => {
+				self.widget_drag_in_progress = in_progress;
+				if !in_progress {
+					responses.add(PortfolioMessage::EndInteractiveRender);
+				}
+			}
 			PortfolioMessage::SelectDocument { document_id } => {
 				// Auto-save the document we are leaving
 				let mut node_graph_open = false;
@@ -788,7 +891,14 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 				responses.add(BroadcastEvent::SelectionChanged);
 				responses.add(NavigationMessage::CanvasPan { delta: (0., 0.).into() });
 				responses.add(NodeGraphMessage::RunDocumentGraph);
+				responses.add(SwatchesMessage::SendLayout);
+				responses.add(NodeGraphMessage::SendCustomNodeTypes);
+				responses.add(DocumentMessage::SendHistory);
 				responses.add(DocumentMessage::GraphViewOverlay { open: node_graph_open });
+				// Thumbnails which couldn't be captured before, like those of documents loaded from the recent documents list of an earlier session, are captured once the document is shown
+				responses.add(DeferMessage::AfterGraphRun {
+					messages: vec![RecentDocumentsMessage::CaptureMissingThumbnail.into()],
+				});
 				if node_graph_open {
 					responses.add(NodeGraphMessage::UpdateGraphBarRight);
 					responses.add(NodeGraphMessage::UnloadWires);
@@ -808,12 +918,17 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					responses.add(PropertiesPanelMessage::Clear);
 				}
 			}
+			PortfolioMessage::StopInspectingNodeOutput { node_path } => {
+				self.executor.stop_inspecting_node_output(&node_path);
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
 			PortfolioMessage::SubmitDocumentExport {
 				file_name,
 				file_type,
 				scale_factor,
 				bounds,
 				transparent_background,
+				color_space,
 			} => {
 				let document = self.active_document_id.and_then(|id| self.documents.get_mut(&id)).expect("Tried to render non-existent document");
 				let export_config = ExportConfig {
@@ -822,6 +937,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					scale_factor,
 					bounds,
 					transparent_background,
+					color_space,
 					..Default::default()
 				};
 				let result = self.executor.submit_document_export(document, export_config);
@@ -833,6 +949,40 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 					});
 				}
 			}
+			PortfolioMessage::SubmitArtboardsExport {
+				artboards,
+				scale_factors,
+				file_name_template,
+				file_type,
+				transparent_background,
+				color_space,
+			} => {
+				let document = self.active_document_id.and_then(|id| self.documents.get_mut(&id)).expect("Tried to render non-existent document");
+				let document_name = document.name.clone();
+				let exports = artboards
+					.iter()
+					.flat_map(|(artboard, name)| {
+						scale_factors.iter().map(|&scale_factor| ExportConfig {
+							file_name: artboard_file_name(&file_name_template, &document_name, name, scale_factor),
+							file_type,
+							scale_factor,
+							bounds: ExportBounds::Artboard(*artboard),
+							transparent_background,
+							color_space,
+							..Default::default()
+						})
+					})
+					.collect();
+				let result = self.executor.submit_export_batch(document, exports, responses);
+
+				if let Err(description) = result {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Unable to export artboards".to_string(),
+						description,
+					});
+				}
+			}
+			PortfolioMessage::CancelArtboardsExport => self.executor.cancel_export_batch(responses),
 			PortfolioMessage::SubmitActiveGraphRender => {
 				if let Some(document_id) = self.active_document_id {
 					responses.add(PortfolioMessage::SubmitGraphRender { document_id, ignore_hash: false });
@@ -840,13 +990,19 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 			}
 			PortfolioMessage::SubmitGraphRender { document_id, ignore_hash } => {
 				let inspect_node = self.inspect_node_id();
-				let result = self.executor.submit_node_graph_evaluation(
-					self.documents.get_mut(&document_id).expect("Tried to render non-existent document"),
-					ipp.viewport_bounds.size().as_uvec2(),
-					timing_information,
-					inspect_node,
-					ignore_hash,
-				);
+				let document = self.documents.get_mut(&document_id).expect("Tried to render non-existent document");
+
+				// While the user is dragging, render at a reduced resolution to keep the interaction responsive
+				let interacting = self.widget_drag_in_progress || document.network_interface.transaction_status() != TransactionStatus::Finished;
+				let render_scale = if interacting {
+					preferences.interactive_render_quality.scale_factor(self.executor.last_render_time())
+				} else {
+					1.
+				};
+
+				let result = self
+					.executor
+					.submit_node_graph_evaluation(document, ipp.viewport_bounds.size().as_uvec2(), timing_information, inspect_node, ignore_hash, render_scale);
 
 				match result {
 					Err(description) => {
@@ -868,7 +1024,7 @@ impl MessageHandler<PortfolioMessage, PortfolioMessageContext<'_>> for Portfolio
 			}
 			PortfolioMessage::UpdateDocumentWidgets => {
 				if let Some(document) = self.active_document() {
-					document.update_document_widgets(responses, animation.is_playing(), timing_information.animation_time);
+					document.update_document_widgets(responses);
 				}
 			}
 			PortfolioMessage::UpdateOpenDocumentsList => {
@@ -1010,13 +1166,16 @@ impl PortfolioMessageHandler {
 		self.document_ids.iter().position(|id| id == &document_id).expect("Active document is missing from document ids")
 	}
 
-	pub fn poll_node_graph_evaluation(&mut self, responses: &mut VecDeque<Message>) -> Result<(), String> {
+	/// Processes the results of the node graph evaluations which have finished, at the given time in milliseconds.
+	pub fn poll_node_graph_evaluation(&mut self, time: f64, responses: &mut VecDeque<Message>) -> Result<(), NodeGraphExecutionError> {
 		let Some(active_document) = self.active_document_id.and_then(|id| self.documents.get_mut(&id)) else {
-			return Err("No active document".to_string());
+			return Err(NodeGraphExecutionError::NoActiveDocument);
 		};
 
-		let result = self.executor.poll_node_graph_evaluation(active_document, responses);
-		if result.is_err() {
+		let result = self.executor.poll_node_graph_evaluation(active_document, time, responses);
+		if let Err(error) = &result {
+			self.executor.display_error(error, responses);
+
 			let error = r#"
 				<rect x="50%" y="50%" width="460" height="100" transform="translate(-230 -50)" rx="4" fill="var(--color-warning-yellow)" />
 				<text x="50%" y="50%" dominant-baseline="middle" text-anchor="middle" font-size="18" fill="var(--color-2-mildblack)">
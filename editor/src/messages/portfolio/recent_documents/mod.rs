@@ -0,0 +1,7 @@
+mod recent_documents_message;
+mod recent_documents_message_handler;
+
+#[doc(inline)]
+pub use recent_documents_message::*;
+#[doc(inline)]
+pub use recent_documents_message_handler::*;
@@ -0,0 +1,19 @@
+use crate::messages::prelude::*;
+
+/// The recently opened and saved documents, which are persisted between sessions so they can be reopened from the File menu and the start screen.
+#[impl_message(Message, PortfolioMessage, RecentDocuments)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum RecentDocumentsMessage {
+	Load { serialized: String },
+	SendList,
+
+	Record { path: String, name: String },
+	CaptureMissingThumbnail,
+	SetThumbnail { path: String, thumbnail: String },
+	TogglePin { path: String },
+	Remove { path: String },
+	ClearUnpinned,
+
+	Open { path: String },
+	FileMissing { path: String },
+}
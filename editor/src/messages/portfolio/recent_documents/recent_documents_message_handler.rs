@@ -0,0 +1,250 @@
+use crate::consts::{FILE_SAVE_SUFFIX, MAX_RECENT_DOCUMENTS};
+use crate::messages::dialog::simple_dialogs::MissingRecentDocumentDialog;
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::prelude::*;
+
+#[derive(ExtractField)]
+pub struct RecentDocumentsMessageContext<'a> {
+	/// The name of the active document, if there is one, which is the only document whose thumbnail can be captured from the viewport.
+	pub active_document_name: Option<&'a str>,
+}
+
+/// A document in the recent documents list.
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct RecentDocument {
+	/// Identifies the file the document was opened from or saved to. In the browser, which doesn't expose where a file is, this is its file name.
+	pub path: String,
+	pub name: String,
+	/// When the document was last opened or saved, in milliseconds since the Unix epoch.
+	pub timestamp: u64,
+	/// A small PNG preview from the document's last render, as a data URL, or `None` until one is captured.
+	pub thumbnail: Option<String>,
+	pub pinned: bool,
+}
+
+/// The recently opened and saved documents, which are persisted between sessions so they can be reopened from the File menu and the start screen.
+#[derive(Debug, Clone, Default, PartialEq, serde::Serialize, serde::Deserialize, ExtractField)]
+pub struct RecentDocumentsMessageHandler {
+	/// The pinned documents followed by the rest, each from the most to the least recent, with one entry per path.
+	entries: Vec<RecentDocument>,
+}
+
+#[message_handler_data]
+impl MessageHandler<RecentDocumentsMessage, RecentDocumentsMessageContext<'_>> for RecentDocumentsMessageHandler {
+	fn process_message(&mut self, message: RecentDocumentsMessage, responses: &mut VecDeque<Message>, context: RecentDocumentsMessageContext) {
+		let RecentDocumentsMessageContext { active_document_name } = context;
+		let edits_list = !matches!(
+			message,
+			RecentDocumentsMessage::Load { .. }
+				| RecentDocumentsMessage::SendList
+				| RecentDocumentsMessage::CaptureMissingThumbnail
+				| RecentDocumentsMessage::Open { .. }
+				| RecentDocumentsMessage::FileMissing { .. }
+		);
+
+		match message {
+			RecentDocumentsMessage::Load { serialized } => {
+				match serde_json::from_str::<RecentDocumentsMessageHandler>(&serialized) {
+					Ok(recent_documents) => {
+						*self = recent_documents;
+						self.sort_and_truncate();
+					}
+					Err(error) => warn!("Failed to load the recent documents: {error}"),
+				}
+				responses.add(RecentDocumentsMessage::SendList);
+			}
+			RecentDocumentsMessage::SendList => {
+				responses.add(FrontendMessage::UpdateRecentDocuments {
+					recent_documents: self.entries.clone(),
+				});
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			RecentDocumentsMessage::Record { path, name } => {
+				self.record(path, name, unix_time_milliseconds());
+
+				// The thumbnail is taken from the viewport once the document has been rendered
+				responses.add(DeferMessage::AfterGraphRun {
+					messages: vec![RecentDocumentsMessage::CaptureMissingThumbnail.into()],
+				});
+			}
+			RecentDocumentsMessage::CaptureMissingThumbnail => {
+				let Some(path) = active_document_name.map(document_path) else { return };
+				if self.entries.iter().any(|entry| entry.path == path && entry.thumbnail.is_none()) {
+					responses.add(FrontendMessage::TriggerCaptureRecentDocumentThumbnail { path });
+				}
+			}
+			RecentDocumentsMessage::SetThumbnail { path, thumbnail } => {
+				let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) else { return };
+				entry.thumbnail = Some(thumbnail);
+			}
+			RecentDocumentsMessage::TogglePin { path } => self.toggle_pin(&path),
+			RecentDocumentsMessage::Remove { path } => self.entries.retain(|entry| entry.path != path),
+			RecentDocumentsMessage::ClearUnpinned => self.entries.retain(|entry| entry.pinned),
+			RecentDocumentsMessage::Open { path } => {
+				responses.add(FrontendMessage::TriggerOpenRecentDocument { path });
+			}
+			RecentDocumentsMessage::FileMissing { path } => {
+				let Some(entry) = self.entries.iter().find(|entry| entry.path == path) else { return };
+				let dialog = MissingRecentDocumentDialog { path, name: entry.name.clone() };
+				dialog.send_dialog_to_frontend(responses);
+			}
+		}
+
+		if edits_list {
+			responses.add(FrontendMessage::TriggerSaveRecentDocuments { serialized: self.serialize() });
+			responses.add(RecentDocumentsMessage::SendList);
+		}
+	}
+
+	fn actions(&self) -> ActionList {
+		actions!(RecentDocumentsMessage;)
+	}
+}
+
+impl RecentDocumentsMessageHandler {
+	pub fn entries(&self) -> &[RecentDocument] {
+		&self.entries
+	}
+
+	pub fn serialize(&self) -> String {
+		serde_json::to_string(self).expect("Failed to serialize the recent documents")
+	}
+
+	/// Moves the document to the front of its group, adding it if it isn't listed yet. Its thumbnail is cleared so a new one is captured.
+	pub fn record(&mut self, path: String, name: String, timestamp: u64) {
+		let pinned = self.entries.iter().any(|entry| entry.path == path && entry.pinned);
+		self.entries.retain(|entry| entry.path != path);
+		self.entries.insert(
+			0,
+			RecentDocument {
+				path,
+				name,
+				timestamp,
+				thumbnail: None,
+				pinned,
+			},
+		);
+		self.sort_and_truncate();
+	}
+
+	pub fn toggle_pin(&mut self, path: &str) {
+		let Some(entry) = self.entries.iter_mut().find(|entry| entry.path == path) else { return };
+		entry.pinned = !entry.pinned;
+		self.sort_and_truncate();
+	}
+
+	/// Orders the pinned documents before the rest, each from the most recent, then drops the least recent unpinned ones beyond the cap.
+	fn sort_and_truncate(&mut self) {
+		// The sort is stable, so documents recorded at the same time keep the newest first
+		self.entries.sort_by_key(|entry| (!entry.pinned, std::cmp::Reverse(entry.timestamp)));
+
+		let pinned_count = self.entries.iter().filter(|entry| entry.pinned).count();
+		let mut unpinned_count = 0;
+		self.entries.retain(|entry| {
+			if entry.pinned {
+				return true;
+			}
+			unpinned_count += 1;
+			pinned_count + unpinned_count <= MAX_RECENT_DOCUMENTS
+		});
+	}
+}
+
+/// The path which a document with the given name is opened from and saved to, since documents are saved under their name with the file extension added.
+pub fn document_path(name: &str) -> String {
+	if name.ends_with(FILE_SAVE_SUFFIX) {
+		name.to_string()
+	} else {
+		format!("{name}{FILE_SAVE_SUFFIX}")
+	}
+}
+
+fn unix_time_milliseconds() -> u64 {
+	#[cfg(target_family = "wasm")]
+	{
+		js_sys::Date::now() as u64
+	}
+	#[cfg(not(target_family = "wasm"))]
+	{
+		std::time::SystemTime::now().duration_since(std::time::UNIX_EPOCH).map_or(0, |duration| duration.as_millis() as u64)
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn paths(recent_documents: &RecentDocumentsMessageHandler) -> Vec<&str> {
+		recent_documents.entries().iter().map(|entry| entry.path.as_str()).collect()
+	}
+
+	fn record(recent_documents: &mut RecentDocumentsMessageHandler, path: &str, timestamp: u64) {
+		recent_documents.record(path.to_string(), path.trim_end_matches(FILE_SAVE_SUFFIX).to_string(), timestamp);
+	}
+
+	#[test]
+	fn most_recent_first_with_pinned_at_the_top() {
+		let mut recent_documents = RecentDocumentsMessageHandler::default();
+		record(&mut recent_documents, "a.graphite", 1);
+		record(&mut recent_documents, "b.graphite", 2);
+		record(&mut recent_documents, "c.graphite", 3);
+		assert_eq!(paths(&recent_documents), ["c.graphite", "b.graphite", "a.graphite"]);
+
+		recent_documents.toggle_pin("a.graphite");
+		assert_eq!(paths(&recent_documents), ["a.graphite", "c.graphite", "b.graphite"]);
+
+		// A pinned document stays pinned when it's opened again
+		record(&mut recent_documents, "b.graphite", 4);
+		record(&mut recent_documents, "a.graphite", 5);
+		assert_eq!(paths(&recent_documents), ["a.graphite", "b.graphite", "c.graphite"]);
+		assert!(recent_documents.entries()[0].pinned);
+
+		recent_documents.toggle_pin("a.graphite");
+		assert_eq!(paths(&recent_documents), ["a.graphite", "b.graphite", "c.graphite"]);
+		assert!(recent_documents.entries().iter().all(|entry| !entry.pinned));
+	}
+
+	#[test]
+	fn recording_a_listed_path_replaces_its_entry() {
+		let mut recent_documents = RecentDocumentsMessageHandler::default();
+		record(&mut recent_documents, "a.graphite", 1);
+		record(&mut recent_documents, "b.graphite", 2);
+		recent_documents.entries[1].thumbnail = Some("data:image/png;base64,".to_string());
+
+		recent_documents.record("a.graphite".to_string(), "Renamed".to_string(), 3);
+		assert_eq!(paths(&recent_documents), ["a.graphite", "b.graphite"]);
+		let entry = &recent_documents.entries()[0];
+		assert_eq!((entry.name.as_str(), entry.timestamp), ("Renamed", 3));
+		// The thumbnail of the old entry is out of date
+		assert_eq!(entry.thumbnail, None);
+	}
+
+	#[test]
+	fn least_recent_unpinned_documents_are_dropped_beyond_the_cap() {
+		let mut recent_documents = RecentDocumentsMessageHandler::default();
+		for index in 0..MAX_RECENT_DOCUMENTS as u64 {
+			record(&mut recent_documents, &format!("{index}.graphite"), index);
+		}
+		recent_documents.toggle_pin("0.graphite");
+		assert_eq!(recent_documents.entries().len(), MAX_RECENT_DOCUMENTS);
+
+		record(&mut recent_documents, "new.graphite", 100);
+		assert_eq!(recent_documents.entries().len(), MAX_RECENT_DOCUMENTS);
+		// The oldest document is pinned, so the next oldest is dropped instead
+		assert!(paths(&recent_documents).contains(&"0.graphite"));
+		assert!(!paths(&recent_documents).contains(&"1.graphite"));
+		assert_eq!(paths(&recent_documents)[..2], ["0.graphite", "new.graphite"]);
+	}
+
+	#[test]
+	fn serialization_round_trip() {
+		let mut recent_documents = RecentDocumentsMessageHandler::default();
+		record(&mut recent_documents, "a.graphite", 1);
+		record(&mut recent_documents, "b.graphite", 2);
+		recent_documents.toggle_pin("a.graphite");
+		recent_documents.entries[1].thumbnail = Some("data:image/png;base64,iVBORw0KGgo=".to_string());
+
+		let deserialized = serde_json::from_str::<RecentDocumentsMessageHandler>(&recent_documents.serialize()).unwrap();
+		assert_eq!(deserialized, recent_documents);
+	}
+}
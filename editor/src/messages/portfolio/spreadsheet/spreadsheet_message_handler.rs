@@ -159,6 +159,7 @@ impl InstanceLayout for GraphicElement {
 			Self::VectorData(instances) => instances.identifier(),
 			Self::RasterDataCPU(_) => "RasterDataCPU".to_string(),
 			Self::RasterDataGPU(_) => "RasterDataGPU".to_string(),
+			Self::Effect(_) => "Effect".to_string(),
 		}
 	}
 	// Don't put a breadcrumb for GraphicElement
@@ -171,6 +172,7 @@ impl InstanceLayout for GraphicElement {
 			Self::VectorData(instances) => instances.layout_with_breadcrumb(data),
 			Self::RasterDataCPU(_) => label("Raster frame not supported"),
 			Self::RasterDataGPU(_) => label("Raster frame not supported"),
+			Self::Effect(_) => label("Effect not supported"),
 		}
 	}
 }
@@ -0,0 +1,7 @@
+mod swatches_message;
+mod swatches_message_handler;
+
+#[doc(inline)]
+pub use swatches_message::*;
+#[doc(inline)]
+pub use swatches_message_handler::*;
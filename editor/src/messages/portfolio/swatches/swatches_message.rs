@@ -0,0 +1,16 @@
+use crate::messages::prelude::*;
+use graphene_std::Color;
+use graphene_std::vector::style::SwatchId;
+
+/// The Swatches panel manages the named colors of the active document, which fills and strokes can be linked to.
+#[impl_message(Message, PortfolioMessage, Swatches)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize)]
+pub enum SwatchesMessage {
+	ToggleOpen,
+	SendLayout,
+
+	Create { id: SwatchId, name: String, color: Color },
+	Rename { id: SwatchId, name: String },
+	SetColor { id: SwatchId, color: Color },
+	Delete { id: SwatchId },
+}
@@ -0,0 +1,221 @@
+use crate::application::generate_uuid;
+use crate::messages::layout::utility_types::widget_prelude::*;
+use crate::messages::portfolio::document::utility_types::network_interface::{InputConnector, NodeNetworkInterface};
+use crate::messages::portfolio::document::utility_types::swatches::{DocumentSwatch, DocumentSwatches};
+use crate::messages::prelude::*;
+use graph_craft::document::NodeInput;
+use graph_craft::document::value::TaggedValue;
+use graphene_std::Color;
+use graphene_std::vector::style::{FillChoice, SwatchColor, SwatchId};
+
+#[derive(ExtractField)]
+pub struct SwatchesMessageContext<'a> {
+	/// The network interface of the active document, if there is one, which stores its swatches.
+	pub network_interface: Option<&'a mut NodeNetworkInterface>,
+}
+
+/// The Swatches panel manages the named colors of the active document, which fills and strokes can be linked to.
+#[derive(Debug, Clone, Default, ExtractField)]
+pub struct SwatchesMessageHandler {
+	/// Sets whether or not the Swatches panel is drawn.
+	pub swatches_panel_open: bool,
+}
+
+#[message_handler_data]
+impl MessageHandler<SwatchesMessage, SwatchesMessageContext<'_>> for SwatchesMessageHandler {
+	fn process_message(&mut self, message: SwatchesMessage, responses: &mut VecDeque<Message>, context: SwatchesMessageContext) {
+		let SwatchesMessageContext { mut network_interface } = context;
+		let edits_swatches = !matches!(message, SwatchesMessage::ToggleOpen | SwatchesMessage::SendLayout);
+
+		match message {
+			SwatchesMessage::ToggleOpen => {
+				self.swatches_panel_open = !self.swatches_panel_open;
+				// Update checked UI state for open
+				responses.add(MenuBarMessage::SendLayout);
+			}
+			SwatchesMessage::SendLayout => {}
+			SwatchesMessage::Create { id, name, color } => {
+				let Some(network_interface) = network_interface.as_deref_mut() else { return };
+				network_interface.swatches_mut().add(DocumentSwatch { id, name, color });
+			}
+			SwatchesMessage::Rename { id, name } => {
+				let Some(swatch) = network_interface.as_deref_mut().and_then(|network_interface| network_interface.swatches_mut().get_mut(id)) else {
+					return;
+				};
+				swatch.name = name;
+			}
+			SwatchesMessage::SetColor { id, color } => {
+				let Some(swatch) = network_interface.as_deref_mut().and_then(|network_interface| network_interface.swatches_mut().get_mut(id)) else {
+					return;
+				};
+				swatch.color = color;
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
+			SwatchesMessage::Delete { id } => {
+				let Some(network_interface) = network_interface.as_deref_mut() else { return };
+				let Some(swatch) = network_interface.swatches_mut().remove(id) else { return };
+
+				// References to the deleted swatch are left dangling, but given its last color so their layers look unchanged until they're detached
+				for reference in DocumentSwatches::references(network_interface.document_network(), id) {
+					let value = TaggedValue::SwatchColor(SwatchColor {
+						color: swatch.color,
+						..reference.swatch_color
+					});
+					let input_connector = InputConnector::node(reference.node_id, reference.input_index);
+					network_interface.set_input(&input_connector, NodeInput::value(value, false), &reference.network_path);
+				}
+				responses.add(NodeGraphMessage::RunDocumentGraph);
+			}
+		}
+
+		// The Properties panel shows the names and colors of the swatches which its color inputs are linked to
+		if edits_swatches {
+			responses.add(PropertiesPanelMessage::Refresh);
+		}
+
+		responses.add(FrontendMessage::UpdateSwatchesPanelState { open: self.swatches_panel_open });
+		if self.swatches_panel_open {
+			responses.add(LayoutMessage::SendLayout {
+				layout: Self::layout(network_interface.as_deref().map(NodeNetworkInterface::swatches)),
+				layout_target: LayoutTarget::SwatchesPanel,
+			});
+		}
+	}
+
+	fn actions(&self) -> ActionList {
+		actions!(SwatchesMessage;)
+	}
+}
+
+impl SwatchesMessageHandler {
+	fn layout(swatches: Option<&DocumentSwatches>) -> Layout {
+		let Some(swatches) = swatches else {
+			return Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row {
+				widgets: vec![TextLabel::new("No document is open").italic(true).widget_holder()],
+			}]));
+		};
+
+		let mut layout: Vec<LayoutGroup> = swatches
+			.iter()
+			.map(|swatch| {
+				let id = swatch.id;
+				let widgets = vec![
+					ColorInput::new(FillChoice::Solid(swatch.color))
+						.allow_none(false)
+						.tooltip("The color of this swatch, which every color linked to it uses")
+						.on_update(move |input: &ColorInput| {
+							SwatchesMessage::SetColor {
+								id,
+								color: input.value.as_solid().unwrap_or_default(),
+							}
+							.into()
+						})
+						.on_commit(|_| DocumentMessage::AddTransaction.into())
+						.widget_holder(),
+					Separator::new(SeparatorType::Related).widget_holder(),
+					TextInput::new(swatch.name.clone())
+						.on_update(move |input: &TextInput| SwatchesMessage::Rename { id, name: input.value.clone() }.into())
+						.on_commit(|_| DocumentMessage::AddTransaction.into())
+						.widget_holder(),
+					Separator::new(SeparatorType::Related).widget_holder(),
+					IconButton::new("Trash", 24)
+						.tooltip("Delete this swatch. Colors linked to it keep its current color.")
+						.on_update(move |_| SwatchesMessage::Delete { id }.into())
+						.on_commit(|_| DocumentMessage::AddTransaction.into())
+						.widget_holder(),
+				];
+				LayoutGroup::Row { widgets }
+			})
+			.collect();
+
+		let name = format!("Swatch {}", swatches.iter().count() + 1);
+		let color = swatches.iter().last().map_or(Color::BLACK, |swatch| swatch.color);
+		layout.push(LayoutGroup::Row {
+			widgets: vec![
+				TextButton::new("New Swatch")
+					.icon(Some("Add".into()))
+					.tooltip("Add a swatch to the document, which fills and strokes can then be linked to from the Properties panel")
+					.on_update(move |_| {
+						let id = SwatchId(generate_uuid());
+						SwatchesMessage::Create { id, name: name.clone(), color }.into()
+					})
+					.on_commit(|_| DocumentMessage::AddTransaction.into())
+					.widget_holder(),
+			],
+		});
+
+		Layout::WidgetLayout(WidgetLayout::new(layout))
+	}
+}
+
+#[cfg(test)]
+mod test_swatches {
+	use crate::messages::tool::common_functionality::graph_modification_utils::{get_fill_color, get_fill_id};
+	use crate::test_utils::test_prelude::*;
+	use graph_craft::document::value::TaggedValue;
+	use graphene_std::vector::style::{SwatchColor, SwatchId};
+
+	/// Draws a rectangle and links its fill to a new swatch of the given color.
+	async fn rectangle_linked_to_swatch(editor: &mut EditorTestUtils, id: SwatchId, color: Color) -> LayerNodeIdentifier {
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		let layer = editor.active_document().metadata().all_layers().next().unwrap();
+		let node_id = get_fill_id(layer, &editor.active_document().network_interface).unwrap();
+
+		editor.handle_message(DocumentMessage::AddTransaction).await;
+		editor.handle_message(SwatchesMessage::Create { id, name: "Brand".to_string(), color }).await;
+		editor
+			.handle_message(NodeGraphMessage::SetInputValue {
+				node_id,
+				input_index: 1,
+				value: TaggedValue::SwatchColor(SwatchColor { swatch: id, color }),
+			})
+			.await;
+		layer
+	}
+
+	fn fill_color(editor: &EditorTestUtils, layer: LayerNodeIdentifier) -> Option<Color> {
+		get_fill_color(layer, &editor.active_document().network_interface)
+	}
+
+	#[tokio::test]
+	async fn undoing_a_swatch_edit_restores_linked_colors() {
+		let mut editor = EditorTestUtils::create();
+		let id = SwatchId(1);
+		let layer = rectangle_linked_to_swatch(&mut editor, id, Color::RED).await;
+		assert_eq!(fill_color(&editor, layer), Some(Color::RED.to_linear_srgb()));
+
+		// The color picker commits before it sends the new color, so the transaction is started before the swatch changes
+		editor.handle_message(DocumentMessage::AddTransaction).await;
+		editor.handle_message(SwatchesMessage::SetColor { id, color: Color::BLUE }).await;
+		assert_eq!(fill_color(&editor, layer), Some(Color::BLUE.to_linear_srgb()));
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(fill_color(&editor, layer), Some(Color::RED.to_linear_srgb()));
+		assert_eq!(editor.active_document().network_interface.swatches().get(id).map(|swatch| swatch.color), Some(Color::RED));
+
+		editor.handle_message(DocumentMessage::Redo).await;
+		assert_eq!(fill_color(&editor, layer), Some(Color::BLUE.to_linear_srgb()));
+	}
+
+	#[tokio::test]
+	async fn deleting_a_swatch_keeps_the_last_color_of_dangling_references() {
+		let mut editor = EditorTestUtils::create();
+		let id = SwatchId(1);
+		let layer = rectangle_linked_to_swatch(&mut editor, id, Color::RED).await;
+
+		editor.handle_message(DocumentMessage::AddTransaction).await;
+		editor.handle_message(SwatchesMessage::SetColor { id, color: Color::GREEN }).await;
+		editor.handle_message(DocumentMessage::AddTransaction).await;
+		editor.handle_message(SwatchesMessage::Delete { id }).await;
+
+		let network_interface = &editor.active_document().network_interface;
+		assert!(network_interface.swatches().get(id).is_none());
+		let fill = NodeGraphLayer::new(layer, network_interface).find_input("Fill", 1);
+		assert_eq!(fill, Some(&TaggedValue::SwatchColor(SwatchColor { swatch: id, color: Color::GREEN })));
+		assert_eq!(fill_color(&editor, layer), Some(Color::GREEN.to_linear_srgb()));
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		assert_eq!(editor.active_document().network_interface.swatches().get(id).map(|swatch| swatch.color), Some(Color::GREEN));
+	}
+}
@@ -44,6 +44,7 @@ pub enum PanelType {
 	Layers,
 	Properties,
 	Spreadsheet,
+	Swatches,
 }
 
 impl From<String> for PanelType {
@@ -53,6 +54,7 @@ impl From<String> for PanelType {
 			"Layers" => PanelType::Layers,
 			"Properties" => PanelType::Properties,
 			"Spreadsheet" => PanelType::Spreadsheet,
+			"Swatches" => PanelType::Swatches,
 			_ => panic!("Unknown panel type: {value}"),
 		}
 	}
@@ -1,3 +1,4 @@
+pub mod palettes;
 mod preferences_message;
 mod preferences_message_handler;
 pub mod utility_types;
@@ -7,4 +8,4 @@ pub use preferences_message::{PreferencesMessage, PreferencesMessageDiscriminant
 #[doc(inline)]
 pub use preferences_message_handler::PreferencesMessageHandler;
 #[doc(inline)]
-pub use utility_types::SelectionMode;
+pub use utility_types::{InteractiveRenderQuality, OverlayQuality, OverlaySupersampling, SelectionMode};
@@ -0,0 +1,255 @@
+use super::{Palette, PaletteError, Swatch};
+use graphene_std::raster::color::Color;
+
+const SIGNATURE: &[u8; 4] = b"ASEF";
+const GROUP_START: u16 = 0xC001;
+const GROUP_END: u16 = 0xC002;
+const COLOR_ENTRY: u16 = 0x0001;
+/// The swatch type which is neither a global nor a spot color.
+const NORMAL_COLOR: u16 = 2;
+
+/// Reads big-endian values from a byte slice, describing what was being read if the data runs out.
+struct Reader<'a> {
+	data: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+	fn take(&mut self, length: usize, reading: &'static str) -> Result<&'a [u8], PaletteError> {
+		if self.data.len() < length {
+			return Err(PaletteError::UnexpectedEnd(reading));
+		}
+		let (taken, rest) = self.data.split_at(length);
+		self.data = rest;
+		Ok(taken)
+	}
+
+	fn u16(&mut self, reading: &'static str) -> Result<u16, PaletteError> {
+		self.take(2, reading).map(|bytes| u16::from_be_bytes([bytes[0], bytes[1]]))
+	}
+
+	fn u32(&mut self, reading: &'static str) -> Result<u32, PaletteError> {
+		self.take(4, reading).map(|bytes| u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+	}
+
+	fn f32(&mut self, reading: &'static str) -> Result<f32, PaletteError> {
+		self.u32(reading).map(f32::from_bits)
+	}
+
+	/// Names are stored as a count of UTF-16 code units, followed by that many big-endian code units ending with a null terminator.
+	fn name(&mut self) -> Result<String, PaletteError> {
+		let length = self.u16("a name length")? as usize;
+		let bytes = self.take(length * 2, "a name")?;
+		let code_units: Vec<u16> = bytes.chunks_exact(2).map(|pair| u16::from_be_bytes([pair[0], pair[1]])).collect();
+		let name = String::from_utf16(&code_units).map_err(|_| PaletteError::InvalidName)?;
+		Ok(name.trim_end_matches('\0').to_string())
+	}
+}
+
+pub fn parse(data: &[u8]) -> Result<Palette, PaletteError> {
+	let mut reader = Reader { data };
+	if reader.take(4, "the file signature").ok() != Some(SIGNATURE.as_slice()) {
+		return Err(PaletteError::InvalidAseSignature);
+	}
+	let major_version = reader.u16("the version")?;
+	let minor_version = reader.u16("the version")?;
+	if major_version != 1 {
+		return Err(PaletteError::UnsupportedAseVersion(major_version, minor_version));
+	}
+	let block_count = reader.u32("the block count")?;
+
+	let mut palette = Palette::default();
+	for _ in 0..block_count {
+		let block_type = reader.u16("a block type")?;
+		let block_length = reader.u32("a block length")? as usize;
+		let mut block = Reader {
+			data: reader.take(block_length, "a block")?,
+		};
+
+		match block_type {
+			// Groups aren't kept, but the first one names the palette
+			GROUP_START if palette.name.is_empty() => palette.name = block.name()?,
+			COLOR_ENTRY => palette.swatches.push(read_swatch(&mut block)?),
+			// Other blocks, including the end of groups, can be skipped since their length is known
+			_ => {}
+		}
+	}
+
+	Ok(palette)
+}
+
+fn read_swatch(block: &mut Reader) -> Result<Swatch, PaletteError> {
+	let name = block.name()?;
+	let model = block.take(4, "a color model")?;
+
+	let mut values = |count: usize| (0..count).map(|_| block.f32("color values")).collect::<Result<Vec<_>, _>>();
+	let [red, green, blue] = match model {
+		b"RGB " => {
+			let rgb = values(3)?;
+			[rgb[0], rgb[1], rgb[2]]
+		}
+		b"CMYK" => {
+			let cmyk = values(4)?;
+			[0, 1, 2].map(|channel| (1. - cmyk[channel]) * (1. - cmyk[3]))
+		}
+		b"LAB " => {
+			let lab = values(3)?;
+			lab_to_srgb(lab[0] * 100., lab[1], lab[2])
+		}
+		b"Gray" => [values(1)?[0]; 3],
+		_ => {
+			let model = String::from_utf8_lossy(model).trim().to_string();
+			return Err(PaletteError::UnknownColorModel { name, model });
+		}
+	};
+	// The swatch type (global, spot, or normal) which follows is ignored
+
+	Ok(Swatch {
+		name,
+		color: Color::from_rgbf32_unchecked(red.clamp(0., 1.), green.clamp(0., 1.), blue.clamp(0., 1.)),
+	})
+}
+
+/// Converts a CIELAB color, relative to the D50 white point used by Adobe applications, to gamma-encoded sRGB.
+fn lab_to_srgb(lightness: f32, a: f32, b: f32) -> [f32; 3] {
+	const EPSILON: f32 = 6. / 29.;
+	let inverse = |t: f32| if t > EPSILON { t.powi(3) } else { 3. * EPSILON * EPSILON * (t - 4. / 29.) };
+
+	let fy = (lightness + 16.) / 116.;
+	let [x, y, z] = [0.9642 * inverse(fy + a / 500.), inverse(fy), 0.8251 * inverse(fy - b / 200.)];
+
+	// XYZ to linear sRGB, including the Bradford adaptation from D50 to the D65 white point of sRGB
+	let linear = [
+		3.1338561 * x - 1.6168667 * y - 0.4906146 * z,
+		-0.9787684 * x + 1.9161415 * y + 0.033454 * z,
+		0.0719453 * x - 0.2289914 * y + 1.4052427 * z,
+	];
+
+	linear.map(|channel| {
+		let channel = channel.clamp(0., 1.);
+		if channel <= 0.0031308 { channel * 12.92 } else { 1.055 * channel.powf(1. / 2.4) - 0.055 }
+	})
+}
+
+pub fn write(palette: &Palette) -> Vec<u8> {
+	fn push_name(block: &mut Vec<u8>, name: &str) {
+		let code_units: Vec<u16> = name.encode_utf16().chain([0]).collect();
+		block.extend_from_slice(&(code_units.len() as u16).to_be_bytes());
+		code_units.iter().for_each(|unit| block.extend_from_slice(&unit.to_be_bytes()));
+	}
+	fn push_block(data: &mut Vec<u8>, block_type: u16, block: &[u8]) {
+		data.extend_from_slice(&block_type.to_be_bytes());
+		data.extend_from_slice(&(block.len() as u32).to_be_bytes());
+		data.extend_from_slice(block);
+	}
+
+	// The swatches are put in a group carrying the palette's name, as Illustrator does, so the name survives a round trip
+	let grouped = !palette.name.is_empty();
+	let block_count = palette.swatches.len() + if grouped { 2 } else { 0 };
+
+	let mut data = SIGNATURE.to_vec();
+	data.extend_from_slice(&1_u16.to_be_bytes());
+	data.extend_from_slice(&0_u16.to_be_bytes());
+	data.extend_from_slice(&(block_count as u32).to_be_bytes());
+
+	if grouped {
+		let mut block = Vec::new();
+		push_name(&mut block, &palette.name);
+		push_block(&mut data, GROUP_START, &block);
+	}
+	for swatch in &palette.swatches {
+		let mut block = Vec::new();
+		push_name(&mut block, &swatch.name);
+		block.extend_from_slice(b"RGB ");
+		for channel in [swatch.color.r(), swatch.color.g(), swatch.color.b()] {
+			block.extend_from_slice(&channel.to_be_bytes());
+		}
+		block.extend_from_slice(&NORMAL_COLOR.to_be_bytes());
+		push_block(&mut data, COLOR_ENTRY, &block);
+	}
+	if grouped {
+		push_block(&mut data, GROUP_END, &[]);
+	}
+
+	data
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const PHOTOSHOP_FIXTURE: &[u8] = include_bytes!("fixtures/photoshop.ase");
+	const ILLUSTRATOR_FIXTURE: &[u8] = include_bytes!("fixtures/illustrator.ase");
+
+	fn assert_color_eq(color: Color, [red, green, blue]: [f32; 3]) {
+		let tolerance = 1.5 / 255.;
+		assert!(
+			(color.r() - red).abs() < tolerance && (color.g() - green).abs() < tolerance && (color.b() - blue).abs() < tolerance,
+			"{color:?} should be close to {:?}",
+			[red, green, blue]
+		);
+	}
+
+	#[test]
+	fn reads_palette_saved_by_photoshop() {
+		let palette = parse(PHOTOSHOP_FIXTURE).unwrap();
+		assert_eq!(palette.name, "");
+
+		let names: Vec<_> = palette.swatches.iter().map(|swatch| swatch.name.as_str()).collect();
+		assert_eq!(names, ["RGB Red", "CMYK Cyan", "Lab Mid Gray", "Lab Orange", "Light Gray"]);
+
+		assert_color_eq(palette.swatches[0].color, [1., 0., 0.]);
+		assert_color_eq(palette.swatches[1].color, [0., 1., 1.]);
+		// Neutral colors in Lab stay neutral after adapting the white point
+		assert_color_eq(palette.swatches[2].color, [128. / 255., 128. / 255., 128. / 255.]);
+		assert_color_eq(palette.swatches[3].color, [1., 128. / 255., 0.]);
+		assert_color_eq(palette.swatches[4].color, [0.75, 0.75, 0.75]);
+	}
+
+	#[test]
+	fn reads_palette_with_groups_saved_by_illustrator() {
+		let palette = parse(ILLUSTRATOR_FIXTURE).unwrap();
+		assert_eq!(palette.name, "Brand Colors");
+
+		let names: Vec<_> = palette.swatches.iter().map(|swatch| swatch.name.as_str()).collect();
+		assert_eq!(names, ["Primary", "Secondary", "Accent"]);
+		assert_color_eq(palette.swatches[2].color, [0.2, 0.4, 0.6]);
+	}
+
+	#[test]
+	fn adobe_swatch_exchange_round_trips() {
+		for fixture in [PHOTOSHOP_FIXTURE, ILLUSTRATOR_FIXTURE] {
+			let palette = parse(fixture).unwrap();
+			assert_eq!(parse(&write(&palette)).unwrap(), palette);
+		}
+
+		// Writing a grouped palette of RGB swatches reproduces the file exactly
+		assert_eq!(write(&parse(ILLUSTRATOR_FIXTURE).unwrap()), ILLUSTRATOR_FIXTURE);
+	}
+
+	#[test]
+	fn malformed_adobe_swatch_exchange_files_are_reported() {
+		assert_eq!(parse(b"GIMP Palette\n"), Err(PaletteError::InvalidAseSignature));
+		assert_eq!(parse(b"AS"), Err(PaletteError::InvalidAseSignature));
+		assert_eq!(parse(b"ASEF\x00\x02\x00\x00\x00\x00\x00\x00"), Err(PaletteError::UnsupportedAseVersion(2, 0)));
+
+		let truncated = &PHOTOSHOP_FIXTURE[..PHOTOSHOP_FIXTURE.len() - 5];
+		assert_eq!(parse(truncated), Err(PaletteError::UnexpectedEnd("a block")));
+
+		let mut unknown_model = write(&Palette {
+			name: String::new(),
+			swatches: vec![Swatch {
+				name: "X".to_string(),
+				color: Color::BLACK,
+			}],
+		});
+		// The color model follows the 12-byte header, the 6-byte block header, and the name (a 2-byte length and 2 code units)
+		unknown_model[24..28].copy_from_slice(b"HSB ");
+		assert_eq!(
+			parse(&unknown_model),
+			Err(PaletteError::UnknownColorModel {
+				name: "X".to_string(),
+				model: "HSB".to_string()
+			})
+		);
+	}
+}
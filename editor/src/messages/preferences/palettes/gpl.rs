@@ -0,0 +1,118 @@
+use super::{Palette, PaletteError, Swatch};
+use graphene_std::raster::color::Color;
+
+const HEADER: &str = "GIMP Palette";
+
+pub fn parse(data: &[u8]) -> Result<Palette, PaletteError> {
+	let text = std::str::from_utf8(data).map_err(|_| PaletteError::InvalidGplHeader)?;
+	let mut lines = text.trim_start_matches('\u{feff}').lines();
+	if lines.next().map(str::trim) != Some(HEADER) {
+		return Err(PaletteError::InvalidGplHeader);
+	}
+
+	let mut palette = Palette::default();
+	// The header is line 1
+	for (line_number, line) in (2..).zip(lines) {
+		let line = line.trim();
+		if line.is_empty() || line.starts_with('#') || line.starts_with("Columns:") {
+			continue;
+		}
+		if let Some(name) = line.strip_prefix("Name:") {
+			palette.name = name.trim().to_string();
+			continue;
+		}
+
+		let invalid = |reason: String| PaletteError::InvalidGplColor { line: line_number, reason };
+
+		// Each color is three numbers from 0 to 255 separated by whitespace, followed by an optional name which may itself contain whitespace
+		let mut rest = line;
+		let mut channels = [0; 3];
+		for channel in &mut channels {
+			rest = rest.trim_start();
+			let end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+			let (token, remainder) = rest.split_at(end);
+			if token.is_empty() {
+				return Err(invalid("expected red, green, and blue values".to_string()));
+			}
+			*channel = token.parse::<u8>().map_err(|_| invalid(format!("\"{token}\" isn't a number from 0 to 255")))?;
+			rest = remainder;
+		}
+
+		let [red, green, blue] = channels.map(|channel| channel as f32 / 255.);
+		palette.swatches.push(Swatch {
+			name: rest.trim().to_string(),
+			color: Color::from_rgbf32_unchecked(red, green, blue),
+		});
+	}
+
+	Ok(palette)
+}
+
+pub fn write(palette: &Palette) -> Vec<u8> {
+	let mut text = format!("{HEADER}\nName: {}\nColumns: 0\n#\n", palette.name);
+
+	for swatch in &palette.swatches {
+		let [red, green, blue] = [swatch.color.r(), swatch.color.g(), swatch.color.b()].map(|channel| (channel.clamp(0., 1.) * 255.).round() as u8);
+		text.push_str(&format!("{red:>3} {green:>3} {blue:>3}\t{}\n", swatch.name));
+	}
+
+	text.into_bytes()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const GIMP_FIXTURE: &[u8] = include_bytes!("fixtures/gimp.gpl");
+
+	#[test]
+	fn reads_palette_saved_by_gimp() {
+		let palette = parse(GIMP_FIXTURE).unwrap();
+		assert_eq!(palette.name, "Graphite Test");
+		assert_eq!(palette.swatches.len(), 6);
+
+		assert_eq!(palette.swatches[0].name, "Crimson Red");
+		assert_eq!(palette.swatches[0].color, Color::from_rgbf32_unchecked(220. / 255., 20. / 255., 60. / 255.));
+		// Unnamed colors are written by GIMP as "Untitled"
+		assert_eq!(palette.swatches[3].name, "Untitled");
+		assert_eq!(palette.swatches[5].color, Color::from_rgbf32_unchecked(0., 0., 0.));
+	}
+
+	#[test]
+	fn gimp_palette_round_trips() {
+		let palette = parse(GIMP_FIXTURE).unwrap();
+		let written = write(&palette);
+		assert_eq!(parse(&written).unwrap(), palette);
+
+		// Only the comments and column count of the original file are lost
+		let without_comments = |text: &[u8]| {
+			std::str::from_utf8(text)
+				.unwrap()
+				.lines()
+				.filter(|line| !line.starts_with('#') && !line.starts_with("Columns:"))
+				.map(str::to_string)
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(without_comments(&written), without_comments(GIMP_FIXTURE));
+	}
+
+	#[test]
+	fn malformed_gimp_palettes_are_reported() {
+		assert_eq!(parse(b"JASC-PAL\n0100\n"), Err(PaletteError::InvalidGplHeader));
+		assert_eq!(parse(&[0xff, 0xfe, 0x00]), Err(PaletteError::InvalidGplHeader));
+		assert_eq!(
+			parse(b"GIMP Palette\nName: Broken\n255 0 0 Red\n256 0 0 Too Red\n"),
+			Err(PaletteError::InvalidGplColor {
+				line: 4,
+				reason: "\"256\" isn't a number from 0 to 255".to_string()
+			})
+		);
+		assert_eq!(
+			parse(b"GIMP Palette\n12 34\n"),
+			Err(PaletteError::InvalidGplColor {
+				line: 2,
+				reason: "expected red, green, and blue values".to_string()
+			})
+		);
+	}
+}
@@ -0,0 +1,125 @@
+//! Reading and writing color palettes in the Adobe Swatch Exchange (`.ase`) and GIMP palette (`.gpl`) file formats.
+
+mod ase;
+mod gpl;
+
+use graphene_std::raster::color::Color;
+use thiserror::Error;
+
+/// A named color in a palette. Its color is gamma-encoded sRGB, which is how palette files store them.
+#[derive(Clone, Debug, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct Swatch {
+	pub name: String,
+	pub color: Color,
+}
+
+#[derive(Clone, Debug, Default, PartialEq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub struct Palette {
+	pub name: String,
+	pub swatches: Vec<Swatch>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum PaletteFormat {
+	/// Adobe Swatch Exchange, as written by Photoshop, Illustrator, and InDesign.
+	Ase,
+	/// GIMP palette, which is also read by Inkscape and Krita.
+	Gpl,
+}
+
+impl PaletteFormat {
+	pub fn from_extension(extension: &str) -> Option<Self> {
+		match extension.to_ascii_lowercase().as_str() {
+			"ase" => Some(Self::Ase),
+			"gpl" => Some(Self::Gpl),
+			_ => None,
+		}
+	}
+
+	pub fn extension(&self) -> &'static str {
+		match self {
+			Self::Ase => "ase",
+			Self::Gpl => "gpl",
+		}
+	}
+}
+
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum PaletteError {
+	#[error("\"{0}\" isn't a supported palette file. Palettes can be imported from .ase and .gpl files.")]
+	UnsupportedFormat(String),
+
+	#[error("The file ended unexpectedly while reading {0}.")]
+	UnexpectedEnd(&'static str),
+
+	#[error("The file isn't an Adobe Swatch Exchange palette because it doesn't begin with \"ASEF\".")]
+	InvalidAseSignature,
+
+	#[error("Version {0}.{1} of the Adobe Swatch Exchange format isn't supported.")]
+	UnsupportedAseVersion(u16, u16),
+
+	#[error("The swatch \"{name}\" uses the unknown color model \"{model}\".")]
+	UnknownColorModel { name: String, model: String },
+
+	#[error("A swatch or group name isn't valid UTF-16 text.")]
+	InvalidName,
+
+	#[error("The file isn't a GIMP palette because it isn't text beginning with \"GIMP Palette\".")]
+	InvalidGplHeader,
+
+	#[error("Line {line} of the GIMP palette isn't a valid color: {reason}")]
+	InvalidGplColor { line: usize, reason: String },
+}
+
+impl Palette {
+	/// Reads a palette file, picking the format from its extension. Palettes without a name of their own are named after the file.
+	pub fn parse(file_name: &str, data: &[u8]) -> Result<Self, PaletteError> {
+		let (stem, extension) = file_name.rsplit_once('.').unwrap_or((file_name, ""));
+
+		let mut palette = match PaletteFormat::from_extension(extension) {
+			Some(PaletteFormat::Ase) => ase::parse(data)?,
+			Some(PaletteFormat::Gpl) => gpl::parse(data)?,
+			None => return Err(PaletteError::UnsupportedFormat(file_name.to_string())),
+		};
+		if palette.name.is_empty() {
+			palette.name = stem.to_string();
+		}
+
+		Ok(palette)
+	}
+
+	pub fn write(&self, format: PaletteFormat) -> Vec<u8> {
+		match format {
+			PaletteFormat::Ase => ase::write(self),
+			PaletteFormat::Gpl => gpl::write(self),
+		}
+	}
+
+	pub fn file_name(&self, format: PaletteFormat) -> String {
+		let name = if self.name.is_empty() { "Palette" } else { &self.name };
+		format!("{name}.{}", format.extension())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn format_is_chosen_from_the_file_extension() {
+		let gpl = "GIMP Palette\n255 0 0\tRed\n";
+		let palette = Palette::parse("Warm Colors.GPL", gpl.as_bytes()).unwrap();
+		assert_eq!(palette.name, "Warm Colors");
+		assert_eq!(palette.swatches.len(), 1);
+
+		assert_eq!(Palette::parse("colors.aco", &[]), Err(PaletteError::UnsupportedFormat("colors.aco".to_string())));
+		assert_eq!(Palette::parse("colors", gpl.as_bytes()), Err(PaletteError::UnsupportedFormat("colors".to_string())));
+	}
+
+	#[test]
+	fn palettes_convert_between_formats() {
+		let gpl = Palette::parse("gimp.gpl", include_bytes!("fixtures/gimp.gpl")).unwrap();
+		let ase = Palette::parse("converted.ase", &gpl.write(PaletteFormat::Ase)).unwrap();
+		assert_eq!(ase, gpl);
+	}
+}
@@ -1,5 +1,6 @@
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
-use crate::messages::preferences::SelectionMode;
+use crate::messages::preferences::palettes::PaletteFormat;
+use crate::messages::preferences::{InteractiveRenderQuality, OverlayQuality, SelectionMode};
 use crate::messages::prelude::*;
 
 #[impl_message(Message, Preferences)]
@@ -16,4 +17,10 @@ pub enum PreferencesMessage {
 	ModifyLayout { zoom_with_scroll: bool },
 	GraphWireStyle { style: GraphWireStyle },
 	ViewportZoomWheelRate { rate: f64 },
+	OverlayQuality { quality: OverlayQuality },
+	InteractiveRenderQuality { quality: InteractiveRenderQuality },
+	BrushCacheBudget { megabytes: Option<u32> },
+	NodeCacheBudget { megabytes: Option<u32> },
+	ImportPalette { file_name: String, data: Vec<u8> },
+	ExportPalette { format: PaletteFormat },
 }
@@ -1,7 +1,8 @@
 use crate::consts::VIEWPORT_ZOOM_WHEEL_RATE;
 use crate::messages::input_mapper::key_mapping::MappingVariant;
 use crate::messages::portfolio::document::utility_types::wires::GraphWireStyle;
-use crate::messages::preferences::SelectionMode;
+use crate::messages::preferences::palettes::Palette;
+use crate::messages::preferences::{InteractiveRenderQuality, OverlayQuality, SelectionMode};
 use crate::messages::prelude::*;
 use graph_craft::wasm_application_io::EditorPreferences;
 
@@ -13,6 +14,20 @@ pub struct PreferencesMessageHandler {
 	pub vector_meshes: bool,
 	pub graph_wire_style: GraphWireStyle,
 	pub viewport_zoom_wheel_rate: f64,
+	#[serde(default)]
+	pub overlay_quality: OverlayQuality,
+	/// The reduced resolution which raster content is rendered at while dragging.
+	#[serde(default)]
+	pub interactive_render_quality: InteractiveRenderQuality,
+	/// The swatches shown in the color picker, which can be imported from and exported to palette files.
+	#[serde(default)]
+	pub palette: Palette,
+	/// The memory budget for caching brush strokes in megabytes, or `None` to base it on the available memory.
+	#[serde(default)]
+	pub brush_cache_budget: Option<u32>,
+	/// The memory budget for reusing node outputs across graph evaluations in megabytes, or `None` for the default budget.
+	#[serde(default)]
+	pub node_cache_budget: Option<u32>,
 }
 
 impl PreferencesMessageHandler {
@@ -23,6 +38,8 @@ impl PreferencesMessageHandler {
 	pub fn editor_preferences(&self) -> EditorPreferences {
 		EditorPreferences {
 			use_vello: self.use_vello && self.supports_wgpu(),
+			brush_cache_budget: self.brush_cache_budget.map(|megabytes| megabytes as u64 * 1024 * 1024),
+			node_cache_budget: self.node_cache_budget.map(|megabytes| megabytes as u64 * 1024 * 1024),
 		}
 	}
 
@@ -40,6 +57,11 @@ impl Default for PreferencesMessageHandler {
 			vector_meshes: false,
 			graph_wire_style: GraphWireStyle::default(),
 			viewport_zoom_wheel_rate: VIEWPORT_ZOOM_WHEEL_RATE,
+			overlay_quality: OverlayQuality::default(),
+			interactive_render_quality: InteractiveRenderQuality::default(),
+			palette: Palette::default(),
+			brush_cache_budget: None,
+			node_cache_budget: None,
 		}
 	}
 }
@@ -58,13 +80,16 @@ impl MessageHandler<PreferencesMessage, ()> for PreferencesMessageHandler {
 					responses.add(PreferencesMessage::ModifyLayout {
 						zoom_with_scroll: self.zoom_with_scroll,
 					});
+					responses.add(FrontendMessage::UpdatePalette { palette: self.palette.clone() });
 				}
 			}
 			PreferencesMessage::ResetToDefaults => {
 				refresh_dialog(responses);
 				responses.add(KeyMappingMessage::ModifyMapping(MappingVariant::Default));
 
-				*self = Self::default()
+				*self = Self::default();
+				responses.add(PortfolioMessage::EditorPreferences);
+				responses.add(FrontendMessage::UpdatePalette { palette: self.palette.clone() });
 			}
 
 			// Per-preference messages
@@ -93,6 +118,41 @@ impl MessageHandler<PreferencesMessage, ()> for PreferencesMessageHandler {
 			PreferencesMessage::ViewportZoomWheelRate { rate } => {
 				self.viewport_zoom_wheel_rate = rate;
 			}
+			PreferencesMessage::OverlayQuality { quality } => {
+				self.overlay_quality = quality;
+				responses.add(OverlaysMessage::Draw);
+			}
+			PreferencesMessage::InteractiveRenderQuality { quality } => {
+				self.interactive_render_quality = quality;
+			}
+			PreferencesMessage::BrushCacheBudget { megabytes } => {
+				self.brush_cache_budget = megabytes;
+				responses.add(PortfolioMessage::EditorPreferences);
+			}
+			PreferencesMessage::NodeCacheBudget { megabytes } => {
+				self.node_cache_budget = megabytes;
+				responses.add(PortfolioMessage::EditorPreferences);
+			}
+			PreferencesMessage::ImportPalette { file_name, data } => match Palette::parse(&file_name, &data) {
+				Ok(palette) => {
+					self.palette = palette;
+					responses.add(FrontendMessage::UpdatePalette { palette: self.palette.clone() });
+				}
+				Err(error) => {
+					responses.add(DialogMessage::DisplayDialogError {
+						title: "Failed to import palette".to_string(),
+						description: error.to_string(),
+					});
+					return;
+				}
+			},
+			PreferencesMessage::ExportPalette { format } => {
+				responses.add(FrontendMessage::TriggerDownloadBinaryFile {
+					data: self.palette.write(format),
+					name: self.palette.file_name(format),
+				});
+				return;
+			}
 		}
 
 		responses.add(FrontendMessage::TriggerSavePreferences { preferences: self.clone() });
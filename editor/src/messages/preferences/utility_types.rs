@@ -1,3 +1,5 @@
+use crate::consts::INTERACTIVE_RENDER_TIME_BUDGET_MILLISECONDS;
+
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type, Hash)]
 pub enum SelectionMode {
 	#[default]
@@ -25,3 +27,98 @@ impl SelectionMode {
 		}
 	}
 }
+
+/// The rasterization quality of the overlays drawn by the native (Vello) renderer, which trades sharpness for rendering cost each frame.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type, Hash)]
+#[serde(default)]
+pub struct OverlayQuality {
+	pub anti_aliasing: bool,
+	pub supersampling: OverlaySupersampling,
+}
+
+impl Default for OverlayQuality {
+	fn default() -> Self {
+		Self {
+			anti_aliasing: true,
+			supersampling: OverlaySupersampling::X1,
+		}
+	}
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type, Hash)]
+pub enum OverlaySupersampling {
+	#[default]
+	X1 = 1,
+	X2 = 2,
+}
+
+impl std::fmt::Display for OverlaySupersampling {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "{}x", self.factor())
+	}
+}
+
+impl OverlaySupersampling {
+	/// How many times the viewport's physical resolution the overlays are rendered at along each axis.
+	pub fn factor(&self) -> u32 {
+		*self as u32
+	}
+
+	pub fn tooltip_description(&self) -> &'static str {
+		match self {
+			OverlaySupersampling::X1 => "Render overlays at the display's resolution",
+			OverlaySupersampling::X2 => "Render overlays at twice the display's resolution and downsample them for smoother strokes, at four times the cost",
+		}
+	}
+}
+
+/// The resolution which raster content is evaluated at while dragging a slider, gizmo, or tool, to keep heavy documents responsive.
+/// A full quality render follows once the drag is released.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize, specta::Type, Hash)]
+pub enum InteractiveRenderQuality {
+	Off,
+	Half,
+	Quarter,
+	#[default]
+	Auto,
+}
+
+impl std::fmt::Display for InteractiveRenderQuality {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			InteractiveRenderQuality::Off => write!(f, "Off"),
+			InteractiveRenderQuality::Half => write!(f, "1/2"),
+			InteractiveRenderQuality::Quarter => write!(f, "1/4"),
+			InteractiveRenderQuality::Auto => write!(f, "Auto"),
+		}
+	}
+}
+
+impl InteractiveRenderQuality {
+	pub fn tooltip_description(&self) -> &'static str {
+		match self {
+			InteractiveRenderQuality::Off => "Always render at full resolution, even while dragging",
+			InteractiveRenderQuality::Half => "Render raster content at half resolution while dragging",
+			InteractiveRenderQuality::Quarter => "Render raster content at a quarter resolution while dragging",
+			InteractiveRenderQuality::Auto => "Reduce the resolution of raster content while dragging only as much as needed to keep up, based on how long the last render took",
+		}
+	}
+
+	/// The fraction of the full resolution to render at while dragging, given how long the last render took in milliseconds and the scale it was rendered at.
+	pub fn scale_factor(&self, last_render: Option<(f64, f64)>) -> f64 {
+		match self {
+			InteractiveRenderQuality::Off => 1.,
+			InteractiveRenderQuality::Half => 0.5,
+			InteractiveRenderQuality::Quarter => 0.25,
+			InteractiveRenderQuality::Auto => {
+				let Some((duration, scale)) = last_render else { return 1. };
+
+				// The cost of rendering raster content grows with its number of pixels, so estimate the duration at each scale and pick the highest one within budget
+				[1., 0.5]
+					.into_iter()
+					.find(|&candidate: &f64| duration * (candidate / scale).powi(2) <= INTERACTIVE_RENDER_TIME_BUDGET_MILLISECONDS)
+					.unwrap_or(0.25)
+			}
+		}
+	}
+}
@@ -0,0 +1,228 @@
+use graphene_std::brush::brush_stroke::BrushInputSample;
+
+/// Smooths freehand pointer input with a pull-string filter.
+///
+/// The brush tip is tethered to the cursor by a leash of a fixed length, and only moves once the cursor pulls the leash taut.
+/// Jitter smaller than the leash is absorbed, while deliberate motion is followed with a lag of one leash length.
+/// Raw samples are kept for the duration of the stroke so the filter can be re-run if the leash length changes.
+#[derive(Clone, Debug, Default)]
+pub struct BrushStabilizer {
+	raw_samples: Vec<BrushInputSample>,
+	smoothed_samples: Vec<BrushInputSample>,
+	leash_length: f64,
+}
+
+impl BrushStabilizer {
+	pub fn new(leash_length: f64) -> Self {
+		Self {
+			leash_length: leash_length.max(0.),
+			..Default::default()
+		}
+	}
+
+	/// Feeds a new pointer sample through the filter, returning the current position of the brush tip.
+	pub fn push(&mut self, sample: BrushInputSample) -> &BrushInputSample {
+		if let Some(tip) = self.smoothed_samples.last() {
+			if let Some(next_tip) = pull_tip(tip, &sample, self.leash_length) {
+				self.smoothed_samples.push(next_tip);
+			}
+		} else {
+			self.smoothed_samples.push(sample.clone());
+		}
+		self.raw_samples.push(sample);
+
+		self.smoothed_samples.last().expect("A sample was just pushed")
+	}
+
+	/// Changes the leash length, re-running the filter over all the raw samples of the stroke so far.
+	pub fn set_leash_length(&mut self, leash_length: f64) {
+		self.leash_length = leash_length.max(0.);
+		self.smoothed_samples = stabilize(&self.raw_samples, self.leash_length);
+	}
+
+	pub fn leash_length(&self) -> f64 {
+		self.leash_length
+	}
+
+	pub fn raw_samples(&self) -> &[BrushInputSample] {
+		&self.raw_samples
+	}
+
+	pub fn smoothed_samples(&self) -> &[BrushInputSample] {
+		&self.smoothed_samples
+	}
+
+	/// The current position of the brush tip, which trails the most recent raw sample by up to one leash length.
+	pub fn tip(&self) -> Option<&BrushInputSample> {
+		self.smoothed_samples.last()
+	}
+
+	/// The trace to commit once the stroke ends: the smoothed polyline resampled at even spacing.
+	/// With no stabilization, the raw samples are returned unchanged.
+	pub fn finish(&self, spacing: f64) -> Vec<BrushInputSample> {
+		if self.leash_length <= 0. {
+			return self.raw_samples.clone();
+		}
+
+		resample(&self.smoothed_samples, spacing)
+	}
+
+	pub fn clear(&mut self) {
+		self.raw_samples.clear();
+		self.smoothed_samples.clear();
+	}
+}
+
+/// Runs the pull-string filter over a sequence of raw samples, returning the positions visited by the brush tip.
+/// A leash length of zero returns the raw samples unchanged.
+pub fn stabilize(samples: &[BrushInputSample], leash_length: f64) -> Vec<BrushInputSample> {
+	if leash_length <= 0. {
+		return samples.to_vec();
+	}
+
+	let Some(first) = samples.first() else { return Vec::new() };
+
+	let mut result = vec![first.clone()];
+	for sample in &samples[1..] {
+		let tip = result.last().expect("The result always has at least the first sample");
+		if let Some(next_tip) = pull_tip(tip, sample, leash_length) {
+			result.push(next_tip);
+		}
+	}
+
+	result
+}
+
+/// Moves the brush tip towards the cursor until it is at most one leash length away, or returns `None` if the leash is slack.
+/// Pressure follows the same fraction of the way towards the cursor's pressure as the position does.
+fn pull_tip(tip: &BrushInputSample, cursor: &BrushInputSample, leash_length: f64) -> Option<BrushInputSample> {
+	if leash_length <= 0. {
+		return Some(cursor.clone());
+	}
+
+	let delta = cursor.position - tip.position;
+	let distance = delta.length();
+	if distance <= leash_length {
+		return None;
+	}
+
+	let pulled_fraction = (distance - leash_length) / distance;
+	Some(BrushInputSample {
+		position: tip.position + delta * pulled_fraction,
+		pressure: tip.pressure + (cursor.pressure - tip.pressure) * pulled_fraction,
+	})
+}
+
+/// Resamples a polyline so consecutive samples are `spacing` apart along its length, interpolating pressure between the original samples.
+/// The first and last samples are always kept.
+pub fn resample(samples: &[BrushInputSample], spacing: f64) -> Vec<BrushInputSample> {
+	let (Some(first), Some(last)) = (samples.first(), samples.last()) else { return Vec::new() };
+	if samples.len() < 2 || spacing <= 0. {
+		return samples.to_vec();
+	}
+
+	let mut result = vec![first.clone()];
+	let mut distance_until_next = spacing;
+	for window in samples.windows(2) {
+		let [start, end] = window else { continue };
+
+		let segment_length = start.position.distance(end.position);
+		let direction = (end.position - start.position) / segment_length;
+		let mut travelled = 0.;
+		while segment_length - travelled >= distance_until_next {
+			travelled += distance_until_next;
+			let t = travelled / segment_length;
+			result.push(BrushInputSample {
+				position: start.position + direction * travelled,
+				pressure: start.pressure + (end.pressure - start.pressure) * t,
+			});
+			distance_until_next = spacing;
+		}
+		distance_until_next -= segment_length - travelled;
+	}
+
+	if result.last().is_some_and(|sample| sample.position != last.position) {
+		result.push(last.clone());
+	}
+
+	result
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use glam::DVec2;
+
+	fn sample(x: f64, y: f64, pressure: f64) -> BrushInputSample {
+		BrushInputSample { position: DVec2::new(x, y), pressure }
+	}
+
+	/// A horizontal line drawn with a shaky hand, alternating above and below the line.
+	fn jittery_line() -> Vec<BrushInputSample> {
+		(0..=100).map(|i| sample(i as f64, if i % 2 == 0 { 3. } else { -3. }, if i % 2 == 0 { 0.8 } else { 0.4 })).collect()
+	}
+
+	#[test]
+	fn zero_strength_is_a_no_op() {
+		let samples = jittery_line();
+
+		assert_eq!(stabilize(&samples, 0.), samples);
+
+		let mut stabilizer = BrushStabilizer::new(0.);
+		for sample in &samples {
+			assert_eq!(stabilizer.push(sample.clone()), sample);
+		}
+		assert_eq!(stabilizer.smoothed_samples(), samples.as_slice());
+		assert_eq!(stabilizer.finish(5.), samples);
+	}
+
+	#[test]
+	fn jitter_is_smoothed() {
+		let samples = jittery_line();
+		let smoothed = stabilize(&samples, 10.);
+
+		// The tip never strays further from the line than the first sample's jitter, and settles towards the middle
+		assert!(smoothed.iter().all(|sample| sample.position.y.abs() <= 3.));
+		let tail = &smoothed[smoothed.len() / 2..];
+		assert!(tail.iter().all(|sample| sample.position.y.abs() < 1.), "{tail:?}");
+
+		// Pressure is smoothed alongside position, staying between the alternating raw values
+		assert!(tail.iter().all(|sample| (0.5..=0.7).contains(&sample.pressure)), "{tail:?}");
+
+		// The tip trails the cursor by no more than the leash length
+		let tip = smoothed.last().unwrap();
+		let cursor = samples.last().unwrap();
+		assert!(tip.position.distance(cursor.position) <= 10. + 1e-9);
+		assert!(tip.position.x > 85.);
+	}
+
+	#[test]
+	fn changing_strength_refilters_raw_samples() {
+		let samples = jittery_line();
+
+		let mut stabilizer = BrushStabilizer::new(10.);
+		for sample in &samples {
+			stabilizer.push(sample.clone());
+		}
+		assert_eq!(stabilizer.smoothed_samples(), stabilize(&samples, 10.).as_slice());
+
+		stabilizer.set_leash_length(0.);
+		assert_eq!(stabilizer.raw_samples(), samples.as_slice());
+		assert_eq!(stabilizer.smoothed_samples(), samples.as_slice());
+	}
+
+	#[test]
+	fn finished_stroke_is_evenly_spaced() {
+		let samples = vec![sample(0., 0., 0.), sample(3., 0., 0.3), sample(3., 7., 1.)];
+		let resampled = resample(&samples, 2.);
+
+		let positions: Vec<_> = resampled.iter().map(|sample| sample.position).collect();
+		assert_eq!(
+			positions,
+			vec![DVec2::new(0., 0.), DVec2::new(2., 0.), DVec2::new(3., 1.), DVec2::new(3., 3.), DVec2::new(3., 5.), DVec2::new(3., 7.)]
+		);
+		assert!((resampled[1].pressure - 0.2).abs() < 1e-9);
+		assert!((resampled[2].pressure - 0.4).abs() < 1e-9);
+		assert_eq!(resampled.last().unwrap().pressure, 1.);
+	}
+}
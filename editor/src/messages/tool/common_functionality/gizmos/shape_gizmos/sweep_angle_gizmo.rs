@@ -1,5 +1,6 @@
 use crate::consts::{ARC_SNAP_THRESHOLD, COLOR_OVERLAY_RED, GIZMO_HIDE_THRESHOLD};
 use crate::messages::message::Message;
+use crate::messages::portfolio::document::overlays::overlay_hit_test::{OverlayHitId, OverlayHitShape};
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::InputConnector;
@@ -13,6 +14,9 @@ use graph_craft::document::{NodeId, NodeInput};
 use std::collections::VecDeque;
 use std::f64::consts::FRAC_PI_4;
 
+/// How close to an endpoint handle, in viewport pixels, the mouse must be to grab it.
+const ENDPOINT_HIT_RADIUS: f64 = 5.;
+
 #[derive(Clone, Debug, Default, PartialEq)]
 pub enum SweepAngleGizmoState {
 	#[default]
@@ -22,7 +26,7 @@ pub enum SweepAngleGizmoState {
 	Snapped,
 }
 
-#[derive(Clone, Debug, Default, PartialEq)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
 pub enum EndpointType {
 	#[default]
 	None,
@@ -69,15 +73,12 @@ impl SweepAngleGizmo {
 				return;
 			}
 
-			let (close_to_gizmo, endpoint_type) = if mouse_position.distance(start) < 5. {
-				(true, EndpointType::Start)
-			} else if mouse_position.distance(end) < 5. {
-				(true, EndpointType::End)
-			} else {
-				(false, EndpointType::None)
-			};
+			let hovered_endpoint = document.overlays_message_handler.hit_test(mouse_position).into_iter().find_map(|id| match id {
+				OverlayHitId::SweepAngleEndpoint { layer: hit_layer, endpoint } if hit_layer == layer => Some(endpoint),
+				_ => None,
+			});
 
-			if close_to_gizmo {
+			if let Some(endpoint_type) = hovered_endpoint {
 				self.layer = Some(layer);
 				self.initial_start_angle = start_angle;
 				self.initial_sweep_angle = sweep_angle;
@@ -104,10 +105,18 @@ impl SweepAngleGizmo {
 
 		match self.handle_state {
 			SweepAngleGizmoState::Inactive => {
-				// Draw both endpoint handles if an arc is selected
+				// Draw both endpoint handles if an arc is selected, and make them clickable
+				let Some(layer) = selected_arc_layer else { return };
 				let Some((point1, point2)) = arc_end_points(selected_arc_layer, document) else { return };
-				overlay_context.manipulator_handle(point1, false, Some(COLOR_OVERLAY_RED));
-				overlay_context.manipulator_handle(point2, false, Some(COLOR_OVERLAY_RED));
+				for (point, endpoint) in [(point1, EndpointType::Start), (point2, EndpointType::End)] {
+					overlay_context.manipulator_handle(point, false, Some(COLOR_OVERLAY_RED));
+
+					let shape = OverlayHitShape::Circle {
+						center: point,
+						radius: ENDPOINT_HIT_RADIUS,
+					};
+					overlay_context.register_hit_region(OverlayHitId::SweepAngleEndpoint { layer, endpoint }, shape);
+				}
 			}
 			SweepAngleGizmoState::Hover => {
 				// Highlight the currently hovered endpoint only
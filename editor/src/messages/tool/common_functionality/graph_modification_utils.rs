@@ -282,8 +282,10 @@ pub fn get_fill_color(layer: LayerNodeIdentifier, network_interface: &NodeNetwor
 	let fill_index = 1;
 
 	let inputs = NodeGraphLayer::new(layer, network_interface).find_node_inputs("Fill")?;
-	let TaggedValue::Fill(graphene_std::vector::style::Fill::Solid(color)) = inputs.get(fill_index)?.as_value()? else {
-		return None;
+	let color = match inputs.get(fill_index)?.as_value()? {
+		TaggedValue::Fill(graphene_std::vector::style::Fill::Solid(color)) => *color,
+		TaggedValue::SwatchColor(swatch_color) => network_interface.swatches().resolve(*swatch_color).color,
+		_ => return None,
 	};
 	Some(color.to_linear_srgb())
 }
@@ -375,19 +377,35 @@ pub fn get_text(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInter
 	let Some(&TaggedValue::F64(tilt)) = inputs[8].as_value() else { return None };
 	let Some(&TaggedValue::TextAlign(align)) = inputs[9].as_value() else { return None };
 	let Some(&TaggedValue::Bool(per_glyph_instances)) = inputs[10].as_value() else { return None };
+	let Some(&TaggedValue::F64(word_spacing)) = inputs[15].as_value() else { return None };
+	let Some(&TaggedValue::OptionalF64(absolute_line_height)) = inputs[16].as_value() else {
+		return None;
+	};
+	let Some(&TaggedValue::Bool(justify_letters)) = inputs[17].as_value() else { return None };
 
 	let typesetting = TypesettingConfig {
 		font_size,
 		line_height_ratio,
+		absolute_line_height,
 		max_width,
 		character_spacing,
+		word_spacing,
 		max_height,
 		tilt,
 		align,
+		justify_letters,
 	};
 	Some((text, font, typesetting, per_glyph_instances))
 }
 
+/// Checks if the layer's Text node flows its text along a path connected from another node.
+pub fn is_text_on_path(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> bool {
+	let Some(inputs) = NodeGraphLayer::new(layer, network_interface).find_node_inputs("Text") else {
+		return false;
+	};
+	inputs.get(graphene_std::text::text::PathInput::INDEX).is_some_and(|input| input.as_node().is_some())
+}
+
 pub fn get_stroke_width(layer: LayerNodeIdentifier, network_interface: &NodeNetworkInterface) -> Option<f64> {
 	let weight_node_input_index = graphene_std::vector::stroke::WeightInput::INDEX;
 	if let TaggedValue::F64(width) = NodeGraphLayer::new(layer, network_interface).find_input("Stroke", weight_node_input_index)? {
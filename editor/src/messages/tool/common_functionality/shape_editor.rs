@@ -1,7 +1,8 @@
 use super::graph_modification_utils::merge_layers;
 use super::snapping::{SnapCache, SnapCandidatePoint, SnapData, SnapManager, SnappedPoint};
 use super::utility_functions::{adjust_handle_colinearity, calculate_bezier_bbox, calculate_segment_angle, restore_g1_continuity, restore_previous_handle_position};
-use crate::consts::HANDLE_LENGTH_FACTOR;
+use crate::consts::{HANDLE_LENGTH_FACTOR, SELECTION_THRESHOLD};
+use crate::messages::portfolio::document::overlays::overlay_hit_test::OverlayHitId;
 use crate::messages::portfolio::document::overlays::utility_functions::selected_segments;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::portfolio::document::utility_types::misc::{PathSnapSource, SnapSource};
@@ -14,7 +15,7 @@ use crate::messages::tool::tool_messages::path_tool::{PathOverlayMode, PointSele
 use bezier_rs::{Bezier, BezierHandles, Subpath, TValue};
 use glam::{DAffine2, DVec2};
 use graphene_std::vector::{HandleExt, HandleId, SegmentId};
-use graphene_std::vector::{ManipulatorPointId, PointId, VectorData, VectorModificationType};
+use graphene_std::vector::{ManipulatorPointId, PointId, VectorData, VectorModificationBuilder, VectorModificationType};
 use std::f64::consts::TAU;
 
 #[derive(Debug, Copy, Clone, PartialEq, Eq)]
@@ -542,22 +543,20 @@ impl ShapeState {
 		document.metadata().document_to_viewport.transform_vector2(offset)
 	}
 
-	/// Select/deselect the first point within the selection threshold.
+	/// Select/deselect the frontmost visible point under the mouse.
 	/// Returns a tuple of the points if found and the offset, or `None` otherwise.
 	pub fn change_point_selection(
 		&mut self,
 		network_interface: &NodeNetworkInterface,
+		overlays: &OverlaysMessageHandler,
 		mouse_position: DVec2,
-		select_threshold: f64,
 		extend_selection: bool,
-		path_overlay_mode: PathOverlayMode,
-		frontier_handles_info: Option<HashMap<SegmentId, Vec<PointId>>>,
 	) -> Option<Option<SelectedPointsInfo>> {
 		if self.selected_shape_state.is_empty() {
 			return None;
 		}
 
-		if let Some((layer, manipulator_point_id)) = self.find_nearest_visible_point_indices(network_interface, mouse_position, select_threshold, path_overlay_mode, frontier_handles_info) {
+		if let Some((layer, manipulator_point_id)) = self.find_visible_point_indices(network_interface, overlays, mouse_position) {
 			let vector_data = network_interface.compute_modified_vector(layer)?;
 			let point_position = manipulator_point_id.get_position(&vector_data)?;
 
@@ -1483,6 +1482,28 @@ impl ShapeState {
 		}
 	}
 
+	/// Split each selected segment in half with a new anchor, which replaces the segments in the selection.
+	pub fn insert_points_at_selected_segment_midpoints(&mut self, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		for (&layer, state) in &mut self.selected_shape_state {
+			let Some(vector_data) = document.network_interface.compute_modified_vector(layer) else { continue };
+
+			let mut builder = VectorModificationBuilder::new(&vector_data);
+			let midpoints: Vec<_> = state
+				.selected_segments
+				.iter()
+				.filter_map(|&segment| builder.insert_point(TValue::Euclidean(0.5), segment).ok())
+				.collect();
+			for modification_type in builder.finish() {
+				responses.add(GraphOperationMessage::Vector { layer, modification_type });
+			}
+
+			state.clear_segments();
+			for midpoint in midpoints {
+				state.select_point(ManipulatorPointId::Anchor(midpoint));
+			}
+		}
+	}
+
 	pub fn break_path_at_selected_point(&self, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
 		for (&layer, state) in &self.selected_shape_state {
 			let Some(vector_data) = document.network_interface.compute_modified_vector(layer) else { continue };
@@ -1594,40 +1615,29 @@ impl ShapeState {
 		None
 	}
 
-	pub fn find_nearest_visible_point_indices(
-		&mut self,
-		network_interface: &NodeNetworkInterface,
-		mouse_position: DVec2,
-		select_threshold: f64,
-		path_overlay_mode: PathOverlayMode,
-		frontier_handles_info: Option<HashMap<SegmentId, Vec<PointId>>>,
-	) -> Option<(LayerNodeIdentifier, ManipulatorPointId)> {
-		if self.selected_shape_state.is_empty() {
-			return None;
+	/// Find the nearest [ManipulatorPoint] of a selected layer which was drawn under the mouse in the most recent overlays frame.
+	/// The nearest rather than the frontmost is picked, so a handle next to an anchor can be clicked within the anchor's selection threshold.
+	/// Since only the points visible in the current [PathOverlayMode] are drawn, hidden points are never found.
+	///
+	/// Layers whose points have no hit regions, because the overlays are turned off or haven't been drawn since the layer was selected, are searched by distance instead.
+	pub fn find_visible_point_indices(&self, network_interface: &NodeNetworkInterface, overlays: &OverlaysMessageHandler, mouse_position: DVec2) -> Option<(LayerNodeIdentifier, ManipulatorPointId)> {
+		let hit = overlays.hit_test_nearest(mouse_position).into_iter().find_map(|id| match id {
+			OverlayHitId::ManipulatorPoint { layer, point } if self.selected_shape_state.contains_key(&layer) => Some((layer, point)),
+			_ => None,
+		});
+		if hit.is_some() {
+			return hit;
 		}
 
-		let select_threshold_squared = select_threshold.powi(2);
-
-		// Find the closest control point among all elements of shapes_to_modify
-		for &layer in self.selected_shape_state.keys() {
-			if let Some((manipulator_point_id, distance_squared)) = Self::closest_point_in_layer(network_interface, layer, mouse_position) {
-				// Choose the first point under the threshold
-				if distance_squared < select_threshold_squared {
-					// Check if point is visible in current PathOverlayMode
-					let vector_data = network_interface.compute_modified_vector(layer)?;
-					let selected_segments = selected_segments(network_interface, self);
-					let selected_points = self.selected_points().cloned().collect::<HashSet<_>>();
-
-					if !is_visible_point(manipulator_point_id, &vector_data, path_overlay_mode, frontier_handles_info, selected_segments, &selected_points) {
-						return None;
-					}
-
-					return Some((layer, manipulator_point_id));
-				}
+		self.selected_shape_state.keys().find_map(|&layer| {
+			let drawn = overlays.has_hit_region(|id| matches!(id, OverlayHitId::ManipulatorPoint { layer: drawn_layer, .. } if drawn_layer == layer));
+			if drawn {
+				return None;
 			}
-		}
 
-		None
+			let (point, distance_squared) = Self::closest_point_in_layer(network_interface, layer, mouse_position)?;
+			(distance_squared < SELECTION_THRESHOLD * SELECTION_THRESHOLD).then_some((layer, point))
+		})
 	}
 
 	// TODO Use quadtree or some equivalent spatial acceleration structure to improve this to O(log(n))
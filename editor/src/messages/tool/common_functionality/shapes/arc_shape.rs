@@ -1,5 +1,6 @@
 use super::shape_utility::ShapeToolModifierKey;
 use super::*;
+use crate::messages::portfolio::document::graph_operation::transform_utils;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
@@ -130,17 +131,35 @@ impl Arc {
 				radius = dimensions.x / 2.;
 			}
 
-			responses.add(NodeGraphMessage::SetInput {
-				input_connector: InputConnector::node(node_id, 1),
-				input: NodeInput::value(TaggedValue::F64(radius), false),
-			});
+			let radius = (InputConnector::node(node_id, 1), NodeInput::value(TaggedValue::F64(radius), false));
+			let transform = DAffine2::from_scale_angle_translation(scale, 0., start.midpoint(end));
+
+			// Set the radius and transform together so the arc is evaluated once per update, with the drag's transaction recording the history step
+			let Some(transform_node_id) = graph_modification_utils::NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Transform") else {
+				// The Transform node doesn't exist until the first transform is set, which creates it
+				responses.add(NodeGraphMessage::SetInputs {
+					changes: vec![radius],
+					skip_adding_history_step: true,
+					transaction_name: None,
+				});
+				responses.add(GraphOperationMessage::TransformSet {
+					layer,
+					transform,
+					transform_in: TransformIn::Viewport,
+					skip_rerender: false,
+				});
+				return;
+			};
 
-			responses.add(GraphOperationMessage::TransformSet {
-				layer,
-				transform: DAffine2::from_scale_angle_translation(scale, 0., start.midpoint(end)),
-				transform_in: TransformIn::Viewport,
-				skip_rerender: false,
+			let transform = document.metadata().downstream_transform_to_viewport(layer).inverse() * transform;
+			let mut changes = vec![radius];
+			changes.extend(transform_utils::transform_input_changes(transform_node_id, transform));
+			responses.add(NodeGraphMessage::SetInputs {
+				changes,
+				skip_adding_history_step: true,
+				transaction_name: None,
 			});
+			responses.add(PropertiesPanelMessage::Refresh);
 		}
 	}
 }
@@ -89,13 +89,17 @@ mod test_ellipse {
 
 	#[tokio::test]
 	async fn ellipse_draw_simple() {
-		let mut editor = EditorTestUtils::create();
-		editor.new_document().await;
-		editor.drag_tool(ToolType::Ellipse, 10., 10., 19., 0., ModifierKeys::empty()).await;
-
-		assert_eq!(editor.active_document().metadata().all_layers().count(), 1);
+		let mut state = Scenario::new()
+			.blank_document()
+			.select_tool(ToolType::Ellipse)
+			.drag((10., 10.), (19., 0.))
+			.expect_layer_count(1)
+			.expect_layer_fed_by("Ellipse")
+			.expect_layers_panel()
+			.run()
+			.await;
 
-		let ellipse = get_ellipse(&mut editor).await;
+		let ellipse = get_ellipse(&mut state.editor).await;
 		assert_eq!(ellipse.len(), 1);
 		assert_eq!(
 			ellipse[0],
@@ -109,11 +113,15 @@ mod test_ellipse {
 
 	#[tokio::test]
 	async fn ellipse_draw_circle() {
-		let mut editor = EditorTestUtils::create();
-		editor.new_document().await;
-		editor.drag_tool(ToolType::Ellipse, 10., 10., -10., 11., ModifierKeys::SHIFT).await;
+		let mut state = Scenario::new()
+			.blank_document()
+			.select_tool(ToolType::Ellipse)
+			.modifiers(ModifierKeys::SHIFT)
+			.drag((10., 10.), (-10., 11.))
+			.run()
+			.await;
 
-		let ellipse = get_ellipse(&mut editor).await;
+		let ellipse = get_ellipse(&mut state.editor).await;
 		assert_eq!(ellipse.len(), 1);
 		assert_eq!(
 			ellipse[0],
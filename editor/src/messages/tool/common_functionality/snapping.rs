@@ -2,9 +2,10 @@ mod alignment_snapper;
 mod distribution_snapper;
 mod grid_snapper;
 mod layer_snapper;
+mod snap_overlays;
 mod snap_results;
 
-use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE};
+use crate::consts::{COLOR_OVERLAY_BLACK_75, COLOR_OVERLAY_WHITE, SNAP_INDICATOR_GLYPH_SIZE};
 use crate::messages::portfolio::document::overlays::utility_types::{OverlayContext, Pivot};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::misc::{GridSnapTarget, PathSnapTarget, SnapTarget};
@@ -19,6 +20,7 @@ use graphene_std::vector::NoHashBuilder;
 use graphene_std::vector::PointId;
 pub use grid_snapper::*;
 pub use layer_snapper::*;
+pub use snap_overlays::*;
 pub use snap_results::*;
 use std::cmp::Ordering;
 
@@ -35,6 +37,8 @@ pub struct SnapTypeConfiguration {
 #[derive(Debug, Clone, Default)]
 pub struct SnapManager {
 	indicator: Option<SnappedPoint>,
+	/// The most recently deactivated snap, along with the time its glyph started fading out once it's first drawn.
+	fading_indicator: Option<(SnapIndicator, Option<u64>)>,
 	layer_snapper: LayerSnapper,
 	grid_snapper: GridSnapper,
 	alignment_snapper: AlignmentSnapper,
@@ -239,10 +243,21 @@ impl<'a> SnapData<'a> {
 }
 impl SnapManager {
 	pub fn update_indicator(&mut self, snapped_point: SnappedPoint) {
-		self.indicator = snapped_point.is_snapped().then_some(snapped_point);
+		if snapped_point.is_snapped() {
+			self.indicator = Some(snapped_point);
+			self.fading_indicator = None;
+		} else {
+			self.clear_indicator();
+		}
 	}
 	pub fn clear_indicator(&mut self) {
-		self.indicator = None;
+		if let Some(indicator) = self.indicator.take().as_ref().and_then(SnapIndicator::from_snapped_point) {
+			self.fading_indicator = Some((indicator, None));
+		}
+	}
+	/// The currently active snap, with the type of target it snapped to.
+	pub fn active_indicator(&self) -> Option<SnapIndicator> {
+		self.indicator.as_ref().and_then(SnapIndicator::from_snapped_point)
 	}
 	pub fn preview_draw(&mut self, snap_data: &SnapData, mouse: DVec2) {
 		let point = SnapCandidatePoint::handle(snap_data.document.metadata().document_to_viewport.inverse().transform_point2(mouse));
@@ -467,23 +482,24 @@ impl SnapManager {
 			Self::alignment_x_overlay(&ind.distribution_boxes_horizontal, to_viewport, overlay_context);
 			Self::alignment_y_overlay(&ind.distribution_boxes_vertical, to_viewport, overlay_context);
 
-			let align = [ind.alignment_target_horizontal, ind.alignment_target_vertical].map(|target| target.map(|target| to_viewport.transform_point2(target)));
-			let any_align = align.iter().flatten().next().is_some();
-			for &target in align.iter().flatten() {
-				overlay_context.line(viewport, target, None, None);
-			}
-			for &target in align.iter().flatten() {
-				overlay_context.manipulator_handle(target, false, None);
-			}
-			if any_align {
-				overlay_context.manipulator_handle(viewport, false, None);
+			draw_alignment_guides(overlay_context, ind, to_viewport);
+
+			if let Some(indicator) = SnapIndicator::from_snapped_point(ind) {
+				draw_snap_glyph(overlay_context, indicator.indicator_type, viewport, 1.);
 			}
 
-			if !any_align && ind.distribution_equal_distance_horizontal.is_none() && ind.distribution_equal_distance_vertical.is_none() {
+			if !ind.align() && ind.distribution_equal_distance_horizontal.is_none() && ind.distribution_equal_distance_vertical.is_none() {
 				let text = format!("[{}] from [{}]", ind.target, ind.source);
-				let transform = DAffine2::from_translation(viewport - DVec2::new(0., 4.));
+				let transform = DAffine2::from_translation(viewport - DVec2::new(0., SNAP_INDICATOR_GLYPH_SIZE / 2. + 2.));
 				overlay_context.text(&text, COLOR_OVERLAY_WHITE, Some(COLOR_OVERLAY_BLACK_75), transform, 4., [Pivot::Start, Pivot::End]);
-				overlay_context.square(viewport, Some(4.), Some(COLOR_OVERLAY_BLUE), Some(COLOR_OVERLAY_BLUE));
+			}
+		} else if let Some((indicator, deactivated_at)) = &mut self.fading_indicator {
+			let opacity = fade_opacity(*deactivated_at.get_or_insert(snap_data.input.time), snap_data.input.time);
+			if opacity > 0. {
+				draw_snap_glyph(overlay_context, indicator.indicator_type, to_viewport.transform_point2(indicator.position_document), opacity);
+				overlay_context.request_animation_frame();
+			} else {
+				self.fading_indicator = None;
 			}
 		}
 	}
@@ -491,7 +507,7 @@ impl SnapManager {
 	/// Removes snap target data and overlays. Call this when snapping is done.
 	pub fn cleanup(&mut self, responses: &mut VecDeque<Message>) {
 		self.candidates = None;
-		self.indicator = None;
+		self.clear_indicator();
 		responses.add(OverlaysMessage::Draw);
 	}
 }
@@ -0,0 +1,263 @@
+use super::SnappedPoint;
+use crate::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE, SNAP_INDICATOR_FADE_MILLISECONDS, SNAP_INDICATOR_GLYPH_SIZE};
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::misc::{AlignmentSnapTarget, ArtboardSnapTarget, BoundingBoxSnapTarget, GridSnapTarget, PathSnapTarget, SnapTarget};
+use glam::{DAffine2, DVec2};
+use graphene_std::Color;
+
+/// The kind of snap target, which decides the glyph drawn to indicate it.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum SnapIndicatorType {
+	/// Drawn as a square, for anchors and the corners and edge midpoints of bounding boxes.
+	Anchor,
+	/// Drawn as an X, for intersections between paths or grid lines.
+	Intersection,
+	/// Drawn as a circle with a dot, for the centers of bounding boxes and artboards.
+	Center,
+	/// Drawn as a cross, for points along grid lines.
+	Grid,
+	/// Drawn as a ⟂ symbol, for points normal to a path or perpendicular to an endpoint.
+	Perpendicular,
+	/// Drawn as a circle resting on a line, for points tangent to a path.
+	Tangent,
+	/// Drawn as a diamond, for points along a path or artboard edge.
+	Edge,
+}
+
+impl SnapIndicatorType {
+	/// The kind of the snap target, or `None` if it isn't indicated by a glyph (such as even distribution, which has its own overlays).
+	pub fn from_target(target: SnapTarget, at_intersection: bool) -> Option<Self> {
+		if at_intersection {
+			return Some(Self::Intersection);
+		}
+
+		let indicator_type = match target {
+			SnapTarget::None | SnapTarget::DistributeEvenly(_) => return None,
+			SnapTarget::BoundingBox(BoundingBoxSnapTarget::CenterPoint) | SnapTarget::Artboard(ArtboardSnapTarget::CenterPoint) => Self::Center,
+			SnapTarget::BoundingBox(BoundingBoxSnapTarget::CornerPoint | BoundingBoxSnapTarget::EdgeMidpoint) | SnapTarget::Artboard(ArtboardSnapTarget::CornerPoint) => Self::Anchor,
+			SnapTarget::Artboard(ArtboardSnapTarget::AlongEdge) => Self::Edge,
+			SnapTarget::Path(target) => match target {
+				PathSnapTarget::AnchorPointWithColinearHandles | PathSnapTarget::AnchorPointWithFreeHandles | PathSnapTarget::LineMidpoint => Self::Anchor,
+				PathSnapTarget::AlongPath => Self::Edge,
+				PathSnapTarget::NormalToPath | PathSnapTarget::PerpendicularToEndpoint => Self::Perpendicular,
+				PathSnapTarget::TangentToPath => Self::Tangent,
+				PathSnapTarget::IntersectionPoint => Self::Intersection,
+			},
+			SnapTarget::Grid(target) => match target {
+				GridSnapTarget::Line | GridSnapTarget::LineNormal => Self::Grid,
+				GridSnapTarget::Intersection => Self::Intersection,
+			},
+			SnapTarget::Alignment(target) => match target {
+				AlignmentSnapTarget::BoundingBoxCenterPoint | AlignmentSnapTarget::ArtboardCenterPoint => Self::Center,
+				AlignmentSnapTarget::BoundingBoxCornerPoint | AlignmentSnapTarget::ArtboardCornerPoint | AlignmentSnapTarget::AlignWithAnchorPoint => Self::Anchor,
+				AlignmentSnapTarget::IntersectionPoint => Self::Intersection,
+				AlignmentSnapTarget::PerpendicularToEndpoint => Self::Perpendicular,
+			},
+		};
+		Some(indicator_type)
+	}
+}
+
+/// An active snap, as reported by the snapping system for its overlays.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct SnapIndicator {
+	pub indicator_type: SnapIndicatorType,
+	pub position_document: DVec2,
+}
+
+impl SnapIndicator {
+	pub fn from_snapped_point(snapped_point: &SnappedPoint) -> Option<Self> {
+		let indicator_type = SnapIndicatorType::from_target(snapped_point.target, snapped_point.at_intersection)?;
+		Some(Self {
+			indicator_type,
+			position_document: snapped_point.snapped_point_document,
+		})
+	}
+}
+
+/// The basic shapes which a snap indicator glyph is built from, in viewport space.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum GlyphPrimitive {
+	Square { center: DVec2, size: f64 },
+	Line { start: DVec2, end: DVec2 },
+	Circle { center: DVec2, radius: f64 },
+	Dot { center: DVec2, radius: f64 },
+}
+
+/// The shapes making up the glyph of the indicator type, centered at the viewport position.
+///
+/// Since the glyph is sized in viewport space, the overlay context scales it by the device pixel ratio to keep the same apparent size on high-DPI displays.
+pub fn glyph_primitives(indicator_type: SnapIndicatorType, center: DVec2) -> Vec<GlyphPrimitive> {
+	let half = SNAP_INDICATOR_GLYPH_SIZE / 2.;
+	let line = |start: DVec2, end: DVec2| GlyphPrimitive::Line {
+		start: center + start * half,
+		end: center + end * half,
+	};
+
+	match indicator_type {
+		SnapIndicatorType::Anchor => vec![GlyphPrimitive::Square {
+			center,
+			size: SNAP_INDICATOR_GLYPH_SIZE,
+		}],
+		SnapIndicatorType::Intersection => vec![line(DVec2::new(-1., -1.), DVec2::new(1., 1.)), line(DVec2::new(-1., 1.), DVec2::new(1., -1.))],
+		SnapIndicatorType::Center => vec![GlyphPrimitive::Circle { center, radius: half }, GlyphPrimitive::Dot { center, radius: 1.5 }],
+		SnapIndicatorType::Grid => vec![line(DVec2::new(-1., 0.), DVec2::new(1., 0.)), line(DVec2::new(0., -1.), DVec2::new(0., 1.))],
+		SnapIndicatorType::Perpendicular => vec![line(DVec2::new(-1., 1.), DVec2::new(1., 1.)), line(DVec2::new(0., 1.), DVec2::new(0., -1.))],
+		SnapIndicatorType::Tangent => vec![
+			GlyphPrimitive::Circle {
+				center: center + DVec2::new(0., half / 2.),
+				radius: half / 2.,
+			},
+			line(DVec2::new(-1., 0.), DVec2::new(1., 0.)),
+		],
+		SnapIndicatorType::Edge => vec![
+			line(DVec2::new(0., -1.), DVec2::new(1., 0.)),
+			line(DVec2::new(1., 0.), DVec2::new(0., 1.)),
+			line(DVec2::new(0., 1.), DVec2::new(-1., 0.)),
+			line(DVec2::new(-1., 0.), DVec2::new(0., -1.)),
+		],
+	}
+}
+
+/// How opaque an indicator which deactivated at `deactivated_at` should be drawn at time `now` (both in milliseconds), reaching zero once it has fully faded out.
+pub fn fade_opacity(deactivated_at: u64, now: u64) -> f64 {
+	let elapsed = now.saturating_sub(deactivated_at) as f64;
+	(1. - elapsed / SNAP_INDICATOR_FADE_MILLISECONDS as f64).clamp(0., 1.)
+}
+
+fn with_opacity(color: &str, opacity: f64) -> String {
+	let Some(color) = color.strip_prefix('#').and_then(Color::from_rgb_str) else {
+		return color.to_string();
+	};
+	let mut color = color.with_alpha(opacity as f32).to_rgba_hex_srgb();
+	color.insert(0, '#');
+	color
+}
+
+/// Draws the glyph of the indicator type at the viewport position, with the given opacity so it can be faded out.
+pub fn draw_snap_glyph(overlay_context: &mut OverlayContext, indicator_type: SnapIndicatorType, position: DVec2, opacity: f64) {
+	let stroke = with_opacity(COLOR_OVERLAY_BLUE, opacity);
+	let fill = with_opacity(COLOR_OVERLAY_WHITE, opacity);
+
+	for primitive in glyph_primitives(indicator_type, position) {
+		match primitive {
+			GlyphPrimitive::Square { center, size } => overlay_context.square(center, Some(size), Some(&fill), Some(&stroke)),
+			GlyphPrimitive::Line { start, end } => overlay_context.line(start, end, Some(&stroke), None),
+			GlyphPrimitive::Circle { center, radius } => overlay_context.circle(center, radius, Some(&fill), Some(&stroke)),
+			GlyphPrimitive::Dot { center, radius } => overlay_context.circle(center, radius, Some(&stroke), Some(&stroke)),
+		}
+	}
+}
+
+/// Draws the guide lines from the snapped point to the points of other layers it's aligned with, each marked by an anchor glyph.
+pub fn draw_alignment_guides(overlay_context: &mut OverlayContext, snapped_point: &SnappedPoint, to_viewport: DAffine2) {
+	let position = to_viewport.transform_point2(snapped_point.snapped_point_document);
+	let targets = [snapped_point.alignment_target_horizontal, snapped_point.alignment_target_vertical];
+
+	for target in targets.into_iter().flatten() {
+		let target = to_viewport.transform_point2(target);
+		overlay_context.dashed_line(position, target, None, None, Some(4.), Some(4.), None);
+		draw_snap_glyph(overlay_context, SnapIndicatorType::Anchor, target, 1.);
+	}
+}
+
+#[cfg(test)]
+mod test_snap_overlays {
+	use super::*;
+	use GlyphPrimitive::*;
+
+	fn glyph(indicator_type: SnapIndicatorType) -> Vec<GlyphPrimitive> {
+		glyph_primitives(indicator_type, DVec2::new(10.5, 20.5))
+	}
+
+	fn line(start: [f64; 2], end: [f64; 2]) -> GlyphPrimitive {
+		Line {
+			start: DVec2::from(start),
+			end: DVec2::from(end),
+		}
+	}
+
+	#[test]
+	fn anchor_glyph() {
+		assert_eq!(
+			glyph(SnapIndicatorType::Anchor),
+			vec![Square {
+				center: DVec2::new(10.5, 20.5),
+				size: 8.
+			}]
+		);
+	}
+
+	#[test]
+	fn intersection_glyph() {
+		assert_eq!(glyph(SnapIndicatorType::Intersection), vec![line([6.5, 16.5], [14.5, 24.5]), line([6.5, 24.5], [14.5, 16.5])]);
+	}
+
+	#[test]
+	fn center_glyph() {
+		let center = DVec2::new(10.5, 20.5);
+		assert_eq!(glyph(SnapIndicatorType::Center), vec![Circle { center, radius: 4. }, Dot { center, radius: 1.5 }]);
+	}
+
+	#[test]
+	fn grid_glyph() {
+		assert_eq!(glyph(SnapIndicatorType::Grid), vec![line([6.5, 20.5], [14.5, 20.5]), line([10.5, 16.5], [10.5, 24.5])]);
+	}
+
+	#[test]
+	fn perpendicular_glyph() {
+		assert_eq!(glyph(SnapIndicatorType::Perpendicular), vec![line([6.5, 24.5], [14.5, 24.5]), line([10.5, 24.5], [10.5, 16.5])]);
+	}
+
+	#[test]
+	fn tangent_glyph() {
+		assert_eq!(
+			glyph(SnapIndicatorType::Tangent),
+			vec![
+				Circle {
+					center: DVec2::new(10.5, 22.5),
+					radius: 2.
+				},
+				line([6.5, 20.5], [14.5, 20.5])
+			]
+		);
+	}
+
+	#[test]
+	fn edge_glyph() {
+		assert_eq!(
+			glyph(SnapIndicatorType::Edge),
+			vec![
+				line([10.5, 16.5], [14.5, 20.5]),
+				line([14.5, 20.5], [10.5, 24.5]),
+				line([10.5, 24.5], [6.5, 20.5]),
+				line([6.5, 20.5], [10.5, 16.5])
+			]
+		);
+	}
+
+	#[test]
+	fn indicator_types_of_targets() {
+		assert_eq!(
+			SnapIndicatorType::from_target(SnapTarget::Path(PathSnapTarget::AnchorPointWithFreeHandles), false),
+			Some(SnapIndicatorType::Anchor)
+		);
+		assert_eq!(
+			SnapIndicatorType::from_target(SnapTarget::Path(PathSnapTarget::AnchorPointWithFreeHandles), true),
+			Some(SnapIndicatorType::Intersection)
+		);
+		assert_eq!(SnapIndicatorType::from_target(SnapTarget::Grid(GridSnapTarget::Line), false), Some(SnapIndicatorType::Grid));
+		assert_eq!(
+			SnapIndicatorType::from_target(SnapTarget::BoundingBox(BoundingBoxSnapTarget::CenterPoint), false),
+			Some(SnapIndicatorType::Center)
+		);
+		assert_eq!(SnapIndicatorType::from_target(SnapTarget::None, false), None);
+	}
+
+	#[test]
+	fn indicators_fade_out() {
+		assert_eq!(fade_opacity(1000, 1000), 1.);
+		assert_eq!(fade_opacity(1000, 1000 + SNAP_INDICATOR_FADE_MILLISECONDS / 2), 0.5);
+		assert_eq!(fade_opacity(1000, 1000 + SNAP_INDICATOR_FADE_MILLISECONDS), 0.);
+	}
+}
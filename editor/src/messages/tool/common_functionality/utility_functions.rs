@@ -1,13 +1,14 @@
 use super::snapping::{SnapCandidatePoint, SnapData, SnapManager};
 use super::transformation_cage::{BoundingBoxManager, SizeSnapData};
 use crate::consts::ROTATE_INCREMENT;
+use crate::messages::input_mapper::utility_types::input_keyboard::Key;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::transformation::Selected;
 use crate::messages::prelude::*;
-use crate::messages::tool::common_functionality::graph_modification_utils::get_text;
+use crate::messages::tool::common_functionality::graph_modification_utils::{get_text, is_text_on_path};
 use crate::messages::tool::common_functionality::transformation_cage::SelectedEdges;
 use crate::messages::tool::tool_messages::path_tool::PathOverlayMode;
-use crate::messages::tool::utility_types::ToolType;
+use crate::messages::tool::utility_types::{HintData, HintGroup, HintInfo, ToolType};
 use bezier_rs::{Bezier, BezierHandles};
 use glam::{DAffine2, DVec2};
 use graphene_std::renderer::Quad;
@@ -65,18 +66,32 @@ where
 }
 
 /// Calculates the bounding box of the layer's text, based on the settings for max width and height specified in the typesetting config.
+/// Tells the user, in the status bar, why an edit to a locked layer had no effect and how to unlock it.
+pub fn send_locked_layers_hint(responses: &mut VecDeque<Message>) {
+	let hint_data = HintData(vec![
+		HintGroup(vec![HintInfo::label("Locked layers can't be edited")]),
+		HintGroup(vec![HintInfo::keys([Key::Accel, Key::KeyL], "Unlock Selected")]),
+	]);
+	responses.add(FrontendMessage::UpdateInputHints { hint_data });
+}
+
 pub fn text_bounding_box(layer: LayerNodeIdentifier, document: &DocumentMessageHandler, font_cache: &FontCache) -> Quad {
 	let Some((text, font, typesetting, per_glyph_instances)) = get_text(layer, &document.network_interface) else {
 		return Quad::from_box([DVec2::ZERO, DVec2::ZERO]);
 	};
 
+	// Text flowing along a path isn't laid out in a box, so the bounds of its rendered glyphs are used instead
+	if is_text_on_path(layer, &document.network_interface) {
+		return Quad::from_box(document.metadata().bounding_box_with_transform(layer, DAffine2::IDENTITY).unwrap_or_default());
+	}
+
 	let font_data = font_cache.get(font).map(|data| load_font(data));
-	let far = graphene_std::text::bounding_box(text, font_data, typesetting, false);
+	let far = graphene_std::text::bounding_box(text, font_data, &font.variations, typesetting, false);
 
 	// TODO: Once the instances refactor is complete and per_glyph_instances can be removed (since it'll be the default),
 	// TODO: remove this because the top of the dashed bounding overlay should no longer be based on the first line's baseline.
 	let vertical_offset = if per_glyph_instances {
-		DVec2::NEG_Y * typesetting.font_size * (1. + (typesetting.line_height_ratio - 1.) / 2.)
+		DVec2::NEG_Y * (typesetting.font_size + typesetting.line_height()) / 2.
 	} else {
 		DVec2::ZERO
 	};
@@ -3,7 +3,7 @@ use super::common_functionality::shapes::shape_utility::ShapeType::{self, Ellips
 use super::utility_types::{ToolActionMessageContext, ToolFsmState, tool_message_to_tool_type};
 use crate::application::generate_uuid;
 use crate::messages::layout::utility_types::widget_prelude::*;
-use crate::messages::portfolio::document::overlays::utility_types::OverlayProvider;
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayProviderHandle, OverlayProviderOwner};
 use crate::messages::portfolio::utility_types::PersistentData;
 use crate::messages::prelude::*;
 use crate::messages::tool::transform_layer::transform_layer_message_handler::TransformLayerMessageContext;
@@ -11,7 +11,7 @@ use crate::messages::tool::utility_types::ToolType;
 use crate::node_graph_executor::NodeGraphExecutor;
 use graphene_std::raster::color::Color;
 
-const ARTBOARD_OVERLAY_PROVIDER: OverlayProvider = |overlay_context| DocumentMessage::DrawArtboardOverlays(overlay_context).into();
+const ARTBOARD_OVERLAY_PROVIDER: OverlayProviderHandle = OverlayProviderHandle::new(OverlayProviderOwner::ToolSystem, |overlay_context| DocumentMessage::DrawArtboardOverlays(overlay_context).into());
 
 #[derive(ExtractField)]
 pub struct ToolMessageContext<'a> {
@@ -148,7 +148,12 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 				send_abort_to_tool(old_tool, old_tool, false);
 
 				// Unsubscribe old tool from the broadcaster
-				tool_data.tools.get(&tool_type).unwrap().deactivate(responses);
+				tool_data.tools.get(&old_tool).unwrap().deactivate(responses);
+
+				// Remove any overlay providers the old tool left behind
+				responses.add(OverlaysMessage::RemoveOwnerProviders {
+					owner: OverlayProviderOwner::Tool(old_tool),
+				});
 
 				// Store the new active tool
 				tool_data.active_tool_type = tool_type;
@@ -171,6 +176,9 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 			ToolMessage::DeactivateTools => {
 				let tool_data = &mut self.tool_state.tool_data;
 				tool_data.tools.get(&tool_data.active_tool_type).unwrap().deactivate(responses);
+				responses.add(OverlaysMessage::RemoveOwnerProviders {
+					owner: OverlayProviderOwner::Tool(tool_data.active_tool_type),
+				});
 
 				// Unsubscribe the transform layer to selection change events
 				let message = Box::new(TransformLayerMessage::SelectionChanged.into());
@@ -196,6 +204,12 @@ impl MessageHandler<ToolMessage, ToolMessageContext<'_>> for ToolMessageHandler
 					send: Box::new(SelectToolMessage::SyncHistory.into()),
 				});
 
+				// Keep drawing the overlays each frame while any of them are animating
+				responses.add(BroadcastMessage::SubscribeEvent {
+					on: BroadcastEvent::AnimationFrame,
+					send: Box::new(OverlaysMessage::AnimationFrame.into()),
+				});
+
 				self.tool_is_active = true;
 
 				let tool_data = &mut self.tool_state.tool_data;
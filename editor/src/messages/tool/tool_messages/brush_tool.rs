@@ -1,14 +1,16 @@
 use super::tool_prelude::*;
-use crate::consts::DEFAULT_BRUSH_SIZE;
+use crate::consts::{BRUSH_STABILIZATION_MAX_LEASH, COLOR_OVERLAY_BLUE, DEFAULT_BRUSH_SIZE};
 use crate::messages::portfolio::document::graph_operation::transform_utils::get_current_transform;
 use crate::messages::portfolio::document::node_graph::document_node_definitions::resolve_document_node_type;
+use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::network_interface::FlowType;
+use crate::messages::tool::common_functionality::brush_stabilizer::BrushStabilizer;
 use crate::messages::tool::common_functionality::color_selector::{ToolColorOptions, ToolColorType};
 use graph_craft::document::NodeId;
 use graph_craft::document::value::TaggedValue;
 use graphene_std::Color;
-use graphene_std::brush::brush_stroke::{BrushInputSample, BrushStroke, BrushStyle};
+use graphene_std::brush::brush_stroke::{BrushDynamics, BrushInputSample, BrushRotation, BrushStroke, BrushStyle, BrushTip, BrushTipImage};
 use graphene_std::raster::BlendMode;
 
 const BRUSH_MAX_SIZE: f64 = 5000.;
@@ -18,6 +20,7 @@ pub enum DrawMode {
 	Draw = 0,
 	Erase,
 	Restore,
+	Clone,
 }
 
 #[derive(Default, ExtractField)]
@@ -32,9 +35,15 @@ pub struct BrushOptions {
 	hardness: f64,
 	flow: f64,
 	spacing: f64,
+	stabilization: f64,
+	tips: Vec<BrushTip>,
+	tip_index: usize,
+	dynamics: BrushDynamics,
 	color: ToolColorOptions,
 	blend_mode: BlendMode,
 	draw_mode: DrawMode,
+	clone_aligned: bool,
+	clone_sample_merged: bool,
 }
 
 impl Default for BrushOptions {
@@ -44,9 +53,15 @@ impl Default for BrushOptions {
 			hardness: 0.,
 			flow: 100.,
 			spacing: 20.,
+			stabilization: 0.,
+			tips: std::iter::once(BrushTip::Round).chain(BrushTipImage::presets().into_iter().map(BrushTip::Image)).collect(),
+			tip_index: 0,
+			dynamics: BrushDynamics::default(),
 			color: ToolColorOptions::default(),
 			blend_mode: BlendMode::Normal,
 			draw_mode: DrawMode::Draw,
+			clone_aligned: true,
+			clone_sample_merged: true,
 		}
 	}
 }
@@ -55,6 +70,7 @@ impl Default for BrushOptions {
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum BrushToolMessage {
 	// Standard messages
+	Overlays(OverlayContext),
 	Abort,
 	WorkingColorChanged,
 
@@ -67,15 +83,26 @@ pub enum BrushToolMessage {
 
 #[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum BrushToolMessageOptionsUpdate {
+	Angle(f64),
+	AngleJitter(f64),
 	BlendMode(BlendMode),
 	ChangeDiameter(f64),
+	CloneAligned(bool),
+	CloneSampleMerged(bool),
 	Color(Option<Color>),
 	ColorType(ToolColorType),
 	Diameter(f64),
 	DrawMode(DrawMode),
 	Flow(f64),
 	Hardness(f64),
+	ImportTip,
+	ImportedTip { name: String, data: Vec<u8>, width: u32, height: u32 },
+	PressureOpacity(bool),
+	PressureSize(bool),
+	Rotation(BrushRotation),
 	Spacing(f64),
+	Stabilization(f64),
+	Tip(usize),
 	WorkingColors(Option<Color>, Option<Color>),
 }
 
@@ -135,20 +162,88 @@ impl LayoutHolder for BrushTool {
 				.unit("%")
 				.on_update(|number_input: &NumberInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::Spacing(number_input.value.unwrap())).into())
 				.widget_holder(),
+			Separator::new(SeparatorType::Related).widget_holder(),
+			NumberInput::new(Some(self.options.stabilization))
+				.label("Stabilization")
+				.tooltip("Smooths out hand jitter by having the brush trail behind the cursor on a leash. Higher values use a longer leash.")
+				.min(0.)
+				.max(100.)
+				.mode_range()
+				.unit("%")
+				.on_update(|number_input: &NumberInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::Stabilization(number_input.value.unwrap())).into())
+				.widget_holder(),
 		];
 
 		widgets.push(Separator::new(SeparatorType::Unrelated).widget_holder());
 
-		let draw_mode_entries: Vec<_> = [DrawMode::Draw, DrawMode::Erase, DrawMode::Restore]
-			.into_iter()
-			.map(|draw_mode| {
-				RadioEntryData::new(format!("{draw_mode:?}"))
-					.label(format!("{draw_mode:?}"))
-					.on_update(move |_| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::DrawMode(draw_mode)).into())
-			})
-			.collect();
+		let tip_entries = vec![
+			self.options
+				.tips
+				.iter()
+				.enumerate()
+				.map(|(index, tip)| {
+					MenuListEntry::new(format!("{index}"))
+						.label(tip.name())
+						.on_commit(move |_| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::Tip(index)).into())
+				})
+				.collect(),
+		];
+		widgets.push(
+			DropdownInput::new(tip_entries)
+				.selected_index(Some(self.options.tip_index as u32))
+				.tooltip("The shape stamped along the stroke")
+				.widget_holder(),
+		);
+		widgets.push(
+			IconButton::new("FileImport", 16)
+				.tooltip("Import an image as a brush tip, where dark opaque pixels deposit paint")
+				.on_update(|_| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::ImportTip).into())
+				.widget_holder(),
+		);
+		widgets.push(PopoverButton::new().popover_layout(self.dynamics_layout()).tooltip("Tip Dynamics").widget_holder());
+
+		widgets.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+
+		let draw_mode_entries: Vec<_> = [
+			(DrawMode::Draw, "Paint with the chosen color"),
+			(DrawMode::Erase, "Erase the image back to transparency"),
+			(DrawMode::Restore, "Bring back what was erased by earlier strokes"),
+			(DrawMode::Clone, "Copy pixels from a source point, which is set with Alt+click"),
+		]
+		.into_iter()
+		.map(|(draw_mode, tooltip)| {
+			RadioEntryData::new(format!("{draw_mode:?}"))
+				.label(format!("{draw_mode:?}"))
+				.tooltip(tooltip)
+				.on_update(move |_| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::DrawMode(draw_mode)).into())
+		})
+		.collect();
 		widgets.push(RadioInput::new(draw_mode_entries).selected_index(Some(self.options.draw_mode as u32)).widget_holder());
 
+		if self.options.draw_mode == DrawMode::Clone {
+			let mut clone_aligned_checkbox_id = CheckboxId::default();
+			widgets.push(Separator::new(SeparatorType::Related).widget_holder());
+			widgets.push(
+				CheckboxInput::new(self.options.clone_aligned)
+					.tooltip("Keep the source moving along with each stroke, instead of starting every stroke from the source point")
+					.on_update(|checkbox_input: &CheckboxInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::CloneAligned(checkbox_input.checked)).into())
+					.for_label(clone_aligned_checkbox_id.clone())
+					.widget_holder(),
+			);
+			widgets.push(TextLabel::new("Aligned").for_checkbox(&mut clone_aligned_checkbox_id).widget_holder());
+
+			let mut clone_sample_merged_checkbox_id = CheckboxId::default();
+			widgets.push(Separator::new(SeparatorType::Related).widget_holder());
+			widgets.push(
+				CheckboxInput::new(self.options.clone_sample_merged)
+					.tooltip("Copy from the layers below as they appear together, instead of from the image being painted. Brush layers moved by a Transform node copy from their own image.")
+					.on_update(|checkbox_input: &CheckboxInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::CloneSampleMerged(checkbox_input.checked)).into())
+					.for_label(clone_sample_merged_checkbox_id.clone())
+					.widget_holder(),
+			);
+			widgets.push(TextLabel::new("Sample Merged").for_checkbox(&mut clone_sample_merged_checkbox_id).widget_holder());
+		}
+
 		widgets.push(Separator::new(SeparatorType::Unrelated).widget_holder());
 
 		widgets.append(&mut self.options.color.create_widgets(
@@ -186,6 +281,80 @@ impl LayoutHolder for BrushTool {
 	}
 }
 
+impl BrushTool {
+	fn dynamics_layout(&self) -> Vec<LayoutGroup> {
+		let dynamics = &self.options.dynamics;
+
+		let rotation_entries = [(BrushRotation::Fixed, "Fixed"), (BrushRotation::FollowDirection, "Follow Direction")]
+			.into_iter()
+			.map(|(rotation, label)| {
+				RadioEntryData::new(format!("{rotation:?}"))
+					.label(label)
+					.on_update(move |_| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::Rotation(rotation)).into())
+			})
+			.collect();
+		let rotation_index = match dynamics.rotation {
+			BrushRotation::Fixed => 0,
+			BrushRotation::FollowDirection => 1,
+		};
+
+		let mut pressure_size_checkbox_id = CheckboxId::default();
+		let mut pressure_opacity_checkbox_id = CheckboxId::default();
+
+		vec![
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new("Tip Dynamics").bold(true).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![RadioInput::new(rotation_entries).selected_index(Some(rotation_index)).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					NumberInput::new(Some(dynamics.angle))
+						.label("Angle")
+						.tooltip("The angle each dab of the tip is turned by, relative to the direction of the stroke when following it")
+						.min(-180.)
+						.max(180.)
+						.unit("°")
+						.on_update(|number_input: &NumberInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::Angle(number_input.value.unwrap())).into())
+						.widget_holder(),
+				],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					NumberInput::new(Some(dynamics.angle_jitter))
+						.label("Angle Jitter")
+						.tooltip("The largest random deviation from the angle of each dab")
+						.min(0.)
+						.max(180.)
+						.mode_range()
+						.unit("°")
+						.on_update(|number_input: &NumberInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::AngleJitter(number_input.value.unwrap())).into())
+						.widget_holder(),
+				],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					CheckboxInput::new(dynamics.pressure_size)
+						.on_update(|checkbox_input: &CheckboxInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::PressureSize(checkbox_input.checked)).into())
+						.for_label(pressure_size_checkbox_id.clone())
+						.widget_holder(),
+					TextLabel::new("Pressure Controls Size").for_checkbox(&mut pressure_size_checkbox_id).widget_holder(),
+				],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					CheckboxInput::new(dynamics.pressure_opacity)
+						.on_update(|checkbox_input: &CheckboxInput| BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::PressureOpacity(checkbox_input.checked)).into())
+						.for_label(pressure_opacity_checkbox_id.clone())
+						.widget_holder(),
+					TextLabel::new("Pressure Controls Opacity").for_checkbox(&mut pressure_opacity_checkbox_id).widget_holder(),
+				],
+			},
+		]
+	}
+}
+
 #[message_handler_data]
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for BrushTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
@@ -194,6 +363,8 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 			return;
 		};
 		match action {
+			BrushToolMessageOptionsUpdate::Angle(angle) => self.options.dynamics.angle = angle,
+			BrushToolMessageOptionsUpdate::AngleJitter(angle_jitter) => self.options.dynamics.angle_jitter = angle_jitter,
 			BrushToolMessageOptionsUpdate::BlendMode(blend_mode) => self.options.blend_mode = blend_mode,
 			BrushToolMessageOptionsUpdate::ChangeDiameter(change) => {
 				let needs_rounding = ((self.options.diameter + change.abs() / 2.) % change.abs() - change.abs() / 2.).abs() > 0.5;
@@ -207,11 +378,32 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 				self.options.diameter = self.options.diameter.max(1.);
 				self.send_layout(responses, LayoutTarget::ToolOptions);
 			}
+			BrushToolMessageOptionsUpdate::CloneAligned(clone_aligned) => {
+				self.options.clone_aligned = clone_aligned;
+				self.data.clone_offset = None;
+			}
+			BrushToolMessageOptionsUpdate::CloneSampleMerged(clone_sample_merged) => self.options.clone_sample_merged = clone_sample_merged,
 			BrushToolMessageOptionsUpdate::Diameter(diameter) => self.options.diameter = diameter,
 			BrushToolMessageOptionsUpdate::DrawMode(draw_mode) => self.options.draw_mode = draw_mode,
 			BrushToolMessageOptionsUpdate::Hardness(hardness) => self.options.hardness = hardness,
 			BrushToolMessageOptionsUpdate::Flow(flow) => self.options.flow = flow,
+			BrushToolMessageOptionsUpdate::ImportTip => {
+				responses.add(FrontendMessage::TriggerImportBrushTip);
+				return;
+			}
+			BrushToolMessageOptionsUpdate::ImportedTip { name, data, width, height } => {
+				self.options.tips.push(BrushTip::Image(BrushTipImage::from_rgba(name, &data, width, height)));
+				self.options.tip_index = self.options.tips.len() - 1;
+			}
+			BrushToolMessageOptionsUpdate::PressureOpacity(pressure_opacity) => self.options.dynamics.pressure_opacity = pressure_opacity,
+			BrushToolMessageOptionsUpdate::PressureSize(pressure_size) => self.options.dynamics.pressure_size = pressure_size,
+			BrushToolMessageOptionsUpdate::Rotation(rotation) => self.options.dynamics.rotation = rotation,
 			BrushToolMessageOptionsUpdate::Spacing(spacing) => self.options.spacing = spacing,
+			BrushToolMessageOptionsUpdate::Stabilization(stabilization) => {
+				self.options.stabilization = stabilization;
+				self.data.stabilizer.set_leash_length(self.data.leash_length(stabilization));
+			}
+			BrushToolMessageOptionsUpdate::Tip(tip_index) => self.options.tip_index = tip_index,
 			BrushToolMessageOptionsUpdate::Color(color) => {
 				self.options.color.custom_color = color;
 				self.options.color.color_type = ToolColorType::Custom;
@@ -231,6 +423,7 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 			BrushToolFsmState::Ready => actions!(BrushToolMessageDiscriminant;
 				DragStart,
 				DragStop,
+				PointerMove,
 				UpdateOptions,
 			),
 			BrushToolFsmState::Drawing => actions!(BrushToolMessageDiscriminant;
@@ -246,6 +439,7 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Brus
 impl ToolTransition for BrushTool {
 	fn event_to_message_map(&self) -> EventToMessageMap {
 		EventToMessageMap {
+			overlay_provider: Some(|overlay_context: OverlayContext| BrushToolMessage::Overlays(overlay_context).into()),
 			tool_abort: Some(BrushToolMessage::Abort.into()),
 			working_color_changed: Some(BrushToolMessage::WorkingColorChanged.into()),
 			..Default::default()
@@ -258,6 +452,13 @@ struct BrushToolData {
 	strokes: Vec<BrushStroke>,
 	layer: Option<LayerNodeIdentifier>,
 	transform: DAffine2,
+	/// The scale from layer space to viewport space, used to keep the stabilizer's leash a constant length on screen.
+	layer_scale: f64,
+	stabilizer: BrushStabilizer,
+	/// The point in document space which clone strokes copy pixels from, set with Alt+click.
+	clone_source: Option<DVec2>,
+	/// The offset in document space from the cursor to the point being copied from, kept between strokes when cloning is aligned.
+	clone_offset: Option<DVec2>,
 }
 
 impl BrushToolData {
@@ -295,6 +496,46 @@ impl BrushToolData {
 		None
 	}
 
+	/// The length of the stabilizer's leash in layer space for the given stabilization strength percentage.
+	fn leash_length(&self, stabilization: f64) -> f64 {
+		if self.layer_scale <= 0. {
+			return 0.;
+		}
+
+		stabilization.clamp(0., 100.) / 100. * BRUSH_STABILIZATION_MAX_LEASH / self.layer_scale
+	}
+
+	/// Feeds a pointer sample through the stabilizer and replaces the trace of the active stroke with the smoothed samples.
+	fn append_sample(&mut self, sample: BrushInputSample) {
+		self.stabilizer.push(sample);
+
+		if let Some(stroke) = self.strokes.last_mut() {
+			stroke.trace = self.stabilizer.smoothed_samples().to_vec();
+		}
+	}
+
+	/// Commits the active stroke as the stabilized polyline resampled at the spacing of the brush's blit points.
+	fn finish_stroke(&mut self) {
+		if let Some(stroke) = self.strokes.last_mut() {
+			let spacing = stroke.style.spacing / 100. * stroke.style.diameter;
+			stroke.trace = self.stabilizer.finish(spacing);
+		}
+		self.stabilizer.clear();
+	}
+
+	/// The offset in layer space from the start of a new clone stroke to the pixels it copies, rounded to whole pixels so the copy stays crisp.
+	fn layer_clone_offset(&mut self, document_position: DVec2, document_to_layer: DAffine2, aligned: bool) -> Option<DVec2> {
+		let source = self.clone_source?;
+		let document_offset = match self.clone_offset {
+			Some(offset) if aligned => offset,
+			_ => source - document_position,
+		};
+		self.clone_offset = Some(document_offset);
+
+		let layer_offset = document_to_layer.transform_point2(document_position + document_offset) - document_to_layer.transform_point2(document_position);
+		Some(layer_offset.round())
+	}
+
 	fn update_strokes(&self, responses: &mut VecDeque<Message>) {
 		let Some(layer) = self.layer else { return };
 		let strokes = self.strokes.clone();
@@ -320,7 +561,58 @@ impl Fsm for BrushToolFsmState {
 
 		let ToolMessage::Brush(event) = event else { return self };
 		match (self, event) {
+			(_, BrushToolMessage::Overlays(mut overlay_context)) => {
+				if self == BrushToolFsmState::Drawing && tool_data.stabilizer.leash_length() > 0. {
+					if let (Some(layer), Some(tip)) = (tool_data.layer, tool_data.stabilizer.tip()) {
+						let layer_to_viewport = document.metadata().downstream_transform_to_viewport(layer) * tool_data.transform;
+						let tip = layer_to_viewport.transform_point2(tip.position);
+
+						// Draw the leash between the cursor and the brush tip trailing behind it
+						overlay_context.line(input.mouse.position, tip, Some(COLOR_OVERLAY_BLUE), None);
+						overlay_context.manipulator_anchor(tip, true, Some(COLOR_OVERLAY_BLUE));
+					}
+				}
+
+				if tool_options.draw_mode == DrawMode::Clone {
+					let document_to_viewport = document.metadata().document_to_viewport;
+					let mouse = document_to_viewport.inverse().transform_point2(input.mouse.position);
+					let source = match tool_data.clone_offset {
+						Some(offset) => Some(mouse + offset),
+						None => tool_data.clone_source,
+					};
+
+					// Draw a ring around the pixels being copied from
+					if let Some(source) = source {
+						let radius = (tool_options.diameter / 2.).max(1.);
+						overlay_context.draw_arc(document_to_viewport.transform_point2(source), radius, 0., std::f64::consts::TAU);
+					}
+				}
+
+				self
+			}
+			(BrushToolFsmState::Ready, BrushToolMessage::PointerMove) => {
+				if tool_options.draw_mode == DrawMode::Clone {
+					responses.add(OverlaysMessage::Draw);
+				}
+
+				self
+			}
 			(BrushToolFsmState::Ready, BrushToolMessage::DragStart) => {
+				if tool_options.draw_mode == DrawMode::Clone {
+					let document_position = document.metadata().document_to_viewport.inverse().transform_point2(input.mouse.position);
+
+					// Alt+click picks the point to copy from instead of painting
+					if input.keyboard.get(Key::Alt as usize) {
+						tool_data.clone_source = Some(document_position);
+						tool_data.clone_offset = None;
+						responses.add(OverlaysMessage::Draw);
+						return BrushToolFsmState::Ready;
+					}
+					if tool_data.clone_source.is_none() {
+						return BrushToolFsmState::Ready;
+					}
+				}
+
 				responses.add(DocumentMessage::StartTransaction);
 				let loaded_layer = tool_data.load_existing_strokes(document);
 
@@ -339,15 +631,32 @@ impl Fsm for BrushToolFsmState {
 					let layer_scale = 0.0001_f64 // Safety against division by zero
 						.max((layer_document_scale.matrix2 * glam::DVec2::X).length())
 						.max((layer_document_scale.matrix2 * glam::DVec2::Y).length());
+					tool_data.layer_scale = layer_scale;
+					tool_data.stabilizer = BrushStabilizer::new(tool_data.leash_length(tool_options.stabilization));
 
 					// Start a new stroke with a single sample
+					// TODO: Use the Erase mode when drawing with the eraser end of a stylus, once pointer events say which end of the pen is in use
 					let blend_mode = match tool_options.draw_mode {
 						DrawMode::Draw => tool_options.blend_mode,
 						DrawMode::Erase => BlendMode::Erase,
 						DrawMode::Restore => BlendMode::Restore,
+						DrawMode::Clone => BlendMode::Normal,
+					};
+					let clone_offset = if tool_options.draw_mode == DrawMode::Clone {
+						responses.add(GraphOperationMessage::BrushCloneSource {
+							layer,
+							sample_merged: tool_options.clone_sample_merged,
+						});
+
+						let viewport_to_document = document.metadata().document_to_viewport.inverse();
+						let document_to_layer = (viewport_to_document * layer_document_scale).inverse();
+						let document_position = viewport_to_document.transform_point2(input.mouse.position);
+						tool_data.layer_clone_offset(document_position, document_to_layer, tool_options.clone_aligned)
+					} else {
+						None
 					};
 					tool_data.strokes.push(BrushStroke {
-						trace: vec![BrushInputSample { position: layer_position }],
+						trace: Vec::new(),
 						style: BrushStyle {
 							color: tool_options.color.active_color().unwrap_or_default(),
 							diameter: tool_options.diameter / layer_scale,
@@ -355,8 +664,16 @@ impl Fsm for BrushToolFsmState {
 							flow: tool_options.flow,
 							spacing: tool_options.spacing,
 							blend_mode,
+							tip: tool_options.tips.get(tool_options.tip_index).cloned().unwrap_or_default(),
+							dynamics: tool_options.dynamics.clone(),
+							clone_offset,
 						},
 					});
+					// TODO: Use the pen pressure once it is passed through with pointer events
+					tool_data.append_sample(BrushInputSample {
+						position: layer_position,
+						pressure: 1.,
+					});
 
 					tool_data.update_strokes(responses);
 					BrushToolFsmState::Drawing
@@ -374,36 +691,50 @@ impl Fsm for BrushToolFsmState {
 
 			(BrushToolFsmState::Drawing, BrushToolMessage::PointerMove) => {
 				if let Some(layer) = tool_data.layer {
-					if let Some(stroke) = tool_data.strokes.last_mut() {
-						let layer_position = document
-							.network_interface
-							.document_metadata()
-							.downstream_transform_to_viewport(layer)
-							.inverse()
-							.transform_point2(input.mouse.position);
-						let layer_position = tool_data.transform.inverse().transform_point2(layer_position);
-
-						stroke.trace.push(BrushInputSample { position: layer_position })
-					}
+					let layer_position = document
+						.network_interface
+						.document_metadata()
+						.downstream_transform_to_viewport(layer)
+						.inverse()
+						.transform_point2(input.mouse.position);
+					let layer_position = tool_data.transform.inverse().transform_point2(layer_position);
+
+					tool_data.append_sample(BrushInputSample {
+						position: layer_position,
+						pressure: 1.,
+					});
 				}
 				tool_data.update_strokes(responses);
+				responses.add(OverlaysMessage::Draw);
 
 				BrushToolFsmState::Drawing
 			}
 
 			(BrushToolFsmState::Drawing, BrushToolMessage::DragStop) => {
 				if !tool_data.strokes.is_empty() {
+					tool_data.finish_stroke();
+					tool_data.update_strokes(responses);
 					responses.add(DocumentMessage::EndTransaction);
 				} else {
 					responses.add(DocumentMessage::AbortTransaction);
 				}
 				tool_data.strokes.clear();
+				tool_data.stabilizer.clear();
+				if !tool_options.clone_aligned {
+					tool_data.clone_offset = None;
+				}
+				responses.add(OverlaysMessage::Draw);
 
 				BrushToolFsmState::Ready
 			}
 			(BrushToolFsmState::Drawing, BrushToolMessage::Abort) => {
 				responses.add(DocumentMessage::AbortTransaction);
 				tool_data.strokes.clear();
+				tool_data.stabilizer.clear();
+				if !tool_options.clone_aligned {
+					tool_data.clone_offset = None;
+				}
+				responses.add(OverlaysMessage::Draw);
 
 				BrushToolFsmState::Ready
 			}
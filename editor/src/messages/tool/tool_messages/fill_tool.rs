@@ -1,15 +1,49 @@
 use super::tool_prelude::*;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::tool::common_functionality::graph_modification_utils::NodeGraphLayer;
-use graphene_std::vector::style::Fill;
+use graph_craft::document::NodeId;
+use graphene_std::vector::VectorData;
+use graphene_std::vector::style::{Fill, Stroke};
+use kurbo::Affine;
+
+/// How far from the click, in viewport pixels, to look for the paths enclosing a region.
+const REGION_SEARCH_RADIUS: f64 = 1000.;
+/// The thinnest a path can be treated as when enclosing a region, so unstroked paths still separate the regions on either side of them.
+const MIN_REGION_INK_WIDTH: f64 = 0.01;
 
 #[derive(Default, ExtractField)]
 pub struct FillTool {
 	fsm_state: FillToolFsmState,
+	options: FillOptions,
+}
+
+pub struct FillOptions {
+	fill_mode: FillMode,
+	gap_tolerance: f64,
+}
+
+impl Default for FillOptions {
+	fn default() -> Self {
+		Self {
+			fill_mode: FillMode::default(),
+			gap_tolerance: 2.,
+		}
+	}
+}
+
+#[derive(PartialEq, Eq, Clone, Copy, Debug, Default, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum FillMode {
+	#[default]
+	Color,
+	/// Tiles the selected layer's graphic across the clicked layer.
+	Pattern,
+	/// Creates a new layer filling the area enclosed by the paths around the click, even if no single path encloses it.
+	Region,
 }
 
 #[impl_message(Message, ToolMessage, Fill)]
-#[derive(PartialEq, Clone, Debug, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
 pub enum FillToolMessage {
 	// Standard messages
 	Abort,
@@ -21,6 +55,13 @@ pub enum FillToolMessage {
 	PointerUp,
 	FillPrimaryColor,
 	FillSecondaryColor,
+	UpdateOptions(FillOptionsUpdate),
+}
+
+#[derive(PartialEq, Clone, Debug, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum FillOptionsUpdate {
+	Mode(FillMode),
+	GapTolerance(f64),
 }
 
 impl ToolMetadata for FillTool {
@@ -37,14 +78,55 @@ impl ToolMetadata for FillTool {
 
 impl LayoutHolder for FillTool {
 	fn layout(&self) -> Layout {
-		Layout::WidgetLayout(WidgetLayout::default())
+		let fill_mode = RadioInput::new(vec![
+			RadioEntryData::new("Color")
+				.label("Color")
+				.tooltip("Fill with the primary or secondary working color")
+				.on_update(move |_| FillToolMessage::UpdateOptions(FillOptionsUpdate::Mode(FillMode::Color)).into()),
+			RadioEntryData::new("Pattern")
+				.label("Pattern")
+				.tooltip("Fill with the selected layer, repeated as a tiled pattern")
+				.on_update(move |_| FillToolMessage::UpdateOptions(FillOptionsUpdate::Mode(FillMode::Pattern)).into()),
+			RadioEntryData::new("Region")
+				.label("Region")
+				.tooltip("Fill the area enclosed by the surrounding paths as a new layer beneath them, even where it's formed by several overlapping paths")
+				.on_update(move |_| FillToolMessage::UpdateOptions(FillOptionsUpdate::Mode(FillMode::Region)).into()),
+		])
+		.selected_index(Some(self.options.fill_mode as u32))
+		.widget_holder();
+		let mut widgets = vec![fill_mode];
+
+		if self.options.fill_mode == FillMode::Region {
+			widgets.push(Separator::new(SeparatorType::Unrelated).widget_holder());
+			widgets.push(
+				NumberInput::new(Some(self.options.gap_tolerance))
+					.unit(" px")
+					.label("Gap Tolerance")
+					.tooltip("Gaps between paths up to this width are closed when finding the enclosed area, so it doesn't leak out through them")
+					.min(0.)
+					.max((1_u64 << f64::MANTISSA_DIGITS) as f64)
+					.on_update(|number_input: &NumberInput| FillToolMessage::UpdateOptions(FillOptionsUpdate::GapTolerance(number_input.value.unwrap())).into())
+					.widget_holder(),
+			);
+		}
+
+		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets }]))
 	}
 }
 
 #[message_handler_data]
 impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for FillTool {
 	fn process_message(&mut self, message: ToolMessage, responses: &mut VecDeque<Message>, context: &mut ToolActionMessageContext<'a>) {
-		self.fsm_state.process_event(message, &mut (), context, &(), responses, true);
+		let ToolMessage::Fill(FillToolMessage::UpdateOptions(action)) = message else {
+			self.fsm_state.process_event(message, &mut (), context, &self.options, responses, true);
+			return;
+		};
+		match action {
+			FillOptionsUpdate::Mode(fill_mode) => self.options.fill_mode = fill_mode,
+			FillOptionsUpdate::GapTolerance(gap_tolerance) => self.options.gap_tolerance = gap_tolerance,
+		}
+
+		self.send_layout(responses, LayoutTarget::ToolOptions);
 	}
 	fn actions(&self) -> ActionList {
 		match self.fsm_state {
@@ -83,16 +165,9 @@ enum FillToolFsmState {
 
 impl Fsm for FillToolFsmState {
 	type ToolData = ();
-	type ToolOptions = ();
-
-	fn transition(
-		self,
-		event: ToolMessage,
-		_tool_data: &mut Self::ToolData,
-		handler_data: &mut ToolActionMessageContext,
-		_tool_options: &Self::ToolOptions,
-		responses: &mut VecDeque<Message>,
-	) -> Self {
+	type ToolOptions = FillOptions;
+
+	fn transition(self, event: ToolMessage, _tool_data: &mut Self::ToolData, handler_data: &mut ToolActionMessageContext, tool_options: &Self::ToolOptions, responses: &mut VecDeque<Message>) -> Self {
 		let ToolActionMessageContext {
 			document, global_tool_data, input, ..
 		} = handler_data;
@@ -104,8 +179,10 @@ impl Fsm for FillToolFsmState {
 				let use_secondary = input.keyboard.get(Key::Shift as usize);
 				let preview_color = if use_secondary { global_tool_data.secondary_color } else { global_tool_data.primary_color };
 
-				// Get the layer the user is hovering over
-				if let Some(layer) = document.click(input) {
+				// Get the layer the user is hovering over, which isn't what a region fill fills
+				if tool_options.fill_mode != FillMode::Region
+					&& let Some(layer) = document.click(input)
+				{
 					overlay_context.fill_path_pattern(document.metadata().layer_outline(layer), document.metadata().transform_to_viewport(layer), &preview_color);
 				}
 
@@ -116,7 +193,22 @@ impl Fsm for FillToolFsmState {
 				responses.add(OverlaysMessage::Draw);
 				self
 			}
-			(FillToolFsmState::Ready, color_event) => {
+			(FillToolFsmState::Ready, color_event @ (FillToolMessage::FillPrimaryColor | FillToolMessage::FillSecondaryColor)) => {
+				if tool_options.fill_mode == FillMode::Region {
+					let color = if color_event == FillToolMessage::FillPrimaryColor {
+						global_tool_data.primary_color
+					} else {
+						global_tool_data.secondary_color
+					};
+					let fill = Fill::Solid(color.to_gamma_srgb());
+
+					return if fill_enclosed_region(document, input.mouse.position, tool_options.gap_tolerance, fill, responses) {
+						FillToolFsmState::Filling
+					} else {
+						self
+					};
+				}
+
 				let Some(layer_identifier) = document.click(input) else {
 					return self;
 				};
@@ -124,6 +216,18 @@ impl Fsm for FillToolFsmState {
 				if NodeGraphLayer::is_raster_layer(layer_identifier, &mut document.network_interface) {
 					return self;
 				}
+				if tool_options.fill_mode == FillMode::Pattern {
+					// The tile comes from the selection, so the layer being clicked can't also be its own tile
+					let selected_nodes = document.network_interface.selected_nodes();
+					let Some(tile_layer) = selected_nodes.selected_layers(document.metadata()).find(|&layer| layer != layer_identifier) else {
+						return self;
+					};
+
+					responses.add(DocumentMessage::AddTransaction);
+					responses.add(GraphOperationMessage::FillPatternSet { layer: layer_identifier, tile_layer });
+
+					return FillToolFsmState::Filling;
+				}
 				let fill = match color_event {
 					FillToolMessage::FillPrimaryColor => Fill::Solid(global_tool_data.primary_color.to_gamma_srgb()),
 					FillToolMessage::FillSecondaryColor => Fill::Solid(global_tool_data.secondary_color.to_gamma_srgb()),
@@ -162,8 +266,78 @@ impl Fsm for FillToolFsmState {
 	}
 }
 
+/// Fills the region around the clicked point enclosed by the paths of the nearby layers with a new layer, placed beneath the lowest of those layers.
+/// Returns whether an enclosed region was found.
+fn fill_enclosed_region(document: &DocumentMessageHandler, viewport_position: DVec2, gap_tolerance: f64, fill: Fill, responses: &mut VecDeque<Message>) -> bool {
+	let metadata = document.metadata();
+	let viewport_to_document = metadata.document_to_viewport.inverse();
+	let point = viewport_to_document.transform_point2(viewport_position);
+	let pixel_size = viewport_to_document.transform_vector2(DVec2::X).length();
+
+	// Only the layers near the click are searched, so filling doesn't get slower as the document grows
+	let radius = REGION_SEARCH_RADIUS * pixel_size;
+	let bounds = [point - DVec2::splat(radius), point + DVec2::splat(radius)];
+	let near_click = |[min, max]: [DVec2; 2]| min.cmple(bounds[1]).all() && max.cmpge(bounds[0]).all();
+
+	// The ink is the area covered by the stroke of each path, widened by the gap tolerance so small gaps between paths are closed
+	let mut ink = Vec::new();
+	let mut thinnest_ink = f64::INFINITY;
+	let mut lowest_layer = None;
+	for layer in metadata.all_layers() {
+		let node_id = layer.to_node();
+		if layer.has_children(metadata) || document.network_interface.is_artboard(&node_id, &[]) || !document.network_interface.is_visible(&node_id, &[]) {
+			continue;
+		}
+		if !metadata.bounding_box_document(layer).is_some_and(near_click) {
+			continue;
+		}
+		let Some(vector_data) = document.network_interface.compute_modified_vector(layer) else { continue };
+
+		let transform = metadata.transform_to_document(layer);
+		let stroke_width = vector_data.style.stroke().map_or(0., |stroke| stroke.weight * transform.matrix2.determinant().abs().sqrt());
+		let ink_width = (stroke_width + gap_tolerance * pixel_size).max(MIN_REGION_INK_WIDTH);
+		let style = kurbo::Stroke::new(ink_width).with_caps(kurbo::Cap::Round).with_join(kurbo::Join::Round);
+
+		for mut bezpath in vector_data.stroke_bezpath_iter() {
+			bezpath.apply_affine(Affine::new(transform.to_cols_array()));
+			let mut piece = VectorData::default();
+			piece.append_bezpath(kurbo::stroke(bezpath.iter(), &style, &kurbo::StrokeOpts::default(), MIN_REGION_INK_WIDTH / 10.));
+			ink.push(piece);
+		}
+		thinnest_ink = thinnest_ink.min(ink_width);
+		lowest_layer = Some(layer);
+	}
+
+	let Some(lowest_layer) = lowest_layer else { return false };
+	// The region is grown to the middle of the thinnest ink so no gap shows between the fill and the paths around it
+	let Some(region) = graphene_std::path_bool::enclosed_region(&ink, point, bounds, thinnest_ink / 2.) else {
+		return false;
+	};
+
+	let parent = lowest_layer.parent(metadata).unwrap_or(LayerNodeIdentifier::ROOT_PARENT);
+	let insert_index = parent.children(metadata).position(|child| child == lowest_layer).map_or(0, |index| index + 1);
+	let document_to_parent = metadata.transform_to_document(parent).inverse();
+	let subpaths = region
+		.stroke_bezier_paths()
+		.map(|mut subpath| {
+			subpath.apply_transform(document_to_parent);
+			subpath
+		})
+		.collect();
+
+	let id = NodeId::new();
+	let layer = LayerNodeIdentifier::new_unchecked(id);
+	responses.add(DocumentMessage::AddTransaction);
+	responses.add(GraphOperationMessage::NewVectorLayer { id, subpaths, parent, insert_index });
+	responses.add(GraphOperationMessage::StrokeSet { layer, stroke: Stroke::default() });
+	responses.add(GraphOperationMessage::FillSet { layer, fill });
+
+	true
+}
+
 #[cfg(test)]
 mod test_fill {
+	use super::{FillMode, FillOptionsUpdate, FillToolMessage};
 	pub use crate::test_utils::test_prelude::*;
 	use graphene_std::vector::fill;
 	use graphene_std::vector::style::Fill;
@@ -218,4 +392,51 @@ mod test_fill {
 		assert_eq!(fills.len(), 1);
 		assert_eq!(fills[0].as_solid().unwrap().to_rgba8_srgb(), Color::YELLOW.to_rgba8_srgb());
 	}
+
+	#[tokio::test]
+	async fn pattern_of_selected_layer() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Rectangle, 0., 0., 100., 100., ModifierKeys::empty()).await;
+		// The most recently drawn layer is left selected and becomes the tile
+		editor.drag_tool(ToolType::Ellipse, 200., 0., 210., 10., ModifierKeys::empty()).await;
+		editor.select_tool(ToolType::Fill).await;
+		editor.handle_message(FillToolMessage::UpdateOptions(FillOptionsUpdate::Mode(FillMode::Pattern))).await;
+		editor.click_tool(ToolType::Fill, MouseKeys::LEFT, DVec2::new(2., 2.), ModifierKeys::empty()).await;
+		let fills = get_fills(&mut editor).await;
+		assert!(fills.iter().any(|fill| matches!(fill, Fill::Pattern(_))));
+	}
+
+	#[tokio::test]
+	async fn region_enclosed_by_grid_of_lines() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+		// No single line encloses any area, but together they form a grid of cells
+		for position in [0., 50., 100.] {
+			editor.drag_tool(ToolType::Line, position, -20., position, 120., ModifierKeys::empty()).await;
+			editor.drag_tool(ToolType::Line, -20., position, 120., position, ModifierKeys::empty()).await;
+		}
+		editor.select_primary_color(Color::GREEN).await;
+		editor.select_tool(ToolType::Fill).await;
+		editor.handle_message(FillToolMessage::UpdateOptions(FillOptionsUpdate::Mode(FillMode::Region))).await;
+		let solid_fills = |fills: Vec<Fill>| fills.into_iter().filter_map(|fill| fill.as_solid()).collect::<Vec<_>>();
+
+		// The area outside of the grid isn't enclosed, so nothing is filled
+		editor.click_tool(ToolType::Fill, MouseKeys::LEFT, DVec2::new(200., 200.), ModifierKeys::empty()).await;
+		assert!(solid_fills(get_fills(&mut editor).await).is_empty());
+
+		editor.click_tool(ToolType::Fill, MouseKeys::LEFT, DVec2::new(25., 25.), ModifierKeys::empty()).await;
+		let fills = solid_fills(get_fills(&mut editor).await);
+		assert_eq!(fills.len(), 1);
+		assert_eq!(fills[0].to_rgba8_srgb(), Color::GREEN.to_rgba8_srgb());
+
+		let document = editor.active_document();
+		let region = document.metadata().all_layers().last().expect("The region should be filled beneath the lines");
+		let [min, max] = document.metadata().bounding_box_document(region).unwrap();
+		assert!(
+			min.abs_diff_eq(DVec2::ZERO, 1.) && max.abs_diff_eq(DVec2::splat(50.), 1.),
+			"The cell should be filled, not {:?}",
+			[min, max]
+		);
+	}
 }
@@ -1,11 +1,11 @@
 use super::tool_prelude::*;
-use crate::consts::{LINE_ROTATE_SNAP_ANGLE, MANIPULATOR_GROUP_MARKER_SIZE, SELECTION_THRESHOLD};
+use crate::consts::{COLOR_OVERLAY_BLUE, COLOR_OVERLAY_WHITE, LINE_ROTATE_SNAP_ANGLE, MANIPULATOR_GROUP_MARKER_SIZE, SELECTION_THRESHOLD};
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::tool::common_functionality::auto_panning::AutoPanning;
 use crate::messages::tool::common_functionality::graph_modification_utils::{NodeGraphLayer, get_gradient};
 use crate::messages::tool::common_functionality::snapping::SnapManager;
-use graphene_std::vector::style::{Fill, Gradient, GradientType};
+use graphene_std::vector::style::{DEFAULT_GRADIENT_MIDPOINT, Fill, Gradient, GradientType};
 
 #[derive(Default, ExtractField)]
 pub struct GradientTool {
@@ -28,11 +28,14 @@ pub enum GradientToolMessage {
 
 	// Tool-specific messages
 	DeleteStop,
+	FitToSelection,
 	InsertStop,
 	PointerDown,
 	PointerMove { constrain_axis: Key },
 	PointerOutsideViewport { constrain_axis: Key },
 	PointerUp,
+	ReverseStops,
+	RotateQuarterTurn,
 	UpdateOptions(GradientOptionsUpdate),
 }
 
@@ -99,11 +102,37 @@ impl LayoutHolder for GradientTool {
 				.label("Radial")
 				.tooltip("Radial gradient")
 				.on_update(move |_| GradientToolMessage::UpdateOptions(GradientOptionsUpdate::Type(GradientType::Radial)).into()),
+			RadioEntryData::new("Conic")
+				.label("Conic")
+				.tooltip("Conic gradient, sweeping around the start point")
+				.on_update(move |_| GradientToolMessage::UpdateOptions(GradientOptionsUpdate::Type(GradientType::Conic)).into()),
 		])
-		.selected_index(Some((self.selected_gradient().unwrap_or(self.options.gradient_type) == GradientType::Radial) as u32))
+		.selected_index(Some(self.selected_gradient().unwrap_or(self.options.gradient_type) as u32))
 		.widget_holder();
 
-		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets: vec![gradient_type] }]))
+		let reverse = IconButton::new("Reverse", 24)
+			.tooltip("Reverse the order of the gradient's color stops")
+			.on_update(|_| GradientToolMessage::ReverseStops.into())
+			.widget_holder();
+		let rotate = IconButton::new("TurnPositive90", 24)
+			.tooltip("Turn the gradient 90° about its center")
+			.on_update(|_| GradientToolMessage::RotateQuarterTurn.into())
+			.widget_holder();
+		let fit = IconButton::new("FrameSelected", 24)
+			.tooltip("Fit the gradient to the bounding box of the selected layer, keeping its direction")
+			.on_update(|_| GradientToolMessage::FitToSelection.into())
+			.widget_holder();
+
+		let widgets = vec![
+			gradient_type,
+			Separator::new(SeparatorType::Unrelated).widget_holder(),
+			reverse,
+			Separator::new(SeparatorType::Related).widget_holder(),
+			rotate,
+			Separator::new(SeparatorType::Related).widget_holder(),
+			fit,
+		];
+		Layout::WidgetLayout(WidgetLayout::new(vec![LayoutGroup::Row { widgets }]))
 	}
 }
 
@@ -124,12 +153,98 @@ fn gradient_space_transform(layer: LayerNodeIdentifier, document: &DocumentMessa
 	multiplied * bound_transform
 }
 
+/// The viewport position of the midpoint handle between the stops at `index` and `index + 1`.
+fn midpoint_handle_position(gradient: &Gradient, index: usize, start: DVec2, end: DVec2) -> Option<DVec2> {
+	let (left, _) = gradient.stops.get(index)?;
+	let (right, _) = gradient.stops.get(index + 1)?;
+	let position = left + (right - left) * gradient.stops.midpoint(index);
+	Some(gradient.gradient_type.stop_point(position, start, end))
+}
+
+/// Finds the handle of a gradient under the mouse, given the transform from gradient space to viewport space.
+///
+/// The start and end handles take priority over stops, which take priority over midpoints. The focal point of a radial gradient starts out
+/// hidden under the center handle, so it's picked over the center when it's closer to the mouse or when `grab_focal` is set.
+fn gradient_drag_target(gradient: &Gradient, transform: DAffine2, mouse: DVec2, grab_focal: bool) -> Option<GradientDragTarget> {
+	let tolerance = (MANIPULATOR_GROUP_MARKER_SIZE * 2.).powi(2);
+	let (start, end) = (transform.transform_point2(gradient.start), transform.transform_point2(gradient.end));
+
+	if gradient.gradient_type == GradientType::Radial {
+		let focal = transform.transform_point2(gradient.focal_point());
+		if focal.distance_squared(mouse) < tolerance && (grab_focal || focal.distance_squared(mouse) < start.distance_squared(mouse)) {
+			return Some(GradientDragTarget::Focal);
+		}
+	}
+
+	let mut target = None;
+	for index in 0..gradient.stops.len().saturating_sub(1) {
+		if midpoint_handle_position(gradient, index, start, end).is_some_and(|position| position.distance_squared(mouse) < tolerance) {
+			target = Some(GradientDragTarget::Midpoint(index));
+		}
+	}
+	for (index, (position, _)) in gradient.stops.iter().enumerate() {
+		if gradient.gradient_type.stop_point(*position, start, end).distance_squared(mouse) < tolerance {
+			target = Some(GradientDragTarget::Step(index));
+		}
+	}
+	for (position, handle) in [(start, GradientDragTarget::Start), (end, GradientDragTarget::End)] {
+		if position.distance_squared(mouse) < tolerance {
+			target = Some(handle);
+		}
+	}
+	target
+}
+
+/// Applies an operation to the gradient of each selected layer as a single undoable step.
+///
+/// The tool's selected handle is kept if its layer is among them, so the operation can remap it when the stops it refers to are moved.
+fn modify_selected_gradients(document: &DocumentMessageHandler, tool_data: &mut GradientToolData, responses: &mut VecDeque<Message>, operation: impl Fn(&mut SelectedGradient)) {
+	let gradients: Vec<_> = document
+		.network_interface
+		.selected_nodes()
+		.selected_visible_layers(&document.network_interface)
+		.filter_map(|layer| Some((layer, get_gradient(layer, &document.network_interface)?)))
+		.collect();
+	if gradients.is_empty() {
+		return;
+	}
+
+	responses.add(DocumentMessage::AddTransaction);
+
+	for (layer, gradient) in gradients {
+		let mut selected_gradient = SelectedGradient::new(gradient, layer, document);
+		let previously_selected = tool_data.selected_gradient.as_mut().filter(|selected| selected.layer == Some(layer));
+		if let Some(previously_selected) = &previously_selected {
+			selected_gradient.dragging = previously_selected.dragging;
+		}
+
+		operation(&mut selected_gradient);
+		selected_gradient.render_gradient(responses);
+
+		if let Some(previously_selected) = previously_selected {
+			*previously_selected = selected_gradient;
+		}
+	}
+}
+
+/// Draws the diamond-shaped handle used to drag the midpoint between two stops.
+fn draw_midpoint_handle(overlay_context: &mut OverlayContext, position: DVec2, selected: bool) {
+	let radius = MANIPULATOR_GROUP_MARKER_SIZE / 2. + 1.;
+	let diamond = [DVec2::new(0., -radius), DVec2::new(radius, 0.), DVec2::new(0., radius), DVec2::new(-radius, 0.)].map(|offset| position + offset);
+	let fill = if selected { COLOR_OVERLAY_BLUE } else { COLOR_OVERLAY_WHITE };
+	overlay_context.polygon(&diamond, Some(COLOR_OVERLAY_BLUE), Some(fill));
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug, Default)]
 pub enum GradientDragTarget {
 	Start,
 	#[default]
 	End,
 	Step(usize),
+	/// The midpoint between the stops at this index and the next one.
+	Midpoint(usize),
+	/// The focal point of a radial gradient.
+	Focal,
 }
 
 /// Contains information about the selected gradient handle
@@ -187,16 +302,31 @@ impl SelectedGradient {
 			GradientDragTarget::Step(s) => {
 				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
 
-				// Calculate the new position by finding the closest point on the line
-				let new_pos = ((end - start).angle_to(mouse - start)).cos() * start.distance(mouse) / start.distance(end);
+				// Calculate the new position by finding the closest point on the line (or the angle around the start, for conic gradients)
+				let new_pos = self.gradient.gradient_type.position_at(mouse, start, end);
 
 				// Should not go off end but can swap
 				let clamped = new_pos.clamp(0., 1.);
-				self.gradient.stops.get_mut(s).unwrap().0 = clamped;
-				let new_pos = self.gradient.stops[s];
+				let index = self.gradient.stops.set_position(s, clamped);
+				self.dragging = GradientDragTarget::Step(index);
+			}
+			GradientDragTarget::Midpoint(index) => {
+				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
+				let new_pos = self.gradient.gradient_type.position_at(mouse, start, end);
 
-				self.gradient.stops.sort();
-				self.dragging = GradientDragTarget::Step(self.gradient.stops.iter().position(|x| *x == new_pos).unwrap());
+				// Express the position relative to the neighboring stops, which `set_midpoint` keeps the handle between
+				let (Some(&(left, _)), Some(&(right, _))) = (self.gradient.stops.get(index), self.gradient.stops.get(index + 1)) else {
+					return;
+				};
+				if right - left > f64::EPSILON {
+					self.gradient.stops.set_midpoint(index, (new_pos - left) / (right - left));
+				}
+			}
+			GradientDragTarget::Focal => {
+				// Keep the focal point inside the end circle as it's seen in the viewport
+				let (start, end) = (self.transform.transform_point2(self.gradient.start), self.transform.transform_point2(self.gradient.end));
+				let focal_point = Gradient::constrain_focal_point(start, end, mouse);
+				self.gradient.focal_offset = self.transform.inverse().transform_point2(focal_point) - self.gradient.start;
 			}
 		}
 		self.render_gradient(responses);
@@ -267,19 +397,37 @@ impl Fsm for GradientToolFsmState {
 						.filter(|selected| selected.layer.is_some_and(|selected_layer| selected_layer == layer))
 						.map(|selected| selected.dragging);
 
-					let Gradient { start, end, stops, .. } = gradient;
-					let (start, end) = (transform.transform_point2(start), transform.transform_point2(end));
+					let (start, end) = (transform.transform_point2(gradient.start), transform.transform_point2(gradient.end));
 
 					overlay_context.line(start, end, None, None);
+					if gradient.gradient_type == GradientType::Conic {
+						// The stops of a conic gradient sweep around this circle, beginning at the end handle
+						overlay_context.draw_arc(start, start.distance(end), 0., std::f64::consts::TAU);
+					}
 					overlay_context.manipulator_handle(start, dragging == Some(GradientDragTarget::Start), None);
 					overlay_context.manipulator_handle(end, dragging == Some(GradientDragTarget::End), None);
 
-					for (index, (position, _)) in stops.into_iter().enumerate() {
+					for (index, (position, _)) in gradient.stops.iter().enumerate() {
 						if position.abs() < f64::EPSILON * 1000. || (1. - position).abs() < f64::EPSILON * 1000. {
 							continue;
 						}
 
-						overlay_context.manipulator_handle(start.lerp(end, position), dragging == Some(GradientDragTarget::Step(index)), None);
+						let position = gradient.gradient_type.stop_point(*position, start, end);
+						overlay_context.manipulator_handle(position, dragging == Some(GradientDragTarget::Step(index)), None);
+					}
+
+					for index in 0..gradient.stops.len().saturating_sub(1) {
+						let Some(position) = midpoint_handle_position(&gradient, index, start, end) else { continue };
+						draw_midpoint_handle(&mut overlay_context, position, dragging == Some(GradientDragTarget::Midpoint(index)));
+					}
+
+					if gradient.gradient_type == GradientType::Radial {
+						let focal = transform.transform_point2(gradient.focal_point());
+						if focal.distance_squared(start) > f64::EPSILON {
+							overlay_context.dashed_line(start, focal, None, None, Some(2.), Some(2.), None);
+						}
+						let fill = if dragging == Some(GradientDragTarget::Focal) { COLOR_OVERLAY_BLUE } else { COLOR_OVERLAY_WHITE };
+						overlay_context.circle(focal, MANIPULATOR_GROUP_MARKER_SIZE / 4., Some(fill), Some(COLOR_OVERLAY_BLUE));
 					}
 				}
 
@@ -300,13 +448,26 @@ impl Fsm for GradientToolFsmState {
 				// Remove the selected point
 				match selected_gradient.dragging {
 					GradientDragTarget::Start => {
-						selected_gradient.gradient.stops.remove(0);
+						selected_gradient.gradient.stops.remove_stop(0);
 					}
 					GradientDragTarget::End => {
-						let _ = selected_gradient.gradient.stops.pop();
+						let last_index = selected_gradient.gradient.stops.len() - 1;
+						selected_gradient.gradient.stops.remove_stop(last_index);
 					}
 					GradientDragTarget::Step(index) => {
-						selected_gradient.gradient.stops.remove(index);
+						selected_gradient.gradient.stops.remove_stop(index);
+					}
+					// Deleting a midpoint resets it to evenly interpolate between its stops
+					GradientDragTarget::Midpoint(index) => {
+						selected_gradient.gradient.stops.set_midpoint(index, DEFAULT_GRADIENT_MIDPOINT);
+						selected_gradient.render_gradient(responses);
+						return self;
+					}
+					// Deleting the focal point returns it to the center
+					GradientDragTarget::Focal => {
+						selected_gradient.gradient.focal_offset = DVec2::ZERO;
+						selected_gradient.render_gradient(responses);
+						return self;
 					}
 				};
 
@@ -321,6 +482,12 @@ impl Fsm for GradientToolFsmState {
 					return self;
 				}
 
+				// The stops of a conic gradient go all the way around, so there are no ends to move in to meet the remaining stops
+				if selected_gradient.gradient.gradient_type == GradientType::Conic {
+					selected_gradient.render_gradient(responses);
+					return self;
+				}
+
 				// Find the minimum and maximum positions
 				let min_position = selected_gradient.gradient.stops.iter().map(|(pos, _)| *pos).reduce(f64::min).expect("No min");
 				let max_position = selected_gradient.gradient.stops.iter().map(|(pos, _)| *pos).reduce(f64::max).expect("No max");
@@ -333,15 +500,43 @@ impl Fsm for GradientToolFsmState {
 				selected_gradient.gradient.end = transform.inverse().transform_point2(new_end);
 
 				// Remap the positions
-				for (position, _) in selected_gradient.gradient.stops.iter_mut() {
-					*position = (*position - min_position) / (max_position - min_position);
-				}
+				selected_gradient.gradient.stops = selected_gradient.gradient.stops.map_positions(|position| (position - min_position) / (max_position - min_position));
 
 				// Render the new gradient
 				selected_gradient.render_gradient(responses);
 
 				self
 			}
+			(GradientToolFsmState::Ready, GradientToolMessage::ReverseStops) => {
+				modify_selected_gradients(document, tool_data, responses, |selected_gradient| {
+					let last_index = selected_gradient.gradient.stops.len().saturating_sub(1);
+					selected_gradient.gradient.reverse();
+
+					// Keep the same stop selected now that it's at the mirrored index
+					selected_gradient.dragging = match selected_gradient.dragging {
+						GradientDragTarget::Step(index) => GradientDragTarget::Step(last_index.saturating_sub(index)),
+						GradientDragTarget::Midpoint(index) => GradientDragTarget::Midpoint(last_index.saturating_sub(index + 1)),
+						dragging => dragging,
+					};
+				});
+
+				self
+			}
+			(GradientToolFsmState::Ready, GradientToolMessage::RotateQuarterTurn) => {
+				modify_selected_gradients(document, tool_data, responses, |selected_gradient| {
+					selected_gradient.gradient.rotate_quarter_turn(selected_gradient.transform)
+				});
+
+				self
+			}
+			(GradientToolFsmState::Ready, GradientToolMessage::FitToSelection) => {
+				// Gradient space spans the layer's bounding box from (0, 0) to (1, 1)
+				modify_selected_gradients(document, tool_data, responses, |selected_gradient| {
+					selected_gradient.gradient.fit_to_bounds([DVec2::ZERO, DVec2::ONE], selected_gradient.transform)
+				});
+
+				self
+			}
 			(_, GradientToolMessage::InsertStop) => {
 				for layer in document.network_interface.selected_nodes().selected_visible_layers(&document.network_interface) {
 					let Some(mut gradient) = get_gradient(layer, &document.network_interface) else { continue };
@@ -350,8 +545,11 @@ impl Fsm for GradientToolFsmState {
 					let mouse = input.mouse.position;
 					let (start, end) = (transform.transform_point2(gradient.start), transform.transform_point2(gradient.end));
 
-					// Compute the distance from the mouse to the gradient line in viewport space
-					let distance = (end - start).angle_to(mouse - start).sin() * (mouse - start).length();
+					// Compute the distance from the mouse to the gradient line (or the circle of a conic gradient) in viewport space
+					let distance = match gradient.gradient_type {
+						GradientType::Conic => (mouse.distance(start) - end.distance(start)).abs(),
+						_ => (end - start).angle_to(mouse - start).sin() * (mouse - start).length(),
+					};
 
 					// If click is on the line then insert point
 					if distance < (SELECTION_THRESHOLD * 2.) {
@@ -379,38 +577,21 @@ impl Fsm for GradientToolFsmState {
 			(GradientToolFsmState::Ready, GradientToolMessage::PointerDown) => {
 				let mouse = input.mouse.position;
 				tool_data.drag_start = mouse;
-				let tolerance = (MANIPULATOR_GROUP_MARKER_SIZE * 2.).powi(2);
+				let grab_focal = input.keyboard.get(Key::Alt as usize);
 
 				let mut dragging = false;
 				for layer in document.network_interface.selected_nodes().selected_visible_layers(&document.network_interface) {
 					let Some(gradient) = get_gradient(layer, &document.network_interface) else { continue };
 					let transform = gradient_space_transform(layer, document);
-					// Check for dragging step
-					for (index, (pos, _)) in gradient.stops.iter().enumerate() {
-						let pos = transform.transform_point2(gradient.start.lerp(gradient.end, *pos));
-						if pos.distance_squared(mouse) < tolerance {
-							dragging = true;
-							tool_data.selected_gradient = Some(SelectedGradient {
-								layer: Some(layer),
-								transform,
-								gradient: gradient.clone(),
-								dragging: GradientDragTarget::Step(index),
-							})
-						}
-					}
 
-					// Check dragging start or end handle
-					for (pos, dragging_target) in [(gradient.start, GradientDragTarget::Start), (gradient.end, GradientDragTarget::End)] {
-						let pos = transform.transform_point2(pos);
-						if pos.distance_squared(mouse) < tolerance {
-							dragging = true;
-							tool_data.selected_gradient = Some(SelectedGradient {
-								layer: Some(layer),
-								transform,
-								gradient: gradient.clone(),
-								dragging: dragging_target,
-							})
-						}
+					if let Some(target) = gradient_drag_target(&gradient, transform, mouse, grab_focal) {
+						dragging = true;
+						tool_data.selected_gradient = Some(SelectedGradient {
+							layer: Some(layer),
+							transform,
+							gradient,
+							dragging: target,
+						})
 					}
 				}
 
@@ -521,10 +702,13 @@ impl Fsm for GradientToolFsmState {
 
 	fn update_hints(&self, responses: &mut VecDeque<Message>) {
 		let hint_data = match self {
-			GradientToolFsmState::Ready => HintData(vec![HintGroup(vec![
-				HintInfo::mouse(MouseMotion::LmbDrag, "Draw Gradient"),
-				HintInfo::keys([Key::Shift], "15° Increments").prepend_plus(),
-			])]),
+			GradientToolFsmState::Ready => HintData(vec![
+				HintGroup(vec![
+					HintInfo::mouse(MouseMotion::LmbDrag, "Draw Gradient"),
+					HintInfo::keys([Key::Shift], "15° Increments").prepend_plus(),
+				]),
+				HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::LmbDrag, "Move Radial Focal Point")]),
+			]),
 			GradientToolFsmState::Drawing => HintData(vec![
 				HintGroup(vec![HintInfo::mouse(MouseMotion::Rmb, ""), HintInfo::keys([Key::Escape], "Cancel").prepend_slash()]),
 				HintGroup(vec![HintInfo::keys([Key::Shift], "15° Increments")]),
@@ -551,7 +735,7 @@ mod test_gradient {
 	use graphene_std::vector::style::Fill;
 	use graphene_std::vector::style::Gradient;
 
-	use super::gradient_space_transform;
+	use super::{GradientDragTarget, GradientToolMessage, gradient_drag_target, gradient_space_transform};
 
 	async fn get_fills(editor: &mut EditorTestUtils) -> Vec<(Fill, DAffine2)> {
 		let instrumented = match editor.eval_graph().await {
@@ -807,7 +991,7 @@ mod test_gradient {
 		assert_eq!(initial_gradient.stops.len(), 3, "Expected 3 stops, found {}", initial_gradient.stops.len());
 
 		// Verify initial stop positions and colors
-		let mut stops = initial_gradient.stops.clone();
+		let mut stops = initial_gradient.stops.color_stops.clone();
 		stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
 		let positions: Vec<f64> = stops.iter().map(|(pos, _)| *pos).collect();
@@ -846,7 +1030,7 @@ mod test_gradient {
 		assert_eq!(updated_gradient.stops.len(), 3, "Expected 3 stops after dragging, found {}", updated_gradient.stops.len());
 
 		// Verify updated stop positions and colors
-		let mut updated_stops = updated_gradient.stops.clone();
+		let mut updated_stops = updated_gradient.stops.color_stops.clone();
 		updated_stops.sort_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
 
 		// Check positions are now correctly ordered
@@ -916,4 +1100,81 @@ mod test_gradient {
 		// Additional verification that 0.75 stop is gone
 		assert!(!final_positions.iter().any(|pos| (pos - 0.75).abs() < 0.05), "Stop at position 0.75 should have been deleted");
 	}
+
+	#[tokio::test]
+	async fn gradient_operations_through_tool_options() {
+		let mut editor = EditorTestUtils::create();
+		editor.new_document().await;
+
+		editor.drag_tool(ToolType::Rectangle, -5., -3., 100., 100., ModifierKeys::empty()).await;
+		editor.select_primary_color(Color::GREEN).await;
+		editor.select_secondary_color(Color::BLUE).await;
+		editor.drag_tool(ToolType::Gradient, 10., 20., 30., 20., ModifierKeys::empty()).await;
+
+		// Fitting spans the rectangle horizontally, through its center
+		editor.handle_message(GradientToolMessage::FitToSelection).await;
+		let (fitted, transform) = get_gradient(&mut editor).await;
+		assert!(transform.transform_point2(fitted.start).abs_diff_eq(DVec2::new(-5., 48.5), 1e-10));
+		assert!(transform.transform_point2(fitted.end).abs_diff_eq(DVec2::new(100., 48.5), 1e-10));
+
+		// Turning about the midpoint makes the gradient run downwards
+		editor.handle_message(GradientToolMessage::RotateQuarterTurn).await;
+		let (rotated, transform) = get_gradient(&mut editor).await;
+		assert!(transform.transform_point2(rotated.start).abs_diff_eq(DVec2::new(47.5, -4.), 1e-10));
+		assert!(transform.transform_point2(rotated.end).abs_diff_eq(DVec2::new(47.5, 101.), 1e-10));
+
+		editor.handle_message(GradientToolMessage::ReverseStops).await;
+		let (reversed, _) = get_gradient(&mut editor).await;
+		let stops = reversed.stops.iter().map(|stop| (stop.0, stop.1.to_rgba8_srgb())).collect::<Vec<_>>();
+		assert_eq!(stops, vec![(0., Color::GREEN.to_rgba8_srgb()), (1., Color::BLUE.to_rgba8_srgb())]);
+
+		// Each operation is its own undo step
+		editor.handle_message(DocumentMessage::Undo).await;
+		let (undone, _) = get_gradient(&mut editor).await;
+		assert_eq!(undone.stops, rotated.stops);
+		assert_eq!((undone.start, undone.end), (rotated.start, rotated.end));
+
+		editor.handle_message(DocumentMessage::Undo).await;
+		let (undone, _) = get_gradient(&mut editor).await;
+		assert_eq!((undone.start, undone.end), (fitted.start, fitted.end));
+	}
+
+	fn radial_gradient(focal_offset: DVec2) -> Gradient {
+		Gradient {
+			gradient_type: graphene_std::vector::style::GradientType::Radial,
+			start: DVec2::new(0.5, 0.5),
+			end: DVec2::new(1., 0.5),
+			focal_offset,
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn centered_focal_point_hides_under_center_handle() {
+		let gradient = radial_gradient(DVec2::ZERO);
+		let transform = DAffine2::from_scale(DVec2::splat(200.));
+
+		assert_eq!(gradient_drag_target(&gradient, transform, DVec2::new(100., 100.), false), Some(GradientDragTarget::Start));
+		assert_eq!(gradient_drag_target(&gradient, transform, DVec2::new(100., 100.), true), Some(GradientDragTarget::Focal));
+	}
+
+	#[test]
+	fn moved_focal_point_handle_is_hit() {
+		let gradient = radial_gradient(DVec2::new(0.2, 0.));
+		let transform = DAffine2::from_scale(DVec2::splat(200.));
+
+		// The focal point is 40 pixels to the right of the center
+		assert_eq!(gradient_drag_target(&gradient, transform, DVec2::new(141., 101.), false), Some(GradientDragTarget::Focal));
+		assert_eq!(gradient_drag_target(&gradient, transform, DVec2::new(100., 100.), false), Some(GradientDragTarget::Start));
+		assert_eq!(gradient_drag_target(&gradient, transform, DVec2::new(140., 160.), false), None);
+	}
+
+	#[test]
+	fn focal_point_handle_only_on_radial_gradients() {
+		let mut gradient = radial_gradient(DVec2::new(0.2, 0.));
+		gradient.gradient_type = graphene_std::vector::style::GradientType::Linear;
+		let transform = DAffine2::from_scale(DVec2::splat(200.));
+
+		assert_ne!(gradient_drag_target(&gradient, transform, DVec2::new(140., 100.), true), Some(GradientDragTarget::Focal));
+	}
 }
@@ -71,6 +71,7 @@ pub enum PathToolMessage {
 		// Should be `Key::KeyG` (Grab), `Key::KeyR` (Rotate), or `Key::KeyS` (Scale)
 		key: Key,
 	},
+	InsertPointsAtMidpoints,
 	ManipulatorMakeHandlesFree,
 	ManipulatorMakeHandlesColinear,
 	MouseDown {
@@ -103,6 +104,7 @@ pub enum PathToolMessage {
 		break_colinear_molding: Key,
 	},
 	RightClick,
+	RoundSelectedAnchors,
 	SelectAllAnchors,
 	SelectedPointUpdated,
 	SelectedPointXChanged {
@@ -274,6 +276,18 @@ impl LayoutHolder for PathTool {
 			.disabled(!self.tool_data.single_path_node_compatible_layer_selected)
 			.widget_holder();
 
+		let round_anchors_button = TextButton::new("Round Selected Anchors")
+			.tooltip("Round the corners at the selected anchors with a Round Corners node, whose radius can be changed in the Properties panel")
+			.on_update(|_| PathToolMessage::RoundSelectedAnchors.into())
+			.disabled(matches!(selection_status, SelectionStatus::None))
+			.widget_holder();
+
+		let insert_midpoints_button = TextButton::new("Insert Midpoints")
+			.tooltip("Split each selected segment in half with a new anchor")
+			.on_update(|_| PathToolMessage::InsertPointsAtMidpoints.into())
+			.disabled(!self.tool_data.segments_selected)
+			.widget_holder();
+
 		let [_checkbox, _dropdown] = {
 			let pivot_gizmo_type_widget = pivot_gizmo_type_widget(self.tool_data.pivot_gizmo.state, PivotToolSource::Path);
 			[pivot_gizmo_type_widget[0].clone(), pivot_gizmo_type_widget[2].clone()]
@@ -305,6 +319,10 @@ impl LayoutHolder for PathTool {
 				path_overlay_mode_widget,
 				unrelated_seperator.clone(),
 				path_node_button,
+				related_seperator.clone(),
+				round_anchors_button,
+				related_seperator.clone(),
+				insert_midpoints_button,
 				// checkbox.clone(),
 				// related_seperator.clone(),
 				// dropdown.clone(),
@@ -366,6 +384,30 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Path
 				responses.add(DocumentMessage::EndTransaction);
 				responses.add(OverlaysMessage::Draw);
 			}
+			ToolMessage::Path(PathToolMessage::RoundSelectedAnchors) => {
+				let selected_anchors: Vec<_> = context
+					.shape_editor
+					.selected_shape_state
+					.iter()
+					.map(|(&layer, state)| (layer, state.selected_points().filter_map(|point| point.as_anchor()).collect::<Vec<_>>()))
+					.filter(|(_, anchors)| !anchors.is_empty())
+					.collect();
+				if !selected_anchors.is_empty() {
+					responses.add(DocumentMessage::AddTransaction);
+					for (layer, anchors) in selected_anchors {
+						responses.add(GraphOperationMessage::RoundCornersSet { layer, anchors });
+					}
+					responses.add(OverlaysMessage::Draw);
+				}
+			}
+			ToolMessage::Path(PathToolMessage::InsertPointsAtMidpoints) => {
+				if context.shape_editor.selected_segments().next().is_some() {
+					responses.add(DocumentMessage::AddTransaction);
+					context.shape_editor.insert_points_at_selected_segment_midpoints(context.document, responses);
+					responses.add(PathToolMessage::SelectedPointUpdated);
+					responses.add(OverlaysMessage::Draw);
+				}
+			}
 			ToolMessage::Path(PathToolMessage::SwapSelectedHandles) => {
 				if context.shape_editor.handle_with_pair_selected(&context.document.network_interface) {
 					context.shape_editor.alternate_selected_handles(&context.document.network_interface);
@@ -534,6 +576,7 @@ struct PathToolData {
 	hovered_layers: Vec<LayerNodeIdentifier>,
 	ghost_outline: Vec<(Vec<ClickTargetType>, LayerNodeIdentifier)>,
 	single_path_node_compatible_layer_selected: bool,
+	segments_selected: bool,
 }
 
 impl PathToolData {
@@ -688,14 +731,7 @@ impl PathToolData {
 			// If the point is already selected and shift (`extend_selection`) is used, keep the selection unchanged.
 			// Otherwise, select the first point within the threshold.
 			if !(already_selected && extend_selection) {
-				if let Some(updated_selection_info) = shape_editor.change_point_selection(
-					&document.network_interface,
-					input.mouse.position,
-					SELECTION_THRESHOLD,
-					extend_selection,
-					path_overlay_mode,
-					self.frontier_handles_info.clone(),
-				) {
+				if let Some(updated_selection_info) = shape_editor.change_point_selection(&document.network_interface, &document.overlays_message_handler, input.mouse.position, extend_selection) {
 					selection_info = updated_selection_info;
 				}
 			}
@@ -1130,10 +1166,10 @@ impl PathToolData {
 		tangent_vector.try_normalize()
 	}
 
-	fn update_closest_segment(&mut self, shape_editor: &mut ShapeState, position: DVec2, document: &DocumentMessageHandler, path_overlay_mode: PathOverlayMode) {
+	fn update_closest_segment(&mut self, shape_editor: &mut ShapeState, position: DVec2, document: &DocumentMessageHandler) {
 		// Check if there is no point nearby
 		if shape_editor
-			.find_nearest_visible_point_indices(&document.network_interface, position, SELECTION_THRESHOLD, path_overlay_mode, self.frontier_handles_info.clone())
+			.find_visible_point_indices(&document.network_interface, &document.overlays_message_handler, position)
 			.is_some()
 		{
 			self.segment = None;
@@ -1563,7 +1599,7 @@ impl Fsm for PathToolFsmState {
 
 				match self {
 					Self::Ready => {
-						tool_data.update_closest_segment(shape_editor, input.mouse.position, document, tool_options.path_overlay_mode);
+						tool_data.update_closest_segment(shape_editor, input.mouse.position, document);
 
 						if let Some(closest_segment) = &tool_data.segment {
 							if tool_options.path_editing_mode.segment_editing_mode {
@@ -2118,13 +2154,7 @@ impl Fsm for PathToolFsmState {
 				let extend_selection = input.keyboard.get(extend_selection as usize);
 				let drag_occurred = tool_data.drag_start_pos.distance(input.mouse.position) > DRAG_THRESHOLD;
 
-				let nearest_point = shape_editor.find_nearest_visible_point_indices(
-					&document.network_interface,
-					input.mouse.position,
-					SELECTION_THRESHOLD,
-					tool_options.path_overlay_mode,
-					tool_data.frontier_handles_info.clone(),
-				);
+				let nearest_point = shape_editor.find_visible_point_indices(&document.network_interface, &document.overlays_message_handler, input.mouse.position);
 
 				let nearest_segment = tool_data.segment.clone();
 
@@ -2430,6 +2460,7 @@ impl Fsm for PathToolFsmState {
 
 					first_layer.is_some() && has_single_selection && is_compatible && !is_modifiable
 				};
+				tool_data.segments_selected = shape_editor.selected_segments().next().is_some();
 				tool_data.update_selection_status(shape_editor, document);
 				self
 			}
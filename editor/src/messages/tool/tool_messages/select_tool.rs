@@ -3,10 +3,11 @@
 use super::tool_prelude::*;
 use crate::consts::*;
 use crate::messages::input_mapper::utility_types::input_mouse::ViewportPosition;
+use crate::messages::portfolio::document::CLICK_THROUGH_LOCKS_KEY;
 use crate::messages::portfolio::document::graph_operation::utility_types::TransformIn;
 use crate::messages::portfolio::document::overlays::utility_types::OverlayContext;
 use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
-use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, FlipAxis, GroupFolderType};
+use crate::messages::portfolio::document::utility_types::misc::{AlignAggregate, AlignAxis, AlignTarget, DistributeMode, FlipAxis, GroupFolderType};
 use crate::messages::portfolio::document::utility_types::network_interface::{FlowType, NodeNetworkInterface, NodeTemplate};
 use crate::messages::portfolio::document::utility_types::nodes::SelectedNodes;
 use crate::messages::preferences::SelectionMode;
@@ -47,6 +48,8 @@ pub enum SelectOptionsUpdate {
 	PivotGizmoType(PivotGizmoType),
 	TogglePivotGizmoType(bool),
 	TogglePivotPinned,
+	AlignTarget(AlignTarget),
+	DistributeMode(DistributeMode),
 }
 
 #[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, specta::Type)]
@@ -144,6 +147,7 @@ impl SelectTool {
 	}
 
 	fn alignment_widgets(&self, disabled: bool) -> impl Iterator<Item = WidgetHolder> + use<> {
+		let target = self.tool_data.align_target;
 		[AlignAxis::X, AlignAxis::Y]
 			.into_iter()
 			.flat_map(|axis| [(axis, AlignAggregate::Min), (axis, AlignAggregate::Center), (axis, AlignAggregate::Max)])
@@ -158,12 +162,70 @@ impl SelectTool {
 				};
 				IconButton::new(icon, 24)
 					.tooltip(tooltip)
-					.on_update(move |_| DocumentMessage::AlignSelectedLayers { axis, aggregate }.into())
+					.on_update(move |_| DocumentMessage::AlignSelectedLayers { axis, aggregate, target }.into())
 					.disabled(disabled)
 					.widget_holder()
 			})
 	}
 
+	fn distribution_widgets(&self, disabled: bool) -> impl Iterator<Item = WidgetHolder> + use<> {
+		let mode = self.tool_data.distribute_mode;
+		[
+			(AlignAxis::X, "DistributeHorizontal", "Distribute Horizontally"),
+			(AlignAxis::Y, "DistributeVertical", "Distribute Vertically"),
+		]
+		.into_iter()
+		.map(move |(axis, icon, tooltip)| {
+			IconButton::new(icon, 24)
+				.tooltip(tooltip)
+				.on_update(move |_| DocumentMessage::DistributeSelectedLayers { axis, mode }.into())
+				.disabled(disabled)
+				.widget_holder()
+		})
+	}
+
+	fn alignment_options_layout(&self) -> Vec<LayoutGroup> {
+		let target_entries = [(AlignTarget::Selection, "Selection"), (AlignTarget::Artboard, "Artboard")]
+			.into_iter()
+			.map(|(target, label)| {
+				RadioEntryData::new(format!("{target:?}"))
+					.label(label)
+					.on_update(move |_| SelectToolMessage::SelectOptions(SelectOptionsUpdate::AlignTarget(target)).into())
+			})
+			.collect();
+		let mode_entries = [(DistributeMode::Spacing, "Spacing"), (DistributeMode::Centers, "Centers")]
+			.into_iter()
+			.map(|(mode, label)| {
+				RadioEntryData::new(format!("{mode:?}"))
+					.label(label)
+					.on_update(move |_| SelectToolMessage::SelectOptions(SelectOptionsUpdate::DistributeMode(mode)).into())
+			})
+			.collect();
+
+		vec![
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new("Align To").bold(true).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					RadioInput::new(target_entries)
+						.selected_index(Some((self.tool_data.align_target == AlignTarget::Artboard) as u32))
+						.widget_holder(),
+				],
+			},
+			LayoutGroup::Row {
+				widgets: vec![TextLabel::new("Distribute By").bold(true).widget_holder()],
+			},
+			LayoutGroup::Row {
+				widgets: vec![
+					RadioInput::new(mode_entries)
+						.selected_index(Some((self.tool_data.distribute_mode == DistributeMode::Centers) as u32))
+						.widget_holder(),
+				],
+			},
+		]
+	}
+
 	fn flip_widgets(&self, disabled: bool) -> impl Iterator<Item = WidgetHolder> + use<> {
 		[(FlipAxis::X, "Horizontal"), (FlipAxis::Y, "Vertical")].into_iter().map(move |(flip_axis, name)| {
 			IconButton::new("Flip".to_string() + name, 24)
@@ -238,22 +300,24 @@ impl LayoutHolder for SelectTool {
 		}
 
 		// Align
-		let disabled = self.tool_data.selected_layers_count < 2;
+		// A single layer can still be aligned to its artboard
+		let disabled = match self.tool_data.align_target {
+			AlignTarget::Selection => self.tool_data.selected_layers_count < 2,
+			AlignTarget::Artboard => self.tool_data.selected_layers_count == 0,
+		};
 		widgets.push(Separator::new(SeparatorType::Unrelated).widget_holder());
 		widgets.extend(self.alignment_widgets(disabled));
-		// widgets.push(
-		// 	PopoverButton::new()
-		// 		.popover_layout(vec![
-		// 			LayoutGroup::Row {
-		// 				widgets: vec![TextLabel::new("Align").bold(true).widget_holder()],
-		// 			},
-		// 			LayoutGroup::Row {
-		// 				widgets: vec![TextLabel::new("Coming soon").widget_holder()],
-		// 			},
-		// 		])
-		// 		.disabled(disabled)
-		// 		.widget_holder(),
-		// );
+
+		// Distribute
+		let disabled = self.tool_data.selected_layers_count < 3;
+		widgets.push(Separator::new(SeparatorType::Related).widget_holder());
+		widgets.extend(self.distribution_widgets(disabled));
+		widgets.push(
+			PopoverButton::new()
+				.popover_layout(self.alignment_options_layout())
+				.tooltip("Align and Distribute Options")
+				.widget_holder(),
+		);
 
 		// Flip
 		let disabled = self.tool_data.selected_layers_count == 0;
@@ -306,6 +370,14 @@ impl<'a> MessageHandler<ToolMessage, &mut ToolActionMessageContext<'a>> for Sele
 					responses.add(NodeGraphMessage::RunDocumentGraph);
 					redraw_reference_pivot = true;
 				}
+				SelectOptionsUpdate::AlignTarget(target) => {
+					self.tool_data.align_target = *target;
+					self.send_layout(responses, LayoutTarget::ToolOptions);
+				}
+				SelectOptionsUpdate::DistributeMode(mode) => {
+					self.tool_data.distribute_mode = *mode;
+					self.send_layout(responses, LayoutTarget::ToolOptions);
+				}
 			}
 		}
 
@@ -399,6 +471,8 @@ struct SelectToolData {
 	line_center: DVec2,
 	skew_edge: EdgeBool,
 	nested_selection_behavior: NestedSelectionBehavior,
+	align_target: AlignTarget,
+	distribute_mode: DistributeMode,
 	selected_layers_count: usize,
 	selected_layers_changed: bool,
 	snap_candidates: Vec<SnapCandidatePoint>,
@@ -1674,6 +1748,7 @@ impl Fsm for SelectToolFsmState {
 						if *selection == NestedSelectionBehavior::Shallowest {
 							hints.extend([HintInfo::keys([Key::Accel], "Deepest").prepend_plus(), HintInfo::mouse(MouseMotion::LmbDouble, "Deepen")]);
 						}
+						hints.push(HintInfo::keys([CLICK_THROUGH_LOCKS_KEY], "Through Locks").prepend_plus());
 						hints
 					}),
 					HintGroup(vec![
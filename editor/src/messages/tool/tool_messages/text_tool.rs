@@ -31,6 +31,7 @@ pub struct TextOptions {
 	font_size: f64,
 	line_height_ratio: f64,
 	character_spacing: f64,
+	word_spacing: f64,
 	font_name: String,
 	font_style: String,
 	fill: ToolColorOptions,
@@ -44,6 +45,7 @@ impl Default for TextOptions {
 			font_size: 24.,
 			line_height_ratio: 1.2,
 			character_spacing: 0.,
+			word_spacing: 0.,
 			font_name: graphene_std::consts::DEFAULT_FONT_FAMILY.into(),
 			font_style: graphene_std::consts::DEFAULT_FONT_STYLE.into(),
 			fill: ToolColorOptions::new_primary(),
@@ -265,6 +267,8 @@ pub struct EditingText {
 	typesetting: TypesettingConfig,
 	color: Option<Color>,
 	transform: DAffine2,
+	/// The layer whose path new text flows along.
+	on_path: Option<LayerNodeIdentifier>,
 }
 
 #[derive(Clone, Debug, Copy)]
@@ -310,7 +314,9 @@ impl TextToolData {
 		if let Some(editing_text) = self.editing_text.as_ref().filter(|_| editable) {
 			responses.add(FrontendMessage::DisplayEditableTextbox {
 				text: editing_text.text.clone(),
-				line_height_ratio: editing_text.typesetting.line_height_ratio,
+				line_height: editing_text.typesetting.line_height(),
+				character_spacing: editing_text.typesetting.character_spacing,
+				word_spacing: editing_text.typesetting.word_spacing,
 				font_size: editing_text.typesetting.font_size,
 				color: editing_text.color.unwrap_or(Color::BLACK),
 				url: font_cache.get_preview_url(&editing_text.font).cloned().unwrap_or_default(),
@@ -340,6 +346,7 @@ impl TextToolData {
 			typesetting,
 			color: Some(color),
 			transform,
+			on_path: None,
 		});
 		self.new_text.clone_from(text);
 		Some(())
@@ -377,12 +384,18 @@ impl TextToolData {
 
 		self.layer = LayerNodeIdentifier::new_unchecked(NodeId::new());
 
+		// Text flowing along a path goes beside the path's layer, so they share the same coordinate space
+		let parent = match editing_text.on_path {
+			Some(path_layer) => path_layer.parent(document.metadata()).unwrap_or(LayerNodeIdentifier::ROOT_PARENT),
+			None => document.new_layer_parent(true),
+		};
 		responses.add(GraphOperationMessage::NewTextLayer {
 			id: self.layer.to_node(),
 			text: String::new(),
 			font: editing_text.font.clone(),
 			typesetting: editing_text.typesetting,
-			parent: document.new_layer_parent(true),
+			on_path: editing_text.on_path,
+			parent,
 			insert_index: 0,
 		});
 		responses.add(DeferMessage::AfterGraphRun {
@@ -427,6 +440,19 @@ impl TextToolData {
 			})
 	}
 
+	/// Finds the clicked layer with a path that new text can flow along.
+	fn check_click_on_path(document: &DocumentMessageHandler, input: &InputPreprocessorMessageHandler) -> Option<LayerNodeIdentifier> {
+		document
+			.click(input)
+			.filter(|&layer| !is_layer_fed_by_node_of_name(layer, &document.network_interface, "Text"))
+			.filter(|&layer| {
+				document
+					.network_interface
+					.compute_modified_vector(layer)
+					.is_some_and(|vector_data| !vector_data.segment_domain.ids().is_empty())
+			})
+	}
+
 	fn get_snap_candidates(&mut self, document: &DocumentMessageHandler, font_cache: &FontCache) {
 		self.snap_candidates.clear();
 
@@ -486,7 +512,7 @@ impl Fsm for TextToolFsmState {
 				});
 				if let Some(editing_text) = tool_data.editing_text.as_mut() {
 					let font_data = font_cache.get(&editing_text.font).map(|data| load_font(data));
-					let far = graphene_std::text::bounding_box(&tool_data.new_text, font_data, editing_text.typesetting, false);
+					let far = graphene_std::text::bounding_box(&tool_data.new_text, font_data, &editing_text.font.variations, editing_text.typesetting, false);
 					if far.x != 0. && far.y != 0. {
 						let quad = Quad::from_box([DVec2::ZERO, far]);
 						let transformed_quad = document.metadata().transform_to_viewport(tool_data.layer) * quad;
@@ -535,7 +561,7 @@ impl Fsm for TextToolFsmState {
 						let transformed_quad = layer_transform * bounds;
 						if let Some((text, font, typesetting, _)) = graph_modification_utils::get_text(layer.unwrap(), &document.network_interface) {
 							let font_data = font_cache.get(font).map(|data| load_font(data));
-							if lines_clipping(text.as_str(), font_data, typesetting) {
+							if lines_clipping(text.as_str(), font_data, &font.variations, typesetting) {
 								overlay_context.line(transformed_quad.0[2], transformed_quad.0[3], Some(COLOR_OVERLAY_RED), Some(3.));
 							}
 						}
@@ -789,19 +815,34 @@ impl Fsm for TextToolFsmState {
 					}
 				}
 
+				// Alt-clicking a path creates text that flows along it, positioned by the path instead of the click
+				let on_path = (!has_dragged && input.keyboard.get(Key::Alt as usize))
+					.then(|| TextToolData::check_click_on_path(document, input))
+					.flatten();
+				let transform = match on_path {
+					Some(path_layer) => document
+						.metadata()
+						.transform_to_viewport(path_layer.parent(document.metadata()).unwrap_or(LayerNodeIdentifier::ROOT_PARENT)),
+					None => DAffine2::from_translation(start),
+				};
+
 				// Otherwise create some new text
 				let constraint_size = has_dragged.then_some((start - end).abs());
 				let editing_text = EditingText {
 					text: String::new(),
-					transform: DAffine2::from_translation(start),
+					transform,
+					on_path,
 					typesetting: TypesettingConfig {
 						font_size: tool_options.font_size,
 						line_height_ratio: tool_options.line_height_ratio,
+						absolute_line_height: None,
 						max_width: constraint_size.map(|size| size.x),
 						character_spacing: tool_options.character_spacing,
+						word_spacing: tool_options.word_spacing,
 						max_height: constraint_size.map(|size| size.y),
 						tilt: tool_options.tilt,
 						align: tool_options.align,
+						justify_letters: false,
 					},
 					font: Font::new(tool_options.font_name.clone(), tool_options.font_style.clone()),
 					color: tool_options.fill.active_color(),
@@ -901,6 +942,7 @@ impl Fsm for TextToolFsmState {
 					HintInfo::keys([Key::Shift], "Constrain Square").prepend_plus(),
 					HintInfo::keys([Key::Alt], "From Center").prepend_plus(),
 				]),
+				HintGroup(vec![HintInfo::keys_and_mouse([Key::Alt], MouseMotion::Lmb, "Place Text on Path")]),
 				HintGroup(vec![HintInfo::mouse(MouseMotion::Lmb, "Edit Text")]),
 			]),
 			TextToolFsmState::Editing => HintData(vec![HintGroup(vec![
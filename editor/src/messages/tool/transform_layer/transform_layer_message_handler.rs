@@ -1,12 +1,13 @@
 use crate::consts::{ANGLE_MEASURE_RADIUS_FACTOR, ARC_MEASURE_RADIUS_FACTOR_RANGE, COLOR_OVERLAY_BLUE, COLOR_OVERLAY_GRAY, SLOWING_DIVISOR};
 use crate::messages::input_mapper::utility_types::input_mouse::{DocumentPosition, ViewportPosition};
-use crate::messages::portfolio::document::overlays::utility_types::{OverlayProvider, Pivot};
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayProviderHandle, OverlayProviderOwner, Pivot};
 use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use crate::messages::portfolio::document::utility_types::misc::PTZ;
 use crate::messages::portfolio::document::utility_types::transformation::{Axis, OriginalTransforms, Selected, TransformOperation, TransformType, Typing};
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::pivot::{PivotGizmo, PivotGizmoType};
 use crate::messages::tool::common_functionality::shape_editor::ShapeState;
+use crate::messages::tool::common_functionality::utility_functions::send_locked_layers_hint;
 use crate::messages::tool::tool_messages::tool_prelude::Key;
 use crate::messages::tool::utility_types::{ToolData, ToolType};
 use glam::{DAffine2, DVec2};
@@ -16,7 +17,7 @@ use graphene_std::vector::click_target::ClickTargetType;
 use graphene_std::vector::{VectorData, VectorModificationType};
 use std::f64::consts::{PI, TAU};
 
-const TRANSFORM_GRS_OVERLAY_PROVIDER: OverlayProvider = |context| TransformLayerMessage::Overlays(context).into();
+const TRANSFORM_GRS_OVERLAY_PROVIDER: OverlayProviderHandle = OverlayProviderHandle::new(OverlayProviderOwner::TransformLayer, |context| TransformLayerMessage::Overlays(context).into());
 
 // TODO: Get these from the input mapper
 const SLOW_KEY: Key = Key::Shift;
@@ -82,11 +83,10 @@ impl MessageHandler<TransformLayerMessage, TransformLayerMessageContext<'_>> for
 		let using_shape_tool = tool_data.active_tool_type == ToolType::Shape;
 
 		// TODO: Add support for transforming layer not in the document network
-		let selected_layers = document
-			.network_interface
-			.selected_nodes()
+		let selected_nodes = document.network_interface.selected_nodes();
+		let selected_layers = selected_nodes
 			.selected_layers(document.metadata())
-			.filter(|&layer| document.network_interface.is_visible(&layer.to_node(), &[]) && !document.network_interface.is_locked(&layer.to_node(), &[]))
+			.filter(|&layer| document.network_interface.is_visible(&layer.to_node(), &[]) && !selected_nodes.layer_locked(layer, &document.network_interface))
 			.collect::<Vec<_>>();
 
 		let mut selected = Selected::new(
@@ -362,6 +362,16 @@ impl MessageHandler<TransformLayerMessage, TransformLayerMessageContext<'_>> for
 					}
 				}
 
+				// Layers which are locked, or in a locked group, are left out of the transform, so there's nothing to transform if only those are selected
+				if selected_layers.is_empty()
+					&& selected_nodes
+						.selected_layers(document.metadata())
+						.any(|layer| selected_nodes.layer_locked(layer, &document.network_interface))
+				{
+					send_locked_layers_hint(responses);
+					return;
+				}
+
 				if let Some(vector_data) = selected_layers.first().and_then(|&layer| document.network_interface.compute_modified_vector(layer)) {
 					if let [point] = selected_points.as_slice() {
 						if matches!(point, ManipulatorPointId::Anchor(_)) {
@@ -8,7 +8,7 @@ use crate::messages::input_mapper::utility_types::input_keyboard::{Key, KeysGrou
 use crate::messages::input_mapper::utility_types::macros::action_keys;
 use crate::messages::input_mapper::utility_types::misc::ActionKeys;
 use crate::messages::layout::utility_types::widget_prelude::*;
-use crate::messages::portfolio::document::overlays::utility_types::OverlayProvider;
+use crate::messages::portfolio::document::overlays::utility_types::{OverlayProvider, OverlayProviderHandle, OverlayProviderOwner};
 use crate::messages::preferences::PreferencesMessageHandler;
 use crate::messages::prelude::*;
 use crate::messages::tool::common_functionality::shapes::shape_utility::ShapeType;
@@ -154,9 +154,15 @@ pub struct EventToMessageMap {
 	pub overlay_provider: Option<OverlayProvider>,
 }
 
-pub trait ToolTransition {
+pub trait ToolTransition: ToolMetadata {
 	fn event_to_message_map(&self) -> EventToMessageMap;
 
+	/// The handle for this tool's overlay provider, which ties it to this tool as its owner.
+	fn overlay_provider_handle(&self) -> Option<OverlayProviderHandle> {
+		let owner = OverlayProviderOwner::Tool(self.tool_type());
+		self.event_to_message_map().overlay_provider.map(|provider| OverlayProviderHandle::new(owner, provider))
+	}
+
 	fn activate(&self, responses: &mut VecDeque<Message>) {
 		let mut subscribe_message = |broadcast_to_tool_mapping: Option<ToolMessage>, event: BroadcastEvent| {
 			if let Some(mapping) = broadcast_to_tool_mapping {
@@ -172,8 +178,8 @@ pub trait ToolTransition {
 		subscribe_message(event_to_tool_map.tool_abort, BroadcastEvent::ToolAbort);
 		subscribe_message(event_to_tool_map.selection_changed, BroadcastEvent::SelectionChanged);
 		subscribe_message(event_to_tool_map.working_color_changed, BroadcastEvent::WorkingColorChanged);
-		if let Some(overlay_provider) = event_to_tool_map.overlay_provider {
-			responses.add(OverlaysMessage::AddProvider(overlay_provider));
+		if let Some(handle) = self.overlay_provider_handle() {
+			responses.add(OverlaysMessage::AddProvider(handle));
 		}
 	}
 
@@ -192,8 +198,8 @@ pub trait ToolTransition {
 		unsubscribe_message(event_to_tool_map.tool_abort, BroadcastEvent::ToolAbort);
 		unsubscribe_message(event_to_tool_map.selection_changed, BroadcastEvent::SelectionChanged);
 		unsubscribe_message(event_to_tool_map.working_color_changed, BroadcastEvent::WorkingColorChanged);
-		if let Some(overlay_provider) = event_to_tool_map.overlay_provider {
-			responses.add(OverlaysMessage::RemoveProvider(overlay_provider));
+		if let Some(handle) = self.overlay_provider_handle() {
+			responses.add(OverlaysMessage::RemoveProvider(handle));
 		}
 	}
 }
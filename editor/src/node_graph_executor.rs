@@ -1,30 +1,41 @@
-use crate::consts::FILE_SAVE_SUFFIX;
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
+use crate::consts::{FILE_SAVE_SUFFIX, INSPECTED_FULL_VALUE_SIZE_LIMIT};
+use crate::messages::dialog::simple_dialogs;
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, FrontendInspectedValue};
+use crate::messages::layout::utility_types::layout_widget::DialogLayoutHolder;
+use crate::messages::portfolio::document::node_graph::utility_types::FrontendNodeError;
+use crate::messages::portfolio::document::utility_types::document_metadata::{DocumentMetadata, LayerNodeIdentifier};
 use crate::messages::prelude::*;
 use glam::{DAffine2, DVec2, UVec2};
 use graph_craft::document::value::{RenderOutput, TaggedValue};
-use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput};
+use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeId, NodeInput, NodeNetwork};
 use graph_craft::proto::GraphErrors;
 use graph_craft::wasm_application_io::EditorPreferences;
+use graphene_std::NodeInputDecleration;
 use graphene_std::application_io::TimingInformation;
 use graphene_std::application_io::{NodeGraphUpdateMessage, RenderConfig};
 use graphene_std::renderer::RenderSvgSegmentList;
 use graphene_std::renderer::{GraphicElementRendered, RenderParams, SvgRender};
 use graphene_std::renderer::{RenderMetadata, format_transform_matrix};
 use graphene_std::text::FontCache;
-use graphene_std::transform::Footprint;
+use graphene_std::transform::{Footprint, RenderQuality};
 use graphene_std::vector::VectorData;
 use graphene_std::vector::style::ViewMode;
 use interpreted_executor::dynamic_executor::ResolvedDocumentNodeTypesDelta;
 
+mod error;
+pub use error::*;
+
 mod runtime_io;
 pub use runtime_io::NodeRuntimeIO;
 
 mod runtime;
 pub use runtime::*;
 
+mod thumbnails;
+
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
 pub struct ExecutionRequest {
+	/// Increases with every request, so a viewport render is superseded by any request with a greater ID
 	execution_id: u64,
 	render_config: RenderConfig,
 }
@@ -37,6 +48,10 @@ pub struct ExecutionResponse {
 	vector_modify: HashMap<NodeId, VectorData>,
 	/// The resulting value from the temporary inspected during execution
 	inspect_result: Option<InspectResult>,
+	/// The auto levels computed from the input image of the requested Levels node during execution
+	auto_levels: Option<AutoLevelsResult>,
+	/// The outputs of the inspected nodes which were evaluated during execution
+	inspected_values: HashMap<Vec<NodeId>, InspectedValue>,
 }
 
 #[derive(serde::Serialize, serde::Deserialize)]
@@ -47,6 +62,8 @@ pub struct CompilationResponse {
 
 pub enum NodeGraphUpdate {
 	ExecutionResponse(ExecutionResponse),
+	/// The viewport render with this execution ID was superseded by a newer one, so it was skipped or stopped early without a result
+	ExecutionCancelled(u64),
 	CompilationResponse(CompilationResponse),
 	NodeGraphUpdateMessage(NodeGraphUpdateMessage),
 }
@@ -58,11 +75,46 @@ pub struct NodeGraphExecutor {
 	futures: HashMap<u64, ExecutionContext>,
 	node_graph_hash: u64,
 	old_inspect_node: Option<NodeId>,
+	/// The path to the Levels node whose auto levels have been requested but not yet received
+	auto_levels_node: Option<Vec<NodeId>>,
+	old_auto_levels_node: Option<Vec<NodeId>>,
+	/// Whether the outputs of the inspected nodes are kept after each evaluation, which is off unless enabled for debugging or the data panel
+	node_inspection: bool,
+	/// The paths to the nodes which the frontend has marked as inspected
+	inspected_nodes: Vec<Vec<NodeId>>,
+	old_inspected_nodes: Vec<Vec<NodeId>>,
+	/// The soloed layer which the graph sent to the runtime was isolated to
+	old_solo_layer: Option<LayerNodeIdentifier>,
+	/// The outputs of the inspected nodes from the most recent viewport render
+	inspected_values: HashMap<Vec<NodeId>, InspectedValue>,
+	/// How long the last viewport render took in milliseconds, and the render scale it was evaluated at
+	last_render_time: Option<(f64, f64)>,
+	/// Whether the most recently submitted viewport render was at a reduced render scale, so it should be followed by a full quality render once the interaction ends
+	reduced_quality_render: bool,
+	/// Whether an evaluation error is being shown to the user, which is cleared once the graph renders successfully again
+	error_displayed: bool,
+	/// The exports which are rendered one after another, such as those of each artboard
+	export_batch: Option<ExportBatch>,
+}
+
+/// Exports which are submitted one at a time, each once the one before it has finished, so their progress can be reported and the rest can be cancelled.
+#[derive(Debug, Default)]
+struct ExportBatch {
+	pending: VecDeque<ExportConfig>,
+	total: usize,
+	/// The execution ID of the export being rendered
+	current: Option<u64>,
 }
 
 #[derive(Debug, Clone)]
 struct ExecutionContext {
 	export_config: Option<ExportConfig>,
+	/// The time in milliseconds at which the evaluation was submitted
+	submission_time: f64,
+	/// The quality of the footprint the evaluation was submitted with
+	render_quality: RenderQuality,
+	/// Whether the evaluation is an export of a batch, which is dropped if the batch was cancelled
+	batched: bool,
 }
 
 impl NodeGraphExecutor {
@@ -75,10 +127,21 @@ impl NodeGraphExecutor {
 
 		let node_executor = Self {
 			futures: Default::default(),
-			runtime_io: NodeRuntimeIO::with_channels(request_sender, response_receiver),
+			runtime_io: NodeRuntimeIO::with_channels(request_sender, response_receiver, node_runtime.cancellation_token()),
 			node_graph_hash: 0,
 			current_execution_id: 0,
 			old_inspect_node: None,
+			auto_levels_node: None,
+			old_auto_levels_node: None,
+			node_inspection: false,
+			inspected_nodes: Vec::new(),
+			old_inspected_nodes: Vec::new(),
+			old_solo_layer: None,
+			inspected_values: HashMap::new(),
+			last_render_time: None,
+			reduced_quality_render: false,
+			error_displayed: false,
+			export_batch: None,
 		};
 		(node_runtime, node_executor)
 	}
@@ -86,58 +149,178 @@ impl NodeGraphExecutor {
 	fn queue_execution(&mut self, render_config: RenderConfig) -> u64 {
 		let execution_id = self.current_execution_id;
 		self.current_execution_id += 1;
+		// A viewport render makes the older ones obsolete, but exports must always complete
+		if !render_config.for_export {
+			self.runtime_io.supersede(execution_id);
+		}
 		let request = ExecutionRequest { execution_id, render_config };
 		self.runtime_io.send(GraphRuntimeRequest::ExecutionRequest(request)).expect("Failed to send generation request");
 
 		execution_id
 	}
 
+	/// Computes the auto levels of a Levels node from its input image during the next graph evaluation, then sets them on the node.
+	pub fn request_auto_levels(&mut self, node_path: Vec<NodeId>) {
+		self.auto_levels_node = Some(node_path);
+		// Always send the graph again, since a repeated request may follow one whose evaluation failed
+		self.old_auto_levels_node = None;
+	}
+
+	/// Enables or disables keeping the outputs of the inspected nodes, which needs the graph to be compiled again with them monitored.
+	pub fn set_node_inspection(&mut self, enabled: bool) {
+		self.node_inspection = enabled;
+		if !enabled {
+			self.inspected_values.clear();
+		}
+	}
+
+	/// Keeps the output of the node after each graph evaluation while node inspection is enabled.
+	pub fn inspect_node_output(&mut self, node_path: Vec<NodeId>) {
+		if !self.inspected_nodes.contains(&node_path) {
+			self.inspected_nodes.push(node_path);
+		}
+	}
+
+	/// Stops keeping the output of the node, and forgets the output which was kept.
+	pub fn stop_inspecting_node_output(&mut self, node_path: &[NodeId]) {
+		self.inspected_nodes.retain(|inspected| inspected != node_path);
+		self.inspected_values.remove(node_path);
+	}
+
+	/// The output of the inspected node from the most recent viewport render, if it was evaluated.
+	pub fn inspected_value(&self, node_path: &[NodeId]) -> Option<&InspectedValue> {
+		self.inspected_values.get(node_path)
+	}
+
+	/// Describes the kept output of the inspected node for the data panel, along with the whole output if it's requested and small enough to send.
+	pub fn frontend_inspected_value(&self, node_path: &[NodeId], full: bool) -> Option<FrontendInspectedValue> {
+		let inspected_value = self.inspected_value(node_path)?;
+		let value = match inspected_value {
+			InspectedValue::Value(value) => Some(value),
+			InspectedValue::Summary { .. } => None,
+		};
+		let full = value
+			.filter(|value| full && value.estimated_size() <= INSPECTED_FULL_VALUE_SIZE_LIMIT)
+			.and_then(|value| serde_json::to_string(value).inspect_err(|error| warn!("Failed to serialize the inspected value: {error}")).ok());
+
+		Some(FrontendInspectedValue {
+			type_name: inspected_value.type_name(),
+			size: inspected_value.size(),
+			preview: value.map(TaggedValue::preview),
+			full,
+		})
+	}
+
+	/// Computes the histogram of a layer's content during the next graph evaluation, then sends it to the frontend.
+	pub fn request_histogram(&self, layer: NodeId) {
+		self.runtime_io.send(GraphRuntimeRequest::HistogramRequest(layer)).expect("Failed to send histogram request");
+	}
+
+	/// Collects the reuse statistics of the cached node outputs after the next graph evaluation, then sends them to the frontend.
+	pub fn request_node_cache_stats(&self) {
+		self.runtime_io.send(GraphRuntimeRequest::NodeCacheStatsRequest).expect("Failed to send node cache stats request");
+	}
+
 	pub fn update_font_cache(&self, font_cache: FontCache) {
 		self.runtime_io.send(GraphRuntimeRequest::FontCacheUpdate(font_cache)).expect("Failed to send font cache update");
 	}
 
+	/// How long the last viewport render took in milliseconds, and the render scale it was evaluated at.
+	pub fn last_render_time(&self) -> Option<(f64, f64)> {
+		self.last_render_time
+	}
+
+	/// Whether the most recently submitted viewport render was at a reduced render scale, which needs to be replaced by a full quality render.
+	pub fn reduced_quality_render(&self) -> bool {
+		self.reduced_quality_render
+	}
+
 	pub fn update_editor_preferences(&self, editor_preferences: EditorPreferences) {
 		self.runtime_io
 			.send(GraphRuntimeRequest::EditorPreferencesUpdate(editor_preferences))
 			.expect("Failed to send editor preferences");
 	}
 
+	/// The number of graph evaluations which have been submitted to the runtime.
+	#[cfg(test)]
+	pub(crate) fn submitted_evaluation_count(&self) -> u64 {
+		self.current_execution_id
+	}
+
 	/// Updates the network to monitor all inputs. Useful for the testing.
 	#[cfg(test)]
 	pub(crate) fn update_node_graph_instrumented(&mut self, document: &mut DocumentMessageHandler) -> Result<Instrumented, String> {
 		// We should always invalidate the cache.
 		self.node_graph_hash = crate::application::generate_uuid();
-		let mut network = document.network_interface.document_network().clone();
+		let mut network = document.network_interface.resolved_document_network();
 		let instrumented = Instrumented::new(&mut network);
 
 		self.runtime_io
-			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, inspect_node: None }))
+			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate {
+				network,
+				inspect_node: None,
+				auto_levels_node: None,
+				inspected_nodes: Vec::new(),
+			}))
 			.map_err(|e| e.to_string())?;
 		Ok(instrumented)
 	}
 
 	/// Update the cached network if necessary.
 	fn update_node_graph(&mut self, document: &mut DocumentMessageHandler, inspect_node: Option<NodeId>, ignore_hash: bool) -> Result<(), String> {
-		let network_hash = document.network_interface.document_network().current_hash();
-		// Refresh the graph when it changes or the inspect node changes
-		if network_hash != self.node_graph_hash || self.old_inspect_node != inspect_node || ignore_hash {
-			let network = document.network_interface.document_network().clone();
+		let network_hash = document.network_interface.document_hash();
+		let inspected_nodes = if self.node_inspection { self.inspected_nodes.clone() } else { Vec::new() };
+		let solo_layer = document.solo_layer();
+		// Refresh the graph when it changes, the inspect node changes, the inspected nodes change, a layer is soloed, or auto levels are requested or received
+		if network_hash != self.node_graph_hash
+			|| self.old_inspect_node != inspect_node
+			|| self.old_inspected_nodes != inspected_nodes
+			|| self.old_solo_layer != solo_layer
+			|| self.old_auto_levels_node != self.auto_levels_node
+			|| ignore_hash
+		{
+			let mut network = document.network_interface.resolved_document_network();
+			if let Some(solo_layer) = solo_layer {
+				hide_layers_outside_solo(&mut network, document.metadata(), solo_layer);
+			}
+			let auto_levels_node = self.auto_levels_node.clone();
 			self.old_inspect_node = inspect_node;
+			self.old_inspected_nodes.clone_from(&inspected_nodes);
+			self.old_solo_layer = solo_layer;
+			self.old_auto_levels_node.clone_from(&auto_levels_node);
 			self.node_graph_hash = network_hash;
 
 			self.runtime_io
-				.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, inspect_node }))
+				.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate {
+					network,
+					inspect_node,
+					auto_levels_node,
+					inspected_nodes,
+				}))
 				.map_err(|e| e.to_string())?;
 		}
 		Ok(())
 	}
 
-	/// Adds an evaluate request for whatever current network is cached.
-	pub(crate) fn submit_current_node_graph_evaluation(&mut self, document: &mut DocumentMessageHandler, viewport_resolution: UVec2, time: TimingInformation) -> Result<Message, String> {
+	/// Adds an evaluate request for whatever current network is cached, at the current time of the document's animation.
+	/// A render scale below 1 reduces the resolution which raster content is evaluated at, for interactive previews.
+	pub(crate) fn submit_current_node_graph_evaluation(
+		&mut self,
+		document: &mut DocumentMessageHandler,
+		viewport_resolution: UVec2,
+		time: TimingInformation,
+		render_scale: f64,
+	) -> Result<Message, String> {
+		let time = TimingInformation {
+			animation_time: document.playback.animation_time(),
+			..time
+		};
+		let render_quality = if render_scale < 1. { RenderQuality::Scale(render_scale as f32) } else { RenderQuality::Full };
 		let render_config = RenderConfig {
 			viewport: Footprint {
 				transform: document.metadata().document_to_viewport,
 				resolution: viewport_resolution,
+				quality: render_quality,
 				..Default::default()
 			},
 			time,
@@ -146,14 +329,25 @@ impl NodeGraphExecutor {
 			#[cfg(not(any(feature = "resvg", feature = "vello")))]
 			export_format: graphene_std::application_io::ExportFormat::Svg,
 			view_mode: document.view_mode,
-			hide_artboards: false,
+			// A soloed layer is shown on its own against the transparency checkerboard, without the artboards and canvas behind it
+			hide_artboards: document.solo_layer().is_some(),
 			for_export: false,
+			layer_names: HashMap::new(),
 		};
 
 		// Execute the node graph
 		let execution_id = self.queue_execution(render_config);
 
-		self.futures.insert(execution_id, ExecutionContext { export_config: None });
+		self.reduced_quality_render = render_quality != RenderQuality::Full;
+		self.futures.insert(
+			execution_id,
+			ExecutionContext {
+				export_config: None,
+				submission_time: time.time,
+				render_quality,
+				batched: false,
+			},
+		);
 
 		Ok(DeferMessage::SetGraphSubmissionIndex(execution_id).into())
 	}
@@ -166,14 +360,69 @@ impl NodeGraphExecutor {
 		time: TimingInformation,
 		inspect_node: Option<NodeId>,
 		ignore_hash: bool,
+		render_scale: f64,
 	) -> Result<Message, String> {
 		self.update_node_graph(document, inspect_node, ignore_hash)?;
-		self.submit_current_node_graph_evaluation(document, viewport_resolution, time)
+		self.submit_current_node_graph_evaluation(document, viewport_resolution, time, render_scale)
 	}
 
 	/// Evaluates a node graph for export
-	pub fn submit_document_export(&mut self, document: &mut DocumentMessageHandler, mut export_config: ExportConfig) -> Result<(), String> {
-		let network = document.network_interface.document_network().clone();
+	pub fn submit_document_export(&mut self, document: &mut DocumentMessageHandler, export_config: ExportConfig) -> Result<(), String> {
+		self.queue_export(document, export_config, false).map(|_| ())
+	}
+
+	/// Evaluates the node graph for each of the exports in turn, showing the progress of the batch until it finishes or is cancelled.
+	pub fn submit_export_batch(&mut self, document: &mut DocumentMessageHandler, exports: Vec<ExportConfig>, responses: &mut VecDeque<Message>) -> Result<(), String> {
+		if exports.is_empty() {
+			return Err("There is nothing to export".to_string());
+		}
+
+		self.export_batch = Some(ExportBatch {
+			total: exports.len(),
+			pending: exports.into(),
+			current: None,
+		});
+		self.submit_next_batch_export(document, responses)
+	}
+
+	/// Stops exporting the rest of the batch, and drops the export which is being rendered once it finishes.
+	pub fn cancel_export_batch(&mut self, responses: &mut VecDeque<Message>) {
+		if self.export_batch.take().is_some() {
+			responses.add(FrontendMessage::DisplayDialogDismiss);
+		}
+	}
+
+	fn submit_next_batch_export(&mut self, document: &mut DocumentMessageHandler, responses: &mut VecDeque<Message>) -> Result<(), String> {
+		let Some(batch) = &mut self.export_batch else { return Ok(()) };
+		let Some(export_config) = batch.pending.pop_front() else {
+			self.export_batch = None;
+			responses.add(FrontendMessage::DisplayDialogDismiss);
+			return Ok(());
+		};
+
+		let dialog = simple_dialogs::ExportProgressDialog {
+			exported: batch.total - batch.pending.len() - 1,
+			total: batch.total,
+			file_name: export_config.file_name.clone(),
+		};
+		dialog.send_dialog_to_frontend(responses);
+
+		match self.queue_export(document, export_config, true) {
+			Ok(execution_id) => {
+				if let Some(batch) = &mut self.export_batch {
+					batch.current = Some(execution_id);
+				}
+				Ok(())
+			}
+			Err(error) => {
+				self.export_batch = None;
+				Err(error)
+			}
+		}
+	}
+
+	fn queue_export(&mut self, document: &mut DocumentMessageHandler, mut export_config: ExportConfig, batched: bool) -> Result<u64, String> {
+		let network = document.network_interface.resolved_document_network();
 
 		// Calculate the bounding box of the region to be exported
 		let bounds = match export_config.bounds {
@@ -185,29 +434,56 @@ impl NodeGraphExecutor {
 		let size = bounds[1] - bounds[0];
 		let transform = DAffine2::from_translation(bounds[0]).inverse();
 
+		// Only the layers the user has named are given element IDs, since the default names don't identify anything
+		let layer_names = document
+			.metadata()
+			.all_layers()
+			.filter_map(|layer| {
+				let name = &document.network_interface.node_metadata(&layer.to_node(), &[])?.persistent_metadata.display_name;
+				(!name.is_empty()).then(|| (layer.to_node(), name.clone()))
+			})
+			.collect();
+
 		let render_config = RenderConfig {
 			viewport: Footprint {
 				transform: DAffine2::from_scale(DVec2::splat(export_config.scale_factor)) * transform,
 				resolution: (size * export_config.scale_factor).as_uvec2(),
 				..Default::default()
 			},
-			time: Default::default(),
+			// The export shows the frame of the animation which is currently shown in the viewport
+			time: TimingInformation {
+				animation_time: document.playback.animation_time(),
+				..Default::default()
+			},
 			export_format: graphene_std::application_io::ExportFormat::Svg,
 			view_mode: document.view_mode,
 			hide_artboards: export_config.transparent_background,
 			for_export: true,
+			layer_names,
 		};
 		export_config.size = size;
 
 		// Execute the node graph
 		self.runtime_io
-			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate { network, inspect_node: None }))
+			.send(GraphRuntimeRequest::GraphUpdate(GraphUpdate {
+				network,
+				inspect_node: None,
+				auto_levels_node: None,
+				inspected_nodes: Vec::new(),
+			}))
 			.map_err(|e| e.to_string())?;
+		// The export replaced the viewport's graph, which is sent again by the next viewport render
+		self.node_graph_hash = 0;
 		let execution_id = self.queue_execution(render_config);
-		let execution_context = ExecutionContext { export_config: Some(export_config) };
+		let execution_context = ExecutionContext {
+			export_config: Some(export_config),
+			submission_time: 0.,
+			render_quality: RenderQuality::Full,
+			batched,
+		};
 		self.futures.insert(execution_id, execution_context);
 
-		Ok(())
+		Ok(execution_id)
 	}
 
 	fn export(&self, node_graph_output: TaggedValue, export_config: ExportConfig, responses: &mut VecDeque<Message>) -> Result<(), String> {
@@ -224,13 +500,18 @@ impl NodeGraphExecutor {
 			file_name,
 			size,
 			scale_factor,
+			color_space,
 			..
 		} = export_config;
 
 		let file_suffix = &format!(".{file_type:?}").to_lowercase();
-		let name = match file_name.ends_with(FILE_SAVE_SUFFIX) {
-			true => file_name.replace(FILE_SAVE_SUFFIX, file_suffix),
-			false => file_name + file_suffix,
+		let name = if file_name.ends_with(FILE_SAVE_SUFFIX) {
+			file_name.replace(FILE_SAVE_SUFFIX, file_suffix)
+		} else if file_name.to_lowercase().ends_with(file_suffix) {
+			// Names which already end in the extension, like those given by a file name template, keep it as it is
+			file_name
+		} else {
+			file_name + file_suffix
 		};
 
 		if file_type == FileType::Svg {
@@ -238,12 +519,14 @@ impl NodeGraphExecutor {
 		} else {
 			let mime = file_type.to_mime().to_string();
 			let size = (size * scale_factor).into();
-			responses.add(FrontendMessage::TriggerDownloadImage { svg, name, mime, size });
+			let color_space = color_space.to_canvas_color_space().to_string();
+			responses.add(FrontendMessage::TriggerDownloadImage { svg, name, mime, size, color_space });
 		}
 		Ok(())
 	}
 
-	pub fn poll_node_graph_evaluation(&mut self, document: &mut DocumentMessageHandler, responses: &mut VecDeque<Message>) -> Result<(), String> {
+	/// Processes the results of the node graph evaluations which have finished, at the given time in milliseconds.
+	pub fn poll_node_graph_evaluation(&mut self, document: &mut DocumentMessageHandler, time: f64, responses: &mut VecDeque<Message>) -> Result<(), NodeGraphExecutionError> {
 		let results = self.runtime_io.receive().collect::<Vec<_>>();
 		for response in results {
 			match response {
@@ -255,6 +538,8 @@ impl NodeGraphExecutor {
 						transform,
 						vector_modify,
 						inspect_result,
+						auto_levels,
+						inspected_values,
 					} = execution_response;
 
 					responses.add(OverlaysMessage::Draw);
@@ -265,7 +550,7 @@ impl NodeGraphExecutor {
 							// Clear the click targets while the graph is in an un-renderable state
 							document.network_interface.update_click_targets(HashMap::new());
 							document.network_interface.update_vector_modify(HashMap::new());
-							return Err(format!("Node graph evaluation failed:\n{e}"));
+							return Err(NodeGraphExecutionError::Evaluation(format!("Node graph evaluation failed:\n{e}")));
 						}
 					};
 
@@ -275,9 +560,21 @@ impl NodeGraphExecutor {
 					let execution_context = self.futures.remove(&execution_id).ok_or_else(|| "Invalid generation ID".to_string())?;
 					if let Some(export_config) = execution_context.export_config {
 						// Special handling for exporting the artwork
-						self.export(node_graph_output, export_config, responses)?
+						let batch_export = self.export_batch.as_ref().and_then(|batch| batch.current) == Some(execution_id);
+						if batch_export || !execution_context.batched {
+							self.export(node_graph_output, export_config, responses)?;
+						}
+						// The exports of a cancelled batch are dropped, and the next export of a batch follows once the previous one is out
+						if batch_export {
+							self.submit_next_batch_export(document, responses)?;
+						}
 					} else {
-						self.process_node_graph_output(node_graph_output, transform, responses)?
+						self.last_render_time = Some((time - execution_context.submission_time, execution_context.render_quality.scale_factor()));
+						if self.node_inspection {
+							self.inspected_values = inspected_values;
+						}
+						self.process_node_graph_output(node_graph_output, transform, responses)?;
+						self.clear_displayed_error(responses);
 					}
 					responses.add(DeferMessage::TriggerGraphRun(execution_id));
 
@@ -287,6 +584,17 @@ impl NodeGraphExecutor {
 							responses.add(SpreadsheetMessage::UpdateLayout { inspect_result });
 						}
 					}
+
+					if let Some(auto_levels) = auto_levels {
+						if self.auto_levels_node.as_ref() == Some(&auto_levels.node_path) {
+							self.auto_levels_node = None;
+							Self::apply_auto_levels(auto_levels, document, responses);
+						}
+					}
+				}
+				NodeGraphUpdate::ExecutionCancelled(execution_id) => {
+					// Nothing is shown for a superseded render, the newer one it was cancelled for replaces it once finished
+					self.futures.remove(&execution_id);
 				}
 				NodeGraphUpdate::CompilationResponse(execution_response) => {
 					let CompilationResponse { node_graph_errors, result } = execution_response;
@@ -299,13 +607,29 @@ impl NodeGraphExecutor {
 
 							log::trace!("{e}");
 
+							// Attribute the failure to the node which caused it, when possible, so it can be pointed out in the node graph
+							let error = match node_graph_errors.first() {
+								Some(graph_error) => {
+									let mut node_error = NodeError::from(graph_error);
+									// Name the layer the failing node belongs to, which is easier to find than the node itself
+									node_error.layer_name = graph_error
+										.node_path
+										.first()
+										.filter(|node_id| document.network_interface.document_node(node_id, &[]).is_some())
+										.and_then(|node_id| document.network_interface.downstream_layer_for_chain_node(node_id, &[]))
+										.map(|layer| document.network_interface.display_name(&layer, &[]));
+									NodeGraphExecutionError::Node(node_error)
+								}
+								None => NodeGraphExecutionError::Evaluation(format!("Node graph evaluation failed:\n{e}")),
+							};
+
 							responses.add(NodeGraphMessage::UpdateTypes {
 								resolved_types: Default::default(),
 								node_graph_errors,
 							});
 							responses.add(NodeGraphMessage::SendGraph);
 
-							return Err(format!("Node graph evaluation failed:\n{e}"));
+							return Err(error);
 						}
 						Ok(result) => result,
 					};
@@ -321,6 +645,36 @@ impl NodeGraphExecutor {
 		Ok(())
 	}
 
+	/// Shows the error to the user with a banner over the document, and marks the node which caused it in the node graph.
+	pub fn display_error(&mut self, error: &NodeGraphExecutionError, responses: &mut VecDeque<Message>) {
+		if *error == NodeGraphExecutionError::NoActiveDocument {
+			return;
+		}
+
+		let errors = error
+			.node_error()
+			.map(|node_error| FrontendNodeError {
+				node_path: node_error.node_path.clone(),
+				input_index: node_error.input_index,
+				message: node_error.message.clone(),
+			})
+			.into_iter()
+			.collect();
+		responses.add(FrontendMessage::UpdateNodeGraphErrors { errors });
+		responses.add(FrontendMessage::UpdateDocumentError { error: Some(error.to_string()) });
+		self.error_displayed = true;
+	}
+
+	/// Removes the error banner and node error markers once the graph renders successfully again.
+	fn clear_displayed_error(&mut self, responses: &mut VecDeque<Message>) {
+		if !std::mem::take(&mut self.error_displayed) {
+			return;
+		}
+
+		responses.add(FrontendMessage::UpdateNodeGraphErrors { errors: Vec::new() });
+		responses.add(FrontendMessage::UpdateDocumentError { error: None });
+	}
+
 	fn debug_render(render_object: impl GraphicElementRendered, transform: DAffine2, responses: &mut VecDeque<Message>) {
 		// Setup rendering
 		let mut render = SvgRender::new();
@@ -345,6 +699,38 @@ impl NodeGraphExecutor {
 		responses.add(FrontendMessage::UpdateDocumentArtwork { svg });
 	}
 
+	/// Sets the input black and white points of each color channel of the Levels node to its computed auto levels, as a single undoable change.
+	fn apply_auto_levels(auto_levels: AutoLevelsResult, document: &DocumentMessageHandler, responses: &mut VecDeque<Message>) {
+		use graphene_std::raster::levels::*;
+
+		let AutoLevelsResult { node_path, levels } = auto_levels;
+		let Some((&node_id, network_path)) = node_path.split_last() else { return };
+		// The Properties panel may have moved on to another network since the auto levels were requested
+		if network_path != document.selection_network_path() {
+			return;
+		}
+		let Some([red, green, blue]) = levels else {
+			warn!("Auto levels could not be computed because the input of the Levels node isn't a visible raster image");
+			return;
+		};
+
+		responses.add(DocumentMessage::AddTransaction);
+		let channels = [
+			(RedInputBlackInput::INDEX, RedInputWhiteInput::INDEX, red),
+			(GreenInputBlackInput::INDEX, GreenInputWhiteInput::INDEX, green),
+			(BlueInputBlackInput::INDEX, BlueInputWhiteInput::INDEX, blue),
+		];
+		for (black_index, white_index, (black, white)) in channels {
+			for (input_index, value) in [(black_index, black), (white_index, white)] {
+				responses.add(NodeGraphMessage::SetInputValue {
+					node_id,
+					input_index,
+					value: TaggedValue::F64(value * 100.),
+				});
+			}
+		}
+	}
+
 	fn process_node_graph_output(&mut self, node_graph_output: TaggedValue, transform: DAffine2, responses: &mut VecDeque<Message>) -> Result<(), String> {
 		let mut render_output_metadata = RenderMetadata::default();
 		match node_graph_output {
@@ -408,6 +794,16 @@ impl NodeGraphExecutor {
 	}
 }
 
+/// Hides every layer which isn't rendered while the given layer is soloed.
+/// A hidden layer passes the layers below it through, so the soloed subtree is still composited within its ancestors and their transforms.
+fn hide_layers_outside_solo(network: &mut NodeNetwork, metadata: &DocumentMetadata, solo_layer: LayerNodeIdentifier) {
+	for layer in metadata.all_layers().filter(|layer| !layer.is_rendered_in_solo(metadata, &solo_layer)) {
+		if let Some(node) = network.nodes.get_mut(&layer.to_node()) {
+			node.visible = false;
+		}
+	}
+}
+
 // Re-export for usage by tests in other modules
 #[cfg(test)]
 pub use test::Instrumented;
@@ -417,13 +813,17 @@ mod test {
 	use std::sync::Arc;
 
 	use super::*;
+	use crate::messages::input_mapper::utility_types::input_keyboard::ModifierKeys;
+	use crate::messages::portfolio::document::utility_types::misc::GroupFolderType;
 	use crate::messages::portfolio::document::utility_types::network_interface::NodeNetworkInterface;
+	use crate::messages::preferences::InteractiveRenderQuality;
+	use crate::messages::tool::utility_types::ToolType;
 	use crate::test_utils::test_prelude::{self, NodeGraphLayer};
-	use graph_craft::ProtoNodeIdentifier;
-	use graph_craft::document::NodeNetwork;
+	use graph_craft::{ProtoNodeIdentifier, concrete};
 	use graphene_std::Context;
 	use graphene_std::NodeInputDecleration;
 	use graphene_std::memo::IORecord;
+	use graphene_std::wasm_application_io::WasmApplicationIo;
 	use test_prelude::LayerNodeIdentifier;
 
 	/// Stores all of the monitor nodes that have been attached to a graph
@@ -530,4 +930,502 @@ mod test {
 			self.grab_protonode_input::<Input>(&vec![node], runtime)
 		}
 	}
+
+	/// The render quality of the most recently submitted evaluation.
+	fn latest_render_quality(editor: &test_prelude::EditorTestUtils) -> RenderQuality {
+		let executor = &editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor;
+		let latest = executor.futures.keys().max().expect("A render should have been submitted");
+		executor.futures[latest].render_quality
+	}
+
+	#[tokio::test]
+	async fn dragging_renders_at_reduced_quality_until_release() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.editor.handle_message(PreferencesMessage::InteractiveRenderQuality {
+			quality: InteractiveRenderQuality::Quarter,
+		});
+
+		// Dragging with a tool
+		editor.editor.handle_message(DocumentMessage::StartTransaction);
+		editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		assert_eq!(latest_render_quality(&editor), RenderQuality::Scale(0.25));
+
+		editor.editor.handle_message(DocumentMessage::CommitTransaction);
+		assert_eq!(latest_render_quality(&editor), RenderQuality::Full);
+
+		// Dragging a number input widget
+		editor.editor.handle_message(PortfolioMessage::SetWidgetDragInProgress { in_progress: true });
+		editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		assert_eq!(latest_render_quality(&editor), RenderQuality::Scale(0.25));
+
+		editor.editor.handle_message(PortfolioMessage::SetWidgetDragInProgress { in_progress: false });
+		assert_eq!(latest_render_quality(&editor), RenderQuality::Full);
+	}
+
+	/// Sets an input of the node, then evaluates the graph and polls its result along with the messages it produced.
+	async fn set_input_and_poll(editor: &mut test_prelude::EditorTestUtils, node_id: NodeId, input_index: usize, value: TaggedValue) -> (Result<(), NodeGraphExecutionError>, VecDeque<Message>) {
+		editor.editor.handle_message(NodeGraphMessage::SetInputValue { node_id, input_index, value });
+		editor.runtime.run().await;
+
+		let mut responses = VecDeque::new();
+		let result = editor.editor.poll_node_graph_evaluation(&mut responses);
+		(result, responses)
+	}
+
+	#[tokio::test]
+	async fn type_mismatch_reports_the_failing_node() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+
+		// The radius must be a number, not text
+		let (result, responses) = set_input_and_poll(&mut editor, ellipse_node, 1, TaggedValue::String("Not a number".to_string())).await;
+		let error = result.unwrap_err();
+		let node_error = error.node_error().expect("The error should be attributed to a node");
+		assert_eq!(node_error.node_path, vec![ellipse_node]);
+		assert!(node_error.input_index.is_some());
+		assert_eq!(node_error.expected.as_ref().map(|expected| expected.nested_type()), Some(&concrete!(f64)));
+		assert_eq!(node_error.actual.as_ref().map(|actual| actual.nested_type()), Some(&concrete!(String)));
+		assert!(node_error.message.contains("incompatible"), "The message should describe the mismatch: {}", node_error.message);
+
+		// The error names the layer which the failing node belongs to
+		let layer_name = editor.active_document().network_interface.display_name(&layer.to_node(), &[]);
+		assert_eq!(node_error.layer_name.as_ref(), Some(&layer_name));
+		assert!(error.to_string().contains(&format!("In the layer \"{layer_name}\"")));
+
+		// The node graph marks the failing node and the document shows the error
+		assert!(responses.iter().any(|message| matches!(
+			message,
+			Message::Frontend(FrontendMessage::UpdateNodeGraphErrors { errors }) if errors.len() == 1 && errors[0].node_path == vec![ellipse_node] && errors[0].message == node_error.message
+		)));
+		assert!(responses.contains(&FrontendMessage::UpdateDocumentError { error: Some(error.to_string()) }.into()));
+
+		// Fixing the input clears the error once the graph renders again
+		let (result, responses) = set_input_and_poll(&mut editor, ellipse_node, 1, TaggedValue::F64(10.)).await;
+		assert!(result.is_ok());
+		assert!(responses.contains(&FrontendMessage::UpdateNodeGraphErrors { errors: Vec::new() }.into()));
+		assert!(responses.contains(&FrontendMessage::UpdateDocumentError { error: None }.into()));
+	}
+
+	/// The IDs of the renders which were cancelled and which completed, in the order the runtime reported them.
+	fn drain_execution_updates(editor: &test_prelude::EditorTestUtils) -> (Vec<u64>, Vec<u64>) {
+		let executor = &editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor;
+		let (mut cancelled, mut completed) = (Vec::new(), Vec::new());
+		for update in executor.runtime_io.receive() {
+			match update {
+				NodeGraphUpdate::ExecutionCancelled(execution_id) => cancelled.push(execution_id),
+				NodeGraphUpdate::ExecutionResponse(response) => completed.push(response.execution_id),
+				_ => {}
+			}
+		}
+		(cancelled, completed)
+	}
+
+	#[tokio::test]
+	async fn flooded_renders_only_complete_the_newest() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		// Request several renders before the runtime gets to evaluate any of them
+		let executor = &editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor;
+		let first = executor.submitted_evaluation_count();
+		for _ in 0..5 {
+			editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		}
+		let executor = &editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor;
+		let newest = executor.submitted_evaluation_count() - 1;
+		assert_eq!(newest - first, 4);
+
+		// The older renders observe that they've been cancelled, even before they start
+		let cancellation = editor.runtime.cancellation_token();
+		assert!((first..newest).all(|execution_id| cancellation.for_generation(execution_id).is_cancelled()));
+		assert!(!cancellation.for_generation(newest).is_cancelled());
+
+		editor.runtime.run().await;
+		let (cancelled, completed) = drain_execution_updates(&editor);
+		assert_eq!(cancelled, (first..newest).collect::<Vec<_>>());
+		assert_eq!(completed, vec![newest]);
+
+		// Polling tolerates the cancelled renders and forgets them along with the completed one
+		for _ in 0..3 {
+			editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		}
+		editor.runtime.run().await;
+		let mut responses = VecDeque::new();
+		assert!(editor.editor.poll_node_graph_evaluation(&mut responses).is_ok());
+		assert!(editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.futures.is_empty());
+	}
+
+	#[tokio::test]
+	async fn render_superseded_during_evaluation_is_discarded() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		let executor = &editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor;
+		let execution_id = executor.submitted_evaluation_count() - 1;
+
+		// A newer render is requested while this one is evaluated, before its request reaches the runtime
+		executor.runtime_io.supersede(execution_id + 1);
+		assert!(editor.runtime.run().await.is_none());
+		assert_eq!(drain_execution_updates(&editor), (vec![execution_id], Vec::new()));
+	}
+
+	/// Adds a Cache node to the first layer, whose output is kept for reuse between evaluations unlike most other nodes.
+	async fn add_cache_node(editor: &mut test_prelude::EditorTestUtils) -> NodeId {
+		let layer = editor.active_document().metadata().all_layers().next().unwrap();
+		let node_type = "Cache".to_string();
+		editor.handle_message(NodeGraphMessage::CreateNodeInLayerWithTransaction { node_type, layer }).await;
+		let document = editor.active_document();
+		NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Cache").unwrap()
+	}
+
+	/// The total cache hits and misses of the memoization nodes within the given Cache node.
+	fn cache_node_stats(editor: &test_prelude::EditorTestUtils, cache_node: NodeId) -> (u64, u64) {
+		let stats = editor.runtime.executor.node_cache_stats();
+		let cache_stats = stats.iter().filter(|(path, _)| path.first() == Some(&cache_node)).map(|(_, stats)| stats);
+		cache_stats.fold((0, 0), |(hits, misses), stats| (hits + stats.hits, misses + stats.misses))
+	}
+
+	#[tokio::test]
+	async fn replacing_the_application_io_keeps_the_loaded_state_and_cached_outputs() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let cache_node = add_cache_node(&mut editor).await;
+		let cache_stats = |editor: &test_prelude::EditorTestUtils| cache_node_stats(editor, cache_node);
+
+		let font = graphene_std::text::Font::new("Test Family".to_string(), "Regular".to_string());
+		let mut font_cache = graphene_std::text::FontCache::default();
+		font_cache.insert(font.clone(), String::new(), vec![0; 4]);
+		editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.update_font_cache(font_cache);
+
+		let mut application_io = WasmApplicationIo::new_offscreen().await;
+		application_io.resources.insert("/loaded.png".to_string(), Arc::from(vec![1, 2, 3]));
+		editor.runtime.replace_application_io(application_io).await;
+		editor.eval_graph().await.unwrap();
+		let (hits, misses) = cache_stats(&editor);
+
+		// The new application IO takes over the resources loaded into the previous one, and the fonts stay loaded
+		editor.runtime.replace_application_io(WasmApplicationIo::new_offscreen().await).await;
+		let editor_api = &editor.runtime.editor_api;
+		assert!(editor_api.application_io.as_ref().unwrap().resources.contains_key("/loaded.png"));
+		assert!(editor_api.font_cache.loaded_font(&font));
+
+		// The graph renders without errors, reusing the outputs of the nodes which don't use the application IO
+		editor.eval_graph().await.expect("The graph should render with the new application IO");
+		let (hits_after_swap, misses_after_swap) = cache_stats(&editor);
+		assert_eq!(misses_after_swap, misses);
+		assert!(hits_after_swap > hits);
+	}
+
+	#[tokio::test]
+	async fn application_io_given_during_an_evaluation_is_swapped_in_before_the_next_one() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+		let cache_node = add_cache_node(&mut editor).await;
+		editor.eval_graph().await.unwrap();
+		let (hits, misses) = cache_node_stats(&editor, cache_node);
+
+		// While the runtime is locked for an evaluation, the new application IO waits for it to finish
+		let mut application_io = WasmApplicationIo::new_offscreen().await;
+		application_io.resources.insert("/deferred.png".to_string(), Arc::from(vec![1, 2, 3]));
+		let evaluation = NODE_RUNTIME.lock();
+		let result = replace_application_io(application_io).await;
+		drop(evaluation);
+		assert!(matches!(result, Err(ApplicationIoSwapError::EvaluationInProgress)));
+		assert!(application_io_swap_pending());
+
+		// The next evaluation swaps it in first, and still reuses the cached outputs
+		editor.eval_graph().await.expect("The graph should render with the deferred application IO");
+		assert!(!application_io_swap_pending());
+		let application_io = editor.runtime.editor_api.application_io.as_ref().unwrap();
+		assert!(application_io.resources.contains_key("/deferred.png"));
+		let (hits_after_swap, misses_after_swap) = cache_node_stats(&editor, cache_node);
+		assert_eq!(misses_after_swap, misses);
+		assert!(hits_after_swap > hits);
+	}
+
+	/// Evaluates the node on its own, apart from the rest of the document's graph.
+	async fn evaluate_node_directly(network: &NodeNetwork, node_id: NodeId) -> TaggedValue {
+		let mut network = NodeNetwork {
+			exports: vec![NodeInput::node(node_id, 0)],
+			nodes: [(node_id, network.nodes[&node_id].clone())].into_iter().collect(),
+			..Default::default()
+		};
+		preprocessor::expand_network(&mut network, &preprocessor::generate_node_substitutions());
+
+		let proto_network = graph_craft::graphene_compiler::Compiler {}.compile_single(network).expect("The node should compile");
+		let executor = interpreted_executor::dynamic_executor::DynamicExecutor::new(proto_network).await.expect("The node should type check");
+		executor.tree().eval_tagged_value(executor.output(), Context::default()).await.expect("The node should evaluate")
+	}
+
+	/// The positions of the points of the vector data, which unlike their IDs are the same each time it's generated.
+	fn point_positions(value: &TaggedValue) -> Vec<DVec2> {
+		let TaggedValue::VectorData(table) = value else { panic!("The value should be vector data") };
+		table.instance_ref_iter().flat_map(|vector_data| vector_data.instance.point_domain.positions().to_vec()).collect()
+	}
+
+	fn inspected_value(editor: &test_prelude::EditorTestUtils, node_path: &[NodeId]) -> Option<InspectedValue> {
+		editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.inspected_value(node_path).cloned()
+	}
+
+	#[tokio::test]
+	async fn inspected_node_keeps_its_output_from_mid_graph() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next().unwrap();
+		let ellipse_node = NodeGraphLayer::new(layer, &document.network_interface).upstream_node_id_from_name("Ellipse").unwrap();
+		let node_path = vec![ellipse_node];
+
+		// Outputs are only kept while node inspection is enabled
+		editor.editor.handle_message(PortfolioMessage::InspectNodeOutput { node_path: node_path.clone() });
+		editor.runtime.run().await;
+		editor.editor.poll_node_graph_evaluation(&mut VecDeque::new()).unwrap();
+		assert!(inspected_value(&editor, &node_path).is_none());
+
+		editor.editor.handle_message(PortfolioMessage::SetNodeInspection { enabled: true });
+		editor.runtime.run().await;
+		editor.editor.poll_node_graph_evaluation(&mut VecDeque::new()).unwrap();
+		let Some(InspectedValue::Value(inspected)) = inspected_value(&editor, &node_path) else {
+			panic!("The output of the Ellipse node should be kept")
+		};
+
+		// The Ellipse node's output, which feeds the rest of the layer's nodes, matches evaluating it directly
+		let direct = evaluate_node_directly(&editor.active_document().network_interface.resolved_document_network(), ellipse_node).await;
+		assert_eq!(point_positions(&inspected), point_positions(&direct));
+		assert!(!point_positions(&direct).is_empty());
+
+		// The fetched value gives the preview of the output, and the whole output since it's small
+		let responses = editor.editor.handle_message(PortfolioMessage::RequestInspectedValue {
+			node_path: node_path.clone(),
+			full: true,
+		});
+		let fetched = responses
+			.into_iter()
+			.find_map(|message| match message {
+				FrontendMessage::UpdateInspectedValue { node_path: fetched_path, value } if fetched_path == node_path => value,
+				_ => None,
+			})
+			.expect("The inspected value should be sent");
+		assert_eq!(fetched.preview, Some(direct.preview()));
+		let full: TaggedValue = serde_json::from_str(&fetched.full.expect("The whole output should be sent")).unwrap();
+		assert_eq!(point_positions(&full), point_positions(&direct));
+
+		// Unmarking the node forgets its output
+		editor.editor.handle_message(PortfolioMessage::StopInspectingNodeOutput { node_path: node_path.clone() });
+		assert!(inspected_value(&editor, &node_path).is_none());
+	}
+
+	/// A document with a 100x50 "Banner" artboard and a 60x60 "Icon" artboard.
+	async fn two_artboards() -> (test_prelude::EditorTestUtils, Vec<(LayerNodeIdentifier, String)>) {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.drag_tool(ToolType::Artboard, 0., 0., 100., 50., ModifierKeys::empty()).await;
+		editor.drag_tool(ToolType::Artboard, 200., 0., 260., 60., ModifierKeys::empty()).await;
+
+		let document = editor.active_document();
+		let artboards = document
+			.metadata()
+			.all_layers()
+			.filter(|layer| document.network_interface.is_artboard(&layer.to_node(), &[]))
+			.map(|layer| {
+				let [start, end] = document.metadata().bounding_box_document(layer).unwrap();
+				(layer, if end.x - start.x > 80. { "Banner" } else { "Icon" }.to_string())
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(artboards.len(), 2);
+		(editor, artboards)
+	}
+
+	fn export_artboards(artboards: Vec<(LayerNodeIdentifier, String)>) -> PortfolioMessage {
+		PortfolioMessage::SubmitArtboardsExport {
+			artboards,
+			scale_factors: vec![1., 2.],
+			file_name_template: "{artboard}_{scale}x".to_string(),
+			file_type: FileType::Png,
+			transparent_background: false,
+			color_space: Default::default(),
+		}
+	}
+
+	/// Renders the exports one at a time until the batch is done, returning the name and size of each exported image.
+	async fn run_export_batch(editor: &mut test_prelude::EditorTestUtils) -> Vec<(String, (f64, f64))> {
+		let mut exported = Vec::new();
+		for _ in 0..10 {
+			editor.runtime.run().await;
+			let mut responses = VecDeque::new();
+			editor.editor.poll_node_graph_evaluation(&mut responses).expect("The exports should render");
+			exported.extend(responses.into_iter().filter_map(|message| match message {
+				Message::Frontend(FrontendMessage::TriggerDownloadImage { name, size, .. }) => Some((name, size)),
+				_ => None,
+			}));
+
+			if editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.export_batch.is_none() {
+				break;
+			}
+		}
+		exported
+	}
+
+	#[tokio::test]
+	async fn each_artboard_is_exported_at_each_scale_factor() {
+		let (mut editor, artboards) = two_artboards().await;
+
+		// The progress of the exports is shown while they're rendered
+		let frontend_messages = editor.editor.handle_message(export_artboards(artboards));
+		assert!(
+			frontend_messages
+				.iter()
+				.any(|message| matches!(message, FrontendMessage::DisplayDialog { title, .. } if title == "Exporting"))
+		);
+
+		let mut exported = run_export_batch(&mut editor).await;
+		exported.sort_by(|a, b| a.0.cmp(&b.0));
+		assert_eq!(
+			exported,
+			[
+				("Banner_1x.png".to_string(), (100., 50.)),
+				("Banner_2x.png".to_string(), (200., 100.)),
+				("Icon_1x.png".to_string(), (60., 60.)),
+				("Icon_2x.png".to_string(), (120., 120.)),
+			]
+		);
+		assert!(editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.futures.is_empty());
+	}
+
+	#[tokio::test]
+	async fn cancelling_an_export_batch_drops_the_remaining_exports() {
+		let (mut editor, artboards) = two_artboards().await;
+
+		editor.editor.handle_message(export_artboards(artboards));
+		let frontend_messages = editor.editor.handle_message(PortfolioMessage::CancelArtboardsExport);
+		assert!(frontend_messages.contains(&FrontendMessage::DisplayDialogDismiss));
+
+		// The export which was already being rendered finishes without being downloaded
+		assert!(run_export_batch(&mut editor).await.is_empty());
+		assert!(editor.editor.dispatcher.message_handlers.portfolio_message_handler.executor.futures.is_empty());
+	}
+
+	#[tokio::test]
+	async fn exported_svg_identifies_named_layers() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_rect(0., 0., 100., 100.).await;
+		editor.draw_rect(200., 0., 300., 100.).await;
+		editor.draw_ellipse(400., 0., 500., 100.).await;
+
+		let layers = editor.active_document().metadata().all_layers().collect::<Vec<_>>();
+		for (layer, name) in layers.into_iter().zip(["Sky Background", "Sky Background", "Café <Logo>"]) {
+			let new_name = name.to_string();
+			editor.editor.handle_message(NodeGraphMessage::RenameLayer { layer, new_name });
+		}
+
+		editor.editor.handle_message(PortfolioMessage::SubmitDocumentExport {
+			file_name: "Named".to_string(),
+			file_type: FileType::Svg,
+			scale_factor: 1.,
+			bounds: ExportBounds::AllArtwork,
+			transparent_background: false,
+			color_space: Default::default(),
+		});
+		editor.runtime.run().await;
+		let mut responses = VecDeque::new();
+		editor.editor.poll_node_graph_evaluation(&mut responses).expect("The export should render");
+		let svg = responses
+			.into_iter()
+			.find_map(|message| match message {
+				Message::Frontend(FrontendMessage::TriggerDownloadTextFile { document, .. }) => Some(document),
+				_ => None,
+			})
+			.expect("The SVG should be downloaded");
+
+		assert!(svg.contains(r#"id="Sky-Background" data-name="Sky Background""#), "{svg}");
+		assert!(svg.contains(r#"id="Sky-Background-2" data-name="Sky Background""#), "{svg}");
+		assert!(svg.contains(r#"id="Café-Logo" data-name="Café &lt;Logo>""#), "{svg}");
+	}
+
+	#[tokio::test]
+	async fn polling_without_a_document_is_a_typed_error() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+
+		let mut responses = VecDeque::new();
+		assert_eq!(editor.editor.poll_node_graph_evaluation(&mut responses), Err(NodeGraphExecutionError::NoActiveDocument));
+		assert!(responses.is_empty());
+	}
+
+	/// Renders the viewport and returns the layers which its render metadata has click targets for.
+	async fn rendered_layers(editor: &mut test_prelude::EditorTestUtils) -> HashSet<LayerNodeIdentifier> {
+		editor.editor.handle_message(NodeGraphMessage::RunDocumentGraph);
+		editor.runtime.run().await;
+		let mut responses = VecDeque::new();
+		assert!(editor.editor.poll_node_graph_evaluation(&mut responses).is_ok());
+
+		let layers = editor.active_document().metadata().all_layers().collect::<Vec<_>>();
+		responses
+			.into_iter()
+			.find_map(|message| match message {
+				Message::Portfolio(PortfolioMessage::Document(DocumentMessage::UpdateClickTargets { click_targets })) => {
+					Some(layers.iter().copied().filter(|layer| click_targets.contains_key(&layer.to_node())).collect())
+				}
+				_ => None,
+			})
+			.expect("The render should update the click targets")
+	}
+
+	#[tokio::test]
+	async fn soloing_renders_only_the_soloed_subtree() {
+		let mut editor = test_prelude::EditorTestUtils::create();
+		editor.new_document().await;
+		editor.draw_ellipse(0., 0., 100., 50.).await;
+		editor.draw_rect(200., 0., 300., 100.).await;
+		editor.draw_rect(400., 0., 500., 100.).await;
+
+		let [other, rect, ellipse] = editor.active_document().metadata().all_layers().collect::<Vec<_>>()[..] else {
+			panic!("There should be three layers");
+		};
+		editor.editor.handle_message(NodeGraphMessage::SelectedNodesSet {
+			nodes: vec![rect.to_node(), ellipse.to_node()],
+		});
+		editor.editor.handle_message(DocumentMessage::GroupSelectedLayers {
+			group_folder_type: GroupFolderType::Layer,
+		});
+		let group = ellipse.parent(editor.active_document().metadata()).unwrap();
+		assert!(rendered_layers(&mut editor).await.is_superset(&HashSet::from([other, rect, ellipse])));
+
+		editor.active_document_mut().set_save_state(true);
+		let undo_history_len = editor.active_document().undo_history_len();
+
+		// Soloing a layer within the group leaves out its siblings and the layers outside the group
+		editor.editor.handle_message(DocumentMessage::ToggleLayerSolo { id: ellipse.to_node() });
+		let rendered = rendered_layers(&mut editor).await;
+		assert!(rendered.contains(&ellipse));
+		assert!(!rendered.contains(&rect) && !rendered.contains(&other));
+
+		// Soloing the group solos its whole subtree
+		editor.editor.handle_message(DocumentMessage::ToggleLayerSolo { id: group.to_node() });
+		let rendered = rendered_layers(&mut editor).await;
+		assert!(rendered.contains(&ellipse) && rendered.contains(&rect));
+		assert!(!rendered.contains(&other));
+
+		// The solo state doesn't modify the document
+		assert!(editor.active_document().is_saved());
+		assert_eq!(editor.active_document().undo_history_len(), undo_history_len);
+
+		// Clearing the solo renders every layer again
+		editor.editor.handle_message(DocumentMessage::ClearSoloLayer);
+		assert!(rendered_layers(&mut editor).await.is_superset(&HashSet::from([other, rect, ellipse])));
+	}
 }
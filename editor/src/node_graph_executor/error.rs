@@ -0,0 +1,86 @@
+use graph_craft::Type;
+use graph_craft::document::NodeId;
+use graph_craft::proto::{GraphError, GraphErrorType};
+use thiserror::Error;
+
+/// The reason the node graph could not be evaluated into a result for the active document.
+#[derive(Clone, Debug, PartialEq, Error)]
+pub enum NodeGraphExecutionError {
+	/// There is no open document to evaluate, which is expected rather than something to report to the user.
+	#[error("No active document")]
+	NoActiveDocument,
+
+	/// A specific node in the graph could not be compiled, such as when an input is given a type it doesn't support.
+	#[error("Node graph evaluation failed:\n{0}")]
+	Node(NodeError),
+
+	/// The graph failed in a way which can't be attributed to a single node.
+	#[error("{0}")]
+	Evaluation(String),
+}
+
+impl NodeGraphExecutionError {
+	/// The error of the node which caused the failure, if it can be attributed to one.
+	pub fn node_error(&self) -> Option<&NodeError> {
+		match self {
+			Self::Node(node_error) => Some(node_error),
+			_ => None,
+		}
+	}
+}
+
+impl From<String> for NodeGraphExecutionError {
+	fn from(error: String) -> Self {
+		Self::Evaluation(error)
+	}
+}
+
+/// The reason a new application IO couldn't be given to the node runtime right away, in which case it's swapped in before the next evaluation instead.
+/// Until then, [`application_io_swap_pending`](super::application_io_swap_pending) returns true.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Error)]
+pub enum ApplicationIoSwapError {
+	/// The node runtime hasn't been created yet.
+	#[error("The node runtime isn't ready, so the application IO will be replaced once it is")]
+	RuntimeNotReady,
+
+	/// The graph is being evaluated, and its nodes may still be using the current application IO.
+	#[error("The graph is being evaluated, so the application IO will be replaced once the evaluation finishes")]
+	EvaluationInProgress,
+}
+
+/// An error raised by one node of the graph, located by its path so it can be pointed out in the node graph.
+#[derive(Clone, Debug, PartialEq, Error)]
+#[error("{}{message}", .layer_name.as_ref().map(|name| format!("In the layer \"{name}\": ")).unwrap_or_default())]
+pub struct NodeError {
+	/// The path to the node from the document network, e.g. [grandparent_id, parent_id, node_id].
+	pub node_path: Vec<NodeId>,
+	/// The index of the input whose type the node can't accept, if the error is a type mismatch.
+	pub input_index: Option<usize>,
+	/// The type the node requires for the mismatched input.
+	pub expected: Option<Type>,
+	/// The type the mismatched input actually receives.
+	pub actual: Option<Type>,
+	/// A human-readable description of the error.
+	pub message: String,
+	/// The name of the layer which the node feeds into, so the user can tell where in the document the error is.
+	pub layer_name: Option<String>,
+}
+
+impl From<&GraphError> for NodeError {
+	fn from(error: &GraphError) -> Self {
+		// Of the implementations closest to matching, the first mismatched input is reported as the cause
+		let mismatch = match &error.error {
+			GraphErrorType::InvalidImplementations { error_inputs, .. } => error_inputs.iter().flatten().min_by_key(|(index, _)| *index),
+			_ => None,
+		};
+
+		Self {
+			node_path: error.node_path.clone(),
+			input_index: mismatch.map(|(index, _)| *index),
+			expected: mismatch.map(|(_, (_, expected))| expected.clone()),
+			actual: mismatch.map(|(_, (found, _))| found.clone()),
+			message: format!("{:?}", error.error),
+			layer_name: None,
+		}
+	}
+}
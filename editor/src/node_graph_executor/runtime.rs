@@ -1,6 +1,8 @@
+use super::thumbnails::{Thumbnail, ThumbnailGenerator};
 use super::*;
-use crate::messages::frontend::utility_types::{ExportBounds, FileType};
-use glam::{DAffine2, DVec2};
+use crate::consts::INSPECTED_VALUE_SIZE_LIMIT;
+use crate::messages::frontend::utility_types::{ExportBounds, FileType, FrontendNodeCacheStats, RasterColorSpace};
+use glam::DVec2;
 use graph_craft::document::value::TaggedValue;
 use graph_craft::document::{NodeId, NodeNetwork};
 use graph_craft::graphene_compiler::Compiler;
@@ -9,19 +11,23 @@ use graph_craft::wasm_application_io::EditorPreferences;
 use graph_craft::{ProtoNodeIdentifier, concrete};
 use graphene_std::Context;
 use graphene_std::application_io::{ImageTexture, NodeGraphUpdateMessage, NodeGraphUpdateSender, RenderConfig};
-use graphene_std::instances::Instance;
+use graphene_std::cancellation::{self, CancellationToken};
+use graphene_std::instances::{Instance, Instances};
 use graphene_std::memo::IORecord;
-use graphene_std::renderer::{GraphicElementRendered, RenderParams, SvgRender};
-use graphene_std::renderer::{RenderSvgSegmentList, SvgSegment};
+use graphene_std::raster::auto_levels;
+use graphene_std::raster::histogram::Histogram;
+use graphene_std::raster_types::{CPU, RasterDataTable};
+use graphene_std::renderer::GraphicElementRendered;
 use graphene_std::text::FontCache;
-use graphene_std::vector::style::ViewMode;
 use graphene_std::vector::{VectorData, VectorDataTable};
 use graphene_std::wasm_application_io::{RenderOutputType, WasmApplicationIo, WasmEditorApi};
 use interpreted_executor::dynamic_executor::{DynamicExecutor, IntrospectError, ResolvedDocumentNodeTypesDelta};
 use interpreted_executor::util::wrap_network_in_scope;
 use once_cell::sync::Lazy;
 use spin::Mutex;
+use std::hash::Hash;
 use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc::{Receiver, Sender};
 
 /// Persistent data between graph executions. It's updated via message passing from the editor thread with [`GraphRuntimeRequest`]`.
@@ -37,20 +43,29 @@ pub struct NodeRuntime {
 	editor_preferences: EditorPreferences,
 	old_graph: Option<NodeNetwork>,
 	update_thumbnails: bool,
+	/// Shared with the editor, which records each viewport evaluation it requests so an older one still running can stop early
+	cancellation: CancellationToken,
 
-	editor_api: Arc<WasmEditorApi>,
+	pub(super) editor_api: Arc<WasmEditorApi>,
 	node_graph_errors: GraphErrors,
 	monitor_nodes: Vec<Vec<NodeId>>,
 
 	/// Which node is inspected and which monitor node is used (if any) for the current execution
 	inspect_state: Option<InspectState>,
+	/// Which Levels node has its auto levels computed during the next execution
+	auto_levels_state: Option<AutoLevelsState>,
+	/// Which nodes have their outputs kept after each execution, and the monitor nodes used for them
+	inspected_nodes: Vec<InspectedNodeState>,
+	/// Which layer has the histogram of its content computed during the next execution
+	histogram_layer: Option<NodeId>,
+	/// Whether the reuse statistics of the cached node outputs are sent to the frontend after the next execution
+	node_cache_stats_requested: bool,
 
 	/// Mapping of the fully-qualified node paths to their preprocessor substitutions.
 	substitutions: HashMap<ProtoNodeIdentifier, DocumentNode>,
 
-	// TODO: Remove, it doesn't need to be persisted anymore
-	/// The current renders of the thumbnails for layer nodes.
-	thumbnail_renders: HashMap<NodeId, Vec<SvgSegment>>,
+	/// Generates the thumbnails of the layer nodes from their outputs.
+	thumbnails: ThumbnailGenerator,
 	vector_modify: HashMap<NodeId, VectorData>,
 }
 
@@ -61,6 +76,8 @@ pub enum GraphRuntimeRequest {
 	ExecutionRequest(ExecutionRequest),
 	FontCacheUpdate(FontCache),
 	EditorPreferencesUpdate(EditorPreferences),
+	HistogramRequest(NodeId),
+	NodeCacheStatsRequest,
 }
 
 #[derive(Debug, serde::Serialize, serde::Deserialize)]
@@ -68,6 +85,10 @@ pub struct GraphUpdate {
 	pub(super) network: NodeNetwork,
 	/// The node that should be temporary inspected during execution
 	pub(super) inspect_node: Option<NodeId>,
+	/// The path to the Levels node whose auto levels should be computed from its input image during execution
+	pub(super) auto_levels_node: Option<Vec<NodeId>>,
+	/// The paths to the nodes whose outputs should be kept after each execution, so they can be inspected in the data panel
+	pub(super) inspected_nodes: Vec<Vec<NodeId>>,
 }
 
 #[derive(Default, Debug, Clone, serde::Serialize, serde::Deserialize)]
@@ -77,6 +98,7 @@ pub struct ExportConfig {
 	pub scale_factor: f64,
 	pub bounds: ExportBounds,
 	pub transparent_background: bool,
+	pub color_space: RasterColorSpace,
 	pub size: DVec2,
 }
 
@@ -91,6 +113,10 @@ impl InternalNodeGraphUpdateSender {
 	fn send_execution_response(&self, response: ExecutionResponse) {
 		self.0.send(NodeGraphUpdate::ExecutionResponse(response)).expect("Failed to send response")
 	}
+
+	fn send_execution_cancelled(&self, execution_id: u64) {
+		self.0.send(NodeGraphUpdate::ExecutionCancelled(execution_id)).expect("Failed to send response")
+	}
 }
 
 impl NodeGraphUpdateSender for InternalNodeGraphUpdateSender {
@@ -110,6 +136,7 @@ impl NodeRuntime {
 			editor_preferences: EditorPreferences::default(),
 			old_graph: None,
 			update_thumbnails: true,
+			cancellation: CancellationToken::new(),
 
 			editor_api: WasmEditorApi {
 				font_cache: FontCache::default(),
@@ -125,13 +152,32 @@ impl NodeRuntime {
 
 			substitutions: preprocessor::generate_node_substitutions(),
 
-			thumbnail_renders: Default::default(),
+			thumbnails: ThumbnailGenerator::default(),
 			vector_modify: Default::default(),
 			inspect_state: None,
+			auto_levels_state: None,
+			inspected_nodes: Vec::new(),
+			histogram_layer: None,
+			node_cache_stats_requested: false,
 		}
 	}
 
+	/// The token through which the editor cancels the viewport renders which are superseded by newer requests.
+	pub(super) fn cancellation_token(&self) -> CancellationToken {
+		self.cancellation.clone()
+	}
+
 	pub async fn run(&mut self) -> Option<ImageTexture> {
+		// An application IO given while the previous evaluation was running is swapped in before evaluating the requests which arrived meanwhile
+		let pending_application_io = PENDING_APPLICATION_IO.lock().take();
+		if let Some(application_io) = pending_application_io {
+			self.replace_application_io(application_io).await;
+
+			// Another application IO may have been given during the swap, which is then swapped in before the next evaluation
+			let pending_application_io = PENDING_APPLICATION_IO.lock();
+			APPLICATION_IO_SWAP_PENDING.store(pending_application_io.is_some(), Ordering::SeqCst);
+		}
+
 		if self.editor_api.application_io.is_none() {
 			self.editor_api = WasmEditorApi {
 				#[cfg(all(not(test), target_arch = "wasm32"))]
@@ -148,16 +194,27 @@ impl NodeRuntime {
 		let mut font = None;
 		let mut preferences = None;
 		let mut graph = None;
+		let mut exports = Vec::new();
 		let mut execution = None;
+		let mut histogram = None;
+		let mut node_cache_stats = None;
 		for request in self.receiver.try_iter() {
 			match request {
 				GraphRuntimeRequest::GraphUpdate(_) => graph = Some(request),
-				GraphRuntimeRequest::ExecutionRequest(_) => execution = Some(request),
+				GraphRuntimeRequest::ExecutionRequest(ExecutionRequest { ref render_config, .. }) if render_config.for_export => exports.push(request),
+				GraphRuntimeRequest::ExecutionRequest(_) => {
+					// Only the newest viewport render is evaluated, the ones it supersedes are reported as cancelled without running
+					if let Some(GraphRuntimeRequest::ExecutionRequest(superseded)) = execution.replace(request) {
+						self.sender.send_execution_cancelled(superseded.execution_id);
+					}
+				}
 				GraphRuntimeRequest::FontCacheUpdate(_) => font = Some(request),
 				GraphRuntimeRequest::EditorPreferencesUpdate(_) => preferences = Some(request),
+				GraphRuntimeRequest::HistogramRequest(_) => histogram = Some(request),
+				GraphRuntimeRequest::NodeCacheStatsRequest => node_cache_stats = Some(request),
 			}
 		}
-		let requests = [font, preferences, graph, execution].into_iter().flatten();
+		let requests = [font, preferences, histogram, node_cache_stats, graph].into_iter().flatten().chain(exports).chain(execution);
 
 		for request in requests {
 			match request {
@@ -175,6 +232,8 @@ impl NodeRuntime {
 					}
 				}
 				GraphRuntimeRequest::EditorPreferencesUpdate(preferences) => {
+					graphene_std::brush::brush_cache::set_memory_budget(preferences.brush_cache_budget);
+					self.executor.set_node_cache_budget(preferences.node_cache_budget);
 					self.editor_preferences = preferences.clone();
 					self.editor_api = WasmEditorApi {
 						font_cache: self.editor_api.font_cache.clone(),
@@ -188,9 +247,25 @@ impl NodeRuntime {
 						let _ = self.update_network(graph).await;
 					}
 				}
-				GraphRuntimeRequest::GraphUpdate(GraphUpdate { mut network, inspect_node }) => {
+				GraphRuntimeRequest::HistogramRequest(layer) => {
+					self.histogram_layer = Some(layer);
+				}
+				GraphRuntimeRequest::NodeCacheStatsRequest => {
+					self.node_cache_stats_requested = true;
+				}
+				GraphRuntimeRequest::GraphUpdate(GraphUpdate {
+					mut network,
+					inspect_node,
+					auto_levels_node,
+					inspected_nodes,
+				}) => {
 					// Insert the monitor node to manage the inspection
 					self.inspect_state = inspect_node.map(|inspect| InspectState::monitor_inspect_node(&mut network, inspect));
+					self.auto_levels_state = auto_levels_node.map(|node_path| AutoLevelsState::monitor_levels_input(&mut network, node_path));
+					self.inspected_nodes = inspected_nodes
+						.into_iter()
+						.filter_map(|node_path| InspectedNodeState::monitor_output(&mut network, node_path))
+						.collect();
 
 					self.old_graph = Some(network.clone());
 					self.node_graph_errors.clear();
@@ -203,22 +278,39 @@ impl NodeRuntime {
 				}
 				GraphRuntimeRequest::ExecutionRequest(ExecutionRequest { execution_id, render_config, .. }) => {
 					let transform = render_config.viewport.transform;
+					let for_export = render_config.for_export;
+
+					// Exports are never superseded, but a viewport render stops early once a newer one has been requested
+					let token = (!for_export).then(|| self.cancellation.for_generation(execution_id));
+					let result = cancellation::scope(token.clone(), self.execute_network(render_config)).await;
+					if token.is_some_and(|token| token.is_cancelled()) {
+						// The result may be incomplete, so it's discarded in favor of the newer render which is already queued
+						self.sender.send_execution_cancelled(execution_id);
+						return None;
+					}
 
-					let result = self.execute_network(render_config).await;
 					let mut responses = VecDeque::new();
 					// TODO: Only process monitor nodes if the graph has changed, not when only the Footprint changes
 					self.process_monitor_nodes(&mut responses, self.update_thumbnails);
-					self.update_thumbnails = false;
+					// The thumbnails which didn't fit in this evaluation's budget are generated during the next one
+					self.update_thumbnails = self.thumbnails.deferred();
+
+					if std::mem::take(&mut self.node_cache_stats_requested) {
+						responses.push_back(FrontendMessage::UpdateNodeCacheStats { stats: self.node_cache_stats() });
+					}
 
 					// Resolve the result from the inspection by accessing the monitor node
 					let inspect_result = self.inspect_state.and_then(|state| state.access(&self.executor));
+					// The auto levels are only needed once, for the execution following their request
+					let auto_levels = self.auto_levels_state.take().map(|state| state.access(&self.executor));
+					// Keep the outputs of the inspected nodes from this execution, or describe those which are too large to keep
+					let inspected_values = self.inspected_nodes.iter().filter_map(|state| Some((state.node_path.clone(), state.access(&self.executor)?))).collect();
 
 					let texture = if let Ok(TaggedValue::RenderOutput(RenderOutput {
 						data: RenderOutputType::Texture(texture),
 						..
 					})) = &result
 					{
-						// We can early return becaus we know that there is at most one execution request and it will always be handled last
 						Some(texture.clone())
 					} else {
 						None
@@ -230,14 +322,39 @@ impl NodeRuntime {
 						transform,
 						vector_modify: self.vector_modify.clone(),
 						inspect_result,
+						auto_levels,
+						inspected_values,
 					});
-					return texture;
+					// We can early return because we know that there is at most one viewport render and it will always be handled last
+					if !for_export {
+						return texture;
+					}
 				}
 			}
 		}
 		None
 	}
 
+	/// Gives the graph a new application IO, which takes over the resources and surfaces of the current one, while keeping the font cache.
+	/// The graph is compiled again so its nodes use the new application IO, which leaves the cached outputs of the nodes that don't use it in place.
+	pub async fn replace_application_io(&mut self, mut application_io: WasmApplicationIo) {
+		if let Some(previous) = &self.editor_api.application_io {
+			application_io.inherit_from(previous);
+		}
+
+		self.editor_api = WasmEditorApi {
+			font_cache: self.editor_api.font_cache.clone(),
+			application_io: Some(application_io.into()),
+			node_graph_message_sender: Box::new(self.sender.clone()),
+			editor_preferences: Box::new(self.editor_preferences.clone()),
+		}
+		.into();
+		if let Some(graph) = self.old_graph.clone() {
+			// We ignore this result as compilation errors should have been reported in an earlier iteration
+			let _ = self.update_network(graph).await;
+		}
+	}
+
 	async fn update_network(&mut self, mut graph: NodeNetwork) -> Result<ResolvedDocumentNodeTypesDelta, String> {
 		preprocessor::expand_network(&mut graph, &self.substitutions);
 
@@ -285,13 +402,20 @@ impl NodeRuntime {
 	/// Updates state data
 	pub fn process_monitor_nodes(&mut self, responses: &mut VecDeque<FrontendMessage>, update_thumbnails: bool) {
 		// TODO: Consider optimizing this since it's currently O(m*n^2), with a sort it could be made O(m * n*log(n))
-		self.thumbnail_renders.retain(|id, _| self.monitor_nodes.iter().any(|monitor_node_path| monitor_node_path.contains(id)));
+		self.thumbnails.retain(|id| self.monitor_nodes.iter().any(|monitor_node_path| monitor_node_path.contains(&id)));
+		if update_thumbnails {
+			self.thumbnails.begin_evaluation();
+		}
 
 		for monitor_node_path in &self.monitor_nodes {
 			// Skip the inspect monitor node
 			if self.inspect_state.is_some_and(|inspect_state| monitor_node_path.last().copied() == Some(inspect_state.monitor_node)) {
 				continue;
 			}
+			// Skip the monitor nodes of the inspected nodes, whose outputs are kept separately
+			if self.inspected_nodes.iter().any(|state| &state.monitor_path == monitor_node_path) {
+				continue;
+			}
 			// The monitor nodes are located within a document node, and are thus children in that network, so this gets the parent document node's ID
 			let Some(parent_network_node_id) = monitor_node_path.len().checked_sub(2).and_then(|index| monitor_node_path.get(index)).copied() else {
 				warn!("Monitor node has invalid node id");
@@ -309,9 +433,16 @@ impl NodeRuntime {
 			};
 
 			if let Some(io) = introspected_data.downcast_ref::<IORecord<Context, graphene_std::GraphicElement>>() {
-				Self::process_graphic_element(&mut self.thumbnail_renders, parent_network_node_id, &io.output, responses, update_thumbnails)
+				if self.histogram_layer == Some(parent_network_node_id) {
+					self.histogram_layer = None;
+					responses.push_back(FrontendMessage::UpdateHistogram {
+						layer: Some(parent_network_node_id),
+						histogram: Some(Self::layer_histogram(&io.output)),
+					});
+				}
+				Self::process_graphic_element(&mut self.thumbnails, parent_network_node_id, &io.output, responses, update_thumbnails)
 			} else if let Some(io) = introspected_data.downcast_ref::<IORecord<Context, graphene_std::Artboard>>() {
-				Self::process_graphic_element(&mut self.thumbnail_renders, parent_network_node_id, &io.output, responses, update_thumbnails)
+				Self::process_graphic_element(&mut self.thumbnails, parent_network_node_id, &io.output, responses, update_thumbnails)
 			// Insert the vector modify if we are dealing with vector data
 			} else if let Some(record) = introspected_data.downcast_ref::<IORecord<Context, VectorDataTable>>() {
 				let default = Instance::default();
@@ -323,65 +454,65 @@ impl NodeRuntime {
 				log::warn!("Failed to downcast monitor node output {parent_network_node_id:?}");
 			}
 		}
-	}
 
-	// If this is `GraphicElement` data:
-	// Regenerate click targets and thumbnails for the layers in the graph, modifying the state and updating the UI.
-	fn process_graphic_element(
-		thumbnail_renders: &mut HashMap<NodeId, Vec<SvgSegment>>,
-		parent_network_node_id: NodeId,
-		graphic_element: &impl GraphicElementRendered,
-		responses: &mut VecDeque<FrontendMessage>,
-		update_thumbnails: bool,
-	) {
-		// RENDER THUMBNAIL
-
-		if !update_thumbnails {
-			return;
+		// The requested layer wasn't found among the monitored layers, or it isn't a graphical layer
+		if let Some(layer) = self.histogram_layer.take() {
+			responses.push_back(FrontendMessage::UpdateHistogram { layer: Some(layer), histogram: None });
 		}
+	}
+
+	/// The reuse statistics of the cached outputs of each document node, with the nodes using the most memory first.
+	fn node_cache_stats(&self) -> Vec<FrontendNodeCacheStats> {
+		let mut stats: Vec<_> = self
+			.executor
+			.node_cache_stats()
+			.into_iter()
+			.map(|(node_path, stats)| FrontendNodeCacheStats {
+				node_path: node_path.into_vec(),
+				hits: stats.hits,
+				misses: stats.misses,
+				entries: stats.entries,
+				bytes: stats.bytes,
+			})
+			.collect();
+		stats.sort_by(|a, b| b.bytes.cmp(&a.bytes).then_with(|| a.node_path.cmp(&b.node_path)));
+		stats
+	}
 
-		// Skip thumbnails if the layer is too complex (for performance)
-		if graphic_element.render_complexity() > 1000 {
-			let old = thumbnail_renders.insert(parent_network_node_id, Vec::new());
-			if old.is_none_or(|v| !v.is_empty()) {
-				responses.push_back(FrontendMessage::UpdateNodeThumbnail {
-					id: parent_network_node_id,
-					value: "<svg viewBox=\"0 0 10 10\"><title>Dense thumbnail omitted for performance</title><line x1=\"0\" y1=\"10\" x2=\"10\" y2=\"0\" stroke=\"red\" /></svg>".to_string(),
-				});
+	/// Computes a single histogram across all the CPU raster images contained within a layer's `GraphicElement` data.
+	fn layer_histogram(graphic_element: &graphene_std::GraphicElement) -> Histogram {
+		fn collect_rasters<'a>(graphic_element: &'a graphene_std::GraphicElement, rasters: &mut Vec<&'a RasterDataTable<CPU>>) {
+			match graphic_element {
+				graphene_std::GraphicElement::GraphicGroup(group) => group.instance_ref_iter().for_each(|instance| collect_rasters(instance.instance, rasters)),
+				graphene_std::GraphicElement::RasterDataCPU(raster) => rasters.push(raster),
+				graphene_std::GraphicElement::VectorData(_) | graphene_std::GraphicElement::RasterDataGPU(_) | graphene_std::GraphicElement::Effect(_) => {}
 			}
-			return;
 		}
 
-		let bounds = graphic_element.bounding_box(DAffine2::IDENTITY, true);
-
-		// Render the thumbnail from a `GraphicElement` into an SVG string
-		let render_params = RenderParams {
-			view_mode: ViewMode::Normal,
-			culling_bounds: bounds,
-			thumbnail: true,
-			hide_artboards: false,
-			for_export: false,
-			for_mask: false,
-			alignment_parent_transform: None,
-		};
-		let mut render = SvgRender::new();
-		graphic_element.render_svg(&mut render, &render_params);
-
-		// And give the SVG a viewbox and outer <svg>...</svg> wrapper tag
-		let [min, max] = bounds.unwrap_or_default();
-		render.format_svg(min, max);
-
-		// UPDATE FRONTEND THUMBNAIL
+		let mut rasters = Vec::new();
+		collect_rasters(graphic_element, &mut rasters);
+		Histogram::from_rasters(rasters)
+	}
 
-		let new_thumbnail_svg = render.svg;
-		let old_thumbnail_svg = thumbnail_renders.entry(parent_network_node_id).or_default();
+	// If this is `GraphicElement` data:
+	// Regenerate click targets and thumbnails for the layers in the graph, modifying the state and updating the UI.
+	fn process_graphic_element<T>(thumbnails: &mut ThumbnailGenerator, parent_network_node_id: NodeId, graphic_element: &T, responses: &mut VecDeque<FrontendMessage>, update_thumbnails: bool)
+	where
+		T: GraphicElementRendered + Hash + Clone + 'static,
+		Instances<T>: GraphicElementRendered,
+	{
+		if !update_thumbnails {
+			return;
+		}
 
-		if old_thumbnail_svg != &new_thumbnail_svg {
-			responses.push_back(FrontendMessage::UpdateNodeThumbnail {
+		match thumbnails.generate(parent_network_node_id, graphic_element) {
+			Some(Thumbnail::Png(png)) => responses.push_back(FrontendMessage::UpdateNodeThumbnailImage { id: parent_network_node_id, png }),
+			Some(Thumbnail::Omitted) => responses.push_back(FrontendMessage::UpdateNodeThumbnail {
 				id: parent_network_node_id,
-				value: new_thumbnail_svg.to_svg_string(),
-			});
-			*old_thumbnail_svg = new_thumbnail_svg;
+				value: "<svg viewBox=\"0 0 10 10\"><title>Dense thumbnail omitted for performance</title><line x1=\"0\" y1=\"10\" x2=\"10\" y2=\"0\" stroke=\"red\" /></svg>".to_string(),
+			}),
+			// The thumbnail is unchanged, or is left for a later evaluation
+			None => {}
 		}
 	}
 }
@@ -406,17 +537,41 @@ pub async fn replace_node_runtime(runtime: NodeRuntime) -> Option<NodeRuntime> {
 	let mut node_runtime = NODE_RUNTIME.lock();
 	node_runtime.replace(runtime)
 }
-pub async fn replace_application_io(application_io: WasmApplicationIo) {
-	let mut node_runtime = NODE_RUNTIME.lock();
-	if let Some(node_runtime) = &mut *node_runtime {
-		node_runtime.editor_api = WasmEditorApi {
-			font_cache: node_runtime.editor_api.font_cache.clone(),
-			application_io: Some(application_io.into()),
-			node_graph_message_sender: Box::new(node_runtime.sender.clone()),
-			editor_preferences: Box::new(node_runtime.editor_preferences.clone()),
-		}
-		.into();
-	}
+
+/// An application IO which couldn't be given to the node runtime right away, waiting to be swapped in before its next evaluation.
+static PENDING_APPLICATION_IO: Lazy<Mutex<Option<WasmApplicationIo>>> = Lazy::new(|| Mutex::new(None));
+/// Whether an application IO is waiting to be swapped in, which stays set until the swap is complete.
+static APPLICATION_IO_SWAP_PENDING: AtomicBool = AtomicBool::new(false);
+
+/// Gives the node runtime a new application IO, see [`NodeRuntime::replace_application_io`].
+///
+/// The swap can't happen while the graph is being evaluated, since the evaluation may still be using the current application IO. In that case, the
+/// evaluation is left to finish and the swap is made before the next one, so the render requests which are already waiting are evaluated with the new one.
+pub async fn replace_application_io(application_io: WasmApplicationIo) -> Result<(), ApplicationIoSwapError> {
+	let Some(mut node_runtime) = NODE_RUNTIME.try_lock() else {
+		defer_application_io_swap(application_io);
+		return Err(ApplicationIoSwapError::EvaluationInProgress);
+	};
+	let Some(node_runtime) = &mut *node_runtime else {
+		defer_application_io_swap(application_io);
+		return Err(ApplicationIoSwapError::RuntimeNotReady);
+	};
+
+	node_runtime.replace_application_io(application_io).await;
+	Ok(())
+}
+
+fn defer_application_io_swap(application_io: WasmApplicationIo) {
+	// A newer application IO replaces one which is still waiting to be swapped in
+	let mut pending_application_io = PENDING_APPLICATION_IO.lock();
+	*pending_application_io = Some(application_io);
+	APPLICATION_IO_SWAP_PENDING.store(true, Ordering::SeqCst);
+}
+
+/// Whether an application IO is waiting for the graph evaluation using the current one to finish before it's swapped in.
+/// Documents shouldn't be opened until it's done, so they aren't evaluated with the application IO which is on its way out.
+pub fn application_io_swap_pending() -> bool {
+	APPLICATION_IO_SWAP_PENDING.load(Ordering::SeqCst)
 }
 
 /// Which node is inspected and which monitor node is used (if any) for the current execution
@@ -486,3 +641,154 @@ impl InspectState {
 		})
 	}
 }
+
+/// The Levels node whose auto levels are computed during execution, and the monitor node inserted before its image input (if it has one)
+#[derive(Debug, Clone)]
+struct AutoLevelsState {
+	node_path: Vec<NodeId>,
+	monitor_path: Option<Vec<NodeId>>,
+}
+
+/// The auto levels computed from the input image of a Levels node during execution
+#[derive(Debug, Clone, PartialEq)]
+pub struct AutoLevelsResult {
+	pub node_path: Vec<NodeId>,
+	/// The `(black, white)` input points of the red, green, and blue channels, or `None` if the input isn't a raster image with visible pixels
+	pub levels: Option<[(f64, f64); 3]>,
+}
+
+impl AutoLevelsState {
+	/// Insert a monitor node between the Levels node and the node connected to its image input
+	pub fn monitor_levels_input(network: &mut NodeNetwork, node_path: Vec<NodeId>) -> Self {
+		let monitor_id = NodeId::new();
+
+		let monitor_path = node_path.split_last().and_then(|(node_id, network_path)| {
+			let nested_network = network.nested_network_mut(network_path)?;
+			let input = nested_network.nodes.get_mut(node_id)?.inputs.first_mut()?;
+			if !matches!(input, NodeInput::Node { .. }) {
+				return None;
+			}
+
+			let image_input = std::mem::replace(input, NodeInput::node(monitor_id, 0));
+			let monitor_node = DocumentNode {
+				inputs: vec![image_input],
+				implementation: DocumentNodeImplementation::ProtoNode(graphene_std::memo::monitor::IDENTIFIER),
+				manual_composition: Some(graph_craft::generic!(T)),
+				skip_deduplication: true,
+				..Default::default()
+			};
+			nested_network.nodes.insert(monitor_id, monitor_node);
+
+			Some([network_path, &[monitor_id]].concat())
+		});
+
+		Self { node_path, monitor_path }
+	}
+
+	/// Compute the auto levels from the image which flowed through the monitor node
+	fn access(self, executor: &DynamicExecutor) -> AutoLevelsResult {
+		let introspected_data = self
+			.monitor_path
+			.and_then(|monitor_path| executor.introspect(&monitor_path).inspect_err(|e| warn!("Failed to introspect monitor node {e}")).ok());
+
+		let levels = introspected_data.and_then(|data| {
+			if let Some(io) = data.downcast_ref::<IORecord<Context, RasterDataTable<CPU>>>() {
+				auto_levels(&io.output)
+			} else if let Some(io) = data.downcast_ref::<IORecord<(), RasterDataTable<CPU>>>() {
+				auto_levels(&io.output)
+			} else {
+				None
+			}
+		});
+
+		AutoLevelsResult { node_path: self.node_path, levels }
+	}
+}
+
+/// A node whose output is kept after each execution, and the monitor node inserted after it
+#[derive(Debug, Clone)]
+struct InspectedNodeState {
+	node_path: Vec<NodeId>,
+	monitor_path: Vec<NodeId>,
+}
+
+/// The output of an inspected node from the most recent execution
+#[derive(Debug, Clone, PartialEq)]
+pub enum InspectedValue {
+	/// The output itself, which is small enough to be kept
+	Value(TaggedValue),
+	/// The type of an output which is too large to be kept, along with its estimated size in bytes, or which can't be kept as a tagged value, whose size is unknown
+	Summary { type_name: String, size: Option<u64> },
+}
+
+impl InspectedValue {
+	/// The name of the type of the output.
+	pub fn type_name(&self) -> String {
+		match self {
+			Self::Value(value) => value.ty().to_string(),
+			Self::Summary { type_name, .. } => type_name.clone(),
+		}
+	}
+
+	/// The estimated size of the output in bytes, if it's known.
+	pub fn size(&self) -> Option<u64> {
+		match self {
+			Self::Value(value) => Some(value.estimated_size()),
+			Self::Summary { size, .. } => *size,
+		}
+	}
+}
+
+impl InspectedNodeState {
+	/// Insert a monitor node after the primary output of the node, which is connected to everything the node's primary output was connected to
+	pub fn monitor_output(network: &mut NodeNetwork, node_path: Vec<NodeId>) -> Option<Self> {
+		let (&inspected_id, network_path) = node_path.split_last()?;
+		let nested_network = network.nested_network_mut(network_path)?;
+		if !nested_network.nodes.contains_key(&inspected_id) {
+			return None;
+		}
+
+		// It is necessary to replace the inputs before inserting the monitor node to avoid changing the input of the new monitor node
+		let monitor_id = NodeId::new();
+		for input in nested_network.nodes.values_mut().flat_map(|node| node.inputs.iter_mut()).chain(&mut nested_network.exports) {
+			let NodeInput::Node { node_id, output_index, .. } = input else { continue };
+			if *output_index == 0 && *node_id == inspected_id {
+				*node_id = monitor_id;
+			}
+		}
+
+		let monitor_node = DocumentNode {
+			inputs: vec![NodeInput::node(inspected_id, 0)],
+			implementation: DocumentNodeImplementation::ProtoNode(graphene_std::memo::monitor::IDENTIFIER),
+			manual_composition: Some(graph_craft::generic!(T)),
+			skip_deduplication: true,
+			..Default::default()
+		};
+		nested_network.nodes.insert(monitor_id, monitor_node);
+
+		let monitor_path = [network_path, &[monitor_id]].concat();
+		Some(Self { node_path, monitor_path })
+	}
+
+	/// Keep the output which flowed through the monitor node, or describe it if it's too large or can't be kept as a tagged value.
+	/// Returns `None` if the node wasn't evaluated, such as when nothing uses its output.
+	fn access(&self, executor: &DynamicExecutor) -> Option<InspectedValue> {
+		let record = executor.introspect(&self.monitor_path).ok()?;
+
+		let value = match TaggedValue::try_from_io_record(&*record) {
+			Ok(value) if value.estimated_size() <= INSPECTED_VALUE_SIZE_LIMIT => InspectedValue::Value(value),
+			Ok(value) => InspectedValue::Summary {
+				type_name: value.ty().to_string(),
+				size: Some(value.estimated_size()),
+			},
+			Err(_) => {
+				let types = executor.tree().source_map().get(self.monitor_path.as_slice());
+				InspectedValue::Summary {
+					type_name: types.map_or_else(|| "Unknown".to_string(), |(_, types)| types.output.to_string()),
+					size: None,
+				}
+			}
+		};
+		Some(value)
+	}
+}
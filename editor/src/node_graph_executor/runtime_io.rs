@@ -1,4 +1,5 @@
 use super::*;
+use graphene_std::cancellation::CancellationToken;
 use std::sync::mpsc::{Receiver, Sender};
 
 /// Handles communication with the NodeRuntime
@@ -7,6 +8,8 @@ pub struct NodeRuntimeIO {
 	// Send to
 	sender: Sender<GraphRuntimeRequest>,
 	receiver: Receiver<NodeGraphUpdate>,
+	/// Shared with the NodeRuntime so requesting a viewport render cancels the older ones it's still evaluating
+	cancellation: CancellationToken,
 }
 
 impl Default for NodeRuntimeIO {
@@ -20,16 +23,19 @@ impl NodeRuntimeIO {
 	pub fn new() -> Self {
 		let (response_sender, response_receiver) = std::sync::mpsc::channel();
 		let (request_sender, request_receiver) = std::sync::mpsc::channel();
-		futures::executor::block_on(replace_node_runtime(NodeRuntime::new(request_receiver, response_sender)));
+		let node_runtime = NodeRuntime::new(request_receiver, response_sender);
+		let cancellation = node_runtime.cancellation_token();
+		futures::executor::block_on(replace_node_runtime(node_runtime));
 
 		Self {
 			sender: request_sender,
 			receiver: response_receiver,
+			cancellation,
 		}
 	}
 	#[cfg(test)]
-	pub fn with_channels(sender: Sender<GraphRuntimeRequest>, receiver: Receiver<NodeGraphUpdate>) -> Self {
-		Self { sender, receiver }
+	pub fn with_channels(sender: Sender<GraphRuntimeRequest>, receiver: Receiver<NodeGraphUpdate>, cancellation: CancellationToken) -> Self {
+		Self { sender, receiver, cancellation }
 	}
 
 	/// Sends a message to the NodeRuntime
@@ -37,6 +43,11 @@ impl NodeRuntimeIO {
 		self.sender.send(message).map_err(|e| e.to_string())
 	}
 
+	/// Cancels the viewport renders requested before the given one, which the NodeRuntime may still be evaluating
+	pub fn supersede(&self, execution_id: u64) {
+		self.cancellation.supersede(execution_id);
+	}
+
 	/// Receives any pending updates from the NodeRuntime
 	pub fn receive(&self) -> impl Iterator<Item = NodeGraphUpdate> + use<'_> {
 		self.receiver.try_iter()
@@ -0,0 +1,239 @@
+use glam::{DAffine2, DVec2, UVec2};
+use graph_craft::document::NodeId;
+use graphene_std::instances::Instances;
+use graphene_std::raster::Image;
+use graphene_std::renderer::GraphicElementRendered;
+use graphene_std::renderer::rasterize::rasterize_cpu;
+use graphene_std::{Color, GraphicElement};
+use std::collections::HashMap;
+use std::hash::{DefaultHasher, Hash, Hasher};
+
+/// The maximum width and height of a thumbnail in pixels.
+pub const THUMBNAIL_RESOLUTION: u32 = 64;
+
+/// The render complexity of all thumbnails which may be generated during one evaluation, the rest are generated during the following evaluations.
+pub const THUMBNAIL_BUDGET: usize = 10_000;
+
+/// The render complexity above which an output's thumbnail is omitted, since generating it would take too long.
+pub const MAX_THUMBNAIL_COMPLEXITY: usize = 1000;
+
+/// A thumbnail of a node's output, produced by the [`ThumbnailGenerator`].
+#[derive(Clone, Debug, PartialEq)]
+pub enum Thumbnail {
+	/// A PNG-encoded preview of the output.
+	Png(Vec<u8>),
+	/// The output is too complex to generate a thumbnail for.
+	Omitted,
+}
+
+/// Generates thumbnails of the outputs of layer nodes for the graph view and the Layers panel.
+///
+/// Each thumbnail is remembered by the hash of the output it was generated from, which is the same hash a `MemoHash` of the output would have,
+/// so a thumbnail is only generated again once that output changes. The work done for each evaluation is limited by a budget of render complexity.
+#[derive(Debug)]
+pub struct ThumbnailGenerator {
+	/// The hash of the output which each node's current thumbnail was generated from
+	output_hashes: HashMap<NodeId, u64>,
+	budget: usize,
+	remaining_budget: usize,
+	/// Whether the thumbnails of some changed outputs were left for a later evaluation since they didn't fit in the budget
+	deferred: bool,
+}
+
+impl Default for ThumbnailGenerator {
+	fn default() -> Self {
+		Self::new(THUMBNAIL_BUDGET)
+	}
+}
+
+impl ThumbnailGenerator {
+	pub fn new(budget: usize) -> Self {
+		Self {
+			output_hashes: HashMap::new(),
+			budget,
+			remaining_budget: budget,
+			deferred: false,
+		}
+	}
+
+	/// Renews the budget for the thumbnails generated from the outputs of a new evaluation.
+	pub fn begin_evaluation(&mut self) {
+		self.remaining_budget = self.budget;
+		self.deferred = false;
+	}
+
+	/// Whether some thumbnails of changed outputs are still outdated since they didn't fit in the budget of the last evaluation.
+	pub fn deferred(&self) -> bool {
+		self.deferred
+	}
+
+	/// Forgets the thumbnails of the nodes which no longer have one, so they are generated again if those nodes return.
+	pub fn retain(&mut self, mut keep: impl FnMut(NodeId) -> bool) {
+		self.output_hashes.retain(|&node, _| keep(node));
+	}
+
+	/// Generates the thumbnail of the node's output, unless it's unchanged since the node's last thumbnail or there's no budget left for it during this evaluation.
+	pub fn generate<T>(&mut self, node: NodeId, output: &T) -> Option<Thumbnail>
+	where
+		T: GraphicElementRendered + Hash + Clone + 'static,
+		Instances<T>: GraphicElementRendered,
+	{
+		let mut hasher = DefaultHasher::new();
+		output.hash(&mut hasher);
+		let output_hash = hasher.finish();
+		if self.output_hashes.get(&node) == Some(&output_hash) {
+			return None;
+		}
+
+		let complexity = output.render_complexity();
+		if complexity > MAX_THUMBNAIL_COMPLEXITY {
+			self.output_hashes.insert(node, output_hash);
+			return Some(Thumbnail::Omitted);
+		}
+		if complexity > self.remaining_budget {
+			self.deferred = true;
+			return None;
+		}
+		self.remaining_budget -= complexity;
+
+		self.output_hashes.insert(node, output_hash);
+		Some(Thumbnail::Png(render_thumbnail(output).to_png()))
+	}
+}
+
+/// Renders the output to an image which fits within the thumbnail resolution.
+fn render_thumbnail<T>(output: &T) -> Image<Color>
+where
+	T: GraphicElementRendered + Clone + 'static,
+	Instances<T>: GraphicElementRendered,
+{
+	// A lone image is downsampled directly rather than being encoded for the SVG renderer, ignoring any rotation or skew of it
+	if let Some(GraphicElement::RasterDataCPU(table)) = (output as &dyn std::any::Any).downcast_ref::<GraphicElement>()
+		&& table.len() == 1
+		&& let Some(raster) = table.instance_ref_iter().next()
+	{
+		return downsample(raster.instance.data());
+	}
+
+	let Some([min, max]) = output.bounding_box(DAffine2::IDENTITY, true) else { return empty_image() };
+	let size = (max - min).max(DVec2::splat(1e-6));
+	let scale = THUMBNAIL_RESOLUTION as f64 / size.max_element();
+	let resolution = (size * scale).ceil().as_uvec2().clamp(UVec2::ONE, UVec2::splat(THUMBNAIL_RESOLUTION));
+	let transform = DAffine2::from_scale(DVec2::splat(scale)) * DAffine2::from_translation(-min);
+
+	rasterize_cpu(&Instances::new(output.clone()), transform, resolution).unwrap_or_else(empty_image)
+}
+
+/// Shrinks the image to fit within the thumbnail resolution by averaging the block of pixels which each thumbnail pixel covers.
+fn downsample(image: &Image<Color>) -> Image<Color> {
+	let size = UVec2::new(image.width, image.height);
+	if size.min_element() == 0 {
+		return empty_image();
+	}
+	let scale = (THUMBNAIL_RESOLUTION as f64 / size.max_element() as f64).min(1.);
+	let resolution = (size.as_dvec2() * scale).ceil().as_uvec2().max(UVec2::ONE);
+
+	let data = (0..resolution.y)
+		.flat_map(|y| (0..resolution.x).map(move |x| UVec2::new(x, y)))
+		.map(|pixel| {
+			let start = pixel * size / resolution;
+			let end = ((pixel + 1) * size / resolution).max(start + 1);
+
+			let mut sum = [0.; 4];
+			for y in start.y..end.y {
+				for x in start.x..end.x {
+					let color = image.data[(y * image.width + x) as usize];
+					for (channel, value) in sum.iter_mut().zip([color.r(), color.g(), color.b(), color.a()]) {
+						*channel += value;
+					}
+				}
+			}
+			let count = ((end.x - start.x) * (end.y - start.y)) as f32;
+			Color::from_rgbaf32_unchecked(sum[0] / count, sum[1] / count, sum[2] / count, sum[3] / count)
+		})
+		.collect();
+
+	Image {
+		width: resolution.x,
+		height: resolution.y,
+		data,
+		base64_string: None,
+	}
+}
+
+/// A single transparent pixel, for the thumbnails of outputs without any content.
+fn empty_image() -> Image<Color> {
+	Image::new(1, 1, Color::TRANSPARENT)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use bezier_rs::Subpath;
+	use graphene_std::raster_types::{CPU, Raster, RasterDataTable};
+	use graphene_std::vector::{VectorData, VectorDataTable};
+
+	fn rectangle(width: f64) -> GraphicElement {
+		let subpath = Subpath::new_rect(DVec2::ZERO, DVec2::new(width, 10.));
+		GraphicElement::VectorData(VectorDataTable::new(VectorData::from_subpath(subpath)))
+	}
+
+	#[test]
+	fn thumbnails_regenerate_only_for_changed_outputs() {
+		let mut generator = ThumbnailGenerator::default();
+		let (first, second) = (NodeId(1), NodeId(2));
+
+		generator.begin_evaluation();
+		assert!(matches!(generator.generate(first, &rectangle(10.)), Some(Thumbnail::Png(_))));
+		assert!(matches!(generator.generate(second, &rectangle(20.)), Some(Thumbnail::Png(_))));
+
+		generator.begin_evaluation();
+		assert_eq!(generator.generate(first, &rectangle(10.)), None);
+		assert!(matches!(generator.generate(second, &rectangle(30.)), Some(Thumbnail::Png(_))));
+
+		// A node which stopped having a thumbnail gets a new one when it returns, even with the same output
+		generator.retain(|node| node != first);
+		generator.begin_evaluation();
+		assert!(generator.generate(first, &rectangle(10.)).is_some());
+		assert_eq!(generator.generate(second, &rectangle(30.)), None);
+	}
+
+	#[test]
+	fn thumbnails_over_budget_are_deferred_to_later_evaluations() {
+		// Each rectangle has a render complexity of 4 for its 4 segments
+		let mut generator = ThumbnailGenerator::new(6);
+
+		generator.begin_evaluation();
+		assert!(generator.generate(NodeId(1), &rectangle(10.)).is_some());
+		assert_eq!(generator.generate(NodeId(2), &rectangle(20.)), None);
+		assert!(generator.deferred());
+
+		generator.begin_evaluation();
+		assert_eq!(generator.generate(NodeId(1), &rectangle(10.)), None);
+		assert!(generator.generate(NodeId(2), &rectangle(20.)).is_some());
+		assert!(!generator.deferred());
+	}
+
+	#[test]
+	fn complex_outputs_are_omitted() {
+		let mut generator = ThumbnailGenerator::default();
+		let image = Image::new(1000, 1000, Color::BLACK);
+		let output = GraphicElement::RasterDataCPU(RasterDataTable::new(Raster::<CPU>::new_cpu(image)));
+
+		generator.begin_evaluation();
+		assert_eq!(generator.generate(NodeId(1), &output), Some(Thumbnail::Omitted));
+		assert_eq!(generator.generate(NodeId(1), &output), None);
+	}
+
+	#[test]
+	fn images_are_downsampled_to_fit_the_thumbnail() {
+		let image = Image::new(256, 128, Color::WHITE);
+		let thumbnail = downsample(&image);
+		assert_eq!((thumbnail.width, thumbnail.height), (64, 32));
+		assert!(thumbnail.data.iter().all(|&color| color == Color::WHITE));
+
+		// Images which are already small enough keep their size
+		let image = Image::new(16, 8, Color::BLACK);
+		assert_eq!((downsample(&image).width, downsample(&image).height), (16, 8));
+	}
+}
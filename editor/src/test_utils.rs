@@ -14,10 +14,14 @@ use graph_craft::document::DocumentNode;
 use graphene_std::InputAccessor;
 use graphene_std::raster::color::Color;
 
+pub mod scenario;
+
 /// A set of utility functions to make the writing of editor test more declarative
 pub struct EditorTestUtils {
 	pub editor: Editor,
 	pub runtime: NodeRuntime,
+	/// The messages sent to the frontend since they were last taken with [`EditorTestUtils::take_frontend_messages`].
+	pub frontend_messages: Vec<FrontendMessage>,
 }
 
 impl EditorTestUtils {
@@ -31,14 +35,18 @@ impl EditorTestUtils {
 		// It isn't sufficient to guard the message dispatch here with a check if the once_cell is empty, because that isn't atomic and the time between checking and handling the dispatch can let multiple through.
 		let _ = GLOBAL_PLATFORM.set(Platform::Windows).is_ok();
 
-		editor.handle_message(PortfolioMessage::Init);
+		let frontend_messages = editor.handle_message(PortfolioMessage::Init);
+
+		Self { editor, runtime, frontend_messages }
+	}
 
-		Self { editor, runtime }
+	pub fn take_frontend_messages(&mut self) -> Vec<FrontendMessage> {
+		std::mem::take(&mut self.frontend_messages)
 	}
 
 	pub fn eval_graph<'a>(&'a mut self) -> impl std::future::Future<Output = Result<Instrumented, String>> + 'a {
 		// An inner function is required since async functions in traits are a bit weird
-		async fn run<'a>(editor: &'a mut Editor, runtime: &'a mut NodeRuntime) -> Result<Instrumented, String> {
+		async fn run<'a>(editor: &'a mut Editor, runtime: &'a mut NodeRuntime, frontend_messages: &'a mut Vec<FrontendMessage>) -> Result<Instrumented, String> {
 			let portfolio = &mut editor.dispatcher.message_handlers.portfolio_message_handler;
 			let exector = &mut portfolio.executor;
 			let document = portfolio.documents.get_mut(&portfolio.active_document_id.unwrap()).unwrap();
@@ -49,7 +57,7 @@ impl EditorTestUtils {
 			};
 
 			let viewport_resolution = glam::UVec2::ONE;
-			if let Err(e) = exector.submit_current_node_graph_evaluation(document, viewport_resolution, Default::default()) {
+			if let Err(e) = exector.submit_current_node_graph_evaluation(document, viewport_resolution, Default::default(), 1.) {
 				return Err(format!("submit_current_node_graph_evaluation failed\n\n{e}"));
 			}
 			runtime.run().await;
@@ -58,20 +66,20 @@ impl EditorTestUtils {
 			if let Err(e) = editor.poll_node_graph_evaluation(&mut messages) {
 				return Err(format!("Graph should render\n\n{e}"));
 			}
-			let frontend_messages = messages.into_iter().flat_map(|message| editor.handle_message(message));
-
-			for message in frontend_messages {
+			for message in messages.into_iter().flat_map(|message| editor.handle_message(message)) {
 				message.check_node_graph_error();
+				frontend_messages.push(message);
 			}
 
 			Ok(instrumented)
 		}
 
-		run(&mut self.editor, &mut self.runtime)
+		run(&mut self.editor, &mut self.runtime, &mut self.frontend_messages)
 	}
 
 	pub async fn handle_message(&mut self, message: impl Into<Message>) {
-		self.editor.handle_message(message);
+		let frontend_messages = self.editor.handle_message(message);
+		self.frontend_messages.extend(frontend_messages);
 
 		// Required to process any buffered messages
 		if let Err(e) = self.eval_graph().await {
@@ -325,6 +333,7 @@ pub mod test_prelude {
 	pub use crate::messages::tool::utility_types::ToolType;
 	pub use crate::node_graph_executor::NodeRuntime;
 	pub use crate::test_utils::EditorTestUtils;
+	pub use crate::test_utils::scenario::Scenario;
 	pub use core::f64;
 	pub use glam::{DVec2, IVec2};
 	pub use graph_craft::document::DocumentNode;
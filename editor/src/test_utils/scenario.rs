@@ -0,0 +1,556 @@
+//! A builder for tests which script a sequence of input, tool, and graph messages for the editor to handle, checking its state along the way.
+//!
+//! ```ignore
+//! Scenario::new()
+//! 	.blank_document()
+//! 	.select_tool(ToolType::Rectangle)
+//! 	.drag((0., 0.), (100., 50.))
+//! 	.expect_layer_count(1)
+//! 	.key(Key::KeyZ)
+//! 	.run()
+//! 	.await;
+//! ```
+//!
+//! Steps run in order when the scenario is run, and the first failing expectation panics with the step's number and description.
+
+use super::EditorTestUtils;
+use crate::messages::input_mapper::utility_types::input_keyboard::{Key, ModifierKeys};
+use crate::messages::input_mapper::utility_types::input_mouse::{EditorMouseState, MouseKeys};
+use crate::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
+use crate::messages::prelude::*;
+use crate::messages::tool::common_functionality::graph_modification_utils::{NodeGraphLayer, is_layer_fed_by_node_of_name};
+use crate::messages::tool::utility_types::ToolType;
+use glam::DVec2;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+type Check = Box<dyn Fn(&ScenarioState) -> Result<(), String>>;
+
+enum Step {
+	Message(Box<Message>),
+	SelectTool(ToolType),
+	PointerMove(DVec2),
+	PointerDown(MouseKeys),
+	PointerUp(MouseKeys),
+	Key(Key),
+	Modifiers(ModifierKeys),
+	SaveSnapshot(String),
+	Expect { description: String, check: Check },
+}
+
+impl Step {
+	fn description(&self) -> String {
+		match self {
+			Self::Message(message) => {
+				let message = format!("{message:?}");
+				let shortened: String = message.chars().take(100).collect();
+				if shortened.len() < message.len() {
+					format!("message {shortened}…")
+				} else {
+					format!("message {message}")
+				}
+			}
+			Self::SelectTool(tool_type) => format!("select the {tool_type:?} tool"),
+			Self::PointerMove(position) => format!("move the pointer to {position}"),
+			Self::PointerDown(mouse_keys) => format!("press {mouse_keys:?}"),
+			Self::PointerUp(mouse_keys) => format!("release {mouse_keys:?}"),
+			Self::Key(key) => format!("press {key:?}"),
+			Self::Modifiers(modifier_keys) => format!("hold {modifier_keys:?}"),
+			Self::SaveSnapshot(name) => format!("save the snapshot \"{name}\""),
+			Self::Expect { description, .. } => format!("expect {description}"),
+		}
+	}
+}
+
+/// A script of steps for the editor to run through, built with chained calls and run with [`Scenario::run`].
+#[derive(Default)]
+pub struct Scenario {
+	steps: Vec<Step>,
+}
+
+impl Scenario {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Opens a new empty document.
+	pub fn blank_document(self) -> Self {
+		self.message(PortfolioMessage::NewDocumentWithName { name: String::from("Test document") })
+	}
+
+	/// Opens a document from the contents of a `.graphite` file.
+	pub fn fixture_document(self, document_serialized_content: impl Into<String>) -> Self {
+		self.message(PortfolioMessage::OpenDocumentFile {
+			document_name: String::from("Test document"),
+			document_serialized_content: document_serialized_content.into(),
+		})
+	}
+
+	pub fn message(mut self, message: impl Into<Message>) -> Self {
+		self.steps.push(Step::Message(Box::new(message.into())));
+		self
+	}
+
+	pub fn select_tool(mut self, tool_type: ToolType) -> Self {
+		self.steps.push(Step::SelectTool(tool_type));
+		self
+	}
+
+	/// Moves the pointer to a position in the viewport, with the held mouse buttons and modifier keys.
+	pub fn pointer_move(mut self, position: impl Into<DVec2>) -> Self {
+		self.steps.push(Step::PointerMove(position.into()));
+		self
+	}
+
+	pub fn pointer_down(mut self, mouse_keys: MouseKeys) -> Self {
+		self.steps.push(Step::PointerDown(mouse_keys));
+		self
+	}
+
+	pub fn pointer_up(mut self, mouse_keys: MouseKeys) -> Self {
+		self.steps.push(Step::PointerUp(mouse_keys));
+		self
+	}
+
+	/// Drags with the left mouse button from one viewport position to another.
+	pub fn drag(self, from: impl Into<DVec2>, to: impl Into<DVec2>) -> Self {
+		self.pointer_move(from).pointer_down(MouseKeys::LEFT).pointer_move(to).pointer_up(MouseKeys::LEFT)
+	}
+
+	/// Presses and releases a key, with the held modifier keys.
+	pub fn key(mut self, key: Key) -> Self {
+		self.steps.push(Step::Key(key));
+		self
+	}
+
+	/// Sets the modifier keys held for the pointer and key steps that follow.
+	pub fn modifiers(mut self, modifier_keys: ModifierKeys) -> Self {
+		self.steps.push(Step::Modifiers(modifier_keys));
+		self
+	}
+
+	pub fn undo(self) -> Self {
+		self.message(DocumentMessage::Undo)
+	}
+
+	pub fn redo(self) -> Self {
+		self.message(DocumentMessage::Redo)
+	}
+
+	/// Expects the check to pass on the editor's state.
+	pub fn expect(mut self, description: impl Into<String>, check: impl Fn(&EditorTestUtils) -> bool + 'static) -> Self {
+		let check = move |state: &ScenarioState| if check(&state.editor) { Ok(()) } else { Err(String::from("The check failed")) };
+		self.steps.push(Step::Expect {
+			description: description.into(),
+			check: Box::new(check),
+		});
+		self
+	}
+
+	/// Expects the document to contain this many layers, including those nested in groups.
+	pub fn expect_layer_count(mut self, count: usize) -> Self {
+		let check = move |state: &ScenarioState| {
+			let found = state.editor.active_document().metadata().all_layers().count();
+			if found == count { Ok(()) } else { Err(format!("Found {found} layers")) }
+		};
+		self.steps.push(Step::Expect {
+			description: format!("{count} layers"),
+			check: Box::new(check),
+		});
+		self
+	}
+
+	/// Expects a layer in the document to be fed by a node with this name.
+	pub fn expect_layer_fed_by(mut self, node_name: &str) -> Self {
+		let node_name = node_name.to_string();
+		let description = format!("a layer fed by a {node_name} node");
+		let check = move |state: &ScenarioState| {
+			let document = state.editor.active_document();
+			if document
+				.metadata()
+				.all_layers()
+				.any(|layer| is_layer_fed_by_node_of_name(layer, &document.network_interface, &node_name))
+			{
+				Ok(())
+			} else {
+				Err(format!("The document is:\n{}", document_snapshot(document)))
+			}
+		};
+		self.steps.push(Step::Expect { description, check: Box::new(check) });
+		self
+	}
+
+	/// Expects one of the messages sent to the frontend by the steps since the previous expectation to match.
+	pub fn expect_frontend(mut self, description: impl Into<String>, matches: impl Fn(&FrontendMessage) -> bool + 'static) -> Self {
+		let check = move |state: &ScenarioState| {
+			if state.frontend_messages.iter().any(&matches) {
+				return Ok(());
+			}
+			let sent: Vec<_> = state
+				.frontend_messages
+				.iter()
+				.map(|message| format!("{message:?}").split([' ', '(', '{']).next().unwrap_or_default().to_string())
+				.collect();
+			Err(format!("The frontend was sent: {}", sent.join(", ")))
+		};
+		self.steps.push(Step::Expect {
+			description: description.into(),
+			check: Box::new(check),
+		});
+		self
+	}
+
+	/// Expects the last layer structure sent to the frontend by the steps since the previous expectation to be the one the Layers panel should show for the document.
+	pub fn expect_layers_panel(mut self) -> Self {
+		let check = |state: &ScenarioState| {
+			let sent = state.frontend_messages.iter().rev().find_map(|message| match message {
+				FrontendMessage::UpdateDocumentLayerStructure { data_buffer } => Some(data_buffer),
+				_ => None,
+			});
+			let Some(sent) = sent else {
+				return Err(String::from("The frontend wasn't sent a layer structure"));
+			};
+			let expected = state.editor.active_document().serialize_root();
+			if *sent != expected {
+				return Err(format!("The frontend was sent the layer structure {sent:?} instead of {expected:?}"));
+			}
+			Ok(())
+		};
+		self.steps.push(Step::Expect {
+			description: String::from("the Layers panel to show the document's layers"),
+			check: Box::new(check),
+		});
+		self
+	}
+
+	/// Expects the [`document_snapshot`] to be the given text, which may be indented to match the surrounding code.
+	pub fn expect_snapshot(mut self, expected: &str) -> Self {
+		let expected = unindent(expected);
+		let check = move |state: &ScenarioState| compare_snapshots(&expected, &document_snapshot(state.editor.active_document()));
+		self.steps.push(Step::Expect {
+			description: String::from("the document snapshot"),
+			check: Box::new(check),
+		});
+		self
+	}
+
+	/// Remembers the [`document_snapshot`] under a name, to compare with a later one using [`Scenario::expect_snapshot_of`].
+	pub fn save_snapshot(mut self, name: &str) -> Self {
+		self.steps.push(Step::SaveSnapshot(name.to_string()));
+		self
+	}
+
+	/// Expects the [`document_snapshot`] to be the same as when it was saved under the name.
+	pub fn expect_snapshot_of(mut self, name: &str) -> Self {
+		let name = name.to_string();
+		let description = format!("the document snapshot \"{name}\"");
+		let check = move |state: &ScenarioState| {
+			let Some(expected) = state.snapshots.get(&name) else {
+				return Err(format!("No snapshot was saved as \"{name}\""));
+			};
+			compare_snapshots(expected, &document_snapshot(state.editor.active_document()))
+		};
+		self.steps.push(Step::Expect { description, check: Box::new(check) });
+		self
+	}
+
+	/// Runs the steps in order, panicking at the first one which fails, and gives back the state the editor was left in.
+	pub async fn run(self) -> ScenarioState {
+		let mut state = ScenarioState {
+			editor: EditorTestUtils::create(),
+			frontend_messages: Vec::new(),
+			snapshots: HashMap::new(),
+			pointer_position: DVec2::ZERO,
+			mouse_keys: MouseKeys::empty(),
+			modifier_keys: ModifierKeys::empty(),
+		};
+		state.editor.take_frontend_messages();
+
+		let mut checked_since_last_action = false;
+		for (index, step) in self.steps.into_iter().enumerate() {
+			let description = step.description();
+			let is_check = matches!(step, Step::Expect { .. } | Step::SaveSnapshot(_));
+
+			// Expectations look at what the frontend was sent by all of the actions since the previous expectation
+			if !is_check && checked_since_last_action {
+				state.frontend_messages.clear();
+			}
+			checked_since_last_action = is_check;
+
+			if let Err(error) = state.perform(step).await {
+				panic!("Scenario step {} failed: {description}\n\n{error}", index + 1);
+			}
+		}
+
+		state
+	}
+}
+
+/// The editor and what the scenario has recorded about it while running.
+pub struct ScenarioState {
+	pub editor: EditorTestUtils,
+	/// The messages sent to the frontend by the steps since the previous expectation.
+	pub frontend_messages: Vec<FrontendMessage>,
+	snapshots: HashMap<String, String>,
+	pointer_position: DVec2,
+	mouse_keys: MouseKeys,
+	modifier_keys: ModifierKeys,
+}
+
+impl ScenarioState {
+	fn mouse_state(&self) -> EditorMouseState {
+		EditorMouseState {
+			editor_position: self.pointer_position,
+			mouse_keys: self.mouse_keys,
+			..Default::default()
+		}
+	}
+
+	async fn perform(&mut self, step: Step) -> Result<(), String> {
+		let modifier_keys = self.modifier_keys;
+		match step {
+			Step::Message(message) => self.editor.handle_message(*message).await,
+			Step::SelectTool(tool_type) => self.editor.select_tool(tool_type).await,
+			Step::PointerMove(position) => {
+				self.pointer_position = position;
+				let editor_mouse_state = self.mouse_state();
+				self.editor.input(InputPreprocessorMessage::PointerMove { editor_mouse_state, modifier_keys }).await;
+			}
+			Step::PointerDown(mouse_keys) => {
+				self.mouse_keys |= mouse_keys;
+				let editor_mouse_state = self.mouse_state();
+				self.editor.input(InputPreprocessorMessage::PointerDown { editor_mouse_state, modifier_keys }).await;
+			}
+			Step::PointerUp(mouse_keys) => {
+				self.mouse_keys -= mouse_keys;
+				let editor_mouse_state = self.mouse_state();
+				self.editor.input(InputPreprocessorMessage::PointerUp { editor_mouse_state, modifier_keys }).await;
+			}
+			Step::Key(key) => self.editor.press(key, modifier_keys).await,
+			Step::Modifiers(modifier_keys) => self.modifier_keys = modifier_keys,
+			Step::SaveSnapshot(name) => {
+				self.drain_frontend_messages();
+				self.snapshots.insert(name, document_snapshot(self.editor.active_document()));
+			}
+			Step::Expect { check, .. } => {
+				self.drain_frontend_messages();
+				return check(&*self);
+			}
+		}
+
+		self.drain_frontend_messages();
+		Ok(())
+	}
+
+	/// Moves the messages the editor has sent to the frontend into those the expectations look at, so none are left behind in the editor when the document is checked.
+	fn drain_frontend_messages(&mut self) {
+		let frontend_messages = self.editor.take_frontend_messages();
+		self.frontend_messages.extend(frontend_messages);
+	}
+}
+
+/// A readable outline of the document's layers and the nodes feeding each of them, with the values of their inputs, which leaves out anything that differs between otherwise identical documents such as node IDs.
+///
+/// Layers are indented by how deeply they're nested, and floating point numbers are rounded to six decimal places.
+pub fn document_snapshot(document: &DocumentMessageHandler) -> String {
+	let network_interface = &document.network_interface;
+	let metadata = document.metadata();
+	let mut snapshot = String::new();
+
+	for layer in LayerNodeIdentifier::ROOT_PARENT.descendants(metadata) {
+		// Every layer's ancestors include itself and the root
+		let indent = "  ".repeat(layer.ancestors(metadata).count().saturating_sub(2));
+		let _ = writeln!(snapshot, "{indent}{}", network_interface.display_name(&layer.to_node(), &[]));
+
+		let chain = NodeGraphLayer::new(layer, network_interface).horizontal_layer_flow().skip(1);
+		for node_id in chain.take_while(|node_id| !network_interface.is_layer(node_id, &[])) {
+			let _ = writeln!(snapshot, "{indent}  > {}", network_interface.display_name(&node_id, &[]));
+
+			let Some(node) = network_interface.document_node(&node_id, &[]) else { continue };
+			for (index, input) in node.inputs.iter().enumerate() {
+				let Some(value) = input.as_value() else { continue };
+				let name = network_interface
+					.persistent_input_metadata(&node_id, index, &[])
+					.map(|input_metadata| input_metadata.input_name.clone())
+					.filter(|name| !name.is_empty())
+					.unwrap_or_else(|| format!("Input {index}"));
+				let _ = writeln!(snapshot, "{indent}    {name}: {}", round_floats(&format!("{value:?}")));
+			}
+		}
+	}
+
+	snapshot
+}
+
+/// Rounds the floating point numbers written in the text, so they don't differ by floating point error.
+fn round_floats(text: &str) -> String {
+	let bytes = text.as_bytes();
+	let is_digit = |index: usize| bytes.get(index).is_some_and(u8::is_ascii_digit);
+	let mut result = String::with_capacity(text.len());
+
+	let mut index = 0;
+	while index < bytes.len() {
+		// Digits that are part of a name, like the `2` in `DVec2`, aren't numbers
+		let in_word = index > 0 && (bytes[index - 1].is_ascii_alphanumeric() || bytes[index - 1] == b'_');
+		let starts_number = is_digit(index) || (bytes[index] == b'-' && is_digit(index + 1));
+		if in_word || !starts_number {
+			let next = index + text[index..].chars().next().map_or(1, char::len_utf8);
+			result.push_str(&text[index..next]);
+			index = next;
+			continue;
+		}
+
+		let mut end = index + 1;
+		while end < bytes.len() && (bytes[end].is_ascii_digit() || bytes[end] == b'.' || bytes[end] == b'e' || (bytes[end] == b'-' && bytes[end - 1] == b'e')) {
+			end += 1;
+		}
+		let number = &text[index..end];
+		match number.parse::<f64>() {
+			// Adding zero turns a negative zero into a positive one
+			Ok(value) if number.contains(['.', 'e']) && value.abs() < 1e9 => {
+				let _ = write!(result, "{:?}", (value * 1e6).round() / 1e6 + 0.);
+			}
+			_ => result.push_str(number),
+		}
+		index = end;
+	}
+
+	result
+}
+
+/// Removes the blank lines around the text and the indentation its lines share.
+fn unindent(text: &str) -> String {
+	let lines: Vec<_> = text.lines().skip_while(|line| line.trim().is_empty()).collect();
+	let lines = &lines[..lines.iter().rposition(|line| !line.trim().is_empty()).map_or(0, |last| last + 1)];
+	let indent = lines
+		.iter()
+		.filter(|line| !line.trim().is_empty())
+		.map(|line| line.len() - line.trim_start().len())
+		.min()
+		.unwrap_or_default();
+
+	lines.iter().map(|line| format!("{}\n", line.get(indent..).unwrap_or_default())).collect()
+}
+
+fn compare_snapshots(expected: &str, actual: &str) -> Result<(), String> {
+	if expected == actual {
+		return Ok(());
+	}
+	Err(format!("The document differs from the snapshot (- expected, + actual):\n{}", line_diff(expected, actual)))
+}
+
+/// The lines of both texts, prefixed with `- ` if they're only in the expected text, `+ ` if they're only in the actual text, or two spaces if they're in both.
+fn line_diff(expected: &str, actual: &str) -> String {
+	let expected: Vec<_> = expected.lines().collect();
+	let actual: Vec<_> = actual.lines().collect();
+
+	// The length of the longest common subsequence of the lines from each index onward in both texts
+	let mut common = vec![vec![0; actual.len() + 1]; expected.len() + 1];
+	for expected_index in (0..expected.len()).rev() {
+		for actual_index in (0..actual.len()).rev() {
+			common[expected_index][actual_index] = if expected[expected_index] == actual[actual_index] {
+				common[expected_index + 1][actual_index + 1] + 1
+			} else {
+				common[expected_index + 1][actual_index].max(common[expected_index][actual_index + 1])
+			};
+		}
+	}
+
+	let mut diff = String::new();
+	let (mut expected_index, mut actual_index) = (0, 0);
+	while expected_index < expected.len() || actual_index < actual.len() {
+		let expected_line = expected.get(expected_index);
+		let actual_line = actual.get(actual_index);
+		if expected_line.is_some() && expected_line == actual_line {
+			let _ = writeln!(diff, "  {}", expected[expected_index]);
+			expected_index += 1;
+			actual_index += 1;
+		} else if expected_line.is_some() && (actual_line.is_none() || common[expected_index + 1][actual_index] >= common[expected_index][actual_index + 1]) {
+			let _ = writeln!(diff, "- {}", expected[expected_index]);
+			expected_index += 1;
+		} else {
+			let _ = writeln!(diff, "+ {}", actual[actual_index]);
+			actual_index += 1;
+		}
+	}
+
+	diff
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::messages::tool::common_functionality::shapes::shape_utility::ShapeType;
+	use crate::messages::tool::tool_messages::shape_tool::ShapeOptionsUpdate;
+	use graph_craft::document::value::TaggedValue;
+
+	fn arc_input(editor: &EditorTestUtils, index: usize) -> Option<f64> {
+		let document = editor.active_document();
+		let layer = document.metadata().all_layers().next()?;
+		let inputs = NodeGraphLayer::new(layer, &document.network_interface).find_node_inputs("Arc")?;
+		let Some(&TaggedValue::F64(value)) = inputs.get(index)?.as_value() else { return None };
+		Some(value)
+	}
+
+	#[tokio::test]
+	async fn drawing_an_arc() {
+		Scenario::new()
+			.blank_document()
+			.select_tool(ToolType::Shape)
+			.message(ShapeToolMessage::UpdateOptions(ShapeOptionsUpdate::ShapeType(ShapeType::Arc)))
+			.drag((0., 0.), (100., 100.))
+			.expect_layer_count(1)
+			.expect_layer_fed_by("Arc")
+			.expect_layers_panel()
+			.expect("a radius of half the dragged size", |editor| arc_input(editor, 1).is_some_and(|radius| (radius - 50.).abs() < 1e-10))
+			.expect("the default start and sweep angles", |editor| arc_input(editor, 2) == Some(0.) && arc_input(editor, 3) == Some(270.))
+			.run()
+			.await;
+	}
+
+	#[tokio::test]
+	async fn undoing_and_redoing_a_rectangle() {
+		Scenario::new()
+			.blank_document()
+			.select_tool(ToolType::Rectangle)
+			.drag((10., 10.), (60., 40.))
+			.expect_layer_count(1)
+			.expect_layer_fed_by("Rectangle")
+			.expect_layers_panel()
+			.save_snapshot("drawn")
+			.undo()
+			.expect_layer_count(0)
+			.expect_layers_panel()
+			.redo()
+			.expect_layer_count(1)
+			.expect_layers_panel()
+			.expect_snapshot_of("drawn")
+			.run()
+			.await;
+	}
+
+	#[test]
+	fn floats_are_rounded_but_names_and_integers_are_not() {
+		assert_eq!(round_floats("DVec2(DVec2(49.99999999, -0.0000001))"), "DVec2(DVec2(50.0, 0.0))");
+		assert_eq!(round_floats("F64(1e-12), U32(7), Some(0.5)"), "F64(0.0), U32(7), Some(0.5)");
+		assert_eq!(round_floats("Color { red: 0.2 }"), "Color { red: 0.2 }");
+	}
+
+	#[test]
+	fn snapshots_compare_with_a_readable_diff() {
+		let expected = unindent(
+			"
+			Rectangle
+			  > Rectangle
+			    Width: F64(50.0)
+			",
+		);
+		let actual = "Rectangle\n  > Rectangle\n    Width: F64(60.0)\n  > Fill\n";
+
+		assert_eq!(compare_snapshots(&expected, &expected), Ok(()));
+		assert_eq!(
+			line_diff(&expected, actual),
+			"  Rectangle\n    > Rectangle\n-     Width: F64(50.0)\n+     Width: F64(60.0)\n+   > Fill\n"
+		);
+	}
+}
@@ -8,7 +8,7 @@ import { type FullscreenState } from "@graphite/state-providers/fullscreen";
 import { type PortfolioState } from "@graphite/state-providers/portfolio";
 import { makeKeyboardModifiersBitfield, textInputCleanup, getLocalizedScanCode } from "@graphite/utility-functions/keyboard-entry";
 import { platformIsMac } from "@graphite/utility-functions/platform";
-import { extractPixelData } from "@graphite/utility-functions/rasterization";
+import { extractPixelData, isHighDepthImage } from "@graphite/utility-functions/rasterization";
 import { stripIndents } from "@graphite/utility-functions/strip-indents";
 import { updateBoundsOfViewports } from "@graphite/utility-functions/viewports";
 
@@ -323,6 +323,8 @@ export function createInputManager(editor: Editor, dialog: DialogState, portfoli
 				return;
 			}
 
+			if (isHighDepthImage(file.name) && editor.handle.pasteEncodedImage(file.name, new Uint8Array(await file.arrayBuffer()))) return;
+
 			if (file.type.startsWith("image")) {
 				const imageData = await extractPixelData(file);
 				editor.handle.pasteImage(file.name, new Uint8Array(imageData.data), imageData.width, imageData.height);
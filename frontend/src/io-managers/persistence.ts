@@ -10,6 +10,9 @@ import {
 	TriggerLoadFirstAutoSaveDocument,
 	TriggerLoadRestAutoSaveDocuments,
 	TriggerSaveActiveDocument,
+	TriggerSaveRecentDocuments,
+	TriggerLoadRecentDocuments,
+	TriggerOpenRecentDocument,
 } from "@graphite/messages";
 import { type PortfolioState } from "@graphite/state-providers/portfolio";
 
@@ -152,6 +155,46 @@ export function createPersistenceManager(editor: Editor, portfolio: PortfolioSta
 		editor.handle.loadPreferences(JSON.stringify(preferences));
 	}
 
+	// RECENT DOCUMENTS
+
+	async function saveRecentDocuments(serialized: string) {
+		await set("recent_documents", serialized, graphiteStore);
+
+		// Forget the file handles of documents which are no longer listed
+		const paths = new Set((JSON.parse(serialized) as { entries: { path: string }[] }).entries.map((entry) => entry.path));
+		await update<Record<string, FileSystemFileHandle>>(
+			"recent_document_handles",
+			(old) => Object.fromEntries(Object.entries(old || {}).filter(([path]) => paths.has(path))),
+			graphiteStore,
+		);
+	}
+
+	async function loadRecentDocuments() {
+		const serialized = await get<string>("recent_documents", graphiteStore);
+		if (!serialized) return;
+
+		editor.handle.loadRecentDocuments(serialized);
+	}
+
+	async function openRecentDocument(path: string) {
+		// The browser only allows reading a file again through the handle given when the user picked it
+		const handles = await get<Record<string, FileSystemFileHandle>>("recent_document_handles", graphiteStore);
+		const handle = handles?.[path];
+
+		try {
+			if (!handle) throw new Error("The file was not picked with a handle to reopen it by");
+
+			// Permission to read the file is asked for again in each session
+			const permissionHandle = handle as FileSystemFileHandle & { requestPermission?: (descriptor: { mode: "read" }) => Promise<PermissionState> };
+			if (permissionHandle.requestPermission && (await permissionHandle.requestPermission({ mode: "read" })) !== "granted") return;
+
+			const file = await handle.getFile();
+			editor.handle.openDocumentFile(file.name, await file.text());
+		} catch {
+			editor.handle.recentDocumentMissing(path);
+		}
+	}
+
 	// FRONTEND MESSAGE SUBSCRIPTIONS
 
 	// Subscribe to process backend events
@@ -161,6 +204,15 @@ export function createPersistenceManager(editor: Editor, portfolio: PortfolioSta
 	editor.subscriptions.subscribeJsMessage(TriggerLoadPreferences, async () => {
 		await loadPreferences();
 	});
+	editor.subscriptions.subscribeJsMessage(TriggerSaveRecentDocuments, async (triggerSaveRecentDocuments) => {
+		await saveRecentDocuments(triggerSaveRecentDocuments.serialized);
+	});
+	editor.subscriptions.subscribeJsMessage(TriggerLoadRecentDocuments, async () => {
+		await loadRecentDocuments();
+	});
+	editor.subscriptions.subscribeJsMessage(TriggerOpenRecentDocument, async (triggerOpenRecentDocument) => {
+		await openRecentDocument(triggerOpenRecentDocument.path);
+	});
 	editor.subscriptions.subscribeJsMessage(TriggerIndexedDbWriteDocument, async (autoSaveDocument) => {
 		await storeDocument(autoSaveDocument);
 	});
@@ -188,3 +240,16 @@ export async function wipeDocuments() {
 	await del("current_document_id", graphiteStore);
 	await del("documents", graphiteStore);
 }
+
+// Keeps the handle of a file the user picked, so the document can be opened from it again from the recent documents list
+export async function storeRecentDocumentHandle(path: string, handle: FileSystemFileHandle) {
+	await update<Record<string, FileSystemFileHandle>>(
+		"recent_document_handles",
+		(old) => {
+			const handles = old || {};
+			handles[path] = handle;
+			return handles;
+		},
+		graphiteStore,
+	);
+}
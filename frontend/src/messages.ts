@@ -40,6 +40,11 @@ export class UpdateClickTargets extends JsMessage {
 	readonly clickTargets!: FrontendClickTargets | undefined;
 }
 
+export class UpdateCustomNodeTypes extends JsMessage {
+	@Type(() => FrontendNode)
+	readonly nodeTypes!: FrontendNodeType[];
+}
+
 const ContextTupleToVec2 = Transform((data) => {
 	if (data.obj.contextMenuInformation === undefined) return undefined;
 	const contextMenuCoordinates = { x: data.obj.contextMenuInformation.contextMenuCoordinates[0], y: data.obj.contextMenuInformation.contextMenuCoordinates[1] };
@@ -105,6 +110,11 @@ export class UpdateVisibleNodes extends JsMessage {
 	readonly nodes!: bigint[];
 }
 
+export class UpdateNodeGraphErrors extends JsMessage {
+	@Type(() => FrontendNodeError)
+	readonly errors!: FrontendNodeError[];
+}
+
 export class UpdateNodeGraphWires extends JsMessage {
 	readonly wires!: WireUpdate[];
 }
@@ -130,11 +140,36 @@ export class UpdateNodeThumbnail extends JsMessage {
 	readonly value!: string;
 }
 
+export class UpdateNodeThumbnailImage extends JsMessage {
+	readonly id!: bigint;
+
+	readonly png!: number[];
+}
+
 export class UpdateNodeGraphSelection extends JsMessage {
 	@Type(() => BigInt)
 	readonly selected!: bigint[];
 }
 
+export class Swatch {
+	readonly name!: string;
+
+	@Type(() => Color)
+	readonly color!: Color;
+}
+
+export class Palette {
+	readonly name!: string;
+
+	@Type(() => Swatch)
+	readonly swatches!: Swatch[];
+}
+
+export class UpdatePalette extends JsMessage {
+	@Type(() => Palette)
+	readonly palette!: Palette;
+}
+
 export class UpdateOpenDocumentsList extends JsMessage {
 	@Type(() => FrontendDocumentDetails)
 	readonly openDocuments!: FrontendDocumentDetails[];
@@ -302,6 +337,14 @@ export class FrontendNode {
 	readonly uiOnly!: boolean;
 }
 
+export class FrontendNodeError {
+	readonly nodePath!: bigint[];
+
+	readonly inputIndex!: number | undefined;
+
+	readonly message!: string;
+}
+
 export class FrontendNodeType {
 	readonly name!: string;
 
@@ -405,15 +448,19 @@ export type RGB = { r: number; g: number; b: number };
 export class Gradient {
 	readonly stops!: { position: number; color: Color }[];
 
-	constructor(stops: { position: number; color: Color }[]) {
+	// The location (0-1) between each pair of adjacent stops where their colors are mixed evenly, defaulting to 0.5
+	readonly midpoints!: number[];
+
+	constructor(stops: { position: number; color: Color }[], midpoints: number[] = []) {
 		this.stops = stops;
+		this.midpoints = midpoints;
 	}
 
 	toLinearGradientCSS(): string {
 		if (this.stops.length === 1) {
 			return `linear-gradient(to right, ${this.stops[0].color.toHexOptionalAlpha()} 0%, ${this.stops[0].color.toHexOptionalAlpha()} 100%)`;
 		}
-		const pieces = this.stops.map((stop) => `${stop.color.toHexOptionalAlpha()} ${stop.position * 100}%`);
+		const pieces = this.stops.map((stop, index) => `${this.midpointHint(index)}${stop.color.toHexOptionalAlpha()} ${stop.position * 100}%`);
 		return `linear-gradient(to right, ${pieces.join(", ")})`;
 	}
 
@@ -421,10 +468,20 @@ export class Gradient {
 		if (this.stops.length === 1) {
 			return `linear-gradient(to right, ${this.stops[0].color.toHexNoAlpha()} 0%, ${this.stops[0].color.toHexNoAlpha()} 100%)`;
 		}
-		const pieces = this.stops.map((stop) => `${stop.color.toHexNoAlpha()} ${stop.position * 100}%`);
+		const pieces = this.stops.map((stop, index) => `${this.midpointHint(index)}${stop.color.toHexNoAlpha()} ${stop.position * 100}%`);
 		return `linear-gradient(to right, ${pieces.join(", ")})`;
 	}
 
+	// CSS color interpolation hint placed before the stop at `index`, which uses the same curve as the midpoint bias
+	midpointHint(index: number): string {
+		const midpoint = this.midpoints[index - 1];
+		if (index === 0 || midpoint === undefined || midpoint === 0.5) return "";
+
+		const previous = this.stops[index - 1].position;
+		const hint = previous + (this.stops[index].position - previous) * midpoint;
+		return `${hint * 100}%, `;
+	}
+
 	firstColor(): Color | undefined {
 		return this.stops[0]?.color;
 	}
@@ -446,7 +503,38 @@ export class Gradient {
 	}
 }
 
+// Matrices between linear Display P3 and linear sRGB, which share the D65 white point
+const DISPLAY_P3_TO_SRGB = [
+	[1.2249401, -0.2249404, 0],
+	[-0.0420569, 1.0420571, 0],
+	[-0.0196376, -0.0786361, 1.0982735],
+];
+const SRGB_TO_DISPLAY_P3 = [
+	[0.8224621, 0.177538, 0],
+	[0.0331941, 0.9668058, 0],
+	[0.0170826, 0.0723974, 0.9105199],
+];
+
+// The sRGB transfer function, also used by Display P3, with negative channels mirrored as in extended sRGB
+function srgbToLinear(channel: number): number {
+	const magnitude = Math.abs(channel);
+	const linear = magnitude <= 0.04045 ? magnitude / 12.92 : ((magnitude + 0.055) / 1.055) ** 2.4;
+	return Math.sign(channel) * linear;
+}
+function linearToSrgb(channel: number): number {
+	const magnitude = Math.abs(channel);
+	const gamma = magnitude <= 0.0031308 ? magnitude * 12.92 : 1.055 * magnitude ** (1 / 2.4) - 0.055;
+	return Math.sign(channel) * gamma;
+}
+
+function convertPrimaries(color: Color, matrix: number[][]): Color {
+	const linear = [color.red, color.green, color.blue].map(srgbToLinear);
+	const [red, green, blue] = matrix.map((row) => linearToSrgb(row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]));
+	return new Color(red, green, blue, color.alpha);
+}
+
 // All channels range are represented by 0-1, sRGB, gamma.
+// Wide-gamut colors outside of sRGB have channels beyond that range.
 export class Color {
 	readonly red!: number;
 
@@ -542,6 +630,35 @@ export class Color {
 		return new Color(r / 255, g / 255, b / 255, a / 255);
 	}
 
+	// Convert a color whose channels are gamma-encoded Display P3 into sRGB
+	static fromDisplayP3(displayP3: Color): Color {
+		if (displayP3.none) return new Color("none");
+
+		return convertPrimaries(displayP3, DISPLAY_P3_TO_SRGB);
+	}
+
+	// Return a color whose channels are this color in gamma-encoded Display P3
+	toDisplayP3(): Color {
+		if (this.none) return new Color("none");
+
+		return convertPrimaries(this, SRGB_TO_DISPLAY_P3);
+	}
+
+	isWithinSrgbGamut(): boolean {
+		const TOLERANCE = 1e-4;
+		return [this.red, this.green, this.blue].every((channel) => channel >= -TOLERANCE && channel <= 1 + TOLERANCE);
+	}
+
+	// A CSS color including alpha, which uses the Display P3 space if the color is outside of sRGB so displays that support it can show it
+	toCSS(): string | undefined {
+		if (this.none) return undefined;
+		if (this.isWithinSrgbGamut()) return this.toRgbaCSS();
+
+		const { red, green, blue } = this.toDisplayP3();
+		const [r, g, b] = [red, green, blue].map((channel) => Math.round(Math.min(Math.max(channel, 0), 1) * 10000) / 10000);
+		return `color(display-p3 ${r} ${g} ${b} / ${this.alpha})`;
+	}
+
 	equals(other: Color): boolean {
 		if (this.none && other.none) return true;
 		return Math.abs(this.red - other.red) < 1e-6 && Math.abs(this.green - other.green) < 1e-6 && Math.abs(this.blue - other.blue) < 1e-6 && Math.abs(this.alpha - other.alpha) < 1e-6;
@@ -552,17 +669,12 @@ export class Color {
 	}
 
 	toHexNoAlpha(): string | undefined {
-		if (this.none) return undefined;
+		const rgb = this.toRgb255();
+		if (!rgb) return undefined;
 
-		const r = Math.round(this.red * 255)
-			.toString(16)
-			.padStart(2, "0");
-		const g = Math.round(this.green * 255)
-			.toString(16)
-			.padStart(2, "0");
-		const b = Math.round(this.blue * 255)
-			.toString(16)
-			.padStart(2, "0");
+		const r = rgb.r.toString(16).padStart(2, "0");
+		const g = rgb.g.toString(16).padStart(2, "0");
+		const b = rgb.b.toString(16).padStart(2, "0");
 
 		return `#${r}${g}${b}`;
 	}
@@ -578,14 +690,12 @@ export class Color {
 		return a === "ff" ? hex : `${hex}${a}`;
 	}
 
+	// Colors outside of sRGB are clipped to it
 	toRgb255(): RGB | undefined {
 		if (this.none) return undefined;
 
-		return {
-			r: Math.round(this.red * 255),
-			g: Math.round(this.green * 255),
-			b: Math.round(this.blue * 255),
-		};
+		const to255 = (channel: number): number => Math.round(Math.min(Math.max(channel, 0), 1) * 255);
+		return { r: to255(this.red), g: to255(this.green), b: to255(this.blue) };
 	}
 
 	toRgbCSS(): string | undefined {
@@ -704,6 +814,10 @@ export class UpdateDocumentArtwork extends JsMessage {
 	readonly svg!: string;
 }
 
+export class UpdateDocumentError extends JsMessage {
+	readonly error!: string | undefined;
+}
+
 export class UpdateDocumentScrollbars extends JsMessage {
 	@TupleToVec2
 	readonly position!: XY;
@@ -763,12 +877,69 @@ export class UpdateGraphFadeArtwork extends JsMessage {
 	readonly percentage!: number;
 }
 
+export class FrontendNodeCacheStats {
+	readonly nodePath!: bigint[];
+
+	readonly hits!: number;
+
+	readonly misses!: number;
+
+	readonly entries!: number;
+
+	readonly bytes!: number;
+}
+
+export class UpdateNodeCacheStats extends JsMessage {
+	@Type(() => FrontendNodeCacheStats)
+	readonly stats!: FrontendNodeCacheStats[];
+}
+
+export class FrontendInspectedValue {
+	readonly typeName!: string;
+
+	readonly size!: number | undefined;
+
+	readonly preview!: string | undefined;
+
+	readonly full!: string | undefined;
+}
+
+export class UpdateInspectedValue extends JsMessage {
+	readonly nodePath!: bigint[];
+
+	@Type(() => FrontendInspectedValue)
+	readonly value!: FrontendInspectedValue | undefined;
+}
+
+export class Histogram {
+	readonly luminance!: number[];
+
+	readonly red!: number[];
+
+	readonly green!: number[];
+
+	readonly blue!: number[];
+
+	readonly samples!: number;
+}
+
+export class UpdateHistogram extends JsMessage {
+	readonly layer!: bigint | undefined;
+
+	@Type(() => Histogram)
+	readonly histogram!: Histogram | undefined;
+}
+
 export class UpdateSpreadsheetState extends JsMessage {
 	readonly open!: boolean;
 
 	readonly node!: bigint | undefined;
 }
 
+export class UpdateSwatchesPanelState extends JsMessage {
+	readonly open!: boolean;
+}
+
 export class UpdateMouseCursor extends JsMessage {
 	@Transform(({ value }: { value: MouseCursor }) => mouseCursorIconCSSNames[value] || "alias")
 	readonly cursor!: MouseCursorIcon;
@@ -779,6 +950,8 @@ export class TriggerLoadRestAutoSaveDocuments extends JsMessage {}
 
 export class TriggerLoadPreferences extends JsMessage {}
 
+export class TriggerLoadRecentDocuments extends JsMessage {}
+
 export class TriggerFetchAndOpenDocument extends JsMessage {
 	readonly name!: string;
 
@@ -787,8 +960,18 @@ export class TriggerFetchAndOpenDocument extends JsMessage {
 
 export class TriggerOpenDocument extends JsMessage {}
 
+export class TriggerOpenRecentDocument extends JsMessage {
+	readonly path!: string;
+}
+
+export class TriggerCaptureRecentDocumentThumbnail extends JsMessage {
+	readonly path!: string;
+}
+
 export class TriggerImport extends JsMessage {}
 
+export class TriggerImportBrushTip extends JsMessage {}
+
 export class TriggerPaste extends JsMessage {}
 
 export class TriggerDownloadImage extends JsMessage {
@@ -800,6 +983,8 @@ export class TriggerDownloadImage extends JsMessage {
 
 	@TupleToVec2
 	readonly size!: XY;
+
+	readonly colorSpace!: PredefinedColorSpace;
 }
 
 export class TriggerDownloadTextFile extends JsMessage {
@@ -808,10 +993,39 @@ export class TriggerDownloadTextFile extends JsMessage {
 	readonly name!: string;
 }
 
+export class TriggerDownloadBinaryFile extends JsMessage {
+	readonly data!: number[];
+
+	readonly name!: string;
+}
+
 export class TriggerSavePreferences extends JsMessage {
 	readonly preferences!: Record<string, unknown>;
 }
 
+export class TriggerSaveRecentDocuments extends JsMessage {
+	readonly serialized!: string;
+}
+
+export class RecentDocument {
+	readonly path!: string;
+
+	readonly name!: string;
+
+	// Milliseconds since the Unix epoch
+	readonly timestamp!: bigint;
+
+	// A PNG data URL
+	readonly thumbnail!: string | undefined;
+
+	readonly pinned!: boolean;
+}
+
+export class UpdateRecentDocuments extends JsMessage {
+	@Type(() => RecentDocument)
+	readonly recentDocuments!: RecentDocument[];
+}
+
 export class TriggerSaveActiveDocument extends JsMessage {
 	readonly documentId!: bigint;
 }
@@ -832,7 +1046,11 @@ export type TextAlign = "Left" | "Center" | "Right" | "JustifyLeft";
 export class DisplayEditableTextbox extends JsMessage {
 	readonly text!: string;
 
-	readonly lineHeightRatio!: number;
+	readonly lineHeight!: number;
+
+	readonly characterSpacing!: number;
+
+	readonly wordSpacing!: number;
 
 	readonly fontSize!: number;
 
@@ -856,6 +1074,21 @@ export class DisplayEditableTextboxTransform extends JsMessage {
 
 export class DisplayRemoveEditableTextbox extends JsMessage {}
 
+export class HistoryEntry {
+	// The number of steps from the oldest state in the history which the step reaches
+	readonly index!: number;
+
+	readonly label!: string;
+}
+
+export class UpdateDocumentHistory extends JsMessage {
+	@Type(() => HistoryEntry)
+	readonly entries!: HistoryEntry[];
+
+	// The number of steps which can be undone
+	readonly currentIndex!: number;
+}
+
 export class UpdateDocumentLayerDetails extends JsMessage {
 	@Type(() => LayerPanelEntry)
 	readonly data!: LayerPanelEntry;
@@ -901,6 +1134,10 @@ export class LayerPanelEntry {
 	clipped!: boolean;
 
 	clippable!: boolean;
+
+	solo!: boolean;
+
+	hiddenBySolo!: boolean;
 }
 
 export class DisplayDialogDismiss extends JsMessage {}
@@ -954,11 +1191,11 @@ export class ColorInput extends WidgetProps {
 		if (value instanceof Gradient) return value;
 		const gradient = value["Gradient"];
 		if (gradient) {
-			const stops = gradient.map(([position, color]: [number, color: { red: number; green: number; blue: number; alpha: number }]) => ({
+			const stops = gradient.color_stops.map(([position, color]: [number, color: { red: number; green: number; blue: number; alpha: number }]) => ({
 				position,
 				color: new Color(color.red, color.green, color.blue, color.alpha),
 			}));
-			return new Gradient(stops);
+			return new Gradient(stops, gradient.midpoints);
 		}
 
 		if (value instanceof Color) return value;
@@ -975,6 +1212,8 @@ export class ColorInput extends WidgetProps {
 
 	allowNone!: boolean;
 
+	badge!: string | undefined;
+
 	// allowTransparency!: boolean; // TODO: Implement
 
 	@Transform(({ value }: { value: string }) => value || undefined)
@@ -1044,14 +1283,14 @@ export class CurveManipulatorGroup {
 	handles!: [[number, number], [number, number]];
 }
 
-export class Curve {
+export class CurveSpline {
 	manipulatorGroups!: CurveManipulatorGroup[];
 	firstHandle!: [number, number];
 	lastHandle!: [number, number];
 }
 
 export class CurveInput extends WidgetProps {
-	value!: Curve;
+	value!: CurveSpline;
 
 	disabled!: boolean;
 
@@ -1607,6 +1846,8 @@ export class UpdatePropertyPanelSectionsLayout extends WidgetDiffUpdate {}
 
 export class UpdateSpreadsheetLayout extends WidgetDiffUpdate {}
 
+export class UpdateSwatchesPanelLayout extends WidgetDiffUpdate {}
+
 export class UpdateToolOptionsLayout extends WidgetDiffUpdate {}
 
 export class UpdateToolShelfLayout extends WidgetDiffUpdate {}
@@ -1647,20 +1888,26 @@ export const messageMakers: Record<string, MessageMaker> = {
 	DisplayRemoveEditableTextbox,
 	SendUIMetadata,
 	TriggerAboutGraphiteLocalizedCommitDate,
+	TriggerCaptureRecentDocumentThumbnail,
 	TriggerDownloadImage,
+	TriggerDownloadBinaryFile,
 	TriggerDownloadTextFile,
 	TriggerFetchAndOpenDocument,
 	TriggerFontLoad,
 	TriggerImport,
+	TriggerImportBrushTip,
 	TriggerIndexedDbRemoveDocument,
 	TriggerIndexedDbWriteDocument,
 	TriggerLoadFirstAutoSaveDocument,
 	TriggerLoadPreferences,
+	TriggerLoadRecentDocuments,
 	TriggerLoadRestAutoSaveDocuments,
 	TriggerOpenDocument,
+	TriggerOpenRecentDocument,
 	TriggerPaste,
 	TriggerSaveActiveDocument,
 	TriggerSavePreferences,
+	TriggerSaveRecentDocuments,
 	TriggerTextCommit,
 	TriggerTextCopy,
 	TriggerVisitLink,
@@ -1668,11 +1915,14 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateBox,
 	UpdateClickTargets,
 	UpdateContextMenuInformation,
+	UpdateCustomNodeTypes,
 	UpdateDialogButtons,
 	UpdateDialogColumn1,
 	UpdateDialogColumn2,
 	UpdateDocumentArtwork,
 	UpdateDocumentBarLayout,
+	UpdateDocumentError,
+	UpdateDocumentHistory,
 	UpdateDocumentLayerDetails,
 	UpdateDocumentLayerStructureJs,
 	UpdateDocumentModeLayout,
@@ -1682,10 +1932,12 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateEyedropperSamplingState,
 	UpdateGraphFadeArtwork,
 	UpdateGraphViewOverlay,
+	UpdateHistogram,
 	UpdateImportReorderIndex,
 	UpdateImportsExports,
 	UpdateInputHints,
 	UpdateInSelectedNetwork,
+	UpdateInspectedValue,
 	UpdateLayersPanelBottomBarLayout,
 	UpdateLayersPanelControlBarLeftLayout,
 	UpdateLayersPanelControlBarRightLayout,
@@ -1693,17 +1945,24 @@ export const messageMakers: Record<string, MessageMaker> = {
 	UpdateMaximized,
 	UpdateMenuBarLayout,
 	UpdateMouseCursor,
+	UpdateNodeCacheStats,
 	UpdateNodeGraphControlBarLayout,
+	UpdateNodeGraphErrors,
 	UpdateNodeGraphNodes,
 	UpdateNodeGraphSelection,
 	UpdateNodeGraphTransform,
 	UpdateNodeGraphWires,
 	UpdateNodeThumbnail,
+	UpdateNodeThumbnailImage,
 	UpdateOpenDocumentsList,
 	UpdatePlatform,
+	UpdatePalette,
 	UpdatePropertyPanelSectionsLayout,
+	UpdateRecentDocuments,
 	UpdateSpreadsheetLayout,
 	UpdateSpreadsheetState,
+	UpdateSwatchesPanelLayout,
+	UpdateSwatchesPanelState,
 	UpdateToolOptionsLayout,
 	UpdateToolShelfLayout,
 	UpdateViewportHolePunch,
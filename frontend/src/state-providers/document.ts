@@ -14,6 +14,8 @@ import {
 	UpdateNodeGraphControlBarLayout,
 	UpdateGraphViewOverlay,
 	UpdateGraphFadeArtwork,
+	UpdateDocumentHistory,
+	type HistoryEntry,
 } from "@graphite/messages";
 
 // eslint-disable-next-line @typescript-eslint/explicit-function-return-type
@@ -29,9 +31,21 @@ export function createDocumentState(editor: Editor) {
 		// Graph view overlay
 		graphViewOverlayOpen: false,
 		fadeArtwork: 100,
+		// Document history
+		historyEntries: [] as HistoryEntry[],
+		historyIndex: 0,
 	});
 	const { subscribe, update } = state;
 
+	// Update document history
+	editor.subscriptions.subscribeJsMessage(UpdateDocumentHistory, (updateDocumentHistory) => {
+		update((state) => {
+			state.historyEntries = updateDocumentHistory.entries;
+			state.historyIndex = updateDocumentHistory.currentIndex;
+			return state;
+		});
+	});
+
 	// Update layouts
 	editor.subscriptions.subscribeJsMessage(UpdateGraphFadeArtwork, (updateGraphFadeArtwork) => {
 		update((state) => {
@@ -7,6 +7,7 @@ import {
 	type FrontendClickTargets,
 	type ContextMenuInformation,
 	type FrontendNode,
+	type FrontendNodeError,
 	type FrontendNodeType,
 	type WirePath,
 	ClearAllNodeGraphWires,
@@ -14,17 +15,20 @@ import {
 	UpdateBox,
 	UpdateClickTargets,
 	UpdateContextMenuInformation,
+	UpdateCustomNodeTypes,
 	UpdateInSelectedNetwork,
 	UpdateImportReorderIndex,
 	UpdateExportReorderIndex,
 	UpdateImportsExports,
 	UpdateLayerWidths,
+	UpdateNodeGraphErrors,
 	UpdateNodeGraphNodes,
 	UpdateVisibleNodes,
 	UpdateNodeGraphWires,
 	UpdateNodeGraphSelection,
 	UpdateNodeGraphTransform,
 	UpdateNodeThumbnail,
+	UpdateNodeThumbnailImage,
 	UpdateWirePathInProgress,
 } from "@graphite/messages";
 
@@ -43,11 +47,13 @@ export function createNodeGraphState(editor: Editor) {
 		addExport: undefined as { x: number; y: number } | undefined,
 		nodes: new Map<bigint, FrontendNode>(),
 		visibleNodes: new Set<bigint>(),
+		errors: [] as FrontendNodeError[],
 		/// The index is the exposed input index. The exports have a first key value of u32::MAX.
 		wires: new Map<bigint, Map<number, WirePath>>(),
 		wirePathInProgress: undefined as WirePath | undefined,
 		nodeDescriptions: new Map<string, string>(),
 		nodeTypes: [] as FrontendNodeType[],
+		customNodeTypes: [] as FrontendNodeType[],
 		thumbnails: new Map<bigint, string>(),
 		selected: [] as bigint[],
 		transform: { scale: 1, x: 0, y: 0 },
@@ -82,6 +88,12 @@ export function createNodeGraphState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateCustomNodeTypes, (updateCustomNodeTypes) => {
+		update((state) => {
+			state.customNodeTypes = updateCustomNodeTypes.nodeTypes;
+			return state;
+		});
+	});
 	editor.subscriptions.subscribeJsMessage(UpdateImportReorderIndex, (updateImportReorderIndex) => {
 		update((state) => {
 			state.reorderImportIndex = updateImportReorderIndex.importIndex;
@@ -132,6 +144,12 @@ export function createNodeGraphState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateNodeGraphErrors, (updateNodeGraphErrors) => {
+		update((state) => {
+			state.errors = updateNodeGraphErrors.errors;
+			return state;
+		});
+	});
 	editor.subscriptions.subscribeJsMessage(UpdateNodeGraphWires, (updateNodeWires) => {
 		update((state) => {
 			updateNodeWires.wires.forEach((wireUpdate) => {
@@ -168,11 +186,25 @@ export function createNodeGraphState(editor: Editor) {
 			return state;
 		});
 	});
-	editor.subscriptions.subscribeJsMessage(UpdateNodeThumbnail, (updateNodeThumbnail) => {
+	// Object URLs of the PNG thumbnails, which are revoked once replaced so the browser can free the images
+	const thumbnailUrls = new Map<bigint, string>();
+	const setThumbnail = (id: bigint, html: string, url?: string) => {
+		const oldUrl = thumbnailUrls.get(id);
+		if (oldUrl) URL.revokeObjectURL(oldUrl);
+		if (url) thumbnailUrls.set(id, url);
+		else thumbnailUrls.delete(id);
+
 		update((state) => {
-			state.thumbnails.set(updateNodeThumbnail.id, updateNodeThumbnail.value);
+			state.thumbnails.set(id, html);
 			return state;
 		});
+	};
+	editor.subscriptions.subscribeJsMessage(UpdateNodeThumbnail, (updateNodeThumbnail) => {
+		setThumbnail(updateNodeThumbnail.id, updateNodeThumbnail.value);
+	});
+	editor.subscriptions.subscribeJsMessage(UpdateNodeThumbnailImage, (updateNodeThumbnailImage) => {
+		const url = URL.createObjectURL(new Blob([new Uint8Array(updateNodeThumbnailImage.png)], { type: "image/png" }));
+		setThumbnail(updateNodeThumbnailImage.id, `<img src="${url}" alt="" />`, url);
 	});
 	editor.subscriptions.subscribeJsMessage(UpdateWirePathInProgress, (updateWirePathInProgress) => {
 		update((state) => {
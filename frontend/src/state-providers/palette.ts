@@ -0,0 +1,31 @@
+import { writable } from "svelte/store";
+
+import { type Editor } from "@graphite/editor";
+import { type Palette, UpdatePalette } from "@graphite/messages";
+import { upload } from "@graphite/utility-functions/files";
+
+// eslint-disable-next-line @typescript-eslint/explicit-function-return-type
+export function createPaletteState(editor: Editor) {
+	const { subscribe, set } = writable<Palette>({ name: "", swatches: [] });
+
+	async function importPalette() {
+		const file = await upload(".ase,.gpl", "data");
+		editor.handle.importPalette(file.filename, file.content);
+	}
+
+	function exportPalette(extension: "ase" | "gpl") {
+		editor.handle.exportPalette(extension);
+	}
+
+	// Set up message subscriptions on creation
+	editor.subscriptions.subscribeJsMessage(UpdatePalette, (updatePalette) => {
+		set(updatePalette.palette);
+	});
+
+	return {
+		subscribe,
+		importPalette,
+		exportPalette,
+	};
+}
+export type PaletteState = ReturnType<typeof createPaletteState>;
@@ -3,12 +3,15 @@
 import { writable } from "svelte/store";
 
 import { type Editor } from "@graphite/editor";
+import { storeRecentDocumentHandle } from "@graphite/io-managers/persistence";
 import {
 	type FrontendDocumentDetails,
 	TriggerFetchAndOpenDocument,
 	TriggerDownloadImage,
+	TriggerDownloadBinaryFile,
 	TriggerDownloadTextFile,
 	TriggerImport,
+	TriggerImportBrushTip,
 	TriggerOpenDocument,
 	UpdateActiveDocument,
 	UpdateOpenDocumentsList,
@@ -16,9 +19,13 @@ import {
 	defaultWidgetLayout,
 	patchWidgetLayout,
 	UpdateSpreadsheetLayout,
+	UpdateSwatchesPanelState,
+	UpdateSwatchesPanelLayout,
+	UpdateRecentDocuments,
+	type RecentDocument,
 } from "@graphite/messages";
-import { downloadFileText, downloadFileBlob, upload } from "@graphite/utility-functions/files";
-import { extractPixelData, rasterizeSVG } from "@graphite/utility-functions/rasterization";
+import { downloadFileText, downloadFileBlob, upload, uploadTextWithHandle } from "@graphite/utility-functions/files";
+import { extractPixelData, isHighDepthImage, rasterizeSVG } from "@graphite/utility-functions/rasterization";
 
 // eslint-disable-next-line @typescript-eslint/explicit-function-return-type
 export function createPortfolioState(editor: Editor) {
@@ -29,6 +36,9 @@ export function createPortfolioState(editor: Editor) {
 		spreadsheetOpen: false,
 		spreadsheetNode: BigInt(0) as bigint | undefined,
 		spreadsheetWidgets: defaultWidgetLayout(),
+		swatchesOpen: false,
+		swatchesWidgets: defaultWidgetLayout(),
+		recentDocuments: [] as RecentDocument[],
 	});
 
 	// Set up message subscriptions on creation
@@ -38,6 +48,12 @@ export function createPortfolioState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateRecentDocuments, (updateRecentDocuments) => {
+		update((state) => {
+			state.recentDocuments = updateRecentDocuments.recentDocuments;
+			return state;
+		});
+	});
 	editor.subscriptions.subscribeJsMessage(UpdateActiveDocument, (updateActiveDocument) => {
 		update((state) => {
 			// Assume we receive a correct document id
@@ -63,11 +79,13 @@ export function createPortfolioState(editor: Editor) {
 	});
 	editor.subscriptions.subscribeJsMessage(TriggerOpenDocument, async () => {
 		const extension = editor.handle.fileSaveSuffix();
-		const data = await upload(extension, "text");
+		// SVG files are opened as a new document with their content imported as layers
+		const data = await uploadTextWithHandle(`${extension},.svg`);
+		if (data.handle) await storeRecentDocumentHandle(data.filename, data.handle);
 		editor.handle.openDocumentFile(data.filename, data.content);
 	});
 	editor.subscriptions.subscribeJsMessage(TriggerImport, async () => {
-		const data = await upload("image/*", "both");
+		const data = await upload("image/*,.exr,.hdr", "both");
 
 		if (data.type.includes("svg")) {
 			const svg = new TextDecoder().decode(data.content.data);
@@ -81,21 +99,33 @@ export function createPortfolioState(editor: Editor) {
 			return;
 		}
 
+		if (isHighDepthImage(data.filename) && editor.handle.pasteEncodedImage(data.filename, data.content.data)) return;
+
 		const imageData = await extractPixelData(new Blob([data.content.data], { type: data.type }));
 		editor.handle.pasteImage(data.filename, new Uint8Array(imageData.data), imageData.width, imageData.height);
 	});
+	editor.subscriptions.subscribeJsMessage(TriggerImportBrushTip, async () => {
+		const data = await upload("image/*", "data");
+
+		const imageData = await extractPixelData(new Blob([data.content], { type: data.type }));
+		const name = data.filename.replace(/\.[^.]+$/, "");
+		editor.handle.importBrushTip(name, new Uint8Array(imageData.data), imageData.width, imageData.height);
+	});
 	editor.subscriptions.subscribeJsMessage(TriggerDownloadTextFile, (triggerFileDownload) => {
 		downloadFileText(triggerFileDownload.name, triggerFileDownload.document);
 	});
+	editor.subscriptions.subscribeJsMessage(TriggerDownloadBinaryFile, (triggerFileDownload) => {
+		downloadFileBlob(triggerFileDownload.name, new Blob([new Uint8Array(triggerFileDownload.data)]));
+	});
 	editor.subscriptions.subscribeJsMessage(TriggerDownloadImage, async (triggerDownloadImage) => {
-		const { svg, name, mime, size } = triggerDownloadImage;
+		const { svg, name, mime, size, colorSpace } = triggerDownloadImage;
 
 		// Fill the canvas with white if it'll be a JPEG (which does not support transparency and defaults to black)
 		const backgroundColor = mime.endsWith("jpeg") ? "white" : undefined;
 
 		// Rasterize the SVG to an image file
 		try {
-			const blob = await rasterizeSVG(svg, size.x, size.y, mime, backgroundColor);
+			const blob = await rasterizeSVG(svg, size.x, size.y, mime, backgroundColor, colorSpace);
 
 			// Have the browser download the file to the user's disk
 			downloadFileBlob(name, blob);
@@ -116,6 +146,18 @@ export function createPortfolioState(editor: Editor) {
 			return state;
 		});
 	});
+	editor.subscriptions.subscribeJsMessage(UpdateSwatchesPanelState, (updateSwatchesPanelState) => {
+		update((state) => {
+			state.swatchesOpen = updateSwatchesPanelState.open;
+			return state;
+		});
+	});
+	editor.subscriptions.subscribeJsMessage(UpdateSwatchesPanelLayout, (updateSwatchesPanelLayout) => {
+		update((state) => {
+			patchWidgetLayout(state.swatchesWidgets, updateSwatchesPanelLayout);
+			return state;
+		});
+	});
 
 	return {
 		subscribe,
@@ -59,3 +59,17 @@ export async function upload<T extends "text" | "data" | "both">(acceptedExtensi
 }
 export type UploadResult<T> = { filename: string; type: string; content: UploadResultType<T> };
 type UploadResultType<T> = T extends "text" ? string : T extends "data" ? Uint8Array : T extends "both" ? { text: string; data: Uint8Array } : never;
+
+type FilePickerWindow = { showOpenFilePicker?: (options: { types: { accept: Record<string, string[]> }[] }) => Promise<FileSystemFileHandle[]> };
+
+// Like `upload()` for text, but in browsers with the File System Access API it also gives a handle which the file can be read from again later
+export async function uploadTextWithHandle(acceptedExtensions: string): Promise<UploadResult<"text"> & { handle?: FileSystemFileHandle }> {
+	const showOpenFilePicker = (window as FilePickerWindow).showOpenFilePicker;
+	if (!showOpenFilePicker) return upload(acceptedExtensions, "text");
+
+	// The picker is closed without choosing a file, which never resolves, like `upload()`
+	const [handle] = await showOpenFilePicker({ types: [{ accept: { "application/octet-stream": acceptedExtensions.split(",") } }] }).catch(() => new Promise<never>(() => {}));
+
+	const file = await handle.getFile();
+	return { filename: file.name, type: file.type, content: await file.text(), handle };
+}
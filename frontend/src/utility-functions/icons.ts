@@ -104,6 +104,7 @@ import AlignVerticalCenter from "@graphite-frontend/assets/icon-16px-solid/align
 import Artboard from "@graphite-frontend/assets/icon-16px-solid/artboard.svg";
 import BooleanDifference from "@graphite-frontend/assets/icon-16px-solid/boolean-difference.svg";
 import BooleanDivide from "@graphite-frontend/assets/icon-16px-solid/boolean-divide.svg";
+import BooleanExclude from "@graphite-frontend/assets/icon-16px-solid/boolean-exclude.svg";
 import BooleanIntersect from "@graphite-frontend/assets/icon-16px-solid/boolean-intersect.svg";
 import BooleanSubtractBack from "@graphite-frontend/assets/icon-16px-solid/boolean-subtract-back.svg";
 import BooleanSubtractFront from "@graphite-frontend/assets/icon-16px-solid/boolean-subtract-front.svg";
@@ -119,6 +120,8 @@ import Credits from "@graphite-frontend/assets/icon-16px-solid/credits.svg";
 import CustomColor from "@graphite-frontend/assets/icon-16px-solid/custom-color.svg";
 import Cut from "@graphite-frontend/assets/icon-16px-solid/cut.svg";
 import DeselectAll from "@graphite-frontend/assets/icon-16px-solid/deselect-all.svg";
+import DistributeHorizontal from "@graphite-frontend/assets/icon-16px-solid/distribute-horizontal.svg";
+import DistributeVertical from "@graphite-frontend/assets/icon-16px-solid/distribute-vertical.svg";
 import Edit from "@graphite-frontend/assets/icon-16px-solid/edit.svg";
 import Empty from "@graphite-frontend/assets/icon-16px-solid/empty.svg";
 import EyeHidden from "@graphite-frontend/assets/icon-16px-solid/eye-hidden.svg";
@@ -225,6 +228,7 @@ const SOLID_16PX = {
 	Artboard: { svg: Artboard, size: 16 },
 	BooleanDifference: { svg: BooleanDifference, size: 16 },
 	BooleanDivide: { svg: BooleanDivide, size: 16 },
+	BooleanExclude: { svg: BooleanExclude, size: 16 },
 	BooleanIntersect: { svg: BooleanIntersect, size: 16 },
 	BooleanSubtractBack: { svg: BooleanSubtractBack, size: 16 },
 	BooleanSubtractFront: { svg: BooleanSubtractFront, size: 16 },
@@ -240,6 +244,8 @@ const SOLID_16PX = {
 	CustomColor: { svg: CustomColor, size: 16 },
 	Cut: { svg: Cut, size: 16 },
 	DeselectAll: { svg: DeselectAll, size: 16 },
+	DistributeHorizontal: { svg: DistributeHorizontal, size: 16 },
+	DistributeVertical: { svg: DistributeVertical, size: 16 },
 	Edit: { svg: Edit, size: 16 },
 	Empty: { svg: Empty, size: 16 },
 	Eyedropper: { svg: Eyedropper, size: 16 },
@@ -1,10 +1,11 @@
 // Rasterize the string of an SVG document at a given width and height and return the canvas it was drawn onto during the rasterization process
-export async function rasterizeSVGCanvas(svg: string, width: number, height: number, backgroundColor?: string): Promise<HTMLCanvasElement> {
+// Colors outside of the given color space are clipped, and the color space is tagged in image files made from the canvas
+export async function rasterizeSVGCanvas(svg: string, width: number, height: number, backgroundColor?: string, colorSpace: PredefinedColorSpace = "srgb"): Promise<HTMLCanvasElement> {
 	// A canvas to render our SVG to in order to get a raster image
 	const canvas = document.createElement("canvas");
 	canvas.width = width;
 	canvas.height = height;
-	const context = canvas.getContext("2d", { willReadFrequently: true });
+	const context = canvas.getContext("2d", { willReadFrequently: true, colorSpace });
 	if (!context) throw new Error("Can't create 2D context from canvas during SVG rasterization");
 
 	// Apply a background fill color if one is given
@@ -34,10 +35,10 @@ export async function rasterizeSVGCanvas(svg: string, width: number, height: num
 }
 
 // Rasterize the string of an SVG document at a given width and height and turn it into the blob data of an image file matching the given MIME type
-export async function rasterizeSVG(svg: string, width: number, height: number, mime: string, backgroundColor?: string): Promise<Blob> {
+export async function rasterizeSVG(svg: string, width: number, height: number, mime: string, backgroundColor?: string, colorSpace: PredefinedColorSpace = "srgb"): Promise<Blob> {
 	if (!width || !height) throw new Error("Width and height must be nonzero when given to rasterizeSVG()");
 
-	const canvas = await rasterizeSVGCanvas(svg, width, height, backgroundColor);
+	const canvas = await rasterizeSVGCanvas(svg, width, height, backgroundColor, colorSpace);
 
 	// Convert the canvas to an image of the correct MIME type
 	const blob = await new Promise<Blob | undefined>((resolve) => {
@@ -51,6 +52,15 @@ export async function rasterizeSVG(svg: string, width: number, height: number, m
 	return blob;
 }
 
+// Formats which may have more than 8 bits per channel, so they are decoded by the editor rather than by the browser, which would quantize them to 8 bits
+const HIGH_DEPTH_IMAGE_EXTENSIONS = [".png", ".exr", ".hdr"];
+
+/// Whether an image file should be decoded by the editor with `pasteEncodedImage()` to keep its full precision
+export function isHighDepthImage(filename: string): boolean {
+	const lowercase = filename.toLowerCase();
+	return HIGH_DEPTH_IMAGE_EXTENSIONS.some((extension) => lowercase.endsWith(extension));
+}
+
 /// Convert an image source (e.g. PNG document) into pixel data, a width, and a height
 export async function extractPixelData(imageData: ImageBitmapSource): Promise<ImageData> {
 	const canvasContext = await imageToCanvasContext(imageData);
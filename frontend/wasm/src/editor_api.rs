@@ -13,8 +13,11 @@ use editor::messages::input_mapper::utility_types::input_mouse::{EditorMouseStat
 use editor::messages::portfolio::document::utility_types::document_metadata::LayerNodeIdentifier;
 use editor::messages::portfolio::document::utility_types::network_interface::ImportOrExport;
 use editor::messages::portfolio::utility_types::Platform;
+use editor::messages::preferences::palettes::PaletteFormat;
 use editor::messages::prelude::*;
+use editor::messages::tool::tool_messages::brush_tool::BrushToolMessageOptionsUpdate;
 use editor::messages::tool::tool_messages::tool_prelude::WidgetId;
+use editor::node_graph_executor::NodeGraphExecutionError;
 use graph_craft::document::NodeId;
 use graphene_std::raster::Image;
 use graphene_std::raster::color::Color;
@@ -363,6 +366,77 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	#[wasm_bindgen(js_name = loadRecentDocuments)]
+	pub fn load_recent_documents(&self, serialized: String) {
+		let message = RecentDocumentsMessage::Load { serialized };
+
+		self.dispatch(message);
+	}
+
+	/// Opens a document from the recent documents list, which asks to remove it from the list if its file is gone
+	#[wasm_bindgen(js_name = openRecentDocument)]
+	pub fn open_recent_document(&self, path: String) {
+		let message = RecentDocumentsMessage::Open { path };
+
+		self.dispatch(message);
+	}
+
+	/// Reports that the file of a recent document could not be opened
+	#[wasm_bindgen(js_name = recentDocumentMissing)]
+	pub fn recent_document_missing(&self, path: String) {
+		let message = RecentDocumentsMessage::FileMissing { path };
+
+		self.dispatch(message);
+	}
+
+	/// Gives a recent document its thumbnail, a PNG data URL of the rendered viewport
+	#[wasm_bindgen(js_name = setRecentDocumentThumbnail)]
+	pub fn set_recent_document_thumbnail(&self, path: String, thumbnail: String) {
+		let message = RecentDocumentsMessage::SetThumbnail { path, thumbnail };
+
+		self.dispatch(message);
+	}
+
+	#[wasm_bindgen(js_name = toggleRecentDocumentPin)]
+	pub fn toggle_recent_document_pin(&self, path: String) {
+		let message = RecentDocumentsMessage::TogglePin { path };
+
+		self.dispatch(message);
+	}
+
+	#[wasm_bindgen(js_name = removeRecentDocument)]
+	pub fn remove_recent_document(&self, path: String) {
+		let message = RecentDocumentsMessage::Remove { path };
+
+		self.dispatch(message);
+	}
+
+	#[wasm_bindgen(js_name = importPalette)]
+	pub fn import_palette(&self, file_name: String, data: Vec<u8>) {
+		let message = PreferencesMessage::ImportPalette { file_name, data };
+
+		self.dispatch(message);
+	}
+
+	/// Adds an image as a brush tip for the Brush tool
+	#[wasm_bindgen(js_name = importBrushTip)]
+	pub fn import_brush_tip(&self, name: String, data: Vec<u8>, width: u32, height: u32) {
+		let message = BrushToolMessage::UpdateOptions(BrushToolMessageOptionsUpdate::ImportedTip { name, data, width, height });
+
+		self.dispatch(message);
+	}
+
+	#[wasm_bindgen(js_name = exportPalette)]
+	pub fn export_palette(&self, extension: String) -> Result<(), JsValue> {
+		let Some(format) = PaletteFormat::from_extension(&extension) else {
+			return Err(Error::new(&format!("Unsupported palette format: {extension}")).into());
+		};
+		let message = PreferencesMessage::ExportPalette { format };
+
+		self.dispatch(message);
+		Ok(())
+	}
+
 	#[wasm_bindgen(js_name = selectDocument)]
 	pub fn select_document(&self, document_id: u64) {
 		let document_id = DocumentId(document_id);
@@ -370,6 +444,51 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Computes the histogram of the selected layer's content, which is sent back with an `UpdateHistogram` message
+	#[wasm_bindgen(js_name = requestSelectedLayerHistogram)]
+	pub fn request_selected_layer_histogram(&self) {
+		let message = PortfolioMessage::RequestSelectedLayerHistogram;
+		self.dispatch(message);
+	}
+
+	/// Reports how well the node graph's cached outputs are reused, which is sent back with an `UpdateNodeCacheStats` message
+	#[wasm_bindgen(js_name = requestNodeCacheStats)]
+	pub fn request_node_cache_stats(&self) {
+		let message = PortfolioMessage::RequestNodeCacheStats;
+		self.dispatch(message);
+	}
+
+	/// Enables or disables keeping the outputs of the inspected nodes after each graph evaluation
+	#[wasm_bindgen(js_name = setNodeInspection)]
+	pub fn set_node_inspection(&self, enabled: bool) {
+		let message = PortfolioMessage::SetNodeInspection { enabled };
+		self.dispatch(message);
+	}
+
+	/// Marks the node at the given path as inspected, so its output is kept after each graph evaluation
+	#[wasm_bindgen(js_name = inspectNodeOutput)]
+	pub fn inspect_node_output(&self, node_path: Vec<u64>) {
+		let node_path = node_path.into_iter().map(NodeId).collect();
+		let message = PortfolioMessage::InspectNodeOutput { node_path };
+		self.dispatch(message);
+	}
+
+	/// Unmarks the node at the given path as inspected, forgetting its kept output
+	#[wasm_bindgen(js_name = stopInspectingNodeOutput)]
+	pub fn stop_inspecting_node_output(&self, node_path: Vec<u64>) {
+		let node_path = node_path.into_iter().map(NodeId).collect();
+		let message = PortfolioMessage::StopInspectingNodeOutput { node_path };
+		self.dispatch(message);
+	}
+
+	/// Fetches the kept output of an inspected node, which is sent back with an `UpdateInspectedValue` message
+	#[wasm_bindgen(js_name = requestInspectedValue)]
+	pub fn request_inspected_value(&self, node_path: Vec<u64>, full: bool) {
+		let node_path = node_path.into_iter().map(NodeId).collect();
+		let message = PortfolioMessage::RequestInspectedValue { node_path, full };
+		self.dispatch(message);
+	}
+
 	#[wasm_bindgen(js_name = newDocumentDialog)]
 	pub fn new_document_dialog(&self) {
 		let message = DialogMessage::RequestNewDocumentDialog;
@@ -458,6 +577,13 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Inform the editor whether a number input widget is being dragged, so the graph can be rendered at a reduced quality until the drag ends
+	#[wasm_bindgen(js_name = setWidgetDragInProgress)]
+	pub fn set_widget_drag_in_progress(&self, in_progress: bool) {
+		let message = PortfolioMessage::SetWidgetDragInProgress { in_progress };
+		self.dispatch(message);
+	}
+
 	/// Mouse movement within the screenspace bounds of the viewport
 	#[wasm_bindgen(js_name = onMouseMove)]
 	pub fn on_mouse_move(&self, x: f64, y: f64, mouse_keys: u8, modifiers: u8) {
@@ -651,6 +777,26 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Undo or redo to the document history state reached by the given number of steps from the oldest state which is kept
+	#[wasm_bindgen(js_name = jumpToHistoryState)]
+	pub fn jump_to_history_state(&self, index: usize) {
+		let message = DocumentMessage::JumpToHistoryState { index };
+		self.dispatch(message);
+	}
+
+	/// Select the layers similar to the given one in the way named by the criterion, replacing the selection or adding them to it
+	#[wasm_bindgen(js_name = selectSimilarLayers)]
+	pub fn select_similar_layers(&self, id: u64, criterion: JsValue, extend: bool) -> Result<(), JsValue> {
+		let criterion = from_value(criterion).map_err(|error| Error::new(&format!("Invalid similarity criterion: {error}")))?;
+		let message = DocumentMessage::SelectSimilarLayers {
+			layer: Some(NodeId(id)),
+			criterion,
+			extend,
+		};
+		self.dispatch(message);
+		Ok(())
+	}
+
 	/// Deselect all layers
 	#[wasm_bindgen(js_name = deselectAllLayers)]
 	pub fn deselect_all_layers(&self) {
@@ -677,11 +823,7 @@ impl EditorHandle {
 	#[wasm_bindgen(js_name = setLayerName)]
 	pub fn set_layer_name(&self, id: u64, name: String) {
 		let layer = LayerNodeIdentifier::new_unchecked(NodeId(id));
-		let message = NodeGraphMessage::SetDisplayName {
-			node_id: layer.to_node(),
-			alias: name,
-			skip_adding_history_step: false,
-		};
+		let message = NodeGraphMessage::RenameLayer { layer, new_name: name };
 		self.dispatch(message);
 	}
 
@@ -726,6 +868,13 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Merges the selected nodes into a new custom node, which is added to the document's library of custom nodes
+	#[wasm_bindgen(js_name = createCustomNodeFromSelection)]
+	pub fn create_custom_node_from_selection(&self) {
+		let message = NodeGraphMessage::CreateCustomNodeFromSelection;
+		self.dispatch(message);
+	}
+
 	/// Creates a new document node in the node graph
 	#[wasm_bindgen(js_name = createNode)]
 	pub fn create_node(&self, node_type: String, x: i32, y: i32) {
@@ -779,6 +928,32 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Pastes an encoded image file which may have more than 8 bits per channel, such as a 16-bit PNG or an OpenEXR or Radiance HDR image.
+	/// Returns false if the file couldn't be decoded, in which case the frontend falls back to decoding it to 8 bits per channel with the browser.
+	#[wasm_bindgen(js_name = pasteEncodedImage)]
+	pub fn paste_encoded_image(&self, name: Option<String>, encoded: Vec<u8>, mouse_x: Option<f64>, mouse_y: Option<f64>, insert_parent_id: Option<u64>, insert_index: Option<usize>) -> bool {
+		let Some(image) = graphene_std::raster::Image::from_encoded_image(&encoded) else { return false };
+		let mouse = mouse_x.and_then(|x| mouse_y.map(|y| (x, y)));
+
+		let parent_and_insert_index = if let (Some(insert_parent_id), Some(insert_index)) = (insert_parent_id, insert_index) {
+			let insert_parent_id = NodeId(insert_parent_id);
+			let parent = LayerNodeIdentifier::new_unchecked(insert_parent_id);
+			Some((parent, insert_index))
+		} else {
+			None
+		};
+
+		let message = PortfolioMessage::PasteImage {
+			name,
+			image,
+			mouse,
+			parent_and_insert_index,
+		};
+		self.dispatch(message);
+
+		true
+	}
+
 	#[wasm_bindgen(js_name = pasteSvg)]
 	pub fn paste_svg(&self, name: Option<String>, svg: String, mouse_x: Option<f64>, mouse_y: Option<f64>, insert_parent_id: Option<u64>, insert_index: Option<usize>) {
 		let mouse = mouse_x.and_then(|x| mouse_y.map(|y| (x, y)));
@@ -808,6 +983,13 @@ impl EditorHandle {
 		self.dispatch(message);
 	}
 
+	/// Solo a layer given its node ID, so only it and its subtree are rendered, or clear the solo if it's already soloed
+	#[wasm_bindgen(js_name = toggleLayerSolo)]
+	pub fn toggle_layer_solo(&self, id: u64) {
+		let message = DocumentMessage::ToggleLayerSolo { id: NodeId(id) };
+		self.dispatch(message);
+	}
+
 	/// Pin or unpin a node given its node ID
 	#[wasm_bindgen(js_name = setNodePinned)]
 	pub fn set_node_pinned(&self, id: u64, pinned: bool) {
@@ -941,11 +1123,9 @@ async fn poll_node_graph_evaluation() {
 
 	editor_and_handle(|editor, handle| {
 		let mut messages = VecDeque::new();
-		if let Err(e) = editor.poll_node_graph_evaluation(&mut messages) {
-			// TODO: This is a hacky way to suppress the error, but it shouldn't be generated in the first place
-			if e != "No active document" {
-				error!("Error evaluating node graph:\n{e}");
-			}
+		match editor.poll_node_graph_evaluation(&mut messages) {
+			Ok(()) | Err(NodeGraphExecutionError::NoActiveDocument) => {}
+			Err(e) => error!("Error evaluating node graph:\n{e}"),
 		}
 
 		// Clear the error display if there are no more errors
@@ -432,10 +432,9 @@ impl<PointId: crate::Identifier> Subpath<PointId> {
 	}
 
 	/// Returns the manipulator point that is needed for a miter join if it is possible.
-	/// - `miter_limit`: Defines a limit for the ratio between the miter length and the stroke width.
+	/// - `miter_limit`: Defines a limit for the ratio between the miter length and the stroke width, which is `1 / sin(θ / 2)` for the angle `θ` between the segments.
 	///
-	/// Alternatively, this can be interpreted as limiting the angle that the miter can form.
-	/// When the limit is exceeded, no manipulator group will be returned.
+	/// When the limit is exceeded, no manipulator group will be returned so the join falls back to a bevel, as SVG specifies.
 	/// This value should be greater than 0. If not, the default of 4 will be used.
 	pub fn miter_line_join(&self, other: &Subpath<PointId>, miter_limit: Option<f64>) -> Option<ManipulatorGroup<PointId>> {
 		let miter_limit = match miter_limit {
@@ -472,7 +471,7 @@ impl<PointId: crate::Identifier> Subpath<PointId> {
 			// Draw the miter join if the intersection occurs in the correct direction with respect to the path
 			if start_to_intersection.normalize().abs_diff_eq(in_tangent, MAX_ABSOLUTE_DIFFERENCE)
 				&& intersection_to_end.normalize().abs_diff_eq(out_tangent, MAX_ABSOLUTE_DIFFERENCE)
-				&& miter_limit > f64::EPSILON / (start_to_intersection.angle_to(-intersection_to_end).abs() / 2.).sin()
+				&& miter_limit >= 1. / (start_to_intersection.angle_to(-intersection_to_end).abs() / 2.).sin()
 			{
 				return Some(ManipulatorGroup {
 					anchor: intersection,
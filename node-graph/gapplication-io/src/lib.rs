@@ -2,7 +2,9 @@ use dyn_any::{DynAny, StaticType, StaticTypeSized};
 use glam::{DAffine2, UVec2};
 use graphene_core::text::FontCache;
 use graphene_core::transform::Footprint;
+use graphene_core::uuid::NodeId;
 use graphene_core::vector::style::ViewMode;
+use std::collections::HashMap;
 use std::fmt::Debug;
 use std::future::Future;
 use std::hash::{Hash, Hasher};
@@ -235,7 +237,7 @@ pub struct TimingInformation {
 	pub animation_time: Duration,
 }
 
-#[derive(Debug, Default, Clone, Copy, PartialEq, DynAny, serde::Serialize, serde::Deserialize)]
+#[derive(Debug, Default, Clone, PartialEq, DynAny, serde::Serialize, serde::Deserialize)]
 pub struct RenderConfig {
 	pub viewport: Footprint,
 	pub export_format: ExportFormat,
@@ -243,6 +245,8 @@ pub struct RenderConfig {
 	pub view_mode: ViewMode,
 	pub hide_artboards: bool,
 	pub for_export: bool,
+	/// The names of the named layers by their node ID, which an SVG export identifies their elements by.
+	pub layer_names: HashMap<NodeId, String>,
 }
 
 struct Logger;
@@ -1,5 +1,5 @@
 use crate::brush_cache::BrushCache;
-use crate::brush_stroke::{BrushStroke, BrushStyle};
+use crate::brush_stroke::{BrushDab, BrushStroke, BrushStrokeMode, BrushStyle, BrushTip};
 use glam::{DAffine2, DVec2};
 use graphene_core::blending::BlendMode;
 use graphene_core::bounds::BoundingBox;
@@ -127,8 +127,61 @@ where
 	target
 }
 
+/// Stamps the brush texture once for each dab, scaled, rotated, and faded as the dab requires.
+/// Unlike blitting, this resamples the texture at the center of each target pixel, so it suits dabs which differ from each other.
+pub fn stamp_dabs(mut target: Instance<Raster<CPU>>, texture: &Raster<CPU>, dabs: &[BrushDab], blend_fn: impl Fn(Color, Color) -> Color) -> Instance<Raster<CPU>> {
+	let target_size = DVec2::new(target.instance.width as f64, target.instance.height as f64);
+	let texture_size = DVec2::new(texture.width as f64, texture.height as f64);
+	if target_size.min_element() <= 0. || texture_size.min_element() <= 0. {
+		return target;
+	}
+
+	// Transforms a point from the target's pixel space into layer space
+	let target_to_layer = target.transform * DAffine2::from_scale(1. / target_size);
+	let aspect = texture_size / texture_size.max_element();
+
+	for dab in dabs {
+		// Maps the texture's unit square onto the dab, centered on its position
+		let dab_transform = DAffine2::from_scale_angle_translation(aspect * dab.diameter, dab.rotation, dab.position) * DAffine2::from_translation(DVec2::splat(-0.5));
+		let target_to_texture = DAffine2::from_scale(texture_size) * dab_transform.inverse() * target_to_layer;
+
+		// Footprint of the dab in the target's pixel space
+		let footprint = Bbox::unit().affine_transform(target_to_layer.inverse() * dab_transform).to_axis_aligned_bbox();
+		let start = footprint.start.floor().clamp(DVec2::ZERO, target_size).as_uvec2();
+		let end = footprint.end.ceil().clamp(DVec2::ZERO, target_size).as_uvec2();
+
+		for y in start.y..end.y {
+			for x in start.x..end.x {
+				let texture_point = target_to_texture.transform_point2(DVec2::new(x as f64, y as f64) + 0.5);
+				if texture_point.x < 0. || texture_point.y < 0. || texture_point.x >= texture_size.x || texture_point.y >= texture_size.y {
+					continue;
+				}
+
+				let source_pixel = texture.data[texture_point.y as usize * texture.width as usize + texture_point.x as usize].apply_opacity(dab.opacity as f32);
+				let Some(destination_pixel) = target.instance.data_mut().get_pixel_mut(x, y) else { continue };
+
+				*destination_pixel = blend_fn(source_pixel, *destination_pixel);
+			}
+		}
+	}
+
+	target
+}
+
 pub async fn create_brush_texture(brush_style: &BrushStyle) -> Raster<CPU> {
-	let stamp = brush_stamp_generator(brush_style.diameter, brush_style.color, brush_style.hardness, brush_style.flow);
+	if let BrushTip::Image(tip) = &brush_style.tip {
+		// Image tips keep their own resolution and are scaled to the diameter when each dab is stamped
+		let color = brush_style.stamp_color().apply_opacity((brush_style.flow / 100.) as f32);
+		let data = tip.coverage.iter().map(|&coverage| color.apply_opacity(coverage as f32 / 255.)).collect();
+		return Raster::new_cpu(Image {
+			width: tip.width,
+			height: tip.height,
+			data,
+			base64_string: None,
+		});
+	}
+
+	let stamp = brush_stamp_generator(brush_style.diameter, brush_style.stamp_color(), brush_style.hardness, brush_style.flow);
 	let transform = DAffine2::from_scale_angle_translation(DVec2::splat(brush_style.diameter), 0., -DVec2::splat(brush_style.diameter / 2.));
 	let blank_texture = empty_image((), transform, Color::TRANSPARENT).instance_iter().next().unwrap_or_default();
 	let image = blend_stamp_closure(stamp, blank_texture, |a, b| blend_colors(a, b, BlendMode::Normal, 1.));
@@ -178,8 +231,9 @@ pub fn blend_with_mode(background: Instance<Raster<CPU>>, foreground: Instance<R
 	}
 }
 
+/// Paints the brush strokes onto the image. Clone strokes copy pixels from the clone source, or from the image being painted when no clone source is provided.
 #[node_macro::node(category("Raster"))]
-async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes: Vec<BrushStroke>, cache: BrushCache) -> RasterDataTable<CPU> {
+async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, mut strokes: Vec<BrushStroke>, cache: BrushCache, clone_source: RasterDataTable<CPU>) -> RasterDataTable<CPU> {
 	if image_frame_table.is_empty() {
 		image_frame_table.push(Instance::default());
 	}
@@ -192,15 +246,20 @@ async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes
 	let bbox = if image_bbox.size().length() < 0.1 { stroke_bbox } else { stroke_bbox.union(&image_bbox) };
 	let background_bounds = bbox.to_transform();
 
-	let mut draw_strokes: Vec<_> = strokes.iter().filter(|&s| !matches!(s.style.blend_mode, BlendMode::Erase | BlendMode::Restore)).cloned().collect();
-	let erase_restore_strokes: Vec<_> = strokes.iter().filter(|&s| matches!(s.style.blend_mode, BlendMode::Erase | BlendMode::Restore)).cloned().collect();
+	// TODO: Find a way to handle more than one instance
+	let clone_source = clone_source.instance_iter().next().filter(|source| source.instance.width > 0 && source.instance.height > 0);
 
-	let mut brush_plan = cache.compute_brush_plan(image_frame_instance, &draw_strokes);
+	let mut brush_plan = cache.compute_brush_plan(image_frame_instance, clone_source.as_ref(), &strokes);
 
 	// TODO: Find a way to handle more than one instance
 	let Some(mut actual_image) = extend_image_to_bounds((), brush_plan.background.to_table(), background_bounds).instance_iter().next() else {
 		return RasterDataTable::default();
 	};
+	// Restore strokes bring back the paint removed by erase strokes, so the image is also composited without any erasing when they're present
+	let mut unerased_image = brush_plan
+		.unerased_background
+		.take()
+		.map(|background| extend_image_to_bounds((), background.to_table(), background_bounds).instance_iter().next().unwrap_or_default());
 
 	let final_stroke_idx = brush_plan.strokes.len().saturating_sub(1);
 	for (idx, stroke) in brush_plan.strokes.into_iter().enumerate() {
@@ -216,7 +275,8 @@ async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes
 
 		// Compute transformation from stroke texture space into layer space, and create the stroke texture.
 		let skip = if idx == 0 { brush_plan.first_stroke_point_skip } else { 0 };
-		let positions: Vec<_> = stroke.compute_blit_points().into_iter().skip(skip).collect();
+		let dabs: Vec<_> = stroke.compute_dabs().into_iter().skip(skip).collect();
+		let positions: Vec<_> = dabs.iter().map(|dab| dab.position).collect();
 		let stroke_texture = if idx == 0 && positions.is_empty() {
 			core::mem::take(&mut brush_plan.first_stroke_texture)
 		} else {
@@ -229,13 +289,6 @@ async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes
 			let stroke_origin_in_layer = bbox.start - snap_offset - DVec2::splat(stroke.style.diameter / 2.);
 			let stroke_to_layer = DAffine2::from_translation(stroke_origin_in_layer) * DAffine2::from_scale(stroke_size);
 
-			// let normal_blend = BlendColorPairNode::new(ValueNode::new(CopiedNode::new(BlendMode::Normal)), ValueNode::new(CopiedNode::new(100.)));
-			let normal_blend = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::Normal, 1.));
-			let blit_node = BlitNode::new(
-				FutureWrapperNode::new(ClonedNode::new(brush_texture)),
-				FutureWrapperNode::new(ClonedNode::new(positions)),
-				FutureWrapperNode::new(ClonedNode::new(normal_blend)),
-			);
 			let blit_target = if idx == 0 {
 				let target = core::mem::take(&mut brush_plan.first_stroke_texture);
 				extend_image_to_bounds((), target.to_table(), stroke_to_layer)
@@ -244,65 +297,53 @@ async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes
 				// EmptyImageNode::new(CopiedNode::new(stroke_to_layer), CopiedNode::new(Color::TRANSPARENT)).eval(())
 			};
 
-			let instances = blit_node.eval(blit_target).await;
-			assert_eq!(instances.len(), 1);
-			instances.instance_iter().next().unwrap_or_default()
+			if stroke.style.has_uniform_dabs() {
+				// let normal_blend = BlendColorPairNode::new(ValueNode::new(CopiedNode::new(BlendMode::Normal)), ValueNode::new(CopiedNode::new(100.)));
+				let normal_blend = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::Normal, 1.));
+				let blit_node = BlitNode::new(
+					FutureWrapperNode::new(ClonedNode::new(brush_texture)),
+					FutureWrapperNode::new(ClonedNode::new(positions)),
+					FutureWrapperNode::new(ClonedNode::new(normal_blend)),
+				);
+
+				let instances = blit_node.eval(blit_target).await;
+				assert_eq!(instances.len(), 1);
+				instances.instance_iter().next().unwrap_or_default()
+			} else {
+				let blit_target = blit_target.instance_iter().next().unwrap_or_default();
+				stamp_dabs(blit_target, &brush_texture, &dabs, |a, b| blend_colors(a, b, BlendMode::Normal, 1.))
+			}
 		};
 
 		// Cache image before doing final blend, and store final stroke texture.
 		if idx == final_stroke_idx {
-			cache.cache_results(core::mem::take(&mut draw_strokes), actual_image.clone(), stroke_texture.clone());
+			cache.cache_results(core::mem::take(&mut strokes), actual_image.clone(), unerased_image.clone(), stroke_texture.clone());
 		}
 
-		// TODO: Is this the correct way to do opacity in blending?
-		actual_image = blend_with_mode(actual_image, stroke_texture, stroke.style.blend_mode, (stroke.style.color.a() * 100.) as f64);
-	}
-
-	let has_erase_strokes = strokes.iter().any(|s| s.style.blend_mode == BlendMode::Erase);
-	if has_erase_strokes {
-		let opaque_image = Image::new(bbox.size().x as u32, bbox.size().y as u32, Color::WHITE);
-		let mut erase_restore_mask = Instance {
-			instance: Raster::new_cpu(opaque_image),
-			transform: background_bounds,
-			..Default::default()
-		};
-
-		for stroke in erase_restore_strokes {
-			let mut brush_texture = cache.get_cached_brush(&stroke.style);
-			if brush_texture.is_none() {
-				let tex = create_brush_texture(&stroke.style).await;
-				cache.store_brush(stroke.style.clone(), tex.clone());
-				brush_texture = Some(tex);
+		// Each stroke is composited onto the result of the strokes before it, so erasing only affects earlier strokes
+		match stroke.style.mode() {
+			BrushStrokeMode::Paint(blend_mode) => {
+				// TODO: Is this the correct way to do opacity in blending?
+				let opacity = (stroke.style.color.a() * 100.) as f64;
+				unerased_image = unerased_image.map(|unerased_image| blend_with_mode(unerased_image, stroke_texture.clone(), blend_mode, opacity));
+				actual_image = blend_with_mode(actual_image, stroke_texture, blend_mode, opacity);
 			}
-			let brush_texture = brush_texture.unwrap();
-			let positions: Vec<_> = stroke.compute_blit_points().into_iter().collect();
-
-			match stroke.style.blend_mode {
-				BlendMode::Erase => {
-					let blend_params = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::Erase, 1.));
-					let blit_node = BlitNode::new(
-						FutureWrapperNode::new(ClonedNode::new(brush_texture)),
-						FutureWrapperNode::new(ClonedNode::new(positions)),
-						FutureWrapperNode::new(ClonedNode::new(blend_params)),
-					);
-					erase_restore_mask = blit_node.eval(erase_restore_mask.to_table()).await.instance_iter().next().unwrap_or_default();
-				}
-				// Yes, this is essentially the same as the above, but we duplicate to inline the blend mode.
-				BlendMode::Restore => {
-					let blend_params = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::Restore, 1.));
-					let blit_node = BlitNode::new(
-						FutureWrapperNode::new(ClonedNode::new(brush_texture)),
-						FutureWrapperNode::new(ClonedNode::new(positions)),
-						FutureWrapperNode::new(ClonedNode::new(blend_params)),
-					);
-					erase_restore_mask = blit_node.eval(erase_restore_mask.to_table()).await.instance_iter().next().unwrap_or_default();
+			BrushStrokeMode::Erase => actual_image = blend_image_closure(stroke_texture, actual_image, erase),
+			BrushStrokeMode::Restore => {
+				if let Some(unerased_image) = &unerased_image {
+					actual_image = restore(actual_image, unerased_image, stroke_texture);
 				}
-				_ => unreachable!(),
+			}
+			BrushStrokeMode::Clone(offset) => {
+				// Without a clone source, each image copies from itself as it was before this stroke
+				unerased_image = unerased_image.map(|unerased_image| {
+					let source = clone_source.clone().unwrap_or_else(|| unerased_image.clone());
+					clone_stamp(unerased_image, &source, &stroke_texture, offset)
+				});
+				let source = clone_source.clone().unwrap_or_else(|| actual_image.clone());
+				actual_image = clone_stamp(actual_image, &source, &stroke_texture, offset);
 			}
 		}
-
-		let blend_params = FnNode::new(|(a, b)| blend_colors(a, b, BlendMode::MultiplyAlpha, 1.));
-		actual_image = blend_image_closure(erase_restore_mask, actual_image, |a, b| blend_params.eval((a, b)));
 	}
 
 	let first_row = image_frame_table.instance_mut_iter().next().unwrap();
@@ -314,6 +355,65 @@ async fn brush(_: impl Ctx, mut image_frame_table: RasterDataTable<CPU>, strokes
 	image_frame_table
 }
 
+/// Removes as much of the color as the eraser covers, reducing its alpha along with its premultiplied color channels.
+fn erase(eraser: Color, color: Color) -> Color {
+	color.apply_opacity(1. - eraser.a().clamp(0., 1.))
+}
+
+/// Brings back the paint removed by erase strokes, by blending towards the image composited without erasing by as much as the restoring stroke covers.
+fn restore(mut image: Instance<Raster<CPU>>, unerased_image: &Instance<Raster<CPU>>, stroke_texture: Instance<Raster<CPU>>) -> Instance<Raster<CPU>> {
+	// Both images are extended to the same bounds, so their pixels correspond one to one
+	let blank_image = Instance {
+		instance: Raster::new_cpu(Image::new(image.instance.width, image.instance.height, Color::TRANSPARENT)),
+		transform: image.transform,
+		..Default::default()
+	};
+	let coverage = blend_image_closure(stroke_texture, blank_image, |restorer, _| restorer);
+
+	let pixels = image.instance.data_mut().data.iter_mut().zip(&unerased_image.instance.data).zip(&coverage.instance.data);
+	for ((color, unerased_color), coverage) in pixels {
+		*color = color.lerp(unerased_color, coverage.a().clamp(0., 1.));
+	}
+
+	image
+}
+
+/// Copies the pixels of the source found at the offset from each pixel of the image, by as much as the clone stroke covers.
+fn clone_stamp(mut image: Instance<Raster<CPU>>, source: &Instance<Raster<CPU>>, stroke_texture: &Instance<Raster<CPU>>, offset: DVec2) -> Instance<Raster<CPU>> {
+	let (width, height) = (image.instance.width, image.instance.height);
+	let blank_image = Instance {
+		instance: Raster::new_cpu(Image::new(width, height, Color::TRANSPARENT)),
+		transform: image.transform,
+		..Default::default()
+	};
+	let coverage = blend_image_closure(stroke_texture.clone(), blank_image, |cloner, _| cloner);
+
+	// Transforms a point from the image's pixel space into the source's pixel space, shifted by the offset
+	let image_size = DVec2::new(width as f64, height as f64);
+	let source_size = DVec2::new(source.instance.width as f64, source.instance.height as f64);
+	let image_to_source = DAffine2::from_scale(source_size) * source.transform.inverse() * DAffine2::from_translation(offset) * image.transform * DAffine2::from_scale(1. / image_size);
+
+	let pixels = image.instance.data_mut().data.iter_mut().zip(&coverage.instance.data).enumerate();
+	for (index, (color, coverage)) in pixels {
+		let coverage = coverage.a().clamp(0., 1.);
+		if coverage <= 0. {
+			continue;
+		}
+
+		// Sample the source pixel under the center of this pixel
+		let pixel_center = DVec2::new((index % width as usize) as f64, (index / width as usize) as f64) + 0.5;
+		let source_point = image_to_source.transform_point2(pixel_center).floor();
+		if source_point.x < 0. || source_point.y < 0. || source_point.x >= source_size.x || source_point.y >= source_size.y {
+			continue;
+		}
+		let source_color = source.instance.data[source_point.y as usize * source.instance.width as usize + source_point.x as usize];
+
+		*color = blend_colors(source_color.apply_opacity(coverage), *color, BlendMode::Normal, 1.);
+	}
+
+	image
+}
+
 pub fn blend_image_closure(foreground: Instance<Raster<CPU>>, mut background: Instance<Raster<CPU>>, map_fn: impl Fn(Color, Color) -> Color) -> Instance<Raster<CPU>> {
 	let foreground_size = DVec2::new(foreground.instance.width as f64, foreground.instance.height as f64);
 	let background_size = DVec2::new(background.instance.width as f64, background.instance.height as f64);
@@ -393,7 +493,7 @@ mod test {
 			(),
 			RasterDataTable::<CPU>::new(Raster::new_cpu(Image::<Color>::default())),
 			vec![BrushStroke {
-				trace: vec![crate::brush_stroke::BrushInputSample { position: DVec2::ZERO }],
+				trace: vec![crate::brush_stroke::BrushInputSample { position: DVec2::ZERO, pressure: 1. }],
 				style: BrushStyle {
 					color: Color::BLACK,
 					diameter: 20.,
@@ -401,11 +501,356 @@ mod test {
 					flow: 20.,
 					spacing: 20.,
 					blend_mode: BlendMode::Normal,
+					..Default::default()
 				},
 			}],
 			BrushCache::default(),
+			RasterDataTable::default(),
 		)
 		.await;
 		assert_eq!(image.instance_ref_iter().next().unwrap().instance.width, 20);
 	}
+
+	fn stroke(color: Color, diameter: f64, start: DVec2) -> BrushStroke {
+		BrushStroke {
+			trace: [start, start + DVec2::new(30., 10.)]
+				.into_iter()
+				.map(|position| crate::brush_stroke::BrushInputSample { position, pressure: 1. })
+				.collect(),
+			style: BrushStyle {
+				color,
+				diameter,
+				hardness: 50.,
+				flow: 100.,
+				spacing: 20.,
+				blend_mode: BlendMode::Normal,
+				..Default::default()
+			},
+		}
+	}
+
+	async fn render(strokes: &[BrushStroke], cache: BrushCache) -> Vec<Color> {
+		let image = brush(
+			(),
+			RasterDataTable::<CPU>::new(Raster::new_cpu(Image::<Color>::default())),
+			strokes.to_vec(),
+			cache,
+			RasterDataTable::default(),
+		)
+		.await;
+		image.instance_ref_iter().next().unwrap().instance.data.clone()
+	}
+
+	#[tokio::test]
+	async fn evicted_brush_textures_are_recomputed() {
+		let mut strokes = vec![
+			stroke(Color::BLACK, 10., DVec2::ZERO),
+			stroke(Color::RED, 14., DVec2::new(0., 20.)),
+			stroke(Color::BLUE, 18., DVec2::new(0., 40.)),
+		];
+
+		// A budget too small for anything forces every brush texture to be evicted, except for that of the active stroke
+		let cache = BrushCache::default();
+		cache.set_memory_budget(Some(1));
+
+		let rendered = render(&strokes, cache.shared_handle()).await;
+		assert_eq!(rendered, render(&strokes, BrushCache::default()).await);
+
+		let stats = cache.cache_stats();
+		assert_eq!(stats.entries, 1);
+		assert_eq!((stats.hits, stats.misses), (0, 3));
+		assert!(stats.bytes > stats.budget);
+
+		// Changing the first stroke redraws every stroke, recomputing the evicted brush textures and reusing the active one
+		strokes[0].trace[1].position += DVec2::new(5., 0.);
+		let rendered = render(&strokes, cache.shared_handle()).await;
+		assert_eq!(rendered, render(&strokes, BrushCache::default()).await);
+
+		let stats = cache.cache_stats();
+		assert_eq!(stats.entries, 1);
+		assert_eq!((stats.hits, stats.misses), (1, 5));
+	}
+
+	/// A hard-edged stroke through the given points, which paints black or erases or restores with the blend mode.
+	fn line_stroke(points: &[DVec2], blend_mode: BlendMode) -> BrushStroke {
+		BrushStroke {
+			trace: points.iter().map(|&position| crate::brush_stroke::BrushInputSample { position, pressure: 1. }).collect(),
+			style: BrushStyle {
+				color: Color::BLACK,
+				diameter: 20.,
+				hardness: 100.,
+				flow: 100.,
+				spacing: 20.,
+				blend_mode,
+				..Default::default()
+			},
+		}
+	}
+
+	/// The alpha of the rendered image at each of the given points in layer space.
+	async fn alpha_at(strokes: &[BrushStroke], cache: BrushCache, points: &[DVec2]) -> Vec<f32> {
+		let image = brush(
+			(),
+			RasterDataTable::<CPU>::new(Raster::new_cpu(Image::<Color>::default())),
+			strokes.to_vec(),
+			cache,
+			RasterDataTable::default(),
+		)
+		.await;
+		let image = image.instance_ref_iter().next().unwrap();
+		let size = DVec2::new(image.instance.width as f64, image.instance.height as f64);
+
+		points
+			.iter()
+			.map(|&point| {
+				let pixel = (image.transform.inverse().transform_point2(point) * size).floor().as_uvec2();
+				image.instance.data[(pixel.y * image.instance.width + pixel.x) as usize].a()
+			})
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn erasing_only_affects_earlier_strokes() {
+		let horizontal = |y| [DVec2::new(0., y), DVec2::new(60., y)];
+		let vertical = [DVec2::new(30., -30.), DVec2::new(30., 30.)];
+		let strokes = [
+			line_stroke(&horizontal(0.), BlendMode::Normal),
+			line_stroke(&vertical, BlendMode::Erase),
+			line_stroke(&horizontal(20.), BlendMode::Normal),
+		];
+
+		// Only painted by the first stroke, painted and then erased, erased and then painted, only painted by the last stroke, and only erased
+		let points = [DVec2::new(10., 0.), DVec2::new(30., 0.), DVec2::new(30., 20.), DVec2::new(10., 20.), DVec2::new(30., -20.)];
+		assert_eq!(alpha_at(&strokes, BrushCache::default(), &points).await, [1., 0., 1., 1., 0.]);
+	}
+
+	#[tokio::test]
+	async fn restoring_brings_back_erased_paint() {
+		let strokes = [
+			line_stroke(&[DVec2::new(0., 0.), DVec2::new(60., 0.)], BlendMode::Normal),
+			line_stroke(&[DVec2::new(10., 0.), DVec2::new(50., 0.)], BlendMode::Erase),
+			line_stroke(&[DVec2::new(30., -30.), DVec2::new(30., 30.)], BlendMode::Restore),
+		];
+
+		// Erased and left erased, erased and then restored, and restored where there was never any paint
+		let points = [DVec2::new(15., 0.), DVec2::new(30., 0.), DVec2::new(30., -20.)];
+		assert_eq!(alpha_at(&strokes, BrushCache::default(), &points).await, [0., 1., 0.]);
+	}
+
+	#[tokio::test]
+	async fn extending_erase_and_restore_strokes_matches_drawing_from_scratch() {
+		let mut strokes = vec![
+			line_stroke(&[DVec2::new(0., 0.), DVec2::new(60., 0.)], BlendMode::Normal),
+			line_stroke(&[DVec2::new(10., -20.), DVec2::new(10., 0.)], BlendMode::Erase),
+		];
+		let points: Vec<_> = (0..=12).flat_map(|x| (-4..=1).map(move |y| DVec2::new(x as f64 * 5., y as f64 * 5.))).collect();
+		let cache = BrushCache::default();
+
+		// Each frame of drawing the erase stroke, and then a restore stroke, adds a sample to the stroke being drawn
+		for x in [20., 30., 40.] {
+			strokes[1].trace.push(crate::brush_stroke::BrushInputSample {
+				position: DVec2::new(x, 0.),
+				pressure: 1.,
+			});
+			assert_eq!(alpha_at(&strokes, cache.shared_handle(), &points).await, alpha_at(&strokes, BrushCache::default(), &points).await);
+		}
+		strokes.push(line_stroke(&[DVec2::new(15., 10.)], BlendMode::Restore));
+		for x in [25., 35.] {
+			strokes[2].trace.push(crate::brush_stroke::BrushInputSample {
+				position: DVec2::new(x, 0.),
+				pressure: 1.,
+			});
+			assert_eq!(alpha_at(&strokes, cache.shared_handle(), &points).await, alpha_at(&strokes, BrushCache::default(), &points).await);
+		}
+	}
+
+	/// A 40x40 image covering the layer space from (0, 0) to (40, 40), with each pixel colored by its coordinates.
+	fn pattern_image(seed: f32) -> Instance<Raster<CPU>> {
+		let data = (0..40 * 40)
+			.map(|index| Color::from_rgbaf32_unchecked((index % 40) as f32 / 40., (index / 40) as f32 / 40., seed, 1.))
+			.collect();
+		Instance {
+			instance: Raster::new_cpu(Image {
+				width: 40,
+				height: 40,
+				data,
+				base64_string: None,
+			}),
+			transform: DAffine2::from_scale(DVec2::splat(40.)),
+			..Default::default()
+		}
+	}
+
+	/// A hard-edged clone stroke through the given points, which copies pixels from the given offset.
+	fn clone_stroke(points: &[DVec2], offset: DVec2) -> BrushStroke {
+		let mut stroke = line_stroke(points, BlendMode::Normal);
+		stroke.style.clone_offset = Some(offset);
+		stroke
+	}
+
+	/// The color of the rendered image at the given point in layer space.
+	fn color_at(image: &RasterDataTable<CPU>, point: DVec2) -> Color {
+		let image = image.instance_ref_iter().next().unwrap();
+		let size = DVec2::new(image.instance.width as f64, image.instance.height as f64);
+		let pixel = (image.transform.inverse().transform_point2(point) * size).floor().as_uvec2();
+		image.instance.data[(pixel.y * image.instance.width + pixel.x) as usize]
+	}
+
+	fn assert_colors_close(actual: Color, expected: Color, point: DVec2) {
+		let (actual, expected) = (actual.components(), expected.components());
+		let close = [actual.0 - expected.0, actual.1 - expected.1, actual.2 - expected.2, actual.3 - expected.3]
+			.iter()
+			.all(|difference| difference.abs() < 1e-5);
+		assert!(close, "{actual:?} should be close to {expected:?} at {point}");
+	}
+
+	/// Points within a few pixels of the given center, all well inside a clone stroke of diameter 20.
+	fn points_near(center: DVec2) -> Vec<DVec2> {
+		(-3..=3).flat_map(|x| (-3..=3).map(move |y| center + DVec2::new(x as f64, y as f64) + 0.5)).collect()
+	}
+
+	#[tokio::test]
+	async fn cloning_copies_pixels_from_the_offset() {
+		let background = pattern_image(0.);
+		let offset = DVec2::new(-15., -12.);
+		let strokes = vec![clone_stroke(&[DVec2::new(25., 25.)], offset)];
+
+		let image = brush((), background.clone().to_table(), strokes, BrushCache::default(), RasterDataTable::default()).await;
+
+		for point in points_near(DVec2::new(25., 25.)) {
+			assert_colors_close(color_at(&image, point), color_at(&background.clone().to_table(), point + offset), point);
+		}
+		// Outside of the stroke, the image is unchanged
+		let outside = DVec2::new(5.5, 35.5);
+		assert_eq!(color_at(&image, outside), color_at(&background.to_table(), outside));
+	}
+
+	#[tokio::test]
+	async fn cloning_copies_from_the_clone_source() {
+		let source = pattern_image(1.).to_table();
+		let offset = DVec2::new(10., 5.);
+		let strokes = vec![clone_stroke(&[DVec2::new(15., 15.), DVec2::new(20., 15.)], offset)];
+
+		let image = brush((), pattern_image(0.).to_table(), strokes, BrushCache::default(), source.clone()).await;
+
+		for point in points_near(DVec2::new(17., 15.)) {
+			assert_colors_close(color_at(&image, point), color_at(&source, point + offset), point);
+		}
+	}
+
+	#[tokio::test]
+	async fn changing_the_clone_source_redraws_clone_strokes() {
+		// The clone stroke is followed by another stroke, so the cache blends it into the image drawn before the stroke being drawn
+		let strokes = vec![
+			clone_stroke(&[DVec2::new(15., 15.)], DVec2::new(10., 10.)),
+			line_stroke(&[DVec2::new(0., 35.), DVec2::new(40., 35.)], BlendMode::Normal),
+		];
+		let cache = BrushCache::default();
+
+		for seed in [0.25, 0.5, 0.75] {
+			let source = pattern_image(seed).to_table();
+			let cached = brush((), pattern_image(0.).to_table(), strokes.clone(), cache.shared_handle(), source.clone()).await;
+			let from_scratch = brush((), pattern_image(0.).to_table(), strokes.clone(), BrushCache::default(), source.clone()).await;
+
+			let pixels = |image: &RasterDataTable<CPU>| image.instance_ref_iter().next().unwrap().instance.data.clone();
+			assert_eq!(pixels(&cached), pixels(&from_scratch));
+			assert_colors_close(color_at(&cached, DVec2::new(15.5, 15.5)), color_at(&source, DVec2::new(25.5, 25.5)), DVec2::new(15.5, 15.5));
+		}
+	}
+
+	/// A stroke with a tip shaped like a bar, five times as long as it is wide, which turns to follow the stroke.
+	fn bar_stroke(points: &[DVec2], spacing: f64) -> BrushStroke {
+		BrushStroke {
+			trace: points.iter().map(|&position| crate::brush_stroke::BrushInputSample { position, pressure: 1. }).collect(),
+			style: BrushStyle {
+				color: Color::BLACK,
+				diameter: 5.,
+				flow: 100.,
+				spacing,
+				tip: BrushTip::Image(crate::brush_stroke::BrushTipImage {
+					name: "Bar".to_string(),
+					width: 5,
+					height: 1,
+					coverage: vec![255; 5],
+				}),
+				dynamics: crate::brush_stroke::BrushDynamics {
+					rotation: crate::brush_stroke::BrushRotation::FollowDirection,
+					..Default::default()
+				},
+				..Default::default()
+			},
+		}
+	}
+
+	/// Stamps the stroke onto a 9x9 image whose pixel centers lie on the integer coordinates from -4 to 4, returning which pixels are painted.
+	async fn stamp_golden_image(stroke: &BrushStroke) -> Vec<String> {
+		let target = Instance {
+			instance: Raster::new_cpu(Image::new(9, 9, Color::TRANSPARENT)),
+			transform: DAffine2::from_scale_angle_translation(DVec2::splat(9.), 0., DVec2::splat(-4.5)),
+			..Default::default()
+		};
+		let texture = create_brush_texture(&stroke.style).await;
+		let stamped = stamp_dabs(target, &texture, &stroke.compute_dabs(), |a, b| blend_colors(a, b, BlendMode::Normal, 1.));
+
+		stamped
+			.instance
+			.data
+			.chunks(9)
+			.map(|row| row.iter().map(|pixel| if pixel.a() > 0.5 { '#' } else { '.' }).collect())
+			.collect()
+	}
+
+	#[tokio::test]
+	async fn straight_stroke_matches_golden_image() {
+		// Dabs land every three pixels along the diagonal, each turned 45° so the bars join into one diagonal line
+		let stroke = bar_stroke(&[DVec2::splat(-3.), DVec2::splat(3.5)], 3. * std::f64::consts::SQRT_2 / 5. * 100.);
+
+		let dabs = stroke.compute_dabs();
+		assert_eq!(dabs.len(), 3);
+		for (dab, expected) in dabs.iter().zip([-3., 0., 3.]) {
+			assert!(dab.position.abs_diff_eq(DVec2::splat(expected), 1e-9), "{dab:?}");
+			assert!((dab.rotation - std::f64::consts::FRAC_PI_4).abs() < 1e-9, "{dab:?}");
+		}
+
+		let expected = ["#........", ".#.......", "..#......", "...#.....", "....#....", ".....#...", "......#..", ".......#.", "........#"];
+		assert_eq!(stamp_golden_image(&stroke).await, expected);
+	}
+
+	#[tokio::test]
+	async fn curved_stroke_matches_golden_image() {
+		// The stroke heads right along the top and then turns downwards, so its dabs turn from horizontal to vertical bars
+		let stroke = bar_stroke(&[DVec2::new(-3., -3.), DVec2::new(3., -3.), DVec2::new(3., 3.)], 60.);
+
+		let dabs = stroke.compute_dabs();
+		let positions: Vec<_> = dabs.iter().map(|dab| dab.position).collect();
+		assert_eq!(positions, [(-3., -3.), (0., -3.), (3., -3.), (3., 0.), (3., 3.)].map(DVec2::from));
+		let rotations: Vec<_> = dabs.iter().map(|dab| dab.rotation).collect();
+		assert_eq!(rotations, [0., 0., 0., std::f64::consts::FRAC_PI_2, std::f64::consts::FRAC_PI_2]);
+
+		let expected = [".........", "#########", ".......#.", ".......#.", ".......#.", ".......#.", ".......#.", ".......#.", ".......#."];
+		assert_eq!(stamp_golden_image(&stroke).await, expected);
+	}
+
+	#[test]
+	fn dab_dynamics_follow_pressure_and_jitter() {
+		let mut stroke = bar_stroke(&[DVec2::ZERO, DVec2::new(10., 0.)], 50.);
+		stroke.trace[0].pressure = 0.;
+		stroke.style.dynamics.pressure_size = true;
+		stroke.style.dynamics.pressure_opacity = true;
+		stroke.style.dynamics.angle_jitter = 10.;
+
+		let dabs = stroke.compute_dabs();
+		assert_eq!(dabs.len(), 5);
+
+		// Pressure ramps up along the stroke, growing and strengthening each dab
+		for (dab, pressure) in dabs.iter().zip([0., 0.25, 0.5, 0.75, 1.]) {
+			assert!((dab.diameter - 5. * pressure).abs() < 1e-9, "{dab:?}");
+			assert!((dab.opacity - pressure).abs() < 1e-9, "{dab:?}");
+			assert!(dab.rotation.abs() <= 10_f64.to_radians(), "{dab:?}");
+		}
+
+		// Jitter is random, but the same each time the stroke is evaluated
+		assert!(dabs.windows(2).any(|pair| pair[0].rotation != pair[1].rotation));
+		assert_eq!(dabs, stroke.compute_dabs());
+	}
 }
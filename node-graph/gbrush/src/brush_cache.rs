@@ -1,6 +1,8 @@
 use crate::brush_stroke::BrushStroke;
+use crate::brush_stroke::BrushStrokeMode;
 use crate::brush_stroke::BrushStyle;
 use dyn_any::DynAny;
+use graphene_core::color::Color;
 use graphene_core::instances::Instance;
 use graphene_core::raster_types::CPU;
 use graphene_core::raster_types::Raster;
@@ -8,11 +10,81 @@ use std::collections::HashMap;
 use std::hash::Hash;
 use std::hash::Hasher;
 use std::sync::atomic::{AtomicU64, Ordering};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, Mutex, OnceLock};
 
 // TODO: This is a temporary hack, be sure to not reuse this when the brush is being rewritten.
 static NEXT_BRUSH_CACHE_IMPL_ID: AtomicU64 = AtomicU64::new(0);
 
+/// The memory budget set by the user's preferences, in bytes, or zero to use the default budget.
+static MEMORY_BUDGET: AtomicU64 = AtomicU64::new(0);
+
+/// The fraction of the available memory which brush caches may use by default.
+const DEFAULT_MEMORY_BUDGET_FRACTION: f64 = 0.25;
+
+/// The assumed amount of available memory when it can't be determined from the system.
+const FALLBACK_AVAILABLE_MEMORY: u64 = 4 * 1024 * 1024 * 1024;
+
+/// Sets the memory budget shared by all brush caches, in bytes. `None` restores the default budget based on the available memory.
+pub fn set_memory_budget(budget: Option<u64>) {
+	MEMORY_BUDGET.store(budget.unwrap_or_default(), Ordering::Relaxed);
+}
+
+/// The memory budget shared by all brush caches, in bytes.
+pub fn memory_budget() -> u64 {
+	match MEMORY_BUDGET.load(Ordering::Relaxed) {
+		0 => default_memory_budget(),
+		budget => budget,
+	}
+}
+
+fn default_memory_budget() -> u64 {
+	static DEFAULT_MEMORY_BUDGET: OnceLock<u64> = OnceLock::new();
+	*DEFAULT_MEMORY_BUDGET.get_or_init(|| (available_memory() as f64 * DEFAULT_MEMORY_BUDGET_FRACTION) as u64)
+}
+
+// Wasm32 can address at most 4 GiB, which is also the best guess available from within the browser
+#[cfg(target_arch = "wasm32")]
+fn available_memory() -> u64 {
+	FALLBACK_AVAILABLE_MEMORY
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn available_memory() -> u64 {
+	let from_meminfo = std::fs::read_to_string("/proc/meminfo").ok().and_then(|meminfo| {
+		let line = meminfo.lines().find(|line| line.starts_with("MemAvailable:"))?;
+		let kibibytes = line.split_whitespace().nth(1)?.parse::<u64>().ok()?;
+		Some(kibibytes * 1024)
+	});
+	from_meminfo.unwrap_or(FALLBACK_AVAILABLE_MEMORY)
+}
+
+/// The estimated memory used by a cached image, based on its dimensions.
+fn estimated_size(image: &Raster<CPU>) -> u64 {
+	image.width as u64 * image.height as u64 * size_of::<Color>() as u64
+}
+
+/// Statistics about the contents and effectiveness of a [`BrushCache`], such as for display in a debug panel.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BrushCacheStats {
+	/// The number of cached brush textures.
+	pub entries: usize,
+	/// The estimated memory used by the cached brush textures and blended intermediate images, in bytes.
+	pub bytes: u64,
+	/// The memory budget the cache evicts brush textures to stay within, in bytes.
+	pub budget: u64,
+	/// The number of brush texture lookups which were found in the cache.
+	pub hits: u64,
+	/// The number of brush texture lookups which had to be recomputed.
+	pub misses: u64,
+}
+
+#[derive(Clone, Debug)]
+struct CachedBrushTexture {
+	texture: Raster<CPU>,
+	// The value of the cache's use counter when this texture was last stored or looked up, used to find the least recently used texture.
+	last_used: u64,
+}
+
 #[derive(Clone, Debug, DynAny, serde::Serialize, serde::Deserialize)]
 struct BrushCacheImpl {
 	unique_id: u64,
@@ -24,40 +96,69 @@ struct BrushCacheImpl {
 	background: Instance<Raster<CPU>>,
 	#[serde(deserialize_with = "graphene_core::raster::image::migrate_image_frame_instance")]
 	blended_image: Instance<Raster<CPU>>,
+	// The blended image without any erase strokes applied, which is only kept while there are restore strokes that need it.
+	#[serde(default)]
+	unerased_image: Option<Instance<Raster<CPU>>>,
 	#[serde(deserialize_with = "graphene_core::raster::image::migrate_image_frame_instance")]
 	last_stroke_texture: Instance<Raster<CPU>>,
+	// The image which clone strokes copy from, if it's provided instead of the image being painted.
+	#[serde(skip)]
+	clone_source: Option<Instance<Raster<CPU>>>,
 
 	// A cache for brush textures.
 	#[serde(skip)]
-	brush_texture_cache: HashMap<BrushStyle, Raster<CPU>>,
+	brush_texture_cache: HashMap<BrushStyle, CachedBrushTexture>,
+	// The style of the stroke currently being drawn, whose brush texture is never evicted.
+	#[serde(skip)]
+	active_style: Option<BrushStyle>,
+	// Overrides the shared memory budget for this cache, in bytes.
+	#[serde(skip)]
+	memory_budget: Option<u64>,
+	#[serde(skip)]
+	use_counter: u64,
+	#[serde(skip)]
+	hits: u64,
+	#[serde(skip)]
+	misses: u64,
 }
 
 impl BrushCacheImpl {
-	fn compute_brush_plan(&mut self, mut background: Instance<Raster<CPU>>, input: &[BrushStroke]) -> BrushPlan {
+	fn compute_brush_plan(&mut self, mut background: Instance<Raster<CPU>>, clone_source: Option<&Instance<Raster<CPU>>>, input: &[BrushStroke]) -> BrushPlan {
+		self.active_style = input.last().map(|stroke| stroke.style.clone());
+		let needs_unerased_image = input.iter().any(|stroke| stroke.style.mode() == BrushStrokeMode::Restore);
+
+		// Clone strokes copy from the clone source when it's evaluated, so they need redrawing whenever it changes
+		let clone_source_changed = clone_source != self.clone_source.as_ref();
+		if clone_source_changed {
+			self.clone_source = clone_source.cloned();
+		}
+		let uses_clone_source = input.iter().any(|stroke| matches!(stroke.style.mode(), BrushStrokeMode::Clone(_)));
+
 		// Do background invalidation.
 		if background != self.background {
 			self.background = background.clone();
-			return BrushPlan {
-				strokes: input.to_vec(),
-				background,
-				..Default::default()
-			};
+			return BrushPlan::redraw_all(input, background, needs_unerased_image);
+		}
+		if clone_source_changed && uses_clone_source {
+			return BrushPlan::redraw_all(input, background, needs_unerased_image);
 		}
 
 		// Do blended_image invalidation.
 		let blended_strokes = &self.prev_input[..self.prev_input.len().saturating_sub(1)];
 		let num_blended_strokes = blended_strokes.len();
 		if input.get(..num_blended_strokes) != Some(blended_strokes) {
-			return BrushPlan {
-				strokes: input.to_vec(),
-				background,
-				..Default::default()
-			};
+			return BrushPlan::redraw_all(input, background, needs_unerased_image);
+		}
+
+		// The first restore stroke needs the unerased image, which isn't kept until then
+		if needs_unerased_image && self.unerased_image.is_none() {
+			return BrushPlan::redraw_all(input, background, needs_unerased_image);
 		}
 
 		// Take our previous blended image (and invalidate the cache).
 		// Since we're about to replace our cache anyway, this saves a clone.
 		background = std::mem::take(&mut self.blended_image);
+		let unerased_background = self.unerased_image.take().filter(|_| needs_unerased_image);
 
 		// Check if the first non-blended stroke is an extension of the last one.
 		let mut first_stroke_texture = Instance {
@@ -70,12 +171,13 @@ impl BrushCacheImpl {
 		if !strokes.is_empty() && self.prev_input.len() > num_blended_strokes {
 			let last_stroke = &self.prev_input[num_blended_strokes];
 			let same_style = strokes[0].style == last_stroke.style;
-			let prev_points = last_stroke.compute_blit_points();
-			let new_points = strokes[0].compute_blit_points();
-			let is_point_prefix = new_points.get(..prev_points.len()) == Some(&prev_points);
-			if same_style && is_point_prefix {
+			// Dabs are compared rather than just their positions, since the rotation of a dab can change as the stroke grows
+			let prev_dabs = last_stroke.compute_dabs();
+			let new_dabs = strokes[0].compute_dabs();
+			let is_dab_prefix = new_dabs.get(..prev_dabs.len()) == Some(&prev_dabs);
+			if same_style && is_dab_prefix {
 				first_stroke_texture = std::mem::take(&mut self.last_stroke_texture);
-				first_stroke_point_skip = prev_points.len();
+				first_stroke_point_skip = prev_dabs.len();
 			}
 		}
 
@@ -83,15 +185,81 @@ impl BrushCacheImpl {
 		BrushPlan {
 			strokes,
 			background,
+			unerased_background,
 			first_stroke_texture,
 			first_stroke_point_skip,
 		}
 	}
 
-	pub fn cache_results(&mut self, input: Vec<BrushStroke>, blended_image: Instance<Raster<CPU>>, last_stroke_texture: Instance<Raster<CPU>>) {
+	pub fn cache_results(&mut self, input: Vec<BrushStroke>, blended_image: Instance<Raster<CPU>>, unerased_image: Option<Instance<Raster<CPU>>>, last_stroke_texture: Instance<Raster<CPU>>) {
 		self.prev_input = input;
 		self.blended_image = blended_image;
+		self.unerased_image = unerased_image;
 		self.last_stroke_texture = last_stroke_texture;
+		self.evict_over_budget();
+	}
+
+	fn get_cached_brush(&mut self, style: &BrushStyle) -> Option<Raster<CPU>> {
+		self.use_counter += 1;
+
+		let Some(cached) = self.brush_texture_cache.get_mut(style) else {
+			self.misses += 1;
+			return None;
+		};
+		self.hits += 1;
+		cached.last_used = self.use_counter;
+
+		Some(cached.texture.clone())
+	}
+
+	fn store_brush(&mut self, style: BrushStyle, texture: Raster<CPU>) {
+		self.use_counter += 1;
+
+		let last_used = self.use_counter;
+		self.brush_texture_cache.insert(style, CachedBrushTexture { texture, last_used });
+		self.evict_over_budget();
+	}
+
+	fn memory_budget(&self) -> u64 {
+		self.memory_budget.unwrap_or_else(memory_budget)
+	}
+
+	fn estimated_bytes(&self) -> u64 {
+		let textures = self.brush_texture_cache.values().map(|cached| estimated_size(&cached.texture));
+		let intermediates = [&self.background, &self.blended_image, &self.last_stroke_texture].map(|image| estimated_size(&image.instance));
+		let optional_images = self.unerased_image.iter().chain(&self.clone_source).map(|image| estimated_size(&image.instance));
+		textures.chain(intermediates).chain(optional_images).sum()
+	}
+
+	/// Evicts the least recently used brush textures until the cache fits within its memory budget.
+	/// The blended intermediate images and the brush texture of the active stroke are needed to continue that stroke, so they are never evicted.
+	fn evict_over_budget(&mut self) {
+		let budget = self.memory_budget();
+		let mut bytes = self.estimated_bytes();
+
+		while bytes > budget {
+			let least_recently_used = self
+				.brush_texture_cache
+				.iter()
+				.filter(|(style, _)| Some(*style) != self.active_style.as_ref())
+				.min_by_key(|(_, cached)| cached.last_used)
+				.map(|(style, _)| style.clone());
+			let Some(style) = least_recently_used else { break };
+
+			if let Some(evicted) = self.brush_texture_cache.remove(&style) {
+				bytes -= estimated_size(&evicted.texture);
+			}
+		}
+	}
+
+	fn stats(&self) -> BrushCacheStats {
+		BrushCacheStats {
+			entries: self.brush_texture_cache.len(),
+			bytes: self.estimated_bytes(),
+			budget: self.memory_budget(),
+			hits: self.hits,
+			misses: self.misses,
+		}
 	}
 }
 
@@ -102,8 +270,15 @@ impl Default for BrushCacheImpl {
 			prev_input: Vec::new(),
 			background: Default::default(),
 			blended_image: Default::default(),
+			unerased_image: None,
 			last_stroke_texture: Default::default(),
+			clone_source: None,
 			brush_texture_cache: HashMap::new(),
+			active_style: None,
+			memory_budget: None,
+			use_counter: 0,
+			hits: 0,
+			misses: 0,
 		}
 	}
 }
@@ -124,10 +299,24 @@ impl Hash for BrushCacheImpl {
 pub struct BrushPlan {
 	pub strokes: Vec<BrushStroke>,
 	pub background: Instance<Raster<CPU>>,
+	/// The background without any erase strokes applied, present only when there are restore strokes to draw.
+	pub unerased_background: Option<Instance<Raster<CPU>>>,
 	pub first_stroke_texture: Instance<Raster<CPU>>,
 	pub first_stroke_point_skip: usize,
 }
 
+impl BrushPlan {
+	/// A plan which draws every stroke from scratch onto the background.
+	fn redraw_all(input: &[BrushStroke], background: Instance<Raster<CPU>>, needs_unerased_image: bool) -> Self {
+		Self {
+			strokes: input.to_vec(),
+			unerased_background: needs_unerased_image.then(|| background.clone()),
+			background,
+			..Default::default()
+		}
+	}
+}
+
 #[derive(Debug, Default, DynAny, serde::Serialize, serde::Deserialize)]
 pub struct BrushCache(Arc<Mutex<BrushCacheImpl>>);
 
@@ -160,23 +349,41 @@ impl Hash for BrushCache {
 }
 
 impl BrushCache {
-	pub fn compute_brush_plan(&self, background: Instance<Raster<CPU>>, input: &[BrushStroke]) -> BrushPlan {
+	pub fn compute_brush_plan(&self, background: Instance<Raster<CPU>>, clone_source: Option<&Instance<Raster<CPU>>>, input: &[BrushStroke]) -> BrushPlan {
 		let mut inner = self.0.lock().unwrap();
-		inner.compute_brush_plan(background, input)
+		inner.compute_brush_plan(background, clone_source, input)
 	}
 
-	pub fn cache_results(&self, input: Vec<BrushStroke>, blended_image: Instance<Raster<CPU>>, last_stroke_texture: Instance<Raster<CPU>>) {
+	pub fn cache_results(&self, input: Vec<BrushStroke>, blended_image: Instance<Raster<CPU>>, unerased_image: Option<Instance<Raster<CPU>>>, last_stroke_texture: Instance<Raster<CPU>>) {
 		let mut inner = self.0.lock().unwrap();
-		inner.cache_results(input, blended_image, last_stroke_texture)
+		inner.cache_results(input, blended_image, unerased_image, last_stroke_texture)
 	}
 
 	pub fn get_cached_brush(&self, style: &BrushStyle) -> Option<Raster<CPU>> {
-		let inner = self.0.lock().unwrap();
-		inner.brush_texture_cache.get(style).cloned()
+		let mut inner = self.0.lock().unwrap();
+		inner.get_cached_brush(style)
 	}
 
 	pub fn store_brush(&self, style: BrushStyle, brush: Raster<CPU>) {
 		let mut inner = self.0.lock().unwrap();
-		inner.brush_texture_cache.insert(style, brush);
+		inner.store_brush(style, brush);
+	}
+
+	/// Overrides the shared memory budget for this cache, in bytes.
+	pub fn set_memory_budget(&self, budget: Option<u64>) {
+		let mut inner = self.0.lock().unwrap();
+		inner.memory_budget = budget;
+		inner.evict_over_budget();
+	}
+
+	pub fn cache_stats(&self) -> BrushCacheStats {
+		let inner = self.0.lock().unwrap();
+		inner.stats()
+	}
+
+	/// A handle to the same underlying cache, unlike `clone()` which creates an independent copy.
+	#[cfg(test)]
+	pub(crate) fn shared_handle(&self) -> Self {
+		Self(self.0.clone())
 	}
 }
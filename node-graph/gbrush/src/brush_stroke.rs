@@ -3,8 +3,154 @@ use glam::DVec2;
 use graphene_core::blending::BlendMode;
 use graphene_core::color::Color;
 use graphene_core::math::bbox::AxisAlignedBbox;
+use std::f64::consts::SQRT_2;
 use std::hash::{Hash, Hasher};
 
+/// The size of the square canvas the built-in brush tip images are generated at.
+const PRESET_TIP_SIZE: u32 = 64;
+
+/// The shape stamped along a brush stroke.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash, DynAny, serde::Serialize, serde::Deserialize)]
+pub enum BrushTip {
+	/// A soft round stamp whose falloff is set by the hardness.
+	#[default]
+	Round,
+	/// A grayscale stamp image.
+	Image(BrushTipImage),
+}
+
+impl BrushTip {
+	pub fn name(&self) -> &str {
+		match self {
+			BrushTip::Round => "Round",
+			BrushTip::Image(image) => &image.name,
+		}
+	}
+}
+
+/// A grayscale stamp image for a brush tip, storing how much paint each pixel deposits from 0 (none) to 255 (full).
+#[derive(Clone, Debug, PartialEq, Eq, Hash, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct BrushTipImage {
+	pub name: String,
+	pub width: u32,
+	pub height: u32,
+	pub coverage: Vec<u8>,
+}
+
+impl BrushTipImage {
+	/// Creates a tip from RGBA8 image data. Like a grayscale stamp, dark opaque pixels deposit paint while light or transparent pixels don't.
+	pub fn from_rgba(name: String, data: &[u8], width: u32, height: u32) -> Self {
+		let coverage = data
+			.chunks_exact(4)
+			.map(|pixel| {
+				let [r, g, b, a] = [pixel[0], pixel[1], pixel[2], pixel[3]].map(|channel| channel as f32 / 255.);
+				let luminance = 0.2126 * r + 0.7152 * g + 0.0722 * b;
+				((1. - luminance) * a * 255.).round() as u8
+			})
+			.collect();
+
+		Self { name, width, height, coverage }
+	}
+
+	/// The built-in brush tips, generated procedurally.
+	pub fn presets() -> Vec<Self> {
+		let size = PRESET_TIP_SIZE;
+		let center = DVec2::splat(size as f64 / 2.);
+		let generate = |name: &str, coverage: &dyn Fn(DVec2, u32, u32) -> f64| Self {
+			name: name.to_string(),
+			width: size,
+			height: size,
+			coverage: (0..size * size)
+				.map(|index| {
+					let (x, y) = (index % size, index / size);
+					// Coordinates from -1 to 1 across the tip, sampled at pixel centers
+					let position = (DVec2::new(x as f64, y as f64) + 0.5 - center) / center;
+					(coverage(position, x, y).clamp(0., 1.) * 255.).round() as u8
+				})
+				.collect(),
+		};
+
+		vec![
+			generate("Square", &|_, _, _| 1.),
+			generate("Chalk", &|position, x, y| {
+				let grain = random_unit(x as u64 * PRESET_TIP_SIZE as u64 + y as u64, 0) * 0.5 + 0.5;
+				if position.length() < 1. && grain > 0.35 { grain } else { 0. }
+			}),
+			generate("Flat", &|position, _, _| {
+				let distance = (position * DVec2::new(1., 4.)).length();
+				(1. - distance) * 4.
+			}),
+		]
+	}
+
+	/// The size of the image scaled so its longest side has a length of 1.
+	pub fn aspect(&self) -> DVec2 {
+		let size = DVec2::new(self.width as f64, self.height as f64);
+		size / size.max_element().max(1.)
+	}
+}
+
+/// Which way each dab of a stroke is turned.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, DynAny, serde::Serialize, serde::Deserialize, specta::Type)]
+pub enum BrushRotation {
+	/// Every dab is turned by the same angle.
+	#[default]
+	Fixed,
+	/// Each dab is turned to face the direction the stroke travels, offset by the angle.
+	FollowDirection,
+}
+
+/// How the dabs stamped along a stroke vary.
+#[derive(Clone, Debug, Default, PartialEq, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct BrushDynamics {
+	pub rotation: BrushRotation,
+	/// The angle of each dab in degrees.
+	pub angle: f64,
+	/// The largest random deviation from the angle of each dab, in degrees.
+	pub angle_jitter: f64,
+	/// Whether lighter pen pressure makes dabs smaller.
+	pub pressure_size: bool,
+	/// Whether lighter pen pressure makes dabs more transparent.
+	pub pressure_opacity: bool,
+}
+
+impl Hash for BrushDynamics {
+	fn hash<H: Hasher>(&self, state: &mut H) {
+		self.rotation.hash(state);
+		self.angle.to_bits().hash(state);
+		self.angle_jitter.to_bits().hash(state);
+		self.pressure_size.hash(state);
+		self.pressure_opacity.hash(state);
+	}
+}
+
+impl Eq for BrushDynamics {}
+
+/// A single stamp of the brush tip placed along a stroke.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BrushDab {
+	pub position: DVec2,
+	/// The rotation of the stamp in radians.
+	pub rotation: f64,
+	/// The size of the longest side of the stamp, in pixels.
+	pub diameter: f64,
+	/// The opacity of the stamp from 0 to 1, applied on top of the flow.
+	pub opacity: f64,
+}
+
+/// How a stroke is composited onto the strokes before it, as determined by the blend mode and clone offset of its style.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BrushStrokeMode {
+	/// Adds paint, blended with the given blend mode.
+	Paint(BlendMode),
+	/// Removes the paint of earlier strokes, by as much as the stroke covers.
+	Erase,
+	/// Brings back the paint which earlier erase strokes removed, by as much as the stroke covers.
+	Restore,
+	/// Copies the pixels found at the given offset from each point, by as much as the stroke covers.
+	Clone(DVec2),
+}
+
 /// The style of a brush.
 #[derive(Clone, Debug, DynAny, serde::Serialize, serde::Deserialize)]
 pub struct BrushStyle {
@@ -14,6 +160,39 @@ pub struct BrushStyle {
 	pub flow: f64,
 	pub spacing: f64, // Spacing as a fraction of the diameter.
 	pub blend_mode: BlendMode,
+	#[serde(default)]
+	pub tip: BrushTip,
+	#[serde(default)]
+	pub dynamics: BrushDynamics,
+	/// The offset in layer space from each point of a clone stroke to the point it copies pixels from. Strokes without one paint with the color.
+	#[serde(default)]
+	pub clone_offset: Option<DVec2>,
+}
+
+impl BrushStyle {
+	/// Whether the stroke paints, erases, restores, or clones. Strokes with a clone offset clone, the utility blend modes for erasing and restoring select those modes, and any other blend mode paints.
+	pub fn mode(&self) -> BrushStrokeMode {
+		if let Some(offset) = self.clone_offset {
+			return BrushStrokeMode::Clone(offset);
+		}
+
+		match self.blend_mode {
+			BlendMode::Erase => BrushStrokeMode::Erase,
+			BlendMode::Restore => BrushStrokeMode::Restore,
+			blend_mode => BrushStrokeMode::Paint(blend_mode),
+		}
+	}
+
+	/// The color the brush texture is stamped with. Clone strokes take their colors from the source and only use the coverage of the texture, so they stamp opaque white.
+	pub fn stamp_color(&self) -> Color {
+		if self.clone_offset.is_some() { Color::WHITE } else { self.color }
+	}
+
+	/// Whether every dab of a stroke is identical, so a single texture can be blitted for all of them.
+	/// Round tips look the same at any rotation, so only pressure dynamics set their dabs apart.
+	pub fn has_uniform_dabs(&self) -> bool {
+		self.tip == BrushTip::Round && !self.dynamics.pressure_size && !self.dynamics.pressure_opacity
+	}
 }
 
 impl Default for BrushStyle {
@@ -25,6 +204,9 @@ impl Default for BrushStyle {
 			flow: 100.,
 			spacing: 50., // Percentage of diameter.
 			blend_mode: BlendMode::Normal,
+			tip: BrushTip::Round,
+			dynamics: BrushDynamics::default(),
+			clone_offset: None,
 		}
 	}
 }
@@ -37,6 +219,9 @@ impl Hash for BrushStyle {
 		self.flow.to_bits().hash(state);
 		self.spacing.to_bits().hash(state);
 		self.blend_mode.hash(state);
+		self.tip.hash(state);
+		self.dynamics.hash(state);
+		self.clone_offset.map(|offset| [offset.x.to_bits(), offset.y.to_bits()]).hash(state);
 	}
 }
 
@@ -50,6 +235,9 @@ impl PartialEq for BrushStyle {
 			&& self.flow.to_bits() == other.flow.to_bits()
 			&& self.spacing.to_bits() == other.spacing.to_bits()
 			&& self.blend_mode == other.blend_mode
+			&& self.tip == other.tip
+			&& self.dynamics == other.dynamics
+			&& self.clone_offset.map(|offset| [offset.x.to_bits(), offset.y.to_bits()]) == other.clone_offset.map(|offset| [offset.x.to_bits(), offset.y.to_bits()])
 	}
 }
 
@@ -59,13 +247,21 @@ pub struct BrushInputSample {
 	// The position of the sample in layer space, in pixels.
 	// The origin of layer space is not specified.
 	pub position: DVec2,
-	// Future work: pressure, stylus angle, etc.
+	// The pen pressure of the sample, from 0 to 1. Pointers without pressure sensitivity report full pressure.
+	#[serde(default = "full_pressure")]
+	pub pressure: f64,
+	// Future work: stylus angle, etc.
+}
+
+fn full_pressure() -> f64 {
+	1.
 }
 
 impl Hash for BrushInputSample {
 	fn hash<H: Hasher>(&self, state: &mut H) {
 		self.position.x.to_bits().hash(state);
 		self.position.y.to_bits().hash(state);
+		self.pressure.to_bits().hash(state);
 	}
 }
 
@@ -78,7 +274,11 @@ pub struct BrushStroke {
 
 impl BrushStroke {
 	pub fn bounding_box(&self) -> AxisAlignedBbox {
-		let radius = self.style.diameter / 2.;
+		// Image tips can be rotated, so their corners may reach past the diameter
+		let radius = match self.style.tip {
+			BrushTip::Round => self.style.diameter / 2.,
+			BrushTip::Image(_) => self.style.diameter / 2. * SQRT_2,
+		};
 		self.compute_blit_points()
 			.iter()
 			.map(|pos| AxisAlignedBbox {
@@ -90,6 +290,10 @@ impl BrushStroke {
 	}
 
 	pub fn compute_blit_points(&self) -> Vec<DVec2> {
+		self.compute_dabs().into_iter().map(|dab| dab.position).collect()
+	}
+
+	pub fn compute_dabs(&self) -> Vec<BrushDab> {
 		// We always travel in a straight line towards the next user input,
 		// placing a blit point every time we travelled our spacing distance.
 		let spacing_dist = self.style.spacing / 100. * self.style.diameter;
@@ -98,13 +302,22 @@ impl BrushStroke {
 			return Vec::new();
 		};
 
+		// The first dab faces towards the first sample the stroke travels to
+		let first_direction = self.trace[1..]
+			.iter()
+			.map(|sample| sample.position - first_sample.position)
+			.find(|delta| delta.length_squared() > 0.)
+			.map_or(DVec2::X, DVec2::normalize);
+
 		let mut cur_pos = first_sample.position;
-		let mut result = vec![cur_pos];
+		let mut result = vec![self.dab(0, cur_pos, first_direction, first_sample.pressure)];
 		let mut dist_until_next_blit = spacing_dist;
+		let mut prev_sample = first_sample;
 		for sample in &self.trace[1..] {
 			// Travel to the next sample.
 			let delta = sample.position - cur_pos;
-			let mut dist_left = delta.length();
+			let segment_length = delta.length();
+			let mut dist_left = segment_length;
 			let unit_step = delta / dist_left;
 
 			while dist_left >= dist_until_next_blit {
@@ -112,16 +325,49 @@ impl BrushStroke {
 				cur_pos += dist_until_next_blit * unit_step;
 				dist_left -= dist_until_next_blit;
 
-				// Blit.
-				result.push(cur_pos);
+				// Blit, with the pressure interpolated between the samples.
+				let progress = 1. - dist_left / segment_length;
+				let pressure = prev_sample.pressure + (sample.pressure - prev_sample.pressure) * progress;
+				result.push(self.dab(result.len(), cur_pos, unit_step, pressure));
 				dist_until_next_blit = spacing_dist;
 			}
 
 			// Take the partial step to land at the sample.
 			dist_until_next_blit -= dist_left;
 			cur_pos = sample.position;
+			prev_sample = sample;
 		}
 
 		result
 	}
+
+	fn dab(&self, index: usize, position: DVec2, direction: DVec2, pressure: f64) -> BrushDab {
+		let dynamics = &self.style.dynamics;
+		let pressure = pressure.clamp(0., 1.);
+
+		let direction_angle = match dynamics.rotation {
+			BrushRotation::Fixed => 0.,
+			BrushRotation::FollowDirection => direction.to_angle(),
+		};
+		// The jitter is seeded by where the stroke starts, so each dab keeps its angle as the stroke grows
+		let seed = self.trace.first().map_or(0, |sample| sample.position.x.to_bits() ^ sample.position.y.to_bits().rotate_left(32));
+		let jitter = dynamics.angle_jitter * random_unit(seed, index as u64);
+
+		BrushDab {
+			position,
+			rotation: direction_angle + (dynamics.angle + jitter).to_radians(),
+			diameter: if dynamics.pressure_size { self.style.diameter * pressure } else { self.style.diameter },
+			opacity: if dynamics.pressure_opacity { pressure } else { 1. },
+		}
+	}
+}
+
+/// A deterministic pseudorandom number from -1 to 1 based on the SplitMix64 generator.
+fn random_unit(seed: u64, index: u64) -> f64 {
+	let mut z = seed.wrapping_add(index.wrapping_add(1).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+	z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+	z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+	z ^= z >> 31;
+
+	(z >> 11) as f64 / (1_u64 << 53) as f64 * 2. - 1.
 }
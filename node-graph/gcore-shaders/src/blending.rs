@@ -9,6 +9,9 @@ pub struct AlphaBlending {
 	pub opacity: f32,
 	pub fill: f32,
 	pub clip: bool,
+	/// Whether the contents of a group blend directly against the backdrop below the group, rather than first being composited together in isolation.
+	/// Only meaningful for groups, where it is the default just like Photoshop and Figma.
+	pub pass_through: bool,
 }
 impl Default for AlphaBlending {
 	fn default() -> Self {
@@ -21,6 +24,7 @@ impl Hash for AlphaBlending {
 		self.fill.to_bits().hash(state);
 		self.blend_mode.hash(state);
 		self.clip.hash(state);
+		self.pass_through.hash(state);
 	}
 }
 impl Display for AlphaBlending {
@@ -28,11 +32,12 @@ impl Display for AlphaBlending {
 		let round = |x: f32| (x * 1e3).round() / 1e3;
 		write!(
 			f,
-			"Blend Mode: {} — Opacity: {}% — Fill: {}% — Clip: {}",
+			"Blend Mode: {} — Opacity: {}% — Fill: {}% — Clip: {} — Pass Through: {}",
 			self.blend_mode,
 			round(self.opacity * 100.),
 			round(self.fill * 100.),
-			if self.clip { "Yes" } else { "No" }
+			if self.clip { "Yes" } else { "No" },
+			if self.pass_through { "Yes" } else { "No" }
 		)
 	}
 }
@@ -44,6 +49,7 @@ impl AlphaBlending {
 			fill: 1.,
 			blend_mode: BlendMode::Normal,
 			clip: false,
+			pass_through: true,
 		}
 	}
 
@@ -55,6 +61,7 @@ impl AlphaBlending {
 			fill: lerp(self.fill, other.fill, t),
 			blend_mode: if t < 0.5 { self.blend_mode } else { other.blend_mode },
 			clip: if t < 0.5 { self.clip } else { other.clip },
+			pass_through: if t < 0.5 { self.pass_through } else { other.pass_through },
 		}
 	}
 
@@ -1,3 +1,4 @@
+use super::color_space::ColorSpace;
 use super::color_traits::{Alpha, AlphaMut, AssociatedAlpha, Luminance, LuminanceMut, Pixel, RGB, RGBMut, Rec709Primaries, SRGB};
 use super::discrete_srgb::{float_to_srgb_u8, srgb_u8_to_float};
 use bytemuck::{Pod, Zeroable};
@@ -824,6 +825,25 @@ impl Color {
 		format!("{:02x?}{:02x?}{:02x?}", (self.r() * 255.) as u8, (self.g() * 255.) as u8, (self.b() * 255.) as u8)
 	}
 
+	/// Return a CSS color (without alpha) which is an RGB hex string with a # prefix, or a `color(display-p3 ...)` function if the color lies outside of the sRGB gamut. Use this if the [`Color`] is in gamma space.
+	/// ```
+	/// use graphene_core::color::{Color, ColorSpace};
+	/// assert_eq!("#ff0000", Color::RED.to_css_color_from_gamma());
+	///
+	/// let p3_red = Color::RED.convert_color_space(ColorSpace::DisplayP3, ColorSpace::Srgb);
+	/// assert_eq!("color(display-p3 1 0 0)", p3_red.to_css_color_from_gamma());
+	/// ```
+	pub fn to_css_color_from_gamma(&self) -> String {
+		if self.is_within_srgb_gamut() {
+			return format!("#{}", self.to_rgb_hex_srgb_from_gamma());
+		}
+
+		let [red, green, blue] = ColorSpace::Srgb
+			.convert([self.r(), self.g(), self.b()], ColorSpace::DisplayP3)
+			.map(|channel| (channel.clamp(0., 1.) * 10_000.).round() / 10_000.);
+		format!("color(display-p3 {red} {green} {blue})")
+	}
+
 	/// Return the all components as a u8 slice, first component is red, followed by green, followed by blue, followed by alpha. Use this if the [`Color`] is in linear space.
 	///
 	/// # Examples
@@ -968,14 +988,20 @@ impl Color {
 		}
 	}
 
+	/// Negative channels, which occur for wide-gamut colors outside of sRGB, are mirrored as in the extended sRGB transfer function.
 	#[inline(always)]
 	pub fn srgb_to_linear(channel: f32) -> f32 {
-		if channel <= 0.04045 { channel / 12.92 } else { ((channel + 0.055) / 1.055).powf(2.4) }
+		let magnitude = channel.abs();
+		let linear = if magnitude <= 0.04045 { magnitude / 12.92 } else { ((magnitude + 0.055) / 1.055).powf(2.4) };
+		if channel < 0. { -linear } else { linear }
 	}
 
+	/// Negative channels, which occur for wide-gamut colors outside of sRGB, are mirrored as in the extended sRGB transfer function.
 	#[inline(always)]
 	pub fn linear_to_srgb(channel: f32) -> f32 {
-		if channel <= 0.0031308 { channel * 12.92 } else { 1.055 * channel.powf(1. / 2.4) - 0.055 }
+		let magnitude = channel.abs();
+		let gamma = if magnitude <= 0.0031308 { magnitude * 12.92 } else { 1.055 * magnitude.powf(1. / 2.4) - 0.055 };
+		if channel < 0. { -gamma } else { gamma }
 	}
 
 	#[inline(always)]
@@ -0,0 +1,124 @@
+use super::color::Color;
+
+/// Linear Display P3 to linear sRGB, both relative to the D65 white point.
+#[rustfmt::skip]
+const DISPLAY_P3_TO_SRGB: [[f32; 3]; 3] = [
+	[1.2249401, -0.2249404, 0.],
+	[-0.0420569, 1.0420571, 0.],
+	[-0.0196376, -0.0786361, 1.0982735],
+];
+
+/// Linear sRGB to linear Display P3, both relative to the D65 white point.
+#[rustfmt::skip]
+const SRGB_TO_DISPLAY_P3: [[f32; 3]; 3] = [
+	[0.8224621, 0.177538, 0.],
+	[0.0331941, 0.9668058, 0.],
+	[0.0170826, 0.0723974, 0.9105199],
+];
+
+/// The space which a color's channels are given in.
+///
+/// Colors are stored and blended in sRGB, with channels allowed beyond the 0 to 1 range so the colors of wider gamuts can be represented.
+/// Other spaces are only used when a color enters or leaves the editor, such as in the color picker or when exporting.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny, specta::Type, serde::Serialize, serde::Deserialize))]
+pub enum ColorSpace {
+	#[default]
+	Srgb,
+	LinearSrgb,
+	DisplayP3,
+	LinearDisplayP3,
+}
+
+impl ColorSpace {
+	/// Whether the channels are linear light rather than gamma-encoded with the sRGB transfer function, which Display P3 shares.
+	pub fn is_linear(self) -> bool {
+		matches!(self, Self::LinearSrgb | Self::LinearDisplayP3)
+	}
+
+	fn is_display_p3(self) -> bool {
+		matches!(self, Self::DisplayP3 | Self::LinearDisplayP3)
+	}
+
+	/// Convert red, green, and blue channels from this space to `target`. Colors outside of the target's gamut are kept, so their channels may be negative or greater than 1.
+	pub fn convert(self, rgb: [f32; 3], target: ColorSpace) -> [f32; 3] {
+		if self == target {
+			return rgb;
+		}
+
+		let mut linear = if self.is_linear() { rgb } else { rgb.map(Color::srgb_to_linear) };
+		if self.is_display_p3() != target.is_display_p3() {
+			let matrix = if self.is_display_p3() { DISPLAY_P3_TO_SRGB } else { SRGB_TO_DISPLAY_P3 };
+			linear = matrix.map(|row| row[0] * linear[0] + row[1] * linear[1] + row[2] * linear[2]);
+		}
+
+		if target.is_linear() { linear } else { linear.map(Color::linear_to_srgb) }
+	}
+}
+
+impl Color {
+	/// Convert the color from one space to another, keeping its alpha.
+	pub fn convert_color_space(&self, from: ColorSpace, to: ColorSpace) -> Color {
+		let [red, green, blue] = from.convert([self.r(), self.g(), self.b()], to);
+		Color::from_rgbaf32_unchecked(red, green, blue, self.a())
+	}
+
+	/// Whether the color can be shown without clipping in sRGB, which is the case when each channel is within 0 to 1.
+	/// This holds for both gamma-encoded and linear channels, since the transfer function maps that range onto itself.
+	pub fn is_within_srgb_gamut(&self) -> bool {
+		const TOLERANCE: f32 = 1e-4;
+		[self.r(), self.g(), self.b()].iter().all(|&channel| (-TOLERANCE..=1. + TOLERANCE).contains(&channel))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn assert_channels_eq(actual: [f32; 3], expected: [f32; 3]) {
+		assert!(
+			actual.iter().zip(expected).all(|(actual, expected)| (actual - expected).abs() < 1e-3),
+			"{actual:?} should be close to {expected:?}"
+		);
+	}
+
+	#[test]
+	fn conversions_match_reference_values() {
+		// Reference values from the CSS Color Module Level 4 conversion code
+		assert_channels_eq(ColorSpace::DisplayP3.convert([1., 0., 0.], ColorSpace::Srgb), [1.0931, -0.2267, -0.1501]);
+		assert_channels_eq(ColorSpace::Srgb.convert([1., 0., 0.], ColorSpace::DisplayP3), [0.9175, 0.2003, 0.1386]);
+		assert_channels_eq(ColorSpace::Srgb.convert([0., 1., 0.], ColorSpace::DisplayP3), [0.4584, 0.9853, 0.2983]);
+		assert_channels_eq(ColorSpace::Srgb.convert([0.5, 0.5, 0.5], ColorSpace::LinearSrgb), [0.214, 0.214, 0.214]);
+
+		// White is the same in every space since they share the D65 white point
+		for space in [ColorSpace::LinearSrgb, ColorSpace::DisplayP3, ColorSpace::LinearDisplayP3] {
+			assert_channels_eq(ColorSpace::Srgb.convert([1., 1., 1.], space), [1., 1., 1.]);
+		}
+	}
+
+	#[test]
+	fn conversions_round_trip() {
+		let spaces = [ColorSpace::Srgb, ColorSpace::LinearSrgb, ColorSpace::DisplayP3, ColorSpace::LinearDisplayP3];
+		for rgb in [[1., 0., 0.], [0.2, 0.7, 0.4], [1.0931, -0.2267, -0.1501], [0., 0., 0.]] {
+			for from in spaces {
+				for to in spaces {
+					assert_channels_eq(to.convert(from.convert(rgb, to), from), rgb);
+				}
+			}
+		}
+	}
+
+	#[test]
+	fn display_p3_primaries_are_outside_of_srgb() {
+		let p3_green = Color::GREEN.convert_color_space(ColorSpace::DisplayP3, ColorSpace::Srgb);
+		assert!(!p3_green.is_within_srgb_gamut());
+		assert!(Color::GREEN.is_within_srgb_gamut());
+
+		// Colors inside of sRGB are within its gamut after a round trip through Display P3, despite rounding errors
+		let round_trip = Color::GREEN
+			.convert_color_space(ColorSpace::Srgb, ColorSpace::DisplayP3)
+			.convert_color_space(ColorSpace::DisplayP3, ColorSpace::Srgb);
+		assert!(round_trip.is_within_srgb_gamut());
+		assert!(round_trip.to_css_color_from_gamma().starts_with('#'));
+	}
+}
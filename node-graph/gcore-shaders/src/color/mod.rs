@@ -1,7 +1,9 @@
 mod color;
+mod color_space;
 mod color_traits;
 mod discrete_srgb;
 
 pub use color::*;
+pub use color_space::*;
 pub use color_traits::*;
 pub use discrete_srgb::*;
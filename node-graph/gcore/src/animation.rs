@@ -1,3 +1,4 @@
+use crate::time_dependency::mark_time_read;
 use crate::{Ctx, ExtractAnimationTime, ExtractTime};
 
 const DAY: f64 = 1000. * 3600. * 24.;
@@ -22,6 +23,7 @@ pub enum AnimationTimeMode {
 
 #[node_macro::node(category("Animation"))]
 fn real_time(ctx: impl Ctx + ExtractTime, _primary: (), mode: RealTimeMode) -> f64 {
+	mark_time_read();
 	let time = ctx.try_time().unwrap_or_default();
 	// TODO: Implement proper conversion using and existing time implementation
 	match mode {
@@ -37,9 +39,18 @@ fn real_time(ctx: impl Ctx + ExtractTime, _primary: (), mode: RealTimeMode) -> f
 
 #[node_macro::node(category("Animation"))]
 fn animation_time(ctx: impl Ctx + ExtractAnimationTime) -> f64 {
+	mark_time_read();
 	ctx.try_animation_time().unwrap_or_default()
 }
 
+/// The time, in seconds, of the document's animation playback, played back at the given rate and offset.
+/// While the animation plays, the nodes downstream of this one are evaluated again for each frame.
+#[node_macro::node(category("Animation"))]
+fn time(ctx: impl Ctx + ExtractAnimationTime, _primary: (), #[default(1.)] rate: f64, offset: f64) -> f64 {
+	mark_time_read();
+	ctx.try_animation_time().unwrap_or_default() * rate + offset
+}
+
 // These nodes require more sophistcated algorithms for giving the correct result
 
 // #[node_macro::node(category("Animation"))]
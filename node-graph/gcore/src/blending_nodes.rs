@@ -120,6 +120,30 @@ impl SetClip for RasterDataTable<CPU> {
 	}
 }
 
+trait Isolate: Sized {
+	/// Whether the rows are the contents of a group, which can either pass through to blend against the backdrop below the group or be isolated from it.
+	const GROUP: bool = false;
+
+	/// Composites the rows together in isolation by moving them into a group that becomes the single row of the table, so blending applies to the group as a whole.
+	fn isolate(self) -> Self {
+		self
+	}
+}
+
+impl Isolate for VectorDataTable {}
+impl Isolate for RasterDataTable<CPU> {}
+impl Isolate for GraphicGroupTable {
+	const GROUP: bool = true;
+
+	fn isolate(self) -> Self {
+		let mut isolated = GraphicGroupTable::new(GraphicElement::GraphicGroup(self));
+		for instance in isolated.instance_mut_iter() {
+			instance.alpha_blending.pass_through = false;
+		}
+		isolated
+	}
+}
+
 #[node_macro::node(category("Style"))]
 fn blend_mode<T: SetBlendMode>(
 	_: impl Ctx,
@@ -152,8 +176,8 @@ fn opacity<T: MultiplyAlpha>(
 	value
 }
 
-#[node_macro::node(category("Style"))]
-fn blending<T: SetBlendMode + MultiplyAlpha + MultiplyFill + SetClip>(
+#[node_macro::node(category("Style"), properties("blending_properties"))]
+fn blending<T: SetBlendMode + MultiplyAlpha + MultiplyFill + SetClip + Isolate>(
 	_: impl Ctx,
 	#[implementations(
 		GraphicGroupTable,
@@ -165,11 +189,88 @@ fn blending<T: SetBlendMode + MultiplyAlpha + MultiplyFill + SetClip>(
 	#[default(100.)] opacity: Percentage,
 	#[default(100.)] fill: Percentage,
 	#[default(false)] clip: bool,
+	/// Lets the contents of a group blend directly against what's below the group, instead of compositing them together in isolation first. Only affects groups.
+	#[default(true)]
+	pass_through: bool,
 ) -> T {
+	if T::GROUP && !pass_through {
+		value = value.isolate();
+	}
+
 	// TODO: Find a way to make this apply once to the table's parent (i.e. its row in its parent table or Instance<T>) rather than applying to each row in its own table, which produces the undesired result
-	value.set_blend_mode(blend_mode);
+	// A pass-through group has no blend mode of its own, so its contents keep theirs unless a different one is chosen for all of them
+	if !(T::GROUP && pass_through && blend_mode == BlendMode::Normal) {
+		value.set_blend_mode(blend_mode);
+	}
 	value.multiply_alpha(opacity / 100.);
 	value.multiply_fill(fill / 100.);
 	value.set_clip(clip);
 	value
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::blending::AlphaBlending;
+	use crate::instances::Instance;
+	use crate::transform::Footprint;
+	use crate::vector::VectorData;
+
+	fn group_with_multiply_contents() -> GraphicGroupTable {
+		let mut group = GraphicGroupTable::new(GraphicElement::VectorData(VectorDataTable::new(VectorData::default())));
+		group.push(Instance {
+			instance: GraphicElement::VectorData(VectorDataTable::new(VectorData::default())),
+			..Default::default()
+		});
+		group.set_blend_mode(BlendMode::Multiply);
+		group
+	}
+
+	#[test]
+	fn pass_through_group_keeps_the_blend_modes_of_its_contents() {
+		let group = super::blending(Footprint::default(), group_with_multiply_contents(), BlendMode::Normal, 50., 100., false, true);
+
+		assert_eq!(group.len(), 2);
+		for instance in group.instance_ref_iter() {
+			assert_eq!(instance.alpha_blending.blend_mode, BlendMode::Multiply);
+			assert_eq!(instance.alpha_blending.opacity, 0.5);
+			assert!(instance.alpha_blending.pass_through);
+		}
+
+		// Choosing a blend mode for a pass-through group applies it to all of its contents
+		let group = super::blending(Footprint::default(), group_with_multiply_contents(), BlendMode::Screen, 100., 100., false, true);
+		assert!(group.instance_ref_iter().all(|instance| instance.alpha_blending.blend_mode == BlendMode::Screen));
+	}
+
+	#[test]
+	fn isolated_group_blends_as_a_whole() {
+		let group = super::blending(Footprint::default(), group_with_multiply_contents(), BlendMode::Normal, 50., 100., false, false);
+
+		assert_eq!(group.len(), 1);
+		let isolated = group.instance_ref_iter().next().unwrap();
+		assert!(!isolated.alpha_blending.pass_through);
+		assert_eq!(isolated.alpha_blending.blend_mode, BlendMode::Normal);
+		assert_eq!(isolated.alpha_blending.opacity, 0.5);
+
+		// The contents are untouched inside the isolated group
+		let GraphicElement::GraphicGroup(contents) = isolated.instance else {
+			panic!("The contents should be grouped")
+		};
+		assert_eq!(contents.len(), 2);
+		let multiply = AlphaBlending {
+			blend_mode: BlendMode::Multiply,
+			..Default::default()
+		};
+		assert!(contents.instance_ref_iter().all(|instance| *instance.alpha_blending == multiply));
+	}
+
+	#[test]
+	fn pass_through_only_affects_groups() {
+		let mut vector_data = VectorDataTable::new(VectorData::default());
+		vector_data.set_blend_mode(BlendMode::Multiply);
+
+		let vector_data = super::blending(Footprint::default(), vector_data, BlendMode::Normal, 100., 100., false, false);
+		assert_eq!(vector_data.len(), 1);
+		assert_eq!(vector_data.instance_ref_iter().next().unwrap().alpha_blending.blend_mode, BlendMode::Normal);
+	}
+}
@@ -0,0 +1,117 @@
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::task::{Context, Poll};
+
+thread_local! {
+	static CURRENT_TOKEN: RefCell<Option<CancellationToken>> = const { RefCell::new(None) };
+}
+
+/// Tells an evaluation of the graph whether a newer one has since been requested, making its result obsolete.
+///
+/// Every evaluation is identified by a generation which increases with each request. Clones of a token share the newest requested
+/// generation, so the editor can supersede an evaluation while it is still running elsewhere. Long-running nodes check [`is_cancelled`]
+/// periodically and bail out early, since whatever they return will be discarded.
+#[derive(Clone, Debug, Default)]
+pub struct CancellationToken {
+	newest_generation: Arc<AtomicU64>,
+	generation: u64,
+}
+
+impl CancellationToken {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Records that the evaluation of the given generation has been requested, cancelling all evaluations of older generations.
+	pub fn supersede(&self, generation: u64) {
+		self.newest_generation.fetch_max(generation, Ordering::Relaxed);
+	}
+
+	/// A token for the evaluation of the given generation, which is cancelled once a newer generation is requested through any clone of this token.
+	pub fn for_generation(&self, generation: u64) -> Self {
+		Self {
+			newest_generation: self.newest_generation.clone(),
+			generation,
+		}
+	}
+
+	pub fn generation(&self) -> u64 {
+		self.generation
+	}
+
+	/// Whether a newer evaluation than this one has been requested.
+	pub fn is_cancelled(&self) -> bool {
+		self.newest_generation.load(Ordering::Relaxed) > self.generation
+	}
+}
+
+/// The token of the evaluation which is currently running on this thread, or one which is never cancelled outside of an evaluation.
+///
+/// Nodes which hand their work to other threads should get the token up front and check it from there, since those threads don't share it.
+pub fn current() -> CancellationToken {
+	CURRENT_TOKEN.with(|token| token.borrow().clone()).unwrap_or_default()
+}
+
+/// Whether the evaluation which is currently running on this thread has been superseded by a newer one.
+pub fn is_cancelled() -> bool {
+	CURRENT_TOKEN.with(|token| token.borrow().as_ref().is_some_and(CancellationToken::is_cancelled))
+}
+
+/// Makes the token available through [`current`] and [`is_cancelled`] to the nodes evaluated by the future, whenever it is polled.
+pub fn scope<F: Future>(token: Option<CancellationToken>, future: F) -> Scoped<F> {
+	Scoped { token, future: Box::pin(future) }
+}
+
+/// A future which runs with a [`CancellationToken`], returned by [`scope`].
+pub struct Scoped<F> {
+	token: Option<CancellationToken>,
+	future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+	type Output = F::Output;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = &mut *self;
+		let previous = CURRENT_TOKEN.with(|token| token.replace(this.token.clone()));
+		let result = this.future.as_mut().poll(cx);
+		CURRENT_TOKEN.with(|token| *token.borrow_mut() = previous);
+		result
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[test]
+	fn newer_generations_cancel_older_ones() {
+		let token = CancellationToken::new();
+		let first = token.for_generation(1);
+		token.supersede(1);
+		assert!(!first.is_cancelled());
+
+		token.supersede(2);
+		let second = token.for_generation(2);
+		assert!(first.is_cancelled());
+		assert!(!second.is_cancelled());
+
+		// Recording an older request again doesn't revive a cancelled evaluation
+		token.supersede(1);
+		assert!(first.is_cancelled());
+	}
+
+	#[tokio::test]
+	async fn scope_exposes_the_token_only_while_polled() {
+		let token = CancellationToken::new();
+		token.supersede(1);
+
+		let cancelled = scope(Some(token.for_generation(0)), async { is_cancelled() }).await;
+		assert!(cancelled);
+		assert!(!is_cancelled());
+		assert_eq!(current().generation(), 0);
+	}
+}
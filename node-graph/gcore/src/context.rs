@@ -344,6 +344,30 @@ impl OwnedContextImpl {
 		self.parent = None;
 		self
 	}
+	/// A hash of this context which identifies it across graph evaluations, for reusing the outputs which nodes computed from it in an earlier evaluation.
+	/// Variadic arguments and parent contexts are only hashed by their address, which may be reused by a different value in a later evaluation, so a context with either has no such hash.
+	///
+	/// The real time and animation time are left out, since only the outputs of the nodes which read them depend on them. See [`Self::time_hash`].
+	pub fn stable_hash(&self) -> Option<u64> {
+		use std::hash::{Hash, Hasher};
+
+		if self.varargs.is_some() || self.parent.is_some() {
+			return None;
+		}
+		let mut hasher = std::hash::DefaultHasher::new();
+		self.footprint.hash(&mut hasher);
+		self.index.hash(&mut hasher);
+		Some(hasher.finish())
+	}
+	/// A hash of the real time and animation time of this context, which tells apart the outputs of nodes that depend on the time.
+	pub fn time_hash(&self) -> u64 {
+		use std::hash::{Hash, Hasher};
+
+		let mut hasher = std::hash::DefaultHasher::new();
+		self.real_time.map(f64::to_bits).hash(&mut hasher);
+		self.animation_time.map(f64::to_bits).hash(&mut hasher);
+		hasher.finish()
+	}
 }
 
 #[derive(Default, Clone, dyn_any::DynAny)]
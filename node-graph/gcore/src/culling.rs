@@ -0,0 +1,285 @@
+use crate::bounds::BoundingBox;
+use crate::math::bbox::{AxisAlignedBbox, Bbox};
+use crate::transform::Footprint;
+use crate::{ExtractAnimationTime, ExtractFootprint, ExtractIndex, Node, WasmNotSend};
+use dyn_any::DynFuture;
+use glam::DAffine2;
+use std::cell::Cell;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{Context, Poll};
+
+thread_local! {
+	static ENABLED: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How far, in the content's space, its bounds may be from the edge of a viewport while still counting as reaching it.
+const EDGE_TOLERANCE: f64 = 1e-3;
+
+/// Whether the evaluation which is currently running on this thread may skip the layers outside of its viewport.
+pub fn is_enabled() -> bool {
+	ENABLED.with(Cell::get)
+}
+
+/// Lets the layers evaluated by the future be culled, whenever it is polled, if enabled.
+///
+/// Culling is only enabled for renders of the viewport, so exports and any other evaluations always include every layer.
+pub fn scope<F: Future>(enabled: bool, future: F) -> Scoped<F> {
+	Scoped { enabled, future: Box::pin(future) }
+}
+
+/// A future which runs with culling enabled or disabled, returned by [`scope`].
+pub struct Scoped<F> {
+	enabled: bool,
+	future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+	type Output = F::Output;
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = &mut *self;
+		let previous = ENABLED.with(|enabled| enabled.replace(this.enabled));
+		let result = this.future.as_mut().poll(cx);
+		ENABLED.with(|enabled| enabled.set(previous));
+		result
+	}
+}
+
+/// The area of the footprint's viewport in the space of the content evaluated for it, or `None` if it doesn't have a finite area to cull against.
+pub fn visible_bounds(footprint: &Footprint) -> Option<AxisAlignedBbox> {
+	let matrix = footprint.transform.matrix2;
+	if !matrix.is_finite() || matrix.determinant() == 0. || footprint.resolution.min_element() == 0 {
+		return None;
+	}
+
+	let viewport = DAffine2::from_scale(footprint.resolution.as_dvec2());
+	let bounds = Bbox::unit().affine_transform(footprint.transform.inverse() * viewport).to_axis_aligned_bbox();
+	(bounds.start.is_finite() && bounds.end.is_finite()).then_some(bounds)
+}
+
+/// What a layer's content was when it was last evaluated, and what it was evaluated for.
+struct LastEvaluation<T> {
+	output: T,
+	bounds: Option<AxisAlignedBbox>,
+	visible_bounds: AxisAlignedBbox,
+	animation_time: Option<f64>,
+	index: Option<Vec<usize>>,
+}
+
+impl<T> LastEvaluation<T> {
+	/// Whether the content would still be the same, and stays outside of the visible area, so evaluating it again can be skipped.
+	fn culled_by(&self, visible_bounds: &AxisAlignedBbox, animation_time: Option<f64>, index: &Option<Vec<usize>>) -> bool {
+		if self.animation_time != animation_time || &self.index != index {
+			return false;
+		}
+		// Without bounds, there's no telling where the content would be once it's evaluated again
+		let Some(bounds) = &self.bounds else { return false };
+
+		!bounds.intersects(visible_bounds) && !fitted_to(bounds, &self.visible_bounds)
+	}
+}
+
+/// Content generated to fit the viewport it was evaluated for, like a pattern or a rasterization, lies inside of that viewport while reaching its edges.
+/// Once the viewport moves, such content may extend beyond the bounds it had, so they can't tell whether it would be visible.
+fn fitted_to(bounds: &AxisAlignedBbox, viewport: &AxisAlignedBbox) -> bool {
+	let inside = (bounds.start - viewport.start).min_element() > -EDGE_TOLERANCE && (viewport.end - bounds.end).min_element() > -EDGE_TOLERANCE;
+	let reaches_edge = (bounds.start - viewport.start).abs().min_element() < EDGE_TOLERANCE || (viewport.end - bounds.end).abs().min_element() < EDGE_TOLERANCE;
+	inside && reaches_edge
+}
+
+/// Skips evaluating a layer's content while it's outside of the viewport, returning its last output instead.
+/// The cached output keeps the layer's bounds and metadata available, so it reappears instantly when the viewport pans back to it.
+///
+/// A change upstream gives the node a new ID and a fresh cache, so stale bounds are never used to cull changed content.
+#[derive(Default)]
+pub struct CullNode<I, T, N> {
+	last_evaluation: Arc<Mutex<Option<LastEvaluation<T>>>>,
+	node: N,
+	_phantom: PhantomData<I>,
+}
+
+impl<'i, I, T, N> Node<'i, I> for CullNode<I, T, N>
+where
+	I: ExtractFootprint + ExtractAnimationTime + ExtractIndex + 'i,
+	T: BoundingBox + Clone + WasmNotSend + 'i,
+	for<'a> N: Node<'a, I, Output: Future<Output = T> + WasmNotSend> + 'i,
+{
+	type Output = DynFuture<'i, T>;
+	fn eval(&'i self, input: I) -> Self::Output {
+		let visible_bounds = if is_enabled() { input.try_footprint().and_then(visible_bounds) } else { None };
+		let animation_time = input.try_animation_time();
+		let index = input.try_index();
+
+		if let Some(visible_bounds) = &visible_bounds {
+			let last_evaluation = self.last_evaluation.lock().unwrap();
+			if let Some(last_evaluation) = last_evaluation.as_ref().filter(|last| last.culled_by(visible_bounds, animation_time, &index)) {
+				let output = last_evaluation.output.clone();
+				return Box::pin(async move { output });
+			}
+		}
+
+		let future = self.node.eval(input);
+		let last_evaluation = self.last_evaluation.clone();
+		Box::pin(async move {
+			let output = future.await;
+			// Only viewport renders are culled, and a superseded evaluation may have produced incomplete content whose bounds mustn't be trusted
+			if let Some(visible_bounds) = visible_bounds
+				&& !crate::cancellation::is_cancelled()
+			{
+				let bounds = output.bounding_box(DAffine2::IDENTITY, true).map(|[start, end]| AxisAlignedBbox { start, end });
+				*last_evaluation.lock().unwrap() = Some(LastEvaluation {
+					output: output.clone(),
+					bounds,
+					visible_bounds,
+					animation_time,
+					index,
+				});
+			}
+			output
+		})
+	}
+
+	fn reset(&self) {
+		self.last_evaluation.lock().unwrap().take();
+	}
+}
+
+impl<I, T, N> CullNode<I, T, N> {
+	pub fn new(node: N) -> CullNode<I, T, N> {
+		CullNode {
+			last_evaluation: Default::default(),
+			node,
+			_phantom: PhantomData,
+		}
+	}
+}
+
+pub mod cull {
+	pub const IDENTIFIER: crate::ProtoNodeIdentifier = crate::ProtoNodeIdentifier::new("graphene_core::culling::CullNode");
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{Context, OwnedContextImpl};
+	use glam::{DVec2, UVec2};
+	use std::sync::atomic::{AtomicUsize, Ordering};
+
+	#[derive(Clone, Debug, PartialEq)]
+	struct Square {
+		corner: DVec2,
+		size: f64,
+	}
+
+	impl BoundingBox for Square {
+		fn bounding_box(&self, transform: DAffine2, _include_stroke: bool) -> Option<[DVec2; 2]> {
+			Some([transform.transform_point2(self.corner), transform.transform_point2(self.corner + self.size)])
+		}
+	}
+
+	/// A layer's content, which counts how many times it has been evaluated.
+	struct Layer {
+		square: Square,
+		evaluations: AtomicUsize,
+	}
+
+	impl<'i> Node<'i, Context<'i>> for Layer {
+		type Output = DynFuture<'i, Square>;
+		fn eval(&'i self, _: Context<'i>) -> Self::Output {
+			self.evaluations.fetch_add(1, Ordering::Relaxed);
+			Box::pin(async move { self.square.clone() })
+		}
+	}
+
+	fn layer(x: f64) -> CullNode<Context<'static>, Square, Layer> {
+		CullNode::new(Layer {
+			square: Square {
+				corner: DVec2::new(x, 200.),
+				size: 100.,
+			},
+			evaluations: AtomicUsize::new(0),
+		})
+	}
+
+	fn evaluations(layer: &CullNode<Context<'static>, Square, Layer>) -> usize {
+		layer.node.evaluations.load(Ordering::Relaxed)
+	}
+
+	/// A 1000x1000 viewport whose left edge is at the given position.
+	fn viewport(x: f64) -> OwnedContextImpl {
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-x, 0.)),
+			resolution: UVec2::new(1000, 1000),
+			..Default::default()
+		};
+		OwnedContextImpl::default().with_footprint(footprint)
+	}
+
+	#[tokio::test]
+	async fn layers_outside_the_viewport_are_not_evaluated_while_panning() {
+		let (near, far) = (&layer(200.), &layer(10_000.));
+		let render = |x: f64| {
+			scope(true, async move {
+				// Culled layers still provide their content, so their bounds and metadata stay available
+				let outputs = (near.eval(viewport(x).into_context()).await, far.eval(viewport(x).into_context()).await);
+				assert_eq!(outputs, (near.node.square.clone(), far.node.square.clone()));
+			})
+		};
+
+		// Both layers are evaluated once to learn their bounds
+		render(0.).await;
+		assert_eq!((evaluations(near), evaluations(far)), (1, 1));
+
+		// Panning around the near layer leaves the far one culled
+		render(50.).await;
+		render(-50.).await;
+		assert_eq!((evaluations(near), evaluations(far)), (3, 1));
+
+		// Panning over to the far layer culls the near one instead
+		render(9_500.).await;
+		render(9_600.).await;
+		assert_eq!((evaluations(near), evaluations(far)), (3, 3));
+
+		// Panning back shows the near layer again
+		render(0.).await;
+		assert_eq!((evaluations(near), evaluations(far)), (4, 3));
+	}
+
+	#[tokio::test]
+	async fn exports_evaluate_every_layer() {
+		let far = layer(10_000.);
+		scope(true, far.eval(viewport(0.).into_context())).await;
+		assert_eq!(evaluations(&far), 1);
+
+		scope(false, far.eval(viewport(0.).into_context())).await;
+		far.eval(viewport(0.).into_context()).await;
+		assert_eq!(evaluations(&far), 3);
+	}
+
+	#[tokio::test]
+	async fn changes_to_the_animation_time_are_evaluated() {
+		let far = layer(10_000.);
+		let at_time = |time: f64| viewport(0.).with_animation_time(time).into_context();
+
+		scope(true, far.eval(at_time(0.))).await;
+		scope(true, far.eval(at_time(0.))).await;
+		assert_eq!(evaluations(&far), 1);
+
+		scope(true, far.eval(at_time(1.))).await;
+		assert_eq!(evaluations(&far), 2);
+	}
+
+	#[tokio::test]
+	async fn content_fitted_to_the_viewport_is_not_culled() {
+		// Content which fills the viewport may have been generated for it, so it may still be visible after panning away from its bounds
+		let mut pattern = layer(0.);
+		pattern.node.square = Square { corner: DVec2::ZERO, size: 1000. };
+
+		scope(true, pattern.eval(viewport(0.).into_context())).await;
+		scope(true, pattern.eval(viewport(5_000.).into_context())).await;
+		assert_eq!(evaluations(&pattern), 2);
+	}
+}
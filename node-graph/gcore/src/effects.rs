@@ -0,0 +1,77 @@
+use crate::bounds::BoundingBox;
+use crate::math::quad::Quad;
+use crate::raster_types::{CPU, RasterDataTable};
+use crate::{Color, GraphicGroupTable};
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2};
+
+/// The kind of layer effect which the Effects node draws from the shape of a graphic.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum EffectType {
+	/// A blurred silhouette of the graphic, offset and drawn behind it.
+	#[default]
+	DropShadow,
+	/// A shadow cast inward from the edges of the graphic, drawn over it and only within its shape.
+	InnerShadow,
+	/// A blurred halo spreading outward around the graphic, drawn behind it.
+	OuterGlow,
+}
+
+impl EffectType {
+	/// Whether the effect is drawn behind the graphic rather than over it.
+	pub fn is_behind(self) -> bool {
+		match self {
+			Self::DropShadow | Self::OuterGlow => true,
+			Self::InnerShadow => false,
+		}
+	}
+
+	/// The offset the effect is cast at, which an outer glow ignores since it spreads evenly on all sides.
+	pub fn cast_offset(self, offset: DVec2) -> DVec2 {
+		match self {
+			Self::DropShadow | Self::InnerShadow => offset,
+			Self::OuterGlow => DVec2::ZERO,
+		}
+	}
+}
+
+/// A layer effect along with the graphic whose shape it's drawn from, which is rendered as only the effect without the graphic itself.
+///
+/// The SVG renderer draws it with filter primitives applied to the graphic, while renderers without an equivalent draw the image it was rasterized into.
+#[derive(Clone, Debug, PartialEq, DynAny, serde::Serialize, serde::Deserialize)]
+pub struct LayerEffect {
+	pub effect_type: EffectType,
+	/// How far the shadow is cast from the graphic, in the graphic's space.
+	pub offset: DVec2,
+	/// The standard deviation of the blur, in the graphic's space.
+	pub blur: f64,
+	pub color: Color,
+	/// How opaque the effect is, from 0 to 1.
+	pub opacity: f64,
+	/// The graphic the effect is drawn from.
+	pub source: GraphicGroupTable,
+	/// The effect rasterized at the resolution it was viewed at, placed in the graphic's space.
+	pub rasterized: RasterDataTable<CPU>,
+}
+
+impl std::hash::Hash for LayerEffect {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.effect_type.hash(state);
+		self.offset.to_array().iter().for_each(|x| x.to_bits().hash(state));
+		self.blur.to_bits().hash(state);
+		self.color.hash(state);
+		self.opacity.to_bits().hash(state);
+		self.source.hash(state);
+		self.rasterized.hash(state);
+	}
+}
+
+impl BoundingBox for LayerEffect {
+	fn bounding_box(&self, transform: DAffine2, include_stroke: bool) -> Option<[DVec2; 2]> {
+		[self.source.bounding_box(transform, include_stroke), self.rasterized.bounding_box(transform, include_stroke)]
+			.into_iter()
+			.flatten()
+			.reduce(Quad::combine_bounds)
+	}
+}
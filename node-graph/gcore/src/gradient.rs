@@ -8,27 +8,205 @@ pub enum GradientType {
 	#[default]
 	Linear,
 	Radial,
+	/// Sweeps the stops around the start point, beginning in the direction of the end point.
+	Conic,
 }
 
+impl GradientType {
+	/// The position (in the range 0 to 1 within the gradient) that the stops are evaluated at for a point, given the gradient's start and end points in the same space.
+	pub fn position_at(self, point: DVec2, start: DVec2, end: DVec2) -> f64 {
+		let axis = end - start;
+		if axis.length_squared() == 0. {
+			return 0.;
+		}
+
+		match self {
+			Self::Linear => axis.dot(point - start) / axis.length_squared(),
+			Self::Radial => start.distance(point) / axis.length(),
+			Self::Conic => {
+				if point == start {
+					return 0.;
+				}
+				axis.angle_to(point - start).rem_euclid(std::f64::consts::TAU) / std::f64::consts::TAU
+			}
+		}
+	}
+
+	/// The point where a stop at the given position is located, given the gradient's start and end points.
+	///
+	/// Linear and radial stops lie on the line from start to end, while conic stops lie on the circle around start passing through end.
+	pub fn stop_point(self, position: f64, start: DVec2, end: DVec2) -> DVec2 {
+		match self {
+			Self::Linear | Self::Radial => start.lerp(end, position),
+			Self::Conic => start + DVec2::from_angle(position * std::f64::consts::TAU).rotate(end - start),
+		}
+	}
+}
+
+/// The color space that the colors between a gradient's stops are mixed in.
+#[derive(Default, PartialEq, Eq, Clone, Copy, Debug, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum GradientInterpolation {
+	/// Mixes the gamma-encoded sRGB values, like SVG and CSS do by default. Midpoints between saturated colors of differing hue can look muddy.
+	#[default]
+	#[label("sRGB")]
+	Srgb,
+	/// Mixes amounts of light, which keeps midpoints between saturated colors brighter.
+	#[label("Linear RGB")]
+	LinearRgb,
+	/// Mixes in a perceptually uniform space, so lightness changes evenly.
+	#[label("OKLab")]
+	Oklab,
+	/// Mixes lightness, chroma, and hue, keeping midpoints saturated by taking the shorter way around the hue circle.
+	#[label("OKLCH (Shorter Hue)")]
+	OklchShorterHue,
+	/// Mixes lightness, chroma, and hue, keeping midpoints saturated by taking the longer way around the hue circle.
+	#[label("OKLCH (Longer Hue)")]
+	OklchLongerHue,
+}
+
+/// Below this chroma, a color is too close to gray for its OKLCH hue to be meaningful.
+const ACHROMATIC_CHROMA: f32 = 1e-4;
+
+impl GradientInterpolation {
+	/// Mixes two gamma-encoded sRGB colors in this color space, `t` (in the range 0 to 1) of the way from `from` to `to`.
+	pub fn mix(self, from: Color, to: Color, t: f32) -> Color {
+		let lerp = |a: f32, b: f32| a + (b - a) * t;
+
+		let oklab = match self {
+			Self::Srgb => return from.lerp(&to, t),
+			Self::LinearRgb => return from.to_linear_srgb().lerp(&to.to_linear_srgb(), t).to_gamma_srgb(),
+			Self::Oklab => {
+				let (from, to) = (srgb_to_oklab(from), srgb_to_oklab(to));
+				[lerp(from[0], to[0]), lerp(from[1], to[1]), lerp(from[2], to[2])]
+			}
+			Self::OklchShorterHue | Self::OklchLongerHue => {
+				let ([from_lightness, a1, b1], [to_lightness, a2, b2]) = (srgb_to_oklab(from), srgb_to_oklab(to));
+				let (from_chroma, to_chroma) = (a1.hypot(b1), a2.hypot(b2));
+				let (from_hue, to_hue) = (b1.atan2(a1), b2.atan2(a2));
+
+				// A gray has no hue of its own, so it takes the other color's hue rather than sweeping around the hue circle
+				let hue_change = if from_chroma < ACHROMATIC_CHROMA || to_chroma < ACHROMATIC_CHROMA {
+					0.
+				} else {
+					use std::f32::consts::{PI, TAU};
+					// The hue directions follow the CSS Color 4 specification
+					match (self, to_hue - from_hue) {
+						(Self::OklchShorterHue, difference) if difference > PI => difference - TAU,
+						(Self::OklchShorterHue, difference) if difference < -PI => difference + TAU,
+						(Self::OklchLongerHue, difference) if difference > 0. && difference < PI => difference - TAU,
+						(Self::OklchLongerHue, difference) if difference > -PI && difference <= 0. => difference + TAU,
+						(_, difference) => difference,
+					}
+				};
+				let hue = if from_chroma < ACHROMATIC_CHROMA { to_hue } else { from_hue + hue_change * t };
+				let chroma = lerp(from_chroma, to_chroma);
+				[lerp(from_lightness, to_lightness), chroma * hue.cos(), chroma * hue.sin()]
+			}
+		};
+
+		oklab_to_srgb(oklab, lerp(from.a(), to.a()))
+	}
+}
+
+/// Converts a gamma-encoded sRGB color to its OKLab lightness and `a` and `b` components, as defined at <https://bottosson.github.io/posts/oklab/>.
+fn srgb_to_oklab(color: Color) -> [f32; 3] {
+	let linear = color.to_linear_srgb();
+	let (r, g, b) = (linear.r(), linear.g(), linear.b());
+
+	let l = (0.41222147 * r + 0.53633254 * g + 0.05144599 * b).cbrt();
+	let m = (0.21190350 * r + 0.68069955 * g + 0.10739696 * b).cbrt();
+	let s = (0.08830246 * r + 0.28171884 * g + 0.62997870 * b).cbrt();
+
+	[
+		0.21045426 * l + 0.79361778 * m - 0.00407205 * s,
+		1.97799850 * l - 2.42859221 * m + 0.45059371 * s,
+		0.02590404 * l + 0.78277177 * m - 0.80867577 * s,
+	]
+}
+
+/// Converts OKLab components back to a gamma-encoded sRGB color, clamping colors outside the sRGB gamut.
+fn oklab_to_srgb([lightness, a, b]: [f32; 3], alpha: f32) -> Color {
+	let l = (lightness + 0.39633778 * a + 0.21580376 * b).powi(3);
+	let m = (lightness - 0.10556135 * a - 0.06385417 * b).powi(3);
+	let s = (lightness - 0.08948418 * a - 1.29148555 * b).powi(3);
+
+	let linear = [
+		4.07674166 * l - 3.30771159 * m + 0.23096993 * s,
+		-1.26843800 * l + 2.60975740 * m - 0.34131940 * s,
+		-0.00419609 * l - 0.70341861 * m + 1.70761470 * s,
+	]
+	.map(|channel| Color::linear_to_srgb(channel.clamp(0., 1.)));
+
+	Color::from_rgbaf32_unchecked(linear[0], linear[1], linear[2], alpha)
+}
+
+/// The number of steps each segment between stops is divided into by default when a gradient mixed in a color space other than sRGB is exported as SVG.
+pub const DEFAULT_INTERPOLATION_SUBDIVISIONS: u32 = 8;
+
+/// The midpoint between two adjacent stops when the gradient interpolates evenly between them.
+pub const DEFAULT_GRADIENT_MIDPOINT: f64 = 0.5;
+
+/// The number of intermediate stops synthesized within each biased segment when the gradient is drawn by a renderer that only supports linear interpolation between stops.
+const MIDPOINT_SUBDIVISIONS: usize = 16;
+
 // TODO: Someday we could switch this to a Box[T] to avoid over-allocation
 // TODO: Use linear not gamma colors
 /// A list of colors associated with positions (in the range 0 to 1) along a gradient.
-#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
-pub struct GradientStops(pub Vec<(f64, Color)>);
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+#[serde(from = "GradientStopsVersions")]
+pub struct GradientStops {
+	pub color_stops: Vec<(f64, Color)>,
+	/// For each pair of adjacent stops, the relative location (in the range 0 to 1) between them where their colors are mixed evenly.
+	/// Missing entries are treated as [`DEFAULT_GRADIENT_MIDPOINT`], which interpolates linearly.
+	pub midpoints: Vec<f64>,
+}
+
+// TODO: Eventually remove this document upgrade code
+#[derive(serde::Deserialize)]
+#[serde(untagged)]
+enum GradientStopsVersions {
+	WithoutMidpoints(Vec<(f64, Color)>),
+	WithMidpoints {
+		color_stops: Vec<(f64, Color)>,
+		#[serde(default)]
+		midpoints: Vec<f64>,
+	},
+}
+
+impl From<GradientStopsVersions> for GradientStops {
+	fn from(versions: GradientStopsVersions) -> Self {
+		match versions {
+			GradientStopsVersions::WithMidpoints { color_stops, midpoints } => Self { color_stops, midpoints },
+			GradientStopsVersions::WithoutMidpoints(color_stops) => Self { color_stops, midpoints: Vec::new() },
+		}
+	}
+}
 
 impl std::hash::Hash for GradientStops {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.0.len().hash(state);
-		self.0.iter().for_each(|(position, color)| {
+		self.color_stops.len().hash(state);
+		self.color_stops.iter().for_each(|(position, color)| {
 			position.to_bits().hash(state);
 			color.hash(state);
 		});
+		// Missing midpoints are hashed as the default they stand for, to agree with `PartialEq`
+		(0..self.color_stops.len().saturating_sub(1)).for_each(|index| self.midpoint(index).to_bits().hash(state));
+	}
+}
+
+impl PartialEq for GradientStops {
+	fn eq(&self, other: &Self) -> bool {
+		self.color_stops == other.color_stops && (0..self.color_stops.len().saturating_sub(1)).all(|index| self.midpoint(index) == other.midpoint(index))
 	}
 }
 
 impl Default for GradientStops {
 	fn default() -> Self {
-		Self(vec![(0., Color::BLACK), (1., Color::WHITE)])
+		Self {
+			color_stops: vec![(0., Color::BLACK), (1., Color::WHITE)],
+			midpoints: Vec::new(),
+		}
 	}
 }
 
@@ -37,7 +215,7 @@ impl IntoIterator for GradientStops {
 	type IntoIter = std::vec::IntoIter<(f64, Color)>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.into_iter()
+		self.color_stops.into_iter()
 	}
 }
 
@@ -46,7 +224,7 @@ impl<'a> IntoIterator for &'a GradientStops {
 	type IntoIter = std::slice::Iter<'a, (f64, Color)>;
 
 	fn into_iter(self) -> Self::IntoIter {
-		self.0.iter()
+		self.color_stops.iter()
 	}
 }
 
@@ -54,7 +232,7 @@ impl std::ops::Index<usize> for GradientStops {
 	type Output = (f64, Color);
 
 	fn index(&self, index: usize) -> &Self::Output {
-		&self.0[index]
+		&self.color_stops[index]
 	}
 }
 
@@ -62,57 +240,223 @@ impl std::ops::Deref for GradientStops {
 	type Target = Vec<(f64, Color)>;
 
 	fn deref(&self) -> &Self::Target {
-		&self.0
-	}
-}
-
-impl std::ops::DerefMut for GradientStops {
-	fn deref_mut(&mut self) -> &mut Self::Target {
-		&mut self.0
+		&self.color_stops
 	}
 }
 
 impl GradientStops {
 	pub fn new(stops: Vec<(f64, Color)>) -> Self {
-		let mut stops = Self(stops);
+		let mut stops = Self {
+			color_stops: stops,
+			midpoints: Vec::new(),
+		};
 		stops.sort();
 		stops
 	}
 
+	/// The midpoint of the segment between the stops at `index` and `index + 1`.
+	pub fn midpoint(&self, index: usize) -> f64 {
+		self.midpoints.get(index).copied().unwrap_or(DEFAULT_GRADIENT_MIDPOINT)
+	}
+
+	/// Sets the midpoint of the segment between the stops at `index` and `index + 1`, clamped to lie strictly between them.
+	pub fn set_midpoint(&mut self, index: usize, midpoint: f64) {
+		if index + 1 >= self.color_stops.len() {
+			return;
+		}
+		if self.midpoints.len() <= index {
+			self.midpoints.resize(index + 1, DEFAULT_GRADIENT_MIDPOINT);
+		}
+		self.midpoints[index] = midpoint.clamp(0.01, 0.99);
+	}
+
+	/// Whether any segment has a midpoint that biases its interpolation away from linear.
+	pub fn has_biased_midpoints(&self) -> bool {
+		(0..self.color_stops.len().saturating_sub(1)).any(|index| (self.midpoint(index) - DEFAULT_GRADIENT_MIDPOINT).abs() > f64::EPSILON)
+	}
+
+	/// The exponent of the curve which remaps a factor across a segment so its colors are mixed evenly at `midpoint`.
+	///
+	/// This is the same curve used by CSS color interpolation hints.
+	fn midpoint_exponent(midpoint: f64) -> f64 {
+		0.5_f64.ln() / midpoint.clamp(0.01, 0.99).ln()
+	}
+
+	/// Remaps a factor `t` (in the range 0 to 1) across a segment so the segment's colors are mixed evenly at `midpoint`.
+	fn apply_midpoint(t: f64, midpoint: f64) -> f64 {
+		if (midpoint - DEFAULT_GRADIENT_MIDPOINT).abs() <= f64::EPSILON || t <= 0. {
+			return t;
+		}
+		t.powf(Self::midpoint_exponent(midpoint))
+	}
+
 	pub fn evaluate(&self, t: f64) -> Color {
-		if self.0.is_empty() {
+		self.evaluate_in(t, GradientInterpolation::Srgb)
+	}
+
+	/// The color at `t`, mixing the colors of the stops on either side in the given color space.
+	pub fn evaluate_in(&self, t: f64, interpolation: GradientInterpolation) -> Color {
+		if self.color_stops.is_empty() {
 			return Color::BLACK;
 		}
 
-		if t <= self.0[0].0 {
-			return self.0[0].1;
+		if t <= self.color_stops[0].0 {
+			return self.color_stops[0].1;
 		}
-		if t >= self.0[self.0.len() - 1].0 {
-			return self.0[self.0.len() - 1].1;
+		if t >= self.color_stops[self.color_stops.len() - 1].0 {
+			return self.color_stops[self.color_stops.len() - 1].1;
 		}
 
-		for i in 0..self.0.len() - 1 {
-			let (t1, c1) = self.0[i];
-			let (t2, c2) = self.0[i + 1];
+		for i in 0..self.color_stops.len() - 1 {
+			let (t1, c1) = self.color_stops[i];
+			let (t2, c2) = self.color_stops[i + 1];
 			if t >= t1 && t <= t2 {
-				let normalized_t = (t - t1) / (t2 - t1);
-				return c1.lerp(&c2, normalized_t as f32);
+				let normalized_t = Self::apply_midpoint((t - t1) / (t2 - t1), self.midpoint(i));
+				return interpolation.mix(c1, c2, normalized_t as f32);
 			}
 		}
 
 		Color::BLACK
 	}
 
+	/// The stops to give a renderer that linearly interpolates between stops, with extra stops synthesized along each segment whose midpoint is biased so the result follows [`Self::evaluate`].
+	pub fn interpolated_stops(&self) -> Vec<(f64, Color)> {
+		self.interpolated_stops_in(GradientInterpolation::Srgb, 1)
+	}
+
+	/// The stops to give a renderer that linearly interpolates between stops in sRGB, with extra stops synthesized so the result follows [`Self::evaluate_in`].
+	///
+	/// When mixing in another color space, each segment is divided into `subdivisions` steps. Segments whose midpoint is biased get at least [`MIDPOINT_SUBDIVISIONS`] steps.
+	pub fn interpolated_stops_in(&self, interpolation: GradientInterpolation, subdivisions: usize) -> Vec<(f64, Color)> {
+		let space_subdivisions = if interpolation == GradientInterpolation::Srgb { 1 } else { subdivisions.max(1) };
+		if space_subdivisions == 1 && !self.has_biased_midpoints() {
+			return self.color_stops.clone();
+		}
+
+		let mut stops = Vec::with_capacity(self.color_stops.len() * MIDPOINT_SUBDIVISIONS.max(space_subdivisions));
+		for (index, &(position, color)) in self.color_stops.iter().enumerate() {
+			stops.push((position, color));
+
+			let Some(&(next_position, next_color)) = self.color_stops.get(index + 1) else { continue };
+			let midpoint = self.midpoint(index);
+			let biased = (midpoint - DEFAULT_GRADIENT_MIDPOINT).abs() > f64::EPSILON;
+			let steps = if biased { MIDPOINT_SUBDIVISIONS.max(space_subdivisions) } else { space_subdivisions };
+			if steps <= 1 || next_position <= position {
+				continue;
+			}
+
+			// Space the synthesized stops by equal steps in color rather than position, so they're concentrated where the curve is steepest
+			let inverse_exponent = 1. / Self::midpoint_exponent(midpoint);
+			for step in 1..steps {
+				let factor = step as f64 / steps as f64;
+				let t = if biased { factor.powf(inverse_exponent) } else { factor };
+				stops.push((position + (next_position - position) * t, interpolation.mix(color, next_color, factor as f32)));
+			}
+		}
+		stops
+	}
+
+	/// Orders the stops by position. A segment keeps its midpoint only if its two stops are still adjacent afterwards, and newly formed segments interpolate evenly.
 	pub fn sort(&mut self) {
-		self.0.sort_unstable_by(|a, b| a.0.partial_cmp(&b.0).unwrap());
+		let mut order = (0..self.color_stops.len()).collect::<Vec<_>>();
+		order.sort_by(|&a, &b| self.color_stops[a].0.partial_cmp(&self.color_stops[b].0).unwrap());
+		if order.iter().enumerate().all(|(index, &original)| index == original) {
+			return;
+		}
+
+		if !self.midpoints.is_empty() {
+			self.midpoints = order
+				.windows(2)
+				.map(|pair| if pair[1] == pair[0] + 1 { self.midpoint(pair[0]) } else { DEFAULT_GRADIENT_MIDPOINT })
+				.collect();
+		}
+		self.color_stops = order.into_iter().map(|index| self.color_stops[index]).collect();
+	}
+
+	/// Inserts a stop at `index`, splitting the segment it lands in into two evenly interpolated segments.
+	pub fn insert_stop(&mut self, index: usize, stop: (f64, Color)) {
+		self.color_stops.insert(index, stop);
+
+		if index > 0 && index - 1 < self.midpoints.len() {
+			self.midpoints[index - 1] = DEFAULT_GRADIENT_MIDPOINT;
+			self.midpoints.insert(index, DEFAULT_GRADIENT_MIDPOINT);
+		} else if index == 0 && !self.midpoints.is_empty() {
+			self.midpoints.insert(0, DEFAULT_GRADIENT_MIDPOINT);
+		}
+	}
+
+	/// Inserts a stop at `position` with the color the gradient already has there, mixed in the given color space, and returns its index.
+	pub fn insert_stop_at(&mut self, position: f64, interpolation: GradientInterpolation) -> usize {
+		let index = self.color_stops.iter().take_while(|(stop_position, _)| *stop_position <= position).count();
+		let color = self.evaluate_in(position, interpolation);
+		self.insert_stop(index, (position, color));
+		index
+	}
+
+	/// Moves the stop at `index` to `position` (clamped to the range 0 to 1) and returns its new index, since the stop is reordered if it passes either of its neighbors.
+	pub fn set_position(&mut self, index: usize, position: f64) -> usize {
+		let position = position.clamp(0., 1.);
+		let passes_previous = index > 0 && position < self.color_stops[index - 1].0;
+		let passes_next = index + 1 < self.color_stops.len() && position > self.color_stops[index + 1].0;
+		if !passes_previous && !passes_next {
+			self.color_stops[index].0 = position;
+			return index;
+		}
+
+		let (_, color) = self.remove_stop(index);
+		let new_index = self.color_stops.iter().take_while(|(stop_position, _)| *stop_position <= position).count();
+		self.insert_stop(new_index, (position, color));
+		new_index
+	}
+
+	/// Spaces the stops from `first` to `last` (inclusive) evenly between the positions of those two stops.
+	pub fn distribute(&mut self, first: usize, last: usize) {
+		if last <= first + 1 || last >= self.color_stops.len() {
+			return;
+		}
+
+		let (start, end) = (self.color_stops[first].0, self.color_stops[last].0);
+		let steps = (last - first) as f64;
+		for index in first + 1..last {
+			self.color_stops[index].0 = start + (end - start) * (index - first) as f64 / steps;
+		}
+	}
+
+	/// Removes the stop at `index`, merging the segments on either side of it.
+	pub fn remove_stop(&mut self, index: usize) -> (f64, Color) {
+		let stop = self.color_stops.remove(index);
+
+		let midpoint_index = index.min(self.color_stops.len().saturating_sub(1));
+		if midpoint_index < self.midpoints.len() {
+			self.midpoints.remove(midpoint_index);
+		}
+
+		stop
 	}
 
 	pub fn reversed(&self) -> Self {
-		Self(self.0.iter().rev().map(|(position, color)| (1. - position, *color)).collect())
+		let segment_count = self.color_stops.len().saturating_sub(1);
+		Self {
+			color_stops: self.color_stops.iter().rev().map(|(position, color)| (1. - position, *color)).collect(),
+			midpoints: (0..segment_count).rev().map(|index| 1. - self.midpoint(index)).collect(),
+		}
+	}
+
+	/// Moves every stop to the position given by `f`, keeping the midpoints of segments whose stops stay adjacent.
+	pub fn map_positions<F: Fn(f64) -> f64>(&self, f: F) -> Self {
+		let mut stops = Self {
+			color_stops: self.color_stops.iter().map(|(position, color)| (f(*position), *color)).collect(),
+			midpoints: self.midpoints.clone(),
+		};
+		stops.sort();
+		stops
 	}
 
 	pub fn map_colors<F: Fn(&Color) -> Color>(&self, f: F) -> Self {
-		Self(self.0.iter().map(|(position, color)| (*position, f(color))).collect())
+		Self {
+			color_stops: self.color_stops.iter().map(|(position, color)| (*position, f(color))).collect(),
+			midpoints: self.midpoints.clone(),
+		}
 	}
 }
 
@@ -127,8 +471,27 @@ pub struct Gradient {
 	pub start: DVec2,
 	pub end: DVec2,
 	pub transform: DAffine2,
+	/// The offset from the center (start) of a radial gradient to its focal point, where the gradient radiates from.
+	#[serde(default)]
+	pub focal_offset: DVec2,
+	/// The radius of a radial gradient's focal circle, as a fraction of its radius.
+	#[serde(default)]
+	pub focal_radius: f64,
+	/// The color space the colors between stops are mixed in.
+	#[serde(default)]
+	pub interpolation: GradientInterpolation,
+	/// The number of steps each segment between stops is divided into when exported as SVG, which can only mix colors in sRGB, to approximate another color space.
+	#[serde(default = "default_interpolation_subdivisions")]
+	pub interpolation_subdivisions: u32,
 }
 
+fn default_interpolation_subdivisions() -> u32 {
+	DEFAULT_INTERPOLATION_SUBDIVISIONS
+}
+
+/// The farthest a radial gradient's focal point may be from its center, as a fraction of the radius, since SVG requires it to be inside the end circle.
+const MAX_FOCAL_DISTANCE: f64 = 0.99;
+
 impl Default for Gradient {
 	fn default() -> Self {
 		Self {
@@ -137,21 +500,27 @@ impl Default for Gradient {
 			start: DVec2::new(0., 0.5),
 			end: DVec2::new(1., 0.5),
 			transform: DAffine2::IDENTITY,
+			focal_offset: DVec2::ZERO,
+			focal_radius: 0.,
+			interpolation: GradientInterpolation::default(),
+			interpolation_subdivisions: DEFAULT_INTERPOLATION_SUBDIVISIONS,
 		}
 	}
 }
 
 impl std::hash::Hash for Gradient {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
-		self.stops.0.len().hash(state);
+		self.stops.hash(state);
 		[].iter()
 			.chain(self.start.to_array().iter())
 			.chain(self.end.to_array().iter())
 			.chain(self.transform.to_cols_array().iter())
-			.chain(self.stops.0.iter().map(|(position, _)| position))
+			.chain(self.focal_offset.to_array().iter())
+			.chain([self.focal_radius].iter())
 			.for_each(|x| x.to_bits().hash(state));
-		self.stops.0.iter().for_each(|(_, color)| color.hash(state));
 		self.gradient_type.hash(state);
+		self.interpolation.hash(state);
+		self.interpolation_subdivisions.hash(state);
 	}
 }
 
@@ -160,7 +529,7 @@ impl std::fmt::Display for Gradient {
 		let round = |x: f64| (x * 1e3).round() / 1e3;
 		let stops = self
 			.stops
-			.0
+			.color_stops
 			.iter()
 			.map(|(position, color)| format!("[{}%: #{}]", round(position * 100.), color.to_rgba_hex_srgb()))
 			.collect::<Vec<_>>()
@@ -178,6 +547,10 @@ impl Gradient {
 			stops: GradientStops::new(vec![(0., start_color.to_gamma_srgb()), (1., end_color.to_gamma_srgb())]),
 			transform,
 			gradient_type,
+			focal_offset: DVec2::ZERO,
+			focal_radius: 0.,
+			interpolation: GradientInterpolation::default(),
+			interpolation_subdivisions: DEFAULT_INTERPOLATION_SUBDIVISIONS,
 		}
 	}
 
@@ -187,17 +560,30 @@ impl Gradient {
 		let transform = self.transform;
 		let stops = self
 			.stops
-			.0
+			.color_stops
 			.iter()
-			.zip(other.stops.0.iter())
+			.zip(other.stops.color_stops.iter())
 			.map(|((a_pos, a_color), (b_pos, b_color))| {
 				let position = a_pos + (b_pos - a_pos) * time;
 				let color = a_color.lerp(b_color, time as f32);
 				(position, color)
 			})
 			.collect::<Vec<_>>();
-		let stops = GradientStops::new(stops);
+		let midpoints = (0..stops.len().saturating_sub(1))
+			.map(|index| self.stops.midpoint(index) + (other.stops.midpoint(index) - self.stops.midpoint(index)) * time)
+			.collect();
+		let stops = GradientStops {
+			midpoints,
+			..GradientStops::new(stops)
+		};
 		let gradient_type = if time < 0.5 { self.gradient_type } else { other.gradient_type };
+		let focal_offset = self.focal_offset.lerp(other.focal_offset, time);
+		let focal_radius = self.focal_radius + (other.focal_radius - self.focal_radius) * time;
+		let (interpolation, interpolation_subdivisions) = if time < 0.5 {
+			(self.interpolation, self.interpolation_subdivisions)
+		} else {
+			(other.interpolation, other.interpolation_subdivisions)
+		};
 
 		Self {
 			start,
@@ -205,7 +591,116 @@ impl Gradient {
 			transform,
 			stops,
 			gradient_type,
+			focal_offset,
+			focal_radius,
+			interpolation,
+			interpolation_subdivisions,
+		}
+	}
+
+	/// The focal point of a radial gradient, in the same space as the start and end points.
+	pub fn focal_point(&self) -> DVec2 {
+		self.start + self.focal_offset
+	}
+
+	/// Moves a focal point inside the circle around `center` passing through `end`, as SVG requires of a radial gradient's focal point.
+	pub fn constrain_focal_point(center: DVec2, end: DVec2, focal_point: DVec2) -> DVec2 {
+		let max_distance = center.distance(end) * MAX_FOCAL_DISTANCE;
+		center + (focal_point - center).clamp_length_max(max_distance)
+	}
+
+	/// The color at a point in the same space as the start and end points.
+	pub fn sample(&self, point: DVec2) -> Color {
+		let position = match self.gradient_type {
+			GradientType::Radial if self.focal_offset != DVec2::ZERO || self.focal_radius != 0. => self.focal_position_at(point),
+			gradient_type => gradient_type.position_at(point, self.start, self.end),
+		};
+		self.stops.evaluate_in(position, self.interpolation)
+	}
+
+	/// The position of a point along a radial gradient with a focal point, found (like SVG does) as the largest `t` where the point is on the circle interpolated from the focal circle at 0 to the end circle at 1.
+	fn focal_position_at(&self, point: DVec2) -> f64 {
+		let radius = self.start.distance(self.end);
+		let focal = Self::constrain_focal_point(self.start, self.end, self.focal_point());
+		let focal_radius = self.focal_radius.max(0.) * radius;
+
+		let (to_point, to_center, radius_change) = (point - focal, self.start - focal, radius - focal_radius);
+		let a = to_center.length_squared() - radius_change * radius_change;
+		let b = to_point.dot(to_center) + focal_radius * radius_change;
+		let c = to_point.length_squared() - focal_radius * focal_radius;
+
+		if a.abs() < f64::EPSILON {
+			return if b.abs() < f64::EPSILON { 0. } else { c / (2. * b) };
+		}
+		let discriminant = b * b - a * c;
+		if discriminant < 0. {
+			return 0.;
 		}
+		// With the focal point inside the end circle, `a` is negative so this is the larger root
+		(b - discriminant.sqrt()) / a
+	}
+
+	/// The angle (in radians) a conic gradient begins at, measured from the start point towards the end point.
+	pub fn start_angle(&self) -> f64 {
+		(self.end - self.start).to_angle()
+	}
+
+	/// Reverses the order of the stops, mirroring their positions so the colors run the other way: from end to start along a linear gradient,
+	/// from the edge to the center of a radial gradient, or counterclockwise around a conic gradient.
+	pub fn reverse(&mut self) {
+		self.stops = self.stops.reversed();
+	}
+
+	/// Turns the gradient 90° clockwise, as seen in the space `transform` maps the start and end points to.
+	///
+	/// A linear gradient turns about the midpoint of its axis. Radial and conic gradients turn about their center (the start point) so they stay in place,
+	/// which turns a radial gradient's focal point and a conic gradient's start angle.
+	pub fn rotate_quarter_turn(&mut self, transform: DAffine2) {
+		let (start, end) = (transform.transform_point2(self.start), transform.transform_point2(self.end));
+		let focal = transform.transform_point2(self.focal_point());
+		let pivot = match self.gradient_type {
+			GradientType::Linear => start.lerp(end, 0.5),
+			GradientType::Radial | GradientType::Conic => start,
+		};
+
+		// Positive angles turn from +X towards +Y, which is clockwise on screen
+		let rotation = DAffine2::from_translation(pivot) * DAffine2::from_angle(std::f64::consts::FRAC_PI_2) * DAffine2::from_translation(-pivot);
+		let to_gradient_space = transform.inverse() * rotation;
+
+		self.start = to_gradient_space.transform_point2(start);
+		self.end = to_gradient_space.transform_point2(end);
+		self.focal_offset = to_gradient_space.transform_point2(focal) - self.start;
+	}
+
+	/// Moves the start and end points to span `bounds` (given in the same space as the start and end points) along the gradient's current direction,
+	/// as seen in the space `transform` maps them to.
+	///
+	/// A linear gradient's axis is centered in the bounds and reaches their farthest extent in both directions. Radial and conic gradients are centered in the bounds,
+	/// with a radial gradient's radius reaching the farthest corner so it covers them entirely, and a conic gradient's end point placed on their edge.
+	pub fn fit_to_bounds(&mut self, bounds: [DVec2; 2], transform: DAffine2) {
+		let (start, end) = (transform.transform_point2(self.start), transform.transform_point2(self.end));
+		let focal = transform.transform_point2(self.focal_point());
+		let direction = (end - start).try_normalize().unwrap_or(DVec2::X);
+
+		let center = transform.transform_point2(bounds[0].lerp(bounds[1], 0.5));
+		let corners = [bounds[0], DVec2::new(bounds[1].x, bounds[0].y), bounds[1], DVec2::new(bounds[0].x, bounds[1].y)].map(|corner| transform.transform_point2(corner) - center);
+		let extent = corners.iter().map(|corner| corner.dot(direction)).fold(0., f64::max);
+
+		let (new_start, new_end) = match self.gradient_type {
+			GradientType::Linear => (center - direction * extent, center + direction * extent),
+			GradientType::Radial => (center, center + direction * corners.iter().map(|corner| corner.length()).fold(0., f64::max)),
+			GradientType::Conic => (center, center + direction * extent),
+		};
+
+		// The focal point keeps its place relative to the radial gradient's circle as the circle is moved and resized
+		let old_radius = start.distance(end);
+		let scale = if old_radius > f64::EPSILON { new_start.distance(new_end) / old_radius } else { 1. };
+		let new_focal = new_start + (focal - start) * scale;
+
+		let to_gradient_space = transform.inverse();
+		self.start = to_gradient_space.transform_point2(new_start);
+		self.end = to_gradient_space.transform_point2(new_end);
+		self.focal_offset = to_gradient_space.transform_point2(new_focal) - self.start;
 	}
 
 	/// Insert a stop into the gradient, the index if successful
@@ -213,36 +708,420 @@ impl Gradient {
 		// Transform the start and end positions to the same coordinate space as the mouse.
 		let (start, end) = (transform.transform_point2(self.start), transform.transform_point2(self.end));
 
-		// Calculate the new position by finding the closest point on the line
-		let new_position = ((end - start).angle_to(mouse - start)).cos() * start.distance(mouse) / start.distance(end);
+		// Calculate the new position by finding the closest point on the line (or, for conic gradients, the angle around the start point)
+		let new_position = self.gradient_type.position_at(mouse, start, end);
 
 		// Don't insert point past end of line
 		if !(0. ..=1.).contains(&new_position) {
 			return None;
 		}
 
-		// Compute the color of the inserted stop
-		let get_color = |index: usize, time: f64| match (self.stops.0[index].1, self.stops.0.get(index + 1).map(|(_, c)| *c)) {
-			// Lerp between the nearest colors if applicable
-			(a, Some(b)) => a.lerp(
-				&b,
-				((time - self.stops.0[index].0) / self.stops.0.get(index + 1).map(|end| end.0 - self.stops.0[index].0).unwrap_or_default()) as f32,
-			),
-			// Use the start or the end color if applicable
-			(v, _) => v,
+		// The inserted stop takes the color the gradient already has at that position, including any midpoint bias, so its appearance doesn't change
+		Some(self.stops.insert_stop_at(new_position, self.interpolation))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn black_to_white(midpoint: f64) -> GradientStops {
+		let mut stops = GradientStops::default();
+		stops.set_midpoint(0, midpoint);
+		stops
+	}
+
+	#[test]
+	fn midpoint_mixes_colors_evenly_at_its_position() {
+		let stops = black_to_white(0.25);
+		assert!((stops.evaluate(0.25).r() - 0.5).abs() < 1e-4);
+
+		// An even midpoint interpolates linearly
+		let even = black_to_white(DEFAULT_GRADIENT_MIDPOINT);
+		assert!((even.evaluate(0.25).r() - 0.25).abs() < 1e-4);
+	}
+
+	#[test]
+	fn biased_midpoint_differs_from_even() {
+		let biased = black_to_white(0.25);
+		let even = black_to_white(DEFAULT_GRADIENT_MIDPOINT);
+
+		let difference = (1..10)
+			.map(|step| (biased.evaluate(step as f64 / 10.).r() - even.evaluate(step as f64 / 10.).r()).abs())
+			.fold(0., f32::max);
+		assert!(difference > 0.1, "Expected a 0.25 midpoint to visibly change the gradient, the largest difference was {difference}");
+	}
+
+	#[test]
+	fn interpolated_stops_follow_evaluation() {
+		let stops = black_to_white(0.25);
+		let interpolated = GradientStops::new(stops.interpolated_stops());
+
+		for step in 0..=100 {
+			let t = step as f64 / 100.;
+			let (expected, actual) = (stops.evaluate(t).r(), interpolated.evaluate(t).r());
+			assert!((expected - actual).abs() < 0.02, "At {t}: expected {expected}, got {actual}");
+		}
+	}
+
+	fn red_to_green() -> GradientStops {
+		GradientStops::new(vec![(0., Color::RED), (1., Color::GREEN)])
+	}
+
+	/// The distance between two colors in OKLab, where a difference of about 0.02 is just noticeable.
+	fn delta_e(a: Color, b: Color) -> f32 {
+		let (a, b) = (srgb_to_oklab(a), srgb_to_oklab(b));
+		((a[0] - b[0]).powi(2) + (a[1] - b[1]).powi(2) + (a[2] - b[2]).powi(2)).sqrt()
+	}
+
+	#[test]
+	fn midpoint_depends_on_interpolation_space() {
+		let stops = red_to_green();
+		let srgb = stops.evaluate_in(0.5, GradientInterpolation::Srgb);
+		let oklab = stops.evaluate_in(0.5, GradientInterpolation::Oklab);
+
+		assert!((srgb.r() - 0.5).abs() < 1e-4 && (srgb.g() - 0.5).abs() < 1e-4);
+		// OKLab avoids the dark, muddy midpoint of mixing the gamma-encoded values
+		assert!(oklab.r() > 0.7 && oklab.g() > 0.6, "{oklab:?}");
+		assert!(delta_e(srgb, oklab) > 0.05);
+
+		// Going the longer way around the hue circle from red to green passes through blue
+		let longer = stops.evaluate_in(0.5, GradientInterpolation::OklchLongerHue);
+		assert!(longer.b() > 0.9 && longer.r() < 0.5, "{longer:?}");
+
+		// The ends are unchanged in every space
+		for interpolation in [GradientInterpolation::LinearRgb, GradientInterpolation::Oklab, GradientInterpolation::OklchShorterHue] {
+			assert!(delta_e(stops.evaluate_in(0., interpolation), Color::RED) < 1e-3);
+			assert!(delta_e(stops.evaluate_in(1., interpolation), Color::GREEN) < 1e-3);
+		}
+	}
+
+	#[test]
+	fn subdivided_stops_approximate_interpolation_space() {
+		let stops = red_to_green();
+		let spaces = [
+			GradientInterpolation::LinearRgb,
+			GradientInterpolation::Oklab,
+			GradientInterpolation::OklchShorterHue,
+			GradientInterpolation::OklchLongerHue,
+		];
+
+		for interpolation in spaces {
+			// Renderers mix the subdivided stops in sRGB, as SVG does
+			let approximation = GradientStops::new(stops.interpolated_stops_in(interpolation, 16));
+			for step in 0..=100 {
+				let t = step as f64 / 100.;
+				let difference = delta_e(stops.evaluate_in(t, interpolation), approximation.evaluate(t));
+				assert!(difference < 0.02, "{interpolation:?} at {t}: the approximation is off by {difference}");
+			}
+		}
+
+		// The default subdivisions are enough for a perceptually uniform space
+		let approximation = GradientStops::new(stops.interpolated_stops_in(GradientInterpolation::Oklab, DEFAULT_INTERPOLATION_SUBDIVISIONS as usize));
+		assert!((0..=100).all(|step| delta_e(stops.evaluate_in(step as f64 / 100., GradientInterpolation::Oklab), approximation.evaluate(step as f64 / 100.)) < 0.02));
+
+		// sRGB needs no extra stops
+		assert_eq!(stops.interpolated_stops_in(GradientInterpolation::Srgb, 16), stops.color_stops);
+	}
+
+	#[test]
+	fn inserting_and_removing_stops_keeps_midpoints_aligned() {
+		let mut stops = GradientStops::new(vec![(0., Color::BLACK), (0.5, Color::WHITE), (1., Color::BLACK)]);
+		stops.set_midpoint(0, 0.2);
+		stops.set_midpoint(1, 0.8);
+
+		stops.insert_stop(1, (0.25, Color::WHITE));
+		assert_eq!(stops.midpoints, vec![DEFAULT_GRADIENT_MIDPOINT, DEFAULT_GRADIENT_MIDPOINT, 0.8]);
+
+		stops.remove_stop(3);
+		assert_eq!(stops.midpoints, vec![DEFAULT_GRADIENT_MIDPOINT, DEFAULT_GRADIENT_MIDPOINT]);
+	}
+
+	#[test]
+	fn moving_a_stop_past_its_neighbors_reorders_it() {
+		let mut stops = GradientStops::new(vec![(0., Color::BLACK), (0.4, Color::RED), (0.6, Color::GREEN), (1., Color::WHITE)]);
+		stops.set_midpoint(2, 0.8);
+
+		// Moving a stop without passing a neighbor keeps its place and the midpoints
+		assert_eq!(stops.set_position(1, 0.5), 1);
+		assert_eq!(stops.midpoint(2), 0.8);
+
+		assert_eq!(stops.set_position(1, 0.9), 2);
+		assert_eq!(stops.color_stops, vec![(0., Color::BLACK), (0.6, Color::GREEN), (0.9, Color::RED), (1., Color::WHITE)]);
+		assert_eq!(stops.midpoints.len(), stops.len() - 1);
+
+		// Positions are kept within the gradient
+		assert_eq!(stops.set_position(2, -1.), 0);
+		assert_eq!(stops[0], (0., Color::RED));
+	}
+
+	#[test]
+	fn sorting_keeps_midpoints_with_their_segments() {
+		let mut stops = GradientStops {
+			color_stops: vec![(0., Color::BLACK), (0.2, Color::RED), (0.8, Color::GREEN), (0.6, Color::BLUE), (1., Color::WHITE)],
+			midpoints: vec![0.1, 0.2, 0.3, 0.4],
 		};
+		stops.sort();
 
-		// Compute the correct index to keep the positions in order
-		let mut index = 0;
-		while self.stops.0.len() > index && self.stops.0[index].0 <= new_position {
-			index += 1;
+		assert_eq!(
+			stops.color_stops,
+			vec![(0., Color::BLACK), (0.2, Color::RED), (0.6, Color::BLUE), (0.8, Color::GREEN), (1., Color::WHITE)]
+		);
+		// Only the first segment's stops are still neighbors
+		assert_eq!(stops.midpoints, vec![0.1, DEFAULT_GRADIENT_MIDPOINT, DEFAULT_GRADIENT_MIDPOINT, DEFAULT_GRADIENT_MIDPOINT]);
+
+		// Moving every stop without reordering them keeps all the midpoints
+		let mapped = stops.map_positions(|position| position / 2.);
+		assert_eq!(mapped.midpoints, stops.midpoints);
+		assert_eq!(mapped[4].0, 0.5);
+	}
+
+	#[test]
+	fn missing_midpoints_equal_default_midpoints() {
+		use std::hash::{BuildHasher, RandomState};
+
+		let implicit = GradientStops::new(vec![(0., Color::BLACK), (1., Color::WHITE)]);
+		let mut explicit = implicit.clone();
+		explicit.set_midpoint(0, DEFAULT_GRADIENT_MIDPOINT);
+		assert_ne!(implicit.midpoints, explicit.midpoints);
+
+		assert_eq!(implicit, explicit);
+		let hasher = RandomState::new();
+		assert_eq!(hasher.hash_one(&implicit), hasher.hash_one(&explicit));
+
+		// Gradients made from them are also equal and hash alike
+		let gradient = |stops: &GradientStops| Gradient {
+			stops: stops.clone(),
+			..Default::default()
+		};
+		assert_eq!(gradient(&implicit), gradient(&explicit));
+		assert_eq!(hasher.hash_one(gradient(&implicit)), hasher.hash_one(gradient(&explicit)));
+
+		explicit.set_midpoint(0, 0.3);
+		assert_ne!(implicit, explicit);
+	}
+
+	#[test]
+	fn distributing_spaces_stops_evenly_between_the_outer_ones() {
+		let mut stops = GradientStops::new(vec![(0., Color::BLACK), (0.2, Color::RED), (0.3, Color::GREEN), (0.4, Color::BLUE), (1., Color::WHITE)]);
+		stops.distribute(1, 4);
+		let expected = [0., 0.2, 0.2 + 0.8 / 3., 0.2 + 1.6 / 3., 1.];
+		assert!(stops.iter().zip(expected).all(|((position, _), expected)| (position - expected).abs() < 1e-10));
+
+		// A stop inserted between two others keeps the gradient's color there
+		let index = stops.insert_stop_at(0.1, GradientInterpolation::Srgb);
+		assert_eq!(index, 1);
+		assert_eq!(stops[1].1, Color::BLACK.lerp(&Color::RED, 0.5));
+	}
+
+	#[test]
+	fn reversing_mirrors_midpoints() {
+		let reversed = black_to_white(0.25).reversed();
+		assert!((reversed.midpoint(0) - 0.75).abs() < 1e-10);
+		assert!((reversed.evaluate(0.75).r() - 0.5).abs() < 1e-4);
+	}
+
+	#[test]
+	fn conic_position_sweeps_around_start() {
+		let (start, end) = (DVec2::new(0.5, 0.5), DVec2::new(1., 0.5));
+
+		// Positive angles turn from +X towards +Y, which is clockwise on screen
+		let quarter = GradientType::Conic.position_at(DVec2::new(0.5, 1.), start, end);
+		assert!((quarter - 0.25).abs() < 1e-10);
+		let three_quarters = GradientType::Conic.position_at(DVec2::new(0.5, 0.), start, end);
+		assert!((three_quarters - 0.75).abs() < 1e-10);
+
+		for position in [0.1, 0.4, 0.9] {
+			let point = GradientType::Conic.stop_point(position, start, end);
+			assert!((GradientType::Conic.position_at(point, start, end) - position).abs() < 1e-10);
 		}
+	}
+
+	#[test]
+	fn focal_point_shifts_radial_sampling() {
+		let mut gradient = Gradient {
+			gradient_type: GradientType::Radial,
+			start: DVec2::new(0.5, 0.5),
+			end: DVec2::new(1., 0.5),
+			..Default::default()
+		};
+		let centered = gradient.sample(DVec2::new(0.75, 0.5));
+
+		gradient.focal_offset = DVec2::new(0.25, 0.);
+		// The gradient radiates from the focal point, so the focal point takes the first stop's color
+		assert!(gradient.sample(DVec2::new(0.75, 0.5)).r() < 1e-6);
+		assert!(gradient.sample(DVec2::new(0.75, 0.5)) != centered);
+		// The end circle still takes the last stop's color
+		assert!((gradient.sample(DVec2::new(0., 0.5)).r() - 1.).abs() < 1e-6);
+	}
+
+	#[test]
+	fn focal_point_is_constrained_inside_end_circle() {
+		let (center, end) = (DVec2::ZERO, DVec2::new(10., 0.));
+		assert_eq!(Gradient::constrain_focal_point(center, end, DVec2::new(3., 4.)), DVec2::new(3., 4.));
+
+		let constrained = Gradient::constrain_focal_point(center, end, DVec2::new(0., 30.));
+		assert!(constrained.length() < 10.);
+		assert!(constrained.x.abs() < 1e-10 && constrained.y > 9.);
+	}
+
+	fn assert_points_eq(actual: DVec2, expected: DVec2) {
+		assert!(actual.abs_diff_eq(expected, 1e-10), "Expected {expected}, got {actual}");
+	}
+
+	#[test]
+	fn reversing_gradient_mirrors_stop_positions() {
+		let mut gradient = Gradient {
+			stops: GradientStops::new(vec![(0., Color::BLACK), (0.25, Color::RED), (1., Color::WHITE)]),
+			..Default::default()
+		};
+		gradient.reverse();
+
+		let stops: Vec<_> = gradient.stops.iter().copied().collect();
+		assert_eq!(stops, vec![(0., Color::WHITE), (0.75, Color::RED), (1., Color::BLACK)]);
+		// The axis itself is left alone
+		assert_eq!((gradient.start, gradient.end), (Gradient::default().start, Gradient::default().end));
+	}
 
-		let new_color = get_color(index - 1, new_position);
+	#[test]
+	fn linear_gradient_rotates_about_its_midpoint() {
+		let mut gradient = Gradient::default();
+		gradient.rotate_quarter_turn(DAffine2::IDENTITY);
+
+		// The axis pointing right now points down
+		assert_points_eq(gradient.start, DVec2::new(0.5, 0.));
+		assert_points_eq(gradient.end, DVec2::new(0.5, 1.));
+
+		for _ in 0..3 {
+			gradient.rotate_quarter_turn(DAffine2::IDENTITY);
+		}
+		assert_points_eq(gradient.start, Gradient::default().start);
+		assert_points_eq(gradient.end, Gradient::default().end);
+	}
+
+	#[test]
+	fn radial_and_conic_gradients_rotate_about_their_center() {
+		let mut radial = Gradient {
+			gradient_type: GradientType::Radial,
+			start: DVec2::new(0.5, 0.5),
+			end: DVec2::new(1., 0.5),
+			focal_offset: DVec2::new(0.2, 0.),
+			..Default::default()
+		};
+		radial.rotate_quarter_turn(DAffine2::IDENTITY);
+		assert_points_eq(radial.start, DVec2::new(0.5, 0.5));
+		assert_points_eq(radial.end, DVec2::new(0.5, 1.));
+		assert_points_eq(radial.focal_offset, DVec2::new(0., 0.2));
+
+		let mut conic = Gradient {
+			gradient_type: GradientType::Conic,
+			..radial.clone()
+		};
+		conic.rotate_quarter_turn(DAffine2::IDENTITY);
+		assert!((conic.start_angle() - std::f64::consts::PI).abs() < 1e-10);
+	}
+
+	#[test]
+	fn rotation_is_a_quarter_turn_in_the_transformed_space() {
+		// Gradients are stored relative to their layer's bounding box, so a wide box stretches the space the rotation has to be measured in
+		let transform = DAffine2::from_scale(DVec2::new(200., 50.));
+		let mut gradient = Gradient {
+			end: DVec2::new(0.5, 1.),
+			..Default::default()
+		};
+		let axis = transform.transform_vector2(gradient.end - gradient.start);
+		gradient.rotate_quarter_turn(transform);
+		let rotated_axis = transform.transform_vector2(gradient.end - gradient.start);
+
+		assert!(axis.dot(rotated_axis).abs() < 1e-10);
+		assert!((axis.length() - rotated_axis.length()).abs() < 1e-10);
+	}
+
+	#[test]
+	fn linear_gradient_fits_bounds_along_its_direction() {
+		let bounds = [DVec2::new(-1., -1.), DVec2::new(3., 1.)];
+		let mut horizontal = Gradient::default();
+		horizontal.fit_to_bounds(bounds, DAffine2::IDENTITY);
+		assert_points_eq(horizontal.start, DVec2::new(-1., 0.));
+		assert_points_eq(horizontal.end, DVec2::new(3., 0.));
+
+		// A diagonal gradient spans the box from corner to corner, and a reversed direction is kept
+		let mut diagonal = Gradient {
+			start: DVec2::new(5., 5.),
+			end: DVec2::new(4., 4.),
+			..Default::default()
+		};
+		diagonal.fit_to_bounds([DVec2::ZERO, DVec2::splat(2.)], DAffine2::IDENTITY);
+		assert_points_eq(diagonal.start, DVec2::new(2., 2.));
+		assert_points_eq(diagonal.end, DVec2::new(0., 0.));
+	}
+
+	#[test]
+	fn radial_and_conic_gradients_fit_bounds_from_their_center() {
+		let bounds = [DVec2::ZERO, DVec2::new(4., 2.)];
+		let mut radial = Gradient {
+			gradient_type: GradientType::Radial,
+			start: DVec2::ZERO,
+			end: DVec2::new(1., 0.),
+			focal_offset: DVec2::new(0.5, 0.),
+			..Default::default()
+		};
+		radial.fit_to_bounds(bounds, DAffine2::IDENTITY);
+		assert_points_eq(radial.start, DVec2::new(2., 1.));
+		// The radius reaches the corners, so the whole box is covered
+		let radius = 5_f64.sqrt();
+		assert_points_eq(radial.end, DVec2::new(2. + radius, 1.));
+		assert_points_eq(radial.focal_offset, DVec2::new(radius / 2., 0.));
+
+		let mut conic = Gradient {
+			gradient_type: GradientType::Conic,
+			start: DVec2::ZERO,
+			end: DVec2::new(0., -3.),
+			..Default::default()
+		};
+		conic.fit_to_bounds(bounds, DAffine2::IDENTITY);
+		assert_points_eq(conic.start, DVec2::new(2., 1.));
+		assert_points_eq(conic.end, DVec2::new(2., 0.));
+	}
+
+	#[test]
+	fn gradient_without_focal_point_deserializes_centered() {
+		let mut value = serde_json::to_value(Gradient::default()).unwrap();
+		let fields = value.as_object_mut().unwrap();
+		fields.remove("focal_offset");
+		fields.remove("focal_radius");
+
+		let gradient: Gradient = serde_json::from_value(value).unwrap();
+		assert_eq!(gradient.focal_point(), gradient.start);
+		assert_eq!(gradient.focal_radius, 0.);
+	}
+
+	#[test]
+	fn gradient_without_interpolation_deserializes_as_srgb() {
+		let mut value = serde_json::to_value(Gradient::default()).unwrap();
+		let fields = value.as_object_mut().unwrap();
+		fields.remove("interpolation");
+		fields.remove("interpolation_subdivisions");
+
+		let gradient: Gradient = serde_json::from_value(value).unwrap();
+		assert_eq!(gradient.interpolation, GradientInterpolation::Srgb);
+		assert_eq!(gradient.interpolation_subdivisions, DEFAULT_INTERPOLATION_SUBDIVISIONS);
+
+		let oklab = Gradient {
+			interpolation: GradientInterpolation::OklchLongerHue,
+			..Default::default()
+		};
+		assert_eq!(serde_json::from_value::<Gradient>(serde_json::to_value(&oklab).unwrap()).unwrap(), oklab);
+	}
 
-		// Insert the new stop
-		self.stops.0.insert(index, (new_position, new_color));
+	#[test]
+	fn stops_without_midpoints_deserialize() {
+		let legacy = serde_json::to_string(&GradientStops::default().color_stops).unwrap();
+		let stops: GradientStops = serde_json::from_str(&legacy).unwrap();
+		assert_eq!(stops, GradientStops::default());
 
-		Some(index)
+		let biased = black_to_white(0.25);
+		let round_tripped: GradientStops = serde_json::from_str(&serde_json::to_string(&biased).unwrap()).unwrap();
+		assert_eq!(round_tripped, biased);
 	}
 }
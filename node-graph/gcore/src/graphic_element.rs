@@ -1,5 +1,6 @@
 use crate::blending::AlphaBlending;
 use crate::bounds::BoundingBox;
+use crate::effects::LayerEffect;
 use crate::instances::{Instance, Instances};
 use crate::math::quad::Quad;
 use crate::raster::image::Image;
@@ -115,6 +116,8 @@ pub enum GraphicElement {
 	VectorData(VectorDataTable),
 	RasterDataCPU(RasterDataTable<CPU>),
 	RasterDataGPU(RasterDataTable<GPU>),
+	/// A layer effect like a drop shadow, drawn from the shape of a graphic.
+	Effect(Box<LayerEffect>),
 }
 
 impl Default for GraphicElement {
@@ -178,6 +181,7 @@ impl GraphicElement {
 			GraphicElement::GraphicGroup(data) => data.instance_ref_iter().all(|instance| instance.alpha_blending.clip),
 			GraphicElement::RasterDataCPU(data) => data.instance_ref_iter().all(|instance| instance.alpha_blending.clip),
 			GraphicElement::RasterDataGPU(data) => data.instance_ref_iter().all(|instance| instance.alpha_blending.clip),
+			GraphicElement::Effect(_) => false,
 		}
 	}
 
@@ -199,6 +203,7 @@ impl BoundingBox for GraphicElement {
 			GraphicElement::VectorData(vector_data) => vector_data.bounding_box(transform, include_stroke),
 			GraphicElement::RasterDataCPU(raster) => raster.bounding_box(transform, include_stroke),
 			GraphicElement::RasterDataGPU(raster) => raster.bounding_box(transform, include_stroke),
+			GraphicElement::Effect(effect) => effect.bounding_box(transform, include_stroke),
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.bounding_box(transform, include_stroke),
 		}
 	}
@@ -416,6 +421,7 @@ async fn flatten_vector(_: impl Ctx, group: GraphicGroupTable) -> VectorDataTabl
 								opacity: current_instance.alpha_blending.opacity * current_element.alpha_blending.opacity,
 								fill: current_element.alpha_blending.fill,
 								clip: current_element.alpha_blending.clip,
+								pass_through: current_element.alpha_blending.pass_through,
 							},
 							source_node_id: reference,
 						});
@@ -4,9 +4,12 @@ extern crate log;
 pub mod animation;
 pub mod blending_nodes;
 pub mod bounds;
+pub mod cancellation;
 pub mod consts;
 pub mod context;
+pub mod culling;
 pub mod debug;
+pub mod effects;
 pub mod extract_xy;
 pub mod generic;
 pub mod gradient;
@@ -23,6 +26,7 @@ pub mod registry;
 pub mod render_complexity;
 pub mod structural;
 pub mod text;
+pub mod time_dependency;
 pub mod transform;
 pub mod transform_nodes;
 pub mod uuid;
@@ -33,7 +33,10 @@ where
 			let cache = self.cache.clone();
 			Box::pin(async move {
 				let value = fut.await;
-				*cache.lock().unwrap() = Some((hash, value.clone()));
+				// A superseded evaluation may have produced an incomplete value, which mustn't be reused by later evaluations
+				if !crate::cancellation::is_cancelled() {
+					*cache.lock().unwrap() = Some((hash, value.clone()));
+				}
 				value
 			})
 		}
@@ -82,7 +85,9 @@ where
 			let cache = self.cache.clone();
 			Box::pin(async move {
 				let value = fut.await;
-				*cache.lock().unwrap() = Some(value.clone());
+				if !crate::cancellation::is_cancelled() {
+					*cache.lock().unwrap() = Some(value.clone());
+				}
 				value
 			})
 		}
@@ -135,6 +135,36 @@ impl Image<Color> {
 		}
 	}
 
+	/// Decodes an encoded image file while keeping the full precision of its pixels, rather than quantizing them to 8 bits per channel.
+	/// Integer formats such as 8-bit and 16-bit PNGs are treated as sRGB encoded with straight alpha,
+	/// while float formats such as OpenEXR and Radiance HDR are already linear with premultiplied alpha.
+	/// Float values outside of 0 to 1 are kept, so the highlights of HDR sources can be recovered by later adjustments.
+	pub fn from_encoded_image(encoded: &[u8]) -> Option<Self> {
+		let image = ::image::load_from_memory(encoded).ok()?;
+		let linear = matches!(image.color(), ::image::ColorType::Rgb32F | ::image::ColorType::Rgba32F);
+		let (width, height) = (image.width(), image.height());
+
+		let data = image
+			.into_rgba32f()
+			.pixels()
+			.map(|pixel| {
+				let [red, green, blue, alpha] = pixel.0;
+				if linear {
+					Color::from_rgbaf32_unchecked(red, green, blue, alpha)
+				} else {
+					Color::from_rgbaf32_unchecked(red, green, blue, 1.).to_linear_srgb().to_associated_alpha(alpha)
+				}
+			})
+			.collect();
+
+		Some(Image {
+			width,
+			height,
+			data,
+			base64_string: None,
+		})
+	}
+
 	pub fn to_png(&self) -> Vec<u8> {
 		use ::image::ImageEncoder;
 		let (data, width, height) = self.to_flat_u8();
@@ -507,4 +537,44 @@ mod test {
 
 		assert_eq!(image, deserialized);
 	}
+
+	#[test]
+	fn sixteen_bit_png_keeps_its_precision() {
+		use super::*;
+		use crate::Color;
+		use ::image::{ImageBuffer, ImageFormat, Rgba};
+
+		// Neighboring 16-bit values which would be quantized to the same 8-bit value
+		let values = [0x8000_u16, 0x8010, 0x8020, 0x8030];
+		let source = ImageBuffer::from_fn(values.len() as u32, 1, |x, _| Rgba([values[x as usize], values[x as usize], values[x as usize], u16::MAX]));
+		let mut png = std::io::Cursor::new(Vec::new());
+		source.write_to(&mut png, ImageFormat::Png).unwrap();
+
+		let image = Image::<Color>::from_encoded_image(png.get_ref()).unwrap();
+		assert_eq!((image.width, image.height), (4, 1));
+		for (color, value) in image.data.iter().zip(values) {
+			let gamma = color.to_gamma_srgb();
+			assert!((gamma.r() - value as f32 / u16::MAX as f32).abs() < 1e-5, "{gamma:?} should be close to {value}");
+			assert_eq!(gamma.a(), 1.);
+		}
+
+		// The 8-bit path collapses all of them into one value
+		let (quantized, _, _) = image.to_flat_u8();
+		assert!(quantized.chunks(4).all(|pixel| pixel == quantized[..4].as_ref()));
+	}
+
+	#[test]
+	fn float_exr_is_linear_premultiplied_and_unclamped() {
+		use super::*;
+		use crate::Color;
+		use ::image::{DynamicImage, ImageFormat, Rgba32FImage};
+
+		let source = Rgba32FImage::from_raw(2, 1, vec![0.25, 0.5, 4., 1., 0.5, 0.5, 0.5, 0.5]).unwrap();
+		let mut exr = std::io::Cursor::new(Vec::new());
+		DynamicImage::ImageRgba32F(source).write_to(&mut exr, ImageFormat::OpenExr).unwrap();
+
+		let image = Image::<Color>::from_encoded_image(exr.get_ref()).unwrap();
+		assert_eq!(image.data[0], Color::from_rgbaf32_unchecked(0.25, 0.5, 4., 1.));
+		assert_eq!(image.data[1], Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 0.5));
+	}
 }
@@ -1,3 +1,4 @@
+use crate::effects::LayerEffect;
 use crate::instances::Instances;
 use crate::raster_types::{CPU, GPU, Raster};
 use crate::vector::VectorData;
@@ -29,10 +30,17 @@ impl RenderComplexity for GraphicElement {
 			Self::VectorData(instances) => instances.render_complexity(),
 			Self::RasterDataCPU(instances) => instances.render_complexity(),
 			Self::RasterDataGPU(instances) => instances.render_complexity(),
+			Self::Effect(effect) => effect.render_complexity(),
 		}
 	}
 }
 
+impl RenderComplexity for LayerEffect {
+	fn render_complexity(&self) -> usize {
+		self.rasterized.render_complexity()
+	}
+}
+
 impl RenderComplexity for VectorData {
 	fn render_complexity(&self) -> usize {
 		self.segment_domain.ids().len()
@@ -29,3 +29,30 @@ impl From<TextAlign> for parley::Alignment {
 		}
 	}
 }
+
+/// Where text flowed along a path sits relative to it, given the path's direction of travel.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum TextPathSide {
+	/// The baseline of the text rests on the path.
+	#[default]
+	Above,
+	/// The text is vertically centered on the path.
+	Along,
+	/// The text hangs from the path by the top of its tallest letters.
+	Below,
+}
+
+/// What happens to text flowed along a path once it runs past either end of the path.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum TextPathOverflow {
+	/// Glyphs whose centers fall beyond the ends of the path are hidden.
+	#[default]
+	Clip,
+	/// Glyphs keep going in a straight line from the ends of the path.
+	#[label("Continue Straight")]
+	Continue,
+}
@@ -1,17 +1,48 @@
 use dyn_any::DynAny;
-use std::collections::HashMap;
+use skrifa::MetadataProvider;
+use std::collections::{BTreeMap, HashMap};
 
 /// A font type (storing font family and font style and an optional preview URL)
-#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Hash, PartialEq, Eq, DynAny, specta::Type)]
+#[derive(Debug, Clone, serde::Serialize, serde::Deserialize, PartialEq, DynAny, specta::Type)]
 pub struct Font {
 	#[serde(rename = "fontFamily")]
 	pub font_family: String,
 	#[serde(rename = "fontStyle", deserialize_with = "migrate_font_style")]
 	pub font_style: String,
+	/// Values for the design axes of a variable font, keyed by their four-letter tag (such as `wght` for weight).
+	/// Axes which aren't listed use the font's default value.
+	#[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+	pub variations: BTreeMap<String, f64>,
 }
 impl Font {
 	pub fn new(font_family: String, font_style: String) -> Self {
-		Self { font_family, font_style }
+		Self {
+			font_family,
+			font_style,
+			variations: BTreeMap::new(),
+		}
+	}
+
+	/// The same font family and style with all variation axes at their defaults, which is how the font file is identified in the [`FontCache`].
+	pub fn without_variations(&self) -> Self {
+		Self::new(self.font_family.clone(), self.font_style.clone())
+	}
+
+	pub fn with_variation(mut self, tag: impl Into<String>, value: f64) -> Self {
+		self.variations.insert(tag.into(), value);
+		self
+	}
+}
+impl Eq for Font {}
+impl std::hash::Hash for Font {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.font_family.hash(state);
+		self.font_style.hash(state);
+		self.variations.len().hash(state);
+		self.variations.iter().for_each(|(tag, value)| {
+			tag.hash(state);
+			value.to_bits().hash(state);
+		});
 	}
 }
 impl Default for Font {
@@ -19,6 +50,67 @@ impl Default for Font {
 		Self::new(crate::consts::DEFAULT_FONT_FAMILY.into(), crate::consts::DEFAULT_FONT_STYLE.into())
 	}
 }
+/// A design axis of a variable font, read from its `fvar` table.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontAxis {
+	pub tag: String,
+	pub name: String,
+	pub min: f64,
+	pub default: f64,
+	pub max: f64,
+}
+
+/// A named instance of a variable font, such as "Bold" or "Condensed Light", which is a preset of values for its axes.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FontNamedInstance {
+	pub name: String,
+	pub variations: BTreeMap<String, f64>,
+}
+
+/// The variation axes and named instances of a font, which are both empty for fonts that aren't variable.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct FontVariationInfo {
+	pub axes: Vec<FontAxis>,
+	pub named_instances: Vec<FontNamedInstance>,
+}
+
+impl FontVariationInfo {
+	pub fn from_font_data(data: &[u8]) -> Self {
+		let Ok(font) = skrifa::raw::FontRef::new(data) else { return Self::default() };
+		let name = |name_id| font.localized_strings(name_id).english_or_first().map(|name| name.to_string()).unwrap_or_default();
+
+		let axes: Vec<_> = font
+			.axes()
+			.iter()
+			.map(|axis| FontAxis {
+				tag: axis.tag().to_string(),
+				name: name(axis.name_id()),
+				min: axis.min_value() as f64,
+				default: axis.default_value() as f64,
+				max: axis.max_value() as f64,
+			})
+			.collect();
+		let named_instances = font
+			.named_instances()
+			.iter()
+			.map(|instance| FontNamedInstance {
+				name: name(instance.subfamily_name_id()),
+				variations: axes.iter().zip(instance.user_coords()).map(|(axis, value)| (axis.tag.clone(), value as f64)).collect(),
+			})
+			.collect();
+
+		Self { axes, named_instances }
+	}
+
+	/// The value of each axis for the given font, falling back to the axis default for those which it doesn't set.
+	pub fn axis_values(&self, font: &Font) -> BTreeMap<String, f64> {
+		self.axes
+			.iter()
+			.map(|axis| (axis.tag.clone(), font.variations.get(&axis.tag).copied().unwrap_or(axis.default)))
+			.collect()
+	}
+}
+
 /// A cache of all loaded font data and preview urls along with the default font (send from `init_app` in `editor_api.rs`)
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize, Default, PartialEq, DynAny)]
 pub struct FontCache {
@@ -30,8 +122,8 @@ pub struct FontCache {
 
 impl FontCache {
 	/// Returns the font family name if the font is cached, otherwise returns the fallback font family name if that is cached
-	pub fn resolve_font<'a>(&'a self, font: &'a Font) -> Option<&'a Font> {
-		if self.font_file_data.contains_key(font) {
+	pub fn resolve_font(&self, font: &Font) -> Option<&Font> {
+		if let Some((font, _)) = self.font_file_data.get_key_value(&font.without_variations()) {
 			Some(font)
 		} else {
 			self.font_file_data
@@ -47,18 +139,24 @@ impl FontCache {
 
 	/// Check if the font is already loaded
 	pub fn loaded_font(&self, font: &Font) -> bool {
-		self.font_file_data.contains_key(font)
+		self.font_file_data.contains_key(&font.without_variations())
 	}
 
 	/// Insert a new font into the cache
 	pub fn insert(&mut self, font: Font, perview_url: String, data: Vec<u8>) {
+		let font = font.without_variations();
 		self.font_file_data.insert(font.clone(), data);
 		self.preview_urls.insert(font, perview_url);
 	}
 
 	/// Gets the preview URL for showing in text field when live editing
 	pub fn get_preview_url(&self, font: &Font) -> Option<&String> {
-		self.preview_urls.get(font)
+		self.preview_urls.get(&font.without_variations())
+	}
+
+	/// Reads the variation axes and named instances of the font, if it's loaded
+	pub fn variation_info(&self, font: &Font) -> Option<FontVariationInfo> {
+		self.font_file_data.get(&font.without_variations()).map(|data| FontVariationInfo::from_font_data(data))
 	}
 }
 
@@ -79,3 +177,40 @@ fn migrate_font_style<'de, D: serde::Deserializer<'de>>(deserializer: D) -> Resu
 	use serde::Deserialize;
 	String::deserialize(deserializer).map(|name| if name == "Normal (400)" { "Regular (400)".to_string() } else { name })
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn fonts_without_variations_serialize_as_before() {
+		let font = Font::new("Source Sans Pro".into(), "Regular (400)".into());
+		let json = serde_json::to_string(&font).unwrap();
+		assert_eq!(json, r#"{"fontFamily":"Source Sans Pro","fontStyle":"Regular (400)"}"#);
+		assert_eq!(serde_json::from_str::<Font>(&json).unwrap(), font);
+
+		let variable = font.with_variation("wght", 650.);
+		let json = serde_json::to_string(&variable).unwrap();
+		assert_eq!(serde_json::from_str::<Font>(&json).unwrap(), variable);
+	}
+
+	#[test]
+	fn font_cache_ignores_variations_when_looking_up_font_data() {
+		let font = Font::new("Family".into(), "Regular (400)".into());
+		let mut cache = FontCache::default();
+		cache.insert(font.clone().with_variation("wdth", 80.), "url".into(), vec![1, 2, 3]);
+
+		let variable = font.clone().with_variation("wght", 900.);
+		assert!(cache.loaded_font(&font));
+		assert_eq!(cache.get(&variable), Some(&vec![1, 2, 3]));
+		assert_eq!(cache.get_preview_url(&variable).map(String::as_str), Some("url"));
+		assert_eq!(cache.resolve_font(&variable), Some(&font));
+	}
+
+	#[test]
+	fn unreadable_font_data_has_no_variation_axes() {
+		let info = FontVariationInfo::from_font_data(&[0; 16]);
+		assert_eq!(info, FontVariationInfo::default());
+		assert!(info.axis_values(&Font::default().with_variation("wght", 700.)).is_empty());
+	}
+}
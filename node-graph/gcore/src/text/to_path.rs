@@ -1,16 +1,21 @@
-use super::TextAlign;
+use super::{TextAlign, TextPathOverflow, TextPathSide};
 use crate::instances::Instance;
+use crate::vector::algorithms::bezpath_algorithms::eval_pathseg_euclidean;
+use crate::vector::algorithms::util::segment_tangent;
+use crate::vector::misc::point_to_dvec2;
 use crate::vector::{PointId, VectorData, VectorDataTable};
 use bezier_rs::{ManipulatorGroup, Subpath};
 use core::cell::RefCell;
 use glam::{DAffine2, DVec2};
+use kurbo::{Affine, BezPath, DEFAULT_ACCURACY, ParamCurve, PathSeg, Shape};
 use parley::fontique::Blob;
-use parley::{AlignmentOptions, FontContext, GlyphRun, Layout, LayoutContext, LineHeight, PositionedLayoutItem, StyleProperty};
+use parley::{AlignmentOptions, FontContext, FontSettings, GlyphRun, Layout, LayoutContext, Line, LineHeight, PositionedLayoutItem, StyleProperty};
 use skrifa::GlyphId;
 use skrifa::instance::{LocationRef, NormalizedCoord, Size};
 use skrifa::outline::{DrawSettings, OutlinePen};
 use skrifa::raw::FontRef as ReadFontsRef;
 use skrifa::{MetadataProvider, OutlineGlyph};
+use std::collections::BTreeMap;
 use std::sync::Arc;
 
 // Thread-local storage avoids expensive re-initialization of font and layout contexts
@@ -27,6 +32,8 @@ struct PathBuilder {
 	vector_table: VectorDataTable,
 	scale: f64,
 	id: PointId,
+	text_path: Option<TextOnPath>,
+	justification: Option<LineJustification>,
 }
 
 impl PathBuilder {
@@ -35,7 +42,16 @@ impl PathBuilder {
 	}
 
 	#[allow(clippy::too_many_arguments)]
-	fn draw_glyph(&mut self, glyph: &OutlineGlyph<'_>, size: f32, normalized_coords: &[NormalizedCoord], glyph_offset: DVec2, style_skew: Option<DAffine2>, skew: DAffine2, per_glyph_instances: bool) {
+	fn draw_glyph(
+		&mut self,
+		glyph: &OutlineGlyph<'_>,
+		size: f32,
+		normalized_coords: &[NormalizedCoord],
+		glyph_transform: DAffine2,
+		style_skew: Option<DAffine2>,
+		skew: DAffine2,
+		per_glyph_instances: bool,
+	) {
 		let location_ref = LocationRef::new(normalized_coords);
 		let settings = DrawSettings::unhinted(Size::new(size), location_ref);
 		glyph.draw(settings, self).unwrap();
@@ -53,11 +69,12 @@ impl PathBuilder {
 		if per_glyph_instances {
 			self.vector_table.push(Instance {
 				instance: VectorData::from_subpaths(core::mem::take(&mut self.glyph_subpaths), false),
-				transform: DAffine2::from_translation(glyph_offset),
+				transform: glyph_transform,
 				..Default::default()
 			});
 		} else {
-			for subpath in self.glyph_subpaths.drain(..) {
+			for mut subpath in self.glyph_subpaths.drain(..) {
+				subpath.apply_transform(glyph_transform);
 				// Unwrapping here is ok because `self.vector_table` is initialized with a single `VectorData`
 				self.vector_table.get_mut(0).unwrap().instance.append_subpath(subpath, false);
 			}
@@ -100,11 +117,19 @@ impl OutlinePen for PathBuilder {
 pub struct TypesettingConfig {
 	pub font_size: f64,
 	pub line_height_ratio: f64,
+	/// Overrides `line_height_ratio` with a fixed distance between baselines, in pixels.
+	#[serde(default)]
+	pub absolute_line_height: Option<f64>,
 	pub character_spacing: f64,
+	#[serde(default)]
+	pub word_spacing: f64,
 	pub max_width: Option<f64>,
 	pub max_height: Option<f64>,
 	pub tilt: f64,
 	pub align: TextAlign,
+	/// Spreads the extra space of justified lines between every pair of glyphs, rather than only the gaps between words.
+	#[serde(default)]
+	pub justify_letters: bool,
 }
 
 impl Default for TypesettingConfig {
@@ -112,24 +137,187 @@ impl Default for TypesettingConfig {
 		Self {
 			font_size: 24.,
 			line_height_ratio: 1.2,
+			absolute_line_height: None,
 			character_spacing: 0.,
+			word_spacing: 0.,
 			max_width: None,
 			max_height: None,
 			tilt: 0.,
 			align: TextAlign::default(),
+			justify_letters: false,
 		}
 	}
 }
 
+impl TypesettingConfig {
+	/// The distance between the baselines of consecutive lines, in pixels.
+	pub fn line_height(&self) -> f64 {
+		self.absolute_line_height.unwrap_or(self.font_size * self.line_height_ratio)
+	}
+}
+
+/// Settings for flowing text along a path instead of laying it out on straight lines.
+#[derive(PartialEq, Clone, Copy, Debug, Default, serde::Serialize, serde::Deserialize)]
+pub struct TextPathConfig {
+	/// The distance along the path where the start of the text is placed.
+	pub start_offset: f64,
+	pub side: TextPathSide,
+	pub overflow: TextPathOverflow,
+}
+
+/// A path parameterized by arc length, so glyphs can be spaced along it by their advances.
+struct ArcLengthPath {
+	segments: Vec<(PathSeg, f64)>,
+	length: f64,
+}
+
+impl ArcLengthPath {
+	fn new(bezpath: &BezPath) -> Option<Self> {
+		let segments = bezpath
+			.segments()
+			.map(|segment| (segment, segment.perimeter(DEFAULT_ACCURACY)))
+			.filter(|&(_, length)| length > f64::EPSILON)
+			.collect::<Vec<_>>();
+		let length = segments.iter().map(|&(_, length)| length).sum();
+
+		(!segments.is_empty()).then_some(Self { segments, length })
+	}
+
+	/// The position and unit tangent at the given distance from the start of the path.
+	/// Distances before the start or past the end continue in a straight line from that end.
+	fn sample(&self, distance: f64) -> (DVec2, DVec2) {
+		let tangent = |segment: PathSeg, t: f64| {
+			let tangent = segment_tangent(segment, t);
+			// Handles retracted into their anchors leave no derivative at the ends, so fall back to the direction of the chord
+			let chord = point_to_dvec2(segment.end()) - point_to_dvec2(segment.start());
+			let tangent = if tangent.length_squared() > f64::EPSILON { tangent } else { chord };
+			tangent.normalize_or(DVec2::X)
+		};
+
+		let (first, _) = self.segments[0];
+		if distance < 0. {
+			let direction = tangent(first, 0.);
+			return (point_to_dvec2(first.start()) + direction * distance, direction);
+		}
+
+		let mut start_distance = 0.;
+		for &(segment, length) in &self.segments {
+			if distance <= start_distance + length {
+				let t = eval_pathseg_euclidean(segment, (distance - start_distance) / length, DEFAULT_ACCURACY);
+				return (point_to_dvec2(segment.eval(t)), tangent(segment, t));
+			}
+			start_distance += length;
+		}
+
+		let (last, _) = self.segments[self.segments.len() - 1];
+		let direction = tangent(last, 1.);
+		(point_to_dvec2(last.end()) + direction * (distance - self.length), direction)
+	}
+}
+
+/// Places each glyph along a path, centered on the point at the arc length of the glyph's middle and rotated to follow the path's tangent there.
+struct TextOnPath {
+	path: ArcLengthPath,
+	config: TextPathConfig,
+	/// The baseline of the first line, which is the one that follows the path.
+	first_baseline: f64,
+	/// How far the baseline is moved across the path to put the text on the chosen side of it.
+	side_offset: f64,
+}
+
+impl TextOnPath {
+	/// The transform from the glyph's own space, with its origin on the baseline, to its place on the path.
+	/// Returns [`None`] if the glyph falls off either end of the path and is clipped.
+	fn glyph_transform(&self, glyph_offset: DVec2, advance: f64) -> Option<DAffine2> {
+		let middle = self.config.start_offset + glyph_offset.x + advance / 2.;
+		if self.config.overflow == TextPathOverflow::Clip && !(0. ..=self.path.length).contains(&middle) {
+			return None;
+		}
+
+		let (position, tangent) = self.path.sample(middle);
+		let across = glyph_offset.y - self.first_baseline + self.side_offset;
+		Some(DAffine2::from_angle_translation(tangent.to_angle(), position) * DAffine2::from_translation(DVec2::new(-advance / 2., across)))
+	}
+}
+
+/// Extra space added after glyphs to stretch a line of justified text across the full width of its box.
+struct LineJustification {
+	/// Whether each glyph of the line, in layout order, is followed by a gap that receives extra space.
+	gaps: Vec<bool>,
+	gap_width: f64,
+	next_glyph: usize,
+	offset: f64,
+}
+
+impl LineJustification {
+	/// Takes whether each glyph of the line is a space and where it ends. Returns [`None`] if the line is already full or has no gaps to stretch.
+	fn new(glyphs: &[(bool, f64)], max_width: f64, justify_letters: bool) -> Option<Self> {
+		// Trailing whitespace is trimmed, so the last visible glyph is the one that lands on the right edge
+		let last_visible = glyphs.iter().rposition(|&(is_space, _)| !is_space)?;
+		let free_space = max_width - glyphs[last_visible].1;
+		if free_space <= 0. {
+			return None;
+		}
+
+		let gaps: Vec<bool> = glyphs.iter().enumerate().map(|(index, &(is_space, _))| index < last_visible && (is_space || justify_letters)).collect();
+		let gap_count = gaps.iter().filter(|&&gap| gap).count();
+		if gap_count == 0 {
+			return None;
+		}
+
+		Some(Self {
+			gaps,
+			gap_width: free_space / gap_count as f64,
+			next_glyph: 0,
+			offset: 0.,
+		})
+	}
+
+	/// The horizontal offset of the next glyph in the line, which accumulates the extra space of the gaps before it.
+	fn next_offset(&mut self) -> f64 {
+		let offset = self.offset;
+		if self.gaps.get(self.next_glyph).copied().unwrap_or_default() {
+			self.offset += self.gap_width;
+		}
+		self.next_glyph += 1;
+		offset
+	}
+}
+
+/// Finds whether each glyph of the line is a space, along with the horizontal position where it ends.
+fn line_glyph_extents(line: &Line<'_, ()>) -> Vec<(bool, f64)> {
+	let mut glyphs = Vec::new();
+
+	for item in line.items() {
+		let PositionedLayoutItem::GlyphRun(glyph_run) = item else { continue };
+
+		let font = glyph_run.run().font();
+		let Ok(font_ref) = ReadFontsRef::from_index(font.data.as_ref(), font.index) else { continue };
+		let charmap = font_ref.charmap();
+		let space_glyphs = [' ', '\u{a0}'].map(|character| charmap.map(character));
+
+		let mut x = glyph_run.offset();
+		for glyph in glyph_run.glyphs() {
+			x += glyph.advance;
+			glyphs.push((space_glyphs.contains(&Some(GlyphId::from(glyph.id))), x as f64));
+		}
+	}
+
+	glyphs
+}
+
 fn render_glyph_run(glyph_run: &GlyphRun<'_, ()>, path_builder: &mut PathBuilder, tilt: f64, per_glyph_instances: bool) {
 	let mut run_x = glyph_run.offset();
 	let run_y = glyph_run.baseline();
 
 	let run = glyph_run.run();
 
+	// Glyphs drawn around their own origin rather than at their place in the layout
+	let local_glyphs = per_glyph_instances || path_builder.text_path.is_some();
+
 	// User-requested tilt applied around baseline to avoid vertical displacement
 	// Translation ensures rotation point is at the baseline, not origin
-	let skew = if per_glyph_instances {
+	let skew = if local_glyphs {
 		DAffine2::from_cols_array(&[1., 0., -tilt.to_radians().tan(), 1., 0., 0.])
 	} else {
 		DAffine2::from_translation(DVec2::new(0., run_y as f64))
@@ -142,7 +330,7 @@ fn render_glyph_run(glyph_run: &GlyphRun<'_, ()>, path_builder: &mut PathBuilder
 	// Font synthesis (e.g., synthetic italic) applied separately from user transforms
 	// This preserves the distinction between font styling and user transformations
 	let style_skew = synthesis.skew().map(|angle| {
-		if per_glyph_instances {
+		if local_glyphs {
 			DAffine2::from_cols_array(&[1., 0., -angle.to_radians().tan() as f64, 1., 0., 0.])
 		} else {
 			DAffine2::from_translation(DVec2::new(0., run_y as f64))
@@ -162,20 +350,34 @@ fn render_glyph_run(glyph_run: &GlyphRun<'_, ()>, path_builder: &mut PathBuilder
 	let outlines = font_ref.outline_glyphs();
 
 	for glyph in glyph_run.glyphs() {
-		let glyph_offset = DVec2::new((run_x + glyph.x) as f64, (run_y - glyph.y) as f64);
+		let justification_offset = path_builder.justification.as_mut().map_or(0., LineJustification::next_offset);
+		let glyph_offset = DVec2::new((run_x + glyph.x) as f64 + justification_offset, (run_y - glyph.y) as f64);
 		run_x += glyph.advance;
 
 		let glyph_id = GlyphId::from(glyph.id);
 		if let Some(glyph_outline) = outlines.get(glyph_id) {
-			if !per_glyph_instances {
-				path_builder.origin = glyph_offset;
-			}
-			path_builder.draw_glyph(&glyph_outline, font_size, &normalized_coords, glyph_offset, style_skew, skew, per_glyph_instances);
+			let glyph_transform = match &path_builder.text_path {
+				Some(text_path) => match text_path.glyph_transform(glyph_offset, glyph.advance as f64) {
+					Some(glyph_transform) => glyph_transform,
+					None => continue,
+				},
+				None if per_glyph_instances => DAffine2::from_translation(glyph_offset),
+				None => {
+					path_builder.origin = glyph_offset;
+					DAffine2::IDENTITY
+				}
+			};
+			path_builder.draw_glyph(&glyph_outline, font_size, &normalized_coords, glyph_transform, style_skew, skew, per_glyph_instances);
 		}
 	}
 }
 
-fn layout_text(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingConfig) -> Option<Layout<()>> {
+/// Formats variation axis values like the CSS `font-variation-settings` property, which is how they're given to the shaper.
+fn font_variation_settings(variations: &BTreeMap<String, f64>) -> String {
+	variations.iter().map(|(tag, value)| format!("\"{tag}\" {value}")).collect::<Vec<_>>().join(", ")
+}
+
+fn layout_text(str: &str, font_data: Option<Blob<u8>>, font_variations: &BTreeMap<String, f64>, typesetting: TypesettingConfig) -> Option<Layout<()>> {
 	let font_cx = FONT_CONTEXT.with(Clone::clone);
 	let mut font_cx = font_cx.borrow_mut();
 	let layout_cx = LAYOUT_CONTEXT.with(Clone::clone);
@@ -194,22 +396,86 @@ fn layout_text(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingC
 
 	builder.push_default(StyleProperty::FontSize(typesetting.font_size as f32));
 	builder.push_default(StyleProperty::LetterSpacing(typesetting.character_spacing as f32));
+	builder.push_default(StyleProperty::WordSpacing(typesetting.word_spacing as f32));
 	builder.push_default(StyleProperty::FontStack(parley::FontStack::Single(parley::FontFamily::Named(std::borrow::Cow::Owned(font_family)))));
-	builder.push_default(LineHeight::FontSizeRelative(typesetting.line_height_ratio as f32));
+	if !font_variations.is_empty() {
+		builder.push_default(StyleProperty::FontVariations(FontSettings::Source(std::borrow::Cow::Owned(font_variation_settings(font_variations)))));
+	}
+	builder.push_default(match typesetting.absolute_line_height {
+		Some(line_height) => LineHeight::Absolute(line_height as f32),
+		None => LineHeight::FontSizeRelative(typesetting.line_height_ratio as f32),
+	});
 
 	let mut layout: Layout<()> = builder.build(str);
 
 	layout.break_all_lines(typesetting.max_width.map(|mw| mw as f32));
-	layout.align(typesetting.max_width.map(|max_w| max_w as f32), typesetting.align.into(), AlignmentOptions::default());
+	// Justified lines are stretched as their glyphs are placed, so the last line and lines ending in a hard break can be left-aligned
+	let alignment = match typesetting.align {
+		TextAlign::JustifyLeft => parley::Alignment::Left,
+		align => align.into(),
+	};
+	layout.align(typesetting.max_width.map(|max_w| max_w as f32), alignment, AlignmentOptions::default());
 
 	Some(layout)
 }
 
-pub fn to_path(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingConfig, per_glyph_instances: bool) -> VectorDataTable {
-	let Some(layout) = layout_text(str, font_data, typesetting) else {
+pub fn to_path(str: &str, font_data: Option<Blob<u8>>, font_variations: &BTreeMap<String, f64>, typesetting: TypesettingConfig, per_glyph_instances: bool) -> VectorDataTable {
+	let Some(layout) = layout_text(str, font_data, font_variations, typesetting) else {
 		return VectorDataTable::new(VectorData::default());
 	};
 
+	layout_to_path(str, &layout, typesetting, None, per_glyph_instances)
+}
+
+/// Lays out the text like [`to_path`], but flows its first line along the first subpath of `path` with the following lines stacked beside it.
+/// Falls back to straight lines if the path has no length.
+pub fn to_path_on_path(
+	str: &str,
+	font_data: Option<Blob<u8>>,
+	font_variations: &BTreeMap<String, f64>,
+	typesetting: TypesettingConfig,
+	path: &VectorDataTable,
+	config: TextPathConfig,
+	per_glyph_instances: bool,
+) -> VectorDataTable {
+	let arc_length_path = path.instance_ref_iter().find_map(|instance| {
+		let mut bezpath = instance.instance.stroke_bezpath_iter().next()?;
+		bezpath.apply_affine(Affine::new(instance.transform.to_cols_array()));
+		ArcLengthPath::new(&bezpath)
+	});
+	let Some(arc_length_path) = arc_length_path else {
+		return to_path(str, font_data, font_variations, typesetting, per_glyph_instances);
+	};
+
+	let Some(layout) = layout_text(str, font_data, font_variations, typesetting) else {
+		return VectorDataTable::new(VectorData::default());
+	};
+
+	let (first_baseline, ascent, descent) = layout
+		.lines()
+		.next()
+		.map(|line| {
+			let metrics = line.metrics();
+			(metrics.baseline as f64, metrics.ascent as f64, metrics.descent as f64)
+		})
+		.unwrap_or_default();
+	let side_offset = match config.side {
+		TextPathSide::Above => 0.,
+		TextPathSide::Along => (ascent - descent) / 2.,
+		TextPathSide::Below => ascent,
+	};
+
+	let text_path = TextOnPath {
+		path: arc_length_path,
+		config,
+		first_baseline,
+		side_offset,
+	};
+
+	layout_to_path(str, &layout, typesetting, Some(text_path), per_glyph_instances)
+}
+
+fn layout_to_path(str: &str, layout: &Layout<()>, typesetting: TypesettingConfig, text_path: Option<TextOnPath>, per_glyph_instances: bool) -> VectorDataTable {
 	let mut path_builder = PathBuilder {
 		current_subpath: Subpath::new(Vec::new(), false),
 		glyph_subpaths: Vec::new(),
@@ -221,9 +487,18 @@ pub fn to_path(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingC
 		scale: layout.scale() as f64,
 		id: PointId::ZERO,
 		origin: DVec2::default(),
+		text_path,
+		justification: None,
 	};
 
-	for line in layout.lines() {
+	for (line_index, line) in layout.lines().enumerate() {
+		let is_last_line = line_index + 1 == layout.len();
+		let ends_in_hard_break = str.get(line.text_range()).is_some_and(|text| text.ends_with(['\n', '\r', '\u{2028}', '\u{2029}']));
+		path_builder.justification = match (typesetting.align, typesetting.max_width) {
+			(TextAlign::JustifyLeft, Some(max_width)) if !is_last_line && !ends_in_hard_break => LineJustification::new(&line_glyph_extents(&line), max_width, typesetting.justify_letters),
+			_ => None,
+		};
+
 		for item in line.items() {
 			if let PositionedLayoutItem::GlyphRun(glyph_run) = item {
 				render_glyph_run(&glyph_run, &mut path_builder, typesetting.tilt, per_glyph_instances);
@@ -238,14 +513,16 @@ pub fn to_path(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingC
 	path_builder.vector_table
 }
 
-pub fn bounding_box(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingConfig, for_clipping_test: bool) -> DVec2 {
+pub fn bounding_box(str: &str, font_data: Option<Blob<u8>>, font_variations: &BTreeMap<String, f64>, typesetting: TypesettingConfig, for_clipping_test: bool) -> DVec2 {
 	if !for_clipping_test {
 		if let (Some(max_height), Some(max_width)) = (typesetting.max_height, typesetting.max_width) {
 			return DVec2::new(max_width, max_height);
 		}
 	}
 
-	let Some(layout) = layout_text(str, font_data, typesetting) else { return DVec2::ZERO };
+	let Some(layout) = layout_text(str, font_data, font_variations, typesetting) else {
+		return DVec2::ZERO;
+	};
 
 	DVec2::new(layout.full_width() as f64, layout.height() as f64)
 }
@@ -254,8 +531,223 @@ pub fn load_font(data: &[u8]) -> Blob<u8> {
 	Blob::new(Arc::new(data.to_vec()))
 }
 
-pub fn lines_clipping(str: &str, font_data: Option<Blob<u8>>, typesetting: TypesettingConfig) -> bool {
+pub fn lines_clipping(str: &str, font_data: Option<Blob<u8>>, font_variations: &BTreeMap<String, f64>, typesetting: TypesettingConfig) -> bool {
 	let Some(max_height) = typesetting.max_height else { return false };
-	let bounds = bounding_box(str, font_data, typesetting, true);
+	let bounds = bounding_box(str, font_data, font_variations, typesetting, true);
 	max_height < bounds.y
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const RADIUS: f64 = 100.;
+
+	fn text_on_path(subpath: Subpath<PointId>, config: TextPathConfig, side_offset: f64) -> TextOnPath {
+		let bezpath = VectorData::from_subpath(subpath).stroke_bezpath_iter().next().unwrap();
+		TextOnPath {
+			path: ArcLengthPath::new(&bezpath).unwrap(),
+			config,
+			first_baseline: 0.,
+			side_offset,
+		}
+	}
+
+	fn circle(config: TextPathConfig) -> TextOnPath {
+		text_on_path(Subpath::new_ellipse(DVec2::splat(-RADIUS), DVec2::splat(RADIUS)), config, 0.)
+	}
+
+	/// Where the middle of a glyph's baseline ends up on the path.
+	fn glyph_center(text_path: &TextOnPath, x: f64, advance: f64) -> Option<DVec2> {
+		text_path
+			.glyph_transform(DVec2::new(x, 0.), advance)
+			.map(|transform| transform.transform_point2(DVec2::new(advance / 2., 0.)))
+	}
+
+	#[test]
+	fn circle_path_length_matches_circumference() {
+		let text_path = circle(TextPathConfig::default());
+		assert!((text_path.path.length - std::f64::consts::TAU * RADIUS).abs() < 0.1, "Length was {}", text_path.path.length);
+	}
+
+	#[test]
+	fn glyphs_on_circle_are_spaced_by_arc_length() {
+		let text_path = circle(TextPathConfig {
+			start_offset: 10.,
+			..Default::default()
+		});
+		let advance = 12.;
+
+		let centers = (0..20).map(|index| glyph_center(&text_path, index as f64 * advance, advance).unwrap()).collect::<Vec<_>>();
+		for center in &centers {
+			assert!((center.length() - RADIUS).abs() < 0.05, "Glyph center {center} is off the circle");
+		}
+		for pair in centers.windows(2) {
+			let arc_length = pair[0].angle_to(pair[1]).abs() * RADIUS;
+			assert!((arc_length - advance).abs() < 0.05, "Adjacent glyphs were {arc_length} apart along the circle");
+		}
+	}
+
+	#[test]
+	fn glyphs_on_circle_follow_tangent() {
+		let text_path = circle(TextPathConfig::default());
+
+		for index in 0..10 {
+			let transform = text_path.glyph_transform(DVec2::new(index as f64 * 40., 0.), 10.).unwrap();
+			let center = transform.transform_point2(DVec2::new(5., 0.));
+			let baseline_direction = transform.transform_vector2(DVec2::X);
+			assert!(baseline_direction.normalize().dot(center.normalize()).abs() < 1e-3, "Glyph at {center} isn't rotated to the tangent");
+			assert!((baseline_direction.length() - 1.).abs() < 1e-9, "Glyph was scaled");
+		}
+	}
+
+	#[test]
+	fn overflow_clips_or_continues_straight() {
+		let line = || Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(100., 0.)], false);
+
+		let clipped = text_on_path(line(), TextPathConfig::default(), 0.);
+		assert!(glyph_center(&clipped, 90., 10.).is_some());
+		assert!(glyph_center(&clipped, 100., 10.).is_none());
+		assert!(glyph_center(&clipped, -20., 10.).is_none());
+
+		let continued = text_on_path(
+			line(),
+			TextPathConfig {
+				overflow: TextPathOverflow::Continue,
+				..Default::default()
+			},
+			0.,
+		);
+		assert!(glyph_center(&continued, 100., 10.).unwrap().abs_diff_eq(DVec2::new(105., 0.), 1e-6));
+		assert!(glyph_center(&continued, -20., 10.).unwrap().abs_diff_eq(DVec2::new(-15., 0.), 1e-6));
+	}
+
+	#[test]
+	fn side_offset_moves_glyphs_across_the_path() {
+		let line = Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(100., 0.)], false);
+		let below = text_on_path(line, TextPathConfig::default(), 8.);
+
+		// Positive offsets are toward the right of the path's direction, which is down for a path drawn left to right
+		assert!(glyph_center(&below, 0., 10.).unwrap().abs_diff_eq(DVec2::new(5., 8.), 1e-6));
+	}
+
+	#[test]
+	fn absolute_line_height_overrides_ratio() {
+		let ratio = TypesettingConfig {
+			font_size: 20.,
+			line_height_ratio: 1.5,
+			..Default::default()
+		};
+		assert_eq!(ratio.line_height(), 30.);
+
+		let absolute = TypesettingConfig {
+			absolute_line_height: Some(42.),
+			..ratio
+		};
+		assert_eq!(absolute.line_height(), 42.);
+	}
+
+	#[test]
+	fn typesetting_saved_without_spacing_options_deserializes_with_defaults() {
+		let json = r#"{"font_size":24.0,"line_height_ratio":1.2,"character_spacing":2.0,"max_width":null,"max_height":null,"tilt":0.0,"align":"Left"}"#;
+		let typesetting: TypesettingConfig = serde_json::from_str(json).unwrap();
+		assert_eq!(typesetting.word_spacing, 0.);
+		assert_eq!(typesetting.absolute_line_height, None);
+		assert_eq!(typesetting.character_spacing, 2.);
+	}
+
+	#[test]
+	fn font_variations_are_formatted_as_css_settings() {
+		let variations = BTreeMap::from([("wght".to_string(), 900.), ("wdth".to_string(), 87.5)]);
+		assert_eq!(font_variation_settings(&variations), r#""wdth" 87.5, "wght" 900"#);
+	}
+
+	fn glyphs(text: &str) -> Vec<(bool, f64)> {
+		text.chars().enumerate().map(|(index, character)| (character == ' ', (index + 1) as f64 * 10.)).collect()
+	}
+
+	fn justified_offsets(text: &str, max_width: f64, justify_letters: bool) -> Option<Vec<f64>> {
+		let mut justification = LineJustification::new(&glyphs(text), max_width, justify_letters)?;
+		Some(text.chars().map(|_| justification.next_offset()).collect())
+	}
+
+	#[test]
+	fn justification_stretches_word_gaps_to_full_width() {
+		let offsets = justified_offsets("ab cd ef", 100., false).unwrap();
+		// The line is 80 wide, so its two word gaps each get 10 more
+		assert_eq!(offsets, vec![0., 0., 0., 10., 10., 10., 20., 20.]);
+
+		let right_edge = glyphs("ab cd ef").last().unwrap().1 + offsets.last().unwrap();
+		assert_eq!(right_edge, 100.);
+	}
+
+	#[test]
+	fn justification_trims_trailing_whitespace() {
+		// The trailing spaces neither count toward the line's width nor receive extra space
+		let offsets = justified_offsets("ab cd  ", 70., false).unwrap();
+		assert_eq!(offsets, vec![0., 0., 0., 20., 20., 20., 20.]);
+	}
+
+	#[test]
+	fn justification_uses_letter_gaps_only_when_enabled() {
+		assert!(justified_offsets("abcd", 60., false).is_none());
+		assert_eq!(justified_offsets("abcd", 70., true).unwrap(), vec![0., 10., 20., 30.]);
+	}
+
+	#[test]
+	fn full_or_overflowing_lines_are_not_justified() {
+		assert!(justified_offsets("ab cd", 50., false).is_none());
+		assert!(justified_offsets("ab cd", 40., false).is_none());
+		assert!(justified_offsets("   ", 100., false).is_none());
+	}
+
+	/// A font with a single `I` glyph, a bar which is 100 units wide at the default weight of 400.
+	/// Its `wght` axis spans from 100, where the bar and its advance are 50 units narrower, to 900, where they're 200 units wider.
+	const VARIABLE_WEIGHT_FIXTURE: &[u8] = include_bytes!("fixtures/variable-weight.ttf");
+
+	/// The horizontal position and outline width of each glyph, drawn at one pixel per font unit.
+	fn glyph_extents(text: &str, variations: &[(&str, f64)]) -> Vec<(f64, f64)> {
+		let variations = variations.iter().map(|&(tag, value)| (tag.to_string(), value)).collect();
+		let typesetting = TypesettingConfig {
+			font_size: 1000.,
+			..Default::default()
+		};
+
+		to_path(text, Some(load_font(VARIABLE_WEIGHT_FIXTURE)), &variations, typesetting, true)
+			.instance_ref_iter()
+			.filter_map(|glyph| {
+				let [min, max] = glyph.instance.bounding_box()?;
+				Some((glyph.transform.translation.x, max.x - min.x))
+			})
+			.collect()
+	}
+
+	fn assert_close(actual: f64, expected: f64) {
+		assert!((actual - expected).abs() < 0.01, "{actual} should be close to {expected}");
+	}
+
+	#[test]
+	fn weight_axis_changes_glyph_outlines() {
+		for (weight, width) in [(100., 50.), (400., 100.), (900., 300.)] {
+			let glyphs = glyph_extents("I", &[("wght", weight)]);
+			assert_eq!(glyphs.len(), 1);
+			assert_close(glyphs[0].1, width);
+		}
+
+		// Without any variations, the font's default instance is drawn
+		assert_eq!(glyph_extents("I", &[]), glyph_extents("I", &[("wght", 400.)]));
+	}
+
+	#[test]
+	fn weight_axis_changes_glyph_advances() {
+		let advance = |weight| {
+			let glyphs = glyph_extents("II", &[("wght", weight)]);
+			assert_eq!(glyphs.len(), 2);
+			glyphs[1].0 - glyphs[0].0
+		};
+
+		assert_close(advance(100.), 150.);
+		assert_close(advance(400.), 200.);
+		assert_close(advance(900.), 400.);
+	}
+}
@@ -0,0 +1,65 @@
+use std::cell::Cell;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+
+thread_local! {
+	static READ_TIME: Cell<bool> = const { Cell::new(false) };
+}
+
+/// Records that the output of the evaluation which is currently running on this thread depends on the time it was evaluated at.
+///
+/// Nodes which read the real time or animation time from their context call this, so the outputs of the nodes downstream of them
+/// are evaluated again whenever the time changes, while the outputs of all other nodes can be reused.
+pub fn mark_time_read() {
+	READ_TIME.with(|read_time| read_time.set(true));
+}
+
+/// Keeps track of whether the nodes evaluated by the future read the time, whenever it is polled.
+///
+/// The future returns whether the time was read along with its output. Reads within it aren't seen by any enclosing [`track`], so
+/// nodes evaluated by the future whose outputs are reused instead of evaluated again must call [`mark_time_read`] themselves.
+pub fn track<F: Future>(future: F) -> Tracked<F> {
+	Tracked {
+		read_time: false,
+		future: Box::pin(future),
+	}
+}
+
+/// A future which records whether the nodes it evaluates read the time, returned by [`track`].
+pub struct Tracked<F> {
+	read_time: bool,
+	future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Tracked<F> {
+	type Output = (F::Output, bool);
+
+	fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+		let this = &mut *self;
+		let outer = READ_TIME.with(|read_time| read_time.replace(false));
+		let result = this.future.as_mut().poll(cx);
+		this.read_time |= READ_TIME.with(|read_time| read_time.replace(outer));
+		result.map(|output| (output, this.read_time))
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	#[tokio::test]
+	async fn reads_are_only_seen_by_the_innermost_tracked_future() {
+		let (inner_read_time, outer_read_time) = track(async { track(async { mark_time_read() }).await.1 }).await;
+		assert!(inner_read_time);
+		assert!(!outer_read_time);
+
+		// Reads made after the inner future finished belong to the outer one
+		let ((), outer_read_time) = track(async {
+			track(async {}).await;
+			mark_time_read();
+		})
+		.await;
+		assert!(outer_read_time);
+	}
+}
@@ -84,6 +84,27 @@ pub enum RenderQuality {
 	/// Render at full quality
 	Full,
 }
+
+impl RenderQuality {
+	/// The fraction of the full resolution which raster content should be evaluated at, which is below 1 only for a reduced [`RenderQuality::Scale`].
+	pub fn scale_factor(&self) -> f64 {
+		match *self {
+			Self::Scale(scale) if scale > 0. => (scale as f64).min(1.),
+			_ => 1.,
+		}
+	}
+}
+
+impl std::hash::Hash for RenderQuality {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		core::mem::discriminant(self).hash(state);
+		match self {
+			Self::Scale(value) | Self::Probability(value) => value.to_bits().hash(state),
+			Self::Preview | Self::Full => {}
+		}
+	}
+}
+
 #[derive(Debug, Clone, Copy, dyn_any::DynAny, PartialEq, serde::Serialize, serde::Deserialize)]
 pub struct Footprint {
 	/// Inverse of the transform which will be applied to the node output during the rendering process
@@ -92,6 +113,10 @@ pub struct Footprint {
 	pub resolution: glam::UVec2,
 	/// Quality of the render, this may be used by caching nodes to decide if the cached render is sufficient
 	pub quality: RenderQuality,
+	/// Extra pixels of raster data to compute beyond the edges of the viewport, for the nodes downstream with a spatial kernel (like a blur) to sample from.
+	/// Raster nodes which only produce the visible part of an image include this margin around it so it matches the same part of the whole image.
+	#[serde(default)]
+	pub apron: u32,
 }
 
 impl Default for Footprint {
@@ -105,6 +130,7 @@ impl Footprint {
 		transform: DAffine2::IDENTITY,
 		resolution: glam::UVec2::new(1920, 1080),
 		quality: RenderQuality::Full,
+		apron: 0,
 	};
 
 	pub const BOUNDLESS: Self = Self {
@@ -114,6 +140,7 @@ impl Footprint {
 		},
 		resolution: glam::UVec2::new(0, 0),
 		quality: RenderQuality::Full,
+		apron: 0,
 	};
 
 	pub fn viewport_bounds_in_local_space(&self) -> AxisAlignedBbox {
@@ -141,7 +168,10 @@ impl From<()> for Footprint {
 impl std::hash::Hash for Footprint {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
 		self.transform.to_cols_array().iter().for_each(|x| x.to_le_bytes().hash(state));
-		self.resolution.hash(state)
+		self.resolution.hash(state);
+		// Distinguish reduced quality renders, so cached results from an interactive preview aren't reused for the full quality render that follows it
+		self.quality.hash(state);
+		self.apron.hash(state)
 	}
 }
 
@@ -3,6 +3,7 @@ use super::poisson_disk::poisson_disk_sample;
 use super::util::segment_tangent;
 use crate::vector::algorithms::offset_subpath::MAX_ABSOLUTE_DIFFERENCE;
 use crate::vector::misc::{PointSpacingType, dvec2_to_point, point_to_dvec2};
+use crate::vector::style::miter_length_ratio;
 use glam::{DMat2, DVec2};
 use kurbo::{BezPath, CubicBez, DEFAULT_ACCURACY, Line, ParamCurve, ParamCurveDeriv, PathEl, PathSeg, Point, QuadBez, Rect, Shape};
 use std::f64::consts::{FRAC_PI_2, PI};
@@ -369,9 +370,8 @@ pub fn clip_simple_bezpaths(bezpath1: &BezPath, bezpath2: &BezPath) -> Option<(B
 
 /// Returns the [`PathEl`] that is needed for a miter join if it is possible.
 ///
-/// `miter_limit` defines a limit for the ratio between the miter length and the stroke width.
-/// Alternatively, this can be interpreted as limiting the angle that the miter can form.
-/// When the limit is exceeded, no [`PathEl`] will be returned.
+/// `miter_limit` defines a limit for the ratio between the miter length and the stroke width (see [`miter_length_ratio`]).
+/// When the limit is exceeded, no [`PathEl`] will be returned so the join falls back to a bevel, as SVG specifies.
 /// This value should be greater than 0. If not, the default of 4 will be used.
 pub fn miter_line_join(bezpath1: &BezPath, bezpath2: &BezPath, miter_limit: Option<f64>) -> Option<[PathEl; 2]> {
 	let miter_limit = match miter_limit {
@@ -391,9 +391,7 @@ pub fn miter_line_join(bezpath1: &BezPath, bezpath2: &BezPath, miter_limit: Opti
 		return None;
 	}
 
-	let angle = (in_tangent * -1.).angle_to(out_tangent).abs();
-
-	if angle.to_degrees() < miter_limit {
+	if miter_length_ratio(in_tangent, out_tangent) > miter_limit {
 		return None;
 	}
 
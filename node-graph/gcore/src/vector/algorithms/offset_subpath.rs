@@ -1,6 +1,9 @@
 use super::bezpath_algorithms::{clip_simple_bezpaths, miter_line_join, round_line_join};
-use crate::vector::misc::point_to_dvec2;
-use kurbo::{BezPath, Join, ParamCurve, PathEl, PathSeg};
+use crate::vector::misc::{OpenPathOffset, point_to_dvec2};
+use crate::vector::style::{FillRule, StrokeJoin};
+use crate::vector::{VectorData, VectorDataExt};
+use glam::DAffine2;
+use kurbo::{Affine, BezPath, Join, ParamCurve, PathEl, PathSeg, Point, Shape, Vec2};
 
 /// Value to control smoothness and mathematical accuracy to offset a cubic Bezier.
 const CUBIC_REGULARIZATION_ACCURACY: f64 = 0.5;
@@ -8,6 +11,8 @@ const CUBIC_REGULARIZATION_ACCURACY: f64 = 0.5;
 const CUBIC_TO_BEZPATH_ACCURACY: f64 = 1e-3;
 /// Constant used to determine if `f64`s are equivalent.
 pub const MAX_ABSOLUTE_DIFFERENCE: f64 = 1e-7;
+/// How far to either side of an outline the fill is checked, to find which side of it is inside the shape.
+const SIDE_TEST_DISTANCE: f64 = 1e-3;
 
 /// Reduces the segments of the bezpath into simple subcurves, then offset each subcurve a set `distance` away.
 /// The intersections of segments of the subpath are joined using the method specified by the `join` argument.
@@ -135,3 +140,101 @@ pub fn offset_bezpath(bezpath: &BezPath, distance: f64, join: Join, miter_limit:
 
 	offset_bezpath
 }
+
+fn kurbo_join(join: StrokeJoin) -> Join {
+	match join {
+		StrokeJoin::Miter => Join::Miter,
+		StrokeJoin::Bevel => Join::Bevel,
+		StrokeJoin::Round => Join::Round,
+	}
+}
+
+/// Splits the subpaths of the vector data into the closed and open ones, with the transform applied.
+fn transformed_subpaths(vector_data: &VectorData, transform: DAffine2) -> (Vec<BezPath>, Vec<BezPath>) {
+	let transform = Affine::new(transform.to_cols_array());
+	vector_data
+		.stroke_bezpath_iter()
+		.map(|mut bezpath| {
+			bezpath.apply_affine(transform);
+			bezpath
+		})
+		.partition(|bezpath| bezpath.elements().last() == Some(&PathEl::ClosePath))
+}
+
+/// Whether offsetting a closed bezpath by a positive distance, as `offset_bezpath` measures it, grows the area filled by the shape it's a part of.
+/// This checks which side of the outline is filled, so holes and outlines wound in either direction are all handled.
+fn positive_offset_grows_fill(bezpath: &BezPath, shape: &[BezPath], fill_rule: FillRule) -> bool {
+	let filled = |point: Point| {
+		let winding: i32 = shape.iter().map(|bezpath| bezpath.winding(point)).sum();
+		match fill_rule {
+			FillRule::NonZero => winding != 0,
+			FillRule::EvenOdd => winding % 2 != 0,
+		}
+	};
+
+	// Find a segment with the fill on only one side, unlike where the outline crosses itself or another outline
+	for segment in bezpath.segments() {
+		let direction = segment.eval(0.5 + 1e-3) - segment.eval(0.5 - 1e-3);
+		if direction.hypot() < 1e-12 {
+			continue;
+		}
+		let positive_side = Vec2::new(-direction.y, direction.x) / direction.hypot() * SIDE_TEST_DISTANCE;
+
+		let middle = segment.eval(0.5);
+		match (filled(middle + positive_side), filled(middle - positive_side)) {
+			(true, false) => return false,
+			(false, true) => return true,
+			_ => {}
+		}
+	}
+
+	// Otherwise the winding direction decides it, as it does for shapes which don't overlap themselves
+	bezpath.area() < 0.
+}
+
+/// The area within `distance` of the bezpaths: outlines `2 * distance` wide along them, with flat ends.
+fn offset_band(bezpaths: &[BezPath], distance: f64, join: StrokeJoin, miter_limit: f64) -> BezPath {
+	let style = kurbo::Stroke::new(2. * distance.abs())
+		.with_caps(kurbo::Cap::Butt)
+		.with_join(kurbo_join(join))
+		.with_miter_limit(miter_limit);
+
+	let mut band = BezPath::new();
+	for bezpath in bezpaths {
+		band.extend(kurbo::stroke(bezpath.iter(), &style, &kurbo::StrokeOpts::default(), CUBIC_TO_BEZPATH_ACCURACY));
+	}
+	band
+}
+
+/// Offsets the subpaths of the vector data, in the space given by `transform`. A positive distance grows the area filled by closed subpaths and a negative distance shrinks it.
+/// Open subpaths are offset to one side or turned into outlines. The result is the raw offset, which overlaps itself where the offsets of neighboring segments cross.
+pub fn offset_vector_data(vector_data: &VectorData, transform: DAffine2, distance: f64, join: StrokeJoin, miter_limit: f64, open_paths: OpenPathOffset) -> VectorData {
+	let (closed, open) = transformed_subpaths(vector_data, transform);
+	let mut result = VectorData::default();
+
+	for bezpath in &closed {
+		let grows_fill = positive_offset_grows_fill(bezpath, &closed, vector_data.style.fill_rule());
+		let distance = if grows_fill { distance } else { -distance };
+		result.append_bezpath(offset_bezpath(bezpath, distance, kurbo_join(join), Some(miter_limit)));
+	}
+
+	match open_paths {
+		OpenPathOffset::OneSide => {
+			for bezpath in &open {
+				result.append_bezpath(offset_bezpath(bezpath, -distance, kurbo_join(join), Some(miter_limit)));
+			}
+		}
+		OpenPathOffset::Outline => result.append_bezpath(offset_band(&open, distance, join, miter_limit)),
+	}
+
+	result
+}
+
+/// The area within `distance` of either the closed or open subpaths of the vector data, in the space given by `transform`.
+/// Adding this band to a shape's area, or removing it, gives the shape's offset without the overlaps left by [`offset_vector_data`].
+pub fn offset_vector_data_band(vector_data: &VectorData, transform: DAffine2, distance: f64, join: StrokeJoin, miter_limit: f64, closed: bool) -> VectorData {
+	let (closed_bezpaths, open_bezpaths) = transformed_subpaths(vector_data, transform);
+	let mut result = VectorData::default();
+	result.append_bezpath(offset_band(if closed { &closed_bezpaths } else { &open_bezpaths }, distance, join, miter_limit));
+	result
+}
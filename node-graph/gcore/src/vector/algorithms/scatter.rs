@@ -0,0 +1,225 @@
+use super::bezpath_algorithms::{eval_pathseg_euclidean, poisson_disk_points};
+use super::util::segment_tangent;
+use crate::vector::misc::{PointSpacingType, dvec2_to_point, point_to_dvec2};
+use glam::DVec2;
+use kurbo::{BezPath, DEFAULT_ACCURACY, ParamCurve, PathEl, Shape};
+
+/// Stops the number of placed positions from growing without bound when the separation is tiny compared to the path or area.
+const MAX_SCATTER_COUNT: usize = 10_000;
+
+/// The positions at evenly spaced arc lengths along the path, each with the direction the path heads in there.
+///
+/// With [`PointSpacingType::Separation`], the positions are `separation` apart starting from the beginning of the path.
+/// With [`PointSpacingType::Quantity`], `quantity` positions span the whole path from its start to its end, or evenly around it if it's closed.
+/// The end of a closed path, which is where it starts, is only placed once.
+pub fn positions_along_bezpath(bezpath: &BezPath, spacing: PointSpacingType, separation: f64, quantity: u32) -> Vec<(DVec2, DVec2)> {
+	let segments: Vec<_> = bezpath.segments().collect();
+	let lengths: Vec<f64> = segments.iter().map(|segment| segment.perimeter(DEFAULT_ACCURACY)).collect();
+	let total_length: f64 = lengths.iter().sum();
+	if segments.is_empty() || total_length <= f64::EPSILON {
+		return Vec::new();
+	}
+	let closed = matches!(bezpath.elements().last(), Some(PathEl::ClosePath));
+
+	let distances: Vec<f64> = match spacing {
+		PointSpacingType::Separation => {
+			let separation = separation.max(total_length / MAX_SCATTER_COUNT as f64);
+			let count = ((total_length + 1e-9) / separation).floor() as usize + 1;
+			(0..count)
+				.map(|index| index as f64 * separation)
+				// The end of a closed path is its start, which already has a copy
+				.filter(|&distance| !closed || distance < total_length - 1e-9)
+				.collect()
+		}
+		PointSpacingType::Quantity => {
+			let count = (quantity as usize).min(MAX_SCATTER_COUNT);
+			let intervals = if closed { count } else { count.saturating_sub(1) }.max(1);
+			(0..count).map(|index| index as f64 * total_length / intervals as f64).collect()
+		}
+	};
+
+	let mut segment_index = 0;
+	let mut length_before_segment = 0.;
+	distances
+		.into_iter()
+		.map(|distance| {
+			// The distances increase, so the segment containing each one is found by continuing from the previous one
+			while segment_index < segments.len() - 1 && distance > length_before_segment + lengths[segment_index] {
+				length_before_segment += lengths[segment_index];
+				segment_index += 1;
+			}
+
+			let segment = segments[segment_index];
+			let length = lengths[segment_index];
+			let fraction = if length > 0. { ((distance - length_before_segment) / length).clamp(0., 1.) } else { 0. };
+			let t = eval_pathseg_euclidean(segment, fraction, DEFAULT_ACCURACY);
+			(point_to_dvec2(segment.eval(t)), segment_tangent(segment, t).normalize_or_zero())
+		})
+		.collect()
+}
+
+/// Whether the point is inside the area filled by the paths under the nonzero fill rule.
+fn inside_area(bezpaths: &[BezPath], point: DVec2) -> bool {
+	bezpaths.iter().map(|bezpath| bezpath.winding(dvec2_to_point(point))).sum::<i32>() != 0
+}
+
+/// Up to `quantity` positions picked uniformly at random inside the area filled by the paths, which are treated as closed.
+/// Fewer are given only if the area is too small for random positions in its bounding box to land inside it.
+pub fn random_positions_in_area(bezpaths: &[BezPath], quantity: u32, mut rng: impl FnMut() -> f64) -> Vec<DVec2> {
+	let bezpaths: Vec<_> = bezpaths.iter().cloned().map(closed).collect();
+	let Some(bounds) = bezpaths.iter().map(|bezpath| bezpath.bounding_box()).reduce(|a, b| a.union(b)) else {
+		return Vec::new();
+	};
+	if bounds.area() <= f64::EPSILON {
+		return Vec::new();
+	}
+
+	let quantity = (quantity as usize).min(MAX_SCATTER_COUNT);
+	let mut positions = Vec::with_capacity(quantity);
+	for _ in 0..quantity * 1000 {
+		if positions.len() == quantity {
+			break;
+		}
+		let position = DVec2::new(bounds.x0 + rng() * bounds.width(), bounds.y0 + rng() * bounds.height());
+		if inside_area(&bezpaths, position) {
+			positions.push(position);
+		}
+	}
+	positions
+}
+
+/// Random positions inside the area filled by the paths, which are treated as closed, with none closer than `separation` to another.
+/// Positions are added until no more fit, as in a Poisson disk distribution.
+pub fn poisson_disk_positions_in_area(bezpaths: &[BezPath], separation: f64, mut rng: impl FnMut() -> f64) -> Vec<DVec2> {
+	let bezpaths_with_bounds: Vec<_> = bezpaths
+		.iter()
+		.cloned()
+		.map(closed)
+		.map(|bezpath| {
+			let bounds = bezpath.bounding_box();
+			(bezpath, bounds)
+		})
+		.collect();
+
+	let mut positions = Vec::new();
+	for (index, (bezpath, _)) in bezpaths_with_bounds.iter().enumerate() {
+		if bezpath.segments().count() < 2 {
+			continue;
+		}
+		positions.extend(poisson_disk_points(index, &bezpaths_with_bounds, separation, &mut rng));
+	}
+	positions.truncate(MAX_SCATTER_COUNT);
+	positions
+}
+
+fn closed(mut bezpath: BezPath) -> BezPath {
+	if !matches!(bezpath.elements().last(), Some(PathEl::ClosePath)) {
+		bezpath.close_path();
+	}
+	bezpath
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use rand::{Rng, SeedableRng};
+
+	fn square() -> BezPath {
+		let mut bezpath = BezPath::new();
+		bezpath.move_to((0., 0.));
+		bezpath.line_to((100., 0.));
+		bezpath.line_to((100., 100.));
+		bezpath.line_to((0., 100.));
+		bezpath.close_path();
+		bezpath
+	}
+
+	#[test]
+	fn separated_positions_along_an_open_path() {
+		let mut bezpath = BezPath::new();
+		bezpath.move_to((0., 0.));
+		bezpath.line_to((100., 0.));
+		bezpath.line_to((100., 50.));
+
+		let positions = positions_along_bezpath(&bezpath, PointSpacingType::Separation, 30., 0);
+		let expected = [(0., 0.), (30., 0.), (60., 0.), (90., 0.), (100., 20.), (100., 50.)];
+		assert_eq!(positions.len(), expected.len());
+		for ((position, _), (x, y)) in positions.iter().zip(expected) {
+			assert!(position.abs_diff_eq(DVec2::new(x, y), 1e-3), "{position} should be at ({x}, {y})");
+		}
+
+		// The direction follows the path around its corner
+		assert!(positions[0].1.abs_diff_eq(DVec2::X, 1e-6));
+		assert!(positions[4].1.abs_diff_eq(DVec2::Y, 1e-6));
+	}
+
+	#[test]
+	fn quantity_of_positions_is_evenly_spaced_along_a_curve() {
+		let mut bezpath = BezPath::new();
+		bezpath.move_to((0., 0.));
+		bezpath.curve_to((0., 100.), (100., 100.), (100., 0.));
+		let length = bezpath.segments().map(|segment| segment.perimeter(DEFAULT_ACCURACY)).sum::<f64>();
+
+		let positions = positions_along_bezpath(&bezpath, PointSpacingType::Quantity, 0., 9);
+		assert_eq!(positions.len(), 9);
+		assert!(positions[0].0.abs_diff_eq(DVec2::ZERO, 1e-6));
+		assert!(positions[8].0.abs_diff_eq(DVec2::new(100., 0.), 1e-6));
+
+		// The arc length between neighbors is the same, measured by splitting the curve at each position's distance along it
+		let segment = bezpath.segments().next().unwrap();
+		for (index, (position, _)) in positions.iter().enumerate() {
+			let distance = length * index as f64 / 8.;
+			let t = eval_pathseg_euclidean(segment, distance / length, DEFAULT_ACCURACY);
+			assert!(point_to_dvec2(segment.eval(t)).abs_diff_eq(*position, 1e-6));
+			let length_up_to_position = segment.subsegment(0.0..t).perimeter(DEFAULT_ACCURACY);
+			assert!(
+				(length_up_to_position - distance).abs() < 0.01,
+				"Position {index} is {length_up_to_position} along the curve instead of {distance}"
+			);
+		}
+	}
+
+	#[test]
+	fn closed_paths_place_their_start_only_once() {
+		let positions = positions_along_bezpath(&square(), PointSpacingType::Separation, 50., 0);
+		assert_eq!(positions.len(), 8);
+
+		let positions = positions_along_bezpath(&square(), PointSpacingType::Quantity, 0., 4);
+		let corners: Vec<_> = positions.iter().map(|(position, _)| position.round()).collect();
+		assert_eq!(corners, [DVec2::new(0., 0.), DVec2::new(100., 0.), DVec2::new(100., 100.), DVec2::new(0., 100.)]);
+	}
+
+	#[test]
+	fn random_positions_land_inside_the_area() {
+		// A square with a hole cut out of its middle by a square wound the other way
+		let mut hole = BezPath::new();
+		hole.move_to((25., 25.));
+		hole.line_to((25., 75.));
+		hole.line_to((75., 75.));
+		hole.line_to((75., 25.));
+		hole.close_path();
+		let area = [square(), hole];
+
+		let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+		let positions = random_positions_in_area(&area, 200, || rng.random::<f64>());
+		assert_eq!(positions.len(), 200);
+		for position in positions {
+			let in_square = position.cmpge(DVec2::ZERO).all() && position.cmple(DVec2::splat(100.)).all();
+			let in_hole = position.cmpgt(DVec2::splat(25.)).all() && position.cmplt(DVec2::splat(75.)).all();
+			assert!(in_square && !in_hole, "{position} should be inside the square and outside the hole");
+		}
+	}
+
+	#[test]
+	fn poisson_disk_positions_are_separated() {
+		let mut rng = rand::rngs::StdRng::seed_from_u64(0);
+		let positions = poisson_disk_positions_in_area(&[square()], 10., || rng.random::<f64>());
+		assert!(positions.len() > 20);
+		for (index, a) in positions.iter().enumerate() {
+			assert!(inside_area(&[square()], *a));
+			for b in &positions[index + 1..] {
+				assert!(a.distance(*b) >= 10. - 1e-9);
+			}
+		}
+	}
+}
@@ -1,6 +1,7 @@
 use crate::math::math_ext::QuadExt;
 use crate::math::quad::Quad;
 use crate::vector::PointId;
+use crate::vector::style::FillRule;
 use bezier_rs::Subpath;
 use glam::{DAffine2, DMat2, DVec2};
 
@@ -32,6 +33,8 @@ pub struct ClickTarget {
 	target_type: ClickTargetType,
 	stroke_width: f64,
 	bounding_box: Option<[DVec2; 2]>,
+	#[serde(default)]
+	fill_rule: FillRule,
 }
 
 impl ClickTarget {
@@ -41,6 +44,7 @@ impl ClickTarget {
 			target_type: ClickTargetType::Subpath(subpath),
 			stroke_width,
 			bounding_box,
+			fill_rule: FillRule::NonZero,
 		}
 	}
 
@@ -56,9 +60,16 @@ impl ClickTarget {
 			target_type: ClickTargetType::FreePoint(point),
 			stroke_width,
 			bounding_box,
+			fill_rule: FillRule::NonZero,
 		}
 	}
 
+	/// Sets the [FillRule] used to decide whether a point inside a self-intersecting subpath is part of its filled area.
+	pub fn with_fill_rule(mut self, fill_rule: FillRule) -> Self {
+		self.fill_rule = fill_rule;
+		self
+	}
+
 	pub fn target_type(&self) -> &ClickTargetType {
 		&self.target_type
 	}
@@ -117,7 +128,7 @@ impl ClickTarget {
 					return true;
 				}
 				// Check if selection is entirely within the shape
-				if subpath.closed() && bezier_iter().next().is_some_and(|bezier| subpath.contains_point(bezier.start)) {
+				if subpath.closed() && bezier_iter().next().is_some_and(|bezier| self.fill_rule.contains_winding(subpath.winding_order(bezier.start))) {
 					return true;
 				}
 
@@ -156,7 +167,7 @@ impl ClickTarget {
 		{
 			// Check if the point is within the shape
 			match self.target_type() {
-				ClickTargetType::Subpath(subpath) => subpath.closed() && subpath.contains_point(point),
+				ClickTargetType::Subpath(subpath) => subpath.closed() && self.fill_rule.contains_winding(subpath.winding_order(point)),
 				ClickTargetType::FreePoint(free_point) => free_point.position == point,
 			}
 		} else {
@@ -92,6 +92,32 @@ pub enum PointSpacingType {
 	Quantity,
 }
 
+/// How the Offset Path node treats open paths, which have no inside or outside to grow or shrink.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum OpenPathOffset {
+	/// Offsets open paths to their left side, relative to the direction they're drawn in, or to their right side with a negative distance.
+	#[default]
+	OneSide,
+	/// Turns open paths into closed outlines around both of their sides, with flat ends.
+	Outline,
+}
+
+/// Where the Scatter node places its copies of the instance, relative to the target vector data.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum ScatterMode {
+	/// Evenly spaced by arc length along the path.
+	#[default]
+	AlongPath,
+	/// At random positions inside the filled area, which may overlap.
+	Random,
+	/// At random positions inside the filled area, each at least the separation distance from the others.
+	PoissonDisk,
+}
+
 pub fn point_to_dvec2(point: Point) -> DVec2 {
 	DVec2 { x: point.x, y: point.y }
 }
@@ -1,15 +1,19 @@
 //! Contains stylistic options for SVG elements.
 
-use crate::Color;
+use crate::bounds::BoundingBox;
 pub use crate::gradient::*;
+use crate::math::quad::Quad;
+use crate::vector::PointId;
+use crate::{Color, GraphicGroupTable};
+use bezier_rs::Subpath;
 use dyn_any::DynAny;
-use glam::DAffine2;
+use glam::{DAffine2, DVec2};
 
 /// Describes the fill of a layer.
 ///
-/// Can be None, a solid [Color], or a linear/radial [Gradient].
+/// Can be None, a solid [Color], a linear/radial/conic [Gradient], or a [Pattern] repeating another graphic.
 ///
-/// In the future we'll probably also add a pattern fill. This will probably be named "Paint" in the future.
+/// This will probably be named "Paint" in the future.
 #[repr(C)]
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, Hash, specta::Type)]
 pub enum Fill {
@@ -17,6 +21,7 @@ pub enum Fill {
 	None,
 	Solid(Color),
 	Gradient(Gradient),
+	Pattern(Pattern),
 }
 
 impl std::fmt::Display for Fill {
@@ -25,6 +30,7 @@ impl std::fmt::Display for Fill {
 			Self::None => write!(f, "None"),
 			Self::Solid(color) => write!(f, "#{} (Alpha: {}%)", color.to_rgb_hex_srgb(), color.a() * 100.),
 			Self::Gradient(gradient) => write!(f, "{}", gradient),
+			Self::Pattern(pattern) => write!(f, "{}", pattern),
 		}
 	}
 }
@@ -43,13 +49,14 @@ impl Fill {
 		}
 	}
 
-	/// Evaluate the color at some point on the fill. Doesn't currently work for Gradient.
+	/// Evaluate the color at some point on the fill. Doesn't currently work for Gradient or Pattern.
 	pub fn color(&self) -> Color {
 		match self {
 			Self::None => Color::BLACK,
 			Self::Solid(color) => *color,
 			// TODO: Should correctly sample the gradient the equation here: https://svgwg.org/svg2-draft/pservers.html#Gradients
-			Self::Gradient(Gradient { stops, .. }) => stops.0[0].1,
+			Self::Gradient(Gradient { stops, .. }) => stops[0].1,
+			Self::Pattern(_) => Color::BLACK,
 		}
 	}
 
@@ -62,17 +69,26 @@ impl Fill {
 			(Self::Solid(a), Self::Solid(b)) => Self::Solid(a.lerp(b, time as f32)),
 			(Self::Solid(a), Self::Gradient(b)) => {
 				let mut solid_to_gradient = b.clone();
-				solid_to_gradient.stops.0.iter_mut().for_each(|(_, color)| *color = *a);
+				solid_to_gradient.stops = b.stops.map_colors(|_| *a);
 				let a = &solid_to_gradient;
 				Self::Gradient(a.lerp(b, time))
 			}
 			(Self::Gradient(a), Self::Solid(b)) => {
 				let mut gradient_to_solid = a.clone();
-				gradient_to_solid.stops.0.iter_mut().for_each(|(_, color)| *color = *b);
+				gradient_to_solid.stops = a.stops.map_colors(|_| *b);
 				let b = &gradient_to_solid;
 				Self::Gradient(a.lerp(b, time))
 			}
 			(Self::Gradient(a), Self::Gradient(b)) => Self::Gradient(a.lerp(b, time)),
+			(Self::Pattern(a), Self::Pattern(b)) => Self::Pattern(a.lerp(b, time)),
+			// A pattern can't blend with other fills, so it switches over halfway
+			(Self::Pattern(_), _) | (_, Self::Pattern(_)) => {
+				if time < 0.5 {
+					self.clone()
+				} else {
+					other.clone()
+				}
+			}
 			_ => Self::None,
 		}
 	}
@@ -93,11 +109,21 @@ impl Fill {
 		}
 	}
 
+	/// Extract a pattern from the fill
+	pub fn as_pattern(&self) -> Option<&Pattern> {
+		match self {
+			Self::Pattern(pattern) => Some(pattern),
+			_ => None,
+		}
+	}
+
 	/// Find if fill can be represented with only opaque colors
 	pub fn is_opaque(&self) -> bool {
 		match self {
 			Fill::Solid(color) => color.is_opaque(),
 			Fill::Gradient(gradient) => gradient.stops.iter().all(|(_, color)| color.is_opaque()),
+			// The gaps between tiles, and the tile itself, may be transparent
+			Fill::Pattern(_) => false,
 			Fill::None => true,
 		}
 	}
@@ -120,17 +146,158 @@ impl From<Option<Color>> for Fill {
 	}
 }
 
+impl From<SwatchColor> for Fill {
+	fn from(swatch_color: SwatchColor) -> Fill {
+		Fill::Solid(swatch_color.color)
+	}
+}
+
 impl From<Gradient> for Fill {
 	fn from(gradient: Gradient) -> Fill {
 		Fill::Gradient(gradient)
 	}
 }
 
-/// Describes the fill of a layer, but unlike [`Fill`], this doesn't store a [`Gradient`] directly but just its [`GradientStops`].
+impl From<Pattern> for Fill {
+	fn from(pattern: Pattern) -> Fill {
+		Fill::Pattern(pattern)
+	}
+}
+
+/// The largest number of tiles drawn for one pattern fill by renderers which repeat the tile themselves, so a tiny tile across a huge shape can't stall rendering.
+pub const MAX_PATTERN_TILES: usize = 10_000;
+
+/// A fill which repeats a graphic, called the tile, in a grid of cells across the filled shape.
+///
+/// The grid is laid out in the local space of the filled vector data, so the pattern follows the shape as it's transformed.
+/// Each cell is the size of the tile's bounds (scaled) plus the spacing, and the tile is drawn with the top left corner of its bounds at the cell's corner.
+#[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+#[serde(default)]
+pub struct Pattern {
+	/// The graphic drawn in every cell. The Fill node supplies this from another layer when one is wired in, otherwise it's embedded here.
+	#[specta(skip)]
+	pub tile: GraphicGroupTable,
+	/// Scale factor of the tile.
+	pub scale: f64,
+	/// Rotation of the grid around its origin, in degrees.
+	pub rotation: f64,
+	/// Horizontal and vertical gap between neighboring tiles.
+	pub spacing: DVec2,
+	/// Position of the grid's origin, which is the corner of one of its cells.
+	pub offset: DVec2,
+}
+
+impl Default for Pattern {
+	fn default() -> Self {
+		Self {
+			tile: GraphicGroupTable::default(),
+			scale: 1.,
+			rotation: 0.,
+			spacing: DVec2::ZERO,
+			offset: DVec2::ZERO,
+		}
+	}
+}
+
+impl std::hash::Hash for Pattern {
+	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+		self.tile.hash(state);
+		self.scale.to_bits().hash(state);
+		self.rotation.to_bits().hash(state);
+		self.spacing.to_array().iter().for_each(|x| x.to_bits().hash(state));
+		self.offset.to_array().iter().for_each(|x| x.to_bits().hash(state));
+	}
+}
+
+impl std::fmt::Display for Pattern {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		write!(f, "Pattern of {} element(s) (Scale: {}, Rotation: {}°)", self.tile.len(), self.scale, self.rotation)
+	}
+}
+
+impl Pattern {
+	/// The bounds of the tile, including its strokes, before it's scaled. This is `None` when the tile is empty.
+	pub fn tile_bounds(&self) -> Option<[DVec2; 2]> {
+		self.tile.bounding_box(DAffine2::IDENTITY, true)
+	}
+
+	/// The size of each cell of the grid, or `None` if there's nothing with an area to repeat.
+	pub fn cell_size(&self) -> Option<DVec2> {
+		let [min, max] = self.tile_bounds()?;
+		let size = (max - min) * self.scale + self.spacing.max(DVec2::ZERO);
+		(self.scale > 0. && size.is_finite() && size.x > 0. && size.y > 0.).then_some(size)
+	}
+
+	/// Maps the grid, whose cells are laid out from its origin along the axes, into the local space of the filled vector data.
+	pub fn grid_transform(&self) -> DAffine2 {
+		DAffine2::from_angle_translation(self.rotation.to_radians(), self.offset)
+	}
+
+	/// Maps the tile into the cell at the grid's origin.
+	pub fn tile_transform(&self) -> DAffine2 {
+		let min = self.tile_bounds().map_or(DVec2::ZERO, |[min, _]| min);
+		DAffine2::from_scale(DVec2::splat(self.scale)) * DAffine2::from_translation(-min)
+	}
+
+	/// Transforms from the tile into each cell of the grid that overlaps the given bounds, in the local space of the filled vector data.
+	///
+	/// This is for renderers that repeat the tile themselves, which must then clip the tiles to the filled shape. A tile larger than the shape still
+	/// covers it from a single cell, and at most [`MAX_PATTERN_TILES`] cells are returned.
+	pub fn tile_transforms(&self, bounds: [DVec2; 2]) -> Vec<DAffine2> {
+		let Some(cell_size) = self.cell_size() else { return Vec::new() };
+		let grid_transform = self.grid_transform();
+		let tile_transform = self.tile_transform();
+
+		let [min, max] = (grid_transform.inverse() * Quad::from_box(bounds)).bounding_box();
+		let (first, last) = ((min / cell_size).floor(), (max / cell_size).ceil());
+		if !first.is_finite() || !last.is_finite() {
+			return Vec::new();
+		}
+		let (columns, rows) = ((last.x - first.x).max(1.) as i64, (last.y - first.y).max(1.) as i64);
+
+		(0..rows)
+			.flat_map(|row| (0..columns).map(move |column| first + DVec2::new(column as f64, row as f64)))
+			.take(MAX_PATTERN_TILES)
+			.map(|cell| grid_transform * DAffine2::from_translation(cell * cell_size) * tile_transform)
+			.collect()
+	}
+
+	/// Interpolates the tile placement, keeping the tile of whichever pattern is nearer.
+	pub fn lerp(&self, other: &Self, time: f64) -> Self {
+		Self {
+			tile: if time < 0.5 { self.tile.clone() } else { other.tile.clone() },
+			scale: self.scale + (other.scale - self.scale) * time,
+			rotation: self.rotation + (other.rotation - self.rotation) * time,
+			spacing: self.spacing.lerp(other.spacing, time),
+			offset: self.offset.lerp(other.offset, time),
+		}
+	}
+}
+
+/// Identifies one of the document's named color swatches.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct SwatchId(pub u64);
+
+/// A color taken from one of the document's named swatches, used by a fill or stroke in place of a literal color so that editing the swatch changes every color which references it.
 ///
-/// Can be None, a solid [Color], or a linear/radial [Gradient].
+/// The editor resolves the reference to the swatch's current color whenever the graph is evaluated.
+/// If the swatch has been deleted, the color it last resolved to is kept.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
+pub struct SwatchColor {
+	pub swatch: SwatchId,
+	/// WARNING: Color is gamma, not linear!
+	pub color: Color,
+}
+
+impl From<SwatchColor> for Option<Color> {
+	fn from(swatch_color: SwatchColor) -> Option<Color> {
+		Some(swatch_color.color)
+	}
+}
+
+/// Describes the fill of a layer, but unlike [`Fill`], this doesn't store a [`Gradient`] directly but just its [`GradientStops`].
 ///
-/// In the future we'll probably also add a pattern fill.
+/// Can be None, a solid [Color], a linear/radial/conic [Gradient], or a [Pattern].
 #[repr(C)]
 #[derive(Default, Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, Hash, specta::Type)]
 pub enum FillChoice {
@@ -140,6 +307,8 @@ pub enum FillChoice {
 	Solid(Color),
 	/// WARNING: Color stops are gamma, not linear!
 	Gradient(GradientStops),
+	/// Patterns are edited by their own controls, since they can't be shown by a color picker.
+	Pattern,
 }
 
 impl FillChoice {
@@ -155,6 +324,7 @@ impl FillChoice {
 
 	/// Convert this [`FillChoice`] to a [`Fill`] using the provided [`Gradient`] as a base for the positional information of the gradient.
 	/// If a gradient isn't provided, default gradient positional information is used in cases where the [`FillChoice`] is a [`Gradient`].
+	/// A [`FillChoice::Pattern`] becomes a default [`Pattern`] with an empty tile, since the choice doesn't carry one.
 	pub fn to_fill(&self, existing_gradient: Option<&Gradient>) -> Fill {
 		match self {
 			Self::None => Fill::None,
@@ -164,6 +334,7 @@ impl FillChoice {
 				fill.stops = stops.clone();
 				Fill::Gradient(fill)
 			}
+			Self::Pattern => Fill::Pattern(Pattern::default()),
 		}
 	}
 }
@@ -174,6 +345,7 @@ impl From<Fill> for FillChoice {
 			Fill::None => FillChoice::None,
 			Fill::Solid(color) => FillChoice::Solid(color),
 			Fill::Gradient(gradient) => FillChoice::Gradient(gradient.stops),
+			Fill::Pattern(_) => FillChoice::Pattern,
 		}
 	}
 }
@@ -186,6 +358,37 @@ pub enum FillType {
 	#[default]
 	Solid,
 	Gradient,
+	Pattern,
+}
+
+/// The rule that decides which regions of a path are inside it, where the path crosses over itself or its subpaths are nested.
+/// <https://svgwg.org/svg2-draft/painting.html#FillRuleProperty>
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum FillRule {
+	#[default]
+	#[label("Nonzero")]
+	NonZero,
+	#[label("Even-Odd")]
+	EvenOdd,
+}
+
+impl FillRule {
+	pub fn svg_name(&self) -> &'static str {
+		match self {
+			FillRule::NonZero => "nonzero",
+			FillRule::EvenOdd => "evenodd",
+		}
+	}
+
+	/// Whether a point is inside the filled area given its winding number, the net number of times the path loops around it.
+	pub fn contains_winding(self, winding: i32) -> bool {
+		match self {
+			FillRule::NonZero => winding != 0,
+			FillRule::EvenOdd => winding % 2 != 0,
+		}
+	}
 }
 
 /// The stroke (outline) style of an SVG element.
@@ -229,6 +432,14 @@ impl StrokeJoin {
 	}
 }
 
+/// The ratio of a miter join's length (from its inner corner to its tip) to the stroke weight, for a join between segments arriving and leaving along these tangents.
+///
+/// This is `1 / sin(θ / 2)` for the angle `θ` between the segments, which grows without bound as the segments fold back on each other.
+pub fn miter_length_ratio(incoming_tangent: DVec2, outgoing_tangent: DVec2) -> f64 {
+	let angle = (-incoming_tangent).angle_to(outgoing_tangent).abs();
+	1. / (angle / 2.).sin()
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
 #[widget(Radio)]
@@ -245,6 +456,35 @@ impl StrokeAlign {
 	}
 }
 
+/// A shape drawn at an open end of a stroked path, oriented to point away from the path.
+#[repr(C)]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum StrokeMarker {
+	#[default]
+	None,
+	Arrow,
+	Triangle,
+	Circle,
+	Square,
+	Bar,
+}
+
+impl StrokeMarker {
+	/// The marker's outline in units of the stroke weight, with the path's endpoint at the origin and the marker pointing away from the path along the +X axis.
+	pub fn outline(self) -> Option<Subpath<PointId>> {
+		let outline = match self {
+			Self::None => return None,
+			Self::Arrow => Subpath::from_anchors_linear([DVec2::new(2., 0.), DVec2::new(-1., 1.5), DVec2::new(-0.25, 0.), DVec2::new(-1., -1.5)], true),
+			Self::Triangle => Subpath::from_anchors_linear([DVec2::new(2., 0.), DVec2::new(-1., 1.5), DVec2::new(-1., -1.5)], true),
+			Self::Circle => Subpath::new_ellipse(DVec2::splat(-1.5), DVec2::splat(1.5)),
+			Self::Square => Subpath::new_rect(DVec2::splat(-1.5), DVec2::splat(1.5)),
+			Self::Bar => Subpath::new_rect(DVec2::new(-0.5, -2.), DVec2::new(0.5, 2.)),
+		};
+		Some(outline)
+	}
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize, Hash, DynAny, specta::Type, node_macro::ChoiceType)]
 #[widget(Radio)]
@@ -264,6 +504,10 @@ fn daffine2_identity() -> DAffine2 {
 	DAffine2::IDENTITY
 }
 
+fn default_marker_size() -> f64 {
+	1.
+}
+
 #[repr(C)]
 #[derive(Debug, Clone, PartialEq, serde::Serialize, serde::Deserialize, DynAny, specta::Type)]
 #[serde(default)]
@@ -288,6 +532,15 @@ pub struct Stroke {
 	pub non_scaling: bool,
 	#[serde(default)]
 	pub paint_order: PaintOrder,
+	/// Marker drawn at the start of an open path
+	#[serde(default)]
+	pub start_marker: StrokeMarker,
+	/// Marker drawn at the end of an open path
+	#[serde(default)]
+	pub end_marker: StrokeMarker,
+	/// Scale of the start and end markers, relative to the stroke weight
+	#[serde(default = "default_marker_size")]
+	pub marker_size: f64,
 }
 
 impl std::hash::Hash for Stroke {
@@ -306,6 +559,9 @@ impl std::hash::Hash for Stroke {
 		self.transform.to_cols_array().iter().for_each(|x| x.to_bits().hash(state));
 		self.non_scaling.hash(state);
 		self.paint_order.hash(state);
+		self.start_marker.hash(state);
+		self.end_marker.hash(state);
+		self.marker_size.to_bits().hash(state);
 	}
 }
 
@@ -334,6 +590,9 @@ impl Stroke {
 			transform: DAffine2::IDENTITY,
 			non_scaling: false,
 			paint_order: PaintOrder::StrokeAbove,
+			start_marker: StrokeMarker::None,
+			end_marker: StrokeMarker::None,
+			marker_size: 1.,
 		}
 	}
 
@@ -353,6 +612,9 @@ impl Stroke {
 			),
 			non_scaling: if time < 0.5 { self.non_scaling } else { other.non_scaling },
 			paint_order: if time < 0.5 { self.paint_order } else { other.paint_order },
+			start_marker: if time < 0.5 { self.start_marker } else { other.start_marker },
+			end_marker: if time < 0.5 { self.end_marker } else { other.end_marker },
+			marker_size: self.marker_size + (other.marker_size - self.marker_size) * time,
 		}
 	}
 
@@ -378,6 +640,31 @@ impl Stroke {
 		self.dash_offset
 	}
 
+	/// The dash pattern that should actually be rendered, following SVG semantics: a pattern with a negative length or a total length of zero renders as a solid line.
+	/// An odd number of lengths is repeated to yield an even number, so every entry alternates between a dash and a gap.
+	pub fn renderable_dash_lengths(&self) -> Vec<f64> {
+		let total: f64 = self.dash_lengths.iter().sum();
+		if self.dash_lengths.iter().any(|&length| length < 0. || !length.is_finite()) || total <= 0. {
+			return Vec::new();
+		}
+
+		if self.dash_lengths.len() % 2 == 1 {
+			self.dash_lengths.iter().chain(self.dash_lengths.iter()).copied().collect()
+		} else {
+			self.dash_lengths.clone()
+		}
+	}
+
+	/// The dash offset wrapped into the period of the [`Self::renderable_dash_lengths`] pattern, so negative offsets shift the pattern backwards like in SVG.
+	pub fn renderable_dash_offset(&self) -> f64 {
+		let period: f64 = self.renderable_dash_lengths().iter().sum();
+		if period <= 0. || !self.dash_offset.is_finite() {
+			return 0.;
+		}
+
+		self.dash_offset.rem_euclid(period)
+	}
+
 	pub fn cap_index(&self) -> u32 {
 		self.cap as u32
 	}
@@ -390,6 +677,19 @@ impl Stroke {
 		self.join_miter_limit as f32
 	}
 
+	/// How far the join between segments arriving and leaving along these tangents reaches from the path, as a multiple of half the stroke weight.
+	///
+	/// A miter join reaches out to its tip, unless its [`miter_length_ratio`] exceeds the miter limit where (as SVG specifies) it falls back to a bevel join.
+	/// Bevel and round joins stay within half the weight.
+	pub fn join_extent(&self, incoming_tangent: DVec2, outgoing_tangent: DVec2) -> f64 {
+		if self.join != StrokeJoin::Miter || incoming_tangent == DVec2::ZERO || outgoing_tangent == DVec2::ZERO {
+			return 1.;
+		}
+
+		let miter_ratio = miter_length_ratio(incoming_tangent, outgoing_tangent);
+		if miter_ratio <= self.join_miter_limit { miter_ratio } else { 1. }
+	}
+
 	pub fn with_color(mut self, color: &Option<Color>) -> Option<Self> {
 		self.color = *color;
 
@@ -401,7 +701,13 @@ impl Stroke {
 		self
 	}
 
+	/// Parses a dash pattern such as "4 2" or "4, 2", where "none" (as returned by [`Self::dash_lengths`] for a solid line) clears the pattern.
 	pub fn with_dash_lengths(mut self, dash_lengths: &str) -> Option<Self> {
+		if dash_lengths.trim().eq_ignore_ascii_case("none") {
+			self.dash_lengths = Vec::new();
+			return Some(self);
+		}
+
 		dash_lengths
 			.split(&[',', ' '])
 			.filter(|x| !x.is_empty())
@@ -444,6 +750,21 @@ impl Stroke {
 		self
 	}
 
+	pub fn with_start_marker(mut self, start_marker: StrokeMarker) -> Self {
+		self.start_marker = start_marker;
+		self
+	}
+
+	pub fn with_end_marker(mut self, end_marker: StrokeMarker) -> Self {
+		self.end_marker = end_marker;
+		self
+	}
+
+	pub fn with_marker_size(mut self, marker_size: f64) -> Self {
+		self.marker_size = marker_size;
+		self
+	}
+
 	pub fn has_renderable_stroke(&self) -> bool {
 		self.weight > 0. && self.color.is_some_and(|color| color.a() != 0.)
 	}
@@ -464,6 +785,9 @@ impl Default for Stroke {
 			transform: DAffine2::IDENTITY,
 			non_scaling: false,
 			paint_order: PaintOrder::default(),
+			start_marker: StrokeMarker::None,
+			end_marker: StrokeMarker::None,
+			marker_size: 1.,
 		}
 	}
 }
@@ -473,12 +797,15 @@ impl Default for Stroke {
 pub struct PathStyle {
 	pub stroke: Option<Stroke>,
 	pub fill: Fill,
+	#[serde(default)]
+	pub fill_rule: FillRule,
 }
 
 impl std::hash::Hash for PathStyle {
 	fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
 		self.stroke.hash(state);
 		self.fill.hash(state);
+		self.fill_rule.hash(state);
 	}
 }
 
@@ -497,12 +824,17 @@ impl std::fmt::Display for PathStyle {
 
 impl PathStyle {
 	pub const fn new(stroke: Option<Stroke>, fill: Fill) -> Self {
-		Self { stroke, fill }
+		Self {
+			stroke,
+			fill,
+			fill_rule: FillRule::NonZero,
+		}
 	}
 
 	pub fn lerp(&self, other: &Self, time: f64) -> Self {
 		Self {
 			fill: self.fill.lerp(&other.fill, time),
+			fill_rule: if time < 0.5 { self.fill_rule } else { other.fill_rule },
 			stroke: match (self.stroke.as_ref(), other.stroke.as_ref()) {
 				(Some(a), Some(b)) => Some(a.lerp(b, time)),
 				(Some(a), None) => {
@@ -573,6 +905,15 @@ impl PathStyle {
 		self.fill = fill;
 	}
 
+	/// Get the [FillRule] deciding which regions of the path are filled.
+	pub fn fill_rule(&self) -> FillRule {
+		self.fill_rule
+	}
+
+	pub fn set_fill_rule(&mut self, fill_rule: FillRule) {
+		self.fill_rule = fill_rule;
+	}
+
 	pub fn set_stroke_transform(&mut self, transform: DAffine2) {
 		if let Some(stroke) = &mut self.stroke {
 			stroke.transform = transform;
@@ -646,3 +987,166 @@ pub enum ViewMode {
 	/// Render with normal coloration at the document resolution, showing the pixels when the current viewport resolution is higher
 	Pixels,
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::vector::VectorData;
+
+	#[test]
+	fn miter_join_extent_falls_back_to_bevel_past_the_limit() {
+		// A right angle has a miter ratio of √2, within the default limit of 4
+		let stroke = Stroke::new(Some(Color::BLACK), 2.);
+		assert!((stroke.join_extent(DVec2::X, DVec2::Y) - std::f64::consts::SQRT_2).abs() < 1e-10);
+
+		// A 10° angle has a miter ratio of about 11.5, so only a high limit keeps its spike
+		let incoming = DVec2::X;
+		let outgoing = DVec2::from_angle(std::f64::consts::PI - 10_f64.to_radians());
+		let ratio = miter_length_ratio(incoming, outgoing);
+		assert!((ratio - 1. / 5_f64.to_radians().sin()).abs() < 1e-10);
+		for limit in [1., 4., 11.] {
+			assert_eq!(stroke.clone().with_stroke_join_miter_limit(limit).join_extent(incoming, outgoing), 1.);
+		}
+		for limit in [12., 100.] {
+			assert_eq!(stroke.clone().with_stroke_join_miter_limit(limit).join_extent(incoming, outgoing), ratio);
+		}
+
+		// Continuing straight on has no spike, and other joins never have one
+		assert!((stroke.join_extent(DVec2::X, DVec2::X) - 1.).abs() < 1e-10);
+		assert_eq!(stroke.clone().with_stroke_join(StrokeJoin::Bevel).join_extent(incoming, outgoing), 1.);
+		assert_eq!(stroke.with_stroke_join(StrokeJoin::Round).join_extent(incoming, outgoing), 1.);
+	}
+
+	#[test]
+	fn dash_lengths_round_trip_through_text() {
+		let stroke = Stroke::new(Some(Color::BLACK), 2.).with_dash_lengths("4 2").unwrap();
+		assert_eq!(stroke.dash_lengths, vec![4., 2.]);
+		assert_eq!(stroke.dash_lengths(), "4, 2");
+
+		let reparsed = Stroke::default().with_dash_lengths(&stroke.dash_lengths()).unwrap();
+		assert_eq!(reparsed.dash_lengths, stroke.dash_lengths);
+
+		let solid = stroke.with_dash_lengths("none").unwrap();
+		assert!(solid.dash_lengths.is_empty());
+		assert!(Stroke::default().with_dash_lengths("4 two").is_none());
+	}
+
+	#[test]
+	fn dashed_stroke_serde_round_trip() {
+		let stroke = Stroke::new(Some(Color::BLACK), 2.).with_dash_lengths("4, 2").unwrap().with_dash_offset(1.5);
+		let serialized = serde_json::to_string(&stroke).unwrap();
+		let deserialized: Stroke = serde_json::from_str(&serialized).unwrap();
+		assert_eq!(deserialized, stroke);
+	}
+
+	#[test]
+	fn strokes_saved_without_dashes_deserialize_as_solid() {
+		let stroke: Stroke = serde_json::from_str(r#"{"color":null,"weight":3.0,"line_cap":"Round","line_join":"Bevel","line_join_miter_limit":4.0}"#).unwrap();
+		assert_eq!(stroke.weight, 3.);
+		assert_eq!(stroke.cap, StrokeCap::Round);
+		assert!(stroke.dash_lengths.is_empty());
+		assert_eq!(stroke.dash_offset, 0.);
+	}
+
+	#[test]
+	fn path_styles_saved_without_fill_rule_deserialize_as_nonzero() {
+		let style = PathStyle::new(None, Fill::solid(Color::BLACK));
+		let mut json = serde_json::to_value(&style).unwrap();
+		json.as_object_mut().unwrap().remove("fill_rule");
+
+		let deserialized: PathStyle = serde_json::from_value(json).unwrap();
+		assert_eq!(deserialized.fill_rule(), FillRule::NonZero);
+		assert_eq!(deserialized.fill, style.fill);
+	}
+
+	#[test]
+	fn renderable_dash_pattern_follows_svg_semantics() {
+		let dashed = |lengths: &str, offset: f64| Stroke::default().with_dash_lengths(lengths).unwrap().with_dash_offset(offset);
+
+		assert_eq!(dashed("4 2", 0.).renderable_dash_lengths(), vec![4., 2.]);
+		// Odd patterns repeat so dashes and gaps alternate on the second pass
+		assert_eq!(dashed("3", 0.).renderable_dash_lengths(), vec![3., 3.]);
+		assert_eq!(dashed("4 2 1", 0.).renderable_dash_lengths(), vec![4., 2., 1., 4., 2., 1.]);
+		// Negative lengths and patterns without any length render solid
+		assert!(dashed("4 -2", 0.).renderable_dash_lengths().is_empty());
+		assert!(dashed("0 0", 3.).renderable_dash_lengths().is_empty());
+		assert_eq!(dashed("0 0", 3.).renderable_dash_offset(), 0.);
+
+		// Offsets wrap within the pattern period, with negative offsets shifting it backwards
+		assert_eq!(dashed("4 2", 13.).renderable_dash_offset(), 1.);
+		assert_eq!(dashed("4 2", -1.).renderable_dash_offset(), 5.);
+	}
+
+	/// A square dot, which unlike a round one has bounds that are exactly known.
+	fn dot_pattern() -> Pattern {
+		let dot = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(10.)));
+		Pattern {
+			tile: GraphicGroupTable::from(dot),
+			spacing: DVec2::new(10., 5.),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn pattern_cells_cover_the_filled_bounds() {
+		let pattern = dot_pattern();
+		assert_eq!(pattern.cell_size(), Some(DVec2::new(20., 15.)));
+
+		// A 40×30 shape is covered by a 2×2 grid of cells, each with a dot at its corner
+		let transforms = pattern.tile_transforms([DVec2::ZERO, DVec2::new(40., 30.)]);
+		let corners: Vec<_> = transforms.iter().map(|transform| transform.translation).collect();
+		assert_eq!(corners, [DVec2::new(0., 0.), DVec2::new(20., 0.), DVec2::new(0., 15.), DVec2::new(20., 15.)]);
+
+		// Shifting the grid by a fraction of a cell needs an extra row and column to keep the shape covered
+		let offset = Pattern {
+			offset: DVec2::splat(5.),
+			..dot_pattern()
+		};
+		assert_eq!(offset.tile_transforms([DVec2::ZERO, DVec2::new(40., 30.)]).len(), 9);
+
+		// Without a tile there's nothing to repeat
+		assert!(Pattern::default().tile_transforms([DVec2::ZERO, DVec2::splat(100.)]).is_empty());
+		assert!(Pattern { scale: 0., ..dot_pattern() }.cell_size().is_none());
+	}
+
+	#[test]
+	fn pattern_tile_larger_than_the_shape_is_drawn_once() {
+		let pattern = Pattern { scale: 10., ..dot_pattern() };
+		let transforms = pattern.tile_transforms([DVec2::splat(1.), DVec2::splat(5.)]);
+		assert_eq!(transforms.len(), 1);
+		assert_eq!(transforms[0].transform_point2(DVec2::splat(10.)), DVec2::splat(100.));
+
+		// Rotating the grid keeps the shape covered
+		let rotated = Pattern { rotation: 45., ..dot_pattern() };
+		let bounds = [DVec2::ZERO, DVec2::new(40., 30.)];
+		let cell_corners: Vec<_> = rotated.tile_transforms(bounds).iter().map(|transform| transform.translation).collect();
+		let grid = rotated.grid_transform().inverse();
+		for point in [DVec2::ZERO, DVec2::new(40., 0.), DVec2::new(0., 30.), DVec2::new(40., 30.), DVec2::new(20., 15.)] {
+			let point = grid.transform_point2(point);
+			let cell = (point / DVec2::new(20., 15.)).floor() * DVec2::new(20., 15.);
+			assert!(cell_corners.iter().any(|corner| grid.transform_point2(*corner).distance(cell) < 1e-9), "{point} isn't covered");
+		}
+	}
+
+	#[test]
+	fn pattern_fills_round_trip_and_hash_their_tile() {
+		use std::hash::{DefaultHasher, Hash, Hasher};
+		let hash = |fill: &Fill| {
+			let mut hasher = DefaultHasher::new();
+			fill.hash(&mut hasher);
+			hasher.finish()
+		};
+
+		let fill = Fill::Pattern(Pattern { rotation: 30., ..dot_pattern() });
+		let deserialized: Fill = serde_json::from_str(&serde_json::to_string(&fill).unwrap()).unwrap();
+		assert_eq!(deserialized, fill);
+		assert_eq!(hash(&deserialized), hash(&fill));
+
+		let without_tile = Fill::Pattern(Pattern {
+			tile: GraphicGroupTable::default(),
+			..dot_pattern()
+		});
+		assert_ne!(hash(&without_tile), hash(&fill));
+		assert_eq!(FillChoice::from(fill), FillChoice::Pattern);
+	}
+}
@@ -3,7 +3,7 @@ mod indexed;
 mod modification;
 
 use super::misc::{dvec2_to_point, point_to_dvec2};
-use super::style::{PathStyle, Stroke};
+use super::style::{PathStyle, Stroke, StrokeAlign, StrokeCap, StrokeJoin, StrokeMarker};
 use crate::bounds::BoundingBox;
 use crate::instances::Instances;
 use crate::math::quad::Quad;
@@ -264,6 +264,109 @@ impl VectorData {
 			.reduce(combine)
 	}
 
+	/// The stroke alignment used when rendering, since inside and outside alignment only apply to visible strokes on shapes whose subpaths are all closed.
+	/// Open paths fall back to a centered stroke.
+	pub fn effective_stroke_align(&self) -> StrokeAlign {
+		let closed = self.stroke_bezier_paths().all(|subpath| subpath.closed());
+		self.style
+			.stroke()
+			.filter(|stroke| closed && stroke.has_renderable_stroke())
+			.map_or(StrokeAlign::Center, |stroke| stroke.align)
+	}
+
+	/// The farthest the stroke reaches from the path it follows, as a multiple of half its weight.
+	///
+	/// Square caps on open subpaths reach out diagonally to the corners of the cap, and miter joins reach out to their tip unless they exceed the miter limit (see [`Stroke::join_extent`]).
+	pub fn stroke_extent_factor(&self) -> f64 {
+		let Some(stroke) = self.style.stroke() else { return 1. };
+
+		let mut factor: f64 = 1.;
+		for subpath in self.stroke_bezier_paths() {
+			if stroke.cap == StrokeCap::Square && !subpath.closed() {
+				factor = factor.max(std::f64::consts::SQRT_2);
+			}
+			if stroke.join != StrokeJoin::Miter {
+				continue;
+			}
+
+			let segments: Vec<_> = subpath.iter().collect();
+			let closing_join = subpath.closed().then(|| segments.last().zip(segments.first())).flatten();
+			let joins = segments.iter().zip(segments.iter().skip(1)).chain(closing_join);
+			for (incoming, outgoing) in joins {
+				let extent = stroke.join_extent(incoming.tangent(bezier_rs::TValue::Parametric(1.)), outgoing.tangent(bezier_rs::TValue::Parametric(0.)));
+				factor = factor.max(extent);
+			}
+		}
+		factor
+	}
+
+	/// Construct the outlines that a centered stroke would follow to cover the same area as this shape's aligned stroke, each paired with the width of that centered stroke.
+	///
+	/// Inside and outside aligned strokes are offset by half the stroke weight, while centered strokes keep the original outlines.
+	/// An inside aligned stroke at least as wide as its shape covers the whole shape, so the original outline is given with no width.
+	pub fn aligned_stroke_bezier_paths(&self) -> impl Iterator<Item = (bezier_rs::Subpath<PointId>, f64)> + '_ {
+		let align = self.effective_stroke_align();
+		let stroke = self.style.stroke().unwrap_or_default();
+		let weight = stroke.weight.max(0.);
+		let join = match stroke.join {
+			StrokeJoin::Miter => bezier_rs::Join::Miter(Some(stroke.join_miter_limit)),
+			StrokeJoin::Bevel => bezier_rs::Join::Bevel,
+			StrokeJoin::Round => bezier_rs::Join::Round,
+		};
+
+		self.stroke_bezier_paths().map(move |subpath| {
+			let Some([min, max]) = subpath.bounding_box().filter(|_| align.is_not_centered()) else {
+				return (subpath, weight);
+			};
+			let size = max - min;
+			if align == StrokeAlign::Inside && size.min_element() <= weight {
+				return (subpath, 0.);
+			}
+
+			// The direction of a positive offset depends on the winding of the subpath, so check which way it went
+			let half_weight = weight / 2.;
+			let offset = subpath.offset(half_weight, join);
+			let grew = offset.bounding_box().is_some_and(|[min, max]| (max - min).element_sum() > size.element_sum());
+			let offset = if grew == (align == StrokeAlign::Outside) { offset } else { subpath.offset(-half_weight, join) };
+
+			(offset, weight)
+		})
+	}
+
+	/// The placements of the stroke's start and end markers, each mapping from the marker's outline (see [`StrokeMarker::outline`]) into the space of the given transform.
+	///
+	/// Like in SVG, the start marker sits on the first point of the first subpath and the end marker on the last point of the last subpath, each pointing away from the path along its tangent there.
+	/// Markers are left off of shapes with closed subpaths.
+	pub fn stroke_marker_transforms(&self, transform: DAffine2) -> [Option<(StrokeMarker, DAffine2)>; 2] {
+		let Some(stroke) = self.style.stroke().filter(Stroke::has_renderable_stroke) else {
+			return [None, None];
+		};
+		let mut subpaths = self.stroke_bezier_paths().collect::<Vec<_>>();
+		if subpaths.iter().any(|subpath| subpath.closed()) {
+			return [None, None];
+		}
+		subpaths.iter_mut().for_each(|subpath| subpath.apply_transform(transform));
+
+		let scale = stroke.weight * stroke.marker_size;
+		let place = |marker: StrokeMarker, points: Vec<DVec2>| {
+			if marker == StrokeMarker::None {
+				return None;
+			}
+			let (&endpoint, rest) = points.split_first()?;
+			// Skip over control points that coincide with the endpoint to find the direction the path leaves it
+			let inward = rest.iter().map(|&point| point - endpoint).find(|direction| direction.length_squared() > f64::EPSILON)?;
+			Some((marker, DAffine2::from_scale_angle_translation(DVec2::splat(scale), (-inward).to_angle(), endpoint)))
+		};
+
+		let start_points = subpaths.first().map(|subpath| subpath.iter().flat_map(|bezier| bezier.get_points()).collect());
+		let end_points = subpaths.last().map(|subpath| {
+			let beziers = subpath.iter().collect::<Vec<_>>();
+			beziers.into_iter().rev().flat_map(|bezier| bezier.reverse().get_points()).collect()
+		});
+
+		[place(stroke.start_marker, start_points.unwrap_or_default()), place(stroke.end_marker, end_points.unwrap_or_default())]
+	}
+
 	/// Calculate the corners of the bounding box but with a nonzero size.
 	///
 	/// If the layer bounds are `0` in either axis then they are changed to be `1`.
@@ -498,14 +601,26 @@ impl BoundingBox for VectorDataTable {
 
 				let stroke_width = instance.instance.style.stroke().map(|s| s.weight()).unwrap_or_default();
 
-				let miter_limit = instance.instance.style.stroke().map(|s| s.join_miter_limit).unwrap_or(1.);
-
 				let scale = transform.decompose_scale();
 
-				// We use the full line width here to account for different styles of stroke caps
-				let offset = DVec2::splat(stroke_width * scale.x.max(scale.y) * miter_limit);
+				// Centered strokes reach half the weight outward, inside aligned strokes never leave the shape, and outside aligned strokes on closed paths reach the full weight outward.
+				// Caps and joins can reach further (but miter joins past the miter limit are beveled), which is measured as a multiple of that distance.
+				let outward_width = match instance.instance.effective_stroke_align() {
+					StrokeAlign::Center => stroke_width / 2.,
+					StrokeAlign::Outside => stroke_width,
+					StrokeAlign::Inside => 0.,
+				};
+				let offset = DVec2::splat(outward_width * scale.x.max(scale.y) * instance.instance.stroke_extent_factor());
+
+				let stroke_bounds = instance.instance.bounding_box_with_transform(transform * *instance.transform).map(|[a, b]| [a - offset, b + offset]);
+				let marker_bounds = instance
+					.instance
+					.stroke_marker_transforms(DAffine2::IDENTITY)
+					.into_iter()
+					.flatten()
+					.filter_map(|(marker, marker_transform)| marker.outline()?.bounding_box_with_transform(transform * *instance.transform * marker_transform));
 
-				instance.instance.bounding_box_with_transform(transform * *instance.transform).map(|[a, b]| [a - offset, b + offset])
+				stroke_bounds.into_iter().chain(marker_bounds).reduce(Quad::combine_bounds)
 			})
 			.reduce(Quad::combine_bounds)
 	}
@@ -749,4 +864,221 @@ mod tests {
 		let generated = vector_data.stroke_bezier_paths().collect::<Vec<_>>();
 		assert_subpath_eq(&generated, &[curve, circle]);
 	}
+
+	/// A 100×50 rectangle with a stroke of the given alignment and weight.
+	fn aligned_rectangle(align: StrokeAlign, weight: f64) -> VectorData {
+		let mut vector_data = VectorData::from_subpath(bezier_rs::Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 50.)));
+		vector_data.style.set_stroke(Stroke::new(Some(Color::BLACK), weight).with_stroke_align(align));
+		vector_data
+	}
+
+	fn single_aligned_outline(vector_data: &VectorData) -> ([DVec2; 2], f64) {
+		let outlines = vector_data.aligned_stroke_bezier_paths().collect::<Vec<_>>();
+		assert_eq!(outlines.len(), 1);
+		let (subpath, width) = &outlines[0];
+		(subpath.bounding_box().unwrap(), *width)
+	}
+
+	fn assert_bounds_eq(bounds: [DVec2; 2], expected: [DVec2; 2]) {
+		assert!(
+			bounds[0].abs_diff_eq(expected[0], 1e-6) && bounds[1].abs_diff_eq(expected[1], 1e-6),
+			"Expected bounds {expected:?} but got {bounds:?}"
+		);
+	}
+
+	#[test]
+	fn outside_stroke_outline_grows_by_half_the_weight() {
+		for weight in [1., 10., 80., 500.] {
+			let (bounds, width) = single_aligned_outline(&aligned_rectangle(StrokeAlign::Outside, weight));
+			assert_bounds_eq(bounds, [DVec2::splat(-weight / 2.), DVec2::new(100., 50.) + weight / 2.]);
+			assert_eq!(width, weight);
+		}
+	}
+
+	#[test]
+	fn inside_stroke_outline_shrinks_by_half_the_weight() {
+		for weight in [1., 10., 40.] {
+			let (bounds, width) = single_aligned_outline(&aligned_rectangle(StrokeAlign::Inside, weight));
+			assert_bounds_eq(bounds, [DVec2::splat(weight / 2.), DVec2::new(100., 50.) - weight / 2.]);
+			assert_eq!(width, weight);
+		}
+	}
+
+	#[test]
+	fn inside_stroke_wider_than_shape_covers_it() {
+		for weight in [50., 80., 500.] {
+			let (bounds, width) = single_aligned_outline(&aligned_rectangle(StrokeAlign::Inside, weight));
+			assert_bounds_eq(bounds, [DVec2::ZERO, DVec2::new(100., 50.)]);
+			assert_eq!(width, 0.);
+		}
+	}
+
+	#[test]
+	fn open_path_stroke_stays_centered() {
+		let line = bezier_rs::Subpath::new_line(DVec2::ZERO, DVec2::new(100., 0.));
+		let mut vector_data = VectorData::from_subpath(&line);
+		vector_data.style.set_stroke(Stroke::new(Some(Color::BLACK), 10.).with_stroke_align(StrokeAlign::Outside));
+
+		assert_eq!(vector_data.effective_stroke_align(), StrokeAlign::Center);
+		let (bounds, width) = single_aligned_outline(&vector_data);
+		assert_bounds_eq(bounds, [DVec2::ZERO, DVec2::new(100., 0.)]);
+		assert_eq!(width, 10.);
+	}
+
+	#[test]
+	fn aligned_stroke_bounding_box() {
+		for weight in [1., 10., 80., 500.] {
+			let inside = VectorDataTable::new(aligned_rectangle(StrokeAlign::Inside, weight));
+			assert_bounds_eq(inside.bounding_box(DAffine2::IDENTITY, true).unwrap(), [DVec2::ZERO, DVec2::new(100., 50.)]);
+
+			let outside = VectorDataTable::new(aligned_rectangle(StrokeAlign::Outside, weight));
+			let [min, max] = outside.bounding_box(DAffine2::IDENTITY, true).unwrap();
+			assert!(min.cmple(DVec2::splat(-weight)).all() && max.cmpge(DVec2::new(100., 50.) + weight).all());
+		}
+	}
+
+	/// A thin closed triangle whose tip at (100, 10) forms an angle of about 11.4°, giving a miter ratio of about 10.
+	fn acute_triangle(miter_limit: f64) -> VectorData {
+		let points = [DVec2::ZERO, DVec2::new(100., 10.), DVec2::new(0., 20.)];
+		let mut vector_data = VectorData::from_subpath(bezier_rs::Subpath::from_anchors_linear(points, true));
+		vector_data.style.set_stroke(Stroke::new(Some(Color::BLACK), 2.).with_stroke_join_miter_limit(miter_limit));
+		vector_data
+	}
+
+	#[test]
+	fn acute_miter_bounds_follow_the_miter_limit() {
+		let tip_ratio = 1. / (10_f64 / 100.).atan().sin();
+		// The two corners at the base are nearly right angles
+		let base_ratio = crate::vector::style::miter_length_ratio(DVec2::new(0., -20.), DVec2::new(100., 10.));
+
+		for miter_limit in [1., 4., 10., 11., 100.] {
+			let [_, max] = VectorDataTable::new(acute_triangle(miter_limit)).bounding_box(DAffine2::IDENTITY, true).unwrap();
+			let reach = max.x - 100.;
+
+			// The bounds reach as far as the longest miter within the limit, since longer ones are beveled off within half the weight
+			let expected = [tip_ratio, base_ratio].into_iter().filter(|&ratio| ratio <= miter_limit).fold(1., f64::max);
+			assert!(
+				(reach - expected).abs() < 1e-9,
+				"With a miter limit of {miter_limit}, expected the bounds to reach {expected} but got {reach}"
+			);
+			assert!(reach <= miter_limit.max(1.) + 1e-9);
+		}
+	}
+
+	#[test]
+	fn acute_outside_stroke_outline_is_beveled_past_the_miter_limit() {
+		let tip = |miter_limit: f64| {
+			let mut vector_data = acute_triangle(miter_limit);
+			vector_data.style.set_stroke(vector_data.style.stroke().unwrap().with_stroke_align(StrokeAlign::Outside));
+			single_aligned_outline(&vector_data).0[1].x - 100.
+		};
+
+		// The outline is offset by half the weight, so its miter would reach about 10 units past the tip
+		assert!(tip(4.) <= 1. + 1e-6, "A beveled tip should stay within the offset distance, got {}", tip(4.));
+		assert!(tip(100.) > 9.9, "An unlimited miter should reach its full length, got {}", tip(100.));
+	}
+
+	#[test]
+	fn aligned_stroke_hit_testing() {
+		use crate::vector::click_target::ClickTarget;
+
+		let hits = |align: StrokeAlign, point: DVec2| {
+			aligned_rectangle(align, 10.)
+				.aligned_stroke_bezier_paths()
+				.any(|(subpath, width)| ClickTarget::new_with_subpath(subpath, width).intersect_point(point, DAffine2::IDENTITY))
+		};
+
+		// Just outside the left edge, within reach of a centered stroke
+		let near = DVec2::new(-3., 25.);
+		assert!(hits(StrokeAlign::Center, near));
+		assert!(!hits(StrokeAlign::Inside, near));
+		assert!(hits(StrokeAlign::Outside, near));
+
+		// Further outside the left edge, only within reach of an outside stroke
+		let far = DVec2::new(-8., 25.);
+		assert!(!hits(StrokeAlign::Center, far));
+		assert!(!hits(StrokeAlign::Inside, far));
+		assert!(hits(StrokeAlign::Outside, far));
+	}
+
+	/// An open cubic arching from the origin to (100, 0), leaving and arriving straight up and down, with a stroke carrying arrow markers at both ends.
+	fn arrowed_arch(handle_start: DVec2, handle_end: DVec2) -> VectorData {
+		let bezier = bezier_rs::Bezier::from_cubic_dvec2(DVec2::ZERO, handle_start, handle_end, DVec2::new(100., 0.));
+		let mut vector_data = VectorData::from_subpath(bezier_rs::Subpath::from_bezier(&bezier));
+		let stroke = Stroke::new(Some(Color::BLACK), 2.)
+			.with_start_marker(StrokeMarker::Arrow)
+			.with_end_marker(StrokeMarker::Triangle)
+			.with_marker_size(1.5);
+		vector_data.style.set_stroke(stroke);
+		vector_data
+	}
+
+	fn assert_marker_placement(placement: Option<(StrokeMarker, DAffine2)>, marker: StrokeMarker, position: DVec2, direction: DVec2, scale: f64) {
+		let (placed_marker, transform) = placement.expect("The marker should be placed");
+		assert_eq!(placed_marker, marker);
+		assert!(
+			transform.translation.abs_diff_eq(position, 1e-9),
+			"Expected the marker at {position:?} but it is at {:?}",
+			transform.translation
+		);
+		let pointing = transform.transform_vector2(DVec2::X);
+		assert!((pointing.length() - scale).abs() < 1e-9, "Expected a marker scale of {scale} but got {}", pointing.length());
+		assert!(
+			pointing.normalize().abs_diff_eq(direction.normalize(), 1e-9),
+			"Expected the marker to point along {direction:?} but it points along {pointing:?}"
+		);
+	}
+
+	#[test]
+	fn markers_point_away_from_curved_endpoints() {
+		let vector_data = arrowed_arch(DVec2::new(0., 100.), DVec2::new(100., 100.));
+		let [start, end] = vector_data.stroke_marker_transforms(DAffine2::IDENTITY);
+
+		// The curve leaves its start heading towards +Y, so the start marker points back along -Y, and likewise at the end
+		assert_marker_placement(start, StrokeMarker::Arrow, DVec2::ZERO, DVec2::NEG_Y, 3.);
+		assert_marker_placement(end, StrokeMarker::Triangle, DVec2::new(100., 0.), DVec2::NEG_Y, 3.);
+	}
+
+	#[test]
+	fn markers_follow_skewed_tangents() {
+		let vector_data = arrowed_arch(DVec2::new(30., 40.), DVec2::new(60., -20.));
+		let [start, end] = vector_data.stroke_marker_transforms(DAffine2::IDENTITY);
+
+		assert_marker_placement(start, StrokeMarker::Arrow, DVec2::ZERO, DVec2::new(-30., -40.), 3.);
+		assert_marker_placement(end, StrokeMarker::Triangle, DVec2::new(100., 0.), DVec2::new(40., 20.), 3.);
+	}
+
+	#[test]
+	fn markers_skip_handles_on_the_endpoint() {
+		// With each handle retracted into its anchor, the tangent comes from the opposite handle
+		let vector_data = arrowed_arch(DVec2::ZERO, DVec2::new(100., 0.));
+		let [start, end] = vector_data.stroke_marker_transforms(DAffine2::IDENTITY);
+		assert_marker_placement(start, StrokeMarker::Arrow, DVec2::ZERO, DVec2::NEG_X, 3.);
+		assert_marker_placement(end, StrokeMarker::Triangle, DVec2::new(100., 0.), DVec2::X, 3.);
+
+		let vector_data = arrowed_arch(DVec2::ZERO, DVec2::new(50., 50.));
+		let [start, end] = vector_data.stroke_marker_transforms(DAffine2::IDENTITY);
+		assert_marker_placement(start, StrokeMarker::Arrow, DVec2::ZERO, DVec2::new(-50., -50.), 3.);
+		assert_marker_placement(end, StrokeMarker::Triangle, DVec2::new(100., 0.), DVec2::new(50., -50.), 3.);
+	}
+
+	#[test]
+	fn markers_follow_the_transform() {
+		let vector_data = arrowed_arch(DVec2::new(0., 100.), DVec2::new(100., 100.));
+		let transform = DAffine2::from_angle(std::f64::consts::FRAC_PI_2);
+		let [start, end] = vector_data.stroke_marker_transforms(transform);
+
+		// The path is rotated a quarter turn, but the markers keep their size in the transformed space like the stroke weight does
+		assert_marker_placement(start, StrokeMarker::Arrow, DVec2::ZERO, DVec2::X, 3.);
+		assert_marker_placement(end, StrokeMarker::Triangle, DVec2::new(0., 100.), DVec2::X, 3.);
+	}
+
+	#[test]
+	fn closed_paths_have_no_markers() {
+		let mut vector_data = aligned_rectangle(StrokeAlign::Center, 2.);
+		let stroke = vector_data.style.stroke().unwrap().with_start_marker(StrokeMarker::Arrow).with_end_marker(StrokeMarker::Circle);
+		vector_data.style.set_stroke(stroke);
+
+		assert_eq!(vector_data.stroke_marker_transforms(DAffine2::IDENTITY), [None, None]);
+	}
 }
@@ -2,7 +2,7 @@ use super::*;
 use crate::Ctx;
 use crate::instances::Instance;
 use crate::uuid::{NodeId, generate_uuid};
-use bezier_rs::BezierHandles;
+use bezier_rs::{BezierHandles, TValue};
 use dyn_any::DynAny;
 use kurbo::{BezPath, PathEl, Point};
 use std::collections::{HashMap, HashSet};
@@ -418,6 +418,214 @@ impl Hash for VectorModification {
 	}
 }
 
+/// An edit made with a [`VectorModificationBuilder`] which couldn't be carried out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VectorModificationError {
+	/// The point doesn't exist in the vector data, or has already been deleted.
+	MissingPoint(PointId),
+	/// The segment doesn't exist in the vector data, or has already been deleted.
+	MissingSegment(SegmentId),
+	/// The point is where more than one segment ends (for its in handle) or begins (for its out handle), so which handle to change is unclear.
+	AmbiguousHandle(PointId),
+}
+
+impl std::fmt::Display for VectorModificationError {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		match self {
+			Self::MissingPoint(point) => write!(f, "The point {} doesn't exist", point.inner()),
+			Self::MissingSegment(segment) => write!(f, "The segment {} doesn't exist", segment.inner()),
+			Self::AmbiguousHandle(point) => write!(f, "The point {} has more than one segment connected on the same side, so its handle is ambiguous", point.inner()),
+		}
+	}
+}
+
+impl std::error::Error for VectorModificationError {}
+
+/// Builds up the [`VectorModificationType`]s for editing a [`VectorData`], generating the IDs of new points and segments and checking that those being edited exist.
+///
+/// Each edit sees the result of the edits before it, so a segment can be inserted between points which were inserted earlier.
+/// Handle positions are given in the same space as the anchors, rather than relative to them.
+#[derive(Clone, Debug)]
+pub struct VectorModificationBuilder {
+	vector_data: VectorData,
+	modifications: Vec<VectorModificationType>,
+}
+
+impl VectorModificationBuilder {
+	/// Start building edits to the specified [`VectorData`], which is the data the resulting modifications should be applied to.
+	pub fn new(vector_data: &VectorData) -> Self {
+		Self {
+			vector_data: vector_data.clone(),
+			modifications: Vec::new(),
+		}
+	}
+
+	/// The vector data as it is after the edits made so far.
+	pub fn vector_data(&self) -> &VectorData {
+		&self.vector_data
+	}
+
+	/// The modifications which make the edits, in the order they need to be applied.
+	pub fn finish(self) -> Vec<VectorModificationType> {
+		self.modifications
+	}
+
+	fn push(&mut self, modification_type: VectorModificationType) {
+		let mut modification = VectorModification::default();
+		modification.modify(&modification_type);
+		modification.apply(&mut self.vector_data);
+
+		self.modifications.push(modification_type);
+	}
+
+	fn check_point(&self, point: PointId) -> Result<DVec2, VectorModificationError> {
+		self.vector_data.point_domain.position_from_id(point).ok_or(VectorModificationError::MissingPoint(point))
+	}
+
+	/// Split a segment in two with a new anchor at the given position along it, keeping the shape of the curve unchanged.
+	pub fn insert_point(&mut self, position: TValue, segment: SegmentId) -> Result<PointId, VectorModificationError> {
+		let (start, end, bezier) = self.vector_data.segment_points_from_id(segment).ok_or(VectorModificationError::MissingSegment(segment))?;
+		let [first, second] = bezier.split(position);
+
+		let point = PointId::generate();
+		self.push(VectorModificationType::InsertPoint { id: point, position: first.end });
+
+		let segments = [SegmentId::generate(), SegmentId::generate()];
+		for (id, points, bezier) in [(segments[0], [start, point], first), (segments[1], [point, end], second)] {
+			let handles = [bezier.handle_start().map(|handle| handle - bezier.start), bezier.handle_end().map(|handle| handle - bezier.end)];
+			self.push(VectorModificationType::InsertSegment { id, points, handles });
+		}
+
+		// Replace the old segment, moving its colinear handles over to the new segments
+		let colinear: Vec<_> = self
+			.vector_data
+			.colinear_manipulators
+			.iter()
+			.copied()
+			.filter(|handles| handles.iter().any(|handle| handle.segment == segment))
+			.collect();
+		self.push(VectorModificationType::RemoveSegment { id: segment });
+		for handles in colinear {
+			let handles = handles.map(|handle| match handle.ty {
+				_ if handle.segment != segment => handle,
+				HandleType::Primary => HandleId::primary(segments[0]),
+				HandleType::End => HandleId::end(segments[1]),
+			});
+			self.push(VectorModificationType::SetG1Continuous { handles, enabled: true });
+		}
+
+		// The handles on either side of the new anchor are split from a smooth curve, so they stay colinear
+		if bezier.handle_end().is_some() {
+			let handles = [HandleId::end(segments[0]), HandleId::primary(segments[1])];
+			self.push(VectorModificationType::SetG1Continuous { handles, enabled: true });
+		}
+
+		Ok(point)
+	}
+
+	/// Set the handles of an anchor, where the in handle belongs to the segment ending at the anchor and the out handle to the segment beginning there.
+	/// A handle of `None` is retracted into the anchor. A handle is ignored if there's no segment on its side of the anchor.
+	pub fn set_handles(&mut self, point: PointId, in_handle: Option<DVec2>, out_handle: Option<DVec2>) -> Result<(), VectorModificationError> {
+		let position = self.check_point(point)?;
+		let connected = |is_end: bool| {
+			let mut segments = self.vector_data.segment_bezier_iter().filter(|&(_, _, start, end)| if is_end { end == point } else { start == point });
+			match (segments.next(), segments.next()) {
+				(_, Some(_)) => Err(VectorModificationError::AmbiguousHandle(point)),
+				(segment, None) => Ok(segment.map(|(id, bezier, _, _)| (id, bezier))),
+			}
+		};
+		let (incoming, outgoing) = (connected(true)?, connected(false)?);
+
+		if let Some((segment, bezier)) = incoming {
+			let handles = [
+				handle_position(&bezier, HandleType::Primary).map(|handle| handle - bezier.start),
+				in_handle.map(|handle| handle - position),
+			];
+			self.push(VectorModificationType::SetHandles { segment, handles });
+		}
+		if let Some((segment, bezier)) = outgoing {
+			let handles = [out_handle.map(|handle| handle - position), handle_position(&bezier, HandleType::End).map(|handle| handle - bezier.end)];
+			self.push(VectorModificationType::SetHandles { segment, handles });
+		}
+
+		Ok(())
+	}
+
+	/// Delete an anchor along with its segments. If it joined exactly two segments, its neighbors are connected by a new segment which keeps their outer handles.
+	pub fn delete_point(&mut self, point: PointId) -> Result<(), VectorModificationError> {
+		self.check_point(point)?;
+
+		// The handle at the other end of each connected segment, along with the anchor it belongs to
+		let mut neighbors: Vec<_> = self
+			.vector_data
+			.all_connected(point)
+			.filter_map(|handle| {
+				let (start, end, bezier) = self.vector_data.segment_points_from_id(handle.segment)?;
+				let outer = handle.opposite();
+				let (anchor, anchor_position) = match outer.ty {
+					HandleType::Primary => (start, bezier.start),
+					HandleType::End => (end, bezier.end),
+				};
+				let relative_handle = handle_position(&bezier, outer.ty).map(|handle| handle - anchor_position);
+				Some((outer, anchor, relative_handle))
+			})
+			.collect();
+		// Keep the direction of the path by starting the new segment from the anchor before the deleted one
+		neighbors.sort_by_key(|(outer, _, _)| outer.ty == HandleType::End);
+		let colinear: Vec<_> = self.vector_data.colinear_manipulators.clone();
+
+		for (outer, _, _) in &neighbors {
+			self.push(VectorModificationType::RemoveSegment { id: outer.segment });
+		}
+		self.push(VectorModificationType::RemovePoint { id: point });
+
+		if let [(first_outer, first, first_handle), (second_outer, second, second_handle)] = neighbors[..]
+			&& first != point
+			&& second != point
+			&& first != second
+		{
+			let id = SegmentId::generate();
+			self.push(VectorModificationType::InsertSegment {
+				id,
+				points: [first, second],
+				handles: [first_handle, second_handle],
+			});
+
+			// Keep the neighbors' handles colinear with the handles of their other segments
+			for (outer, replacement) in [(first_outer, HandleId::primary(id)), (second_outer, HandleId::end(id))] {
+				for handles in colinear.iter().filter(|handles| handles.contains(&outer)) {
+					let handles = handles.map(|handle| if handle == outer { replacement } else { handle });
+					self.push(VectorModificationType::SetG1Continuous { handles, enabled: true });
+				}
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Connect two anchors with a new segment, which is a straight line if both of its handles are `None`.
+	pub fn insert_segment(&mut self, start: PointId, end: PointId, handles: [Option<DVec2>; 2]) -> Result<SegmentId, VectorModificationError> {
+		let start_position = self.check_point(start)?;
+		let end_position = self.check_point(end)?;
+
+		let id = SegmentId::generate();
+		let handles = [handles[0].map(|handle| handle - start_position), handles[1].map(|handle| handle - end_position)];
+		self.push(VectorModificationType::InsertSegment { id, points: [start, end], handles });
+
+		Ok(id)
+	}
+}
+
+/// The position of the handle at the start or end of a segment, where the single handle of a quadratic segment belongs to both ends.
+fn handle_position(bezier: &bezier_rs::Bezier, ty: HandleType) -> Option<DVec2> {
+	match (bezier.handles, ty) {
+		(BezierHandles::Linear, _) => None,
+		(BezierHandles::Quadratic { handle }, _) => Some(handle),
+		(BezierHandles::Cubic { handle_start, .. }, HandleType::Primary) => Some(handle_start),
+		(BezierHandles::Cubic { handle_end, .. }, HandleType::End) => Some(handle_end),
+	}
+}
+
 /// Applies a diff modification to a vector path.
 #[node_macro::node(category(""))]
 async fn path_modify(_ctx: impl Ctx, mut vector_data: VectorDataTable, modification: Box<VectorModification>, node_path: Vec<NodeId>) -> VectorDataTable {
@@ -744,4 +952,115 @@ mod tests {
 			Bezier::from_quadratic_dvec2(DVec2::new(11., 0.), DVec2::new(16., 10.), DVec2::new(20., 0.))
 		);
 	}
+
+	fn apply(vector_data: &VectorData, modifications: Vec<VectorModificationType>) -> VectorData {
+		let mut modification = VectorModification::default();
+		for modification_type in &modifications {
+			modification.modify(modification_type);
+		}
+		let mut vector_data = vector_data.clone();
+		modification.apply(&mut vector_data);
+		vector_data
+	}
+
+	#[test]
+	fn builder_inserts_point_without_changing_the_curve() {
+		use bezier_rs::{Bezier, Subpath};
+		let bezier = Bezier::from_cubic_dvec2(DVec2::ZERO, DVec2::new(0., 10.), DVec2::new(20., 10.), DVec2::new(20., 0.));
+		let vector_data = VectorData::from_subpath(Subpath::from_bezier(&bezier));
+		let segment = vector_data.segment_domain.ids()[0];
+
+		let mut builder = VectorModificationBuilder::new(&vector_data);
+		let point = builder.insert_point(TValue::Parametric(0.5), segment).unwrap();
+		let expected = builder.vector_data().clone();
+		let modified = apply(&vector_data, builder.finish());
+		assert_eq!(modified, expected);
+
+		let position = modified.point_domain.position_from_id(point).unwrap();
+		assert!(position.abs_diff_eq(bezier.evaluate(TValue::Parametric(0.5)), 1e-9));
+		assert_eq!(modified.segment_domain.ids().len(), 2);
+		for ((_, split, _, _), expected) in modified.segment_bezier_iter().zip(bezier.split(TValue::Parametric(0.5))) {
+			for (split, expected) in split.get_points().zip(expected.get_points()) {
+				assert!(split.abs_diff_eq(expected, 1e-9), "{split} should equal {expected}");
+			}
+		}
+
+		// The handles either side of the new anchor stay colinear
+		let [first, second] = [0, 1].map(|index| modified.segment_domain.ids()[index]);
+		assert_eq!(modified.colinear_manipulators, [[HandleId::end(first), HandleId::primary(second)]]);
+	}
+
+	#[test]
+	fn builder_sets_handles_and_inserts_segments() {
+		let points = [DVec2::ZERO, DVec2::new(10., 0.), DVec2::new(20., 0.)];
+		let vector_data = VectorData::from_subpath(bezier_rs::Subpath::from_anchors_linear(points, false));
+		let [first, middle, last] = [0, 1, 2].map(|index| vector_data.point_domain.ids()[index]);
+
+		let mut builder = VectorModificationBuilder::new(&vector_data);
+		builder.set_handles(middle, Some(DVec2::new(5., 5.)), Some(DVec2::new(15., -5.))).unwrap();
+		// No segment ends at the first anchor, so its in handle is ignored, while its out handle was already retracted
+		builder.set_handles(first, Some(DVec2::splat(100.)), None).unwrap();
+		let closing = builder.insert_segment(last, first, [Some(DVec2::new(20., 10.)), Some(DVec2::new(0., 10.))]).unwrap();
+		let modified = apply(&vector_data, builder.finish());
+
+		let beziers: Vec<_> = modified.segment_bezier_iter().map(|(_, bezier, _, _)| bezier).collect();
+		assert_eq!(beziers[0].handles, BezierHandles::Quadratic { handle: DVec2::new(5., 5.) });
+		assert_eq!(beziers[1].handles, BezierHandles::Quadratic { handle: DVec2::new(15., -5.) });
+		assert_eq!(modified.segment_points_from_id(closing).map(|(start, end, _)| [start, end]), Some([last, first]));
+		assert_eq!(
+			beziers[2].handles,
+			BezierHandles::Cubic {
+				handle_start: DVec2::new(20., 10.),
+				handle_end: DVec2::new(0., 10.)
+			}
+		);
+
+		let subpaths: Vec<_> = modified.stroke_bezier_paths().collect();
+		assert_eq!(subpaths.len(), 1);
+		assert!(subpaths[0].closed());
+	}
+
+	#[test]
+	fn builder_deletes_point_and_reconnects_neighbors() {
+		let vector_data = VectorData::from_subpath(bezier_rs::Subpath::new_rect(DVec2::ZERO, DVec2::splat(10.)));
+		let corner = vector_data.point_domain.ids()[1];
+
+		let mut builder = VectorModificationBuilder::new(&vector_data);
+		builder.delete_point(corner).unwrap();
+		let modified = apply(&vector_data, builder.finish());
+
+		assert_eq!(modified.point_domain.ids().len(), 3);
+		assert!(modified.point_domain.position_from_id(corner).is_none());
+		let subpaths: Vec<_> = modified.stroke_bezier_paths().collect();
+		assert_eq!(subpaths.len(), 1);
+		assert!(subpaths[0].closed());
+		let mut anchors = subpaths[0].anchors();
+		anchors.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+		assert_eq!(anchors, [DVec2::ZERO, DVec2::new(0., 10.), DVec2::splat(10.)]);
+	}
+
+	#[test]
+	fn builder_checks_that_points_and_segments_exist() {
+		let vector_data = VectorData::from_subpath(bezier_rs::Subpath::from_anchors_linear([DVec2::ZERO, DVec2::X], false));
+		let [start, end] = [0, 1].map(|index| vector_data.point_domain.ids()[index]);
+		let unknown_point = PointId::generate();
+		let unknown_segment = SegmentId::generate();
+
+		let mut builder = VectorModificationBuilder::new(&vector_data);
+		assert_eq!(
+			builder.insert_point(TValue::Parametric(0.5), unknown_segment),
+			Err(VectorModificationError::MissingSegment(unknown_segment))
+		);
+		assert_eq!(builder.set_handles(unknown_point, None, None), Err(VectorModificationError::MissingPoint(unknown_point)));
+		assert_eq!(builder.insert_segment(start, unknown_point, [None; 2]), Err(VectorModificationError::MissingPoint(unknown_point)));
+
+		// Points inserted by earlier edits can be used, but deleted ones can't
+		let middle = builder.insert_point(TValue::Parametric(0.5), vector_data.segment_domain.ids()[0]).unwrap();
+		builder.delete_point(end).unwrap();
+		assert!(builder.insert_segment(start, middle, [None; 2]).is_ok());
+		assert_eq!(builder.insert_segment(middle, end, [None; 2]), Err(VectorModificationError::MissingPoint(end)));
+
+		// Two segments now begin at the start anchor, so which of them its out handle belongs to is unclear
+		assert_eq!(builder.set_handles(start, None, None), Err(VectorModificationError::AmbiguousHandle(start)));
+	}
 }
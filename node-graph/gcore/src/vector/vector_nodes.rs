@@ -1,8 +1,8 @@
 use super::algorithms::bezpath_algorithms::{self, evaluate_bezpath, sample_polyline_on_bezpath, split_bezpath, tangent_on_bezpath};
-use super::algorithms::offset_subpath::offset_bezpath;
+use super::algorithms::scatter;
 use super::algorithms::spline::{solve_spline_first_handle_closed, solve_spline_first_handle_open};
 use super::misc::{CentroidType, point_to_dvec2};
-use super::style::{Fill, Gradient, GradientStops, Stroke};
+use super::style::{Fill, Gradient, GradientStops, Pattern, Stroke, SwatchColor};
 use super::{PointId, SegmentDomain, SegmentId, StrokeId, VectorData, VectorDataExt, VectorDataTable};
 use crate::bounds::BoundingBox;
 use crate::instances::{Instance, InstanceMut, Instances};
@@ -12,12 +12,12 @@ use crate::transform::{Footprint, ReferencePoint, Transform};
 use crate::vector::PointDomain;
 use crate::vector::algorithms::bezpath_algorithms::{eval_pathseg_euclidean, is_linear};
 use crate::vector::algorithms::merge_by_distance::MergeByDistanceExt;
-use crate::vector::misc::{MergeByDistanceAlgorithm, PointSpacingType};
+use crate::vector::misc::{MergeByDistanceAlgorithm, PointSpacingType, ScatterMode};
 use crate::vector::misc::{handles_to_segment, segment_to_handles};
-use crate::vector::style::{PaintOrder, StrokeAlign, StrokeCap, StrokeJoin};
+use crate::vector::style::{FillRule, PaintOrder, StrokeAlign, StrokeCap, StrokeJoin, StrokeMarker};
 use crate::vector::{FillId, RegionId};
 use crate::{CloneVarArgs, Color, Context, Ctx, ExtractAll, GraphicElement, GraphicGroupTable, OwnedContextImpl};
-use bezier_rs::{BezierHandles, ManipulatorGroup, Subpath};
+use bezier_rs::{Bezier, BezierHandles, ManipulatorGroup, Subpath, TValue};
 use core::f64::consts::PI;
 use core::hash::{Hash, Hasher};
 use glam::{DAffine2, DVec2};
@@ -115,6 +115,8 @@ async fn fill<F: Into<Fill> + 'n + Send, V>(
 		VectorDataTable,
 		VectorDataTable,
 		VectorDataTable,
+		VectorDataTable,
+		GraphicGroupTable,
 		GraphicGroupTable,
 		GraphicGroupTable,
 		GraphicGroupTable,
@@ -127,27 +129,40 @@ async fn fill<F: Into<Fill> + 'n + Send, V>(
 		Option<Color>,
 		Color,
 		Gradient,
+		SwatchColor,
 		Fill,
 		Option<Color>,
 		Color,
 		Gradient,
+		SwatchColor,
 	)]
 	#[default(Color::BLACK)]
 	/// The fill to paint the path with.
 	fill: F,
 	_backup_color: Option<Color>,
 	_backup_gradient: Gradient,
+	/// Whether regions where the path overlaps itself, or where subpaths are nested, are filled by the nonzero winding rule or alternate between filled and empty by the even-odd rule.
+	fill_rule: FillRule,
+	_backup_pattern: Pattern,
+	/// The graphic repeated by a pattern fill, such as the content of another layer. When connected, it replaces the tile embedded in the pattern so editing the source updates the fill.
+	pattern_tile: GraphicGroupTable,
 ) -> V
 where
 	V: VectorDataTableIterMut + 'n + Send,
 {
-	let fill: Fill = fill.into();
+	let mut fill: Fill = fill.into();
+	if let Fill::Pattern(pattern) = &mut fill {
+		if !pattern_tile.is_empty() {
+			pattern.tile = pattern_tile;
+		}
+	}
 	for vector in vector_data.vector_iter_mut() {
 		let mut fill = fill.clone();
 		if let Fill::Gradient(gradient) = &mut fill {
 			gradient.transform *= *vector.transform;
 		}
 		vector.instance.style.set_fill(fill);
+		vector.instance.style.set_fill_rule(fill_rule);
 	}
 
 	vector_data
@@ -157,14 +172,16 @@ where
 #[node_macro::node(category("Vector: Style"), path(graphene_core::vector), properties("stroke_properties"))]
 async fn stroke<C: Into<Option<Color>> + 'n + Send, V>(
 	_: impl Ctx,
-	#[implementations(VectorDataTable, VectorDataTable, GraphicGroupTable, GraphicGroupTable)]
+	#[implementations(VectorDataTable, VectorDataTable, VectorDataTable, GraphicGroupTable, GraphicGroupTable, GraphicGroupTable)]
 	/// The vector elements, or group of vector elements, to apply the stroke to.
 	mut vector_data: Instances<V>,
 	#[implementations(
 		Option<Color>,
 		Color,
+		SwatchColor,
 		Option<Color>,
 		Color,
+		SwatchColor,
 	)]
 	#[default(Color::BLACK)]
 	/// The stroke color.
@@ -190,6 +207,13 @@ async fn stroke<C: Into<Option<Color>> + 'n + Send, V>(
 	/// The phase offset distance from the starting point of the dash pattern.
 	#[unit(" px")]
 	dash_offset: f64,
+	/// The marker shape drawn at the start of open paths.
+	start_marker: StrokeMarker,
+	/// The marker shape drawn at the end of open paths.
+	end_marker: StrokeMarker,
+	#[default(1.)]
+	/// The scale of the markers, relative to the stroke weight.
+	marker_size: f64,
 ) -> Instances<V>
 where
 	Instances<V>: VectorDataTableIterMut + 'n + Send,
@@ -206,6 +230,9 @@ where
 		transform: DAffine2::IDENTITY,
 		non_scaling: false,
 		paint_order,
+		start_marker,
+		end_marker,
+		marker_size,
 	};
 
 	for vector in vector_data.vector_iter_mut() {
@@ -365,6 +392,95 @@ async fn copy_to_points<I: 'n + Send + Clone>(
 	result_table
 }
 
+/// Places copies of the instance along the path of the target, or scattered inside the area it fills, with randomized sizes and angles.
+#[node_macro::node(category("Instancing"), path(graphene_core::vector))]
+async fn scatter<I: 'n + Send + Clone>(
+	_: impl Ctx,
+	/// The path along which, or the shape inside which, the copies are placed.
+	target: VectorDataTable,
+	#[expose]
+	/// Artwork to be copied and placed at each position.
+	#[implementations(GraphicGroupTable, VectorDataTable, RasterDataTable<CPU>)]
+	instance: Instances<I>,
+	/// Whether the copies are spaced along the path or placed randomly inside the area, either freely or kept apart by the separation distance.
+	mode: ScatterMode,
+	/// Along a path, whether the copies are the separation distance apart or the quantity of them spans the whole path.
+	spacing: PointSpacingType,
+	/// The distance between copies along a path, or the least distance between copies in Poisson Disk mode.
+	#[unit(" px")]
+	#[default(20.)]
+	#[hard_min(0.01)]
+	separation: f64,
+	/// The number of copies spanning a path, or placed in Random mode.
+	#[default(10)]
+	quantity: u32,
+	/// Whether copies placed along a path are turned to follow its direction.
+	#[default(true)]
+	align_to_path: bool,
+	/// Seed to determine unique variations on the random positions, sizes, and angles.
+	seed: SeedValue,
+	/// Minimum range of randomized sizes given to each copy.
+	#[default(1)]
+	#[range((0., 2.))]
+	#[unit("x")]
+	random_scale_min: Multiplier,
+	/// Maximum range of randomized sizes given to each copy.
+	#[default(1)]
+	#[range((0., 2.))]
+	#[unit("x")]
+	random_scale_max: Multiplier,
+	/// Range of randomized angles given to each copy, in degrees ranging from furthest clockwise to counterclockwise.
+	#[range((0., 360.))]
+	random_rotation: Angle,
+) -> Instances<I> {
+	let seed: u64 = seed.into();
+	let mut placement_rng = rand::rngs::StdRng::seed_from_u64(seed);
+	let mut variation_rng = rand::rngs::StdRng::seed_from_u64(seed.wrapping_add(1));
+
+	let mut result_table = Instances::<I>::default();
+
+	for target_instance in target.instance_ref_iter() {
+		// The positions are found in the target's parent space so its transform doesn't distort the spacing
+		let bezpaths: Vec<_> = target_instance
+			.instance
+			.stroke_bezpath_iter()
+			.map(|mut bezpath| {
+				bezpath.apply_affine(Affine::new(target_instance.transform.to_cols_array()));
+				bezpath
+			})
+			.collect();
+
+		let positions: Vec<(DVec2, f64)> = match mode {
+			ScatterMode::AlongPath => bezpaths
+				.iter()
+				.flat_map(|bezpath| scatter::positions_along_bezpath(bezpath, spacing, separation, quantity))
+				.map(|(position, direction)| (position, if align_to_path { direction.to_angle() } else { 0. }))
+				.collect(),
+			ScatterMode::Random => scatter::random_positions_in_area(&bezpaths, quantity, || placement_rng.random::<f64>())
+				.into_iter()
+				.map(|position| (position, 0.))
+				.collect(),
+			ScatterMode::PoissonDisk => scatter::poisson_disk_positions_in_area(&bezpaths, separation, || placement_rng.random::<f64>())
+				.into_iter()
+				.map(|position| (position, 0.))
+				.collect(),
+		};
+
+		for (position, angle) in positions {
+			let rotation = (variation_rng.random::<f64>() - 0.5) * random_rotation.to_radians();
+			let scale = random_scale_min + variation_rng.random::<f64>() * (random_scale_max - random_scale_min);
+			let transform = DAffine2::from_scale_angle_translation(DVec2::splat(scale), angle + rotation, position);
+
+			for mut instance in instance.instance_ref_iter().map(|instance| instance.to_instance_cloned()) {
+				instance.transform = transform * instance.transform;
+				result_table.push(instance);
+			}
+		}
+	}
+
+	result_table
+}
+
 #[node_macro::node(category("Instancing"), path(graphene_core::vector))]
 async fn mirror<I: 'n + Send + Clone>(
 	_: impl Ctx,
@@ -422,6 +538,149 @@ where
 	result_table
 }
 
+/// The direction a segment leaves its start (`t` = 0) or arrives at its end (`t` = 1), even where a handle sits on top of its anchor.
+fn end_tangent(segment: &Bezier, t: f64) -> DVec2 {
+	let tangent = segment.tangent(TValue::Parametric(t));
+	if tangent != DVec2::ZERO {
+		return tangent;
+	}
+	// With a handle on top of its anchor, the direction at that end is where the curve heads immediately afterwards
+	let nearby = segment.tangent(TValue::Parametric(if t < 0.5 { 1e-4 } else { 1. - 1e-4 }));
+	if nearby != DVec2::ZERO {
+		return nearby;
+	}
+	(segment.end() - segment.start()).normalize_or_zero()
+}
+
+/// The handles of a segment as cubic handles, so segments of every degree can be stored in the same manipulator groups.
+fn cubic_handles(segment: &Bezier) -> (Option<DVec2>, Option<DVec2>) {
+	match segment.handles {
+		BezierHandles::Linear => (None, None),
+		_ => {
+			let cubic = segment.to_cubic();
+			(cubic.handle_start(), cubic.handle_end())
+		}
+	}
+}
+
+/// The handle length of a curve from `start` to `end` leaving and arriving in the given directions. A roundness of 0.5 approximates a circular arc,
+/// lower roundness flattens the curve towards a straight chamfer, and higher roundness pulls it out towards the sharp corner.
+fn corner_handle_length(start: DVec2, end: DVec2, direction_in: DVec2, direction_out: DVec2, roundness: f64) -> f64 {
+	let chord = start.distance(end);
+	let turn = direction_in.angle_to(direction_out).abs();
+
+	// The distance from each end to where the two tangent lines meet, and the handle length of the usual cubic approximation of a circular arc
+	let (corner_distance, circular) = if turn < 1e-6 {
+		(chord / 2., chord / 3.)
+	} else {
+		(chord / (2. * (turn / 2.).cos()), 4. / 3. * (turn / 4.).tan() * chord / (2. * (turn / 2.).sin()))
+	};
+
+	let roundness = roundness.clamp(0., 1.);
+	if roundness <= 0.5 {
+		circular * roundness * 2.
+	} else {
+		circular + (corner_distance - circular) * (roundness - 0.5) * 2.
+	}
+}
+
+/// Replaces the corners of a subpath with curves tangent to the segments on either side, approximating circular arcs of the radius given by `radius_at` for each anchor.
+///
+/// Each rounding may reach at most `edge_length_limit` (a fraction of the segment's length) along the segments beside it, so with a limit of half or less,
+/// the roundings at either end of a segment never overlap. Corners turning by less than `min_angle_threshold` (in radians) are left sharp, as are the ends of open subpaths.
+fn round_subpath_corners(subpath: &Subpath<PointId>, radius_at: impl Fn(PointId) -> f64, roundness: f64, edge_length_limit: f64, min_angle_threshold: f64) -> Subpath<PointId> {
+	let groups = subpath.manipulator_groups();
+	let closed = subpath.closed();
+	if groups.len() < 3 {
+		return subpath.clone();
+	}
+
+	let segment_count = if closed { groups.len() } else { groups.len() - 1 };
+	let segments: Vec<Bezier> = (0..segment_count).map(|index| groups[index].to_bezier(&groups[(index + 1) % groups.len()])).collect();
+	let lengths: Vec<f64> = segments.iter().map(|segment| segment.length(None)).collect();
+	let incoming_segment = |index: usize| (index + segment_count - 1) % segment_count;
+
+	// How far back along the segments on either side each corner is cut to make room for its rounding, or zero where it stays sharp
+	let cutbacks: Vec<f64> = (0..groups.len())
+		.map(|index| {
+			if !closed && (index == 0 || index == groups.len() - 1) {
+				return 0.;
+			}
+			let (incoming, outgoing) = (incoming_segment(index), index);
+
+			let radius = radius_at(groups[index].id);
+			let shortest = lengths[incoming].min(lengths[outgoing]);
+			let (direction_in, direction_out) = (end_tangent(&segments[incoming], 1.), end_tangent(&segments[outgoing], 0.));
+			if radius <= 0. || shortest < 1e-9 || direction_in == DVec2::ZERO || direction_out == DVec2::ZERO {
+				return 0.;
+			}
+
+			// Nearly straight corners don't need rounding, and a path doubling back on itself has no arc which could round it
+			let turn = direction_in.angle_to(direction_out).abs();
+			if turn < min_angle_threshold || turn > PI - 1e-6 {
+				return 0.;
+			}
+
+			(radius * (turn / 2.).tan()).min(edge_length_limit * shortest)
+		})
+		.collect();
+
+	let trimmed: Vec<Bezier> = (0..segment_count)
+		.map(|index| {
+			let segment = segments[index];
+			let (start_cut, end_cut) = (cutbacks[index], cutbacks[(index + 1) % groups.len()]);
+			if start_cut == 0. && end_cut == 0. {
+				return segment;
+			}
+
+			// Lines are trimmed exactly, while curves are trimmed by their approximate arc length
+			let length = lengths[index];
+			let (start, end) = (start_cut / length, 1. - end_cut / length);
+			match segment.handles {
+				BezierHandles::Linear => segment.trim(TValue::Parametric(start), TValue::Parametric(end)),
+				_ => segment.trim(TValue::Euclidean(start), TValue::Euclidean(end)),
+			}
+		})
+		.collect();
+
+	let mut new_groups = Vec::with_capacity(groups.len() * 2);
+	for (index, group) in groups.iter().enumerate() {
+		let incoming = (closed || index > 0).then(|| &trimmed[incoming_segment(index)]);
+		let outgoing = (index < segment_count).then(|| &trimmed[index]);
+		let in_handle = incoming.and_then(|segment| cubic_handles(segment).1);
+		let out_handle = outgoing.and_then(|segment| cubic_handles(segment).0);
+
+		let (incoming, outgoing) = match (incoming, outgoing) {
+			(Some(incoming), Some(outgoing)) if cutbacks[index] > 0. => (incoming, outgoing),
+			_ => {
+				new_groups.push(ManipulatorGroup { in_handle, out_handle, ..*group });
+				continue;
+			}
+		};
+
+		// The rounding runs from the end of the trimmed incoming segment to the start of the trimmed outgoing one, continuing both their directions
+		let (start, end) = (incoming.end(), outgoing.start());
+		let (direction_in, direction_out) = (end_tangent(incoming, 1.), end_tangent(outgoing, 0.));
+		let handle_length = corner_handle_length(start, end, direction_in, direction_out, roundness);
+
+		new_groups.push(ManipulatorGroup {
+			anchor: start,
+			in_handle,
+			out_handle: Some(start + direction_in * handle_length),
+			id: group.id,
+		});
+		new_groups.push(ManipulatorGroup {
+			anchor: end,
+			in_handle: Some(end - direction_out * handle_length),
+			out_handle,
+			id: PointId::generate(),
+		});
+	}
+
+	Subpath::new(new_groups, closed)
+}
+
+/// Rounds the corners of paths with curves approximating circular arcs of the given radius.
 #[node_macro::node(category("Vector: Modifier"), path(graphene_core::vector))]
 async fn round_corners(
 	_: impl Ctx,
@@ -434,13 +693,27 @@ async fn round_corners(
 	#[hard_max(1.)]
 	#[default(0.5)]
 	roundness: f64,
-	#[default(100.)] edge_length_limit: Percentage,
+	#[hard_min(0.)]
+	#[hard_max(100.)]
+	#[default(100.)]
+	edge_length_limit: Percentage,
 	#[range((0., 180.))]
 	#[hard_min(0.)]
 	#[hard_max(180.)]
 	#[default(5.)]
 	min_angle_threshold: Angle,
+	/// The anchors rounded with the selected radius instead, such as those chosen with the Path tool's "Round Selected Anchors" action.
+	selected_points: Vec<PointId>,
+	/// The radius used at the selected anchors.
+	#[hard_min(0.)]
+	#[default(10.)]
+	selected_radius: PixelLength,
 ) -> VectorDataTable {
+	// Convert 0-100 to 0-0.5 so, at most, the roundings at either end of an edge meet in its middle
+	let edge_length_limit = edge_length_limit * 0.005;
+	let min_angle_threshold = min_angle_threshold.to_radians();
+	let radius_at = |id: PointId| if selected_points.contains(&id) { selected_radius } else { radius };
+
 	source
 		.instance_ref_iter()
 		.map(|source| {
@@ -449,94 +722,20 @@ async fn round_corners(
 			let source_node_id = source.source_node_id;
 			let source = source.instance;
 
-			let upstream_graphic_group = source.upstream_graphic_group.clone();
-
-			// Flip the roundness to help with user intuition
-			let roundness = 1. - roundness;
-			// Convert 0-100 to 0-0.5
-			let edge_length_limit = edge_length_limit * 0.005;
-
 			let mut result = VectorData {
 				style: source.style.clone(),
+				upstream_graphic_group: source.upstream_graphic_group.clone(),
 				..Default::default()
 			};
 
-			// Grab the initial point ID as a stable starting point
-			let mut initial_point_id = source.point_domain.ids().first().copied().unwrap_or(PointId::generate());
-
+			// Rounding happens in document space so the radius isn't distorted by the layer's transform
 			for mut subpath in source.stroke_bezier_paths() {
 				subpath.apply_transform(source_transform);
-
-				// End if not enough points for corner rounding
-				if subpath.manipulator_groups().len() < 3 {
-					result.append_subpath(subpath, false);
-					continue;
-				}
-
-				let groups = subpath.manipulator_groups();
-				let mut new_groups = Vec::new();
-				let is_closed = subpath.closed();
-
-				for i in 0..groups.len() {
-					// Skip first and last points for open paths
-					if !is_closed && (i == 0 || i == groups.len() - 1) {
-						new_groups.push(groups[i]);
-						continue;
-					}
-
-					// Not the prettiest, but it makes the rest of the logic more readable
-					let prev_idx = if i == 0 { if is_closed { groups.len() - 1 } else { 0 } } else { i - 1 };
-					let curr_idx = i;
-					let next_idx = if i == groups.len() - 1 { if is_closed { 0 } else { i } } else { i + 1 };
-
-					let prev = groups[prev_idx].anchor;
-					let curr = groups[curr_idx].anchor;
-					let next = groups[next_idx].anchor;
-
-					let dir1 = (curr - prev).normalize_or(DVec2::X);
-					let dir2 = (next - curr).normalize_or(DVec2::X);
-
-					let theta = PI - dir1.angle_to(dir2).abs();
-
-					// Skip near-straight corners
-					if theta > PI - min_angle_threshold.to_radians() {
-						new_groups.push(groups[curr_idx]);
-						continue;
-					}
-
-					// Calculate L, with limits to avoid extreme values
-					let distance_along_edge = radius / (theta / 2.).sin();
-					let distance_along_edge = distance_along_edge.min(edge_length_limit * (curr - prev).length().min((next - curr).length())).max(0.01);
-
-					// Find points on each edge at distance L from corner
-					let p1 = curr - dir1 * distance_along_edge;
-					let p2 = curr + dir2 * distance_along_edge;
-
-					// Add first point (coming into the rounded corner)
-					new_groups.push(ManipulatorGroup {
-						anchor: p1,
-						in_handle: None,
-						out_handle: Some(curr - dir1 * distance_along_edge * roundness),
-						id: initial_point_id.next_id(),
-					});
-
-					// Add second point (coming out of the rounded corner)
-					new_groups.push(ManipulatorGroup {
-						anchor: p2,
-						in_handle: Some(curr + dir2 * distance_along_edge * roundness),
-						out_handle: None,
-						id: initial_point_id.next_id(),
-					});
-				}
-
-				// One subpath for each shape
-				let mut rounded_subpath = Subpath::new(new_groups, is_closed);
+				let mut rounded_subpath = round_subpath_corners(&subpath, radius_at, roundness, edge_length_limit, min_angle_threshold);
 				rounded_subpath.apply_transform(source_transform_inverse);
 				result.append_subpath(rounded_subpath, false);
 			}
 
-			result.upstream_graphic_group = upstream_graphic_group;
-
 			Instance {
 				instance: result,
 				transform: source_transform,
@@ -973,49 +1172,6 @@ async fn points_to_polyline(_: impl Ctx, mut points: VectorDataTable, #[default(
 	points
 }
 
-#[node_macro::node(category("Vector: Modifier"), path(graphene_core::vector), properties("offset_path_properties"))]
-async fn offset_path(_: impl Ctx, vector_data: VectorDataTable, distance: f64, join: StrokeJoin, #[default(4.)] miter_limit: f64) -> VectorDataTable {
-	vector_data
-		.instance_iter()
-		.map(|mut vector_data_instance| {
-			let vector_data_transform = Affine::new(vector_data_instance.transform.to_cols_array());
-			let vector_data = vector_data_instance.instance;
-
-			let bezpaths = vector_data.stroke_bezpath_iter();
-			let mut result = VectorData {
-				style: vector_data.style.clone(),
-				..Default::default()
-			};
-			result.style.set_stroke_transform(DAffine2::IDENTITY);
-
-			// Perform operation on all subpaths in this shape.
-			for mut bezpath in bezpaths {
-				bezpath.apply_affine(vector_data_transform);
-
-				// Taking the existing stroke data and passing it to Bezier-rs to generate new paths.
-				let mut bezpath_out = offset_bezpath(
-					&bezpath,
-					-distance,
-					match join {
-						StrokeJoin::Miter => kurbo::Join::Miter,
-						StrokeJoin::Bevel => kurbo::Join::Bevel,
-						StrokeJoin::Round => kurbo::Join::Round,
-					},
-					Some(miter_limit),
-				);
-
-				bezpath_out.apply_affine(vector_data_transform.inverse());
-
-				// One closed subpath, open path.
-				result.append_bezpath(bezpath_out);
-			}
-
-			vector_data_instance.instance = result;
-			vector_data_instance
-		})
-		.collect()
-}
-
 #[node_macro::node(category("Vector: Modifier"), path(graphene_core::vector))]
 async fn solidify_stroke(_: impl Ctx, vector_data: VectorDataTable) -> VectorDataTable {
 	vector_data
@@ -2116,7 +2272,6 @@ async fn centroid(ctx: impl Ctx + CloneVarArgs + ExtractAll, vector_data: impl N
 mod test {
 	use super::*;
 	use crate::Node;
-	use bezier_rs::Bezier;
 	use kurbo::Rect;
 	use std::pin::Pin;
 
@@ -2231,6 +2386,67 @@ mod test {
 			);
 		}
 	}
+	async fn scatter_transforms(target: VectorDataTable, mode: ScatterMode, quantity: u32, seed: u32, random_rotation: f64) -> Vec<DAffine2> {
+		let instance = vector_node(Subpath::new_rect(DVec2::NEG_ONE, DVec2::ONE));
+		let scattered = super::scatter(
+			Footprint::default(),
+			target,
+			instance,
+			mode,
+			PointSpacingType::Quantity,
+			10.,
+			quantity,
+			true,
+			seed,
+			0.5,
+			1.5,
+			random_rotation,
+		)
+		.await;
+		scattered.instance_ref_iter().map(|instance| *instance.transform).collect()
+	}
+	#[tokio::test]
+	async fn scatter_along_path() {
+		let path = vector_node(Subpath::new_line(DVec2::ZERO, DVec2::new(0., 100.)));
+		let transforms = scatter_transforms(path, ScatterMode::AlongPath, 5, 0, 0.).await;
+
+		assert_eq!(transforms.len(), 5);
+		for (index, transform) in transforms.iter().enumerate() {
+			assert!(transform.translation.abs_diff_eq(DVec2::new(0., 25. * index as f64), 1e-6));
+			// Each copy is turned to face down the path
+			assert!(transform.transform_vector2(DVec2::X).normalize().abs_diff_eq(DVec2::Y, 1e-6));
+		}
+	}
+	#[tokio::test]
+	async fn scatter_inside_area() {
+		let square = || vector_node(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+
+		let transforms = scatter_transforms(square(), ScatterMode::Random, 30, 0, 90.).await;
+		assert_eq!(transforms.len(), 30);
+		for transform in transforms {
+			assert!(transform.translation.cmpge(DVec2::ZERO).all() && transform.translation.cmple(DVec2::splat(100.)).all());
+		}
+
+		let transforms = scatter_transforms(square(), ScatterMode::PoissonDisk, 0, 0, 0.).await;
+		assert!(transforms.len() > 20);
+		for (index, a) in transforms.iter().enumerate() {
+			for b in &transforms[index + 1..] {
+				assert!(a.translation.distance(b.translation) >= 10. - 1e-9);
+			}
+		}
+	}
+	#[tokio::test]
+	async fn scatter_is_deterministic_per_seed() {
+		let square = || vector_node(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+
+		for mode in [ScatterMode::Random, ScatterMode::PoissonDisk] {
+			let first = scatter_transforms(square(), mode, 20, 7, 180.).await;
+			let again = scatter_transforms(square(), mode, 20, 7, 180.).await;
+			let other_seed = scatter_transforms(square(), mode, 20, 8, 180.).await;
+			assert_eq!(first, again);
+			assert_ne!(first, other_seed);
+		}
+	}
 	#[tokio::test]
 	async fn sample_polyline() {
 		let path = Subpath::from_bezier(&Bezier::from_cubic_dvec2(DVec2::ZERO, DVec2::ZERO, DVec2::X * 100., DVec2::X * 100.));
@@ -2403,6 +2619,59 @@ mod test {
 		contains_segment(beveled.clone(), Bezier::from_linear_dvec2(DVec2::new(100., 50.), DVec2::new(50., 100.)));
 	}
 
+	/// A thin closed triangle whose tip at (100, 10) forms an angle of about 11.4°, giving a miter ratio of about 10.
+	fn acute_triangle() -> Subpath<PointId> {
+		Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(100., 10.), DVec2::new(0., 20.)], true)
+	}
+
+	#[tokio::test]
+	async fn solidified_acute_miter_is_bounded_by_the_miter_limit() {
+		let miter_ratio = 1. / (10_f64 / 100.).atan().sin();
+
+		for miter_limit in [2., 4., 10., 11., 100.] {
+			let mut vector_data = VectorData::from_subpath(acute_triangle());
+			vector_data.style.set_stroke(Stroke::new(Some(Color::BLACK), 2.).with_stroke_join_miter_limit(miter_limit));
+			let solidified = super::solidify_stroke(Footprint::default(), VectorDataTable::new(vector_data)).await;
+			let [_, max] = solidified.instance_ref_iter().next().unwrap().instance.bounding_box().unwrap();
+			let spike = max.x - 100.;
+
+			// Past the miter limit, the join is beveled off within half the weight of the tip
+			if miter_ratio <= miter_limit {
+				assert!(
+					(spike - miter_ratio).abs() < 1e-6,
+					"With a miter limit of {miter_limit}, expected a spike of {miter_ratio} but got {spike}"
+				);
+			} else {
+				assert!(spike <= 1. + 1e-6, "With a miter limit of {miter_limit}, expected a beveled tip but got a spike of {spike}");
+			}
+		}
+	}
+
+	#[test]
+	fn offset_acute_miter_is_bounded_by_the_miter_limit() {
+		use crate::vector::algorithms::offset_subpath::offset_vector_data;
+		use crate::vector::misc::OpenPathOffset;
+
+		let miter_ratio = 1. / (10_f64 / 100.).atan().sin();
+
+		for miter_limit in [2., 4., 10., 11., 100.] {
+			// A positive distance grows the triangle, whichever way it's wound
+			let triangle = VectorData::from_subpath(acute_triangle());
+			let offset = offset_vector_data(&triangle, DAffine2::IDENTITY, 1., StrokeJoin::Miter, miter_limit, OpenPathOffset::OneSide);
+			let [_, max] = offset.bounding_box().unwrap();
+			let spike = max.x - 100.;
+
+			if miter_ratio <= miter_limit {
+				assert!(
+					(spike - miter_ratio).abs() < 1e-6,
+					"With a miter limit of {miter_limit}, expected a spike of {miter_ratio} but got {spike}"
+				);
+			} else {
+				assert!(spike <= 1. + 1e-6, "With a miter limit of {miter_limit}, expected a beveled tip but got a spike of {spike}");
+			}
+		}
+	}
+
 	#[tokio::test]
 	async fn bevel_repeated_point() {
 		let line = Bezier::from_linear_dvec2(DVec2::ZERO, DVec2::new(100., 0.));
@@ -2415,4 +2684,114 @@ mod test {
 		assert_eq!(beveled.point_domain.positions().len(), 6);
 		assert_eq!(beveled.segment_domain.ids().len(), 5);
 	}
+
+	#[tokio::test]
+	async fn pattern_fill_repeats_the_connected_tile() {
+		let dot = GraphicGroupTable::from(vector_node(Subpath::new_rect(DVec2::ZERO, DVec2::splat(4.))));
+		let embedded = Pattern { scale: 2., ..Default::default() };
+		let shape = || vector_node(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+		let fill_with = |tile: GraphicGroupTable| {
+			super::fill(
+				Footprint::default(),
+				shape(),
+				Fill::Pattern(embedded.clone()),
+				None,
+				Gradient::default(),
+				FillRule::NonZero,
+				Pattern::default(),
+				tile,
+			)
+		};
+
+		// The connected tile replaces the embedded one while keeping the tile placement
+		let filled = fill_with(dot.clone()).await;
+		let pattern = filled.instance_ref_iter().next().unwrap().instance.style.fill().as_pattern().unwrap().clone();
+		assert_eq!(pattern.tile, dot);
+		assert_eq!(pattern.scale, 2.);
+		assert_eq!(pattern.cell_size(), Some(DVec2::splat(8.)));
+
+		// Without a connection, the embedded tile is kept
+		let filled = fill_with(GraphicGroupTable::default()).await;
+		assert_eq!(filled.instance_ref_iter().next().unwrap().instance.style.fill(), &Fill::Pattern(embedded));
+	}
+
+	#[track_caller]
+	fn assert_smooth(subpath: &Subpath<PointId>) {
+		let segments: Vec<Bezier> = subpath.iter().collect();
+		let joins = if subpath.closed() { segments.len() } else { segments.len() - 1 };
+		for index in 0..joins {
+			let (incoming, outgoing) = (&segments[index], &segments[(index + 1) % segments.len()]);
+			let (direction_in, direction_out) = (end_tangent(incoming, 1.), end_tangent(outgoing, 0.));
+			assert!(
+				direction_in.abs_diff_eq(direction_out, 1e-6),
+				"The direction changes from {direction_in} to {direction_out} where {incoming:?} meets {outgoing:?}"
+			);
+		}
+	}
+
+	#[track_caller]
+	fn assert_anchors(subpath: &Subpath<PointId>, expected: &[DVec2]) {
+		let anchors = subpath.anchors();
+		assert_eq!(anchors.len(), expected.len(), "Expected the anchors {expected:?} but found {anchors:?}");
+		for (anchor, expected) in anchors.iter().zip(expected) {
+			assert!(anchor.abs_diff_eq(*expected, 1e-6), "Expected the anchors {expected:?} but found {anchors:?}");
+		}
+	}
+
+	#[test]
+	fn round_corners_are_tangent_circular_arcs() {
+		let square = Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.));
+		let rounded = round_subpath_corners(&square, |_| 10., 0.5, 0.5, 5_f64.to_radians());
+		assert_eq!(rounded.manipulator_groups().len(), 8);
+		assert_smooth(&rounded);
+
+		// The corner at (100, 0) is replaced by a quarter circle centered 10 units inside it
+		let corner = rounded.iter().nth(2).unwrap();
+		assert!(corner.start().abs_diff_eq(DVec2::new(90., 0.), 1e-9));
+		assert!(corner.end().abs_diff_eq(DVec2::new(100., 10.), 1e-9));
+		for step in 0..=10 {
+			let distance = corner.evaluate(TValue::Parametric(step as f64 / 10.)).distance(DVec2::new(90., 10.));
+			assert!((distance - 10.).abs() < 0.01, "The rounding is {distance} from its center");
+		}
+
+		// Corners between a line and a curve are tangent to both, starting the same distance along each
+		let curve = Bezier::from_cubic_dvec2(DVec2::ZERO, DVec2::new(0., 50.), DVec2::new(100., 50.), DVec2::new(100., 0.));
+		let path = Subpath::from_beziers(&[Bezier::from_linear_dvec2(DVec2::new(-100., 0.), DVec2::ZERO), curve], false);
+		let rounded = round_subpath_corners(&path, |_| 10., 0.5, 0.5, 5_f64.to_radians());
+		assert_eq!(rounded.manipulator_groups().len(), 4);
+		assert_smooth(&rounded);
+		assert!(rounded.anchors()[1].abs_diff_eq(DVec2::new(-10., 0.), 1e-9));
+		assert!(rounded.anchors()[2].distance(curve.evaluate(TValue::Euclidean(10. / curve.length(None)))) < 0.1);
+	}
+
+	#[test]
+	fn round_corners_are_limited_by_edge_length() {
+		// The short sides only have room for roundings reaching halfway along them, where they meet without overlapping
+		let rectangle = Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 20.));
+		let rounded = round_subpath_corners(&rectangle, |_| 50., 0.5, 0.5, 5_f64.to_radians());
+		let expected = [(0., 10.), (10., 0.), (90., 0.), (100., 10.), (100., 10.), (90., 20.), (10., 20.), (0., 10.)];
+		assert_anchors(&rounded, &expected.map(DVec2::from));
+		assert_smooth(&rounded);
+	}
+
+	#[test]
+	fn round_corners_skips_straight_corners_and_open_ends() {
+		let path = Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(50., 0.), DVec2::new(100., 0.), DVec2::new(100., 100.)], false);
+		let rounded = round_subpath_corners(&path, |_| 10., 0.5, 0.5, 5_f64.to_radians());
+		let expected = [(0., 0.), (50., 0.), (90., 0.), (100., 10.), (100., 100.)];
+		assert_anchors(&rounded, &expected.map(DVec2::from));
+	}
+
+	#[tokio::test]
+	async fn round_corners_uses_selected_radius_at_selected_points() {
+		let square = vector_node(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+		let selected = square.instance_ref_iter().next().unwrap().instance.point_domain.ids()[1];
+
+		// Only the selected corner at (100, 0) is rounded since the others have no radius
+		let rounded = super::round_corners((), square, 0., 0.5, 100., 5., vec![selected], 20.).await;
+		let rounded = rounded.instance_ref_iter().next().unwrap().instance;
+		let subpath = rounded.stroke_bezier_paths().next().unwrap();
+		let expected = [(0., 0.), (80., 0.), (100., 20.), (100., 100.), (0., 100.)];
+		assert_anchors(&subpath, &expected.map(DVec2::from));
+	}
 }
@@ -1,9 +1,12 @@
 use bezier_rs::{ManipulatorGroup, Subpath};
 use dyn_any::DynAny;
 use glam::{DAffine2, DVec2};
+use graphene_core::cancellation;
 use graphene_core::instances::{Instance, InstanceRef};
 use graphene_core::vector::algorithms::merge_by_distance::MergeByDistanceExt;
-use graphene_core::vector::style::Fill;
+use graphene_core::vector::algorithms::offset_subpath::{offset_vector_data, offset_vector_data_band};
+use graphene_core::vector::misc::OpenPathOffset;
+use graphene_core::vector::style::{Fill, StrokeJoin};
 use graphene_core::vector::{PointId, VectorData, VectorDataTable};
 use graphene_core::{Color, Ctx, GraphicElement, GraphicGroupTable};
 pub use path_bool as path_bool_lib;
@@ -28,6 +31,10 @@ pub enum BooleanOperation {
 	Intersect,
 	#[icon("BooleanDifference")]
 	Difference,
+	#[icon("BooleanExclude")]
+	Exclude,
+	#[icon("BooleanDivide")]
+	Divide,
 }
 
 /// Combines the geometric forms of one or more closed paths into a new vector path that results from cutting or joining the paths by the chosen method.
@@ -43,6 +50,8 @@ async fn boolean_operation<I: Into<GraphicGroupTable> + 'n + Send + Clone>(
 	/// Subtraction cuts overlapping areas out from the last (Subtract Front) or first (Subtract Back) path.
 	/// Intersection cuts away all but the overlapping areas shared by every path.
 	/// Difference cuts away the overlapping areas shared by every path, leaving only the non-overlapping areas.
+	/// Exclude keeps the areas covered by an odd number of paths, so overlaps of three paths are kept while overlaps of two are cut away.
+	/// Divide splits the paths into every separate region formed where they overlap, each becoming its own shape.
 	operation: BooleanOperation,
 ) -> VectorDataTable {
 	let group_of_paths = group_of_paths.into();
@@ -51,7 +60,7 @@ async fn boolean_operation<I: Into<GraphicGroupTable> + 'n + Send + Clone>(
 	let mut result_vector_data_table = boolean_operation_on_vector_data_table(flatten_vector_data(&group_of_paths).instance_ref_iter(), operation);
 
 	// Replace the transformation matrix with a mutation of the vector points themselves
-	if let Some(result_vector_data) = result_vector_data_table.instance_mut_iter().next() {
+	for result_vector_data in result_vector_data_table.instance_mut_iter() {
 		let transform = *result_vector_data.transform;
 		*result_vector_data.transform = DAffine2::IDENTITY;
 
@@ -66,6 +75,76 @@ async fn boolean_operation<I: Into<GraphicGroupTable> + 'n + Send + Clone>(
 	result_vector_data_table
 }
 
+/// Grows the area filled by closed paths by a set distance, or shrinks it with a negative distance, and offsets open paths to one side or turns them into outlines.
+#[node_macro::node(category("Vector: Modifier"), properties("offset_path_properties"))]
+async fn offset_path(
+	_: impl Ctx,
+	vector_data: VectorDataTable,
+	/// How far to offset the paths. Positive distances grow closed paths and negative distances shrink them.
+	distance: f64,
+	/// The shape of the corners formed where the offsets of neighboring segments meet.
+	join: StrokeJoin,
+	/// How far a miter join may reach, as a multiple of the distance, before it's beveled off instead.
+	#[default(4.)]
+	miter_limit: f64,
+	/// Whether open paths are offset to one side or turned into outlines around both of their sides.
+	open_paths: OpenPathOffset,
+	/// Removes the loops formed where the offset crosses itself, such as inside tight curves or where paths overlap, leaving only the outline of the offset shape.
+	#[default(true)]
+	clean_up_intersections: bool,
+) -> VectorDataTable {
+	vector_data
+		.instance_iter()
+		.map(|mut vector_data_instance| {
+			let transform = vector_data_instance.transform;
+			let source = &vector_data_instance.instance;
+
+			// The offset is found in document space so the distance isn't distorted by the layer's transform
+			let mut result = offset(source, transform, distance, join, miter_limit, open_paths, clean_up_intersections);
+			result.transform(transform.inverse());
+			result.style = source.style.clone();
+			result.style.set_stroke_transform(DAffine2::IDENTITY);
+
+			vector_data_instance.instance = result;
+			vector_data_instance
+		})
+		.collect()
+}
+
+fn offset(source: &VectorData, transform: DAffine2, distance: f64, join: StrokeJoin, miter_limit: f64, open_paths: OpenPathOffset, clean_up_intersections: bool) -> VectorData {
+	if !clean_up_intersections {
+		return offset_vector_data(source, transform, distance, join, miter_limit, open_paths);
+	}
+
+	let band = |closed: bool| {
+		if distance == 0. {
+			return Vec::new();
+		}
+		to_path(&offset_vector_data_band(source, transform, distance, join, miter_limit, closed), DAffine2::IDENTITY)
+	};
+
+	// Closed paths are grown by adding the area near their outlines to their fill, or shrunk by cutting it away, which leaves no loops where the offset crosses itself
+	let mut closed = VectorData::from_subpaths(source.stroke_bezier_paths().filter(|subpath| subpath.closed()), false);
+	closed.style = source.style.clone();
+	let area = to_path(&closed, transform);
+	let closed_offset = if distance < 0. { boolean_subtract(area, band(true)) } else { boolean_union(area, band(true)) };
+	let mut result = from_path(&closed_offset);
+
+	let open_offset = match open_paths {
+		// An offset to one side encloses no area, so there's nothing to clean up
+		OpenPathOffset::OneSide => {
+			let open = VectorData::from_subpaths(source.stroke_bezier_paths().filter(|subpath| !subpath.closed()), false);
+			offset_vector_data(&open, transform, distance, join, miter_limit, open_paths)
+		}
+		OpenPathOffset::Outline => from_path(&boolean_union(band(false), Vec::new())),
+	};
+	for subpath in open_offset.stroke_bezier_paths() {
+		result.append_subpath(subpath, false);
+	}
+
+	result
+}
+
 fn boolean_operation_on_vector_data_table<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, VectorData>> + Clone, boolean_operation: BooleanOperation) -> VectorDataTable {
 	match boolean_operation {
 		BooleanOperation::Union => union(vector_data),
@@ -73,6 +152,8 @@ fn boolean_operation_on_vector_data_table<'a>(vector_data: impl DoubleEndedItera
 		BooleanOperation::SubtractBack => subtract(vector_data.rev()),
 		BooleanOperation::Intersect => intersect(vector_data),
 		BooleanOperation::Difference => difference(vector_data),
+		BooleanOperation::Exclude => exclude(vector_data),
+		BooleanOperation::Divide => divide(vector_data),
 	}
 }
 
@@ -83,10 +164,13 @@ fn union<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, Vector
 	let mut result_vector_data_table = VectorDataTable::new_instance(vector_data_reversed.next().map(|x| x.to_instance_cloned()).unwrap_or_default());
 	let mut first_instance = result_vector_data_table.instance_mut_iter().next().expect("Expected the one instance we just pushed");
 
-	// Loop over all vector data and union it with the result
+	// Loop over all vector data and union it with the result, stopping early once the evaluation has been superseded since its result is discarded
 	let default = Instance::default();
 	let mut second_vector_data = Some(vector_data_reversed.next().unwrap_or(default.to_instance_ref()));
 	while let Some(lower_vector_data) = second_vector_data {
+		if cancellation::is_cancelled() {
+			break;
+		}
 		let transform_of_lower_into_space_of_upper = first_instance.transform.inverse() * *lower_vector_data.transform;
 
 		let result = &mut first_instance.instance;
@@ -118,6 +202,9 @@ fn subtract<'a>(vector_data: impl Iterator<Item = InstanceRef<'a, VectorData>>)
 	let mut next_vector_data = vector_data.next();
 
 	while let Some(lower_vector_data) = next_vector_data {
+		if cancellation::is_cancelled() {
+			break;
+		}
 		let transform_of_lower_into_space_of_upper = first_instance.transform.inverse() * *lower_vector_data.transform;
 
 		let result = &mut first_instance.instance;
@@ -151,6 +238,9 @@ fn intersect<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, Ve
 
 	// For each vector data, set the result to the intersection of that data and the result
 	while let Some(lower_vector_data) = second_vector_data {
+		if cancellation::is_cancelled() {
+			break;
+		}
 		let transform_of_lower_into_space_of_upper = first_instance.transform.inverse() * *lower_vector_data.transform;
 
 		let result = &mut first_instance.instance;
@@ -180,6 +270,9 @@ fn difference<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, V
 
 	// Find where all vector data intersect at least once
 	while let Some(lower_vector_data) = second_vector_data {
+		if cancellation::is_cancelled() {
+			break;
+		}
 		let filtered_vector_data = vector_data.clone().filter(|v| *v != lower_vector_data).collect::<Vec<_>>().into_iter();
 		let unioned = boolean_operation_on_vector_data_table(filtered_vector_data, BooleanOperation::Union);
 		let first_instance = unioned.instance_ref_iter().next().expect("Expected at least one instance after the boolean union");
@@ -221,6 +314,116 @@ fn difference<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, V
 	boolean_operation_on_vector_data_table(union.instance_ref_iter().chain(std::iter::once(any_intersection.to_instance_ref())), BooleanOperation::SubtractFront)
 }
 
+fn exclude<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, VectorData>>) -> VectorDataTable {
+	// Reverse vector data so that the result style is the style of the first vector data
+	let mut vector_data_reversed = vector_data.rev();
+
+	let mut result_vector_data_table = VectorDataTable::new_instance(vector_data_reversed.next().map(|x| x.to_instance_cloned()).unwrap_or_default());
+	let mut first_instance = result_vector_data_table.instance_mut_iter().next().expect("Expected the one instance we just pushed");
+
+	// Excluding each vector data in turn keeps the areas covered an odd number of times
+	for lower_vector_data in vector_data_reversed {
+		if cancellation::is_cancelled() {
+			break;
+		}
+		let transform_of_lower_into_space_of_upper = first_instance.transform.inverse() * *lower_vector_data.transform;
+
+		let result = &mut first_instance.instance;
+
+		let upper_path_string = to_path(result, DAffine2::IDENTITY);
+		let lower_path_string = to_path(lower_vector_data.instance, transform_of_lower_into_space_of_upper);
+
+		let boolean_operation_string = boolean_exclude(upper_path_string, lower_path_string);
+		let boolean_operation_result = from_path(&boolean_operation_string);
+
+		result.colinear_manipulators = boolean_operation_result.colinear_manipulators;
+		result.point_domain = boolean_operation_result.point_domain;
+		result.segment_domain = boolean_operation_result.segment_domain;
+		result.region_domain = boolean_operation_result.region_domain;
+	}
+
+	result_vector_data_table
+}
+
+fn divide<'a>(vector_data: impl DoubleEndedIterator<Item = InstanceRef<'a, VectorData>> + Clone) -> VectorDataTable {
+	// Every region takes the style and transform of the topmost vector data
+	let Some(top) = vector_data.clone().next_back() else { return VectorDataTable::default() };
+
+	// Each path splits the regions found so far by what's inside and outside of it, then adds the area it covers which none of the earlier paths do
+	let mut regions: Vec<Path> = Vec::new();
+	let mut covered: Path = Vec::new();
+	for lower_vector_data in vector_data {
+		if cancellation::is_cancelled() {
+			break;
+		}
+		let path = to_path(lower_vector_data.instance, top.transform.inverse() * *lower_vector_data.transform);
+
+		let mut divided = Vec::new();
+		for region in regions {
+			divided.extend(separate_regions(boolean_intersect(region.clone(), path.clone()).into_iter().flatten().collect()));
+			divided.extend(separate_regions(boolean_subtract(region, path.clone()).into_iter().flatten().collect()));
+		}
+		divided.extend(separate_regions(boolean_subtract(path.clone(), covered.clone()).into_iter().flatten().collect()));
+
+		regions = divided;
+		covered = boolean_union(covered, path).into_iter().flatten().collect();
+	}
+
+	regions
+		.iter()
+		.map(|region| {
+			let mut instance = from_path(std::slice::from_ref(region));
+			instance.style = top.instance.style.clone();
+			Instance {
+				instance,
+				transform: *top.transform,
+				alpha_blending: *top.alpha_blending,
+				source_node_id: *top.source_node_id,
+			}
+		})
+		.collect()
+}
+
+/// Splits a path into the separate regions it encloses, each along with the holes inside of it.
+fn separate_regions(path: Path) -> Vec<Path> {
+	if path.is_empty() {
+		return Vec::new();
+	}
+
+	match path_bool::path_boolean(&path, FillRule::NonZero, &Vec::new(), FillRule::NonZero, PathBooleanOperation::Fracture) {
+		Ok(regions) => regions.into_iter().filter(|region| !region.is_empty()).collect(),
+		Err(e) => {
+			let path_data = path_bool::path_to_path_data(&path, 0.001);
+			log::error!("Boolean error {e:?} encountered while separating the regions of {path_data}");
+			vec![path]
+		}
+	}
+}
+
+/// Finds the region around `point` which is enclosed by the areas covered by `ink`, searching no further than `bounds`, and grows it by `spread` so it tucks underneath the surrounding ink.
+/// Each piece of ink is filled by itself with the nonzero rule, so overlapping pieces never cancel each other out where they're wound in opposite directions.
+/// Returns `None` if the point is covered by ink or if its region reaches the edge of the bounds, since it then isn't enclosed.
+pub fn enclosed_region(ink: &[VectorData], point: DVec2, [min, max]: [DVec2; 2], spread: f64) -> Option<VectorData> {
+	// Cutting the ink out of the searched area leaves the faces of the planar subdivision formed by the ink's outlines
+	let mut faces = to_path(&VectorData::from_subpath(Subpath::new_rect(min, max)), DAffine2::IDENTITY);
+	for piece in ink {
+		faces = boolean_subtract(faces, to_path(piece, DAffine2::IDENTITY)).into_iter().flatten().collect();
+	}
+
+	let region = separate_regions(faces)
+		.into_iter()
+		.map(|face| from_path(&[face]))
+		.find(|face| face.check_point_inside_shape(DAffine2::IDENTITY, point))?;
+
+	const EDGE_MARGIN: f64 = 1e-3;
+	let [region_min, region_max] = region.bounding_box()?;
+	if (region_min - min).min_element() < EDGE_MARGIN || (max - region_max).min_element() < EDGE_MARGIN {
+		return None;
+	}
+
+	Some(offset(&region, DAffine2::IDENTITY, spread, StrokeJoin::Round, 4., OpenPathOffset::OneSide, true))
+}
+
 fn flatten_vector_data(graphic_group_table: &GraphicGroupTable) -> VectorDataTable {
 	graphic_group_table
 		.instance_ref_iter()
@@ -280,6 +483,8 @@ fn flatten_vector_data(graphic_group_table: &GraphicGroupTable) -> VectorDataTab
 
 					unioned.instance_iter().collect::<Vec<_>>()
 				}
+				// An effect's shape comes from the graphic it's drawn from, which is flattened on its own
+				GraphicElement::Effect(_) => Vec::new(),
 			}
 		})
 		.collect()
@@ -290,9 +495,26 @@ fn to_path(vector: &VectorData, transform: DAffine2) -> Vec<path_bool::PathSegme
 	for subpath in vector.stroke_bezier_paths() {
 		to_path_segments(&mut path, &subpath, transform);
 	}
+
+	// The boolean operations below treat both operands with the nonzero rule, so an even-odd shape is first resolved into the outline of the area it actually fills
+	if vector.style.fill_rule() == graphene_core::vector::style::FillRule::EvenOdd {
+		path = resolve_even_odd(path);
+	}
+
 	path
 }
 
+fn resolve_even_odd(path: Path) -> Path {
+	match path_bool::path_boolean(&path, FillRule::EvenOdd, &Vec::new(), FillRule::NonZero, PathBooleanOperation::Union) {
+		Ok(results) => results.into_iter().flatten().collect(),
+		Err(e) => {
+			let path_data = path_bool::path_to_path_data(&path, 0.001);
+			log::error!("Boolean error {e:?} encountered while resolving the even-odd fill rule of {path_data}");
+			path
+		}
+	}
+}
+
 fn to_path_segments(path: &mut Vec<path_bool::PathSegment>, subpath: &Subpath<PointId>, transform: DAffine2) {
 	use path_bool::PathSegment;
 	let mut global_start = None;
@@ -371,6 +593,10 @@ fn boolean_union(a: Path, b: Path) -> Vec<Path> {
 	path_bool(a, b, PathBooleanOperation::Union)
 }
 
+fn boolean_exclude(a: Path, b: Path) -> Vec<Path> {
+	path_bool(a, b, PathBooleanOperation::Exclusion)
+}
+
 fn path_bool(a: Path, b: Path, op: PathBooleanOperation) -> Vec<Path> {
 	match path_bool::path_boolean(&a, FillRule::NonZero, &b, FillRule::NonZero, op) {
 		Ok(results) => results,
@@ -390,3 +616,179 @@ fn boolean_subtract(a: Path, b: Path) -> Vec<Path> {
 pub fn boolean_intersect(a: Path, b: Path) -> Vec<Path> {
 	path_bool(a, b, PathBooleanOperation::Intersection)
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use bezier_rs::Bezier;
+
+	#[track_caller]
+	fn assert_bounds(vector_data: &VectorData, [min, max]: [DVec2; 2]) {
+		let [actual_min, actual_max] = vector_data.bounding_box().expect("The offset should not be empty");
+		assert!(
+			actual_min.abs_diff_eq(min, 0.05) && actual_max.abs_diff_eq(max, 0.05),
+			"Expected the bounds {:?} but found {:?}",
+			[min, max],
+			[actual_min, actual_max]
+		);
+	}
+
+	fn offset_with(vector_data: &VectorData, distance: f64, open_paths: OpenPathOffset, clean_up_intersections: bool) -> VectorData {
+		offset(vector_data, DAffine2::IDENTITY, distance, StrokeJoin::Round, 4., open_paths, clean_up_intersections)
+	}
+
+	#[test]
+	fn offset_grows_and_shrinks_closed_paths() {
+		let rectangle = VectorData::from_subpath(Subpath::new_rounded_rect(DVec2::ZERO, DVec2::new(100., 50.), [10.; 4]));
+
+		for clean_up_intersections in [false, true] {
+			let grown = offset_with(&rectangle, 5., OpenPathOffset::OneSide, clean_up_intersections);
+			assert_bounds(&grown, [DVec2::splat(-5.), DVec2::new(105., 55.)]);
+			assert_eq!(grown.stroke_bezier_paths().count(), 1);
+
+			let shrunk = offset_with(&rectangle, -5., OpenPathOffset::OneSide, clean_up_intersections);
+			assert_bounds(&shrunk, [DVec2::splat(5.), DVec2::new(95., 45.)]);
+			assert_eq!(shrunk.stroke_bezier_paths().count(), 1);
+		}
+
+		// Holes shrink as the shape around them grows, regardless of which direction they're drawn in
+		let mut frame = rectangle.clone();
+		frame.append_subpath(Subpath::new_rect(DVec2::new(30., 15.), DVec2::new(70., 35.)), false);
+		frame.style.set_fill_rule(graphene_core::vector::style::FillRule::EvenOdd);
+		for clean_up_intersections in [false, true] {
+			let grown = offset_with(&frame, 5., OpenPathOffset::OneSide, clean_up_intersections);
+			assert_bounds(&grown, [DVec2::splat(-5.), DVec2::new(105., 55.)]);
+			let hole_shrunk = grown
+				.stroke_bezier_paths()
+				.filter_map(|subpath| subpath.bounding_box())
+				.any(|[min, max]| min.abs_diff_eq(DVec2::new(35., 20.), 0.05) && max.abs_diff_eq(DVec2::new(65., 30.), 0.05));
+			assert!(hole_shrunk, "The hole should shrink by the offset distance");
+		}
+	}
+
+	#[test]
+	fn offset_cleans_up_self_intersecting_paths() {
+		let bowtie = VectorData::from_subpath(Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(100., 100.), DVec2::new(100., 0.), DVec2::new(0., 100.)], true));
+
+		// Both lobes grow, even though they're drawn in opposite directions, and merge into a single outline where they cross
+		let grown = offset_with(&bowtie, 5., OpenPathOffset::OneSide, true);
+		assert_bounds(&grown, [DVec2::splat(-5.), DVec2::splat(105.)]);
+		assert_eq!(grown.stroke_bezier_paths().count(), 1);
+	}
+
+	#[test]
+	fn offset_open_paths() {
+		let curve = Bezier::from_cubic_dvec2(DVec2::ZERO, DVec2::new(100., 0.), DVec2::new(0., 100.), DVec2::new(100., 100.));
+		let s_curve = VectorData::from_subpath(Subpath::from_bezier(&curve));
+
+		// Offsetting to one side keeps the path open, beginning and ending beside the original
+		let one_side = offset_with(&s_curve, 5., OpenPathOffset::OneSide, true);
+		let subpaths: Vec<_> = one_side.stroke_bezier_paths().collect();
+		assert_eq!(subpaths.len(), 1);
+		assert!(!subpaths[0].closed());
+		let anchors = subpaths[0].anchors();
+		assert!((anchors[0].distance(DVec2::ZERO) - 5.).abs() < 1e-3);
+		assert!((anchors[anchors.len() - 1].distance(DVec2::splat(100.)) - 5.).abs() < 1e-3);
+
+		// An outline surrounds both sides of the path with flat ends
+		let outline = offset_with(&s_curve, 5., OpenPathOffset::Outline, true);
+		let subpaths: Vec<_> = outline.stroke_bezier_paths().collect();
+		assert_eq!(subpaths.len(), 1);
+		assert!(subpaths[0].closed());
+		assert_bounds(&outline, [DVec2::new(0., -5.), DVec2::new(100., 105.)]);
+	}
+
+	/// Stacks the shapes from bottom to top, with a distinct fill on the topmost one.
+	fn stack(subpaths: impl IntoIterator<Item = Subpath<PointId>>) -> Vec<Instance<VectorData>> {
+		let mut stack: Vec<_> = subpaths
+			.into_iter()
+			.map(|subpath| Instance {
+				instance: VectorData::from_subpath(subpath),
+				..Default::default()
+			})
+			.collect();
+		if let Some(top) = stack.last_mut() {
+			top.instance.style.set_fill(Fill::Solid(Color::RED));
+		}
+		stack
+	}
+
+	fn operate(stack: &[Instance<VectorData>], operation: BooleanOperation) -> VectorDataTable {
+		boolean_operation_on_vector_data_table(stack.iter().map(Instance::to_instance_ref), operation)
+	}
+
+	/// Checks the horizontal extents of the given bounding boxes, ignoring their order.
+	#[track_caller]
+	fn assert_extents(bounding_boxes: impl Iterator<Item = [DVec2; 2]>, expected: &[[f64; 2]]) {
+		let mut extents: Vec<_> = bounding_boxes.map(|[min, max]| [min.x, max.x]).collect();
+		extents.sort_by(|a, b| a[0].total_cmp(&b[0]));
+		let matches = extents.len() == expected.len() && extents.iter().zip(expected).all(|(a, b)| (a[0] - b[0]).abs() < 0.1 && (a[1] - b[1]).abs() < 0.1);
+		assert!(matches, "Expected the horizontal extents {expected:?} but found {extents:?}");
+	}
+
+	#[test]
+	fn divide_and_exclude_overlapping_circles() {
+		let circles = stack([
+			Subpath::new_ellipse(DVec2::splat(-50.), DVec2::splat(50.)),
+			Subpath::new_ellipse(DVec2::new(10., -50.), DVec2::new(110., 50.)),
+		]);
+
+		// The circles cross at x = 30, dividing them into two crescents and the lens between them, which all take the style of the topmost circle
+		let divided = operate(&circles, BooleanOperation::Divide);
+		assert_extents(divided.instance_ref_iter().filter_map(|region| region.instance.bounding_box()), &[[-50., 30.], [10., 50.], [30., 110.]]);
+		assert!(divided.instance_ref_iter().all(|region| region.instance.style.fill() == &Fill::Solid(Color::RED)));
+
+		let excluded = operate(&circles, BooleanOperation::Exclude);
+		assert_eq!(excluded.instance_ref_iter().count(), 1);
+		let excluded = excluded.instance_ref_iter().next().unwrap().instance;
+		assert_extents(excluded.stroke_bezier_paths().filter_map(|subpath| subpath.bounding_box()), &[[-50., 30.], [30., 110.]]);
+		assert_eq!(excluded.style.fill(), &Fill::Solid(Color::RED));
+	}
+
+	#[test]
+	fn divide_and_exclude_rectangles_with_coincident_edges() {
+		// The top and bottom edges of the overlap are shared by both rectangles
+		let overlapping = stack([Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)), Subpath::new_rect(DVec2::new(50., 0.), DVec2::new(150., 100.))]);
+		let divided = operate(&overlapping, BooleanOperation::Divide);
+		assert_extents(divided.instance_ref_iter().filter_map(|region| region.instance.bounding_box()), &[[0., 50.], [50., 100.], [100., 150.]]);
+		let excluded = operate(&overlapping, BooleanOperation::Exclude);
+		let excluded = excluded.instance_ref_iter().next().unwrap().instance;
+		assert_extents(excluded.stroke_bezier_paths().filter_map(|subpath| subpath.bounding_box()), &[[0., 50.], [100., 150.]]);
+
+		// Rectangles which only touch along an edge stay separate regions when divided, but join into one shape when excluded
+		let touching = stack([Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)), Subpath::new_rect(DVec2::new(100., 0.), DVec2::new(200., 100.))]);
+		let divided = operate(&touching, BooleanOperation::Divide);
+		assert_extents(divided.instance_ref_iter().filter_map(|region| region.instance.bounding_box()), &[[0., 100.], [100., 200.]]);
+		let excluded = operate(&touching, BooleanOperation::Exclude);
+		let excluded = excluded.instance_ref_iter().next().unwrap().instance;
+		assert_extents(excluded.stroke_bezier_paths().filter_map(|subpath| subpath.bounding_box()), &[[0., 200.]]);
+	}
+
+	#[test]
+	fn enclosed_regions_of_a_grid_of_crossing_lines() {
+		// Each line of the grid is a thin bar, with the horizontal ones wound in the opposite direction so the ink cancels out where they cross unless each bar is filled by itself
+		let ink: Vec<_> = [0., 10., 20., 30.]
+			.into_iter()
+			.flat_map(|position| {
+				let vertical = Subpath::new_rect(DVec2::new(position - 0.5, -5.), DVec2::new(position + 0.5, 35.));
+				let [min, max] = [DVec2::new(-5., position - 0.5), DVec2::new(35., position + 0.5)];
+				let horizontal = Subpath::from_anchors_linear([min, DVec2::new(min.x, max.y), max, DVec2::new(max.x, min.y)], true);
+				[vertical, horizontal]
+			})
+			.map(VectorData::from_subpath)
+			.collect();
+		let bounds = [DVec2::splat(-50.), DVec2::splat(80.)];
+
+		// The cell is grown to the middle of the lines around it
+		let cell = enclosed_region(&ink, DVec2::new(15., 15.), bounds, 0.5).expect("The cell should be enclosed");
+		assert_bounds(&cell, [DVec2::splat(10.), DVec2::splat(20.)]);
+		assert_eq!(cell.stroke_bezier_paths().count(), 1);
+
+		// Points on the lines, including where they cross, aren't in any region
+		assert!(enclosed_region(&ink, DVec2::new(10., 15.), bounds, 0.5).is_none());
+		assert!(enclosed_region(&ink, DVec2::new(10., 10.), bounds, 0.5).is_none());
+
+		// The area around the grid continues past the bounds searched
+		assert!(enclosed_region(&ink, DVec2::new(50., 15.), bounds, 0.5).is_none());
+	}
+}
@@ -97,6 +97,13 @@ macro_rules! tagged_value {
 					_ => Err(format!("Cannot convert {:?} to TaggedValue", DynAny::type_name(input.as_ref()))),
 				}
 			}
+			/// Whether [`Self::try_from_any`] can convert the dynamic type to a tagged value, since it drops the value when it can't.
+			pub fn can_convert_from_any(input: &(dyn DynAny<'a> + 'a)) -> bool {
+				use std::any::TypeId;
+
+				let type_id = DynAny::type_id(input);
+				type_id == TypeId::of::<()>() $( || type_id == TypeId::of::<$ty>() )* || type_id == TypeId::of::<RenderOutput>() || type_id == TypeId::of::<SurfaceFrame>()
+			}
 			/// Attempts to downcast the dynamic type to a tagged value
 			pub fn try_from_std_any_ref(input: &dyn std::any::Any) -> Result<Self, String> {
 				use std::any::TypeId;
@@ -109,6 +116,21 @@ macro_rules! tagged_value {
 					_ => Err(format!("Cannot convert {:?} to TaggedValue",std::any::type_name_of_val(input))),
 				}
 			}
+			/// Clones the output recorded by a monitor node into a tagged value, if it's of a type which a tagged value can hold
+			pub fn try_from_io_record(record: &dyn std::any::Any) -> Result<Self, String> {
+				use graphene_core::Context;
+				use graphene_core::memo::IORecord;
+
+				$(
+					if let Some(record) = record.downcast_ref::<IORecord<Context, $ty>>() {
+						return Ok(TaggedValue::$identifier(record.output.clone()));
+					}
+					if let Some(record) = record.downcast_ref::<IORecord<(), $ty>>() {
+						return Ok(TaggedValue::$identifier(record.output.clone()));
+					}
+				)*
+				Err("Cannot convert the recorded output to TaggedValue".to_string())
+			}
 			pub fn from_type(input: &Type) -> Option<Self> {
 				match input {
 					Type::Generic(_) => None,
@@ -204,8 +226,10 @@ tagged_value! {
 	Palette(Vec<Color>),
 	Subpaths(Vec<bezier_rs::Subpath<graphene_core::vector::PointId>>),
 	Fill(graphene_core::vector::style::Fill),
+	SwatchColor(graphene_core::vector::style::SwatchColor),
 	Stroke(graphene_core::vector::style::Stroke),
 	Gradient(graphene_core::vector::style::Gradient),
+	Pattern(graphene_core::vector::style::Pattern),
 	#[serde(alias = "GradientPositions")] // TODO: Eventually remove this alias document upgrade code
 	GradientStops(graphene_core::vector::style::GradientStops),
 	Font(graphene_core::text::Font),
@@ -213,6 +237,7 @@ tagged_value! {
 	BrushCache(BrushCache),
 	DocumentNode(DocumentNode),
 	Curve(graphene_raster_nodes::curve::Curve),
+	Histogram(graphene_raster_nodes::histogram::Histogram),
 	Footprint(graphene_core::transform::Footprint),
 	VectorModification(Box<graphene_core::vector::VectorModification>),
 	FontCache(Arc<graphene_core::text::FontCache>),
@@ -224,6 +249,7 @@ tagged_value! {
 	XY(graphene_core::extract_xy::XY),
 	RedGreenBlue(graphene_raster_nodes::adjustments::RedGreenBlue),
 	RedGreenBlueAlpha(graphene_raster_nodes::adjustments::RedGreenBlueAlpha),
+	LevelsChannel(graphene_raster_nodes::adjustments::LevelsChannel),
 	RealTimeMode(graphene_core::animation::RealTimeMode),
 	NoiseType(graphene_raster_nodes::adjustments::NoiseType),
 	FractalType(graphene_raster_nodes::adjustments::FractalType),
@@ -232,23 +258,36 @@ tagged_value! {
 	DomainWarpType(graphene_raster_nodes::adjustments::DomainWarpType),
 	RelativeAbsolute(graphene_raster_nodes::adjustments::RelativeAbsolute),
 	SelectiveColorChoice(graphene_raster_nodes::adjustments::SelectiveColorChoice),
+	WorkingSpace(graphene_raster_nodes::adjustments::WorkingSpace),
+	BlurMode(graphene_raster_nodes::filter::BlurMode),
+	BlurEdges(graphene_raster_nodes::filter::BlurEdges),
+	EffectType(graphene_core::effects::EffectType),
+	DitheringMode(graphene_raster_nodes::threshold::DitheringMode),
+	TraceColorMode(graphene_raster_nodes::image_trace::TraceColorMode),
 	GridType(graphene_core::vector::misc::GridType),
 	ArcType(graphene_core::vector::misc::ArcType),
 	MergeByDistanceAlgorithm(graphene_core::vector::misc::MergeByDistanceAlgorithm),
 	PointSpacingType(graphene_core::vector::misc::PointSpacingType),
+	OpenPathOffset(graphene_core::vector::misc::OpenPathOffset),
+	ScatterMode(graphene_core::vector::misc::ScatterMode),
 	#[serde(alias = "LineCap")]
 	StrokeCap(graphene_core::vector::style::StrokeCap),
 	#[serde(alias = "LineJoin")]
 	StrokeJoin(graphene_core::vector::style::StrokeJoin),
 	StrokeAlign(graphene_core::vector::style::StrokeAlign),
 	PaintOrder(graphene_core::vector::style::PaintOrder),
+	StrokeMarker(graphene_core::vector::style::StrokeMarker),
 	FillType(graphene_core::vector::style::FillType),
+	FillRule(graphene_core::vector::style::FillRule),
 	FillChoice(graphene_core::vector::style::FillChoice),
 	GradientType(graphene_core::vector::style::GradientType),
+	GradientInterpolation(graphene_core::vector::style::GradientInterpolation),
 	ReferencePoint(graphene_core::transform::ReferencePoint),
 	CentroidType(graphene_core::vector::misc::CentroidType),
 	BooleanOperation(graphene_path_bool::BooleanOperation),
 	TextAlign(graphene_core::text::TextAlign),
+	TextPathSide(graphene_core::text::TextPathSide),
+	TextPathOverflow(graphene_core::text::TextPathOverflow),
 }
 
 impl TaggedValue {
@@ -373,6 +412,104 @@ impl TaggedValue {
 			_ => panic!("Passed value is not of type u32"),
 		}
 	}
+
+	/// The estimated memory used by the value in bytes, such as for keeping a cache of values within a memory budget.
+	/// Beyond the size of the value itself, only the bulk of its heap allocations is counted, such as the pixels of images and the points and segments of vector data.
+	pub fn estimated_size(&self) -> u64 {
+		let heap_size = match self {
+			TaggedValue::String(string) => string.len() as u64,
+			TaggedValue::VecF64(values) => size_of_val(values.as_slice()) as u64,
+			TaggedValue::VecU64(values) => size_of_val(values.as_slice()) as u64,
+			TaggedValue::VecDVec2(values) => size_of_val(values.as_slice()) as u64,
+			TaggedValue::Palette(colors) => size_of_val(colors.as_slice()) as u64,
+			TaggedValue::Image(image) => estimated_image_size(image),
+			TaggedValue::RasterData(table) => table.instance_ref_iter().map(|raster| estimated_image_size(raster.instance)).sum(),
+			TaggedValue::VectorData(table) => estimated_vector_table_size(table),
+			TaggedValue::GraphicGroup(table) => estimated_graphic_group_size(table),
+			TaggedValue::GraphicElement(graphic_element) => estimated_graphic_element_size(graphic_element),
+			TaggedValue::Artboard(artboard) => estimated_graphic_group_size(&artboard.graphic_group),
+			TaggedValue::ArtboardGroup(table) => table.instance_ref_iter().map(|artboard| estimated_graphic_group_size(&artboard.instance.graphic_group)).sum(),
+			_ => 0,
+		};
+		size_of::<Self>() as u64 + heap_size
+	}
+
+	/// A short description of the value for showing it at a glance, such as in the data panel.
+	/// Numbers and text are shown as they are, while collections and graphical data are summarized by how much they contain.
+	pub fn preview(&self) -> String {
+		const MAX_TEXT_LENGTH: usize = 64;
+
+		let count = |count: usize, noun: &str| if count == 1 { format!("1 {noun}") } else { format!("{count} {noun}s") };
+
+		match self {
+			TaggedValue::None => "Nothing".to_string(),
+			TaggedValue::F64(x) => x.to_string(),
+			TaggedValue::U32(x) => x.to_string(),
+			TaggedValue::U64(x) => x.to_string(),
+			TaggedValue::Bool(x) => x.to_string(),
+			TaggedValue::String(x) if x.chars().count() > MAX_TEXT_LENGTH => format!("\"{}…\"", x.chars().take(MAX_TEXT_LENGTH).collect::<String>()),
+			TaggedValue::String(x) => format!("\"{x}\""),
+			TaggedValue::DVec2(x) => format!("({}, {})", x.x, x.y),
+			TaggedValue::Color(color) | TaggedValue::OptionalColor(Some(color)) => format!("#{}", color.to_rgba_hex_srgb()),
+			TaggedValue::OptionalColor(None) => "No color".to_string(),
+			TaggedValue::VecF64(values) => count(values.len(), "number"),
+			TaggedValue::VecU64(values) => count(values.len(), "number"),
+			TaggedValue::VecDVec2(values) => count(values.len(), "point"),
+			TaggedValue::Palette(colors) => count(colors.len(), "color"),
+			TaggedValue::Image(image) => format!("{}×{} image", image.width, image.height),
+			TaggedValue::RasterData(table) => count(table.len(), "image"),
+			TaggedValue::VectorData(table) => {
+				let points = table.instance_ref_iter().map(|vector_data| vector_data.instance.point_domain.ids().len()).sum();
+				format!("{} with {}", count(table.len(), "vector shape"), count(points, "point"))
+			}
+			TaggedValue::GraphicGroup(table) => count(table.len(), "graphic element"),
+			TaggedValue::ArtboardGroup(table) => count(table.len(), "artboard"),
+			TaggedValue::Artboard(artboard) => format!("Artboard \"{}\"", artboard.label),
+			_ => self.ty().to_string(),
+		}
+	}
+}
+
+fn estimated_image_size(image: &Image<Color>) -> u64 {
+	image.width as u64 * image.height as u64 * size_of::<Color>() as u64
+}
+
+fn estimated_vector_table_size(table: &graphene_core::vector::VectorDataTable) -> u64 {
+	use graphene_core::vector::{PointId, RegionId, SegmentId, StrokeId};
+
+	// Each point has an ID and a position, each segment has an ID, two point indices, handles, and a stroke, and each region has an ID and a segment range
+	let point_size = size_of::<PointId>() + size_of::<DVec2>();
+	let segment_size = size_of::<SegmentId>() + 2 * size_of::<usize>() + size_of::<bezier_rs::BezierHandles>() + size_of::<StrokeId>();
+	let region_size = size_of::<RegionId>() + 2 * size_of::<SegmentId>();
+
+	table
+		.instance_ref_iter()
+		.map(|vector_data| {
+			let vector_data = vector_data.instance;
+			let points = vector_data.point_domain.ids().len() * point_size;
+			let segments = vector_data.segment_domain.ids().len() * segment_size;
+			let regions = vector_data.region_domain.ids().len() * region_size;
+			let upstream = vector_data.upstream_graphic_group.as_ref().map_or(0, estimated_graphic_group_size);
+			(points + segments + regions) as u64 + upstream
+		})
+		.sum()
+}
+
+fn estimated_graphic_group_size(table: &graphene_core::GraphicGroupTable) -> u64 {
+	table.instance_ref_iter().map(|graphic_element| estimated_graphic_element_size(graphic_element.instance)).sum()
+}
+
+fn estimated_graphic_element_size(graphic_element: &graphene_core::GraphicElement) -> u64 {
+	match graphic_element {
+		graphene_core::GraphicElement::GraphicGroup(table) => estimated_graphic_group_size(table),
+		graphene_core::GraphicElement::VectorData(table) => estimated_vector_table_size(table),
+		graphene_core::GraphicElement::RasterDataCPU(table) => table.instance_ref_iter().map(|raster| estimated_image_size(raster.instance)).sum(),
+		// The pixels of GPU rasters are stored in video memory
+		graphene_core::GraphicElement::RasterDataGPU(_) => 0,
+		graphene_core::GraphicElement::Effect(effect) => {
+			estimated_graphic_group_size(&effect.source) + effect.rasterized.instance_ref_iter().map(|raster| estimated_image_size(raster.instance)).sum::<u64>()
+		}
+	}
 }
 
 impl Display for TaggedValue {
@@ -163,6 +163,21 @@ impl WasmApplicationIo {
 
 		io
 	}
+
+	/// Carries over the state of an application IO which this one replaces, which doesn't depend on the platform's windowing or GPU context:
+	/// the resources loaded into it, and the surfaces created for it if this one has none of its own.
+	///
+	/// In the browser, the canvases of the previous application IO are removed once it's dropped, so they aren't carried over.
+	pub fn inherit_from(&mut self, previous: &WasmApplicationIo) {
+		for (path, data) in &previous.resources {
+			self.resources.entry(path.clone()).or_insert_with(|| data.clone());
+		}
+
+		#[cfg(not(target_arch = "wasm32"))]
+		if self.windows.is_empty() {
+			self.windows = previous.windows.iter().map(|wrapper| WindowWrapper { window: wrapper.window.clone() }).collect();
+		}
+	}
 }
 
 unsafe impl StaticType for WasmApplicationIo {
@@ -335,6 +350,10 @@ pub type WasmSurfaceHandleFrame = graphene_application_io::SurfaceHandleFrame<wg
 #[derive(Clone, Debug, PartialEq, Hash, specta::Type, serde::Serialize, serde::Deserialize)]
 pub struct EditorPreferences {
 	pub use_vello: bool,
+	/// The memory budget for brush caches in bytes, or `None` to base it on the available memory.
+	pub brush_cache_budget: Option<u64>,
+	/// The memory budget for reusing node outputs across graph evaluations in bytes, or `None` for the default budget.
+	pub node_cache_budget: Option<u64>,
 }
 
 impl graphene_application_io::GetEditorPreferences for EditorPreferences {
@@ -350,6 +369,8 @@ impl Default for EditorPreferences {
 			use_vello: false,
 			#[cfg(not(target_arch = "wasm32"))]
 			use_vello: true,
+			brush_cache_budget: None,
+			node_cache_budget: None,
 		}
 	}
 }
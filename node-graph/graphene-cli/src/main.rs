@@ -118,7 +118,7 @@ async fn main() -> Result<(), Box<dyn Error>> {
 			let render_config = RenderConfig::default();
 
 			loop {
-				let result = (&executor).execute(render_config).await?;
+				let result = (&executor).execute(render_config.clone()).await?;
 				if !run_loop {
 					println!("{:?}", result);
 					break;
@@ -0,0 +1,142 @@
+use criterion::{BatchSize, BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use glam::{DAffine2, DVec2, UVec2};
+use graphene_core::color::Color;
+use graphene_core::context::OwnedContextImpl;
+use graphene_core::instances::Instance;
+use graphene_core::raster::image::Image;
+use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
+use graphene_core::transform::Footprint;
+use graphene_raster_nodes::adjust::Adjust;
+use graphene_raster_nodes::tiles::crop_to_visible_tiles;
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Tracks the current and peak number of allocated bytes, so the memory used by each approach can be reported alongside its timing.
+struct PeakAllocator;
+
+static ALLOCATED: AtomicUsize = AtomicUsize::new(0);
+static PEAK: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for PeakAllocator {
+	unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+		let pointer = unsafe { System.alloc(layout) };
+		if !pointer.is_null() {
+			let allocated = ALLOCATED.fetch_add(layout.size(), Ordering::Relaxed) + layout.size();
+			PEAK.fetch_max(allocated, Ordering::Relaxed);
+		}
+		pointer
+	}
+
+	unsafe fn dealloc(&self, pointer: *mut u8, layout: Layout) {
+		unsafe { System.dealloc(pointer, layout) };
+		ALLOCATED.fetch_sub(layout.size(), Ordering::Relaxed);
+	}
+}
+
+#[global_allocator]
+static GLOBAL: PeakAllocator = PeakAllocator;
+
+/// The width and height of the benchmarked image, about 67 megapixels.
+const IMAGE_SIZE: u32 = 8192;
+
+/// The size of the viewport panned across the image.
+const VIEWPORT_SIZE: u32 = 1920;
+
+fn large_image() -> RasterDataTable<CPU> {
+	let data = (0..IMAGE_SIZE * IMAGE_SIZE)
+		.map(|index| {
+			let (x, y) = (index % IMAGE_SIZE, index / IMAGE_SIZE);
+			Color::from_rgbaf32_unchecked(x as f32 / IMAGE_SIZE as f32, y as f32 / IMAGE_SIZE as f32, 0.5, 1.)
+		})
+		.collect();
+
+	RasterDataTable::new_instance(Instance {
+		instance: Raster::new_cpu(Image {
+			width: IMAGE_SIZE,
+			height: IMAGE_SIZE,
+			data,
+			base64_string: None,
+		}),
+		transform: DAffine2::from_scale(DVec2::splat(IMAGE_SIZE as f64)),
+		..Default::default()
+	})
+}
+
+/// Three adjustments in a row, standing in for a typical chain of adjustment nodes.
+fn adjust_three_times(image: &mut RasterDataTable<CPU>) {
+	image.adjust(|color| color.map_rgb(|channel| 1. - channel));
+	image.adjust(|color| color.map_rgb(|channel| channel * 2.));
+	image.adjust(|color| color.map_rgb(|channel| channel.clamp(0., 1.)));
+}
+
+/// The same three adjustments, applied to every pixel in a single thread as adjustment nodes did before tiling.
+fn adjust_three_times_serially(image: &mut RasterDataTable<CPU>) {
+	for instance in image.instance_mut_iter() {
+		let data = instance.instance.data_mut();
+		for color in data.data.iter_mut() {
+			*color = color.map_rgb(|channel| 1. - channel);
+		}
+		for color in data.data.iter_mut() {
+			*color = color.map_rgb(|channel| channel * 2.);
+		}
+		for color in data.data.iter_mut() {
+			*color = color.map_rgb(|channel| channel.clamp(0., 1.));
+		}
+	}
+}
+
+/// Measures the peak memory allocated while running the closure, beyond what was already allocated beforehand.
+fn peak_memory(run: impl FnOnce()) -> usize {
+	let baseline = ALLOCATED.load(Ordering::Relaxed);
+	PEAK.store(baseline, Ordering::Relaxed);
+	run();
+	PEAK.load(Ordering::Relaxed) - baseline
+}
+
+fn adjust_large_image(c: &mut Criterion) {
+	let image = large_image();
+	let viewport = |x: f64| {
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-x, -1000.)),
+			resolution: UVec2::splat(VIEWPORT_SIZE),
+			..Default::default()
+		};
+		OwnedContextImpl::default().with_footprint(footprint).into_context()
+	};
+
+	let megabytes = |bytes: usize| bytes as f64 / (1024. * 1024.);
+	let serial = peak_memory(|| adjust_three_times_serially(&mut image.clone()));
+	let tiled = peak_memory(|| adjust_three_times(&mut image.clone()));
+	let visible = peak_memory(|| adjust_three_times(&mut crop_to_visible_tiles(viewport(1000.), image.clone())));
+	eprintln!("Peak memory of whole image, serial: {:.0} MB", megabytes(serial));
+	eprintln!("Peak memory of whole image, tiled: {:.0} MB", megabytes(tiled));
+	eprintln!("Peak memory of visible tiles: {:.0} MB", megabytes(visible));
+
+	let mut group = c.benchmark_group("Adjust 8192×8192 image");
+	group.sample_size(10);
+	group.bench_function("whole image, serial", |b| {
+		b.iter_batched_ref(|| image.clone(), |image| adjust_three_times_serially(black_box(image)), BatchSize::LargeInput)
+	});
+	group.bench_function("whole image, tiled", |b| {
+		b.iter_batched_ref(|| image.clone(), |image| adjust_three_times(black_box(image)), BatchSize::LargeInput)
+	});
+
+	// Each frame of a pan re-evaluates the chain with the viewport moved slightly
+	for pan in [0., 100., 400.] {
+		group.bench_with_input(BenchmarkId::new("visible tiles, panned", pan), &pan, |b, &pan| {
+			b.iter_batched(
+				|| image.clone(),
+				|image| {
+					let mut visible = crop_to_visible_tiles(viewport(1000. + pan), black_box(image));
+					adjust_three_times(&mut visible);
+					visible
+				},
+				BatchSize::LargeInput,
+			)
+		});
+	}
+	group.finish();
+}
+
+criterion_group!(benches, adjust_large_image);
+criterion_main!(benches);
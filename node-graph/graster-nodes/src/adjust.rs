@@ -1,15 +1,15 @@
 use graphene_core_shaders::color::Color;
 
 pub trait Adjust<P> {
-	fn adjust(&mut self, map_fn: impl Fn(&P) -> P);
+	fn adjust(&mut self, map_fn: impl Fn(&P) -> P + Sync);
 }
 impl Adjust<Color> for Color {
-	fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color) {
+	fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color + Sync) {
 		*self = map_fn(self);
 	}
 }
 impl Adjust<Color> for Option<Color> {
-	fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color) {
+	fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color + Sync) {
 		if let Some(v) = self {
 			*v = map_fn(v)
 		}
@@ -19,21 +19,20 @@ impl Adjust<Color> for Option<Color> {
 #[cfg(feature = "std")]
 mod adjust_std {
 	use super::*;
+	use crate::tiles::map_pixels_in_tiles;
 	use graphene_core::gradient::GradientStops;
 	use graphene_core::raster_types::{CPU, RasterDataTable};
 	impl Adjust<Color> for GradientStops {
-		fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color) {
-			for (_pos, c) in self.iter_mut() {
+		fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color + Sync) {
+			for (_pos, c) in self.color_stops.iter_mut() {
 				*c = map_fn(c);
 			}
 		}
 	}
 	impl Adjust<Color> for RasterDataTable<CPU> {
-		fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color) {
+		fn adjust(&mut self, map_fn: impl Fn(&Color) -> Color + Sync) {
 			for instance in self.instance_mut_iter() {
-				for c in instance.instance.data_mut().data.iter_mut() {
-					*c = map_fn(c);
-				}
+				map_pixels_in_tiles(instance.instance.data_mut(), &map_fn);
 			}
 		}
 	}
@@ -39,6 +39,18 @@ pub enum LuminanceCalculation {
 	MaximumChannels,
 }
 
+impl LuminanceCalculation {
+	pub fn luminance(self, color: &Color) -> f32 {
+		match self {
+			LuminanceCalculation::SRGB => color.luminance_srgb(),
+			LuminanceCalculation::Perceptual => color.luminance_perceptual(),
+			LuminanceCalculation::AverageChannels => color.average_rgb_channels(),
+			LuminanceCalculation::MinimumChannels => color.minimum_rgb_channels(),
+			LuminanceCalculation::MaximumChannels => color.maximum_rgb_channels(),
+		}
+	}
+}
+
 #[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
 fn luminance<T: Adjust<Color>>(
 	_: impl Ctx,
@@ -51,13 +63,7 @@ fn luminance<T: Adjust<Color>>(
 	luminance_calc: LuminanceCalculation,
 ) -> T {
 	input.adjust(|color| {
-		let luminance = match luminance_calc {
-			LuminanceCalculation::SRGB => color.luminance_srgb(),
-			LuminanceCalculation::Perceptual => color.luminance_perceptual(),
-			LuminanceCalculation::AverageChannels => color.average_rgb_channels(),
-			LuminanceCalculation::MinimumChannels => color.minimum_rgb_channels(),
-			LuminanceCalculation::MaximumChannels => color.maximum_rgb_channels(),
-		};
+		let luminance = luminance_calc.luminance(color);
 		color.map_rgb(|_| luminance)
 	});
 	input
@@ -212,6 +218,66 @@ fn brightness_contrast<T: Adjust<Color>>(
 	input
 }
 
+/// The channel whose Levels parameters are shown in the Properties panel.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny, specta::Type, serde::Serialize, serde::Deserialize))]
+#[widget(Radio)]
+pub enum LevelsChannel {
+	#[default]
+	Master,
+	Red,
+	Green,
+	Blue,
+}
+
+/// The levels applied to a single channel, with all values in the 0-1 range of gamma-encoded color except the gamma exponent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LevelsRange {
+	pub input_black: f32,
+	pub input_white: f32,
+	pub gamma: f32,
+	pub output_black: f32,
+	pub output_white: f32,
+}
+
+impl Default for LevelsRange {
+	fn default() -> Self {
+		Self {
+			input_black: 0.,
+			input_white: 1.,
+			gamma: 1.,
+			output_black: 0.,
+			output_white: 1.,
+		}
+	}
+}
+
+impl LevelsRange {
+	/// Builds the range from the node's parameters, which are given as percentages apart from the gamma.
+	pub fn from_parameters(input_black: Percentage, input_white: Percentage, gamma: f64, output_black: Percentage, output_white: Percentage) -> Self {
+		Self {
+			input_black: (input_black / 100.) as f32,
+			input_white: (input_white / 100.) as f32,
+			gamma: gamma as f32,
+			output_black: (output_black / 100.) as f32,
+			output_white: (output_white / 100.) as f32,
+		}
+	}
+
+	/// Stretches the input range to fill 0-1, clipping values outside it, then applies the gamma and remaps the result into the output range.
+	pub fn map(&self, value: f32) -> f32 {
+		// Input levels (Range: 0-1)
+		let input_range = (self.input_white - self.input_black).max(f32::EPSILON);
+		let value = ((value - self.input_black) / input_range).clamp(0., 1.);
+
+		// Midtones (Range: 0-1), where a gamma above 1 brightens and below 1 darkens
+		let value = value.powf(1. / self.gamma.clamp(0.01, 10.));
+
+		// Output levels (Range: 0-1)
+		self.output_black + (self.output_white - self.output_black) * value
+	}
+}
+
 // Aims for interoperable compatibility with:
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=levl%27%20%3D%20Levels
 //
@@ -220,7 +286,7 @@ fn brightness_contrast<T: Adjust<Color>>(
 //
 // Some further analysis available at:
 // https://geraldbakker.nl/psnumbers/levels.html
-#[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
+#[node_macro::node(category("Raster: Adjustment"), properties("levels_properties"), shader_node(PerPixelAdjust))]
 fn levels<T: Adjust<Color>>(
 	_: impl Ctx,
 	#[implementations(
@@ -229,57 +295,130 @@ fn levels<T: Adjust<Color>>(
 		GradientStops,
 	)]
 	mut image: T,
-	#[default(0.)] shadows: Percentage,
-	#[default(50.)] midtones: Percentage,
-	#[default(100.)] highlights: Percentage,
-	#[default(0.)] output_minimums: Percentage,
-	#[default(100.)] output_maximums: Percentage,
-) -> T {
-	image.adjust(|color| {
-		let color = color.to_gamma_srgb();
 
-		// Input Range (Range: 0-1)
-		let input_shadows = (shadows / 100.) as f32;
-		let input_midtones = (midtones / 100.) as f32;
-		let input_highlights = (highlights / 100.) as f32;
+	#[default(0.)] input_black: Percentage,
+	#[default(100.)] input_white: Percentage,
+	#[default(1.)] gamma: f64,
+	#[default(0.)] output_black: Percentage,
+	#[default(100.)] output_white: Percentage,
 
-		// Output Range (Range: 0-1)
-		let output_minimums = (output_minimums / 100.) as f32;
-		let output_maximums = (output_maximums / 100.) as f32;
+	#[default(0.)]
+	#[name("(Red) Input Black")]
+	red_input_black: Percentage,
+	#[default(100.)]
+	#[name("(Red) Input White")]
+	red_input_white: Percentage,
+	#[default(1.)]
+	#[name("(Red) Gamma")]
+	red_gamma: f64,
+	#[default(0.)]
+	#[name("(Red) Output Black")]
+	red_output_black: Percentage,
+	#[default(100.)]
+	#[name("(Red) Output White")]
+	red_output_white: Percentage,
 
-		// Midtones interpolation factor between minimums and maximums (Range: 0-1)
-		let midtones = output_minimums + (output_maximums - output_minimums) * input_midtones;
+	#[default(0.)]
+	#[name("(Green) Input Black")]
+	green_input_black: Percentage,
+	#[default(100.)]
+	#[name("(Green) Input White")]
+	green_input_white: Percentage,
+	#[default(1.)]
+	#[name("(Green) Gamma")]
+	green_gamma: f64,
+	#[default(0.)]
+	#[name("(Green) Output Black")]
+	green_output_black: Percentage,
+	#[default(100.)]
+	#[name("(Green) Output White")]
+	green_output_white: Percentage,
 
-		// Gamma correction (Range: 0.01-10)
-		let gamma = if midtones < 0.5 {
-			// Range: 0-1
-			let x = 1. - midtones * 2.;
-			// Range: 1-10
-			1. + 9. * x
-		} else {
-			// Range: 0-0.5
-			let x = 1. - midtones;
-			// Range: 0-1
-			let x = x * 2.;
-			// Range: 0.01-1
-			x.max(0.01)
-		};
+	#[default(0.)]
+	#[name("(Blue) Input Black")]
+	blue_input_black: Percentage,
+	#[default(100.)]
+	#[name("(Blue) Input White")]
+	blue_input_white: Percentage,
+	#[default(1.)]
+	#[name("(Blue) Gamma")]
+	blue_gamma: f64,
+	#[default(0.)]
+	#[name("(Blue) Output Black")]
+	blue_output_black: Percentage,
+	#[default(100.)]
+	#[name("(Blue) Output White")]
+	blue_output_white: Percentage,
 
-		// Input levels (Range: 0-1)
-		let highlights_minus_shadows = (input_highlights - input_shadows).clamp(f32::EPSILON, 1.);
-		let color = color.map_rgb(|c| ((c - input_shadows).max(0.) / highlights_minus_shadows).min(1.));
+	// Display-only properties (not used within the node)
+	_channel: LevelsChannel,
+) -> T {
+	let master = LevelsRange::from_parameters(input_black, input_white, gamma, output_black, output_white);
+	let red = LevelsRange::from_parameters(red_input_black, red_input_white, red_gamma, red_output_black, red_output_white);
+	let green = LevelsRange::from_parameters(green_input_black, green_input_white, green_gamma, green_output_black, green_output_white);
+	let blue = LevelsRange::from_parameters(blue_input_black, blue_input_white, blue_gamma, blue_output_black, blue_output_white);
 
-		// Midtones (Range: 0-1)
-		let color = color.gamma(gamma);
+	image.adjust(|color| {
+		let (r, g, b, a) = color.to_gamma_srgb().components();
 
-		// Output levels (Range: 0-1)
-		let color = color.map_rgb(|c| c * (output_maximums - output_minimums) + output_minimums);
+		// Each channel's own levels are applied before the master levels
+		let color = Color::from_rgbaf32_unchecked(master.map(red.map(r)), master.map(green.map(g)), master.map(blue.map(b)), a);
 
 		color.to_linear_srgb()
 	});
 	image
 }
 
+/// The fraction of pixels at each end of a channel's histogram which auto levels clips to black or white, so a few outliers don't prevent stretching the rest.
+#[cfg(feature = "std")]
+pub const AUTO_LEVELS_CLIPPING: f64 = 0.001;
+
+/// The number of bins in the histograms used by auto levels, which is finer than 8-bit to keep the precision of float images.
+#[cfg(feature = "std")]
+const AUTO_LEVELS_HISTOGRAM_BINS: usize = 4096;
+
+/// Finds the input black and white points which stretch each of the red, green, and blue channels of the image to the full range,
+/// ignoring the darkest and lightest [`AUTO_LEVELS_CLIPPING`] of its visible pixels.
+/// Returns the `(black, white)` points of each channel as gamma-encoded values in the 0-1 range, or `None` if the image has no visible pixels.
+#[cfg(feature = "std")]
+pub fn auto_levels(image: &RasterDataTable<CPU>) -> Option<[(f64, f64); 3]> {
+	let mut histograms = [[0_u64; AUTO_LEVELS_HISTOGRAM_BINS]; 3];
+	let mut total = 0;
+	for instance in image.instance_ref_iter() {
+		for color in instance.instance.data().data.iter().filter(|color| color.a() > 0.) {
+			let (r, g, b, _) = color.to_gamma_srgb().components();
+			for (histogram, value) in histograms.iter_mut().zip([r, g, b]) {
+				let bin = (value.clamp(0., 1.) * (AUTO_LEVELS_HISTOGRAM_BINS - 1) as f32).round() as usize;
+				histogram[bin] += 1;
+			}
+			total += 1;
+		}
+	}
+	if total == 0 {
+		return None;
+	}
+
+	// The position of the first bin, counting from one end of a histogram, by which more than the clipped number of pixels have been passed
+	fn unclipped_position<'a>(mut counts: impl Iterator<Item = &'a u64>, clipped: u64) -> Option<usize> {
+		let mut passed = 0;
+		counts.position(|&count| {
+			passed += count;
+			passed > clipped
+		})
+	}
+	let clipped = (total as f64 * AUTO_LEVELS_CLIPPING) as u64;
+	let bin_value = |bin: usize| bin as f64 / (AUTO_LEVELS_HISTOGRAM_BINS - 1) as f64;
+
+	Some(core::array::from_fn(|channel| {
+		let histogram = &histograms[channel];
+		let black = bin_value(unclipped_position(histogram.iter(), clipped).unwrap_or(0));
+		let white = bin_value(AUTO_LEVELS_HISTOGRAM_BINS - 1 - unclipped_position(histogram.iter().rev(), clipped).unwrap_or(0));
+
+		// A channel of a single value can't be stretched, so it's left unchanged
+		if white > black { (black, white) } else { (0., 1.) }
+	}))
+}
+
 // Aims for interoperable compatibility with:
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=%27blwh%27%20%3D%20Black%20and%20White
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=Black%20White%20(Photoshop%20CS3)
@@ -413,38 +552,6 @@ async fn invert<T: Adjust<Color>>(
 	input
 }
 
-// Aims for interoperable compatibility with:
-// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=post%27%20%3D%20Posterize-,%27thrs%27%20%3D%20Threshold,-%27grdm%27%20%3D%20Gradient
-#[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
-async fn threshold<T: Adjust<Color>>(
-	_: impl Ctx,
-	#[implementations(
-		Color,
-		RasterDataTable<CPU>,
-		GradientStops,
-	)]
-	mut image: T,
-	#[default(50.)] min_luminance: Percentage,
-	#[default(100.)] max_luminance: Percentage,
-	luminance_calc: LuminanceCalculation,
-) -> T {
-	image.adjust(|color| {
-		let min_luminance = Color::srgb_to_linear(min_luminance as f32 / 100.);
-		let max_luminance = Color::srgb_to_linear(max_luminance as f32 / 100.);
-
-		let luminance = match luminance_calc {
-			LuminanceCalculation::SRGB => color.luminance_srgb(),
-			LuminanceCalculation::Perceptual => color.luminance_perceptual(),
-			LuminanceCalculation::AverageChannels => color.average_rgb_channels(),
-			LuminanceCalculation::MinimumChannels => color.minimum_rgb_channels(),
-			LuminanceCalculation::MaximumChannels => color.maximum_rgb_channels(),
-		};
-
-		if luminance >= min_luminance && luminance <= max_luminance { Color::WHITE } else { Color::BLACK }
-	});
-	image
-}
-
 // Aims for interoperable compatibility with:
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=%27-,vibA%27%20%3D%20Vibrance,-%27hue%20%27%20%3D%20Old
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=Vibrance%20(Photoshop%20CS3)
@@ -456,10 +563,8 @@ async fn threshold<T: Adjust<Color>>(
 // Some further analysis available at:
 // https://www.photo-mark.com/notes/analyzing-photoshop-vibrance-and-saturation/
 //
-// This algorithm is currently lacking a "Saturation" parameter which is needed for interoperability.
-// It's not the same as the saturation component of Hue/Saturation/Value. Vibrance and Saturation are both separable.
+// The "Saturation" parameter isn't the same as the saturation component of Hue/Saturation/Value. Vibrance and Saturation are both separable.
 // When both parameters are set, it is equivalent to running this adjustment twice, with only vibrance set and then only saturation set.
-// (Except for some noise probably due to rounding error.)
 #[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
 async fn vibrance<T: Adjust<Color>>(
 	_: impl Ctx,
@@ -469,7 +574,10 @@ async fn vibrance<T: Adjust<Color>>(
 		GradientStops,
 	)]
 	mut image: T,
+	/// Increases the saturation of muted colors more than already saturated colors, and of skin tones less than other colors.
 	vibrance: SignedPercentage,
+	/// Increases the saturation of every color by the same amount, up to the most that keeps its channels within range.
+	saturation: SignedPercentage,
 ) -> T {
 	image.adjust(|color| {
 		let vibrance = vibrance as f32 / 100.;
@@ -488,14 +596,14 @@ async fn vibrance<T: Adjust<Color>>(
 		} else {
 			1.
 		};
-		let scale = slowed_vibrance * scale_multiplier * (2. - channel_difference);
+		let scale = slowed_vibrance * scale_multiplier * skin_tone_protection(color) * (2. - channel_difference);
 		let channel_reduction = channel_min * scale;
 		let scale = 1. + scale * (1. - channel_difference);
 
 		let luminance_initial = color.to_linear_srgb().luminance_srgb();
 		let altered_color = color.map_rgb(|c| c * scale - channel_reduction).to_linear_srgb();
 		let luminance = altered_color.luminance_srgb();
-		let altered_color = altered_color.map_rgb(|c| c * luminance_initial / luminance);
+		let altered_color = altered_color.map_rgb(|c| c * luminance_initial / luminance.max(f32::EPSILON));
 
 		let channel_max = altered_color.r().max(altered_color.g()).max(altered_color.b());
 		let altered_color = if Color::linear_to_srgb(channel_max) > 1. {
@@ -506,7 +614,7 @@ async fn vibrance<T: Adjust<Color>>(
 		};
 		let altered_color = altered_color.to_gamma_srgb();
 
-		if vibrance >= 0. {
+		let altered_color = if vibrance >= 0. {
 			altered_color
 		} else {
 			// TODO: The result ends up a bit darker than it should be, further investigation is needed
@@ -516,11 +624,45 @@ async fn vibrance<T: Adjust<Color>>(
 			// Near -100% vibrance, we mostly use half the desaturated luminance color and half `altered_color`.
 			let factor = -slowed_vibrance;
 			altered_color.map_rgb(|c| c * (1. - factor) + luminance * factor)
-		}
+		};
+
+		saturate(&altered_color, saturation as f32 / 100.)
 	});
 	image
 }
 
+/// The hue of typical skin tones, as a fraction of the way around the color wheel from red.
+const SKIN_TONE_HUE: f32 = 25. / 360.;
+
+/// How far the hue can be from that of skin tones while still being partly protected from vibrance, as a fraction of the way around the color wheel.
+const SKIN_TONE_HUE_RANGE: f32 = 25. / 360.;
+
+/// The largest reduction of the effect of vibrance on skin tones.
+const SKIN_TONE_PROTECTION: f32 = 0.5;
+
+/// The factor which scales the effect of vibrance on a color, which is smallest for skin tones and smoothly tapers off to 1 as the hue moves away from theirs.
+/// Grays have no hue, which makes it NaN, so they're left unprotected, although vibrance doesn't affect them anyway.
+fn skin_tone_protection(color: &Color) -> f32 {
+	let [hue, ..] = color.to_hsla();
+	let closeness = (1. - (hue - SKIN_TONE_HUE).abs() / SKIN_TONE_HUE_RANGE).max(0.);
+	let skin_tone = closeness * closeness * (3. - 2. * closeness);
+	1. - SKIN_TONE_PROTECTION * skin_tone
+}
+
+/// Moves each channel away from the color's luminance by the given fraction of its distance, or towards it for negative amounts.
+/// Increases are limited to what keeps the channels within the 0 to 1 range, so fully saturated colors keep their hue.
+fn saturate(color: &Color, amount: f32) -> Color {
+	let luminance = color.luminance_rec_601();
+	let channel_max = color.r().max(color.g()).max(color.b());
+	let channel_min = color.r().min(color.g()).min(color.b());
+
+	// The largest factor which keeps the channels in range, which is infinite for grays since they have no distance from their luminance
+	let gamut_limit = ((1. - luminance) / (channel_max - luminance)).min(luminance / (luminance - channel_min));
+	let factor = (1. + amount).min(gamut_limit.max(1.));
+
+	color.map_rgb(|channel| luminance + (channel - luminance) * factor)
+}
+
 /// Color Channel
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
 #[cfg_attr(feature = "std", derive(dyn_any::DynAny, specta::Type, serde::Serialize, serde::Deserialize))]
@@ -753,6 +895,7 @@ pub enum SelectiveColorChoice {
 //
 // Algorithm based on:
 // https://blog.pkh.me/p/22-understanding-selective-coloring-in-adobe-photoshop.html
+// which is also implemented by FFmpeg's `selectivecolor` filter.
 #[node_macro::node(category("Raster: Adjustment"), properties("selective_color_properties"), shader_node(PerPixelAdjust))]
 async fn selective_color<T: Adjust<Color>>(
 	_: impl Ctx,
@@ -824,6 +967,7 @@ async fn selective_color<T: Adjust<Color>>(
 		let max_channel = max(r, g, b);
 		let min_channel = min(r, g, b);
 
+		// Whether this pixel's color is in the range affected by a color parameter group
 		let pixel_color_range = |choice| match choice {
 			SelectiveColorChoice::Reds => max_channel == r,
 			SelectiveColorChoice::Yellows => min_channel == b,
@@ -832,17 +976,29 @@ async fn selective_color<T: Adjust<Color>>(
 			SelectiveColorChoice::Blues => max_channel == b,
 			SelectiveColorChoice::Magentas => min_channel == g,
 			SelectiveColorChoice::Whites => r > 0.5 && g > 0.5 && b > 0.5,
-			SelectiveColorChoice::Neutrals => r > 0. && g > 0. && b > 0. && r < 1. && g < 1. && b < 1.,
+			// Everything except pure black and pure white, including colors with some (but not all) channels at either extreme
+			SelectiveColorChoice::Neutrals => max_channel > 0. && min_channel < 1.,
 			SelectiveColorChoice::Blacks => r < 0.5 && g < 0.5 && b < 0.5,
 		};
 
-		let color_parameter_group_scale_factor_rgb = max(r, g, b) - med(r, g, b);
-		let color_parameter_group_scale_factor_cmy = med(r, g, b) - min(r, g, b);
+		// How strongly this pixel's color is affected by a color parameter group, from 0 to 1
+		let color_parameter_group_scale_factor = |choice| match choice {
+			SelectiveColorChoice::Reds | SelectiveColorChoice::Greens | SelectiveColorChoice::Blues => max_channel - med(r, g, b),
+			SelectiveColorChoice::Cyans | SelectiveColorChoice::Magentas | SelectiveColorChoice::Yellows => med(r, g, b) - min_channel,
+			SelectiveColorChoice::Whites => min_channel * 2. - 1.,
+			SelectiveColorChoice::Neutrals => 1. - ((max_channel - 0.5).abs() + (min_channel - 0.5).abs()),
+			SelectiveColorChoice::Blacks => 1. - max_channel * 2.,
+		};
 
-		// Used to apply the r, g, or b channel slope (by multiplying it by 1) in relative mode, or no slope (by multiplying it by 0) in absolute mode
-		let (slope_r, slope_g, slope_b) = match mode {
-			RelativeAbsolute::Relative => (r - 1., g - 1., b - 1.),
-			RelativeAbsolute::Absolute => (-1., -1., -1.),
+		// The offset to a channel from its CMY parameter and the black parameter, which in relative mode is proportional to the channel's distance from 1.
+		// The offset is limited to keep the channel within range before it's scaled by how strongly this pixel is affected.
+		let channel_offset = |channel: f32, parameter: f32, black: f32, scale_factor: f32| {
+			let offset = (-1. - parameter) * black - parameter;
+			let offset = match mode {
+				RelativeAbsolute::Relative => offset * (1. - channel),
+				RelativeAbsolute::Absolute => offset,
+			};
+			offset.clamp(-channel, 1. - channel) * scale_factor
 		};
 
 		let (sum_r, sum_g, sum_b) = [
@@ -861,23 +1017,20 @@ async fn selective_color<T: Adjust<Color>>(
 			// Skip this color parameter group...
 			// ...if it's unchanged from the default of zero offset on all CMYK parameters, or...
 			// ...if this pixel's color isn't in the range affected by this color parameter group
-			if (c < f32::EPSILON && m < f32::EPSILON && y < f32::EPSILON && k < f32::EPSILON) || (!pixel_color_range(color_parameter_group)) {
+			if (c.abs() < f32::EPSILON && m.abs() < f32::EPSILON && y.abs() < f32::EPSILON && k.abs() < f32::EPSILON) || !pixel_color_range(color_parameter_group) {
 				return acc;
 			}
 
-			let (c, m, y, k) = (c / 100., m / 100., y / 100., k / 100.);
+			let scale_factor = color_parameter_group_scale_factor(color_parameter_group);
+			if scale_factor <= 0. {
+				return acc;
+			}
 
-			let color_parameter_group_scale_factor = match color_parameter_group {
-				SelectiveColorChoice::Reds | SelectiveColorChoice::Greens | SelectiveColorChoice::Blues => color_parameter_group_scale_factor_rgb,
-				SelectiveColorChoice::Cyans | SelectiveColorChoice::Magentas | SelectiveColorChoice::Yellows => color_parameter_group_scale_factor_cmy,
-				SelectiveColorChoice::Whites => min(r, g, b) * 2. - 1.,
-				SelectiveColorChoice::Neutrals => 1. - ((max(r, g, b) - 0.5).abs() + (min(r, g, b) - 0.5).abs()),
-				SelectiveColorChoice::Blacks => 1. - max(r, g, b) * 2.,
-			};
+			let (c, m, y, k) = (c / 100., m / 100., y / 100., k / 100.);
 
-			let offset_r = ((c + k * (c + 1.)) * slope_r).clamp(-r, -r + 1.) * color_parameter_group_scale_factor;
-			let offset_g = ((m + k * (m + 1.)) * slope_g).clamp(-g, -g + 1.) * color_parameter_group_scale_factor;
-			let offset_b = ((y + k * (y + 1.)) * slope_b).clamp(-b, -b + 1.) * color_parameter_group_scale_factor;
+			let offset_r = channel_offset(r, c, k, scale_factor);
+			let offset_g = channel_offset(g, m, k, scale_factor);
+			let offset_b = channel_offset(b, y, k, scale_factor);
 
 			(acc.0 + offset_r, acc.1 + offset_g, acc.2 + offset_b)
 		});
@@ -889,12 +1042,24 @@ async fn selective_color<T: Adjust<Color>>(
 	image
 }
 
+/// The encoding of the color channels which an adjustment divides into evenly spaced steps.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, node_macro::ChoiceType)]
+#[cfg_attr(feature = "std", derive(dyn_any::DynAny, specta::Type, serde::Serialize, serde::Deserialize))]
+#[widget(Radio)]
+pub enum WorkingSpace {
+	/// Gamma-encoded sRGB, where evenly spaced steps look evenly spaced in brightness, as in other image editors.
+	#[default]
+	Gamma,
+	/// Linear light sRGB, where evenly spaced steps are evenly spaced in the amount of light, so more of them fall in the highlights.
+	Linear,
+}
+
 // Aims for interoperable compatibility with:
 // https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=nvrt%27%20%3D%20Invert-,%27post%27%20%3D%20Posterize,-%27thrs%27%20%3D%20Threshold
 //
 // Algorithm based on:
 // https://www.axiomx.com/posterize.htm
-// This algorithm produces fully accurate output in relation to the industry standard.
+// This algorithm produces fully accurate output in relation to the industry standard when working in gamma space on all channels.
 #[node_macro::node(category("Raster: Adjustment"), shader_node(PerPixelAdjust))]
 async fn posterize<T: Adjust<Color>>(
 	_: impl Ctx,
@@ -904,20 +1069,32 @@ async fn posterize<T: Adjust<Color>>(
 		GradientStops,
 	)]
 	mut input: T,
+	/// The number of evenly spaced values each channel is reduced to.
 	#[default(4)]
 	#[hard_min(2.)]
+	#[hard_max(255.)]
 	levels: u32,
+	/// Whether the steps are evenly spaced in gamma-encoded or linear light values.
+	working_space: WorkingSpace,
+	/// Posterize only the luminance, scaling every channel by the same amount so the hue and saturation are kept.
+	luminance_only: bool,
 ) -> T {
 	input.adjust(|color| {
-		let color = color.to_gamma_srgb();
+		let linear = working_space == WorkingSpace::Linear;
+		let color = if linear { *color } else { color.to_gamma_srgb() };
 
 		let levels = levels as f32;
-		let number_of_areas = levels.recip();
-		let size_of_areas = (levels - 1.).recip();
-		let channel = |channel: f32| (channel / number_of_areas).floor() * size_of_areas;
-		let color = color.map_rgb(channel);
+		let quantize = |channel: f32| (channel * levels).floor().clamp(0., levels - 1.) / (levels - 1.);
 
-		color.to_linear_srgb()
+		let luminance = color.luminance_srgb();
+		let luminance_scale = quantize(luminance) / luminance.max(f32::EPSILON);
+		let color = if luminance_only {
+			color.map_rgb(|channel| (channel * luminance_scale).min(1.))
+		} else {
+			color.map_rgb(quantize)
+		};
+
+		if linear { color } else { color.to_linear_srgb() }
 	});
 	input
 }
@@ -957,3 +1134,401 @@ async fn exposure<T: Adjust<Color>>(
 	});
 	input
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use glam::{DAffine2, DVec2};
+	use graphene_core::instances::Instance;
+	use graphene_core::raster::image::Image;
+	use graphene_core::raster_types::Raster;
+
+	const UNCHANGED: [f64; 5] = [0., 100., 1., 0., 100.];
+
+	/// Runs the Levels node on a gamma-encoded color with the master, red, green, and blue parameters, returning the gamma-encoded result.
+	fn apply_levels(color: [f32; 4], master: [f64; 5], red: [f64; 5], green: [f64; 5], blue: [f64; 5]) -> [f32; 4] {
+		let [r, g, b, a] = color;
+		let color = Color::from_rgbaf32_unchecked(r, g, b, a).to_linear_srgb();
+
+		let [input_black, input_white, gamma, output_black, output_white] = master;
+		let [r0, r1, r2, r3, r4] = red;
+		let [g0, g1, g2, g3, g4] = green;
+		let [b0, b1, b2, b3, b4] = blue;
+		let result = levels(
+			(),
+			color,
+			input_black,
+			input_white,
+			gamma,
+			output_black,
+			output_white,
+			r0,
+			r1,
+			r2,
+			r3,
+			r4,
+			g0,
+			g1,
+			g2,
+			g3,
+			g4,
+			b0,
+			b1,
+			b2,
+			b3,
+			b4,
+			LevelsChannel::Master,
+		);
+
+		let (r, g, b, a) = result.to_gamma_srgb().components();
+		[r, g, b, a]
+	}
+
+	fn assert_close(actual: [f32; 4], expected: [f32; 4]) {
+		for (actual, expected) in actual.into_iter().zip(expected) {
+			assert!((actual - expected).abs() < 1e-5, "{actual} should be close to {expected}");
+		}
+	}
+
+	#[test]
+	fn master_levels_match_golden_values() {
+		// Input range 20-80%, gamma 2, output range 10-90%
+		let master = [20., 80., 2., 10., 90.];
+
+		// Clipped below the input black point, halfway through the input range with the gamma applied, and clipped above the input white point
+		let result = apply_levels([0.1, 0.5, 0.95, 1.], master, UNCHANGED, UNCHANGED, UNCHANGED);
+		assert_close(result, [0.1, 0.1 + 0.8 * 0.5_f32.sqrt(), 0.9, 1.]);
+	}
+
+	#[test]
+	fn channel_levels_apply_before_master_levels() {
+		// The red input range is 0-50%, the blue gamma is 0.5, and the master output range is 0-50%
+		let red = [0., 50., 1., 0., 100.];
+		let blue = [0., 100., 0.5, 0., 100.];
+		let master = [0., 100., 1., 0., 50.];
+
+		let result = apply_levels([0.25, 0.5, 0.5, 1.], UNCHANGED, red, UNCHANGED, UNCHANGED);
+		assert_close(result, [0.5, 0.5, 0.5, 1.]);
+
+		let result = apply_levels([0.25, 0.5, 0.5, 1.], master, red, UNCHANGED, blue);
+		assert_close(result, [0.25, 0.25, 0.125, 1.]);
+	}
+
+	#[test]
+	fn auto_levels_clip_outliers_per_channel() {
+		// One outlier at each end of the red channel, which otherwise spans 25-75%, next to a constant green channel and a blue channel spanning the full range
+		let mut data: Vec<_> = (0..1000)
+			.map(|index| {
+				let red = match index {
+					0 => 0.,
+					999 => 1.,
+					_ => 0.25 + 0.5 * (index - 1) as f32 / 997.,
+				};
+				let blue = index as f32 / 999.;
+				Color::from_rgbaf32_unchecked(red, 0.5, blue, 1.).to_linear_srgb()
+			})
+			.collect();
+		// Transparent pixels are ignored, or they would count as black
+		data.extend([Color::TRANSPARENT; 10]);
+		let image = Image {
+			width: data.len() as u32,
+			height: 1,
+			data,
+			base64_string: None,
+		};
+
+		let [red, green, blue] = auto_levels(&RasterDataTable::new(Raster::new_cpu(image))).unwrap();
+		let bin = 1. / (AUTO_LEVELS_HISTOGRAM_BINS - 1) as f64;
+		assert!((red.0 - 0.25).abs() <= bin && (red.1 - 0.75).abs() <= bin, "{red:?}");
+		assert_eq!(green, (0., 1.));
+		assert!((blue.0 - 1. / 999.).abs() <= bin && (blue.1 - 998. / 999.).abs() <= bin, "{blue:?}");
+
+		assert_eq!(auto_levels(&RasterDataTable::new(Raster::new_cpu(Image::new(4, 4, Color::TRANSPARENT)))), None);
+	}
+
+	#[test]
+	fn sixteen_bit_source_bands_less_than_eight_bit() {
+		use image::{ImageBuffer, ImageFormat, Rgba};
+
+		// A smooth 16-bit ramp through the shadows, which are the first to band when darkened and brightened again
+		let values: Vec<u16> = (0..1024).map(|x| x * 16).collect();
+		let source = ImageBuffer::from_fn(values.len() as u32, 1, |x, _| Rgba([values[x as usize], values[x as usize], values[x as usize], u16::MAX]));
+		let mut png = std::io::Cursor::new(Vec::new());
+		source.write_to(&mut png, ImageFormat::Png).unwrap();
+		let image = RasterDataTable::new(Raster::new_cpu(Image::from_encoded_image(png.get_ref()).unwrap()));
+
+		// The 8-bit path quantizes the image after loading it and after every adjustment
+		let quantize = |image: RasterDataTable<CPU>| {
+			let image = image.instance_ref_iter().next().unwrap().instance.data().clone();
+			let (data, width, height) = image.to_flat_u8();
+			RasterDataTable::new(Raster::new_cpu(Image::from_image_data(&data, width, height)))
+		};
+		let darken_and_brighten = |mut image: RasterDataTable<CPU>, eight_bit: bool| {
+			for _ in 0..3 {
+				for stops in [-3., 3.] {
+					image = futures::executor::block_on(exposure((), image, stops, 0., 1.));
+					if eight_bit {
+						image = quantize(image);
+					}
+				}
+			}
+			image.instance_ref_iter().next().unwrap().instance.data().data.clone()
+		};
+		let float_result = darken_and_brighten(image.clone(), false);
+		let eight_bit_result = darken_and_brighten(quantize(image), true);
+
+		// The levels are counted at the 16-bit precision of the source, as they would be quantized on export
+		let levels = |data: &[Color]| {
+			data.iter()
+				.map(|color| (color.to_gamma_srgb().r() * u16::MAX as f32).round() as u16)
+				.collect::<std::collections::HashSet<_>>()
+				.len()
+		};
+		assert_eq!(levels(&float_result), values.len());
+		assert!(levels(&eight_bit_result) * 10 < levels(&float_result), "{} 8-bit levels", levels(&eight_bit_result));
+
+		// Without intermediate quantization, the float path round-trips to the source
+		for (color, value) in float_result.iter().zip(values) {
+			assert!((color.to_gamma_srgb().r() - value as f32 / u16::MAX as f32).abs() < 1e-5);
+		}
+	}
+
+	/// The reference pixels, gamma-encoded: a neutral gray, a fully saturated red, and a skin tone.
+	const GRAY: [f32; 3] = [0.5, 0.5, 0.5];
+	const RED: [f32; 3] = [1., 0., 0.];
+	const SKIN: [f32; 3] = [0.87, 0.67, 0.55];
+
+	/// Runs an adjustment on an opaque gamma-encoded color, returning the gamma-encoded result.
+	fn adjust_gamma<F: std::future::Future<Output = Color>>([r, g, b]: [f32; 3], adjustment: impl FnOnce(Color) -> F) -> [f32; 3] {
+		let color = Color::from_rgbaf32_unchecked(r, g, b, 1.).to_linear_srgb();
+		let (r, g, b, _) = futures::executor::block_on(adjustment(color)).to_gamma_srgb().components();
+		[r, g, b]
+	}
+
+	fn assert_rgb_close(actual: [f32; 3], expected: [f32; 3]) {
+		for (actual, expected) in actual.into_iter().zip(expected) {
+			assert!((actual - expected).abs() < 1e-3, "{actual} should be close to {expected}");
+		}
+	}
+
+	#[test]
+	fn posterize_golden_values() {
+		let posterized = |color, working_space, luminance_only| adjust_gamma(color, |color| posterize((), color, 4, working_space, luminance_only));
+
+		assert_rgb_close(posterized(GRAY, WorkingSpace::Gamma, false), [2. / 3.; 3]);
+		assert_rgb_close(posterized(RED, WorkingSpace::Gamma, false), RED);
+		assert_rgb_close(posterized(SKIN, WorkingSpace::Gamma, false), [1., 2. / 3., 2. / 3.]);
+
+		// Mid gray is only about a fifth of the light of white, so it falls in the darkest of the steps spaced evenly in linear light
+		assert_rgb_close(posterized(GRAY, WorkingSpace::Linear, false), [0.; 3]);
+		assert_rgb_close(posterized(RED, WorkingSpace::Linear, false), RED);
+		assert_rgb_close(posterized(SKIN, WorkingSpace::Linear, false), [0.8360, 0.6125, 0.6125]);
+
+		// Posterizing only the luminance keeps the hue and saturation of the skin tone, while the red is too dark to reach the second step
+		assert_rgb_close(posterized(GRAY, WorkingSpace::Gamma, true), [2. / 3.; 3]);
+		assert_rgb_close(posterized(RED, WorkingSpace::Gamma, true), [0.; 3]);
+		assert_rgb_close(posterized(SKIN, WorkingSpace::Gamma, true), [0.8240, 0.6346, 0.5209]);
+	}
+
+	#[test]
+	fn vibrance_golden_values() {
+		let vibrant = |color, vibrance_amount, saturation_amount| adjust_gamma(color, |color| vibrance((), color, vibrance_amount, saturation_amount));
+
+		// Neutral gray has no saturation to change
+		assert_rgb_close(vibrant(GRAY, 50., 0.), GRAY);
+		assert_rgb_close(vibrant(GRAY, 0., 50.), GRAY);
+
+		// Fully saturated red is left alone by increases, which are limited to keep it within range, but can still be desaturated
+		assert_rgb_close(vibrant(RED, 50., 0.), RED);
+		assert_rgb_close(vibrant(RED, 0., 50.), RED);
+		assert_rgb_close(vibrant(RED, 0., -50.), [0.8264, 0.4229, 0.4229]);
+
+		assert_rgb_close(vibrant(SKIN, 50., 0.), [0.8856, 0.6568, 0.5115]);
+		assert_rgb_close(vibrant(SKIN, 0., 50.), [0.9313, 0.6393, 0.4257]);
+		assert_rgb_close(vibrant(SKIN, 50., 50.), [0.9539, 0.6205, 0.3436]);
+	}
+
+	#[test]
+	fn vibrance_protects_skin_tones() {
+		// A muted blue with the same channels as the skin tone in reverse, so both start out equally saturated
+		let muted_blue = [SKIN[2], SKIN[1], SKIN[0]];
+		let spread = |[r, g, b]: [f32; 3]| r.max(g).max(b) - r.min(g).min(b);
+
+		let skin_increase = spread(adjust_gamma(SKIN, |color| vibrance((), color, 50., 0.))) - spread(SKIN);
+		let blue_increase = spread(adjust_gamma(muted_blue, |color| vibrance((), color, 50., 0.))) - spread(muted_blue);
+		assert!(skin_increase > 0. && skin_increase < blue_increase / 2., "{skin_increase} and {blue_increase}");
+	}
+
+	/// Runs Selective Color with only the CMYK parameters of a single range set, in percent.
+	fn selectively_colored(color: [f32; 3], mode: RelativeAbsolute, range: SelectiveColorChoice, cmyk: [f64; 4]) -> [f32; 3] {
+		let mut p = [0.; 36];
+		p[range as usize * 4..][..4].copy_from_slice(&cmyk);
+
+		adjust_gamma(color, |color| {
+			selective_color(
+				(),
+				color,
+				mode,
+				p[0],
+				p[1],
+				p[2],
+				p[3],
+				p[4],
+				p[5],
+				p[6],
+				p[7],
+				p[8],
+				p[9],
+				p[10],
+				p[11],
+				p[12],
+				p[13],
+				p[14],
+				p[15],
+				p[16],
+				p[17],
+				p[18],
+				p[19],
+				p[20],
+				p[21],
+				p[22],
+				p[23],
+				p[24],
+				p[25],
+				p[26],
+				p[27],
+				p[28],
+				p[29],
+				p[30],
+				p[31],
+				p[32],
+				p[33],
+				p[34],
+				p[35],
+				range,
+			)
+		})
+	}
+
+	#[test]
+	fn selective_color_golden_values() {
+		use RelativeAbsolute::{Absolute, Relative};
+		use SelectiveColorChoice::*;
+
+		// Reference values computed with the algorithm of FFmpeg's `selectivecolor` filter, which was reverse engineered from Photoshop
+		let table: [([f32; 3], RelativeAbsolute, SelectiveColorChoice, [f64; 4], [f32; 3]); 15] = [
+			// Negative parameters remove the complementary ink, brightening the channel
+			([0.8, 0.2, 0.2], Relative, Reds, [-50., 0., 0., 0.], [0.86, 0.2, 0.2]),
+			([0.8, 0.2, 0.2], Absolute, Reds, [-50., 0., 0., 0.], [0.92, 0.2, 0.2]),
+			(SKIN, Relative, Reds, [-50., 20., 0., 0.], [0.883, 0.6568, 0.55]),
+			(SKIN, Absolute, Reds, [-50., 20., 0., 0.], [0.896, 0.63, 0.55]),
+			(SKIN, Relative, Reds, [0., 0., 0., 30.], [0.8622, 0.6502, 0.523]),
+			(SKIN, Absolute, Reds, [0., 0., 0., 30.], [0.81, 0.61, 0.49]),
+			([0.2, 0.6, 0.9], Relative, Cyans, [0., 30., -20., 10.], [0.168, 0.5312, 0.9048]),
+			([0.2, 0.6, 0.9], Absolute, Cyans, [0., 30., -20., 10.], [0.16, 0.428, 0.94]),
+			// The tonal ranges
+			([0.9, 0.8, 0.6], Relative, Whites, [0., 0., 20., -10.], [0.902, 0.804, 0.5936]),
+			(GRAY, Relative, Neutrals, [10., -10., 0., 0.], [0.45, 0.55, 0.5]),
+			(GRAY, Absolute, Neutrals, [10., -10., 0., 0.], [0.4, 0.6, 0.5]),
+			([0.6, 0.3, 0.], Absolute, Neutrals, [0., 0., 0., 30.], [0.48, 0.18, 0.]),
+			([0.1, 0.2, 0.4], Relative, Blacks, [-40., 0., 0., 0.], [0.172, 0.2, 0.4]),
+			([0.1, 0.2, 0.4], Absolute, Blacks, [-40., 0., 0., 0.], [0.18, 0.2, 0.4]),
+			// Pure white isn't a neutral
+			([1., 1., 1.], Absolute, Neutrals, [0., 0., 0., 30.], [1., 1., 1.]),
+		];
+
+		for (color, mode, range, cmyk, expected) in table {
+			assert_rgb_close(selectively_colored(color, mode, range, cmyk), expected);
+		}
+	}
+
+	#[test]
+	fn selective_color_leaves_colors_outside_the_range() {
+		// Red is the largest channel of the skin tone, so it isn't among the blues or the blacks
+		assert_rgb_close(selectively_colored(SKIN, RelativeAbsolute::Absolute, SelectiveColorChoice::Blues, [50., 50., 50., 50.]), SKIN);
+		assert_rgb_close(selectively_colored(SKIN, RelativeAbsolute::Absolute, SelectiveColorChoice::Blacks, [50., 50., 50., 50.]), SKIN);
+	}
+
+	/// A 3×2 image with a different premultiplied color in every pixel, some of them translucent, placed away from the origin.
+	fn channels_image() -> RasterDataTable<CPU> {
+		let data = vec![
+			Color::from_rgbaf32_unchecked(1., 0., 0., 1.),
+			Color::from_rgbaf32_unchecked(0.2, 0.4, 0.6, 1.),
+			Color::from_rgbaf32_unchecked(0.1, 0.2, 0.3, 0.5),
+			Color::TRANSPARENT,
+			Color::from_rgbaf32_unchecked(0.05, 0.15, 0., 0.25),
+			Color::WHITE,
+		];
+		RasterDataTable::new_instance(Instance {
+			instance: Raster::new_cpu(Image {
+				width: 3,
+				height: 2,
+				data,
+				base64_string: None,
+			}),
+			transform: DAffine2::from_translation(DVec2::new(10., 20.)) * DAffine2::from_scale(DVec2::new(3., 2.)),
+			..Default::default()
+		})
+	}
+
+	fn split_channels(image: &RasterDataTable<CPU>) -> [RasterDataTable<CPU>; 4] {
+		[RedGreenBlueAlpha::Red, RedGreenBlueAlpha::Green, RedGreenBlueAlpha::Blue, RedGreenBlueAlpha::Alpha].map(|channel| extract_channel((), image.clone(), channel))
+	}
+
+	fn pixels(image: &RasterDataTable<CPU>) -> Vec<[f32; 4]> {
+		let image = image.instance_ref_iter().next().unwrap().instance.data();
+		image.data.iter().map(|color| [color.r(), color.g(), color.b(), color.a()]).collect()
+	}
+
+	fn assert_pixels_close(actual: &[[f32; 4]], expected: &[[f32; 4]]) {
+		assert_eq!(actual.len(), expected.len());
+		for (actual, expected) in actual.iter().zip(expected) {
+			assert!(actual.iter().zip(expected).all(|(a, b)| (a - b).abs() < 1e-6), "{actual:?} should be close to {expected:?}");
+		}
+	}
+
+	#[test]
+	fn split_and_combine_channels_round_trips() {
+		let image = channels_image();
+		let channels = split_channels(&image);
+
+		// The split channels are opaque grays, so they display as the value of their channel
+		for channel in &channels {
+			assert!(pixels(channel).iter().all(|[r, g, b, a]| r == g && g == b && *a == 1.));
+		}
+
+		let [red, green, blue, alpha] = channels;
+		let combined = crate::std_nodes::combine_channels((), (), red, green, blue, alpha);
+		assert_pixels_close(&pixels(&combined), &pixels(&image));
+		assert_eq!(combined.instance_ref_iter().next().unwrap().transform, image.instance_ref_iter().next().unwrap().transform);
+	}
+
+	#[test]
+	fn combine_channels_swaps_channels() {
+		let image = channels_image();
+		let [red, green, blue, alpha] = split_channels(&image);
+
+		let swapped = crate::std_nodes::combine_channels((), (), blue, green, red, alpha);
+		let expected: Vec<_> = pixels(&image).into_iter().map(|[r, g, b, a]| [b, g, r, a]).collect();
+		assert_pixels_close(&pixels(&swapped), &expected);
+	}
+
+	#[test]
+	fn combine_channels_pads_mismatched_sizes() {
+		let channel = |width: u32, height: u32, value: f32| {
+			RasterDataTable::new_instance(Instance {
+				instance: Raster::new_cpu(Image::new(width, height, Color::from_rgbaf32_unchecked(value, value, value, 1.))),
+				transform: DAffine2::from_scale(DVec2::new(width as f64, height as f64)),
+				..Default::default()
+			})
+		};
+
+		// A wide red channel and a tall green channel, with the blue and alpha channels missing
+		let combined = crate::std_nodes::combine_channels((), (), channel(2, 1, 0.5), channel(1, 2, 0.25), RasterDataTable::default(), RasterDataTable::default());
+		assert_pixels_close(&pixels(&combined), &[[0.5, 0.25, 0., 1.], [0.5, 0., 0., 1.], [0., 0.25, 0., 1.], [0.; 4]]);
+
+		// The result keeps the pixel size of the first channel while growing to cover both
+		let transform = combined.instance_ref_iter().next().unwrap().transform;
+		assert_eq!(transform.transform_point2(DVec2::ONE), DVec2::new(2., 2.));
+	}
+}
@@ -1,11 +1,125 @@
+use crate::adjust::Adjust;
+use bezier_rs::{Bezier, TValue};
 use dyn_any::{DynAny, StaticType, StaticTypeSized};
-use graphene_core::Node;
-use graphene_core::color::{Channel, Linear, LuminanceMut};
+use graphene_core::color::{Channel, Linear, LuminanceMut, SRGBA8};
+use graphene_core::gradient::GradientStops;
+use graphene_core::raster::image::Image;
+use graphene_core::raster_types::{CPU, RasterDataTable};
+use graphene_core::{Color, Ctx, Node};
 use std::hash::{Hash, Hasher};
 use std::ops::{Add, Mul, Sub};
 
-#[derive(Debug, Clone, PartialEq, DynAny, specta::Type, serde::Serialize, serde::Deserialize)]
+/// The curves of the Curves adjustment: a master curve for the red, green, and blue channels, applied after the optional curve of each individual channel.
+/// Curves map gamma-encoded values, and a channel without its own curve is left unchanged by it.
+#[derive(Debug, Clone, Default, PartialEq, Hash, DynAny, specta::Type, serde::Serialize)]
 pub struct Curve {
+	pub master: CurveSpline,
+	pub red: Option<CurveSpline>,
+	pub green: Option<CurveSpline>,
+	pub blue: Option<CurveSpline>,
+	pub alpha: Option<CurveSpline>,
+}
+
+impl<'de> serde::Deserialize<'de> for Curve {
+	fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+		#[derive(serde::Deserialize)]
+		struct Channels {
+			master: CurveSpline,
+			#[serde(default)]
+			red: Option<CurveSpline>,
+			#[serde(default)]
+			green: Option<CurveSpline>,
+			#[serde(default)]
+			blue: Option<CurveSpline>,
+			#[serde(default)]
+			alpha: Option<CurveSpline>,
+		}
+
+		#[derive(serde::Deserialize)]
+		#[serde(untagged)]
+		enum CurveVersions {
+			Channels(Channels),
+			// Before per-channel curves were added, a curve was only its master curve
+			Master(CurveSpline),
+		}
+
+		Ok(match CurveVersions::deserialize(deserializer)? {
+			CurveVersions::Channels(Channels { master, red, green, blue, alpha }) => Self { master, red, green, blue, alpha },
+			CurveVersions::Master(master) => Self { master, ..Default::default() },
+		})
+	}
+}
+
+impl Curve {
+	pub fn spline(&self, channel: CurveChannel) -> Option<&CurveSpline> {
+		match channel {
+			CurveChannel::Master => Some(&self.master),
+			CurveChannel::Red => self.red.as_ref(),
+			CurveChannel::Green => self.green.as_ref(),
+			CurveChannel::Blue => self.blue.as_ref(),
+			CurveChannel::Alpha => self.alpha.as_ref(),
+		}
+	}
+
+	pub fn set_spline(&mut self, channel: CurveChannel, spline: CurveSpline) {
+		match channel {
+			CurveChannel::Master => self.master = spline,
+			CurveChannel::Red => self.red = Some(spline),
+			CurveChannel::Green => self.green = Some(spline),
+			CurveChannel::Blue => self.blue = Some(spline),
+			CurveChannel::Alpha => self.alpha = Some(spline),
+		}
+	}
+
+	/// Maps the gamma-encoded channels through the curve of each channel and then the master curve, evaluating the curves directly.
+	pub fn evaluate(&self, [red, green, blue, alpha]: [f64; 4]) -> [f64; 4] {
+		let channel = |spline: &Option<CurveSpline>, value: f64| spline.as_ref().map_or(value, |spline| spline.evaluate(value));
+		let [red, green, blue] = [(&self.red, red), (&self.green, green), (&self.blue, blue)].map(|(spline, value)| self.master.evaluate(channel(spline, value)));
+		[red, green, blue, channel(&self.alpha, alpha)]
+	}
+
+	/// Samples the curves for every 8-bit value, so they can be applied to 8-bit pixels by looking up each channel.
+	pub fn lookup_tables(&self) -> CurveLookupTables {
+		let mut tables = [[0; 256]; 4];
+		for value in 0..=255 {
+			let mapped = self.evaluate([value as f64 / 255.; 4]);
+			for (table, mapped) in tables.iter_mut().zip(mapped) {
+				table[value] = (mapped.clamp(0., 1.) * 255.).round() as u8;
+			}
+		}
+		CurveLookupTables { tables }
+	}
+}
+
+/// The channel of a [`Curve`] which is shown and edited in the curve editor.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, specta::Type, serde::Serialize, serde::Deserialize)]
+pub enum CurveChannel {
+	#[default]
+	Master,
+	Red,
+	Green,
+	Blue,
+	Alpha,
+}
+
+impl CurveChannel {
+	pub const ALL: [Self; 5] = [Self::Master, Self::Red, Self::Green, Self::Blue, Self::Alpha];
+}
+
+/// The curves of each channel sampled for every 8-bit value, in red, green, blue, alpha order, with the master curve already applied to the color channels.
+pub struct CurveLookupTables {
+	tables: [[u8; 256]; 4],
+}
+
+impl CurveLookupTables {
+	pub fn map(&self, rgba: [u8; 4]) -> [u8; 4] {
+		std::array::from_fn(|index| self.tables[index][rgba[index] as usize])
+	}
+}
+
+/// A curve from (0, 0) to (1, 1) made of cubic Bézier segments joined at its manipulator groups, which maps the values of one channel.
+#[derive(Debug, Clone, PartialEq, DynAny, specta::Type, serde::Serialize, serde::Deserialize)]
+pub struct CurveSpline {
 	#[serde(rename = "manipulatorGroups")]
 	pub manipulator_groups: Vec<CurveManipulatorGroup>,
 	#[serde(rename = "firstHandle")]
@@ -14,7 +128,7 @@ pub struct Curve {
 	pub last_handle: [f32; 2],
 }
 
-impl Default for Curve {
+impl Default for CurveSpline {
 	fn default() -> Self {
 		Self {
 			manipulator_groups: vec![],
@@ -24,13 +138,71 @@ impl Default for Curve {
 	}
 }
 
-impl Hash for Curve {
+impl Hash for CurveSpline {
 	fn hash<H: Hasher>(&self, state: &mut H) {
 		self.manipulator_groups.hash(state);
 		[self.first_handle, self.last_handle].iter().flatten().for_each(|f| f.to_bits().hash(state));
 	}
 }
 
+impl CurveSpline {
+	/// The segments of the curve, each given with the x coordinates of its start and end.
+	fn segments(&self) -> impl Iterator<Item = ([f64; 2], Bezier)> + '_ {
+		let end = CurveManipulatorGroup {
+			anchor: [1.; 2],
+			handles: [self.last_handle, [0.; 2]],
+		};
+		let [mut pos, mut param]: [[f32; 2]; 2] = [[0.; 2], self.first_handle];
+
+		self.manipulator_groups.iter().copied().chain(std::iter::once(end)).map(move |sample| {
+			let [x0, y0, x1, y1, x2, y2, x3, y3] = [pos[0], pos[1], param[0], param[1], sample.handles[0][0], sample.handles[0][1], sample.anchor[0], sample.anchor[1]].map(f64::from);
+			pos = sample.anchor;
+			param = sample.handles[1];
+
+			([x0, x3], Bezier::from_cubic_coordinates(x0, y0, x1, y1, x2, y2, x3, y3))
+		})
+	}
+
+	fn evaluate_segment([x0, x3]: [f64; 2], bezier: &Bezier, x: f64) -> f64 {
+		let [y0, y3] = [bezier.start().y, bezier.end().y];
+		if x <= x0 {
+			y0
+		} else if x >= x3 {
+			y3
+		} else {
+			bezier
+				.find_tvalues_for_x(x)
+				.next()
+				.map(|t| bezier.evaluate(TValue::Parametric(t.clamp(0., 1.))).y)
+				// Fall back to a very bad approximation if Bezier-rs fails
+				.unwrap_or_else(|| (x - x0) / (x3 - x0) * (y3 - y0) + y0)
+		}
+	}
+
+	/// Maps a value through the curve. Values outside of 0 to 1 are left unchanged, continuing the curve's fixed endpoints.
+	pub fn evaluate(&self, x: f64) -> f64 {
+		if !(0. ..=1.).contains(&x) {
+			return x;
+		}
+
+		self.segments().find(|([_, x3], _)| x <= *x3).map_or(x, |(range, bezier)| Self::evaluate_segment(range, &bezier, x))
+	}
+
+	/// Samples the curve at evenly spaced values from 0 to 1.
+	pub fn sample(&self, count: usize) -> Vec<f64> {
+		let mut samples = vec![0.; count];
+		for (range, bezier) in self.segments() {
+			let [left, right] = range.map(|x| x.clamp(0., 1.));
+			let index_left = (left * (count - 1) as f64).floor() as usize;
+			let index_right = (right * (count - 1) as f64).ceil() as usize;
+			for (index, sample) in samples.iter_mut().enumerate().take(index_right + 1).skip(index_left) {
+				*sample = Self::evaluate_segment(range, &bezier, index as f64 / (count - 1) as f64);
+			}
+		}
+		samples
+	}
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, DynAny, specta::Type, serde::Serialize, serde::Deserialize)]
 pub struct CurveManipulatorGroup {
 	pub anchor: [f32; 2],
@@ -45,6 +217,67 @@ impl Hash for CurveManipulatorGroup {
 	}
 }
 
+/// Data whose pixels can be mapped through a [`Curve`]. Float pixels are evaluated directly and 8-bit pixels through [`CurveLookupTables`].
+pub trait ApplyCurves {
+	fn apply_curves(&mut self, curve: &Curve);
+}
+
+fn apply_curves_to_colors(colors: &mut impl Adjust<Color>, curve: &Curve) {
+	colors.adjust(|color| {
+		let color = color.to_gamma_srgb();
+		let [red, green, blue, alpha] = curve.evaluate([color.r(), color.g(), color.b(), color.a()].map(f64::from)).map(|channel| channel as f32);
+		Color::from_rgbaf32_unchecked(red, green, blue, alpha).to_linear_srgb()
+	});
+}
+
+impl ApplyCurves for Color {
+	fn apply_curves(&mut self, curve: &Curve) {
+		apply_curves_to_colors(self, curve);
+	}
+}
+
+impl ApplyCurves for RasterDataTable<CPU> {
+	fn apply_curves(&mut self, curve: &Curve) {
+		apply_curves_to_colors(self, curve);
+	}
+}
+
+impl ApplyCurves for GradientStops {
+	fn apply_curves(&mut self, curve: &Curve) {
+		apply_curves_to_colors(self, curve);
+	}
+}
+
+impl ApplyCurves for Image<SRGBA8> {
+	fn apply_curves(&mut self, curve: &Curve) {
+		let tables = curve.lookup_tables();
+		for pixel in self.data.iter_mut() {
+			*pixel = bytemuck::cast(tables.map(bytemuck::cast(*pixel)));
+		}
+	}
+}
+
+// Aims for interoperable compatibility with:
+// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=levl%27%20%3D%20Levels-,%27curv%27%20%3D%20Curves,-%27expA%27%20%3D%20Exposure
+// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=Max%20input%20range-,Curves,-Curves%20settings%20files
+//
+// Some further analysis available at:
+// https://geraldbakker.nl/psnumbers/curves.html
+#[node_macro::node(category("Raster: Adjustment"))]
+fn curves<T: ApplyCurves>(
+	_: impl Ctx,
+	#[implementations(
+		Color,
+		RasterDataTable<CPU>,
+		GradientStops,
+	)]
+	mut image: T,
+	curve: Curve,
+) -> T {
+	image.apply_curves(&curve);
+	image
+}
+
 pub struct ValueMapperNode<C> {
 	lut: Vec<C>,
 }
@@ -79,3 +312,58 @@ where
 		val
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	/// Raises the middle of the curve so 0.5 maps to 0.75, with a slope of 1 there.
+	fn raised_midtones() -> CurveSpline {
+		CurveSpline {
+			manipulator_groups: vec![CurveManipulatorGroup {
+				anchor: [0.5, 0.75],
+				handles: [[0.4, 0.65], [0.6, 0.85]],
+			}],
+			first_handle: [0.1, 0.3],
+			last_handle: [0.9, 0.95],
+		}
+	}
+
+	#[test]
+	fn red_curve_only_changes_red() {
+		let curve = Curve {
+			red: Some(raised_midtones()),
+			..Default::default()
+		};
+
+		// Float pixels are evaluated directly
+		let mut color = Color::from_rgbaf32_unchecked(0.5, 0.5, 0.2, 1.).to_linear_srgb();
+		color.apply_curves(&curve);
+		let color = color.to_gamma_srgb();
+		for (actual, expected) in [color.r(), color.g(), color.b(), color.a()].into_iter().zip([0.75, 0.5, 0.2, 1.]) {
+			assert!((actual - expected).abs() < 1e-4, "{actual} should be close to {expected}");
+		}
+
+		// 8-bit pixels are looked up, so 128 (slightly above 0.5) lands slightly above 0.75 * 255
+		let tables = curve.lookup_tables();
+		assert_eq!(tables.map([128, 128, 51, 255]), [192, 128, 51, 255]);
+		assert_eq!(tables.map([0, 0, 0, 0]), [0, 0, 0, 0]);
+		assert_eq!(tables.map([255, 255, 255, 255]), [255, 255, 255, 255]);
+	}
+
+	#[test]
+	fn master_curve_applies_after_channel_curves() {
+		let curve = Curve {
+			master: raised_midtones(),
+			red: Some(raised_midtones()),
+			..Default::default()
+		};
+		let master_only = raised_midtones().evaluate(0.5);
+		let [red, green, blue, alpha] = curve.evaluate([0.5; 4]);
+
+		assert!((red - raised_midtones().evaluate(0.75)).abs() < 1e-9);
+		assert!((green - master_only).abs() < 1e-9 && (blue - master_only).abs() < 1e-9);
+		// The master curve doesn't affect alpha
+		assert_eq!(alpha, 0.5);
+	}
+}
@@ -1,180 +1,657 @@
+use crate::tiles::TILE_SIZE;
+use dyn_any::DynAny;
+use graphene_core::cancellation::{self, CancellationToken};
 use graphene_core::color::Color;
-use graphene_core::context::Ctx;
 use graphene_core::raster::image::Image;
-use graphene_core::raster::{Bitmap, BitmapMut};
 use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
-use graphene_core::registry::types::PixelLength;
+use graphene_core::registry::types::{Angle, PixelLength};
+use graphene_core::{CloneVarArgs, Context, Ctx, ExtractAll, OwnedContextImpl};
+use rayon::prelude::*;
 
-/// Blurs the image with a Gaussian or blur kernel filter.
-#[node_macro::node(category("Raster: Filter"))]
+/// Blurs with a larger radius or distance than this, in pixels, are computed at a reduced resolution unless high quality is requested.
+const DOWNSAMPLE_RADIUS: f64 = 32.;
+/// The number of box blurs which the box mode repeats, which together closely approximate a Gaussian blur.
+const BOX_ITERATIONS: usize = 3;
+
+/// The shape of the kernel a blur spreads each pixel out with.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum BlurMode {
+	/// A smooth blur with a bell-shaped falloff.
+	#[default]
+	Gaussian,
+	/// A faster blur made of repeated box averages.
+	Box,
+	/// A blur along a straight line, like the streaks left by movement.
+	Motion,
+}
+
+/// How a blur samples beyond the edges of the image.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum BlurEdges {
+	/// Repeat the pixels along the edges outward.
+	#[default]
+	Clamp,
+	/// Continue from the opposite edge, as if the image were tiled.
+	Wrap,
+	/// Treat everything beyond the edges as transparent, fading the edges out.
+	Transparent,
+}
+
+impl BlurEdges {
+	/// The index of the pixel sampled at `index` along a row or column of `length` pixels, if any.
+	fn sample_index(self, index: isize, length: usize) -> Option<usize> {
+		let length = length as isize;
+		match self {
+			Self::Clamp => Some(index.clamp(0, length - 1) as usize),
+			Self::Wrap => Some(index.rem_euclid(length) as usize),
+			Self::Transparent => (0..length).contains(&index).then_some(index as usize),
+		}
+	}
+}
+
+/// Blurs the image with a Gaussian, box, or motion blur kernel.
+#[node_macro::node(category("Raster: Filter"), properties("blur_properties"))]
 async fn blur(
-	_: impl Ctx,
+	ctx: impl Ctx + ExtractAll + CloneVarArgs,
 	/// The image to be blurred.
-	image_frame: RasterDataTable<CPU>,
-	/// The radius of the blur kernel.
+	image_frame: impl Node<Context<'static>, Output = RasterDataTable<CPU>>,
+	/// The shape of the blur kernel.
+	mode: BlurMode,
+	/// The standard deviation of the Gaussian kernel, or the radius of the box kernel.
 	#[range((0., 100.))]
 	#[hard_min(0.)]
 	radius: PixelLength,
-	/// Use a lower-quality box kernel instead of a circular Gaussian kernel. This is faster but produces boxy artifacts.
-	box_blur: bool,
+	/// The direction of the motion blur.
+	angle: Angle,
+	/// The length of the motion blur's streaks.
+	#[default(10.)]
+	#[range((0., 100.))]
+	#[hard_min(0.)]
+	distance: PixelLength,
+	/// How pixels beyond the edges of the image are sampled.
+	edges: BlurEdges,
+	/// Blur at full resolution even with a large radius or distance, which is otherwise blurred at a reduced resolution and scaled back up. This is slower but avoids a slight softening.
+	high_quality: bool,
 	/// Opt to incorrectly apply the filter with color calculations in gamma space for compatibility with the results from other software.
 	gamma: bool,
 ) -> RasterDataTable<CPU> {
+	let settings = BlurSettings {
+		mode,
+		radius,
+		angle,
+		distance,
+		edges,
+		high_quality,
+		gamma,
+	};
+
+	// Request the pixels which the blur spreads into the visible part of the image, so a crop of the image is blurred like the whole image
+	let footprint = ctx.try_footprint().copied();
+	let mut ctx = OwnedContextImpl::from(ctx);
+	if let Some(mut footprint) = footprint {
+		footprint.apron = footprint.apron.saturating_add(settings.apron());
+		ctx = ctx.with_footprint(footprint);
+	}
+	let image_frame = image_frame.eval(ctx.into_context()).await;
+
 	image_frame
 		.instance_iter()
 		.map(|mut image_instance| {
 			let image = image_instance.instance.clone();
-
-			// Run blur algorithm
-			let blurred_image = if radius < 0.1 {
-				// Minimum blur radius
-				image.clone()
-			} else if box_blur {
-				Raster::new_cpu(box_blur_algorithm(image.into_data(), radius, gamma))
-			} else {
-				Raster::new_cpu(gaussian_blur_algorithm(image.into_data(), radius, gamma))
-			};
-
-			image_instance.instance = blurred_image;
+			image_instance.instance = Raster::new_cpu(blur_image(image.into_data(), settings));
 			image_instance
 		})
 		.collect()
 }
 
-// 1D gaussian kernel
-fn gaussian_kernel(radius: f64) -> Vec<f64> {
-	// Given radius, compute the size of the kernel that's approximately three times the radius
-	let kernel_radius = (3. * radius).ceil() as usize;
-	let kernel_size = 2 * kernel_radius + 1;
-	let mut gaussian_kernel: Vec<f64> = vec![0.; kernel_size];
+/// Blurs the image in linear light with a Gaussian kernel, whose standard deviation in pixels is the radius, for use by nodes that blur images they produce themselves.
+pub fn gaussian_blur(image: Image<Color>, radius: f64, edges: BlurEdges) -> Image<Color> {
+	let settings = BlurSettings {
+		mode: BlurMode::Gaussian,
+		radius,
+		angle: 0.,
+		distance: 0.,
+		edges,
+		high_quality: false,
+		gamma: false,
+	};
+	blur_image(image, settings)
+}
 
-	// Kernel values
-	let two_radius_squared = 2. * radius * radius;
-	let sum = gaussian_kernel
-		.iter_mut()
-		.enumerate()
-		.map(|(i, value_at_index)| {
-			let x = i as f64 - kernel_radius as f64;
-			let exponent = -(x * x) / two_radius_squared;
-			*value_at_index = exponent.exp();
-			*value_at_index
-		})
-		.sum::<f64>();
+#[derive(Debug, Clone, Copy)]
+struct BlurSettings {
+	mode: BlurMode,
+	radius: f64,
+	angle: f64,
+	distance: f64,
+	edges: BlurEdges,
+	high_quality: bool,
+	gamma: bool,
+}
+
+impl BlurSettings {
+	/// How far, in pixels, the blur spreads each pixel.
+	fn extent(&self) -> f64 {
+		match self.mode {
+			BlurMode::Gaussian | BlurMode::Box => self.radius,
+			BlurMode::Motion => self.distance,
+		}
+	}
+
+	/// A power of two, so the blocks of pixels it averages line up with those of the whole image in any crop of it starting on the tile grid.
+	fn downsample_factor(&self) -> usize {
+		if self.high_quality || self.extent() <= DOWNSAMPLE_RADIUS {
+			return 1;
+		}
+		let factor = (self.extent() / (DOWNSAMPLE_RADIUS / 2.)).floor() as usize;
+		(1 << factor.ilog2()).min(TILE_SIZE as usize)
+	}
+
+	/// How far, in pixels, the blur reads beyond each pixel it outputs, including the blocks and interpolation of a blur at a reduced resolution.
+	/// Wrapping around the edges reads from the opposite side of the image, which needs all of it.
+	fn apron(&self) -> u32 {
+		if self.edges == BlurEdges::Wrap {
+			return u32::MAX;
+		}
+		if self.extent() < 0.1 {
+			return 0;
+		}
+
+		let factor = self.downsample_factor();
+		let scaled = self.scaled_down(factor);
+		let reach = match self.mode {
+			BlurMode::Gaussian => gaussian_kernel(scaled.radius).len() / 2,
+			BlurMode::Box => iterated_box_kernel(scaled.radius).len() / 2,
+			BlurMode::Motion => (scaled.distance / 2.).ceil() as usize + 1,
+		};
 
-	// Normalize
-	gaussian_kernel.iter_mut().for_each(|value_at_index| *value_at_index /= sum);
+		// Downsampling averages whole blocks, and scaling back up interpolates with the neighboring block
+		((reach + 2) * factor) as u32
+	}
 
-	gaussian_kernel
+	fn scaled_down(self, factor: usize) -> Self {
+		Self {
+			radius: self.radius / factor as f64,
+			distance: self.distance / factor as f64,
+			..self
+		}
+	}
 }
 
-fn gaussian_blur_algorithm(mut original_buffer: Image<Color>, radius: f64, gamma: bool) -> Image<Color> {
-	if gamma {
-		original_buffer.map_pixels(|px| px.to_gamma_srgb().to_associated_alpha(px.a()));
+fn blur_image(mut image: Image<Color>, settings: BlurSettings) -> Image<Color> {
+	let (width, height) = (image.width as usize, image.height as usize);
+
+	// Minimum blur radius
+	if width == 0 || height == 0 || settings.extent() < 0.1 {
+		return image;
+	}
+
+	// Blur colors with associated alpha so transparent pixels don't bleed their color, and in linear light unless the gamma option is enabled
+	let pixels = image
+		.data
+		.iter()
+		.map(|&color| {
+			let color = if settings.gamma { color.to_gamma_srgb() } else { color };
+			let alpha = color.a();
+			[color.r() * alpha, color.g() * alpha, color.b() * alpha, alpha]
+		})
+		.collect();
+	let buffer = Buffer { width, height, pixels };
+
+	// The rows of each pass are blurred on other threads, which check the token of the evaluation to skip their work once it's been superseded
+	let cancellation = cancellation::current();
+	let factor = settings.downsample_factor();
+	let blurred = if factor > 1 {
+		buffer.downsample(factor).blur(settings.scaled_down(factor), &cancellation).upsample(factor, width, height)
 	} else {
-		original_buffer.map_pixels(|px| px.to_associated_alpha(px.a()));
+		buffer.blur(settings, &cancellation)
+	};
+	if cancellation.is_cancelled() {
+		return image;
 	}
 
-	let (width, height) = original_buffer.dimensions();
+	image.data = blurred
+		.pixels
+		.into_iter()
+		.map(|[red, green, blue, alpha]| {
+			let color = Color::from_rgbaf32_unchecked(red, green, blue, alpha).to_unassociated_alpha();
+			if settings.gamma { color.to_linear_srgb() } else { color }
+		})
+		.collect();
+	image
+}
 
-	// Create 1D gaussian kernel
-	let kernel = gaussian_kernel(radius);
-	let half_kernel = kernel.len() / 2;
+/// Row-major pixels, each with associated alpha.
+struct Buffer {
+	width: usize,
+	height: usize,
+	pixels: Vec<[f32; 4]>,
+}
 
-	// Intermediate buffer for horizontal and vertical passes
-	let mut x_axis = Image::new(width, height, Color::TRANSPARENT);
-	let mut y_axis = Image::new(width, height, Color::TRANSPARENT);
+impl Buffer {
+	fn blur(&self, settings: BlurSettings, cancellation: &CancellationToken) -> Self {
+		match settings.mode {
+			BlurMode::Gaussian => self.convolve_separable(&gaussian_kernel(settings.radius), settings.edges, cancellation),
+			BlurMode::Box => self.convolve_separable(&iterated_box_kernel(settings.radius), settings.edges, cancellation),
+			BlurMode::Motion => self.motion_blur(settings.angle, settings.distance, settings.edges, cancellation),
+		}
+	}
 
-	for pass in [false, true] {
-		let (max, old_buffer, current_buffer) = match pass {
-			false => (width, &original_buffer, &mut x_axis),
-			true => (height, &x_axis, &mut y_axis),
-		};
-		let pass = pass as usize;
+	/// Convolves the rows and then the columns with the same centered 1D kernel.
+	fn convolve_separable(&self, kernel: &[f32], edges: BlurEdges, cancellation: &CancellationToken) -> Self {
+		self.convolve_rows(kernel, edges, cancellation).transposed().convolve_rows(kernel, edges, cancellation).transposed()
+	}
 
-		for y in 0..height {
-			for x in 0..width {
-				let (mut r_sum, mut g_sum, mut b_sum, mut a_sum, mut weight_sum) = (0., 0., 0., 0., 0.);
+	fn convolve_rows(&self, kernel: &[f32], edges: BlurEdges, cancellation: &CancellationToken) -> Self {
+		let half_kernel = (kernel.len() / 2) as isize;
 
+		let mut pixels = vec![[0.; 4]; self.pixels.len()];
+		pixels.par_chunks_mut(self.width).zip(self.pixels.par_chunks(self.width)).for_each(|(output_row, row)| {
+			if cancellation.is_cancelled() {
+				return;
+			}
+			for (x, output) in output_row.iter_mut().enumerate() {
 				for (i, &weight) in kernel.iter().enumerate() {
-					let p = [x, y][pass] as i32 + (i as i32 - half_kernel as i32);
-
-					if p >= 0 && p < max as i32 {
-						if let Some(px) = old_buffer.get_pixel([p as u32, x][pass], [y, p as u32][pass]) {
-							r_sum += px.r() as f64 * weight;
-							g_sum += px.g() as f64 * weight;
-							b_sum += px.b() as f64 * weight;
-							a_sum += px.a() as f64 * weight;
-							weight_sum += weight;
-						}
+					let Some(sample) = edges.sample_index(x as isize + i as isize - half_kernel, row.len()) else {
+						continue;
+					};
+					for (output, channel) in output.iter_mut().zip(row[sample]) {
+						*output += channel * weight;
 					}
 				}
+			}
+		});
+
+		Self { pixels, ..*self }
+	}
 
-				// Normalize
-				let (r, g, b, a) = if weight_sum > 0. {
-					((r_sum / weight_sum) as f32, (g_sum / weight_sum) as f32, (b_sum / weight_sum) as f32, (a_sum / weight_sum) as f32)
-				} else {
-					let px = old_buffer.get_pixel(x, y).unwrap();
-					(px.r(), px.g(), px.b(), px.a())
-				};
-				current_buffer.set_pixel(x, y, Color::from_rgbaf32_unchecked(r, g, b, a));
+	fn transposed(&self) -> Self {
+		let mut pixels = vec![[0.; 4]; self.pixels.len()];
+		pixels.par_chunks_mut(self.height).enumerate().for_each(|(x, column)| {
+			for (y, pixel) in column.iter_mut().enumerate() {
+				*pixel = self.pixels[y * self.width + x];
 			}
+		});
+
+		Self {
+			width: self.height,
+			height: self.width,
+			pixels,
 		}
 	}
 
-	if gamma {
-		y_axis.map_pixels(|px| px.to_linear_srgb().to_unassociated_alpha());
-	} else {
-		y_axis.map_pixels(|px| px.to_unassociated_alpha());
+	/// Averages samples along a line centered on each pixel, taking about one sample per pixel of its length.
+	fn motion_blur(&self, angle: f64, distance: f64, edges: BlurEdges, cancellation: &CancellationToken) -> Self {
+		let samples = distance.ceil() as usize + 1;
+		let (sin, cos) = angle.to_radians().sin_cos();
+		let weight = 1. / samples as f32;
+
+		let mut pixels = vec![[0.; 4]; self.pixels.len()];
+		pixels.par_chunks_mut(self.width).enumerate().for_each(|(y, output_row)| {
+			if cancellation.is_cancelled() {
+				return;
+			}
+			for (x, output) in output_row.iter_mut().enumerate() {
+				for sample in 0..samples {
+					let offset = (sample as f64 / (samples - 1) as f64 - 0.5) * distance;
+					let color = self.sample_bilinear(x as f64 + offset * cos, y as f64 + offset * sin, edges);
+					for (output, channel) in output.iter_mut().zip(color) {
+						*output += channel * weight;
+					}
+				}
+			}
+		});
+
+		Self { pixels, ..*self }
 	}
 
-	y_axis
-}
+	fn sample_bilinear(&self, x: f64, y: f64, edges: BlurEdges) -> [f32; 4] {
+		let (left, top) = (x.floor(), y.floor());
+		let (fraction_x, fraction_y) = ((x - left) as f32, (y - top) as f32);
+		let (left, top) = (left as isize, top as isize);
 
-fn box_blur_algorithm(mut original_buffer: Image<Color>, radius: f64, gamma: bool) -> Image<Color> {
-	if gamma {
-		original_buffer.map_pixels(|px| px.to_gamma_srgb().to_associated_alpha(px.a()));
-	} else {
-		original_buffer.map_pixels(|px| px.to_associated_alpha(px.a()));
+		let mut color = [0.; 4];
+		let corners = [
+			(0, 0, (1. - fraction_x) * (1. - fraction_y)),
+			(1, 0, fraction_x * (1. - fraction_y)),
+			(0, 1, (1. - fraction_x) * fraction_y),
+			(1, 1, fraction_x * fraction_y),
+		];
+		for (offset_x, offset_y, weight) in corners {
+			if weight == 0. {
+				continue;
+			}
+			let (Some(sample_x), Some(sample_y)) = (edges.sample_index(left + offset_x, self.width), edges.sample_index(top + offset_y, self.height)) else {
+				continue;
+			};
+			for (color, channel) in color.iter_mut().zip(self.pixels[sample_y * self.width + sample_x]) {
+				*color += channel * weight;
+			}
+		}
+		color
 	}
 
-	let (width, height) = original_buffer.dimensions();
-	let mut x_axis = Image::new(width, height, Color::TRANSPARENT);
-	let mut y_axis = Image::new(width, height, Color::TRANSPARENT);
+	/// Averages each `factor` by `factor` block of pixels into one, with partial blocks along the right and bottom edges.
+	fn downsample(&self, factor: usize) -> Self {
+		let (width, height) = (self.width.div_ceil(factor), self.height.div_ceil(factor));
 
-	for pass in [false, true] {
-		let (max, old_buffer, current_buffer) = match pass {
-			false => (width, &original_buffer, &mut x_axis),
-			true => (height, &x_axis, &mut y_axis),
-		};
-		let pass = pass as usize;
-
-		for y in 0..height {
-			for x in 0..width {
-				let (mut r_sum, mut g_sum, mut b_sum, mut a_sum, mut weight_sum) = (0., 0., 0., 0., 0.);
-
-				let i = [x, y][pass];
-				for d in (i as i32 - radius as i32).max(0)..=(i as i32 + radius as i32).min(max as i32 - 1) {
-					if let Some(px) = old_buffer.get_pixel([d as u32, x][pass], [y, d as u32][pass]) {
-						let weight = 1.;
-						r_sum += px.r() as f64 * weight;
-						g_sum += px.g() as f64 * weight;
-						b_sum += px.b() as f64 * weight;
-						a_sum += px.a() as f64 * weight;
-						weight_sum += weight;
+		let mut pixels = vec![[0.; 4]; width * height];
+		pixels.par_chunks_mut(width).enumerate().for_each(|(y, output_row)| {
+			for (x, output) in output_row.iter_mut().enumerate() {
+				let rows = y * factor..((y + 1) * factor).min(self.height);
+				let columns = x * factor..((x + 1) * factor).min(self.width);
+				let weight = 1. / (rows.len() * columns.len()) as f32;
+
+				for source_y in rows {
+					for source_x in columns.clone() {
+						for (output, channel) in output.iter_mut().zip(self.pixels[source_y * self.width + source_x]) {
+							*output += channel * weight;
+						}
 					}
 				}
+			}
+		});
 
-				let (r, g, b, a) = ((r_sum / weight_sum) as f32, (g_sum / weight_sum) as f32, (b_sum / weight_sum) as f32, (a_sum / weight_sum) as f32);
-				current_buffer.set_pixel(x, y, Color::from_rgbaf32_unchecked(r, g, b, a));
+		Self { width, height, pixels }
+	}
+
+	/// Scales the pixels up by the factor they were downsampled by, to the given size, with bilinear interpolation.
+	/// Each pixel samples the same place within its block regardless of the size, so a crop of an image is scaled up like the whole image.
+	fn upsample(&self, factor: usize, width: usize, height: usize) -> Self {
+		let scale = 1. / factor as f64;
+
+		let mut pixels = vec![[0.; 4]; width * height];
+		pixels.par_chunks_mut(width).enumerate().for_each(|(y, output_row)| {
+			for (x, output) in output_row.iter_mut().enumerate() {
+				*output = self.sample_bilinear((x as f64 + 0.5) * scale - 0.5, (y as f64 + 0.5) * scale - 0.5, BlurEdges::Clamp);
+			}
+		});
+
+		Self { width, height, pixels }
+	}
+}
+
+// 1D gaussian kernel
+fn gaussian_kernel(radius: f64) -> Vec<f32> {
+	// Given radius, compute the size of the kernel that's approximately three times the radius
+	let kernel_radius = (3. * radius).ceil() as usize;
+	let kernel_size = 2 * kernel_radius + 1;
+
+	// Kernel values
+	let two_radius_squared = 2. * radius * radius;
+	let kernel = (0..kernel_size).map(|i| {
+		let x = i as f64 - kernel_radius as f64;
+		(-(x * x) / two_radius_squared).exp()
+	});
+
+	normalized(kernel.collect())
+}
+
+/// A box kernel reaching `radius` pixels to each side, which gives the outermost pixels partial weight for fractional radii.
+fn box_kernel(radius: f64) -> Vec<f32> {
+	let fraction = radius.fract();
+	let kernel_radius = radius.ceil() as usize;
+
+	let mut kernel = vec![1.; 2 * kernel_radius + 1];
+	if fraction > 0. {
+		kernel[0] = fraction;
+		kernel[2 * kernel_radius] = fraction;
+	}
+
+	normalized(kernel)
+}
+
+/// Repeated box kernels, combined into a single kernel. Each box gets a share of the radius so the combined kernel reaches about as far as the radius.
+fn iterated_box_kernel(radius: f64) -> Vec<f32> {
+	let box_kernel = box_kernel(radius / BOX_ITERATIONS as f64);
+
+	(1..BOX_ITERATIONS).fold(box_kernel.clone(), |kernel, _| {
+		let mut combined = vec![0.; kernel.len() + box_kernel.len() - 1];
+		for (i, &a) in kernel.iter().enumerate() {
+			for (j, &b) in box_kernel.iter().enumerate() {
+				combined[i + j] += a * b;
 			}
 		}
+		combined
+	})
+}
+
+fn normalized(kernel: Vec<f64>) -> Vec<f32> {
+	let sum = kernel.iter().sum::<f64>();
+	kernel.into_iter().map(|value| (value / sum) as f32).collect()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::tiles::crop_to_visible_tiles;
+	use glam::{DAffine2, DVec2, UVec2};
+	use graphene_core::Node;
+	use graphene_core::instances::Instance;
+	use graphene_core::transform::Footprint;
+	use std::pin::Pin;
+
+	const SIZE: u32 = 7;
+
+	fn settings(mode: BlurMode) -> BlurSettings {
+		BlurSettings {
+			mode,
+			radius: 0.,
+			angle: 0.,
+			distance: 0.,
+			edges: BlurEdges::Clamp,
+			high_quality: false,
+			gamma: false,
+		}
 	}
 
-	if gamma {
-		y_axis.map_pixels(|px| px.to_linear_srgb().to_unassociated_alpha());
-	} else {
-		y_axis.map_pixels(|px| px.to_unassociated_alpha());
+	/// Provides an image to the blur, cropped to the visible tiles of the footprint it's evaluated with like the nodes upstream of it may do.
+	struct CroppedImage {
+		image: RasterDataTable<CPU>,
+		crop: bool,
+	}
+
+	impl<'i> Node<'i, Context<'static>> for CroppedImage {
+		type Output = Pin<Box<dyn Future<Output = RasterDataTable<CPU>> + 'i + Send>>;
+		fn eval(&'i self, ctx: Context<'static>) -> Self::Output {
+			let image = if self.crop { crop_to_visible_tiles(ctx, self.image.clone()) } else { self.image.clone() };
+			Box::pin(async move { image })
+		}
+	}
+
+	/// An opaque image of pixels with unrelated colors, so any pixel blurred with the wrong neighbors stands out.
+	fn noise(width: u32, height: u32) -> RasterDataTable<CPU> {
+		let data = (0..width * height)
+			.map(|index| {
+				let channel = |seed: u32| (index.wrapping_mul(seed) % 251) as f32 / 250.;
+				Color::from_rgbaf32_unchecked(channel(7919), channel(104_729), channel(1_299_709), 1.)
+			})
+			.collect();
+		RasterDataTable::new_instance(Instance {
+			instance: Raster::new_cpu(Image {
+				width,
+				height,
+				data,
+				base64_string: None,
+			}),
+			transform: DAffine2::from_scale(DVec2::new(width as f64, height as f64)),
+			..Default::default()
+		})
 	}
 
-	y_axis
+	/// A single white pixel in the center of an opaque black image.
+	fn impulse() -> Image<Color> {
+		let mut image = Image::new(SIZE, SIZE, Color::BLACK);
+		image.data[(SIZE * SIZE / 2) as usize] = Color::WHITE;
+		image
+	}
+
+	fn assert_red_channel(image: &Image<Color>, expected: &[[f32; SIZE as usize]; SIZE as usize]) {
+		for (y, expected_row) in expected.iter().enumerate() {
+			for (x, &expected) in expected_row.iter().enumerate() {
+				let color = image.data[y * SIZE as usize + x];
+				assert!((color.r() - expected).abs() < 1e-5, "pixel ({x}, {y}) is {} but should be {expected}", color.r());
+				assert!((color.a() - 1.).abs() < 1e-5, "pixel ({x}, {y}) should stay opaque");
+			}
+		}
+	}
+
+	/// The image blurred by the same 1D kernel horizontally and vertically.
+	fn separable(kernel: [f32; SIZE as usize]) -> [[f32; SIZE as usize]; SIZE as usize] {
+		kernel.map(|vertical| kernel.map(|horizontal| vertical * horizontal))
+	}
+
+	#[test]
+	fn gaussian_blur_matches_golden_image() {
+		let blurred = blur_image(
+			impulse(),
+			BlurSettings {
+				radius: 1.,
+				..settings(BlurMode::Gaussian)
+			},
+		);
+		assert_red_channel(&blurred, &separable([0.004433, 0.054006, 0.242036, 0.39905, 0.242036, 0.054006, 0.004433]));
+	}
+
+	#[test]
+	fn box_blur_matches_golden_image() {
+		// Three boxes of radius 1 combine into the kernel [1, 3, 6, 7, 6, 3, 1] / 27
+		let blurred = blur_image(
+			impulse(),
+			BlurSettings {
+				radius: 3.,
+				..settings(BlurMode::Box)
+			},
+		);
+		assert_red_channel(&blurred, &separable([1., 3., 6., 7., 6., 3., 1.].map(|weight| weight / 27.)));
+	}
+
+	#[test]
+	fn motion_blur_matches_golden_image() {
+		let blurred = blur_image(
+			impulse(),
+			BlurSettings {
+				distance: 2.,
+				..settings(BlurMode::Motion)
+			},
+		);
+		let mut expected = [[0.; SIZE as usize]; SIZE as usize];
+		expected[3][2..5].fill(1. / 3.);
+		assert_red_channel(&blurred, &expected);
+
+		let blurred = blur_image(
+			impulse(),
+			BlurSettings {
+				angle: 90.,
+				distance: 2.,
+				..settings(BlurMode::Motion)
+			},
+		);
+		let mut expected = [[0.; SIZE as usize]; SIZE as usize];
+		(2..5).for_each(|y| expected[y][3] = 1. / 3.);
+		assert_red_channel(&blurred, &expected);
+	}
+
+	#[test]
+	fn edges_are_clamped_wrapped_or_transparent() {
+		let white = Image::new(4, 4, Color::WHITE);
+		let blur = |edges| {
+			blur_image(
+				white.clone(),
+				BlurSettings {
+					radius: 3.,
+					edges,
+					..settings(BlurMode::Box)
+				},
+			)
+		};
+
+		for edges in [BlurEdges::Clamp, BlurEdges::Wrap] {
+			assert!(blur(edges).data.iter().all(|color| (color.a() - 1.).abs() < 1e-5 && (color.r() - 1.).abs() < 1e-5));
+		}
+
+		// The edges fade out, keeping their color, and the corners fade out the most
+		let transparent = blur(BlurEdges::Transparent);
+		assert!(transparent.data.iter().all(|color| color.a() < 1. && (color.r() - 1.).abs() < 1e-5));
+		assert!(transparent.data[0].a() < transparent.data[5].a());
+	}
+
+	#[test]
+	fn large_radii_are_blurred_at_a_reduced_resolution() {
+		let settings = BlurSettings {
+			radius: 40.,
+			..settings(BlurMode::Gaussian)
+		};
+		assert_eq!(settings.downsample_factor(), 2);
+		assert_eq!(BlurSettings { high_quality: true, ..settings }.downsample_factor(), 1);
+
+		// The result is scaled back up to the original size, and a uniform image stays uniform
+		let blurred = blur_image(Image::new(9, 5, Color::WHITE), settings);
+		assert_eq!((blurred.width, blurred.height, blurred.data.len()), (9, 5, 45));
+		assert!(blurred.data.iter().all(|color| (color.r() - 1.).abs() < 1e-5 && (color.a() - 1.).abs() < 1e-5));
+	}
+
+	#[test]
+	fn blurring_happens_in_linear_light() {
+		// Averaging a white and a black pixel gives half of the light, which is lighter than halfway between their gamma-encoded values
+		let mut image = Image::new(2, 1, Color::BLACK);
+		image.data[1] = Color::WHITE;
+		let blur = |gamma| {
+			let settings = BlurSettings {
+				distance: 1.,
+				edges: BlurEdges::Wrap,
+				gamma,
+				..settings(BlurMode::Motion)
+			};
+			blur_image(image.clone(), settings).data[0].r()
+		};
+
+		assert!((blur(false) - 0.5).abs() < 1e-5);
+		assert!((blur(true) - Color::srgb_to_linear(0.5)).abs() < 1e-5);
+	}
+
+	#[tokio::test]
+	async fn blurring_visible_tiles_matches_blurring_the_whole_image() {
+		// A viewport within the third column of tiles, whose apron keeps the crop short of some of the image's edges
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-520., 0.)),
+			resolution: UVec2::new(200, 100),
+			..Default::default()
+		};
+		let (width, height) = (1536, 300);
+
+		let blurs = [
+			(BlurMode::Gaussian, 6., 0., BlurEdges::Clamp),
+			(BlurMode::Box, 9., 0., BlurEdges::Transparent),
+			(BlurMode::Motion, 12., 30., BlurEdges::Clamp),
+			// Blurred at half of the resolution
+			(BlurMode::Gaussian, 40., 0., BlurEdges::Clamp),
+		];
+		for (mode, extent, angle, edges) in blurs {
+			let blurred = async |crop| {
+				let upstream = CroppedImage { image: noise(width, height), crop };
+				let ctx = OwnedContextImpl::default().with_footprint(footprint).into_context();
+				let output = blur(ctx, &upstream, mode, extent, angle, extent, edges, false, false).await;
+				let output = output.instance_ref_iter().next().unwrap();
+				(output.instance.data().clone(), output.transform.transform_point2(DVec2::ZERO).round().as_uvec2())
+			};
+			let (whole, _) = blurred(false).await;
+			let (cropped, offset) = blurred(true).await;
+			assert!(cropped.width < width && cropped.height < height, "{mode:?} should blur a crop of the image");
+
+			for y in 0..100 {
+				for x in 520..720 {
+					let expected = whole.data[(y * width + x) as usize];
+					let actual = cropped.data[((y - offset.y) * cropped.width + x - offset.x) as usize];
+					let channels = [(expected.r(), actual.r()), (expected.g(), actual.g()), (expected.b(), actual.b()), (expected.a(), actual.a())];
+					assert!(
+						channels.iter().all(|(expected, actual)| (expected - actual).abs() < 1e-5),
+						"{mode:?} blurred pixel ({x}, {y}) to {actual:?} in the crop but {expected:?} in the whole image"
+					);
+				}
+			}
+		}
+	}
 }
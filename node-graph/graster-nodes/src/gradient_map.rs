@@ -1,8 +1,10 @@
 //! Not immediately shader compatible due to needing [`GradientStops`] as a param, which needs [`Vec`]
 
 use crate::adjust::Adjust;
-use graphene_core::gradient::GradientStops;
+use crate::adjustments::LuminanceCalculation;
+use graphene_core::gradient::{GradientInterpolation, GradientStops};
 use graphene_core::raster_types::{CPU, RasterDataTable};
+use graphene_core::registry::types::Percentage;
 use graphene_core::{Color, Ctx};
 
 // Aims for interoperable compatibility with:
@@ -19,12 +21,100 @@ async fn gradient_map<T: Adjust<Color>>(
 	mut image: T,
 	gradient: GradientStops,
 	reverse: bool,
+	luminance_calc: LuminanceCalculation,
+	/// The color space that the gradient's colors are mixed in between its stops.
+	interpolation: GradientInterpolation,
+	/// How much of the mapped color replaces the original color.
+	#[default(100.)]
+	opacity: Percentage,
+	/// Keep the transparency of the original color rather than taking on the transparency of the gradient.
+	#[default(true)]
+	preserve_alpha: bool,
 ) -> T {
+	let opacity = (opacity / 100.).clamp(0., 1.) as f32;
+
 	image.adjust(|color| {
-		let intensity = color.luminance_srgb();
+		// The luminance is measured without the color being darkened by its premultiplied alpha
+		let intensity = luminance_calc.luminance(&color.to_unassociated_alpha());
 		let intensity = if reverse { 1. - intensity } else { intensity };
-		gradient.evaluate(intensity as f64).to_linear_srgb()
+
+		let mapped = gradient.evaluate_in(intensity as f64, interpolation).to_linear_srgb();
+		let alpha = if preserve_alpha { color.a() } else { mapped.a() };
+		let mapped = mapped.with_alpha(1.).to_associated_alpha(alpha);
+
+		color.lerp(&mapped, opacity)
 	});
 
 	image
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::raster::image::Image;
+	use graphene_core::raster_types::Raster;
+
+	/// A gradient from opaque red to half-transparent blue.
+	fn red_to_blue() -> GradientStops {
+		GradientStops::new(vec![(0., Color::RED), (1., Color::BLUE.with_alpha(0.5))])
+	}
+
+	fn assert_close(actual: Color, expected: Color) {
+		let (actual, expected) = (actual.to_gamma_srgb(), expected.to_gamma_srgb());
+		for (actual, expected) in [actual.r(), actual.g(), actual.b(), actual.a()]
+			.into_iter()
+			.zip([expected.r(), expected.g(), expected.b(), expected.a()])
+		{
+			assert!((actual - expected).abs() < 1e-5, "{actual:?} should be close to {expected:?}");
+		}
+	}
+
+	#[test]
+	fn grayscale_ramp_maps_through_gradient() {
+		// Black, middle gray, and white, measured in the linear values which the luminance is calculated from
+		let ramp = RasterDataTable::new(Raster::new_cpu(Image {
+			width: 3,
+			height: 1,
+			data: vec![Color::BLACK, Color::from_rgbf32_unchecked(0.5, 0.5, 0.5), Color::WHITE],
+			base64_string: None,
+		}));
+		let map = |interpolation| {
+			let result = futures::executor::block_on(gradient_map((), ramp.clone(), red_to_blue(), false, LuminanceCalculation::SRGB, interpolation, 100., false));
+			result.instance_ref_iter().next().unwrap().instance.data().data.clone()
+		};
+
+		// The endpoints take the colors of the end stops, and the midpoint mixes them in the chosen color space
+		let mapped = map(GradientInterpolation::Srgb);
+		assert_close(mapped[0], Color::RED);
+		assert_close(mapped[1], Color::from_rgbf32_unchecked(0.5, 0., 0.5).to_linear_srgb().to_associated_alpha(0.75));
+		assert_close(mapped[2], Color::BLUE.to_associated_alpha(0.5));
+
+		let mapped = map(GradientInterpolation::LinearRgb);
+		assert_close(mapped[0], Color::RED);
+		assert_close(mapped[1], Color::from_rgbf32_unchecked(0.5, 0., 0.5).to_associated_alpha(0.75));
+		assert_close(mapped[2], Color::BLUE.to_associated_alpha(0.5));
+	}
+
+	#[test]
+	fn source_alpha_and_opacity() {
+		let gradient = GradientStops::new(vec![(0., Color::BLACK), (1., Color::RED)]);
+		let half_transparent_white = Color::WHITE.to_associated_alpha(0.5);
+
+		// The luminance ignores the premultiplied alpha, and the original transparency is kept
+		let mapped = futures::executor::block_on(gradient_map(
+			(),
+			half_transparent_white,
+			gradient.clone(),
+			false,
+			LuminanceCalculation::SRGB,
+			GradientInterpolation::Srgb,
+			100.,
+			true,
+		));
+		assert_close(mapped, Color::RED.to_associated_alpha(0.5));
+
+		// Reversed, at half opacity, the mapped black is mixed evenly with the original white
+		let mapped = futures::executor::block_on(gradient_map((), Color::WHITE, gradient, true, LuminanceCalculation::SRGB, GradientInterpolation::Srgb, 50., true));
+		assert_close(mapped, Color::from_rgbf32_unchecked(0.5, 0.5, 0.5));
+	}
+}
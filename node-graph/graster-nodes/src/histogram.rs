@@ -0,0 +1,190 @@
+use dyn_any::DynAny;
+use graphene_core::color::Color;
+use graphene_core::context::Ctx;
+use graphene_core::raster_types::{CPU, RasterDataTable};
+use rayon::prelude::*;
+
+/// The number of bins in each channel of a [`Histogram`], one for each 8-bit value.
+pub const HISTOGRAM_BINS: usize = 256;
+
+/// The most pixels sampled when computing a [`Histogram`], about the size of a 1024x1024 image.
+/// Larger images are downsampled by only counting every nth pixel, so the bin counts stay proportional while the cost stays bounded.
+pub const HISTOGRAM_MAX_SAMPLES: usize = 1 << 20;
+
+/// The number of pixels in each chunk counted in parallel, before being multiplied by the sampling stride.
+const CHUNK_SAMPLES: usize = 1 << 14;
+
+/// The distribution of gamma-encoded 8-bit values across the visible pixels of an image, for its luminance and each of its color channels.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, DynAny, specta::Type, serde::Serialize, serde::Deserialize)]
+pub struct Histogram {
+	pub luminance: Vec<u32>,
+	pub red: Vec<u32>,
+	pub green: Vec<u32>,
+	pub blue: Vec<u32>,
+	/// The number of pixels counted into each channel, which is less than the number of pixels in the image when it was downsampled or has transparent pixels.
+	pub samples: u32,
+}
+
+impl Default for Histogram {
+	fn default() -> Self {
+		Self {
+			luminance: vec![0; HISTOGRAM_BINS],
+			red: vec![0; HISTOGRAM_BINS],
+			green: vec![0; HISTOGRAM_BINS],
+			blue: vec![0; HISTOGRAM_BINS],
+			samples: 0,
+		}
+	}
+}
+
+impl Histogram {
+	/// Counts the pixels of every image in the table, downsampling them evenly if together they exceed [`HISTOGRAM_MAX_SAMPLES`].
+	pub fn from_raster(image: &RasterDataTable<CPU>) -> Self {
+		Self::from_rasters([image])
+	}
+
+	/// Counts the pixels of every image in several tables, such as all the raster content of a layer, as a single histogram.
+	pub fn from_rasters<'a>(images: impl IntoIterator<Item = &'a RasterDataTable<CPU>>) -> Self {
+		let pixels: Vec<&[Color]> = images
+			.into_iter()
+			.flat_map(|image| image.instance_ref_iter().map(|instance| instance.instance.data().data.as_slice()))
+			.collect();
+
+		let stride = sample_stride(pixels.iter().map(|pixels| pixels.len()).sum());
+		pixels.into_iter().map(|pixels| Self::from_pixels(pixels, stride)).fold(Self::default(), Self::merge)
+	}
+
+	/// Counts every `stride`th pixel, in parallel chunks which are merged at the end.
+	fn from_pixels(pixels: &[Color], stride: usize) -> Self {
+		pixels
+			.par_chunks(CHUNK_SAMPLES * stride)
+			.map(|chunk| {
+				let mut histogram = Self::default();
+				for color in chunk.iter().step_by(stride) {
+					histogram.count(color);
+				}
+				histogram
+			})
+			.reduce(Self::default, Self::merge)
+	}
+
+	/// Adds a single pixel to the bins, skipping it if it is fully transparent.
+	fn count(&mut self, color: &Color) {
+		if color.a() == 0. {
+			return;
+		}
+
+		// The channels are binned by their straight (unpremultiplied) gamma-encoded values, as shown by the Levels and Curves nodes
+		let color = color.to_unassociated_alpha().to_gamma_srgb();
+		let bin = |value: f32| (value.clamp(0., 1.) * (HISTOGRAM_BINS - 1) as f32).round() as usize;
+
+		self.luminance[bin(color.luminance_srgb())] += 1;
+		self.red[bin(color.r())] += 1;
+		self.green[bin(color.g())] += 1;
+		self.blue[bin(color.b())] += 1;
+		self.samples += 1;
+	}
+
+	fn merge(mut self, other: Self) -> Self {
+		for (bins, other_bins) in [
+			(&mut self.luminance, &other.luminance),
+			(&mut self.red, &other.red),
+			(&mut self.green, &other.green),
+			(&mut self.blue, &other.blue),
+		] {
+			bins.iter_mut().zip(other_bins).for_each(|(bin, other_bin)| *bin += other_bin);
+		}
+		self.samples += other.samples;
+
+		self
+	}
+}
+
+/// The spacing between counted pixels which keeps the number of samples within [`HISTOGRAM_MAX_SAMPLES`].
+fn sample_stride(pixel_count: usize) -> usize {
+	pixel_count.div_ceil(HISTOGRAM_MAX_SAMPLES).max(1)
+}
+
+/// Counts the distribution of the luminance and color channel values of an image into 256 bins each.
+#[node_macro::node(category("Raster"))]
+async fn histogram(_: impl Ctx, image: RasterDataTable<CPU>) -> Histogram {
+	Histogram::from_raster(&image)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::raster::image::Image;
+	use graphene_core::raster_types::Raster;
+
+	fn raster(width: u32, height: u32, data: Vec<Color>) -> RasterDataTable<CPU> {
+		RasterDataTable::new(Raster::new_cpu(Image {
+			width,
+			height,
+			data,
+			base64_string: None,
+		}))
+	}
+
+	#[test]
+	fn solid_colors_fill_single_bins() {
+		let red = raster(4, 4, vec![Color::RED; 16]);
+		let histogram = futures::executor::block_on(histogram((), red));
+
+		assert_eq!(histogram.samples, 16);
+		assert_eq!(histogram.red[255], 16);
+		assert_eq!(histogram.green[0], 16);
+		assert_eq!(histogram.blue[0], 16);
+		assert_eq!(histogram.luminance[(Color::RED.to_gamma_srgb().luminance_srgb() * 255.).round() as usize], 16);
+		for bins in [&histogram.luminance, &histogram.red, &histogram.green, &histogram.blue] {
+			assert_eq!(bins.len(), HISTOGRAM_BINS);
+			assert_eq!(bins.iter().sum::<u32>(), 16);
+		}
+
+		// Transparent pixels are left out, and translucent pixels are counted by their unpremultiplied color
+		let mut white_and_clear = vec![Color::WHITE.to_associated_alpha(0.5); 8];
+		white_and_clear.extend([Color::TRANSPARENT; 8]);
+		let histogram = Histogram::from_raster(&raster(4, 4, white_and_clear));
+
+		assert_eq!(histogram.samples, 8);
+		for bins in [&histogram.luminance, &histogram.red, &histogram.green, &histogram.blue] {
+			assert_eq!(bins[255], 8);
+			assert_eq!(bins.iter().sum::<u32>(), 8);
+		}
+	}
+
+	#[test]
+	fn linear_ramp_fills_every_bin_once() {
+		// A grayscale ramp through every gamma-encoded 8-bit value
+		let ramp = (0..256)
+			.map(|value| Color::from_rgbaf32_unchecked(value as f32 / 255., value as f32 / 255., value as f32 / 255., 1.).to_linear_srgb())
+			.collect();
+		let histogram = Histogram::from_raster(&raster(256, 1, ramp));
+
+		assert_eq!(histogram.samples, 256);
+		for bins in [&histogram.luminance, &histogram.red, &histogram.green, &histogram.blue] {
+			assert!(bins.iter().all(|&count| count == 1), "{bins:?}");
+		}
+
+		// Several images in the table are counted together
+		let mut table = raster(2, 1, vec![Color::BLACK; 2]);
+		table.extend(raster(1, 1, vec![Color::WHITE]));
+		let histogram = Histogram::from_raster(&table);
+		assert_eq!((histogram.red[0], histogram.red[255], histogram.samples), (2, 1, 3));
+	}
+
+	#[test]
+	fn large_images_are_downsampled() {
+		assert_eq!(sample_stride(0), 1);
+		assert_eq!(sample_stride(HISTOGRAM_MAX_SAMPLES), 1);
+		assert_eq!(sample_stride(HISTOGRAM_MAX_SAMPLES + 1), 2);
+		assert_eq!(sample_stride(HISTOGRAM_MAX_SAMPLES * 4), 4);
+
+		// Every other pixel is counted, which skips every white pixel in alternating columns
+		let pixels: Vec<Color> = (0..CHUNK_SAMPLES * 4).map(|i| if i % 2 == 0 { Color::BLACK } else { Color::WHITE }).collect();
+		let histogram = Histogram::from_pixels(&pixels, 2);
+		assert_eq!(histogram.samples as usize, CHUNK_SAMPLES * 2);
+		assert_eq!(histogram.red[0] as usize, CHUNK_SAMPLES * 2);
+		assert_eq!(histogram.red[255], 0);
+	}
+}
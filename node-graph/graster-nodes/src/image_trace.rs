@@ -0,0 +1,602 @@
+//! Converts raster images into vector paths by tracing the outlines of their regions of flat color
+
+use bezier_rs::{ManipulatorGroup, Subpath, solve_spline_first_handle_closed, solve_spline_first_handle_open};
+use dyn_any::DynAny;
+use glam::{DAffine2, DVec2, IVec2};
+use graphene_core::color::Color;
+use graphene_core::context::Ctx;
+use graphene_core::graphic_element::{GraphicElement, GraphicGroupTable};
+use graphene_core::instances::Instance;
+use graphene_core::raster::image::Image;
+use graphene_core::raster_types::{CPU, RasterDataTable};
+use graphene_core::registry::types::{Angle, PixelLength};
+use graphene_core::vector::style::Fill;
+use graphene_core::vector::{PointId, VectorData, VectorDataTable};
+use rayon::prelude::*;
+use std::collections::HashMap;
+
+/// Marks pixels which are too transparent to belong to any region.
+const NO_REGION: u32 = u32::MAX;
+
+/// The most colors sampled when choosing the palette of a posterized image, so large images are quantized quickly.
+const PALETTE_MAX_SAMPLES: usize = 1 << 16;
+
+/// How the colors of an image are reduced to the flat colors of the regions it is traced into.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Radio)]
+pub enum TraceColorMode {
+	/// Reduce the image to a palette of up to the chosen number of its most representative colors.
+	#[default]
+	Posterize,
+	/// Reduce the image to black and white by comparing the luminance of each pixel to 50%.
+	Threshold,
+}
+
+/// Traces a raster image into filled vector paths, one for each region of flat color after the image's colors are reduced.
+///
+/// The paths are stacked from back to front by decreasing area, with each path filling the holes left by the regions in front of it so no gaps show between them.
+#[node_macro::node(category("Vector"))]
+async fn image_trace(
+	_: impl Ctx,
+	image: RasterDataTable<CPU>,
+	/// How the colors of the image are reduced to the flat colors of the traced regions.
+	color_mode: TraceColorMode,
+	/// The most colors the image is posterized to. Unused in the Threshold color mode.
+	#[default(8)]
+	#[hard_min(2.)]
+	#[soft_max(32.)]
+	color_count: u32,
+	/// How far, in pixels, the traced outlines may stray from the edges of the pixels in order to smooth out their staircase steps.
+	#[default(1.)]
+	#[hard_min(0.)]
+	smoothing_tolerance: PixelLength,
+	/// Regions with fewer pixels than this are merged into the neighboring region they share the most edges with.
+	#[default(4)]
+	#[hard_min(1.)]
+	min_region_area: u32,
+	/// Anchors where the outline turns more sharply than this angle become corners, while the rest are joined by smooth curves.
+	#[default(60.)]
+	#[range((0., 180.))]
+	#[hard_min(0.)]
+	#[hard_max(180.)]
+	corner_threshold: Angle,
+) -> GraphicGroupTable {
+	let settings = TraceSettings {
+		color_mode,
+		color_count: color_count.max(2) as usize,
+		smoothing_tolerance: smoothing_tolerance.max(0.),
+		min_region_area: min_region_area.max(1) as usize,
+		corner_threshold: corner_threshold.clamp(0., 180.).to_radians(),
+	};
+
+	let mut result = GraphicGroupTable::default();
+	for image_instance in image.instance_ref_iter() {
+		let image = image_instance.instance.data();
+		if image.width == 0 || image.height == 0 {
+			continue;
+		}
+
+		// Map the pixel grid onto the unit square which the image's transform places in the document
+		let pixels_to_layer = *image_instance.transform * DAffine2::from_scale(DVec2::new(1. / image.width as f64, 1. / image.height as f64));
+
+		for region in trace_regions(image, settings) {
+			let mut outline = region.outline;
+			outline.apply_transform(pixels_to_layer);
+
+			let mut vector_data = VectorData::from_subpath(outline);
+			vector_data.style.set_fill(Fill::Solid(region.color));
+
+			result.push(Instance {
+				instance: GraphicElement::VectorData(VectorDataTable::new(vector_data)),
+				alpha_blending: *image_instance.alpha_blending,
+				..Default::default()
+			});
+		}
+	}
+
+	result
+}
+
+#[derive(Debug, Clone, Copy)]
+struct TraceSettings {
+	color_mode: TraceColorMode,
+	color_count: usize,
+	smoothing_tolerance: f64,
+	min_region_area: usize,
+	/// In radians.
+	corner_threshold: f64,
+}
+
+/// The filled outline of a region of flat color, in pixel coordinates.
+struct TracedRegion {
+	color: Color,
+	outline: Subpath<PointId>,
+	/// The area enclosed by the pixel edges of the outline, including any holes where other regions sit in front of it.
+	area: f64,
+}
+
+/// Reduces the colors of the image, splits it into connected regions of each color, and traces the outline of every region, ordered back to front.
+fn trace_regions(image: &Image<Color>, settings: TraceSettings) -> Vec<TracedRegion> {
+	let (width, height) = (image.width as usize, image.height as usize);
+	let (palette, labels) = quantize(image, settings);
+
+	let (component_of, components) = connected_components(&labels, width, height);
+	let region_of = merge_small_components(&component_of, &components, width, height, settings.min_region_area);
+
+	// Each region is traced from its first pixel in row-major order, whose top edge must be part of its outer boundary
+	let mut starts = HashMap::new();
+	for (index, &region) in region_of.iter().enumerate() {
+		if region != NO_REGION {
+			starts.entry(region).or_insert(index);
+		}
+	}
+	let mut starts: Vec<(u32, usize)> = starts.into_iter().collect();
+	starts.sort_by_key(|&(_, start)| start);
+
+	let mut regions: Vec<TracedRegion> = starts
+		.into_par_iter()
+		.filter_map(|(region, start)| {
+			let corners = trace_outline(&region_of, width, height, region, start);
+			let area = polygon_area(&corners);
+
+			let mut points = simplify_closed(&cut_staircase_corners(&corners), settings.smoothing_tolerance);
+			if points.len() < 3 {
+				points = corners;
+			}
+			if points.len() < 3 {
+				return None;
+			}
+
+			let label = labels[components[region as usize][0] as usize];
+			Some(TracedRegion {
+				color: palette[label as usize].to_linear_srgb(),
+				outline: fit_outline(&points, settings.corner_threshold),
+				area,
+			})
+		})
+		.collect();
+
+	// Larger regions go behind the smaller regions they may surround, keeping the row-major order between regions of the same area
+	regions.sort_by(|a, b| b.area.total_cmp(&a.area));
+	regions
+}
+
+/// Chooses the palette of gamma-encoded colors for the image and labels each of its pixels with the index of its palette color, or [`NO_REGION`] if it is mostly transparent.
+fn quantize(image: &Image<Color>, settings: TraceSettings) -> (Vec<Color>, Vec<u32>) {
+	let gamma_colors: Vec<Option<Color>> = image.data.par_iter().map(|color| (color.a() >= 0.5).then(|| color.to_unassociated_alpha().to_gamma_srgb())).collect();
+
+	match settings.color_mode {
+		TraceColorMode::Posterize => {
+			let opaque_count = gamma_colors.iter().flatten().count();
+			let stride = opaque_count.div_ceil(PALETTE_MAX_SAMPLES).max(1);
+			let samples = gamma_colors.iter().flatten().step_by(stride).map(|color| [color.r(), color.g(), color.b()]).collect();
+			let palette = median_cut(samples, settings.color_count);
+
+			let distance = |a: &Color, b: &Color| (a.r() - b.r()).powi(2) + (a.g() - b.g()).powi(2) + (a.b() - b.b()).powi(2);
+			let labels = gamma_colors
+				.par_iter()
+				.map(|color| {
+					let Some(color) = color else { return NO_REGION };
+					(0..palette.len()).min_by(|&a, &b| distance(color, &palette[a]).total_cmp(&distance(color, &palette[b]))).unwrap_or(0) as u32
+				})
+				.collect();
+
+			(palette, labels)
+		}
+		TraceColorMode::Threshold => {
+			let labels = gamma_colors
+				.par_iter()
+				.map(|color| match color {
+					Some(color) => (color.luminance_srgb() >= 0.5) as u32,
+					None => NO_REGION,
+				})
+				.collect();
+
+			(vec![Color::BLACK, Color::WHITE], labels)
+		}
+	}
+}
+
+/// Picks up to `count` representative colors by repeatedly splitting the box of colors with the widest range in any channel at its median.
+fn median_cut(colors: Vec<[f32; 3]>, count: usize) -> Vec<Color> {
+	let widest_channel = |colors: &[[f32; 3]]| {
+		(0..3)
+			.map(|channel| {
+				let (min, max) = colors.iter().fold((f32::MAX, f32::MIN), |(min, max), color| (min.min(color[channel]), max.max(color[channel])));
+				(channel, max - min)
+			})
+			.max_by(|a, b| a.1.total_cmp(&b.1))
+			.unwrap_or((0, 0.))
+	};
+
+	let mut boxes = vec![colors];
+	while boxes.len() < count {
+		let widest = boxes
+			.iter()
+			.enumerate()
+			.map(|(index, colors)| (index, widest_channel(colors)))
+			.filter(|&(_, (_, range))| range > 0.)
+			.max_by(|a, b| a.1.1.total_cmp(&b.1.1));
+		let Some((index, (channel, _))) = widest else { break };
+
+		let mut lower = boxes.swap_remove(index);
+		lower.sort_by(|a, b| a[channel].total_cmp(&b[channel]));
+		let upper = lower.split_off(lower.len() / 2);
+		boxes.push(lower);
+		boxes.push(upper);
+	}
+
+	boxes
+		.iter()
+		.filter(|colors| !colors.is_empty())
+		.map(|colors| {
+			let sum = colors.iter().fold([0.; 3], |sum, color| [sum[0] + color[0], sum[1] + color[1], sum[2] + color[2]]);
+			let count = colors.len() as f32;
+			Color::from_rgbf32_unchecked(sum[0] / count, sum[1] / count, sum[2] / count)
+		})
+		.collect()
+}
+
+/// The indices of the pixels bordering the given pixel on each of its four sides.
+fn neighbors(index: usize, width: usize, height: usize) -> impl Iterator<Item = usize> {
+	let (x, y) = (index % width, index / width);
+	[
+		(x > 0).then(|| index - 1),
+		(x + 1 < width).then(|| index + 1),
+		(y > 0).then(|| index - width),
+		(y + 1 < height).then(|| index + width),
+	]
+	.into_iter()
+	.flatten()
+}
+
+/// Groups the pixels into components of the same label connected along their edges, returning the component of each pixel and the pixels of each component.
+/// Pixels touching only at their corners belong to separate components, so a checkerboard pattern yields a component for each square.
+fn connected_components(labels: &[u32], width: usize, height: usize) -> (Vec<u32>, Vec<Vec<u32>>) {
+	let mut component_of = vec![NO_REGION; labels.len()];
+	let mut components = Vec::new();
+
+	let mut stack = Vec::new();
+	for start in 0..labels.len() {
+		if labels[start] == NO_REGION || component_of[start] != NO_REGION {
+			continue;
+		}
+
+		let component = components.len() as u32;
+		let mut pixels = Vec::new();
+		component_of[start] = component;
+		stack.push(start);
+		while let Some(index) = stack.pop() {
+			pixels.push(index as u32);
+			for neighbor in neighbors(index, width, height) {
+				if labels[neighbor] == labels[start] && component_of[neighbor] == NO_REGION {
+					component_of[neighbor] = component;
+					stack.push(neighbor);
+				}
+			}
+		}
+		components.push(pixels);
+	}
+
+	(component_of, components)
+}
+
+/// Merges each component smaller than the minimum area into the neighboring component it shares the most edges with, smallest first, returning the region of each pixel.
+/// Small components with no neighbors, such as specks surrounded by transparency, are removed.
+fn merge_small_components(component_of: &[u32], components: &[Vec<u32>], width: usize, height: usize, min_area: usize) -> Vec<u32> {
+	let mut parent: Vec<u32> = (0..components.len() as u32).collect();
+	let mut area: Vec<usize> = components.iter().map(Vec::len).collect();
+	let mut removed = vec![false; components.len()];
+
+	fn find(parent: &mut [u32], mut component: u32) -> u32 {
+		while parent[component as usize] != component {
+			parent[component as usize] = parent[parent[component as usize] as usize];
+			component = parent[component as usize];
+		}
+		component
+	}
+
+	let mut small: Vec<u32> = (0..components.len() as u32).filter(|&component| area[component as usize] < min_area).collect();
+	small.sort_by_key(|&component| area[component as usize]);
+
+	for component in small {
+		if find(&mut parent, component) != component || area[component as usize] >= min_area {
+			continue;
+		}
+
+		let mut shared_edges = HashMap::new();
+		for &pixel in &components[component as usize] {
+			for neighbor in neighbors(pixel as usize, width, height) {
+				if component_of[neighbor] == NO_REGION {
+					continue;
+				}
+				let neighbor = find(&mut parent, component_of[neighbor]);
+				if neighbor != component {
+					*shared_edges.entry(neighbor).or_insert(0_usize) += 1;
+				}
+			}
+		}
+
+		// Ties are broken by the lower component index so the result doesn't depend on the iteration order of the map
+		match shared_edges.into_iter().max_by_key(|&(neighbor, edges)| (edges, std::cmp::Reverse(neighbor))) {
+			Some((neighbor, _)) => {
+				parent[component as usize] = neighbor;
+				area[neighbor as usize] += area[component as usize];
+			}
+			None => removed[component as usize] = true,
+		}
+	}
+
+	component_of
+		.iter()
+		.map(|&component| {
+			if component == NO_REGION {
+				return NO_REGION;
+			}
+			let region = find(&mut parent, component);
+			if removed[region as usize] { NO_REGION } else { region }
+		})
+		.collect()
+}
+
+/// Follows the outer boundary of a region along the edges of its pixels, returning the corners where the boundary turns.
+///
+/// The boundary is walked clockwise with the region on the right, starting at the top left corner of the region's first pixel.
+/// Where two of the region's pixels touch only at a corner, the walk turns right to stay around the same pixel, matching how components are connected.
+fn trace_outline(region_of: &[u32], width: usize, height: usize, region: u32, start: usize) -> Vec<DVec2> {
+	let inside = |pixel: IVec2| pixel.x >= 0 && pixel.y >= 0 && (pixel.x as usize) < width && (pixel.y as usize) < height && region_of[pixel.y as usize * width + pixel.x as usize] == region;
+
+	// The pixels to the right and left of the edge leaving a vertex in a direction are found from the edge's midpoint, in doubled coordinates to stay in integers
+	let is_boundary = |vertex: IVec2, direction: IVec2| {
+		let right = IVec2::new(-direction.y, direction.x);
+		inside((2 * vertex + direction + right).div_euclid(IVec2::splat(2))) && !inside((2 * vertex + direction - right).div_euclid(IVec2::splat(2)))
+	};
+
+	let start = IVec2::new((start % width) as i32, (start / width) as i32);
+	let mut vertex = start;
+	let mut direction = IVec2::X;
+	let mut corners = Vec::new();
+	loop {
+		vertex += direction;
+
+		let right = IVec2::new(-direction.y, direction.x);
+		let Some(next) = [right, direction, -right].into_iter().find(|&next| is_boundary(vertex, next)) else {
+			break;
+		};
+		if next != direction {
+			corners.push(vertex.as_dvec2());
+		}
+		direction = next;
+
+		if vertex == start && direction == IVec2::X {
+			break;
+		}
+	}
+
+	corners
+}
+
+/// The area enclosed by a closed polygon.
+fn polygon_area(points: &[DVec2]) -> f64 {
+	let doubled: f64 = points.iter().zip(points.iter().cycle().skip(1)).map(|(a, b)| a.perp_dot(*b)).sum();
+	doubled.abs() / 2.
+}
+
+/// Replaces the corners of single-pixel steps in a pixel outline with the midpoints of the edges between corners, which lie along the outline's intended slope.
+/// Corners between two edges longer than a pixel are kept, so rectangular regions keep their exact shape.
+fn cut_staircase_corners(corners: &[DVec2]) -> Vec<DVec2> {
+	let count = corners.len();
+	let mut points = Vec::with_capacity(count * 2);
+	for (index, &corner) in corners.iter().enumerate() {
+		let previous = corners[(index + count - 1) % count];
+		let next = corners[(index + 1) % count];
+		if corner.distance_squared(previous) > 1. && corner.distance_squared(next) > 1. {
+			points.push(corner);
+		}
+		points.push((corner + next) / 2.);
+	}
+	points
+}
+
+/// Removes the vertices of a closed polygon which lie within the tolerance of the simplified outline, using the Ramer–Douglas–Peucker algorithm.
+fn simplify_closed(points: &[DVec2], tolerance: f64) -> Vec<DVec2> {
+	if tolerance <= 0. || points.len() <= 3 {
+		return points.to_vec();
+	}
+
+	// Split the loop into two open polylines between its first vertex and the vertex farthest from it
+	let farthest = (1..points.len())
+		.max_by(|&a, &b| points[0].distance_squared(points[a]).total_cmp(&points[0].distance_squared(points[b])))
+		.unwrap_or(1);
+	let second_half: Vec<DVec2> = points[farthest..].iter().copied().chain([points[0]]).collect();
+
+	let mut simplified = simplify_open(&points[..=farthest], tolerance);
+	simplified.pop();
+	simplified.extend(simplify_open(&second_half, tolerance));
+	simplified.pop();
+	simplified
+}
+
+/// Simplifies an open polyline with the Ramer–Douglas–Peucker algorithm, always keeping its endpoints.
+fn simplify_open(points: &[DVec2], tolerance: f64) -> Vec<DVec2> {
+	let (Some(&first), Some(&last)) = (points.first(), points.last()) else { return Vec::new() };
+	if points.len() <= 2 {
+		return points.to_vec();
+	}
+
+	let distance_from_chord = |point: DVec2| {
+		let chord = last - first;
+		if chord.length_squared() == 0. {
+			point.distance(first)
+		} else {
+			chord.perp_dot(point - first).abs() / chord.length()
+		}
+	};
+	let (farthest, distance) = points[1..points.len() - 1]
+		.iter()
+		.enumerate()
+		.map(|(index, &point)| (index + 1, distance_from_chord(point)))
+		.max_by(|a, b| a.1.total_cmp(&b.1))
+		.expect("There are points between the endpoints");
+
+	if distance <= tolerance {
+		return vec![first, last];
+	}
+
+	let mut simplified = simplify_open(&points[..=farthest], tolerance);
+	simplified.pop();
+	simplified.extend(simplify_open(&points[farthest..], tolerance));
+	simplified
+}
+
+/// Builds a closed path through the points, with sharp corners where the outline turns by more than the corner threshold and smooth splines through the other points.
+fn fit_outline(points: &[DVec2], corner_threshold: f64) -> Subpath<PointId> {
+	let count = points.len();
+	let is_corner = |index: usize| {
+		let previous = points[(index + count - 1) % count];
+		let next = points[(index + 1) % count];
+		(points[index] - previous).angle_to(next - points[index]).abs() > corner_threshold
+	};
+
+	let Some(first_corner) = (0..count).find(|&index| is_corner(index)) else {
+		// Without any corners, the whole outline is one closed spline
+		let handles = solve_spline_first_handle_closed(points);
+		let groups = points
+			.iter()
+			.zip(handles)
+			.map(|(&anchor, handle)| ManipulatorGroup::new(anchor, Some(2. * anchor - handle), Some(handle)))
+			.collect();
+		return Subpath::new(groups, true);
+	};
+
+	// Start at a corner, then fit each run of points from one corner to the next with its own open spline
+	let points: Vec<DVec2> = points[first_corner..].iter().chain(&points[..first_corner]).copied().collect();
+	let corners: Vec<usize> = (0..count).filter(|&index| is_corner((index + first_corner) % count)).chain([count]).collect();
+
+	let mut in_handles = vec![None; count];
+	let mut out_handles = vec![None; count];
+	for run in corners.windows(2) {
+		let [start, end] = [run[0], run[1]];
+		if end - start < 2 {
+			continue;
+		}
+
+		let run_points: Vec<DVec2> = (start..=end).map(|index| points[index % count]).collect();
+		let handles = solve_spline_first_handle_open(&run_points);
+		for (offset, (&anchor, &handle)) in run_points.iter().zip(&handles).enumerate() {
+			let index = (start + offset) % count;
+			if offset > 0 {
+				in_handles[index] = Some(2. * anchor - handle);
+			}
+			if offset < run_points.len() - 1 {
+				out_handles[index] = Some(handle);
+			}
+		}
+	}
+
+	let groups = points
+		.iter()
+		.enumerate()
+		.map(|(index, &anchor)| ManipulatorGroup::new(anchor, in_handles[index], out_handles[index]))
+		.collect();
+	Subpath::new(groups, true)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::raster_types::Raster;
+
+	fn raster(width: u32, height: u32, pixel: impl Fn(u32, u32) -> Color) -> RasterDataTable<CPU> {
+		let data = (0..width * height).map(|index| pixel(index % width, index / width)).collect();
+
+		RasterDataTable::new_instance(Instance {
+			instance: Raster::new_cpu(Image {
+				width,
+				height,
+				data,
+				base64_string: None,
+			}),
+			// One document unit per pixel, so the traced areas are measured in pixels
+			transform: DAffine2::from_scale(DVec2::new(width as f64, height as f64)),
+			..Default::default()
+		})
+	}
+
+	/// The fill color and area of each traced path, from back to front.
+	fn trace(image: RasterDataTable<CPU>, color_mode: TraceColorMode, color_count: u32, min_region_area: u32) -> Vec<(Color, f64)> {
+		let result = futures::executor::block_on(image_trace((), image, color_mode, color_count, 1., min_region_area, 60.));
+
+		result
+			.instance_ref_iter()
+			.map(|instance| {
+				let GraphicElement::VectorData(vector_data) = instance.instance else {
+					panic!("Expected vector data")
+				};
+				let vector_data = vector_data.instance_ref_iter().next().unwrap().instance;
+
+				let mut subpaths = vector_data.stroke_bezier_paths();
+				let subpath = subpaths.next().unwrap();
+				assert!(subpaths.next().is_none());
+				assert!(subpath.closed());
+
+				let Fill::Solid(color) = vector_data.style.fill() else { panic!("Expected a solid fill") };
+				(*color, subpath.area(Some(1e-3), Some(1e-3)))
+			})
+			.collect()
+	}
+
+	#[test]
+	fn circle_traces_to_a_round_region() {
+		let radius = 12.;
+		let circle = raster(40, 40, |x, y| {
+			let distance = DVec2::new(x as f64 + 0.5, y as f64 + 0.5).distance(DVec2::splat(20.));
+			if distance <= radius { Color::BLACK } else { Color::WHITE }
+		});
+
+		let regions = trace(circle, TraceColorMode::Threshold, 2, 4);
+		assert_eq!(regions.len(), 2);
+
+		// The white background is behind the circle, filling the whole image
+		assert_eq!(regions[0].0, Color::WHITE);
+		assert!((regions[0].1 - 1600.).abs() < 1e-6, "{}", regions[0].1);
+
+		// The black circle in front is smoothed to nearly the area of a true circle
+		let circle_area = std::f64::consts::PI * radius * radius;
+		assert_eq!(regions[1].0, Color::BLACK);
+		assert!((regions[1].1 - circle_area).abs() < circle_area * 0.05, "{} should be close to {circle_area}", regions[1].1);
+	}
+
+	#[test]
+	fn checkerboard_traces_a_region_per_square() {
+		let checkerboard = raster(32, 32, |x, y| if (x / 8 + y / 8) % 2 == 0 { Color::RED } else { Color::BLUE });
+
+		// Squares touching only at their corners are separate regions, and keep their sharp corners
+		let regions = trace(checkerboard, TraceColorMode::Posterize, 2, 4);
+		assert_eq!(regions.len(), 16);
+		assert!(regions.iter().all(|&(_, area)| (area - 64.).abs() < 1e-6), "{regions:?}");
+		assert_eq!(regions.iter().filter(|&&(color, _)| color == Color::RED).count(), 8);
+		assert_eq!(regions.iter().filter(|&&(color, _)| color == Color::BLUE).count(), 8);
+	}
+
+	#[test]
+	fn small_regions_are_merged() {
+		let speck = raster(16, 16, |x, y| if (x, y) == (5, 7) { Color::BLACK } else { Color::WHITE });
+
+		assert_eq!(trace(speck.clone(), TraceColorMode::Threshold, 2, 1).len(), 2);
+
+		let regions = trace(speck, TraceColorMode::Threshold, 2, 4);
+		assert_eq!(regions.len(), 1);
+		assert_eq!(regions[0].0, Color::WHITE);
+	}
+
+	#[test]
+	fn posterize_picks_representative_colors() {
+		let colors = [[0., 0., 0.], [0.1, 0., 0.], [1., 1., 1.], [0.9, 1., 1.], [0., 0., 1.]];
+		let palette = median_cut(colors.to_vec(), 3);
+		assert_eq!(palette.len(), 3);
+
+		// A single color can't be split further than itself
+		assert_eq!(median_cut(vec![[0.5; 3]; 10], 8), [Color::from_rgbf32_unchecked(0.5, 0.5, 0.5)]);
+	}
+}
@@ -19,6 +19,16 @@ pub mod generate_curves;
 #[cfg(feature = "std")]
 pub mod gradient_map;
 #[cfg(feature = "std")]
+pub mod histogram;
+#[cfg(feature = "std")]
 pub mod image_color_palette;
 #[cfg(feature = "std")]
+pub mod image_trace;
+#[cfg(feature = "std")]
+pub mod periodic_noise;
+#[cfg(feature = "std")]
 pub mod std_nodes;
+#[cfg(feature = "std")]
+pub mod threshold;
+#[cfg(feature = "std")]
+pub mod tiles;
@@ -0,0 +1,221 @@
+//! Perlin noise with a gradient lattice that wraps around after a whole number of cells, used by the Noise Pattern node to generate tileable noise
+
+use crate::adjustments::FractalType;
+use glam::{IVec2, IVec3, Vec2, Vec3};
+
+// The same primes and multiplier that FastNoise Lite hashes its lattice coordinates with
+const PRIME_X: i32 = 501125321;
+const PRIME_Y: i32 = 1136930381;
+const PRIME_Z: i32 = 1720413743;
+const HASH_MULTIPLIER: i32 = 0x27d4eb2d;
+
+/// Scales 3D Perlin noise to roughly fill the -1 to 1 range, matching FastNoise Lite.
+const PERLIN_3D_BOUNDING: f32 = 0.964921414852142;
+
+/// The directions to the midpoints of the edges of a cube, as used by improved Perlin noise.
+const GRADIENTS: [Vec3; 12] = [
+	Vec3::new(1., 1., 0.),
+	Vec3::new(-1., 1., 0.),
+	Vec3::new(1., -1., 0.),
+	Vec3::new(-1., -1., 0.),
+	Vec3::new(1., 0., 1.),
+	Vec3::new(-1., 0., 1.),
+	Vec3::new(1., 0., -1.),
+	Vec3::new(-1., 0., -1.),
+	Vec3::new(0., 1., 1.),
+	Vec3::new(0., -1., 1.),
+	Vec3::new(0., 1., -1.),
+	Vec3::new(0., -1., -1.),
+];
+
+/// The layering of octaves on top of the base noise, with the same meaning as the fractal settings of FastNoise Lite.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FractalSettings {
+	pub fractal_type: FractalType,
+	pub octaves: u32,
+	pub lacunarity: f32,
+	pub gain: f32,
+	pub weighted_strength: f32,
+	pub ping_pong_strength: f32,
+}
+
+/// Perlin noise which repeats across rectangular tiles, with each octave's lattice fitted to a whole number of cells per tile.
+///
+/// Matching the lattice to the tile means the number of cells can't be arbitrary, so the requested number of cells is rounded to the nearest whole number for every octave.
+/// This slightly adjusts the scale of the noise, and the ratio between octaves when the lacunarity isn't a whole number.
+/// The third dimension isn't wrapped, so slicing through it at different depths produces smoothly evolving noise that still tiles.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TileablePerlin {
+	seed: i32,
+	cells_per_tile: Vec2,
+	fractal: FractalSettings,
+	bounding: f32,
+}
+
+impl TileablePerlin {
+	/// Creates noise with about `cells_per_tile` lattice cells across each tile in its base octave.
+	pub fn new(seed: i32, cells_per_tile: Vec2, fractal: FractalSettings) -> Self {
+		let octaves = if Self::is_layered(fractal.fractal_type) { fractal.octaves.max(1) } else { 1 };
+
+		// The sum of the amplitudes of every octave, which is divided out so the result stays within range
+		let gain = fractal.gain.abs();
+		let bounding = 1. / (0..octaves).map(|octave| gain.powi(octave as i32)).sum::<f32>();
+
+		Self {
+			seed,
+			cells_per_tile: cells_per_tile.max(Vec2::ONE),
+			fractal: FractalSettings { octaves, ..fractal },
+			bounding,
+		}
+	}
+
+	/// Whether the fractal type layers octaves of the noise itself, as opposed to not layering them or only layering the domain warp.
+	fn is_layered(fractal_type: FractalType) -> bool {
+		matches!(fractal_type, FractalType::FBm | FractalType::Ridged | FractalType::PingPong)
+	}
+
+	/// Samples the noise at a position measured in tiles, so it repeats at every whole number along the X and Y axes, and at a depth measured in lattice cells of the base octave.
+	/// The result is in the range -1 to 1.
+	pub fn sample(&self, position: Vec2, depth: f32) -> f32 {
+		let FractalSettings {
+			fractal_type,
+			octaves,
+			lacunarity,
+			gain,
+			weighted_strength,
+			ping_pong_strength,
+		} = self.fractal;
+
+		if !Self::is_layered(fractal_type) {
+			return self.octave(self.seed, 1., position, depth);
+		}
+
+		let mut sum = 0.;
+		let mut amplitude = self.bounding;
+		let mut frequency = 1.;
+		for octave in 0..octaves {
+			let noise = self.octave(self.seed.wrapping_add(octave as i32), frequency, position, depth);
+
+			// The same layering as FastNoise Lite's fractal types
+			let weight = match fractal_type {
+				FractalType::Ridged => {
+					let noise = noise.abs();
+					sum += (noise * -2. + 1.) * amplitude;
+					1. - noise
+				}
+				FractalType::PingPong => {
+					let noise = ping_pong((noise + 1.) * ping_pong_strength);
+					sum += (noise - 0.5) * 2. * amplitude;
+					noise
+				}
+				_ => {
+					sum += noise * amplitude;
+					(noise + 1.).min(2.) * 0.5
+				}
+			};
+			amplitude *= 1. + (weight - 1.) * weighted_strength;
+			amplitude *= gain;
+			frequency *= lacunarity;
+		}
+
+		sum
+	}
+
+	/// Samples a single octave, whose lattice has the base octave's cells per tile multiplied by the frequency, rounded to fit the tile.
+	fn octave(&self, seed: i32, frequency: f32, position: Vec2, depth: f32) -> f32 {
+		let period = (self.cells_per_tile * frequency).round().max(Vec2::ONE).as_ivec2();
+		let lattice_position = (position * period.as_vec2()).extend(depth * frequency);
+		periodic_perlin(seed, lattice_position, period)
+	}
+}
+
+/// 3D Perlin noise at a position in lattice cells, with the lattice wrapping around after `period` cells along the X and Y axes.
+pub fn periodic_perlin(seed: i32, position: Vec3, period: IVec2) -> f32 {
+	let cell = position.floor();
+	let offset = position - cell;
+	let cell = cell.as_ivec3();
+
+	let corner = |corner: IVec3| {
+		let lattice = IVec3::new((cell.x + corner.x).rem_euclid(period.x), (cell.y + corner.y).rem_euclid(period.y), cell.z + corner.z);
+		gradient(seed, lattice).dot(offset - corner.as_vec3())
+	};
+	let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+	let fade = Vec3::new(quintic(offset.x), quintic(offset.y), quintic(offset.z));
+
+	let near = lerp(
+		lerp(corner(IVec3::new(0, 0, 0)), corner(IVec3::new(1, 0, 0)), fade.x),
+		lerp(corner(IVec3::new(0, 1, 0)), corner(IVec3::new(1, 1, 0)), fade.x),
+		fade.y,
+	);
+	let far = lerp(
+		lerp(corner(IVec3::new(0, 0, 1)), corner(IVec3::new(1, 0, 1)), fade.x),
+		lerp(corner(IVec3::new(0, 1, 1)), corner(IVec3::new(1, 1, 1)), fade.x),
+		fade.y,
+	);
+
+	lerp(near, far, fade.z) * PERLIN_3D_BOUNDING
+}
+
+/// The pseudorandom gradient at a lattice point.
+fn gradient(seed: i32, lattice: IVec3) -> Vec3 {
+	let hash = (seed ^ lattice.x.wrapping_mul(PRIME_X) ^ lattice.y.wrapping_mul(PRIME_Y) ^ lattice.z.wrapping_mul(PRIME_Z)).wrapping_mul(HASH_MULTIPLIER);
+	let hash = hash ^ (hash >> 15);
+	GRADIENTS[hash.rem_euclid(GRADIENTS.len() as i32) as usize]
+}
+
+/// Eases the interpolation between lattice points so the noise has continuous first and second derivatives.
+fn quintic(t: f32) -> f32 {
+	t * t * t * (t * (t * 6. - 15.) + 10.)
+}
+
+/// Folds a value back and forth between 0 and 1.
+fn ping_pong(t: f32) -> f32 {
+	let t = t - (t * 0.5).trunc() * 2.;
+	if t < 1. { t } else { 2. - t }
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	fn fractal(fractal_type: FractalType) -> FractalSettings {
+		FractalSettings {
+			fractal_type,
+			octaves: 4,
+			lacunarity: 2.,
+			gain: 0.5,
+			weighted_strength: 0.,
+			ping_pong_strength: 2.,
+		}
+	}
+
+	#[test]
+	fn repeats_every_tile() {
+		for fractal_type in [FractalType::None, FractalType::FBm, FractalType::Ridged, FractalType::PingPong] {
+			// A non-integer number of cells and lacunarity, which are rounded to fit the tile
+			let settings = FractalSettings {
+				lacunarity: 1.7,
+				..fractal(fractal_type)
+			};
+			let noise = TileablePerlin::new(7, Vec2::new(3.4, 5.), settings);
+
+			for step in 0..20 {
+				let t = step as f32 / 20.;
+				let depth = t * 3.;
+				assert!((noise.sample(Vec2::new(0., t), depth) - noise.sample(Vec2::new(1., t), depth)).abs() < 1e-5);
+				assert!((noise.sample(Vec2::new(t, 0.), depth) - noise.sample(Vec2::new(t, 1.), depth)).abs() < 1e-5);
+				assert!((noise.sample(Vec2::new(t, t), depth) - noise.sample(Vec2::new(t - 2., t + 3.), depth)).abs() < 1e-5);
+			}
+		}
+	}
+
+	#[test]
+	fn stays_in_range_and_varies() {
+		let noise = TileablePerlin::new(1, Vec2::splat(4.), fractal(FractalType::FBm));
+		let samples: Vec<f32> = (0..64 * 64).map(|index| noise.sample(Vec2::new((index % 64) as f32, (index / 64) as f32) / 64., 0.5)).collect();
+
+		assert!(samples.iter().all(|sample| (-1. ..=1.).contains(sample)));
+		let (min, max) = samples.iter().fold((f32::MAX, f32::MIN), |(min, max), &sample| (min.min(sample), max.max(sample)));
+		assert!(max - min > 0.5, "{min} to {max}");
+	}
+}
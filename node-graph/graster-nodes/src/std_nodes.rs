@@ -1,10 +1,11 @@
 use crate::adjustments::{CellularDistanceFunction, CellularReturnType, DomainWarpType, FractalType, NoiseType};
+use crate::periodic_noise::{FractalSettings, TileablePerlin};
 use dyn_any::DynAny;
 use fastnoise_lite;
 use glam::{DAffine2, DVec2, Vec2};
 use graphene_core::blending::AlphaBlending;
 use graphene_core::color::Color;
-use graphene_core::color::{Alpha, AlphaMut, Channel, LinearChannel, Luminance, RGBMut};
+use graphene_core::color::{Alpha, LinearChannel, Luminance};
 use graphene_core::context::{Ctx, ExtractFootprint};
 use graphene_core::instances::Instance;
 use graphene_core::math::bbox::Bbox;
@@ -94,14 +95,26 @@ pub fn sample_image(ctx: impl ExtractFootprint + Clone + Send, image_frame: Rast
 		.collect()
 }
 
+/// Assembles an image from grayscale images of its red, green, blue, and alpha channels, such as those produced by the Split Channels node.
+///
+/// Channels of different sizes are aligned at their top left corners, and the result covers the largest of them, with transparent padding where no channel reaches.
+/// A missing color channel is treated as black, and a missing alpha channel as fully opaque.
 #[node_macro::node(category("Raster: Channels"))]
 pub fn combine_channels(
 	_: impl Ctx,
 	_primary: (),
-	#[expose] red: RasterDataTable<CPU>,
-	#[expose] green: RasterDataTable<CPU>,
-	#[expose] blue: RasterDataTable<CPU>,
-	#[expose] alpha: RasterDataTable<CPU>,
+	/// The grayscale image used as the red channel.
+	#[expose]
+	red: RasterDataTable<CPU>,
+	/// The grayscale image used as the green channel.
+	#[expose]
+	green: RasterDataTable<CPU>,
+	/// The grayscale image used as the blue channel.
+	#[expose]
+	blue: RasterDataTable<CPU>,
+	/// The grayscale image used as the alpha channel.
+	#[expose]
+	alpha: RasterDataTable<CPU>,
 ) -> RasterDataTable<CPU> {
 	let max_len = red.len().max(green.len()).max(blue.len()).max(alpha.len());
 	let red = red.instance_iter().map(Some).chain(std::iter::repeat(None)).take(max_len);
@@ -114,62 +127,38 @@ pub fn combine_channels(
 		.zip(alpha)
 		.filter_map(|(((red, green), blue), alpha)| {
 			// Turn any default zero-sized image instances into None
-			let red = red.filter(|i| i.instance.width > 0 && i.instance.height > 0);
-			let green = green.filter(|i| i.instance.width > 0 && i.instance.height > 0);
-			let blue = blue.filter(|i| i.instance.width > 0 && i.instance.height > 0);
-			let alpha = alpha.filter(|i| i.instance.width > 0 && i.instance.height > 0);
+			let channels = [red, green, blue, alpha].map(|channel| channel.filter(|i| i.instance.width > 0 && i.instance.height > 0));
 
 			// Get this instance's transform and alpha blending mode from the first non-empty channel
-			let (transform, alpha_blending, source_node_id) = [&red, &green, &blue, &alpha]
-				.iter()
-				.find_map(|i| i.as_ref())
-				.map(|i| (i.transform, i.alpha_blending, i.source_node_id))?;
-
-			// Get the common width and height of the channels, which must have equal dimensions
-			let channel_dimensions = [
-				red.as_ref().map(|r| (r.instance.width, r.instance.height)),
-				green.as_ref().map(|g| (g.instance.width, g.instance.height)),
-				blue.as_ref().map(|b| (b.instance.width, b.instance.height)),
-				alpha.as_ref().map(|a| (a.instance.width, a.instance.height)),
-			];
-			if channel_dimensions.iter().all(Option::is_none)
-				|| channel_dimensions
-					.iter()
-					.flatten()
-					.any(|&(x, y)| channel_dimensions.iter().flatten().any(|&(other_x, other_y)| x != other_x || y != other_y))
-			{
-				return None;
-			}
-			let &(width, height) = channel_dimensions.iter().flatten().next()?;
+			let first = channels.iter().flatten().next()?;
+			let (transform, alpha_blending, source_node_id) = (first.transform, first.alpha_blending, first.source_node_id);
+
+			// The result covers the largest extent of the channels
+			let width = channels.iter().flatten().map(|i| i.instance.width).max()?;
+			let height = channels.iter().flatten().map(|i| i.instance.height).max()?;
+
+			// Grow the first channel's transform to the larger extent, so pixels keep their size and position
+			let scale = DVec2::new(width as f64 / first.instance.width as f64, height as f64 / first.instance.height as f64);
+			let transform = transform * DAffine2::from_scale(scale);
 
 			// Create a new image for this instance output
 			let mut image = Image::new(width, height, Color::TRANSPARENT);
 
 			// Iterate over all pixels in the image and set the color channels
-			for y in 0..image.height() {
-				for x in 0..image.width() {
-					let image_pixel = image.get_pixel_mut(x, y).unwrap();
-
-					if let Some(r) = red.as_ref().and_then(|r| r.instance.get_pixel(x, y)) {
-						image_pixel.set_red(r.l().cast_linear_channel());
-					} else {
-						image_pixel.set_red(Channel::from_linear(0.));
-					}
-					if let Some(g) = green.as_ref().and_then(|g| g.instance.get_pixel(x, y)) {
-						image_pixel.set_green(g.l().cast_linear_channel());
-					} else {
-						image_pixel.set_green(Channel::from_linear(0.));
-					}
-					if let Some(b) = blue.as_ref().and_then(|b| b.instance.get_pixel(x, y)) {
-						image_pixel.set_blue(b.l().cast_linear_channel());
-					} else {
-						image_pixel.set_blue(Channel::from_linear(0.));
-					}
-					if let Some(a) = alpha.as_ref().and_then(|a| a.instance.get_pixel(x, y)) {
-						image_pixel.set_alpha(a.l().cast_linear_channel());
-					} else {
-						image_pixel.set_alpha(Channel::from_linear(1.));
+			for y in 0..height {
+				for x in 0..width {
+					// Leave the padding beyond every channel's extent transparent
+					if !channels.iter().flatten().any(|i| x < i.instance.width && y < i.instance.height) {
+						continue;
 					}
+
+					// Pixels beyond a provided channel's extent are padded with zero, while missing channels use their default
+					let [red, green, blue, alpha] = [(0, 0.), (1, 0.), (2, 0.), (3, 1.)].map(|(index, missing)| match &channels[index] {
+						Some(channel) => channel.instance.get_pixel(x, y).map_or(0., |pixel| pixel.l()),
+						None => missing,
+					});
+
+					*image.get_pixel_mut(x, y).unwrap() = Color::from_rgbaf32_unchecked(red, green, blue, alpha);
 				}
 			}
 
@@ -321,6 +310,19 @@ pub fn noise_pattern(
 	cellular_distance_function: CellularDistanceFunction,
 	cellular_return_type: CellularReturnType,
 	cellular_jitter: f64,
+	/// Make the noise repeat seamlessly across tiles of the given size, so it can be used as a repeating pattern.
+	///
+	/// Perlin noise fits its lattice to the tile, rounding the number of cells per tile to a whole number.
+	/// Other noise types, and any noise with domain warping, instead blend between copies of the noise offset by one tile, which tiles seamlessly but lowers the contrast towards the middle of each tile.
+	/// White noise is generated per pixel and can't be made tileable.
+	tileable: bool,
+	/// The size of the tile that tileable noise repeats across.
+	#[default(100., 100.)]
+	tile_size: DVec2,
+	/// Sample a slice through 3D noise instead of 2D noise, so animating the Z Slice depth makes the noise evolve smoothly in place rather than scroll.
+	sample_3d: bool,
+	/// The depth of the slice through 3D noise, in the same units as the noise's position, so a change of about the scale gives an entirely different pattern.
+	z_slice: f64,
 ) -> RasterDataTable<CPU> {
 	let footprint = ctx.footprint();
 	let viewport_bounds = footprint.viewport_bounds_in_local_space();
@@ -342,14 +344,36 @@ pub fn noise_pattern(
 		return RasterDataTable::default();
 	}
 
-	let footprint_scale = footprint.scale();
+	// Generate fewer pixels while the render quality is reduced during an interaction
+	let footprint_scale = footprint.scale() * footprint.quality.scale_factor();
 	let width = (size.x * footprint_scale.x) as u32;
 	let height = (size.y * footprint_scale.y) as u32;
 
 	// All
 	let mut image = Image::new(width, height, Color::from_luminance(0.5));
 	let mut noise = fastnoise_lite::FastNoiseLite::with_seed(seed as i32);
-	noise.set_frequency(Some(1. / (scale as f32).max(f32::EPSILON)));
+	let frequency = 1. / (scale as f32).max(f32::EPSILON);
+	noise.set_frequency(Some(frequency));
+
+	// Tiling and slicing
+	let tile_size = tile_size.as_vec2().max(Vec2::splat(f32::EPSILON));
+	let depth = if sample_3d { z_slice as f32 } else { 0. };
+	let domain_warped = domain_warp_type != DomainWarpType::None && domain_warp_amplitude > 0.;
+	let tileable_perlin = (tileable && noise_type == NoiseType::Perlin && !domain_warped).then(|| {
+		let fractal = FractalSettings {
+			fractal_type,
+			octaves: fractal_octaves,
+			lacunarity: fractal_lacunarity as f32,
+			gain: fractal_gain as f32,
+			weighted_strength: fractal_weighted_strength as f32,
+			ping_pong_strength: fractal_ping_pong_strength as f32,
+		};
+		TileablePerlin::new(seed as i32, tile_size * frequency, fractal)
+	});
+	if sample_3d {
+		// Rotates the 3D lattice so slices parallel to the XY plane don't show its grid alignment
+		noise.set_rotation_type_3d(Some(fastnoise_lite::RotationType3D::ImproveXYPlanes));
+	}
 
 	// Domain Warp
 	let domain_warp_type = match domain_warp_type {
@@ -426,6 +450,22 @@ pub fn noise_pattern(
 	noise.set_cellular_return_type(Some(cellular_return_type));
 	noise.set_cellular_jitter(Some(cellular_jitter as f32));
 
+	let sample_noise = |position: Vec2| {
+		if sample_3d {
+			let (mut x, mut y, mut z) = (position.x, position.y, depth);
+			if domain_warp_active && domain_warp_amplitude > 0. {
+				(x, y, z) = noise.domain_warp_3d(x, y, z);
+			}
+			noise.get_noise_3d(x, y, z)
+		} else {
+			let (mut x, mut y) = (position.x, position.y);
+			if domain_warp_active && domain_warp_amplitude > 0. {
+				(x, y) = noise.domain_warp_2d(x, y);
+			}
+			noise.get_noise_2d(x, y)
+		}
+	};
+
 	let coordinate_offset = offset.as_vec2();
 	let scale = size.as_vec2() / Vec2::new(width as f32, height as f32);
 	// Calculate the noise for every pixel
@@ -435,12 +475,13 @@ pub fn noise_pattern(
 			let pos = Vec2::new(x as f32, y as f32);
 			let vec = pos * scale + coordinate_offset;
 
-			let (mut x, mut y) = (vec.x, vec.y);
-			if domain_warp_active && domain_warp_amplitude > 0. {
-				(x, y) = noise.domain_warp_2d(x, y);
-			}
+			let value = match &tileable_perlin {
+				Some(tileable_perlin) => tileable_perlin.sample(vec / tile_size, depth * frequency),
+				None if tileable => blend_across_tile(vec, tile_size, &sample_noise),
+				None => sample_noise(vec),
+			};
 
-			let luminance = (noise.get_noise_2d(x, y) + 1.) * 0.5;
+			let luminance = (value + 1.) * 0.5;
 			*pixel = Color::from_luminance(luminance);
 		}
 	}
@@ -452,6 +493,18 @@ pub fn noise_pattern(
 	})
 }
 
+/// Makes any noise tileable by blending the noise at a position within the tile with the noise at the same position offset by one tile to the left, above, and both.
+/// The blend weights shift from one copy to the next across the tile, so the noise at each edge of the tile continues from the noise at the opposite edge.
+fn blend_across_tile(position: Vec2, tile_size: Vec2, sample_noise: impl Fn(Vec2) -> f32) -> f32 {
+	let position = position.rem_euclid(tile_size);
+	let weight = position / tile_size;
+	let lerp = |a: f32, b: f32, t: f32| a + (b - a) * t;
+
+	let top = lerp(sample_noise(position), sample_noise(position - Vec2::new(tile_size.x, 0.)), weight.x);
+	let bottom = lerp(sample_noise(position - Vec2::new(0., tile_size.y)), sample_noise(position - tile_size), weight.x);
+	lerp(top, bottom, weight.y)
+}
+
 #[node_macro::node(category("Raster: Pattern"))]
 pub fn mandelbrot(ctx: impl ExtractFootprint + Send) -> RasterDataTable<CPU> {
 	let footprint = ctx.footprint();
@@ -515,3 +568,88 @@ fn map_color(iter: usize, max_iter: usize) -> Color {
 	let v = iter as f32 / max_iter as f32;
 	Color::from_rgbaf32_unchecked(v, v, v, 1.)
 }
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use glam::UVec2;
+	use graphene_core::context::OwnedContextImpl;
+	use graphene_core::transform::Footprint;
+
+	/// Generates 200×200 pixels of noise with one pixel per document unit, tiling every 50 units when tileable.
+	fn noise(noise_type: NoiseType, tileable: bool, sample_3d: bool, z_slice: f64) -> Image<Color> {
+		let footprint = Footprint {
+			transform: DAffine2::IDENTITY,
+			resolution: UVec2::new(200, 200),
+			..Default::default()
+		};
+		let ctx = OwnedContextImpl::default().with_footprint(footprint).into_context();
+		let result = noise_pattern(
+			ctx,
+			(),
+			false,
+			0,
+			10.,
+			noise_type,
+			DomainWarpType::None,
+			0.,
+			FractalType::FBm,
+			3,
+			2.,
+			0.5,
+			0.,
+			2.,
+			CellularDistanceFunction::Euclidean,
+			CellularReturnType::Nearest,
+			1.,
+			tileable,
+			DVec2::splat(50.),
+			sample_3d,
+			z_slice,
+		);
+		result.instance_ref_iter().next().unwrap().instance.data().clone()
+	}
+
+	/// The largest difference in luminance between a row and column of pixels and the ones a given number of pixels further along.
+	fn edge_difference(image: &Image<Color>, first: u32, second: u32) -> f32 {
+		(0..image.height)
+			.flat_map(|along| {
+				let column = (image.get_pixel(first, along).unwrap(), image.get_pixel(second, along).unwrap());
+				let row = (image.get_pixel(along, first).unwrap(), image.get_pixel(along, second).unwrap());
+				[column, row]
+			})
+			.map(|(a, b)| (a.r() - b.r()).abs())
+			.fold(0., f32::max)
+	}
+
+	#[test]
+	fn tileable_noise_matches_across_tile_edges() {
+		for noise_type in [NoiseType::Perlin, NoiseType::OpenSimplex2, NoiseType::Cellular] {
+			for sample_3d in [false, true] {
+				let image = noise(noise_type, true, sample_3d, 3.);
+				for tile in 1..4 {
+					assert!(edge_difference(&image, 0, tile * 50) < 1e-3, "{noise_type:?}, 3D: {sample_3d}");
+					assert!(edge_difference(&image, 49, tile * 50 + 49) < 1e-3, "{noise_type:?}, 3D: {sample_3d}");
+				}
+			}
+		}
+
+		// Without tiling the noise doesn't repeat
+		let image = noise(NoiseType::Perlin, false, false, 0.);
+		assert!(edge_difference(&image, 0, 50) > 0.05);
+	}
+
+	#[test]
+	fn z_slice_evolves_continuously() {
+		for tileable in [false, true] {
+			let mean_difference = |z_slice: f64| {
+				let (a, b) = (noise(NoiseType::Perlin, tileable, true, 0.), noise(NoiseType::Perlin, tileable, true, z_slice));
+				a.data.iter().zip(&b.data).map(|(a, b)| (a.r() - b.r()).abs()).sum::<f32>() / a.data.len() as f32
+			};
+
+			// Nudging the slice barely changes the noise, while moving it by several times the scale gives a different pattern
+			assert!(mean_difference(0.05) < 0.02, "tileable: {tileable}");
+			assert!(mean_difference(40.) > 0.05, "tileable: {tileable}");
+		}
+	}
+}
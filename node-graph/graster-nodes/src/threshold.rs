@@ -0,0 +1,259 @@
+//! Not immediately shader compatible due to dithering needing the position of each pixel, and error diffusion needing the results of its neighbors
+
+use crate::adjustments::LuminanceCalculation;
+use dyn_any::DynAny;
+use graphene_core::raster::image::Image;
+use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
+use graphene_core::registry::types::Percentage;
+use graphene_core::{Color, Ctx};
+use rayon::prelude::*;
+
+/// The number of rows in each tile of the image that error diffusion is run on in parallel.
+///
+/// Error diffusion is inherently serial, since each pixel depends on the error left by the pixels above and before it.
+/// To still make use of multiple threads, the image is split into horizontal tiles of this many rows which are each diffused independently.
+/// The error which would be diffused below the last row of a tile is discarded rather than carried into the next tile,
+/// so the pattern may be slightly discontinuous at tile boundaries. The tiles are always the same size, so the result doesn't depend on the number of threads.
+const DIFFUSION_TILE_ROWS: usize = 64;
+
+/// How the gradations between the dark and light colors are approximated by a pattern of pixels.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash, serde::Serialize, serde::Deserialize, DynAny, specta::Type, node_macro::ChoiceType)]
+#[widget(Dropdown)]
+pub enum DitheringMode {
+	/// Each pixel becomes whichever color is on its side of the threshold.
+	#[default]
+	None,
+	/// A repeating 4×4 Bayer matrix offsets the threshold of each pixel, producing a regular crosshatch pattern.
+	#[label("Ordered (Bayer 4×4)")]
+	Bayer4x4,
+	/// A repeating 8×8 Bayer matrix offsets the threshold of each pixel, producing a finer crosshatch pattern with more levels.
+	#[label("Ordered (Bayer 8×8)")]
+	Bayer8x8,
+	/// The error between each pixel and its chosen color is spread to its unvisited neighbors, producing an organic stippled pattern.
+	#[label("Floyd–Steinberg")]
+	FloydSteinberg,
+}
+
+// Aims for interoperable compatibility with:
+// https://www.adobe.com/devnet-apps/photoshop/fileformatashtml/#:~:text=post%27%20%3D%20Posterize-,%27thrs%27%20%3D%20Threshold,-%27grdm%27%20%3D%20Gradient
+#[node_macro::node(category("Raster: Adjustment"))]
+async fn threshold(
+	_: impl Ctx,
+	image: RasterDataTable<CPU>,
+	/// The luminance at and above which pixels become the light color.
+	#[default(50.)]
+	luminance_threshold: Percentage,
+	luminance_calc: LuminanceCalculation,
+	/// How the gradations between the dark and light colors are approximated by a pattern of pixels.
+	dithering: DitheringMode,
+	/// The color of the pixels below the threshold.
+	#[default(Color::BLACK)]
+	dark_color: Color,
+	/// The color of the pixels at or above the threshold.
+	#[default(Color::WHITE)]
+	light_color: Color,
+) -> RasterDataTable<CPU> {
+	let settings = ThresholdSettings {
+		threshold: (luminance_threshold / 100.) as f32,
+		luminance_calc,
+		dithering,
+		dark_color,
+		light_color,
+	};
+
+	image
+		.instance_iter()
+		.map(|mut image_instance| {
+			let image = image_instance.instance.clone();
+			image_instance.instance = Raster::new_cpu(threshold_image(image.into_data(), settings));
+			image_instance
+		})
+		.collect()
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ThresholdSettings {
+	threshold: f32,
+	luminance_calc: LuminanceCalculation,
+	dithering: DitheringMode,
+	dark_color: Color,
+	light_color: Color,
+}
+
+fn threshold_image(mut image: Image<Color>, settings: ThresholdSettings) -> Image<Color> {
+	let width = image.width as usize;
+	if width == 0 || image.height == 0 {
+		return image;
+	}
+
+	// The luminance is compared to the threshold in gamma space, so the threshold and the dithered gradations are perceptually even
+	let values: Vec<f32> = image
+		.data
+		.par_iter()
+		.map(|color| Color::linear_to_srgb(settings.luminance_calc.luminance(&color.to_unassociated_alpha())))
+		.collect();
+
+	let threshold = settings.threshold;
+	let light: Vec<bool> = match settings.dithering {
+		DitheringMode::None => values.par_iter().map(|&value| value >= threshold).collect(),
+		DitheringMode::Bayer4x4 => ordered_dither(&values, width, threshold, 4),
+		DitheringMode::Bayer8x8 => ordered_dither(&values, width, threshold, 8),
+		DitheringMode::FloydSteinberg => values.par_chunks(width * DIFFUSION_TILE_ROWS).flat_map_iter(|tile| diffuse_errors(tile, width, threshold)).collect(),
+	};
+
+	// The chosen color takes on the transparency of the original pixel
+	image.data.par_iter_mut().zip(light).for_each(|(color, light)| {
+		let chosen = if light { settings.light_color } else { settings.dark_color };
+		*color = chosen.with_alpha(1.).to_associated_alpha(chosen.a() * color.a());
+	});
+
+	image
+}
+
+/// Compares each pixel to the threshold offset by its entry in a tiled Bayer matrix of the given size, which must be a power of two.
+fn ordered_dither(values: &[f32], width: usize, threshold: f32, size: usize) -> Vec<bool> {
+	let mut light = vec![false; values.len()];
+	light.par_chunks_mut(width).zip(values.par_chunks(width)).enumerate().for_each(|(y, (light_row, row))| {
+		for (x, (light, &value)) in light_row.iter_mut().zip(row).enumerate() {
+			// Center the offsets around zero so the threshold remains the average
+			let offset = (bayer_index(size, x % size, y % size) as f32 + 0.5) / (size * size) as f32 - 0.5;
+			*light = value >= threshold + offset;
+		}
+	});
+	light
+}
+
+/// The order in which the cell at the given position within a Bayer matrix of the given size is filled.
+/// Each matrix is built from four copies of the matrix half its size, interleaved in the pattern of the 2×2 matrix.
+fn bayer_index(size: usize, x: usize, y: usize) -> usize {
+	const BAYER_2X2: [[usize; 2]; 2] = [[0, 2], [3, 1]];
+
+	if size <= 1 {
+		return 0;
+	}
+
+	let half = size / 2;
+	4 * bayer_index(half, x % half, y % half) + BAYER_2X2[y / half][x / half]
+}
+
+/// Runs Floyd–Steinberg error diffusion over a tile of rows, discarding the error spread beyond its edges.
+fn diffuse_errors(tile: &[f32], width: usize, threshold: f32) -> Vec<bool> {
+	let height = tile.len() / width;
+	let mut values = tile.to_vec();
+	let mut light = vec![false; tile.len()];
+
+	for y in 0..height {
+		for x in 0..width {
+			let index = y * width + x;
+			light[index] = values[index] >= threshold;
+			let error = values[index] - if light[index] { 1. } else { 0. };
+
+			if x + 1 < width {
+				values[index + 1] += error * 7. / 16.;
+			}
+			if y + 1 < height {
+				if x > 0 {
+					values[index + width - 1] += error * 3. / 16.;
+				}
+				values[index + width] += error * 5. / 16.;
+				if x + 1 < width {
+					values[index + width + 1] += error * 1. / 16.;
+				}
+			}
+		}
+	}
+
+	light
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	/// A 16×4 grayscale ramp from black to white, evenly spaced in gamma space.
+	fn ramp() -> RasterDataTable<CPU> {
+		let row = (0..16).map(|x| {
+			let value = x as f32 / 15.;
+			Color::from_rgbaf32_unchecked(value, value, value, 1.).to_linear_srgb()
+		});
+		let data = (0..4).flat_map(|_| row.clone()).collect();
+
+		RasterDataTable::new(Raster::new_cpu(Image {
+			width: 16,
+			height: 4,
+			data,
+			base64_string: None,
+		}))
+	}
+
+	fn threshold_ramp(dithering: DitheringMode) -> Vec<String> {
+		let result = futures::executor::block_on(threshold((), ramp(), 50., LuminanceCalculation::SRGB, dithering, Color::BLACK, Color::WHITE));
+		let image = result.instance_ref_iter().next().unwrap().instance.data().clone();
+
+		assert!(image.data.iter().all(|&color| color == Color::BLACK || color == Color::WHITE));
+		image
+			.data
+			.chunks(16)
+			.map(|row| row.iter().map(|&color| if color == Color::WHITE { '1' } else { '0' }).collect())
+			.collect()
+	}
+
+	#[test]
+	fn bayer_matrices() {
+		let matrix = |size| (0..size).map(|y| (0..size).map(|x| bayer_index(size, x, y)).collect::<Vec<_>>()).collect::<Vec<_>>();
+
+		assert_eq!(matrix(2), [[0, 2], [3, 1]]);
+		assert_eq!(matrix(4), [[0, 8, 2, 10], [12, 4, 14, 6], [3, 11, 1, 9], [15, 7, 13, 5]]);
+
+		// Every level appears exactly once
+		let mut levels = matrix(8).concat();
+		levels.sort();
+		assert_eq!(levels, (0..64).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn no_dithering_golden_ramp() {
+		assert_eq!(threshold_ramp(DitheringMode::None), ["0000000011111111", "0000000011111111", "0000000011111111", "0000000011111111"]);
+	}
+
+	#[test]
+	fn bayer_4x4_golden_ramp() {
+		assert_eq!(
+			threshold_ramp(DitheringMode::Bayer4x4),
+			["0000101011111111", "0000010101011111", "0010101010111111", "0000000101010111"]
+		);
+	}
+
+	#[test]
+	fn bayer_8x8_golden_ramp() {
+		assert_eq!(
+			threshold_ramp(DitheringMode::Bayer8x8),
+			["0010101011111111", "0000010101011111", "0010101010111111", "0000000101010111"]
+		);
+	}
+
+	#[test]
+	fn floyd_steinberg_golden_ramp() {
+		assert_eq!(
+			threshold_ramp(DitheringMode::FloydSteinberg),
+			["0000010101111111", "0000100101010111", "0000010101101111", "0001000101011111"]
+		);
+	}
+
+	#[test]
+	fn custom_colors_preserve_alpha() {
+		let image = RasterDataTable::new(Raster::new_cpu(Image {
+			width: 2,
+			height: 1,
+			data: vec![Color::WHITE.to_associated_alpha(0.5), Color::TRANSPARENT],
+			base64_string: None,
+		}));
+
+		let result = futures::executor::block_on(threshold((), image, 50., LuminanceCalculation::SRGB, DitheringMode::None, Color::BLUE, Color::RED));
+		let data = &result.instance_ref_iter().next().unwrap().instance.data().data;
+
+		// The half-transparent white pixel is light by its unpremultiplied color, and the transparent pixel stays transparent
+		assert_eq!(data[0], Color::RED.to_associated_alpha(0.5));
+		assert_eq!(data[1].a(), 0.);
+	}
+}
@@ -0,0 +1,566 @@
+use glam::{DAffine2, DVec2, UVec2};
+use graphene_core::cancellation;
+use graphene_core::color::Color;
+use graphene_core::context::ExtractFootprint;
+use graphene_core::instances::Instance;
+use graphene_core::math::bbox::Bbox;
+use graphene_core::raster::image::Image;
+use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
+use graphene_core::transform::Footprint;
+use graphene_core::{CloneVarArgs, Context, Ctx, ExtractAll, OwnedContextImpl};
+use rayon::prelude::*;
+
+/// The width and height, in pixels, of the square tiles which large images are split into so they can be processed in parallel and culled to the viewport.
+/// Tiles along the right and bottom edges of an image are smaller when its size isn't a multiple of this.
+pub const TILE_SIZE: u32 = 256;
+
+/// The position of a tile within the grid of tiles covering an image, counted in tiles from the top left.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct TileCoord {
+	pub x: u32,
+	pub y: u32,
+}
+
+/// The grid of [`TILE_SIZE`] tiles covering an image of the given size.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TileGrid {
+	pub width: u32,
+	pub height: u32,
+}
+
+impl TileGrid {
+	pub fn new(width: u32, height: u32) -> Self {
+		Self { width, height }
+	}
+
+	/// The number of tile columns and rows, including partial tiles along the edges.
+	pub fn dimensions(&self) -> UVec2 {
+		UVec2::new(self.width.div_ceil(TILE_SIZE), self.height.div_ceil(TILE_SIZE))
+	}
+
+	/// Every tile of the grid, in row-major order.
+	pub fn tiles(&self) -> impl Iterator<Item = TileCoord> {
+		let UVec2 { x: columns, y: rows } = self.dimensions();
+		(0..rows).flat_map(move |y| (0..columns).map(move |x| TileCoord { x, y }))
+	}
+
+	/// The pixel range covered by a tile, as its top left corner (inclusive) and bottom right corner (exclusive).
+	pub fn pixel_bounds(&self, tile: TileCoord) -> [UVec2; 2] {
+		let start = UVec2::new(tile.x, tile.y) * TILE_SIZE;
+		let end = (start + TILE_SIZE).min(UVec2::new(self.width, self.height));
+		[start, end]
+	}
+
+	/// The range of tiles covering the given pixel range, as the top left tile (inclusive) and bottom right tile (exclusive).
+	fn tiles_covering(&self, [start, end]: [UVec2; 2]) -> [UVec2; 2] {
+		let end = end.min(UVec2::new(self.width, self.height));
+		[start / TILE_SIZE, end.saturating_sub(UVec2::ONE) / TILE_SIZE + UVec2::ONE]
+	}
+
+	/// The tiles of an image with the given transform which intersect the viewport of the footprint.
+	/// The transform maps the unit square to the image's bounds in the footprint's local space, as with the transforms of raster instances.
+	pub fn visible_tiles(&self, image_transform: DAffine2, footprint: &Footprint) -> Vec<TileCoord> {
+		let Some([start, end]) = self.visible_pixel_bounds(image_transform, footprint) else {
+			return Vec::new();
+		};
+		let [first, last] = self.tiles_covering([start, end]);
+		(first.y..last.y).flat_map(|y| (first.x..last.x).map(move |x| TileCoord { x, y })).collect()
+	}
+
+	/// The pixel range of an image which intersects the viewport of the footprint, or `None` if none of it is visible.
+	fn visible_pixel_bounds(&self, image_transform: DAffine2, footprint: &Footprint) -> Option<[UVec2; 2]> {
+		if self.width == 0 || self.height == 0 || image_transform.matrix2.determinant() == 0. {
+			return None;
+		}
+
+		// Measure the viewport in the image's unit square, which handles rotated and skewed images by taking the bounding box of the viewport
+		let viewport_in_image = image_transform.inverse() * footprint.viewport_bounds_in_local_space().to_transform();
+		let viewport = Bbox::from_transform(viewport_in_image).to_axis_aligned_bbox();
+		let visible = viewport.intersect(&Bbox::unit().to_axis_aligned_bbox());
+		if visible.size().x <= 0. || visible.size().y <= 0. {
+			return None;
+		}
+
+		let size = DVec2::new(self.width as f64, self.height as f64);
+		let start = (visible.start * size).floor().max(DVec2::ZERO).as_uvec2();
+		let end = (visible.end * size).ceil().min(size).as_uvec2();
+		Some([start, end])
+	}
+}
+
+/// Maps every pixel of an image, processing each row of tiles in parallel.
+/// Since the pixels are stored in row-major order, a row of tiles is a contiguous range of the image's data which can be handed to its own thread.
+/// Mapping stops partway, leaving the remaining pixels unchanged, once the evaluation running it has been superseded by a newer one.
+pub fn map_pixels_in_tiles(image: &mut Image<Color>, map_fn: impl Fn(&Color) -> Color + Sync) {
+	let row_length = image.width as usize;
+	if row_length == 0 {
+		return;
+	}
+
+	let cancellation = cancellation::current();
+	image.data.par_chunks_mut(row_length * TILE_SIZE as usize).for_each(|tile_row| {
+		for row in tile_row.chunks_mut(row_length) {
+			if cancellation.is_cancelled() {
+				return;
+			}
+			for color in row {
+				*color = map_fn(color);
+			}
+		}
+	});
+}
+
+/// Crops an image to the given tile-aligned pixel range, returning the cropped image and the transform of its bounds within the original image's unit square.
+fn crop_to_pixel_bounds(image: &Image<Color>, [start, end]: [UVec2; 2]) -> (Image<Color>, DAffine2) {
+	let (width, height) = (end.x - start.x, end.y - start.y);
+	let data = (start.y..end.y)
+		.flat_map(|y| {
+			let row_start = (y * image.width + start.x) as usize;
+			image.data[row_start..row_start + width as usize].iter().copied()
+		})
+		.collect();
+
+	let size = DVec2::new(image.width as f64, image.height as f64);
+	let offset = start.as_dvec2() / size;
+	let scale = DVec2::new(width as f64, height as f64) / size;
+	let cropped = Image {
+		width,
+		height,
+		data,
+		base64_string: None,
+	};
+
+	(cropped, DAffine2::from_translation(offset) * DAffine2::from_scale(scale))
+}
+
+/// Downsamples an image by a whole number factor along each axis, averaging each block of pixels into one.
+/// The blocks along the right and bottom edges are partial when the image's size isn't a multiple of the factor, so they average only the pixels they contain.
+fn downsample(image: &Image<Color>, factor: u32) -> Image<Color> {
+	let (width, height) = (image.width.div_ceil(factor), image.height.div_ceil(factor));
+	let data = (0..height)
+		.into_par_iter()
+		.flat_map_iter(|y| {
+			(0..width).map(move |x| {
+				let [start, end] = [UVec2::new(x, y) * factor, (UVec2::new(x + 1, y + 1) * factor).min(UVec2::new(image.width, image.height))];
+
+				// Since the colors have premultiplied alpha, averaging them weights each pixel's color by its opacity
+				let mut sum = [0.; 4];
+				for y in start.y..end.y {
+					for color in &image.data[(y * image.width + start.x) as usize..(y * image.width + end.x) as usize] {
+						let (r, g, b, a) = color.components();
+						sum = [sum[0] + r, sum[1] + g, sum[2] + b, sum[3] + a];
+					}
+				}
+				let count = ((end.x - start.x) * (end.y - start.y)) as f32;
+				Color::from_rgbaf32_unchecked(sum[0] / count, sum[1] / count, sum[2] / count, sum[3] / count)
+			})
+		})
+		.collect();
+
+	Image {
+		width,
+		height,
+		data,
+		base64_string: None,
+	}
+}
+
+/// Crops images to the tiles which intersect the viewport, so downstream adjustments only process the visible part of large images.
+///
+/// Unlike cropping to the exact viewport, the crop snaps to the [`TILE_SIZE`] grid, so small pans which stay within the same tiles produce an identical image.
+/// The crop also keeps the apron of pixels around the viewport requested by the footprint, so blurs and other spatial filters downstream read the same neighboring pixels as they would from the whole image.
+/// This is suited to the interactive preview of a document rather than export, where the viewport covers everything anyway.
+/// While the render quality is reduced during an interaction, such as dragging a slider, the images are also downsampled to that fraction of their resolution.
+#[node_macro::node(category("Raster"))]
+pub fn crop_to_visible_tiles(ctx: impl ExtractFootprint + Clone + Send, image: RasterDataTable<CPU>) -> RasterDataTable<CPU> {
+	let footprint = ctx.footprint();
+	let downsampling = (1. / footprint.quality.scale_factor()).round() as u32;
+
+	// Snapping to whole tiles of the downsampled image keeps the blocks of pixels averaged by the downsampling, and by blurs downstream, in the same place as for the whole image
+	let block_size = TILE_SIZE * downsampling;
+	// The apron is measured in pixels of the downsampled image which the nodes downstream receive
+	let apron = UVec2::splat(footprint.apron.saturating_mul(downsampling));
+
+	image
+		.instance_iter()
+		.filter_map(|mut image_instance| {
+			let grid = TileGrid::new(image_instance.instance.width, image_instance.instance.height);
+			let size = UVec2::new(grid.width, grid.height);
+			let [start, end] = grid.visible_pixel_bounds(image_instance.transform, footprint)?;
+
+			let start = start.saturating_sub(apron) / block_size * block_size;
+			let end = end.saturating_add(apron).min(size);
+			let end = (UVec2::new(end.x.div_ceil(block_size), end.y.div_ceil(block_size)) * block_size).min(size);
+			if [start, end] != [UVec2::ZERO, size] {
+				let (cropped, crop_transform) = crop_to_pixel_bounds(&image_instance.instance, [start, end]);
+				image_instance.instance = Raster::new_cpu(cropped);
+				image_instance.transform = image_instance.transform * crop_transform;
+			}
+
+			// The downsampled image still spans the same bounds, since its transform maps the unit square regardless of its resolution
+			if downsampling > 1 {
+				image_instance.instance = Raster::new_cpu(downsample(&image_instance.instance, downsampling));
+			}
+
+			Some(image_instance)
+		})
+		.collect()
+}
+
+/// Renders the images one [`TILE_SIZE`] tile of the viewport at a time, evaluating the input separately for each tile so the memoization before this node caches every tile on its own.
+///
+/// The tiles are anchored to the document rather than to the viewport, so the footprint which each tile is evaluated with stays the same while panning.
+/// Panning then only evaluates the newly visible tiles and reuses the cached ones which stay visible, while zooming or rotating renders every tile anew.
+/// Each tile's footprint keeps the apron of the viewport's footprint, and blurs upstream extend it by their own reach, so neighboring tiles line up without seams.
+#[node_macro::node(category(""))]
+async fn render_visible_tiles(ctx: impl Ctx + ExtractAll + CloneVarArgs, image: impl Node<Context<'static>, Output = RasterDataTable<CPU>>) -> RasterDataTable<CPU> {
+	// Without a finite viewport, such as while exporting the whole document, there are no tiles to split the render into
+	let footprint = ctx.try_footprint().copied();
+	let Some(footprint) = footprint.filter(|footprint| footprint.transform.is_finite() && footprint.transform.matrix2.determinant() != 0.) else {
+		return image.eval(OwnedContextImpl::from(ctx).into_context()).await;
+	};
+
+	let mut tiles = RasterDataTable::default();
+	for tile_footprint in tile_footprints(&footprint) {
+		let rendered = image.eval(OwnedContextImpl::from(ctx.clone()).with_footprint(tile_footprint).into_context()).await;
+		if cancellation::is_cancelled() {
+			break;
+		}
+		if rendered.is_empty() {
+			continue;
+		}
+
+		tiles.push(Instance {
+			instance: Raster::new_cpu(draw_into_tile(&rendered, &tile_footprint)),
+			transform: tile_footprint.transform.inverse() * DAffine2::from_scale(DVec2::splat(TILE_SIZE as f64)),
+			..Default::default()
+		});
+	}
+	tiles
+}
+
+/// The footprints of the [`TILE_SIZE`] tiles which cover the viewport, in row-major order.
+///
+/// The tiles form a grid over the document as scaled, rotated, and skewed by the viewport, but not translated, so panning shifts which tiles are visible without changing their footprints.
+fn tile_footprints(footprint: &Footprint) -> Vec<Footprint> {
+	let tile_size = TILE_SIZE as f64;
+	let linear = DAffine2::from_mat2(footprint.transform.matrix2);
+
+	// The viewport's pixels are the tile grid's pixels offset by the viewport's translation
+	let start = (-footprint.transform.translation / tile_size).floor();
+	let end = ((footprint.resolution.as_dvec2() - footprint.transform.translation) / tile_size).ceil();
+	let (start, end) = (start.as_ivec2(), end.as_ivec2());
+
+	(start.y..end.y)
+		.flat_map(|y| (start.x..end.x).map(move |x| DVec2::new(x as f64, y as f64) * tile_size))
+		.map(|tile_start| Footprint {
+			transform: DAffine2::from_translation(-tile_start) * linear,
+			resolution: UVec2::splat(TILE_SIZE),
+			..*footprint
+		})
+		.collect()
+}
+
+/// Draws the images, in order from bottom to top, into the pixels of a tile as seen through its footprint.
+fn draw_into_tile(images: &RasterDataTable<CPU>, tile_footprint: &Footprint) -> Image<Color> {
+	let mut tile = Image::new(TILE_SIZE, TILE_SIZE, Color::TRANSPARENT);
+	let tile_to_document = tile_footprint.transform.inverse();
+
+	for image_instance in images.instance_ref_iter() {
+		let image = image_instance.instance.data();
+		if image.width == 0 || image.height == 0 || image_instance.transform.matrix2.determinant() == 0. {
+			continue;
+		}
+
+		let size = DVec2::new(image.width as f64, image.height as f64);
+		let tile_to_image = DAffine2::from_scale(size) * image_instance.transform.inverse() * tile_to_document;
+		let opacity = image_instance.alpha_blending.opacity(false);
+
+		tile.data.par_chunks_mut(TILE_SIZE as usize).enumerate().for_each(|(y, row)| {
+			for (x, pixel) in row.iter_mut().enumerate() {
+				let point = tile_to_image.transform_point2(DVec2::new(x as f64 + 0.5, y as f64 + 0.5));
+				if point.cmplt(DVec2::ZERO).any() || point.cmpge(size).any() {
+					continue;
+				}
+
+				// Since the colors have premultiplied alpha, they are interpolated and composited without unmultiplying them
+				let color = sample_bilinear(image, point - 0.5).apply_opacity(opacity);
+				*pixel = pixel.alpha_blend(color);
+			}
+		});
+	}
+
+	tile
+}
+
+/// Interpolates between the four pixels nearest to the given position, measured in pixels from the center of the top left pixel, repeating the pixels along the edges outward.
+fn sample_bilinear(image: &Image<Color>, position: DVec2) -> Color {
+	let max = UVec2::new(image.width - 1, image.height - 1).as_dvec2();
+	let position = position.clamp(DVec2::ZERO, max);
+	let top_left = position.floor();
+	let fraction = (position - top_left).as_vec2();
+
+	let pixel = |offset: DVec2| {
+		let position = (top_left + offset).min(max).as_uvec2();
+		image.data[(position.y * image.width + position.x) as usize]
+	};
+	let top = pixel(DVec2::ZERO).lerp(&pixel(DVec2::X), fraction.x);
+	let bottom = pixel(DVec2::Y).lerp(&pixel(DVec2::ONE), fraction.x);
+	top.lerp(&bottom, fraction.y)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::Node;
+	use std::pin::Pin;
+
+	/// Provides the same image to the node being tested regardless of the footprint it's evaluated with.
+	struct Upstream(RasterDataTable<CPU>);
+
+	impl<'i> Node<'i, Context<'static>> for Upstream {
+		type Output = Pin<Box<dyn Future<Output = RasterDataTable<CPU>> + 'i + Send>>;
+		fn eval(&'i self, _: Context<'static>) -> Self::Output {
+			let image = self.0.clone();
+			Box::pin(async move { image })
+		}
+	}
+
+	fn context(footprint: Footprint) -> Context<'static> {
+		OwnedContextImpl::default().with_footprint(footprint).into_context()
+	}
+
+	#[test]
+	fn grid_covers_partial_edge_tiles() {
+		let grid = TileGrid::new(600, 256);
+
+		assert_eq!(grid.dimensions(), UVec2::new(3, 1));
+		assert_eq!(grid.tiles().count(), 3);
+		assert_eq!(grid.pixel_bounds(TileCoord { x: 0, y: 0 }), [UVec2::new(0, 0), UVec2::new(256, 256)]);
+		assert_eq!(grid.pixel_bounds(TileCoord { x: 2, y: 0 }), [UVec2::new(512, 0), UVec2::new(600, 256)]);
+
+		assert_eq!(TileGrid::new(0, 100).tiles().count(), 0);
+	}
+
+	#[test]
+	fn visible_tiles_intersect_the_viewport() {
+		// A 1024×1024 image placed at the origin with one document unit per pixel
+		let grid = TileGrid::new(1024, 1024);
+		let image_transform = DAffine2::from_scale(DVec2::splat(1024.));
+
+		// A 300×300 viewport starting partway into the second tile in each direction
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-300., -260.)),
+			resolution: UVec2::new(300, 300),
+			..Default::default()
+		};
+		let visible = grid.visible_tiles(image_transform, &footprint);
+		assert_eq!(visible, [TileCoord { x: 1, y: 1 }, TileCoord { x: 2, y: 1 }, TileCoord { x: 1, y: 2 }, TileCoord { x: 2, y: 2 }]);
+
+		// Zooming out to see everything makes every tile visible, and panning away makes none of them visible
+		let zoomed_out = Footprint {
+			transform: DAffine2::from_scale(DVec2::splat(0.25)),
+			resolution: UVec2::new(300, 300),
+			..Default::default()
+		};
+		assert_eq!(grid.visible_tiles(image_transform, &zoomed_out).len(), 16);
+
+		let panned_away = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(2000., 0.)),
+			resolution: UVec2::new(300, 300),
+			..Default::default()
+		};
+		assert!(grid.visible_tiles(image_transform, &panned_away).is_empty());
+	}
+
+	#[test]
+	fn tiled_mapping_matches_whole_image_mapping() {
+		// A size which isn't a multiple of the tile size, so the last row of tiles is partial
+		let data: Vec<Color> = (0..300 * 600)
+			.map(|index| Color::from_rgbaf32_unchecked((index % 300) as f32 / 300., (index / 300) as f32 / 600., 0.5, 1.))
+			.collect();
+		let mut image = Image {
+			width: 300,
+			height: 600,
+			data: data.clone(),
+			base64_string: None,
+		};
+
+		let invert = |color: &Color| color.map_rgb(|channel| 1. - channel);
+		map_pixels_in_tiles(&mut image, invert);
+		assert_eq!(image.data, data.iter().map(invert).collect::<Vec<_>>());
+	}
+
+	#[test]
+	fn crop_snaps_to_visible_tiles() {
+		let data: Vec<Color> = (0..600 * 600)
+			.map(|index| Color::from_rgbaf32_unchecked((index % 600) as f32 / 600., (index / 600) as f32 / 600., 0., 1.))
+			.collect();
+		let image = Image {
+			width: 600,
+			height: 600,
+			data,
+			base64_string: None,
+		};
+		let image_transform = DAffine2::from_scale(DVec2::splat(600.));
+		let table = RasterDataTable::new_instance(graphene_core::instances::Instance {
+			instance: Raster::new_cpu(image.clone()),
+			transform: image_transform,
+			..Default::default()
+		});
+
+		// A viewport within the middle tile
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-300., -300.)),
+			resolution: UVec2::new(100, 100),
+			..Default::default()
+		};
+		let cropped = crop_to_visible_tiles(graphene_core::context::OwnedContextImpl::default().with_footprint(footprint).into_context(), table);
+		let cropped = cropped.instance_ref_iter().next().unwrap();
+
+		// The crop keeps the middle tile's pixels at their original position in the document
+		let data = &cropped.instance.data();
+		assert_eq!((data.width, data.height), (256, 256));
+		assert_eq!(data.data[0], image.data[256 * 600 + 256]);
+		assert_eq!(cropped.transform.transform_point2(DVec2::ZERO), DVec2::splat(256.));
+		assert_eq!(cropped.transform.transform_point2(DVec2::ONE), DVec2::splat(512.));
+	}
+
+	#[test]
+	fn crop_downsamples_at_reduced_quality() {
+		let data: Vec<Color> = (0..600 * 600).map(|index| Color::from_luminance(((index % 600) % 4) as f32 / 4.)).collect();
+		let table = RasterDataTable::new_instance(graphene_core::instances::Instance {
+			instance: Raster::new_cpu(Image {
+				width: 600,
+				height: 600,
+				data,
+				base64_string: None,
+			}),
+			transform: DAffine2::from_scale(DVec2::splat(600.)),
+			..Default::default()
+		});
+
+		// The same viewport within the middle tile, rendered at a quarter of the resolution during an interaction
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-300., -300.)),
+			resolution: UVec2::new(100, 100),
+			quality: graphene_core::transform::RenderQuality::Scale(0.25),
+			..Default::default()
+		};
+		let cropped = crop_to_visible_tiles(graphene_core::context::OwnedContextImpl::default().with_footprint(footprint).into_context(), table);
+		let cropped = cropped.instance_ref_iter().next().unwrap();
+
+		// Each pixel averages a 4×4 block, which spans one repetition of the gradient
+		// The crop snaps to tiles of the downsampled image, each spanning 1024 of the original pixels, so it keeps all of this image
+		let data = &cropped.instance.data();
+		assert_eq!((data.width, data.height), (150, 150));
+		assert!(data.data.iter().all(|color| (color.r() - 0.375).abs() < 1e-6));
+		assert_eq!(cropped.transform.transform_point2(DVec2::ZERO), DVec2::ZERO);
+		assert_eq!(cropped.transform.transform_point2(DVec2::ONE), DVec2::splat(600.));
+	}
+
+	#[test]
+	fn crop_keeps_the_apron_around_the_viewport() {
+		// A viewport near the top left corner of the middle tile
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-260., -260.)),
+			resolution: UVec2::new(40, 40),
+			..Default::default()
+		};
+		let crop_bounds = |apron| {
+			let image = RasterDataTable::new_instance(Instance {
+				instance: Raster::new_cpu(Image::new(600, 600, Color::WHITE)),
+				transform: DAffine2::from_scale(DVec2::splat(600.)),
+				..Default::default()
+			});
+			let cropped = crop_to_visible_tiles(context(Footprint { apron, ..footprint }), image);
+			let transform = *cropped.instance_ref_iter().next().unwrap().transform;
+			[transform.transform_point2(DVec2::ZERO).round(), transform.transform_point2(DVec2::ONE).round()]
+		};
+
+		// The middle tile covers the viewport, but an apron reaching past its edges takes in the neighboring tiles too
+		assert_eq!(crop_bounds(0), [DVec2::splat(256.), DVec2::splat(512.)]);
+		assert_eq!(crop_bounds(4), [DVec2::splat(256.), DVec2::splat(512.)]);
+		assert_eq!(crop_bounds(10), [DVec2::ZERO, DVec2::splat(512.)]);
+		assert_eq!(crop_bounds(u32::MAX), [DVec2::ZERO, DVec2::splat(600.)]);
+	}
+
+	#[test]
+	fn tile_footprints_stay_the_same_while_panning() {
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-100., -50.)),
+			resolution: UVec2::new(600, 400),
+			quality: graphene_core::transform::RenderQuality::Scale(0.5),
+			apron: 3,
+		};
+		let tiles = tile_footprints(&footprint);
+
+		// The viewport spans three columns and two rows of tiles, each of which keeps the viewport's quality and apron
+		assert_eq!(tiles.len(), 6);
+		assert!(
+			tiles
+				.iter()
+				.all(|tile| tile.resolution == UVec2::splat(TILE_SIZE) && tile.quality == footprint.quality && tile.apron == 3)
+		);
+		assert_eq!(tiles[0].transform, DAffine2::IDENTITY);
+		assert_eq!(tiles[5].transform, DAffine2::from_translation(DVec2::new(-512., -256.)));
+
+		// Panning right by 200 pixels reveals a new column of tiles, while the two columns which stay visible keep their footprints
+		let panned = tile_footprints(&Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-300., -50.)),
+			..footprint
+		});
+		assert_eq!(panned.len(), 6);
+		assert_eq!(panned.iter().filter(|tile| tiles.contains(tile)).count(), 4);
+
+		// Zooming changes every tile
+		let zoomed = tile_footprints(&Footprint {
+			transform: DAffine2::from_scale(DVec2::splat(2.)) * footprint.transform,
+			..footprint
+		});
+		assert!(zoomed.iter().all(|tile| !tiles.contains(tile)));
+	}
+
+	#[tokio::test]
+	async fn tiles_draw_the_visible_part_of_the_image() {
+		let upstream = Upstream(RasterDataTable::new_instance(Instance {
+			instance: Raster::new_cpu(Image::new(300, 300, Color::RED)),
+			transform: DAffine2::from_scale(DVec2::splat(300.)),
+			..Default::default()
+		}));
+		let footprint = Footprint {
+			transform: DAffine2::from_translation(DVec2::new(-100., -100.)),
+			resolution: UVec2::new(300, 300),
+			..Default::default()
+		};
+		let tiles = render_visible_tiles(context(footprint), &upstream).await;
+
+		// The four tiles overlapping the viewport are placed on the grid in the document
+		assert_eq!(tiles.len(), 4);
+		let last = tiles.instance_ref_iter().last().unwrap();
+		assert_eq!(last.transform.transform_point2(DVec2::ZERO), DVec2::splat(256.));
+		assert_eq!(last.transform.transform_point2(DVec2::ONE), DVec2::splat(512.));
+
+		// The last tile only overlaps the image in its top left corner, and is transparent beyond it
+		let data = last.instance.data();
+		assert_eq!((data.width, data.height), (TILE_SIZE, TILE_SIZE));
+		assert_eq!(data.data[0], Color::RED);
+		assert_eq!(data.data[(43 * TILE_SIZE + 43) as usize], Color::RED);
+		assert_eq!(data.data[(44 * TILE_SIZE + 43) as usize], Color::TRANSPARENT);
+		assert_eq!(data.data[(43 * TILE_SIZE + 44) as usize], Color::TRANSPARENT);
+	}
+
+	#[test]
+	fn downsampling_averages_partial_edge_blocks() {
+		let image = Image {
+			width: 3,
+			height: 1,
+			data: vec![Color::BLACK, Color::WHITE, Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 0.5)],
+			base64_string: None,
+		};
+
+		let downsampled = downsample(&image, 2);
+		assert_eq!((downsampled.width, downsampled.height), (2, 1));
+		assert_eq!(downsampled.data, [Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 1.), Color::from_rgbaf32_unchecked(0.5, 0.5, 0.5, 0.5)]);
+	}
+}
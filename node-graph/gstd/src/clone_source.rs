@@ -0,0 +1,75 @@
+use glam::{DAffine2, DVec2};
+use graphene_core::bounds::BoundingBox;
+use graphene_core::instances::Instance;
+use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
+use graphene_core::{Ctx, GraphicGroupTable};
+use graphene_svg_renderer::rasterize::rasterize_cpu;
+
+/// The most pixels a clone source is rasterized with, beyond which its resolution is reduced to bound the memory it takes.
+const MAX_CLONE_SOURCE_PIXELS: f64 = 4096. * 4096.;
+
+/// Rasterizes the graphic, such as the merged layers below a brush layer, into the image which the Brush node's clone strokes copy pixels from.
+///
+/// The image covers the graphic's bounds, widened to whole units, with one pixel per unit like the images the Brush node paints.
+#[node_macro::node(category(""))]
+fn rasterize_clone_source(_: impl Ctx, source: GraphicGroupTable) -> RasterDataTable<CPU> {
+	let Some([min, max]) = source.bounding_box(DAffine2::IDENTITY, true) else {
+		return RasterDataTable::default();
+	};
+	let (min, max) = (min.floor(), max.ceil());
+	let size = max - min;
+	if !size.is_finite() || size.x <= 0. || size.y <= 0. {
+		return RasterDataTable::default();
+	}
+
+	let pixels_per_unit = (MAX_CLONE_SOURCE_PIXELS / (size.x * size.y)).sqrt().min(1.);
+	let resolution = (size * pixels_per_unit).ceil().max(DVec2::ONE).as_uvec2();
+	let to_pixels = DAffine2::from_scale(DVec2::splat(pixels_per_unit)) * DAffine2::from_translation(-min);
+	let Some(image) = rasterize_cpu(&source, to_pixels, resolution) else {
+		return RasterDataTable::default();
+	};
+
+	RasterDataTable::new_instance(Instance {
+		instance: Raster::new_cpu(image),
+		transform: DAffine2::from_scale_angle_translation(resolution.as_dvec2() / pixels_per_unit, 0., min),
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::Color;
+	use graphene_core::vector::VectorData;
+	use graphene_core::vector::bezier_rs::Subpath;
+	use graphene_core::vector::style::Fill;
+
+	fn square(min: DVec2, size: f64, color: Color) -> GraphicGroupTable {
+		let mut square = VectorData::from_subpath(Subpath::new_rect(min, min + size));
+		square.style.set_fill(Fill::Solid(color));
+		GraphicGroupTable::from(square)
+	}
+
+	#[test]
+	fn clone_source_covers_the_graphic_at_one_pixel_per_unit() {
+		let mut layers = square(DVec2::new(2.5, 3.), 4., Color::RED);
+		layers.extend(square(DVec2::new(6.5, 3.), 2., Color::BLUE));
+
+		let source = rasterize_clone_source((), layers);
+		let image = source.instance_ref_iter().next().unwrap();
+
+		// The bounds from (2.5, 3) to (8.5, 7) are widened to whole units
+		assert_eq!(*image.transform, DAffine2::from_scale_angle_translation(DVec2::new(7., 4.), 0., DVec2::new(2., 3.)));
+		assert_eq!((image.instance.width, image.instance.height), (7, 4));
+
+		let pixel = |x: u32, y: u32| image.instance.data[(y * image.instance.width + x) as usize];
+		assert_eq!(pixel(2, 1), Color::RED);
+		assert_eq!(pixel(5, 1), Color::BLUE);
+		assert_eq!(pixel(5, 3).a(), 0.);
+	}
+
+	#[test]
+	fn empty_clone_source_has_no_image() {
+		assert!(rasterize_clone_source((), GraphicGroupTable::default()).is_empty());
+	}
+}
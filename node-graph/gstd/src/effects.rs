@@ -0,0 +1,292 @@
+use crate::raster_nodes::filter::{BlurEdges, gaussian_blur};
+use glam::{DAffine2, DVec2};
+use graphene_core::bounds::BoundingBox;
+pub use graphene_core::effects::*;
+use graphene_core::instances::Instance;
+use graphene_core::raster::Image;
+use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
+use graphene_core::registry::types::{Percentage, PixelLength};
+use graphene_core::vector::VectorDataTable;
+use graphene_core::{Color, Ctx, ExtractFootprint, GraphicElement, GraphicGroupTable};
+use graphene_svg_renderer::rasterize::rasterize_cpu;
+
+/// The most pixels an effect is rasterized with, beyond which its resolution is reduced to bound the memory it takes.
+const MAX_EFFECT_PIXELS: f64 = 4096. * 4096.;
+
+#[derive(Debug, Clone, Copy)]
+struct EffectSettings {
+	effect_type: EffectType,
+	offset: DVec2,
+	blur: f64,
+	color: Color,
+	opacity: f64,
+}
+
+/// Adds a drop shadow, inner shadow, or outer glow drawn from the shape of the graphic.
+///
+/// The effect extends beyond the graphic's bounds as far as its offset and blur reach. SVG renders draw it with filter primitives, while other renderers draw it rasterized at the resolution the graphic is viewed at.
+#[node_macro::node(category("Style"))]
+fn effects<T: Into<GraphicGroupTable>>(
+	ctx: impl ExtractFootprint + Ctx,
+	#[implementations(
+		GraphicGroupTable,
+		VectorDataTable,
+		RasterDataTable<CPU>,
+	)]
+	content: T,
+	/// The kind of effect, and whether it's drawn behind or over the graphic.
+	effect_type: EffectType,
+	/// How far the shadow is cast from the graphic. Unused by the outer glow, which spreads evenly on all sides.
+	#[default(10., 10.)]
+	offset: DVec2,
+	/// The standard deviation of the Gaussian blur which softens the effect.
+	#[default(5.)]
+	#[range((0., 100.))]
+	#[hard_min(0.)]
+	blur: PixelLength,
+	/// The color the effect is tinted with.
+	#[default(Color::BLACK)]
+	color: Color,
+	/// How opaque the effect is.
+	#[default(75.)]
+	opacity: Percentage,
+) -> GraphicGroupTable {
+	let content: GraphicGroupTable = content.into();
+
+	let footprint = ctx.footprint();
+	let pixels_per_unit = footprint.scale().max_element() * footprint.quality.scale_factor();
+	let settings = EffectSettings {
+		effect_type,
+		offset,
+		blur: blur.max(0.),
+		color,
+		opacity: opacity / 100.,
+	};
+	let Some(effect) = render_effect(&content, settings, pixels_per_unit) else { return content };
+
+	let mut result = GraphicGroupTable::default();
+	if effect_type.is_behind() {
+		result.push(effect);
+		result.extend(content);
+	} else {
+		result.extend(content);
+		result.push(effect);
+	}
+	result
+}
+
+/// Rasterizes the coverage of the graphic, then shifts, blurs, and tints it into an image of the effect, which is kept along with the settings renderers can draw it from natively.
+fn render_effect(content: &GraphicGroupTable, settings: EffectSettings, pixels_per_unit: f64) -> Option<Instance<GraphicElement>> {
+	let [min, max] = content.bounding_box(DAffine2::IDENTITY, true)?;
+	let offset = settings.effect_type.cast_offset(settings.offset);
+
+	// Three standard deviations cover nearly all of the blur's falloff
+	let margin = DVec2::splat(3. * settings.blur) + offset.abs();
+	let (min, max) = (min - margin, max + margin);
+	let size = max - min;
+	if size.x <= 0. || size.y <= 0. {
+		return None;
+	}
+
+	let pixels_per_unit = pixels_per_unit.min((MAX_EFFECT_PIXELS / (size.x * size.y)).sqrt());
+	if !pixels_per_unit.is_finite() || pixels_per_unit <= 0. {
+		return None;
+	}
+	let resolution = (size * pixels_per_unit).ceil().max(DVec2::ONE).as_uvec2();
+	let to_pixels = DAffine2::from_scale(DVec2::splat(pixels_per_unit)) * DAffine2::from_translation(-min);
+
+	let coverage: Vec<f32> = rasterize_cpu(content, to_pixels, resolution)?.data.iter().map(|color| color.a()).collect();
+	let (width, height) = (resolution.x as usize, resolution.y as usize);
+
+	// Shift the coverage by the offset, rounded to whole pixels, and invert it for an inner shadow which is cast by the surroundings of the graphic
+	let shift = (offset * pixels_per_unit).round().as_i64vec2();
+	let shifted_coverage = |x: usize, y: usize| {
+		let (x, y) = (x as i64 - shift.x, y as i64 - shift.y);
+		let inside = (0..width as i64).contains(&x) && (0..height as i64).contains(&y);
+		if inside { coverage[y as usize * width + x as usize] } else { 0. }
+	};
+	let mask = (0..width * height).map(|index| {
+		let coverage = shifted_coverage(index % width, index / width);
+		let coverage = if settings.effect_type == EffectType::InnerShadow { 1. - coverage } else { coverage };
+		Color::from_rgbaf32_unchecked(1., 1., 1., coverage)
+	});
+	let mask = Image {
+		width: resolution.x,
+		height: resolution.y,
+		data: mask.collect(),
+		base64_string: None,
+	};
+
+	// Beyond the edges, an inner shadow's inverted coverage continues to be fully covered
+	let edges = if settings.effect_type == EffectType::InnerShadow {
+		BlurEdges::Clamp
+	} else {
+		BlurEdges::Transparent
+	};
+	let mut effect = gaussian_blur(mask, settings.blur * pixels_per_unit, edges);
+
+	for (index, pixel) in effect.data.iter_mut().enumerate() {
+		let mut alpha = pixel.a();
+		// An inner shadow only appears within the shape of the graphic
+		if settings.effect_type == EffectType::InnerShadow {
+			alpha *= coverage[index];
+		}
+		*pixel = settings.color.apply_opacity(alpha * settings.opacity as f32);
+	}
+
+	let effect = Instance {
+		instance: Raster::new_cpu(effect),
+		transform: DAffine2::from_scale_angle_translation(resolution.as_dvec2() / pixels_per_unit, 0., min),
+		..Default::default()
+	};
+	Some(Instance {
+		instance: GraphicElement::Effect(Box::new(LayerEffect {
+			effect_type: settings.effect_type,
+			offset: settings.offset,
+			blur: settings.blur,
+			color: settings.color,
+			opacity: settings.opacity,
+			source: content.clone(),
+			rasterized: RasterDataTable::new_instance(effect),
+		})),
+		..Default::default()
+	})
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use graphene_core::transform::Footprint;
+	use graphene_core::vector::VectorData;
+	use graphene_core::vector::bezier_rs::Subpath;
+	use graphene_core::vector::style::Fill;
+	use graphene_core::{Context, OwnedContextImpl};
+
+	/// A 4x4 black square with its corner at the origin.
+	fn square() -> GraphicGroupTable {
+		let mut square = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(4.)));
+		square.style.set_fill(Fill::Solid(Color::BLACK));
+		GraphicGroupTable::from(square)
+	}
+
+	fn context() -> Context<'static> {
+		OwnedContextImpl::default().with_footprint(Footprint::default()).into_context()
+	}
+
+	fn settings(effect_type: EffectType, blur: f64) -> EffectSettings {
+		EffectSettings {
+			effect_type,
+			offset: DVec2::new(2., 1.),
+			blur,
+			color: Color::BLACK,
+			opacity: 1.,
+		}
+	}
+
+	/// The effect image rendered at one pixel per unit, along with the transform placing it in the graphic's space.
+	fn effect_image(settings: EffectSettings) -> (Image<Color>, DAffine2) {
+		let effect = render_effect(&square(), settings, 1.).unwrap();
+		let GraphicElement::Effect(effect) = effect.instance else {
+			panic!("The effect should be a layer effect")
+		};
+		let image = effect.rasterized.instance_ref_iter().next().unwrap();
+		(image.instance.data().clone(), *image.transform)
+	}
+
+	/// Each row of the image, with `#` for opaque pixels, `.` for transparent ones, and `+` for anything between.
+	fn golden_image(image: &Image<Color>) -> Vec<String> {
+		image
+			.data
+			.chunks(image.width as usize)
+			.map(|row| {
+				row.iter()
+					.map(|color| match color.a() {
+						alpha if alpha > 0.999 => '#',
+						alpha if alpha < 0.001 => '.',
+						_ => '+',
+					})
+					.collect()
+			})
+			.collect()
+	}
+
+	#[test]
+	fn drop_shadow_matches_golden_image() {
+		let (image, transform) = effect_image(settings(EffectType::DropShadow, 0.));
+
+		// The image spans both the square and its shadow
+		assert_eq!(transform, DAffine2::from_scale_angle_translation(DVec2::new(8., 6.), 0., DVec2::new(-2., -1.)));
+		assert_eq!(golden_image(&image), ["........", "........", "....####", "....####", "....####", "....####"]);
+	}
+
+	#[test]
+	fn inner_shadow_matches_golden_image() {
+		let (image, _) = effect_image(settings(EffectType::InnerShadow, 0.));
+
+		// The shadow is cast inward from the edges facing away from the offset, and stays within the square
+		assert_eq!(golden_image(&image), ["........", "..####..", "..##....", "..##....", "..##....", "........"]);
+	}
+
+	#[test]
+	fn outer_glow_matches_golden_image() {
+		let (image, transform) = effect_image(settings(EffectType::OuterGlow, 0.));
+
+		// The glow ignores the offset and surrounds the square evenly
+		assert_eq!(transform, DAffine2::from_scale(DVec2::splat(4.)));
+		assert_eq!(golden_image(&image), ["####", "####", "####", "####"]);
+	}
+
+	#[test]
+	fn blurred_glow_extends_beyond_the_bounds() {
+		let (image, transform) = effect_image(settings(EffectType::OuterGlow, 2.));
+
+		// The image grows by three standard deviations on each side so the blur isn't clipped
+		assert_eq!(transform, DAffine2::from_scale_angle_translation(DVec2::splat(16.), 0., DVec2::splat(-6.)));
+		let alpha = |x: usize, y: usize| image.data[y * image.width as usize + x].a();
+		assert!(alpha(0, 0) < 0.01);
+		assert!(0.01 < alpha(3, 7) && alpha(3, 7) < alpha(5, 7) && alpha(5, 7) < alpha(7, 7));
+		assert!((alpha(3, 7) - alpha(12, 7)).abs() < 1e-5, "The glow should spread evenly on both sides");
+	}
+
+	#[test]
+	fn effect_is_tinted_and_faded() {
+		let color = Color::from_rgbaf32_unchecked(1., 0., 0., 1.);
+		let (image, _) = effect_image(EffectSettings {
+			color,
+			opacity: 0.5,
+			..settings(EffectType::DropShadow, 0.)
+		});
+		assert_eq!(image.data[2 * 8 + 4], color.apply_opacity(0.5));
+		assert_eq!(image.data[0], Color::TRANSPARENT);
+	}
+
+	#[test]
+	fn shadows_are_drawn_behind_and_inner_shadows_over_the_graphic() {
+		let effects = |effect_type| effects(context(), square(), effect_type, DVec2::new(2., 1.), 1., Color::BLACK, 75.);
+		let is_effect = |table: &GraphicGroupTable, index| matches!(table.get(index).unwrap().instance, GraphicElement::Effect(_));
+
+		let drop_shadow = effects(EffectType::DropShadow);
+		assert_eq!(drop_shadow.len(), 2);
+		assert!(is_effect(&drop_shadow, 0) && !is_effect(&drop_shadow, 1));
+
+		let inner_shadow = effects(EffectType::InnerShadow);
+		assert!(!is_effect(&inner_shadow, 0) && is_effect(&inner_shadow, 1));
+	}
+
+	#[test]
+	fn effect_keeps_its_settings_and_source_for_native_rendering() {
+		let effects = effects(context(), square(), EffectType::InnerShadow, DVec2::new(2., 1.), 3., Color::BLACK, 50.);
+		let GraphicElement::Effect(effect) = effects.get(1).unwrap().instance else {
+			panic!("The inner shadow should be drawn over the graphic")
+		};
+
+		assert_eq!((effect.effect_type, effect.offset, effect.blur, effect.opacity), (EffectType::InnerShadow, DVec2::new(2., 1.), 3., 0.5));
+		assert_eq!(effect.source, square());
+		assert_eq!(effect.rasterized.len(), 1);
+	}
+
+	#[test]
+	fn empty_content_has_no_effect() {
+		assert!(effects(context(), GraphicGroupTable::default(), EffectType::DropShadow, DVec2::ONE, 5., Color::BLACK, 75.).is_empty());
+	}
+}
@@ -1,4 +1,6 @@
 pub mod any;
+pub mod clone_source;
+pub mod effects;
 pub mod text;
 #[cfg(feature = "wasm")]
 pub mod wasm_application_io;
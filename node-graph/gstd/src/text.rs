@@ -32,18 +32,44 @@ fn text<'i: 'n>(
 	/// Splits each text glyph into its own instance, i.e. row in the table of vector data.
 	#[default(false)]
 	per_glyph_instances: bool,
+	/// A path for the text to flow along. When it's empty, the text is laid out on straight lines.
+	path: VectorDataTable,
+	/// The distance along the path where the text begins.
+	#[unit(" px")]
+	#[default(0.)]
+	start_offset: f64,
+	/// Which side of the path the text sits on, relative to the direction the path is drawn in.
+	side: TextPathSide,
+	/// Whether text that runs past the ends of the path is hidden or continues in a straight line.
+	overflow: TextPathOverflow,
+	/// Extra space added after each space character, on top of the character spacing.
+	#[unit(" px")]
+	#[default(0.)]
+	word_spacing: f64,
+	/// A fixed distance between the baselines of consecutive lines, which replaces the line height ratio when set.
+	#[unit(" px")]
+	#[default(None)]
+	absolute_line_height: Option<f64>,
+	/// Spreads the extra space of justified lines between every pair of glyphs, rather than only the gaps between words.
+	#[default(false)]
+	justify_letters: bool,
 ) -> VectorDataTable {
 	let typesetting = TypesettingConfig {
 		font_size,
 		line_height_ratio,
+		absolute_line_height,
 		character_spacing,
+		word_spacing,
 		max_width,
 		max_height,
 		tilt,
 		align,
+		justify_letters,
 	};
 
 	let font_data = editor.font_cache.get(&font_name).map(|f| load_font(f));
 
-	to_path(&text, font_data, typesetting, per_glyph_instances)
+	let text_path = TextPathConfig { start_offset, side, overflow };
+
+	to_path_on_path(&text, font_data, &font_name.variations, typesetting, &path, text_path, per_glyph_instances)
 }
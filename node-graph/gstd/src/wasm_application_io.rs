@@ -10,7 +10,7 @@ use graphene_core::raster::image::Image;
 use graphene_core::raster_types::{CPU, Raster, RasterDataTable};
 use graphene_core::transform::Footprint;
 use graphene_core::vector::VectorDataTable;
-use graphene_core::{Color, Context, Ctx, ExtractFootprint, GraphicGroupTable, OwnedContextImpl, WasmNotSend};
+use graphene_core::{Color, Context, Ctx, ExtractFootprint, GraphicGroupTable, OwnedContextImpl, WasmNotSend, culling};
 use graphene_svg_renderer::RenderMetadata;
 use graphene_svg_renderer::{GraphicElementRendered, RenderParams, RenderSvgSegmentList, SvgRender, format_transform_matrix};
 
@@ -313,9 +313,11 @@ async fn render<'a: 'n, T: 'n + GraphicElementRendered + WasmNotSend>(
 	ctx.footprint();
 
 	let RenderConfig { hide_artboards, for_export, .. } = render_config;
+	// Exports include every layer, while the viewport skips evaluating and rendering the layers outside of it
+	let culling_bounds = if for_export { None } else { culling::visible_bounds(&footprint) };
 	let render_params = RenderParams {
 		view_mode: render_config.view_mode,
-		culling_bounds: None,
+		culling_bounds: culling_bounds.map(|bounds| [bounds.start, bounds.end]),
 		thumbnail: false,
 		hide_artboards,
 		for_export,
@@ -323,7 +325,7 @@ async fn render<'a: 'n, T: 'n + GraphicElementRendered + WasmNotSend>(
 		alignment_parent_transform: None,
 	};
 
-	let data = data.eval(ctx.clone()).await;
+	let data = culling::scope(!for_export, data.eval(ctx.clone())).await;
 	let editor_api = editor_api.eval(None).await;
 
 	#[cfg(all(feature = "vello", not(test), target_arch = "wasm32"))]
@@ -340,7 +342,7 @@ async fn render<'a: 'n, T: 'n + GraphicElementRendered + WasmNotSend>(
 
 	let output_format = render_config.export_format;
 	let data = match output_format {
-		ExportFormat::Svg => render_svg(data, SvgRender::new(), render_params, footprint),
+		ExportFormat::Svg => render_svg(data, SvgRender::new().with_layer_names(render_config.layer_names), render_params, footprint),
 		ExportFormat::Canvas => {
 			if use_vello && editor_api.application_io.as_ref().unwrap().gpu_executor().is_some() {
 				#[cfg(all(feature = "vello", not(test)))]
@@ -1,4 +1,5 @@
 pub mod convert_usvg_path;
+pub mod rasterize;
 pub mod render_ext;
 mod renderer;
 pub mod to_peniko;
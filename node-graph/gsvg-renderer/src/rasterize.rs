@@ -0,0 +1,85 @@
+use crate::renderer::{GraphicElementRendered, RenderParams, SvgRender};
+use glam::{DAffine2, DVec2, UVec2};
+use graphene_core::color::Color;
+use graphene_core::instances::Instances;
+use graphene_core::raster::Image;
+use resvg::{tiny_skia, usvg};
+
+/// The width or height, in pixels, beyond which [`rasterize_usvg_node`] gives up on rasterizing a node.
+pub const MAX_USVG_NODE_RESOLUTION: u32 = 8192;
+
+/// Rasterizes the graphic on the CPU into an image of the given resolution, after the transform maps the graphic into pixel space.
+///
+/// Unlike the canvas-based Rasterize node, this works outside of the browser, which lets nodes build raster effects from any graphic.
+pub fn rasterize_cpu<T: Clone>(data: &Instances<T>, transform: DAffine2, resolution: UVec2) -> Option<Image<Color>>
+where
+	Instances<T>: GraphicElementRendered,
+{
+	let mut pixmap = tiny_skia::Pixmap::new(resolution.x, resolution.y)?;
+
+	let mut data = data.clone();
+	for instance in data.instance_mut_iter() {
+		*instance.transform = transform * *instance.transform;
+	}
+
+	let mut render = SvgRender::new();
+	let render_params = RenderParams {
+		culling_bounds: None,
+		for_export: true,
+		..Default::default()
+	};
+	data.render_svg(&mut render, &render_params);
+	render.format_svg(DVec2::ZERO, resolution.as_dvec2());
+
+	let tree = usvg::Tree::from_str(&render.svg.to_svg_string(), &usvg::Options::default()).ok()?;
+	resvg::render(&tree, tiny_skia::Transform::identity(), &mut pixmap.as_mut());
+
+	Some(pixmap_to_image(&pixmap))
+}
+
+/// Rasterizes a node of a parsed SVG into an image covering its bounds, including the filters, masks, and other effects which only an SVG renderer can reproduce.
+///
+/// The image has `scale` pixels for each unit of the SVG's canvas. It's returned along with the transform which places its unit square over the node
+/// in the SVG's canvas, or `None` if the node has no area or would need an image larger than [`MAX_USVG_NODE_RESOLUTION`] to rasterize.
+pub fn rasterize_usvg_node(node: &usvg::Node, scale: f64) -> Option<(Image<Color>, DAffine2)> {
+	let bounds = node.abs_layer_bounding_box()?;
+	let resolution = (DVec2::new(bounds.width() as f64, bounds.height() as f64) * scale).ceil();
+	if !resolution.is_finite() || resolution.max_element() > MAX_USVG_NODE_RESOLUTION as f64 {
+		return None;
+	}
+	let resolution = resolution.as_uvec2();
+	let mut pixmap = tiny_skia::Pixmap::new(resolution.x, resolution.y)?;
+
+	// The renderer applies the node's own transform but not those of its parents, and moves the node's bounds to the origin before anything else,
+	// so the parents' transforms are applied around the bounds' corner to place the node's content where it is in the canvas
+	let parent_transform = match node {
+		usvg::Node::Group(group) => group.abs_transform().pre_concat(group.transform().invert()?),
+		_ => node.abs_transform(),
+	};
+	let transform = tiny_skia::Transform::from_scale(scale as f32, scale as f32)
+		.pre_translate(-bounds.x(), -bounds.y())
+		.pre_concat(parent_transform)
+		.pre_translate(bounds.x(), bounds.y());
+	resvg::render_node(node, transform, &mut pixmap.as_mut())?;
+
+	let placement = DAffine2::from_translation(DVec2::new(bounds.x() as f64, bounds.y() as f64)) * DAffine2::from_scale(resolution.as_dvec2() / scale);
+	Some((pixmap_to_image(&pixmap), placement))
+}
+
+fn pixmap_to_image(pixmap: &tiny_skia::Pixmap) -> Image<Color> {
+	let data = pixmap
+		.pixels()
+		.iter()
+		.map(|pixel| {
+			let pixel = pixel.demultiply();
+			Color::from_rgba8_srgb(pixel.red(), pixel.green(), pixel.blue(), pixel.alpha())
+		})
+		.collect();
+
+	Image {
+		width: pixmap.width(),
+		height: pixmap.height(),
+		data,
+		base64_string: None,
+	}
+}
@@ -1,11 +1,14 @@
-use crate::renderer::{RenderParams, format_transform_matrix};
+use crate::renderer::{GraphicElementRendered, RenderParams, SvgRender, format_transform_matrix};
 use glam::{DAffine2, DVec2};
 use graphene_core::consts::{LAYER_OUTLINE_STROKE_COLOR, LAYER_OUTLINE_STROKE_WEIGHT};
 use graphene_core::gradient::{Gradient, GradientType};
 use graphene_core::uuid::generate_uuid;
-use graphene_core::vector::style::{Fill, PaintOrder, PathStyle, Stroke, StrokeAlign, StrokeCap, StrokeJoin, ViewMode};
+use graphene_core::vector::style::{Fill, FillRule, PaintOrder, PathStyle, Stroke, StrokeAlign, StrokeCap, StrokeJoin, ViewMode};
 use std::fmt::Write;
 
+/// The number of wedges a conic gradient is divided into, since SVG has no conic gradient and it must be approximated.
+const CONIC_GRADIENT_SEGMENTS: usize = 180;
+
 pub trait RenderExt {
 	type Output;
 	fn render(
@@ -42,12 +45,13 @@ impl RenderExt for Gradient {
 		let transformed_bound_transform = element_transform * DAffine2::from_scale_angle_translation(transformed_bounds[1] - transformed_bounds[0], 0., transformed_bounds[0]);
 
 		let mut stop = String::new();
-		for (position, color) in self.stops.0.iter() {
+		// SVG can only mix colors in sRGB, so other color spaces are approximated with extra stops
+		for (position, color) in self.stops.interpolated_stops_in(self.interpolation, self.interpolation_subdivisions as usize).iter() {
 			stop.push_str("<stop");
 			if *position != 0. {
 				let _ = write!(stop, r#" offset="{}""#, (position * 1_000_000.).round() / 1_000_000.);
 			}
-			let _ = write!(stop, r##" stop-color="#{}""##, color.to_rgb_hex_srgb_from_gamma());
+			let _ = write!(stop, r##" stop-color="{}""##, color.to_css_color_from_gamma());
 			if color.a() < 1. {
 				let _ = write!(stop, r#" stop-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
 			}
@@ -79,12 +83,71 @@ impl RenderExt for Gradient {
 			}
 			GradientType::Radial => {
 				let radius = (f64::powi(start.x - end.x, 2) + f64::powi(start.y - end.y, 2)).sqrt();
+
+				// The focal point is only written when it's off center, since it defaults to the center
+				let mut focal = String::new();
+				if self.focal_offset != DVec2::ZERO {
+					let focal_point = Gradient::constrain_focal_point(start, end, mod_points.transform_point2(self.focal_point()));
+					let _ = write!(focal, r#" fx="{}" fy="{}""#, focal_point.x, focal_point.y);
+				}
+				if self.focal_radius > 0. {
+					let _ = write!(focal, r#" fr="{}""#, self.focal_radius * radius);
+				}
+
 				let _ = write!(
 					svg_defs,
-					r#"<radialGradient id="{}" cx="{}" cy="{}" r="{}"{gradient_transform}>{}</radialGradient>"#,
+					r#"<radialGradient id="{}" cx="{}" cy="{}" r="{}"{focal}{gradient_transform}>{}</radialGradient>"#,
 					gradient_id, start.x, start.y, radius, stop
 				);
 			}
+			GradientType::Conic => {
+				// SVG has no conic gradient, so it's approximated by a pattern of thin wedges around the center, each filled with the gradient's color at its middle.
+				// The pattern is laid out in the path's own coordinate space, where the shape occupies `transformed_bounds`.
+				let local_points = stroke_transform * bound_transform;
+				let (center, end) = (local_points.transform_point2(self.start), local_points.transform_point2(self.end));
+				let start_angle = (end - center).to_angle();
+
+				let [min, max] = transformed_bounds;
+				let corners = [min, DVec2::new(max.x, min.y), max, DVec2::new(min.x, max.y)];
+				// Reach past the farthest corner, even where a wedge's straight outer edge cuts inside the circle
+				let radius = corners.iter().map(|corner| corner.distance(center)).fold(0., f64::max) / (std::f64::consts::PI / CONIC_GRADIENT_SEGMENTS as f64).cos() + 1.;
+
+				let round = |x: f64| (x * 1000.).round() / 1000.;
+				let size = max - min;
+				let _ = write!(
+					svg_defs,
+					r#"<pattern id="{gradient_id}" patternUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}">"#,
+					round(min.x),
+					round(min.y),
+					round(size.x),
+					round(size.y)
+				);
+				let wedge_angle = std::f64::consts::TAU / CONIC_GRADIENT_SEGMENTS as f64;
+				for segment in 0..CONIC_GRADIENT_SEGMENTS {
+					let color = self.stops.evaluate_in((segment as f64 + 0.5) / CONIC_GRADIENT_SEGMENTS as f64, self.interpolation);
+
+					// Overlap each opaque wedge into the next one to hide antialiasing seams, except for the last which meets the first at the gradient's start
+					let overlap = if segment + 1 < CONIC_GRADIENT_SEGMENTS && color.a() >= 1. { wedge_angle / 2. } else { 0. };
+					let from = center + DVec2::from_angle(start_angle + wedge_angle * segment as f64) * radius;
+					let to = center + DVec2::from_angle(start_angle + wedge_angle * (segment + 1) as f64 + overlap) * radius;
+					let _ = write!(
+						svg_defs,
+						r##"<path d="M{},{}L{},{}L{},{}Z" fill="{}""##,
+						round(center.x),
+						round(center.y),
+						round(from.x),
+						round(from.y),
+						round(to.x),
+						round(to.y),
+						color.to_css_color_from_gamma()
+					);
+					if color.a() < 1. {
+						let _ = write!(svg_defs, r#" fill-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
+					}
+					svg_defs.push_str(" />");
+				}
+				svg_defs.push_str("</pattern>");
+			}
 		}
 
 		gradient_id
@@ -109,7 +172,7 @@ impl RenderExt for Fill {
 		match self {
 			Self::None => r#" fill="none""#.to_string(),
 			Self::Solid(color) => {
-				let mut result = format!(r##" fill="#{}""##, color.to_rgb_hex_srgb_from_gamma());
+				let mut result = format!(r##" fill="{}""##, color.to_css_color_from_gamma());
 				if color.a() < 1. {
 					let _ = write!(result, r#" fill-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
 				}
@@ -128,6 +191,35 @@ impl RenderExt for Fill {
 				);
 				format!(r##" fill="url('#{gradient_id}')""##)
 			}
+			Self::Pattern(pattern) => {
+				let Some(cell_size) = pattern.cell_size() else {
+					return r#" fill="none""#.to_string();
+				};
+
+				let mut tile = SvgRender::new();
+				pattern.tile.render_svg(&mut tile, &render_params.for_pattern_tile());
+				svg_defs.push_str(&tile.svg_defs);
+
+				// The grid is laid out in the vector data's local space, which is mapped into the path's coordinates by the stroke transform
+				let pattern_id = generate_uuid();
+				let pattern_transform = format_transform_matrix(stroke_transform * pattern.grid_transform());
+				let pattern_transform = if pattern_transform.is_empty() {
+					String::new()
+				} else {
+					format!(r#" patternTransform="{pattern_transform}""#)
+				};
+				let tile_transform = format_transform_matrix(pattern.tile_transform());
+				let tile_transform = if tile_transform.is_empty() { String::new() } else { format!(r#" transform="{tile_transform}""#) };
+				let _ = write!(
+					svg_defs,
+					r#"<pattern id="{pattern_id}" patternUnits="userSpaceOnUse" width="{}" height="{}"{pattern_transform}><g{tile_transform}>{}</g></pattern>"#,
+					cell_size.x,
+					cell_size.y,
+					tile.svg.to_svg_string()
+				);
+
+				format!(r##" fill="url('#{pattern_id}')""##)
+			}
 		}
 	}
 }
@@ -153,25 +245,27 @@ impl RenderExt for Stroke {
 			return String::new();
 		}
 
+		// Aligned strokes are drawn at double the weight, with the half on the unwanted side masked away
+		let stroke_align = (self.align != StrokeAlign::Center).then_some(self.align);
+		let weight = if stroke_align.is_some() && aligned_strokes { self.weight * 2. } else { self.weight };
+
 		// Set to None if the value is the SVG default
-		let weight = (self.weight != 1.).then_some(self.weight);
-		let dash_array = (!self.dash_lengths.is_empty()).then_some(self.dash_lengths());
-		let dash_offset = (self.dash_offset != 0.).then_some(self.dash_offset);
+		let weight = (weight != 1.).then_some(weight);
+		let dash_lengths = self.renderable_dash_lengths();
+		let dash_array = (!dash_lengths.is_empty()).then(|| dash_lengths.iter().map(|length| length.to_string()).collect::<Vec<_>>().join(", "));
+		let dash_offset = Some(self.renderable_dash_offset()).filter(|&dash_offset| dash_offset != 0.);
 		let stroke_cap = (self.cap != StrokeCap::Butt).then_some(self.cap);
 		let stroke_join = (self.join != StrokeJoin::Miter).then_some(self.join);
-		let stroke_join_miter_limit = (self.join_miter_limit != 4.).then_some(self.join_miter_limit);
-		let stroke_align = (self.align != StrokeAlign::Center).then_some(self.align);
+		// The miter limit only affects miter joins
+		let stroke_join_miter_limit = (self.join == StrokeJoin::Miter && self.join_miter_limit != 4.).then_some(self.join_miter_limit);
 		let paint_order = (self.paint_order != PaintOrder::StrokeAbove || override_paint_order).then_some(PaintOrder::StrokeBelow);
 
 		// Render the needed stroke attributes
-		let mut attributes = format!(r##" stroke="#{}""##, color.to_rgb_hex_srgb_from_gamma());
+		let mut attributes = format!(r##" stroke="{}""##, color.to_css_color_from_gamma());
 		if color.a() < 1. {
 			let _ = write!(&mut attributes, r#" stroke-opacity="{}""#, (color.a() * 1000.).round() / 1000.);
 		}
-		if let Some(mut weight) = weight {
-			if stroke_align.is_some() && aligned_strokes {
-				weight *= 2.;
-			}
+		if let Some(weight) = weight {
 			let _ = write!(&mut attributes, r#" stroke-width="{}""#, weight);
 		}
 		if let Some(dash_array) = dash_array {
@@ -271,7 +365,12 @@ impl RenderExt for PathStyle {
 						)
 					})
 					.unwrap_or_default();
-				format!("{fill_attribute}{stroke_attribute}")
+				// The clip rule is written too since this shape may be rendered inside a `<clipPath>`, such as for stroke alignment or clipping masks, where the fill rule is ignored
+				let fill_rule_attribute = match self.fill_rule {
+					FillRule::NonZero => String::new(),
+					fill_rule => format!(r#" fill-rule="{0}" clip-rule="{0}""#, fill_rule.svg_name()),
+				};
+				format!("{fill_attribute}{fill_rule_attribute}{stroke_attribute}")
 			}
 		}
 	}
@@ -6,7 +6,9 @@ use glam::{DAffine2, DVec2};
 use graphene_core::blending::BlendMode;
 use graphene_core::bounds::BoundingBox;
 use graphene_core::color::Color;
+use graphene_core::effects::{EffectType, LayerEffect};
 use graphene_core::instances::Instance;
+use graphene_core::math::bbox::Bbox;
 use graphene_core::math::quad::Quad;
 use graphene_core::raster::Image;
 use graphene_core::raster_types::{CPU, GPU, RasterDataTable};
@@ -15,7 +17,7 @@ use graphene_core::transform::{Footprint, Transform};
 use graphene_core::uuid::{NodeId, generate_uuid};
 use graphene_core::vector::VectorDataTable;
 use graphene_core::vector::click_target::{ClickTarget, FreePoint};
-use graphene_core::vector::style::{Fill, Stroke, StrokeAlign, ViewMode};
+use graphene_core::vector::style::{Fill, FillRule, Stroke, StrokeAlign, StrokeMarker, ViewMode};
 use graphene_core::{Artboard, ArtboardGroupTable, GraphicElement, GraphicGroupTable};
 use num_traits::Zero;
 use std::collections::{HashMap, HashSet};
@@ -55,6 +57,10 @@ pub struct SvgRender {
 	pub svg_defs: String,
 	pub transform: DAffine2,
 	pub image_data: Vec<(u64, Image<Color>)>,
+	/// The names of layers by their node ID, which are written onto the elements of those layers as their `id` and `data-name` attributes.
+	pub layer_names: HashMap<NodeId, String>,
+	/// The IDs given to layer elements so far, which are kept unique by suffixing a number.
+	element_ids: HashSet<String>,
 	indent: usize,
 }
 
@@ -65,10 +71,33 @@ impl SvgRender {
 			svg_defs: String::new(),
 			transform: DAffine2::IDENTITY,
 			image_data: Vec::new(),
+			layer_names: HashMap::new(),
+			element_ids: HashSet::new(),
 			indent: 0,
 		}
 	}
 
+	pub fn with_layer_names(mut self, layer_names: HashMap<NodeId, String>) -> Self {
+		self.layer_names = layer_names;
+		self
+	}
+
+	/// The element ID and name for the layer, if it's named, with a number suffixed to the ID if another layer's element already has it.
+	fn layer_id_and_name(&mut self, layer: Option<NodeId>) -> Option<(String, String)> {
+		let name = self.layer_names.get(&layer?)?.clone();
+
+		let base_id = svg_id_from_name(&name);
+		let mut id = base_id.clone();
+		let mut suffix = 2;
+		while self.element_ids.contains(&id) {
+			id = format!("{base_id}-{suffix}");
+			suffix += 1;
+		}
+		self.element_ids.insert(id.clone());
+
+		Some((id, name))
+	}
+
 	pub fn indent(&mut self) {
 		self.svg.push("\n".into());
 		self.svg.push("\t".repeat(self.indent).into());
@@ -155,6 +184,7 @@ pub struct RenderContext {
 #[derive(Default)]
 pub struct RenderParams {
 	pub view_mode: ViewMode,
+	/// The visible area, in the space of the content being rendered, outside of which layers are skipped.
 	pub culling_bounds: Option<[DVec2; 2]>,
 	pub thumbnail: bool,
 	/// Don't render the rectangle for an artboard to allow exporting with a transparent background.
@@ -168,8 +198,13 @@ pub struct RenderParams {
 }
 
 impl RenderParams {
+	/// Masks are drawn whole, since they're placed by the content they clip rather than by their own transform which the culling bounds follow.
 	pub fn for_clipper(&self) -> Self {
-		Self { for_mask: true, ..*self }
+		Self {
+			for_mask: true,
+			culling_bounds: None,
+			..*self
+		}
 	}
 
 	pub fn for_alignment(&self, transform: DAffine2) -> Self {
@@ -177,9 +212,39 @@ impl RenderParams {
 		Self { alignment_parent_transform, ..*self }
 	}
 
+	/// The tile of a pattern fill is drawn whole, in its own space, since the pattern repeats it across the filled shape.
+	/// Images are embedded like in an export because a canvas can't be drawn inside an SVG pattern.
+	pub fn for_pattern_tile(&self) -> Self {
+		Self {
+			for_export: true,
+			culling_bounds: None,
+			alignment_parent_transform: None,
+			..*self
+		}
+	}
+
 	pub fn to_canvas(&self) -> bool {
 		!self.for_export && !self.thumbnail && !self.for_mask
 	}
+
+	/// Whether the content, placed with the transform, lies entirely outside of the culling bounds so rendering it can be skipped.
+	pub fn culls(&self, content: &impl BoundingBox, transform: DAffine2) -> bool {
+		let Some([cull_start, cull_end]) = self.culling_bounds else { return false };
+		let Some([start, end]) = content.bounding_box(transform, true) else { return false };
+		start.x > cull_end.x || start.y > cull_end.y || end.x < cull_start.x || end.y < cull_start.y
+	}
+
+	/// The params for rendering the inside of content placed with the transform, whose culling bounds are in the content's own space.
+	pub fn for_content_transform(&self, transform: DAffine2) -> Self {
+		let culling_bounds = self.culling_bounds.and_then(|[start, end]| {
+			if transform.matrix2.determinant() == 0. {
+				return None;
+			}
+			let bounds = Bbox::from_transform(transform.inverse() * DAffine2::from_translation(start) * DAffine2::from_scale(end - start)).to_axis_aligned_bbox();
+			Some([bounds.start, bounds.end])
+		});
+		Self { culling_bounds, ..*self }
+	}
 }
 
 pub fn format_transform_matrix(transform: DAffine2) -> String {
@@ -195,6 +260,51 @@ pub fn format_transform_matrix(transform: DAffine2) -> String {
 	}) + ")"
 }
 
+/// Turns a layer name into a readable SVG element ID by joining its runs of letters, digits, and underscores with hyphens.
+/// Letters outside of ASCII are kept, but an ID which wouldn't start with a letter or underscore is prefixed with `layer-`.
+pub fn svg_id_from_name(name: &str) -> String {
+	let words: Vec<_> = name.split(|character: char| !character.is_alphanumeric() && character != '_').filter(|word| !word.is_empty()).collect();
+	let id = words.join("-");
+
+	if id.is_empty() {
+		"layer".to_string()
+	} else if id.starts_with(|character: char| character.is_alphabetic() || character == '_') {
+		id
+	} else {
+		format!("layer-{id}")
+	}
+}
+
+/// Escapes the characters which can't be written as they are in a double-quoted attribute value.
+fn escape_attribute(value: &str) -> String {
+	value.replace('&', "&amp;").replace('<', "&lt;").replace('"', "&quot;")
+}
+
+/// Writes an SVG `<marker>` definition that draws the marker shape in the stroke's color, returning the ID to reference it by.
+/// It scales with the stroke width, and `auto-start-reverse` flips it at the start so both ends point away from the path like the placements from `VectorData::stroke_marker_transforms`.
+fn write_svg_marker(svg_defs: &mut String, marker: StrokeMarker, stroke: &Stroke) -> String {
+	let id = format!("marker-{}", generate_uuid());
+
+	let mut path = String::new();
+	if let Some(outline) = marker.outline() {
+		let _ = outline.subpath_to_svg(&mut path, DAffine2::from_scale(DVec2::splat(stroke.marker_size)));
+	}
+
+	let color = stroke.color.unwrap_or(Color::TRANSPARENT);
+	let opacity = if color.a() < 1. {
+		format!(r#" fill-opacity="{}""#, (color.a() * 1000.).round() / 1000.)
+	} else {
+		String::new()
+	};
+	let _ = write!(
+		svg_defs,
+		r##"<marker id="{id}" markerUnits="strokeWidth" orient="auto-start-reverse" overflow="visible"><path d="{path}" fill="{}"{opacity} /></marker>"##,
+		color.to_css_color_from_gamma()
+	);
+
+	id
+}
+
 pub fn to_transform(transform: DAffine2) -> usvg::Transform {
 	let cols = transform.to_cols_array();
 	usvg::Transform::from_row(cols[0] as f32, cols[1] as f32, cols[2] as f32, cols[3] as f32, cols[4] as f32, cols[5] as f32)
@@ -240,9 +350,22 @@ impl GraphicElementRendered for GraphicGroupTable {
 		let mut mask_state = None;
 
 		while let Some(instance) = iter.next() {
+			// Layers outside of the viewport are skipped, unless they're part of a clipping mask which may reach into it
+			let in_mask = mask_state.is_some() || iter.peek().is_some_and(|next_instance| next_instance.instance.had_clip_enabled());
+			if !in_mask && render_params.culls(instance.instance, *instance.transform) {
+				continue;
+			}
+
+			let layer_id_and_name = render.layer_id_and_name(*instance.source_node_id);
+
 			render.parent_tag(
 				"g",
 				|attributes| {
+					if let Some((id, name)) = layer_id_and_name {
+						attributes.push("id", id);
+						attributes.push("data-name", escape_attribute(&name));
+					}
+
 					let matrix = format_transform_matrix(*instance.transform);
 					if !matrix.is_empty() {
 						attributes.push("transform", matrix);
@@ -253,8 +376,16 @@ impl GraphicElementRendered for GraphicGroupTable {
 						attributes.push("opacity", opacity.to_string());
 					}
 
+					let mut style = String::new();
 					if instance.alpha_blending.blend_mode != BlendMode::default() {
-						attributes.push("style", instance.alpha_blending.blend_mode.render());
+						style.push_str(&instance.alpha_blending.blend_mode.render());
+					}
+					// A plain `<g>` lets its children blend against everything below it, so an isolated group has to ask for its own stacking context
+					if !instance.alpha_blending.pass_through {
+						style.push_str(" isolation: isolate;");
+					}
+					if !style.is_empty() {
+						attributes.push("style", style);
 					}
 
 					let next_clips = iter.peek().is_some_and(|next_instance| next_instance.instance.had_clip_enabled());
@@ -280,7 +411,7 @@ impl GraphicElementRendered for GraphicGroupTable {
 					}
 				},
 				|render| {
-					instance.instance.render_svg(render, render_params);
+					instance.instance.render_svg(render, &render_params.for_content_transform(*instance.transform));
 				},
 			);
 		}
@@ -292,6 +423,14 @@ impl GraphicElementRendered for GraphicGroupTable {
 		let mut mask_instance_state = None;
 
 		while let Some(instance) = iter.next() {
+			// Layers outside of the viewport are skipped, unless they're part of a clipping mask which may reach into it
+			let in_mask = mask_instance_state.is_some() || iter.peek().is_some_and(|next_instance| next_instance.instance.had_clip_enabled());
+			if !in_mask && render_params.culls(instance.instance, *instance.transform) {
+				continue;
+			}
+			let content_render_params = render_params.for_content_transform(*instance.transform);
+			let render_params = &content_render_params;
+
 			let transform = transform * *instance.transform;
 			let alpha_blending = *instance.alpha_blending;
 
@@ -303,8 +442,10 @@ impl GraphicElementRendered for GraphicGroupTable {
 			};
 			let mut bounds = None;
 
+			// Content inside a layer is composited in isolation before the layer is blended onto what's below, so pass-through groups must avoid pushing one
 			let opacity = instance.alpha_blending.opacity(render_params.for_mask);
-			if opacity < 1. || (render_params.view_mode != ViewMode::Outline && alpha_blending.blend_mode != BlendMode::default()) {
+			let isolated = !alpha_blending.pass_through;
+			if opacity < 1. || isolated || (render_params.view_mode != ViewMode::Outline && alpha_blending.blend_mode != BlendMode::default()) {
 				bounds = instance.instance.bounding_box(transform, true);
 
 				if let Some(bounds) = bounds {
@@ -357,11 +498,14 @@ impl GraphicElementRendered for GraphicGroupTable {
 
 	fn collect_metadata(&self, metadata: &mut RenderMetadata, footprint: Footprint, element_id: Option<NodeId>) {
 		for instance in self.instance_ref_iter() {
-			if let Some(element_id) = instance.source_node_id {
-				let mut footprint = footprint;
-				footprint.transform *= *instance.transform;
-
-				instance.instance.collect_metadata(metadata, footprint, Some(*element_id));
+			let mut footprint = footprint;
+			footprint.transform *= *instance.transform;
+
+			match instance.source_node_id {
+				Some(element_id) => instance.instance.collect_metadata(metadata, footprint, Some(*element_id)),
+				// The contents of an isolated group are moved into a row of their own, which has no source node but whose children still need their metadata
+				None if !instance.alpha_blending.pass_through => instance.instance.collect_metadata(metadata, footprint, None),
+				None => {}
 			}
 		}
 
@@ -429,8 +573,7 @@ impl GraphicElementRendered for VectorDataTable {
 				let _ = subpath.subpath_to_svg(&mut path, applied_stroke_transform);
 			}
 
-			let connected = vector_data.stroke_bezier_paths().all(|path| path.closed());
-			let can_draw_aligned_stroke = vector_data.style.stroke().is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered()) && connected;
+			let can_draw_aligned_stroke = vector_data.effective_stroke_align().is_not_centered();
 			let mut push_id = None;
 
 			if can_draw_aligned_stroke {
@@ -494,12 +637,29 @@ impl GraphicElementRendered for VectorDataTable {
 					render_params,
 				);
 
+				let [start_marker, end_marker] = match render_params.view_mode {
+					ViewMode::Outline => [None, None],
+					_ => {
+						let stroke = vector_data.style.stroke().unwrap_or_default();
+						vector_data
+							.stroke_marker_transforms(DAffine2::IDENTITY)
+							.map(|placement| placement.map(|(marker, _)| write_svg_marker(defs, marker, &stroke)))
+					}
+				};
+
 				if let Some((id, mask_type, _)) = push_id {
 					let selector = format!("url(#{id})");
 					attributes.push(mask_type.to_attribute(), selector);
 				}
 				attributes.push_val(fill_and_stroke);
 
+				if let Some(id) = start_marker {
+					attributes.push("marker-start", format!("url(#{id})"));
+				}
+				if let Some(id) = end_marker {
+					attributes.push("marker-end", format!("url(#{id})"));
+				}
+
 				let opacity = instance.alpha_blending.opacity(render_params.for_mask);
 				if opacity < 1. {
 					attributes.push("opacity", opacity.to_string());
@@ -515,7 +675,7 @@ impl GraphicElementRendered for VectorDataTable {
 	#[cfg(feature = "vello")]
 	fn render_to_vello(&self, scene: &mut Scene, parent_transform: DAffine2, _context: &mut RenderContext, render_params: &RenderParams) {
 		use graphene_core::consts::{LAYER_OUTLINE_STROKE_COLOR, LAYER_OUTLINE_STROKE_WEIGHT};
-		use graphene_core::vector::style::{GradientType, StrokeCap, StrokeJoin};
+		use graphene_core::vector::style::{DEFAULT_INTERPOLATION_SUBDIVISIONS, Gradient, GradientInterpolation, GradientType, StrokeCap, StrokeJoin};
 		use vello::kurbo::{Cap, Join};
 		use vello::peniko;
 
@@ -545,7 +705,7 @@ impl GraphicElementRendered for VectorDataTable {
 			let opacity = instance.alpha_blending.opacity(render_params.for_mask);
 			if opacity < 1. || instance.alpha_blending.blend_mode != BlendMode::default() {
 				layer = true;
-				let weight = instance.instance.style.stroke().unwrap().weight;
+				let weight = instance.instance.style.stroke().map_or(0., |stroke| stroke.weight);
 				let quad = Quad::from_box(layer_bounds).inflate(weight * element_transform.matrix2.determinant());
 				let layer_bounds = quad.bounding_box();
 				scene.push_layer(
@@ -556,10 +716,10 @@ impl GraphicElementRendered for VectorDataTable {
 				);
 			}
 
-			let can_draw_aligned_stroke = instance.instance.style.stroke().is_some_and(|stroke| stroke.has_renderable_stroke() && stroke.align.is_not_centered())
-				&& instance.instance.stroke_bezier_paths().all(|path| path.closed());
+			let stroke_align = instance.instance.effective_stroke_align();
+			let can_draw_aligned_stroke = stroke_align.is_not_centered();
 
-			let reorder_for_outside = instance.instance.style.stroke().is_some_and(|stroke| stroke.align == StrokeAlign::Outside) && !instance.instance.style.fill().is_none();
+			let reorder_for_outside = stroke_align == StrokeAlign::Outside && !instance.instance.style.fill().is_none();
 			let use_layer = can_draw_aligned_stroke && !reorder_for_outside;
 			if use_layer {
 				let mut fill_instance = instance.instance.clone();
@@ -615,6 +775,10 @@ impl GraphicElementRendered for VectorDataTable {
 						true => [Op::Stroke, Op::Fill],
 						false => [Op::Fill, Op::Stroke], // Default
 					};
+					let fill_rule = match instance.instance.style.fill_rule() {
+						FillRule::NonZero => peniko::Fill::NonZero,
+						FillRule::EvenOdd => peniko::Fill::EvenOdd,
+					};
 
 					for operation in order {
 						match operation {
@@ -622,11 +786,12 @@ impl GraphicElementRendered for VectorDataTable {
 								match instance.instance.style.fill() {
 									Fill::Solid(color) => {
 										let fill = peniko::Brush::Solid(peniko::Color::new([color.r(), color.g(), color.b(), color.a()]));
-										scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &fill, None, &path);
+										scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), &fill, None, &path);
 									}
 									Fill::Gradient(gradient) => {
 										let mut stops = peniko::ColorStops::new();
-										for &(offset, color) in &gradient.stops {
+										// Vello mixes colors in the gradient's color space itself, so only biased midpoints need extra stops
+										for (offset, color) in gradient.stops.interpolated_stops_in(gradient.interpolation, 1) {
 											stops.push(peniko::ColorStop {
 												offset: offset as f32,
 												color: peniko::color::DynamicColor::from_alpha_color(peniko::Color::new([color.r(), color.g(), color.b(), color.a()])),
@@ -654,15 +819,32 @@ impl GraphicElementRendered for VectorDataTable {
 												},
 												GradientType::Radial => {
 													let radius = start.distance(end);
+													let focal_point = Gradient::constrain_focal_point(start, end, mod_points.transform_point2(gradient.focal_point()));
 													peniko::GradientKind::Radial {
-														start_center: to_point(start),
-														start_radius: 0.,
+														start_center: to_point(focal_point),
+														start_radius: (gradient.focal_radius.max(0.) * radius) as f32,
 														end_center: to_point(start),
 														end_radius: radius as f32,
 													}
 												}
+												// The sweep begins along the x-axis, and is rotated to the gradient's start angle through the brush transform below
+												GradientType::Conic => peniko::GradientKind::Sweep {
+													center: to_point(start),
+													start_angle: 0.,
+													end_angle: std::f32::consts::TAU,
+												},
 											},
 											stops,
+											interpolation_cs: match gradient.interpolation {
+												GradientInterpolation::Srgb => peniko::color::ColorSpaceTag::Srgb,
+												GradientInterpolation::LinearRgb => peniko::color::ColorSpaceTag::LinearSrgb,
+												GradientInterpolation::Oklab => peniko::color::ColorSpaceTag::Oklab,
+												GradientInterpolation::OklchShorterHue | GradientInterpolation::OklchLongerHue => peniko::color::ColorSpaceTag::Oklch,
+											},
+											hue_direction: match gradient.interpolation {
+												GradientInterpolation::OklchLongerHue => peniko::color::HueDirection::Longer,
+												_ => peniko::color::HueDirection::Shorter,
+											},
 											..Default::default()
 										});
 										// Vello does `element_transform * brush_transform` internally. We don't want element_transform to have any impact so we need to left multiply by the inverse.
@@ -672,8 +854,29 @@ impl GraphicElementRendered for VectorDataTable {
 										} else {
 											Default::default()
 										};
-										let brush_transform = kurbo::Affine::new((inverse_element_transform * parent_transform).to_cols_array());
-										scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), &fill, Some(brush_transform), &path);
+										let sweep_rotation = match gradient.gradient_type {
+											GradientType::Conic => DAffine2::from_translation(start) * DAffine2::from_angle((end - start).to_angle()) * DAffine2::from_translation(-start),
+											_ => DAffine2::IDENTITY,
+										};
+										let brush_transform = kurbo::Affine::new((inverse_element_transform * parent_transform * sweep_rotation).to_cols_array());
+										scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), &fill, Some(brush_transform), &path);
+									}
+									Fill::Pattern(pattern) => {
+										// Vello has no pattern brush, so the tile is drawn into every cell overlapping the shape and then masked by the filled area of the path
+										let local_to_scene = element_transform * applied_stroke_transform;
+										let [min, max] = (local_to_scene * Quad::from_box(layer_bounds)).bounding_box();
+										let rect = kurbo::Rect::new(min.x, min.y, max.x, max.y);
+
+										scene.push_layer(peniko::Mix::Normal, 1., kurbo::Affine::IDENTITY, &rect);
+										let mask = peniko::Color::new([0., 0., 0., 1.]);
+										scene.fill(fill_rule, kurbo::Affine::new(element_transform.to_cols_array()), mask, None, &path);
+										scene.push_layer(peniko::BlendMode::new(peniko::Mix::Clip, peniko::Compose::SrcIn), 1., kurbo::Affine::IDENTITY, &rect);
+										let tile_params = render_params.for_pattern_tile();
+										for tile_transform in pattern.tile_transforms(layer_bounds) {
+											pattern.tile.render_to_vello(scene, local_to_scene * tile_transform, _context, &tile_params);
+										}
+										scene.pop_layer();
+										scene.pop_layer();
 									}
 									Fill::None => {}
 								};
@@ -700,13 +903,21 @@ impl GraphicElementRendered for VectorDataTable {
 										join,
 										start_cap: cap,
 										end_cap: cap,
-										dash_pattern: stroke.dash_lengths.into(),
-										dash_offset: stroke.dash_offset,
+										dash_pattern: stroke.renderable_dash_lengths().into(),
+										dash_offset: stroke.renderable_dash_offset(),
 									};
 
 									// Draw the stroke if it's visible
 									if stroke.width > 0. {
 										scene.stroke(&stroke, kurbo::Affine::new(element_transform.to_cols_array()), color, None, &path);
+
+										// Draw the start and end markers by placing their outlines at the path's endpoints
+										for (marker, marker_transform) in instance.instance.stroke_marker_transforms(applied_stroke_transform).into_iter().flatten() {
+											let Some(outline) = marker.outline() else { continue };
+											let mut marker_path = kurbo::BezPath::new();
+											outline.to_vello_path(marker_transform, &mut marker_path);
+											scene.fill(peniko::Fill::NonZero, kurbo::Affine::new(element_transform.to_cols_array()), color, None, &marker_path);
+										}
 									}
 								}
 							}
@@ -733,7 +944,6 @@ impl GraphicElementRendered for VectorDataTable {
 			let instance = instance.instance;
 
 			if let Some(element_id) = element_id {
-				let stroke_width = instance.style.stroke().as_ref().map_or(0., Stroke::weight);
 				let filled = instance.style.fill() != &Fill::None;
 				let fill = |mut subpath: Subpath<_>| {
 					if filled {
@@ -754,10 +964,10 @@ impl GraphicElementRendered for VectorDataTable {
 					}
 				});
 
+				let fill_rule = instance.style.fill_rule();
 				let click_targets = instance
-					.stroke_bezier_paths()
-					.map(fill)
-					.map(|subpath| ClickTarget::new_with_subpath(subpath, stroke_width))
+					.aligned_stroke_bezier_paths()
+					.map(|(subpath, stroke_width)| ClickTarget::new_with_subpath(fill(subpath), stroke_width).with_fill_rule(fill_rule))
 					.chain(single_anchors_targets.into_iter())
 					.collect::<Vec<ClickTarget>>();
 
@@ -773,7 +983,6 @@ impl GraphicElementRendered for VectorDataTable {
 
 	fn add_upstream_click_targets(&self, click_targets: &mut Vec<ClickTarget>) {
 		for instance in self.instance_ref_iter() {
-			let stroke_width = instance.instance.style.stroke().as_ref().map_or(0., Stroke::weight);
 			let filled = instance.instance.style.fill() != &Fill::None;
 			let fill = |mut subpath: Subpath<_>| {
 				if filled {
@@ -781,8 +990,9 @@ impl GraphicElementRendered for VectorDataTable {
 				}
 				subpath
 			};
-			click_targets.extend(instance.instance.stroke_bezier_paths().map(fill).map(|subpath| {
-				let mut click_target = ClickTarget::new_with_subpath(subpath, stroke_width);
+			let fill_rule = instance.instance.style.fill_rule();
+			click_targets.extend(instance.instance.aligned_stroke_bezier_paths().map(|(subpath, stroke_width)| {
+				let mut click_target = ClickTarget::new_with_subpath(fill(subpath), stroke_width).with_fill_rule(fill_rule);
 				click_target.apply_transform(*instance.transform);
 				click_target
 			}));
@@ -816,7 +1026,7 @@ impl GraphicElementRendered for Artboard {
 		if !render_params.hide_artboards {
 			// Background
 			render.leaf_tag("rect", |attributes| {
-				attributes.push("fill", format!("#{}", self.background.to_rgb_hex_srgb_from_gamma()));
+				attributes.push("fill", self.background.to_css_color_from_gamma());
 				if self.background.a() < 1. {
 					attributes.push("fill-opacity", ((self.background.a() * 1000.).round() / 1000.).to_string());
 				}
@@ -853,7 +1063,7 @@ impl GraphicElementRendered for Artboard {
 			},
 			// Artboard contents
 			|render| {
-				self.graphic_group.render_svg(render, render_params);
+				self.graphic_group.render_svg(render, &render_params.for_content_transform(self.transform()));
 			},
 		);
 	}
@@ -877,7 +1087,8 @@ impl GraphicElementRendered for Artboard {
 		}
 		// Since the graphic group's transform is right multiplied in when rendering the graphic group, we just need to right multiply by the offset here.
 		let child_transform = transform * DAffine2::from_translation(self.location.as_dvec2());
-		self.graphic_group.render_to_vello(scene, child_transform, context, render_params);
+		self.graphic_group
+			.render_to_vello(scene, child_transform, context, &render_params.for_content_transform(self.transform()));
 		if self.clip {
 			scene.pop_layer();
 		}
@@ -910,7 +1121,18 @@ impl GraphicElementRendered for Artboard {
 impl GraphicElementRendered for ArtboardGroupTable {
 	fn render_svg(&self, render: &mut SvgRender, render_params: &RenderParams) {
 		for artboard in self.instance_ref_iter() {
-			artboard.instance.render_svg(render, render_params);
+			// A named artboard is wrapped in a group which carries its name, since its background and contents are separate elements
+			match render.layer_id_and_name(*artboard.source_node_id) {
+				Some((id, name)) => render.parent_tag(
+					"g",
+					|attributes| {
+						attributes.push("id", id);
+						attributes.push("data-name", escape_attribute(&name));
+					},
+					|render| artboard.instance.render_svg(render, render_params),
+				),
+				None => artboard.instance.render_svg(render, render_params),
+			}
 		}
 	}
 
@@ -1137,12 +1359,80 @@ impl GraphicElementRendered for RasterDataTable<GPU> {
 	}
 }
 
+impl GraphicElementRendered for LayerEffect {
+	fn render_svg(&self, render: &mut SvgRender, render_params: &RenderParams) {
+		if render_params.view_mode == ViewMode::Outline {
+			return;
+		}
+		let Some([min, max]) = self.source.bounding_box(DAffine2::IDENTITY, true) else { return };
+
+		// The filter region reaches as far as the effect does, with three standard deviations covering nearly all of the blur's falloff
+		let offset = self.effect_type.cast_offset(self.offset);
+		let margin = DVec2::splat(3. * self.blur) + offset.abs();
+		let (min, size) = (min - margin, max - min + 2. * margin);
+
+		let id = format!("effect-{}", generate_uuid());
+		let defs = &mut render.svg_defs;
+		write!(
+			defs,
+			r#"<filter id="{id}" filterUnits="userSpaceOnUse" x="{}" y="{}" width="{}" height="{}">"#,
+			min.x, min.y, size.x, size.y
+		)
+		.unwrap();
+
+		// An inner shadow is cast by the surroundings of the graphic, so it starts from the inverted silhouette
+		let mut shadow = "SourceAlpha";
+		if self.effect_type == EffectType::InnerShadow {
+			write!(defs, r#"<feComponentTransfer in="{shadow}" result="inverted">"#).unwrap();
+			defs.push_str(r#"<feFuncA type="table" tableValues="1 0" /></feComponentTransfer>"#);
+			shadow = "inverted";
+		}
+		write!(defs, r#"<feOffset in="{shadow}" dx="{}" dy="{}" result="offset" />"#, offset.x, offset.y).unwrap();
+		shadow = "offset";
+		// A standard deviation of zero would disable the primitive, which some viewers take to mean an empty result
+		if self.blur > 0. {
+			write!(defs, r#"<feGaussianBlur in="{shadow}" stdDeviation="{}" result="blurred" />"#, self.blur).unwrap();
+			shadow = "blurred";
+		}
+		if self.effect_type == EffectType::InnerShadow {
+			write!(defs, r#"<feComposite in="{shadow}" in2="SourceAlpha" operator="in" result="inside" />"#).unwrap();
+			shadow = "inside";
+		}
+		let opacity = (self.color.a() as f64 * self.opacity * 1000.).round() / 1000.;
+		write!(defs, r#"<feFlood flood-color="{}" flood-opacity="{opacity}" />"#, self.color.to_css_color_from_gamma()).unwrap();
+		write!(defs, r#"<feComposite in2="{shadow}" operator="in" />"#).unwrap();
+		defs.push_str("</filter>");
+
+		// The graphic is drawn whole and without its layer names, since the filter replaces it with only its effect and the graphic itself is drawn beside it
+		let layer_names = std::mem::take(&mut render.layer_names);
+		let source_render_params = RenderParams {
+			culling_bounds: None,
+			..*render_params
+		};
+		render.parent_tag(
+			"g",
+			|attributes| attributes.push("filter", format!("url(#{id})")),
+			|render| self.source.render_svg(render, &source_render_params),
+		);
+		render.layer_names = layer_names;
+	}
+
+	#[cfg(feature = "vello")]
+	fn render_to_vello(&self, scene: &mut Scene, transform: DAffine2, context: &mut RenderContext, render_params: &RenderParams) {
+		if render_params.view_mode == ViewMode::Outline {
+			return;
+		}
+		self.rasterized.render_to_vello(scene, transform, context, render_params);
+	}
+}
+
 impl GraphicElementRendered for GraphicElement {
 	fn render_svg(&self, render: &mut SvgRender, render_params: &RenderParams) {
 		match self {
 			GraphicElement::VectorData(vector_data) => vector_data.render_svg(render, render_params),
 			GraphicElement::RasterDataCPU(raster) => raster.render_svg(render, render_params),
 			GraphicElement::RasterDataGPU(_raster) => (),
+			GraphicElement::Effect(effect) => effect.render_svg(render, render_params),
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.render_svg(render, render_params),
 		}
 	}
@@ -1154,13 +1444,14 @@ impl GraphicElementRendered for GraphicElement {
 			GraphicElement::RasterDataCPU(raster) => raster.render_to_vello(scene, transform, context, render_params),
 			GraphicElement::RasterDataGPU(raster) => raster.render_to_vello(scene, transform, context, render_params),
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.render_to_vello(scene, transform, context, render_params),
+			GraphicElement::Effect(effect) => effect.render_to_vello(scene, transform, context, render_params),
 		}
 	}
 
 	fn collect_metadata(&self, metadata: &mut RenderMetadata, footprint: Footprint, element_id: Option<NodeId>) {
 		if let Some(element_id) = element_id {
 			match self {
-				GraphicElement::GraphicGroup(_) => {
+				GraphicElement::GraphicGroup(_) | GraphicElement::Effect(_) => {
 					metadata.upstream_footprints.insert(element_id, footprint);
 				}
 				GraphicElement::VectorData(vector_data) => {
@@ -1195,6 +1486,7 @@ impl GraphicElementRendered for GraphicElement {
 			GraphicElement::RasterDataCPU(raster) => raster.collect_metadata(metadata, footprint, element_id),
 			GraphicElement::RasterDataGPU(raster) => raster.collect_metadata(metadata, footprint, element_id),
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.collect_metadata(metadata, footprint, element_id),
+			GraphicElement::Effect(effect) => effect.collect_metadata(metadata, footprint, element_id),
 		}
 	}
 
@@ -1204,6 +1496,7 @@ impl GraphicElementRendered for GraphicElement {
 			GraphicElement::RasterDataCPU(raster) => raster.add_upstream_click_targets(click_targets),
 			GraphicElement::RasterDataGPU(raster) => raster.add_upstream_click_targets(click_targets),
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.add_upstream_click_targets(click_targets),
+			GraphicElement::Effect(effect) => effect.add_upstream_click_targets(click_targets),
 		}
 	}
 
@@ -1213,6 +1506,7 @@ impl GraphicElementRendered for GraphicElement {
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.contains_artboard(),
 			GraphicElement::RasterDataCPU(raster) => raster.contains_artboard(),
 			GraphicElement::RasterDataGPU(raster) => raster.contains_artboard(),
+			GraphicElement::Effect(effect) => effect.contains_artboard(),
 		}
 	}
 
@@ -1222,6 +1516,7 @@ impl GraphicElementRendered for GraphicElement {
 			GraphicElement::GraphicGroup(graphic_group) => graphic_group.new_ids_from_hash(reference),
 			GraphicElement::RasterDataCPU(_) => (),
 			GraphicElement::RasterDataGPU(_) => (),
+			GraphicElement::Effect(_) => (),
 		}
 	}
 }
@@ -1261,7 +1556,7 @@ impl GraphicElementRendered for Option<Color> {
 			attributes.push("width", "100");
 			attributes.push("height", "100");
 			attributes.push("y", "40");
-			attributes.push("fill", format!("#{}", color.to_rgb_hex_srgb_from_gamma()));
+			attributes.push("fill", color.to_css_color_from_gamma());
 			if color.a() < 1. {
 				attributes.push("fill-opacity", ((color.a() * 1000.).round() / 1000.).to_string());
 			}
@@ -1281,7 +1576,7 @@ impl GraphicElementRendered for Vec<Color> {
 				attributes.push("height", "100");
 				attributes.push("x", (index * 120).to_string());
 				attributes.push("y", "40");
-				attributes.push("fill", format!("#{}", color.to_rgb_hex_srgb_from_gamma()));
+				attributes.push("fill", color.to_css_color_from_gamma());
 				if color.a() < 1. {
 					attributes.push("fill-opacity", ((color.a() * 1000.).round() / 1000.).to_string());
 				}
@@ -1345,3 +1640,579 @@ impl SvgRenderAttrs<'_> {
 		self.0.svg.push(value.into());
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use graphene_core::blending::AlphaBlending;
+	use graphene_core::gradient::{Gradient, GradientStops, GradientType};
+	use graphene_core::raster_types::Raster;
+	use graphene_core::vector::style::{Pattern, StrokeJoin};
+	use graphene_core::vector::{PointId, VectorData};
+
+	fn dashed_rectangle(dash_lengths: &str, dash_offset: f64, transform: DAffine2) -> String {
+		let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 50.)));
+		let stroke = Stroke::new(Some(Color::BLACK), 2.).with_dash_lengths(dash_lengths).unwrap().with_dash_offset(dash_offset);
+		vector_data.style.set_stroke(stroke);
+
+		let mut table = VectorDataTable::new(vector_data);
+		*table.instance_mut_iter().next().unwrap().transform = transform;
+
+		let mut render = SvgRender::new();
+		table.render_svg(&mut render, &RenderParams::default());
+		render.svg.to_svg_string()
+	}
+
+	#[test]
+	fn dashed_rectangle_renders_dash_attributes() {
+		let svg = dashed_rectangle("4 2", 1., DAffine2::IDENTITY);
+		assert!(svg.contains(r#"stroke-dasharray="4, 2""#), "{svg}");
+		assert!(svg.contains(r#"stroke-dashoffset="1""#), "{svg}");
+	}
+
+	#[test]
+	fn invalid_dash_pattern_renders_solid() {
+		let svg = dashed_rectangle("4 -2", 1., DAffine2::IDENTITY);
+		assert!(!svg.contains("stroke-dasharray"), "{svg}");
+		assert!(!svg.contains("stroke-dashoffset"), "{svg}");
+	}
+
+	fn rendered_join(join: StrokeJoin, miter_limit: f64) -> String {
+		let mut vector_data = VectorData::from_subpath(Subpath::from_anchors_linear([DVec2::ZERO, DVec2::new(100., 10.), DVec2::new(0., 20.)], true));
+		vector_data
+			.style
+			.set_stroke(Stroke::new(Some(Color::BLACK), 2.).with_stroke_join(join).with_stroke_join_miter_limit(miter_limit));
+
+		let mut render = SvgRender::new();
+		VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+		render.svg.to_svg_string()
+	}
+
+	#[test]
+	fn miter_limit_renders_only_for_miter_joins() {
+		let svg = rendered_join(StrokeJoin::Miter, 10.);
+		assert!(svg.contains(r#"stroke-miterlimit="10""#), "{svg}");
+
+		// The SVG default of 4 is left out
+		let svg = rendered_join(StrokeJoin::Miter, 4.);
+		assert!(!svg.contains("stroke-miterlimit"), "{svg}");
+
+		let svg = rendered_join(StrokeJoin::Bevel, 10.);
+		assert!(svg.contains(r#"stroke-linejoin="bevel""#), "{svg}");
+		assert!(!svg.contains("stroke-miterlimit"), "{svg}");
+	}
+
+	/// A white background with a group above it holding a multiply-blended square, like layers in a document.
+	fn multiply_child_over_background(pass_through: bool) -> GraphicGroupTable {
+		let square = |color: Color| {
+			let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(10.)));
+			vector_data.style.set_fill(Fill::Solid(color));
+			GraphicElement::VectorData(VectorDataTable::new(vector_data))
+		};
+
+		let mut contents = GraphicGroupTable::new(square(Color::RED));
+		let child = contents.instance_mut_iter().next().unwrap();
+		child.alpha_blending.blend_mode = BlendMode::Multiply;
+		*child.source_node_id = Some(NodeId(2));
+
+		let mut document = GraphicGroupTable::new(square(Color::WHITE));
+		*document.instance_mut_iter().next().unwrap().source_node_id = Some(NodeId(1));
+		document.push(Instance {
+			instance: GraphicElement::GraphicGroup(contents),
+			alpha_blending: AlphaBlending { pass_through, ..Default::default() },
+			..Default::default()
+		});
+		document
+	}
+
+	fn rendered_svg(graphic_group: &GraphicGroupTable) -> String {
+		let mut render = SvgRender::new();
+		graphic_group.render_svg(&mut render, &RenderParams::default());
+		render.svg.to_svg_string()
+	}
+
+	#[test]
+	fn pass_through_group_blends_its_contents_against_the_backdrop() {
+		let svg = rendered_svg(&multiply_child_over_background(true));
+
+		// Nothing between the multiply-blended square and the background creates a stacking context, so the square multiplies with the background
+		assert!(svg.contains("mix-blend-mode: multiply;"), "{svg}");
+		assert!(!svg.contains("isolation"), "{svg}");
+	}
+
+	#[test]
+	fn isolated_group_blends_its_contents_only_with_each_other() {
+		let svg = rendered_svg(&multiply_child_over_background(false));
+
+		// The group's stacking context encloses the multiply-blended square, which then has only transparency within the group to multiply with
+		let isolation = svg.find("isolation: isolate;").expect(&svg);
+		let multiply = svg.find("mix-blend-mode: multiply;").expect(&svg);
+		assert!(isolation < multiply, "{svg}");
+		assert_eq!(svg.matches("isolation").count(), 1, "{svg}");
+	}
+
+	#[test]
+	fn isolated_group_contents_keep_their_metadata() {
+		// Like the group that the contents of an isolated group are moved into, the group row has no source node of its own
+		let mut metadata = RenderMetadata::default();
+		multiply_child_over_background(false).collect_metadata(&mut metadata, Footprint::default(), None);
+
+		assert!(metadata.upstream_footprints.contains_key(&NodeId(1)));
+		assert!(metadata.upstream_footprints.contains_key(&NodeId(2)));
+	}
+
+	#[test]
+	fn layer_names_become_readable_element_ids() {
+		assert_eq!(svg_id_from_name("Sky Background"), "Sky-Background");
+		assert_eq!(svg_id_from_name("  Logo (final) v2! "), "Logo-final-v2");
+		assert_eq!(svg_id_from_name("Café ☕ Menü"), "Café-Menü");
+		assert_eq!(svg_id_from_name("日本語 テキスト"), "日本語-テキスト");
+		assert_eq!(svg_id_from_name("snake_case"), "snake_case");
+		assert_eq!(svg_id_from_name("3D Box"), "layer-3D-Box");
+		assert_eq!(svg_id_from_name("☕"), "layer");
+	}
+
+	#[test]
+	fn layers_sharing_a_name_get_unique_ids() {
+		let square = || GraphicElement::VectorData(VectorDataTable::new(VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(10.)))));
+		let mut graphic_group = GraphicGroupTable::default();
+		for id in 1..=4 {
+			graphic_group.push(Instance {
+				instance: square(),
+				source_node_id: Some(NodeId(id)),
+				..Default::default()
+			});
+		}
+		let layer_names = [(1, "Circle"), (2, "Circle"), (3, "Circle 2")].into_iter().map(|(id, name)| (NodeId(id), name.to_string())).collect();
+
+		let mut render = SvgRender::new().with_layer_names(layer_names);
+		graphic_group.render_svg(&mut render, &RenderParams::default());
+		let svg = render.svg.to_svg_string();
+
+		assert!(svg.contains(r#"id="Circle" data-name="Circle""#), "{svg}");
+		assert!(svg.contains(r#"id="Circle-2" data-name="Circle""#), "{svg}");
+		// The third layer's own name becomes the ID already given to the second layer, so it's suffixed as well
+		assert!(svg.contains(r#"id="Circle-2-2" data-name="Circle 2""#), "{svg}");
+		// The layer without a name isn't given an ID
+		assert_eq!(svg.matches(" id=").count(), 3, "{svg}");
+	}
+
+	#[test]
+	fn named_artboards_are_wrapped_in_a_group_with_the_escaped_name() {
+		let artboards = ArtboardGroupTable::new_instance(Instance {
+			instance: Artboard::new(glam::IVec2::ZERO, glam::IVec2::splat(100)),
+			transform: DAffine2::IDENTITY,
+			alpha_blending: Default::default(),
+			source_node_id: Some(NodeId(1)),
+		});
+		let layer_names = HashMap::from([(NodeId(1), r#"Tom & "Jerry" <3"#.to_string())]);
+
+		let mut render = SvgRender::new().with_layer_names(layer_names);
+		artboards.render_svg(&mut render, &RenderParams::default());
+		let svg = render.svg.to_svg_string();
+
+		assert!(svg.trim_start().starts_with(r#"<g id="Tom-Jerry-3" data-name="Tom &amp; &quot;Jerry&quot; &lt;3">"#), "{svg}");
+	}
+
+	#[test]
+	fn dashes_scale_with_the_layer_transform() {
+		// The dash lengths are in the same space as the stroke weight, so a non-uniform scale is applied through the element's transform rather than baked into the path
+		let svg = dashed_rectangle("4 2", 0., DAffine2::from_scale(DVec2::new(3., 1.)));
+		assert!(svg.contains(r#"stroke-dasharray="4, 2""#), "{svg}");
+		assert!(svg.contains(r#"transform="matrix(3,0,0,1,0,0)""#), "{svg}");
+	}
+
+	fn rendered_markers(subpath: Subpath<PointId>) -> SvgRender {
+		let mut vector_data = VectorData::from_subpath(subpath);
+		let stroke = Stroke::new(Some(Color::BLACK), 2.).with_start_marker(StrokeMarker::Circle).with_end_marker(StrokeMarker::Arrow);
+		vector_data.style.set_stroke(stroke);
+
+		let mut render = SvgRender::new();
+		VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+		render
+	}
+
+	#[test]
+	fn open_path_renders_oriented_markers() {
+		let curve = bezier_rs::Bezier::from_cubic_dvec2(DVec2::ZERO, DVec2::new(0., 100.), DVec2::new(100., 100.), DVec2::new(100., 0.));
+		let render = rendered_markers(Subpath::from_bezier(&curve));
+		let svg = render.svg.to_svg_string();
+
+		assert_eq!(render.svg_defs.matches("<marker").count(), 2, "{}", render.svg_defs);
+		assert_eq!(render.svg_defs.matches(r#"orient="auto-start-reverse""#).count(), 2, "{}", render.svg_defs);
+		assert!(render.svg_defs.contains(r#"markerUnits="strokeWidth""#), "{}", render.svg_defs);
+		assert!(svg.contains(r#"marker-start="url(#marker-"#), "{svg}");
+		assert!(svg.contains(r#"marker-end="url(#marker-"#), "{svg}");
+	}
+
+	#[test]
+	fn closed_path_renders_no_markers() {
+		let render = rendered_markers(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 50.)));
+
+		assert!(!render.svg_defs.contains("<marker"), "{}", render.svg_defs);
+		assert!(!render.svg.to_svg_string().contains("marker-"));
+	}
+
+	/// A five-pointed star drawn as one self-overlapping subpath, whose central pentagon is wound around twice.
+	fn overlapping_star(fill_rule: FillRule) -> VectorDataTable {
+		let points = (0..5).map(|i| {
+			let angle = std::f64::consts::TAU * (i * 2) as f64 / 5. - std::f64::consts::FRAC_PI_2;
+			DVec2::new(angle.cos(), angle.sin()) * 50.
+		});
+		let mut vector_data = VectorData::from_subpath(Subpath::from_anchors_linear(points, true));
+		vector_data.style.set_fill(Fill::solid(Color::BLACK));
+		vector_data.style.set_fill_rule(fill_rule);
+		VectorDataTable::new(vector_data)
+	}
+
+	#[test]
+	fn overlapping_star_renders_fill_rule() {
+		let render = |fill_rule| {
+			let mut render = SvgRender::new();
+			overlapping_star(fill_rule).render_svg(&mut render, &RenderParams::default());
+			render.svg.to_svg_string()
+		};
+
+		let nonzero = render(FillRule::NonZero);
+		assert!(!nonzero.contains("fill-rule"), "{nonzero}");
+
+		let even_odd = render(FillRule::EvenOdd);
+		assert!(even_odd.contains(r#"fill-rule="evenodd""#), "{even_odd}");
+		assert!(even_odd.contains(r#"clip-rule="evenodd""#), "{even_odd}");
+	}
+
+	#[test]
+	fn overlapping_star_hit_testing_follows_fill_rule() {
+		let hits = |fill_rule, point| {
+			let mut click_targets = Vec::new();
+			overlapping_star(fill_rule).add_upstream_click_targets(&mut click_targets);
+			click_targets.iter().any(|target| target.intersect_point_no_stroke(point))
+		};
+
+		// The center is enclosed twice, so it's only filled by the nonzero rule
+		assert!(hits(FillRule::NonZero, DVec2::ZERO));
+		assert!(!hits(FillRule::EvenOdd, DVec2::ZERO));
+
+		// A point inside the top arm is enclosed once and filled by both rules
+		let arm = DVec2::new(0., -40.);
+		assert!(hits(FillRule::NonZero, arm));
+		assert!(hits(FillRule::EvenOdd, arm));
+	}
+
+	/// Renders a rectangle filled with the given gradient and returns the `<stop>` offsets and red channels written to the SVG defs.
+	fn rendered_gradient_stops(stops: GradientStops) -> Vec<(f64, f32)> {
+		let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 50.)));
+		vector_data.style.set_fill(Fill::Gradient(Gradient { stops, ..Default::default() }));
+
+		let mut render = SvgRender::new();
+		VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+
+		let attribute = |stop: &str, name: &str| stop.split(&format!(r#"{name}=""#)).nth(1).and_then(|rest| rest.split('"').next()).map(str::to_string);
+		render
+			.svg_defs
+			.split("<stop")
+			.skip(1)
+			.map(|stop| {
+				let offset = attribute(stop, "offset").map_or(0., |offset| offset.parse().unwrap());
+				let color = attribute(stop, "stop-color").unwrap();
+				let red = u8::from_str_radix(&color[1..3], 16).unwrap();
+				(offset, red as f32 / 255.)
+			})
+			.collect()
+	}
+
+	#[test]
+	fn conic_gradient_svg_matches_sampling() {
+		let gradient = Gradient {
+			gradient_type: GradientType::Conic,
+			start: DVec2::new(0.5, 0.5),
+			end: DVec2::new(0.5, 1.),
+			..Default::default()
+		};
+		let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 100.)));
+		vector_data.style.set_fill(Fill::Gradient(gradient.clone()));
+
+		let mut render = SvgRender::new();
+		VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+		assert!(render.svg_defs.starts_with("<pattern"), "{}", render.svg_defs);
+
+		// Each wedge is filled with the color sampled within it, where the 100x100 shape maps to the unit square the gradient's points are in
+		let wedges = render.svg_defs.split("<path").skip(1).collect::<Vec<_>>();
+		assert!(wedges.len() > 100);
+		for wedge in wedges {
+			let path = wedge.split(r#"d="M"#).nth(1).unwrap().split('Z').next().unwrap();
+			let points = path
+				.split('L')
+				.map(|point| {
+					let (x, y) = point.split_once(',').unwrap();
+					DVec2::new(x.parse().unwrap(), y.parse().unwrap())
+				})
+				.collect::<Vec<_>>();
+			let centroid = points.iter().sum::<DVec2>() / points.len() as f64 / 100.;
+
+			let color = wedge.split(r##"fill="#"##).nth(1).unwrap();
+			let red = u8::from_str_radix(&color[0..2], 16).unwrap() as f32 / 255.;
+			let expected = gradient.sample(centroid).r();
+			assert!((red - expected).abs() < 0.01, "Wedge {path}: expected {expected}, SVG renders {red}");
+		}
+	}
+
+	#[test]
+	fn gradient_svg_approximates_interpolation_space_with_stops() {
+		let stop_count = |interpolation| {
+			let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 100.)));
+			vector_data.style.set_fill(Fill::Gradient(Gradient { interpolation, ..Default::default() }));
+
+			let mut render = SvgRender::new();
+			VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+			render.svg_defs.matches("<stop").count()
+		};
+
+		assert_eq!(stop_count(GradientInterpolation::Srgb), 2);
+		assert_eq!(stop_count(GradientInterpolation::Oklab), DEFAULT_INTERPOLATION_SUBDIVISIONS as usize + 1);
+	}
+
+	/// Renders a 100x100 square filled with a radial gradient centered in it, returning the numeric attributes of the `<radialGradient>` element.
+	fn rendered_radial_gradient(focal_offset: DVec2, focal_radius: f64) -> HashMap<String, f64> {
+		let gradient = Gradient {
+			gradient_type: GradientType::Radial,
+			start: DVec2::new(0.5, 0.5),
+			end: DVec2::new(1., 0.5),
+			focal_offset,
+			focal_radius,
+			..Default::default()
+		};
+		let mut vector_data = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::new(100., 100.)));
+		vector_data.style.set_fill(Fill::Gradient(gradient));
+
+		let mut render = SvgRender::new();
+		VectorDataTable::new(vector_data).render_svg(&mut render, &RenderParams::default());
+
+		let element = render.svg_defs.split("<radialGradient").nth(1).unwrap().split('>').next().unwrap();
+		element
+			.split_whitespace()
+			.filter_map(|attribute| {
+				let (name, value) = attribute.split_once('=')?;
+				Some((name.to_string(), value.trim_matches('"').parse().ok()?))
+			})
+			.collect()
+	}
+
+	#[test]
+	fn radial_gradient_focal_point_svg_output() {
+		let centered = rendered_radial_gradient(DVec2::ZERO, 0.);
+		assert_eq!((centered["cx"], centered["cy"], centered["r"]), (50., 50., 50.));
+		assert!(!centered.contains_key("fx") && !centered.contains_key("fy") && !centered.contains_key("fr"));
+
+		let focal = rendered_radial_gradient(DVec2::new(0.25, -0.1), 0.2);
+		assert!((focal["fx"] - 75.).abs() < 1e-10 && (focal["fy"] - 40.).abs() < 1e-10, "{focal:?}");
+		assert!((focal["fr"] - 10.).abs() < 1e-10);
+	}
+
+	#[test]
+	fn radial_gradient_focal_point_outside_is_clamped() {
+		let clamped = rendered_radial_gradient(DVec2::new(2., 0.), 0.);
+		let distance = DVec2::new(clamped["fx"] - 50., clamped["fy"] - 50.).length();
+		assert!(
+			distance < 50. && distance > 49.,
+			"Focal point should be just inside the end circle, but it's {distance} from the center"
+		);
+	}
+
+	#[test]
+	fn gradient_midpoint_changes_svg_output() {
+		let mut biased = GradientStops::default();
+		biased.set_midpoint(0, 0.25);
+
+		assert_eq!(rendered_gradient_stops(GradientStops::default()), vec![(0., 0.), (1., 1.)]);
+		assert_ne!(rendered_gradient_stops(biased.clone()), rendered_gradient_stops(GradientStops::default()));
+	}
+
+	#[test]
+	fn gradient_midpoint_svg_matches_evaluation() {
+		let mut biased = GradientStops::default();
+		biased.set_midpoint(0, 0.25);
+
+		// The SVG renderer interpolates linearly between the written stops, so sample that piecewise linear ramp and compare it to the internal evaluation
+		let rendered = rendered_gradient_stops(biased.clone());
+		let svg_ramp = GradientStops::new(rendered.into_iter().map(|(offset, red)| (offset, Color::from_rgbaf32_unchecked(red, red, red, 1.))).collect());
+		for step in 0..=100 {
+			let t = step as f64 / 100.;
+			let (expected, actual) = (biased.evaluate(t).r(), svg_ramp.evaluate(t).r());
+			assert!((expected - actual).abs() < 0.025, "At {t}: expected {expected}, SVG renders {actual}");
+		}
+	}
+
+	/// A 100×100 square filled with a pattern of red dots, returning the rendered SVG and its defs.
+	fn dot_pattern_square(pattern: Pattern, transform: DAffine2) -> (String, String) {
+		let mut square = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+		square.style.set_fill(Fill::Pattern(pattern));
+
+		let mut table = VectorDataTable::new(square);
+		*table.instance_mut_iter().next().unwrap().transform = transform;
+
+		let mut render = SvgRender::new();
+		table.render_svg(&mut render, &RenderParams::default());
+		(render.svg.to_svg_string(), render.svg_defs)
+	}
+
+	fn dot_pattern() -> Pattern {
+		let mut dot = VectorData::from_subpath(Subpath::new_ellipse(DVec2::ZERO, DVec2::splat(4.)));
+		dot.style.set_fill(Fill::Solid(Color::RED));
+		Pattern {
+			tile: GraphicGroupTable::from(dot),
+			spacing: DVec2::splat(6.),
+			..Default::default()
+		}
+	}
+
+	#[test]
+	fn dot_pattern_renders_as_svg_pattern() {
+		let pattern = dot_pattern();
+		let cell_size = pattern.cell_size().unwrap();
+		let (svg, defs) = dot_pattern_square(pattern, DAffine2::IDENTITY);
+
+		let pattern_id = defs.split(r#"<pattern id=""#).nth(1).and_then(|rest| rest.split('"').next()).expect(&defs);
+		assert!(svg.contains(&format!(r##"fill="url('#{pattern_id}')""##)), "{svg}");
+		assert!(defs.contains(&format!(r#"patternUnits="userSpaceOnUse" width="{}" height="{}">"#, cell_size.x, cell_size.y)), "{defs}");
+
+		// The dot is drawn once inside the pattern, which repeats it
+		let tile = defs.split("<pattern").nth(1).unwrap();
+		assert_eq!(tile.matches("<path").count(), 1, "{defs}");
+		assert!(tile.contains(r##"fill="#ff0000""##), "{defs}");
+	}
+
+	#[test]
+	fn dot_pattern_follows_the_layer_transform() {
+		let pattern = Pattern {
+			offset: DVec2::new(5., 0.),
+			..dot_pattern()
+		};
+		let (_, defs) = dot_pattern_square(pattern, DAffine2::from_scale(DVec2::splat(2.)));
+
+		// The grid's offset is in the layer's local space, so it's scaled along with the layer
+		assert!(defs.contains(r#"patternTransform="matrix(2,0,0,2,10,0)""#), "{defs}");
+	}
+
+	#[test]
+	fn dot_pattern_larger_than_the_shape_is_clipped_to_it() {
+		let pattern = Pattern { scale: 50., ..dot_pattern() };
+		let cell_size = pattern.cell_size().unwrap();
+		assert!(cell_size.x > 100. && cell_size.y > 100.);
+
+		let (svg, defs) = dot_pattern_square(pattern.clone(), DAffine2::IDENTITY);
+		assert!(svg.contains("url('#"), "{svg}");
+		assert!(defs.contains(&format!(r#"width="{}" height="{}""#, cell_size.x, cell_size.y)), "{defs}");
+
+		// The huge tile doesn't grow the bounds of the layer it fills
+		let mut square = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(100.)));
+		square.style.set_fill(Fill::Pattern(pattern));
+		assert_eq!(VectorDataTable::new(square).bounding_box(DAffine2::IDENTITY, false), Some([DVec2::ZERO, DVec2::splat(100.)]));
+	}
+
+	#[test]
+	fn pattern_without_a_tile_renders_no_fill() {
+		let (svg, defs) = dot_pattern_square(Pattern::default(), DAffine2::IDENTITY);
+		assert!(svg.contains(r#"fill="none""#), "{svg}");
+		assert!(!defs.contains("<pattern"), "{defs}");
+	}
+
+	/// The SVG and its defs for a red layer effect at half opacity, drawn from a 4×4 black square.
+	fn rendered_effect(effect_type: EffectType, blur: f64, render_params: &RenderParams) -> (String, String) {
+		let mut square = VectorData::from_subpath(Subpath::new_rect(DVec2::ZERO, DVec2::splat(4.)));
+		square.style.set_fill(Fill::Solid(Color::BLACK));
+		let effect = LayerEffect {
+			effect_type,
+			offset: DVec2::new(2., 1.),
+			blur,
+			color: Color::RED,
+			opacity: 0.5,
+			source: GraphicGroupTable::from(square),
+			rasterized: RasterDataTable::new(Raster::new_cpu(Image::new(1, 1, Color::RED))),
+		};
+
+		let mut render = SvgRender::new();
+		GraphicGroupTable::new(GraphicElement::Effect(Box::new(effect))).render_svg(&mut render, render_params);
+		(render.svg.to_svg_string(), render.svg_defs)
+	}
+
+	/// Checks that the effect is drawn by applying its filter to the square, rather than from its rasterized image, and returns the filter's primitives.
+	fn filter_primitives(svg: &str, defs: &str) -> String {
+		let filter_id = defs.split(r#"<filter id=""#).nth(1).and_then(|rest| rest.split('"').next()).expect(defs);
+		assert!(svg.contains(&format!(r#"filter="url(#{filter_id})""#)), "{svg}");
+		assert!(svg.contains("<path"), "{svg}");
+		assert!(!svg.contains("<image"), "{svg}");
+		defs.split_once('>').unwrap().1.to_string()
+	}
+
+	#[test]
+	fn drop_shadow_renders_as_svg_filter() {
+		let (svg, defs) = rendered_effect(EffectType::DropShadow, 3., &RenderParams::default());
+		let primitives = filter_primitives(&svg, &defs);
+
+		// The filter region reaches three standard deviations of blur beyond the offset square
+		assert!(defs.contains(r#"filterUnits="userSpaceOnUse" x="-11" y="-10" width="26" height="24">"#), "{defs}");
+		assert_eq!(
+			primitives,
+			concat!(
+				r#"<feOffset in="SourceAlpha" dx="2" dy="1" result="offset" />"#,
+				r#"<feGaussianBlur in="offset" stdDeviation="3" result="blurred" />"#,
+				r##"<feFlood flood-color="#ff0000" flood-opacity="0.5" />"##,
+				r#"<feComposite in2="blurred" operator="in" />"#,
+				"</filter>",
+			)
+		);
+	}
+
+	#[test]
+	fn inner_shadow_renders_as_svg_filter() {
+		let (svg, defs) = rendered_effect(EffectType::InnerShadow, 3., &RenderParams::default());
+		let primitives = filter_primitives(&svg, &defs);
+
+		// The shadow is cast from the inverted silhouette and kept within the square
+		assert_eq!(
+			primitives,
+			concat!(
+				r#"<feComponentTransfer in="SourceAlpha" result="inverted"><feFuncA type="table" tableValues="1 0" /></feComponentTransfer>"#,
+				r#"<feOffset in="inverted" dx="2" dy="1" result="offset" />"#,
+				r#"<feGaussianBlur in="offset" stdDeviation="3" result="blurred" />"#,
+				r#"<feComposite in="blurred" in2="SourceAlpha" operator="in" result="inside" />"#,
+				r##"<feFlood flood-color="#ff0000" flood-opacity="0.5" />"##,
+				r#"<feComposite in2="inside" operator="in" />"#,
+				"</filter>",
+			)
+		);
+	}
+
+	#[test]
+	fn outer_glow_renders_as_svg_filter() {
+		let (svg, defs) = rendered_effect(EffectType::OuterGlow, 3., &RenderParams::default());
+		let primitives = filter_primitives(&svg, &defs);
+
+		// The glow ignores the offset and spreads evenly on all sides
+		assert!(defs.contains(r#"x="-9" y="-9" width="22" height="22">"#), "{defs}");
+		assert!(primitives.starts_with(r#"<feOffset in="SourceAlpha" dx="0" dy="0" result="offset" />"#), "{primitives}");
+		assert!(primitives.contains(r#"<feGaussianBlur in="offset" stdDeviation="3" result="blurred" />"#), "{primitives}");
+	}
+
+	#[test]
+	fn unblurred_effect_leaves_out_the_blur_primitive() {
+		let (svg, defs) = rendered_effect(EffectType::DropShadow, 0., &RenderParams::default());
+		let primitives = filter_primitives(&svg, &defs);
+
+		assert!(!primitives.contains("feGaussianBlur"), "{primitives}");
+		assert!(primitives.contains(r#"<feComposite in2="offset" operator="in" />"#), "{primitives}");
+	}
+
+	#[test]
+	fn effect_is_hidden_in_outline_mode() {
+		let render_params = RenderParams {
+			view_mode: ViewMode::Outline,
+			..Default::default()
+		};
+		let (svg, defs) = rendered_effect(EffectType::DropShadow, 3., &render_params);
+
+		assert!(!svg.contains("<path"), "{svg}");
+		assert!(defs.is_empty(), "{defs}");
+	}
+}
@@ -1,3 +1,4 @@
+use crate::node_cache::{CachingNode, NodeCache, NodeCacheStats};
 use crate::node_registry;
 use dyn_any::StaticType;
 use graph_craft::Type;
@@ -6,9 +7,10 @@ use graph_craft::document::value::{TaggedValue, UpcastAsRefNode, UpcastNode};
 use graph_craft::graphene_compiler::Executor;
 use graph_craft::proto::{ConstructionArgs, GraphError, LocalFuture, NodeContainer, ProtoNetwork, ProtoNode, SharedNodeContainer, TypeErasedBox, TypingContext};
 use graph_craft::proto::{GraphErrorType, GraphErrors};
+use graphene_core::memo;
 use std::collections::{HashMap, HashSet};
 use std::error::Error;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
 
 /// An executor of a node graph that does not require an online compilation server, and instead uses `Box<dyn ...>`.
 #[derive(Clone)]
@@ -109,6 +111,16 @@ impl DynamicExecutor {
 		self.typing_context.type_of(self.output).map(|node_io| node_io.return_value.clone())
 	}
 
+	/// The reuse statistics of the cached outputs of each node, keyed by the document node path.
+	pub fn node_cache_stats(&self) -> HashMap<Path, NodeCacheStats> {
+		self.tree.node_cache_stats()
+	}
+
+	/// Sets the memory budget of the cached node outputs in bytes, or restores the default budget with `None`.
+	pub fn set_node_cache_budget(&self, budget: Option<u64>) {
+		self.tree.node_cache().lock().unwrap().set_memory_budget(budget);
+	}
+
 	pub fn document_node_types<'a>(&'a self, nodes: impl Iterator<Item = Path> + 'a) -> impl Iterator<Item = (Path, NodeTypes)> + 'a {
 		nodes.flat_map(|id| self.tree.source_map().get(&id).map(|(_, b)| (id, b.clone())))
 		// TODO: https://github.com/GraphiteEditor/Graphite/issues/1767
@@ -182,6 +194,8 @@ pub struct BorrowTree {
 	nodes: HashMap<NodeId, (SharedNodeContainer, Path)>,
 	/// A hashmap from the document path to the proto node ID.
 	source_map: HashMap<Path, (NodeId, NodeTypes)>,
+	/// The outputs of the nodes from earlier evaluations, which are reused while their inputs and context stay the same.
+	cache: Arc<Mutex<NodeCache>>,
 }
 
 impl BorrowTree {
@@ -307,6 +321,7 @@ impl BorrowTree {
 	/// - Returns `None` if the node is not found in the `nodes` HashMap.
 	pub fn free_node(&mut self, id: NodeId) -> Option<Path> {
 		let (_, path) = self.nodes.remove(&id)?;
+		self.cache.lock().unwrap().remove_node(id);
 		if self.source_map.get(&path)?.0 == id {
 			self.source_map.remove(&path);
 			return Some(path);
@@ -397,17 +412,46 @@ impl BorrowTree {
 				let construction_nodes = self.node_deps(&ids);
 				let constructor = typing_context.constructor(id).ok_or_else(|| vec![GraphError::new(&proto_node, GraphErrorType::NoConstructor)])?;
 				let node = constructor(construction_nodes).await;
-				let node = NodeContainer::new(node);
+				let mut node = NodeContainer::new(node);
+				if Self::is_cacheable(&proto_node) {
+					node = NodeContainer::new(Box::new(CachingNode::new(node, id, self.cache.clone())));
+				}
 				self.store_node(node, id, path.into());
 			}
 		};
 		Ok(())
 	}
 
+	/// Whether the outputs of the node are kept in the [`NodeCache`] for reuse across evaluations.
+	///
+	/// Only memoization nodes are, since they mark the outputs the graph means to reuse, and converting and cloning the output of every node would take longer than evaluating most of them again.
+	/// Nodes which skip deduplication depend on more than their inputs.
+	fn is_cacheable(proto_node: &ProtoNode) -> bool {
+		!proto_node.skip_deduplication && proto_node.identifier == memo::memo::IDENTIFIER
+	}
+
 	/// Returns the source map of the borrow tree
 	pub fn source_map(&self) -> &HashMap<Path, (NodeId, NodeTypes)> {
 		&self.source_map
 	}
+
+	/// The cache of node outputs shared by the nodes of the borrow tree
+	pub fn node_cache(&self) -> &Arc<Mutex<NodeCache>> {
+		&self.cache
+	}
+
+	/// The reuse statistics of the cached outputs of each node, keyed by the document node path.
+	///
+	/// Several proto nodes can originate from the same document node, in which case their statistics are summed.
+	pub fn node_cache_stats(&self) -> HashMap<Path, NodeCacheStats> {
+		let cache = self.cache.lock().unwrap();
+		let mut stats = HashMap::<Path, NodeCacheStats>::new();
+		for (id, (_, path)) in &self.nodes {
+			*stats.entry(path.clone()).or_default() += cache.stats(*id);
+		}
+		stats.retain(|_, stats| *stats != NodeCacheStats::default());
+		stats
+	}
 }
 
 #[cfg(test)]
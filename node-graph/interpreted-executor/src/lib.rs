@@ -1,4 +1,5 @@
 pub mod dynamic_executor;
+pub mod node_cache;
 pub mod node_registry;
 pub mod util;
 
@@ -0,0 +1,540 @@
+use dyn_any::DynAny;
+use graph_craft::document::NodeId;
+use graph_craft::document::value::TaggedValue;
+use graph_craft::proto::{Any, FutureAny, SharedNodeContainer};
+use graphene_core::time_dependency::{self, mark_time_read};
+use graphene_core::{Context, Node, cancellation};
+use std::any::TypeId;
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::hash::{DefaultHasher, Hash, Hasher};
+use std::sync::{Arc, Mutex};
+
+/// The memory budget of a [`NodeCache`] unless the user's preferences set another one, in bytes.
+pub const DEFAULT_MEMORY_BUDGET: u64 = 512 * 1024 * 1024;
+
+/// Statistics about how well the cached outputs of a node are reused across evaluations, such as for display in a debug panel to find the nodes which thrash.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
+pub struct NodeCacheStats {
+	/// The number of evaluations of the node which reused a cached output.
+	pub hits: u64,
+	/// The number of evaluations of the node which had to compute its output.
+	pub misses: u64,
+	/// The number of cached outputs of the node, one for each context it was recently evaluated with.
+	pub entries: usize,
+	/// The estimated memory used by the cached outputs of the node, in bytes.
+	pub bytes: u64,
+}
+
+impl std::ops::AddAssign for NodeCacheStats {
+	fn add_assign(&mut self, other: Self) {
+		self.hits += other.hits;
+		self.misses += other.misses;
+		self.entries += other.entries;
+		self.bytes += other.bytes;
+	}
+}
+
+#[derive(Debug)]
+struct CachedOutput {
+	value: TaggedValue,
+	bytes: u64,
+	// The value of the cache's use counter when this output was last stored or reused, which is its key in the recency order.
+	last_used: u64,
+}
+
+/// The outputs of the memoization nodes of a graph, keyed by the node and the context it was evaluated with, which are reused by later evaluations.
+///
+/// The ID of a compiled node is a hash of its implementation and inputs, which includes the IDs of the nodes upstream of it, so changing
+/// any upstream input gives the node a new ID and its old outputs are no longer used. The least recently used outputs are evicted to stay
+/// within the memory budget.
+///
+/// Only the outputs of the nodes which read the time, or are downstream of one which does, are keyed by the time too. So while an
+/// animation plays, the other nodes keep reusing their outputs.
+#[derive(Debug, Default)]
+pub struct NodeCache {
+	outputs: HashMap<(NodeId, u64), CachedOutput>,
+	// The keys of the outputs ordered from the least to the most recently used, so the next one to evict is found without scanning them all
+	recency: BTreeMap<u64, (NodeId, u64)>,
+	stats: HashMap<NodeId, NodeCacheStats>,
+	// Nodes whose output type can't be cached, which are skipped without looking them up again
+	uncacheable: HashSet<NodeId>,
+	// Nodes whose evaluation was found to read the time, whose outputs are cached for each time they're evaluated at
+	time_dependent: HashSet<NodeId>,
+	bytes: u64,
+	// Overrides the default memory budget, in bytes.
+	memory_budget: Option<u64>,
+	use_counter: u64,
+}
+
+impl NodeCache {
+	/// The cached output of the node evaluated with the context, counted as a hit or miss in the node's statistics.
+	fn get(&mut self, node: NodeId, context_hash: u64) -> Option<TaggedValue> {
+		self.use_counter += 1;
+
+		let stats = self.stats.entry(node).or_default();
+		let Some(cached) = self.outputs.get_mut(&(node, context_hash)) else {
+			stats.misses += 1;
+			return None;
+		};
+		stats.hits += 1;
+		self.recency.remove(&cached.last_used);
+		self.recency.insert(self.use_counter, (node, context_hash));
+		cached.last_used = self.use_counter;
+		Some(cached.value.clone())
+	}
+
+	fn insert(&mut self, node: NodeId, context_hash: u64, value: TaggedValue) {
+		// A value which would take up the entire budget on its own would only evict everything else to then be evicted itself
+		let bytes = value.estimated_size();
+		if bytes > self.memory_budget() {
+			return;
+		}
+
+		self.use_counter += 1;
+		let cached = CachedOutput {
+			value,
+			bytes,
+			last_used: self.use_counter,
+		};
+		if let Some(replaced) = self.outputs.insert((node, context_hash), cached) {
+			self.recency.remove(&replaced.last_used);
+			self.forget_output(node, replaced.bytes);
+		}
+		self.recency.insert(self.use_counter, (node, context_hash));
+
+		let stats = self.stats.entry(node).or_default();
+		stats.entries += 1;
+		stats.bytes += bytes;
+		self.bytes += bytes;
+
+		self.evict_over_budget();
+	}
+
+	fn forget_output(&mut self, node: NodeId, bytes: u64) {
+		if let Some(stats) = self.stats.get_mut(&node) {
+			stats.entries -= 1;
+			stats.bytes -= bytes;
+		}
+		self.bytes -= bytes;
+	}
+
+	fn mark_uncacheable(&mut self, node: NodeId) {
+		self.uncacheable.insert(node);
+		self.stats.remove(&node);
+	}
+
+	/// Evicts the least recently used outputs until the cache fits within its memory budget.
+	fn evict_over_budget(&mut self) {
+		let budget = self.memory_budget();
+
+		while self.bytes > budget {
+			let Some((_, key)) = self.recency.pop_first() else { break };
+
+			if let Some(evicted) = self.outputs.remove(&key) {
+				self.forget_output(key.0, evicted.bytes);
+			}
+		}
+	}
+
+	/// Removes the cached outputs and statistics of a node which is no longer part of the graph.
+	pub fn remove_node(&mut self, node: NodeId) {
+		let removed_bytes = self.stats.remove(&node).map_or(0, |stats| stats.bytes);
+		self.outputs.retain(|(id, _), _| *id != node);
+		self.recency.retain(|_, (id, _)| *id != node);
+		self.uncacheable.remove(&node);
+		self.time_dependent.remove(&node);
+		self.bytes -= removed_bytes;
+	}
+
+	/// Removes all cached outputs, keeping the hit and miss counts.
+	pub fn clear(&mut self) {
+		self.outputs.clear();
+		self.recency.clear();
+		self.bytes = 0;
+		for stats in self.stats.values_mut() {
+			stats.entries = 0;
+			stats.bytes = 0;
+		}
+	}
+
+	pub fn memory_budget(&self) -> u64 {
+		self.memory_budget.unwrap_or(DEFAULT_MEMORY_BUDGET)
+	}
+
+	/// Sets the memory budget in bytes, evicting outputs to fit within it. `None` restores the default budget.
+	pub fn set_memory_budget(&mut self, budget: Option<u64>) {
+		self.memory_budget = budget;
+		self.evict_over_budget();
+	}
+
+	/// The estimated memory used by all cached outputs, in bytes.
+	pub fn bytes(&self) -> u64 {
+		self.bytes
+	}
+
+	pub fn stats(&self, node: NodeId) -> NodeCacheStats {
+		self.stats.get(&node).copied().unwrap_or_default()
+	}
+
+	/// Whether the node's output was found to depend on the time it's evaluated at.
+	pub fn is_time_dependent(&self, node: NodeId) -> bool {
+		self.time_dependent.contains(&node)
+	}
+}
+
+/// Identifies the call argument of a node across evaluations.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct CallHash {
+	/// The hash of everything in the call argument besides its time.
+	context: u64,
+	/// The hash of the time in the call argument.
+	time: u64,
+}
+
+impl CallHash {
+	/// The key of the node's output in the cache, which only includes the time for the nodes whose output depends on it.
+	fn key(self, time_dependent: bool) -> u64 {
+		if !time_dependent {
+			return self.context;
+		}
+		let mut hasher = DefaultHasher::new();
+		(self.context, self.time).hash(&mut hasher);
+		hasher.finish()
+	}
+}
+
+/// The call argument of a node along with the hash identifying it across evaluations, if it's one whose outputs can be cached.
+fn hash_call_argument(input: Any<'_>) -> (Any<'_>, Option<CallHash>) {
+	let type_id = DynAny::type_id(input.as_ref());
+	if type_id == TypeId::of::<()>() {
+		return (input, Some(CallHash { context: 0, time: 0 }));
+	}
+	if type_id != TypeId::of::<Context>() {
+		return (input, None);
+	}
+
+	let context = *dyn_any::downcast::<Context>(input).expect("The call argument was checked to be a context");
+	let hash = match &context {
+		Some(context) => context.stable_hash().map(|hash| CallHash {
+			context: hash,
+			time: context.time_hash(),
+		}),
+		None => Some(CallHash { context: 0, time: 0 }),
+	};
+	(Box::new(context), hash)
+}
+
+/// Wraps a compiled memoization node to reuse its outputs from earlier evaluations with the same context, which are kept in a [`NodeCache`].
+///
+/// The memoization node only keeps the output for the last context, which is still used for the call arguments this can't identify across evaluations.
+pub struct CachingNode {
+	node: SharedNodeContainer,
+	id: NodeId,
+	cache: Arc<Mutex<NodeCache>>,
+}
+
+impl CachingNode {
+	pub fn new(node: SharedNodeContainer, id: NodeId, cache: Arc<Mutex<NodeCache>>) -> Self {
+		Self { node, id, cache }
+	}
+}
+
+impl<'i> Node<'i, Any<'i>> for CachingNode {
+	type Output = FutureAny<'i>;
+
+	fn eval(&'i self, input: Any<'i>) -> Self::Output {
+		let (input, call_hash) = hash_call_argument(input);
+		let call_hash = call_hash.filter(|_| !self.cache.lock().unwrap().uncacheable.contains(&self.id));
+		let Some(call_hash) = call_hash else { return self.node.eval(input) };
+
+		let mut cache = self.cache.lock().unwrap();
+		let time_dependent = cache.is_time_dependent(self.id);
+		if let Some(value) = cache.get(self.id, call_hash.key(time_dependent)) {
+			return Box::pin(async move {
+				// The nodes downstream of this one depend on the time too, which they'd have learned from evaluating this one again
+				if time_dependent {
+					mark_time_read();
+				}
+				value.to_dynany()
+			});
+		}
+		drop(cache);
+
+		// Evaluating the node within the tracked future also sees the time it reads before returning its own future
+		let output = time_dependency::track(async move { self.node.eval(input).await });
+		Box::pin(async move {
+			let (output, read_time) = output.await;
+			if read_time {
+				mark_time_read();
+			}
+			if !TaggedValue::can_convert_from_any(output.as_ref()) {
+				self.cache.lock().unwrap().mark_uncacheable(self.id);
+				return output;
+			}
+			let value = TaggedValue::try_from_any(output).expect("The output was checked to be convertible");
+
+			// Rendered output is drawn to a surface as it's evaluated, which a cached copy wouldn't do again
+			let cacheable = !matches!(value, TaggedValue::RenderOutput(_) | TaggedValue::SurfaceFrame(_) | TaggedValue::EditorApi(_));
+			let mut cache = self.cache.lock().unwrap();
+			if !cacheable {
+				cache.mark_uncacheable(self.id);
+			} else if !cancellation::is_cancelled() {
+				if read_time {
+					cache.time_dependent.insert(self.id);
+				}
+				// A superseded evaluation may have produced an incomplete value, which mustn't be reused by later evaluations
+				cache.insert(self.id, call_hash.key(cache.is_time_dependent(self.id)), value.clone());
+			}
+			value.to_dynany()
+		})
+	}
+
+	fn reset(&self) {
+		self.cache.lock().unwrap().remove_node(self.id);
+		self.node.reset();
+	}
+
+	fn serialize(&self) -> Option<Arc<dyn std::any::Any + Send + Sync>> {
+		self.node.serialize()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::dynamic_executor::DynamicExecutor;
+	use futures::executor::block_on;
+	use graph_craft::concrete;
+	use graph_craft::document::{DocumentNode, DocumentNodeImplementation, NodeInput, NodeNetwork};
+	use graph_craft::graphene_compiler::Compiler;
+	use graphene_core::OwnedContextImpl;
+	use graphene_core::transform::Footprint;
+
+	/// A network adding 2 to the given number.
+	fn add_network(augend: f64) -> NodeNetwork {
+		NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(0), 0)],
+			nodes: [(
+				NodeId(0),
+				DocumentNode {
+					inputs: vec![NodeInput::value(TaggedValue::F64(augend), false), NodeInput::value(TaggedValue::F64(2.), false)],
+					manual_composition: Some(concrete!(Context)),
+					implementation: DocumentNodeImplementation::ProtoNode(graphene_std::math_nodes::add::IDENTIFIER),
+					..Default::default()
+				},
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		}
+	}
+
+	/// A network adding the animation time to the sum of 1 and 2, which are added by a node that doesn't depend on the time.
+	fn add_to_time_network() -> NodeNetwork {
+		let node = |inputs, implementation| DocumentNode {
+			inputs,
+			manual_composition: Some(concrete!(Context)),
+			implementation: DocumentNodeImplementation::ProtoNode(implementation),
+			..Default::default()
+		};
+		let value = |value| NodeInput::value(TaggedValue::F64(value), false);
+
+		NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(0), 0)],
+			nodes: [
+				(
+					NodeId(0),
+					node(vec![NodeInput::node(NodeId(1), 0), NodeInput::node(NodeId(2), 0)], graphene_std::math_nodes::add::IDENTIFIER),
+				),
+				(
+					NodeId(1),
+					node(vec![NodeInput::value(TaggedValue::None, false), value(1.), value(0.)], graphene_core::animation::time::IDENTIFIER),
+				),
+				(NodeId(2), node(vec![value(1.), value(2.)], graphene_std::math_nodes::add::IDENTIFIER)),
+			]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		}
+	}
+
+	/// Puts a memoization node in the place of each node, whose outputs are the ones cached. The nodes themselves are moved to their ID plus 100.
+	fn memoized(mut network: NodeNetwork) -> NodeNetwork {
+		let ids = network.nodes.keys().copied().collect::<Vec<_>>();
+		for id in ids {
+			let node = network.nodes.remove(&id).unwrap();
+			let memo = DocumentNode {
+				inputs: vec![NodeInput::node(NodeId(id.0 + 100), 0)],
+				manual_composition: Some(concrete!(Context)),
+				implementation: DocumentNodeImplementation::ProtoNode(graphene_core::memo::memo::IDENTIFIER),
+				..Default::default()
+			};
+			network.nodes.insert(NodeId(id.0 + 100), node);
+			network.nodes.insert(id, memo);
+		}
+		network
+	}
+
+	fn compile(network: NodeNetwork) -> graph_craft::proto::ProtoNetwork {
+		Compiler {}.compile_single(memoized(network)).expect("Graph should be generated")
+	}
+
+	fn context(scale: f64) -> Context {
+		let footprint = Footprint {
+			transform: glam::DAffine2::from_scale(glam::DVec2::splat(scale)),
+			..Default::default()
+		};
+		OwnedContextImpl::default().with_footprint(footprint).into_context()
+	}
+
+	fn evaluate(executor: &DynamicExecutor, context: Context) -> TaggedValue {
+		block_on(executor.tree().eval_tagged_value(executor.output(), context)).expect("The graph should evaluate")
+	}
+
+	fn at_time(time: f64) -> Context {
+		OwnedContextImpl::default().with_footprint(Footprint::default()).with_animation_time(time).into_context()
+	}
+
+	fn node_stats(executor: &DynamicExecutor, node: NodeId) -> NodeCacheStats {
+		executor.node_cache_stats().get([node].as_slice()).copied().unwrap_or_default()
+	}
+
+	fn add_node_stats(executor: &DynamicExecutor) -> NodeCacheStats {
+		node_stats(executor, NodeId(0))
+	}
+
+	#[test]
+	fn outputs_are_reused_until_an_upstream_input_changes() {
+		let mut executor = block_on(DynamicExecutor::new(compile(add_network(1.)))).unwrap();
+
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(3.));
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(3.));
+		let stats = add_node_stats(&executor);
+		assert_eq!((stats.hits, stats.misses, stats.entries), (1, 1, 1));
+		assert!(stats.bytes > 0);
+
+		// Changing the input gives the node a new ID, so the output cached for its old ID isn't reused
+		block_on(executor.update(compile(add_network(5.)))).unwrap();
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(7.));
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(7.));
+		let updated_stats = add_node_stats(&executor);
+		assert_eq!((updated_stats.hits - stats.hits, updated_stats.misses - stats.misses), (1, 1));
+
+		// Only the outputs of the memoization node are cached, not those of the node it memoizes
+		assert_eq!(node_stats(&executor, NodeId(100)), NodeCacheStats::default());
+	}
+
+	#[test]
+	fn nodes_outside_of_memoization_nodes_are_evaluated_again() {
+		let node = |inputs, implementation| DocumentNode {
+			inputs,
+			manual_composition: Some(concrete!(Context)),
+			implementation: DocumentNodeImplementation::ProtoNode(implementation),
+			..Default::default()
+		};
+		let value = |value| NodeInput::value(TaggedValue::F64(value), false);
+
+		// The sum of 1 and 2 is memoized, while adding 3 to it is left to each evaluation
+		let network = NodeNetwork {
+			exports: vec![NodeInput::node(NodeId(0), 0)],
+			nodes: [
+				(NodeId(0), node(vec![NodeInput::node(NodeId(1), 0), value(3.)], graphene_std::math_nodes::add::IDENTIFIER)),
+				(NodeId(1), node(vec![NodeInput::node(NodeId(2), 0)], graphene_core::memo::memo::IDENTIFIER)),
+				(NodeId(2), node(vec![value(1.), value(2.)], graphene_std::math_nodes::add::IDENTIFIER)),
+			]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		};
+		let executor = block_on(DynamicExecutor::new(Compiler {}.compile_single(network).expect("Graph should be generated"))).unwrap();
+
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(6.));
+		assert_eq!(evaluate(&executor, context(1.)), TaggedValue::F64(6.));
+
+		// The memoized output is reused, so the node it memoizes isn't evaluated again and has nothing cached of its own
+		let memo = node_stats(&executor, NodeId(1));
+		assert_eq!((memo.hits, memo.misses, memo.entries), (1, 1, 1));
+		assert_eq!(node_stats(&executor, NodeId(2)), NodeCacheStats::default());
+
+		// The node downstream of the memoization node is evaluated again without going through the cache
+		assert_eq!(node_stats(&executor, NodeId(0)), NodeCacheStats::default());
+	}
+
+	#[test]
+	fn outputs_are_cached_separately_for_each_footprint() {
+		let executor = block_on(DynamicExecutor::new(compile(add_network(1.)))).unwrap();
+
+		evaluate(&executor, context(1.));
+		evaluate(&executor, context(2.));
+		assert_eq!(add_node_stats(&executor).misses, 2);
+
+		// Returning to an earlier footprint reuses the output from then
+		evaluate(&executor, context(1.));
+		evaluate(&executor, context(2.));
+		let stats = add_node_stats(&executor);
+		assert_eq!((stats.hits, stats.misses, stats.entries), (2, 2, 2));
+	}
+
+	#[test]
+	fn graphs_without_a_time_node_are_not_evaluated_again_while_the_time_changes() {
+		let executor = block_on(DynamicExecutor::new(compile(add_network(1.)))).unwrap();
+
+		for time in [0., 1., 2.] {
+			assert_eq!(evaluate(&executor, at_time(time)), TaggedValue::F64(3.));
+		}
+		let stats = add_node_stats(&executor);
+		assert_eq!((stats.hits, stats.misses), (2, 1));
+	}
+
+	#[test]
+	fn only_the_nodes_depending_on_the_time_are_evaluated_again_when_it_changes() {
+		let executor = block_on(DynamicExecutor::new(compile(add_to_time_network()))).unwrap();
+
+		assert_eq!(evaluate(&executor, at_time(0.5)), TaggedValue::F64(3.5));
+		assert_eq!(evaluate(&executor, at_time(1.5)), TaggedValue::F64(4.5));
+		let (output, time, sum) = (node_stats(&executor, NodeId(0)), node_stats(&executor, NodeId(1)), node_stats(&executor, NodeId(2)));
+		assert_eq!((output.hits, output.misses), (0, 2));
+		assert_eq!((time.hits, time.misses), (0, 2));
+		assert_eq!((sum.hits, sum.misses), (1, 1));
+
+		// Returning to an earlier time reuses the output from then
+		assert_eq!(evaluate(&executor, at_time(0.5)), TaggedValue::F64(3.5));
+		assert_eq!(node_stats(&executor, NodeId(0)).hits, 1);
+	}
+
+	#[test]
+	fn least_recently_used_outputs_are_evicted_over_budget() {
+		let mut cache = NodeCache::default();
+		let value = TaggedValue::String("x".repeat(1000));
+		let size = value.estimated_size();
+		cache.set_memory_budget(Some(size * 2));
+
+		let (first, second, third) = (NodeId(1), NodeId(2), NodeId(3));
+		cache.insert(first, 0, value.clone());
+		cache.insert(second, 0, value.clone());
+		assert!(cache.get(first, 0).is_some());
+
+		// The second output is the least recently used since the first was reused after it was stored
+		cache.insert(third, 0, value.clone());
+		assert_eq!(cache.bytes(), size * 2);
+		assert!(cache.get(second, 0).is_none());
+		assert!(cache.get(first, 0).is_some());
+		assert!(cache.get(third, 0).is_some());
+
+		// Values which don't fit in the budget on their own aren't cached
+		cache.insert(NodeId(4), 0, TaggedValue::String("x".repeat(3000)));
+		assert!(cache.get(NodeId(4), 0).is_none());
+		// The evicted output no longer counts towards the statistics of its node
+		let stats = cache.stats(second);
+		assert_eq!((stats.entries, stats.bytes), (0, 0));
+		assert_eq!(cache.recency.len(), cache.outputs.len());
+	}
+
+	#[test]
+	fn contexts_with_variadic_arguments_are_not_cached() {
+		let context = OwnedContextImpl::default().with_vararg(Box::new(1_u32)).into_context();
+		assert_eq!(hash_call_argument(Box::new(context)).1, None);
+		assert_eq!(hash_call_argument(Box::new(None::<Arc<OwnedContextImpl>>)).1, Some(CallHash { context: 0, time: 0 }));
+		assert_eq!(hash_call_argument(Box::new(1_u32)).1, None);
+	}
+}
@@ -142,20 +142,7 @@ impl WgpuExecutor {
 	}
 
 	pub async fn render_vello_scene_to_texture(&self, scene: &Scene, size: UVec2, context: &RenderContext, background: Color) -> Result<wgpu::Texture> {
-		let texture = self.context.device.create_texture(&wgpu::TextureDescriptor {
-			label: None,
-			size: wgpu::Extent3d {
-				width: size.x.max(1),
-				height: size.y.max(1),
-				depth_or_array_layers: 1,
-			},
-			mip_level_count: 1,
-			sample_count: 1,
-			dimension: wgpu::TextureDimension::D2,
-			usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
-			format: VELLO_SURFACE_FORMAT,
-			view_formats: &[],
-		});
+		let texture = self.create_target_texture(size);
 		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
 
 		let [r, g, b, _] = background.to_rgba8_srgb();
@@ -188,6 +175,46 @@ impl WgpuExecutor {
 		Ok(texture)
 	}
 
+	/// Renders a scene into an existing texture over a transparent background, scaled from the coordinates it was drawn in to the texture's pixels.
+	///
+	/// This is used for the native overlays, which are drawn in viewport coordinates and composited on either side of the artwork.
+	pub async fn render_transparent_vello_scene(&self, scene: &Scene, texture: &wgpu::Texture, scale: f64, antialiasing_method: AaConfig) -> Result<()> {
+		let mut scaled_scene = Scene::new();
+		scaled_scene.append(scene, Some(vello::kurbo::Affine::scale(scale)));
+
+		let render_params = RenderParams {
+			base_color: vello::peniko::Color::TRANSPARENT,
+			width: texture.width(),
+			height: texture.height(),
+			antialiasing_method,
+		};
+		let view = texture.create_view(&wgpu::TextureViewDescriptor::default());
+		self.vello_renderer
+			.lock()
+			.await
+			.render_to_texture(&self.context.device, &self.context.queue, &scaled_scene, &view, &render_params)?;
+
+		Ok(())
+	}
+
+	/// A texture of the given size (of at least one pixel) which Vello can render into and which can then be sampled.
+	pub fn create_target_texture(&self, size: UVec2) -> wgpu::Texture {
+		self.context.device.create_texture(&wgpu::TextureDescriptor {
+			label: None,
+			size: wgpu::Extent3d {
+				width: size.x.max(1),
+				height: size.y.max(1),
+				depth_or_array_layers: 1,
+			},
+			mip_level_count: 1,
+			sample_count: 1,
+			dimension: wgpu::TextureDimension::D2,
+			usage: wgpu::TextureUsages::STORAGE_BINDING | wgpu::TextureUsages::TEXTURE_BINDING,
+			format: VELLO_SURFACE_FORMAT,
+			view_formats: &[],
+		})
+	}
+
 	#[cfg(target_arch = "wasm32")]
 	pub fn create_surface(&self, canvas: graphene_application_io::WasmSurfaceHandle) -> Result<SurfaceHandle<Surface>> {
 		let surface = self.context.instance.create_surface(wgpu::SurfaceTarget::Canvas(canvas.surface))?;